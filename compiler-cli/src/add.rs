@@ -1,30 +1,23 @@
-use std::path::{Path, PathBuf};
-
 use gleam_core::{
+    config::Requirement,
     error::{FileIoAction, FileKind},
-    Error, Result,
+    io::FileSystemWriter,
+    paths, Error, Result,
 };
 
-use crate::{cli, dependencies::UseManifest, fs};
+use crate::{cli, dependencies::UseManifest, fs::ProjectIO};
 
 pub fn command(packages: Vec<String>, dev: bool) -> Result<()> {
     // Insert the new packages into the manifest and perform dependency
     // resolution to determine suitable versions
-    let manifest = crate::dependencies::download(
+    let mut manifest = crate::dependencies::download(
         cli::Reporter::new(),
         Some((packages.to_vec(), dev)),
         UseManifest::Yes,
     )?;
 
     // Read gleam.toml so we can insert new deps into it
-    let mut toml = fs::read("gleam.toml")?
-        .parse::<toml_edit::Document>()
-        .map_err(|e| Error::FileIo {
-            kind: FileKind::File,
-            action: FileIoAction::Parse,
-            path: PathBuf::from("gleam.toml"),
-            err: Some(e.to_string()),
-        })?;
+    let mut toml = crate::config::root_config_document()?;
 
     // Insert the new deps
     for package_to_add in packages {
@@ -42,18 +35,106 @@ pub fn command(packages: Vec<String>, dev: bool) -> Result<()> {
         // i.e. if 1.2.3 is selected we want ~> 1.2
         let range = format!("~> {}.{}", version.major, version.minor);
 
-        #[allow(clippy::indexing_slicing)]
-        if dev {
-            toml["dev-dependencies"][&package_to_add] = toml_edit::value(range);
-        } else {
-            toml["dependencies"][&package_to_add] = toml_edit::value(range);
-        };
+        add_to_config(&mut toml, &package_to_add, &range, dev)?;
 
         cli::print_added(&format!("{} v{}", package_to_add, version));
+
+        // The manifest records the requirement as written to gleam.toml so
+        // that it is up to date with the config
+        let _ = manifest
+            .requirements
+            .insert(package_to_add, Requirement::hex(&range));
     }
 
     // Write the updated config
-    fs::write(Path::new("gleam.toml"), &toml.to_string())?;
+    ProjectIO::new().write(&paths::root_config(), &toml.to_string())?;
+    crate::dependencies::write_manifest_to_disc(&manifest)?;
 
     Ok(())
 }
+
+/// Insert a requirement into the dependencies of a gleam.toml document, or
+/// its dev-dependencies, adding the section if there is not one already. An
+/// existing requirement is replaced in place, keeping its comments.
+fn add_to_config(
+    toml: &mut toml_edit::Document,
+    package: &str,
+    range: &str,
+    dev: bool,
+) -> Result<()> {
+    let section = if dev {
+        "dev-dependencies"
+    } else {
+        "dependencies"
+    };
+    // Without the explicit table a missing section would be added inline
+    let table = toml
+        .entry(section)
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or_else(|| Error::FileIo {
+            action: FileIoAction::Parse,
+            kind: FileKind::File,
+            path: paths::root_config(),
+            err: Some(format!("`{}` is not a table", section)),
+        })?;
+    match table.get_mut(package).and_then(|item| item.as_value_mut()) {
+        Some(value) => {
+            let decor = value.decor().clone();
+            *value = range.into();
+            *value.decor_mut() = decor;
+        }
+        None => {
+            let _ = table.insert(package, toml_edit::value(range));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn add_to_config_keeps_formatting() {
+    let mut toml = r#"name = "app"
+
+# The packages used by the app
+[dependencies]
+gleam_stdlib = "~> 0.25"
+"#
+    .parse::<toml_edit::Document>()
+    .expect("gleam.toml");
+    add_to_config(&mut toml, "gleam_http", "~> 3.1", false).expect("add");
+    add_to_config(&mut toml, "gleeunit", "~> 0.7", true).expect("add");
+    assert_eq!(
+        toml.to_string(),
+        r#"name = "app"
+
+# The packages used by the app
+[dependencies]
+gleam_stdlib = "~> 0.25"
+gleam_http = "~> 3.1"
+
+[dev-dependencies]
+gleeunit = "~> 0.7"
+"#
+    );
+}
+
+#[test]
+fn add_to_config_replaces_existing_requirement() {
+    let mut toml = "[dependencies]\ngleam_stdlib = \"~> 0.24\" # pinned\n"
+        .parse::<toml_edit::Document>()
+        .expect("gleam.toml");
+    add_to_config(&mut toml, "gleam_stdlib", "~> 0.25", false).expect("add");
+    assert_eq!(
+        toml.to_string(),
+        "[dependencies]\ngleam_stdlib = \"~> 0.25\" # pinned\n"
+    );
+}
+
+#[test]
+fn add_to_config_section_that_is_not_a_table() {
+    let mut toml = "dependencies = \"gleam_stdlib\"\n"
+        .parse::<toml_edit::Document>()
+        .expect("gleam.toml");
+    assert!(add_to_config(&mut toml, "gleam_http", "~> 3.1", false).is_err());
+    assert_eq!(toml.to_string(), "dependencies = \"gleam_stdlib\"\n");
+}
@@ -0,0 +1,285 @@
+//! The `gleam api diff` command, which compares the public interface of the
+//! package with that of a previous release, to check that the version in
+//! gleam.toml has been bumped enough for the changes made since.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use flate2::read::GzDecoder;
+use gleam_core::{
+    build::{Mode, Module, Options},
+    config::PackageConfig,
+    error::{FileIoAction, FileKind},
+    interface::{
+        diff::{self, Bump, Change},
+        ModuleInterface,
+    },
+    io::HttpClient as _,
+    manifest::Manifest,
+    paths, Compiler, Error, Result,
+};
+use hexpm::version::Version;
+use itertools::Itertools;
+
+use crate::{dependencies, http::HttpClient};
+
+/// Compares the package with the release of the given version, or with the
+/// newest release before the version of gleam.toml. When a directory of
+/// interfaces written by `gleam export interfaces` is given it is compared
+/// with instead, as the version given.
+pub fn diff(version: Option<String>, interfaces: Option<PathBuf>) -> Result<()> {
+    let config = crate::config::root_config()?;
+    let version = version
+        .map(|version| {
+            Version::parse(&version).map_err(|error| Error::InvalidVersionFormat {
+                input: version,
+                error: error.to_string(),
+            })
+        })
+        .transpose()?;
+
+    let mut package = crate::build::main(Options {
+        perform_codegen: false,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode: Mode::Dev,
+        target: None,
+    })?;
+    let current = module_interfaces(&mut package.modules);
+
+    let (previous_version, previous) = match interfaces {
+        Some(dir) => (version, read_interfaces(&dir)?),
+        None => {
+            let (version, modules) = released_modules(&config, version)?;
+            (Some(version), modules)
+        }
+    };
+
+    let changes = diff::diff(&previous, &current);
+    print_changes(&changes);
+
+    let (previous_version, required) = match (previous_version, diff::required_bump(&changes)) {
+        (Some(version), Some(required)) => (version, required),
+        _ => return Ok(()),
+    };
+    match Bump::between(&previous_version, &config.version) {
+        Some(declared) if declared >= required => Ok(()),
+        _ => Err(Error::InsufficientVersionBump {
+            previous: previous_version.to_string(),
+            current: config.version.to_string(),
+            required,
+        }),
+    }
+}
+
+fn print_changes(changes: &[Change]) {
+    if changes.is_empty() {
+        println!("The public API has not changed.");
+        return;
+    }
+    for (bump, changes) in &changes.iter().group_by(|change| change.bump) {
+        println!("{} changes:", capitalise(&bump.to_string()));
+        for change in changes {
+            println!("  - {}", change.description);
+        }
+        println!();
+    }
+}
+
+fn capitalise(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn module_interfaces(modules: &mut [Module]) -> Vec<ModuleInterface> {
+    modules
+        .iter_mut()
+        .filter(|module| module.origin.is_src())
+        .map(|module| {
+            module.attach_doc_and_module_comments();
+            ModuleInterface::from_module(&module.ast)
+        })
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .collect()
+}
+
+fn read_interfaces(dir: &Path) -> Result<Vec<ModuleInterface>> {
+    let mut interfaces = vec![];
+    for entry in crate::fs::read_dir(dir)?.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        let json = crate::fs::read(&path)?;
+        let interface = ModuleInterface::from_json(&json).map_err(|error| Error::FileIo {
+            kind: FileKind::File,
+            action: FileIoAction::Parse,
+            path: path.clone(),
+            err: Some(error.to_string()),
+        })?;
+        interfaces.push(interface);
+    }
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(interfaces)
+}
+
+/// Downloads the release of the package with the version, or the newest one
+/// before the version of gleam.toml, and type checks its modules against the
+/// dependencies of the project.
+fn released_modules(
+    config: &PackageConfig,
+    version: Option<Version>,
+) -> Result<(Version, Vec<ModuleInterface>)> {
+    if dependencies::is_offline() {
+        return Err(Error::OfflineUnsupported {
+            command: "api diff".into(),
+        });
+    }
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let registries = crate::hex::registries(config)?;
+    let registry = registries.default();
+    let http = HttpClient::new();
+
+    let request =
+        hexpm::get_package_request(&config.name, registry.api_key.as_deref(), &registry.config);
+    let response = runtime.block_on(http.send(request))?;
+    let package =
+        hexpm::get_package_response(response, &registry.public_key).map_err(Error::hex)?;
+    let release = package
+        .releases
+        .into_iter()
+        .filter(|release| match &version {
+            Some(version) => &release.version == version,
+            None => release.version < config.version,
+        })
+        .max_by(|a, b| a.version.cmp(&b.version))
+        .ok_or_else(|| {
+            Error::Hex(match &version {
+                Some(version) => format!("{} {} has not been published", config.name, version),
+                None => format!("{} has no releases before {}", config.name, config.version),
+            })
+        })?;
+
+    let request = hexpm::get_package_tarball_request(
+        &config.name,
+        &release.version.to_string(),
+        registry.api_key.as_deref(),
+        &registry.config,
+    );
+    let response = runtime.block_on(http.send(request))?;
+    let tarball = hexpm::get_package_tarball_response(response, &release.outer_checksum).map_err(
+        |error| Error::DownloadPackageError {
+            package_name: config.name.clone(),
+            package_version: release.version.to_string(),
+            error: error.to_string(),
+        },
+    )?;
+    tracing::info!(version = %release.version, "comparing_with_release");
+
+    let mut compiler = Compiler::new(config.clone());
+    let manifest = dependencies::read_manifest_from_disc()?;
+    for package in gleam_packages_in_dependency_order(&manifest) {
+        let src = paths::build_deps_package(package).join("src");
+        for path in crate::fs::gleam_files_excluding_gitignore(&src) {
+            let code = crate::fs::read(&path)?;
+            compiler = compiler.add_dependency_source(package, &module_name(&src, &path), &code);
+        }
+    }
+    for (module, code) in released_sources(&tarball)? {
+        compiler = compiler.add_source(&module, &code);
+    }
+    let mut compiled = compiler.check()?;
+    Ok((release.version, module_interfaces(&mut compiled.modules)))
+}
+
+// The Gleam packages of the manifest, each after the packages it depends on
+fn gleam_packages_in_dependency_order(manifest: &Manifest) -> Vec<&str> {
+    fn visit<'a>(
+        name: &'a str,
+        packages: &HashMap<&'a str, &'a gleam_core::manifest::ManifestPackage>,
+        visited: &mut HashSet<&'a str>,
+        order: &mut Vec<&'a str>,
+    ) {
+        let package = match packages.get(name) {
+            Some(package) if visited.insert(name) => package,
+            _ => return,
+        };
+        for requirement in &package.requirements {
+            visit(requirement, packages, visited, order);
+        }
+        if package.build_tools.iter().any(|tool| tool == "gleam") {
+            order.push(name);
+        }
+    }
+
+    let packages = manifest
+        .packages
+        .iter()
+        .map(|package| (package.name.as_str(), package))
+        .collect();
+    let mut visited = HashSet::new();
+    let mut order = vec![];
+    for package in manifest
+        .packages
+        .iter()
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+    {
+        visit(&package.name, &packages, &mut visited, &mut order);
+    }
+    order
+}
+
+fn module_name(src: &Path, path: &Path) -> String {
+    path.strip_prefix(src)
+        .unwrap_or(path)
+        .with_extension("")
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .join("/")
+}
+
+// The Gleam modules of the `src` directory of a Hex package tarball
+fn released_sources(tarball: &[u8]) -> Result<Vec<(String, String)>> {
+    let expand = |error: std::io::Error| Error::ExpandTar {
+        error: error.to_string(),
+    };
+    let mut outer = tar::Archive::new(tarball);
+    for entry in outer.entries().map_err(expand)? {
+        let entry = entry.map_err(expand)?;
+        if entry.path().map_err(expand)?.as_ref() != Path::new("contents.tar.gz") {
+            continue;
+        }
+        let mut sources = vec![];
+        let mut contents = tar::Archive::new(GzDecoder::new(entry));
+        for file in contents.entries().map_err(expand)? {
+            let mut file = file.map_err(expand)?;
+            let path = file.path().map_err(expand)?.to_path_buf();
+            if !path.starts_with("src")
+                || path.extension().and_then(|e| e.to_str()) != Some("gleam")
+            {
+                continue;
+            }
+            let mut code = String::new();
+            let _ = file.read_to_string(&mut code).map_err(expand)?;
+            sources.push((module_name(Path::new("src"), &path), code));
+        }
+        return Ok(sources);
+    }
+    Err(Error::ExpandTar {
+        error: "Unable to locate Hex package contents.tar.gz".into(),
+    })
+}
+
+#[test]
+fn module_name_test() {
+    assert_eq!(
+        module_name(Path::new("src"), Path::new("src/app/parser.gleam")),
+        "app/parser"
+    );
+}
@@ -1,32 +1,267 @@
-use std::time::Instant;
+use std::{
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 use gleam_core::{
-    build::{Options, Package, ProjectCompiler},
-    Result,
+    build::{LocalPackage, Options, Package, Phase, ProjectCompiler, Target, Telemetry, Timings},
+    config::PackageConfig,
+    io::{CommandExecutor, FileSystemWriter, Stdio},
+    paths, type_,
+    uid::UniqueIdGenerator,
+    version::COMPILER_VERSION,
+    Error, Result, Warning,
+};
+
+use crate::{
+    build_lock::BuildLock,
+    cli,
+    dependencies::UseManifest,
+    fs,
+    telemetry::{JsonTelemetry, NullTelemetry, SarifTelemetry, WarningTelemetry},
 };
 
-use crate::{build_lock::BuildLock, cli, dependencies::UseManifest, fs};
+/// How the time taken by each phase of the build is to be reported, as given
+/// by the `--timings` and `--timings-trace` flags.
+#[derive(Debug, Default)]
+struct TimingsReport {
+    summary: bool,
+    trace: Option<PathBuf>,
+}
+
+lazy_static::lazy_static! {
+    static ref TIMINGS: Mutex<TimingsReport> = Mutex::new(TimingsReport::default());
+}
+
+pub fn set_timings(summary: bool, trace: Option<PathBuf>) {
+    *TIMINGS.lock().expect("Timings report lock") = TimingsReport { summary, trace };
+}
 
 pub fn main(options: Options) -> Result<Package> {
+    let perform_codegen = options.perform_codegen;
+    let (compiled, _, elapsed) = compile(options, cli::Reporter::new())?;
+    if perform_codegen {
+        cli::print_compiled(elapsed);
+    } else {
+        cli::print_checked(elapsed);
+    }
+    Ok(compiled)
+}
+
+/// Build the project with the examples in the documentation of the root
+/// package compiled as test modules, for `gleam test --doc`.
+pub fn with_doctests(options: Options) -> Result<Package> {
+    let (compiled, elapsed) = compile_with(options, cli::Reporter::new(), |compiler| {
+        compiler.doctests = true;
+        compiler.compile()
+    })?;
+    cli::print_compiled(elapsed);
+    Ok(compiled)
+}
+
+/// Build the project with TypeScript declarations for the modules of every
+/// package, whatever the configuration of the project, for
+/// `gleam export npm-package`.
+pub fn with_typescript_declarations(options: Options) -> Result<Package> {
+    let (compiled, elapsed) = compile_with(options, cli::Reporter::new(), |compiler| {
+        compiler.emit_typescript_declarations();
+        compiler.compile()
+    })?;
+    cli::print_compiled(elapsed);
+    Ok(compiled)
+}
+
+/// Build the project without printing progress, for commands whose output is
+/// to be read by other programs. Warnings are still printed to stderr.
+pub fn quiet(options: Options) -> Result<Package> {
+    compile(options, WarningTelemetry).map(|(compiled, _, _)| compiled)
+}
+
+/// Build the project, printing each warning and error as a JSON object on a
+/// line of stdout rather than reporting progress. An error is also printed to
+/// stderr as usual.
+pub fn json(options: Options) -> Result<Package> {
+    compile(options, JsonTelemetry)
+        .map(|(compiled, _, _)| compiled)
+        .map_err(|error| {
+            for diagnostic in error.to_json() {
+                println!("{}", diagnostic);
+            }
+            error
+        })
+}
+
+/// Build the project, printing a SARIF log of its warnings and errors to
+/// stdout once it has finished rather than reporting progress. An error is
+/// also printed to stderr as usual.
+pub fn sarif(options: Options) -> Result<Package> {
+    let telemetry = SarifTelemetry::default();
+    let result = compile(options, telemetry.clone()).map(|(compiled, _, _)| compiled);
+    let mut diagnostics = std::mem::take(
+        &mut *telemetry
+            .diagnostics
+            .lock()
+            .expect("SARIF diagnostics lock"),
+    );
+    if let Err(error) = &result {
+        diagnostics.extend(error.to_coded_diagnostics());
+    }
+    let log = gleam_core::diagnostic::sarif::log(&diagnostics, COMPILER_VERSION);
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&log).expect("SARIF log serialisation")
+    );
+    result
+}
+
+/// Build the project without printing anything, returning the warnings of
+/// the build instead, along with the modules that code can import and the
+/// id generator of the build so that changes to the code can be type
+/// checked.
+pub fn with_warnings(
+    options: Options,
+) -> Result<(
+    Package,
+    Vec<Warning>,
+    im::HashMap<String, type_::Module>,
+    UniqueIdGenerator,
+    Target,
+)> {
+    compile_with(options, NullTelemetry, |compiler| {
+        let package = compiler.compile()?;
+        Ok((
+            package,
+            compiler.take_warnings(),
+            compiler.get_importable_modules().clone(),
+            compiler.ids(),
+            compiler.target(),
+        ))
+    })
+    .map(|(compiled, _)| compiled)
+}
+
+/// Build the project, returning the modules that code can import along with
+/// the id generator of the build, so that further code can be type checked
+/// against the project.
+pub fn importable_modules(
+    options: Options,
+) -> Result<(
+    Package,
+    im::HashMap<String, type_::Module>,
+    UniqueIdGenerator,
+)> {
+    let (compiled, elapsed) = compile_with(options, cli::Reporter::new(), |compiler| {
+        let package = compiler.compile()?;
+        Ok((
+            package,
+            compiler.get_importable_modules().clone(),
+            compiler.ids(),
+        ))
+    })?;
+    cli::print_compiled(elapsed);
+    Ok(compiled)
+}
+
+/// Build the project for each of the targets, one after the other.
+pub fn targets(options: Options, targets: &[Target]) -> Result<Vec<Package>> {
+    let (compiled, elapsed) = compile_with(options, cli::Reporter::new(), |compiler| {
+        compiler.compile_targets(targets)
+    })?;
+    cli::print_compiled(elapsed);
+    Ok(compiled)
+}
+
+fn compile<Telem: Telemetry + Clone + 'static>(
+    options: Options,
+    telemetry: Telem,
+) -> Result<(Package, Vec<Warning>, Duration)> {
+    compile_with(options, telemetry, |compiler| {
+        let package = compiler.compile()?;
+        Ok((package, compiler.take_warnings()))
+    })
+    .map(|((compiled, warnings), elapsed)| (compiled, warnings, elapsed))
+}
+
+/// Build the project with the given function, which can take whatever else
+/// is wanted from the compiler before it is dropped.
+fn compile_with<Telem: Telemetry + Clone + 'static, T>(
+    options: Options,
+    telemetry: Telem,
+    build: impl FnOnce(&mut ProjectCompiler<fs::ProjectIO>) -> Result<T>,
+) -> Result<(T, Duration)> {
+    let report = std::mem::take(&mut *TIMINGS.lock().expect("Timings report lock"));
+    let timings = if report.summary || report.trace.is_some() {
+        Timings::new()
+    } else {
+        Timings::disabled()
+    };
+
     let lock = BuildLock::new()?;
-    let manifest = crate::dependencies::download(cli::Reporter::new(), None, UseManifest::Yes)?;
+    let manifest = timings.time(Phase::LoadDependencies, "download", None, || {
+        crate::dependencies::download(telemetry.clone(), None, UseManifest::Yes)
+    })?;
 
-    let perform_codegen = options.perform_codegen;
     let root_config = crate::config::root_config()?;
-    let telemetry = Box::new(cli::Reporter::new());
+    crate::dependencies::check_licence_policy(&root_config, &manifest)?;
+    let telemetry = Box::new(telemetry);
     let io = fs::ProjectIO::new();
     let start = Instant::now();
 
     tracing::info!("Compiling packages");
     let compiled = {
         let _guard = lock.lock(telemetry.as_ref());
-        ProjectCompiler::new(root_config, options, manifest.packages, telemetry, io).compile()?
+        let local_packages = crate::config::local_packages(&root_config, options.mode)?;
+        run_hooks(&root_config, &local_packages, telemetry.as_ref())?;
+        let mut compiler =
+            ProjectCompiler::new(root_config, options, manifest.packages, telemetry, io);
+        compiler.local_packages = local_packages;
+        compiler.global_build_cache = Some(paths::global_build_cache());
+        compiler.timings = timings.clone();
+        build(&mut compiler)?
     };
 
-    if perform_codegen {
-        cli::print_compiled(start.elapsed());
-    } else {
-        cli::print_checked(start.elapsed());
+    if report.summary {
+        cli::print_timings(&timings.summary());
     }
-    Ok(compiled)
+    if let Some(path) = &report.trace {
+        fs::ProjectIO::new().write(path, &timings.chrome_trace())?;
+    }
+
+    Ok((compiled, start.elapsed()))
+}
+
+/// Run the build hooks of the local packages and of the root package, so that
+/// the code they generate is compiled.
+pub fn run_hooks(
+    root_config: &PackageConfig,
+    local_packages: &[LocalPackage],
+    telemetry: &dyn Telemetry,
+) -> Result<()> {
+    let root = paths::root();
+    let packages = local_packages
+        .iter()
+        .map(|package| (&package.config, package.root.as_path()))
+        .chain(std::iter::once((root_config, root.as_path())));
+    for (config, root) in packages {
+        for (name, hook) in config.build.hooks() {
+            telemetry.running_build_hook(&config.name, name);
+            let (shell, flag) = if cfg!(windows) {
+                ("cmd", "/C")
+            } else {
+                ("sh", "-c")
+            };
+            let args = [flag.to_string(), hook.command.clone()];
+            let status =
+                fs::ProjectIO::new().exec(shell, &args, &[], Some(root), Stdio::Inherit)?;
+            if status != 0 {
+                return Err(Error::BuildHook {
+                    package: config.name.clone(),
+                    hook: name.clone(),
+                    status,
+                });
+            }
+        }
+    }
+    Ok(())
 }
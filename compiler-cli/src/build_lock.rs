@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use gleam_core::{build::Telemetry, paths, Result};
 
@@ -10,8 +10,13 @@ pub(crate) struct BuildLock {
 // TODO: return errors rather than crashing.
 impl BuildLock {
     pub fn new() -> Result<Self> {
-        let build = paths::build();
-        crate::fs::mkdir(&build)?;
+        Self::new_in(&paths::build())
+    }
+
+    /// A lock of the given build directory rather than that of the project
+    /// in the current directory.
+    pub fn new_in(build: &Path) -> Result<Self> {
+        crate::fs::mkdir(build)?;
         Ok(Self {
             path: build.join("gleam-compile.lock"),
         })
@@ -32,9 +37,13 @@ impl BuildLock {
 #[derive(Debug)]
 pub(crate) struct Guard(fslock::LockFile);
 
+// The lock is made outside of the source tree so that it is not left there
 #[test]
 fn locking() {
-    let lock = BuildLock::new().expect("make lock");
-    let _guard1 = lock.lock(&crate::telemetry::NullTelemetry);
-    println!("Locked!")
+    let build = std::env::temp_dir().join(format!("gleam-build-lock-{}", std::process::id()));
+    let lock = BuildLock::new_in(&build).expect("make lock");
+    let guard = lock.lock(&crate::telemetry::NullTelemetry);
+    assert!(build.join("gleam-compile.lock").is_file());
+    drop(guard);
+    crate::fs::delete_dir(&build).expect("remove lock");
 }
@@ -0,0 +1,150 @@
+use gleam_core::{
+    build::{Mode, Target},
+    config::{BundleConfig, PackageConfig},
+    error::Error,
+    io::{CommandExecutor, FileSystemWriter, Stdio},
+    paths,
+};
+
+use crate::fs::ProjectIO;
+
+/// Bundling only makes sense for JavaScript, so `--bundle` selects the
+/// JavaScript target unless another target was explicitly asked for.
+pub fn target(target: Option<Target>) -> Result<Target, Error> {
+    match target.unwrap_or(Target::JavaScript) {
+        Target::JavaScript => Ok(Target::JavaScript),
+        target => Err(Error::BundleUnsupportedTarget { target }),
+    }
+}
+
+/// Run the `[javascript.bundle]` command on the compiled project.
+pub fn bundle(config: &PackageConfig) -> Result<(), Error> {
+    let command = &bundle_config(config)?.command;
+    crate::cli::print_bundling(&config.name);
+    match run_hook(command, config, &[], &[])? {
+        0 => Ok(()),
+        _ => Err(Error::ShellCommand {
+            program: command.clone(),
+            err: None,
+        }),
+    }
+}
+
+/// Run the `[javascript.bundle]` serve command on the bundled project with
+/// the given arguments and environment variables, returning its exit status.
+pub fn serve(
+    config: &PackageConfig,
+    arguments: &[String],
+    environment: &[(String, String)],
+) -> Result<i32, Error> {
+    let command = bundle_config(config)?.serve.as_ref().ok_or_else(|| {
+        Error::JavaScriptBundleNotConfigured {
+            key: "serve".into(),
+        }
+    })?;
+    crate::cli::print_serving(&config.name);
+    run_hook(command, config, arguments, environment)
+}
+
+fn bundle_config(config: &PackageConfig) -> Result<&BundleConfig, Error> {
+    config
+        .javascript
+        .bundle
+        .as_ref()
+        .ok_or_else(|| Error::JavaScriptBundleNotConfigured {
+            key: "command".into(),
+        })
+}
+
+// The hook is given the path of an entry module that runs the project's
+// `main` function, followed by any arguments given to `gleam run`, with the
+// output directory and entry module also set as environment variables for
+// commands that need to know where the other compiled packages are.
+fn run_hook(
+    command: &str,
+    config: &PackageConfig,
+    arguments: &[String],
+    environment: &[(String, String)],
+) -> Result<i32, Error> {
+    let out = paths::build_packages(Mode::Dev, Target::JavaScript);
+    let entry = paths::build_package(Mode::Dev, Target::JavaScript, &config.name)
+        .join("gleam_bundle_entry.mjs");
+    let io = ProjectIO::new();
+    io.write(&entry, &entry_module(&config.name))?;
+
+    let out = out.to_string_lossy().to_string();
+    let entry = entry.to_string_lossy().to_string();
+    let mut env = vec![
+        ("GLEAM_OUTPUT_DIRECTORY", out),
+        ("GLEAM_ENTRY_MODULE", entry.clone()),
+    ];
+    env.extend(
+        environment
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone())),
+    );
+    let mut hook_arguments = vec![entry];
+    hook_arguments.extend(arguments.iter().cloned());
+    let (program, args) = shell_command(command, &hook_arguments);
+    io.exec(program, &args, &env, None, Stdio::Inherit)
+}
+
+fn entry_module(module: &str) -> String {
+    format!("import {{ main }} from \"./{}.mjs\";\n\nmain();\n", module)
+}
+
+// The hook arguments are appended to the user's command by the shell so that
+// it can be written just as it would be on the command line.
+#[cfg(not(target_os = "windows"))]
+fn shell_command(command: &str, arguments: &[String]) -> (&'static str, Vec<String>) {
+    let mut args = vec![
+        "-c".to_string(),
+        format!("{} \"$@\"", command),
+        "gleam".to_string(),
+    ];
+    args.extend(arguments.iter().cloned());
+    ("sh", args)
+}
+
+#[cfg(target_os = "windows")]
+fn shell_command(command: &str, arguments: &[String]) -> (&'static str, Vec<String>) {
+    let mut line = command.to_string();
+    for argument in arguments {
+        line.push_str(&format!(" \"{}\"", argument));
+    }
+    ("cmd", vec!["/C".to_string(), line])
+}
+
+#[test]
+fn shell_command_appends_arguments() {
+    let (program, args) = shell_command("esbuild --bundle", &["app.mjs".into()]);
+    if cfg!(target_os = "windows") {
+        assert_eq!(program, "cmd");
+        assert_eq!(args, vec!["/C", "esbuild --bundle \"app.mjs\""]);
+    } else {
+        assert_eq!(program, "sh");
+        assert_eq!(
+            args,
+            vec!["-c", "esbuild --bundle \"$@\"", "gleam", "app.mjs"]
+        );
+    }
+}
+
+#[test]
+fn bundle_target_defaults_to_javascript() {
+    assert_eq!(target(None), Ok(Target::JavaScript));
+    assert_eq!(
+        target(Some(Target::Erlang)),
+        Err(Error::BundleUnsupportedTarget {
+            target: Target::Erlang
+        })
+    );
+}
+
+#[test]
+fn entry_module_runs_main() {
+    assert_eq!(
+        entry_module("app"),
+        "import { main } from \"./app.mjs\";\n\nmain();\n"
+    );
+}
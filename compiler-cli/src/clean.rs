@@ -0,0 +1,389 @@
+//! The `gleam clean` command, which removes the build directory or a
+//! selection of what is within it, after printing how much disk each part of
+//! it occupies.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use gleam_core::{
+    build::{Mode, Target},
+    paths, Result,
+};
+use itertools::Itertools;
+use strum::VariantNames;
+
+use crate::{cli, fs};
+
+/// What `gleam clean` removes. With nothing selected the whole build
+/// directory is removed, and each selector given narrows what is removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    /// Only what was compiled for this target.
+    pub target: Option<Target>,
+    /// Only the compiled code of this package.
+    pub package: Option<String>,
+    /// Only the dependency packages, compiled or downloaded.
+    pub deps: bool,
+}
+
+impl Selection {
+    fn is_everything(&self) -> bool {
+        self.target.is_none() && self.package.is_none() && !self.deps
+    }
+}
+
+pub fn command(selection: Selection) -> Result<()> {
+    let build = paths::build();
+    if !build.is_dir() {
+        cli::print_removed("nothing");
+        return Ok(());
+    }
+
+    // The project's own package is kept when only the dependencies are removed
+    let root = if selection.deps {
+        Some(crate::config::root_config()?.name)
+    } else {
+        None
+    };
+
+    let parts = parts(&build)?;
+    print_usage(&build, &parts);
+
+    let removals = if selection.is_everything() {
+        vec![(build.clone(), parts.iter().map(|part| part.bytes).sum())]
+    } else {
+        removals(&parts, &selection, root.as_deref())
+    };
+    if removals.is_empty() {
+        cli::print_removed("nothing");
+    }
+    for (path, bytes) in removals {
+        if path.is_dir() {
+            fs::delete_dir(&path)?;
+        } else {
+            fs::delete_file(&path)?;
+        }
+        cli::print_removed(&format!("{} ({})", path.display(), size(bytes)));
+    }
+    Ok(())
+}
+
+/// An entry of the build directory, which is the smallest thing that can be
+/// selected for removal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Part {
+    path: PathBuf,
+    /// The directory the part is summarised within, such as `build/dev/erlang`
+    /// or `build/packages`.
+    area: PathBuf,
+    kind: PartKind,
+    bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PartKind {
+    /// The compiled code of a package, or the log of the build tool that
+    /// compiled it.
+    Package {
+        target: Target,
+        name: String,
+    },
+    /// Anything else compiled for a target, such as the build journal.
+    Compiled {
+        target: Target,
+    },
+    /// The downloaded sources of the dependency packages.
+    Downloaded,
+    Other,
+}
+
+/// The entries of the build directory, with those of the directory of each
+/// target listed individually.
+fn parts(build: &Path) -> Result<Vec<Part>> {
+    let mut parts = vec![];
+    for entry in sorted_entries(build)? {
+        let name = file_name(&entry);
+        let is_mode = Mode::VARIANTS.contains(&name.as_str()) && entry.is_dir();
+        if !is_mode {
+            let kind = match name.as_str() {
+                "packages" | "git" => PartKind::Downloaded,
+                _ => PartKind::Other,
+            };
+            parts.push(part(entry.clone(), entry, kind));
+            continue;
+        }
+
+        for entry in sorted_entries(&entry)? {
+            let target = Target::from_str(&file_name(&entry))
+                .ok()
+                .filter(|_| entry.is_dir());
+            let target = match target {
+                Some(target) => target,
+                None => {
+                    parts.push(part(entry.clone(), entry, PartKind::Other));
+                    continue;
+                }
+            };
+            for path in sorted_entries(&entry)? {
+                let name = file_name(&path);
+                let package = if path.is_dir() {
+                    Some(name.as_str())
+                } else {
+                    name.strip_suffix(".log")
+                };
+                let kind = match package {
+                    Some(name) => PartKind::Package {
+                        target,
+                        name: name.to_string(),
+                    },
+                    None => PartKind::Compiled { target },
+                };
+                parts.push(part(path, entry.clone(), kind));
+            }
+        }
+    }
+    Ok(parts)
+}
+
+fn part(path: PathBuf, area: PathBuf, kind: PartKind) -> Part {
+    let bytes = disk_usage(&path);
+    Part {
+        path,
+        area,
+        kind,
+        bytes,
+    }
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect_vec();
+    entries.sort();
+    Ok(entries)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// The size of a file or of the files within a directory. Symbolic links are
+/// not followed, as the build directory links to libraries outside of it.
+fn disk_usage(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn selected(part: &Part, selection: &Selection, root: Option<&str>) -> bool {
+    let target = match &part.kind {
+        PartKind::Package { target, .. } | PartKind::Compiled { target } => Some(*target),
+        PartKind::Downloaded | PartKind::Other => None,
+    };
+    if selection.target.is_some() && selection.target != target {
+        return false;
+    }
+    match (&part.kind, &selection.package) {
+        (PartKind::Package { name, .. }, Some(package)) => return name == package,
+        (_, Some(_)) => return false,
+        (_, None) => (),
+    }
+    if selection.deps {
+        return match &part.kind {
+            PartKind::Package { name, .. } => Some(name.as_str()) != root,
+            PartKind::Downloaded => true,
+            PartKind::Compiled { .. } | PartKind::Other => false,
+        };
+    }
+    true
+}
+
+/// The paths to remove for a selection and the number of bytes each holds.
+/// Where every part of an area is selected the area is removed as a whole.
+fn removals(parts: &[Part], selection: &Selection, root: Option<&str>) -> Vec<(PathBuf, u64)> {
+    let mut removals = vec![];
+    for (area, parts) in &parts.iter().group_by(|part| part.area.clone()) {
+        let parts = parts.collect_vec();
+        let chosen = parts
+            .iter()
+            .filter(|part| selected(part, selection, root))
+            .collect_vec();
+        if chosen.len() == parts.len() {
+            removals.push((area, chosen.iter().map(|part| part.bytes).sum()));
+        } else {
+            removals.extend(chosen.iter().map(|part| (part.path.clone(), part.bytes)));
+        }
+    }
+    removals
+}
+
+fn print_usage(build: &Path, parts: &[Part]) {
+    let mut areas = BTreeMap::new();
+    for part in parts {
+        *areas.entry(part.area.clone()).or_insert(0) += part.bytes;
+    }
+    let total = parts.iter().map(|part| part.bytes).sum();
+    let rows = areas
+        .into_iter()
+        .map(|(area, bytes)| (area.display().to_string(), size(bytes)))
+        .chain(std::iter::once((
+            format!("{} (total)", build.display()),
+            size(total),
+        )))
+        .collect_vec();
+    let width = rows.iter().map(|(_, size)| size.len()).max().unwrap_or(0);
+    for (area, size) in rows {
+        println!("{size:>width$}  {area}");
+    }
+}
+
+fn size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut amount = bytes as f64 / 1024.0;
+    let mut unit = "KiB";
+    for next in UNITS.iter().skip(1) {
+        if amount < 1024.0 {
+            break;
+        }
+        amount /= 1024.0;
+        unit = next;
+    }
+    format!("{:.1} {}", amount, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(path: &str, target: Target, name: &str) -> Part {
+        let path = PathBuf::from(path);
+        Part {
+            area: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            path,
+            kind: PartKind::Package {
+                target,
+                name: name.into(),
+            },
+            bytes: 10,
+        }
+    }
+
+    fn example() -> Vec<Part> {
+        vec![
+            Part {
+                path: "build/dev/erlang/gleam_version".into(),
+                area: "build/dev/erlang".into(),
+                kind: PartKind::Compiled {
+                    target: Target::Erlang,
+                },
+                bytes: 1,
+            },
+            package("build/dev/erlang/app", Target::Erlang, "app"),
+            package(
+                "build/dev/erlang/gleam_stdlib",
+                Target::Erlang,
+                "gleam_stdlib",
+            ),
+            package("build/dev/javascript/app", Target::JavaScript, "app"),
+            package(
+                "build/dev/javascript/gleam_stdlib",
+                Target::JavaScript,
+                "gleam_stdlib",
+            ),
+            Part {
+                path: "build/packages".into(),
+                area: "build/packages".into(),
+                kind: PartKind::Downloaded,
+                bytes: 100,
+            },
+            Part {
+                path: "build/gleam-compile.lock".into(),
+                area: "build/gleam-compile.lock".into(),
+                kind: PartKind::Other,
+                bytes: 0,
+            },
+        ]
+    }
+
+    fn paths(removals: Vec<(PathBuf, u64)>) -> Vec<(String, u64)> {
+        removals
+            .into_iter()
+            .map(|(path, bytes)| (path.display().to_string(), bytes))
+            .collect()
+    }
+
+    #[test]
+    fn remove_target() {
+        let selection = Selection {
+            target: Some(Target::Erlang),
+            ..Default::default()
+        };
+        assert_eq!(
+            paths(removals(&example(), &selection, None)),
+            [("build/dev/erlang".into(), 21)]
+        );
+    }
+
+    #[test]
+    fn remove_package() {
+        let selection = Selection {
+            package: Some("gleam_stdlib".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            paths(removals(&example(), &selection, None)),
+            [
+                ("build/dev/erlang/gleam_stdlib".into(), 10),
+                ("build/dev/javascript/gleam_stdlib".into(), 10)
+            ]
+        );
+    }
+
+    #[test]
+    fn remove_deps() {
+        let selection = Selection {
+            deps: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            paths(removals(&example(), &selection, Some("app"))),
+            [
+                ("build/dev/erlang/gleam_stdlib".into(), 10),
+                ("build/dev/javascript/gleam_stdlib".into(), 10),
+                ("build/packages".into(), 100)
+            ]
+        );
+
+        // The downloaded packages are not for any one target
+        let selection = Selection {
+            deps: true,
+            target: Some(Target::JavaScript),
+            ..Default::default()
+        };
+        assert_eq!(
+            paths(removals(&example(), &selection, Some("app"))),
+            [("build/dev/javascript/gleam_stdlib".into(), 10)]
+        );
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(size(10), "10 B");
+        assert_eq!(size(2048), "2.0 KiB");
+        assert_eq!(size(5 * 1024 * 1024 + 512 * 1024), "5.5 MiB");
+        assert_eq!(size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}
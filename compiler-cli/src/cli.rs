@@ -30,6 +30,10 @@ impl Telemetry for Reporter {
         print_checking(name);
     }
 
+    fn running_build_hook(&self, package: &str, hook: &str) {
+        print_running(&format!("{} hook {}", package, hook));
+    }
+
     fn warning(&self, warning: &Warning) {
         print_warning(warning)
     }
@@ -130,10 +134,45 @@ pub(crate) fn print_running(text: &str) {
     print_colourful_prefix("    Running", text)
 }
 
+pub(crate) fn print_bundling(text: &str) {
+    print_colourful_prefix("   Bundling", text)
+}
+
+pub(crate) fn print_serving(text: &str) {
+    print_colourful_prefix("    Serving", text)
+}
+
 pub(crate) fn print_added(text: &str) {
     print_colourful_prefix("      Added", text)
 }
 
+pub(crate) fn print_removed(text: &str) {
+    print_colourful_prefix("    Removed", text)
+}
+
+pub(crate) fn print_vendored(text: &str) {
+    print_colourful_prefix("   Vendored", text)
+}
+
+pub(crate) fn print_fixed(text: &str) {
+    print_colourful_prefix("      Fixed", text)
+}
+
+pub(crate) fn print_not_fixed(text: &str) {
+    print_colourful_prefix(
+        "    Skipped",
+        &format!("{} as the fixes would not compile", text),
+    )
+}
+
+pub(crate) fn print_exited(status: i32) {
+    print_colourful_prefix("     Exited", &format!("with status {}", status))
+}
+
+pub(crate) fn print_watching() {
+    print_colourful_prefix("   Watching", "for changes")
+}
+
 pub(crate) fn print_generating_documentation() {
     print_colourful_prefix(" Generating", "documentation")
 }
@@ -147,6 +186,12 @@ fn print_packages_downloaded(start: Instant, count: usize) {
     print_colourful_prefix(" Downloaded", &msg)
 }
 
+/// Printed to stderr so that it does not mix with the output of commands
+/// whose output is read by other programs.
+pub fn print_timings(summary: &str) {
+    eprint!("\n{}", summary);
+}
+
 pub fn seconds(duration: Duration) -> String {
     format!("{:.2}s", duration.as_millis() as f32 / 1000.)
 }
@@ -21,6 +21,9 @@ pub fn command(options: CompilePackage) -> Result<()> {
     let target = match options.target {
         Target::Erlang => TargetCodegenConfiguration::Erlang { app_file: None },
         Target::Native => TargetCodegenConfiguration::CPlusPlus,
+        Target::Go => TargetCodegenConfiguration::Go,
+        Target::Llvm => TargetCodegenConfiguration::Llvm,
+        Target::Wasm => TargetCodegenConfiguration::Wasm,
         Target::JavaScript => TargetCodegenConfiguration::JavaScript {
             emit_typescript_definitions: false,
         },
@@ -0,0 +1,682 @@
+//! The `gleam completions` command, which prints a script for a shell that
+//! completes the subcommands, flags, and flag values of the CLI.
+//!
+//! The scripts are generated from the clap definition of the CLI so they
+//! stay in step with it. The names of the dependency packages of a project
+//! are read from its manifest.toml by the script when they are completed.
+
+use std::fmt::Write;
+
+use clap::Command;
+use gleam_core::build::Target;
+use strum::VariantNames;
+
+#[derive(Debug, strum::EnumString, strum::EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+/// The arguments that are completed with the dependency packages of the
+/// project, as they name packages already in the manifest.
+const PACKAGE_ARGUMENTS: [(&str, &str); 3] = [
+    ("gleam__remove", "packages"),
+    ("gleam__deps__update", "packages"),
+    ("gleam__clean", "package"),
+];
+
+pub fn print(shell: CompletionShell, mut command: Command<'_>) {
+    // Building the command adds the help flags and the global flags to each
+    // subcommand
+    command.build();
+    let commands = specs(&command);
+    let script = match shell {
+        CompletionShell::Bash => bash(&commands),
+        CompletionShell::Zsh => zsh(&commands),
+        CompletionShell::Fish => fish(&commands),
+        CompletionShell::Powershell => powershell(&commands),
+    };
+    print!("{script}");
+}
+
+/// A command or subcommand of the CLI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Spec {
+    /// The names of the command and the commands it is a subcommand of,
+    /// joined with `__`, such as `gleam__deps__update`.
+    path: String,
+    subcommands: Vec<Named>,
+    options: Vec<Opt>,
+    /// What the positional arguments of the command are completed with.
+    positional: Values,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Named {
+    name: String,
+    help: String,
+}
+
+/// A flag of a command, which may take a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Opt {
+    long: Option<String>,
+    short: Option<char>,
+    help: String,
+    values: Option<Values>,
+}
+
+impl Opt {
+    fn names(&self) -> Vec<String> {
+        self.long
+            .iter()
+            .map(|long| format!("--{long}"))
+            .chain(self.short.iter().map(|short| format!("-{short}")))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Values {
+    /// Any value, completed with the names of files.
+    Any,
+    Choices(Vec<String>),
+    /// The names of the dependency packages of the project.
+    Packages,
+}
+
+fn specs(command: &Command<'_>) -> Vec<Spec> {
+    let mut specs = vec![];
+    collect_specs(command, "gleam".into(), &mut specs);
+    specs
+}
+
+fn collect_specs(command: &Command<'_>, path: String, specs: &mut Vec<Spec>) {
+    let subcommands = command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set() && subcommand.get_name() != "help")
+        .collect::<Vec<_>>();
+
+    let options = command
+        .get_arguments()
+        .filter(|arg| !arg.is_positional() && !arg.is_hide_set())
+        .map(|arg| Opt {
+            long: arg.get_long().map(String::from),
+            short: arg.get_short(),
+            help: first_line(arg.get_help()),
+            values: arg
+                .is_takes_value_set()
+                .then(|| values(&path, arg, command)),
+        })
+        .collect();
+
+    let positional = command
+        .get_arguments()
+        .find(|arg| arg.is_positional() && !arg.is_hide_set())
+        .map_or(Values::Any, |arg| values(&path, arg, command));
+
+    specs.push(Spec {
+        path: path.clone(),
+        subcommands: subcommands
+            .iter()
+            .map(|subcommand| Named {
+                name: subcommand.get_name().into(),
+                help: first_line(subcommand.get_about()),
+            })
+            .collect(),
+        options,
+        positional,
+    });
+
+    for subcommand in subcommands {
+        collect_specs(
+            subcommand,
+            format!("{path}__{}", subcommand.get_name()),
+            specs,
+        );
+    }
+}
+
+fn values(path: &str, arg: &clap::Arg<'_>, command: &Command<'_>) -> Values {
+    if let Some(values) = arg.get_possible_values() {
+        return Values::Choices(
+            values
+                .iter()
+                .filter(|value| !value.is_hide_set())
+                .map(|value| value.get_name().to_string())
+                .collect(),
+        );
+    }
+    if PACKAGE_ARGUMENTS.contains(&(path, arg.get_id())) {
+        return Values::Packages;
+    }
+    // The targets are parsed from their names rather than being given as
+    // possible values
+    if arg.get_long() == Some("target") {
+        let mut targets = Target::VARIANTS
+            .iter()
+            .map(|target| target.to_string())
+            .collect::<Vec<_>>();
+        if command.get_name() == "build" {
+            targets.push("all".into());
+        }
+        return Values::Choices(targets);
+    }
+    Values::Any
+}
+
+fn first_line(help: Option<&str>) -> String {
+    help.and_then(|help| help.lines().next())
+        .unwrap_or_default()
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// A command that prints the names of the packages in the manifest.toml of
+/// the current directory, for the shell scripts.
+const MANIFEST_PACKAGES: &str =
+    r#"sed -n 's/^ *{ *name = "\([^"]*\)".*/\1/p' manifest.toml 2>/dev/null"#;
+
+fn bash(commands: &[Spec]) -> String {
+    let mut transitions = String::new();
+    let mut cases = String::new();
+    for spec in commands {
+        for subcommand in &spec.subcommands {
+            let next = format!("{}__{}", spec.path, subcommand.name);
+            let _ = writeln!(transitions, "            {next}) cmd={next} ;;");
+        }
+
+        let mut flags = vec![];
+        let mut option_values = String::new();
+        for option in &spec.options {
+            flags.extend(option.names());
+            if let Some(values) = &option.values {
+                let _ = writeln!(
+                    option_values,
+                    "                {})\n                    {}\n                    return\n                    ;;",
+                    option.names().join(" | "),
+                    bash_reply(values),
+                );
+            }
+        }
+        let _ = writeln!(cases, "        {})", spec.path);
+        if !option_values.is_empty() {
+            let _ = write!(
+                cases,
+                "            case \"$prev\" in\n{option_values}            esac\n"
+            );
+        }
+        let _ = writeln!(
+            cases,
+            "            if [[ \"$cur\" == -* ]]; then
+                COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))
+            else
+                {}
+            fi
+            ;;",
+            flags.join(" "),
+            if spec.subcommands.is_empty() {
+                bash_reply(&spec.positional)
+            } else {
+                bash_reply(&Values::Choices(
+                    spec.subcommands.iter().map(|s| s.name.clone()).collect(),
+                ))
+            },
+        );
+    }
+
+    format!(
+        r#"# Completions for the gleam command, written by `gleam completions bash`.
+
+_gleam() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    local cmd=gleam i
+    COMPREPLY=()
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${{cmd}}__${{COMP_WORDS[i]}}" in
+{transitions}        esac
+    done
+
+    case "$cmd" in
+{cases}    esac
+}}
+
+complete -o default -F _gleam gleam
+"#
+    )
+}
+
+fn bash_reply(values: &Values) -> String {
+    match values {
+        // Leaving the reply empty falls back to completing file names
+        Values::Any => "COMPREPLY=()".into(),
+        Values::Choices(choices) => {
+            format!(
+                "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                choices.join(" ")
+            )
+        }
+        Values::Packages => {
+            format!("COMPREPLY=($(compgen -W \"$({MANIFEST_PACKAGES})\" -- \"$cur\"))")
+        }
+    }
+}
+
+fn zsh(commands: &[Spec]) -> String {
+    let mut transitions = String::new();
+    let mut cases = String::new();
+    for spec in commands {
+        for subcommand in &spec.subcommands {
+            let next = format!("{}__{}", spec.path, subcommand.name);
+            let _ = writeln!(transitions, "            {next}) cmd={next} ;;");
+        }
+
+        let _ = writeln!(cases, "        {})", spec.path);
+        let valued = spec
+            .options
+            .iter()
+            .filter_map(|option| Some((option, option.values.as_ref()?)))
+            .collect::<Vec<_>>();
+        if !valued.is_empty() {
+            let _ = writeln!(cases, "            case \"$prev\" in");
+            for (option, values) in valued {
+                let _ = writeln!(
+                    cases,
+                    "                {})\n                    {}\n                    return\n                    ;;",
+                    option.names().join("|"),
+                    zsh_values(values)
+                );
+            }
+            let _ = writeln!(cases, "            esac");
+        }
+        let flags = spec
+            .options
+            .iter()
+            .flat_map(|option| {
+                option
+                    .names()
+                    .into_iter()
+                    .map(|name| format!("'{}:{}'", name, zsh_escape(&option.help)))
+            })
+            .collect::<Vec<_>>();
+        let _ = writeln!(cases, "            if [[ \"$cur\" == -* ]]; then");
+        let _ = writeln!(
+            cases,
+            "                local -a flags=({})",
+            flags.join(" ")
+        );
+        let _ = writeln!(cases, "                _describe 'flag' flags");
+        let _ = writeln!(cases, "            else");
+        if spec.subcommands.is_empty() {
+            let _ = writeln!(cases, "                {}", zsh_values(&spec.positional));
+        } else {
+            let subcommands = spec
+                .subcommands
+                .iter()
+                .map(|subcommand| format!("'{}:{}'", subcommand.name, zsh_escape(&subcommand.help)))
+                .collect::<Vec<_>>();
+            let _ = writeln!(
+                cases,
+                "                local -a commands=({})",
+                subcommands.join(" ")
+            );
+            let _ = writeln!(cases, "                _describe 'command' commands");
+        }
+        let _ = writeln!(cases, "            fi\n            ;;");
+    }
+
+    format!(
+        r#"#compdef gleam
+# Completions for the gleam command, written by `gleam completions zsh`.
+
+_gleam() {{
+    local cur="${{words[CURRENT]}}"
+    local prev="${{words[CURRENT-1]}}"
+    local cmd=gleam i
+
+    for ((i = 2; i < CURRENT; i++)); do
+        case "${{cmd}}__${{words[i]}}" in
+{transitions}        esac
+    done
+
+    case "$cmd" in
+{cases}    esac
+}}
+
+if [ "$funcstack[1]" = "_gleam" ]; then
+    _gleam "$@"
+else
+    compdef _gleam gleam
+fi
+"#
+    )
+}
+
+fn zsh_values(values: &Values) -> String {
+    match values {
+        Values::Any => "_files".into(),
+        Values::Choices(choices) => format!("compadd -- {}", choices.join(" ")),
+        Values::Packages => format!("compadd -- ${{(f)\"$({MANIFEST_PACKAGES})\"}}"),
+    }
+}
+
+/// Escapes text for a single quoted `name:description` item of `_describe`.
+fn zsh_escape(text: &str) -> String {
+    text.replace('\'', "'\\''").replace(':', "\\:")
+}
+
+fn fish(commands: &[Spec]) -> String {
+    let paths = commands
+        .iter()
+        .map(|spec| spec.path.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut script = format!(
+        r#"# Completions for the gleam command, written by `gleam completions fish`.
+
+set -g __gleam_commands {paths}
+
+function __gleam_command_is
+    set -l command gleam
+    for token in (commandline -opc)[2..-1]
+        if contains -- {{$command}}__$token $__gleam_commands
+            set command {{$command}}__$token
+        end
+    end
+    test "$command" = "$argv[1]"
+end
+
+complete -c gleam -f
+"#
+    );
+
+    for spec in commands {
+        let condition = format!("-n '__gleam_command_is {}'", spec.path);
+        for subcommand in &spec.subcommands {
+            let _ = writeln!(
+                script,
+                "complete -c gleam {condition} -a {} -d '{}'",
+                subcommand.name,
+                fish_escape(&subcommand.help)
+            );
+        }
+        for option in &spec.options {
+            let mut line = format!("complete -c gleam {condition}");
+            if let Some(long) = &option.long {
+                let _ = write!(line, " -l {long}");
+            }
+            if let Some(short) = option.short {
+                let _ = write!(line, " -s {short}");
+            }
+            match &option.values {
+                None => (),
+                Some(values) => line.push_str(&fish_values(values)),
+            }
+            let _ = writeln!(line, " -d '{}'", fish_escape(&option.help));
+            script.push_str(&line);
+        }
+        if spec.subcommands.is_empty() {
+            let values = match &spec.positional {
+                Values::Any => " -F".into(),
+                values => fish_values(values),
+            };
+            let _ = writeln!(script, "complete -c gleam {condition}{values}");
+        }
+    }
+    script
+}
+
+fn fish_values(values: &Values) -> String {
+    match values {
+        Values::Any => " -r -F".into(),
+        Values::Choices(choices) => format!(" -x -a '{}'", choices.join(" ")),
+        Values::Packages => format!(" -x -a '({})'", MANIFEST_PACKAGES.replace('\'', "\\'")),
+    }
+}
+
+fn fish_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn powershell(commands: &[Spec]) -> String {
+    let mut cases = String::new();
+    for spec in commands {
+        let mut lines = vec![];
+        for subcommand in &spec.subcommands {
+            lines.push(format!(
+                "[CompletionResult]::new('{0}', '{0}', [CompletionResultType]::ParameterValue, '{1}')",
+                subcommand.name,
+                powershell_escape(&subcommand.help)
+            ));
+        }
+        for option in &spec.options {
+            for name in option.names() {
+                lines.push(format!(
+                    "[CompletionResult]::new('{0}', '{0}', [CompletionResultType]::ParameterName, '{1}')",
+                    name,
+                    powershell_escape(&option.help)
+                ));
+            }
+        }
+        if spec.subcommands.is_empty() {
+            lines.extend(powershell_values(&spec.positional));
+        }
+        let _ = writeln!(cases, "        '{}' {{", spec.path);
+        for line in lines {
+            let _ = writeln!(cases, "            {line}");
+        }
+        let _ = writeln!(cases, "            break\n        }}");
+
+        for option in &spec.options {
+            let values = match &option.values {
+                Some(values) => values,
+                None => continue,
+            };
+            for name in option.names() {
+                let _ = writeln!(cases, "        '{};{}' {{", spec.path, name);
+                for line in powershell_values(values) {
+                    let _ = writeln!(cases, "            {line}");
+                }
+                let _ = writeln!(cases, "            break\n        }}");
+            }
+        }
+    }
+
+    let paths = commands
+        .iter()
+        .map(|spec| format!("'{}'", spec.path))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r#"# Completions for the gleam command, written by `gleam completions powershell`.
+
+using namespace System.Management.Automation
+
+Register-ArgumentCompleter -Native -CommandName 'gleam' -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $commands = @({paths})
+    $command = 'gleam'
+    $previous = ''
+    foreach ($element in $commandAst.CommandElements | Select-Object -Skip 1) {{
+        if ($element.Extent.EndOffset -ge $cursorPosition) {{
+            break
+        }}
+        $next = "$($command)__$element"
+        if ($commands -contains $next) {{
+            $command = $next
+        }}
+        $previous = $element.ToString()
+    }}
+
+    $key = "$command;$previous"
+    $completions = @(switch ($key, $command) {{
+{cases}    }})
+
+    $completions |
+        Where-Object {{ $_.CompletionText -like "$wordToComplete*" }} |
+        Sort-Object -Property ListItemText
+}}
+"#
+    )
+}
+
+fn powershell_values(values: &Values) -> Vec<String> {
+    match values {
+        // Returning nothing falls back to completing file names
+        Values::Any => vec![],
+        Values::Choices(choices) => choices
+            .iter()
+            .map(|choice| {
+                format!(
+                    "[CompletionResult]::new('{0}', '{0}', [CompletionResultType]::ParameterValue, '{0}')",
+                    choice
+                )
+            })
+            .collect(),
+        Values::Packages => vec![
+            "Get-Content manifest.toml -ErrorAction SilentlyContinue | ForEach-Object { if ($_ -match '^\\s*\\{\\s*name = \"([^\"]+)\"') { [CompletionResult]::new($Matches[1], $Matches[1], [CompletionResultType]::ParameterValue, $Matches[1]) } }".into(),
+        ],
+    }
+}
+
+fn powershell_escape(text: &str) -> String {
+    text.replace('\'', "''")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command() -> Command<'static> {
+        let mut command = Command::new("gleam")
+            .arg(clap::Arg::new("offline").long("offline").global(true))
+            .subcommand(
+                Command::new("build")
+                    .about("Build the project")
+                    .arg(clap::Arg::new("target").long("target").takes_value(true)),
+            )
+            .subcommand(
+                Command::new("deps")
+                    .about("Work with dependency packages")
+                    .subcommand(
+                        Command::new("update")
+                            .about("Update dependency packages")
+                            .arg(clap::Arg::new("packages").multiple_values(true)),
+                    ),
+            )
+            .subcommand(
+                Command::new("export").subcommand(
+                    Command::new("dep-graph").arg(
+                        clap::Arg::new("format")
+                            .long("format")
+                            .possible_values(["dot", "json"]),
+                    ),
+                ),
+            )
+            .subcommand(Command::new("lsp").hide(true));
+        command.build();
+        command
+    }
+
+    #[test]
+    fn specs_of_commands() {
+        let specs = specs(&command());
+        let paths = specs
+            .iter()
+            .map(|spec| spec.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "gleam",
+                "gleam__build",
+                "gleam__deps",
+                "gleam__deps__update",
+                "gleam__export",
+                "gleam__export__dep-graph"
+            ]
+        );
+    }
+
+    #[test]
+    fn values_of_arguments() {
+        let specs = specs(&command());
+        let option = |path: &str, long: &str| {
+            specs
+                .iter()
+                .find(|spec| spec.path == path)
+                .and_then(|spec| {
+                    spec.options
+                        .iter()
+                        .find(|option| option.long.as_deref() == Some(long))
+                })
+                .cloned()
+        };
+
+        let mut targets: Vec<String> = Target::VARIANTS.iter().map(|t| t.to_string()).collect();
+        targets.push("all".into());
+        assert_eq!(
+            option("gleam__build", "target").and_then(|option| option.values),
+            Some(Values::Choices(targets))
+        );
+        assert_eq!(
+            option("gleam__export__dep-graph", "format").and_then(|option| option.values),
+            Some(Values::Choices(vec!["dot".into(), "json".into()]))
+        );
+        // Global flags are given to every subcommand
+        assert_eq!(
+            option("gleam__deps__update", "offline").map(|option| option.values),
+            Some(None)
+        );
+        assert_eq!(
+            specs
+                .iter()
+                .find(|spec| spec.path == "gleam__deps__update")
+                .map(|spec| &spec.positional),
+            Some(&Values::Packages)
+        );
+    }
+
+    #[test]
+    fn bash_script() {
+        let script = bash(&specs(&command()));
+        assert!(script.contains("            gleam__deps__update) cmd=gleam__deps__update ;;\n"));
+        assert!(script.contains(
+            "                --format)\n                    COMPREPLY=($(compgen -W \"dot json\" -- \"$cur\"))\n"
+        ));
+        assert!(script.ends_with("complete -o default -F _gleam gleam\n"));
+    }
+
+    #[test]
+    fn zsh_script() {
+        let script = zsh(&specs(&command()));
+        assert!(script.starts_with("#compdef gleam\n"));
+        assert!(script.contains(
+            "                local -a commands=('build:Build the project' 'deps:Work with dependency packages' 'export:')\n"
+        ));
+    }
+
+    #[test]
+    fn fish_script() {
+        let script = fish(&specs(&command()));
+        assert!(script.contains(
+            "complete -c gleam -n '__gleam_command_is gleam__export__dep-graph' -l format -x -a 'dot json' -d ''\n"
+        ));
+        assert!(script.contains(
+            "complete -c gleam -n '__gleam_command_is gleam' -a build -d 'Build the project'\n"
+        ));
+    }
+
+    #[test]
+    fn powershell_script() {
+        let script = powershell(&specs(&command()));
+        assert!(script.contains("        'gleam__export__dep-graph;--format' {\n"));
+        assert!(script.contains("Register-ArgumentCompleter -Native -CommandName 'gleam'"));
+    }
+}
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use gleam_core::{
+    build::{LocalPackage, Mode},
     config::PackageConfig,
     error::{Error, FileIoAction, FileKind},
     paths,
@@ -10,9 +11,86 @@ pub fn root_config() -> Result<PackageConfig, Error> {
     read(paths::root_config())
 }
 
+/// The packages listed as members of the workspace of the root package.
+pub fn workspace_members(config: &PackageConfig) -> Result<Vec<LocalPackage>, Error> {
+    config
+        .workspace
+        .members
+        .iter()
+        .map(|root| {
+            Ok(LocalPackage {
+                config: read(root.join(paths::root_config()))?,
+                root: root.clone(),
+            })
+        })
+        .collect()
+}
+
+/// The packages of the project other than the root package: the members of
+/// its workspace, and the packages depended upon by path by the root package
+/// or by another local package.
+pub fn local_packages(config: &PackageConfig, mode: Mode) -> Result<Vec<LocalPackage>, Error> {
+    let mut packages = workspace_members(config)?;
+    let mut to_visit = vec![(PathBuf::new(), config.clone())];
+    to_visit.extend(
+        packages
+            .iter()
+            .map(|package| (package.root.clone(), package.config.clone())),
+    );
+    while let Some((root, dependent)) = to_visit.pop() {
+        for (name, path) in dependent.path_dependencies_for(mode)? {
+            let known = |package: &LocalPackage| package.config.name == name;
+            if name == config.name || packages.iter().any(known) {
+                continue;
+            }
+            let path = root.join(path);
+            let mut package = read(path.join(paths::root_config()))?;
+            if package.name != name {
+                return Err(Error::WrongDependencyProvided {
+                    path,
+                    expected: name,
+                    found: package.name,
+                });
+            }
+            // Only the dependencies of a path package are needed to use it
+            package.dev_dependencies.clear();
+            to_visit.push((path.clone(), package.clone()));
+            packages.push(LocalPackage {
+                config: package,
+                root: path,
+            });
+        }
+    }
+    Ok(packages)
+}
+
+/// The gleam.toml of the root package as a document that can be edited and
+/// written back without changing its formatting or comments.
+pub fn root_config_document() -> Result<toml_edit::Document, Error> {
+    let path = paths::root_config();
+    crate::fs::read(&path)?
+        .parse::<toml_edit::Document>()
+        .map_err(|e| Error::FileIo {
+            kind: FileKind::File,
+            action: FileIoAction::Parse,
+            path,
+            err: Some(e.to_string()),
+        })
+}
+
 pub fn read(config_path: PathBuf) -> Result<PackageConfig, Error> {
     let toml = crate::fs::read(&config_path)?;
-    toml::from_str(&toml).map_err(|e| Error::FileIo {
+    parse(&toml, config_path)
+}
+
+/// The root package config of a gleam.toml document that has been edited but
+/// not yet written.
+pub fn root_config_from_document(document: &toml_edit::Document) -> Result<PackageConfig, Error> {
+    parse(&document.to_string(), paths::root_config())
+}
+
+fn parse(toml: &str, config_path: PathBuf) -> Result<PackageConfig, Error> {
+    toml::from_str(toml).map_err(|e| Error::FileIo {
         action: FileIoAction::Parse,
         kind: FileKind::File,
         path: config_path,
@@ -0,0 +1,146 @@
+//! The Hex API keys stored by `gleam hex authenticate`, with which packages
+//! are published and managed without entering a password each time.
+//!
+//! Keys are stored by name in a file of the user's config directory that only
+//! they can read:
+//!
+//! ```toml
+//! [keys.default]
+//! registry = "hexpm"
+//! key_name = "gleam-laptop-default-1665000000"
+//! secret = "..."
+//!
+//! [keys.work]
+//! registry = "hexpm"
+//! organization = "acme"
+//! key_name = "gleam-laptop-work-1665000000"
+//! secret = "..."
+//! ```
+
+use std::collections::BTreeMap;
+
+use gleam_core::{
+    error::{FileIoAction, FileKind},
+    io::FileSystemReader,
+    paths, Error, Result,
+};
+
+use crate::fs::{self, ProjectIO};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Credentials {
+    #[serde(default)]
+    pub keys: BTreeMap<String, StoredKey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StoredKey {
+    pub registry: String,
+    /// The organization the key is used to publish the packages of, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+    /// The name of the key on the registry, with which it is revoked.
+    pub key_name: String,
+    pub secret: String,
+}
+
+impl Credentials {
+    pub fn read() -> Result<Self> {
+        let path = paths::hex_credentials();
+        let io = ProjectIO::new();
+        if !io.is_file(&path) {
+            return Ok(Self::default());
+        }
+        let toml = io.read(&path)?;
+        toml::from_str(&toml).map_err(|e| Error::FileIo {
+            action: FileIoAction::Parse,
+            kind: FileKind::File,
+            path,
+            err: Some(e.to_string()),
+        })
+    }
+
+    pub fn write(&self) -> Result<()> {
+        let path = paths::hex_credentials();
+        let toml = toml::to_string(self).expect("hex credentials serialization");
+        fs::write_private(&path, &toml)
+    }
+
+    pub fn get(&self, name: &str) -> Result<&StoredKey> {
+        self.keys.get(name).ok_or_else(|| Error::UnknownHexKey {
+            name: name.to_string(),
+        })
+    }
+
+    /// The key to use with the registry and organization, preferring the key
+    /// named `default` if there are several. Keys of other registries or
+    /// organizations are never used.
+    pub fn for_registry(&self, registry: &str, organization: Option<&str>) -> Option<&StoredKey> {
+        let keys = || {
+            self.keys.iter().filter(|(_, key)| {
+                key.registry == registry && key.organization.as_deref() == organization
+            })
+        };
+        keys()
+            .find(|(name, _)| *name == "default")
+            .or_else(|| keys().next())
+            .map(|(_, key)| key)
+    }
+}
+
+#[cfg(test)]
+fn stored_key(registry: &str, organization: Option<&str>, secret: &str) -> StoredKey {
+    StoredKey {
+        registry: registry.to_string(),
+        organization: organization.map(String::from),
+        key_name: format!("gleam-test-{}", secret),
+        secret: secret.to_string(),
+    }
+}
+
+#[test]
+fn credentials_for_registry() {
+    let credentials = Credentials {
+        keys: [
+            ("a".to_string(), stored_key("hexpm", None, "a")),
+            ("default".to_string(), stored_key("hexpm", None, "default")),
+            (
+                "work".to_string(),
+                stored_key("hexpm", Some("acme"), "work"),
+            ),
+            ("other".to_string(), stored_key("private", None, "other")),
+        ]
+        .into(),
+    };
+    let secret = |registry, organization| {
+        credentials
+            .for_registry(registry, organization)
+            .map(|key| key.secret.as_str())
+    };
+    assert_eq!(secret("hexpm", Some("acme")), Some("work"));
+    assert_eq!(secret("hexpm", Some("other")), None);
+    assert_eq!(secret("hexpm", None), Some("default"));
+    assert_eq!(secret("private", None), Some("other"));
+    assert_eq!(secret("unknown", None), None);
+}
+
+#[test]
+fn credentials_toml() {
+    let credentials = Credentials {
+        keys: [("work".to_string(), stored_key("hexpm", Some("acme"), "s"))].into(),
+    };
+    let toml = toml::to_string(&credentials).expect("credentials toml");
+    assert_eq!(
+        toml,
+        r#"[keys.work]
+registry = "hexpm"
+organization = "acme"
+key_name = "gleam-test-s"
+secret = "s"
+"#
+    );
+    assert_eq!(
+        toml::from_str::<Credentials>(&toml).expect("credentials"),
+        credentials
+    );
+}
@@ -1,16 +1,24 @@
 use std::{
+    cell::RefCell,
     collections::{HashMap, HashSet},
-    time::Instant,
+    io::{Read, Write},
+    path::Path,
+    rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Instant, SystemTime},
 };
 
 use flate2::read::GzDecoder;
 use futures::future;
 use gleam_core::{
-    build::{Mode, Telemetry},
-    config::PackageConfig,
+    build::{Mode, Target, Telemetry},
+    config::{LicencePolicy, PackageConfig, Requirement, SpdxLicense},
     error::{FileIoAction, FileKind, StandardIoAction},
-    hex::{self, HEXPM_PUBLIC_KEY},
-    io::{HttpClient as _, TarUnpacker, Utf8Writer, WrappedReader},
+    hex::{self, Registries, Registry},
+    io::{
+        FileSystemReader, FileSystemWriter, HttpClient as _, TarUnpacker, Utf8Writer, WrappedReader,
+    },
+    licences::{self, LicenceFormat, PackageLicences},
     manifest::{Base16Checksum, Manifest, ManifestPackage, ManifestPackageSource},
     paths, Error, Result,
 };
@@ -22,6 +30,7 @@ use crate::{
     cli,
     fs::{self, ProjectIO},
     http::HttpClient,
+    telemetry::NullTelemetry,
 };
 
 pub fn list() -> Result<()> {
@@ -63,6 +72,7 @@ fn list_manifest_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    registry: None,
                 },
             },
             ManifestPackage {
@@ -73,6 +83,7 @@ fn list_manifest_format() {
                 requirements: vec!["zzz".into(), "gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    registry: None,
                 },
             },
             ManifestPackage {
@@ -83,6 +94,7 @@ fn list_manifest_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    registry: None,
                 },
             },
         ],
@@ -97,14 +109,407 @@ zzz 0.4.0
     )
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Print the locked dependency packages as a tree, or with `invert` the
+/// packages that depend upon the given package, down to the root package.
+/// Only manifest.toml is read, so this works without network access.
+pub fn tree(invert: Option<String>) -> Result<()> {
+    let config = crate::config::root_config()?;
+    let manifest = read_manifest_from_disc()?;
+    let tree = dependency_tree(&config, &manifest, invert.as_deref())?;
+    std::io::stdout()
+        .write_all(tree.as_bytes())
+        .map_err(|e| Error::StandardIo {
+            action: StandardIoAction::Write,
+            err: Some(e.kind()),
+        })
+}
+
+fn dependency_tree(
+    config: &PackageConfig,
+    manifest: &Manifest,
+    invert: Option<&str>,
+) -> Result<String> {
+    let versions: HashMap<&str, &Version> = manifest
+        .packages
+        .iter()
+        .map(|package| (package.name.as_str(), &package.version))
+        .chain(std::iter::once((config.name.as_str(), &config.version)))
+        .collect();
+
+    let mut edges: HashMap<&str, Vec<&str>> = manifest
+        .packages
+        .iter()
+        .map(|package| {
+            let requirements = package.requirements.iter().map(String::as_str);
+            (package.name.as_str(), requirements.sorted().collect())
+        })
+        .collect();
+    let _ = edges.insert(
+        &config.name,
+        manifest
+            .requirements
+            .keys()
+            .map(String::as_str)
+            .sorted()
+            .collect(),
+    );
+
+    let root = match invert {
+        None => config.name.as_str(),
+        Some(package) if versions.contains_key(package) => {
+            let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+            for (dependent, dependencies) in &edges {
+                for dependency in dependencies {
+                    dependents.entry(dependency).or_default().push(dependent);
+                }
+            }
+            dependents
+                .values_mut()
+                .for_each(|dependents| dependents.sort());
+            edges = dependents;
+            package
+        }
+        Some(package) => {
+            return Err(Error::UnknownDependencies {
+                packages: vec![package.to_string()],
+            })
+        }
+    };
+
+    let label = |name: &str| {
+        let version = versions
+            .get(name)
+            .map(|version| format!(" v{}", version))
+            .unwrap_or_default();
+        let dev = if invert.is_none() && config.dev_dependencies.contains_key(name) {
+            " (dev)"
+        } else {
+            ""
+        };
+        format!("{}{}{}", name, version, dev)
+    };
+
+    let mut tree = format!("{}\n", label(root));
+    let mut expanded = HashSet::from([root]);
+    write_dependency_subtree(&mut tree, root, "", &edges, &label, &mut expanded);
+    Ok(tree)
+}
+
+/// Packages reached by more than one path are only expanded the first time
+/// they are printed, and are marked with `(*)` after that.
+fn write_dependency_subtree<'a>(
+    tree: &mut String,
+    package: &'a str,
+    prefix: &str,
+    edges: &HashMap<&'a str, Vec<&'a str>>,
+    label: &impl Fn(&str) -> String,
+    expanded: &mut HashSet<&'a str>,
+) {
+    let children = edges.get(package).map(Vec::as_slice).unwrap_or_default();
+    for (index, child) in children.iter().enumerate() {
+        let last = index + 1 == children.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        let has_children = edges.get(child).map_or(false, |c| !c.is_empty());
+        if has_children && !expanded.insert(child) {
+            tree.push_str(&format!("{}{}{} (*)\n", prefix, branch, label(child)));
+            continue;
+        }
+        tree.push_str(&format!("{}{}{}\n", prefix, branch, label(child)));
+        let prefix = format!("{}{}", prefix, indent);
+        write_dependency_subtree(tree, child, &prefix, edges, label, expanded);
+    }
+}
+
+#[cfg(test)]
+fn tree_manifest() -> (PackageConfig, Manifest) {
+    let package = |name: &str, version, requirements: &[&str]| ManifestPackage {
+        name: name.to_string(),
+        version: Version::parse(version).expect("version"),
+        build_tools: ["gleam".into()].into(),
+        otp_app: None,
+        requirements: requirements.iter().map(|r| r.to_string()).collect(),
+        source: ManifestPackageSource::Hex {
+            outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+            registry: None,
+        },
+    };
+    let mut config = PackageConfig::default();
+    config.name = "app".into();
+    config.version = Version::new(1, 0, 0);
+    let _ = config
+        .dependencies
+        .insert("gleam_http".into(), Requirement::hex("~> 3.0"));
+    let _ = config
+        .dependencies
+        .insert("gleam_stdlib".into(), Requirement::hex("~> 0.25"));
+    let _ = config
+        .dev_dependencies
+        .insert("gleeunit".into(), Requirement::hex("~> 0.7"));
+    let manifest = Manifest {
+        requirements: config.all_dependencies().expect("dependencies"),
+        packages: vec![
+            package("gleam_http", "3.1.0", &["gleam_stdlib"]),
+            package("gleam_stdlib", "0.25.0", &[]),
+            package("gleeunit", "0.7.2", &["gleam_http", "gleam_stdlib"]),
+        ],
+    };
+    (config, manifest)
+}
+
+#[test]
+fn dependency_tree_format() {
+    let (config, manifest) = tree_manifest();
+    assert_eq!(
+        dependency_tree(&config, &manifest, None).expect("tree"),
+        "app v1.0.0
+├── gleam_http v3.1.0
+│   └── gleam_stdlib v0.25.0
+├── gleam_stdlib v0.25.0
+└── gleeunit v0.7.2 (dev)
+    ├── gleam_http v3.1.0 (*)
+    └── gleam_stdlib v0.25.0
+"
+    );
+}
+
+#[test]
+fn dependency_tree_inverted() {
+    let (config, manifest) = tree_manifest();
+    assert_eq!(
+        dependency_tree(&config, &manifest, Some("gleam_stdlib")).expect("tree"),
+        "gleam_stdlib v0.25.0
+├── app v1.0.0
+├── gleam_http v3.1.0
+│   ├── app v1.0.0
+│   └── gleeunit v0.7.2
+│       └── app v1.0.0
+└── gleeunit v0.7.2 (*)
+"
+    );
+    assert!(dependency_tree(&config, &manifest, Some("unknown")).is_err());
+}
+
+/// Print the licences of the dependency packages, returning an error if any
+/// of them are not allowed by the given licences.
+pub fn licences(format: LicenceFormat, allow: Vec<SpdxLicense>) -> Result<()> {
+    let config = crate::config::root_config()?;
+    // Progress is only reported for the text format so that a report printed
+    // to stdout in a machine readable format can be redirected to a file.
+    let manifest = match format {
+        LicenceFormat::Text => download(cli::Reporter::new(), None, UseManifest::Yes)?,
+        LicenceFormat::Json
+        | LicenceFormat::Csv
+        | LicenceFormat::Spdx
+        | LicenceFormat::CycloneDx => download(NullTelemetry, None, UseManifest::Yes)?,
+    };
+    let packages = package_licences(&manifest, &config.name);
+    let created = utc_timestamp(
+        fs::source_date_epoch()
+            .map(|seconds| SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(seconds))
+            .unwrap_or_else(SystemTime::now),
+    );
+    let output = licences::render(format, &config, &packages, &created);
+    std::io::stdout()
+        .write_all(output.as_bytes())
+        .map_err(|e| Error::StandardIo {
+            action: StandardIoAction::Write,
+            err: Some(e.kind()),
+        })?;
+
+    let packages = licences::policy_violations(&LicencePolicy { allow }, &packages);
+    if packages.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::DisallowedDependencyLicences { packages })
+    }
+}
+
+/// Return an error if any of the dependency packages have a licence that is
+/// not allowed by the `[dependency-licences]` section of gleam.toml.
+pub fn check_licence_policy(config: &PackageConfig, manifest: &Manifest) -> Result<()> {
+    if !config.dependency_licences.is_enforced() {
+        return Ok(());
+    }
+    let packages = package_licences(manifest, &config.name);
+    let packages = licences::policy_violations(&config.dependency_licences, &packages);
+    if packages.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::DisallowedDependencyLicences { packages })
+    }
+}
+
+// The licences of each dependency package, taken from the Hex metadata in the
+// package cache, from the gleam.toml of the downloaded package if the Hex
+// tarball is no longer in the cache, or else from the LICENSE file bundled
+// with the package.
+fn package_licences(manifest: &Manifest, project_name: &str) -> Vec<PackageLicences> {
+    manifest
+        .packages
+        .iter()
+        .filter(|package| package.name != project_name)
+        .map(|package| {
+            let licences = hex_metadata_licences(package)
+                .or_else(|| package_config_licences(package))
+                .or_else(|| licence_file_licences(package))
+                .unwrap_or_default();
+            PackageLicences {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                licences,
+            }
+        })
+        .collect()
+}
+
+fn hex_metadata_licences(package: &ManifestPackage) -> Option<Vec<String>> {
+    let registry = match &package.source {
+        ManifestPackageSource::Hex { registry, .. } => registry.as_deref().unwrap_or(hex::HEXPM),
+        ManifestPackageSource::Git { .. } => return None,
+    };
+    let tarball =
+        paths::package_cache_tarball(registry, &package.name, &package.version.to_string());
+    let mut archive = tar::Archive::new(ProjectIO::new().reader(&tarball).ok()?);
+    let mut entry = archive
+        .entries()
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .find(|entry| {
+            entry
+                .path()
+                .map(|path| path.as_ref() == Path::new("metadata.config"))
+                .unwrap_or(false)
+        })?;
+    let mut metadata = String::new();
+    let _ = entry.read_to_string(&mut metadata).ok()?;
+    let licences = licences::from_hex_metadata(&metadata);
+    if licences.is_empty() {
+        None
+    } else {
+        Some(licences)
+    }
+}
+
+fn package_config_licences(package: &ManifestPackage) -> Option<Vec<String>> {
+    let path = paths::build_deps_package_config(&package.name);
+    let config = PackageConfig::read(path, &ProjectIO::new()).ok()?;
+    let licences = config.licences.iter().map(|l| l.to_string()).collect_vec();
+    if licences.is_empty() {
+        None
+    } else {
+        Some(licences)
+    }
+}
+
+fn licence_file_licences(package: &ManifestPackage) -> Option<Vec<String>> {
+    let directory = paths::build_deps_package(&package.name);
+    let io = ProjectIO::new();
+    [
+        "LICENSE",
+        "LICENCE",
+        "LICENSE.md",
+        "LICENCE.md",
+        "LICENSE.txt",
+        "COPYING",
+    ]
+    .iter()
+    .filter_map(|name| io.read(&directory.join(name)).ok())
+    .find_map(|text| licences::from_licence_file(&text))
+    .map(|licence| vec![licence])
+}
+
+// Format a time as an ISO 8601 UTC timestamp, such as 2022-12-11T09:30:00Z.
+fn utc_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+    // Convert days since the epoch to a civil date.
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+#[test]
+fn utc_timestamp_format() {
+    let time = |seconds| SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(seconds);
+    assert_eq!(utc_timestamp(time(0)), "1970-01-01T00:00:00Z");
+    assert_eq!(utc_timestamp(time(951_782_400)), "2000-02-29T00:00:00Z");
+    assert_eq!(utc_timestamp(time(1_670_751_000)), "2022-12-11T09:30:00Z");
+}
+
+#[derive(Debug, Clone)]
 pub enum UseManifest {
     Yes,
     No,
+    /// Use the manifest, other than the locked versions of the given packages
+    /// and of the packages they depend upon.
+    Unlocking(Vec<String>),
+}
+
+/// When set no network access is made: dependencies are only taken from the
+/// locked versions of the manifest and the packages of the vendor directory.
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed)
+}
+
+pub(crate) fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Copy the sources of all the dependency packages to the vendor directory,
+/// replacing those vendored before, so that the project can be built with
+/// `--offline`.
+pub fn vendor() -> Result<()> {
+    let manifest = download(cli::Reporter::new(), None, UseManifest::Yes)?;
+    fs::delete_dir(&paths::vendor())?;
+    fs::mkdir(&paths::vendor())?;
+    for package in &manifest.packages {
+        fs::copy_dir(paths::build_deps_package(&package.name), paths::vendor())?;
+    }
+    LocalPackages::from_manifest(&manifest).write_to_disc(&paths::vendor_packages_toml())?;
+    cli::print_vendored(&match manifest.packages.len() {
+        1 => "1 package".to_string(),
+        count => format!("{} packages", count),
+    });
+    Ok(())
 }
 
-pub fn update() -> Result<()> {
-    download(cli::Reporter::new(), None, UseManifest::No).map(|_| ())
+pub fn update(packages: Vec<String>) -> Result<()> {
+    let use_manifest = if packages.is_empty() {
+        UseManifest::No
+    } else {
+        UseManifest::Unlocking(packages)
+    };
+    download(cli::Reporter::new(), None, use_manifest).map(|_| ())
 }
 
 pub fn download<Telem: Telemetry>(
@@ -114,6 +519,18 @@ pub fn download<Telem: Telemetry>(
     // manifest which will result in the latest versions of the dependency
     // packages being resolved (not the locked ones).
     use_manifest: UseManifest,
+) -> Result<Manifest> {
+    let config = crate::config::root_config()?;
+    download_for_config(telemetry, config, new_package, use_manifest)
+}
+
+/// Resolve and download the dependencies of the given root package config in
+/// place of the one in gleam.toml, such as one edited but not yet written.
+pub fn download_for_config<Telem: Telemetry>(
+    telemetry: Telem,
+    mut config: PackageConfig,
+    new_package: Option<(Vec<String>, bool)>,
+    use_manifest: UseManifest,
 ) -> Result<Manifest> {
     let span = tracing::info_span!("download_deps");
     let _enter = span.enter();
@@ -123,18 +540,12 @@ pub fn download<Telem: Telemetry>(
 
     let mode = Mode::Dev;
 
-    let http = HttpClient::boxed();
-    let fs = ProjectIO::boxed();
-    let downloader = hex::Downloader::new(fs, http, Untar::boxed());
-
-    // Read the project config
-    let mut config = crate::config::root_config()?;
     let project_name = config.name.clone();
 
     // Insert the new packages to add, if it exists
     if let Some((packages, dev)) = new_package {
         for package in packages {
-            let version = hexpm::version::Range::new(">= 0.0.0".into());
+            let version = Requirement::hex(">= 0.0.0");
             let _ = if dev {
                 config.dev_dependencies.insert(package.to_string(), version)
             } else {
@@ -143,6 +554,15 @@ pub fn download<Telem: Telemetry>(
         }
     }
 
+    // The dependencies of the workspace members and path dependencies are
+    // resolved along with those of the root package
+    let local = crate::config::local_packages(&config, mode)?;
+    let local = local
+        .into_iter()
+        .map(|package| package.config)
+        .collect_vec();
+    let config = config.with_local_dependencies(&local)?;
+
     // Start event loop so we can run async functions to call the Hex API
     let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
 
@@ -154,32 +574,79 @@ pub fn download<Telem: Telemetry>(
         &telemetry,
         use_manifest,
     )?;
-    let local = LocalPackages::read_from_disc()?;
+    let local = LocalPackages::read_from_disc(&paths::packages_toml())?;
 
     // Remove any packages that are no longer required due to gleam.toml changes
     remove_extra_packages(&local, &manifest)?;
 
+    // Use the vendored packages rather than downloading them
+    let local = copy_vendored_packages(&manifest, local, &project_name)?;
+
     // Download them from Hex to the local cache
     runtime.block_on(download_missing_packages(
-        downloader,
+        &config,
         &manifest,
         &local,
         project_name,
         &telemetry,
     ))?;
 
+    // Export the locked commits of the git packages from their repositories
+    export_missing_git_packages(&manifest, &local)?;
+
     // Record new state of the packages directory
     if manifest_updated {
         tracing::info!("writing_manifest_toml");
         write_manifest_to_disc(&manifest)?;
     }
-    LocalPackages::from_manifest(&manifest).write_to_disc()?;
+    LocalPackages::from_manifest(&manifest).write_to_disc(&paths::packages_toml())?;
 
     Ok(manifest)
 }
 
+/// Copy the packages missing from the packages directory from the vendor
+/// directory, if they are vendored at their locked version or commit. Returns
+/// the local packages along with those copied.
+fn copy_vendored_packages(
+    manifest: &Manifest,
+    mut local: LocalPackages,
+    project_name: &str,
+) -> Result<LocalPackages> {
+    let vendored = LocalPackages::read_from_disc(&paths::vendor_packages_toml())?;
+    for package in local.missing_local_packages(manifest, project_name) {
+        if vendored.packages.get(&package.name) == Some(&package.version) {
+            copy_vendored_package(&package.name)?;
+            let _ = local
+                .packages
+                .insert(package.name.clone(), package.version.clone());
+        }
+    }
+    for (package, _, commit) in local.missing_git_packages(manifest) {
+        if vendored.git.get(package).map(String::as_str) == Some(commit) {
+            copy_vendored_package(package)?;
+            let _ = local.git.insert(package.to_string(), commit.to_string());
+        }
+    }
+    Ok(local)
+}
+
+fn copy_vendored_package(package: &str) -> Result<()> {
+    tracing::info!(package = package, "copying_vendored_package");
+    fs::delete_dir(&paths::build_deps_package(package))?;
+    fs::mkdir(&paths::packages())?;
+    fs::copy_dir(paths::vendor_package(package), paths::packages())?;
+
+    // Code compiled from a previous version of the package is out of date
+    for mode in [Mode::Dev, Mode::Prod] {
+        for target in Target::ALL.into_iter().chain(Target::EXPERIMENTAL) {
+            fs::delete_dir(&paths::build_package(mode, target, package))?;
+        }
+    }
+    Ok(())
+}
+
 async fn download_missing_packages<Telem: Telemetry>(
-    downloader: hex::Downloader,
+    config: &PackageConfig,
     manifest: &Manifest,
     local: &LocalPackages,
     project_name: String,
@@ -194,17 +661,58 @@ async fn download_missing_packages<Telem: Telemetry>(
             package
         })
         .peekable();
+    if let Some(package) = missing.peek().filter(|_| is_offline()) {
+        return Err(Error::OfflinePackageNotVendored {
+            package: package.name.clone(),
+            version: package.version.to_string(),
+        });
+    }
     if missing.peek().is_some() {
         let start = Instant::now();
         telemetry.downloading_package("packages");
+        // The registries are only looked up when there is something to
+        // download, as looking up their keys may run commands
+        let registries = crate::hex::registries(config)?;
+        let downloader = hex::Downloader::new(
+            ProjectIO::boxed(),
+            HttpClient::boxed(),
+            Untar::boxed(),
+            registries,
+        );
         downloader
-            .download_hex_packages(missing, &project_name)
+            .download_hex_packages(missing, &project_name, telemetry)
             .await?;
         telemetry.packages_downloaded(start, count);
     }
     Ok(())
 }
 
+fn export_missing_git_packages(manifest: &Manifest, local: &LocalPackages) -> Result<()> {
+    for (package, repo, commit) in local.missing_git_packages(manifest) {
+        if is_offline() {
+            return Err(Error::OfflinePackageNotVendored {
+                package: package.to_string(),
+                version: commit.to_string(),
+            });
+        }
+        crate::git::export(
+            &ProjectIO::new(),
+            package,
+            repo,
+            commit,
+            &paths::build_deps_package(package),
+        )?;
+
+        // Code compiled from the previously exported commit is out of date
+        for mode in [Mode::Dev, Mode::Prod] {
+            for target in Target::ALL.into_iter().chain(Target::EXPERIMENTAL) {
+                fs::delete_dir(&paths::build_package(mode, target, package))?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn remove_extra_packages(local: &LocalPackages, manifest: &Manifest) -> Result<()> {
     for (package, version) in local.extra_local_packages(manifest) {
         let path = paths::build_deps_package(&package);
@@ -213,13 +721,20 @@ fn remove_extra_packages(local: &LocalPackages, manifest: &Manifest) -> Result<(
             fs::delete_dir(&path)?;
         }
     }
+    for (package, commit) in local.extra_git_packages(manifest) {
+        let path = paths::build_deps_package(&package);
+        if path.exists() {
+            tracing::info!(package=%package, commit=%commit, "removing_unneeded_package");
+            fs::delete_dir(&path)?;
+        }
+    }
     Ok(())
 }
 
-fn read_manifest_from_disc() -> Result<Manifest> {
+pub(crate) fn read_manifest_from_disc() -> Result<Manifest> {
     tracing::info!("Reading manifest.toml");
     let manifest_path = paths::manifest();
-    let toml = crate::fs::read(&manifest_path)?;
+    let toml = ProjectIO::new().read(&manifest_path)?;
     let manifest = toml::from_str(&toml).map_err(|e| Error::FileIo {
         action: FileIoAction::Parse,
         kind: FileKind::File,
@@ -229,7 +744,7 @@ fn read_manifest_from_disc() -> Result<Manifest> {
     Ok(manifest)
 }
 
-fn write_manifest_to_disc(manifest: &Manifest) -> Result<()> {
+pub fn write_manifest_to_disc(manifest: &Manifest) -> Result<()> {
     let path = paths::manifest();
     let mut file = fs::writer(&path)?;
     let result = manifest.write_to(&mut file);
@@ -237,9 +752,13 @@ fn write_manifest_to_disc(manifest: &Manifest) -> Result<()> {
     Ok(())
 }
 
+/// The packages in the packages directory: those downloaded from Hex by
+/// version, and those exported from git repositories by commit.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct LocalPackages {
     packages: HashMap<String, Version>,
+    #[serde(default)]
+    git: HashMap<String, String>,
 }
 
 impl LocalPackages {
@@ -247,6 +766,7 @@ impl LocalPackages {
         let manifest_packages: HashSet<_> = manifest
             .packages
             .iter()
+            .filter(|p| matches!(p.source, ManifestPackageSource::Hex { .. }))
             .map(|p| (&p.name, &p.version))
             .collect();
         self.packages
@@ -264,40 +784,81 @@ impl LocalPackages {
         manifest
             .packages
             .iter()
+            .filter(|p| matches!(p.source, ManifestPackageSource::Hex { .. }))
             .filter(|p| p.name != root && self.packages.get(&p.name) != Some(&p.version))
             .collect()
     }
 
-    pub fn read_from_disc() -> Result<Self> {
-        let path = paths::packages_toml();
-        if !path.exists() {
+    pub fn extra_git_packages(&self, manifest: &Manifest) -> Vec<(String, String)> {
+        let manifest_packages: HashSet<_> = manifest
+            .packages
+            .iter()
+            .filter_map(|p| match &p.source {
+                ManifestPackageSource::Git { commit, .. } => Some((&p.name, commit)),
+                ManifestPackageSource::Hex { .. } => None,
+            })
+            .collect();
+        self.git
+            .iter()
+            .filter(|(n, c)| !manifest_packages.contains(&(n, c)))
+            .map(|(n, c)| (n.clone(), c.clone()))
+            .collect()
+    }
+
+    /// The name, repository, and commit of each git package of the manifest
+    /// that has not been exported at its locked commit.
+    pub fn missing_git_packages<'a>(
+        &self,
+        manifest: &'a Manifest,
+    ) -> Vec<(&'a str, &'a str, &'a str)> {
+        manifest
+            .packages
+            .iter()
+            .filter_map(|p| match &p.source {
+                ManifestPackageSource::Git { repo, commit } => Some((p, repo, commit)),
+                ManifestPackageSource::Hex { .. } => None,
+            })
+            .filter(|(p, _, commit)| self.git.get(&p.name) != Some(commit))
+            .map(|(p, repo, commit)| (p.name.as_str(), repo.as_str(), commit.as_str()))
+            .collect()
+    }
+
+    pub fn read_from_disc(path: &Path) -> Result<Self> {
+        let io = ProjectIO::new();
+        if !io.is_file(path) {
             return Ok(Self {
                 packages: HashMap::new(),
+                git: HashMap::new(),
             });
         }
-        let toml = crate::fs::read(&path)?;
+        let toml = io.read(path)?;
         toml::from_str(&toml).map_err(|e| Error::FileIo {
             action: FileIoAction::Parse,
             kind: FileKind::File,
-            path: path.clone(),
+            path: path.to_path_buf(),
             err: Some(e.to_string()),
         })
     }
 
-    pub fn write_to_disc(&self) -> Result<()> {
-        let path = paths::packages_toml();
+    pub fn write_to_disc(&self, path: &Path) -> Result<()> {
         let toml = toml::to_string(&self).expect("packages.toml serialization");
-        fs::write(&path, &toml)
+        ProjectIO::new().write(path, &toml)
     }
 
     pub fn from_manifest(manifest: &Manifest) -> Self {
-        Self {
-            packages: manifest
-                .packages
-                .iter()
-                .map(|p| (p.name.to_string(), p.version.clone()))
-                .collect(),
+        let mut packages = HashMap::new();
+        let mut git = HashMap::new();
+        for package in &manifest.packages {
+            match &package.source {
+                ManifestPackageSource::Hex { .. } => {
+                    let _ = packages.insert(package.name.clone(), package.version.clone());
+                }
+                ManifestPackageSource::Git { commit, .. } => {
+                    let _ = git.insert(package.name.clone(), commit.clone());
+                }
+            }
         }
+        Self { packages, git }
     }
 }
 
@@ -314,6 +875,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    registry: None,
                 },
             },
             ManifestPackage {
@@ -324,6 +886,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    registry: None,
                 },
             },
             ManifestPackage {
@@ -334,6 +897,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    registry: None,
                 },
             },
         ],
@@ -344,6 +908,7 @@ fn missing_local_packages() {
             ("local3".to_string(), Version::parse("3.0.0").unwrap()),
         ]
         .into(),
+        git: HashMap::new(),
     }
     .missing_local_packages(&manifest, "root");
     extra.sort();
@@ -358,6 +923,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    registry: None,
                 },
             },
             &ManifestPackage {
@@ -368,6 +934,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    registry: None,
                 },
             },
         ]
@@ -383,6 +950,7 @@ fn extra_local_packages() {
             ("local3".to_string(), Version::parse("3.0.0").unwrap()),
         ]
         .into(),
+        git: HashMap::new(),
     }
     .extra_local_packages(&Manifest {
         requirements: HashMap::new(),
@@ -395,6 +963,7 @@ fn extra_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    registry: None,
                 },
             },
             ManifestPackage {
@@ -405,6 +974,7 @@ fn extra_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![4, 5]),
+                    registry: None,
                 },
             },
         ],
@@ -437,47 +1007,134 @@ fn get_manifest<Telem: Telemetry>(
             tracing::info!("ignoring_manifest");
             true
         }
-        UseManifest::Yes => false,
+        UseManifest::Yes | UseManifest::Unlocking(_) => false,
     };
+    if is_offline() && (should_resolve || matches!(use_manifest, UseManifest::Unlocking(_))) {
+        return Err(Error::OfflineResolution);
+    }
 
     if should_resolve {
-        let manifest = resolve_versions(runtime, mode, config, None, telemetry)?;
+        let manifest = resolve_versions(runtime, mode, config, None, &[], telemetry)?;
         return Ok((true, manifest));
     }
 
     let manifest = read_manifest_from_disc()?;
 
+    // The named packages are resolved again even if the config is unchanged
+    if let UseManifest::Unlocking(packages) = use_manifest {
+        let unknown = packages
+            .iter()
+            .filter(|name| !manifest.packages.iter().any(|p| &&p.name == name))
+            .cloned()
+            .collect_vec();
+        if !unknown.is_empty() {
+            return Err(Error::UnknownDependencies { packages: unknown });
+        }
+        tracing::info!(packages = ?packages, "unlocking_packages");
+        let manifest =
+            resolve_versions(runtime, mode, config, Some(&manifest), &packages, telemetry)?;
+        return Ok((true, manifest));
+    }
+
     // If the config has unchanged since the manifest was written then it is up
     // to date so we can return it unmodified.
     if manifest.requirements == config.all_dependencies()? {
         tracing::info!("manifest_up_to_date");
         Ok((false, manifest))
+    } else if is_offline() {
+        Err(Error::OfflineResolution)
     } else {
         tracing::info!("manifest_outdated");
-        let manifest = resolve_versions(runtime, mode, config, Some(&manifest), telemetry)?;
+        let manifest = resolve_versions(runtime, mode, config, Some(&manifest), &[], telemetry)?;
         Ok((true, manifest))
     }
 }
 
+/// The given packages and the packages they depend upon, directly or
+/// indirectly, according to the manifest.
+fn with_dependencies_of<'a>(packages: &'a [String], manifest: &'a Manifest) -> HashSet<&'a str> {
+    let mut found = HashSet::new();
+    let mut to_visit = packages.iter().map(String::as_str).collect_vec();
+    while let Some(name) = to_visit.pop() {
+        if !found.insert(name) {
+            continue;
+        }
+        if let Some(package) = manifest.packages.iter().find(|p| p.name == name) {
+            to_visit.extend(package.requirements.iter().map(String::as_str));
+        }
+    }
+    found
+}
+
+#[test]
+fn with_dependencies_of_manifest() {
+    let package = |name: &str, requirements: &[&str]| ManifestPackage {
+        name: name.to_string(),
+        version: Version::new(1, 0, 0),
+        build_tools: ["gleam".into()].into(),
+        otp_app: None,
+        requirements: requirements.iter().map(|r| r.to_string()).collect(),
+        source: ManifestPackageSource::Hex {
+            outer_checksum: Base16Checksum(vec![1, 2]),
+            registry: None,
+        },
+    };
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![
+            package("app_a", &["lib_a", "shared"]),
+            package("app_b", &["shared"]),
+            package("lib_a", &["lib_b"]),
+            package("lib_b", &[]),
+            package("shared", &[]),
+        ],
+    };
+    let packages = ["app_a".to_string()];
+    let mut unlocked = with_dependencies_of(&packages, &manifest)
+        .into_iter()
+        .collect_vec();
+    unlocked.sort();
+    assert_eq!(unlocked, ["app_a", "lib_a", "lib_b", "shared"]);
+}
+
 fn resolve_versions<Telem: Telemetry>(
     runtime: tokio::runtime::Handle,
     mode: Mode,
     config: &PackageConfig,
     manifest: Option<&Manifest>,
+    // These packages, and the packages they depend upon, are not kept at
+    // their locked versions
+    unlocking: &[String],
     telemetry: &Telem,
 ) -> Result<Manifest, Error> {
+    let span = tracing::info_span!("resolve_versions");
+    let _enter = span.enter();
     telemetry.resolving_package_versions();
-    let resolved = hex::resolve_versions(
-        PackageFetcher::boxed(runtime.clone()),
-        mode,
-        config,
-        manifest,
-    )?;
-    let packages = runtime.block_on(future::try_join_all(
-        resolved
-            .into_iter()
-            .map(|(name, version)| lookup_package(name, version)),
-    ))?;
+    // Packages that have not changed in the config stay at their locked
+    // versions. This is determined before the git packages are resolved so
+    // that the Hex packages they depend upon are locked too.
+    let mut locked = config.locked(manifest)?;
+    let unlocked = manifest
+        .map(|manifest| with_dependencies_of(unlocking, manifest))
+        .unwrap_or_default();
+    locked.retain(|name, _| !unlocked.contains(name.as_str()));
+    let (resolution, mut git_packages) = resolve_git_packages(config, manifest, &unlocked)?;
+    let registries = crate::hex::registries(&resolution)?;
+    let fetcher = PackageFetcher::boxed(runtime.clone(), registries.clone());
+    let sources = fetcher.sources.clone();
+    let resolved = hex::resolve_versions(fetcher, mode, &resolution, &locked)?;
+    let sources = sources.borrow();
+    let mut packages = runtime.block_on(future::try_join_all(resolved.into_iter().map(
+        |(name, version)| {
+            // Each package is looked up in the registry it was resolved from
+            let registry = match sources.get(&name) {
+                Some(registry) => registries.get(registry),
+                None => Ok(registries.for_package(&name)[0]),
+            };
+            async move { lookup_package(name, version, registry?).await }
+        },
+    )))?;
+    packages.append(&mut git_packages);
     let manifest = Manifest {
         packages,
         requirements: config.all_dependencies()?,
@@ -485,9 +1142,90 @@ fn resolve_versions<Telem: Telemetry>(
     Ok(manifest)
 }
 
-async fn lookup_package(name: String, version: Version) -> Result<ManifestPackage> {
-    let config = hexpm::Config::new();
-    let release = hex::get_package_release(&name, &version, &config, &HttpClient::new()).await?;
+/// Resolve the git dependencies of the config, and the git dependencies of
+/// those packages in turn, to commits. Returns the config with the
+/// dependencies of the git packages in place of the git packages, to resolve
+/// the Hex packages with.
+///
+/// A git package keeps the commit it is locked to in the manifest unless its
+/// requirement has changed.
+fn resolve_git_packages(
+    config: &PackageConfig,
+    manifest: Option<&Manifest>,
+    unlocked: &HashSet<&str>,
+) -> Result<(PackageConfig, Vec<ManifestPackage>)> {
+    let mut packages: Vec<ManifestPackage> = vec![];
+    let mut configs = vec![];
+    loop {
+        let resolution = config.with_local_dependencies(&configs)?;
+        let unresolved = resolution
+            .all_dependencies()?
+            .into_iter()
+            .filter_map(|(name, requirement)| match requirement {
+                Requirement::Git { repo, reference } => Some((name, repo, reference)),
+                Requirement::Hex { .. } | Requirement::Path { .. } => None,
+            })
+            .filter(|(name, _, _)| !packages.iter().any(|package| &package.name == name))
+            .sorted()
+            .collect_vec();
+        if unresolved.is_empty() {
+            return Ok((resolution, packages));
+        }
+
+        for (name, repo, reference) in unresolved {
+            let requirement = Requirement::git(&repo, &reference);
+            let locked = manifest.and_then(|manifest| {
+                let changed = manifest
+                    .requirements
+                    .get(&name)
+                    .map_or(false, |locked| locked != &requirement)
+                    || unlocked.contains(name.as_str());
+                let package = manifest.packages.iter().find(|p| p.name == name)?;
+                match &package.source {
+                    ManifestPackageSource::Git {
+                        repo: locked_repo,
+                        commit,
+                    } if !changed && locked_repo == &repo => Some(commit.clone()),
+                    _ => None,
+                }
+            });
+            let commit = match locked {
+                Some(commit) => commit,
+                None => crate::git::resolve(&ProjectIO::new(), &name, &repo, &reference)?,
+            };
+
+            let mut package = crate::git::read_config(&ProjectIO::new(), &name, &repo, &commit)?;
+            if package.name != name {
+                return Err(Error::GitDependency {
+                    package: name,
+                    repo,
+                    error: format!(
+                        "The gleam.toml file of the repository is for the package {}.",
+                        package.name
+                    ),
+                });
+            }
+            packages.push(ManifestPackage {
+                name,
+                version: package.version.clone(),
+                build_tools: vec!["gleam".into()],
+                otp_app: None,
+                requirements: package.dependencies.keys().cloned().sorted().collect(),
+                source: ManifestPackageSource::Git { repo, commit },
+            });
+            // Only the dependencies of the git package are needed to use it
+            package.dev_dependencies.clear();
+            configs.push(package);
+        }
+    }
+}
+
+async fn lookup_package(
+    name: String,
+    version: Version,
+    registry: &Registry,
+) -> Result<ManifestPackage> {
+    let release = hex::get_package_release(&name, &version, registry, &HttpClient::new()).await?;
     let manifest = ManifestPackage {
         name,
         version,
@@ -496,21 +1234,52 @@ async fn lookup_package(name: String, version: Version) -> Result<ManifestPackag
         requirements: release.requirements.keys().cloned().collect_vec(),
         source: ManifestPackageSource::Hex {
             outer_checksum: Base16Checksum(release.outer_checksum),
+            registry: (registry.name != hex::HEXPM).then(|| registry.name.clone()),
         },
     };
     Ok(manifest)
 }
 
+/// The releases on Hex of each of the Hex packages of the manifest.
+pub(crate) fn hex_releases(
+    config: &PackageConfig,
+    manifest: &Manifest,
+) -> Result<HashMap<String, hexpm::Package>> {
+    if is_offline() {
+        return Err(Error::OfflineUnsupported {
+            command: "outdated".into(),
+        });
+    }
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let fetcher = PackageFetcher::boxed(runtime.handle().clone(), crate::hex::registries(config)?);
+    manifest
+        .packages
+        .iter()
+        .filter(|package| matches!(package.source, ManifestPackageSource::Hex { .. }))
+        .map(|package| {
+            let releases =
+                hexpm::version::PackageFetcher::get_dependencies(fetcher.as_ref(), &package.name)
+                    .map_err(|error| Error::Hex(error.to_string()))?;
+            Ok((package.name.clone(), releases))
+        })
+        .collect()
+}
+
 struct PackageFetcher {
     runtime: tokio::runtime::Handle,
     http: HttpClient,
+    registries: Registries,
+    /// The name of the registry that each package was found in.
+    sources: Rc<RefCell<HashMap<String, String>>>,
 }
 
 impl PackageFetcher {
-    pub fn boxed(runtime: tokio::runtime::Handle) -> Box<Self> {
+    pub fn boxed(runtime: tokio::runtime::Handle, registries: Registries) -> Box<Self> {
         Box::new(Self {
             runtime,
             http: HttpClient::new(),
+            registries,
+            sources: Rc::new(RefCell::new(HashMap::new())),
         })
     }
 }
@@ -546,13 +1315,27 @@ impl hexpm::version::PackageFetcher for PackageFetcher {
         &self,
         package: &str,
     ) -> Result<hexpm::Package, Box<dyn std::error::Error>> {
-        tracing::info!(package = package, "looking_up_hex_package");
-        let config = hexpm::Config::new();
-        let request = hexpm::get_package_request(package, None, &config);
-        let response = self
-            .runtime
-            .block_on(self.http.send(request))
-            .map_err(Box::new)?;
-        hexpm::get_package_response(response, HEXPM_PUBLIC_KEY).map_err(|e| e.into())
+        let mut result = Err(hexpm::ApiError::NotFound);
+        for registry in self.registries.for_package(package) {
+            tracing::info!(package = package, registry = %registry.name, "looking_up_hex_package");
+            let request =
+                hexpm::get_package_request(package, registry.api_key.as_deref(), &registry.config);
+            let response = self
+                .runtime
+                .block_on(self.http.send(request))
+                .map_err(Box::new)?;
+            result = hexpm::get_package_response(response, &registry.public_key);
+            if result.is_ok() {
+                let _ = self
+                    .sources
+                    .borrow_mut()
+                    .insert(package.to_string(), registry.name.clone());
+            }
+            // Packages not found in one registry may be in the next
+            if !matches!(result, Err(hexpm::ApiError::NotFound)) {
+                break;
+            }
+        }
+        result.map_err(|e| e.into())
     }
 }
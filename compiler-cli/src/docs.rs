@@ -1,13 +1,14 @@
-use std::time::Instant;
+use std::{collections::HashMap, time::Instant};
 
-use crate::{cli, hex::ApiKeyCommand, http::HttpClient};
+use crate::{cli, fs::ProjectIO, hex::ApiKeyCommand, http::HttpClient};
 use gleam_core::{
     build::{Mode, Options, Package},
     config::{DocsPage, PackageConfig},
-    error::Error,
+    error::{Error, FileIoAction, FileKind},
     hex,
     io::HttpClient as _,
-    paths, Result,
+    manifest::ManifestPackageSource,
+    paths, type_, Result,
 };
 
 pub fn remove(package: String, version: String) -> Result<()> {
@@ -49,22 +50,60 @@ impl ApiKeyCommand for RemoveCommand {
     }
 }
 
-pub fn build() -> Result<()> {
+/// The format documentation is rendered in by `gleam docs build`.
+#[derive(Debug, strum::EnumString, strum::EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum DocsFormat {
+    /// The pages of the documentation, for people to read.
+    Html,
+    /// A JSON document of the modules and their definitions, for other
+    /// programs.
+    Json,
+    /// A single HTML file of the documentation that makes no requests, for
+    /// reading offline and archiving.
+    Offline,
+}
+
+pub fn build(format: DocsFormat) -> Result<()> {
     let config = crate::config::root_config()?;
     let out = paths::build_docs(&config.name);
-    let mut compiled = crate::build::main(Options {
+    let (mut compiled, importable_modules, _) = crate::build::importable_modules(Options {
         mode: Mode::Prod,
         target: None,
         perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
     })?;
-    let outputs = build_documentation(&config, &mut compiled)?;
+    let (outputs, entry) = match format {
+        DocsFormat::Html => (
+            build_documentation(&config, &mut compiled, &importable_modules, false)?,
+            "index.html".to_string(),
+        ),
+        DocsFormat::Json | DocsFormat::Offline => {
+            let (pages, dependency_docs) =
+                documentation_inputs(&config, &mut compiled, &importable_modules)?;
+            let generate = match format {
+                DocsFormat::Offline => gleam_core::docs::generate_offline,
+                _ => gleam_core::docs::generate_json,
+            };
+            let output = generate(
+                &ProjectIO::new(),
+                &config,
+                compiled.modules.as_slice(),
+                &pages,
+                &dependency_docs,
+            );
+            let entry = output.path.to_string_lossy().to_string();
+            (vec![output], entry)
+        }
+    };
 
     // Write
     crate::fs::delete_dir(&out)?;
     crate::fs::write_outputs_under(&outputs, &out)?;
 
     println!(
-        "\nThe documentation for {package} has been rendered to \n./{out}/index.html",
+        "\nThe documentation for {package} has been rendered to \n./{out}/{entry}",
         package = config.name,
         out = out.to_string_lossy()
     );
@@ -73,10 +112,40 @@ pub fn build() -> Result<()> {
     Ok(())
 }
 
+/// Renders the HTML documentation. When it is to be published to HexDocs
+/// the pages name their counterparts in its latest version there as their
+/// canonical URLs, unless the documentation is configured to be hosted
+/// elsewhere.
 pub(crate) fn build_documentation(
     config: &PackageConfig,
     compiled: &mut Package,
+    importable_modules: &im::HashMap<String, type_::Module>,
+    hexdocs: bool,
 ) -> Result<Vec<gleam_core::io::OutputFile>, Error> {
+    let (pages, dependency_docs) = documentation_inputs(config, compiled, importable_modules)?;
+    let canonical_url = match &config.documentation.base_url {
+        Some(url) => Some(url.clone()),
+        None if hexdocs => Some(format!("https://hexdocs.pm/{}", config.name)),
+        None => None,
+    };
+    let outputs = gleam_core::docs::generate_html(
+        &ProjectIO::new(),
+        config,
+        compiled.modules.as_slice(),
+        &pages,
+        &dependency_docs,
+        canonical_url.as_deref(),
+    );
+    Ok(outputs)
+}
+
+/// The pages of the documentation and the URLs of the documentation of the
+/// dependency modules, shared by each format it is rendered in.
+fn documentation_inputs(
+    config: &PackageConfig,
+    compiled: &mut Package,
+    importable_modules: &im::HashMap<String, type_::Module>,
+) -> Result<(Vec<DocsPage>, HashMap<String, String>)> {
     compiled.attach_doc_and_module_comments();
     cli::print_generating_documentation();
     let mut pages = vec![DocsPage {
@@ -84,9 +153,62 @@ pub(crate) fn build_documentation(
         path: "index.html".to_string(),
         source: paths::readme(), // TODO: support non markdown READMEs. Or a default if there is none.
     }];
+    // The additional pages listed in gleam.toml must exist, unlike the README
+    for page in &config.documentation.pages {
+        if !page.source.is_file() {
+            return Err(Error::FileIo {
+                action: FileIoAction::Read,
+                kind: FileKind::File,
+                path: page.source.clone(),
+                err: Some(format!(
+                    "The source of the documentation page `{}` was not found",
+                    page.title
+                )),
+            });
+        }
+    }
+    for file in config.documentation.theme.files() {
+        if !file.is_file() {
+            return Err(Error::FileIo {
+                action: FileIoAction::Read,
+                kind: FileKind::File,
+                path: file.clone(),
+                err: Some("This file of the documentation theme was not found".into()),
+            });
+        }
+    }
     pages.extend(config.documentation.pages.iter().cloned());
-    let outputs = gleam_core::docs::generate_html(config, compiled.modules.as_slice(), &pages);
-    Ok(outputs)
+    let dependency_docs = dependency_docs(importable_modules)?;
+    Ok((pages, dependency_docs))
+}
+
+/// The URL of the documentation on HexDocs of the package of each module of
+/// the dependencies published to Hex, which the documentation links to.
+fn dependency_docs(
+    importable_modules: &im::HashMap<String, type_::Module>,
+) -> Result<HashMap<String, String>> {
+    let manifest = crate::dependencies::read_manifest_from_disc()?;
+    let hexdocs: HashMap<_, _> = manifest
+        .packages
+        .iter()
+        .filter(|package| {
+            matches!(
+                package.source,
+                ManifestPackageSource::Hex { registry: None, .. }
+            )
+        })
+        .map(|package| {
+            let url = format!("https://hexdocs.pm/{}/{}", package.name, package.version);
+            (package.name.as_str(), url)
+        })
+        .collect();
+    Ok(importable_modules
+        .iter()
+        .filter_map(|(name, module)| {
+            let url = hexdocs.get(module.package.as_str())?;
+            Some((name.clone(), url.clone()))
+        })
+        .collect())
 }
 
 struct PublishCommand {
@@ -101,12 +223,14 @@ pub fn publish() -> Result<()> {
 impl PublishCommand {
     pub fn new() -> Result<Self> {
         let config = crate::config::root_config()?;
-        let mut compiled = crate::build::main(Options {
+        let (mut compiled, importable_modules, _) = crate::build::importable_modules(Options {
             perform_codegen: true,
+            warn_dead_code: false,
+            warning_levels: Default::default(),
             mode: Mode::Dev,
             target: None,
         })?;
-        let outputs = build_documentation(&config, &mut compiled)?;
+        let outputs = build_documentation(&config, &mut compiled, &importable_modules, true)?;
         let archive = crate::fs::create_tar_archive(outputs)?;
         Ok(Self { config, archive })
     }
@@ -1,6 +1,21 @@
+use crate::{dependencies::UseManifest, fs::ProjectIO};
 use gleam_core::{
-    build::{Mode, Options, Target},
-    paths, Result,
+    ast::Statement,
+    build::{Mode, Options, Origin, Package, Target},
+    config::{NativeConfig, PackageConfig},
+    cplusplus::ExternC,
+    error::StandardIoAction,
+    interface::ModuleInterface,
+    io::{CommandExecutor, FileSystemReader, FileSystemWriter, Stdio},
+    paths,
+    wasm::{Component, WORLD},
+    Error, Result,
+};
+use itertools::Itertools;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    path::{Path, PathBuf},
 };
 
 // TODO: start in embedded mode
@@ -15,29 +30,29 @@ use gleam_core::{
 /// - include
 /// - priv
 pub(crate) fn erlang_shipment() -> Result<()> {
+    let io = ProjectIO::new();
     let target = Target::Erlang;
     let mode = Mode::Prod;
     let build = paths::build_packages(mode, target);
     let out = paths::erlang_shipment();
 
-    crate::fs::mkdir(&out)?;
+    io.mkdir(&out)?;
 
     // Reset the directories to ensure we have a clean slate and no old code
-    crate::fs::delete_dir(&build)?;
-    crate::fs::delete_dir(&out)?;
+    io.delete(&build)?;
+    io.delete(&out)?;
 
     // Build project in production mode
     let package = crate::build::main(Options {
         perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
         mode,
         target: Some(target),
     })?;
 
-    for entry in crate::fs::read_dir(&build)?
-        .into_iter()
-        .filter_map(Result::ok)
-    {
-        let path = entry.path();
+    for entry in io.read_dir(&build)?.into_iter().filter_map(Result::ok) {
+        let path = entry.into_path();
 
         // We are only interested in package directories
         if !path.is_dir() {
@@ -47,14 +62,14 @@ pub(crate) fn erlang_shipment() -> Result<()> {
         let name = path.file_name().expect("Directory name").to_string_lossy();
         let build = build.join(name.as_ref());
         let out = out.join(name.as_ref());
-        crate::fs::mkdir(&out)?;
+        io.mkdir(&out)?;
 
         // Copy desired package subdirectories
         for subdirectory in ["ebin", "priv", "include"] {
             let source = build.join(subdirectory);
             if source.is_dir() {
                 let source = crate::fs::canonicalise(&source)?;
-                crate::fs::copy_dir(source, &out)?;
+                io.copy_dir(&source, &out)?;
             }
         }
     }
@@ -63,7 +78,7 @@ pub(crate) fn erlang_shipment() -> Result<()> {
     let entrypoint = out.join("entrypoint.sh");
     let text = include_str!("../templates/erlang-shipment-entrypoint.sh")
         .replace("$PROJECT_NAME_FROM_GLEAM", &package.config.name);
-    crate::fs::write(&entrypoint, &text)?;
+    io.write(&entrypoint, &text)?;
     crate::fs::make_executable(&entrypoint)?;
 
     crate::cli::print_exported(&package.config.name);
@@ -83,3 +98,1839 @@ the entrypoint.sh script.
 
     Ok(())
 }
+
+/// Generate a single self-executing escript of the precompiled Erlang of the
+/// project and its dependencies. Suitable for distributing command line
+/// programs to anyone with Erlang installed.
+///
+/// The escript is a zip archive of the `ebin` and `priv` directories of each
+/// Erlang application (aka package), preceded by the lines telling `escript`
+/// to run the `main` function of the project's entrypoint module.
+pub(crate) fn escript() -> Result<()> {
+    let io = ProjectIO::new();
+    let target = Target::Erlang;
+    let mode = Mode::Prod;
+    let build = paths::build_packages(mode, target);
+    let out = paths::escript();
+
+    // Reset the directories to ensure we have a clean slate and no old code
+    io.delete(&build)?;
+    io.delete(&out)?;
+
+    // Build project in production mode
+    let package = crate::build::main(Options {
+        perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode,
+        target: Some(target),
+    })?;
+
+    let mut files = vec![];
+    for entry in io.read_dir(&build)?.into_iter().filter_map(Result::ok) {
+        let path = entry.into_path();
+
+        // We are only interested in package directories
+        if !path.is_dir() {
+            continue;
+        }
+
+        for subdirectory in ["ebin", "priv"] {
+            let source = path.join(subdirectory);
+            for file in walkdir::WalkDir::new(&source)
+                .follow_links(true)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_file())
+            {
+                // Paths within a zip archive are always separated by slashes
+                let name = file
+                    .path()
+                    .strip_prefix(&build)
+                    .expect("Escript file path")
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .join("/");
+                files.push((name, crate::fs::read_bytes(file.path())?));
+            }
+        }
+    }
+
+    let name = &package.config.name;
+    let mut escript = escript_header().into_bytes();
+    escript.extend(crate::fs::create_zip_archive(files)?);
+    let path = out.join(name);
+    io.mkdir(&out)?;
+    io.write_bytes(&path, &escript)?;
+    crate::fs::make_executable(&path)?;
+
+    crate::cli::print_exported(name);
+
+    println!(
+        "
+Your escript has been generated to {path}.
+
+It can be copied to any computer with Erlang installed and run directly.
+
+    {path}
+",
+        path = path.to_string_lossy(),
+    );
+
+    Ok(())
+}
+
+/// The lines preceding the archive of an escript. The emulator arguments on
+/// the third line make `escript` call `gleam@@main:main/1`.
+fn escript_header() -> String {
+    "#!/usr/bin/env escript\n%%\n%%! -escript main gleam@@main\n".into()
+}
+
+/// Generate a directory of precompiled JavaScript along with a `package.json`
+/// and an entry module that runs the project's `main` function. Suitable for
+/// deployment to a server with Node.js installed.
+///
+/// Each package directory is copied across, without the build tool's cache
+/// files.
+pub(crate) fn javascript_bundle() -> Result<()> {
+    let io = ProjectIO::new();
+    let target = Target::JavaScript;
+    let mode = Mode::Prod;
+    let build = paths::build_packages(mode, target);
+    let out = paths::javascript_bundle();
+
+    // Reset the directories to ensure we have a clean slate and no old code
+    io.delete(&build)?;
+    io.delete(&out)?;
+
+    // Build project in production mode
+    let package = crate::build::main(Options {
+        perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode,
+        target: Some(target),
+    })?;
+
+    io.mkdir(&out)?;
+    let _ = copy_javascript_packages(&build, &out)?;
+
+    // Write entrypoint module and package.json
+    let entrypoint = out.join("index.mjs");
+    io.write(&entrypoint, &javascript_entry_module(&package.config.name))?;
+    io.write(
+        &out.join("package.json"),
+        &javascript_package_json(&package.config),
+    )?;
+
+    crate::cli::print_exported(&package.config.name);
+
+    println!(
+        "
+Your JavaScript bundle has been generated to {path}.
+
+It can be copied to a server with Node.js installed and run with the
+index.mjs entry module, or with `npm start`.
+
+    node {entrypoint}
+",
+        path = out.to_string_lossy(),
+        entrypoint = entrypoint.to_string_lossy(),
+    );
+
+    Ok(())
+}
+
+/// Generate a directory of precompiled JavaScript and TypeScript declarations
+/// along with a `package.json` and a README, and with `publish` publish it to
+/// npm. Suitable for distributing a package to JavaScript projects that do
+/// not use Gleam.
+///
+/// Each package directory is copied across, as the modules of a package
+/// import those of its dependencies from the neighbouring directories. The
+/// modules of the project are the entry points of the npm package.
+pub(crate) fn npm_package(publish: bool) -> Result<()> {
+    let io = ProjectIO::new();
+    let target = Target::JavaScript;
+    let mode = Mode::Prod;
+    let build = paths::build_packages(mode, target);
+    let out = paths::npm_package();
+
+    // Reset the directories to ensure we have a clean slate and no old code
+    io.delete(&build)?;
+    io.delete(&out)?;
+
+    // Build project in production mode
+    let package = crate::build::with_typescript_declarations(Options {
+        perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode,
+        target: Some(target),
+    })?;
+
+    io.mkdir(&out)?;
+    let packages = copy_javascript_packages(&build, &out)?;
+    let modules = package
+        .modules
+        .iter()
+        .filter(|module| module.origin == Origin::Src)
+        .map(|module| module.name.clone())
+        .sorted()
+        .collect_vec();
+    io.write(
+        &out.join("package.json"),
+        &npm_package_json(&package.config, &modules, &packages),
+    )?;
+
+    // The project's README is used if it has one
+    let readme = PathBuf::from("README.md");
+    if readme.is_file() {
+        io.copy(&readme, &out.join("README.md"))?;
+    } else {
+        io.write(&out.join("README.md"), &npm_readme(&package.config))?;
+    }
+
+    crate::cli::print_exported(&package.config.name);
+
+    if publish {
+        exec(
+            "npm",
+            &["publish".into(), out.to_string_lossy().to_string()],
+        )?;
+        return Ok(());
+    }
+
+    println!(
+        "
+Your npm package has been generated to {path}.
+
+It can be published to npm with `npm publish`, or by running this command
+again with the --publish flag.
+
+    npm publish {path}
+",
+        path = out.to_string_lossy(),
+    );
+
+    Ok(())
+}
+
+/// Copy the package directories of a JavaScript build, without the build
+/// tool's cache files, returning the names of the packages in order.
+fn copy_javascript_packages(build: &Path, out: &Path) -> Result<Vec<String>> {
+    let io = ProjectIO::new();
+    let mut packages = vec![];
+    for entry in io.read_dir(build)?.into_iter().filter_map(Result::ok) {
+        let path = entry.into_path();
+
+        // We are only interested in package directories
+        if !path.is_dir() {
+            continue;
+        }
+
+        let name = path.file_name().expect("Directory name").to_string_lossy();
+        let source = crate::fs::canonicalise(&path)?;
+        io.copy_dir(&source, out)?;
+        io.delete(&out.join(name.as_ref()).join(paths::ARTEFACT_DIRECTORY_NAME))?;
+        packages.push(name.to_string());
+    }
+    packages.sort();
+    Ok(packages)
+}
+
+/// Generate a WebAssembly component exporting the public functions of the
+/// project's modules, along with the WIT package describing its interfaces.
+/// Suitable for running with a WebAssembly runtime such as Wasmtime, or for
+/// composing with components written in other languages.
+///
+/// The project is compiled to LLVM IR, which is linked and compiled to a core
+/// WebAssembly module by `llvm-link` and the `clang` of the WASI SDK, and
+/// made into a component by `wasm-tools`. The module imports the functions of
+/// WASI preview 1, which the adapter given implements with WASI preview 2.
+pub(crate) fn wasm_component(adapter: Option<PathBuf>) -> Result<()> {
+    let io = ProjectIO::new();
+    let target = Target::Wasm;
+    let mode = Mode::Prod;
+    let build = paths::build_packages(mode, target);
+    let out = paths::wasm_component();
+
+    // Reset the directories to ensure we have a clean slate and no old code
+    io.delete(&build)?;
+    io.delete(&out)?;
+
+    // Build project in production mode
+    let package = crate::build::main(Options {
+        perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode,
+        target: Some(target),
+    })?;
+
+    io.mkdir(&out)?;
+    let name = &package.config.name;
+    let modules = package
+        .modules
+        .iter()
+        .filter(|module| module.origin == Origin::Src)
+        .map(|module| &module.ast)
+        .collect_vec();
+    let component = Component::new(name, &modules);
+    let wit = out.join(format!("{name}.wit"));
+    io.write(&wit, &component.wit())?;
+    io.write(
+        &build.join("gleam@@exports.ll"),
+        &gleam_core::llvm::component_exports(&component),
+    )?;
+
+    let path = |path: &Path| path.to_string_lossy().to_string();
+    let linked = build.join("component.bc");
+    let mut args = vec!["-o".into(), path(&linked)];
+    args.extend(crate::fs::llvm_files(&build).iter().map(|file| path(file)));
+    exec("llvm-link", &args)?;
+
+    let module = build.join("component.wasm");
+    let args = [
+        "--target=wasm32-wasi".into(),
+        "-mexec-model=reactor".into(),
+        "-O2".into(),
+        "-o".into(),
+        path(&module),
+        path(&linked),
+    ];
+    exec("clang", &args)?;
+
+    let embedded = build.join("embedded.wasm");
+    let args = [
+        "component".into(),
+        "embed".into(),
+        path(&wit),
+        "--world".into(),
+        WORLD.into(),
+        "-o".into(),
+        path(&embedded),
+        path(&module),
+    ];
+    exec("wasm-tools", &args)?;
+
+    let wasm = out.join(format!("{name}.wasm"));
+    let mut args = vec![
+        "component".into(),
+        "new".into(),
+        "-o".into(),
+        path(&wasm),
+        path(&embedded),
+    ];
+    if let Some(adapter) = adapter {
+        args.push("--adapt".into());
+        args.push(format!("wasi_snapshot_preview1={}", path(&adapter)));
+    }
+    exec("wasm-tools", &args)?;
+
+    crate::cli::print_exported(name);
+
+    println!(
+        "
+Your WebAssembly component has been generated to {path}.
+
+It exports the interfaces described by {wit}, and can be run
+with a WebAssembly runtime such as Wasmtime.
+",
+        path = wasm.to_string_lossy(),
+        wit = wit.to_string_lossy(),
+    );
+
+    Ok(())
+}
+
+/// Generate a static and a shared library of the project and its
+/// dependencies, along with an `include` directory of their headers and a
+/// pkg-config file, so they can be used from C and C++ like any other system
+/// library.
+///
+/// The headers include those of the modules, for use from C++, and the C
+/// header of the `extern "C"` functions through which the public functions
+/// of the project's modules can be called from C.
+///
+/// The project is compiled to C++, which is compiled by the C++ compiler
+/// named by the `CXX` environment variable, or `c++`, and archived by `ar`.
+#[derive(Debug, strum::EnumString, strum::EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum NativePackageManager {
+    /// A `conanfile.py` recipe packaging the prebuilt library.
+    Conan,
+    /// A vcpkg port of the prebuilt library, with a `vcpkg.json` manifest.
+    Vcpkg,
+}
+
+pub(crate) fn native_lib(package_manager: Option<NativePackageManager>) -> Result<()> {
+    let io = ProjectIO::new();
+    let target = Target::Native;
+    let mode = Mode::Prod;
+    let build = paths::build_packages(mode, target);
+    let out = paths::native_lib();
+
+    // Reset the directories to ensure we have a clean slate and no old code
+    io.delete(&build)?;
+    io.delete(&out)?;
+
+    // Build project in production mode
+    let package = crate::build::main(Options {
+        perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode,
+        target: Some(target),
+    })?;
+
+    let name = &package.config.name;
+    let include = out.join("include");
+    let lib = out.join("lib");
+    let objects = build.join("objects");
+    let packages = io
+        .read_dir(&build)?
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_dir())
+        .sorted()
+        .collect_vec();
+
+    // Each package has a copy of the prelude, of which only one is compiled.
+    let mut sources = vec![];
+    for dist in packages.iter().map(|path| path.join("dist")) {
+        for header in crate::fs::files_with_extension(&dist, "h") {
+            let path = include.join(header.strip_prefix(&dist).expect("Header path"));
+            io.mkdir(path.parent().expect("Header directory"))?;
+            io.copy(&header, &path)?;
+        }
+        for source in crate::fs::files_with_extension(&dist, "cc") {
+            let prelude = source.strip_prefix(&dist) == Ok(Path::new("gleam.cc"));
+            if !prelude || !sources.iter().any(|s: &PathBuf| s.ends_with("gleam.cc")) {
+                sources.push(source);
+            }
+        }
+    }
+
+    let natives = native_configs(&package.config, &packages)?;
+    let libraries = natives
+        .iter()
+        .flat_map(|native| &native.libraries)
+        .unique()
+        .cloned()
+        .collect_vec();
+
+    let modules = package
+        .modules
+        .iter()
+        .filter(|module| module.origin == Origin::Src)
+        .map(|module| &module.ast)
+        .collect_vec();
+    let extern_c = ExternC::new(name, &modules);
+    io.write(&include.join(extern_c.header_name()), &extern_c.header())?;
+    let implementation = objects.join(extern_c.implementation_name());
+    io.write(&implementation, &extern_c.implementation())?;
+    sources.push(implementation);
+
+    let path = |path: &Path| path.to_string_lossy().to_string();
+    let compiler = std::env::var("CXX").unwrap_or_else(|_| "c++".into());
+    let mut object_files = vec![];
+    for (index, source) in sources.iter().enumerate() {
+        let object = objects.join(format!("{index}.o"));
+        let args = [
+            "-std=c++17".into(),
+            "-O2".into(),
+            "-fPIC".into(),
+            format!("-I{}", path(&include)),
+            "-c".into(),
+            path(source),
+            "-o".into(),
+            path(&object),
+        ];
+        exec(&compiler, &args)?;
+        object_files.push(path(&object));
+    }
+
+    io.mkdir(&lib)?;
+    let mut args = vec!["rcs".into(), path(&lib.join(format!("lib{name}.a")))];
+    args.extend(object_files.iter().cloned());
+    exec("ar", &args)?;
+
+    let shared = if cfg!(target_os = "macos") {
+        format!("lib{name}.dylib")
+    } else {
+        format!("lib{name}.so")
+    };
+    let mut args = vec!["-shared".into(), "-o".into(), path(&lib.join(shared))];
+    args.extend(object_files);
+    args.extend(libraries.iter().map(|library| format!("-l{library}")));
+    exec(&compiler, &args)?;
+
+    let pkg_config = lib.join("pkgconfig").join(format!("{name}.pc"));
+    io.write(
+        &pkg_config,
+        &native_lib_pkg_config(&package.config, &libraries),
+    )?;
+
+    match package_manager {
+        Some(NativePackageManager::Conan) => {
+            io.write(
+                &out.join("conanfile.py"),
+                &conanfile(&package.config, &natives),
+            )?;
+        }
+        Some(NativePackageManager::Vcpkg) => {
+            io.write(
+                &out.join("vcpkg.json"),
+                &vcpkg_json(&package.config, &natives),
+            )?;
+            io.write(
+                &out.join("portfile.cmake"),
+                &vcpkg_portfile(&package.config),
+            )?;
+        }
+        None => (),
+    }
+
+    crate::cli::print_exported(name);
+
+    println!(
+        "
+Your native library has been generated to {path}.
+
+Its C interface is declared in {header}, and C and C++ programs can
+be built with it using the flags given by pkg-config.
+
+    PKG_CONFIG_PATH={pkg_config_path} pkg-config --cflags --libs {name}
+",
+        path = out.to_string_lossy(),
+        header = extern_c.header_name(),
+        pkg_config_path = lib.join("pkgconfig").to_string_lossy(),
+    );
+    match package_manager {
+        Some(NativePackageManager::Conan) => println!(
+            "It can be added to the local Conan cache with its recipe.
+
+    conan create {path}
+",
+            path = out.to_string_lossy(),
+        ),
+        Some(NativePackageManager::Vcpkg) => println!(
+            "It can be installed by vcpkg as an overlay port.
+
+    vcpkg install {port} --overlay-ports={path}
+",
+            port = vcpkg_port_name(name),
+            path = out.to_string_lossy(),
+        ),
+        None => (),
+    }
+
+    Ok(())
+}
+
+/// The `[native]` configuration of each package compiled into the native
+/// library, starting with the root package.
+fn native_configs(config: &PackageConfig, packages: &[PathBuf]) -> Result<Vec<NativeConfig>> {
+    let local = crate::config::local_packages(config, Mode::Prod)?;
+    let mut natives = vec![config.native.clone()];
+    for name in packages
+        .iter()
+        .filter_map(|path| path.file_name())
+        .map(|name| name.to_string_lossy())
+    {
+        if name == config.name {
+            continue;
+        }
+        let native = match local.iter().find(|package| package.config.name == name) {
+            Some(package) => package.config.native.clone(),
+            None => crate::config::read(paths::build_deps_package_config(&name))?.native,
+        };
+        natives.push(native);
+    }
+    Ok(natives)
+}
+
+/// The licences of the package as an SPDX expression.
+fn licence_expression(config: &PackageConfig) -> Option<String> {
+    (!config.licences.is_empty()).then(|| {
+        config
+            .licences
+            .iter()
+            .map(|licence| licence.licence.as_str())
+            .join(" OR ")
+    })
+}
+
+/// A Conan recipe packaging the prebuilt native library, so the package is
+/// created for the settings of the machine it was built on.
+///
+/// The libraries of a package that lists Conan packages are provided by
+/// them, and those of other packages are linked as system libraries.
+fn conanfile(config: &PackageConfig, natives: &[NativeConfig]) -> String {
+    let mut recipe = format!(
+        "import os
+
+from conan import ConanFile
+from conan.tools.files import copy
+
+
+class GleamPackage(ConanFile):
+    name = {name:?}
+    version = \"{version}\"
+",
+        name = config.name,
+        version = config.version,
+    );
+    if !config.description.is_empty() {
+        recipe.push_str(&format!("    description = {:?}\n", config.description));
+    }
+    if let Some(licence) = licence_expression(config) {
+        recipe.push_str(&format!("    license = {licence:?}\n"));
+    }
+    if let Some(url) = config.repository.url() {
+        recipe.push_str(&format!("    url = {url:?}\n"));
+    }
+    recipe.push_str(
+        "    settings = \"os\", \"arch\", \"compiler\", \"build_type\"
+    exports_sources = \"include/*\", \"lib/*\"
+",
+    );
+    let requires = natives
+        .iter()
+        .flat_map(|native| &native.conan)
+        .unique()
+        .collect_vec();
+    if !requires.is_empty() {
+        recipe.push_str("\n    def requirements(self):\n");
+        for requirement in requires {
+            recipe.push_str(&format!("        self.requires({requirement:?})\n"));
+        }
+    }
+    let system_libraries = ["stdc++".to_string(), "m".into()]
+        .iter()
+        .chain(
+            natives
+                .iter()
+                .filter(|native| native.conan.is_empty())
+                .flat_map(|native| &native.libraries),
+        )
+        .unique()
+        .map(|library| format!("{library:?}"))
+        .join(", ");
+    recipe.push_str(&format!(
+        "
+    def package(self):
+        for directory in (\"include\", \"lib\"):
+            copy(
+                self,
+                \"*\",
+                os.path.join(self.source_folder, directory),
+                os.path.join(self.package_folder, directory),
+            )
+
+    def package_info(self):
+        self.cpp_info.libs = [{name:?}]
+        self.cpp_info.system_libs = [{system_libraries}]
+",
+        name = config.name,
+    ));
+    recipe
+}
+
+/// The name of the vcpkg port of a package, which cannot contain
+/// underscores.
+fn vcpkg_port_name(package: &str) -> String {
+    package.replace('_', "-")
+}
+
+/// The `vcpkg.json` of a vcpkg port, whose fields are written in the
+/// conventional order.
+#[derive(Debug, serde::Serialize)]
+struct VcpkgJson<'a> {
+    name: String,
+    #[serde(rename = "version-semver")]
+    version: String,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    description: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    homepage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    dependencies: Vec<&'a String>,
+}
+
+fn vcpkg_json(config: &PackageConfig, natives: &[NativeConfig]) -> String {
+    let json = VcpkgJson {
+        name: vcpkg_port_name(&config.name),
+        version: config.version.to_string(),
+        description: &config.description,
+        homepage: config.repository.url(),
+        license: licence_expression(config),
+        dependencies: natives
+            .iter()
+            .flat_map(|native| &native.vcpkg)
+            .unique()
+            .collect(),
+    };
+    let mut json = serde_json::to_string_pretty(&json).expect("vcpkg.json serialisation");
+    json.push('\n');
+    json
+}
+
+/// The portfile of a vcpkg port, which installs the prebuilt static library
+/// from the directory of the port.
+fn vcpkg_portfile(config: &PackageConfig) -> String {
+    let licence = licence_expression(config).unwrap_or_else(|| "no licence".into());
+    format!(
+        r#"vcpkg_check_linkage(ONLY_STATIC_LIBRARY)
+
+file(INSTALL "${{CMAKE_CURRENT_LIST_DIR}}/include/" DESTINATION "${{CURRENT_PACKAGES_DIR}}/include")
+foreach(directory IN ITEMS "lib" "debug/lib")
+    file(INSTALL "${{CMAKE_CURRENT_LIST_DIR}}/lib/lib{name}.a" DESTINATION "${{CURRENT_PACKAGES_DIR}}/${{directory}}")
+endforeach()
+file(INSTALL "${{CMAKE_CURRENT_LIST_DIR}}/lib/pkgconfig/{name}.pc" DESTINATION "${{CURRENT_PACKAGES_DIR}}/lib/pkgconfig")
+file(WRITE "${{CURRENT_PACKAGES_DIR}}/share/${{PORT}}/copyright" "{name} is available under {licence}.\n")
+"#,
+        name = config.name,
+    )
+}
+
+fn exec(program: &str, args: &[String]) -> Result<()> {
+    let status = ProjectIO::new().exec(program, args, &[], None, Stdio::Inherit)?;
+    if status != 0 {
+        return Err(Error::ShellCommand {
+            program: program.into(),
+            err: None,
+        });
+    }
+    Ok(())
+}
+
+fn javascript_entry_module(package: &str) -> String {
+    format!(
+        "import {{ main }} from \"./{package}/{package}.mjs\";\n\nmain();\n",
+        package = package
+    )
+}
+
+fn javascript_package_json(config: &PackageConfig) -> String {
+    let json = serde_json::json!({
+        "name": config.name,
+        "version": config.version.to_string(),
+        "description": config.description,
+        "private": true,
+        "type": "module",
+        "main": "./index.mjs",
+        "scripts": { "start": "node ./index.mjs" },
+    });
+    let mut json = serde_json::to_string_pretty(&json).expect("package.json serialisation");
+    json.push('\n');
+    json
+}
+
+/// The `package.json` of an npm package, whose fields are written in the
+/// conventional order. The order of the conditions of an export matters, as
+/// TypeScript only finds the declarations if `types` comes first.
+#[derive(Debug, serde::Serialize)]
+struct NpmPackageJson<'a> {
+    name: &'a str,
+    version: String,
+    description: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository: Option<NpmRepository>,
+    #[serde(rename = "type")]
+    type_: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    main: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    types: Option<String>,
+    exports: BTreeMap<String, NpmExport>,
+    files: &'a [String],
+}
+
+#[derive(Debug, serde::Serialize)]
+struct NpmRepository {
+    #[serde(rename = "type")]
+    type_: &'static str,
+    url: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct NpmExport {
+    types: String,
+    import: String,
+}
+
+/// The `package.json` of an npm package. Each module of the project is
+/// exported by its name, and the module named after the package is also the
+/// main entry point.
+fn npm_package_json(config: &PackageConfig, modules: &[String], packages: &[String]) -> String {
+    let export = |module: &str| {
+        let path = format!("./{}/{module}", config.name);
+        NpmExport {
+            types: format!("{path}.d.ts"),
+            import: format!("{path}.mjs"),
+        }
+    };
+    let mut exports: BTreeMap<_, _> = modules
+        .iter()
+        .map(|module| (format!("./{module}"), export(module)))
+        .collect();
+    let main = modules.contains(&config.name).then(|| export(&config.name));
+    if let Some(main) = &main {
+        let _ = exports.insert(".".into(), main.clone());
+    }
+    let json = NpmPackageJson {
+        name: &config.name,
+        version: config.version.to_string(),
+        description: &config.description,
+        license: licence_expression(config),
+        repository: config
+            .repository
+            .url()
+            .map(|url| NpmRepository { type_: "git", url }),
+        type_: "module",
+        main: main.as_ref().map(|main| main.import.clone()),
+        types: main.map(|main| main.types),
+        exports,
+        files: packages,
+    };
+    let mut json = serde_json::to_string_pretty(&json).expect("package.json serialisation");
+    json.push('\n');
+    json
+}
+
+/// The README of an npm package, for projects that do not have their own.
+fn npm_readme(config: &PackageConfig) -> String {
+    let mut readme = format!("# {}\n", config.name);
+    if !config.description.is_empty() {
+        readme.push_str(&format!("\n{}\n", config.description));
+    }
+    readme.push_str(&format!(
+        "
+This package is written in [Gleam](https://gleam.run) and compiled to
+JavaScript, with TypeScript declarations.
+
+```sh
+npm install {name}
+```
+",
+        name = config.name
+    ));
+    readme
+}
+
+/// The pkg-config file of a native library, relative to its own location so
+/// the library can be installed to any prefix. The library is C++, so C
+/// programs that link it statically must also link the C++ standard library.
+fn native_lib_pkg_config(config: &PackageConfig, libraries: &[String]) -> String {
+    let description = if config.description.is_empty() {
+        &config.name
+    } else {
+        &config.description
+    };
+    format!(
+        "prefix=${{pcfiledir}}/../..
+includedir=${{prefix}}/include
+libdir=${{prefix}}/lib
+
+Name: {name}
+Description: {description}
+Version: {version}
+Cflags: -I${{includedir}}
+Libs: -L${{libdir}} -l{name}
+Libs.private: -lstdc++ -lm{libraries}
+",
+        name = config.name,
+        version = config.version,
+        libraries = libraries
+            .iter()
+            .map(|library| format!(" -l{library}"))
+            .join(""),
+    )
+}
+
+/// Write a JSON description of the public interface of each module in the
+/// project, with one file per module.
+pub(crate) fn interfaces() -> Result<()> {
+    let io = ProjectIO::new();
+    let out = paths::interfaces();
+    let mut package = crate::build::main(Options {
+        perform_codegen: false,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode: Mode::Dev,
+        target: None,
+    })?;
+    package.attach_doc_and_module_comments();
+
+    io.delete(&out)?;
+    for module in package
+        .modules
+        .iter()
+        .filter(|module| module.origin.is_src())
+    {
+        let path = out.join(format!("{}.json", module.name));
+        let interface = ModuleInterface::from_module(&module.ast);
+        io.write(&path, &interface.to_json())?;
+    }
+
+    crate::cli::print_exported(&package.config.name);
+
+    println!(
+        "
+The module interfaces have been written to {path}.
+",
+        path = out.to_string_lossy(),
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, strum::EnumString, strum::EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum DependencyGraphFormat {
+    /// A Graphviz DOT graph.
+    Dot,
+    /// A JSON object with the nodes and edges of the graph.
+    Json,
+}
+
+/// A module of the project along with the modules it imports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ModuleImports {
+    name: String,
+    is_test: bool,
+    /// The modules of the project imported by this module.
+    modules: Vec<String>,
+    /// The other packages this module imports modules from.
+    packages: Vec<String>,
+}
+
+/// Print the graph of the imports between the modules of the project, and
+/// with `packages` the packages each module imports from, to stdout.
+pub(crate) fn dependency_graph(format: DependencyGraphFormat, packages: bool) -> Result<()> {
+    let package = crate::build::quiet(Options {
+        perform_codegen: false,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode: Mode::Dev,
+        target: None,
+    })?;
+    let mut modules = module_imports(&package);
+    if !packages {
+        modules
+            .iter_mut()
+            .for_each(|module| module.packages.clear());
+    }
+    let graph = match format {
+        DependencyGraphFormat::Dot => dependency_graph_dot(&package.config.name, &modules),
+        DependencyGraphFormat::Json => dependency_graph_json(&modules),
+    };
+    std::io::stdout()
+        .write_all(graph.as_bytes())
+        .map_err(|e| Error::StandardIo {
+            action: StandardIoAction::Write,
+            err: Some(e.kind()),
+        })
+}
+
+fn module_imports(package: &Package) -> Vec<ModuleImports> {
+    package
+        .modules
+        .iter()
+        .map(|module| {
+            let (modules, packages): (Vec<_>, Vec<_>) = module
+                .ast
+                .statements
+                .iter()
+                .filter_map(|statement| match statement {
+                    Statement::Import {
+                        module, package, ..
+                    } => Some((module.join("/"), package)),
+                    _ => None,
+                })
+                .partition(|(_, imported)| **imported == package.config.name);
+            ModuleImports {
+                name: module.name.clone(),
+                is_test: module.is_test(),
+                modules: modules
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .sorted()
+                    .dedup()
+                    .collect(),
+                packages: packages
+                    .into_iter()
+                    .map(|(_, package)| package.clone())
+                    .sorted()
+                    .dedup()
+                    .collect(),
+            }
+        })
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .collect()
+}
+
+// Packages are prefixed so that they cannot be confused with modules of the
+// same name.
+fn dependency_graph_dot(package: &str, modules: &[ModuleImports]) -> String {
+    let mut dot = format!("digraph {:?} {{\n", package);
+    for module in modules {
+        let style = if module.is_test {
+            " [style=dashed]"
+        } else {
+            ""
+        };
+        dot.push_str(&format!("  {:?}{};\n", module.name, style));
+    }
+    for package in modules.iter().flat_map(|m| &m.packages).sorted().dedup() {
+        dot.push_str(&format!(
+            "  {:?} [label={:?}, shape=box];\n",
+            format!("package:{}", package),
+            package
+        ));
+    }
+    for module in modules {
+        for imported in &module.modules {
+            dot.push_str(&format!("  {:?} -> {:?};\n", module.name, imported));
+        }
+        for package in &module.packages {
+            dot.push_str(&format!(
+                "  {:?} -> {:?};\n",
+                module.name,
+                format!("package:{}", package)
+            ));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn dependency_graph_json(modules: &[ModuleImports]) -> String {
+    let module_nodes = modules.iter().map(|module| {
+        serde_json::json!({ "name": module.name, "kind": "module", "test": module.is_test })
+    });
+    let package_nodes = modules
+        .iter()
+        .flat_map(|module| &module.packages)
+        .sorted()
+        .dedup()
+        .map(|package| serde_json::json!({ "name": package, "kind": "package" }));
+    let edges = modules.iter().flat_map(|module| {
+        let modules = module.modules.iter().map(move |imported| {
+            serde_json::json!({ "from": module.name, "to": imported, "kind": "module" })
+        });
+        let packages = module.packages.iter().map(move |package| {
+            serde_json::json!({ "from": module.name, "to": package, "kind": "package" })
+        });
+        modules.chain(packages)
+    });
+    let graph = serde_json::json!({
+        "nodes": module_nodes.chain(package_nodes).collect_vec(),
+        "edges": edges.collect_vec(),
+    });
+    format!("{:#}\n", graph)
+}
+
+#[derive(Debug, strum::EnumString, strum::EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum BuildFilesFormat {
+    /// A `BUILD.bazel` file for Bazel.
+    Bazel,
+    /// A `BUCK` file for Buck2.
+    Buck2,
+}
+
+impl BuildFilesFormat {
+    fn build_file_name(self) -> &'static str {
+        match self {
+            Self::Bazel => "BUILD.bazel",
+            Self::Buck2 => "BUCK",
+        }
+    }
+
+    fn rules(self) -> &'static str {
+        match self {
+            Self::Bazel => include_str!("../templates/gleam.bazel.bzl"),
+            Self::Buck2 => include_str!("../templates/gleam.buck2.bzl"),
+        }
+    }
+
+    fn public_visibility(self) -> &'static str {
+        match self {
+            Self::Bazel => "//visibility:public",
+            Self::Buck2 => "PUBLIC",
+        }
+    }
+}
+
+/// The targets each package is described for in the build files.
+const BUILD_FILES_TARGETS: [Target; 2] = [Target::Erlang, Target::Native];
+
+/// A package described by the build files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BuildPackage {
+    name: String,
+    /// The directory of the package relative to the root of the project,
+    /// which is empty for the root package.
+    root: String,
+    /// The packages this package depends upon.
+    dependencies: Vec<String>,
+    modules: Vec<BuildModule>,
+    /// The Erlang and C++ source files of the package, relative to `src`.
+    native_files: Vec<String>,
+}
+
+/// A Gleam module described by the build files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BuildModule {
+    name: String,
+    /// The modules imported by the module when compiled for each target.
+    imports: HashMap<Target, Vec<String>>,
+}
+
+/// Write the Bazel or Buck2 build files of the project, along with the rules
+/// they use, to the root of the project.
+pub(crate) fn build_files(format: BuildFilesFormat) -> Result<()> {
+    let io = ProjectIO::new();
+    let manifest =
+        crate::dependencies::download(crate::cli::Reporter::new(), None, UseManifest::Yes)?;
+    let config = crate::config::root_config()?;
+    let mut packages = vec![build_package(&config, Path::new(""))?];
+    for local in crate::config::local_packages(&config, Mode::Prod)? {
+        packages.push(build_package(&local.config, &local.root)?);
+    }
+    let mut skipped = vec![];
+    for package in &manifest.packages {
+        if packages.iter().any(|p| p.name == package.name) {
+            continue;
+        }
+        // Packages built with rebar3 or mix are only needed once the BEAM
+        // bytecode is loaded, so are left for the user to describe.
+        if !package.build_tools.iter().any(|tool| tool == "gleam") {
+            skipped.push(package.name.as_str());
+            continue;
+        }
+        let root = paths::build_deps_package(&package.name);
+        let config = crate::config::read(paths::build_deps_package_config(&package.name))?;
+        packages.push(build_package(&config, &root)?);
+    }
+    let names = packages.iter().map(|p| p.name.clone()).collect_vec();
+    for package in &mut packages {
+        package.dependencies.retain(|name| names.contains(name));
+    }
+
+    let build_file = Path::new(format.build_file_name());
+    io.write(build_file, &build_files_build_file(format, &packages))?;
+    io.write(Path::new("gleam.bzl"), format.rules())?;
+
+    crate::cli::print_exported(&config.name);
+
+    println!(
+        "
+The build files have been written to {build_file} and gleam.bzl.
+
+Each package has a gleam_package target for each of the erlang and native
+targets, and each of its modules a gleam_module target, such as:
+
+    :{name}_erlang.{name}
+",
+        build_file = build_file.to_string_lossy(),
+        name = config.name,
+    );
+    if !skipped.is_empty() {
+        println!(
+            "These packages are not built with Gleam and are not described:
+
+    {}
+",
+            skipped.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+fn build_package(config: &PackageConfig, root: &Path) -> Result<BuildPackage> {
+    let io = ProjectIO::new();
+    let src = root.join("src");
+    let mut modules = vec![];
+    for path in ProjectIO::new().gleam_source_files(&src) {
+        let code = io.read(&path)?;
+        let arena = gleam_core::ast::Arena::new();
+        let (ast, _, errors) = gleam_core::parse::parse_module_with_recovery(&code, &arena);
+        if let Some(errors) = errors {
+            return Err(Error::Parse {
+                path,
+                src: code,
+                errors,
+            });
+        }
+        let imports = BUILD_FILES_TARGETS
+            .iter()
+            .map(|target| {
+                let imports = ast
+                    .iter_statements(*target)
+                    .filter_map(|statement| match statement {
+                        Statement::Import { module, .. } => Some(module.join("/")),
+                        _ => None,
+                    })
+                    .sorted()
+                    .dedup()
+                    .collect();
+                (*target, imports)
+            })
+            .collect();
+        modules.push(BuildModule {
+            name: relative_source_path(&src, &path, true),
+            imports,
+        });
+    }
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    let native_files = ["cc", "cpp", "erl"]
+        .iter()
+        .flat_map(|extension| crate::fs::files_with_extension(&src, extension))
+        .map(|path| relative_source_path(&src, &path, false))
+        .sorted()
+        .collect();
+    Ok(BuildPackage {
+        name: config.name.clone(),
+        root: root.to_string_lossy().replace('\\', "/"),
+        dependencies: config.dependencies.keys().cloned().sorted().collect(),
+        modules,
+        native_files,
+    })
+}
+
+fn relative_source_path(src: &Path, path: &Path, strip_extension: bool) -> String {
+    let path = path.strip_prefix(src).expect("source file in src");
+    let path = if strip_extension {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    };
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// The name of the target compiling a package, or with a module the module
+/// of that package, such as `app_erlang` or `app_erlang.app.router`.
+///
+/// Module names cannot contain dots, so names cannot collide.
+fn build_target_name(package: &str, target: Target, module: Option<&str>) -> String {
+    let name = format!("{package}_{target}");
+    match module {
+        Some(module) => format!("{name}.{}", module.replace('/', ".")),
+        None => name,
+    }
+}
+
+fn starlark_list(items: &[String]) -> String {
+    if items.is_empty() {
+        return "[]".into();
+    }
+    let items: String = items
+        .iter()
+        .map(|i| format!("        {:?},\n", i))
+        .collect();
+    format!("[\n{items}    ]")
+}
+
+fn build_files_build_file(format: BuildFilesFormat, packages: &[BuildPackage]) -> String {
+    let mut file = String::from(
+        "# Generated by `gleam export build-files`, changes will be overwritten.
+
+load(\":gleam.bzl\", \"gleam_module\", \"gleam_package\")
+",
+    );
+    // The package each module belongs to, for the modules they are imported by
+    let mut module_packages = BTreeMap::new();
+    for package in packages.iter().rev() {
+        for module in &package.modules {
+            let _ = module_packages.insert(module.name.as_str(), package.name.as_str());
+        }
+    }
+    for (index, package) in packages.iter().enumerate() {
+        let prefix = if package.root.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", package.root)
+        };
+        let visibility = if index == 0 {
+            format!("    visibility = [{:?}],\n", format.public_visibility())
+        } else {
+            String::new()
+        };
+        for target in BUILD_FILES_TARGETS {
+            let name = build_target_name(&package.name, target, None);
+            let deps = package
+                .dependencies
+                .iter()
+                .map(|dep| format!(":{}", build_target_name(dep, target, None)))
+                .collect_vec();
+            file.push_str(&format!(
+                "
+gleam_package(
+    name = {name:?},
+    package = {package:?},
+    target = \"{target}\",
+    srcs = glob([{srcs:?}]),
+    config = {config:?},
+    modules = {modules},
+    deps = {deps},
+{visibility})
+",
+                package = package.name,
+                srcs = format!("{prefix}src/**"),
+                config = format!("{prefix}gleam.toml"),
+                modules =
+                    starlark_list(&package.modules.iter().map(|m| m.name.clone()).collect_vec()),
+                deps = starlark_list(&deps),
+            ));
+            for module in &package.modules {
+                let file_path = match target {
+                    Target::Erlang => {
+                        format!("_gleam_artefacts/{}.erl", module.name.replace('/', "@"))
+                    }
+                    _ => format!("dist/{}.cc", module.name),
+                };
+                let deps = module
+                    .imports
+                    .get(&target)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|imported| {
+                        let package = module_packages.get(imported.as_str())?;
+                        Some(format!(
+                            ":{}",
+                            build_target_name(package, target, Some(imported))
+                        ))
+                    })
+                    .collect_vec();
+                file.push_str(&build_files_module(
+                    &build_target_name(&package.name, target, Some(&module.name)),
+                    &name,
+                    &file_path,
+                    &deps,
+                    &visibility,
+                ));
+            }
+            // The native files are named with their extension, as they can
+            // share the name of a Gleam module. Every package is generated with
+            // a copy of the C++ prelude, of which the root package's is used.
+            let prelude = (index == 0 && target == Target::Native).then(|| "gleam.cc".to_string());
+            for native in package.native_files.iter().chain(&prelude) {
+                let file_path = match (target, native.ends_with(".erl")) {
+                    (Target::Erlang, true) => format!("_gleam_artefacts/{native}"),
+                    (Target::Native, false) => format!("dist/{native}"),
+                    _ => continue,
+                };
+                file.push_str(&build_files_module(
+                    &build_target_name(&package.name, target, Some(native)),
+                    &name,
+                    &file_path,
+                    &[],
+                    &visibility,
+                ));
+            }
+        }
+    }
+    file
+}
+
+fn build_files_module(
+    name: &str,
+    package: &str,
+    file: &str,
+    deps: &[String],
+    visibility: &str,
+) -> String {
+    format!(
+        "
+gleam_module(
+    name = {name:?},
+    package = \":{package}\",
+    file = {file:?},
+    deps = {deps},
+{visibility})
+",
+        deps = starlark_list(deps),
+    )
+}
+
+#[cfg(test)]
+fn graph_modules() -> Vec<ModuleImports> {
+    vec![
+        ModuleImports {
+            name: "app".into(),
+            is_test: false,
+            modules: vec!["app/router".into()],
+            packages: vec!["gleam_stdlib".into()],
+        },
+        ModuleImports {
+            name: "app/router".into(),
+            is_test: false,
+            modules: vec![],
+            packages: vec!["gleam_http".into(), "gleam_stdlib".into()],
+        },
+        ModuleImports {
+            name: "app_test".into(),
+            is_test: true,
+            modules: vec!["app".into()],
+            packages: vec![],
+        },
+    ]
+}
+
+#[test]
+fn dependency_graph_dot_format() {
+    assert_eq!(
+        dependency_graph_dot("app", &graph_modules()),
+        r#"digraph "app" {
+  "app";
+  "app/router";
+  "app_test" [style=dashed];
+  "package:gleam_http" [label="gleam_http", shape=box];
+  "package:gleam_stdlib" [label="gleam_stdlib", shape=box];
+  "app" -> "app/router";
+  "app" -> "package:gleam_stdlib";
+  "app/router" -> "package:gleam_http";
+  "app/router" -> "package:gleam_stdlib";
+  "app_test" -> "app";
+}
+"#
+    );
+}
+
+#[test]
+fn dependency_graph_json_format() {
+    let graph: serde_json::Value =
+        serde_json::from_str(&dependency_graph_json(&graph_modules())).expect("graph json");
+    assert_eq!(
+        graph,
+        serde_json::json!({
+            "nodes": [
+                { "name": "app", "kind": "module", "test": false },
+                { "name": "app/router", "kind": "module", "test": false },
+                { "name": "app_test", "kind": "module", "test": true },
+                { "name": "gleam_http", "kind": "package" },
+                { "name": "gleam_stdlib", "kind": "package" },
+            ],
+            "edges": [
+                { "from": "app", "to": "app/router", "kind": "module" },
+                { "from": "app", "to": "gleam_stdlib", "kind": "package" },
+                { "from": "app/router", "to": "gleam_http", "kind": "package" },
+                { "from": "app/router", "to": "gleam_stdlib", "kind": "package" },
+                { "from": "app_test", "to": "app", "kind": "module" },
+            ],
+        })
+    );
+}
+
+#[test]
+fn javascript_entry_module_runs_main() {
+    assert_eq!(
+        javascript_entry_module("app"),
+        "import { main } from \"./app/app.mjs\";\n\nmain();\n"
+    );
+}
+
+#[test]
+fn native_lib_pkg_config_is_relocatable() {
+    let config = PackageConfig {
+        name: "app".into(),
+        description: "An app".into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        native_lib_pkg_config(&config, &[]),
+        r#"prefix=${pcfiledir}/../..
+includedir=${prefix}/include
+libdir=${prefix}/lib
+
+Name: app
+Description: An app
+Version: 0.1.0
+Cflags: -I${includedir}
+Libs: -L${libdir} -lapp
+Libs.private: -lstdc++ -lm
+"#
+    );
+}
+
+#[test]
+fn npm_package_json_exports_modules() {
+    let config = PackageConfig {
+        name: "app".into(),
+        description: "An app".into(),
+        licences: vec![
+            gleam_core::config::SpdxLicense {
+                licence: "MIT".into(),
+            },
+            gleam_core::config::SpdxLicense {
+                licence: "Apache-2.0".into(),
+            },
+        ],
+        repository: gleam_core::config::Repository::GitHub {
+            user: "lpil".into(),
+            repo: "app".into(),
+        },
+        ..Default::default()
+    };
+    let modules = ["app".into(), "app/router".into()];
+    let packages = ["app".into(), "gleam_stdlib".into()];
+    assert_eq!(
+        npm_package_json(&config, &modules, &packages),
+        r#"{
+  "name": "app",
+  "version": "0.1.0",
+  "description": "An app",
+  "license": "MIT OR Apache-2.0",
+  "repository": {
+    "type": "git",
+    "url": "https://github.com/lpil/app"
+  },
+  "type": "module",
+  "main": "./app/app.mjs",
+  "types": "./app/app.d.ts",
+  "exports": {
+    ".": {
+      "types": "./app/app.d.ts",
+      "import": "./app/app.mjs"
+    },
+    "./app": {
+      "types": "./app/app.d.ts",
+      "import": "./app/app.mjs"
+    },
+    "./app/router": {
+      "types": "./app/app/router.d.ts",
+      "import": "./app/app/router.mjs"
+    }
+  },
+  "files": [
+    "app",
+    "gleam_stdlib"
+  ]
+}
+"#
+    );
+}
+
+#[test]
+fn npm_package_json_without_main_module() {
+    let config = PackageConfig {
+        name: "app".into(),
+        ..Default::default()
+    };
+    let json: serde_json::Value =
+        serde_json::from_str(&npm_package_json(&config, &["app/router".into()], &[]))
+            .expect("package.json");
+    assert_eq!(json.get("main"), None);
+    assert_eq!(json.get("license"), None);
+    assert_eq!(json.get("repository"), None);
+    assert_eq!(
+        json["exports"].as_object().map(|exports| exports.len()),
+        Some(1)
+    );
+}
+
+#[test]
+fn javascript_package_json_is_a_module() {
+    let config = PackageConfig {
+        name: "app".into(),
+        description: "An app".into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        javascript_package_json(&config),
+        r#"{
+  "description": "An app",
+  "main": "./index.mjs",
+  "name": "app",
+  "private": true,
+  "scripts": {
+    "start": "node ./index.mjs"
+  },
+  "type": "module",
+  "version": "0.1.0"
+}
+"#
+    );
+}
+
+#[cfg(test)]
+fn build_module(name: &str, imports: &[&str]) -> BuildModule {
+    let imports = imports.iter().map(|i| i.to_string()).collect_vec();
+    BuildModule {
+        name: name.into(),
+        imports: BUILD_FILES_TARGETS
+            .iter()
+            .map(|target| (*target, imports.clone()))
+            .collect(),
+    }
+}
+
+#[test]
+fn build_files_bazel() {
+    let packages = [BuildPackage {
+        name: "app".into(),
+        root: "".into(),
+        dependencies: vec![],
+        modules: vec![build_module("app", &[])],
+        native_files: vec![],
+    }];
+    assert_eq!(
+        build_files_build_file(BuildFilesFormat::Bazel, &packages),
+        r#"# Generated by `gleam export build-files`, changes will be overwritten.
+
+load(":gleam.bzl", "gleam_module", "gleam_package")
+
+gleam_package(
+    name = "app_erlang",
+    package = "app",
+    target = "erlang",
+    srcs = glob(["src/**"]),
+    config = "gleam.toml",
+    modules = [
+        "app",
+    ],
+    deps = [],
+    visibility = ["//visibility:public"],
+)
+
+gleam_module(
+    name = "app_erlang.app",
+    package = ":app_erlang",
+    file = "_gleam_artefacts/app.erl",
+    deps = [],
+    visibility = ["//visibility:public"],
+)
+
+gleam_package(
+    name = "app_native",
+    package = "app",
+    target = "native",
+    srcs = glob(["src/**"]),
+    config = "gleam.toml",
+    modules = [
+        "app",
+    ],
+    deps = [],
+    visibility = ["//visibility:public"],
+)
+
+gleam_module(
+    name = "app_native.app",
+    package = ":app_native",
+    file = "dist/app.cc",
+    deps = [],
+    visibility = ["//visibility:public"],
+)
+
+gleam_module(
+    name = "app_native.gleam.cc",
+    package = ":app_native",
+    file = "dist/gleam.cc",
+    deps = [],
+    visibility = ["//visibility:public"],
+)
+"#
+    );
+}
+
+#[test]
+fn build_files_buck2_dependencies() {
+    let mut app = build_module("app/router", &["app/web", "gleam/list"]);
+    let _ = app
+        .imports
+        .insert(Target::Native, vec!["gleam/list".into()]);
+    let packages = [
+        BuildPackage {
+            name: "app".into(),
+            root: "".into(),
+            dependencies: vec!["gleam_stdlib".into()],
+            modules: vec![app, build_module("app/web", &[])],
+            native_files: vec!["app_ffi.erl".into(), "app/native.cc".into()],
+        },
+        BuildPackage {
+            name: "gleam_stdlib".into(),
+            root: "build/packages/gleam_stdlib".into(),
+            dependencies: vec![],
+            modules: vec![build_module("gleam/list", &[])],
+            native_files: vec![],
+        },
+    ];
+    let file = build_files_build_file(BuildFilesFormat::Buck2, &packages);
+    assert!(file.contains(
+        r#"
+gleam_package(
+    name = "gleam_stdlib_native",
+    package = "gleam_stdlib",
+    target = "native",
+    srcs = glob(["build/packages/gleam_stdlib/src/**"]),
+    config = "build/packages/gleam_stdlib/gleam.toml",
+    modules = [
+        "gleam/list",
+    ],
+    deps = [],
+)
+"#
+    ));
+    assert!(file.contains(
+        r#"
+gleam_module(
+    name = "app_erlang.app.router",
+    package = ":app_erlang",
+    file = "_gleam_artefacts/app@router.erl",
+    deps = [
+        ":app_erlang.app.web",
+        ":gleam_stdlib_erlang.gleam.list",
+    ],
+    visibility = ["PUBLIC"],
+)
+"#
+    ));
+    assert!(file.contains(
+        r#"
+gleam_module(
+    name = "app_native.app.router",
+    package = ":app_native",
+    file = "dist/app/router.cc",
+    deps = [
+        ":gleam_stdlib_native.gleam.list",
+    ],
+    visibility = ["PUBLIC"],
+)
+"#
+    ));
+    assert!(file.contains(
+        r#"
+gleam_module(
+    name = "app_erlang.app_ffi.erl",
+    package = ":app_erlang",
+    file = "_gleam_artefacts/app_ffi.erl",
+"#
+    ));
+    assert!(file.contains(
+        r#"
+gleam_module(
+    name = "app_native.app.native.cc",
+    package = ":app_native",
+    file = "dist/app/native.cc",
+"#
+    ));
+    assert!(!file.contains("app_native.app_ffi.erl"));
+    assert!(!file.contains("gleam_stdlib_native.gleam.cc"));
+}
+
+#[cfg(test)]
+fn native_lib_config() -> PackageConfig {
+    PackageConfig {
+        name: "my_app".into(),
+        description: "An \"app\"".into(),
+        licences: vec![gleam_core::config::SpdxLicense {
+            licence: "MIT".into(),
+        }],
+        repository: gleam_core::config::Repository::GitHub {
+            user: "lpil".into(),
+            repo: "my_app".into(),
+        },
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+fn native_lib_natives() -> Vec<NativeConfig> {
+    vec![
+        NativeConfig {
+            libraries: vec!["z".into()],
+            conan: vec!["zlib/1.3.1".into()],
+            vcpkg: vec!["zlib".into()],
+        },
+        NativeConfig {
+            libraries: vec!["sqlite3".into(), "z".into()],
+            conan: vec![],
+            vcpkg: vec!["sqlite3".into(), "zlib".into()],
+        },
+    ]
+}
+
+#[test]
+fn native_lib_conanfile() {
+    assert_eq!(
+        conanfile(&native_lib_config(), &native_lib_natives()),
+        r#"import os
+
+from conan import ConanFile
+from conan.tools.files import copy
+
+
+class GleamPackage(ConanFile):
+    name = "my_app"
+    version = "0.1.0"
+    description = "An \"app\""
+    license = "MIT"
+    url = "https://github.com/lpil/my_app"
+    settings = "os", "arch", "compiler", "build_type"
+    exports_sources = "include/*", "lib/*"
+
+    def requirements(self):
+        self.requires("zlib/1.3.1")
+
+    def package(self):
+        for directory in ("include", "lib"):
+            copy(
+                self,
+                "*",
+                os.path.join(self.source_folder, directory),
+                os.path.join(self.package_folder, directory),
+            )
+
+    def package_info(self):
+        self.cpp_info.libs = ["my_app"]
+        self.cpp_info.system_libs = ["stdc++", "m", "sqlite3", "z"]
+"#
+    );
+}
+
+#[test]
+fn native_lib_vcpkg_port() {
+    assert_eq!(
+        vcpkg_json(&native_lib_config(), &native_lib_natives()),
+        r#"{
+  "name": "my-app",
+  "version-semver": "0.1.0",
+  "description": "An \"app\"",
+  "homepage": "https://github.com/lpil/my_app",
+  "license": "MIT",
+  "dependencies": [
+    "zlib",
+    "sqlite3"
+  ]
+}
+"#
+    );
+    assert_eq!(
+        vcpkg_portfile(&native_lib_config()),
+        r#"vcpkg_check_linkage(ONLY_STATIC_LIBRARY)
+
+file(INSTALL "${CMAKE_CURRENT_LIST_DIR}/include/" DESTINATION "${CURRENT_PACKAGES_DIR}/include")
+foreach(directory IN ITEMS "lib" "debug/lib")
+    file(INSTALL "${CMAKE_CURRENT_LIST_DIR}/lib/libmy_app.a" DESTINATION "${CURRENT_PACKAGES_DIR}/${directory}")
+endforeach()
+file(INSTALL "${CMAKE_CURRENT_LIST_DIR}/lib/pkgconfig/my_app.pc" DESTINATION "${CURRENT_PACKAGES_DIR}/lib/pkgconfig")
+file(WRITE "${CURRENT_PACKAGES_DIR}/share/${PORT}/copyright" "my_app is available under MIT.\n")
+"#
+    );
+}
+
+#[test]
+fn native_lib_pkg_config_links_libraries() {
+    let pkg_config = native_lib_pkg_config(&native_lib_config(), &["sqlite3".into(), "z".into()]);
+    assert!(pkg_config.contains("\nLibs.private: -lstdc++ -lm -lsqlite3 -lz\n"));
+}
@@ -0,0 +1,62 @@
+use gleam_core::{
+    build::{Mode, Options},
+    io::OutputFile,
+    Result, Warning,
+};
+
+pub fn run() -> Result<()> {
+    let (package, warnings, importable_modules, ids, target) =
+        crate::build::with_warnings(Options {
+            perform_codegen: false,
+            warn_dead_code: false,
+            warning_levels: Default::default(),
+            mode: Mode::Dev,
+            target: None,
+        })?;
+    let options = package.config.format_options();
+
+    let mut fixed_count = 0;
+    for module in &package.modules {
+        let module_warnings: Vec<_> = warnings
+            .iter()
+            .filter_map(|warning| match warning {
+                Warning::Type { path, warning, .. } if path == &module.input_path => {
+                    Some(warning.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        let path = &module.input_path;
+        let fixes =
+            gleam_core::fix::fixes(&module.code, path, &module_warnings, &importable_modules)?;
+        let fixed = gleam_core::fix::apply(&module.code, path, &fixes, &options)?;
+        if fixed == module.code {
+            continue;
+        }
+        // A module is left as it was if the fixes would stop it compiling
+        let type_checks = gleam_core::fix::type_checks(
+            &fixed,
+            &module.name,
+            module.origin,
+            target,
+            &package.config,
+            &ids,
+            &importable_modules,
+        );
+        if !type_checks {
+            crate::cli::print_not_fixed(&path.to_string_lossy());
+            continue;
+        }
+        crate::fs::write_output(&OutputFile {
+            path: path.clone(),
+            text: fixed,
+        })?;
+        crate::cli::print_fixed(&path.to_string_lossy());
+        fixed_count += 1;
+    }
+
+    if fixed_count == 0 {
+        println!("Nothing to fix");
+    }
+    Ok(())
+}
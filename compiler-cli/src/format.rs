@@ -1,6 +1,9 @@
 use gleam_core::{
+    ast::SrcSpan,
     error::{Error, FileIoAction, FileKind, Result, StandardIoAction, Unformatted},
+    format::Options,
     io::OutputFile,
+    line_numbers::LineNumbers,
 };
 use std::{
     io::Read,
@@ -8,18 +11,137 @@ use std::{
     str::FromStr,
 };
 
-pub fn run(stdin: bool, check: bool, files: Vec<String>) -> Result<()> {
-    if stdin {
-        process_stdin(check)
+/// A range of lines to format, given as `START:END`. Lines are counted from
+/// 1 and both ends are included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    start: u32,
+    end: u32,
+}
+
+impl FromStr for LineRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected a range of lines such as 3:10, got `{}`", s);
+        let (start, end) = s.split_once(':').ok_or_else(invalid)?;
+        let start: u32 = start.trim().parse().map_err(|_| invalid())?;
+        let end: u32 = end.trim().parse().map_err(|_| invalid())?;
+        if start == 0 || end < start {
+            return Err(invalid());
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl LineRange {
+    fn to_src_span(self, src: &str) -> SrcSpan {
+        let line_numbers = LineNumbers::new(src);
+        SrcSpan {
+            start: line_numbers.byte_index(self.start - 1, 0),
+            end: line_numbers.byte_index(self.end, 0),
+        }
+    }
+}
+
+/// Formats the given files, or the source read from stdin when there is a
+/// path to use for it.
+pub fn run(
+    stdin: Option<PathBuf>,
+    check: bool,
+    files: Vec<String>,
+    line_length: Option<usize>,
+    sort_imports: bool,
+    range: Option<LineRange>,
+    verify: bool,
+) -> Result<()> {
+    let override_options = |options: &mut Options| {
+        if let Some(line_length) = line_length {
+            options.line_length = line_length as isize;
+        }
+        options.sort_imports |= sort_imports;
+        options.verify = verify;
+    };
+    match stdin {
+        Some(path) => {
+            let mut options = configured_options(Some(&path))?;
+            override_options(&mut options);
+            process_stdin(&path, check, &options, range)
+        }
+        None => {
+            let mut options = ProjectOptions::read()?;
+            override_options(&mut options.root);
+            for (_, options) in &mut options.members {
+                override_options(options);
+            }
+            process_files(check, files, &options, range)
+        }
+    }
+}
+
+/// The options of the project in the current directory, and of each member
+/// of its workspace, whose files are formatted with their own options.
+#[derive(Debug)]
+pub struct ProjectOptions {
+    root: Options,
+    members: Vec<(PathBuf, Options)>,
+}
+
+impl ProjectOptions {
+    fn read() -> Result<Self> {
+        let config = gleam_core::paths::root_config();
+        if !config.is_file() {
+            return Ok(Self {
+                root: Options::default(),
+                members: vec![],
+            });
+        }
+        let config = crate::config::read(config)?;
+        let members = crate::config::workspace_members(&config)?
+            .into_iter()
+            .map(|member| (member.root, member.config.format_options()))
+            .collect();
+        Ok(Self {
+            root: config.format_options(),
+            members,
+        })
+    }
+
+    fn for_path(&self, path: &Path) -> &Options {
+        let path = path.strip_prefix(".").unwrap_or(path);
+        self.members
+            .iter()
+            .find(|(root, _)| path.starts_with(root.strip_prefix(".").unwrap_or(root)))
+            .map(|(_, options)| options)
+            .unwrap_or(&self.root)
+    }
+}
+
+// The options of the project the path is in, if there is one, otherwise those
+// of the project in the current directory
+fn configured_options(path: Option<&Path>) -> Result<Options> {
+    let config = path
+        .into_iter()
+        .flat_map(Path::ancestors)
+        .skip(1)
+        .map(|directory| directory.join(gleam_core::paths::root_config()))
+        .find(|config| config.is_file())
+        .unwrap_or_else(gleam_core::paths::root_config);
+    if config.is_file() {
+        Ok(crate::config::read(config)?.format_options())
     } else {
-        process_files(check, files)
+        Ok(Options::default())
     }
 }
 
-fn process_stdin(check: bool) -> Result<()> {
+fn process_stdin(
+    path: &Path,
+    check: bool,
+    options: &Options,
+    range: Option<LineRange>,
+) -> Result<()> {
     let src = read_stdin()?;
-    let mut out = String::new();
-    gleam_core::format::pretty(&mut out, &src, Path::new("<stdin>"))?;
+    let out = format(&src, path, options, range)?;
 
     if !check {
         print!("{}", out);
@@ -40,16 +162,25 @@ fn process_stdin(check: bool) -> Result<()> {
     Ok(())
 }
 
-fn process_files(check: bool, files: Vec<String>) -> Result<()> {
+fn process_files(
+    check: bool,
+    files: Vec<String>,
+    options: &ProjectOptions,
+    range: Option<LineRange>,
+) -> Result<()> {
     if check {
-        check_files(files)
+        check_files(files, options, range)
     } else {
-        format_files(files)
+        format_files(files, options, range)
     }
 }
 
-fn check_files(files: Vec<String>) -> Result<()> {
-    let problem_files = unformatted_files(files)?;
+fn check_files(
+    files: Vec<String>,
+    options: &ProjectOptions,
+    range: Option<LineRange>,
+) -> Result<()> {
+    let problem_files = unformatted_files(files, options, range)?;
 
     if problem_files.is_empty() {
         Ok(())
@@ -58,8 +189,12 @@ fn check_files(files: Vec<String>) -> Result<()> {
     }
 }
 
-fn format_files(files: Vec<String>) -> Result<()> {
-    for file in unformatted_files(files)? {
+fn format_files(
+    files: Vec<String>,
+    options: &ProjectOptions,
+    range: Option<LineRange>,
+) -> Result<()> {
+    for file in unformatted_files(files, options, range)? {
         crate::fs::write_output(&OutputFile {
             path: file.destination,
             text: file.output,
@@ -68,7 +203,11 @@ fn format_files(files: Vec<String>) -> Result<()> {
     Ok(())
 }
 
-pub fn unformatted_files(files: Vec<String>) -> Result<Vec<Unformatted>> {
+pub fn unformatted_files(
+    files: Vec<String>,
+    options: &ProjectOptions,
+    range: Option<LineRange>,
+) -> Result<Vec<Unformatted>> {
     let mut problem_files = Vec::with_capacity(files.len());
 
     for file_path in files {
@@ -81,20 +220,26 @@ pub fn unformatted_files(files: Vec<String>) -> Result<Vec<Unformatted>> {
 
         if path.is_dir() {
             for path in crate::fs::gleam_files_excluding_gitignore(&path) {
-                format_file(&mut problem_files, path)?;
+                let options = options.for_path(&path);
+                format_file(&mut problem_files, path, options, range)?;
             }
         } else {
-            format_file(&mut problem_files, path)?;
+            let options = options.for_path(&path);
+            format_file(&mut problem_files, path, options, range)?;
         }
     }
 
     Ok(problem_files)
 }
 
-fn format_file(problem_files: &mut Vec<Unformatted>, path: PathBuf) -> Result<()> {
+fn format_file(
+    problem_files: &mut Vec<Unformatted>,
+    path: PathBuf,
+    options: &Options,
+    range: Option<LineRange>,
+) -> Result<()> {
     let src = crate::fs::read(&path)?;
-    let mut output = String::new();
-    gleam_core::format::pretty(&mut output, &src, &path)?;
+    let output = format(&src, &path, options, range)?;
 
     if src != output {
         problem_files.push(Unformatted {
@@ -107,6 +252,28 @@ fn format_file(problem_files: &mut Vec<Unformatted>, path: PathBuf) -> Result<()
     Ok(())
 }
 
+// Format the whole of the source, or only the definitions in the range when
+// one is given.
+fn format(src: &str, path: &Path, options: &Options, range: Option<LineRange>) -> Result<String> {
+    let range = match range {
+        Some(range) => range,
+        None => {
+            let mut output = String::new();
+            gleam_core::format::pretty(&mut output, src, path, options)?;
+            return Ok(output);
+        }
+    };
+    let edit = gleam_core::format::pretty_range(src, path, range.to_src_span(src), options)?;
+    let mut output = src.to_string();
+    if let Some(edit) = edit {
+        output.replace_range(
+            edit.location.start as usize..edit.location.end as usize,
+            &edit.new_text,
+        );
+    }
+    Ok(output)
+}
+
 pub fn read_stdin() -> Result<String> {
     let mut src = String::new();
     let _ = std::io::stdin()
@@ -1,8 +1,8 @@
 use gleam_core::{
     error::{Error, FileIoAction, FileKind},
     io::{
-        CommandExecutor, DirEntry, FileSystemIO, FileSystemWriter, OutputFile, ReadDir, Stdio,
-        WrappedReader, WrappedWriter,
+        CommandExecutor, CommandOutput, DirEntry, FileSystemIO, FileSystemWriter, OutputFile,
+        ReadDir, Stdio, WrappedReader, WrappedWriter,
     },
     Result,
 };
@@ -133,7 +133,9 @@ impl CommandExecutor for ProjectIO {
         cwd: Option<&Path>,
         stdio: Stdio,
     ) -> Result<i32, Error> {
-        tracing::debug!(program=program, args=?args.join(" "), env=?env, cwd=?cwd, "command_exec");
+        let span = tracing::info_span!("command", program = program);
+        let _enter = span.enter();
+        tracing::debug!(args=?args.join(" "), env=?env, cwd=?cwd, "command_exec");
         let result = std::process::Command::new(program)
             .args(args)
             .stdin(stdio.get_process_stdio())
@@ -143,22 +145,72 @@ impl CommandExecutor for ProjectIO {
             .status();
 
         match result {
-            Ok(status) => Ok(status.code().unwrap_or_default()),
+            Ok(status) => {
+                let status = status.code().unwrap_or_default();
+                tracing::debug!(status = status, "command_exited");
+                Ok(status)
+            }
+            Err(error) => Err(command_error(program, error)),
+        }
+    }
 
-            Err(error) => Err(match error.kind() {
-                io::ErrorKind::NotFound => Error::ShellProgramNotFound {
-                    program: program.to_string(),
-                },
+    fn exec_captured(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &[(&str, String)],
+        cwd: Option<&Path>,
+    ) -> Result<(i32, String), Error> {
+        let output = self.exec_output(program, args, env, cwd)?;
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        Ok((output.status, text))
+    }
 
-                other => Error::ShellCommand {
-                    program: program.to_string(),
-                    err: Some(other),
-                },
-            }),
+    fn exec_output(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &[(&str, String)],
+        cwd: Option<&Path>,
+    ) -> Result<CommandOutput, Error> {
+        let span = tracing::info_span!("command", program = program);
+        let _enter = span.enter();
+        tracing::debug!(args=?args.join(" "), env=?env, cwd=?cwd, "command_exec_captured");
+        let result = std::process::Command::new(program)
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .envs(env.iter().map(|(a, b)| (a, b)))
+            .current_dir(cwd.unwrap_or_else(|| Path::new("./")))
+            .output();
+
+        match result {
+            Ok(output) => {
+                tracing::debug!(status = output.status.code(), "command_exited");
+                Ok(CommandOutput {
+                    status: output.status.code().unwrap_or_default(),
+                    stdout: output.stdout,
+                    stderr: output.stderr,
+                })
+            }
+            Err(error) => Err(command_error(program, error)),
         }
     }
 }
 
+fn command_error(program: &str, error: io::Error) -> Error {
+    match error.kind() {
+        io::ErrorKind::NotFound => Error::ShellProgramNotFound {
+            program: program.to_string(),
+        },
+
+        other => Error::ShellCommand {
+            program: program.to_string(),
+            err: Some(other),
+        },
+    }
+}
+
 impl FileSystemIO for ProjectIO {}
 
 pub fn delete_dir(dir: &Path) -> Result<(), Error> {
@@ -233,6 +285,67 @@ pub fn make_executable(_path: impl AsRef<Path>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Write a file readable and writable by its owner only, for files that hold
+/// secrets. The text is written to a new file in the same directory that is
+/// created private, which then replaces the file, so that the secret is never
+/// readable by others, even while being written.
+pub fn write_private(path: &Path, text: &str) -> Result<(), Error> {
+    tracing::debug!(path = ?path, "writing_private_file");
+
+    let dir_path = path.parent().ok_or_else(|| Error::FileIo {
+        action: FileIoAction::FindParent,
+        kind: FileKind::Directory,
+        path: path.to_path_buf(),
+        err: None,
+    })?;
+    std::fs::create_dir_all(dir_path).map_err(|e| Error::FileIo {
+        action: FileIoAction::Create,
+        kind: FileKind::Directory,
+        path: dir_path.to_path_buf(),
+        err: Some(e.to_string()),
+    })?;
+
+    // A file left by an earlier failed write may not be private, so it is
+    // removed and the file must be newly created
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+    delete_file(&temporary)?;
+    let mut options = std::fs::OpenOptions::new();
+    let _ = options.write(true).create_new(true);
+    #[cfg(target_family = "unix")]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        let _ = options.mode(0o600);
+    }
+    let mut file = options.open(&temporary).map_err(|e| Error::FileIo {
+        action: FileIoAction::Create,
+        kind: FileKind::File,
+        path: temporary.clone(),
+        err: Some(e.to_string()),
+    })?;
+
+    let written = file
+        .write_all(text.as_bytes())
+        .and_then(|_| file.sync_all());
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&temporary);
+        return Err(Error::FileIo {
+            action: FileIoAction::WriteTo,
+            kind: FileKind::File,
+            path: temporary,
+            err: Some(e.to_string()),
+        });
+    }
+
+    std::fs::rename(&temporary, path).map_err(|e| Error::FileIo {
+        action: FileIoAction::Rename,
+        kind: FileKind::File,
+        path: path.to_path_buf(),
+        err: Some(e.to_string()),
+    })
+}
+
 pub fn writer(path: &Path) -> Result<WrappedWriter, Error> {
     tracing::debug!(path = ?path, "opening_file_writer");
     let dir_path = path.parent().ok_or_else(|| Error::FileIo {
@@ -383,19 +496,38 @@ pub fn erlang_files(dir: &Path) -> Result<impl Iterator<Item = PathBuf> + '_> {
         }))
 }
 
-pub fn create_tar_archive(outputs: Vec<OutputFile>) -> Result<Vec<u8>, Error> {
+/// The LLVM IR files within the directory and its subdirectories, in order.
+pub fn llvm_files(dir: &Path) -> Vec<PathBuf> {
+    files_with_extension(dir, "ll")
+}
+
+/// The files with the extension within the directory and its subdirectories,
+/// in order.
+pub fn files_with_extension(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .follow_links(true)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .map(walkdir::DirEntry::into_path)
+        .filter(|path| path.extension().and_then(OsStr::to_str) == Some(extension))
+        .collect()
+}
+
+pub fn create_tar_archive(mut outputs: Vec<OutputFile>) -> Result<Vec<u8>, Error> {
     tracing::debug!("creating_tar_archive");
 
     let encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
     let mut builder = tar::Builder::new(encoder);
 
+    outputs.sort_by(|a, b| a.path.cmp(&b.path));
     for file in outputs {
-        let mut header = tar::Header::new_gnu();
+        let mut header = tar_header(0o644, file.text.as_bytes().len());
         header.set_path(&file.path).map_err(|e| Error::AddTar {
             path: file.path.clone(),
             err: e.to_string(),
         })?;
-        header.set_size(file.text.as_bytes().len() as u64);
         header.set_cksum();
         builder
             .append(&header, file.text.as_bytes())
@@ -412,6 +544,160 @@ pub fn create_tar_archive(outputs: Vec<OutputFile>) -> Result<Vec<u8>, Error> {
         .map_err(|e| Error::Gzip(e.to_string()))
 }
 
+/// Create a zip archive of the files, given as their paths within the archive
+/// and their contents, each compressed with deflate. The files are written in
+/// order of their paths and with the earliest time a zip archive can hold, so
+/// that archives are reproducible.
+pub fn create_zip_archive(mut files: Vec<(String, Vec<u8>)>) -> Result<Vec<u8>, Error> {
+    tracing::debug!("creating_zip_archive");
+
+    // 00:00 on the 1st of January 1980, in the MS-DOS format used by zip
+    const TIME: u16 = 0;
+    const DATE: u16 = 1 << 5 | 1;
+    const VERSION: u16 = 20;
+    const DEFLATE: u16 = 8;
+
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut archive = vec![];
+    let mut directory = vec![];
+    for (path, contents) in &files {
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(vec![], flate2::Compression::default());
+        encoder
+            .write_all(contents)
+            .map_err(|e| Error::Gzip(e.to_string()))?;
+        let compressed = encoder.finish().map_err(|e| Error::Gzip(e.to_string()))?;
+        let mut crc = flate2::Crc::new();
+        crc.update(contents);
+        let offset = archive.len() as u32;
+
+        // The fields shared by the local header and the central directory
+        let mut fields = vec![];
+        for field in [0, DEFLATE, TIME, DATE] {
+            fields.extend(field.to_le_bytes());
+        }
+        for field in [crc.sum(), compressed.len() as u32, contents.len() as u32] {
+            fields.extend(field.to_le_bytes());
+        }
+        fields.extend((path.len() as u16).to_le_bytes());
+        fields.extend(0u16.to_le_bytes());
+
+        archive.extend(0x04034b50u32.to_le_bytes());
+        archive.extend(VERSION.to_le_bytes());
+        archive.extend(&fields);
+        archive.extend(path.as_bytes());
+        archive.extend(compressed);
+
+        directory.extend(0x02014b50u32.to_le_bytes());
+        directory.extend(VERSION.to_le_bytes());
+        directory.extend(VERSION.to_le_bytes());
+        directory.extend(&fields);
+        // No comment, the first disk, and no attributes
+        directory.extend([0; 10]);
+        directory.extend(offset.to_le_bytes());
+        directory.extend(path.as_bytes());
+    }
+
+    let offset = archive.len() as u32;
+    let entries = files.len() as u16;
+    archive.extend(&directory);
+    archive.extend(0x06054b50u32.to_le_bytes());
+    archive.extend([0; 4]);
+    archive.extend(entries.to_le_bytes());
+    archive.extend(entries.to_le_bytes());
+    archive.extend((directory.len() as u32).to_le_bytes());
+    archive.extend(offset.to_le_bytes());
+    archive.extend([0; 2]);
+    Ok(archive)
+}
+
+/// The header of a file in a tar archive, with no owner and with the time
+/// given by `SOURCE_DATE_EPOCH`, if any, so that archives are reproducible.
+pub fn tar_header(mode: u32, size: usize) -> tar::Header {
+    let mut header = tar::Header::new_gnu();
+    header.set_mode(mode);
+    header.set_size(size as u64);
+    header.set_mtime(source_date_epoch().unwrap_or(0));
+    header.set_cksum();
+    header
+}
+
+/// The time in seconds since the Unix epoch given by the `SOURCE_DATE_EPOCH`
+/// environment variable, to be used in place of the current time in the
+/// output of the build.
+pub fn source_date_epoch() -> Option<u64> {
+    parse_source_date_epoch(&std::env::var("SOURCE_DATE_EPOCH").ok()?)
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn write_private_test() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let directory = std::env::temp_dir().join(format!("gleam-private-{}", std::process::id()));
+    let path = directory.join("credentials.toml");
+    write(&path, "old").expect("write");
+    write_private(&path, "secret").expect("write private");
+    assert_eq!(read(&path).expect("read"), "secret");
+    let mode = std::fs::metadata(&path)
+        .expect("metadata")
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+    assert_eq!(std::fs::read_dir(&directory).expect("read dir").count(), 1);
+    delete_dir(&directory).expect("remove directory");
+}
+
+fn parse_source_date_epoch(seconds: &str) -> Option<u64> {
+    seconds.trim().parse().ok()
+}
+
+#[test]
+fn parse_source_date_epoch_test() {
+    assert_eq!(parse_source_date_epoch("1665000000"), Some(1665000000));
+    assert_eq!(parse_source_date_epoch(" 1665000000\n"), Some(1665000000));
+    assert_eq!(parse_source_date_epoch(""), None);
+    assert_eq!(parse_source_date_epoch("yesterday"), None);
+}
+
+#[test]
+fn create_zip_archive_test() {
+    use std::io::Read;
+
+    let archive = create_zip_archive(vec![
+        ("b/ebin/b.beam".into(), b"second".to_vec()),
+        ("a/ebin/a.app".into(), b"first first first".to_vec()),
+    ])
+    .expect("zip archive");
+    let u16_at = |offset: usize| u16::from_le_bytes([archive[offset], archive[offset + 1]]);
+    let u32_at = |offset: usize| {
+        u32::from_le_bytes([
+            archive[offset],
+            archive[offset + 1],
+            archive[offset + 2],
+            archive[offset + 3],
+        ])
+    };
+
+    // The end of central directory record gives the number of files
+    let end = archive.len() - 22;
+    assert_eq!(u32_at(end), 0x06054b50);
+    assert_eq!(u16_at(end + 10), 2);
+
+    // The files are in order of their paths, each compressed with deflate
+    assert_eq!(u32_at(0), 0x04034b50);
+    assert_eq!(u16_at(8), 8);
+    let compressed = u32_at(18) as usize;
+    assert_eq!(u32_at(22), 17);
+    assert_eq!(&archive[30..42], b"a/ebin/a.app");
+    let mut contents = String::new();
+    let _ = flate2::read::DeflateDecoder::new(&archive[42..42 + compressed])
+        .read_to_string(&mut contents)
+        .expect("inflate");
+    assert_eq!(contents, "first first first");
+    assert_eq!(u32_at(42 + compressed), 0x04034b50);
+}
+
 pub fn mkdir(path: impl AsRef<Path> + Debug) -> Result<(), Error> {
     tracing::debug!(path=?path, "creating_directory");
 
@@ -455,6 +741,17 @@ pub fn read(path: impl AsRef<Path> + Debug) -> Result<String, Error> {
     })
 }
 
+pub fn read_bytes(path: impl AsRef<Path> + Debug) -> Result<Vec<u8>, Error> {
+    tracing::debug!(path=?path,"reading_file");
+
+    std::fs::read(&path).map_err(|err| Error::FileIo {
+        action: FileIoAction::Read,
+        kind: FileKind::File,
+        path: PathBuf::from(path.as_ref()),
+        err: Some(err.to_string()),
+    })
+}
+
 pub fn reader(path: impl AsRef<Path> + Debug) -> Result<WrappedReader, Error> {
     tracing::debug!(path=?path,"opening_file_reader");
 
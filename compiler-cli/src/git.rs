@@ -0,0 +1,451 @@
+//! Fetching the packages that are depended upon by git repository.
+//!
+//! Each repository is cloned once into the build directory and is only
+//! fetched from again when a reference is to be resolved to a commit, or when
+//! the locked commit is not yet in the clone. The locked commit of a package is
+//! exported from the clone into the packages directory, in the same place as
+//! the packages downloaded from Hex.
+
+use std::path::Path;
+
+use gleam_core::{
+    config::PackageConfig,
+    error::{Error, FileIoAction, FileKind},
+    io::CommandExecutor,
+    paths, Result,
+};
+
+/// Resolve a branch, tag, or commit of the repository to the hash of a
+/// commit, fetching the latest changes to the repository first.
+pub fn resolve(
+    io: &impl CommandExecutor,
+    package: &str,
+    repo: &str,
+    reference: &str,
+) -> Result<String> {
+    tracing::info!(
+        package = package,
+        repo = repo,
+        reference = reference,
+        "resolving_git_reference"
+    );
+    // A reference that git would take to be an option is never a name
+    if reference.starts_with('-') {
+        return Err(Error::GitDependency {
+            package: package.to_string(),
+            repo: repo.to_string(),
+            error: "The branch, tag, or commit must not begin with `-`.".to_string(),
+        });
+    }
+    let repository = paths::build_git_repository(package);
+    clone_or_fetch(io, package, repo, &repository)?;
+    let object = format!("{}^{{commit}}", reference);
+    match git(
+        io,
+        package,
+        repo,
+        Some(&repository),
+        &["rev-parse", "--verify", "--quiet", &object],
+    ) {
+        Ok(commit) => {
+            let commit = String::from_utf8_lossy(&commit).trim().to_string();
+            check_commit(package, repo, &commit)?;
+            Ok(commit)
+        }
+        Err(Error::GitDependency { .. }) => Err(Error::GitDependency {
+            package: package.to_string(),
+            repo: repo.to_string(),
+            error: format!("There is no branch, tag, or commit named `{}`.", reference),
+        }),
+        Err(error) => Err(error),
+    }
+}
+
+/// Read the gleam.toml of the package at a commit.
+pub fn read_config(
+    io: &impl CommandExecutor,
+    package: &str,
+    repo: &str,
+    commit: &str,
+) -> Result<PackageConfig> {
+    check_commit(package, repo, commit)?;
+    let repository = ensure_commit(io, package, repo, commit)?;
+    let file = format!("{}:gleam.toml", commit);
+    let toml = git(io, package, repo, Some(&repository), &["show", &file])?;
+    toml::from_str(&String::from_utf8_lossy(&toml)).map_err(|e| Error::FileIo {
+        action: FileIoAction::Parse,
+        kind: FileKind::File,
+        path: repository.join(file),
+        err: Some(e.to_string()),
+    })
+}
+
+/// Replace the contents of the destination directory with the files of the
+/// package at a commit.
+pub fn export(
+    io: &impl CommandExecutor,
+    package: &str,
+    repo: &str,
+    commit: &str,
+    destination: &Path,
+) -> Result<()> {
+    tracing::info!(package = package, commit = commit, "exporting_git_package");
+    check_commit(package, repo, commit)?;
+    let repository = ensure_commit(io, package, repo, commit)?;
+    let tarball = git(
+        io,
+        package,
+        repo,
+        Some(&repository),
+        &["archive", "--format=tar", commit],
+    )?;
+    crate::fs::delete_dir(destination)?;
+    crate::fs::mkdir(destination)?;
+    tar::Archive::new(tarball.as_slice())
+        .unpack(destination)
+        .map_err(|e| Error::ExpandTar {
+            error: e.to_string(),
+        })
+}
+
+/// Check that a commit is the full hash of a git object, SHA-1 or SHA-256,
+/// as the locked commit of manifest.toml is given to git as an argument and
+/// could otherwise be taken to be an option.
+fn check_commit(package: &str, repo: &str, commit: &str) -> Result<()> {
+    let is_hash =
+        matches!(commit.len(), 40 | 64) && commit.bytes().all(|byte| byte.is_ascii_hexdigit());
+    if is_hash {
+        return Ok(());
+    }
+    Err(Error::GitDependency {
+        package: package.to_string(),
+        repo: repo.to_string(),
+        error: format!("`{}` is not the full hash of a commit.", commit),
+    })
+}
+
+fn ensure_commit(
+    io: &impl CommandExecutor,
+    package: &str,
+    repo: &str,
+    commit: &str,
+) -> Result<std::path::PathBuf> {
+    let repository = paths::build_git_repository(package);
+    let object = format!("{}^{{commit}}", commit);
+    let present = repository.is_dir()
+        && git(
+            io,
+            package,
+            repo,
+            Some(&repository),
+            &["cat-file", "-e", &object],
+        )
+        .is_ok();
+    if !present {
+        clone_or_fetch(io, package, repo, &repository)?;
+    }
+    Ok(repository)
+}
+
+fn clone_or_fetch(
+    io: &impl CommandExecutor,
+    package: &str,
+    repo: &str,
+    repository: &Path,
+) -> Result<()> {
+    // A repository that git would take to be an option, such as
+    // `--upload-pack=...`, could run any command
+    if repo.starts_with('-') {
+        return Err(Error::GitDependency {
+            package: package.to_string(),
+            repo: repo.to_string(),
+            error: "The repository must not begin with `-`.".to_string(),
+        });
+    }
+
+    // The clone is replaced if the package is now depended upon from a
+    // different repository
+    if repository.is_dir() {
+        let origin = git(
+            io,
+            package,
+            repo,
+            Some(repository),
+            &["config", "--get", "remote.origin.url"],
+        )
+        .unwrap_or_default();
+        if String::from_utf8_lossy(&origin).trim() == repo {
+            tracing::info!(package = package, "fetching_git_repository");
+            let _ = git(
+                io,
+                package,
+                repo,
+                Some(repository),
+                &[
+                    "fetch",
+                    "--quiet",
+                    "--force",
+                    "--tags",
+                    "origin",
+                    "+refs/heads/*:refs/heads/*",
+                ],
+            )?;
+            return Ok(());
+        }
+        crate::fs::delete_dir(repository)?;
+    }
+
+    tracing::info!(package = package, "cloning_git_repository");
+    let destination = repository.to_string_lossy();
+    let _ = git(
+        io,
+        package,
+        repo,
+        None,
+        &["clone", "--quiet", "--bare", "--", repo, &destination],
+    )?;
+    Ok(())
+}
+
+/// Run git, returning what it printed to stdout.
+fn git(
+    io: &impl CommandExecutor,
+    package: &str,
+    repo: &str,
+    repository: Option<&Path>,
+    args: &[&str],
+) -> Result<Vec<u8>> {
+    let mut arguments = vec![];
+    if let Some(repository) = repository {
+        arguments.push("-C".to_string());
+        arguments.push(repository.to_string_lossy().to_string());
+    }
+    arguments.extend(args.iter().map(|arg| arg.to_string()));
+    // Credentials cannot be asked for as the output of git is captured
+    let env = [("GIT_TERMINAL_PROMPT", "0".to_string())];
+    let output = io.exec_output("git", &arguments, &env, None)?;
+    if output.status == 0 {
+        return Ok(output.stdout);
+    }
+    Err(Error::GitDependency {
+        package: package.to_string(),
+        repo: repo.to_string(),
+        error: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gleam_core::io::{CommandOutput, Stdio};
+    use std::{cell::RefCell, collections::VecDeque};
+
+    /// Records the arguments git is run with, answering with the given
+    /// outputs in turn.
+    #[derive(Debug, Default)]
+    struct FakeGit {
+        calls: RefCell<Vec<Vec<String>>>,
+        outputs: RefCell<VecDeque<CommandOutput>>,
+    }
+
+    impl FakeGit {
+        fn new(outputs: Vec<CommandOutput>) -> Self {
+            Self {
+                calls: RefCell::new(vec![]),
+                outputs: RefCell::new(outputs.into()),
+            }
+        }
+    }
+
+    impl CommandExecutor for FakeGit {
+        fn exec(
+            &self,
+            program: &str,
+            _args: &[String],
+            _env: &[(&str, String)],
+            _cwd: Option<&Path>,
+            _stdio: Stdio,
+        ) -> Result<i32, Error> {
+            panic!("Unexpected exec of {}", program)
+        }
+
+        fn exec_output(
+            &self,
+            program: &str,
+            args: &[String],
+            env: &[(&str, String)],
+            _cwd: Option<&Path>,
+        ) -> Result<CommandOutput, Error> {
+            assert_eq!(program, "git");
+            assert_eq!(env, [("GIT_TERMINAL_PROMPT", "0".to_string())]);
+            self.calls.borrow_mut().push(args.to_vec());
+            Ok(self.outputs.borrow_mut().pop_front().unwrap_or_default())
+        }
+    }
+
+    fn output(status: i32, stdout: &str, stderr: &str) -> CommandOutput {
+        CommandOutput {
+            status,
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    const REPOSITORY: &str = "never/a/directory";
+    const COMMIT: &str = "4f2a9c1e8b7d6a5f4e3d2c1b0a9f8e7d6c5b4a39";
+
+    #[test]
+    fn clone_passes_repository_after_options() {
+        let io = FakeGit::default();
+        clone_or_fetch(
+            &io,
+            "wobble",
+            "https://example.com/wobble.git",
+            Path::new(REPOSITORY),
+        )
+        .expect("clone");
+        assert_eq!(
+            io.calls.into_inner(),
+            vec![vec![
+                "clone",
+                "--quiet",
+                "--bare",
+                "--",
+                "https://example.com/wobble.git",
+                REPOSITORY
+            ]]
+        );
+    }
+
+    #[test]
+    fn repository_that_looks_like_an_option_is_rejected() {
+        let io = FakeGit::default();
+        let result = clone_or_fetch(
+            &io,
+            "wobble",
+            "--upload-pack=touch /tmp/pwned",
+            Path::new(REPOSITORY),
+        );
+        assert_eq!(
+            result,
+            Err(Error::GitDependency {
+                package: "wobble".into(),
+                repo: "--upload-pack=touch /tmp/pwned".into(),
+                error: "The repository must not begin with `-`.".into(),
+            })
+        );
+        assert!(io.calls.into_inner().is_empty());
+    }
+
+    #[test]
+    fn resolve_reference_to_commit() {
+        let io = FakeGit::new(vec![
+            output(0, "", ""),
+            output(0, &format!("{}\n", COMMIT), ""),
+        ]);
+        let commit = resolve(&io, "wobble", "https://example.com/wobble.git", "v1.0.0");
+        assert_eq!(commit, Ok(COMMIT.to_string()));
+        let calls = io.calls.into_inner();
+        assert_eq!(
+            calls.get(1).map(|call| &call[2..]),
+            Some(
+                &[
+                    "rev-parse".to_string(),
+                    "--verify".to_string(),
+                    "--quiet".to_string(),
+                    "v1.0.0^{commit}".to_string()
+                ][..]
+            )
+        );
+    }
+
+    #[test]
+    fn resolve_unknown_reference() {
+        let io = FakeGit::new(vec![output(0, "", ""), output(1, "", "")]);
+        assert_eq!(
+            resolve(&io, "wobble", "https://example.com/wobble.git", "main"),
+            Err(Error::GitDependency {
+                package: "wobble".into(),
+                repo: "https://example.com/wobble.git".into(),
+                error: "There is no branch, tag, or commit named `main`.".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn git_errors_are_reported() {
+        let io = FakeGit::new(vec![output(
+            128,
+            "",
+            "fatal: repository 'https://example.com/wobble.git/' not found\n",
+        )]);
+        assert_eq!(
+            resolve(&io, "wobble", "https://example.com/wobble.git", "main"),
+            Err(Error::GitDependency {
+                package: "wobble".into(),
+                repo: "https://example.com/wobble.git".into(),
+                error: "fatal: repository 'https://example.com/wobble.git/' not found".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn reference_that_looks_like_an_option_is_rejected() {
+        let io = FakeGit::default();
+        assert_eq!(
+            resolve(
+                &io,
+                "wobble",
+                "https://example.com/wobble.git",
+                "--output=/tmp/x"
+            ),
+            Err(Error::GitDependency {
+                package: "wobble".into(),
+                repo: "https://example.com/wobble.git".into(),
+                error: "The branch, tag, or commit must not begin with `-`.".into(),
+            })
+        );
+        assert!(io.calls.into_inner().is_empty());
+    }
+
+    #[test]
+    fn locked_commit_that_is_not_a_hash_is_rejected() {
+        let io = FakeGit::default();
+        let error = Err(Error::GitDependency {
+            package: "wobble".into(),
+            repo: "https://example.com/wobble.git".into(),
+            error: "`--output=/tmp/x` is not the full hash of a commit.".into(),
+        });
+        assert_eq!(
+            export(
+                &io,
+                "wobble",
+                "https://example.com/wobble.git",
+                "--output=/tmp/x",
+                Path::new(REPOSITORY),
+            ),
+            error
+        );
+        assert_eq!(
+            read_config(
+                &io,
+                "wobble",
+                "https://example.com/wobble.git",
+                "--output=/tmp/x",
+            )
+            .map(|_| ()),
+            error
+        );
+        assert!(io.calls.into_inner().is_empty());
+    }
+
+    #[test]
+    fn commit_hashes() {
+        let check = |commit: &str| check_commit("wobble", "wobble.git", commit).is_ok();
+        assert!(check(COMMIT));
+        assert!(check(&"ab".repeat(32)));
+        assert!(!check("4f2a9c1"));
+        assert!(!check(&"g".repeat(40)));
+        assert!(!check(""));
+    }
+}
@@ -1,18 +1,91 @@
+use std::{
+    process::{Command, Stdio},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
 use gleam_core::{
-    hex::{self, RetirementReason},
-    Result,
+    config::{PackageConfig, RegistryConfig},
+    hex::{self, Registries, Registry, RetirementReason},
+    paths, Error, Result,
 };
 
-use crate::{cli, http::HttpClient};
+use crate::{
+    cli,
+    credentials::{Credentials, StoredKey},
+    http::HttpClient,
+};
 
-/// A helper trait that handles the provisioning and destruction of a Hex API key.
-pub trait ApiKeyCommand {
-    const USER_PROMPT: &'static str = "https://hex.pm username";
-    const USER_KEY: &'static str = "HEXPM_USER";
+/// The registries of the project, with the keys of those that are given a
+/// `key-env` or `key-command` in gleam.toml.
+pub fn registries(config: &PackageConfig) -> Result<Registries> {
+    let registries = config
+        .registries
+        .iter()
+        .map(|(name, registry)| Registry::new(name, registry, api_key(name, registry)?))
+        .collect::<Result<Vec<_>>>()?;
+    Registries::new(config, registries)
+}
 
-    const PASS_PROMPT: &'static str = "https://hex.pm password";
-    const PASS_KEY: &'static str = "HEXPM_PASS";
+fn api_key(name: &str, registry: &RegistryConfig) -> Result<Option<String>> {
+    let error = |error: String| Error::InvalidRegistry {
+        registry: name.into(),
+        error,
+    };
+
+    if let Some(variable) = &registry.key_env {
+        return std::env::var(variable)
+            .map(Some)
+            .map_err(|_| error(format!("The environment variable {} is not set.", variable)));
+    }
+
+    let command = match &registry.key_command {
+        Some(command) => command,
+        None => return Ok(None),
+    };
+    tracing::info!(registry = name, "running_registry_key_command");
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    // The command may ask the user to authenticate, so only the key it prints
+    // to stdout is captured
+    let output = Command::new(shell)
+        .arg(flag)
+        .arg(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| {
+            error(format!(
+                "The key command `{}` could not be run: {}",
+                command, e
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(error(format!("The key command `{}` failed.", command)));
+    }
+    Ok(Some(
+        String::from_utf8_lossy(&output.stdout).trim().to_string(),
+    ))
+}
+
+const USER_PROMPT: &str = "https://hex.pm username";
+const USER_KEY: &str = "HEXPM_USER";
+
+const PASS_PROMPT: &str = "https://hex.pm password";
+const PASS_KEY: &str = "HEXPM_PASS";
+
+/// The Hex username and password of the user, from the environment or asked
+/// for.
+fn ask_credentials() -> Result<(String, String)> {
+    let username = std::env::var(USER_KEY).or_else(|_| cli::ask(USER_PROMPT))?;
+    let password = std::env::var(PASS_KEY).or_else(|_| cli::ask_password(PASS_PROMPT))?;
+    Ok((username, password))
+}
 
+/// A helper trait that handles the provisioning and destruction of a Hex API key.
+pub trait ApiKeyCommand {
     fn with_api_key(
         &mut self,
         runtime: &tokio::runtime::Handle,
@@ -20,21 +93,46 @@ pub trait ApiKeyCommand {
         api_key: &str,
     ) -> Result<()>;
 
+    /// The registry to perform the API operation with.
+    fn registry(&self) -> Result<Registry> {
+        Ok(Registry::hexpm())
+    }
+
+    /// The organization of the registry to perform the API operation for.
+    fn organization(&self) -> Option<&str> {
+        None
+    }
+
     fn run(&mut self) -> Result<()> {
         let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+        let registry = self.registry()?;
+        let organization = self.organization().map(String::from);
+        let hex_config = match &organization {
+            Some(organization) => hex::organization_config(&registry, organization)?,
+            None => registry.config.clone(),
+        };
+
+        // Registries with a key given in gleam.toml are used with that key
+        // rather than one created for this operation
+        if let Some(api_key) = registry.api_key {
+            return self.with_api_key(runtime.handle(), &hex_config, &api_key);
+        }
+
+        // As are registries with a key stored by `gleam hex authenticate`
+        let credentials = Credentials::read()?;
+        if let Some(key) = credentials.for_registry(&registry.name, organization.as_deref()) {
+            return self.with_api_key(runtime.handle(), &hex_config, &key.secret);
+        }
+
         let hostname = crate::publish::get_hostname();
-        let hex_config = hexpm::Config::new();
         let http = HttpClient::new();
 
         // Get login creds from user
-
-        let username = std::env::var(Self::USER_KEY).or_else(|_| cli::ask(Self::USER_PROMPT))?;
-        let password =
-            std::env::var(Self::PASS_KEY).or_else(|_| cli::ask_password(Self::PASS_PROMPT))?;
+        let (username, password) = ask_credentials()?;
 
         // Get API key
         let api_key = runtime.block_on(gleam_core::hex::create_api_key(
-            &hostname,
+            &hex::key_name(&hostname),
             &username,
             &password,
             &hex_config,
@@ -47,7 +145,7 @@ pub trait ApiKeyCommand {
 
         // Ensure to remove the API key
         runtime.block_on(gleam_core::hex::remove_api_key(
-            &hostname,
+            &hex::key_name(&hostname),
             &hex_config,
             &api_key,
             &http,
@@ -87,6 +185,13 @@ impl ApiKeyCommand for RetireCommand {
         hex_config: &hexpm::Config,
         api_key: &str,
     ) -> Result<()> {
+        let http = HttpClient::new();
+        handle.block_on(hex::verify_package_owner(
+            &self.package,
+            api_key,
+            hex_config,
+            &http,
+        ))?;
         handle.block_on(hex::retire_release(
             &self.package,
             &self.version,
@@ -94,7 +199,7 @@ impl ApiKeyCommand for RetireCommand {
             self.message.as_deref(),
             api_key,
             hex_config,
-            &HttpClient::new(),
+            &http,
         ))?;
         cli::print_retired(&self.package, &self.version);
         Ok(())
@@ -119,14 +224,129 @@ impl ApiKeyCommand for UnretireCommand {
         hex_config: &hexpm::Config,
         api_key: &str,
     ) -> Result<()> {
+        let http = HttpClient::new();
+        handle.block_on(hex::verify_package_owner(
+            &self.package,
+            api_key,
+            hex_config,
+            &http,
+        ))?;
         handle.block_on(hex::unretire_release(
             &self.package,
             &self.version,
             api_key,
             hex_config,
-            &HttpClient::new(),
+            &http,
         ))?;
         cli::print_unretired(&self.package, &self.version);
         Ok(())
     }
 }
+
+/// The registries of the project in the current directory, or only hex.pm
+/// when there is none.
+fn project_registries() -> Result<Registries> {
+    if paths::root_config().exists() {
+        registries(&crate::config::root_config()?)
+    } else {
+        Ok(Registries::hexpm())
+    }
+}
+
+/// Create an API key with the Hex username and password of the user, named
+/// after this machine and the name it is stored with.
+fn create_stored_key(
+    runtime: &tokio::runtime::Runtime,
+    registry: &Registry,
+    organization: Option<String>,
+    name: &str,
+) -> Result<StoredKey> {
+    let (username, password) = ask_credentials()?;
+    // Hex key names are unique, so a rotated key is given a new name
+    let created = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let key_name = format!(
+        "{}-{}-{}",
+        hex::key_name(&crate::publish::get_hostname()),
+        name,
+        created
+    );
+    let secret = runtime.block_on(hex::create_api_key(
+        &key_name,
+        &username,
+        &password,
+        &registry.config,
+        &HttpClient::new(),
+    ))?;
+    Ok(StoredKey {
+        registry: registry.name.clone(),
+        organization,
+        key_name,
+        secret,
+    })
+}
+
+/// Create an API key for the default registry of the project and store it
+/// under the given name, replacing any key stored with that name before.
+pub fn authenticate(name: String, organization: Option<String>) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let registry = project_registries()?.default().clone();
+    let key = create_stored_key(&runtime, &registry, organization, &name)?;
+    let mut credentials = Credentials::read()?;
+    if let Some(previous) = credentials.keys.insert(name.clone(), key) {
+        revoke_on_registry(&runtime, &previous)?;
+    }
+    credentials.write()?;
+    cli::print_colourful_prefix("Authenticated", &format!("key {}", name));
+    Ok(())
+}
+
+/// Replace the stored key with a new key, revoking the old one.
+pub fn rotate(name: String) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let mut credentials = Credentials::read()?;
+    let previous = credentials.get(&name)?.clone();
+    let registry = project_registries()?.get(&previous.registry)?.clone();
+    let key = create_stored_key(&runtime, &registry, previous.organization.clone(), &name)?;
+    let _ = credentials.keys.insert(name.clone(), key);
+    credentials.write()?;
+    revoke_on_registry(&runtime, &previous)?;
+    cli::print_colourful_prefix("    Rotated", &format!("key {}", name));
+    Ok(())
+}
+
+/// Revoke the stored key on its registry and forget it.
+pub fn revoke(name: String) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let mut credentials = Credentials::read()?;
+    let key = credentials.get(&name)?.clone();
+    revoke_on_registry(&runtime, &key)?;
+    let _ = credentials.keys.remove(&name);
+    credentials.write()?;
+    cli::print_colourful_prefix("    Revoked", &format!("key {}", name));
+    Ok(())
+}
+
+fn revoke_on_registry(runtime: &tokio::runtime::Runtime, key: &StoredKey) -> Result<()> {
+    let registry = project_registries()?.get(&key.registry)?.clone();
+    runtime.block_on(hex::remove_api_key(
+        &key.key_name,
+        &registry.config,
+        &key.secret,
+        &HttpClient::new(),
+    ))
+}
+
+/// List the names of the stored keys, with their registries and
+/// organizations.
+pub fn keys() -> Result<()> {
+    for (name, key) in Credentials::read()?.keys {
+        match key.organization {
+            Some(organization) => println!("{} ({}, {})", name, key.registry, organization),
+            None => println!("{} ({})", name, key.registry),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,241 @@
+//! Logging of what the build tool is doing, for diagnosing slow or failing
+//! builds. Logs are written to stderr as text or as JSON lines, filtered by
+//! the `GLEAM_LOG` environment variable or else by the `--verbose` flag.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Subscriber,
+};
+use tracing_subscriber::{
+    field::RecordFields,
+    fmt::{
+        format::{FmtSpan, Writer},
+        FmtContext, FormatEvent, FormatFields, FormattedFields,
+    },
+    registry::LookupSpan,
+};
+
+#[derive(Debug, strum::EnumString, strum::EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum LogFormat {
+    /// Lines of text, for people to read.
+    Text,
+    /// A JSON object per line, for other programs.
+    Json,
+}
+
+pub fn initialise(verbosity: u8, format: LogFormat) {
+    let enable_colours = std::env::var("GLEAM_LOG_NOCOLOUR").is_err();
+    let filter = std::env::var("GLEAM_LOG").unwrap_or_else(|_| filter(verbosity).to_string());
+    let builder = tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(filter)
+        // Closing a span logs how long was spent within it
+        .with_span_events(FmtSpan::CLOSE);
+    match format {
+        LogFormat::Text => builder
+            .with_target(false)
+            .with_ansi(enable_colours)
+            .without_time()
+            .init(),
+        LogFormat::Json => builder
+            .fmt_fields(JsonFields)
+            .event_format(JsonFormat)
+            .init(),
+    }
+}
+
+/// The filter used when `GLEAM_LOG` is not set. Only the build tool's own
+/// logs are shown, not those of the libraries it uses.
+fn filter(verbosity: u8) -> &'static str {
+    match verbosity {
+        0 => "off",
+        1 => "gleam=info,gleam_core=info",
+        _ => "gleam=debug,gleam_core=debug",
+    }
+}
+
+/// Formats each event as a JSON object on a line of its own, with the spans
+/// it occurred within from outermost to innermost.
+#[derive(Debug, Clone, Copy)]
+struct JsonFormat;
+
+impl<S> FormatEvent<S, JsonFields> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, JsonFields>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut spans = vec![];
+        for span in ctx
+            .event_scope()
+            .into_iter()
+            .flat_map(|scope| scope.from_root())
+        {
+            let mut object = span
+                .extensions()
+                .get::<FormattedFields<JsonFields>>()
+                .map(|fields| json_object(&fields.fields))
+                .unwrap_or_default();
+            let _ = object.insert("name".into(), span.name().into());
+            spans.push(Value::Object(object));
+        }
+
+        let mut fields = JsonVisitor::default();
+        event.record(&mut fields);
+
+        let mut line = Map::new();
+        let _ = line.insert("level".into(), metadata.level().as_str().into());
+        let _ = line.insert("target".into(), metadata.target().into());
+        let _ = line.insert("fields".into(), Value::Object(fields.0));
+        let _ = line.insert("spans".into(), Value::Array(spans));
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+/// Formats the fields of spans as a JSON object, so that they can be included
+/// in the events logged within them.
+#[derive(Debug, Clone, Copy)]
+struct JsonFields;
+
+impl<'writer> FormatFields<'writer> for JsonFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> fmt::Result {
+        let mut visitor = JsonVisitor::default();
+        fields.record(&mut visitor);
+        write!(writer, "{}", Value::Object(visitor.0))
+    }
+
+    fn add_fields(
+        &self,
+        current: &'writer mut FormattedFields<Self>,
+        fields: &span::Record<'_>,
+    ) -> fmt::Result {
+        let mut visitor = JsonVisitor(json_object(&current.fields));
+        fields.record(&mut visitor);
+        current.fields = Value::Object(visitor.0).to_string();
+        Ok(())
+    }
+}
+
+fn json_object(text: &str) -> Map<String, Value> {
+    match serde_json::from_str(text) {
+        Ok(Value::Object(object)) => object,
+        _ => Map::new(),
+    }
+}
+
+#[derive(Debug, Default)]
+struct JsonVisitor(Map<String, Value>);
+
+impl JsonVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        let _ = self.0.insert(field.name().into(), value);
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, format!("{:?}", value).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().expect("Buffer lock").write(bytes)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn json_lines(log: impl FnOnce()) -> Vec<Value> {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_max_level(tracing::Level::DEBUG)
+            .fmt_fields(JsonFields)
+            .event_format(JsonFormat)
+            .finish();
+        tracing::subscriber::with_default(subscriber, log);
+        let bytes = buffer.0.lock().expect("Buffer lock").clone();
+        String::from_utf8(bytes)
+            .expect("UTF-8 log")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("JSON log line"))
+            .collect()
+    }
+
+    #[test]
+    fn json_events_include_their_spans() {
+        let lines = json_lines(|| {
+            let span = tracing::info_span!("compile", package = "app");
+            let _enter = span.enter();
+            let span =
+                tracing::debug_span!("command", program = "erlc", args = tracing::field::Empty);
+            let _ = span.record("args", "-o build");
+            let _enter = span.enter();
+            tracing::debug!(status = 0, "command_exited");
+        });
+        assert_eq!(
+            lines,
+            vec![serde_json::json!({
+                "level": "DEBUG",
+                "target": "gleam::logging::tests",
+                "fields": {"message": "command_exited", "status": 0},
+                "spans": [
+                    {"name": "compile", "package": "app"},
+                    {"name": "command", "program": "erlc", "args": "-o build"},
+                ],
+            })]
+        );
+    }
+
+    #[test]
+    fn verbosity_filter() {
+        assert_eq!(filter(0), "off");
+        assert_eq!(filter(1), "gleam=info,gleam_core=info");
+        assert_eq!(filter(3), "gleam=debug,gleam_core=debug");
+    }
+}
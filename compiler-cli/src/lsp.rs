@@ -5,37 +5,73 @@
 use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
 use crate::{
     build_lock::BuildLock, dependencies::UseManifest, fs::ProjectIO, telemetry::NullTelemetry,
 };
 use gleam_core::{
-    ast::{SrcSpan, Statement},
-    build::{self, Located, Module, ProjectCompiler},
+    ast::{SrcSpan, Statement, TypedStatement},
+    build::{self, Located, Module, ProjectCompiler, Target},
     config::PackageConfig,
     diagnostic::{self, Level},
-    io::{CommandExecutor, FileSystemIO, Stdio},
+    error::RenameProblem,
+    io::{
+        overlay::{Overlay, OverlayFileSystem},
+        CommandExecutor, FileSystemIO, Stdio,
+    },
+    language_server::{
+        CodeAction, CompletionKind, DocumentSymbol, ErrorActions, InlayHintKind, ReferenceIndex,
+        ReferenceKind, Runnable, SemanticTokenKind, SymbolKind,
+    },
     line_numbers::LineNumbers,
+    lint::Lint,
+    parse::extra::ModuleExtra,
     paths,
-    type_::pretty::Printer,
-    Error, Result,
+    type_::{self, pretty::Printer, Type},
+    Error, Result, Warning,
 };
 use itertools::Itertools;
 use lsp::request::GotoDefinition;
 use lsp_types::{
     self as lsp,
-    notification::{DidChangeTextDocument, DidCloseTextDocument, DidSaveTextDocument},
-    request::{Completion, Formatting, HoverRequest},
-    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidSaveTextDocumentParams, Hover,
-    HoverContents, HoverProviderCapability, InitializeParams, MarkedString, Position,
-    PublishDiagnosticsParams, Range, TextEdit, Url,
+    notification::{
+        DidChangeConfiguration, DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        DidSaveTextDocument,
+    },
+    request::{
+        CodeActionRequest, CodeLensRequest, Completion, DocumentHighlightRequest,
+        DocumentSymbolRequest, ExecuteCommand, Formatting, HoverRequest, InlayHintRefreshRequest,
+        InlayHintRequest, OnTypeFormatting, RangeFormatting, References, Rename,
+        SemanticTokensFullRequest, SignatureHelpRequest, WorkspaceSymbol,
+    },
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, Hover, HoverContents, HoverProviderCapability, InitializeParams,
+    Position, PublishDiagnosticsParams, Range, TextEdit, Url,
 };
 #[cfg(target_os = "windows")]
 use urlencoding::decode;
 
 const COMPILING_PROGRESS_TOKEN: &str = "compiling-gleam";
 const CREATE_COMPILING_PROGRESS_TOKEN: &str = "create-compiling-progress-token";
+const REFRESH_INLAY_HINTS: &str = "refresh-inlay-hints";
+
+// The commands of code lenses, run with `workspace/executeCommand`
+const RUN_MAIN_COMMAND: &str = "gleam.runMain";
+const RUN_TEST_COMMAND: &str = "gleam.runTest";
+const DEBUG_TEST_COMMAND: &str = "gleam.debugTest";
+
+/// The types of semantic token, in the order of their indexes.
+const SEMANTIC_TOKEN_TYPES: [lsp::SemanticTokenType; 7] = [
+    lsp::SemanticTokenType::NAMESPACE,
+    lsp::SemanticTokenType::TYPE,
+    lsp::SemanticTokenType::ENUM_MEMBER,
+    lsp::SemanticTokenType::FUNCTION,
+    lsp::SemanticTokenType::PARAMETER,
+    lsp::SemanticTokenType::VARIABLE,
+    lsp::SemanticTokenType::PROPERTY,
+];
 
 pub fn main() -> Result<()> {
     tracing::info!("language_server_starting");
@@ -79,7 +115,7 @@ fn server_capabilities() -> lsp::ServerCapabilities {
     lsp::ServerCapabilities {
         text_document_sync: Some(lsp::TextDocumentSyncCapability::Options(
             lsp::TextDocumentSyncOptions {
-                open_close: None,
+                open_close: Some(true),
                 change: Some(lsp::TextDocumentSyncKind::FULL),
                 will_save: None,
                 will_save_wait_until: None,
@@ -99,35 +135,105 @@ fn server_capabilities() -> lsp::ServerCapabilities {
             work_done_progress_options: lsp::WorkDoneProgressOptions {
                 work_done_progress: None,
             },
+            completion_item: None,
+        }),
+        signature_help_provider: Some(lsp::SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".into(), ",".into()]),
+            retrigger_characters: Some(vec![":".into()]),
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
         }),
-        signature_help_provider: None,
         definition_provider: Some(lsp::OneOf::Left(true)),
         type_definition_provider: None,
         implementation_provider: None,
-        references_provider: None,
-        document_highlight_provider: None,
-        document_symbol_provider: None,
-        workspace_symbol_provider: None,
-        code_action_provider: None,
-        code_lens_provider: None,
+        references_provider: Some(lsp::OneOf::Left(true)),
+        document_highlight_provider: Some(lsp::OneOf::Left(true)),
+        document_symbol_provider: Some(lsp::OneOf::Left(true)),
+        workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
+        code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+        code_lens_provider: Some(lsp::CodeLensOptions {
+            resolve_provider: None,
+        }),
         document_formatting_provider: Some(lsp::OneOf::Left(true)),
-        document_range_formatting_provider: None,
-        document_on_type_formatting_provider: None,
-        rename_provider: None,
+        document_range_formatting_provider: Some(lsp::OneOf::Left(true)),
+        document_on_type_formatting_provider: Some(lsp::DocumentOnTypeFormattingOptions {
+            first_trigger_character: "}".into(),
+            more_trigger_character: Some(vec!["\n".into()]),
+        }),
+        rename_provider: Some(lsp::OneOf::Left(true)),
         document_link_provider: None,
         color_provider: None,
         folding_range_provider: None,
         declaration_provider: None,
-        execute_command_provider: None,
+        execute_command_provider: Some(lsp::ExecuteCommandOptions {
+            commands: vec![
+                RUN_MAIN_COMMAND.into(),
+                RUN_TEST_COMMAND.into(),
+                DEBUG_TEST_COMMAND.into(),
+            ],
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
         workspace: None,
         call_hierarchy_provider: None,
-        semantic_tokens_provider: None,
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                lsp::SemanticTokensOptions {
+                    work_done_progress_options: lsp::WorkDoneProgressOptions {
+                        work_done_progress: None,
+                    },
+                    legend: lsp::SemanticTokensLegend {
+                        token_types: SEMANTIC_TOKEN_TYPES.to_vec(),
+                        token_modifiers: vec![lsp::SemanticTokenModifier::READONLY],
+                    },
+                    range: None,
+                    full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                },
+            ),
+        ),
         moniker_provider: None,
         linked_editing_range_provider: None,
+        inlay_hint_provider: Some(lsp::OneOf::Left(true)),
         experimental: None,
     }
 }
 
+/// The settings of the language server, given by the client as its
+/// initialization options or with the `workspace/didChangeConfiguration`
+/// notification, either as they are or within a `gleam` section.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Settings {
+    inlay_hints: InlayHintSettings,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(default)]
+struct InlayHintSettings {
+    /// Show the inferred types of bindings and of the returns of functions.
+    types: bool,
+    /// Show the labels of arguments given without them.
+    labels: bool,
+}
+
+impl Default for InlayHintSettings {
+    fn default() -> Self {
+        Self {
+            types: true,
+            labels: true,
+        }
+    }
+}
+
+impl Settings {
+    fn from_json(json: &serde_json::Value) -> Option<Self> {
+        let json = json.get("gleam").unwrap_or(json);
+        serde_json::from_value(json.clone()).ok()
+    }
+}
+
 #[derive(Debug)]
 pub struct LspMessage {
     level: Level,
@@ -149,8 +255,9 @@ pub struct LanguageServer {
     /// A cached copy of the absolute path of the project root
     project_root: PathBuf,
 
-    /// Files that have been edited in memory
-    edited: HashMap<String, String>,
+    /// Files that have been edited in memory, which the compiler reads in
+    /// place of the files on disc
+    edited: Overlay,
 
     /// Diagnostics that have been emitted by the compiler but not yet published
     /// to the client
@@ -167,23 +274,30 @@ pub struct LanguageServer {
     /// package.
     /// In the event the the project config changes this will need to be
     /// discarded and reloaded to handle any changes to dependencies.
-    compiler: Option<LspProjectCompiler<ProjectIO>>,
+    compiler: Option<LspProjectCompiler<EditedFilesIO>>,
 
     config: Option<PackageConfig>,
+    settings: Settings,
 }
 
 impl LanguageServer {
     pub fn new(initialise_params: InitializeParams, config: Option<PackageConfig>) -> Result<Self> {
         let project_root = std::env::current_dir().expect("Project root");
+        let settings = initialise_params
+            .initialization_options
+            .as_ref()
+            .and_then(Settings::from_json)
+            .unwrap_or_default();
         let mut language_server = Self {
             initialise_params,
-            edited: HashMap::new(),
+            edited: Overlay::default(),
             stored_messages: Vec::new(),
             stored_diagnostics: HashMap::new(),
             published_diagnostics: HashSet::new(),
             project_root,
             compiler: None,
             config,
+            settings,
         };
         language_server.create_new_compiler()?;
         Ok(language_server)
@@ -224,6 +338,7 @@ impl LanguageServer {
                 typ: match message.level {
                     Level::Error => lsp::MessageType::ERROR,
                     Level::Warning => lsp::MessageType::WARNING,
+                    Level::Info => lsp::MessageType::INFO,
                 },
                 message: message.text,
             };
@@ -256,10 +371,18 @@ impl LanguageServer {
     /// not having a location) it is stored as a message suitable for use with
     /// the `showMessage` notification instead.
     ///
-    fn process_gleam_diagnostic(&mut self, mut diagnostic: diagnostic::Diagnostic) {
+    /// Any structured data about the diagnostic for use by the client, such
+    /// as a suggested replacement for an unknown name, is attached to it.
+    ///
+    fn process_gleam_diagnostic(
+        &mut self,
+        mut diagnostic: diagnostic::Diagnostic,
+        data: Option<serde_json::Value>,
+    ) {
         let hint = diagnostic.hint.take();
         match diagnostic_to_lsp(diagnostic) {
-            LspDisplayable::Diagnostic(path, lsp_diagnostic) => {
+            LspDisplayable::Diagnostic(path, mut lsp_diagnostic) => {
+                lsp_diagnostic.data = data;
                 self.push_diagnostic(path.clone(), lsp_diagnostic.clone());
 
                 if let Some(hint) = hint {
@@ -311,10 +434,10 @@ impl LanguageServer {
                         return Ok(());
                     }
                     let id = request.id.clone();
-                    let result = self.handle_request(request);
+                    let result = self.handle_request(&connection, request);
                     let (response, diagnostic) = result_to_response(result, id);
                     if let Some(diagnostic) = diagnostic {
-                        self.process_gleam_diagnostic(diagnostic);
+                        self.process_gleam_diagnostic(diagnostic, None);
                         self.publish_stored_diagnostics(&connection)?;
                     }
                     connection
@@ -447,7 +570,7 @@ impl LanguageServer {
             let warnings = compiler.project_compiler.take_warnings();
             for warn in warnings {
                 let diagnostic = warn.to_diagnostic();
-                self.process_gleam_diagnostic(diagnostic);
+                self.process_gleam_diagnostic(diagnostic, warning_data(&warn));
             }
         }
     }
@@ -468,7 +591,9 @@ impl LanguageServer {
 
         // Store error diagnostics, if there are any
         if let Err(error) = result {
-            self.process_gleam_diagnostic(error.to_diagnostic());
+            for diagnostic in error.to_diagnostics() {
+                self.process_gleam_diagnostic(diagnostic, diagnostic_data(&error));
+            }
         }
 
         Ok(())
@@ -487,32 +612,77 @@ impl LanguageServer {
                 self.publish_result_diagnostics(result, connection)
             }
 
+            "textDocument/didOpen" => {
+                let params = cast_notification::<DidOpenTextDocument>(notification)
+                    .expect("cast DidOpenTextDocument");
+                let result = self.text_document_did_open(params, connection);
+                self.publish_result_diagnostics(result, connection)
+            }
+
             "textDocument/didClose" => {
                 let params = cast_notification::<DidCloseTextDocument>(notification)
                     .expect("cast DidCloseTextDocument");
-                self.text_document_did_close(params)
+                let result = self.text_document_did_close(params, connection);
+                self.publish_result_diagnostics(result, connection)
             }
 
             "textDocument/didChange" => {
                 let params = cast_notification::<DidChangeTextDocument>(notification)
                     .expect("cast DidChangeTextDocument");
-                self.text_document_did_change(params)
+                let result = self.text_document_did_change(params, connection);
+                self.publish_result_diagnostics(result, connection)
+            }
+
+            "workspace/didChangeConfiguration" => {
+                let params = cast_notification::<DidChangeConfiguration>(notification)
+                    .expect("cast DidChangeConfiguration");
+                if let Some(settings) = Settings::from_json(&params.settings) {
+                    self.settings = settings;
+                    self.refresh_inlay_hints(connection);
+                }
+                Ok(())
             }
 
             "workspace/didChangeWatchedFiles" => {
                 tracing::info!("gleam_toml_changed_so_recompiling_full_project");
                 self.create_new_compiler()?;
-                self.compile(connection)?;
-                Ok(())
+                let result = self.compile(connection);
+                self.publish_result_diagnostics(result, connection)
             }
 
             _ => Ok(()),
         }
     }
 
+    /// Ask the client to request the inlay hints again, if it is able to, as
+    /// the settings for which are shown have changed.
+    fn refresh_inlay_hints(&self, connection: &lsp_server::Connection) {
+        let supports_refresh = self
+            .initialise_params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.inlay_hint.as_ref())
+            .and_then(|inlay_hint| inlay_hint.refresh_support)
+            .unwrap_or(false);
+        if !supports_refresh {
+            return;
+        }
+        let request = lsp_server::Request::new(
+            REFRESH_INLAY_HINTS.to_string().into(),
+            <InlayHintRefreshRequest as lsp::request::Request>::METHOD.into(),
+            (),
+        );
+        connection
+            .sender
+            .send(lsp_server::Message::Request(request))
+            .expect("send workspace/inlayHint/refresh");
+    }
+
     fn create_new_compiler(&mut self) -> Result<(), Error> {
         if let Some(config) = self.config.as_ref() {
-            let compiler = LspProjectCompiler::new(config.clone(), ProjectIO::new())?;
+            let io = EditedFilesIO::new(ProjectIO::new(), self.edited.clone());
+            let compiler = LspProjectCompiler::new(config.clone(), io)?;
             self.compiler = Some(compiler);
         }
         Ok(())
@@ -524,28 +694,53 @@ impl LanguageServer {
         connection: &lsp_server::Connection,
     ) -> Result<()> {
         // The file is in sync with the file system, discard our cache of the changes
-        let _ = self.edited.remove(params.text_document.uri.path());
+        self.edited.remove(params.text_document.uri.path());
         // The files on disc have changed, so compile the project with the new changes
-        self.compile(connection)?;
-        Ok(())
+        self.compile(connection)
     }
 
-    fn text_document_did_close(&mut self, params: DidCloseTextDocumentParams) -> Result<()> {
-        // The file is in sync with the file system, discard our cache of the changes
-        let _ = self.edited.remove(params.text_document.uri.path());
-        Ok(())
+    fn text_document_did_open(
+        &mut self,
+        params: DidOpenTextDocumentParams,
+        connection: &lsp_server::Connection,
+    ) -> Result<()> {
+        // The editor may have content that has not been saved yet, so store it
+        // in memory and compile the project with it
+        let document = params.text_document;
+        self.edited.insert(document.uri.path(), document.text);
+        self.compile(connection)
     }
 
-    fn text_document_did_change(&mut self, params: DidChangeTextDocumentParams) -> Result<()> {
-        // A file has changed in the editor so store a copy of the new content in memory
-        let path = params.text_document.uri.path().to_string();
+    fn text_document_did_close(
+        &mut self,
+        params: DidCloseTextDocumentParams,
+        connection: &lsp_server::Connection,
+    ) -> Result<()> {
+        // Any unsaved changes have been discarded, so compile the project with
+        // the file as it is on disc
+        self.edited.remove(params.text_document.uri.path());
+        self.compile(connection)
+    }
+
+    fn text_document_did_change(
+        &mut self,
+        params: DidChangeTextDocumentParams,
+        connection: &lsp_server::Connection,
+    ) -> Result<()> {
+        // A file has changed in the editor so store a copy of the new content
+        // in memory and compile the project with it
+        let path = params.text_document.uri.path();
         if let Some(changes) = params.content_changes.into_iter().next() {
-            let _ = self.edited.insert(path, changes.text);
+            self.edited.insert(path, changes.text);
         }
-        Ok(())
+        self.compile(connection)
     }
 
-    fn handle_request(&self, request: lsp_server::Request) -> Result<serde_json::Value> {
+    fn handle_request(
+        &self,
+        connection: &lsp_server::Connection,
+        request: lsp_server::Request,
+    ) -> Result<serde_json::Value> {
         match request.method.as_str() {
             "textDocument/formatting" => {
                 let params = cast_request::<Formatting>(request).expect("cast Formatting");
@@ -553,6 +748,20 @@ impl LanguageServer {
                 Ok(serde_json::to_value(text_edit).expect("TextEdits to json"))
             }
 
+            "textDocument/rangeFormatting" => {
+                let params =
+                    cast_request::<RangeFormatting>(request).expect("cast RangeFormatting");
+                let text_edit = self.format_range(params)?;
+                Ok(serde_json::to_value(text_edit).expect("TextEdits to json"))
+            }
+
+            "textDocument/onTypeFormatting" => {
+                let params =
+                    cast_request::<OnTypeFormatting>(request).expect("cast OnTypeFormatting");
+                let text_edit = self.format_on_type(params)?;
+                Ok(serde_json::to_value(text_edit).expect("TextEdits to json"))
+            }
+
             "textDocument/hover" => {
                 let params = cast_request::<HoverRequest>(request).expect("cast HoverRequest");
                 let text_edit = self.hover(params)?;
@@ -571,6 +780,78 @@ impl LanguageServer {
                 Ok(serde_json::to_value(completions).expect("Completions to json"))
             }
 
+            "textDocument/codeAction" => {
+                let params =
+                    cast_request::<CodeActionRequest>(request).expect("cast CodeActionRequest");
+                let actions = self.code_action(params);
+                Ok(serde_json::to_value(actions).expect("CodeActions to json"))
+            }
+
+            "textDocument/references" => {
+                let params = cast_request::<References>(request).expect("cast References");
+                let locations = self.references(params);
+                Ok(serde_json::to_value(locations).expect("Locations to json"))
+            }
+
+            "textDocument/documentHighlight" => {
+                let params = cast_request::<DocumentHighlightRequest>(request)
+                    .expect("cast DocumentHighlightRequest");
+                let highlights = self.document_highlight(params);
+                Ok(serde_json::to_value(highlights).expect("DocumentHighlights to json"))
+            }
+
+            "textDocument/documentSymbol" => {
+                let params =
+                    cast_request::<DocumentSymbolRequest>(request).expect("cast DocumentSymbol");
+                let symbols = self.document_symbol(params);
+                Ok(serde_json::to_value(symbols).expect("DocumentSymbols to json"))
+            }
+
+            "textDocument/inlayHint" => {
+                let params = cast_request::<InlayHintRequest>(request).expect("cast InlayHint");
+                let hints = self.inlay_hint(params);
+                Ok(serde_json::to_value(hints).expect("InlayHints to json"))
+            }
+
+            "textDocument/codeLens" => {
+                let params = cast_request::<CodeLensRequest>(request).expect("cast CodeLens");
+                let lenses = self.code_lens(params);
+                Ok(serde_json::to_value(lenses).expect("CodeLenses to json"))
+            }
+
+            "workspace/executeCommand" => {
+                let params = cast_request::<ExecuteCommand>(request).expect("cast ExecuteCommand");
+                self.execute_command(connection, params)?;
+                Ok(serde_json::Value::Null)
+            }
+
+            "textDocument/signatureHelp" => {
+                let params =
+                    cast_request::<SignatureHelpRequest>(request).expect("cast SignatureHelp");
+                let help = self.signature_help(params);
+                Ok(serde_json::to_value(help).expect("SignatureHelp to json"))
+            }
+
+            "textDocument/semanticTokens/full" => {
+                let params = cast_request::<SemanticTokensFullRequest>(request)
+                    .expect("cast SemanticTokensFull");
+                let tokens = self.semantic_tokens(params);
+                Ok(serde_json::to_value(tokens).expect("SemanticTokens to json"))
+            }
+
+            "workspace/symbol" => {
+                let params =
+                    cast_request::<WorkspaceSymbol>(request).expect("cast WorkspaceSymbol");
+                let symbols = self.workspace_symbol(params);
+                Ok(serde_json::to_value(symbols).expect("SymbolInformation to json"))
+            }
+
+            "textDocument/rename" => {
+                let params = cast_request::<Rename>(request).expect("cast Rename");
+                let edit = self.rename(params)?;
+                Ok(serde_json::to_value(edit).expect("WorkspaceEdit to json"))
+            }
+
             _ => panic!("Unsupported LSP request"),
         }
     }
@@ -604,24 +885,30 @@ impl LanguageServer {
             None => return Ok(None),
         };
 
+        let dependency_module;
         let (uri, line_numbers) = match location.module {
             None => (params.text_document.uri, &line_numbers),
             Some(name) => {
-                let module = match self
-                    .compiler
-                    .as_ref()
-                    .and_then(|compiler| compiler.sources.get(name))
-                {
-                    Some(module) => module,
-                    // TODO: support goto definition for functions defined in
-                    // different packages. Currently it is not possible as the
-                    // required LineNumbers and source file path information is
-                    // not stored in the module metadata.
+                let compiler = match self.compiler.as_ref() {
+                    Some(compiler) => compiler,
                     None => return Ok(None),
                 };
-                let url = Url::parse(&format!("file:///{}", &module.path))
-                    .expect("goto definition URL parse");
-                (url, &module.line_numbers)
+                // Modules of the root package are kept in memory, while those
+                // of other packages are read from their source code
+                let module = match compiler.sources.get(name.as_ref()) {
+                    Some(module) => module,
+                    None => match compiler.dependency_module_source(&name) {
+                        Some(module) => {
+                            dependency_module = module;
+                            &dependency_module
+                        }
+                        None => return Ok(None),
+                    },
+                };
+                (
+                    path_to_uri(PathBuf::from(&module.path)),
+                    &module.line_numbers,
+                )
             }
         };
         let range = src_span_to_lsp_range(location.span, line_numbers);
@@ -629,30 +916,46 @@ impl LanguageServer {
         Ok(Some(lsp::Location { uri, range }))
     }
 
-    // TODO: function & constructor labels
-    // TODO: module types (including private)
-    // TODO: module values (including private)
-    // TODO: locally defined variables
-    // TODO: imported module values
-    // TODO: imported module types
-    // TODO: record accessors
     fn completion(&self, params: lsp::CompletionParams) -> Option<Vec<lsp::CompletionItem>> {
-        let found = self
-            .node_at_position(&params.text_document_position)
-            .map(|(_, found)| found);
-
-        match found {
-            // TODO: test
-            None | Some(Located::Statement(Statement::Import { .. })) => {
-                self.completion_for_import()
-            }
+        let params = params.text_document_position;
+        let compiler = self.compiler.as_ref()?;
+        // Until the module has compiled once only the modules it could import
+        // are known
+        let module = match self.module_for_uri(&params.text_document.uri) {
+            Some(module) => module,
+            None => return self.completion_for_import(),
+        };
 
-            // TODO: autocompletion for other statements
-            Some(Located::Statement(_expression)) => None,
+        // Complete what is being typed, which may not have been saved or
+        // compiled yet
+        let src = self
+            .edited
+            .get(Path::new(params.text_document.uri.path()))
+            .unwrap_or_else(|| module.code.clone());
+        let line_numbers = LineNumbers::new(&src);
+        let cursor = line_numbers.byte_index(params.position.line, params.position.character);
+        let completions = gleam_core::language_server::completions(
+            &src,
+            cursor,
+            &module.ast,
+            &compiler.importable_modules,
+        );
 
-            // TODO: autocompletion for expressions
-            Some(Located::Expression(_expression)) => None,
-        }
+        // The completions are ordered by relevance, which the client is told
+        // of with the text they are sorted by
+        let items = completions
+            .into_iter()
+            .enumerate()
+            .map(|(index, completion)| lsp::CompletionItem {
+                label: completion.label,
+                kind: Some(completion_item_kind(completion.kind)),
+                detail: completion.detail,
+                insert_text: completion.insert_text,
+                sort_text: Some(format!("{:04}", index)),
+                ..Default::default()
+            })
+            .collect();
+        Some(items)
     }
 
     fn completion_for_import(&self) -> Option<Vec<lsp::CompletionItem>> {
@@ -692,25 +995,594 @@ impl LanguageServer {
             None => return Ok(None),
         };
 
-        let expression = match found {
-            Located::Expression(expression) => expression,
-            Located::Statement(_) => return Ok(None),
+        let (type_, location) = match found {
+            Located::Expression(expression) => (Some(expression.type_()), expression.location()),
+            Located::Statement(statement) => (statement_type(statement), statement.location()),
         };
 
-        // Show the type of the hovered node to the user
-        let type_ = Printer::new().pretty_print(expression.type_().as_ref(), 0);
-        let contents = format!(
-            "```gleam
-{}
-```",
-            type_
-        );
+        // Show the type of the hovered node to the user, followed by the
+        // documentation of what it refers to
+        let mut contents = vec![];
+        if let Some(type_) = type_ {
+            contents.push(format!(
+                "```gleam\n{}\n```",
+                Printer::new().pretty_print(type_.as_ref(), 0)
+            ));
+        }
+        let documentation = found.definition_location().and_then(|definition| {
+            let module = match definition.module {
+                Some(name) => name.into_owned(),
+                None => uri_to_module_name(&params.text_document.uri, &self.project_root)?,
+            };
+            let (src, extra) = self.compiler.as_ref()?.module_source(&module)?;
+            gleam_core::language_server::documentation(&src, &extra, definition.span)
+        });
+        contents.extend(documentation);
+        if contents.is_empty() {
+            return Ok(None);
+        }
+
         Ok(Some(Hover {
-            contents: HoverContents::Scalar(MarkedString::String(contents)),
-            range: Some(src_span_to_lsp_range(expression.location(), &line_numbers)),
+            contents: HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value: contents.join("\n\n"),
+            }),
+            range: Some(src_span_to_lsp_range(location, &line_numbers)),
         }))
     }
 
+    fn code_action(&self, params: lsp::CodeActionParams) -> Option<lsp::CodeActionResponse> {
+        let uri = params.text_document.uri;
+        let start = params.range.start;
+        let end = params.range.end;
+        let mut actions = vec![];
+
+        if let Some(module) = self.module_for_uri(&uri) {
+            let line_numbers = LineNumbers::new(&module.code);
+            let selection = SrcSpan {
+                start: line_numbers.byte_index(start.line, start.character),
+                end: line_numbers.byte_index(end.line, end.character),
+            };
+            let found =
+                gleam_core::language_server::code_actions(&module.ast, &module.code, selection);
+            actions.extend(lsp_code_actions(
+                &uri,
+                found,
+                lsp::CodeActionKind::QUICKFIX,
+                &line_numbers,
+            ));
+            let found =
+                gleam_core::language_server::extract_actions(&module.ast, &module.code, selection);
+            actions.extend(lsp_code_actions(
+                &uri,
+                found,
+                lsp::CodeActionKind::REFACTOR_EXTRACT,
+                &line_numbers,
+            ));
+        }
+
+        // The module may have failed to compile with an error that can be
+        // fixed, such as referring to something that is not imported
+        let error_actions = self
+            .compiler
+            .as_ref()
+            .and_then(|compiler| compiler.error_actions.as_ref());
+        if let Some(error_actions) = error_actions {
+            let path = Path::new(uri.path());
+            let same_file = error_actions.path.canonicalize().ok() == path.canonicalize().ok();
+            // The edits are for the source as it was compiled
+            let unchanged = self
+                .edited
+                .get(path)
+                .map_or(true, |src| *src == error_actions.src);
+            let line_numbers = LineNumbers::new(&error_actions.src);
+            let selection = SrcSpan {
+                start: line_numbers.byte_index(start.line, start.character),
+                end: line_numbers.byte_index(end.line, end.character),
+            };
+            let location = error_actions.location;
+            if same_file
+                && unchanged
+                && selection.start <= location.end
+                && selection.end >= location.start
+            {
+                let found = error_actions.actions.clone();
+                actions.extend(lsp_code_actions(
+                    &uri,
+                    found,
+                    lsp::CodeActionKind::QUICKFIX,
+                    &line_numbers,
+                ));
+            }
+        }
+
+        Some(actions)
+    }
+
+    fn rename(&self, params: lsp::RenameParams) -> Result<Option<lsp::WorkspaceEdit>> {
+        let position = params.text_document_position;
+        let compiler = match self.compiler.as_ref() {
+            Some(compiler) => compiler,
+            None => return Ok(None),
+        };
+        let module = match self.module_for_uri(&position.text_document.uri) {
+            Some(module) => module,
+            None => return Ok(None),
+        };
+
+        // The symbols are found in the modules as they last compiled, which
+        // must be what is being edited for the edits to apply to it
+        if !self.is_compiled() {
+            return Err(Error::CannotRename {
+                name: String::new(),
+                new_name: params.new_name,
+                problem: RenameProblem::NotCompiled,
+            });
+        }
+
+        let line_numbers = LineNumbers::new(&module.code);
+        let cursor = line_numbers.byte_index(position.position.line, position.position.character);
+        let edits = gleam_core::language_server::rename(
+            &compiler.references,
+            &module.name,
+            cursor,
+            &params.new_name,
+        )?;
+
+        let mut changes = HashMap::new();
+        for (name, edits) in edits {
+            let source = match compiler.sources.get(&name) {
+                Some(source) => source,
+                None => continue,
+            };
+            let edits = edits
+                .into_iter()
+                .map(|edit| TextEdit {
+                    range: src_span_to_lsp_range(edit.location, &source.line_numbers),
+                    new_text: edit.new_text,
+                })
+                .collect();
+            let _ = changes.insert(path_to_uri(PathBuf::from(&source.path)), edits);
+        }
+        Ok(Some(lsp::WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }))
+    }
+
+    fn references(&self, params: lsp::ReferenceParams) -> Option<Vec<lsp::Location>> {
+        let position = params.text_document_position;
+        let compiler = self.compiler.as_ref()?;
+        let module = self.module_for_uri(&position.text_document.uri)?;
+        if !self.is_compiled() {
+            return None;
+        }
+        let line_numbers = LineNumbers::new(&module.code);
+        let cursor = line_numbers.byte_index(position.position.line, position.position.character);
+
+        let locations = compiler
+            .references
+            .references(&module.name, cursor)
+            .into_iter()
+            .filter(|reference| {
+                params.context.include_declaration || reference.kind == ReferenceKind::Read
+            })
+            .filter_map(|reference| {
+                let source = compiler.sources.get(&reference.module)?;
+                Some(lsp::Location {
+                    uri: path_to_uri(PathBuf::from(&source.path)),
+                    range: src_span_to_lsp_range(reference.location, &source.line_numbers),
+                })
+            })
+            .collect();
+        Some(locations)
+    }
+
+    /// The references in a module to the symbol at the cursor, with where it
+    /// is bound or defined marked as written to.
+    fn document_highlight(
+        &self,
+        params: lsp::DocumentHighlightParams,
+    ) -> Option<Vec<lsp::DocumentHighlight>> {
+        let position = params.text_document_position_params;
+        let compiler = self.compiler.as_ref()?;
+        let module = self.module_for_uri(&position.text_document.uri)?;
+        if !self.is_compiled() {
+            return None;
+        }
+        let line_numbers = LineNumbers::new(&module.code);
+        let cursor = line_numbers.byte_index(position.position.line, position.position.character);
+
+        let highlights = compiler
+            .references
+            .references(&module.name, cursor)
+            .into_iter()
+            .filter(|reference| reference.module == module.name)
+            .map(|reference| lsp::DocumentHighlight {
+                range: src_span_to_lsp_range(reference.location, &line_numbers),
+                kind: Some(match reference.kind {
+                    ReferenceKind::Read => lsp::DocumentHighlightKind::READ,
+                    ReferenceKind::Binding | ReferenceKind::Definition => {
+                        lsp::DocumentHighlightKind::WRITE
+                    }
+                }),
+            })
+            .collect();
+        Some(highlights)
+    }
+
+    fn document_symbol(
+        &self,
+        params: lsp::DocumentSymbolParams,
+    ) -> Option<lsp::DocumentSymbolResponse> {
+        let module = self.module_for_uri(&params.text_document.uri)?;
+        let line_numbers = LineNumbers::new(&module.code);
+        let symbols = gleam_core::language_server::document_symbols(&module.ast, &module.code)
+            .into_iter()
+            .map(|symbol| lsp_document_symbol(symbol, &line_numbers))
+            .collect();
+        Some(lsp::DocumentSymbolResponse::Nested(symbols))
+    }
+
+    fn inlay_hint(&self, params: lsp::InlayHintParams) -> Option<Vec<lsp::InlayHint>> {
+        let compiler = self.compiler.as_ref()?;
+        let uri = params.text_document.uri;
+        let module = self.module_for_uri(&uri)?;
+
+        // The hints are found in the module as it last compiled, so they are
+        // only shown if that is what is being edited
+        if let Some(src) = self.edited.get(Path::new(uri.path())) {
+            if src != module.code {
+                return None;
+            }
+        }
+
+        let line_numbers = LineNumbers::new(&module.code);
+        let range = SrcSpan {
+            start: line_numbers.byte_index(params.range.start.line, params.range.start.character),
+            end: line_numbers.byte_index(params.range.end.line, params.range.end.character),
+        };
+        let settings = self.settings.inlay_hints;
+        let hints = gleam_core::language_server::inlay_hints(
+            &module.ast,
+            &module.code,
+            &compiler.importable_modules,
+            range,
+        )
+        .into_iter()
+        .filter(|hint| match hint.kind {
+            InlayHintKind::BindingType | InlayHintKind::ReturnType => settings.types,
+            InlayHintKind::ArgumentLabel => settings.labels,
+        })
+        .map(|hint| {
+            let position = line_numbers.line_and_column_number(hint.position);
+            let (kind, padding_left, padding_right) = match hint.kind {
+                InlayHintKind::BindingType => (lsp::InlayHintKind::TYPE, false, false),
+                InlayHintKind::ReturnType => (lsp::InlayHintKind::TYPE, true, false),
+                InlayHintKind::ArgumentLabel => (lsp::InlayHintKind::PARAMETER, false, true),
+            };
+            lsp::InlayHint {
+                position: Position::new(position.line - 1, position.column - 1),
+                label: lsp::InlayHintLabel::String(hint.label),
+                kind: Some(kind),
+                text_edits: None,
+                tooltip: None,
+                padding_left: Some(padding_left),
+                padding_right: Some(padding_right),
+            }
+        })
+        .collect();
+        Some(hints)
+    }
+
+    fn code_lens(&self, params: lsp::CodeLensParams) -> Option<Vec<lsp::CodeLens>> {
+        let config = self.config.as_ref()?;
+        let uri = params.text_document.uri;
+        let module = self.module_for_uri(&uri)?;
+
+        // The lenses are placed using the module as it last compiled, so they
+        // are only shown if that is what is being edited
+        if let Some(src) = self.edited.get(Path::new(uri.path())) {
+            if src != module.code {
+                return None;
+            }
+        }
+
+        let line_numbers = LineNumbers::new(&module.code);
+        let lens = |location, title: &str, command: &str, arguments| lsp::CodeLens {
+            range: src_span_to_lsp_range(location, &line_numbers),
+            command: Some(lsp::Command {
+                title: title.into(),
+                command: command.into(),
+                arguments,
+            }),
+            data: None,
+        };
+        let mut lenses = vec![];
+        for found in
+            gleam_core::language_server::code_lenses(&module.ast, module.origin, &config.name)
+        {
+            match found.runnable {
+                Runnable::Main => {
+                    lenses.push(lens(found.location, "Run main", RUN_MAIN_COMMAND, None));
+                }
+                Runnable::Test(test) => {
+                    let arguments = Some(vec![serde_json::Value::String(test.name())]);
+                    let location = found.location;
+                    lenses.push(lens(
+                        location,
+                        "Run test",
+                        RUN_TEST_COMMAND,
+                        arguments.clone(),
+                    ));
+                    // Only the JavaScript runtime can wait for a debugger to
+                    // attach before running the test
+                    if config.target == Target::JavaScript {
+                        lenses.push(lens(location, "Debug test", DEBUG_TEST_COMMAND, arguments));
+                    }
+                }
+            }
+        }
+        Some(lenses)
+    }
+
+    /// Run a command of a code lens, building and running the project with
+    /// `gleam run` or `gleam test` in the background and sending what it
+    /// prints to the client as it is printed.
+    fn execute_command(
+        &self,
+        connection: &lsp_server::Connection,
+        params: lsp::ExecuteCommandParams,
+    ) -> Result<()> {
+        let test = params
+            .arguments
+            .first()
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let (arguments, environment) = match params.command.as_str() {
+            RUN_MAIN_COMMAND => (vec!["run".to_string()], vec![]),
+            RUN_TEST_COMMAND => (vec!["test".into(), "--filter".into(), test], vec![]),
+            DEBUG_TEST_COMMAND => (
+                vec!["test".into(), "--filter".into(), test],
+                vec![("NODE_OPTIONS", "--inspect-brk")],
+            ),
+            _ => return Ok(()),
+        };
+
+        let program = std::env::current_exe().map_err(|error| Error::ShellCommand {
+            program: "gleam".into(),
+            err: Some(error.kind()),
+        })?;
+        let mut child = std::process::Command::new(program)
+            .args(&arguments)
+            .envs(environment)
+            .current_dir(&self.project_root)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|error| Error::ShellCommand {
+                program: "gleam".into(),
+                err: Some(error.kind()),
+            })?;
+
+        let command = format!("gleam {}", arguments.join(" "));
+        let sender = connection.sender.clone();
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let _ = std::thread::spawn(move || {
+            let notify = move |notification| {
+                let _ = sender.send(lsp_server::Message::Notification(notification));
+            };
+
+            // Stream the output of the command a line at a time, with the
+            // lines printed to stderr read on another thread
+            let stderr = stderr.map(|stderr| {
+                let notify = notify.clone();
+                std::thread::spawn(move || log_lines(stderr, notify))
+            });
+            if let Some(stdout) = stdout {
+                log_lines(stdout, &notify);
+            }
+            let _ = stderr.map(std::thread::JoinHandle::join);
+
+            let status = child.wait().ok().and_then(|status| status.code());
+            let (typ, message) = match status {
+                Some(0) => (lsp::MessageType::INFO, format!("`{}` succeeded", command)),
+                Some(code) => (
+                    lsp::MessageType::ERROR,
+                    format!("`{}` failed with status {}", command, code),
+                ),
+                None => (
+                    lsp::MessageType::ERROR,
+                    format!("`{}` was stopped", command),
+                ),
+            };
+            notify(lsp_server::Notification {
+                method: "window/showMessage".into(),
+                params: serde_json::to_value(lsp::ShowMessageParams { typ, message })
+                    .expect("ShowMessageParams json"),
+            });
+        });
+        Ok(())
+    }
+
+    fn signature_help(&self, params: lsp::SignatureHelpParams) -> Option<lsp::SignatureHelp> {
+        let compiler = self.compiler.as_ref()?;
+        let params = params.text_document_position_params;
+        let uri = params.text_document.uri;
+        let module = self.module_for_uri(&uri)?;
+
+        // The call is found in the code as it is being written, which is
+        // likely not to compile
+        let src = self
+            .edited
+            .get(Path::new(uri.path()))
+            .unwrap_or_else(|| module.code.clone());
+        let line_numbers = LineNumbers::new(&src);
+        let cursor = line_numbers.byte_index(params.position.line, params.position.character);
+        let help = gleam_core::language_server::signature_help(
+            &module.ast,
+            &src,
+            cursor,
+            &compiler.importable_modules,
+        )?;
+
+        let source = compiler.module_source(&help.module);
+        let documentation = |docs: Option<String>| {
+            docs.map(|value| {
+                lsp::Documentation::MarkupContent(lsp::MarkupContent {
+                    kind: lsp::MarkupKind::Markdown,
+                    value,
+                })
+            })
+        };
+        let mut parameter_docs = match &source {
+            Some((src, _)) => {
+                gleam_core::language_server::parameter_documentation(src, help.location)
+            }
+            None => vec![],
+        }
+        .into_iter();
+        let parameters = help
+            .parameters
+            .iter()
+            .map(|span| lsp::ParameterInformation {
+                label: lsp::ParameterLabel::LabelOffsets([span.start, span.end]),
+                documentation: documentation(parameter_docs.next().flatten()),
+            })
+            .collect();
+        let docs = source.and_then(|(src, extra)| {
+            gleam_core::language_server::documentation(&src, &extra, help.location)
+        });
+        let active_parameter = help.active_parameter.map(|index| index as u32);
+
+        Some(lsp::SignatureHelp {
+            signatures: vec![lsp::SignatureInformation {
+                label: help.label,
+                documentation: documentation(docs),
+                parameters: Some(parameters),
+                active_parameter,
+            }],
+            active_signature: Some(0),
+            active_parameter,
+        })
+    }
+
+    fn semantic_tokens(&self, params: lsp::SemanticTokensParams) -> Option<lsp::SemanticTokens> {
+        let compiler = self.compiler.as_ref()?;
+        let uri = params.text_document.uri;
+        let module = self.module_for_uri(&uri)?;
+
+        // The tokens are found in the module as it last compiled, so they are
+        // only given if that is what is being edited
+        if let Some(src) = self.edited.get(Path::new(uri.path())) {
+            if src != module.code {
+                return None;
+            }
+        }
+
+        let line_numbers = LineNumbers::new(&module.code);
+        let tokens = gleam_core::language_server::semantic_tokens(
+            &module.ast,
+            &module.code,
+            &compiler.importable_modules,
+        );
+
+        // Each token is positioned relative to the one before it
+        let mut previous = Position::new(0, 0);
+        let data = tokens
+            .into_iter()
+            .map(|token| {
+                let position = line_numbers.line_and_column_number(token.location.start);
+                let position = Position::new(position.line - 1, position.column - 1);
+                let delta_start = if position.line == previous.line {
+                    position.character - previous.character
+                } else {
+                    position.character
+                };
+                let (token_type, modifiers) = match token.kind {
+                    SemanticTokenKind::Module => (lsp::SemanticTokenType::NAMESPACE, 0),
+                    SemanticTokenKind::Type => (lsp::SemanticTokenType::TYPE, 0),
+                    SemanticTokenKind::Constructor => (lsp::SemanticTokenType::ENUM_MEMBER, 0),
+                    SemanticTokenKind::Function => (lsp::SemanticTokenType::FUNCTION, 0),
+                    SemanticTokenKind::Constant => (lsp::SemanticTokenType::VARIABLE, 1),
+                    SemanticTokenKind::Parameter => (lsp::SemanticTokenType::PARAMETER, 0),
+                    SemanticTokenKind::Variable => (lsp::SemanticTokenType::VARIABLE, 0),
+                    SemanticTokenKind::Label => (lsp::SemanticTokenType::PROPERTY, 0),
+                };
+                let token = lsp::SemanticToken {
+                    delta_line: position.line - previous.line,
+                    delta_start,
+                    length: token.location.end - token.location.start,
+                    token_type: SEMANTIC_TOKEN_TYPES
+                        .iter()
+                        .position(|type_| *type_ == token_type)
+                        .unwrap_or_default() as u32,
+                    token_modifiers_bitset: modifiers,
+                };
+                previous = position;
+                token
+            })
+            .collect();
+        Some(lsp::SemanticTokens {
+            result_id: None,
+            data,
+        })
+    }
+
+    fn workspace_symbol(
+        &self,
+        params: lsp::WorkspaceSymbolParams,
+    ) -> Option<Vec<lsp::SymbolInformation>> {
+        let compiler = self.compiler.as_ref()?;
+        let modules = compiler
+            .modules
+            .values()
+            .map(|module| (&module.ast, module.code.as_str()))
+            .collect_vec();
+        let symbols = gleam_core::language_server::workspace_symbols(&modules, &params.query)
+            .into_iter()
+            .filter_map(|symbol| {
+                let source = compiler.sources.get(&symbol.module)?;
+                #[allow(deprecated)]
+                Some(lsp::SymbolInformation {
+                    name: symbol.name,
+                    kind: lsp_symbol_kind(symbol.kind),
+                    tags: None,
+                    deprecated: None,
+                    location: lsp::Location {
+                        uri: path_to_uri(PathBuf::from(&source.path)),
+                        range: src_span_to_lsp_range(symbol.location, &source.line_numbers),
+                    },
+                    container_name: Some(match symbol.container {
+                        Some(custom_type) => format!("{}.{}", symbol.module, custom_type),
+                        None => symbol.module,
+                    }),
+                })
+            })
+            .collect();
+        Some(symbols)
+    }
+
+    /// Whether the files being edited are as they were when the project last
+    /// compiled, so the locations found in the compiled modules are where they
+    /// are in the files.
+    fn is_compiled(&self) -> bool {
+        let compiler = match self.compiler.as_ref() {
+            Some(compiler) => compiler,
+            None => return false,
+        };
+        compiler.modules.values().all(|module| {
+            compiler
+                .sources
+                .get(&module.name)
+                .and_then(|source| self.edited.get(Path::new(&source.path)))
+                .map_or(true, |src| src == module.code)
+        })
+    }
+
     fn node_at_position(
         &self,
         params: &lsp::TextDocumentPositionParams,
@@ -735,22 +1607,100 @@ impl LanguageServer {
     fn format(&self, params: lsp::DocumentFormattingParams) -> Result<Vec<TextEdit>> {
         let path = params.text_document.uri.path();
         let mut new_text = String::new();
+        let options = self
+            .config
+            .as_ref()
+            .map(PackageConfig::format_options)
+            .unwrap_or_default();
 
-        match self.edited.get(path) {
+        match self.edited.get(Path::new(path)) {
             // If we have a cached version of the file in memory format that
             Some(src) => {
-                gleam_core::format::pretty(&mut new_text, src, Path::new(path))?;
+                gleam_core::format::pretty(&mut new_text, &src, Path::new(path), &options)?;
             }
 
             // Otherwise format the file from disc
             None => {
                 let src = crate::fs::read(path)?;
-                gleam_core::format::pretty(&mut new_text, &src, Path::new(path))?;
+                gleam_core::format::pretty(&mut new_text, &src, Path::new(path), &options)?;
             }
         };
 
         Ok(vec![text_edit_replace(new_text)])
     }
+
+    fn format_range(&self, params: lsp::DocumentRangeFormattingParams) -> Result<Vec<TextEdit>> {
+        let path = params.text_document.uri.path();
+        let options = self
+            .config
+            .as_ref()
+            .map(PackageConfig::format_options)
+            .unwrap_or_default();
+        let src = match self.edited.get(Path::new(path)) {
+            Some(src) => src,
+            None => crate::fs::read(path)?,
+        };
+
+        let line_numbers = LineNumbers::new(&src);
+        let start = params.range.start;
+        let end = params.range.end;
+        let range = SrcSpan {
+            start: line_numbers.byte_index(start.line, start.character),
+            end: line_numbers.byte_index(end.line, end.character),
+        };
+        let edit = gleam_core::format::pretty_range(&src, Path::new(path), range, &options)?;
+        Ok(edit
+            .into_iter()
+            .map(|edit| TextEdit {
+                range: src_span_to_lsp_range(edit.location, &line_numbers),
+                new_text: edit.new_text,
+            })
+            .collect())
+    }
+
+    fn format_on_type(&self, params: lsp::DocumentOnTypeFormattingParams) -> Result<Vec<TextEdit>> {
+        let uri = params.text_document_position.text_document.uri;
+        let path = uri.path();
+        let options = self
+            .config
+            .as_ref()
+            .map(PackageConfig::format_options)
+            .unwrap_or_default();
+        let src = match self.edited.get(Path::new(path)) {
+            Some(src) => src,
+            None => crate::fs::read(path)?,
+        };
+        let typed = match params.ch.chars().next() {
+            Some(typed) => typed,
+            None => return Ok(vec![]),
+        };
+
+        let line_numbers = LineNumbers::new(&src);
+        let position = params.text_document_position.position;
+        let position = line_numbers.byte_index(position.line, position.character);
+        let edits = gleam_core::format::on_type(&src, Path::new(path), position, typed, &options);
+        Ok(edits
+            .into_iter()
+            .map(|edit| TextEdit {
+                range: src_span_to_lsp_range(edit.location, &line_numbers),
+                new_text: edit.new_text,
+            })
+            .collect())
+    }
+}
+
+/// A notification for the client to log each line read.
+fn log_lines(reader: impl std::io::Read, notify: impl Fn(lsp_server::Notification)) {
+    for line in std::io::BufRead::lines(std::io::BufReader::new(reader)).map_while(Result::ok) {
+        let params = lsp::LogMessageParams {
+            typ: lsp::MessageType::LOG,
+            message: line,
+        };
+        notify(lsp_server::Notification {
+            method: "window/logMessage".into(),
+            params: serde_json::to_value(params).expect("LogMessageParams json"),
+        });
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -918,10 +1868,54 @@ enum LspDisplayable {
     Message(LspMessage),
 }
 
+/// Structured data about an error for use by the client: a suggested
+/// replacement for an unknown name, or the parts of two types that differ.
+fn diagnostic_data(error: &Error) -> Option<serde_json::Value> {
+    let mut data = serde_json::Map::new();
+    if let Some(name) = error.suggestion() {
+        let _ = data.insert("suggestion".into(), name.into());
+    }
+    if let Some(diff) = error.type_diff() {
+        let diff = serde_json::to_value(diff).expect("type diff to json");
+        let _ = data.insert("type_diff".into(), diff);
+    }
+    if data.is_empty() {
+        None
+    } else {
+        Some(serde_json::Value::Object(data))
+    }
+}
+
+/// Structured data about a warning for use by the client: the edits that fix
+/// a lint, if it has a fix.
+fn warning_data(warning: &Warning) -> Option<serde_json::Value> {
+    let (src, fix) = match warning {
+        Warning::Lint {
+            src,
+            lint: Lint { fix: Some(fix), .. },
+            ..
+        } => (src, fix),
+        _ => return None,
+    };
+    let line_numbers = LineNumbers::new(src);
+    let edits: Vec<_> = fix
+        .edits
+        .iter()
+        .map(|edit| TextEdit {
+            range: src_span_to_lsp_range(edit.location, &line_numbers),
+            new_text: edit.new_text.clone(),
+        })
+        .collect();
+    Some(serde_json::json!({
+        "fix": { "title": fix.title, "edits": edits }
+    }))
+}
+
 fn diagnostic_to_lsp(diagnostic: gleam_core::diagnostic::Diagnostic) -> LspDisplayable {
     let severity = match diagnostic.level {
         Level::Error => lsp::DiagnosticSeverity::ERROR,
         Level::Warning => lsp::DiagnosticSeverity::WARNING,
+        Level::Info => lsp::DiagnosticSeverity::INFORMATION,
     };
     let mut text = diagnostic.title;
 
@@ -973,6 +1967,10 @@ fn path_to_uri(path: PathBuf) -> Url {
     Url::parse(&file).expect("path_to_uri URL parse")
 }
 
+/// File system IO that reads the files being edited from memory, so that the
+/// project is compiled with the changes the user has not yet saved.
+pub type EditedFilesIO = OverlayFileSystem<ProjectIO>;
+
 /// A wrapper around the project compiler which makes it possible to repeatedly
 /// recompile the top level package, reusing the information about the already
 /// compiled dependency packages.
@@ -987,6 +1985,14 @@ pub struct LspProjectCompiler<IO> {
     // Information on compiled modules
     modules: HashMap<String, Module>,
     sources: HashMap<String, ModuleSourceInformation>,
+    /// The interfaces of all the modules the root package could import when
+    /// it was last compiled, including those of the local packages which are
+    /// discarded when the state of the compiler is restored.
+    importable_modules: im::HashMap<String, type_::Module>,
+    /// Where the symbols of the compiled modules are referred to.
+    references: ReferenceIndex,
+    /// Fixes for the error of a module that failed to compile.
+    error_actions: Option<ErrorActions>,
 
     /// A lock to ensure the LSP and the CLI don't try and use build directory
     /// at the same time.
@@ -1006,17 +2012,29 @@ where
             mode: build::Mode::Dev,
             target: None,
             perform_codegen: false,
+            warn_dead_code: false,
+            warning_levels: Default::default(),
         };
+        let local_packages = crate::config::local_packages(&config, options.mode)?;
         let mut project_compiler =
             ProjectCompiler::new(config, options, manifest.packages, Box::new(telemetry), io);
+        project_compiler.local_packages = local_packages;
         // To avoid the Erlang compiler printing to stdout (and thus
         // violating LSP which is currently using stdout) we silence it.
         project_compiler.subprocess_stdio = Stdio::Null;
+        project_compiler.global_build_cache = Some(paths::global_build_cache());
+        // Each build reuses the modules of the previous one that have not
+        // changed, so that only the modules being edited and those that
+        // depend upon them are type checked again
+        project_compiler.enable_incremental_compilation();
 
         Ok(Self {
             project_compiler,
             modules: HashMap::new(),
             sources: HashMap::new(),
+            importable_modules: im::HashMap::new(),
+            references: ReferenceIndex::default(),
+            error_actions: None,
             build_lock: BuildLock::new()?,
             dependencies_compiled: false,
         })
@@ -1031,6 +2049,7 @@ where
             self.project_compiler.compile_dependencies()?;
             self.dependencies_compiled = true;
         }
+        self.project_compiler.clear_changed_modules();
 
         // Save the state prior to compilation of the root package
         let checkpoint = self.project_compiler.checkpoint();
@@ -1038,7 +2057,15 @@ where
         // Do that there compilation. We don't use `?` to return early in the
         // event of an error because we _always_ want to do the restoration of
         // state afterwards.
-        let result = self.project_compiler.compile_root_package();
+        let result = self
+            .project_compiler
+            .compile_local_packages()
+            .and_then(|()| self.project_compiler.compile_root_package());
+
+        self.importable_modules = self.project_compiler.get_importable_modules().clone();
+        self.error_actions = result.as_ref().err().and_then(|error| {
+            gleam_core::language_server::error_actions(error, &self.importable_modules)
+        });
 
         // Restore the state so that later we can compile the root again
         self.project_compiler.restore(checkpoint);
@@ -1056,8 +2083,171 @@ where
             let _ = self.modules.insert(module.name.clone(), module);
         }
 
+        let modules = self
+            .modules
+            .values()
+            .map(|module| (&module.ast, module.code.as_str()))
+            .collect_vec();
+        self.references = ReferenceIndex::new(&modules);
+
         Ok(())
     }
+
+    /// The source of a module from a package other than the root package,
+    /// such as a dependency, the source code of which is found in the build
+    /// directory, or a member of the workspace.
+    fn dependency_module_source(&self, name: &str) -> Option<ModuleSourceInformation> {
+        let path = self.dependency_module_path(name)?;
+        let code = crate::fs::read(&path).ok()?;
+        Some(ModuleSourceInformation {
+            path: path.as_os_str().to_string_lossy().to_string(),
+            line_numbers: LineNumbers::new(&code),
+        })
+    }
+
+    /// The source code of a module of any package, along with the comments
+    /// and other details of it that are not in its syntax tree.
+    fn module_source(&self, name: &str) -> Option<(String, ModuleExtra)> {
+        if let Some(module) = self.modules.get(name) {
+            return Some((module.code.clone(), module.extra.clone()));
+        }
+        let code = crate::fs::read(self.dependency_module_path(name)?).ok()?;
+        let arena = gleam_core::ast::Arena::new();
+        let (_, extra) = gleam_core::parse::parse_module(&code, &arena).ok()?;
+        Some((code, extra))
+    }
+
+    /// The path of the source file of a module from a package other than the
+    /// root package.
+    fn dependency_module_path(&self, name: &str) -> Option<PathBuf> {
+        let module = self.importable_modules.get(name)?;
+        let root = match self
+            .project_compiler
+            .local_packages
+            .iter()
+            .find(|local| local.config.name == module.package)
+        {
+            Some(local) => local.root.clone(),
+            None => paths::build_deps_package(&module.package),
+        };
+        let config = crate::config::read(root.join("gleam.toml")).ok();
+        let file = format!("{}.gleam", name);
+        let path = std::iter::once(root.join("src"))
+            .chain(
+                config
+                    .iter()
+                    .flat_map(|config| config.build.source_directories())
+                    .map(|directory| root.join(directory)),
+            )
+            .map(|directory| directory.join(&file))
+            .find(|path| path.is_file())?;
+        path.canonicalize().ok()
+    }
+}
+
+fn completion_item_kind(kind: CompletionKind) -> lsp::CompletionItemKind {
+    match kind {
+        CompletionKind::Module => lsp::CompletionItemKind::MODULE,
+        CompletionKind::Function => lsp::CompletionItemKind::FUNCTION,
+        CompletionKind::Constructor => lsp::CompletionItemKind::CONSTRUCTOR,
+        CompletionKind::Constant => lsp::CompletionItemKind::CONSTANT,
+        CompletionKind::Variable => lsp::CompletionItemKind::VARIABLE,
+        CompletionKind::Field => lsp::CompletionItemKind::FIELD,
+        CompletionKind::Label => lsp::CompletionItemKind::PROPERTY,
+        CompletionKind::Type => lsp::CompletionItemKind::CLASS,
+    }
+}
+
+/// The type of the value a statement defines, if it defines one.
+fn statement_type(statement: &TypedStatement) -> Option<Arc<Type>> {
+    match statement {
+        Statement::Fn {
+            arguments,
+            return_type,
+            ..
+        } => Some(type_::fn_(
+            arguments
+                .iter()
+                .map(|argument| argument.type_.clone())
+                .collect(),
+            return_type.clone(),
+        )),
+        Statement::ExternalFn {
+            arguments,
+            return_type,
+            ..
+        } => Some(type_::fn_(
+            arguments
+                .iter()
+                .map(|argument| argument.type_.clone())
+                .collect(),
+            return_type.clone(),
+        )),
+        Statement::ModuleConstant { type_, .. } => Some(type_.clone()),
+        Statement::TypeAlias { .. }
+        | Statement::CustomType { .. }
+        | Statement::ExternalType { .. }
+        | Statement::Import { .. } => None,
+    }
+}
+
+#[allow(deprecated)]
+fn lsp_document_symbol(symbol: DocumentSymbol, line_numbers: &LineNumbers) -> lsp::DocumentSymbol {
+    let children = symbol
+        .children
+        .into_iter()
+        .map(|child| lsp_document_symbol(child, line_numbers))
+        .collect_vec();
+    lsp::DocumentSymbol {
+        name: symbol.name,
+        detail: symbol.detail,
+        kind: lsp_symbol_kind(symbol.kind),
+        tags: None,
+        deprecated: None,
+        range: src_span_to_lsp_range(symbol.location, line_numbers),
+        selection_range: src_span_to_lsp_range(symbol.name_location, line_numbers),
+        children: (!children.is_empty()).then_some(children),
+    }
+}
+
+fn lsp_symbol_kind(kind: SymbolKind) -> lsp::SymbolKind {
+    match kind {
+        SymbolKind::Function => lsp::SymbolKind::FUNCTION,
+        SymbolKind::Constant => lsp::SymbolKind::CONSTANT,
+        SymbolKind::Type => lsp::SymbolKind::CLASS,
+        SymbolKind::Constructor => lsp::SymbolKind::CONSTRUCTOR,
+        SymbolKind::Field => lsp::SymbolKind::FIELD,
+    }
+}
+
+fn lsp_code_actions(
+    uri: &Url,
+    actions: Vec<CodeAction>,
+    kind: lsp::CodeActionKind,
+    line_numbers: &LineNumbers,
+) -> Vec<lsp::CodeActionOrCommand> {
+    actions
+        .into_iter()
+        .map(|action| {
+            let edits = action
+                .edits
+                .into_iter()
+                .map(|edit| TextEdit {
+                    range: src_span_to_lsp_range(edit.location, line_numbers),
+                    new_text: edit.new_text,
+                })
+                .collect();
+            lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                title: action.title,
+                kind: Some(kind.clone()),
+                edit: Some(lsp::WorkspaceEdit {
+                    changes: Some(HashMap::from([(uri.clone(), edits)])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        })
+        .collect()
 }
 
 fn src_span_to_lsp_range(location: SrcSpan, line_numbers: &LineNumbers) -> Range {
@@ -1075,3 +2265,23 @@ fn src_span_to_lsp_range(location: SrcSpan, line_numbers: &LineNumbers) -> Range
         },
     }
 }
+
+#[test]
+fn edited_files_io_reads_edited_files_from_memory() {
+    use gleam_core::io::FileSystemReader;
+
+    let edited = Overlay::default();
+    let io = EditedFilesIO::new(ProjectIO::new(), edited.clone());
+    let root = io.current_dir().expect("current dir");
+    let path = Path::new("src/not_on_disc.gleam");
+
+    assert!(io.read(path).is_err());
+    edited.insert(root.join(path), "pub fn main() { 1 }".into());
+    assert_eq!(io.read(path).expect("relative read"), "pub fn main() { 1 }");
+    assert_eq!(
+        io.read(&root.join(path)).expect("absolute read"),
+        "pub fn main() { 1 }"
+    );
+    edited.remove(root.join(path));
+    assert!(io.read(path).is_err());
+}
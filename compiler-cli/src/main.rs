@@ -51,25 +51,37 @@
 extern crate pretty_assertions;
 
 mod add;
+mod api;
 mod build;
 mod build_lock;
+mod bundle;
+mod clean;
 mod cli;
 mod compile_package;
+mod completions;
 mod config;
+mod credentials;
 mod dependencies;
 mod docs;
 mod export;
+mod fix;
 mod format;
 mod fs;
+mod git;
 mod hex;
 mod http;
+mod logging;
 mod lsp;
 mod new;
+mod outdated;
 mod panic;
 mod publish;
+mod remove;
 mod run;
+mod script;
 mod shell;
 mod telemetry;
+mod watch;
 
 use config::root_config;
 use dependencies::UseManifest;
@@ -78,35 +90,136 @@ pub use gleam_core::{
     warning::Warning,
 };
 
+use completions::CompletionShell;
+use docs::DocsFormat;
+use export::{BuildFilesFormat, DependencyGraphFormat, NativePackageManager};
 use gleam_core::{
     build::{Mode, Options, Target},
+    config::{SpdxLicense, WarningLevel, WarningLevels},
+    diagnostic::MessageFormat,
     hex::RetirementReason,
+    licences::LicenceFormat,
+    type_::WarningKind,
 };
 use hex::ApiKeyCommand as _;
+use logging::LogFormat;
+use outdated::OutdatedFormat;
 
 use std::path::PathBuf;
 
-use clap::{Args, Parser, Subcommand};
+use clap::{CommandFactory, Parser};
 use strum::VariantNames;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[derive(Parser, Debug)]
 #[clap(version)]
+struct Cli {
+    /// Forbid network access, using only the locked versions of
+    /// manifest.toml and the packages of the vendor directory
+    #[clap(long, global = true)]
+    offline: bool,
+
+    /// Print the time taken by each phase of the build
+    #[clap(long, global = true)]
+    timings: bool,
+
+    /// Write the time taken by each phase of the build to a file in the
+    /// Chrome trace format
+    #[clap(long, global = true, value_name = "PATH")]
+    timings_trace: Option<PathBuf>,
+
+    /// Log what the build tool is doing to stderr. Given twice, each module
+    /// compiled and each command run is logged too. `GLEAM_LOG` overrides it
+    #[clap(short, long, global = true, parse(from_occurrences))]
+    verbose: u8,
+
+    /// The format to log in
+    #[clap(
+        long,
+        global = true,
+        possible_values = LogFormat::VARIANTS,
+        ignore_case = true,
+        default_value = "text"
+    )]
+    log_format: LogFormat,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Subcommand, Debug)]
 enum Command {
     /// Build the project
     Build {
-        /// Emit compile time warnings as errors
+        #[clap(flatten)]
+        warnings: WarningFlags,
+
+        /// Warn about code that cannot be reached from `main` or the tests
         #[clap(long)]
-        warnings_as_errors: bool,
+        warn_dead_code: bool,
 
-        /// The platform to target
+        /// The platform to target, or `all` to build for every supported
+        /// target. Experimental targets are only built when given by name.
+        /// When not given the `targets` of gleam.toml are built, if any
+        #[clap(long, ignore_case = true)]
+        target: Option<BuildTargets>,
+
+        /// Run the `[javascript.bundle]` command from gleam.toml once compiled
+        #[clap(long)]
+        bundle: bool,
+
+        /// Build again whenever a source file changes
+        #[clap(long)]
+        watch: bool,
+
+        /// How to print warnings and errors: rendered for people to read, as
+        /// one JSON object per line, or as a SARIF log
+        #[clap(
+            long,
+            possible_values = MessageFormat::VARIANTS,
+            ignore_case = true,
+            default_value = "human",
+            conflicts_with = "watch"
+        )]
+        message_format: MessageFormat,
+    },
+
+    /// Type check the project without generating any code
+    Check {
+        /// The platform to type check for
         #[clap(long, ignore_case = true)]
         target: Option<Target>,
+
+        #[clap(flatten)]
+        warnings: WarningFlags,
+
+        /// Warn about code that cannot be reached from `main` or the tests
+        #[clap(long)]
+        warn_dead_code: bool,
+
+        /// How to print warnings and errors: rendered for people to read, as
+        /// one JSON object per line, or as a SARIF log
+        #[clap(
+            long,
+            possible_values = MessageFormat::VARIANTS,
+            ignore_case = true,
+            default_value = "human"
+        )]
+        message_format: MessageFormat,
     },
 
-    /// Type check the project
-    Check,
+    /// Type check the project and print the inferred signatures of the
+    /// functions and constants defined in each module
+    Types {
+        /// Also print the signatures of private functions and constants
+        #[clap(long)]
+        private: bool,
+    },
+
+    /// Type check the project and print the `todo` expressions that remain,
+    /// along with the type of value each one must produce
+    Todos,
 
     /// Publish the project to the Hex package manager
     ///
@@ -120,18 +233,45 @@ enum Command {
         replace: bool,
         #[clap(short, long)]
         yes: bool,
+        /// List the files that would be published without publishing them
+        #[clap(long)]
+        dry_run: bool,
+        /// The Hex organization to publish the package to, as a private
+        /// package
+        #[clap(long)]
+        organization: Option<String>,
     },
 
     /// Render HTML documentation
     #[clap(subcommand)]
     Docs(Docs),
 
+    /// Work with the public API of the package
+    #[clap(subcommand)]
+    Api(Api),
+
     /// Work with dependency packages
     #[clap(subcommand)]
     Deps(Dependencies),
 
     /// Update dependency packages to their latest versions
-    Update,
+    Update {
+        /// The names of the packages to update, keeping the other packages at
+        /// their locked versions. All packages are updated if none are given
+        packages: Vec<String>,
+    },
+
+    /// List the dependency packages that have newer releases on Hex
+    Outdated {
+        /// The format to print the packages in
+        #[clap(
+            long,
+            possible_values = OutdatedFormat::VARIANTS,
+            ignore_case = true,
+            default_value = "text"
+        )]
+        format: OutdatedFormat,
+    },
 
     /// Work with the Hex package manager
     #[clap(subcommand)]
@@ -150,21 +290,81 @@ enum Command {
         #[clap(long)]
         stdin: bool,
 
+        /// Read source from STDIN as if it were the file at this path, which
+        /// is used in error messages and to find the project's gleam.toml
+        #[clap(
+            long,
+            value_name = "PATH",
+            conflicts_with = "files",
+            conflicts_with = "stdin"
+        )]
+        stdin_path: Option<PathBuf>,
+
         /// Check if inputs are formatted without changing them
         #[clap(long)]
         check: bool,
+
+        /// The width in columns to format code to. Defaults to the
+        /// `line_length` of gleam.toml, or 80
+        #[clap(long)]
+        line_length: Option<usize>,
+
+        /// Sort and group imports, as with `sort_imports` in gleam.toml
+        #[clap(long)]
+        sort_imports: bool,
+
+        /// Only format the definitions touching this range of lines, given
+        /// as START:END, leaving the rest of each file unchanged
+        #[clap(long)]
+        range: Option<format::LineRange>,
+
+        /// Check that the formatted code means the same as the original and
+        /// is unchanged by formatting it again, failing rather than writing
+        /// it if not
+        #[clap(long)]
+        verify: bool,
     },
 
-    /// Start an Erlang shell
-    Shell,
+    /// Fix warnings and update outdated syntax in the project's code.
+    ///
+    /// Unused imports are removed, deprecated values are replaced when their
+    /// deprecation names a replacement, and `try` is rewritten to use `use`.
+    Fix,
 
-    /// Run the project
+    /// Start an interactive Gleam shell
+    Shell {
+        /// The platform to target
+        #[clap(long, ignore_case = true)]
+        target: Option<Target>,
+
+        /// Start a plain Erlang shell with the modules of the project loaded
+        /// instead
+        #[clap(long, conflicts_with = "target")]
+        erlang: bool,
+    },
+
+    /// Run the project, or run a single Gleam file given outside of a project
+    /// as a script
     #[clap(trailing_var_arg = true)]
     Run {
         /// The platform to target
         #[clap(long, ignore_case = true)]
         target: Option<Target>,
 
+        /// Bundle the project and serve it with the `[javascript.bundle]`
+        /// serve command from gleam.toml
+        #[clap(long)]
+        bundle: bool,
+
+        /// Set an environment variable for the program, given as
+        /// `KEY=VALUE`. May be given more than once
+        #[clap(
+            long = "env",
+            value_name = "KEY=VALUE",
+            parse(try_from_str = run::parse_environment_variable)
+        )]
+        environment: Vec<(String, String)>,
+
         arguments: Vec<String>,
     },
 
@@ -175,6 +375,33 @@ enum Command {
         #[clap(long, ignore_case = true)]
         target: Option<Target>,
 
+        /// Build and run the tests again whenever a source file changes
+        #[clap(long)]
+        watch: bool,
+
+        /// Only run the test functions whose module and name, such as
+        /// `my_app_test.add_test`, contain this text
+        #[clap(long)]
+        filter: Option<String>,
+
+        /// Run this many test functions at once
+        #[clap(long)]
+        parallel: Option<usize>,
+
+        /// Run the Gleam code examples in the documentation of the package
+        /// rather than its tests
+        #[clap(long, conflicts_with = "watch")]
+        doc: bool,
+
+        /// Set an environment variable for the tests, given as `KEY=VALUE`.
+        /// May be given more than once
+        #[clap(
+            long = "env",
+            value_name = "KEY=VALUE",
+            parse(try_from_str = run::parse_environment_variable)
+        )]
+        environment: Vec<(String, String)>,
+
         arguments: Vec<String>,
     },
 
@@ -197,8 +424,31 @@ enum Command {
         dev: bool,
     },
 
-    /// Clean build artifacts
-    Clean,
+    /// Remove project dependencies
+    Remove {
+        /// The names of the packages to remove
+        #[clap(required = true)]
+        packages: Vec<String>,
+    },
+
+    /// Clean build artifacts, printing how much disk each part of the build
+    /// directory occupies
+    ///
+    /// With no flags the whole build directory is removed. Each flag given
+    /// narrows what is removed.
+    Clean {
+        /// Only remove what was compiled for this target
+        #[clap(long, ignore_case = true)]
+        target: Option<Target>,
+
+        /// Only remove the compiled code of this package
+        #[clap(long, value_name = "NAME")]
+        package: Option<String>,
+
+        /// Only remove the dependency packages, both compiled and downloaded
+        #[clap(long, conflicts_with = "package")]
+        deps: bool,
+    },
 
     /// Run the language server, to be used by editors
     #[clap(name = "lsp", hide = true)]
@@ -207,15 +457,80 @@ enum Command {
     /// Export something useful from the Gleam project
     #[clap(subcommand)]
     Export(ExportTarget),
+
+    /// Print a script that completes the commands and flags of gleam in a
+    /// shell
+    Completions {
+        /// The shell to print the completion script for
+        #[clap(possible_values = CompletionShell::VARIANTS, ignore_case = true)]
+        shell: CompletionShell,
+    },
 }
 
-#[derive(Subcommand, Debug, Clone, Copy)]
+#[derive(clap::Subcommand, Debug, Clone)]
 pub enum ExportTarget {
     /// Precompiled Erlang, suitable for deployment.
     ErlangShipment,
+    /// A self-executing escript of the project and its dependencies,
+    /// suitable for distributing command line programs.
+    Escript,
+    /// Precompiled JavaScript with a package.json, suitable for deployment.
+    JavascriptBundle,
+    /// Precompiled JavaScript and TypeScript declarations with a
+    /// package.json, suitable for publishing to npm.
+    NpmPackage {
+        /// Publish the package to npm with `npm publish` once it has been
+        /// generated
+        #[clap(long)]
+        publish: bool,
+    },
+    /// A static and a shared library of the project and its dependencies,
+    /// along with their headers and a pkg-config file, for use from C and
+    /// C++.
+    NativeLib {
+        /// Also write the files to install the library with a C++ package
+        /// manager
+        #[clap(long, possible_values = NativePackageManager::VARIANTS, ignore_case = true)]
+        package_manager: Option<NativePackageManager>,
+    },
+    /// A WebAssembly component exporting the public functions of the
+    /// project, along with the WIT package describing them.
+    WasmComponent {
+        /// The module adapting the WASI preview 1 functions the component
+        /// imports to WASI preview 2, such as the
+        /// wasi_snapshot_preview1.reactor.wasm released with Wasmtime
+        #[clap(long)]
+        adapter: Option<PathBuf>,
+    },
+    /// A JSON description of the public interface of each module, for use by
+    /// other tools.
+    Interfaces,
+    /// The graph of the imports between the modules of the project, printed
+    /// to stdout.
+    DepGraph {
+        /// The format to print the graph in
+        #[clap(
+            long,
+            possible_values = DependencyGraphFormat::VARIANTS,
+            ignore_case = true,
+            default_value = "dot"
+        )]
+        format: DependencyGraphFormat,
+
+        /// Include the packages each module imports modules from
+        #[clap(long)]
+        packages: bool,
+    },
+    /// Bazel or Buck2 build files describing the compilation of each package
+    /// and module of the project for the Erlang and native targets.
+    BuildFiles {
+        /// The build system to write the build files for
+        #[clap(long, possible_values = BuildFilesFormat::VARIANTS, ignore_case = true)]
+        format: BuildFilesFormat,
+    },
 }
 
-#[derive(Args, Debug, Clone)]
+#[derive(clap::Args, Debug, Clone)]
 pub struct NewOptions {
     /// Location of the project root
     pub project_root: String,
@@ -237,7 +552,59 @@ pub struct NewOptions {
     pub template: new::Template,
 }
 
-#[derive(Args, Debug)]
+/// How warnings are treated, taking precedence over the `[warnings]` section
+/// of gleam.toml.
+#[derive(clap::Args, Debug)]
+pub struct WarningFlags {
+    /// Emit compile time warnings as errors
+    #[clap(long)]
+    warnings_as_errors: bool,
+
+    /// Fail to compile if there are warnings of this kind, such as
+    /// `unused_imported_module`
+    #[clap(long, value_name = "KIND")]
+    deny: Vec<WarningKind>,
+
+    /// Do not show warnings of this kind, such as `todo`
+    #[clap(long, value_name = "KIND")]
+    allow: Vec<WarningKind>,
+}
+
+impl WarningFlags {
+    fn levels(&self) -> WarningLevels {
+        let default = if self.warnings_as_errors {
+            WarningLevel::Deny
+        } else {
+            WarningLevel::Warn
+        };
+        let denied = self.deny.iter().map(|kind| (*kind, WarningLevel::Deny));
+        let allowed = self.allow.iter().map(|kind| (*kind, WarningLevel::Allow));
+        WarningLevels {
+            default,
+            levels: denied.chain(allowed).collect(),
+        }
+    }
+}
+
+/// The targets that `gleam build` compiles for: one of them, or all.
+#[derive(Debug, Clone, Copy)]
+pub enum BuildTargets {
+    One(Target),
+    All,
+}
+
+impl std::str::FromStr for BuildTargets {
+    type Err = strum::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            _ => s.parse().map(Self::One),
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
 pub struct CompilePackage {
     /// The compilation target for the generated project
     #[clap(long, ignore_case = true)]
@@ -260,7 +627,7 @@ pub struct CompilePackage {
     skip_beam_compilation: bool,
 }
 
-#[derive(Subcommand, Debug)]
+#[derive(clap::Subcommand, Debug)]
 enum Dependencies {
     /// List all dependency packages
     List,
@@ -268,11 +635,46 @@ enum Dependencies {
     /// Download all dependency packages
     Download,
 
+    /// Print the dependency packages as a tree
+    Tree {
+        /// Print the packages that depend upon this package instead, to see
+        /// why it is a dependency
+        #[clap(long, value_name = "PACKAGE")]
+        invert: Option<String>,
+    },
+
+    /// Copy the sources of all dependency packages to the vendor directory,
+    /// so that the project can be built with `--offline`
+    Vendor,
+
     /// Update dependency packages to their latest versions
-    Update,
+    Update {
+        /// The names of the packages to update, keeping the other packages at
+        /// their locked versions. All packages are updated if none are given
+        packages: Vec<String>,
+    },
+
+    /// List the licences of all dependency packages
+    #[clap(alias = "licences")]
+    Licenses {
+        /// The format to print the licences in: a table, JSON, CSV, or an
+        /// SPDX or CycloneDX software bill of materials
+        #[clap(
+            long,
+            possible_values = LicenceFormat::VARIANTS,
+            ignore_case = true,
+            default_value = "text"
+        )]
+        format: LicenceFormat,
+
+        /// Fail if a package does not have one of these licences, given as
+        /// SPDX license identifiers
+        #[clap(long, value_name = "LICENCE")]
+        allow: Vec<SpdxLicense>,
+    },
 }
 
-#[derive(Subcommand, Debug)]
+#[derive(clap::Subcommand, Debug)]
 enum Hex {
     /// Retire a release from Hex
     ///
@@ -286,9 +688,16 @@ enum Hex {
 
         version: String,
 
-        #[clap(possible_values = RetirementReason::VARIANTS)]
+        /// Why the release is retired
+        #[clap(
+            long,
+            possible_values = RetirementReason::VARIANTS,
+            default_value = "other"
+        )]
         reason: RetirementReason,
 
+        /// A message explaining the retirement, shown when the release is used
+        #[clap(long)]
         message: Option<String>,
     },
 
@@ -300,12 +709,83 @@ enum Hex {
     /// - HEXPM_PASS: (optional) The Hex password to authenticate with.
     #[clap(verbatim_doc_comment)]
     Unretire { package: String, version: String },
+
+    /// Create a Hex API key and store it, to publish and manage packages
+    /// without entering a password each time
+    ///
+    /// This command uses this environment variables:
+    ///
+    /// - HEXPM_USER: (optional) The Hex username to authenticate with.
+    /// - HEXPM_PASS: (optional) The Hex password to authenticate with.
+    #[clap(verbatim_doc_comment)]
+    Authenticate {
+        /// The name to store the key with
+        #[clap(long, default_value = "default")]
+        name: String,
+
+        /// The organization to use the key for
+        #[clap(long)]
+        organization: Option<String>,
+    },
+
+    /// Replace a stored Hex API key with a new one, revoking the old key
+    ///
+    /// This command uses this environment variables:
+    ///
+    /// - HEXPM_USER: (optional) The Hex username to authenticate with.
+    /// - HEXPM_PASS: (optional) The Hex password to authenticate with.
+    #[clap(verbatim_doc_comment)]
+    Rotate {
+        /// The name of the stored key
+        #[clap(default_value = "default")]
+        name: String,
+    },
+
+    /// Revoke a stored Hex API key and forget it
+    Revoke {
+        /// The name of the stored key
+        #[clap(default_value = "default")]
+        name: String,
+    },
+
+    /// List the stored Hex API keys
+    Keys,
 }
 
-#[derive(Subcommand, Debug)]
+#[derive(clap::Subcommand, Debug)]
+enum Api {
+    /// Compare the public API with that of a previous release, failing if
+    /// the version in gleam.toml is not bumped enough for the changes
+    ///
+    /// Removing or changing definitions requires a major version bump, and
+    /// adding or deprecating them a minor one. Before 1.0.0 these are minor
+    /// and patch bumps respectively.
+    Diff {
+        /// The version to compare with. Defaults to the newest release on
+        /// Hex before the version in gleam.toml
+        version: Option<String>,
+
+        /// Compare with the interfaces written by `gleam export interfaces`
+        /// to this directory rather than with a release on Hex, as the given
+        /// version
+        #[clap(long, value_name = "DIRECTORY")]
+        interfaces: Option<PathBuf>,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
 enum Docs {
     /// Render HTML docs locally
-    Build,
+    Build {
+        /// The format to render the docs in
+        #[clap(
+            long,
+            possible_values = DocsFormat::VARIANTS,
+            ignore_case = true,
+            default_value = "html"
+        )]
+        format: DocsFormat,
+    },
 
     /// Publish HTML docs to HexDocs
     ///
@@ -335,19 +815,46 @@ enum Docs {
 }
 
 fn main() {
-    initialise_logger();
     panic::add_handler();
-    let stderr = cli::stderr_buffer_writer();
 
-    let result = match Command::parse() {
+    let cli = Cli::parse();
+    logging::initialise(cli.verbose, cli.log_format);
+    dependencies::set_offline(cli.offline);
+    build::set_timings(cli.timings, cli.timings_trace);
+    let result = match cli.command {
         Command::Build {
             target,
-            warnings_as_errors: _,
-        } => command_build(target),
+            warnings,
+            warn_dead_code,
+            bundle,
+            watch,
+            message_format,
+        } => command_build(
+            target,
+            warnings,
+            warn_dead_code,
+            bundle,
+            watch,
+            message_format,
+        ),
+
+        Command::Check {
+            target,
+            warnings,
+            warn_dead_code,
+            message_format,
+        } => command_check(target, warnings, warn_dead_code, message_format),
+
+        Command::Types { private } => command_types(private),
 
-        Command::Check => command_check(),
+        Command::Todos => command_todos(),
 
-        Command::Docs(Docs::Build) => docs::build(),
+        Command::Api(Api::Diff {
+            version,
+            interfaces,
+        }) => api::diff(version, interfaces),
+
+        Command::Docs(Docs::Build { format }) => docs::build(format),
 
         Command::Docs(Docs::Publish) => docs::publish(),
 
@@ -355,9 +862,24 @@ fn main() {
 
         Command::Format {
             stdin,
+            stdin_path,
             files,
             check,
-        } => format::run(stdin, check, files),
+            line_length,
+            sort_imports,
+            range,
+            verify,
+        } => format::run(
+            stdin_path.or_else(|| stdin.then(|| PathBuf::from("<stdin>"))),
+            check,
+            files,
+            line_length,
+            sort_imports,
+            range,
+            verify,
+        ),
+
+        Command::Fix => fix::run(),
 
         Command::Deps(Dependencies::List) => dependencies::list(),
 
@@ -365,19 +887,85 @@ fn main() {
             dependencies::download(cli::Reporter::new(), None, UseManifest::Yes).map(|_| ())
         }
 
-        Command::Deps(Dependencies::Update) => dependencies::update(),
+        Command::Deps(Dependencies::Tree { invert }) => dependencies::tree(invert),
+
+        Command::Deps(Dependencies::Vendor) => dependencies::vendor(),
+
+        Command::Deps(Dependencies::Update { packages }) => dependencies::update(packages),
+
+        Command::Deps(Dependencies::Licenses { format, allow }) => {
+            dependencies::licences(format, allow)
+        }
 
         Command::New(options) => new::create(options, VERSION),
 
-        Command::Shell => shell::command(),
+        Command::Shell {
+            target: _,
+            erlang: true,
+        } => shell::erlang(),
 
-        Command::Run { target, arguments } => run::command(arguments, target, run::Which::Src),
+        Command::Shell {
+            target,
+            erlang: false,
+        } => shell::command(target),
+
+        Command::Run {
+            target,
+            bundle: true,
+            environment,
+            arguments,
+        } => run::serve_bundle(target, &arguments, &environment),
+
+        Command::Run {
+            target,
+            bundle: false,
+            environment,
+            arguments,
+        } if script::is_script(&arguments) => script::run(target, arguments, &environment),
+
+        Command::Run {
+            target,
+            bundle: false,
+            environment,
+            arguments,
+        } => run::command(arguments, &environment, target, run::Which::Src),
 
-        Command::Test { target, arguments } => run::command(arguments, target, run::Which::Test),
+        Command::Test {
+            target,
+            watch,
+            filter,
+            parallel,
+            doc,
+            environment,
+            arguments,
+        } => {
+            // The test functions are only run by the test runner of the
+            // compiler when they are to be filtered or run in parallel, as
+            // are the examples of the documentation
+            let which = if doc || filter.is_some() || parallel.is_some() {
+                run::Which::TestFunctions {
+                    filter,
+                    parallel: parallel.unwrap_or(1),
+                    doc,
+                }
+            } else {
+                run::Which::Test
+            };
+            if watch {
+                run::watch(arguments, &environment, target, which)
+            } else {
+                run::command(arguments, &environment, target, which)
+            }
+        }
 
         Command::CompilePackage(opts) => compile_package::command(opts),
 
-        Command::Publish { replace, yes } => publish::command(replace, yes),
+        Command::Publish {
+            replace,
+            yes,
+            dry_run,
+            organization,
+        } => publish::command(replace, yes, dry_run, organization),
 
         Command::PrintConfig => print_config(),
 
@@ -392,15 +980,62 @@ fn main() {
             hex::UnretireCommand::new(package, version).run()
         }
 
+        Command::Hex(Hex::Authenticate { name, organization }) => {
+            hex::authenticate(name, organization)
+        }
+
+        Command::Hex(Hex::Rotate { name }) => hex::rotate(name),
+
+        Command::Hex(Hex::Revoke { name }) => hex::revoke(name),
+
+        Command::Hex(Hex::Keys) => hex::keys(),
+
         Command::Add { packages, dev } => add::command(packages, dev),
 
-        Command::Update => dependencies::update(),
+        Command::Remove { packages } => remove::command(packages),
 
-        Command::Clean => clean(),
+        Command::Update { packages } => dependencies::update(packages),
+
+        Command::Outdated { format } => outdated::command(format),
+
+        Command::Clean {
+            target,
+            package,
+            deps,
+        } => clean::command(clean::Selection {
+            target,
+            package,
+            deps,
+        }),
 
         Command::LanguageServer => lsp::main(),
 
+        Command::Completions { shell } => {
+            completions::print(shell, Cli::command());
+            Ok(())
+        }
+
         Command::Export(ExportTarget::ErlangShipment) => export::erlang_shipment(),
+
+        Command::Export(ExportTarget::Escript) => export::escript(),
+
+        Command::Export(ExportTarget::JavascriptBundle) => export::javascript_bundle(),
+
+        Command::Export(ExportTarget::NpmPackage { publish }) => export::npm_package(publish),
+
+        Command::Export(ExportTarget::NativeLib { package_manager }) => {
+            export::native_lib(package_manager)
+        }
+
+        Command::Export(ExportTarget::WasmComponent { adapter }) => export::wasm_component(adapter),
+
+        Command::Export(ExportTarget::Interfaces) => export::interfaces(),
+
+        Command::Export(ExportTarget::DepGraph { format, packages }) => {
+            export::dependency_graph(format, packages)
+        }
+
+        Command::Export(ExportTarget::BuildFiles { format }) => export::build_files(format),
     };
 
     match result {
@@ -409,51 +1044,178 @@ fn main() {
         }
         Err(error) => {
             tracing::error!(error = ?error, "Failed");
-            let mut buffer = stderr.buffer();
-            error.pretty(&mut buffer);
-            stderr.print(&buffer).expect("Final result error writing");
+            print_error(&error);
             std::process::exit(1);
         }
     }
 }
 
-fn command_check() -> Result<(), Error> {
-    let _ = build::main(Options {
+fn command_check(
+    target: Option<Target>,
+    warnings: WarningFlags,
+    warn_dead_code: bool,
+    message_format: MessageFormat,
+) -> Result<(), Error> {
+    let _ = build_with_format(
+        Options {
+            perform_codegen: false,
+            warn_dead_code,
+            warning_levels: warnings.levels(),
+            mode: Mode::Dev,
+            target,
+        },
+        message_format,
+    )?;
+    Ok(())
+}
+
+fn build_with_format(
+    options: Options,
+    message_format: MessageFormat,
+) -> Result<gleam_core::build::Package, Error> {
+    match message_format {
+        MessageFormat::Human => build::main(options),
+        MessageFormat::Json => build::json(options),
+        MessageFormat::Sarif => build::sarif(options),
+    }
+}
+
+fn command_types(include_private: bool) -> Result<(), Error> {
+    let compiled = build::quiet(Options {
         perform_codegen: false,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
         mode: Mode::Dev,
         target: None,
     })?;
+    let mut modules: Vec<_> = compiled
+        .modules
+        .iter()
+        .filter(|module| module.origin.is_src())
+        .collect();
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    for module in modules {
+        let signatures = gleam_core::signatures::module_signatures(&module.ast, include_private);
+        if signatures.is_empty() {
+            continue;
+        }
+        println!("{}", module.name);
+        for signature in signatures {
+            println!("  {}", signature);
+        }
+    }
     Ok(())
 }
 
-fn command_build(target: Option<Target>) -> Result<(), Error> {
-    let _ = build::main(Options {
-        perform_codegen: true,
+fn command_todos() -> Result<(), Error> {
+    let compiled = build::quiet(Options {
+        perform_codegen: false,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
         mode: Mode::Dev,
-        target,
+        target: None,
     })?;
+    let mut modules: Vec<_> = compiled.modules.iter().collect();
+    modules.sort_by(|a, b| a.input_path.cmp(&b.input_path));
+    for module in modules {
+        let path = module
+            .input_path
+            .strip_prefix(".")
+            .unwrap_or(&module.input_path);
+        for todo in gleam_core::todos::module_todos(&module.ast, &module.code) {
+            let label = match &todo.label {
+                Some(label) => format!(" ({})", label),
+                None => String::new(),
+            };
+            println!(
+                "todo at {}:{} must produce {}{}",
+                path.to_string_lossy(),
+                todo.line,
+                todo.type_,
+                label
+            );
+        }
+    }
     Ok(())
 }
 
+fn command_build(
+    targets: Option<BuildTargets>,
+    warnings: WarningFlags,
+    warn_dead_code: bool,
+    bundle: bool,
+    watch: bool,
+    message_format: MessageFormat,
+) -> Result<(), Error> {
+    let targets = match targets {
+        Some(BuildTargets::One(target)) => vec![target],
+        Some(BuildTargets::All) => Target::ALL.to_vec(),
+        None => root_config()?.targets,
+    };
+    let target = match targets.as_slice() {
+        [] => None,
+        [target] => Some(*target),
+        _ => {
+            let flag = match (bundle, watch, message_format) {
+                (true, _, _) => Some("--bundle".into()),
+                (_, true, _) => Some("--watch".into()),
+                (_, _, MessageFormat::Human) => None,
+                (_, _, _) => Some("--message-format".into()),
+            };
+            if let Some(flag) = flag {
+                return Err(Error::MultipleTargetsUnsupported { flag });
+            }
+            let _ = build::targets(
+                Options {
+                    perform_codegen: true,
+                    warn_dead_code,
+                    warning_levels: warnings.levels(),
+                    mode: Mode::Dev,
+                    target: None,
+                },
+                &targets,
+            )?;
+            return Ok(());
+        }
+    };
+    let target = if bundle {
+        Some(bundle::target(target)?)
+    } else {
+        target
+    };
+    let options = Options {
+        perform_codegen: true,
+        warn_dead_code,
+        warning_levels: warnings.levels(),
+        mode: Mode::Dev,
+        target,
+    };
+    let after_build = |_: &_| {
+        if bundle {
+            bundle::bundle(&root_config()?)?;
+        }
+        Ok(())
+    };
+    if watch {
+        return watch::build(options, after_build);
+    }
+    let package = build_with_format(options, message_format)?;
+    after_build(&package)
+}
+
 fn print_config() -> Result<()> {
     let config = root_config()?;
     println!("{:#?}", config);
     Ok(())
 }
 
-fn clean() -> Result<()> {
-    fs::delete_dir(&gleam_core::paths::build())
-}
-
-fn initialise_logger() {
-    let enable_colours = std::env::var("GLEAM_LOG_NOCOLOUR").is_err();
-    tracing_subscriber::fmt()
-        .with_writer(std::io::stderr)
-        .with_env_filter(&std::env::var("GLEAM_LOG").unwrap_or_else(|_| "off".to_string()))
-        .with_target(false)
-        .with_ansi(enable_colours)
-        .without_time()
-        .init();
+fn print_error(error: &Error) {
+    let buffer_writer = cli::stderr_buffer_writer();
+    let mut buffer = buffer_writer.buffer();
+    error.pretty(&mut buffer);
+    buffer_writer
+        .print(&buffer)
+        .expect("Final result error writing");
 }
 
 fn print_warning(w: &Warning) {
@@ -463,3 +1225,39 @@ fn print_warning(w: &Warning) {
     #[allow(clippy::unwrap_used)]
     buffer_writer.print(&buffer).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_stdin_path_rejects_files() {
+        let result = Cli::try_parse_from(["gleam", "format", "--stdin-path", "src/a.gleam", "src"]);
+        assert_eq!(
+            result.map(|_| ()).map_err(|error| error.kind()),
+            Err(clap::ErrorKind::ArgumentConflict)
+        );
+    }
+
+    #[test]
+    fn format_stdin_path_rejects_stdin() {
+        let result =
+            Cli::try_parse_from(["gleam", "format", "--stdin", "--stdin-path", "src/a.gleam"]);
+        assert_eq!(
+            result.map(|_| ()).map_err(|error| error.kind()),
+            Err(clap::ErrorKind::ArgumentConflict)
+        );
+    }
+
+    #[test]
+    fn format_stdin_path_alone() {
+        let cli = Cli::try_parse_from(["gleam", "format", "--stdin-path", "src/a.gleam"]);
+        assert!(matches!(
+            cli.map(|cli| cli.command),
+            Ok(Command::Format {
+                stdin_path: Some(_),
+                ..
+            })
+        ));
+    }
+}
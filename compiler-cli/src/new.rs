@@ -1,26 +1,37 @@
 use gleam_core::{
     erlang,
-    error::{Error, FileIoAction, FileKind, InvalidProjectNameReason},
+    error::{Error, InvalidProjectNameReason},
+    io::FileSystemWriter,
     parse, Result,
 };
 use serde::{Deserialize, Serialize};
-use std::fs::File;
+use std::env;
 use std::path::{Path, PathBuf};
-use std::{env, io::Write};
 use strum::{Display, EnumString, EnumVariantNames};
 
-use crate::NewOptions;
+use crate::{fs::ProjectIO, NewOptions};
 
-const GLEAM_STDLIB_VERSION: &str = "0.25";
+pub(crate) const GLEAM_STDLIB_VERSION: &str = "0.25";
 const GLEEUNIT_VERSION: &str = "0.7";
 const ERLANG_OTP_VERSION: &str = "25.1";
 const REBAR3_VERSION: &str = "25.1";
 const ELIXIR_VERSION: &str = "1.14.1";
+const GLEAM_ERLANG_VERSION: &str = "0.17";
+const GLEAM_HTTP_VERSION: &str = "3.1";
+const MIST_VERSION: &str = "0.9";
 
 #[derive(Debug, Serialize, Deserialize, Display, EnumString, EnumVariantNames, Clone, Copy)]
 #[strum(serialize_all = "kebab_case")]
 pub enum Template {
     Lib,
+    /// A JavaScript application for the browser, bundled with esbuild.
+    Browser,
+    /// A command line application for the Erlang virtual machine.
+    Cli,
+    /// A web server for the Erlang virtual machine, using mist.
+    Web,
+    /// A native application with C++ FFI, compiled with CMake.
+    Native,
 }
 
 #[derive(Debug)]
@@ -33,6 +44,7 @@ pub struct Creator {
     gleam_version: &'static str,
     options: NewOptions,
     project_name: String,
+    io: ProjectIO,
 }
 
 impl Creator {
@@ -51,25 +63,31 @@ impl Creator {
             gleam_version,
             options,
             project_name,
+            io: ProjectIO::new(),
         }
     }
 
     fn run(&self) -> Result<()> {
-        crate::fs::mkdir(&self.root)?;
-        crate::fs::mkdir(&self.src)?;
-        crate::fs::mkdir(&self.test)?;
-        crate::fs::mkdir(&self.github)?;
-        crate::fs::mkdir(&self.workflows)?;
+        self.io.mkdir(&self.root)?;
+        self.io.mkdir(&self.src)?;
+        self.io.mkdir(&self.test)?;
+        self.io.mkdir(&self.github)?;
+        self.io.mkdir(&self.workflows)?;
         crate::fs::git_init(&self.root)?;
 
+        self.gitignore()?;
+        self.github_ci()?;
+        self.readme()?;
+        self.gleam_toml()?;
+        self.src_module()?;
+        self.test_module()?;
+
         match self.options.template {
-            Template::Lib => {
-                self.gitignore()?;
-                self.github_ci()?;
-                self.readme()?;
-                self.gleam_toml()?;
-                self.src_module()?;
-                self.test_module()?;
+            Template::Lib | Template::Cli | Template::Web => (),
+            Template::Browser => self.index_html()?,
+            Template::Native => {
+                self.native_ffi()?;
+                self.cmake_lists()?;
             }
         }
 
@@ -77,34 +95,223 @@ impl Creator {
     }
 
     fn src_module(&self) -> Result<()> {
-        write(
-            self.src.join(format!("{}.gleam", self.project_name)),
-            &format!(
+        let src = match self.options.template {
+            Template::Lib | Template::Browser => {
                 r#"import gleam/io
 
-pub fn main() {{
-  io.println("Hello from {}!")
+pub fn main() {
+  io.println("Hello from {name}!")
+}
+"#
+            }
+            Template::Cli => {
+                r#"import gleam/erlang
+import gleam/io
+import gleam/string
+
+pub fn main() {
+  erlang.start_arguments()
+  |> greeting
+  |> io.println
+}
+
+pub fn greeting(arguments: List(String)) -> String {
+  case arguments {
+    [] -> "Hello from {name}!"
+    names -> "Hello, " <> string.join(names, " and ") <> "!"
+  }
+}
+"#
+            }
+            Template::Web => {
+                r#"import gleam/bit_builder.{BitBuilder}
+import gleam/erlang/process
+import gleam/http/request.{Request}
+import gleam/http/response.{Response}
+import gleam/io
+import mist
+
+pub fn main() {
+  assert Ok(_) =
+    mist.run_service(8000, handle_request, max_body_limit: 4_000_000)
+  io.println("Listening on http://localhost:8000")
+  process.sleep_forever()
+}
+
+pub fn handle_request(_request: Request(BitString)) -> Response(BitBuilder) {
+  response.new(200)
+  |> response.set_body(bit_builder.from_string("Hello from {name}!"))
+}
+"#
+            }
+            Template::Native => {
+                r#"/// Implemented in C++ in {name}_ffi.cc
+external fn add(Int, Int) -> Int =
+  "{name}_ffi.h" "{name}_ffi::Add"
+
+pub fn main() -> Int {
+  add(1, 2)
+}
+"#
+            }
+        };
+        self.io.write(
+            &self.src.join(format!("{}.gleam", self.project_name)),
+            &src.replace("{name}", &self.project_name),
+        )
+    }
+
+    /// The C++ implementation of the external function of the native
+    /// template, which is copied next to the generated C++.
+    fn native_ffi(&self) -> Result<()> {
+        let name = &self.project_name;
+        let guard = format!("{}_FFI_H_", name.to_uppercase());
+        self.io.write(
+            &self.src.join(format!("{}_ffi.h", name)),
+            &format!(
+                r#"#ifndef {guard}
+#define {guard}
+
+#include <gleam.h>
+
+namespace {name}_ffi {{
+
+int64_t Add(int64_t a, int64_t b);
+
+}} // namespace {name}_ffi
+
+#endif // {guard}
+"#
+            ),
+        )?;
+        self.io.write(
+            &self.src.join(format!("{}_ffi.cc", name)),
+            &format!(
+                r#"#include "{name}_ffi.h"
+
+namespace {name}_ffi {{
+
+int64_t Add(int64_t a, int64_t b) {{ return a + b; }}
+
+}} // namespace {name}_ffi
+"#
+            ),
+        )
+    }
+
+    /// A CMake project that compiles the C++ generated by `gleam build` into
+    /// an executable, and the test module into a test run by `ctest`.
+    fn cmake_lists(&self) -> Result<()> {
+        let name = &self.project_name;
+        let native = self.root.join("native");
+        self.io.mkdir(&native)?;
+        self.io.write(
+            &native.join("main.cc"),
+            &format!(
+                r#"#include <iostream>
+
+#include "{name}.h"
+
+int main() {{
+  std::cout << {name}::main() << std::endl;
+  return 0;
 }}
-"#,
-                self.project_name
+"#
+            ),
+        )?;
+        self.io.write(
+            &native.join("test_main.cc"),
+            &format!(
+                r#"#include "{name}_test.h"
+
+int main() {{ return {name}_test::main() ? 0 : 1; }}
+"#
+            ),
+        )?;
+        self.io.write(
+            &self.root.join("CMakeLists.txt"),
+            &format!(
+                r#"cmake_minimum_required(VERSION 3.19)
+project({name} LANGUAGES CXX)
+
+set(CMAKE_CXX_STANDARD 17)
+set(CMAKE_CXX_STANDARD_REQUIRED ON)
+
+# The C++ is generated by `gleam build`, when configuring so that the sources
+# can be found and before each build so that they are up to date.
+set(GLEAM_BUILD gleam build --target native)
+execute_process(
+  COMMAND ${{GLEAM_BUILD}}
+  WORKING_DIRECTORY ${{CMAKE_CURRENT_SOURCE_DIR}}
+  COMMAND_ERROR_IS_FATAL ANY
+)
+add_custom_target(gleam_build ALL
+  COMMAND ${{GLEAM_BUILD}}
+  WORKING_DIRECTORY ${{CMAKE_CURRENT_SOURCE_DIR}}
+)
+
+set(GLEAM_DIST ${{CMAKE_CURRENT_SOURCE_DIR}}/build/dev/native/{name}/dist)
+file(GLOB_RECURSE GLEAM_SOURCES CONFIGURE_DEPENDS ${{GLEAM_DIST}}/*.cc)
+add_library({name}_gleam ${{GLEAM_SOURCES}})
+target_include_directories({name}_gleam PUBLIC ${{GLEAM_DIST}})
+add_dependencies({name}_gleam gleam_build)
+
+add_executable({name} native/main.cc)
+target_link_libraries({name} PRIVATE {name}_gleam)
+
+enable_testing()
+add_executable({name}_test native/test_main.cc)
+target_link_libraries({name}_test PRIVATE {name}_gleam)
+add_test(NAME {name}_test COMMAND {name}_test)
+"#
             ),
         )
     }
 
     fn gitignore(&self) -> Result<()> {
-        write(
-            self.root.join(".gitignore"),
-            "*.beam
+        let bundle = match self.options.template {
+            Template::Lib | Template::Cli | Template::Web => "",
+            Template::Browser => "priv/static/app.js\n",
+            Template::Native => "cmake-build\n",
+        };
+        self.io.write(
+            &self.root.join(".gitignore"),
+            &format!(
+                "*.beam
 *.ez
 build
 erl_crash.dump
-",
+{bundle}"
+            ),
         )
     }
 
     fn readme(&self) -> Result<()> {
-        write(
-            self.root.join("README.md"),
+        let quick_start = match self.options.template {
+            Template::Lib | Template::Browser => {
+                "gleam run   # Run the project
+gleam test  # Run the tests
+gleam shell # Run an Erlang shell"
+            }
+            Template::Cli => {
+                "gleam run -- Lucy # Run the project with arguments
+gleam test        # Run the tests
+gleam shell       # Run an Erlang shell"
+            }
+            Template::Web => {
+                "gleam run   # Serve the project on http://localhost:8000
+gleam test  # Run the tests
+gleam shell # Run an Erlang shell"
+            }
+            Template::Native => {
+                "cmake -B cmake-build          # Generate the C++ and configure the build
+cmake --build cmake-build     # Compile the project
+./cmake-build/{name}          # Run the project
+ctest --test-dir cmake-build  # Run the tests"
+            }
+        };
+        self.io.write(
+            &self.root.join("README.md"),
             &format!(
                 r#"# {name}
 
@@ -116,9 +323,7 @@ erl_crash.dump
 ## Quick start
 
 ```sh
-gleam run   # Run the project
-gleam test  # Run the tests
-gleam shell # Run an Erlang shell
+{quick_start}
 ```
 
 ## Installation
@@ -133,13 +338,25 @@ and its documentation can be found at <https://hexdocs.pm/{name}>.
 "#,
                 name = self.project_name,
                 description = self.options.description,
+                quick_start = quick_start.replace("{name}", &self.project_name),
             ),
         )
     }
 
     fn github_ci(&self) -> Result<()> {
-        write(
-            self.workflows.join("test.yml"),
+        let test = match self.options.template {
+            Template::Lib | Template::Browser | Template::Cli | Template::Web => {
+                "      - run: gleam deps download
+      - run: gleam test"
+            }
+            Template::Native => {
+                "      - run: cmake -B cmake-build
+      - run: cmake --build cmake-build
+      - run: ctest --test-dir cmake-build"
+            }
+        };
+        self.io.write(
+            &self.workflows.join("test.yml"),
             &format!(
                 r#"name: test
 
@@ -162,21 +379,64 @@ jobs:
           rebar3-version: "{}"
           # elixir-version: "{}"
       - run: gleam format --check src test
-      - run: gleam deps download
-      - run: gleam test
+{}
+"#,
+                ERLANG_OTP_VERSION, self.gleam_version, REBAR3_VERSION, ELIXIR_VERSION, test,
+            ),
+        )
+    }
+
+    fn index_html(&self) -> Result<()> {
+        let static_ = self.root.join("priv").join("static");
+        self.io.mkdir(&static_)?;
+        self.io.write(
+            &static_.join("index.html"),
+            &format!(
+                r#"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8">
+    <title>{}</title>
+    <script type="module" src="/app.js"></script>
+  </head>
+  <body></body>
+</html>
 "#,
-                ERLANG_OTP_VERSION, self.gleam_version, REBAR3_VERSION, ELIXIR_VERSION,
+                self.project_name
             ),
         )
     }
 
     fn gleam_toml(&self) -> Result<()> {
-        write(
-            self.root.join("gleam.toml"),
+        let (target, bundle) = match self.options.template {
+            Template::Lib | Template::Cli | Template::Web => ("", ""),
+            Template::Browser => (
+                "target = \"javascript\"\n",
+                r#"
+# `gleam build --bundle` bundles the project with esbuild and `gleam run
+# --bundle` serves it from priv/static.
+[javascript.bundle]
+command = "npx esbuild --bundle --outfile=priv/static/app.js"
+serve = "npx esbuild --bundle --outfile=priv/static/app.js --servedir=priv/static"
+"#,
+            ),
+            Template::Native => ("target = \"native\"\n", ""),
+        };
+        let dependencies: String = self
+            .dependencies()
+            .iter()
+            .map(|(name, version)| format!("{} = \"~> {}\"\n", name, version))
+            .collect();
+        let dev_dependencies = match self.options.template {
+            Template::Native => String::new(),
+            _ => format!("gleeunit = \"~> {}\"\n", GLEEUNIT_VERSION),
+        };
+        self.io.write(
+            &self.root.join("gleam.toml"),
             &format!(
                 r#"name = "{name}"
 version = "0.1.0"
-
+{target}
 # Fill out these fields if you intend to generate HTML documentation or publish
 # your project to the Hex package manager.
 #
@@ -186,22 +446,37 @@ version = "0.1.0"
 # links = [{{ title = "Website", href = "https://gleam.run" }}]
 
 [dependencies]
-gleam_stdlib = "~> {gleam_stdlib}"
-
+{dependencies}
 [dev-dependencies]
-gleeunit = "~> {gleeunit}"
-"#,
+{dev_dependencies}{bundle}"#,
                 name = self.project_name,
-                gleam_stdlib = GLEAM_STDLIB_VERSION,
-                gleeunit = GLEEUNIT_VERSION,
             ),
         )
     }
 
+    /// The packages the project of the template depends on. Native projects
+    /// have none as the packages on Hex are for Erlang and JavaScript.
+    fn dependencies(&self) -> Vec<(&'static str, &'static str)> {
+        match self.options.template {
+            Template::Lib | Template::Browser => vec![("gleam_stdlib", GLEAM_STDLIB_VERSION)],
+            Template::Cli => vec![
+                ("gleam_stdlib", GLEAM_STDLIB_VERSION),
+                ("gleam_erlang", GLEAM_ERLANG_VERSION),
+            ],
+            Template::Web => vec![
+                ("gleam_stdlib", GLEAM_STDLIB_VERSION),
+                ("gleam_erlang", GLEAM_ERLANG_VERSION),
+                ("gleam_http", GLEAM_HTTP_VERSION),
+                ("mist", MIST_VERSION),
+            ],
+            Template::Native => vec![],
+        }
+    }
+
     fn test_module(&self) -> Result<()> {
-        write(
-            self.test.join(format!("{}_test.gleam", self.project_name)),
-            r#"import gleeunit
+        let test = match self.options.template {
+            Template::Lib | Template::Browser => {
+                r#"import gleeunit
 import gleeunit/should
 
 pub fn main() {
@@ -213,7 +488,62 @@ pub fn hello_world_test() {
   1
   |> should.equal(1)
 }
-"#,
+"#
+            }
+            Template::Cli => {
+                r#"import gleeunit
+import gleeunit/should
+import {name}
+
+pub fn main() {
+  gleeunit.main()
+}
+
+// gleeunit test functions end in `_test`
+pub fn greeting_test() {
+  {name}.greeting([])
+  |> should.equal("Hello from {name}!")
+
+  {name}.greeting(["Lucy"])
+  |> should.equal("Hello, Lucy!")
+}
+"#
+            }
+            Template::Web => {
+                r#"import gleam/http/request
+import gleeunit
+import gleeunit/should
+import {name}
+
+pub fn main() {
+  gleeunit.main()
+}
+
+// gleeunit test functions end in `_test`
+pub fn handle_request_test() {
+  let response =
+    request.new()
+    |> request.set_body(<<>>)
+    |> {name}.handle_request
+
+  response.status
+  |> should.equal(200)
+}
+"#
+            }
+            // The test passes when `main` returns `True`, as run by ctest
+            Template::Native => {
+                r#"import {name}
+
+pub fn main() -> Bool {
+  {name}.main() == 3
+}
+"#
+            }
+        };
+        self.io.write(
+            &self.test.join(format!("{}_test.gleam", self.project_name)),
+            &test.replace("{name}", &self.project_name),
         )
     }
 }
@@ -249,24 +579,6 @@ The project can be compiled and tested by running these commands:
     Ok(())
 }
 
-fn write(path: PathBuf, contents: &str) -> Result<()> {
-    let mut f = File::create(&path).map_err(|err| Error::FileIo {
-        kind: FileKind::File,
-        path: path.clone(),
-        action: FileIoAction::Create,
-        err: Some(err.to_string()),
-    })?;
-
-    f.write_all(contents.as_bytes())
-        .map_err(|err| Error::FileIo {
-            kind: FileKind::File,
-            path,
-            action: FileIoAction::WriteTo,
-            err: Some(err.to_string()),
-        })?;
-    Ok(())
-}
-
 fn validate_root_folder(name: &str) -> Result<(), Error> {
     if Path::new(name).exists() {
         Err(Error::ProjectRootAlreadyExist {
@@ -277,7 +589,7 @@ fn validate_root_folder(name: &str) -> Result<(), Error> {
     }
 }
 
-fn validate_name(name: &str) -> Result<(), Error> {
+pub(crate) fn validate_name(name: &str) -> Result<(), Error> {
     if name.starts_with("gleam_") {
         Err(Error::InvalidProjectName {
             name: name.to_string(),
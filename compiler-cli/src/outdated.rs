@@ -0,0 +1,327 @@
+//! The `gleam outdated` command, which compares the locked versions of the
+//! dependency packages with their releases on Hex.
+
+use std::{collections::HashMap, io::Write};
+
+use gleam_core::{
+    config::{PackageConfig, Requirement},
+    error::StandardIoAction,
+    manifest::{Manifest, ManifestPackageSource},
+    Error, Result,
+};
+use hexpm::version::Version;
+use itertools::Itertools;
+
+use crate::dependencies;
+
+#[derive(Debug, strum::EnumString, strum::EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum OutdatedFormat {
+    /// A table of the outdated packages, for people to read.
+    Text,
+    /// A JSON array of the outdated packages, for other programs.
+    Json,
+}
+
+/// A dependency package with a newer release than the locked version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Outdated {
+    name: String,
+    current: Version,
+    /// The newest release allowed by the requirements of gleam.toml and of
+    /// the packages that depend upon this one, which may be the current one.
+    compatible: Version,
+    /// The newest release.
+    latest: Version,
+    /// Whether the package is a dependency of the project rather than of
+    /// another package.
+    direct: bool,
+}
+
+pub fn command(format: OutdatedFormat) -> Result<()> {
+    let config = crate::config::root_config()?;
+    let manifest = dependencies::read_manifest_from_disc()?;
+    let releases = dependencies::hex_releases(&config, &manifest)?;
+    let outdated = outdated_packages(&config, &manifest, &releases);
+    let output = match format {
+        OutdatedFormat::Text => text(&outdated),
+        OutdatedFormat::Json => json(&outdated),
+    };
+    std::io::stdout()
+        .write_all(output.as_bytes())
+        .map_err(|e| Error::StandardIo {
+            action: StandardIoAction::Write,
+            err: Some(e.kind()),
+        })
+}
+
+fn outdated_packages(
+    config: &PackageConfig,
+    manifest: &Manifest,
+    releases: &HashMap<String, hexpm::Package>,
+) -> Vec<Outdated> {
+    // The requirements the project and the locked packages have of each
+    // package, which a compatible release must meet.
+    let mut requirements: HashMap<&str, Vec<hexpm::version::Range>> = HashMap::new();
+    for (name, requirement) in manifest.requirements.iter() {
+        if let Requirement::Hex { version, .. } = requirement {
+            requirements.entry(name).or_default().push(version.clone());
+        }
+    }
+    for package in &manifest.packages {
+        let locked = releases.get(&package.name).and_then(|hex| {
+            hex.releases
+                .iter()
+                .find(|release| release.version == package.version)
+        });
+        for (name, dependency) in locked.iter().flat_map(|release| &release.requirements) {
+            requirements
+                .entry(name)
+                .or_default()
+                .push(dependency.requirement.clone());
+        }
+    }
+
+    manifest
+        .packages
+        .iter()
+        .filter(|package| matches!(package.source, ManifestPackageSource::Hex { .. }))
+        .filter_map(|package| {
+            let hex = releases.get(&package.name)?;
+            // Pre-releases and retired releases are only suggested to those
+            // who already use them.
+            let candidates = hex
+                .releases
+                .iter()
+                .filter(|release| release.retirement_status.is_none())
+                .map(|release| &release.version)
+                .filter(|version| !version.is_pre() || package.version.is_pre())
+                .collect_vec();
+            let latest = candidates.iter().copied().max()?;
+            if latest <= &package.version {
+                return None;
+            }
+            let ranges = requirements
+                .get(package.name.as_str())
+                .into_iter()
+                .flatten()
+                .filter_map(|range| range.to_pubgrub().ok())
+                .collect_vec();
+            let compatible = candidates
+                .iter()
+                .copied()
+                .filter(|version| ranges.iter().all(|range| range.contains(version)))
+                .max()
+                .filter(|version| *version > &package.version)
+                .unwrap_or(&package.version);
+            Some(Outdated {
+                name: package.name.clone(),
+                current: package.version.clone(),
+                compatible: compatible.clone(),
+                latest: latest.clone(),
+                direct: config.dependencies.contains_key(&package.name)
+                    || config.dev_dependencies.contains_key(&package.name),
+            })
+        })
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .collect()
+}
+
+fn text(outdated: &[Outdated]) -> String {
+    if outdated.is_empty() {
+        return "All dependencies are up to date\n".into();
+    }
+    let rows = outdated
+        .iter()
+        .map(|package| {
+            (
+                package.name.clone(),
+                package.current.to_string(),
+                package.compatible.to_string(),
+                package.latest.to_string(),
+            )
+        })
+        .collect_vec();
+    let width = |header: &str, column: fn(&(String, String, String, String)) -> &String| {
+        rows.iter()
+            .map(|row| column(row).len())
+            .chain(std::iter::once(header.len()))
+            .max()
+            .unwrap_or_default()
+    };
+    let widths = (
+        width("Package", |row| &row.0),
+        width("Current", |row| &row.1),
+        width("Compatible", |row| &row.2),
+    );
+    let header = (
+        "Package".to_string(),
+        "Current".to_string(),
+        "Compatible".to_string(),
+        "Latest".to_string(),
+    );
+    std::iter::once(header)
+        .chain(rows)
+        .map(|(name, current, compatible, latest)| {
+            format!(
+                "{:name$}  {:current$}  {:compatible$}  {}\n",
+                name,
+                current,
+                compatible,
+                latest,
+                name = widths.0,
+                current = widths.1,
+                compatible = widths.2,
+            )
+        })
+        .collect()
+}
+
+fn json(outdated: &[Outdated]) -> String {
+    let packages = outdated
+        .iter()
+        .map(|package| {
+            serde_json::json!({
+                "name": package.name,
+                "current": package.current.to_string(),
+                "compatible": package.compatible.to_string(),
+                "latest": package.latest.to_string(),
+                "direct": package.direct,
+            })
+        })
+        .collect_vec();
+    format!("{:#}\n", serde_json::json!(packages))
+}
+
+#[cfg(test)]
+fn hex_package(name: &str, releases: &[(&str, &[(&str, &str)])]) -> (String, hexpm::Package) {
+    let releases = releases
+        .iter()
+        .map(|(version, requirements)| hexpm::Release {
+            version: Version::parse(version).expect("version"),
+            requirements: requirements
+                .iter()
+                .map(|(name, range)| {
+                    let dependency = hexpm::Dependency {
+                        requirement: hexpm::version::Range::new(range.to_string()),
+                        optional: false,
+                        app: None,
+                        repository: None,
+                    };
+                    (name.to_string(), dependency)
+                })
+                .collect(),
+            retirement_status: None,
+            outer_checksum: vec![],
+            meta: (),
+        })
+        .collect();
+    let package = hexpm::Package {
+        name: name.into(),
+        repository: "hexpm".into(),
+        releases,
+    };
+    (name.into(), package)
+}
+
+#[cfg(test)]
+fn manifest_package(name: &str, version: &str) -> gleam_core::manifest::ManifestPackage {
+    gleam_core::manifest::ManifestPackage {
+        name: name.into(),
+        version: Version::parse(version).expect("version"),
+        build_tools: vec!["gleam".into()],
+        otp_app: None,
+        requirements: vec![],
+        source: ManifestPackageSource::Hex {
+            outer_checksum: gleam_core::manifest::Base16Checksum(vec![]),
+            registry: None,
+        },
+    }
+}
+
+#[cfg(test)]
+fn outdated(name: &str, current: &str, compatible: &str, latest: &str, direct: bool) -> Outdated {
+    Outdated {
+        name: name.into(),
+        current: Version::parse(current).expect("version"),
+        compatible: Version::parse(compatible).expect("version"),
+        latest: Version::parse(latest).expect("version"),
+        direct,
+    }
+}
+
+#[test]
+fn outdated_compatible_and_incompatible_releases() {
+    let mut config = PackageConfig::default();
+    let _ = config
+        .dependencies
+        .insert("gleam_http".into(), Requirement::hex("~> 3.0"));
+    let _ = config
+        .dependencies
+        .insert("gleam_json".into(), Requirement::hex("~> 0.5"));
+    let manifest = Manifest {
+        requirements: config.dependencies.clone(),
+        packages: vec![
+            manifest_package("gleam_http", "3.0.0"),
+            manifest_package("gleam_json", "0.5.0"),
+            manifest_package("gleam_stdlib", "0.25.0"),
+        ],
+    };
+    let releases = [
+        hex_package(
+            "gleam_http",
+            &[
+                ("3.0.0", &[("gleam_stdlib", "~> 0.25.0")]),
+                ("3.1.0", &[("gleam_stdlib", "~> 0.26")]),
+                ("4.0.0", &[]),
+                ("5.0.0-rc1", &[]),
+            ],
+        ),
+        hex_package("gleam_json", &[("0.5.0", &[])]),
+        hex_package(
+            "gleam_stdlib",
+            &[("0.25.0", &[]), ("0.25.1", &[]), ("0.26.0", &[])],
+        ),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        outdated_packages(&config, &manifest, &releases),
+        vec![
+            outdated("gleam_http", "3.0.0", "3.1.0", "4.0.0", true),
+            outdated("gleam_stdlib", "0.25.0", "0.25.1", "0.26.0", false),
+        ]
+    );
+}
+
+#[test]
+fn outdated_text_format() {
+    let packages = vec![
+        outdated("gleam_http", "3.0.0", "3.1.0", "4.0.0", true),
+        outdated("gleam_stdlib", "0.25.0", "0.25.0", "0.26.0", false),
+    ];
+    assert_eq!(
+        text(&packages),
+        "Package       Current  Compatible  Latest
+gleam_http    3.0.0    3.1.0       4.0.0
+gleam_stdlib  0.25.0   0.25.0      0.26.0
+"
+    );
+    assert_eq!(text(&[]), "All dependencies are up to date\n");
+}
+
+#[test]
+fn outdated_json_format() {
+    let packages = vec![outdated("gleam_http", "3.0.0", "3.1.0", "4.0.0", true)];
+    let output: serde_json::Value = serde_json::from_str(&json(&packages)).expect("json");
+    assert_eq!(
+        output,
+        serde_json::json!([{
+            "name": "gleam_http",
+            "current": "3.0.0",
+            "compatible": "3.1.0",
+            "latest": "4.0.0",
+            "direct": true,
+        }])
+    );
+}
@@ -7,17 +7,23 @@ use std::{
 use flate2::{write::GzEncoder, Compression};
 use gleam_core::{
     build::{Mode, Options, Package, Target},
-    config::{PackageConfig, SpdxLicense},
+    config::{ErlangSource, PackageConfig, PublishConfig, SpdxLicense},
     hex, paths, Error, Result,
 };
 use hexpm::version::{Range, Version};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use itertools::Itertools;
 use sha2::Digest;
 
 use crate::{build, cli, docs, fs, hex::ApiKeyCommand, http::HttpClient};
 
-pub fn command(replace: bool, yes: bool) -> Result<()> {
-    PublishCommand::setup(replace, yes)?.run()
+pub fn command(
+    replace: bool,
+    yes: bool,
+    dry_run: bool,
+    organization: Option<String>,
+) -> Result<()> {
+    PublishCommand::setup(replace, yes, dry_run, organization)?.run()
 }
 
 pub struct PublishCommand {
@@ -25,18 +31,28 @@ pub struct PublishCommand {
     package_tarball: Vec<u8>,
     docs_tarball: Vec<u8>,
     replace: bool,
+    organization: Option<String>,
 }
 
 impl PublishCommand {
-    pub fn setup(replace: bool, i_am_sure: bool) -> Result<Self> {
+    /// With `dry_run` the package is built and its files are listed, then the
+    /// process exits without publishing it.
+    pub fn setup(
+        replace: bool,
+        i_am_sure: bool,
+        dry_run: bool,
+        organization: Option<String>,
+    ) -> Result<Self> {
         // Reset the build directory so we know the state of the project
         fs::delete_dir(&paths::build_packages(Mode::Prod, Target::Erlang))?;
 
         // Build the project to check that it is valid
-        let mut compiled = build::main(Options {
+        let (mut compiled, importable_modules, _) = build::importable_modules(Options {
             mode: Mode::Prod,
             target: Some(Target::Erlang),
             perform_codegen: true,
+            warn_dead_code: false,
+            warning_levels: Default::default(),
         })?;
         let config = compiled.config.clone();
 
@@ -49,6 +65,18 @@ impl PublishCommand {
             });
         }
 
+        // Hex packages can only depend on other Hex packages
+        if let Some((package, _)) = config
+            .dependencies
+            .iter()
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .find(|(_, requirement)| requirement.hex_version().is_none())
+        {
+            return Err(Error::PublishNonHexDependency {
+                package: package.clone(),
+            });
+        }
+
         // Build the package release tarball
         let Tarball {
             data: package_tarball,
@@ -56,9 +84,14 @@ impl PublishCommand {
             generated_files_added,
         } = build_hex_tarball(&compiled)?;
 
-        // Build HTML documentation
-        let docs_tarball =
-            fs::create_tar_archive(docs::build_documentation(&config, &mut compiled)?)?;
+        // Build HTML documentation. The documentation of the packages of an
+        // organisation is not on the public HexDocs.
+        let docs_tarball = fs::create_tar_archive(docs::build_documentation(
+            &config,
+            &mut compiled,
+            &importable_modules,
+            organization.is_none(),
+        )?)?;
 
         // Ask user if this is correct
         if !generated_files_added.is_empty() {
@@ -73,6 +106,15 @@ impl PublishCommand {
         }
         println!("\nName: {}", config.name);
         println!("Version: {}", config.version);
+        println!("Size: {}", size(package_tarball.len()));
+        if let Some(organization) = &organization {
+            println!("Organization: {}", organization);
+        }
+
+        if dry_run {
+            println!("\nDry run, not publishing.");
+            std::process::exit(0);
+        }
 
         let should_publish = i_am_sure || {
             let answer = cli::ask("\nDo you wish to publish this package? [y/n]")?;
@@ -88,11 +130,20 @@ impl PublishCommand {
             docs_tarball,
             package_tarball,
             replace,
+            organization,
         })
     }
 }
 
 impl ApiKeyCommand for PublishCommand {
+    fn registry(&self) -> Result<hex::Registry> {
+        Ok(crate::hex::registries(&self.config)?.default().clone())
+    }
+
+    fn organization(&self) -> Option<&str> {
+        self.organization.as_deref()
+    }
+
     fn with_api_key(
         &mut self,
         runtime: &tokio::runtime::Handle,
@@ -120,10 +171,12 @@ impl ApiKeyCommand for PublishCommand {
             &HttpClient::new(),
         ))?;
         cli::print_published(start.elapsed());
-        println!(
-            "\nView your package at https://hex.pm/packages/{}",
-            &self.config.name
-        );
+        if self.config.registry.is_none() {
+            println!(
+                "\nView your package at https://hex.pm/packages/{}",
+                &self.config.name
+            );
+        }
         Ok(())
     }
 }
@@ -136,7 +189,7 @@ struct Tarball {
 
 fn build_hex_tarball(package: &Package) -> Result<Tarball> {
     let generated_files = generated_files(package)?;
-    let src_files = project_files()?;
+    let src_files = project_files(&package.config.publish)?;
     let contents_tar_gz = contents_tarball(&src_files, &generated_files)?;
     let version = "3";
     let metadata = metadata_config(&package.config, &src_files, &generated_files);
@@ -189,7 +242,14 @@ fn metadata_config(
         requirements: config
             .dependencies
             .iter()
-            .map(|(name, requirement)| ReleaseRequirement { name, requirement })
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .filter_map(|(name, requirement)| {
+                Some(ReleaseRequirement {
+                    name,
+                    requirement: requirement.hex_version()?,
+                    repository: requirement.registry(),
+                })
+            })
             .collect(),
         build_tools: vec!["gleam"],
     }
@@ -215,9 +275,11 @@ fn contents_tarball(files: &[PathBuf], data_files: &[(PathBuf, String)]) -> Resu
     Ok(contents_tar_gz)
 }
 
-// TODO: test
 // TODO: Don't include git-ignored native files
-fn project_files() -> Result<Vec<PathBuf>> {
+/// The files of the project to publish: the source code, the private files,
+/// the README and licences, and the files matching the `include` globs of
+/// gleam.toml, without those matching its `exclude` globs.
+fn project_files(config: &PublishConfig) -> Result<Vec<PathBuf>> {
     let src = Path::new("src");
     let mut files: Vec<PathBuf> = fs::gleam_files_excluding_gitignore(src)
         .chain(fs::native_files(src)?)
@@ -244,7 +306,70 @@ fn project_files() -> Result<Vec<PathBuf>> {
     add("NOTICE");
     add("NOTICE.md");
     add("NOTICE.txt");
-    Ok(files)
+
+    // Other files of the project are only published when they are included
+    let candidates: Vec<PathBuf> = if config.include.is_empty() {
+        vec![]
+    } else {
+        fs::private_files_excluding_gitignore(Path::new("."))
+            .map(|path| {
+                path.strip_prefix(".")
+                    .map(Path::to_path_buf)
+                    .unwrap_or(path)
+            })
+            .filter(|path| !path.starts_with("build"))
+            .collect()
+    };
+    select_files(files, candidates, config)
+}
+
+/// The default files along with the candidates that match an `include` glob,
+/// without those that match an `exclude` glob, sorted and deduplicated. The
+/// gleam.toml file is always kept as Hex requires it.
+fn select_files(
+    defaults: Vec<PathBuf>,
+    candidates: Vec<PathBuf>,
+    config: &PublishConfig,
+) -> Result<Vec<PathBuf>> {
+    let include = globs(&config.include)?;
+    let exclude = globs(&config.exclude)?;
+    let matches =
+        |globs: &Gitignore, path: &Path| globs.matched_path_or_any_parents(path, false).is_ignore();
+    Ok(defaults
+        .into_iter()
+        .chain(
+            candidates
+                .into_iter()
+                .filter(|path| matches(&include, path)),
+        )
+        .filter(|path| path == Path::new("gleam.toml") || !matches(&exclude, path))
+        .sorted()
+        .dedup()
+        .collect())
+}
+
+fn globs(globs: &[String]) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new("");
+    for glob in globs {
+        let _ = builder
+            .add_line(None, glob)
+            .map_err(|error| Error::InvalidPublishGlob {
+                glob: glob.clone(),
+                error: error.to_string(),
+            })?;
+    }
+    builder.build().map_err(|error| Error::InvalidPublishGlob {
+        glob: globs.join(", "),
+        error: error.to_string(),
+    })
+}
+
+fn size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    }
 }
 
 // TODO: test
@@ -259,18 +384,21 @@ fn generated_files(package: &Package) -> Result<Vec<(PathBuf, String)>> {
     let tar_src = Path::new("src");
     let tar_include = Path::new("include");
 
-    // Erlang modules
+    // Erlang or Elixir modules
     for module in &package.modules {
         if module.is_test() {
             continue;
         }
-        let name = module.compiled_erlang_path();
+        let name = match package.config.erlang.source {
+            ErlangSource::Erlang => module.compiled_erlang_path(),
+            ErlangSource::Elixir => module.compiled_elixir_path(),
+        };
         files.push((tar_src.join(&name), fs::read(build.join(name))?));
     }
 
     // Erlang headers
     if include.is_dir() {
-        for file in fs::erlang_files(&include)? {
+        for file in fs::erlang_files(&include)?.sorted() {
             let name = file.file_name().expect("generated_files include file name");
             files.push((tar_include.join(name), fs::read(file)?));
         }
@@ -291,15 +419,15 @@ where
 {
     let path = path.as_ref();
     tracing::info!(file=?path, "Adding file to tarball");
-    let mut header = tar::Header::new_gnu();
-    header.set_mode(0o600);
-    header.set_size(data.len() as u64);
-    header.set_cksum();
+    let mut header = fs::tar_header(0o600, data.len());
     tarball
         .append_data(&mut header, path, data)
         .map_err(|e| Error::add_tar(path, e))
 }
 
+/// Files of the project are added with the same metadata whoever publishes
+/// them and whenever they were last modified, so that the tarball is the same
+/// for the same source code.
 fn add_path_to_tar<P, W>(tarball: &mut tar::Builder<W>, path: P) -> Result<()>
 where
     P: AsRef<Path>,
@@ -307,8 +435,10 @@ where
 {
     let path = path.as_ref();
     tracing::info!(file=?path, "Adding file to tarball");
+    let data = fs::read_bytes(path)?;
+    let mut header = fs::tar_header(0o644, data.len());
     tarball
-        .append_path(path)
+        .append_data(&mut header, path, data.as_slice())
         .map_err(|e| Error::add_tar(path, e))
 }
 
@@ -381,8 +511,8 @@ struct ReleaseRequirement<'a> {
     name: &'a str,
     // optional: bool,
     requirement: &'a Range,
-    // Support alternate repositories at a later date.
-    // repository: String,
+    /// The registry of the dependency, if it is not that of the package.
+    repository: Option<&'a str>,
 }
 impl<'a> ReleaseRequirement<'a> {
     pub fn as_erlang(&self) -> String {
@@ -390,11 +520,16 @@ impl<'a> ReleaseRequirement<'a> {
             r#"
   {{<<"{app}">>, [
     {{<<"app">>, <<"{app}">>}},
-    {{<<"optional">>, false}},
+    {{<<"optional">>, false}},{repository}
     {{<<"requirement">>, <<"{requirement}">>}}
   ]}}"#,
             app = self.name,
             requirement = self.requirement,
+            repository = match self.repository {
+                Some(repository) =>
+                    format!("\n    {{<<\"repository\">>, <<\"{}\">>}},", repository),
+                None => String::new(),
+            },
         )
     }
 }
@@ -434,10 +569,12 @@ fn release_metadata_as_erlang() {
             ReleaseRequirement {
                 name: "wibble",
                 requirement: &req1,
+                repository: None,
             },
             ReleaseRequirement {
                 name: "wobble",
                 requirement: &req2,
+                repository: Some("acme"),
             },
         ],
         build_tools: vec!["gleam", "rebar3"],
@@ -463,6 +600,7 @@ fn release_metadata_as_erlang() {
   {<<"wobble">>, [
     {<<"app">>, <<"wobble">>},
     {<<"optional">>, false},
+    {<<"repository">>, <<"acme">>},
     {<<"requirement">>, <<"~> 1.2">>}
   ]}
 ]}.
@@ -479,6 +617,40 @@ fn release_metadata_as_erlang() {
     );
 }
 
+#[test]
+fn select_publish_files() {
+    let config = PublishConfig {
+        include: vec![
+            "examples/**/*.gleam".to_string(),
+            "CHANGELOG.md".to_string(),
+        ],
+        exclude: vec!["src/internal".to_string(), "*.toml".to_string()],
+    };
+    let defaults = vec![
+        PathBuf::from("src/app.gleam"),
+        PathBuf::from("src/internal/secret.gleam"),
+        PathBuf::from("gleam.toml"),
+        PathBuf::from("README.md"),
+    ];
+    let candidates = vec![
+        PathBuf::from("CHANGELOG.md"),
+        PathBuf::from("README.md"),
+        PathBuf::from("examples/basic/main.gleam"),
+        PathBuf::from("examples/basic/notes.txt"),
+        PathBuf::from("test/app_test.gleam"),
+    ];
+    assert_eq!(
+        select_files(defaults, candidates, &config).unwrap(),
+        [
+            PathBuf::from("CHANGELOG.md"),
+            PathBuf::from("README.md"),
+            PathBuf::from("examples/basic/main.gleam"),
+            PathBuf::from("gleam.toml"),
+            PathBuf::from("src/app.gleam"),
+        ]
+    );
+}
+
 pub fn get_hostname() -> String {
     hostname::get()
         .expect("Looking up hostname")
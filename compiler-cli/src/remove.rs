@@ -0,0 +1,91 @@
+use gleam_core::{io::FileSystemWriter, paths, Error, Result};
+
+use crate::{cli, dependencies::UseManifest, fs::ProjectIO};
+
+pub fn command(packages: Vec<String>) -> Result<()> {
+    // Read gleam.toml so we can remove the deps from it
+    let mut toml = crate::config::root_config_document()?;
+
+    // Remove the deps from whichever of the sections they are in
+    remove_from_config(&mut toml, &packages)?;
+
+    // Resolve the dependencies of the updated config so that the removed
+    // packages, and any packages only they depended upon, are removed from
+    // the manifest. gleam.toml is left as it was if this fails.
+    let config = crate::config::root_config_from_document(&toml)?;
+    let _ = crate::dependencies::download_for_config(
+        cli::Reporter::new(),
+        config,
+        None,
+        UseManifest::Yes,
+    )?;
+
+    // Write the updated config
+    ProjectIO::new().write(&paths::root_config(), &toml.to_string())?;
+
+    for package in packages {
+        cli::print_removed(&package);
+    }
+
+    Ok(())
+}
+
+/// Remove packages from the dependencies and dev-dependencies of a
+/// gleam.toml document, returning an error naming any that are in neither.
+fn remove_from_config(toml: &mut toml_edit::Document, packages: &[String]) -> Result<()> {
+    let mut unknown = vec![];
+    for package in packages {
+        let removed = ["dependencies", "dev-dependencies"]
+            .into_iter()
+            .filter_map(|section| toml.get_mut(section)?.as_table_like_mut()?.remove(package))
+            .count();
+        if removed == 0 {
+            unknown.push(package.clone());
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(Error::UnknownDependencies { packages: unknown });
+    }
+    Ok(())
+}
+
+#[test]
+fn remove_from_config_keeps_formatting() {
+    let mut toml = r#"name = "app"
+
+# The packages used by the app
+[dependencies]
+gleam_stdlib = "~> 0.25"
+gleam_http = "~> 3.1"
+
+[dev-dependencies]
+gleeunit = "~> 0.7"
+"#
+    .parse::<toml_edit::Document>()
+    .expect("gleam.toml");
+    remove_from_config(&mut toml, &["gleam_http".into(), "gleeunit".into()]).expect("remove");
+    assert_eq!(
+        toml.to_string(),
+        r#"name = "app"
+
+# The packages used by the app
+[dependencies]
+gleam_stdlib = "~> 0.25"
+
+[dev-dependencies]
+"#
+    );
+}
+
+#[test]
+fn remove_from_config_unknown_packages() {
+    let mut toml = "[dependencies]\ngleam_stdlib = \"~> 0.25\"\n"
+        .parse::<toml_edit::Document>()
+        .expect("gleam.toml");
+    assert_eq!(
+        remove_from_config(&mut toml, &["gleam_stdlib".into(), "wibble".into()]),
+        Err(Error::UnknownDependencies {
+            packages: vec!["wibble".into()]
+        })
+    );
+}
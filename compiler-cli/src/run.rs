@@ -1,55 +1,331 @@
 use gleam_core::{
-    build::{Mode, Options, Target},
+    build::{self, Mode, Options, Package, Target, TestFunction},
     config::PackageConfig,
-    error::Error,
-    io::{CommandExecutor, Stdio},
+    error::{Error, FileIoAction, FileKind},
+    io::{CommandExecutor, FileSystemReader, FileSystemWriter, Stdio},
     paths,
 };
+use itertools::Itertools;
+use std::path::{Path, PathBuf};
 
 use crate::fs::ProjectIO;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Which {
     Src,
     Test,
+    /// The test functions of the root package, run by the test runner of the
+    /// compiler rather than by the `main` function of its test module.
+    TestFunctions {
+        filter: Option<String>,
+        parallel: usize,
+        /// Whether to run the examples in the documentation of the root
+        /// package rather than its test functions.
+        doc: bool,
+    },
 }
 
-pub fn command(arguments: Vec<String>, target: Option<Target>, which: Which) -> Result<(), Error> {
+/// The environment variables are set for the program run, but not for the
+/// build.
+pub fn command(
+    arguments: Vec<String>,
+    environment: &[(String, String)],
+    target: Option<Target>,
+    which: Which,
+) -> Result<(), Error> {
     let config = crate::config::root_config()?;
 
-    // Determine which module to run
-    let module = match which {
-        Which::Src => config.name.to_string(),
-        Which::Test => format!("{}_test", &config.name),
-    };
+    // Determine which modules to run
+    let modules = modules_to_run(&config, &which)?;
 
     // Build project so we have bytecode to run
-    let _ = crate::build::main(Options {
+    let options = Options {
         perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
         mode: Mode::Dev,
         target,
-    })?;
+    };
+    let package = match &which {
+        Which::TestFunctions { doc: true, .. } => crate::build::with_doctests(options)?,
+        _ => crate::build::main(options)?,
+    };
 
     // Don't exit on ctrl+c as it is used by child erlang shell
     ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler");
 
-    crate::cli::print_running(&format!("{}.main", module));
+    if let Which::TestFunctions {
+        filter,
+        parallel,
+        doc,
+    } = &which
+    {
+        let status = run_test_functions(
+            &package,
+            target,
+            filter,
+            *parallel,
+            *doc,
+            arguments,
+            environment,
+        )?;
+        std::process::exit(status);
+    }
+
+    // Check the module can be run before handing it over to the runtime
+    check_main_functions(&package, &modules)?;
+
+    let mut status = 0;
+    for (package, module) in &modules {
+        let module_status = run_main(
+            &config,
+            package,
+            target,
+            module,
+            arguments.clone(),
+            environment,
+            None,
+        )?;
+        if status == 0 {
+            status = module_status;
+        }
+    }
+    std::process::exit(status);
+}
+
+/// Build and run the project, then do so again whenever a source file
+/// changes. Unlike `command` an interrupt stops watching, so it is not caught.
+pub fn watch(
+    arguments: Vec<String>,
+    environment: &[(String, String)],
+    target: Option<Target>,
+    which: Which,
+) -> Result<(), Error> {
+    let config = crate::config::root_config()?;
+    let modules = modules_to_run(&config, &which)?;
+
+    let options = Options {
+        perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode: Mode::Dev,
+        target,
+    };
+    crate::watch::build(options, |package| {
+        if let Which::TestFunctions {
+            filter,
+            parallel,
+            doc,
+        } = &which
+        {
+            let status = run_test_functions(
+                package,
+                target,
+                filter,
+                *parallel,
+                *doc,
+                arguments.clone(),
+                environment,
+            )?;
+            if status != 0 {
+                crate::cli::print_exited(status);
+            }
+            return Ok(());
+        }
+        check_main_functions(package, &modules)?;
+        for (package, module) in &modules {
+            let status = run_main(
+                &config,
+                package,
+                target,
+                module,
+                arguments.clone(),
+                environment,
+                None,
+            )?;
+            if status != 0 {
+                crate::cli::print_exited(status);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// The modules to run, with the packages they belong to. The tests of the
+/// workspace members are run along with those of the root package, which a
+/// workspace need not have.
+fn modules_to_run(config: &PackageConfig, which: &Which) -> Result<Vec<(String, String)>, Error> {
+    let root_test = format!("{}_test", config.name);
+    let root = match which {
+        Which::Src => return Ok(vec![(config.name.clone(), config.name.clone())]),
+        Which::Test => (config.name.clone(), root_test.clone()),
+        Which::TestFunctions { .. } => return Ok(vec![]),
+    };
+
+    let mut modules = vec![];
+    for member in crate::config::workspace_members(config)? {
+        let module = format!("{}_test", member.config.name);
+        let path = member.root.join("test").join(format!("{}.gleam", module));
+        if path.is_file() {
+            modules.push((member.config.name, module));
+        }
+    }
+
+    let root_test_path = paths::test().join(format!("{}.gleam", root_test));
+    if modules.is_empty() || root_test_path.is_file() {
+        modules.insert(0, root);
+    }
+    Ok(modules)
+}
+
+/// Only the modules of the root package are returned by the build, so only
+/// they are checked. The test modules of the workspace members are known to
+/// exist.
+fn check_main_functions(package: &Package, modules: &[(String, String)]) -> Result<(), Error> {
+    for (name, module) in modules {
+        if name == &package.config.name {
+            build::check_main_function(&package.modules, module)?;
+        }
+    }
+    Ok(())
+}
+
+/// Run the test functions of the root package that match the filter, or
+/// those of the examples in its documentation, with the test runner of the
+/// target.
+fn run_test_functions(
+    package: &Package,
+    target: Option<Target>,
+    filter: &Option<String>,
+    parallel: usize,
+    doc: bool,
+    arguments: Vec<String>,
+    environment: &[(String, String)],
+) -> Result<i32, Error> {
+    let env = environment
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect_vec();
+    let tests = build::test_functions(&package.modules, filter.as_deref())
+        .into_iter()
+        .filter(|test| test.module.starts_with(build::DOCTEST_MODULE_PREFIX) == doc)
+        .collect_vec();
+    if let (true, Some(filter)) = (tests.is_empty(), filter) {
+        return Err(Error::NoMatchingTests {
+            filter: filter.clone(),
+        });
+    }
+    crate::cli::print_running(&format!("{} tests", tests.len()));
+    let parallel = parallel.max(1);
+    let config = &package.config;
+    match target.unwrap_or(config.target) {
+        Target::Erlang => {
+            let tests = tests
+                .iter()
+                .map(|test| {
+                    format!(
+                        "{{'{}', '{}', <<\"{}\"/utf8>>}}",
+                        test.module.replace('/', "@"),
+                        test.function,
+                        test.name()
+                    )
+                })
+                .join(", ");
+            let call = format!("gleam@@main:run_tests([{}], {})", tests, parallel);
+            run_erlang_eval(call, arguments, &env, None)
+        }
+        Target::JavaScript => run_javascript_tests(&config.name, &tests, parallel, arguments, &env),
+        Target::Native => Err(Error::CPlusPlusCompilationNotImplemented),
+        Target::Go => Err(Error::GoCommandUnsupported {
+            command: "test".into(),
+        }),
+        Target::Llvm => Err(Error::LlvmCommandUnsupported {
+            command: "test".into(),
+        }),
+        Target::Wasm => Err(Error::WasmCommandUnsupported {
+            command: "test".into(),
+        }),
+    }
+}
 
-    // Run the command
-    let status = match target.unwrap_or(config.target) {
-        Target::Erlang => run_erlang(&module, arguments),
-        Target::JavaScript => run_javascript(&config, &module, arguments),
+/// The project is in the current directory unless another root is given, in
+/// which case the program is still run in the current directory.
+fn run_main(
+    config: &PackageConfig,
+    package: &str,
+    target: Option<Target>,
+    module: &str,
+    arguments: Vec<String>,
+    environment: &[(String, String)],
+    root: Option<&Path>,
+) -> Result<i32, Error> {
+    crate::cli::print_running(&format!("{}.main", module));
+    let env = environment
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect_vec();
+    match target.unwrap_or(config.target) {
+        Target::Erlang => run_erlang(module, arguments, &env, root),
+        Target::JavaScript => run_javascript(package, module, arguments, &env, root),
         Target::Native => Err(Error::CPlusPlusCompilationNotImplemented),
-    }?;
+        Target::Go => run_go(module, arguments, &env, root),
+        Target::Llvm => run_llvm(module, arguments, &env, root),
+        Target::Wasm => Err(Error::WasmCommandUnsupported {
+            command: "run".into(),
+        }),
+    }
+}
 
+/// Build and bundle the project, then serve the bundle with the configured
+/// serve command rather than running it with Node.
+pub fn serve_bundle(
+    target: Option<Target>,
+    arguments: &[String],
+    environment: &[(String, String)],
+) -> Result<(), Error> {
+    let target = crate::bundle::target(target)?;
+    let config = crate::config::root_config()?;
+
+    let _ = crate::build::main(Options {
+        perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode: Mode::Dev,
+        target: Some(target),
+    })?;
+    crate::bundle::bundle(&config)?;
+
+    // Don't exit on ctrl+c as it is used to stop the server
+    ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler");
+
+    let status = crate::bundle::serve(&config, arguments, environment)?;
     std::process::exit(status);
 }
 
-fn run_erlang(module: &str, arguments: Vec<String>) -> Result<i32, Error> {
+fn run_erlang(
+    module: &str,
+    arguments: Vec<String>,
+    env: &[(&str, String)],
+    root: Option<&Path>,
+) -> Result<i32, Error> {
+    run_erlang_eval(
+        format!("gleam@@main:run({})", &module),
+        arguments,
+        env,
+        root,
+    )
+}
+
+fn run_erlang_eval(
+    expression: String,
+    arguments: Vec<String>,
+    env: &[(&str, String)],
+    root: Option<&Path>,
+) -> Result<i32, Error> {
     let mut args = vec![];
 
     // Specify locations of .beam files
-    let packages = paths::build_packages(Mode::Dev, Target::Erlang);
+    let packages = within(root, paths::build_packages(Mode::Dev, Target::Erlang));
 
     for entry in crate::fs::read_dir(&packages)?.filter_map(Result::ok) {
         args.push("-pa".into());
@@ -57,7 +333,7 @@ fn run_erlang(module: &str, arguments: Vec<String>) -> Result<i32, Error> {
     }
 
     args.push("-eval".into());
-    args.push(format!("gleam@@main:run({})", &module));
+    args.push(expression);
 
     // Don't run the Erlang shell
     args.push("-noshell".into());
@@ -68,30 +344,293 @@ fn run_erlang(module: &str, arguments: Vec<String>) -> Result<i32, Error> {
         args.push(argument);
     }
 
-    ProjectIO::new().exec("erl", &args, &[], None, Stdio::Inherit)
+    ProjectIO::new().exec("erl", &args, env, None, Stdio::Inherit)
 }
 
 fn run_javascript(
-    config: &PackageConfig,
+    package: &str,
     module: &str,
     arguments: Vec<String>,
+    env: &[(&str, String)],
+    root: Option<&Path>,
 ) -> Result<i32, Error> {
     let mut args = vec![];
 
-    let module = paths::build_package(Mode::Dev, Target::JavaScript, &config.name).join(module);
+    let module = within(
+        root,
+        paths::build_package(Mode::Dev, Target::JavaScript, package).join(module),
+    );
 
     // Run the main function.
     args.push("-e".into());
     args.push(format!(
-        "import('./{}.mjs').then(module => module.main())",
-        module.to_string_lossy()
+        "import({}).then(module => module.main())",
+        javascript_string(&import_specifier(&module.with_extension("mjs")))
     ));
 
-    // Tell Node that any following argument are for the program
+    // Tell Node that any following argument are for the program. The module
+    // comes first, so that `process.argv` is as though it were run directly.
+    args.push("--".into());
+    args.push(module.with_extension("mjs").to_string_lossy().to_string());
+    for argument in arguments.into_iter() {
+        args.push(argument);
+    }
+
+    ProjectIO::new().exec("node", &args, env, None, Stdio::Inherit)
+}
+
+/// Parses an environment variable given to `gleam run --env` as `KEY=VALUE`.
+/// The value may itself contain `=`.
+pub fn parse_environment_variable(text: &str) -> Result<(String, String), String> {
+    match text.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE but got `{}`", text)),
+    }
+}
+
+#[test]
+fn parse_environment_variable_test() {
+    assert_eq!(
+        parse_environment_variable("PORT=8080"),
+        Ok(("PORT".into(), "8080".into()))
+    );
+    assert_eq!(
+        parse_environment_variable("QUERY=a=b"),
+        Ok(("QUERY".into(), "a=b".into()))
+    );
+    assert_eq!(
+        parse_environment_variable("EMPTY="),
+        Ok(("EMPTY".into(), "".into()))
+    );
+    assert!(parse_environment_variable("PORT").is_err());
+    assert!(parse_environment_variable("=8080").is_err());
+}
+
+/// The packages of the project share one Go module, to which a `main` package
+/// that calls the module's `main` function is added.
+fn run_go(
+    module: &str,
+    arguments: Vec<String>,
+    env: &[(&str, String)],
+    root: Option<&Path>,
+) -> Result<i32, Error> {
+    let io = ProjectIO::new();
+    let directory = within(root, paths::build_packages(Mode::Dev, Target::Go));
+    io.write(
+        &directory.join("main.go"),
+        &gleam_core::go::entrypoint(module),
+    )?;
+
+    let mut args = vec![
+        "run".into(),
+        "-C".into(),
+        directory.to_string_lossy().to_string(),
+        ".".into(),
+    ];
+    for argument in arguments.into_iter() {
+        args.push(argument);
+    }
+
+    io.exec("go", &args, env, None, Stdio::Inherit)
+}
+
+/// The IR of every package is linked with the prelude and a module defining
+/// the C `main` function, and compiled to a static executable which is then
+/// run.
+fn run_llvm(
+    module: &str,
+    arguments: Vec<String>,
+    env: &[(&str, String)],
+    root: Option<&Path>,
+) -> Result<i32, Error> {
+    let io = ProjectIO::new();
+    let directory = within(root, paths::build_packages(Mode::Dev, Target::Llvm));
+    io.write(
+        &directory.join("main.ll"),
+        &gleam_core::llvm::entrypoint(module),
+    )?;
+    let bitcode = directory.join("program.bc");
+    let object = directory.join("program.o");
+    let program = directory.join("program");
+    let path = |path: &Path| path.to_string_lossy().to_string();
+
+    let mut args = vec!["-o".into(), path(&bitcode)];
+    args.extend(
+        crate::fs::llvm_files(&directory)
+            .iter()
+            .map(|file| path(file)),
+    );
+    let status = io.exec("llvm-link", &args, &[], None, Stdio::Inherit)?;
+    if status != 0 {
+        return Ok(status);
+    }
+
+    let args = [
+        "-filetype=obj".into(),
+        "-relocation-model=pic".into(),
+        "-o".into(),
+        path(&object),
+        path(&bitcode),
+    ];
+    let status = io.exec("llc", &args, &[], None, Stdio::Inherit)?;
+    if status != 0 {
+        return Ok(status);
+    }
+
+    let args = ["-static".into(), "-o".into(), path(&program), path(&object)];
+    let status = io.exec("cc", &args, &[], None, Stdio::Inherit)?;
+    if status != 0 {
+        return Ok(status);
+    }
+
+    io.exec(&path(&program), &arguments, env, None, Stdio::Inherit)
+}
+
+/// The test runner is written next to the compiled modules of the package so
+/// that it can import them.
+fn run_javascript_tests(
+    package: &str,
+    tests: &[TestFunction],
+    parallel: usize,
+    arguments: Vec<String>,
+    env: &[(&str, String)],
+) -> Result<i32, Error> {
+    let directory = paths::build_package(Mode::Dev, Target::JavaScript, package);
+    let runner = directory.join("gleam@@test.mjs");
+    let io = ProjectIO::new();
+    io.write(&runner, gleam_core::javascript::TEST_RUNNER)?;
+
+    let mut args = vec![];
+    args.push("-e".into());
+    args.push(javascript_tests_call(&runner, tests, parallel));
+
+    // Tell Node that any following argument are for the program. The runner
+    // comes first, so that `process.argv` is as though it were run directly.
     args.push("--".into());
+    args.push(runner.to_string_lossy().to_string());
     for argument in arguments.into_iter() {
         args.push(argument);
     }
 
-    ProjectIO::new().exec("node", &args, &[], None, Stdio::Inherit)
+    io.exec("node", &args, env, None, Stdio::Inherit)
+}
+
+/// The JavaScript that imports the test runner and runs the tests with it.
+/// Each test is given by its name and the path and function name it is
+/// imported with.
+fn javascript_tests_call(runner: &Path, tests: &[TestFunction], parallel: usize) -> String {
+    let tests = tests
+        .iter()
+        .map(|test| {
+            format!(
+                "[{}, {}, {}]",
+                javascript_string(&test.name()),
+                javascript_string(&format!("./{}.mjs", test.module)),
+                javascript_string(&test.function)
+            )
+        })
+        .join(", ");
+    format!(
+        "import({}).then(runner => runner.run([{}], {}))",
+        javascript_string(&import_specifier(runner)),
+        tests,
+        parallel
+    )
+}
+
+/// A JavaScript string literal of the text. JSON strings are valid JavaScript
+/// strings, unlike the escapes Rust uses when debug printing a string.
+fn javascript_string(text: &str) -> String {
+    serde_json::to_string(text).expect("JavaScript string")
+}
+
+/// Build and run a project that is not in the current directory, such as the
+/// one made for a script, with the program run in the current directory.
+pub fn project_in(
+    root: &Path,
+    target: Option<Target>,
+    arguments: Vec<String>,
+    environment: &[(String, String)],
+) -> Result<i32, Error> {
+    let invocation_directory = ProjectIO::new().current_dir()?;
+    let root = invocation_directory.join(root);
+    set_current_dir(&root)?;
+    let config = crate::config::root_config()?;
+    let built = crate::build::main(Options {
+        perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode: Mode::Dev,
+        target,
+    });
+    set_current_dir(&invocation_directory)?;
+    let package = built?;
+
+    // Don't exit on ctrl+c as it is used by child erlang shell
+    ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler");
+
+    build::check_main_function(&package.modules, &config.name)?;
+    run_main(
+        &config,
+        &config.name,
+        target,
+        &config.name,
+        arguments,
+        environment,
+        Some(&root),
+    )
+}
+
+fn set_current_dir(path: &Path) -> Result<(), Error> {
+    std::env::set_current_dir(path).map_err(|e| Error::FileIo {
+        action: FileIoAction::Open,
+        kind: FileKind::Directory,
+        path: path.to_path_buf(),
+        err: Some(e.to_string()),
+    })
+}
+
+fn within(root: Option<&Path>, path: PathBuf) -> PathBuf {
+    match root {
+        Some(root) => root.join(path),
+        None => path,
+    }
+}
+
+/// The specifier with which Node imports the module at the path, which is
+/// either relative to the current directory or absolute.
+fn import_specifier(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else if Path::new(&path).is_absolute() {
+        format!("file:///{}", path)
+    } else {
+        format!("./{}", path)
+    }
+}
+
+#[test]
+fn import_specifier_test() {
+    assert_eq!(
+        import_specifier(Path::new("build/dev/javascript/app/app.mjs")),
+        "./build/dev/javascript/app/app.mjs"
+    );
+    #[cfg(unix)]
+    assert_eq!(
+        import_specifier(Path::new("/tmp/app/app.mjs")),
+        "file:///tmp/app/app.mjs"
+    );
+}
+
+#[test]
+fn javascript_tests_call_test() {
+    let tests = [TestFunction {
+        module: "app/😀_test".into(),
+        function: r#"it's_"quoted"_test"#.into(),
+    }];
+    assert_eq!(
+        javascript_tests_call(Path::new("build/gleam@@test.mjs"), &tests, 2),
+        r#"import("./build/gleam@@test.mjs").then(runner => runner.run([["app/😀_test.it's_\"quoted\"_test", "./app/😀_test.mjs", "it's_\"quoted\"_test"]], 2))"#
+    );
 }
@@ -0,0 +1,155 @@
+//! Running a single Gleam file as a script with `gleam run path/to/script.gleam`
+//! outside of a project.
+//!
+//! A project is made for the script in the temporary directory, depending on
+//! the standard library and on any packages listed in `//// deps:` comments
+//! of the script, such as:
+//!
+//! ```gleam
+//! //// deps: gleam_json, gleam_http ~> 3.0
+//! ```
+//!
+//! The project of a script is kept between runs so that its dependencies are
+//! only downloaded and compiled once.
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use crate::fs::ProjectIO;
+use gleam_core::{
+    build::Target,
+    error::Error,
+    io::{FileSystemReader, FileSystemWriter},
+    paths,
+};
+
+const DEPENDENCIES_PREFIX: &str = "//// deps:";
+
+/// Whether `gleam run` has been given a script to run rather than arguments
+/// for the project in the current directory.
+pub fn is_script(arguments: &[String]) -> bool {
+    match arguments.first() {
+        Some(path) => {
+            let io = ProjectIO::new();
+            path.ends_with(".gleam")
+                && io.is_file(Path::new(path))
+                && !io.is_file(&paths::root_config())
+        }
+        None => false,
+    }
+}
+
+pub fn run(
+    target: Option<Target>,
+    arguments: Vec<String>,
+    environment: &[(String, String)],
+) -> Result<(), Error> {
+    let mut arguments = arguments.into_iter();
+    let script = PathBuf::from(arguments.next().unwrap_or_default());
+    let io = ProjectIO::new();
+    let src = io.read(&script)?;
+    let name = script
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    crate::new::validate_name(&name)?;
+
+    let root = project_directory(&io, &script, &name);
+    io.mkdir(&root.join("src"))?;
+    io.write(
+        &root.join(paths::root_config()),
+        &package_config(&name, &dependencies(&src)),
+    )?;
+    io.write(&root.join("src").join(format!("{}.gleam", name)), &src)?;
+
+    let status = crate::run::project_in(&root, target, arguments.collect(), environment)?;
+    std::process::exit(status);
+}
+
+/// Each script has a directory of its own, named after the absolute path of
+/// the script as well as its name so that scripts with the same name do not
+/// share a project.
+fn project_directory(io: &impl FileSystemReader, script: &Path, name: &str) -> PathBuf {
+    let script = io
+        .current_dir()
+        .map(|directory| directory.join(script))
+        .unwrap_or_else(|_| script.to_path_buf());
+    let mut hasher = DefaultHasher::new();
+    script.hash(&mut hasher);
+    std::env::temp_dir()
+        .join("gleam-scripts")
+        .join(format!("{}-{:016x}", name, hasher.finish()))
+}
+
+/// The packages listed in the `//// deps:` comments of the script, each with
+/// the versions that may be used. The standard library is always included.
+fn dependencies(src: &str) -> BTreeMap<String, String> {
+    let mut dependencies = BTreeMap::new();
+    let _ = dependencies.insert(
+        "gleam_stdlib".to_string(),
+        format!("~> {}", crate::new::GLEAM_STDLIB_VERSION),
+    );
+    let listed = src
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(DEPENDENCIES_PREFIX))
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|dependency| !dependency.is_empty());
+    for dependency in listed {
+        let (name, requirement) = match dependency.split_once(char::is_whitespace) {
+            Some((name, requirement)) => (name, requirement.trim()),
+            None => (dependency, ">= 0.0.0"),
+        };
+        let _ = dependencies.insert(name.to_string(), requirement.to_string());
+    }
+    dependencies
+}
+
+fn package_config(name: &str, dependencies: &BTreeMap<String, String>) -> String {
+    let mut config = format!(
+        "name = \"{}\"\nversion = \"0.1.0\"\n\n[dependencies]\n",
+        name
+    );
+    for (name, requirement) in dependencies {
+        config.push_str(&format!("{} = \"{}\"\n", name, requirement));
+    }
+    config
+}
+
+#[test]
+fn script_dependencies() {
+    let src = "//// A script\n//// deps: gleam_json, gleam_http ~> 3.0\n//// deps: gleam_stdlib >= 0.20.0 and < 1.0.0\n\npub fn main() { 1 }\n";
+    assert_eq!(
+        package_config("script", &dependencies(src)),
+        r#"name = "script"
+version = "0.1.0"
+
+[dependencies]
+gleam_http = "~> 3.0"
+gleam_json = ">= 0.0.0"
+gleam_stdlib = ">= 0.20.0 and < 1.0.0"
+"#
+    );
+}
+
+#[test]
+fn script_without_dependencies() {
+    let dependencies = dependencies("pub fn main() { 1 }\n");
+    assert_eq!(dependencies.keys().collect::<Vec<_>>(), ["gleam_stdlib"]);
+}
+
+#[test]
+fn script_project_directory_is_named_after_the_absolute_path() {
+    let io = gleam_core::io::memory::InMemoryFileSystem::new();
+    assert_eq!(
+        project_directory(&io, Path::new("scripts/hello.gleam"), "hello"),
+        project_directory(&io, Path::new("/scripts/hello.gleam"), "hello"),
+    );
+    assert_ne!(
+        project_directory(&io, Path::new("/scripts/hello.gleam"), "hello"),
+        project_directory(&io, Path::new("/other/hello.gleam"), "hello"),
+    );
+}
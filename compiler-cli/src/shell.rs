@@ -1,14 +1,105 @@
 use gleam_core::{
     build::{Mode, Options, Target},
-    error::Error,
+    error::{Error, StandardIoAction},
+    io::{FileSystemReader, FileSystemWriter},
     paths,
+    repl::{self, Evaluation},
 };
-use std::process::Command;
+use std::{
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use crate::fs::ProjectIO;
+
+const LINE_LENGTH: isize = 80;
+const VALUE_MARKER: &str = "gleam@@repl:value:";
+const ERROR_MARKER: &str = "gleam@@repl:error";
+
+/// Start an interactive Gleam shell. Each expression entered is type checked
+/// against the project, then compiled and evaluated by the runtime of the
+/// target, and its value is printed along with its type.
+pub fn command(target: Option<Target>) -> Result<(), Error> {
+    let config = crate::config::root_config()?;
+    let target = target.unwrap_or(config.target);
+    match target {
+        Target::Native => return Err(Error::CPlusPlusCompilationNotImplemented),
+        Target::Go => {
+            return Err(Error::GoCommandUnsupported {
+                command: "shell".into(),
+            })
+        }
+        Target::Llvm => {
+            return Err(Error::LlvmCommandUnsupported {
+                command: "shell".into(),
+            })
+        }
+        Target::Wasm => {
+            return Err(Error::WasmCommandUnsupported {
+                command: "shell".into(),
+            })
+        }
+        Target::Erlang | Target::JavaScript => (),
+    }
 
-pub fn command() -> Result<(), Error> {
+    let (_, modules, ids) = crate::build::importable_modules(Options {
+        perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
+        mode: Mode::Dev,
+        target: Some(target),
+    })?;
+
+    // Don't exit on ctrl+c as it is used by the child runtime
+    ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler");
+
+    // The modules of the inputs are written next to the modules of the
+    // project, so that they can import them
+    let package = paths::build_package(Mode::Dev, target, &config.name);
+    let directory = package.join(repl::MODULE_DIRECTORY);
+    crate::fs::delete_dir(&directory)?;
+    crate::fs::mkdir(&directory)?;
+
+    let mut runtime = start_runtime(&package, target)?;
+    let mut runtime_stdin = runtime.stdin.take().expect("Runtime stdin");
+    let mut runtime_stdout = BufReader::new(runtime.stdout.take().expect("Runtime stdout"));
+
+    crate::cli::print_running("Gleam shell, enter :quit to exit");
+    let mut session = repl::Session::new();
+    while let Some(input) = read_input()? {
+        let input = input.trim();
+        if input == ":quit" {
+            break;
+        }
+        if input.is_empty() {
+            continue;
+        }
+        match session.compile(input, target, &config.name, &ids, &modules) {
+            Ok(None) => (),
+            Ok(Some(evaluation)) => evaluate(
+                &package,
+                target,
+                &evaluation,
+                &mut runtime_stdin,
+                &mut runtime_stdout,
+            )?,
+            Err(error) => crate::print_error(&error),
+        }
+    }
+
+    drop(runtime_stdin);
+    let _ = runtime.wait();
+    Ok(())
+}
+
+/// Start a plain Erlang shell with the modules of the project loaded.
+pub fn erlang() -> Result<(), Error> {
     // Build project
     let _ = crate::build::main(Options {
         perform_codegen: true,
+        warn_dead_code: false,
+        warning_levels: Default::default(),
         mode: Mode::Dev,
         target: Some(Target::Erlang),
     })?;
@@ -38,3 +129,174 @@ pub fn command() -> Result<(), Error> {
     })?;
     Ok(())
 }
+
+/// Start the runtime of the target, which evaluates the module at each path
+/// written to its stdin.
+fn start_runtime(package: &Path, target: Target) -> Result<Child, Error> {
+    let io = ProjectIO::new();
+    let (program, mut command) = match target {
+        Target::Erlang => {
+            let mut command = Command::new("erl");
+            let packages = paths::build_packages(Mode::Dev, Target::Erlang);
+            for entry in io.read_dir(&packages)?.into_iter().filter_map(Result::ok) {
+                let _ = command.arg("-pa").arg(entry.as_path().join("ebin"));
+            }
+            let _ = command
+                .arg("-noshell")
+                .arg("-eval")
+                .arg("gleam@@main:repl()");
+            ("erl", command)
+        }
+        Target::JavaScript => {
+            let runner = package.join("gleam@@repl.mjs");
+            io.write(&runner, gleam_core::javascript::REPL)?;
+            let mut command = Command::new("node");
+            let _ = command.arg("-e").arg(format!(
+                "import('./{}').then(repl => repl.run())",
+                runner.to_string_lossy()
+            ));
+            ("node", command)
+        }
+        Target::Native | Target::Go | Target::Llvm | Target::Wasm => {
+            unreachable!("Unsupported targets are rejected above")
+        }
+    };
+    let _ = command.stdin(Stdio::piped()).stdout(Stdio::piped());
+    tracing::info!("Running OS process {:?}", command);
+    command.spawn().map_err(|e| Error::ShellCommand {
+        program: program.to_string(),
+        err: Some(e.kind()),
+    })
+}
+
+/// Read an input, continuing onto further lines while it has unclosed
+/// brackets. Returns `None` once stdin is closed.
+fn read_input() -> Result<Option<String>, Error> {
+    let mut input = String::new();
+    loop {
+        print!("{}", if input.is_empty() { "> " } else { "... " });
+        std::io::stdout()
+            .flush()
+            .map_err(stdio_error(StandardIoAction::Write))?;
+        let mut line = String::new();
+        let read = std::io::stdin()
+            .read_line(&mut line)
+            .map_err(stdio_error(StandardIoAction::Read))?;
+        if read == 0 {
+            println!();
+            return Ok(if input.is_empty() { None } else { Some(input) });
+        }
+        input.push_str(&line);
+        if unclosed_brackets(&input) <= 0 {
+            return Ok(Some(input));
+        }
+    }
+}
+
+/// The number of brackets in the input that have been opened but not yet
+/// closed, ignoring those within strings and comments.
+fn unclosed_brackets(input: &str) -> isize {
+    let mut depth = 0;
+    let mut chars = input.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            '"' => {
+                while let Some(char) = chars.next() {
+                    match char {
+                        '\\' => {
+                            let _ = chars.next();
+                        }
+                        '"' => break,
+                        _ => (),
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for char in chars.by_ref() {
+                    if char == '\n' {
+                        break;
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    depth
+}
+
+/// Write the module of the expression for the runtime to evaluate, then
+/// print what the runtime outputs until it has finished evaluating it.
+fn evaluate(
+    package: &Path,
+    target: Target,
+    evaluation: &Evaluation,
+    runtime_stdin: &mut ChildStdin,
+    runtime_stdout: &mut BufReader<ChildStdout>,
+) -> Result<(), Error> {
+    let io = ProjectIO::new();
+    // The Erlang runtime is given the path of the module, while the
+    // JavaScript runtime imports it relative to the runner
+    let path = match target {
+        Target::Erlang => {
+            let path = package
+                .join(repl::MODULE_DIRECTORY)
+                .join(format!("{}.erl", evaluation.module.replace('/', "@")));
+            io.write(&path, &evaluation.erlang(LINE_LENGTH)?)?;
+            path.to_string_lossy().to_string()
+        }
+        _ => {
+            let path = package.join(format!("{}.mjs", evaluation.module));
+            io.write(&path, &evaluation.javascript(LINE_LENGTH)?)?;
+            format!("./{}.mjs", evaluation.module)
+        }
+    };
+    writeln!(runtime_stdin, "{}", path).map_err(stdio_error(StandardIoAction::Write))?;
+    runtime_stdin
+        .flush()
+        .map_err(stdio_error(StandardIoAction::Write))?;
+
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = runtime_stdout
+            .read_line(&mut line)
+            .map_err(stdio_error(StandardIoAction::Read))?;
+        if read == 0 {
+            return Err(Error::ShellCommand {
+                program: match target {
+                    Target::Erlang => "erl",
+                    _ => "node",
+                }
+                .to_string(),
+                err: None,
+            });
+        }
+        if let Some(value) = line.strip_prefix(VALUE_MARKER) {
+            println!("{} : {}", value.trim_end(), evaluation.type_);
+            return Ok(());
+        }
+        if line.trim_end() == ERROR_MARKER {
+            return Ok(());
+        }
+        print!("{}", line);
+    }
+}
+
+fn stdio_error(action: StandardIoAction) -> impl Fn(std::io::Error) -> Error {
+    move |error| Error::StandardIo {
+        action,
+        err: Some(error.kind()),
+    }
+}
+
+#[test]
+fn unclosed_brackets_test() {
+    assert_eq!(unclosed_brackets("1 + 2"), 0);
+    assert_eq!(unclosed_brackets("fn(x) {\n"), 1);
+    assert_eq!(unclosed_brackets("[1, [2\n"), 2);
+    assert_eq!(unclosed_brackets("\"(\" <> x"), 0);
+    assert_eq!(unclosed_brackets("\"\\\"(\""), 0);
+    assert_eq!(unclosed_brackets("x // (\n"), 0);
+}
@@ -1,6 +1,9 @@
-use std::time::Instant;
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
-use gleam_core::build::Telemetry;
+use gleam_core::{build::Telemetry, diagnostic::sarif::CodedDiagnostic};
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct NullTelemetry;
@@ -11,6 +14,66 @@ impl Telemetry for NullTelemetry {
     fn downloading_package(&self, _name: &str) {}
     fn compiling_package(&self, _name: &str) {}
     fn checking_package(&self, _name: &str) {}
+    fn running_build_hook(&self, _package: &str, _hook: &str) {}
     fn warning(&self, _warning: &gleam_core::Warning) {}
     fn packages_downloaded(&self, _start: Instant, _count: usize) {}
 }
+
+/// Prints warnings to stderr but otherwise reports nothing, for commands whose
+/// standard output is to be read by other programs.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct WarningTelemetry;
+
+impl Telemetry for WarningTelemetry {
+    fn waiting_for_build_directory_lock(&self) {}
+    fn resolving_package_versions(&self) {}
+    fn downloading_package(&self, _name: &str) {}
+    fn compiling_package(&self, _name: &str) {}
+    fn checking_package(&self, _name: &str) {}
+    fn running_build_hook(&self, _package: &str, _hook: &str) {}
+    fn warning(&self, warning: &gleam_core::Warning) {
+        crate::print_warning(warning)
+    }
+    fn packages_downloaded(&self, _start: Instant, _count: usize) {}
+}
+
+/// Prints each warning as a JSON object on a line of stdout but otherwise
+/// reports nothing, for `--message-format=json`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct JsonTelemetry;
+
+impl Telemetry for JsonTelemetry {
+    fn waiting_for_build_directory_lock(&self) {}
+    fn resolving_package_versions(&self) {}
+    fn downloading_package(&self, _name: &str) {}
+    fn compiling_package(&self, _name: &str) {}
+    fn checking_package(&self, _name: &str) {}
+    fn running_build_hook(&self, _package: &str, _hook: &str) {}
+    fn warning(&self, warning: &gleam_core::Warning) {
+        println!("{}", warning.to_json())
+    }
+    fn packages_downloaded(&self, _start: Instant, _count: usize) {}
+}
+
+/// Keeps the warnings to be printed in a SARIF log once the build has
+/// finished, for `--message-format=sarif`, but otherwise reports nothing.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SarifTelemetry {
+    pub diagnostics: Arc<Mutex<Vec<CodedDiagnostic>>>,
+}
+
+impl Telemetry for SarifTelemetry {
+    fn waiting_for_build_directory_lock(&self) {}
+    fn resolving_package_versions(&self) {}
+    fn downloading_package(&self, _name: &str) {}
+    fn compiling_package(&self, _name: &str) {}
+    fn checking_package(&self, _name: &str) {}
+    fn running_build_hook(&self, _package: &str, _hook: &str) {}
+    fn warning(&self, warning: &gleam_core::Warning) {
+        self.diagnostics
+            .lock()
+            .expect("SARIF diagnostics lock")
+            .push(warning.to_coded_diagnostic())
+    }
+    fn packages_downloaded(&self, _start: Instant, _count: usize) {}
+}
@@ -0,0 +1,133 @@
+//! Building the project again whenever its source files change.
+//!
+//! The modification times of the files in the `src` and `test` directories
+//! of the project and its local packages are polled rather than subscribed to, which behaves the same on every
+//! platform and file system, including network and container mounts where
+//! change notifications are unreliable.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use gleam_core::{
+    build::{Options, Package, ProjectCompiler},
+    paths, Result,
+};
+
+use crate::{build_lock::BuildLock, cli, dependencies::UseManifest, fs::ProjectIO};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Editors often write several files, or write one file several times, when
+/// saving. Changes are only acted on once the files have been left alone for
+/// this long.
+const SETTLE_TIME: Duration = Duration::from_millis(100);
+
+/// Build the project, then build it again each time a source file changes,
+/// running `after_build` after each successful build. Only the changed
+/// modules and the modules that import them are compiled again. Errors are
+/// printed rather than returned so that watching continues until the
+/// process is interrupted.
+pub fn build(options: Options, mut after_build: impl FnMut(&Package) -> Result<()>) -> Result<()> {
+    let lock = BuildLock::new()?;
+    let telemetry = cli::Reporter::new();
+    let manifest = crate::dependencies::download(telemetry.clone(), None, UseManifest::Yes)?;
+    let root_config = crate::config::root_config()?;
+    crate::dependencies::check_licence_policy(&root_config, &manifest)?;
+
+    let local_packages = crate::config::local_packages(&root_config, options.mode)?;
+    {
+        let _guard = lock.lock(&telemetry);
+        crate::build::run_hooks(&root_config, &local_packages, &telemetry)?;
+    }
+
+    // The sources of the workspace members and path dependencies are watched
+    // along with those of the root package
+    let mut directories = vec![paths::src(), paths::test()];
+    for package in &local_packages {
+        directories.push(package.root.join("src"));
+        directories.push(package.root.join("test"));
+    }
+
+    let perform_codegen = options.perform_codegen;
+    let mut compiler = ProjectCompiler::new(
+        root_config,
+        options,
+        manifest.packages,
+        Box::new(telemetry.clone()),
+        ProjectIO::new(),
+    );
+    compiler.local_packages = local_packages;
+    compiler.global_build_cache = Some(paths::global_build_cache());
+    compiler.enable_incremental_compilation();
+
+    let mut sources = modification_times(&directories);
+    loop {
+        let start = Instant::now();
+        let result = {
+            let _guard = lock.lock(&telemetry);
+            compiler.compile()
+        };
+        // The warnings have been printed, and are not to be printed again by
+        // the next build
+        let _ = compiler.take_warnings();
+
+        let result = result.and_then(|package| {
+            if perform_codegen {
+                cli::print_compiled(start.elapsed());
+            } else {
+                cli::print_checked(start.elapsed());
+            }
+            after_build(&package)
+        });
+        if let Err(error) = result {
+            crate::print_error(&error);
+        }
+
+        cli::print_watching();
+        sources = wait_for_change(&directories, sources);
+    }
+}
+
+/// Wait until the files in the directories differ from the given modification
+/// times, returning the new modification times.
+fn wait_for_change(
+    directories: &[PathBuf],
+    previous: HashMap<PathBuf, SystemTime>,
+) -> HashMap<PathBuf, SystemTime> {
+    let mut current = previous.clone();
+    while current == previous {
+        std::thread::sleep(POLL_INTERVAL);
+        current = modification_times(directories);
+    }
+    loop {
+        std::thread::sleep(SETTLE_TIME);
+        let settled = modification_times(directories);
+        if settled == current {
+            return settled;
+        }
+        current = settled;
+    }
+}
+
+fn modification_times(directories: &[PathBuf]) -> HashMap<PathBuf, SystemTime> {
+    directories
+        .iter()
+        .flat_map(|directory| files(directory))
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|metadata| metadata.modified());
+            Some((path, modified.ok()?))
+        })
+        .collect()
+}
+
+fn files(directory: &Path) -> impl Iterator<Item = PathBuf> {
+    walkdir::WalkDir::new(directory)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(walkdir::DirEntry::into_path)
+}
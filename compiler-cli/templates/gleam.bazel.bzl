@@ -0,0 +1,106 @@
+# Rules for building Gleam packages with Bazel, written by
+# `gleam export build-files --format bazel`.
+#
+# The `gleam`, `erlc` and `c++` programs are found on the PATH.
+
+GleamPackageInfo = provider(
+    doc = "A Gleam package compiled for a target.",
+    fields = {
+        "name": "The name of the package.",
+        "target": "The target the package was compiled for, erlang or native.",
+        "out": "The directory of the compiled package.",
+        "libraries": "A depset of the directories of the package and the packages it depends upon.",
+    },
+)
+
+def _gleam_package_impl(ctx):
+    out = ctx.actions.declare_directory(ctx.label.name)
+    dependencies = [dep[GleamPackageInfo] for dep in ctx.attr.deps]
+    libraries = depset(
+        direct = [out],
+        transitive = [dep.libraries for dep in dependencies],
+    )
+    links = " && ".join([
+        'ln -s "$PWD/{}" "$LIB/{}"'.format(dep.out.path, dep.name)
+        for dep in dependencies
+    ] + ["true"])
+    ctx.actions.run_shell(
+        inputs = ctx.files.srcs + [ctx.file.config] + [dep.out for dep in dependencies],
+        outputs = [out],
+        command = """
+set -eu
+LIB=$(mktemp -d)
+{links}
+gleam compile-package --target {target} --package {package} --out {out} --lib "$LIB" --no-beam
+""".format(
+            links = links,
+            target = ctx.attr.target,
+            package = ctx.file.config.dirname or ".",
+            out = out.path,
+        ),
+        use_default_shell_env = True,
+        mnemonic = "GleamCompilePackage",
+        progress_message = "Compiling Gleam package {} for {}".format(ctx.attr.package, ctx.attr.target),
+    )
+    return [
+        DefaultInfo(files = depset([out])),
+        GleamPackageInfo(
+            name = ctx.attr.package,
+            target = ctx.attr.target,
+            out = out,
+            libraries = libraries,
+        ),
+    ]
+
+gleam_package = rule(
+    implementation = _gleam_package_impl,
+    doc = "Type checks a Gleam package and generates the code of its modules.",
+    attrs = {
+        "package": attr.string(mandatory = True),
+        "target": attr.string(mandatory = True, values = ["erlang", "native"]),
+        "srcs": attr.label_list(allow_files = True),
+        "config": attr.label(mandatory = True, allow_single_file = ["gleam.toml"]),
+        "modules": attr.string_list(doc = "The Gleam modules of the package."),
+        "deps": attr.label_list(providers = [GleamPackageInfo]),
+    },
+)
+
+def _gleam_module_impl(ctx):
+    package = ctx.attr.package[GleamPackageInfo]
+    src = "{}/{}".format(package.out.path, ctx.attr.file)
+    if package.target == "erlang":
+        name = ctx.attr.file.split("/")[-1].rsplit(".", 1)[0]
+        out = ctx.actions.declare_file("{}/{}.beam".format(ctx.label.name, name))
+        inputs = [package.out]
+        command = 'erlc -o "{}" "{}"'.format(out.dirname, src)
+    else:
+        out = ctx.actions.declare_file("{}/{}.o".format(ctx.label.name, ctx.attr.file.rsplit(".", 1)[0]))
+        libraries = package.libraries.to_list()
+        inputs = libraries
+        command = "c++ -std=c++17 -c {} -o {} {}".format(
+            src,
+            out.path,
+            " ".join(["-I{}/dist".format(library.path) for library in libraries]),
+        )
+    ctx.actions.run_shell(
+        inputs = inputs,
+        outputs = [out],
+        command = command,
+        use_default_shell_env = True,
+        mnemonic = "GleamCompileModule",
+        progress_message = "Compiling {}".format(ctx.attr.file),
+    )
+    return [DefaultInfo(files = depset(
+        direct = [out],
+        transitive = [dep[DefaultInfo].files for dep in ctx.attr.deps],
+    ))]
+
+gleam_module = rule(
+    implementation = _gleam_module_impl,
+    doc = "Compiles a module of a Gleam package to BEAM bytecode or an object file.",
+    attrs = {
+        "package": attr.label(mandatory = True, providers = [GleamPackageInfo]),
+        "file": attr.string(mandatory = True, doc = "The path of the module's code within the compiled package."),
+        "deps": attr.label_list(doc = "The modules this module imports."),
+    },
+)
@@ -0,0 +1,95 @@
+# Rules for building Gleam packages with Buck2, written by
+# `gleam export build-files --format buck2`.
+#
+# The `gleam`, `erlc` and `c++` programs are found on the PATH.
+
+GleamPackageInfo = provider(fields = {
+    "name": provider_field(str),
+    "target": provider_field(str),
+    "out": provider_field(typing.Any),
+    "libraries": provider_field(list),
+})
+
+_LINK_AND_COMPILE = """
+set -eu
+OUT=$1
+TARGET=$2
+PACKAGE=$3
+shift 3
+LIB=$(mktemp -d)
+while [ $# -gt 0 ]; do
+  ln -s "$PWD/$2" "$LIB/$1"
+  shift 2
+done
+gleam compile-package --target "$TARGET" --package "$PACKAGE" --out "$OUT" --lib "$LIB" --no-beam
+"""
+
+def _gleam_package_impl(ctx):
+    out = ctx.actions.declare_output(ctx.label.name, dir = True)
+    dependencies = [dep[GleamPackageInfo] for dep in ctx.attrs.deps]
+    libraries = {}
+    for dep in dependencies:
+        for library in dep.libraries:
+            libraries[library[0]] = library[1]
+    command = cmd_args(
+        "/bin/sh",
+        "-c",
+        _LINK_AND_COMPILE,
+        "--",
+        out.as_output(),
+        ctx.attrs.target,
+        cmd_args(ctx.attrs.config, parent = 1),
+        hidden = ctx.attrs.srcs + [ctx.attrs.config],
+    )
+    for dep in dependencies:
+        command.add(dep.name, dep.out)
+    ctx.actions.run(command, category = "gleam_compile_package")
+    libraries[ctx.attrs.package] = out
+    return [
+        DefaultInfo(default_output = out),
+        GleamPackageInfo(
+            name = ctx.attrs.package,
+            target = ctx.attrs.target,
+            out = out,
+            libraries = libraries.items(),
+        ),
+    ]
+
+gleam_package = rule(
+    impl = _gleam_package_impl,
+    attrs = {
+        "package": attrs.string(),
+        "target": attrs.enum(["erlang", "native"]),
+        "srcs": attrs.list(attrs.source(), default = []),
+        "config": attrs.source(),
+        "modules": attrs.list(attrs.string(), default = []),
+        "deps": attrs.list(attrs.dep(providers = [GleamPackageInfo]), default = []),
+    },
+)
+
+def _gleam_module_impl(ctx):
+    package = ctx.attrs.package[GleamPackageInfo]
+    src = package.out.project(ctx.attrs.file)
+    if package.target == "erlang":
+        name = ctx.attrs.file.split("/")[-1].rpartition(".")[0]
+        out = ctx.actions.declare_output("{}.beam".format(name))
+        command = cmd_args("erlc", "-o", cmd_args(out.as_output(), parent = 1), src)
+    else:
+        out = ctx.actions.declare_output("{}.o".format(ctx.attrs.file.rpartition(".")[0]))
+        command = cmd_args("c++", "-std=c++17", "-c", src, "-o", out.as_output())
+        for library in package.libraries:
+            command.add(cmd_args(library[1].project("dist"), format = "-I{}"))
+    ctx.actions.run(command, category = "gleam_compile_module", identifier = ctx.attrs.file)
+    outputs = [out]
+    for dep in ctx.attrs.deps:
+        outputs.extend(dep[DefaultInfo].default_outputs)
+    return [DefaultInfo(default_outputs = outputs)]
+
+gleam_module = rule(
+    impl = _gleam_module_impl,
+    attrs = {
+        "package": attrs.dep(providers = [GleamPackageInfo]),
+        "file": attrs.string(),
+        "deps": attrs.list(attrs.dep(), default = []),
+    },
+)
@@ -1,18 +1,22 @@
 mod constant;
 mod typed;
 mod untyped;
+pub mod visit;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::typed::TypedExpr;
-pub use self::untyped::{UntypedExpr, Use};
+pub use self::untyped::{Arena, ArenaBox, UntypedExpr, Use};
 
 pub use self::constant::{Constant, TypedConstant, UntypedConstant};
 
 use crate::build::{Located, Target};
-use crate::type_::{self, ModuleValueConstructor, PatternConstructor, Type, ValueConstructor};
-use std::sync::Arc;
+pub use crate::name::Name;
+use crate::type_::{
+    self, ModuleValueConstructor, PatternConstructor, Type, ValueConstructor, Warning, WarningKind,
+};
+use std::{borrow::Cow, sync::Arc};
 
 #[cfg(test)]
 use pretty_assertions::assert_eq;
@@ -21,6 +25,31 @@ pub const TRY_VARIABLE: &str = "_try";
 pub const PIPE_VARIABLE: &str = "_pipe";
 pub const ASSERT_VARIABLE: &str = "_try";
 pub const CAPTURE_VARIABLE: &str = "_capture";
+pub const FIELD_VARIABLE: &str = "_field";
+pub const ECHO_VARIABLE: &str = "_echo";
+pub const RESCUE_VARIABLE: &str = "_rescue";
+pub const RANGE_VARIABLE: &str = "_range";
+pub const RECORD_VARIABLE: &str = "_record";
+
+/// The name of the variable for the `index`th argument hole in a function
+/// capture such as `run(_, 1, _)`.
+pub fn capture_variable(index: usize) -> Name {
+    if index == 0 {
+        CAPTURE_VARIABLE.into()
+    } else {
+        format!("{CAPTURE_VARIABLE}_{index}").into()
+    }
+}
+
+pub fn is_capture_variable(name: &str) -> bool {
+    match name.strip_prefix(CAPTURE_VARIABLE) {
+        Some("") => true,
+        Some(rest) => rest
+            .strip_prefix('_')
+            .map_or(false, |index| index.bytes().all(|b| b.is_ascii_digit())),
+        None => false,
+    }
+}
 
 pub trait HasLocation {
     fn location(&self) -> SrcSpan;
@@ -28,14 +57,15 @@ pub trait HasLocation {
 
 pub type TypedModule = Module<type_::Module, TypedStatement>;
 
-pub type UntypedModule = Module<(), TargetGroup>;
+pub type UntypedModule<'a> = Module<(), TargetGroup<'a>>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Module<Info, Statements> {
-    pub name: Vec<String>,
+    pub name: Vec<Name>,
     pub documentation: Vec<String>,
     pub type_info: Info,
     pub statements: Vec<Statements>,
+    pub warning_suppressions: Vec<WarningSuppression>,
 }
 
 impl TypedModule {
@@ -58,13 +88,13 @@ impl TypedModule {
 /// ```
 /// Outside an if block is `Any`, inside is an `Only`.
 ///
-#[derive(Debug, Clone, PartialEq)]
-pub enum TargetGroup {
-    Any(Vec<UntypedStatement>),
-    Only(Target, Vec<UntypedStatement>),
+#[derive(Debug, PartialEq)]
+pub enum TargetGroup<'a> {
+    Any(Vec<UntypedStatement<'a>>),
+    Only(Target, Vec<UntypedStatement<'a>>),
 }
 
-impl TargetGroup {
+impl<'a> TargetGroup<'a> {
     pub fn is_for(&self, target: Target) -> bool {
         match self {
             Self::Any(_) => true,
@@ -72,14 +102,14 @@ impl TargetGroup {
         }
     }
 
-    pub fn statements(self) -> Vec<UntypedStatement> {
+    pub fn statements(self) -> Vec<UntypedStatement<'a>> {
         match self {
             Self::Any(s) => s,
             Self::Only(_, s) => s,
         }
     }
 
-    pub fn statements_ref(&self) -> &[UntypedStatement] {
+    pub fn statements_ref(&self) -> &[UntypedStatement<'a>] {
         match self {
             Self::Any(s) => s,
             Self::Only(_, s) => s,
@@ -101,7 +131,7 @@ impl<A, B> Module<A, B> {
     }
 }
 
-impl UntypedModule {
+impl<'a> UntypedModule<'a> {
     pub fn dependencies(&self, target: Target) -> Vec<(String, SrcSpan)> {
         self.iter_statements(target)
             .flat_map(|s| match s {
@@ -113,28 +143,41 @@ impl UntypedModule {
             .collect()
     }
 
-    pub fn iter_statements(&self, target: Target) -> impl Iterator<Item = &UntypedStatement> {
+    pub fn iter_statements(&self, target: Target) -> impl Iterator<Item = &UntypedStatement<'a>> {
         self.statements
             .iter()
             .filter(move |group| group.is_for(target))
             .flat_map(|group| group.statements_ref())
     }
 
-    pub fn into_iter_statements(self, target: Target) -> impl Iterator<Item = UntypedStatement> {
+    pub fn into_iter_statements(
+        self,
+        target: Target,
+    ) -> impl Iterator<Item = UntypedStatement<'a>> {
         self.statements
             .into_iter()
             .filter(move |group| group.is_for(target))
             .flat_map(|group| group.statements())
     }
+
+    /// Whether the module has code that is only for some targets, in which
+    /// case it is type checked separately for each target.
+    pub fn has_target_specific_code(&self) -> bool {
+        self.statements
+            .iter()
+            .any(|group| matches!(group, TargetGroup::Only(..)))
+    }
 }
 
 #[test]
 fn module_dependencies_test() {
+    let arena = Arena::new();
     let (module, _) = crate::parse::parse_module(
         "import one 
          if erlang { import two } 
          if javascript { import three } 
          import four",
+        &arena,
     )
     .expect("syntax error");
 
@@ -154,6 +197,17 @@ fn module_dependencies_test() {
     );
 }
 
+#[test]
+fn module_target_specific_code_test() {
+    let arena = Arena::new();
+    let (module, _) =
+        crate::parse::parse_module("pub fn one() { 1 }", &arena).expect("syntax error");
+    assert!(!module.has_target_specific_code());
+    let (module, _) = crate::parse::parse_module("if javascript { pub fn one() { 1 } }", &arena)
+        .expect("syntax error");
+    assert!(module.has_target_specific_code());
+}
+
 pub type TypedArg = Arg<Arc<Type>>;
 pub type UntypedArg = Arg<()>;
 pub type TypedExternalFnArg = ExternalFnArg<Arc<Type>>;
@@ -165,6 +219,7 @@ pub struct Arg<T> {
     pub location: SrcSpan,
     pub annotation: Option<TypeAst>,
     pub type_: T,
+    pub doc: Option<String>,
 }
 
 impl<A> Arg<A> {
@@ -174,9 +229,14 @@ impl<A> Arg<A> {
             names: self.names,
             location: self.location,
             annotation: self.annotation,
+            doc: self.doc,
         }
     }
 
+    pub fn put_doc(&mut self, new_doc: String) {
+        self.doc = Some(new_doc);
+    }
+
     pub fn get_variable_name(&self) -> Option<&str> {
         self.names.get_variable_name()
     }
@@ -189,16 +249,21 @@ impl<A> ExternalFnArg<A> {
             label: self.label,
             annotation: self.annotation,
             type_: t,
+            doc: self.doc,
         }
     }
+
+    pub fn put_doc(&mut self, new_doc: String) {
+        self.doc = Some(new_doc);
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ArgNames {
-    Discard { name: String },
-    LabelledDiscard { label: String, name: String },
-    Named { name: String },
-    NamedLabelled { name: String, label: String },
+    Discard { name: Name },
+    LabelledDiscard { label: Name, name: Name },
+    Named { name: Name },
+    NamedLabelled { name: Name, label: Name },
 }
 
 impl ArgNames {
@@ -210,14 +275,32 @@ impl ArgNames {
     }
 }
 
+/// A generator named in the `@derive` attribute of a custom type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Derive {
+    pub location: SrcSpan,
+    pub name: Name,
+}
+
+/// An implementation of a function in another language, named in an
+/// `@external` attribute and used in place of its body on one target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct External {
+    pub location: SrcSpan,
+    pub target: Target,
+    pub module: String,
+    pub function: String,
+}
+
 pub type TypedRecordConstructor = RecordConstructor<Arc<Type>>;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct RecordConstructor<T> {
     pub location: SrcSpan,
-    pub name: String,
+    pub name: Name,
     pub arguments: Vec<RecordConstructorArg<T>>,
     pub documentation: Option<String>,
+    pub deprecation: Deprecation,
 }
 
 impl<A> RecordConstructor<A> {
@@ -230,7 +313,7 @@ pub type TypedRecordConstructorArg = RecordConstructorArg<Arc<Type>>;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct RecordConstructorArg<T> {
-    pub label: Option<String>,
+    pub label: Option<Name>,
     pub ast: TypeAst,
     pub location: SrcSpan,
     pub type_: T,
@@ -247,8 +330,8 @@ impl<T: PartialEq> RecordConstructorArg<T> {
 pub enum TypeAst {
     Constructor {
         location: SrcSpan,
-        module: Option<String>,
-        name: String,
+        module: Option<Name>,
+        name: Name,
         arguments: Vec<Self>,
     },
 
@@ -260,7 +343,7 @@ pub enum TypeAst {
 
     Var {
         location: SrcSpan,
-        name: String,
+        name: Name,
     },
 
     Tuple {
@@ -270,7 +353,7 @@ pub enum TypeAst {
 
     Hole {
         location: SrcSpan,
-        name: String,
+        name: Name,
     },
 }
 
@@ -359,8 +442,8 @@ impl TypeAst {
     }
 }
 
-pub type TypedStatement = Statement<Arc<Type>, TypedExpr, String, String>;
-pub type UntypedStatement = Statement<(), UntypedExpr, (), ()>;
+pub type TypedStatement = Statement<Arc<Type>, TypedExpr, Name, String>;
+pub type UntypedStatement<'a> = Statement<(), UntypedExpr<'a>, (), ()>;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Statement<T, Expr, ConstantRecordTag, PackageName> {
@@ -374,16 +457,26 @@ pub enum Statement<T, Expr, ConstantRecordTag, PackageName> {
     /// // Private function
     /// fn foo(x: Int) -> Int { ... }
     /// ```
+    ///
+    /// With the `@external` attribute the function is implemented in another
+    /// language on the given targets, and the body is used on all others.
+    ///
+    /// ```gleam
+    /// @external(erlang, "lists", "reverse")
+    /// pub fn reverse(list: List(a)) -> List(a) { ... }
+    /// ```
     Fn {
         location: SrcSpan,
         end_position: u32,
-        name: String,
+        name: Name,
         arguments: Vec<Arg<T>>,
         body: Expr,
         public: bool,
         return_annotation: Option<TypeAst>,
         return_type: T,
         doc: Option<String>,
+        deprecation: Deprecation,
+        externals: Vec<External>,
     },
 
     /// A new name for an existing type
@@ -396,12 +489,13 @@ pub enum Statement<T, Expr, ConstantRecordTag, PackageName> {
     /// ```
     TypeAlias {
         location: SrcSpan,
-        alias: String,
-        parameters: Vec<String>,
+        alias: Name,
+        parameters: Vec<Name>,
         type_ast: TypeAst,
         type_: T,
         public: bool,
         doc: Option<String>,
+        deprecation: Deprecation,
     },
 
     /// A newly defined type with one or more constructors.
@@ -419,15 +513,39 @@ pub enum Statement<T, Expr, ConstantRecordTag, PackageName> {
     ///   Cat(name: String, cuteness: Int)
     /// }
     /// ```
+    ///
+    /// With the `@erlang_map` attribute a type with a single constructor
+    /// with labelled fields is represented as a map rather than a tuple on
+    /// the Erlang target.
+    ///
+    /// ```gleam
+    /// @erlang_map
+    /// pub type Cat {
+    ///   Cat(name: String, cuteness: Int)
+    /// }
+    /// ```
+    ///
+    /// With the `@derive` attribute functions are generated for the type
+    /// by the named generators, as described in the `derive` module.
+    ///
+    /// ```gleam
+    /// @derive(predicates, setters)
+    /// pub type Cat {
+    ///   Cat(name: String, cuteness: Int)
+    /// }
+    /// ```
     CustomType {
         location: SrcSpan,
-        name: String,
-        parameters: Vec<String>,
+        name: Name,
+        parameters: Vec<Name>,
         public: bool,
         constructors: Vec<RecordConstructor<T>>,
         doc: Option<String>,
         opaque: bool,
         typed_parameters: Vec<T>,
+        erlang_map: bool,
+        derives: Vec<Derive>,
+        deprecation: Deprecation,
     },
 
     /// Import a function defined outside of Gleam code.
@@ -444,12 +562,13 @@ pub enum Statement<T, Expr, ConstantRecordTag, PackageName> {
         location: SrcSpan,
         public: bool,
         arguments: Vec<ExternalFnArg<T>>,
-        name: String,
+        name: Name,
         return_: TypeAst,
         return_type: T,
         module: String,
         fun: String,
         doc: Option<String>,
+        deprecation: Deprecation,
     },
 
     /// Import a type defined in another language.
@@ -464,9 +583,10 @@ pub enum Statement<T, Expr, ConstantRecordTag, PackageName> {
     ExternalType {
         location: SrcSpan,
         public: bool,
-        name: String,
-        arguments: Vec<String>,
+        name: Name,
+        arguments: Vec<Name>,
         doc: Option<String>,
+        deprecation: Deprecation,
     },
 
     /// Import another Gleam module so the current module can use the types and
@@ -481,8 +601,8 @@ pub enum Statement<T, Expr, ConstantRecordTag, PackageName> {
     /// ```
     Import {
         location: SrcSpan,
-        module: Vec<String>,
-        as_name: Option<String>,
+        module: Vec<Name>,
+        as_name: Option<Name>,
         unqualified: Vec<UnqualifiedImport>,
         package: PackageName,
     },
@@ -499,10 +619,11 @@ pub enum Statement<T, Expr, ConstantRecordTag, PackageName> {
         doc: Option<String>,
         location: SrcSpan,
         public: bool,
-        name: String,
+        name: Name,
         annotation: Option<TypeAst>,
         value: Box<Constant<T, ConstantRecordTag>>,
         type_: T,
+        deprecation: Deprecation,
     },
 }
 
@@ -556,8 +677,8 @@ impl<A, B, C, E> Statement<A, B, C, E> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UnqualifiedImport {
     pub location: SrcSpan,
-    pub name: String,
-    pub as_name: Option<String>,
+    pub name: Name,
+    pub as_name: Option<Name>,
     pub layer: Layer,
 }
 
@@ -590,12 +711,81 @@ impl Layer {
     }
 }
 
+/// Whether a definition has been marked as deprecated with the
+/// `@deprecated("...")` attribute, in which case a warning is emitted
+/// wherever it is used.
+///
+/// # Example(s)
+///
+/// ```gleam
+/// @deprecated("Use wobble instead")
+/// pub fn wibble() { ... }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Deprecation {
+    #[default]
+    NotDeprecated,
+    Deprecated {
+        message: String,
+    },
+}
+
+impl Deprecation {
+    pub fn is_deprecated(&self) -> bool {
+        matches!(self, Self::Deprecated { .. })
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Self::NotDeprecated => None,
+            Self::Deprecated { message } => Some(message),
+        }
+    }
+}
+
+/// Warnings silenced with an attribute, either for a single definition
+///
+/// ```gleam
+/// @allow(unused_variable)
+/// pub fn wibble() { ... }
+/// ```
+///
+/// or for the whole module.
+///
+/// ```gleam
+/// @module_allow(todo, unused_imported_module)
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarningSuppression {
+    pub location: SrcSpan,
+    pub kinds: Vec<WarningKind>,
+    pub scope: SuppressionScope,
+}
+
+impl WarningSuppression {
+    pub fn suppresses(&self, warning: &Warning) -> bool {
+        self.kinds.contains(&warning.kind())
+            && match self.scope {
+                SuppressionScope::Module => true,
+                SuppressionScope::Definition(span) => span.contains(warning.location().start),
+            }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionScope {
+    Module,
+    /// The span of the whole definition the attribute was written before.
+    Definition(SrcSpan),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExternalFnArg<T> {
     pub location: SrcSpan,
-    pub label: Option<String>,
+    pub label: Option<Name>,
     pub annotation: TypeAst,
     pub type_: T,
+    pub doc: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -695,7 +885,7 @@ impl BinOp {
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CallArg<A> {
-    pub label: Option<String>,
+    pub label: Option<Name>,
     pub location: SrcSpan,
     pub value: A,
     // This is true if this argument is given as the callback in a `use`
@@ -712,31 +902,40 @@ impl CallArg<TypedExpr> {
     }
 }
 
-impl CallArg<UntypedExpr> {
+impl<A> HasLocation for CallArg<A> {
+    fn location(&self) -> SrcSpan {
+        self.location
+    }
+}
+
+impl CallArg<UntypedExpr<'_>> {
     pub fn is_capture_hole(&self) -> bool {
         match &self.value {
-            UntypedExpr::Var { ref name, .. } => name == CAPTURE_VARIABLE,
+            UntypedExpr::Var { ref name, .. } => is_capture_variable(name),
             _ => false,
         }
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub struct RecordUpdateSpread {
-    pub base: Box<UntypedExpr>,
+#[derive(Debug, PartialEq)]
+pub struct RecordUpdateSpread<'a> {
+    pub base: ArenaBox<'a, UntypedExpr<'a>>,
     pub location: SrcSpan,
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub struct UntypedRecordUpdateArg {
-    pub label: String,
+#[derive(Debug, PartialEq)]
+pub struct UntypedRecordUpdateArg<'a> {
+    pub label: Name,
+    /// The labels of fields within nested records to update, such as `port`
+    /// in `Config(..config, server.port: 8080)`. Empty for a plain update.
+    pub path: Vec<Name>,
     pub location: SrcSpan,
-    pub value: UntypedExpr,
+    pub value: UntypedExpr<'a>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct TypedRecordUpdateArg {
-    pub label: String,
+    pub label: Name,
     pub location: SrcSpan,
     pub value: TypedExpr,
     pub index: u32,
@@ -753,9 +952,9 @@ pub type MultiPattern<PatternConstructor, Type> = Vec<Pattern<PatternConstructor
 pub type UntypedMultiPattern = MultiPattern<(), ()>;
 pub type TypedMultiPattern = MultiPattern<PatternConstructor, Arc<Type>>;
 
-pub type TypedClause = Clause<TypedExpr, PatternConstructor, Arc<Type>, String>;
+pub type TypedClause = Clause<TypedExpr, PatternConstructor, Arc<Type>, Name>;
 
-pub type UntypedClause = Clause<UntypedExpr, (), (), ()>;
+pub type UntypedClause<'a> = Clause<UntypedExpr<'a>, (), (), ()>;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Clause<Expr, PatternConstructor, Type, RecordTag> {
@@ -781,10 +980,54 @@ impl TypedClause {
     pub fn find_node(&self, byte_index: u32) -> Option<&TypedExpr> {
         self.then.find_node(byte_index)
     }
+
+    /// Whether the clause matches a feature flag with the given value, if
+    /// that is known at compile time.
+    fn matches_cfg(&self, enabled: bool) -> Option<bool> {
+        let mut matches = Some(false);
+        for pattern in std::iter::once(&self.pattern).chain(&self.alternative_patterns) {
+            match pattern.as_slice() {
+                [Pattern::Discard { .. }] => return Some(true),
+                [Pattern::Constructor {
+                    name,
+                    arguments,
+                    type_,
+                    ..
+                }] if type_.is_bool() && arguments.is_empty() => {
+                    if (name == "True") == enabled {
+                        return Some(true);
+                    }
+                }
+                _ => matches = None,
+            }
+        }
+        matches
+    }
+}
+
+/// The clause of a case expression that runs, if it can be found at compile
+/// time because the only subject is a feature flag. The code generators emit
+/// only the body of this clause, leaving out the code for disabled features.
+pub fn cfg_case_clause<'a>(
+    subjects: &[TypedExpr],
+    clauses: &'a [TypedClause],
+) -> Option<&'a TypedClause> {
+    let enabled = match subjects {
+        [TypedExpr::Cfg { enabled, .. }] => *enabled,
+        _ => return None,
+    };
+    for clause in clauses {
+        match (clause.matches_cfg(enabled)?, &clause.guard) {
+            (false, _) => continue,
+            (true, None) => return Some(clause),
+            (true, Some(_)) => return None,
+        }
+    }
+    None
 }
 
 pub type UntypedClauseGuard = ClauseGuard<(), ()>;
-pub type TypedClauseGuard = ClauseGuard<Arc<Type>, String>;
+pub type TypedClauseGuard = ClauseGuard<Arc<Type>, Name>;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ClauseGuard<Type, RecordTag> {
@@ -863,7 +1106,7 @@ pub enum ClauseGuard<Type, RecordTag> {
     Var {
         location: SrcSpan,
         type_: Type,
-        name: String,
+        name: Name,
     },
 
     TupleIndex {
@@ -873,6 +1116,16 @@ pub enum ClauseGuard<Type, RecordTag> {
         tuple: Box<Self>,
     },
 
+    /// A call to a function defined in the same module whose body can itself
+    /// be used in a guard.
+    /// e.g. `is_small(x)`
+    Call {
+        location: SrcSpan,
+        type_: Type,
+        name: Name,
+        args: Vec<Self>,
+    },
+
     Constant(Constant<Type, RecordTag>),
 }
 
@@ -884,6 +1137,7 @@ impl<A, B> ClauseGuard<A, B> {
             | ClauseGuard::And { location, .. }
             | ClauseGuard::Var { location, .. }
             | ClauseGuard::TupleIndex { location, .. }
+            | ClauseGuard::Call { location, .. }
             | ClauseGuard::Equals { location, .. }
             | ClauseGuard::NotEquals { location, .. }
             | ClauseGuard::GtInt { location, .. }
@@ -914,9 +1168,10 @@ impl<A, B> ClauseGuard<A, B> {
             | ClauseGuard::LtFloat { .. }
             | ClauseGuard::LtEqFloat { .. } => 4,
 
-            ClauseGuard::Constant(_) | ClauseGuard::Var { .. } | ClauseGuard::TupleIndex { .. } => {
-                5
-            }
+            ClauseGuard::Constant(_)
+            | ClauseGuard::Var { .. }
+            | ClauseGuard::TupleIndex { .. }
+            | ClauseGuard::Call { .. } => 5,
         }
     }
 }
@@ -926,6 +1181,7 @@ impl TypedClauseGuard {
         match self {
             ClauseGuard::Var { type_, .. } => type_.clone(),
             ClauseGuard::TupleIndex { type_, .. } => type_.clone(),
+            ClauseGuard::Call { type_, .. } => type_.clone(),
             ClauseGuard::Constant(constant) => constant.type_(),
 
             ClauseGuard::Or { .. }
@@ -962,7 +1218,9 @@ impl SrcSpan {
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct DefinitionLocation<'module> {
-    pub module: Option<&'module str>,
+    /// The module the definition is in, if it is not in the module in which
+    /// it is referenced.
+    pub module: Option<Cow<'module, str>>,
     pub span: SrcSpan,
 }
 
@@ -986,25 +1244,33 @@ pub enum Pattern<Constructor, Type> {
         value: String,
     },
 
+    /// An inclusive range of integers, only allowed in `case` clauses.
+    /// e.g. `0..9 -> "digit"`
+    IntRange {
+        location: SrcSpan,
+        start: String,
+        end: String,
+    },
+
     /// The creation of a variable.
     /// e.g. `assert [this_is_a_var, .._] = x`
     Var {
         location: SrcSpan,
-        name: String,
+        name: Name,
     },
 
     /// A reference to a variable in a bit string. This is always a variable
     /// being used rather than a new variable being assigned.
     VarUsage {
         location: SrcSpan,
-        name: String,
+        name: Name,
         type_: Type,
     },
 
     /// A name given to a sub-pattern using the `as` keyword.
     /// e.g. `assert #(1, [_, _] as the_list) = x`
     Assign {
-        name: String,
+        name: Name,
         location: SrcSpan,
         pattern: Box<Self>,
     },
@@ -1012,7 +1278,7 @@ pub enum Pattern<Constructor, Type> {
     /// A pattern that binds to any value but does not assign a variable.
     /// Always starts with an underscore.
     Discard {
-        name: String,
+        name: Name,
         location: SrcSpan,
     },
 
@@ -1025,9 +1291,9 @@ pub enum Pattern<Constructor, Type> {
     /// The constructor for a custom type. Starts with an uppercase letter.
     Constructor {
         location: SrcSpan,
-        name: String,
+        name: Name,
         arguments: Vec<CallArg<Self>>,
-        module: Option<String>,
+        module: Option<Name>,
         constructor: Constructor,
         with_spread: bool,
         type_: Type,
@@ -1057,8 +1323,8 @@ pub enum Pattern<Constructor, Type> {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AssignName {
-    Variable(String),
-    Discard(String),
+    Variable(Name),
+    Discard(Name),
 }
 
 impl AssignName {
@@ -1081,6 +1347,7 @@ impl<A, B> Pattern<A, B> {
         match self {
             Pattern::Assign { pattern, .. } => pattern.location(),
             Pattern::Int { location, .. }
+            | Pattern::IntRange { location, .. }
             | Pattern::Var { location, .. }
             | Pattern::VarUsage { location, .. }
             | Pattern::List { location, .. }
@@ -1115,7 +1382,7 @@ pub enum AssignmentKind {
 
 // BitStrings
 
-pub type UntypedExprBitStringSegment = BitStringSegment<UntypedExpr, ()>;
+pub type UntypedExprBitStringSegment<'a> = BitStringSegment<UntypedExpr<'a>, ()>;
 pub type TypedExprBitStringSegment = BitStringSegment<TypedExpr, Arc<Type>>;
 
 pub type UntypedConstantBitStringSegment = BitStringSegment<UntypedConstant, ()>;
@@ -1,7 +1,7 @@
 use super::*;
 use crate::type_::{FieldMap, HasType};
 
-pub type TypedConstant = Constant<Arc<Type>, String>;
+pub type TypedConstant = Constant<Arc<Type>, Name>;
 pub type UntypedConstant = Constant<(), ()>;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -34,8 +34,8 @@ pub enum Constant<T, RecordTag> {
 
     Record {
         location: SrcSpan,
-        module: Option<String>,
-        name: String,
+        module: Option<Name>,
+        name: Name,
         args: Vec<CallArg<Self>>,
         tag: RecordTag,
         typ: T,
@@ -49,11 +49,20 @@ pub enum Constant<T, RecordTag> {
 
     Var {
         location: SrcSpan,
-        module: Option<String>,
-        name: String,
+        module: Option<Name>,
+        name: Name,
         constructor: Option<Box<ValueConstructor>>,
         typ: T,
     },
+
+    /// Arithmetic or string concatenation. These are evaluated when the
+    /// constant is type checked so typed constants never contain them.
+    BinOp {
+        location: SrcSpan,
+        name: BinOp,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
 }
 
 impl TypedConstant {
@@ -69,6 +78,9 @@ impl TypedConstant {
             Constant::List { typ, .. }
             | Constant::Record { typ, .. }
             | Constant::Var { typ, .. } => typ.clone(),
+            Constant::BinOp { .. } => {
+                unreachable!("Constant binary operators are evaluated during type checking")
+            }
         }
     }
 }
@@ -89,7 +101,15 @@ impl<A, B> Constant<A, B> {
             | Constant::String { location, .. }
             | Constant::Record { location, .. }
             | Constant::BitString { location, .. }
-            | Constant::Var { location, .. } => *location,
+            | Constant::Var { location, .. }
+            | Constant::BinOp { location, .. } => *location,
+        }
+    }
+
+    pub fn binop_precedence(&self) -> u8 {
+        match self {
+            Self::BinOp { name, .. } => name.precedence(),
+            _ => u8::MAX,
         }
     }
 
@@ -109,11 +129,9 @@ impl<A, B> HasLocation for Constant<A, B> {
 
 impl<A, B> crate::bit_string::GetLitValue for Constant<A, B> {
     fn as_int_literal(&self) -> Option<i64> {
-        if let Constant::Int { value, .. } = self {
-            if let Ok(val) = value.parse::<i64>() {
-                return Some(val);
-            }
+        match self {
+            Constant::Int { value, .. } => crate::int::parse(value),
+            _ => None,
         }
-        None
     }
 }
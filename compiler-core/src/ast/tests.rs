@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use crate::{
-    ast::{SrcSpan, TypedExpr},
+    ast::{
+        visit::{self, Visit},
+        Constant, Deprecation, Pattern, SrcSpan, TypedConstant, TypedExpr, TypedPattern,
+    },
     type_::{
         self, AccessorsMap, Environment, ExprTyper, FieldMap, ModuleValueConstructor,
         RecordAccessor, Type, ValueConstructor, ValueConstructorVariant,
@@ -13,7 +16,8 @@ use super::TypedModule;
 
 fn compile_module(src: &str) -> TypedModule {
     use crate::type_::{build_prelude, infer_module};
-    let (ast, _) = crate::parse::parse_module(src).expect("syntax error");
+    let arena = crate::ast::Arena::new();
+    let (ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
     let ids = UniqueIdGenerator::new();
     let mut modules = im::HashMap::new();
     // DUPE: preludeinsertion
@@ -34,7 +38,8 @@ fn compile_module(src: &str) -> TypedModule {
 }
 
 fn compile_expression(src: &str) -> TypedExpr {
-    let ast = crate::parse::parse_expression_sequence(src).expect("syntax error");
+    let arena = crate::ast::Arena::new();
+    let ast = crate::parse::parse_expression_sequence(src, &arena).expect("syntax error");
 
     let mut modules = im::HashMap::new();
     let ids = UniqueIdGenerator::new();
@@ -44,7 +49,13 @@ fn compile_expression(src: &str) -> TypedExpr {
     // place.
     let _ = modules.insert("gleam".to_string(), type_::build_prelude(&ids));
     let mut warnings = vec![];
-    let mut environment = Environment::new(ids, &[], &modules, &mut warnings);
+    let mut environment = Environment::new(
+        ids,
+        crate::build::Target::Erlang,
+        &[],
+        &modules,
+        &mut warnings,
+    );
 
     // Insert a cat record to use in the tests
     let cat_type = Arc::new(Type::App {
@@ -55,6 +66,7 @@ fn compile_expression(src: &str) -> TypedExpr {
     });
     let variant = ValueConstructorVariant::Record {
         constructors_count: 1,
+        erlang_map: false,
         name: "Cat".into(),
         arity: 2,
         location: SrcSpan { start: 12, end: 15 },
@@ -69,12 +81,14 @@ fn compile_expression(src: &str) -> TypedExpr {
         variant,
         type_::fn_(vec![type_::string(), type_::int()], cat_type.clone()),
         true,
+        Deprecation::NotDeprecated,
     );
 
     environment.insert_accessors(
         "Cat",
         AccessorsMap {
             public: true,
+            erlang_map: false,
             type_: cat_type,
             accessors: [
                 (
@@ -157,6 +171,7 @@ wibble"#,
     let var = TypedExpr::Var {
         location: SrcSpan { start: 15, end: 21 },
         constructor: ValueConstructor {
+            deprecation: Deprecation::NotDeprecated,
             public: false,
             variant: ValueConstructorVariant::LocalVariable {
                 location: SrcSpan { start: 4, end: 10 },
@@ -450,9 +465,11 @@ fn find_node_bool() {
     let bool = TypedExpr::Var {
         location: SrcSpan { start: 1, end: 5 },
         constructor: ValueConstructor {
+            deprecation: Deprecation::NotDeprecated,
             public: true,
             variant: ValueConstructorVariant::Record {
                 constructors_count: 2,
+                erlang_map: false,
                 name: "True".into(),
                 arity: 0,
                 field_map: None,
@@ -514,3 +531,75 @@ import gleam
     assert!(module.find_node(13).is_none());
     assert!(module.find_node(14).is_none());
 }
+
+/// Records the names of the variables and the values of the constants it
+/// visits, in the order they are visited.
+#[derive(Default)]
+struct NameCollector {
+    names: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for NameCollector {
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::Var { name, .. } = expr {
+            self.names.push(name.to_string());
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        if let Pattern::Var { name, .. } = pattern {
+            self.names.push(name.to_string());
+        }
+        visit::visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        if let Constant::Int { value, .. } = constant {
+            self.names.push(value.clone());
+        }
+        visit::visit_typed_constant(self, constant);
+    }
+}
+
+#[test]
+fn visit_module() {
+    let module = compile_module(
+        r#"
+const one = [1, 2]
+
+fn main(x) {
+  let y = fn(z) { #(x, z) }
+  case y(x) {
+    #(a, b) if a == b -> a
+    _ -> x
+  }
+}
+"#,
+    );
+    let mut collector = NameCollector::default();
+    collector.visit_typed_module(&module);
+    assert_eq!(
+        collector.names,
+        vec!["1", "2", "x", "z", "y", "y", "x", "a", "b", "a", "x"]
+    );
+}
+
+#[test]
+fn definition_location_of_module_function() {
+    let module = compile_module(
+        r#"
+fn one() { 1 }
+
+fn two() { one() }
+"#,
+    );
+    // The `one` in the body of `two`
+    let node = module.find_node(28).expect("node");
+    let location = node.definition_location().expect("definition location");
+    assert_eq!(
+        location.module.as_deref(),
+        Some(module.name.join("/").as_str())
+    );
+    assert_eq!(location.span, SrcSpan { start: 1, end: 9 });
+}
@@ -1,5 +1,5 @@
 use super::*;
-use crate::type_::{bool, HasType, Type};
+use crate::type_::{bool, HasType, Type, VariantField};
 
 use lazy_static::lazy_static;
 
@@ -41,7 +41,7 @@ pub enum TypedExpr {
     Var {
         location: SrcSpan,
         constructor: ValueConstructor,
-        name: String,
+        name: Name,
     },
 
     Fn {
@@ -81,6 +81,8 @@ pub enum TypedExpr {
         value: Box<Self>,
         pattern: Pattern<PatternConstructor, Arc<Type>>,
         kind: AssignmentKind,
+        annotation: Option<TypeAst>,
+        message: Option<String>,
     },
 
     Try {
@@ -89,29 +91,34 @@ pub enum TypedExpr {
         value: Box<Self>,
         then: Box<Self>,
         pattern: Pattern<PatternConstructor, Arc<Type>>,
+        annotation: Option<TypeAst>,
     },
 
     Case {
         location: SrcSpan,
         typ: Arc<Type>,
         subjects: Vec<Self>,
-        clauses: Vec<Clause<Self, PatternConstructor, Arc<Type>, String>>,
+        clauses: Vec<Clause<Self, PatternConstructor, Arc<Type>, Name>>,
     },
 
     RecordAccess {
         location: SrcSpan,
         typ: Arc<Type>,
-        label: String,
+        label: Name,
         index: u64,
+        /// The position of the field in each constructor of the record's
+        /// type, if it is not `index` in all of them.
+        variants: Vec<VariantField>,
         record: Box<Self>,
+        erlang_map: bool,
     },
 
     ModuleSelect {
         location: SrcSpan,
         typ: Arc<Type>,
-        label: String,
-        module_name: String,
-        module_alias: String,
+        label: Name,
+        module_name: Name,
+        module_alias: Name,
         constructor: ModuleValueConstructor,
     },
 
@@ -145,33 +152,70 @@ pub enum TypedExpr {
         typ: Arc<Type>,
         spread: Box<Self>,
         args: Vec<TypedRecordUpdateArg>,
+        erlang_map: bool,
     },
 
     Negate {
         location: SrcSpan,
         value: Box<Self>,
     },
+
+    Echo {
+        location: SrcSpan,
+        value: Box<Self>,
+    },
+
+    /// A feature flag, which is known to be enabled or not when the package
+    /// is compiled.
+    Cfg {
+        location: SrcSpan,
+        typ: Arc<Type>,
+        flag: Name,
+        enabled: bool,
+    },
 }
 
 impl TypedExpr {
     // This could be optimised in places to exit early if the first of a series
     // of expressions is after the byte index.
     pub fn find_node(&self, byte_index: u32) -> Option<&Self> {
+        self.find_node_where(byte_index, &|expression| {
+            !matches!(
+                expression,
+                Self::Pipeline { .. }
+                    | Self::Sequence { .. }
+                    | Self::BinOp { .. }
+                    | Self::Assignment { .. }
+            )
+        })
+    }
+
+    /// Find the innermost expression that contains the byte index and that
+    /// the predicate holds for.
+    pub fn find_node_where(
+        &self,
+        byte_index: u32,
+        predicate: &impl Fn(&Self) -> bool,
+    ) -> Option<&Self> {
         if !self.location().contains(byte_index) {
             return None;
         }
 
+        let this = || Some(self).filter(|expression| predicate(expression));
+
         match self {
             Self::Var { .. }
             | Self::Int { .. }
             | Self::Todo { .. }
+            | Self::Cfg { .. }
             | Self::Float { .. }
             | Self::String { .. }
-            | Self::ModuleSelect { .. } => Some(self),
+            | Self::ModuleSelect { .. } => this(),
 
-            Self::Pipeline { expressions, .. } | Self::Sequence { expressions, .. } => {
-                expressions.iter().find_map(|e| e.find_node(byte_index))
-            }
+            Self::Pipeline { expressions, .. } | Self::Sequence { expressions, .. } => expressions
+                .iter()
+                .find_map(|e| e.find_node_where(byte_index, predicate))
+                .or_else(this),
 
             Self::Tuple {
                 elems: expressions, ..
@@ -181,59 +225,66 @@ impl TypedExpr {
                 ..
             } => expressions
                 .iter()
-                .find_map(|e| e.find_node(byte_index))
-                .or(Some(self)),
+                .find_map(|e| e.find_node_where(byte_index, predicate))
+                .or_else(this),
 
-            Self::Negate { value, .. } => value.find_node(byte_index).or(Some(self)),
+            Self::Negate { value, .. } | Self::Echo { value, .. } => {
+                value.find_node_where(byte_index, predicate).or_else(this)
+            }
 
-            Self::Fn { body, .. } => body.find_node(byte_index).or(Some(self)),
+            Self::Fn { body, .. } => body.find_node_where(byte_index, predicate).or_else(this),
 
             Self::Call { fun, args, .. } => args
                 .iter()
-                .find_map(|arg| arg.find_node(byte_index))
-                .or_else(|| fun.find_node(byte_index))
-                .or(Some(self)),
+                .find_map(|arg| arg.value.find_node_where(byte_index, predicate))
+                .or_else(|| fun.find_node_where(byte_index, predicate))
+                .or_else(this),
 
             Self::BinOp { left, right, .. } => left
-                .find_node(byte_index)
-                .or_else(|| right.find_node(byte_index)),
+                .find_node_where(byte_index, predicate)
+                .or_else(|| right.find_node_where(byte_index, predicate))
+                .or_else(this),
 
-            Self::Assignment { value, .. } => value.find_node(byte_index),
+            Self::Assignment { value, .. } => {
+                value.find_node_where(byte_index, predicate).or_else(this)
+            }
 
             Self::Try { value, then, .. } => value
-                .find_node(byte_index)
-                .or_else(|| then.find_node(byte_index))
-                .or(Some(self)),
+                .find_node_where(byte_index, predicate)
+                .or_else(|| then.find_node_where(byte_index, predicate))
+                .or_else(this),
 
             Self::Case {
                 subjects, clauses, ..
             } => subjects
                 .iter()
-                .find_map(|subject| subject.find_node(byte_index))
+                .find_map(|subject| subject.find_node_where(byte_index, predicate))
                 .or_else(|| {
                     clauses
                         .iter()
-                        .find_map(|clause| clause.find_node(byte_index))
+                        .find_map(|clause| clause.then.find_node_where(byte_index, predicate))
                 })
-                .or(Some(self)),
+                .or_else(this),
 
             Self::RecordAccess {
                 record: expression, ..
             }
             | Self::TupleIndex {
                 tuple: expression, ..
-            } => expression.find_node(byte_index).or(Some(self)),
+            } => expression
+                .find_node_where(byte_index, predicate)
+                .or_else(this),
 
             Self::BitString { segments, .. } => segments
                 .iter()
-                .find_map(|arg| arg.find_node(byte_index))
-                .or(Some(self)),
+                .find_map(|segment| segment.value.find_node_where(byte_index, predicate))
+                .or_else(this),
 
             Self::RecordUpdate { spread, args, .. } => args
                 .iter()
-                .find_map(|arg| arg.find_node(byte_index))
-                .or_else(|| spread.find_node(byte_index))
-                .or(Some(self)),
+                .find_map(|arg| arg.value.find_node_where(byte_index, predicate))
+                .or_else(|| spread.find_node_where(byte_index, predicate))
+                .or_else(this),
         }
     }
 
@@ -264,6 +315,8 @@ impl TypedExpr {
             | Self::Tuple { location, .. }
             | Self::String { location, .. }
             | Self::Negate { location, .. }
+            | Self::Echo { location, .. }
+            | Self::Cfg { location, .. }
             | Self::Sequence { location, .. }
             | Self::Pipeline { location, .. }
             | Self::BitString { location, .. }
@@ -290,6 +343,8 @@ impl TypedExpr {
             | Self::Tuple { location, .. }
             | Self::String { location, .. }
             | Self::Negate { location, .. }
+            | Self::Echo { location, .. }
+            | Self::Cfg { location, .. }
             | Self::Pipeline { location, .. }
             | Self::BitString { location, .. }
             | Self::Assignment { location, .. }
@@ -327,6 +382,8 @@ impl TypedExpr {
             | TypedExpr::Float { .. }
             | TypedExpr::Tuple { .. }
             | TypedExpr::Negate { .. }
+            | TypedExpr::Echo { .. }
+            | TypedExpr::Cfg { .. }
             | TypedExpr::String { .. }
             | TypedExpr::Sequence { .. }
             | TypedExpr::Pipeline { .. }
@@ -345,7 +402,7 @@ impl TypedExpr {
                 constructor,
                 ..
             } => Some(DefinitionLocation {
-                module: Some(module_name.as_str()),
+                module: Some(module_name.as_str().into()),
                 span: constructor.location(),
             }),
 
@@ -359,9 +416,11 @@ impl TypedExpr {
             Self::Negate { .. } => bool(),
             Self::Var { constructor, .. } => constructor.type_.clone(),
             Self::Try { then, .. } => then.type_(),
+            Self::Echo { value, .. } => value.type_(),
             Self::Fn { typ, .. }
             | Self::Int { typ, .. }
             | Self::Todo { typ, .. }
+            | Self::Cfg { typ, .. }
             | Self::Case { typ, .. }
             | Self::List { typ, .. }
             | Self::Call { typ, .. }
@@ -409,11 +468,9 @@ impl HasType for TypedExpr {
 
 impl crate::bit_string::GetLitValue for TypedExpr {
     fn as_int_literal(&self) -> Option<i64> {
-        if let TypedExpr::Int { value: val, .. } = self {
-            if let Ok(val) = val.parse::<i64>() {
-                return Some(val);
-            }
+        match self {
+            TypedExpr::Int { value, .. } => crate::int::parse(value),
+            _ => None,
         }
-        None
     }
 }
@@ -2,8 +2,18 @@ use vec1::Vec1;
 
 use super::*;
 
-#[derive(Debug, PartialEq, Clone)]
-pub enum UntypedExpr {
+/// The untyped AST of a module is only needed from when it is parsed until it
+/// has been analysed, so its expressions are allocated together in an arena
+/// that is freed all at once afterwards, rather than each being boxed on its
+/// own. The typed AST is kept by the build cache and the language server
+/// between compilations, so it owns its expressions.
+pub type Arena = bumpalo::Bump;
+
+/// An expression allocated in the arena of its module.
+pub type ArenaBox<'a, T> = bumpalo::boxed::Box<'a, T>;
+
+#[derive(Debug, PartialEq)]
+pub enum UntypedExpr<'a> {
     Int {
         location: SrcSpan,
         value: String,
@@ -26,34 +36,34 @@ pub enum UntypedExpr {
 
     Var {
         location: SrcSpan,
-        name: String,
+        name: Name,
     },
 
     Fn {
         location: SrcSpan,
         is_capture: bool,
         arguments: Vec<Arg<()>>,
-        body: Box<Self>,
+        body: ArenaBox<'a, Self>,
         return_annotation: Option<TypeAst>,
     },
 
     List {
         location: SrcSpan,
         elements: Vec<Self>,
-        tail: Option<Box<Self>>,
+        tail: Option<ArenaBox<'a, Self>>,
     },
 
     Call {
         location: SrcSpan,
-        fun: Box<Self>,
+        fun: ArenaBox<'a, Self>,
         arguments: Vec<CallArg<Self>>,
     },
 
     BinOp {
         location: SrcSpan,
         name: BinOp,
-        left: Box<Self>,
-        right: Box<Self>,
+        left: ArenaBox<'a, Self>,
+        right: ArenaBox<'a, Self>,
     },
 
     PipeLine {
@@ -62,21 +72,24 @@ pub enum UntypedExpr {
 
     Assignment {
         location: SrcSpan,
-        value: Box<Self>,
+        value: ArenaBox<'a, Self>,
         pattern: Pattern<(), ()>,
         kind: AssignmentKind,
         annotation: Option<TypeAst>,
+        /// The message given to an `assert` with `as "..."`, which is used
+        /// for the crash when the pattern does not match.
+        message: Option<String>,
     },
 
     Try {
         location: SrcSpan,
-        value: Box<Self>,
+        value: ArenaBox<'a, Self>,
         pattern: Pattern<(), ()>,
-        then: Box<Self>,
+        then: ArenaBox<'a, Self>,
         annotation: Option<TypeAst>,
     },
 
-    Use(Use),
+    Use(Use<'a>),
 
     Case {
         location: SrcSpan,
@@ -86,8 +99,8 @@ pub enum UntypedExpr {
 
     FieldAccess {
         location: SrcSpan,
-        label: String,
-        container: Box<Self>,
+        label: Name,
+        container: ArenaBox<'a, Self>,
     },
 
     Tuple {
@@ -98,7 +111,7 @@ pub enum UntypedExpr {
     TupleIndex {
         location: SrcSpan,
         index: u64,
-        tuple: Box<Self>,
+        tuple: ArenaBox<'a, Self>,
     },
 
     Todo {
@@ -109,23 +122,36 @@ pub enum UntypedExpr {
 
     BitString {
         location: SrcSpan,
-        segments: Vec<UntypedExprBitStringSegment>,
+        segments: Vec<UntypedExprBitStringSegment<'a>>,
     },
 
     RecordUpdate {
         location: SrcSpan,
-        constructor: Box<Self>,
-        spread: RecordUpdateSpread,
-        arguments: Vec<UntypedRecordUpdateArg>,
+        constructor: ArenaBox<'a, Self>,
+        spread: RecordUpdateSpread<'a>,
+        arguments: Vec<UntypedRecordUpdateArg<'a>>,
     },
 
     Negate {
         location: SrcSpan,
-        value: Box<Self>,
+        value: ArenaBox<'a, Self>,
+    },
+
+    /// `echo value`, which prints the value when it is run and evaluates to
+    /// it unchanged.
+    Echo {
+        location: SrcSpan,
+        value: ArenaBox<'a, Self>,
+    },
+
+    /// `@cfg(flag)`, whether a feature flag of the package is enabled.
+    Cfg {
+        location: SrcSpan,
+        flag: Name,
     },
 }
 
-impl UntypedExpr {
+impl UntypedExpr<'_> {
     pub fn location(&self) -> SrcSpan {
         match self {
             Self::Try { then, .. } => then.location(),
@@ -147,7 +173,9 @@ impl UntypedExpr {
             | Self::TupleIndex { location, .. }
             | Self::FieldAccess { location, .. }
             | Self::RecordUpdate { location, .. }
-            | Self::Negate { location, .. } => *location,
+            | Self::Negate { location, .. }
+            | Self::Echo { location, .. }
+            | Self::Cfg { location, .. } => *location,
             Self::Sequence {
                 location,
                 expressions,
@@ -200,6 +228,13 @@ impl UntypedExpr {
         match self {
             Self::BinOp { name, .. } => name.precedence(),
             Self::PipeLine { .. } => 5,
+            // An operator section such as `_ + 1` always needs wrapping when
+            // used as an operand, as otherwise it would take in the operator.
+            Self::Fn {
+                is_capture: true,
+                body,
+                ..
+            } if !matches!(body.as_ref(), Self::Call { .. }) => 0,
             _ => std::u8::MAX,
         }
     }
@@ -212,15 +247,15 @@ impl UntypedExpr {
     }
 }
 
-impl HasLocation for UntypedExpr {
+impl HasLocation for UntypedExpr<'_> {
     fn location(&self) -> SrcSpan {
         self.location()
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Use {
+#[derive(Debug, PartialEq)]
+pub struct Use<'a> {
     pub location: SrcSpan,
-    pub call: Box<UntypedExpr>,
+    pub call: ArenaBox<'a, UntypedExpr<'a>>,
     pub assignments: Vec<(AssignName, SrcSpan)>,
 }
@@ -0,0 +1,349 @@
+//! A visitor over the typed AST.
+//!
+//! Implement [`Visit`] and override the methods for the nodes of interest.
+//! Each method defaults to visiting the children of its node with the
+//! corresponding `visit_*` function of this module, so an overriding method
+//! calls that function to continue into the children.
+//!
+//! ```ignore
+//! struct CallCounter(usize);
+//!
+//! impl<'ast> Visit<'ast> for CallCounter {
+//!     fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+//!         if let TypedExpr::Call { .. } = expr {
+//!             self.0 += 1;
+//!         }
+//!         visit::visit_typed_expr(self, expr);
+//!     }
+//! }
+//! ```
+
+use super::{
+    BitStringSegment, CallArg, ClauseGuard, Constant, Pattern, Statement, TypedArg, TypedClause,
+    TypedClauseGuard, TypedConstant, TypedExpr, TypedModule, TypedPattern, TypedStatement,
+};
+
+pub trait Visit<'ast> {
+    fn visit_typed_module(&mut self, module: &'ast TypedModule) {
+        visit_typed_module(self, module);
+    }
+
+    fn visit_typed_statement(&mut self, statement: &'ast TypedStatement) {
+        visit_typed_statement(self, statement);
+    }
+
+    fn visit_typed_arg(&mut self, _arg: &'ast TypedArg) {}
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_call_arg(&mut self, arg: &'ast CallArg<TypedExpr>) {
+        visit_typed_call_arg(self, arg);
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        visit_typed_clause(self, clause);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_clause_guard(&mut self, guard: &'ast TypedClauseGuard) {
+        visit_typed_clause_guard(self, guard);
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        visit_typed_constant(self, constant);
+    }
+}
+
+pub fn visit_typed_module<'a, V>(v: &mut V, module: &'a TypedModule)
+where
+    V: Visit<'a> + ?Sized,
+{
+    for statement in &module.statements {
+        v.visit_typed_statement(statement);
+    }
+}
+
+pub fn visit_typed_statement<'a, V>(v: &mut V, statement: &'a TypedStatement)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match statement {
+        Statement::Fn {
+            arguments, body, ..
+        } => {
+            for argument in arguments {
+                v.visit_typed_arg(argument);
+            }
+            v.visit_typed_expr(body);
+        }
+
+        Statement::ModuleConstant { value, .. } => v.visit_typed_constant(value),
+
+        Statement::TypeAlias { .. }
+        | Statement::CustomType { .. }
+        | Statement::ExternalFn { .. }
+        | Statement::ExternalType { .. }
+        | Statement::Import { .. } => (),
+    }
+}
+
+pub fn visit_typed_expr<'a, V>(v: &mut V, expr: &'a TypedExpr)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match expr {
+        TypedExpr::Int { .. }
+        | TypedExpr::Float { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::Var { .. }
+        | TypedExpr::ModuleSelect { .. }
+        | TypedExpr::Todo { .. }
+        | TypedExpr::Cfg { .. } => (),
+
+        TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+            for expression in expressions {
+                v.visit_typed_expr(expression);
+            }
+        }
+
+        TypedExpr::Fn { args, body, .. } => {
+            for arg in args {
+                v.visit_typed_arg(arg);
+            }
+            v.visit_typed_expr(body);
+        }
+
+        TypedExpr::List { elements, tail, .. } => {
+            for element in elements {
+                v.visit_typed_expr(element);
+            }
+            if let Some(tail) = tail {
+                v.visit_typed_expr(tail);
+            }
+        }
+
+        TypedExpr::Call { fun, args, .. } => {
+            v.visit_typed_expr(fun);
+            for arg in args {
+                v.visit_typed_call_arg(arg);
+            }
+        }
+
+        TypedExpr::BinOp { left, right, .. } => {
+            v.visit_typed_expr(left);
+            v.visit_typed_expr(right);
+        }
+
+        TypedExpr::Assignment { value, pattern, .. } => {
+            v.visit_typed_expr(value);
+            v.visit_typed_pattern(pattern);
+        }
+
+        TypedExpr::Try {
+            value,
+            then,
+            pattern,
+            ..
+        } => {
+            v.visit_typed_expr(value);
+            v.visit_typed_pattern(pattern);
+            v.visit_typed_expr(then);
+        }
+
+        TypedExpr::Case {
+            subjects, clauses, ..
+        } => {
+            for subject in subjects {
+                v.visit_typed_expr(subject);
+            }
+            for clause in clauses {
+                v.visit_typed_clause(clause);
+            }
+        }
+
+        TypedExpr::RecordAccess { record, .. } => v.visit_typed_expr(record),
+
+        TypedExpr::Tuple { elems, .. } => {
+            for elem in elems {
+                v.visit_typed_expr(elem);
+            }
+        }
+
+        TypedExpr::TupleIndex { tuple, .. } => v.visit_typed_expr(tuple),
+
+        TypedExpr::BitString { segments, .. } => {
+            for segment in segments {
+                v.visit_typed_expr(&segment.value);
+                for option in &segment.options {
+                    if let Some(value) = option.value() {
+                        v.visit_typed_expr(value);
+                    }
+                }
+            }
+        }
+
+        TypedExpr::RecordUpdate { spread, args, .. } => {
+            v.visit_typed_expr(spread);
+            for arg in args {
+                v.visit_typed_expr(&arg.value);
+            }
+        }
+
+        TypedExpr::Negate { value, .. } | TypedExpr::Echo { value, .. } => {
+            v.visit_typed_expr(value)
+        }
+    }
+}
+
+pub fn visit_typed_call_arg<'a, V>(v: &mut V, arg: &'a CallArg<TypedExpr>)
+where
+    V: Visit<'a> + ?Sized,
+{
+    v.visit_typed_expr(&arg.value);
+}
+
+pub fn visit_typed_clause<'a, V>(v: &mut V, clause: &'a TypedClause)
+where
+    V: Visit<'a> + ?Sized,
+{
+    for pattern in std::iter::once(&clause.pattern)
+        .chain(&clause.alternative_patterns)
+        .flatten()
+    {
+        v.visit_typed_pattern(pattern);
+    }
+    if let Some(guard) = &clause.guard {
+        v.visit_typed_clause_guard(guard);
+    }
+    v.visit_typed_expr(&clause.then);
+}
+
+pub fn visit_typed_pattern<'a, V>(v: &mut V, pattern: &'a TypedPattern)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match pattern {
+        Pattern::Int { .. }
+        | Pattern::IntRange { .. }
+        | Pattern::Float { .. }
+        | Pattern::String { .. }
+        | Pattern::Var { .. }
+        | Pattern::VarUsage { .. }
+        | Pattern::Discard { .. }
+        | Pattern::Concatenate { .. } => (),
+
+        Pattern::Assign { pattern, .. } => v.visit_typed_pattern(pattern),
+
+        Pattern::List { elements, tail, .. } => {
+            for element in elements {
+                v.visit_typed_pattern(element);
+            }
+            if let Some(tail) = tail {
+                v.visit_typed_pattern(tail);
+            }
+        }
+
+        Pattern::Constructor { arguments, .. } => {
+            for argument in arguments {
+                v.visit_typed_pattern(&argument.value);
+            }
+        }
+
+        Pattern::Tuple { elems, .. } => {
+            for elem in elems {
+                v.visit_typed_pattern(elem);
+            }
+        }
+
+        Pattern::BitString { segments, .. } => {
+            for BitStringSegment { value, options, .. } in segments {
+                v.visit_typed_pattern(value);
+                for option in options {
+                    if let Some(value) = option.value() {
+                        v.visit_typed_pattern(value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn visit_typed_clause_guard<'a, V>(v: &mut V, guard: &'a TypedClauseGuard)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match guard {
+        ClauseGuard::Equals { left, right, .. }
+        | ClauseGuard::NotEquals { left, right, .. }
+        | ClauseGuard::GtInt { left, right, .. }
+        | ClauseGuard::GtEqInt { left, right, .. }
+        | ClauseGuard::LtInt { left, right, .. }
+        | ClauseGuard::LtEqInt { left, right, .. }
+        | ClauseGuard::GtFloat { left, right, .. }
+        | ClauseGuard::GtEqFloat { left, right, .. }
+        | ClauseGuard::LtFloat { left, right, .. }
+        | ClauseGuard::LtEqFloat { left, right, .. }
+        | ClauseGuard::Or { left, right, .. }
+        | ClauseGuard::And { left, right, .. } => {
+            v.visit_typed_clause_guard(left);
+            v.visit_typed_clause_guard(right);
+        }
+
+        ClauseGuard::Var { .. } => (),
+
+        ClauseGuard::TupleIndex { tuple, .. } => v.visit_typed_clause_guard(tuple),
+
+        ClauseGuard::Call { args, .. } => {
+            for arg in args {
+                v.visit_typed_clause_guard(arg);
+            }
+        }
+
+        ClauseGuard::Constant(constant) => v.visit_typed_constant(constant),
+    }
+}
+
+pub fn visit_typed_constant<'a, V>(v: &mut V, constant: &'a TypedConstant)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match constant {
+        Constant::Int { .. }
+        | Constant::Float { .. }
+        | Constant::String { .. }
+        | Constant::Var { .. } => (),
+
+        Constant::Tuple { elements, .. } | Constant::List { elements, .. } => {
+            for element in elements {
+                v.visit_typed_constant(element);
+            }
+        }
+
+        Constant::Record { args, .. } => {
+            for arg in args {
+                v.visit_typed_constant(&arg.value);
+            }
+        }
+
+        Constant::BitString { segments, .. } => {
+            for segment in segments {
+                v.visit_typed_constant(&segment.value);
+                for option in &segment.options {
+                    if let Some(value) = option.value() {
+                        v.visit_typed_constant(value);
+                    }
+                }
+            }
+        }
+
+        Constant::BinOp { left, right, .. } => {
+            v.visit_typed_constant(left);
+            v.visit_typed_constant(right);
+        }
+    }
+}
@@ -274,15 +274,9 @@ where
 {
     fn as_int_literal(&self) -> Option<i64> {
         match self {
-            crate::ast::Pattern::Int { value, .. } => {
-                if let Ok(val) = value.parse::<i64>() {
-                    return Some(val);
-                }
-            }
-            crate::ast::Pattern::VarUsage { .. } => return None,
-            _ => (),
+            crate::ast::Pattern::Int { value, .. } => crate::int::parse(value),
+            _ => None,
         }
-        None
     }
 }
 
@@ -1,16 +1,26 @@
 #![allow(warnings)]
 
+mod dead_code;
 mod dep_tree;
+mod doctests;
+mod main_function;
 pub mod package_compiler;
 mod project_compiler;
 mod telemetry;
+mod test_functions;
+mod timings;
 
 #[cfg(test)]
 mod package_compilation_tests;
 
+pub use self::dead_code::find_dead_code;
+pub use self::doctests::{doctest_module, DOCTEST_MODULE_PREFIX};
+pub use self::main_function::check_main_function;
 pub use self::package_compiler::PackageCompiler;
-pub use self::project_compiler::{Options, ProjectCompiler};
+pub use self::project_compiler::{LocalPackage, Options, ProjectCompiler};
 pub use self::telemetry::Telemetry;
+pub use self::test_functions::{test_function_name, test_functions, TestFunction};
+pub use self::timings::{Phase, Span, Timings};
 
 use crate::ast::{DefinitionLocation, TypedExpr, TypedStatement};
 use crate::{
@@ -19,7 +29,10 @@ use crate::{
     erlang,
     error::{Error, FileIoAction, FileKind},
     io::OutputFile,
-    parse::extra::{Comment, ModuleExtra},
+    parse::{
+        extra::{Comment, ModuleExtra},
+        syntax_tree::SyntaxTree,
+    },
     type_,
 };
 use itertools::Itertools;
@@ -30,7 +43,17 @@ use std::{
 use strum::{Display, EnumString, EnumVariantNames, VariantNames};
 
 #[derive(
-    Debug, Serialize, Deserialize, Display, EnumString, EnumVariantNames, Clone, Copy, PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
 )]
 #[strum(serialize_all = "lowercase")]
 pub enum Target {
@@ -40,12 +63,44 @@ pub enum Target {
     JavaScript,
     #[serde(rename = "native")]
     Native,
+    #[serde(rename = "go")]
+    Go,
+    #[serde(rename = "llvm")]
+    Llvm,
+    #[serde(rename = "wasm")]
+    Wasm,
 }
 
 impl Target {
+    /// Every supported target, in the order that `gleam build --target all`
+    /// compiles for them.
+    pub const ALL: [Target; 3] = [Target::Erlang, Target::JavaScript, Target::Native];
+
+    /// The experimental targets, which are only compiled for when asked for
+    /// by name.
+    pub const EXPERIMENTAL: [Target; 3] = [Target::Go, Target::Llvm, Target::Wasm];
+
+    pub fn is_experimental(&self) -> bool {
+        Self::EXPERIMENTAL.contains(self)
+    }
+
     pub fn variant_strings() -> Vec<String> {
         Self::VARIANTS.iter().map(|s| s.to_string()).collect()
     }
+
+    /// The smallest and largest `Int` that can be represented exactly on this
+    /// target, if it does not have arbitrary precision integers. Native code
+    /// and Go use 64 bit integers, LLVM and WebAssembly use 63 bit integers
+    /// tagged to tell them apart from pointers, and JavaScript uses numbers
+    /// which are only exact up to 2^53 - 1.
+    pub fn int_range(&self) -> Option<(i128, i128)> {
+        match self {
+            Self::Erlang => None,
+            Self::JavaScript => Some((-(2_i128.pow(53) - 1), 2_i128.pow(53) - 1)),
+            Self::Native | Self::Go => Some((i64::MIN.into(), i64::MAX.into())),
+            Self::Llvm | Self::Wasm => Some((-(2_i128.pow(62)), 2_i128.pow(62) - 1)),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -57,6 +112,9 @@ pub enum TargetCodegenConfiguration {
         app_file: Option<ErlangAppCodegenConfiguration>,
     },
     CPlusPlus,
+    Go,
+    Llvm,
+    Wasm,
 }
 
 impl TargetCodegenConfiguration {
@@ -65,6 +123,9 @@ impl TargetCodegenConfiguration {
             Self::JavaScript { .. } => Target::JavaScript,
             Self::Erlang { .. } => Target::Erlang,
             Self::CPlusPlus => Target::Native,
+            Self::Go => Target::Go,
+            Self::Llvm => Target::Llvm,
+            Self::Wasm => Target::Wasm,
         }
     }
 }
@@ -113,7 +174,7 @@ impl Package {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Module {
     pub name: String,
     pub code: String,
@@ -130,6 +191,12 @@ impl Module {
         PathBuf::from(path)
     }
 
+    pub fn compiled_elixir_path(&self) -> PathBuf {
+        let mut path = self.name.replace("/", "@");
+        path.push_str(".ex");
+        PathBuf::from(path)
+    }
+
     pub fn is_test(&self) -> bool {
         self.origin == Origin::Test
     }
@@ -138,6 +205,12 @@ impl Module {
         self.ast.find_node(byte_index)
     }
 
+    /// The lossless syntax tree of the module, for editing its source without
+    /// losing comments or formatting.
+    pub fn syntax_tree(&self) -> SyntaxTree {
+        SyntaxTree::new(&self.code, &self.extra)
+    }
+
     pub fn attach_doc_and_module_comments(&mut self) {
         // Module Comments
         self.ast.documentation = self
@@ -165,16 +238,46 @@ impl Module {
                 statement.put_doc(doc);
             }
 
-            if let Statement::CustomType { constructors, .. } = statement {
-                for constructor in constructors {
-                    let docs: Vec<&str> =
-                        comments_before(&mut doc_comments, constructor.location.start, &self.code);
-                    if !docs.is_empty() {
-                        let doc = docs.join("\n");
-                        constructor.put_doc(doc);
+            match statement {
+                Statement::CustomType { constructors, .. } => {
+                    for constructor in constructors {
+                        let docs: Vec<&str> = comments_before(
+                            &mut doc_comments,
+                            constructor.location.start,
+                            &self.code,
+                        );
+                        if !docs.is_empty() {
+                            let doc = docs.join("\n");
+                            constructor.put_doc(doc);
+                        }
+
+                        for argument in constructor.arguments.iter_mut() {
+                            let docs: Vec<&str> = comments_before(
+                                &mut doc_comments,
+                                argument.location.start,
+                                &self.code,
+                            );
+                            if !docs.is_empty() {
+                                let doc = docs.join("\n");
+                                argument.put_doc(doc);
+                            }
+                        }
+                    }
+                }
+
+                Statement::Fn { arguments, .. } => {
+                    for argument in arguments.iter_mut() {
+                        let docs: Vec<&str> =
+                            comments_before(&mut doc_comments, argument.location.start, &self.code);
+                        if !docs.is_empty() {
+                            let doc = docs.join("\n");
+                            argument.put_doc(doc);
+                        }
                     }
+                }
 
-                    for argument in constructor.arguments.iter_mut() {
+                Statement::ExternalFn { arguments, .. } => {
+                    for argument in arguments.iter_mut() {
                         let docs: Vec<&str> =
                             comments_before(&mut doc_comments, argument.location.start, &self.code);
                         if !docs.is_empty() {
@@ -183,6 +286,8 @@ impl Module {
                         }
                     }
                 }
+
+                _ => (),
             }
         }
     }
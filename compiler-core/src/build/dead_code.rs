@@ -0,0 +1,510 @@
+//! Whole package dead code detection.
+//!
+//! The type checker already warns about private items that are never
+//! referenced within their own module, but it cannot tell whether a public
+//! function is used elsewhere in the package, or whether a private function is
+//! only referenced by other code that is itself unused. This pass builds a
+//! graph of references between the top level definitions of every module in a
+//! package and reports anything that cannot be reached from the entrypoints.
+//!
+//! The entrypoints are any `main` functions in `src` modules along with
+//! everything defined in `test` modules. If the package has no `main`
+//! function it is treated as a library and its public API is used instead.
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        Constant, Pattern, SrcSpan, Statement, TypedArg, TypedConstant, TypedExpr, TypedPattern,
+    },
+    build::Module,
+    type_::{
+        collapse_links, ModuleValueConstructor, PatternConstructor, Type, ValueConstructor,
+        ValueConstructorVariant,
+    },
+    warning::DeadCodeKind,
+    Warning,
+};
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Item {
+    Value {
+        module: String,
+        name: String,
+    },
+    Type {
+        module: String,
+        name: String,
+    },
+    /// Module constants are referenced by their location rather than their
+    /// name, so these references are resolved once all modules are registered.
+    ConstantAt {
+        module: String,
+        start: u32,
+    },
+}
+
+#[derive(Debug)]
+struct Definition {
+    kind: DeadCodeKind,
+    location: SrcSpan,
+    public: bool,
+    /// The index of the module in which this item is defined.
+    module: usize,
+    references: HashSet<Item>,
+}
+
+/// Find all definitions in the given modules which are unreachable from the
+/// package entrypoints.
+pub fn find_dead_code(modules: &[Module]) -> Vec<Warning> {
+    let mut graph = Graph::default();
+    for (index, module) in modules.iter().enumerate() {
+        graph.register_module(index, module);
+    }
+    graph.resolve_external_references();
+    graph.dead_code_warnings(modules)
+}
+
+#[derive(Debug, Default)]
+struct Graph {
+    definitions: HashMap<Item, Definition>,
+    /// Values defined in Gleam modules, indexed by their module and location.
+    locations: HashMap<(String, u32), Item>,
+    /// External functions, indexed by the module and function they refer to.
+    externals: HashMap<(String, String), Vec<Item>>,
+    roots: HashSet<Item>,
+    library_roots: HashSet<Item>,
+}
+
+impl Graph {
+    fn register_module(&mut self, index: usize, module: &Module) {
+        let module_name = module.name.clone();
+        let is_test = module.is_test();
+
+        for statement in &module.ast.statements {
+            match statement {
+                Statement::Fn {
+                    name,
+                    public,
+                    location,
+                    arguments,
+                    body,
+                    return_type,
+                    ..
+                } => {
+                    let mut references = References::default();
+                    for argument in arguments {
+                        references.type_(&argument.type_);
+                    }
+                    references.type_(return_type);
+                    references.visit_typed_expr(body);
+                    let item = self.define(
+                        index,
+                        &module_name,
+                        name,
+                        DeadCodeKind::Function,
+                        *location,
+                        *public,
+                        references,
+                    );
+                    if is_test || (module.origin.is_src() && name == "main") {
+                        let _ = self.roots.insert(item.clone());
+                    }
+                    if *public {
+                        let _ = self.library_roots.insert(item);
+                    }
+                }
+
+                Statement::ExternalFn {
+                    name,
+                    public,
+                    location,
+                    arguments,
+                    return_type,
+                    module: external_module,
+                    fun,
+                    ..
+                } => {
+                    let mut references = References::default();
+                    for argument in arguments {
+                        references.type_(&argument.type_);
+                    }
+                    references.type_(return_type);
+                    let item = self.define(
+                        index,
+                        &module_name,
+                        name,
+                        DeadCodeKind::Function,
+                        *location,
+                        *public,
+                        references,
+                    );
+                    self.externals
+                        .entry((external_module.clone(), fun.clone()))
+                        .or_default()
+                        .push(item.clone());
+                    if is_test {
+                        let _ = self.roots.insert(item.clone());
+                    }
+                    if *public {
+                        let _ = self.library_roots.insert(item);
+                    }
+                }
+
+                Statement::ModuleConstant {
+                    name,
+                    public,
+                    location,
+                    value,
+                    type_,
+                    ..
+                } => {
+                    let mut references = References::default();
+                    references.type_(type_);
+                    references.visit_typed_constant(value);
+                    let item = self.define(
+                        index,
+                        &module_name,
+                        name,
+                        DeadCodeKind::Constant,
+                        *location,
+                        *public,
+                        references,
+                    );
+                    if is_test {
+                        let _ = self.roots.insert(item.clone());
+                    }
+                    if *public {
+                        let _ = self.library_roots.insert(item);
+                    }
+                }
+
+                Statement::CustomType {
+                    name,
+                    public,
+                    opaque,
+                    location,
+                    constructors,
+                    ..
+                } => {
+                    let type_item = Item::Type {
+                        module: module_name.clone(),
+                        name: name.to_string(),
+                    };
+                    let _ = self.definitions.insert(
+                        type_item.clone(),
+                        Definition {
+                            kind: DeadCodeKind::Type,
+                            location: *location,
+                            public: *public,
+                            module: index,
+                            references: HashSet::new(),
+                        },
+                    );
+                    if is_test {
+                        let _ = self.roots.insert(type_item.clone());
+                    }
+                    if *public {
+                        let _ = self.library_roots.insert(type_item.clone());
+                    }
+
+                    for constructor in constructors {
+                        let mut references = References::default();
+                        let _ = references.items.insert(type_item.clone());
+                        for argument in &constructor.arguments {
+                            references.type_(&argument.type_);
+                        }
+                        let item = self.define(
+                            index,
+                            &module_name,
+                            &constructor.name,
+                            DeadCodeKind::Constructor,
+                            constructor.location,
+                            *public && !opaque,
+                            references,
+                        );
+                        if is_test {
+                            let _ = self.roots.insert(item.clone());
+                        }
+                        if *public && !opaque {
+                            let _ = self.library_roots.insert(item);
+                        }
+                    }
+                }
+
+                Statement::TypeAlias { .. }
+                | Statement::ExternalType { .. }
+                | Statement::Import { .. } => (),
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn define(
+        &mut self,
+        index: usize,
+        module: &str,
+        name: &str,
+        kind: DeadCodeKind,
+        location: SrcSpan,
+        public: bool,
+        references: References,
+    ) -> Item {
+        let item = Item::Value {
+            module: module.to_string(),
+            name: name.to_string(),
+        };
+        let _ = self
+            .locations
+            .insert((module.to_string(), location.start), item.clone());
+        let definition = Definition {
+            kind,
+            location,
+            public,
+            module: index,
+            references: references.items,
+        };
+        let _ = self.definitions.insert(item.clone(), definition);
+        item
+    }
+
+    /// References are recorded before all the definitions are known, so
+    /// references that do not point at a Gleam definition directly (external
+    /// functions and constants) are resolved here.
+    fn resolve_external_references(&mut self) {
+        let known: HashSet<Item> = self.definitions.keys().cloned().collect();
+        let locations = &self.locations;
+        let externals = &self.externals;
+        for definition in self.definitions.values_mut() {
+            let references = std::mem::take(&mut definition.references);
+            for item in references {
+                if known.contains(&item) {
+                    let _ = definition.references.insert(item);
+                    continue;
+                }
+                match &item {
+                    Item::Value { module, name } => {
+                        if let Some(items) = externals.get(&(module.clone(), name.clone())) {
+                            definition.references.extend(items.iter().cloned());
+                        }
+                    }
+                    Item::ConstantAt { module, start } => {
+                        if let Some(item) = locations.get(&(module.clone(), *start)) {
+                            let _ = definition.references.insert(item.clone());
+                        }
+                    }
+                    Item::Type { .. } => (),
+                }
+            }
+        }
+    }
+
+    fn reachable(&self, roots: &HashSet<Item>) -> HashSet<Item> {
+        let mut reachable = HashSet::new();
+        let mut stack: Vec<&Item> = roots.iter().collect();
+        while let Some(item) = stack.pop() {
+            if !reachable.insert(item.clone()) {
+                continue;
+            }
+            if let Some(definition) = self.definitions.get(item) {
+                stack.extend(definition.references.iter());
+            }
+        }
+        reachable
+    }
+
+    fn dead_code_warnings(&self, modules: &[Module]) -> Vec<Warning> {
+        let has_main = modules.iter().any(|module| {
+            module.origin.is_src() && module.ast.type_info.values.contains_key("main")
+        });
+        let roots = if has_main {
+            self.roots.clone()
+        } else {
+            self.roots.union(&self.library_roots).cloned().collect()
+        };
+        let reachable = self.reachable(&roots);
+
+        // Anything that is never referenced at all and is private has already
+        // been reported by the type checker, so we only report private items
+        // that are referenced by other unreachable code.
+        let referenced: HashSet<&Item> = self
+            .definitions
+            .values()
+            .flat_map(|definition| definition.references.iter())
+            .collect();
+
+        self.definitions
+            .iter()
+            .filter(|(item, _)| !reachable.contains(*item))
+            .filter(|(item, definition)| definition.public || referenced.contains(item))
+            .filter_map(|(item, definition)| {
+                let module = modules.get(definition.module)?;
+                if module.is_test() {
+                    return None;
+                }
+                let name = match item {
+                    Item::Value { name, .. } | Item::Type { name, .. } => name.clone(),
+                    Item::ConstantAt { .. } => return None,
+                };
+                Some(Warning::DeadCode {
+                    path: module.input_path.clone(),
+                    src: module.code.clone(),
+                    location: definition.location,
+                    kind: definition.kind,
+                    name,
+                })
+            })
+            .sorted_by(|a, b| match (a, b) {
+                (
+                    Warning::DeadCode {
+                        path: a_path,
+                        location: a_location,
+                        ..
+                    },
+                    Warning::DeadCode {
+                        path: b_path,
+                        location: b_location,
+                        ..
+                    },
+                ) => (a_path, a_location.start).cmp(&(b_path, b_location.start)),
+                _ => std::cmp::Ordering::Equal,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Default)]
+struct References {
+    items: HashSet<Item>,
+}
+
+impl References {
+    fn value(&mut self, module: &str, name: &str) {
+        let _ = self.items.insert(Item::Value {
+            module: module.to_string(),
+            name: name.to_string(),
+        });
+    }
+
+    fn constant_at(&mut self, module: &str, location: SrcSpan) {
+        let _ = self.items.insert(Item::ConstantAt {
+            module: module.to_string(),
+            start: location.start,
+        });
+    }
+
+    fn type_(&mut self, type_: &Arc<Type>) {
+        match collapse_links(type_.clone()).as_ref() {
+            Type::App {
+                module, name, args, ..
+            } => {
+                if !module.is_empty() {
+                    let _ = self.items.insert(Item::Type {
+                        module: module.join("/"),
+                        name: name.to_string(),
+                    });
+                }
+                for arg in args {
+                    self.type_(arg);
+                }
+            }
+            Type::Fn { args, retrn } => {
+                for arg in args {
+                    self.type_(arg);
+                }
+                self.type_(retrn);
+            }
+            Type::Tuple { elems } => {
+                for elem in elems {
+                    self.type_(elem);
+                }
+            }
+            Type::Var { .. } => (),
+        }
+    }
+
+    /// Record constructors do not store which module they belong to, so we
+    /// find it from the type they construct.
+    fn record_constructor(&mut self, type_: &Arc<Type>, name: &str) {
+        let type_ = collapse_links(type_.clone());
+        let type_ = match type_.as_ref() {
+            Type::Fn { retrn, .. } => collapse_links(retrn.clone()),
+            _ => type_,
+        };
+        if let Type::App { module, .. } = type_.as_ref() {
+            self.value(&module.join("/"), name);
+        }
+    }
+
+    fn value_constructor(&mut self, constructor: &ValueConstructor) {
+        match &constructor.variant {
+            ValueConstructorVariant::LocalVariable { .. } => (),
+            ValueConstructorVariant::ModuleConstant {
+                module, location, ..
+            } => self.constant_at(module, *location),
+            ValueConstructorVariant::ModuleFn { module, name, .. } => {
+                self.value(&module.join("/"), name)
+            }
+            ValueConstructorVariant::Record { module, name, .. } => self.value(module, name),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for References {
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        self.type_(&arg.type_);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        match expr {
+            TypedExpr::Var { constructor, .. } => self.value_constructor(constructor),
+
+            TypedExpr::ModuleSelect {
+                label,
+                module_name,
+                constructor,
+                ..
+            } => match constructor {
+                ModuleValueConstructor::Fn { .. } | ModuleValueConstructor::Record { .. } => {
+                    self.value(module_name, label)
+                }
+                ModuleValueConstructor::Constant { location, .. } => {
+                    self.constant_at(module_name, *location)
+                }
+            },
+
+            _ => visit::visit_typed_expr(self, expr),
+        }
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        if let Pattern::Constructor {
+            constructor: PatternConstructor::Record { name, .. },
+            type_,
+            ..
+        } = pattern
+        {
+            self.record_constructor(type_, name);
+        }
+        visit::visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        match constant {
+            Constant::Record { tag, typ, .. } => self.record_constructor(typ, tag),
+            Constant::Var {
+                constructor: Some(constructor),
+                ..
+            } => self.value_constructor(constructor),
+            _ => (),
+        }
+        visit::visit_typed_constant(self, constant);
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,162 @@
+use super::*;
+use crate::{
+    build::Origin,
+    parse::extra::ModuleExtra,
+    type_::{build_prelude, infer_module},
+    uid::UniqueIdGenerator,
+};
+use std::path::PathBuf;
+
+/// Type check the given modules, which must be in dependency order, and
+/// return the names of the definitions reported as dead.
+fn dead_code(sources: &[(&str, Origin, &str)]) -> Vec<String> {
+    let ids = UniqueIdGenerator::new();
+    let mut importable = im::HashMap::new();
+    let _ = importable.insert("gleam".to_string(), build_prelude(&ids));
+    let mut modules = vec![];
+    for (name, origin, src) in sources {
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+        ast.name = name.split('/').map(Into::into).collect();
+        let ast = infer_module(
+            crate::build::Target::Erlang,
+            &ids,
+            ast,
+            *origin,
+            "thepackage",
+            &importable,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let _ = importable.insert(name.to_string(), ast.type_info.clone());
+        modules.push(Module {
+            name: name.to_string(),
+            code: src.to_string(),
+            input_path: PathBuf::from(format!("/src/{}.gleam", name)),
+            origin: *origin,
+            ast,
+            extra: ModuleExtra::new(),
+        });
+    }
+    find_dead_code(&modules)
+        .into_iter()
+        .map(|warning| match warning {
+            Warning::DeadCode { name, .. } => name,
+            _ => panic!("unexpected warning"),
+        })
+        .collect()
+}
+
+#[test]
+fn public_function_unused_in_package() {
+    assert_eq!(
+        dead_code(&[
+            (
+                "one",
+                Origin::Src,
+                "pub fn used() { 1 } pub fn unused() { 2 }"
+            ),
+            (
+                "app",
+                Origin::Src,
+                "import one pub fn main() { one.used() }"
+            ),
+        ]),
+        vec!["unused"]
+    );
+}
+
+#[test]
+fn functions_used_by_tests_are_live() {
+    assert_eq!(
+        dead_code(&[
+            ("one", Origin::Src, "pub fn tested() { 1 }"),
+            ("app", Origin::Src, "pub fn main() { Nil }"),
+            (
+                "one_test",
+                Origin::Test,
+                "import one pub fn tested_test() { one.tested() }"
+            ),
+        ]),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn private_function_only_used_by_dead_code() {
+    // `helper` is referenced so the type checker does not warn about it, but
+    // it is only used by an unreachable function.
+    assert_eq!(
+        dead_code(&[(
+            "app",
+            Origin::Src,
+            "pub fn main() { Nil } pub fn unused() { helper() } fn helper() { 1 }"
+        )]),
+        vec!["unused", "helper"]
+    );
+}
+
+#[test]
+fn library_public_api_is_live() {
+    assert_eq!(
+        dead_code(&[(
+            "lib",
+            Origin::Src,
+            "pub fn api() { 1 } fn unused() { helper() } fn helper() { 1 }"
+        )]),
+        vec!["helper"]
+    );
+}
+
+#[test]
+fn unused_types_and_constructors() {
+    assert_eq!(
+        dead_code(&[(
+            "app",
+            Origin::Src,
+            "pub type Used { Built Unbuilt }
+pub type Unused { Unused }
+pub fn main() { Built }"
+        )]),
+        vec!["Unbuilt", "Unused", "Unused"]
+    );
+}
+
+#[test]
+fn constructors_used_in_patterns_are_live() {
+    assert_eq!(
+        dead_code(&[
+            ("one", Origin::Src, "pub type Thing { A B }"),
+            (
+                "app",
+                Origin::Src,
+                "import one.{A, B}
+pub fn main() { check(A) }
+fn check(x) { case x { A -> 1 B -> 2 } }"
+            ),
+        ]),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn constants_and_external_functions() {
+    assert_eq!(
+        dead_code(&[
+            (
+                "one",
+                Origin::Src,
+                r#"pub const used = 1
+pub const unused = 2
+pub external fn ext() -> Int = "erlang" "ext"
+pub external fn unused_ext() -> Int = "erlang" "other""#
+            ),
+            (
+                "app",
+                Origin::Src,
+                "import one.{ext} pub fn main() { #(one.used, ext()) }"
+            ),
+        ]),
+        vec!["unused", "unused_ext"]
+    );
+}
@@ -1,3 +1,4 @@
+use itertools::Itertools;
 use petgraph::{algo::Cycle, graph::NodeIndex, Direction};
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
@@ -49,7 +50,9 @@ pub fn toposort_deps(inputs: Vec<(String, Vec<String>)>) -> Result<Vec<String>,
     }
 }
 
-// TODO: test
+/// The values of the cycle in dependency order, so each value depends on the
+/// next and the last depends on the first. The cycle starts from the first
+/// value by name so that it is the same from one build to the next.
 fn import_cycle(
     cycle: Cycle<NodeIndex>,
     graph: &petgraph::Graph<(), ()>,
@@ -58,13 +61,19 @@ fn import_cycle(
     let origin = cycle.node_id();
     let mut path = vec![];
     let _ = find_cycle(origin, origin, &graph, &mut path, &mut HashSet::new());
-    path.iter()
+    let mut cycle: Vec<_> = path
+        .iter()
+        .rev()
         .map(|index| {
             values
                 .remove(index)
                 .expect("dep_tree::import_cycle(): cannot find values for index")
         })
-        .collect()
+        .collect();
+    if let Some(first) = cycle.iter().position_min() {
+        cycle.rotate_left(first);
+    }
+    cycle
 }
 
 fn find_cycle(
@@ -126,6 +135,23 @@ fn toposort_deps_test() {
     );
 }
 
+#[test]
+fn toposort_deps_cycle_test() {
+    assert_eq!(
+        toposort_deps(vec![
+            ("c".to_string(), vec!["a".to_string()]),
+            ("d".to_string(), vec!["b".to_string()]),
+            ("a".to_string(), vec!["b".to_string()]),
+            ("b".to_string(), vec!["c".to_string()]),
+        ]),
+        Err(Error::Cycle(vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string()
+        ]))
+    );
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
     Cycle(Vec<String>),
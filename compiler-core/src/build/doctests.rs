@@ -0,0 +1,203 @@
+//! The examples in the documentation of a package, compiled and run by
+//! `gleam test --doc`.
+//!
+//! Each ```` ```gleam ```` code block in the doc comments and module comments
+//! of a module of the `src` directory is an example, unless `ignore` follows
+//! its language. The examples of a module become the test functions of a
+//! generated test module named after it, such as `doctest/my_app/parser` for
+//! `my_app/parser`. The imports an example starts with are imports of the
+//! test module, which also imports the documented module. The rest of the
+//! example is the body of a test function named after the line the example
+//! starts on, so that failures can be found in the documentation.
+
+use std::path::Path;
+
+use itertools::Itertools;
+
+use crate::{
+    ast::{Arena, SrcSpan},
+    parse::extra::ModuleExtra,
+};
+
+/// The modules generated for the examples are named with this prefix.
+pub const DOCTEST_MODULE_PREFIX: &str = "doctest/";
+
+/// The Gleam source of the test module for the examples in the documentation
+/// of a module, if it has any. Modules that cannot be parsed have none, as
+/// the error is reported when the module itself is compiled.
+pub fn doctest_module(module: &str, path: &Path, src: &str) -> Option<String> {
+    let arena = Arena::new();
+    let (_, extra) = crate::parse::parse_module(src, &arena).ok()?;
+    let examples = examples(src, &extra);
+    if examples.is_empty() {
+        return None;
+    }
+
+    let alias = module.rsplit('/').next().unwrap_or(module);
+    let mut imports = vec![];
+    let mut functions = vec![];
+    for example in examples {
+        let body = example.body.trim();
+        let body = if body.is_empty() { "Nil" } else { body };
+        for import in example.imports {
+            if !imports.contains(&import) {
+                imports.push(import);
+            }
+        }
+        functions.push(format!(
+            "// {}:{}\npub fn line_{}_test() {{\n{}\n}}\n",
+            path.to_string_lossy(),
+            example.line,
+            example.line,
+            body.lines().map(|line| format!("  {}", line)).join("\n")
+        ));
+    }
+
+    // The documented module is imported unless an example imports it itself,
+    // or none of the examples refer to it
+    let documented = format!("import {}", module);
+    let referred = functions
+        .iter()
+        .any(|function| function.contains(&format!("{}.", alias)));
+    let imported = imports
+        .iter()
+        .any(|import| import.split(['.', ' ']).take(2).join(" ") == documented);
+    if referred && !imported {
+        imports.insert(0, documented);
+    }
+
+    Some(format!(
+        "//// The examples in the documentation of `{}`, generated by `gleam test --doc`.\n\n{}\n\n{}",
+        module,
+        imports.join("\n"),
+        functions.join("\n")
+    ))
+}
+
+/// An example in the documentation of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Example {
+    /// The line of the module its code starts on, counting from 1.
+    line: usize,
+    imports: Vec<String>,
+    body: String,
+}
+
+// A fenced code block in a comment, which is an example if it is Gleam code
+enum Block {
+    Example(Example),
+    Other,
+}
+
+fn examples(src: &str, extra: &ModuleExtra) -> Vec<Example> {
+    let comments = extra
+        .module_comments
+        .iter()
+        .chain(extra.doc_comments.iter())
+        .sorted_by_key(|comment| comment.start);
+
+    let mut examples = vec![];
+    let mut block = None;
+    let mut previous_line = 0;
+    for SrcSpan { start, end } in comments {
+        let line = line_number(src, *start as usize);
+        // A code block does not continue past the end of the comment
+        if line != previous_line + 1 {
+            block = None;
+        }
+        previous_line = line;
+
+        let text = src.get(*start as usize..*end as usize).unwrap_or_default();
+        let text = text.strip_prefix(' ').unwrap_or(text);
+        let fence = text.trim_start().strip_prefix("```");
+        match (&mut block, fence) {
+            (None, Some(info)) => {
+                let mut words = info.split([' ', ',']).filter(|word| !word.is_empty());
+                let is_gleam = words.next() == Some("gleam");
+                block = Some(if is_gleam && !words.any(|word| word == "ignore") {
+                    Block::Example(Example {
+                        line: line + 1,
+                        imports: vec![],
+                        body: String::new(),
+                    })
+                } else {
+                    Block::Other
+                });
+            }
+            (Some(_), Some(_)) => {
+                if let Some(Block::Example(example)) = block.take() {
+                    examples.push(example);
+                }
+            }
+            (Some(Block::Example(example)), None) => {
+                let import = text.trim();
+                if example.body.trim().is_empty() && import.starts_with("import ") {
+                    example.imports.push(import.to_string());
+                } else {
+                    example.body.push_str(text);
+                    example.body.push('\n');
+                }
+            }
+            (Some(Block::Other) | None, None) => (),
+        }
+    }
+    examples
+}
+
+fn line_number(src: &str, byte_index: usize) -> usize {
+    src.get(..byte_index)
+        .unwrap_or_default()
+        .matches('\n')
+        .count()
+        + 1
+}
+
+#[test]
+fn doctest_module_test() {
+    let src = r#"//// Parsing.
+////
+//// ```gleam
+//// import gleam/string
+//// assert "1" = string.trim(parser.show(1))
+//// ```
+
+/// Adds one.
+///
+/// ```gleam
+/// assert 2 = parser.add(1)
+/// ```
+///
+/// ```gleam ignore
+/// parser.add(todo)
+/// ```
+///
+/// ```sh
+/// gleam run
+/// ```
+pub fn add(x) { x + 1 }
+"#;
+    assert_eq!(
+        doctest_module("app/parser", Path::new("src/app/parser.gleam"), src).as_deref(),
+        Some(
+            r#"//// The examples in the documentation of `app/parser`, generated by `gleam test --doc`.
+
+import app/parser
+import gleam/string
+
+// src/app/parser.gleam:4
+pub fn line_4_test() {
+  assert "1" = string.trim(parser.show(1))
+}
+
+// src/app/parser.gleam:11
+pub fn line_11_test() {
+  assert 2 = parser.add(1)
+}
+"#
+        )
+    );
+    assert_eq!(
+        doctest_module("app", Path::new("src/app.gleam"), "pub fn main() { 1 }"),
+        None
+    );
+}
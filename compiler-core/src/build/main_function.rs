@@ -0,0 +1,93 @@
+//! Validation of the `main` function that `gleam run` uses as the entrypoint
+//! of a program.
+//!
+//! Without this check a module with a missing, private, or otherwise
+//! unsuitable `main` function compiles successfully and then crashes when the
+//! runtime tries to call it, with an error that refers to the generated code
+//! rather than the Gleam source.
+
+use crate::{
+    ast::Statement,
+    build::Module,
+    error::{Error, MainFunctionProblem},
+    type_::{collapse_links, pretty::Printer, Type},
+};
+use std::sync::Arc;
+
+/// Check that the named module exists and defines a public `main` function
+/// that takes no arguments, so that it can be run.
+pub fn check_main_function(modules: &[Module], module_name: &str) -> Result<(), Error> {
+    let module = match modules.iter().find(|module| module.name == module_name) {
+        Some(module) => module,
+        None => {
+            return Err(Error::InvalidMainFunction {
+                module: module_name.to_string(),
+                problem: MainFunctionProblem::ModuleNotFound,
+                definition: None,
+            })
+        }
+    };
+
+    let error = |problem, location| Error::InvalidMainFunction {
+        module: module_name.to_string(),
+        problem,
+        definition: Some((module.input_path.clone(), module.code.clone(), location)),
+    };
+
+    for statement in &module.ast.statements {
+        let (location, public, arity, return_type) = match statement {
+            Statement::Fn {
+                location,
+                name,
+                public,
+                arguments,
+                return_type,
+                ..
+            } if name == "main" => (*location, *public, arguments.len(), return_type),
+
+            Statement::ExternalFn {
+                location,
+                name,
+                public,
+                arguments,
+                return_type,
+                ..
+            } if name == "main" => (*location, *public, arguments.len(), return_type),
+
+            Statement::ModuleConstant { location, name, .. } if name == "main" => {
+                return Err(error(MainFunctionProblem::NotAFunction, *location));
+            }
+
+            _ => continue,
+        };
+
+        return if !public {
+            Err(error(MainFunctionProblem::Private, location))
+        } else if arity != 0 {
+            Err(error(MainFunctionProblem::HasArguments { arity }, location))
+        } else if returns_function(return_type) {
+            let return_type = Printer::new().pretty_print(return_type, 0);
+            Err(error(
+                MainFunctionProblem::ReturnsFunction { return_type },
+                location,
+            ))
+        } else {
+            Ok(())
+        };
+    }
+
+    Err(Error::InvalidMainFunction {
+        module: module_name.to_string(),
+        problem: MainFunctionProblem::NotFound,
+        definition: None,
+    })
+}
+
+// A `main` function that returns a function is almost certainly a mistake, as
+// the returned function is never called.
+fn returns_function(type_: &Arc<Type>) -> bool {
+    collapse_links(type_.clone()).fn_arity().is_some()
+}
+
+#[cfg(test)]
+mod tests;
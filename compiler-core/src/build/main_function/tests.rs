@@ -0,0 +1,117 @@
+use super::*;
+use crate::{
+    build::Origin,
+    parse::extra::ModuleExtra,
+    type_::{build_prelude, infer_module},
+    uid::UniqueIdGenerator,
+};
+use std::path::PathBuf;
+
+/// Type check the given module and return the problem with its `main`
+/// function, if any.
+fn main_function_problem(src: &str) -> Option<MainFunctionProblem> {
+    let ids = UniqueIdGenerator::new();
+    let mut importable = im::HashMap::new();
+    let _ = importable.insert("gleam".to_string(), build_prelude(&ids));
+    let arena = crate::ast::Arena::new();
+    let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+    ast.name = vec!["app".into()];
+    let ast = infer_module(
+        crate::build::Target::Erlang,
+        &ids,
+        ast,
+        Origin::Src,
+        "thepackage",
+        &importable,
+        &mut vec![],
+    )
+    .expect("should successfully infer");
+    let modules = vec![Module {
+        name: "app".to_string(),
+        code: src.to_string(),
+        input_path: PathBuf::from("/src/app.gleam"),
+        origin: Origin::Src,
+        ast,
+        extra: ModuleExtra::new(),
+    }];
+    match check_main_function(&modules, "app") {
+        Ok(()) => None,
+        Err(Error::InvalidMainFunction { problem, .. }) => Some(problem),
+        Err(error) => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn valid_main_function() {
+    assert_eq!(main_function_problem("pub fn main() { Nil }"), None);
+}
+
+#[test]
+fn main_function_may_return_any_value() {
+    assert_eq!(
+        main_function_problem("pub fn main() { #(1, \"two\") }"),
+        None
+    );
+}
+
+#[test]
+fn external_main_function() {
+    assert_eq!(
+        main_function_problem("pub external fn main() -> Nil = \"app_ffi\" \"main\""),
+        None
+    );
+}
+
+#[test]
+fn module_not_found() {
+    assert_eq!(
+        check_main_function(&[], "app"),
+        Err(Error::InvalidMainFunction {
+            module: "app".to_string(),
+            problem: MainFunctionProblem::ModuleNotFound,
+            definition: None,
+        })
+    );
+}
+
+#[test]
+fn main_function_not_found() {
+    assert_eq!(
+        main_function_problem("pub fn run() { Nil }"),
+        Some(MainFunctionProblem::NotFound)
+    );
+}
+
+#[test]
+fn main_constant() {
+    assert_eq!(
+        main_function_problem("pub const main = 1"),
+        Some(MainFunctionProblem::NotAFunction)
+    );
+}
+
+#[test]
+fn private_main_function() {
+    assert_eq!(
+        main_function_problem("fn main() { Nil }"),
+        Some(MainFunctionProblem::Private)
+    );
+}
+
+#[test]
+fn main_function_with_arguments() {
+    assert_eq!(
+        main_function_problem("pub fn main(args: List(String)) { args }"),
+        Some(MainFunctionProblem::HasArguments { arity: 1 })
+    );
+}
+
+#[test]
+fn main_function_returning_a_function() {
+    assert_eq!(
+        main_function_problem("fn run() { Nil } pub fn main() { run }"),
+        Some(MainFunctionProblem::ReturnsFunction {
+            return_type: "fn() -> Nil".to_string()
+        })
+    );
+}
@@ -6,15 +6,20 @@ use crate::{
         Origin, Target,
     },
     codegen,
-    config::{Docs, ErlangConfig, JavaScriptConfig, PackageConfig, Repository},
+    config::{
+        Docs, ErlangConfig, ErlangSource, JavaScriptConfig, LicencePolicy, PackageConfig,
+        Repository, Requirement, WarningLevels,
+    },
     erlang,
+    error::CycleImport,
     io::test::FilesChannel,
     javascript,
+    lint::LintConfig,
     type_::{self, FieldAccessUsage},
 };
 use std::{collections::HashSet, path::PathBuf, sync::Arc};
 
-use hexpm::version::{Range, Version};
+use hexpm::version::Version;
 use pretty_assertions::assert_eq;
 
 macro_rules! assert_erlang_compile {
@@ -26,7 +31,7 @@ macro_rules! assert_erlang_compile {
             version: Version::new(1, 0, 0),
             licences: vec![],
             description: "The description".into(),
-            documentation: Docs { pages: vec![] },
+            documentation: Docs::default(),
             dependencies: [].into(),
             dev_dependencies: [].into(),
             repository: Repository::None,
@@ -34,11 +39,27 @@ macro_rules! assert_erlang_compile {
             erlang: ErlangConfig {
                 application_start_module: None,
                 extra_applications: vec![],
+                source: ErlangSource::Erlang,
             },
             javascript: JavaScriptConfig {
                 typescript_declarations: false,
+                bundle: None,
             },
             target: Target::Erlang,
+            targets: vec![],
+            dependency_licences: LicencePolicy::default(),
+            warnings: WarningLevels::default(),
+            lints: LintConfig::default(),
+            line_length: 80,
+            sort_imports: false,
+            workspace: Default::default(),
+            registry: None,
+            registries: Default::default(),
+            build: Default::default(),
+            publish: Default::default(),
+            features: Default::default(),
+            dependency_features: Default::default(),
+            native: Default::default(),
         };
         let (file_writer, file_receiver) = FilesChannel::new();
         let root = PathBuf::from("some/build/path/root");
@@ -66,8 +87,7 @@ macro_rules! assert_erlang_compile {
                 let mut outputs = FilesChannel::recv_utf8_files(&file_receiver).unwrap();
                 outputs.sort_by(|a, b| a.path.partial_cmp(&b.path).unwrap());
                 outputs
-            })
-            .map_err(|e| normalise_error(e));
+            });
         let expected = $expected_output.map(|mut outputs: Vec<OutputFile>| {
             outputs.sort_by(|a, b| a.path.partial_cmp(&b.path).unwrap());
             outputs
@@ -85,7 +105,7 @@ macro_rules! assert_javascript_compile {
             version: Version::new(1, 0, 0),
             licences: vec![],
             description: "The description".into(),
-            documentation: Docs { pages: vec![] },
+            documentation: Docs::default(),
             dependencies: [].into(),
             dev_dependencies: [].into(),
             repository: Repository::None,
@@ -93,11 +113,27 @@ macro_rules! assert_javascript_compile {
             erlang: ErlangConfig {
                 application_start_module: None,
                 extra_applications: vec![],
+                source: ErlangSource::Erlang,
             },
             javascript: JavaScriptConfig {
                 typescript_declarations: true,
+                bundle: None,
             },
             target: Target::JavaScript,
+            targets: vec![],
+            dependency_licences: LicencePolicy::default(),
+            warnings: WarningLevels::default(),
+            lints: LintConfig::default(),
+            line_length: 80,
+            sort_imports: false,
+            workspace: Default::default(),
+            registry: None,
+            registries: Default::default(),
+            build: Default::default(),
+            publish: Default::default(),
+            features: Default::default(),
+            dependency_features: Default::default(),
+            native: Default::default(),
         };
         let (file_writer, file_receiver) = FilesChannel::new();
         let root = PathBuf::from("some/build/path/root");
@@ -127,8 +163,7 @@ macro_rules! assert_javascript_compile {
                 let mut outputs = FilesChannel::recv_utf8_files(&file_receiver).unwrap();
                 outputs.sort_by(|a, b| a.path.partial_cmp(&b.path).unwrap());
                 outputs
-            })
-            .map_err(|e| normalise_error(e));
+            });
         let expected = $expected_output.map(|mut outputs: Vec<OutputFile>| {
             outputs.sort_by(|a, b| a.path.partial_cmp(&b.path).unwrap());
             outputs
@@ -146,7 +181,7 @@ macro_rules! assert_no_warnings {
             version: Version::new(1, 0, 0),
             licences: vec![],
             description: "The description".into(),
-            documentation: Docs { pages: vec![] },
+            documentation: Docs::default(),
             dependencies: [].into(),
             dev_dependencies: [].into(),
             repository: Repository::None,
@@ -154,11 +189,27 @@ macro_rules! assert_no_warnings {
             erlang: ErlangConfig {
                 application_start_module: None,
                 extra_applications: vec![],
+                source: ErlangSource::Erlang,
             },
             javascript: JavaScriptConfig {
                 typescript_declarations: false,
+                bundle: None,
             },
             target: Target::Erlang,
+            targets: vec![],
+            dependency_licences: LicencePolicy::default(),
+            warnings: WarningLevels::default(),
+            lints: LintConfig::default(),
+            line_length: 80,
+            sort_imports: false,
+            workspace: Default::default(),
+            registry: None,
+            registries: Default::default(),
+            build: Default::default(),
+            publish: Default::default(),
+            features: Default::default(),
+            dependency_features: Default::default(),
+            native: Default::default(),
         };
         let mut warnings = vec![];
         let (file_writer, file_receiver) = FilesChannel::new();
@@ -902,7 +953,7 @@ make() ->
 
 -type empty() :: empty.
 
--spec id(I) -> I.
+-spec id(J) -> J.
 id(X) ->
     X.
 "
@@ -951,7 +1002,7 @@ make() ->
 
 -type empty() :: empty.
 
--spec id(I) -> I.
+-spec id(J) -> J.
 id(X) ->
     X.
 "
@@ -1087,7 +1138,7 @@ funky() ->
 
 -export(['receive'/1]).
 
--spec \'receive\'(I) -> I.
+-spec \'receive\'(J) -> J.
 'receive'(X) ->
     X.
 "
@@ -1275,7 +1326,7 @@ get_name(Person) ->
                 text: "-module(one).
 -compile(no_auto_import).
 
--export_type([t/1]).\n\n-type t(I) :: {c, integer(), integer()} | {gleam_phantom, I}.
+-export_type([t/1]).\n\n-type t(J) :: {c, integer(), integer()} | {gleam_phantom, J}.
 
 
 "
@@ -1330,7 +1381,7 @@ main() ->
 
 -type t() :: {x, integer()}.
 
--spec id(I) -> I.
+-spec id(J) -> J.
 id(X) ->
     X.
 "
@@ -1420,7 +1471,7 @@ make_list() ->
 
 -export_type([t/1]).
 
--type t(I) :: {c, integer(), integer()} | {gleam_phantom, I}.
+-type t(J) :: {c, integer(), integer()} | {gleam_phantom, J}.
 
 
 "
@@ -1471,7 +1522,7 @@ main() ->
             error: crate::type_::Error::UnknownModuleValue {
                 location: crate::ast::SrcSpan { start: 26, end: 28 },
                 name: "C".to_string(),
-                module_name: vec!["one".to_string(),],
+                module_name: vec!["one".into(),],
                 value_constructors: vec![],
             }
         }),
@@ -1502,8 +1553,8 @@ main() ->
                 location: crate::ast::SrcSpan { start: 31, end: 34 },
                 typ: Arc::new(crate::type_::Type::App {
                     public: true,
-                    module: vec!["one".to_string(),],
-                    name: "T".to_string(),
+                    module: vec!["one".into(),],
+                    name: "T".into(),
                     args: vec![],
                 }),
                 label: "a".to_string(),
@@ -1535,7 +1586,74 @@ main() ->
             },
         ],
         Err(Error::ImportCycle {
-            modules: vec!["one".to_string(), "three".to_string(), "two".to_string()],
+            imports: vec![
+                CycleImport {
+                    module: "one".to_string(),
+                    imported: "two".to_string(),
+                    path: PathBuf::from("/src/one.gleam"),
+                    src: "import two".to_string(),
+                    location: SrcSpan { start: 7, end: 10 },
+                },
+                CycleImport {
+                    module: "two".to_string(),
+                    imported: "three".to_string(),
+                    path: PathBuf::from("/src/two.gleam"),
+                    src: "import three".to_string(),
+                    location: SrcSpan { start: 7, end: 12 },
+                },
+                CycleImport {
+                    module: "three".to_string(),
+                    imported: "one".to_string(),
+                    path: PathBuf::from("/src/three.gleam"),
+                    src: "import one".to_string(),
+                    location: SrcSpan { start: 7, end: 10 },
+                },
+            ],
+            suggested: None,
+        }),
+    );
+
+    // The import of the module imported by the most others is suggested to
+    // be removed
+    assert_erlang_compile!(
+        vec![
+            Source {
+                origin: Origin::Src,
+                path: PathBuf::from("/src/app.gleam"),
+                name: "app".to_string(),
+                code: "import types".to_string(),
+            },
+            Source {
+                origin: Origin::Src,
+                path: PathBuf::from("/src/other.gleam"),
+                name: "other".to_string(),
+                code: "import types".to_string(),
+            },
+            Source {
+                origin: Origin::Src,
+                path: PathBuf::from("/src/types.gleam"),
+                name: "types".to_string(),
+                code: "pub const x = 1\nimport app".to_string(),
+            },
+        ],
+        Err(Error::ImportCycle {
+            imports: vec![
+                CycleImport {
+                    module: "app".to_string(),
+                    imported: "types".to_string(),
+                    path: PathBuf::from("/src/app.gleam"),
+                    src: "import types".to_string(),
+                    location: SrcSpan { start: 7, end: 12 },
+                },
+                CycleImport {
+                    module: "types".to_string(),
+                    imported: "app".to_string(),
+                    path: PathBuf::from("/src/types.gleam"),
+                    src: "pub const x = 1\nimport app".to_string(),
+                    location: SrcSpan { start: 23, end: 26 },
+                },
+            ],
+            suggested: Some(1),
         }),
     );
 }
@@ -1566,7 +1684,7 @@ fn bug_752() {
 
 -export_type([one/1]).
 
--type one(I) :: {one, I}.
+-type one(J) :: {one, J}.
 
 
 "
@@ -1579,7 +1697,7 @@ fn bug_752() {
 
 -export_type([two/1]).
 
--type two(K) :: {two, one:one(integer())} | {gleam_phantom, K}.
+-type two(AKSOJX) :: {two, one:one(integer())} | {gleam_phantom, AKSOJX}.
 
 
 "
@@ -1784,7 +1902,7 @@ fn x() { test }"
                 .to_string(),
             error: type_::Error::UnknownModuleValue {
                 location: SrcSpan { start: 28, end: 33 },
-                module_name: vec!["one".to_string()],
+                module_name: vec!["one".into()],
                 name: "A".to_string(),
                 value_constructors: vec![]
             }
@@ -1942,6 +2060,20 @@ fn config_compilation_test() {
             javascript: Default::default(),
             links: vec![],
             target: Target::Erlang,
+            targets: vec![],
+            dependency_licences: LicencePolicy::default(),
+            warnings: WarningLevels::default(),
+            lints: LintConfig::default(),
+            line_length: 80,
+            sort_imports: false,
+            workspace: Default::default(),
+            registry: None,
+            registries: Default::default(),
+            build: Default::default(),
+            publish: Default::default(),
+            features: Default::default(),
+            dependency_features: Default::default(),
+            native: Default::default(),
         }
     }
 
@@ -2033,7 +2165,7 @@ fn config_compilation_test() {
         ("simple_json", "1.0.0"),
     ]
     .into_iter()
-    .map(|(a, b)| (a.to_string(), Range::new(b.to_string())))
+    .map(|(a, b)| (a.to_string(), Requirement::hex(b)))
     .collect();
     assert_config_compile!(
         config,
@@ -2059,11 +2191,11 @@ fn config_compilation_test() {
     let mut config = make_config();
     config.dependencies = [("gleam_stdlib", "1.0.0"), ("gleam_otp", "1.0.0")]
         .into_iter()
-        .map(|(a, b)| (a.to_string(), Range::new(b.to_string())))
+        .map(|(a, b)| (a.to_string(), Requirement::hex(b)))
         .collect();
     config.dev_dependencies = [("midas", "1.0.0"), ("simple_json", "1.0.0")]
         .into_iter()
-        .map(|(a, b)| (a.to_string(), Range::new(b.to_string())))
+        .map(|(a, b)| (a.to_string(), Requirement::hex(b)))
         .collect();
     assert_config_compile!(
         config,
@@ -2089,11 +2221,11 @@ fn config_compilation_test() {
     let mut config = make_config();
     config.dependencies = [("gleam_stdlib", "1.0.0"), ("gleam_otp", "1.0.0")]
         .into_iter()
-        .map(|(a, b)| (a.to_string(), Range::new(b.to_string())))
+        .map(|(a, b)| (a.to_string(), Requirement::hex(b)))
         .collect();
     config.dev_dependencies = [("midas", "1.0.0"), ("simple_json", "1.0.0")]
         .into_iter()
-        .map(|(a, b)| (a.to_string(), Range::new(b.to_string())))
+        .map(|(a, b)| (a.to_string(), Requirement::hex(b)))
         .collect();
     config.erlang.extra_applications = vec!["inets".into(), "ssl".into()];
     assert_config_compile!(
@@ -2119,16 +2251,6 @@ fn config_compilation_test() {
     );
 }
 
-fn normalise_error(e: Error) -> Error {
-    match e {
-        Error::ImportCycle { mut modules } => {
-            modules.sort();
-            Error::ImportCycle { modules }
-        }
-        e => e,
-    }
-}
-
 // https://github.com/gleam-lang/gleam/issues/922#issuecomment-803272624
 #[test]
 fn qualified_constant_with_nested_module() {
@@ -2271,3 +2393,231 @@ fn import_error() {
         ]),
     );
 }
+
+#[test]
+fn unchanged_modules_are_reused() {
+    let config = PackageConfig {
+        name: "the_package".to_string(),
+        version: Version::new(1, 0, 0),
+        licences: vec![],
+        description: "The description".into(),
+        documentation: Docs::default(),
+        dependencies: [].into(),
+        dev_dependencies: [].into(),
+        repository: Repository::None,
+        links: vec![],
+        erlang: Default::default(),
+        javascript: Default::default(),
+        target: Target::JavaScript,
+        targets: vec![],
+        dependency_licences: LicencePolicy::default(),
+        warnings: WarningLevels::default(),
+        lints: LintConfig::default(),
+        line_length: 80,
+        sort_imports: false,
+        workspace: Default::default(),
+        registry: None,
+        registries: Default::default(),
+        build: Default::default(),
+        publish: Default::default(),
+        features: Default::default(),
+        dependency_features: Default::default(),
+        native: Default::default(),
+    };
+    let ids = crate::uid::UniqueIdGenerator::new();
+    let root = PathBuf::from("some/build/path/root");
+    let out = PathBuf::from("_build/default/lib/the_package");
+    let lib = PathBuf::from("_build/default/lib");
+    let target = TargetCodegenConfiguration::JavaScript {
+        emit_typescript_definitions: false,
+    };
+    let source = |name: &str, code: &str| Source {
+        path: PathBuf::from(format!("src/{}.gleam", name)),
+        name: name.to_string(),
+        code: code.to_string(),
+        origin: Origin::Src,
+    };
+    let mut cache = HashMap::new();
+
+    // Compile the package, returning the names of the compiled modules and
+    // the paths of the files written
+    let mut compile = |sources: Vec<Source>| {
+        let (file_writer, file_receiver) = FilesChannel::new();
+        let mut compiler = PackageCompiler::new(
+            &config,
+            &root,
+            &out,
+            &lib,
+            &target,
+            ids.clone(),
+            file_writer,
+            None,
+        );
+        compiler.write_entrypoint = false;
+        compiler.write_metadata = false;
+        compiler.copy_native_files = false;
+        compiler.cached_modules = Some(&mut cache);
+        compiler.sources = sources;
+        let modules = compiler
+            .compile(
+                &mut vec![],
+                &mut im::HashMap::new(),
+                &mut im::HashMap::new(),
+            )
+            .expect("Should compile OK");
+        let mut written = FilesChannel::recv_utf8_files(&file_receiver)
+            .expect("Should write files")
+            .into_iter()
+            .map(|file| file.path.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        written.sort();
+        let names = modules
+            .into_iter()
+            .map(|module| module.name)
+            .collect::<Vec<_>>();
+        (names, written)
+    };
+
+    let one = source("one", "pub fn one() { 1 }");
+    let two = source("two", "import one pub fn two() { one.one() + 1 }");
+    let three = source("three", "pub fn three() { 3 }");
+    let (_, written) = compile(vec![one.clone(), two.clone(), three.clone()]);
+    assert_eq!(
+        written,
+        vec![
+            "_build/default/lib/the_package/gleam.mjs",
+            "_build/default/lib/the_package/one.mjs",
+            "_build/default/lib/the_package/three.mjs",
+            "_build/default/lib/the_package/two.mjs",
+        ]
+    );
+
+    // Changing only the body of a function compiles just the changed module
+    let changed_one = source("one", "pub fn one() { 10 }");
+    let (names, written) = compile(vec![changed_one, two.clone(), three.clone()]);
+    assert_eq!(names, vec!["one", "three", "two"]);
+    assert_eq!(
+        written,
+        vec![
+            "_build/default/lib/the_package/gleam.mjs",
+            "_build/default/lib/the_package/one.mjs",
+        ]
+    );
+
+    // Changing the public interface also compiles the modules that import it
+    let changed_one = source("one", "pub fn one() { 10 } pub fn ten() { 10 }");
+    let (names, written) = compile(vec![changed_one, two.clone(), three.clone()]);
+    assert_eq!(names, vec!["one", "three", "two"]);
+    assert_eq!(
+        written,
+        vec![
+            "_build/default/lib/the_package/gleam.mjs",
+            "_build/default/lib/the_package/one.mjs",
+            "_build/default/lib/the_package/two.mjs",
+        ]
+    );
+
+    // Adding a private function does not change the interface
+    let changed_one = source(
+        "one",
+        "pub fn one() { 10 } pub fn ten() { 10 } fn x() { 1 }",
+    );
+    let (_, written) = compile(vec![changed_one.clone(), two.clone(), three.clone()]);
+    assert_eq!(
+        written,
+        vec![
+            "_build/default/lib/the_package/gleam.mjs",
+            "_build/default/lib/the_package/one.mjs",
+        ]
+    );
+
+    // Modules that import a module whose interface was changed by an earlier
+    // package of the same build are compiled again
+    let (file_writer, file_receiver) = FilesChannel::new();
+    let mut changed = HashSet::from(["one".to_string()]);
+    let mut compiler = PackageCompiler::new(
+        &config,
+        &root,
+        &out,
+        &lib,
+        &target,
+        ids.clone(),
+        file_writer,
+        None,
+    );
+    compiler.write_metadata = false;
+    compiler.copy_native_files = false;
+    compiler.cached_modules = Some(&mut cache);
+    compiler.changed_modules = Some(&mut changed);
+    compiler.sources = vec![changed_one, two.clone(), three.clone()];
+    let _ = compiler
+        .compile(
+            &mut vec![],
+            &mut im::HashMap::new(),
+            &mut im::HashMap::new(),
+        )
+        .expect("Should compile OK");
+    let mut written = FilesChannel::recv_utf8_files(&file_receiver)
+        .expect("Should write files")
+        .into_iter()
+        .map(|file| file.path.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    written.sort();
+    assert_eq!(
+        written,
+        vec![
+            "_build/default/lib/the_package/gleam.mjs",
+            "_build/default/lib/the_package/two.mjs",
+        ]
+    );
+    assert_eq!(
+        changed,
+        HashSet::from(["one".to_string(), "two".to_string()])
+    );
+
+    // Modules that import a deleted module are compiled again, failing
+    let (file_writer, _) = FilesChannel::new();
+    let mut compiler =
+        PackageCompiler::new(&config, &root, &out, &lib, &target, ids, file_writer, None);
+    compiler.cached_modules = Some(&mut cache);
+    compiler.sources = vec![two, three.clone()];
+    assert!(compiler
+        .compile(
+            &mut vec![],
+            &mut im::HashMap::new(),
+            &mut im::HashMap::new()
+        )
+        .is_err());
+
+    // The warnings of reused modules are emitted again
+    let warned = source("warned", "pub fn warned() { let x = 1 2 }");
+    let mut warnings_of_build = || {
+        let (file_writer, _) = FilesChannel::new();
+        let mut compiler = PackageCompiler::new(
+            &config,
+            &root,
+            &out,
+            &lib,
+            &target,
+            crate::uid::UniqueIdGenerator::new(),
+            file_writer,
+            None,
+        );
+        compiler.write_metadata = false;
+        compiler.copy_native_files = false;
+        compiler.cached_modules = Some(&mut cache);
+        compiler.sources = vec![warned.clone(), three.clone()];
+        let mut warnings = vec![];
+        let _ = compiler
+            .compile(
+                &mut warnings,
+                &mut im::HashMap::new(),
+                &mut im::HashMap::new(),
+            )
+            .expect("Should compile OK");
+        warnings.len()
+    };
+    assert_eq!(warnings_of_build(), 1);
+    assert_eq!(warnings_of_build(), 1);
+    assert!(cache.contains_key("warned"));
+}
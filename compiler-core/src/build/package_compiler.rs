@@ -1,13 +1,17 @@
 use crate::{
-    ast::{SrcSpan, TypedModule, UntypedModule},
-    build::{dep_tree, Mode, Module, Origin, Package, Target},
-    codegen::{CPlusPlus, Erlang, ErlangApp, JavaScript, TypeScriptDeclarations},
-    config::PackageConfig,
-    error,
+    ast::{Arena, Name, SrcSpan, TypedModule, UntypedModule},
+    build::{
+        dep_tree, doctest_module, Mode, Module, Origin, Package, Phase, Target, Timings,
+        DOCTEST_MODULE_PREFIX,
+    },
+    codegen::{CPlusPlus, Elixir, Erlang, ErlangApp, Go, JavaScript, Llvm, TypeScriptDeclarations},
+    config::{ErlangSource, PackageConfig},
+    error::{self, CycleImport},
     io::{
         memory::InMemoryFileSystem, CommandExecutor, FileSystemIO, FileSystemReader,
         FileSystemWriter, Stdio,
     },
+    llvm::PointerWidth,
     metadata::ModuleEncoder,
     parse::extra::ModuleExtra,
     paths, type_,
@@ -15,10 +19,12 @@ use crate::{
     Error, Result, Warning,
 };
 use askama::Template;
-use std::{collections::HashMap, fmt::write};
+use itertools::Itertools;
+use std::{cmp::Reverse, collections::HashMap, fmt::write};
 use std::{
-    collections::HashSet,
+    collections::{BinaryHeap, HashSet},
     path::{Path, PathBuf},
+    sync::{mpsc, Mutex},
 };
 
 use super::{ErlangAppCodegenConfiguration, TargetCodegenConfiguration};
@@ -42,9 +48,35 @@ pub struct PackageCompiler<'a, IO> {
     pub perform_codegen: bool,
     pub write_entrypoint: bool,
     pub copy_native_files: bool,
+    /// The generators that can be named in the `@derive` attributes of the
+    /// custom types of the package.
+    pub derive_generators: crate::derive::Generators,
+    /// The feature flags of the package, with whether each is enabled. These
+    /// are the defaults from its config unless it is compiled as a
+    /// dependency whose flags are set by the root package.
+    pub features: HashMap<String, bool>,
     pub compile_beam_bytecode: bool,
     pub subprocess_stdio: Stdio,
     pub build_journal: Option<&'a mut HashSet<PathBuf>>,
+    /// The modules compiled by the previous build of this package. Those whose
+    /// code has not changed, and that do not import any module whose public
+    /// interface has, are reused rather than compiled again. The cache is
+    /// updated with the modules compiled by this build.
+    pub cached_modules: Option<&'a mut HashMap<String, CompiledModule>>,
+    /// The modules of the packages compiled earlier in this build whose
+    /// interface has changed since the previous build. The modules of this
+    /// package that import them are compiled again rather than reused, and
+    /// the modules of this package whose interface has changed are added.
+    pub changed_modules: Option<&'a mut HashSet<String>>,
+    /// Whether the code generated for the cached modules is in the output
+    /// directory. It is not when they were compiled for another target, in
+    /// which case code is generated for the reused modules too.
+    pub cached_code_generated: bool,
+    /// Where the time taken by each phase of the compilation is recorded.
+    pub timings: Timings,
+    /// Whether the examples in the documentation of the `src` modules are
+    /// compiled as test modules, for `gleam test --doc`.
+    pub doctests: bool,
 }
 
 // TODO: ensure this is not a duplicate module
@@ -78,9 +110,16 @@ where
             perform_codegen: true,
             write_entrypoint: false,
             copy_native_files: true,
+            derive_generators: Default::default(),
+            features: config.features.clone(),
             compile_beam_bytecode: true,
             subprocess_stdio: Stdio::Inherit,
             build_journal,
+            cached_modules: None,
+            changed_modules: None,
+            cached_code_generated: true,
+            timings: Timings::disabled(),
+            doctests: false,
         }
     }
 
@@ -94,36 +133,92 @@ where
         let _enter = span.enter();
 
         tracing::info!("Parsing source code");
+        // The untyped AST of each module is allocated in an arena of its own,
+        // which is freed once the module has been type checked.
+        let mut arenas: Vec<Arena> = self.sources.iter().map(|_| Arena::new()).collect();
         let parsed_modules = parse_sources(
             &self.config.name,
+            &self.timings,
+            &self.derive_generators,
             std::mem::take(&mut self.sources),
+            &mut arenas,
             already_defined_modules,
         )?;
 
-        // Determine order in which modules are to be processed
+        // Determine order in which modules are to be processed. The modules
+        // are sorted by name first so that the order (and so the generated
+        // code) is the same from one build to the next.
         let sequence = dep_tree::toposort_deps(
             parsed_modules
                 .values()
+                .sorted_by(|a, b| a.name.cmp(&b.name))
                 .map(|m| module_deps_for_graph(self.target.target(), m))
                 .collect(),
         )
-        .map_err(convert_deps_tree_error)?;
+        .map_err(|error| convert_deps_tree_error(error, &parsed_modules, self.target.target()))?;
 
         tracing::info!("Type checking modules");
-        let mut modules = type_check(
+        let changed_modules = self.changed_modules.as_deref().cloned();
+        let (checked, reused, changed_modules) = type_check(
             &self.config.name,
             self.target.target(),
             &self.ids,
+            &self.timings,
+            &self.features,
             sequence,
             parsed_modules,
             existing_modules,
             warnings,
+            self.cached_modules.as_deref(),
+            changed_modules.unwrap_or_default(),
         )?;
+        drop(arenas);
+        if let Some(changed) = self.changed_modules.as_mut() {
+            **changed = changed_modules;
+        }
+        let mut module_warnings = HashMap::new();
+        let mut modules = checked
+            .into_iter()
+            .map(|checked| {
+                let _ = module_warnings.insert(checked.module.name.clone(), checked.warnings);
+                checked.module
+            })
+            .collect_vec();
 
-        tracing::info!("Performing code generation");
-        self.perform_codegen(&modules)?;
+        // Documentation is included in TypeScript declarations
+        for module in &mut modules {
+            module.attach_doc_and_module_comments();
+        }
 
-        self.encode_and_write_metadata(&modules)?;
+        // Code is only generated again for the modules that were compiled
+        let compiled_modules;
+        let compiled = if reused.is_empty() || !self.cached_code_generated {
+            modules.as_slice()
+        } else {
+            tracing::info!(count = reused.len(), "Reusing unchanged modules");
+            compiled_modules = modules
+                .iter()
+                .filter(|module| !reused.contains(&module.name))
+                .cloned()
+                .collect_vec();
+            compiled_modules.as_slice()
+        };
+
+        tracing::info!("Performing code generation");
+        self.perform_codegen(&modules, compiled)?;
+
+        self.encode_and_write_metadata(&modules, compiled)?;
+
+        if let Some(cache) = self.cached_modules.as_mut() {
+            cache.retain(|name, _| reused.contains(name));
+            cache.extend(compiled.iter().map(|module| {
+                let compiled = CompiledModule {
+                    module: module.clone(),
+                    warnings: module_warnings.remove(&module.name).unwrap_or_default(),
+                };
+                (module.name.clone(), compiled)
+            }));
+        }
 
         Ok(modules)
     }
@@ -159,9 +254,12 @@ where
         }
         // Compile Erlang and Elixir modules
         // Write a temporary journal of compiled Beam files
-        let status = self
-            .io
-            .exec("escript", &args, &[], None, self.subprocess_stdio)?;
+        let status =
+            self.timings
+                .time(Phase::ErlangCompilation, &self.config.name, None, || {
+                    self.io
+                        .exec("escript", &args, &[], None, self.subprocess_stdio)
+                })?;
 
         let tmp_journal = self.lib.join("gleam_build_journal.tmp");
         if self.io.is_file(&tmp_journal) {
@@ -235,7 +333,7 @@ where
                 .to_path_buf();
 
             match extension {
-                "mjs" | "js" | "hrl" => (),
+                "mjs" | "js" | "hrl" | "go" | "ll" => (),
                 "cc" | "h" | "hpp" | "cpp" | "c" => (),
                 "erl" => {
                     let _ = to_compile_modules.insert(relative_path.clone());
@@ -270,17 +368,22 @@ where
         Ok(())
     }
 
-    fn encode_and_write_metadata(&mut self, modules: &[Module]) -> Result<()> {
+    fn encode_and_write_metadata(&mut self, modules: &[Module], compiled: &[Module]) -> Result<()> {
         if !self.write_metadata {
             tracing::info!("Package metadata writing disabled");
             return Ok(());
         }
         tracing::info!("Writing package metadata to disc");
-        for module in modules {
+        let path = |module: &Module| {
             let name = format!("{}.gleam_module", &module.name.replace('/', "@"));
-            let path = self.out.join(paths::ARTEFACT_DIRECTORY_NAME).join(name);
-            ModuleEncoder::new(&module.ast.type_info).write(self.io.writer(&path)?)?;
-            self.add_build_journal(path);
+            self.out.join(paths::ARTEFACT_DIRECTORY_NAME).join(name)
+        };
+        for module in compiled {
+            ModuleEncoder::new(&module.ast.type_info).write(self.io.writer(&path(module))?)?;
+        }
+        // The metadata of reused modules was written by an earlier build
+        for module in modules {
+            self.add_build_journal(path(module));
         }
         Ok(())
     }
@@ -297,12 +400,50 @@ where
             self.add_module(path, &src, Origin::Src)?;
         }
 
+        // Code generated by the build hooks of the package
+        for directory in self.config.build.source_directories() {
+            let directory = self.root.join(directory);
+            if self.io.is_directory(&directory) {
+                for path in self.io.gleam_source_files(&directory) {
+                    self.add_module(path, &directory, Origin::Src)?;
+                }
+            }
+        }
+
         // Test
         if mode.is_dev() && self.io.is_directory(&test) {
             for path in self.io.gleam_source_files(&test) {
                 self.add_module(path, &test, Origin::Test)?;
             }
         }
+
+        // The examples of the documentation, written to the build directory
+        // so that the code of their errors can be found
+        if mode.is_dev() && self.doctests {
+            let doctests = self
+                .sources
+                .iter()
+                .filter(|source| source.origin == Origin::Src);
+            let doctests = doctests
+                .filter_map(|source| {
+                    let code = doctest_module(&source.name, &source.path, &source.code)?;
+                    let name = format!("{}{}", DOCTEST_MODULE_PREFIX, source.name);
+                    let path = self.out.join(format!("{}.gleam", name));
+                    Some(Source {
+                        name,
+                        path,
+                        code,
+                        origin: Origin::Test,
+                    })
+                })
+                .collect_vec();
+            for source in doctests {
+                self.io
+                    .writer(&source.path)?
+                    .write(source.code.as_bytes())?;
+                self.sources.push(source);
+            }
+        }
         Ok(())
     }
 
@@ -325,19 +466,24 @@ where
         Ok(())
     }
 
-    fn perform_codegen(&mut self, modules: &[Module]) -> Result<()> {
+    /// Generates code for the compiled modules. The Erlang application file
+    /// lists every module of the package, compiled or reused.
+    fn perform_codegen(&mut self, modules: &[Module], compiled: &[Module]) -> Result<()> {
         if !self.perform_codegen {
             tracing::info!("skipping_codegen");
             return Ok(());
         }
 
         match self.target {
-            TargetCodegenConfiguration::CPlusPlus => self.perform_cpp_codegen(modules),
+            TargetCodegenConfiguration::CPlusPlus => self.perform_cpp_codegen(compiled),
+            TargetCodegenConfiguration::Go => self.perform_go_codegen(compiled),
+            TargetCodegenConfiguration::Llvm => self.perform_llvm_codegen(compiled),
+            TargetCodegenConfiguration::Wasm => self.perform_wasm_codegen(compiled),
             TargetCodegenConfiguration::JavaScript {
                 emit_typescript_definitions,
-            } => self.perform_javascript_codegen(modules, *emit_typescript_definitions),
+            } => self.perform_javascript_codegen(compiled, *emit_typescript_definitions),
             TargetCodegenConfiguration::Erlang { app_file } => {
-                self.perform_erlang_codegen(modules, app_file.as_ref())
+                self.perform_erlang_codegen(modules, compiled, app_file.as_ref())
             }
         }
     }
@@ -345,6 +491,7 @@ where
     fn perform_erlang_codegen(
         &mut self,
         modules: &[Module],
+        compiled: &[Module],
         app_file: Option<&ErlangAppCodegenConfiguration>,
     ) -> Result<(), Error> {
         let mut written = HashSet::new();
@@ -378,10 +525,36 @@ where
         // we overwrite any precompiled Erlang that was included in the Hex
         // package. Otherwise we will build the potentially outdated precompiled
         // version and not the newly compiled version.
-        Erlang::new(&build_dir, &include_dir).render(io, modules)?;
+        let source = self.config.erlang.source;
+        let line_length = self.config.line_length as isize;
+        self.timings.time(
+            Phase::Codegen(Target::Erlang),
+            &self.config.name,
+            None,
+            || match source {
+                ErlangSource::Erlang => {
+                    Erlang::new(&build_dir, &include_dir, line_length).render(io, compiled)
+                }
+                ErlangSource::Elixir => {
+                    Elixir::new(&build_dir, &include_dir, line_length).render(io, compiled)
+                }
+            },
+        )?;
 
         if self.compile_beam_bytecode {
-            written.extend(modules.iter().map(Module::compiled_erlang_path));
+            match source {
+                ErlangSource::Erlang => {
+                    written.extend(compiled.iter().map(Module::compiled_erlang_path));
+                }
+                ErlangSource::Elixir => {
+                    maybe_link_elixir_libs(
+                        &self.io,
+                        &self.lib.to_path_buf(),
+                        self.subprocess_stdio,
+                    )?;
+                    written.extend(compiled.iter().map(Module::compiled_elixir_path));
+                }
+            }
             self.compile_erlang_to_beam(&written)?;
         } else {
             tracing::info!("skipping_erlang_bytecode_compilation");
@@ -401,7 +574,15 @@ where
             TypeScriptDeclarations::None
         };
 
-        JavaScript::new(&self.out, typescript).render(&self.io, modules)?;
+        self.timings.time(
+            Phase::Codegen(Target::JavaScript),
+            &self.config.name,
+            None,
+            || {
+                JavaScript::new(&self.out, typescript, self.config.line_length as isize)
+                    .render(&self.io, modules)
+            },
+        )?;
 
         if self.copy_native_files {
             self.copy_project_native_files(&self.out, &mut written)?;
@@ -413,7 +594,16 @@ where
         let mut written = HashSet::new();
         let artifact_dir = self.out.join("dist");
 
-        CPlusPlus::new(&artifact_dir).render(&self.io, modules)?;
+        self.timings.time(
+            Phase::Codegen(Target::Native),
+            &self.config.name,
+            None,
+            || {
+                CPlusPlus::new(&artifact_dir, self.config.line_length as isize)
+                    .render(&self.io, modules)
+            },
+        )?;
+        self.format_cpp_sources(&artifact_dir, modules)?;
 
         if self.copy_native_files {
             self.copy_project_native_files(&artifact_dir, &mut written)?;
@@ -421,6 +611,93 @@ where
         Ok(())
     }
 
+    fn perform_go_codegen(&mut self, modules: &[Module]) -> Result<(), Error> {
+        let mut written = HashSet::new();
+
+        // The packages share one Go module, so code is generated into the
+        // directory shared by all of them rather than this package's own.
+        self.timings
+            .time(Phase::Codegen(Target::Go), &self.config.name, None, || {
+                Go::new(&self.lib, self.config.line_length as isize).render(&self.io, modules)
+            })?;
+
+        if self.copy_native_files {
+            self.copy_project_native_files(self.lib, &mut written)?;
+        }
+        Ok(())
+    }
+
+    fn perform_llvm_codegen(&mut self, modules: &[Module]) -> Result<(), Error> {
+        let mut written = HashSet::new();
+
+        self.timings.time(
+            Phase::Codegen(Target::Llvm),
+            &self.config.name,
+            None,
+            || Llvm::new(self.out, self.lib, PointerWidth::Bits64).render(&self.io, modules),
+        )?;
+
+        if self.copy_native_files {
+            self.copy_project_native_files(self.out, &mut written)?;
+        }
+        Ok(())
+    }
+
+    /// WebAssembly is compiled to LLVM IR in the same way as native code, but
+    /// for a platform with 32-bit addresses.
+    fn perform_wasm_codegen(&mut self, modules: &[Module]) -> Result<(), Error> {
+        let mut written = HashSet::new();
+
+        self.timings.time(
+            Phase::Codegen(Target::Wasm),
+            &self.config.name,
+            None,
+            || Llvm::new(self.out, self.lib, PointerWidth::Bits32).render(&self.io, modules),
+        )?;
+
+        if self.copy_native_files {
+            self.copy_project_native_files(self.out, &mut written)?;
+        }
+        Ok(())
+    }
+
+    /// Runs `clang-format` over the generated C++ sources, using the
+    /// `.clang-format` file written alongside them. Formatting is best effort:
+    /// if `clang-format` is not installed or fails the sources are left as
+    /// generated.
+    fn format_cpp_sources(&mut self, artifact_dir: &Path, modules: &[Module]) -> Result<()> {
+        if modules.is_empty() {
+            return Ok(());
+        }
+
+        let mut args = vec!["-i".into(), "--style=file".into()];
+        for module in modules {
+            for extension in ["h", "cc"] {
+                let path = artifact_dir.join(format!("{}.{}", module.name, extension));
+                args.push(path.to_string_lossy().to_string());
+            }
+        }
+
+        let status = self
+            .timings
+            .time(Phase::CPlusPlusFormatting, &self.config.name, None, || {
+                self.io
+                    .exec("clang-format", &args, &[], Some(artifact_dir), Stdio::Null)
+            });
+        match status {
+            Ok(0) => Ok(()),
+            Ok(status) => {
+                tracing::warn!(status, "clang-format failed, leaving C++ unformatted");
+                Ok(())
+            }
+            Err(Error::ShellProgramNotFound { .. }) => {
+                tracing::warn!("clang-format not found, leaving C++ unformatted");
+                Ok(())
+            }
+            Err(error) => Err(error),
+        }
+    }
+
     fn render_entrypoint_module(
         &mut self,
         out: &Path,
@@ -439,17 +716,23 @@ where
     }
 }
 
+/// Type check the modules of a package, compiling each module on one of the
+/// available threads once all of the modules it imports have been compiled.
+/// The modules, warnings, and any error are the same as if the modules had
+/// been compiled one at a time in the order of the given sequence.
 fn type_check(
     package_name: &str,
     target: Target,
     ids: &UniqueIdGenerator,
+    timings: &Timings,
+    features: &HashMap<String, bool>,
     sequence: Vec<String>,
-    mut parsed_modules: HashMap<String, Parsed>,
+    mut parsed_modules: HashMap<String, Parsed<'_>>,
     module_types: &mut im::HashMap<String, type_::Module>,
     warnings: &mut Vec<Warning>,
-) -> Result<Vec<Module>, Error> {
-    let mut modules = Vec::with_capacity(parsed_modules.len() + 1);
-
+    cached_modules: Option<&HashMap<String, CompiledModule>>,
+    mut changed: HashSet<String>,
+) -> Result<(Vec<CompiledModule>, HashSet<String>, HashSet<String>), Error> {
     // Insert the prelude
     // DUPE: preludeinsertion
     // TODO: Currently we do this here and also in the tests. It would be better
@@ -457,28 +740,280 @@ fn type_check(
     // place.
     let _ = module_types.insert("gleam".to_string(), type_::build_prelude(ids));
 
-    for name in sequence {
+    // Each module has its own block of ids so that its types are the same
+    // whichever order the modules are compiled in.
+    let mut module_ids = ids.reserve_blocks(sequence.len()).into_iter();
+    let mut modules: Vec<ModuleToCheck> = sequence
+        .iter()
+        .map(|name| {
+            let parsed = parsed_modules
+                .remove(name)
+                .expect("Getting parsed module for name");
+            let imports = parsed
+                .ast
+                .dependencies(target)
+                .into_iter()
+                .map(|(dependency, _)| dependency)
+                .collect();
+            ModuleToCheck {
+                imports,
+                waiting_for: 0,
+                dependents: vec![],
+                ids: module_ids.next().expect("Reserved ids for module"),
+                parsed: Some(parsed),
+                checked: None,
+            }
+        })
+        .collect();
+
+    // The modules that must be checked before each module can be
+    let index_of: HashMap<&str, usize> = sequence
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.as_str(), index))
+        .collect();
+    let mut edges = vec![];
+    for (index, module) in modules.iter_mut().enumerate() {
+        for import in &module.imports {
+            if let Some(&imported) = index_of.get(import.as_str()) {
+                module.waiting_for += 1;
+                edges.push((imported, index));
+            }
+        }
+    }
+    for (imported, index) in edges {
+        if let Some(module) = modules.get_mut(imported) {
+            module.dependents.push(index);
+        }
+    }
+    let mut ready: BinaryHeap<Reverse<usize>> = modules
+        .iter()
+        .enumerate()
+        .filter(|(_, module)| module.waiting_for == 0)
+        .map(|(index, _)| Reverse(index))
+        .collect();
+
+    // Modules whose interface has changed, or that have been deleted, since
+    // the cached modules were compiled. Modules that import them must be
+    // compiled again.
+    changed.extend(
+        cached_modules
+            .into_iter()
+            .flat_map(HashMap::keys)
+            .filter(|name| !index_of.contains_key(name.as_str()))
+            .cloned(),
+    );
+    let mut reused = HashSet::new();
+
+    // The earliest module in the sequence that could not be compiled. Modules
+    // after it are not compiled, as their modules and warnings are not used.
+    let mut first_error: Option<(usize, Error)> = None;
+
+    let (job_sender, job_receiver) = mpsc::channel::<(usize, Parsed<'_>, TypeCheckJob)>();
+    let job_receiver = Mutex::new(job_receiver);
+    let (result_sender, result_receiver) = mpsc::channel();
+    // The modules are checked on other threads, so the span of the package is
+    // given to them explicitly
+    let package_span = tracing::Span::current();
+    std::thread::scope(|scope| {
+        let threads = crate::parallel::threads().min(modules.len());
+        for _ in 0..threads {
+            let job_receiver = &job_receiver;
+            let result_sender = result_sender.clone();
+            let package_span = &package_span;
+            let _ = scope.spawn(move || loop {
+                let job = job_receiver.lock().expect("Type check job lock").recv();
+                let (index, parsed, job) = match job {
+                    Ok(job) => job,
+                    Err(_) => return,
+                };
+                let name = parsed.name.clone();
+                let span = tracing::debug_span!(parent: package_span, "module", module = %name);
+                let _enter = span.enter();
+                let result = timings.time(Phase::Analyse, package_name, Some(&name), || {
+                    job.run(package_name, target, parsed)
+                });
+                if result_sender.send((index, result)).is_err() {
+                    return;
+                }
+            });
+        }
+
+        let mut running = 0;
+        loop {
+            while let Some(Reverse(index)) = ready.pop() {
+                if matches!(&first_error, Some((error_index, _)) if index > *error_index) {
+                    continue;
+                }
+                let module = match modules.get_mut(index) {
+                    Some(module) => module,
+                    None => continue,
+                };
+                let parsed = module.parsed.take().expect("Module to check");
+                let imports_changed = module.imports.iter().any(|import| changed.contains(import));
+                let cached = cached_modules.and_then(|cache| cache.get(&parsed.name));
+                match cached {
+                    Some(cached)
+                        if cached.module.code == parsed.code
+                            && cached.module.origin == parsed.origin
+                            && !imports_changed =>
+                    {
+                        tracing::debug!(module = ?parsed.name, "Reusing unchanged module");
+                        let type_info = cached.module.ast.type_info.clone();
+                        let _ = module_types.insert(parsed.name.clone(), type_info);
+                        let _ = reused.insert(parsed.name);
+                        // The warnings of the module are emitted again, as
+                        // though it had been compiled
+                        module.checked = Some((cached.module.clone(), cached.warnings.clone()));
+                        release_dependents(&mut modules, index, &mut ready);
+                    }
+                    _ => {
+                        // A module compiled again because an interface it
+                        // imports changed may expose that interface through
+                        // its own, so its dependents are compiled again too.
+                        if imports_changed {
+                            let _ = changed.insert(parsed.name.clone());
+                        }
+                        let job = TypeCheckJob {
+                            ids: module.ids.clone(),
+                            module_types: module_types.clone(),
+                            features: features.clone(),
+                        };
+                        job_sender
+                            .send((index, parsed, job))
+                            .expect("Sending type check job");
+                        running += 1;
+                    }
+                }
+            }
+
+            if running == 0 {
+                break;
+            }
+            let (index, result) = result_receiver.recv().expect("Type check result");
+            running -= 1;
+            match result {
+                Ok((module, module_warnings)) => {
+                    // Changing only the bodies of functions, or private
+                    // definitions, does not require the modules that import
+                    // this one to be compiled again.
+                    let interface_changed =
+                        match cached_modules.and_then(|cache| cache.get(&module.name)) {
+                            Some(cached) => {
+                                cached.module.ast.type_info.interface_fingerprint()
+                                    != module.ast.type_info.interface_fingerprint()
+                            }
+                            None => true,
+                        };
+                    if interface_changed {
+                        let _ = changed.insert(module.name.clone());
+                    }
+                    let _ = module_types.insert(module.name.clone(), module.ast.type_info.clone());
+                    if let Some(to_check) = modules.get_mut(index) {
+                        to_check.checked = Some((module, module_warnings));
+                    }
+                    release_dependents(&mut modules, index, &mut ready);
+                }
+                Err(error) => {
+                    if !matches!(&first_error, Some((error_index, _)) if *error_index < index) {
+                        first_error = Some((index, error));
+                    }
+                }
+            }
+        }
+
+        // Stop the threads
+        drop(job_sender);
+    });
+
+    // Warnings are registered in the order of the sequence
+    let mut checked = Vec::with_capacity(modules.len());
+    for module in modules {
+        match module.checked {
+            Some((module, module_warnings)) => {
+                warnings.extend(module_warnings.iter().cloned());
+                checked.push(CompiledModule {
+                    module,
+                    warnings: module_warnings,
+                });
+            }
+            None => break,
+        }
+    }
+    match first_error {
+        Some((_, error)) => Err(error),
+        None => Ok((checked, reused, changed)),
+    }
+}
+
+/// Now that a module has been checked, ready the modules that were waiting
+/// for it and no other module.
+fn release_dependents(
+    modules: &mut [ModuleToCheck],
+    index: usize,
+    ready: &mut BinaryHeap<Reverse<usize>>,
+) {
+    let dependents = match modules.get_mut(index) {
+        Some(module) => std::mem::take(&mut module.dependents),
+        None => return,
+    };
+    for dependent in dependents {
+        if let Some(module) = modules.get_mut(dependent) {
+            module.waiting_for -= 1;
+            if module.waiting_for == 0 {
+                ready.push(Reverse(dependent));
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ModuleToCheck<'a> {
+    imports: Vec<String>,
+    /// The number of imported modules of this package not yet checked.
+    waiting_for: usize,
+    /// The modules of this package that import this one.
+    dependents: Vec<usize>,
+    ids: UniqueIdGenerator,
+    parsed: Option<Parsed<'a>>,
+    checked: Option<(Module, Vec<Warning>)>,
+}
+
+/// What a thread needs to type check a module.
+#[derive(Debug)]
+struct TypeCheckJob {
+    ids: UniqueIdGenerator,
+    module_types: im::HashMap<String, type_::Module>,
+    features: HashMap<String, bool>,
+}
+
+impl TypeCheckJob {
+    fn run(
+        self,
+        package_name: &str,
+        target: Target,
+        parsed: Parsed<'_>,
+    ) -> Result<(Module, Vec<Warning>), Error> {
         let Parsed {
             name,
             code,
             ast,
             path,
             origin,
-            package,
+            package: _,
             extra,
-        } = parsed_modules
-            .remove(&name)
-            .expect("Getting parsed module for name");
+        } = parsed;
 
         tracing::debug!(module = ?name, "Type checking");
         let mut type_warnings = Vec::new();
-        let ast = type_::infer_module(
+        let ast = type_::infer_module_with_features(
             target,
-            ids,
+            &self.ids,
             ast,
             origin,
             package_name,
-            module_types,
+            &self.module_types,
+            self.features,
             &mut type_warnings,
         )
         .map_err(|error| Error::Type {
@@ -487,29 +1022,20 @@ fn type_check(
             error,
         })?;
 
-        // Register any warnings emitted as type warnings
-        let type_warnings = type_warnings
+        let warnings = type_warnings
             .into_iter()
-            .map(|w| w.into_warning(path.clone(), code.clone()));
-        warnings.extend(type_warnings);
-
-        // Register the types from this module so they can be imported into
-        // other modules.
-        let _ = module_types.insert(name.clone(), ast.type_info.clone());
-
-        // Register the successfully type checked module data so that it can be
-        // used for code generation
-        modules.push(Module {
+            .map(|w| w.into_warning(path.clone(), code.clone()))
+            .collect();
+        let module = Module {
             origin,
             extra,
             name,
             code,
             ast,
             input_path: path,
-        });
+        };
+        Ok((module, warnings))
     }
-
-    Ok(modules)
 }
 
 pub fn maybe_link_elixir_libs<IO: CommandExecutor + FileSystemIO + Clone>(
@@ -593,13 +1119,70 @@ pub fn maybe_link_elixir_libs<IO: CommandExecutor + FileSystemIO + Clone>(
     Ok(())
 }
 
-fn convert_deps_tree_error(e: dep_tree::Error) -> Error {
+fn convert_deps_tree_error(
+    e: dep_tree::Error,
+    parsed_modules: &HashMap<String, Parsed<'_>>,
+    target: Target,
+) -> Error {
     match e {
-        dep_tree::Error::Cycle(modules) => Error::ImportCycle { modules },
+        dep_tree::Error::Cycle(modules) => import_cycle(modules, parsed_modules, target),
     }
 }
 
-fn module_deps_for_graph(target: Target, module: &Parsed) -> (String, Vec<String>) {
+fn import_cycle(
+    modules: Vec<String>,
+    parsed_modules: &HashMap<String, Parsed<'_>>,
+    target: Target,
+) -> Error {
+    let imports: Vec<_> = modules
+        .iter()
+        .zip(modules.iter().cycle().skip(1))
+        .filter_map(|(module, imported)| {
+            let parsed = parsed_modules.get(module)?;
+            let (_, location) = parsed
+                .ast
+                .dependencies(target)
+                .into_iter()
+                .find(|(dependency, _)| dependency == imported)?;
+            Some(CycleImport {
+                module: module.clone(),
+                imported: imported.clone(),
+                path: parsed.path.clone(),
+                src: parsed.code.clone(),
+                location,
+            })
+        })
+        .collect();
+
+    // The module imported by the most others is likely the lowest level one,
+    // so its import of a module from further up is the one to remove.
+    let importers = |name: &str| {
+        parsed_modules
+            .values()
+            .filter(|parsed| {
+                parsed
+                    .ast
+                    .dependencies(target)
+                    .iter()
+                    .any(|(dependency, _)| dependency == name)
+            })
+            .count()
+    };
+    let counts: Vec<_> = imports
+        .iter()
+        .map(|import| importers(&import.module))
+        .collect();
+    let most = counts.iter().max();
+    let suggested = if counts.iter().all_equal() {
+        None
+    } else {
+        counts.iter().position(|count| Some(count) == most)
+    };
+
+    Error::ImportCycle { imports, suggested }
+}
+
+fn module_deps_for_graph(target: Target, module: &Parsed<'_>) -> (String, Vec<String>) {
     let name = module.name.clone();
     let deps: Vec<_> = module
         .ast
@@ -610,28 +1193,53 @@ fn module_deps_for_graph(target: Target, module: &Parsed) -> (String, Vec<String
     (name, deps)
 }
 
-fn parse_sources(
+fn parse_sources<'a>(
     package_name: &str,
+    timings: &Timings,
+    derive_generators: &crate::derive::Generators,
     sources: Vec<Source>,
+    arenas: &'a mut [Arena],
     already_defined_modules: &mut im::HashMap<String, PathBuf>,
-) -> Result<HashMap<String, Parsed>, Error> {
+) -> Result<HashMap<String, Parsed<'a>>, Error> {
     let mut parsed_modules = HashMap::with_capacity(sources.len());
-    for Source {
-        name,
-        code,
-        path,
-        origin,
-    } in sources
-    {
-        let (mut ast, extra) = crate::parse::parse_module(&code).map_err(|error| Error::Parse {
-            path: path.clone(),
-            src: code.clone(),
-            error,
-        })?;
+    let results = crate::parallel::map_with(&sources, arenas, |source, arena| {
+        let arena: &'a Arena = arena;
+        let (mut ast, extra, errors) =
+            timings.time(Phase::Parse, package_name, Some(&source.name), || {
+                crate::parse::parse_module_with_recovery(&source.code, arena)
+            });
+        if let Some(errors) = errors {
+            return Err(Error::Parse {
+                path: source.path.clone(),
+                src: source.code.clone(),
+                errors,
+            });
+        }
 
         // Store the name
         // TODO: store the module name as a string
-        ast.name = name.split("/").map(String::from).collect();
+        ast.name = source.name.split("/").map(Name::from).collect();
+
+        crate::derive::expand(&mut ast, arena, derive_generators).map_err(|error| {
+            Error::Derive {
+                path: source.path.clone(),
+                src: source.code.clone(),
+                error,
+            }
+        })?;
+        Ok((ast, extra))
+    });
+    for (
+        Source {
+            name,
+            code,
+            path,
+            origin,
+        },
+        result,
+    ) in sources.into_iter().zip(results)
+    {
+        let (ast, extra) = result?;
 
         let module = Parsed {
             package: package_name.to_string(),
@@ -682,7 +1290,15 @@ fn module_name(package_path: &Path, full_module_path: &Path) -> String {
     name.replace("\\", "/")
 }
 
-#[derive(Debug)]
+/// A module along with the warnings emitted when it was compiled, kept so
+/// that it can be reused by later builds.
+#[derive(Debug, Clone)]
+pub struct CompiledModule {
+    pub module: Module,
+    pub warnings: Vec<Warning>,
+}
+
+#[derive(Debug, Clone)]
 pub struct Source {
     pub path: PathBuf,
     pub name: String,
@@ -691,13 +1307,13 @@ pub struct Source {
 }
 
 #[derive(Debug)]
-struct Parsed {
+struct Parsed<'a> {
     path: PathBuf,
     name: String,
     code: String,
     origin: Origin,
     package: String,
-    ast: UntypedModule,
+    ast: UntypedModule<'a>,
     extra: ModuleExtra,
 }
 
@@ -1,24 +1,28 @@
 use crate::{
+    ast::Arena,
     build::{
         dep_tree, package_compiler, package_compiler::PackageCompiler, project_compiler,
-        telemetry::Telemetry, Mode, Module, Origin, Package, Target,
+        telemetry::Telemetry, Mode, Module, Origin, Package, Phase, Target, Timings,
     },
     codegen::{self, ErlangApp},
-    config::PackageConfig,
+    config::{PackageConfig, WarningLevel, WarningLevels},
     error::{FileIoAction, FileKind},
-    io::{CommandExecutor, FileSystemIO, FileSystemWriter, Stdio},
-    manifest::ManifestPackage,
+    io::{CommandExecutor, FileSystemIO, FileSystemReader, FileSystemWriter, Stdio},
+    lint,
+    manifest::{ManifestPackage, ManifestPackageSource},
     metadata, paths, type_,
     uid::UniqueIdGenerator,
     version::COMPILER_VERSION,
     warning, Error, Result, Warning,
 };
 use itertools::Itertools;
+use sha2::{Digest, Sha256};
 use std::{
     collections::{HashMap, HashSet},
     fmt::Write,
     io::BufReader,
     path::{Path, PathBuf},
+    sync::Arc,
     time::Instant,
 };
 
@@ -36,6 +40,10 @@ const ELIXIR_EXECUTABLE: &str = "elixir";
 #[cfg(target_os = "windows")]
 const ELIXIR_EXECUTABLE: &str = "elixir.bat";
 
+/// The file written to an entry of the global build cache once the compiled
+/// package has been copied into it.
+const GLOBAL_BUILD_CACHE_COMPLETE: &str = "gleam_cache_complete";
+
 #[derive(Debug)]
 pub struct Options {
     pub mode: Mode,
@@ -46,6 +54,12 @@ pub struct Options {
     /// track both whether type metadata has been produced and also whether
     /// codegen has been performed. As such there will be 2 kinds of caching.
     pub perform_codegen: bool,
+    /// Whether to report definitions in the root package that cannot be
+    /// reached from its `main` function or tests.
+    pub warn_dead_code: bool,
+    /// Levels given on the command line for the warnings of the root
+    /// package, which take precedence over those of gleam.toml.
+    pub warning_levels: WarningLevels,
 }
 
 #[derive(Debug)]
@@ -56,14 +70,45 @@ pub struct ProjectCompiler<IO> {
     importable_modules: im::HashMap<String, type_::Module>,
     defined_modules: im::HashMap<String, PathBuf>,
     warnings: Vec<Warning>,
-    telemetry: Box<dyn Telemetry>,
+    telemetry: Arc<dyn Telemetry>,
     options: Options,
     ids: UniqueIdGenerator,
     io: IO,
     build_journal: HashSet<PathBuf>,
+    /// What is kept from one build to the next when the project is compiled
+    /// repeatedly, such as by `gleam build --watch`.
+    incremental: Option<IncrementalState>,
     /// We may want to silence subprocess stdout if we are running in LSP mode.
     /// The language server talks over stdio so printing would break that.
     pub subprocess_stdio: Stdio,
+    /// The packages of the project other than the root package, such as the
+    /// members of its workspace. They are compiled every build, after the
+    /// dependencies and before the root package.
+    pub local_packages: Vec<LocalPackage>,
+    /// A directory shared by the projects of the user in which the compiled
+    /// dependencies are kept. A dependency found there is copied into the
+    /// build directory rather than compiled again.
+    pub global_build_cache: Option<PathBuf>,
+    /// Where the time taken by each phase of the build is recorded.
+    pub timings: Timings,
+    /// Whether the examples in the documentation of the root package are
+    /// compiled as test modules, for `gleam test --doc`.
+    pub doctests: bool,
+}
+
+/// A package whose source code is part of the project rather than downloaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalPackage {
+    pub config: PackageConfig,
+    /// The directory of the package, relative to the root of the project.
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageKind {
+    Root,
+    Local,
+    Dependency,
 }
 
 // TODO: test that tests cannot be imported into src
@@ -91,20 +136,49 @@ where
             ids: UniqueIdGenerator::new(),
             warnings: Vec::new(),
             subprocess_stdio: Stdio::Inherit,
-            telemetry,
+            telemetry: Arc::from(telemetry),
             packages,
             options,
             config,
             io,
             build_journal: HashSet::new(),
+            incremental: None,
+            local_packages: vec![],
+            global_build_cache: None,
+            timings: Timings::disabled(),
+            doctests: false,
         }
     }
 
+    // The timings of the build, reporting the work timed to the telemetry
+    fn timings(&self) -> Timings {
+        self.timings.clone().with_telemetry(self.telemetry.clone())
+    }
+
+    /// Keep the compiled dependencies and the modules of the root and local
+    /// packages between calls to `compile`. Later builds then load no
+    /// dependencies and only compile the modules that have changed, along with
+    /// the modules that import a module whose interface has changed.
+    pub fn enable_incremental_compilation(&mut self) {
+        self.incremental = Some(IncrementalState::default());
+    }
+
+    /// Emit TypeScript declarations for the modules of every package, whatever
+    /// the configuration of the root package.
+    pub fn emit_typescript_declarations(&mut self) {
+        self.config.javascript.typescript_declarations = true;
+    }
+
     pub fn get_importable_modules(&self) -> &im::HashMap<String, type_::Module> {
         &self.importable_modules
     }
 
-    // TODO: test
+    /// The id generator of the compilation, for type checking further code
+    /// against the compiled modules.
+    pub fn ids(&self) -> UniqueIdGenerator {
+        self.ids.clone()
+    }
+
     pub fn checkpoint(&self) -> CheckpointState {
         CheckpointState {
             importable_modules: self.importable_modules.clone(),
@@ -113,11 +187,23 @@ where
         }
     }
 
-    // TODO: test
     pub fn restore(&mut self, checkpoint: CheckpointState) {
         self.importable_modules = checkpoint.importable_modules;
         self.defined_modules = checkpoint.defined_modules;
-        self.ids = checkpoint.ids;
+        // The modules kept to be reused by later builds were given ids after
+        // the checkpoint, so those ids must not be given out again
+        if self.incremental.is_none() {
+            self.ids = checkpoint.ids;
+        }
+    }
+
+    /// Forget which modules had their interface changed by the previous build.
+    /// This is done by `compile`, so is only needed when the local and root
+    /// packages are compiled again without it, as the language server does.
+    pub fn clear_changed_modules(&mut self) {
+        if let Some(incremental) = &mut self.incremental {
+            incremental.changed_modules.clear();
+        }
     }
 
     pub fn mode(&self) -> Mode {
@@ -132,31 +218,179 @@ where
         self.options.target.unwrap_or(self.config.target)
     }
 
+    /// Compiles the project for each of the targets in turn, returning the
+    /// root package as compiled for each. The modules of the root and local
+    /// packages are type checked once and have code generated for every
+    /// target, unless they have code specific to a target or import a module
+    /// whose interface differs between the targets.
+    pub fn compile_targets(&mut self, targets: &[Target]) -> Result<Vec<Package>> {
+        if self.incremental.is_none() {
+            self.enable_incremental_compilation();
+        }
+        let mut warnings = vec![];
+        let mut packages = vec![];
+        for target in targets {
+            self.options.target = Some(*target);
+            let result = self.compile();
+            // Each build prints its own warnings, so they are set aside to
+            // not be printed again by the next
+            warnings.append(&mut self.warnings);
+            match result {
+                Ok(package) => packages.push(package),
+                Err(error) => {
+                    self.warnings = warnings;
+                    return Err(error);
+                }
+            }
+        }
+        self.warnings = warnings;
+        Ok(packages)
+    }
+
     /// Returns the compiled information from the root package
     pub fn compile(&mut self) -> Result<Package> {
-        self.check_gleam_version()?;
-        self.compile_dependencies()?;
+        if let Some(incremental) = &mut self.incremental {
+            incremental.changed_modules.clear();
+        }
+        self.load_dependencies()?;
 
+        let mut first_root_warning = self.warnings.len();
+        let result = self.compile_local_packages().and_then(|()| {
+            first_root_warning = self.warnings.len();
+            self.compile_root_package()
+        });
+
+        // Nothing is written for the root and local packages when only type
+        // checking, so the files written by the previous build are kept
         if self.options.perform_codegen {
-            self.telemetry.compiling_package(&self.config.name);
-        } else {
-            self.telemetry.checking_package(&self.config.name);
+            self.check_build_journal()?;
         }
-        let result = self.compile_root_package();
 
-        self.check_build_journal()?;
+        let denied = self.apply_warning_levels(first_root_warning);
 
         // Print warnings
         for warning in &self.warnings {
             self.telemetry.warning(warning);
         }
 
-        result
+        match result {
+            Ok(_) if denied > 0 => Err(Error::ForbiddenWarnings { count: denied }),
+            _ => result,
+        }
+    }
+
+    /// Remove the allowed warnings of the root package, returning how many of
+    /// its warnings are denied. Leftover `echo`s are only reported for
+    /// production builds.
+    fn apply_warning_levels(&mut self, first_root_warning: usize) -> usize {
+        let levels = self
+            .config
+            .warnings
+            .overridden_by(&self.options.warning_levels);
+        let mut denied = 0;
+        let root_warnings = self.warnings.split_off(first_root_warning);
+        for warning in root_warnings {
+            if self.options.mode.is_dev() && warning.kind() == Some(type_::WarningKind::Echo) {
+                continue;
+            }
+            match levels.level(warning.kind()) {
+                WarningLevel::Allow => continue,
+                WarningLevel::Warn => (),
+                WarningLevel::Deny => denied += 1,
+            }
+            self.warnings.push(warning);
+        }
+        denied
+    }
+
+    /// Compiles the dependencies, or when compiling incrementally restores the
+    /// dependencies compiled by the first build for the target.
+    fn load_dependencies(&mut self) -> Result<()> {
+        let target = self.target();
+        let compiled = self
+            .incremental
+            .as_ref()
+            .and_then(|incremental| incremental.dependencies.get(&target));
+        if let Some(dependencies) = compiled {
+            self.importable_modules = dependencies.importable_modules.clone();
+            self.defined_modules = dependencies.defined_modules.clone();
+        } else {
+            self.importable_modules = im::HashMap::new();
+            self.defined_modules = im::HashMap::new();
+            self.check_gleam_version()?;
+            self.compile_dependencies()?;
+            if let Some(incremental) = &mut self.incremental {
+                let _ = incremental.dependencies.insert(
+                    target,
+                    CompiledDependencies {
+                        importable_modules: self.importable_modules.clone(),
+                        defined_modules: self.defined_modules.clone(),
+                    },
+                );
+            }
+        }
+        self.build_journal.clear();
+
+        if let Some(incremental) = &mut self.incremental {
+            if let Some(previous) = incremental.target.filter(|previous| *previous != target) {
+                incremental.switch_target(previous, target);
+            }
+            incremental.target = Some(target);
+        }
+        Ok(())
+    }
+
+    /// Compile the local packages of the project, each after the local
+    /// packages it depends upon.
+    pub fn compile_local_packages(&mut self) -> Result<(), Error> {
+        let sequence = order_local_packages(&self.local_packages, self.mode())?;
+        for name in sequence {
+            let package = self
+                .local_packages
+                .iter()
+                .find(|package| package.config.name == name)
+                .cloned()
+                .expect("Missing local package");
+            self.report_compiling(&package.config.name);
+            let _ = self.compile_gleam_package(
+                &package.config,
+                PackageKind::Local,
+                package.root.clone(),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn report_compiling(&self, package: &str) {
+        if self.options.perform_codegen {
+            self.telemetry.compiling_package(package);
+        } else {
+            self.telemetry.checking_package(package);
+        }
     }
 
     pub fn compile_root_package(&mut self) -> Result<Package, Error> {
         let config = self.config.clone();
-        let modules = self.compile_gleam_package(&config, true, paths::root())?;
+        self.report_compiling(&config.name);
+        let modules = self.compile_gleam_package(&config, PackageKind::Root, paths::root())?;
+
+        if self.options.warn_dead_code {
+            tracing::info!("Detecting dead code");
+            self.warnings.extend(super::find_dead_code(&modules));
+        }
+
+        if config.lints.is_enabled() {
+            tracing::info!("Linting");
+            for module in &modules {
+                let lints = lint::module_lints(&module.ast, &module.code, &config.lints);
+                self.warnings
+                    .extend(lints.into_iter().map(|lint| Warning::Lint {
+                        path: module.input_path.clone(),
+                        src: module.code.clone(),
+                        lint,
+                    }));
+            }
+        }
 
         Ok(Package { config, modules })
     }
@@ -164,21 +398,29 @@ where
     /// Checks that version file found in the build directory matches the
     /// current version of gleam. If not, we will clear the build directory
     /// before continuing. This will ensure that upgrading gleam will not leave
-    /// one with confusing or hard to debug states.
+    /// one with confusing or hard to debug states. Upgrades that only change
+    /// the patch version keep the build directory, as the packages whose
+    /// metadata cannot be read are compiled again when they are loaded.
     pub fn check_gleam_version(&self) -> Result<(), Error> {
         let build_path = paths::build_packages(self.mode(), self.target());
         let version_path = paths::build_gleam_version(self.mode(), self.target());
-        if self.io.is_file(&version_path) {
-            let version = self.io.read(&version_path)?;
-            if version == COMPILER_VERSION {
-                return Ok(());
+        let version = if self.io.is_file(&version_path) {
+            Some(self.io.read(&version_path)?)
+        } else {
+            None
+        };
+        match version {
+            Some(version) if version == COMPILER_VERSION => return Ok(()),
+            Some(version) if same_minor_version(&version, COMPILER_VERSION) => {
+                tracing::info!(version=%version, "keeping_build_state_from_patch_gleam_version");
+            }
+            // Either file is missing or the versions do not match. Time to rebuild
+            _ => {
+                tracing::info!("removing_build_state_from_different_gleam_version");
+                self.io.delete(&build_path)?;
             }
         }
 
-        // Either file is missing our the versions do not match. Time to rebuild
-        tracing::info!("removing_build_state_from_different_gleam_version");
-        self.io.delete(&build_path)?;
-
         // Recreate build directory with new updated version file
         self.io.mkdir(&build_path)?;
         let mut writer = self.io.writer(&version_path)?;
@@ -224,26 +466,64 @@ where
             })
     }
 
+    // Packages are compiled one at a time as they share the IO and the
+    // telemetry of the project, and may run external build tools. The
+    // modules within each package are compiled in parallel.
     pub fn compile_dependencies(&mut self) -> Result<(), Error> {
         let sequence = order_packages(&self.packages)?;
+        let mut direct = self.config.dependencies_for(self.mode())?;
+        for package in &self.local_packages {
+            direct.extend(package.config.dependencies_for(self.mode())?);
+        }
+        let required = required_packages(&self.packages, direct.into_keys());
+        let cache_keys = global_build_cache_keys(
+            &self.packages,
+            &self.codegen_options(),
+            &self.config.dependency_features,
+        );
 
         for name in sequence {
             let package = self.packages.remove(&name).expect("Missing package config");
-            self.load_cache_or_compile_package(&package)?;
+            // The packages only depended upon by dev dependencies are not
+            // compiled for production
+            if !required.contains(&name) {
+                continue;
+            }
+            let cache_entry = self.global_build_cache_entry(&package, &cache_keys)?;
+            self.load_cache_or_compile_package(&package, cache_entry)?;
         }
 
         Ok(())
     }
 
-    fn load_cache_or_compile_package(&mut self, package: &ManifestPackage) -> Result<(), Error> {
+    fn load_cache_or_compile_package(
+        &mut self,
+        package: &ManifestPackage,
+        cache_entry: Option<PathBuf>,
+    ) -> Result<(), Error> {
         let build_path = paths::build_package(self.mode(), self.target(), &package.name);
         if self.io.is_directory(&build_path) {
             tracing::info!(package=%package.name, "loading_precompiled_package");
-            return self.load_cached_package(build_path, package);
+            if self.load_compatible_cached_package(&build_path, package)? {
+                return Ok(());
+            }
+        }
+
+        if let Some(entry) = &cache_entry {
+            if self.io.is_file(&entry.join(GLOBAL_BUILD_CACHE_COMPLETE)) {
+                tracing::info!(package=%package.name, "restoring_package_from_global_build_cache");
+                let build_packages = paths::build_packages(self.mode(), self.target());
+                self.io.mkdir(&build_packages)?;
+                self.io
+                    .copy_dir(&entry.join(&package.name), &build_packages)?;
+                if self.load_compatible_cached_package(&build_path, package)? {
+                    return Ok(());
+                }
+            }
         }
 
         self.telemetry.compiling_package(&package.name);
-        let result = match usable_build_tool(package)? {
+        let result = match usable_build_tool(package, &self.io)? {
             BuildTool::Gleam => self.compile_gleam_dep_package(package),
             BuildTool::Rebar3 => self.compile_rebar3_dep_package(package),
             BuildTool::Mix => self.compile_mix_dep_package(package),
@@ -256,9 +536,73 @@ where
             self.io.delete(&dir)?;
         }
 
+        if let (Ok(()), Some(entry)) = (&result, cache_entry) {
+            self.store_in_global_build_cache(&build_path, &entry);
+        }
+
         result
     }
 
+    /// The directory of the global build cache for the package, if there is
+    /// a cache and the package can be kept in it. Only the packages built by
+    /// Gleam are kept, as the output of other build tools can depend upon the
+    /// environment they are run in.
+    fn global_build_cache_entry(
+        &self,
+        package: &ManifestPackage,
+        cache_keys: &HashMap<String, String>,
+    ) -> Result<Option<PathBuf>> {
+        let (cache, key) = match (&self.global_build_cache, cache_keys.get(&package.name)) {
+            (Some(cache), Some(key)) => (cache, key),
+            _ => return Ok(None),
+        };
+        if !matches!(usable_build_tool(package, &self.io)?, BuildTool::Gleam) {
+            return Ok(None);
+        }
+        Ok(Some(
+            cache
+                .join(COMPILER_VERSION)
+                .join(self.mode().to_string())
+                .join(self.target().to_string())
+                .join(key),
+        ))
+    }
+
+    /// The options of the root package that change the code generated for its
+    /// dependencies, which the packages in the global build cache must have
+    /// been compiled with.
+    fn codegen_options(&self) -> String {
+        match self.target() {
+            Target::JavaScript => format!(
+                "typescript_declarations {}",
+                self.config.javascript.typescript_declarations
+            ),
+            Target::Erlang | Target::Native | Target::Go | Target::Llvm | Target::Wasm => {
+                String::new()
+            }
+        }
+    }
+
+    /// Failing to write to the global build cache does not fail the build, as
+    /// the package has been compiled.
+    fn store_in_global_build_cache(&self, build_path: &Path, entry: &Path) {
+        tracing::info!(entry=?entry, "storing_package_in_global_build_cache");
+        let result = self
+            .io
+            .delete(entry)
+            .and_then(|()| self.io.mkdir(entry))
+            .and_then(|()| self.io.copy_dir(build_path, entry))
+            // The entry is only used once it is known to be complete
+            .and_then(|()| {
+                self.io
+                    .writer(&entry.join(GLOBAL_BUILD_CACHE_COMPLETE))?
+                    .write(COMPILER_VERSION.as_bytes())
+            });
+        if let Err(error) = result {
+            tracing::warn!(error=?error, "failed_to_store_package_in_global_build_cache");
+        }
+    }
+
     fn compile_rebar3_dep_package(&mut self, package: &ManifestPackage) -> Result<(), Error> {
         let name = &package.name;
         let mode = self.mode();
@@ -317,22 +661,7 @@ where
             "--paths".into(),
             rebar3_path(&ebins),
         ];
-        let status = self.io.exec(
-            REBAR_EXECUTABLE,
-            &args,
-            &env,
-            Some(&project_dir),
-            self.subprocess_stdio,
-        )?;
-
-        if status == 0 {
-            Ok(())
-        } else {
-            Err(Error::ShellCommand {
-                program: "rebar3".to_string(),
-                err: None,
-            })
-        }
+        self.run_build_tool(name, "rebar3", REBAR_EXECUTABLE, &args, &env, &project_dir)
     }
 
     fn compile_mix_dep_package(&mut self, package: &ManifestPackage) -> Result<(), Error> {
@@ -396,26 +725,46 @@ where
             "--no-load-deps".into(),
             "--no-protocol-consolidation".into(),
         ];
-        let status = self.io.exec(
-            ELIXIR_EXECUTABLE,
-            &args,
-            &env,
-            Some(&project_dir),
-            self.subprocess_stdio,
-        )?;
+        self.run_build_tool(name, "mix", ELIXIR_EXECUTABLE, &args, &env, &project_dir)?;
+
+        // TODO: unit test
+        let source = mix_build_dir.join("lib").join(name);
+        if self.io.is_directory(&source) && !self.io.is_directory(&dest) {
+            tracing::debug!("linking_{}_to_build", name);
+            self.io.symlink_dir(&source, &dest)?;
+        }
+        Ok(())
+    }
+
+    /// Run the build tool of a dependency in its directory without printing
+    /// its output, which is written to the log of the package and included in
+    /// the error if the package could not be built.
+    fn run_build_tool(
+        &self,
+        package: &str,
+        program: &str,
+        executable: &str,
+        args: &[String],
+        env: &[(&str, String)],
+        project_dir: &Path,
+    ) -> Result<(), Error> {
+        let (status, output) =
+            self.timings()
+                .time(Phase::ErlangCompilation, package, None, || {
+                    self.io
+                        .exec_captured(executable, args, env, Some(project_dir))
+                })?;
+        let log = paths::build_package_log(self.mode(), self.target(), package);
+        self.io.writer(&log)?.write(output.as_bytes())?;
 
         if status == 0 {
-            // TODO: unit test
-            let source = mix_build_dir.join("lib").join(name);
-            if self.io.is_directory(&source) && !self.io.is_directory(&dest) {
-                tracing::debug!("linking_{}_to_build", name);
-                self.io.symlink_dir(&source, &dest)?;
-            }
             Ok(())
         } else {
-            Err(Error::ShellCommand {
-                program: "mix".to_string(),
-                err: None,
+            Err(Error::DependencyBuildFailed {
+                package: package.into(),
+                program: program.into(),
+                output,
+                log,
             })
         }
     }
@@ -424,48 +773,80 @@ where
         let config_path = paths::build_deps_package_config(&package.name);
         let config = PackageConfig::read(config_path, &self.io)?;
         let root = paths::build_deps_package(&package.name);
-        self.compile_gleam_package(&config, false, root)
+        self.compile_gleam_package(&config, PackageKind::Dependency, root)
             .map(|_| ())?;
         Ok(())
     }
 
+    /// Loads the metadata of a precompiled package, returning whether it
+    /// could be loaded. A package whose metadata was written in a format
+    /// this compiler cannot read is removed so that it is compiled again.
+    fn load_compatible_cached_package(
+        &mut self,
+        build_dir: &Path,
+        package: &ManifestPackage,
+    ) -> Result<bool, Error> {
+        match self.load_cached_package(build_dir, package) {
+            Ok(()) => Ok(true),
+            Err(Error::IncompatibleMetadata { .. }) => {
+                tracing::info!(package=%package.name, "removing_package_with_incompatible_metadata");
+                self.io.delete(build_dir)?;
+                Ok(false)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
     fn load_cached_package(
         &mut self,
-        build_dir: PathBuf,
+        build_dir: &Path,
         package: &ManifestPackage,
     ) -> Result<(), Error> {
-        for path in self.io.gleam_metadata_files(&build_dir) {
-            let reader = BufReader::new(self.io.reader(&path)?);
-            let module = metadata::ModuleDecoder::new(self.ids.clone()).read(reader)?;
-            let _ = self
-                .importable_modules
-                .insert(module.name.join("/"), module)
-                .ok_or(())
-                .expect_err("Metadata loaded for already loaded module");
-        }
-        Ok(())
+        let timings = self.timings();
+        timings.time(Phase::LoadDependencies, &package.name, None, || {
+            // Every module is decoded before any is loaded, so that none are
+            // loaded if the package has to be compiled again
+            let mut modules = vec![];
+            for path in self.io.gleam_metadata_files(build_dir) {
+                let reader = BufReader::new(self.io.reader(&path)?);
+                modules.push(metadata::ModuleDecoder::new(self.ids.clone()).read(reader)?);
+            }
+            for module in modules {
+                let _ = self
+                    .importable_modules
+                    .insert(module.name.join("/"), module)
+                    .ok_or(())
+                    .expect_err("Metadata loaded for already loaded module");
+            }
+            Ok(())
+        })
     }
 
     fn compile_gleam_package(
         &mut self,
         config: &PackageConfig,
-        is_root: bool,
+        kind: PackageKind,
         root_path: PathBuf,
     ) -> Result<Vec<Module>, Error> {
+        let is_root = kind == PackageKind::Root;
         let out_path = paths::build_package(self.mode(), self.target(), &config.name);
         let lib_path = paths::build_packages(self.mode(), self.target());
         let mode = self.mode();
         let target = match self.target() {
             Target::Erlang => super::TargetCodegenConfiguration::Erlang {
                 app_file: Some(ErlangAppCodegenConfiguration {
-                    include_dev_deps: is_root,
+                    include_dev_deps: is_root && mode.is_dev(),
                 }),
             },
             Target::Native => super::TargetCodegenConfiguration::CPlusPlus,
+            Target::Go => super::TargetCodegenConfiguration::Go,
+            Target::Llvm => super::TargetCodegenConfiguration::Llvm,
+            Target::Wasm => super::TargetCodegenConfiguration::Wasm,
             Target::JavaScript => super::TargetCodegenConfiguration::JavaScript {
                 emit_typescript_definitions: self.config.javascript.typescript_declarations,
             },
         };
+        let timings = self.timings();
         let mut compiler = PackageCompiler::new(
             config,
             &root_path,
@@ -474,16 +855,39 @@ where
             &target,
             self.ids.clone(),
             self.io.clone(),
-            if (is_root) {
-                Some(&mut self.build_journal)
-            } else {
+            if kind == PackageKind::Dependency {
                 None
+            } else {
+                Some(&mut self.build_journal)
             },
         );
-        compiler.write_metadata = true;
+        // Dependencies are always compiled fully, so that later builds can
+        // load them from the build directory. The other packages are only
+        // type checked when no code is to be generated.
+        let codegen = kind == PackageKind::Dependency || self.options.perform_codegen;
+        compiler.write_metadata = codegen;
+        compiler.perform_codegen = codegen;
         compiler.write_entrypoint = is_root;
-        compiler.compile_beam_bytecode = !is_root || self.options.perform_codegen;
+        compiler.doctests = is_root && self.doctests;
+        compiler.compile_beam_bytecode = codegen;
         compiler.subprocess_stdio = self.subprocess_stdio;
+        if kind != PackageKind::Root {
+            compiler.features = self.config.features_of(config)?;
+        }
+        compiler.timings = timings;
+        if let (PackageKind::Root | PackageKind::Local, Some(incremental)) =
+            (kind, self.incremental.as_mut())
+        {
+            compiler.cached_modules = Some(
+                incremental
+                    .local_modules
+                    .entry(config.name.clone())
+                    .or_default(),
+            );
+            compiler.changed_modules = Some(&mut incremental.changed_modules);
+            compiler.cached_code_generated =
+                incremental.generated.get(&config.name) == Some(&target.target());
+        }
         compiler.read_source_files(mode)?;
 
         // Compile project to Erlang or JavaScript source code
@@ -492,6 +896,17 @@ where
             &mut self.importable_modules,
             &mut self.defined_modules,
         )?;
+        for module in &compiled {
+            self.telemetry.module_compiled(&config.name, &module.name);
+        }
+
+        if let (PackageKind::Root | PackageKind::Local, true, Some(incremental)) =
+            (kind, codegen, self.incremental.as_mut())
+        {
+            let _ = incremental
+                .generated
+                .insert(config.name.clone(), target.target());
+        }
 
         Ok(compiled)
     }
@@ -507,21 +922,123 @@ fn order_packages(packages: &HashMap<String, ManifestPackage>) -> Result<Vec<Str
     .map_err(convert_deps_tree_error)
 }
 
+/// The packages of the manifest that are depended upon, directly or through
+/// other packages, by the given dependencies.
+fn required_packages(
+    packages: &HashMap<String, ManifestPackage>,
+    dependencies: impl Iterator<Item = String>,
+) -> HashSet<String> {
+    let mut required = HashSet::new();
+    let mut to_visit: Vec<String> = dependencies.collect();
+    while let Some(name) = to_visit.pop() {
+        if let Some(package) = packages.get(&name) {
+            if required.insert(name) {
+                to_visit.extend(package.requirements.iter().cloned());
+            }
+        }
+    }
+    required
+}
+
+/// The names of the packages in the global build cache. A name changes with
+/// anything that could change what the package compiles to: its version and
+/// source, the versions and sources of the packages it depends upon, the code
+/// generation options of the root package, and the features it sets for each
+/// of those packages. The names are kept on disk
+/// between compiler versions, so they are hashed with SHA-256 rather than a
+/// hasher whose output may change.
+fn global_build_cache_keys(
+    packages: &HashMap<String, ManifestPackage>,
+    codegen_options: &str,
+    dependency_features: &HashMap<String, HashMap<String, bool>>,
+) -> HashMap<String, String> {
+    packages
+        .values()
+        .map(|package| {
+            let mut hasher = Sha256::new();
+            hasher.update(format!("codegen {}\n", codegen_options));
+            for name in required_packages(packages, std::iter::once(package.name.clone()))
+                .into_iter()
+                .sorted()
+            {
+                let dependency = packages.get(&name).expect("Missing package config");
+                let source = match &dependency.source {
+                    ManifestPackageSource::Hex {
+                        outer_checksum,
+                        registry,
+                    } => format!(
+                        "hex {} {}",
+                        registry.as_deref().unwrap_or(crate::hex::HEXPM),
+                        outer_checksum.to_string()
+                    ),
+                    ManifestPackageSource::Git { repo, commit } => {
+                        format!("git {} {}", repo, commit)
+                    }
+                };
+                hasher.update(format!(
+                    "package {} {} {}\n",
+                    name, dependency.version, source
+                ));
+                let features = dependency_features.get(&name).into_iter().flatten();
+                for (flag, enabled) in features.sorted() {
+                    hasher.update(format!("feature {} {}\n", flag, enabled));
+                }
+            }
+            let key = format!(
+                "{}-{}-{}",
+                package.name,
+                package.version,
+                base16::encode_lower(&hasher.finalize().get(..8).unwrap_or_default())
+            );
+            (package.name.clone(), key)
+        })
+        .collect()
+}
+
+/// Local packages are ordered so that each is compiled after the local
+/// packages it depends upon. Their other dependencies have already been
+/// compiled.
+/// Whether two compiler versions differ only in their patch version.
+fn same_minor_version(a: &str, b: &str) -> bool {
+    fn minor(version: &str) -> Option<(&str, &str)> {
+        let mut parts = version.trim().splitn(3, '.');
+        Some((parts.next()?, parts.next()?))
+    }
+    minor(a).is_some() && minor(a) == minor(b)
+}
+
+fn order_local_packages(packages: &[LocalPackage], mode: Mode) -> Result<Vec<String>, Error> {
+    let is_local = |name: &String| packages.iter().any(|package| &package.config.name == name);
+    let mut graph = Vec::with_capacity(packages.len());
+    for package in packages {
+        let dependencies = package.config.dependencies_for(mode)?;
+        let local_dependencies = dependencies.into_keys().filter(is_local).sorted().collect();
+        graph.push((package.config.name.clone(), local_dependencies));
+    }
+    dep_tree::toposort_deps(graph).map_err(convert_deps_tree_error)
+}
+
 fn convert_deps_tree_error(e: dep_tree::Error) -> Error {
     match e {
         dep_tree::Error::Cycle(packages) => Error::PackageCycle { packages },
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum BuildTool {
     Gleam,
     Rebar3,
     Mix,
 }
 
-/// Determine the build tool we should use to build this package
-fn usable_build_tool(package: &ManifestPackage) -> Result<BuildTool, Error> {
+/// Determine the build tool we should use to build this package. The build
+/// tools of its Hex metadata are preferred, and failing those the tool is
+/// detected from the files of the downloaded package, as some packages only
+/// name build tools that we cannot use, such as `make`.
+fn usable_build_tool(
+    package: &ManifestPackage,
+    io: &impl FileSystemReader,
+) -> Result<BuildTool, Error> {
     for tool in &package.build_tools {
         match tool.as_str() {
             "gleam" => return Ok(BuildTool::Gleam),
@@ -531,14 +1048,418 @@ fn usable_build_tool(package: &ManifestPackage) -> Result<BuildTool, Error> {
         }
     }
 
+    let root = paths::build_deps_package(&package.name);
+    for (file, tool) in [
+        ("gleam.toml", BuildTool::Gleam),
+        ("mix.exs", BuildTool::Mix),
+        ("rebar.config", BuildTool::Rebar3),
+    ] {
+        if io.is_file(&root.join(file)) {
+            return Ok(tool);
+        }
+    }
+
     Err(Error::UnsupportedBuildTool {
         package: package.name.to_string(),
         build_tools: package.build_tools.clone(),
     })
 }
 
+#[derive(Debug, Default)]
+struct IncrementalState {
+    /// The dependencies compiled by the first build for each target.
+    dependencies: HashMap<Target, CompiledDependencies>,
+    /// The modules of the root and local packages compiled by the previous
+    /// builds, by package.
+    local_modules: HashMap<String, HashMap<String, package_compiler::CompiledModule>>,
+    /// The modules compiled by the current build whose interface has changed
+    /// since the previous build.
+    changed_modules: HashSet<String>,
+    /// The target of the previous build.
+    target: Option<Target>,
+    /// The target that code was last generated for, by package.
+    generated: HashMap<String, Target>,
+}
+
+impl IncrementalState {
+    /// Prepare the modules compiled for the previous target to be reused for
+    /// another. The modules with code specific to a target are compiled
+    /// again, as are those that import a dependency module whose interface
+    /// differs between the targets. The warnings of the reused modules have
+    /// already been emitted for the previous target so are not emitted again.
+    fn switch_target(&mut self, previous: Target, target: Target) {
+        for modules in self.local_modules.values_mut() {
+            modules.retain(|_, cached| {
+                let arena = Arena::new();
+                let parsed = crate::parse::parse_module(&cached.module.code, &arena);
+                match parsed {
+                    Ok((module, _)) => !module.has_target_specific_code(),
+                    Err(_) => false,
+                }
+            });
+            for cached in modules.values_mut() {
+                cached.warnings.clear();
+            }
+        }
+
+        let (previous, current) = match (
+            self.dependencies.get(&previous),
+            self.dependencies.get(&target),
+        ) {
+            (Some(previous), Some(current)) => {
+                (&previous.importable_modules, &current.importable_modules)
+            }
+            _ => return,
+        };
+        let fingerprint = |modules: &im::HashMap<String, type_::Module>, name: &String| {
+            modules.get(name).map(type_::Module::interface_fingerprint)
+        };
+        let changed = previous
+            .keys()
+            .chain(current.keys())
+            .filter(|name| fingerprint(previous, name) != fingerprint(current, name))
+            .cloned()
+            .collect_vec();
+        self.changed_modules.extend(changed);
+    }
+}
+
+#[derive(Debug)]
+struct CompiledDependencies {
+    importable_modules: im::HashMap<String, type_::Module>,
+    defined_modules: im::HashMap<String, PathBuf>,
+}
+
 pub struct CheckpointState {
     importable_modules: im::HashMap<String, type_::Module>,
     defined_modules: im::HashMap<String, PathBuf>,
     ids: UniqueIdGenerator,
 }
+
+#[cfg(test)]
+fn test_package(name: &str, version: (u32, u32, u32), requirements: &[&str]) -> ManifestPackage {
+    use crate::manifest::Base16Checksum;
+    ManifestPackage {
+        name: name.into(),
+        version: hexpm::version::Version::new(version.0, version.1, version.2),
+        build_tools: vec!["gleam".into()],
+        otp_app: None,
+        requirements: requirements.iter().map(|name| name.to_string()).collect(),
+        source: ManifestPackageSource::Hex {
+            outer_checksum: Base16Checksum(vec![]),
+            registry: None,
+        },
+    }
+}
+
+#[cfg(test)]
+fn test_packages(packages: Vec<ManifestPackage>) -> HashMap<String, ManifestPackage> {
+    packages
+        .into_iter()
+        .map(|package| (package.name.clone(), package))
+        .collect()
+}
+
+#[test]
+fn same_minor_version_test() {
+    assert!(same_minor_version("0.25.0", "0.25.1"));
+    assert!(same_minor_version("0.25.1-rc1\n", "0.25.1"));
+    assert!(!same_minor_version("0.24.1", "0.25.1"));
+    assert!(!same_minor_version("1.25.1", "0.25.1"));
+    assert!(!same_minor_version("", "0.25.1"));
+}
+
+#[test]
+fn required_packages_leave_out_dev_only_packages() {
+    let packages = test_packages(vec![
+        test_package("gleam_stdlib", (1, 0, 0), &[]),
+        test_package("gleam_http", (1, 0, 0), &["gleam_stdlib"]),
+        test_package("gleeunit", (1, 0, 0), &["gleam_stdlib", "gleam_erlang"]),
+        test_package("gleam_erlang", (1, 0, 0), &["gleam_stdlib"]),
+    ]);
+    assert_eq!(
+        required_packages(&packages, ["gleam_http".to_string()].into_iter()),
+        ["gleam_http".to_string(), "gleam_stdlib".to_string()].into()
+    );
+}
+
+#[test]
+fn global_build_cache_keys_change_with_dependencies() {
+    let keys = global_build_cache_keys(
+        &test_packages(vec![
+            test_package("gleam_stdlib", (1, 0, 0), &[]),
+            test_package("gleam_http", (2, 0, 0), &["gleam_stdlib"]),
+            test_package("gleeunit", (1, 0, 0), &[]),
+        ]),
+        "",
+        &HashMap::new(),
+    );
+    let upgraded_stdlib = global_build_cache_keys(
+        &test_packages(vec![
+            test_package("gleam_stdlib", (1, 1, 0), &[]),
+            test_package("gleam_http", (2, 0, 0), &["gleam_stdlib"]),
+            test_package("gleeunit", (1, 0, 0), &[]),
+        ]),
+        "",
+        &HashMap::new(),
+    );
+    assert!(keys
+        .get("gleam_http")
+        .expect("gleam_http key")
+        .starts_with("gleam_http-2.0.0-"));
+    assert_ne!(
+        keys.get("gleam_stdlib"),
+        upgraded_stdlib.get("gleam_stdlib")
+    );
+    assert_ne!(keys.get("gleam_http"), upgraded_stdlib.get("gleam_http"));
+    assert_eq!(keys.get("gleeunit"), upgraded_stdlib.get("gleeunit"));
+}
+
+#[test]
+fn global_build_cache_keys_change_with_codegen_options() {
+    let packages = test_packages(vec![test_package("gleam_stdlib", (1, 0, 0), &[])]);
+    assert_ne!(
+        global_build_cache_keys(&packages, "typescript_declarations false", &HashMap::new()),
+        global_build_cache_keys(&packages, "typescript_declarations true", &HashMap::new()),
+    );
+}
+
+#[test]
+fn global_build_cache_keys_change_with_dependency_features() {
+    let packages = test_packages(vec![
+        test_package("gleam_stdlib", (1, 0, 0), &[]),
+        test_package("gleam_http", (2, 0, 0), &["gleam_stdlib"]),
+        test_package("gleeunit", (1, 0, 0), &[]),
+    ]);
+    let keys = global_build_cache_keys(&packages, "", &HashMap::new());
+    let features = HashMap::from([(
+        "gleam_stdlib".to_string(),
+        HashMap::from([("bit_strings".to_string(), false)]),
+    )]);
+    let with_features = global_build_cache_keys(&packages, "", &features);
+    assert_ne!(keys.get("gleam_stdlib"), with_features.get("gleam_stdlib"));
+    assert_ne!(keys.get("gleam_http"), with_features.get("gleam_http"));
+    assert_eq!(keys.get("gleeunit"), with_features.get("gleeunit"));
+}
+
+// The keys name directories that are kept between compiler versions, so they
+// must not change when the compiler is built with another version of Rust
+#[test]
+fn global_build_cache_keys_are_stable() {
+    let packages = test_packages(vec![test_package("gleam_stdlib", (1, 0, 0), &[])]);
+    assert_eq!(
+        global_build_cache_keys(&packages, "", &HashMap::new()).get("gleam_stdlib"),
+        Some(&"gleam_stdlib-1.0.0-5596f3d8c127ba4b".to_string())
+    );
+}
+
+#[test]
+fn usable_build_tool_prefers_hex_metadata() {
+    let io = crate::io::memory::InMemoryFileSystem::new();
+    let mut package = test_package("certifi", (2, 9, 0), &[]);
+    package.build_tools = vec!["make".into(), "rebar3".into(), "mix".into()];
+    assert_eq!(usable_build_tool(&package, &io), Ok(BuildTool::Rebar3));
+}
+
+#[test]
+fn usable_build_tool_detected_from_files() {
+    let io = crate::io::memory::InMemoryFileSystem::new();
+    let mut package = test_package("cowlib", (2, 11, 0), &[]);
+    package.build_tools = vec!["make".into()];
+    assert!(matches!(
+        usable_build_tool(&package, &io),
+        Err(Error::UnsupportedBuildTool { .. })
+    ));
+
+    let root = paths::build_deps_package("cowlib");
+    let _ = io.writer(&root.join("rebar.config")).expect("rebar.config");
+    assert_eq!(usable_build_tool(&package, &io), Ok(BuildTool::Rebar3));
+
+    let _ = io.writer(&root.join("mix.exs")).expect("mix.exs");
+    assert_eq!(usable_build_tool(&package, &io), Ok(BuildTool::Mix));
+}
+
+#[cfg(test)]
+#[derive(Debug)]
+struct NullTelemetry;
+
+#[cfg(test)]
+impl Telemetry for NullTelemetry {
+    fn waiting_for_build_directory_lock(&self) {}
+    fn resolving_package_versions(&self) {}
+    fn downloading_package(&self, _name: &str) {}
+    fn packages_downloaded(&self, _start: Instant, _count: usize) {}
+    fn compiling_package(&self, _name: &str) {}
+    fn checking_package(&self, _name: &str) {}
+    fn running_build_hook(&self, _package: &str, _hook: &str) {}
+    fn warning(&self, _warning: &Warning) {}
+}
+
+// The root package is only type checked by `gleam check`, so no code is
+// written for it and the code written by an earlier build is kept
+#[test]
+fn check_performs_no_codegen_for_root_package() {
+    let io = crate::io::memory::InMemoryFileSystem::new();
+    io.writer(&paths::root().join("src/app.gleam"))
+        .and_then(|mut writer| writer.write(b"pub fn main() { 1 }"))
+        .expect("app.gleam");
+    let compile = |perform_codegen| {
+        let config = PackageConfig {
+            name: "app".into(),
+            target: Target::JavaScript,
+            ..Default::default()
+        };
+        let options = Options {
+            mode: Mode::Dev,
+            target: None,
+            perform_codegen,
+            warn_dead_code: false,
+            warning_levels: WarningLevels::default(),
+        };
+        let mut compiler =
+            ProjectCompiler::new(config, options, vec![], Box::new(NullTelemetry), io.clone());
+        let package = compiler.compile().expect("Should compile OK");
+        assert_eq!(
+            package
+                .modules
+                .iter()
+                .map(|module| module.name.to_string())
+                .collect_vec(),
+            vec!["app"]
+        );
+        io.read_dir(&paths::build())
+            .expect("build directory")
+            .into_iter()
+            .filter_map(|entry| Some(entry.ok()?.pathbuf))
+            .filter(|path| path.starts_with("build/dev/javascript/app"))
+            .sorted()
+            .collect_vec()
+    };
+    assert_eq!(compile(false), Vec::<PathBuf>::new());
+    let built = compile(true);
+    assert!(built.contains(&PathBuf::from("build/dev/javascript/app/app.mjs")));
+    assert_eq!(compile(false), built);
+}
+
+// The language server compiles the root package again after each edit,
+// restoring the compiler to a checkpoint afterwards. Only the modules that
+// have changed, or that import a module whose interface was changed by that
+// build, are analysed again.
+#[test]
+fn language_server_builds_reuse_unchanged_modules() {
+    #[derive(Debug)]
+    struct Analysed(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl Telemetry for Analysed {
+        fn waiting_for_build_directory_lock(&self) {}
+        fn resolving_package_versions(&self) {}
+        fn downloading_package(&self, _name: &str) {}
+        fn packages_downloaded(&self, _start: Instant, _count: usize) {}
+        fn compiling_package(&self, _name: &str) {}
+        fn checking_package(&self, _name: &str) {}
+        fn running_build_hook(&self, _package: &str, _hook: &str) {}
+        fn warning(&self, _warning: &Warning) {}
+        fn phase_started(&self, phase: Phase, _package: &str, module: Option<&str>) {
+            if let (Phase::Analyse, Some(module)) = (phase, module) {
+                self.0.lock().expect("Analysed lock").push(module.into());
+            }
+        }
+    }
+
+    let io = crate::io::memory::InMemoryFileSystem::new();
+    let write = |name: &str, code: &str| {
+        let path = paths::root().join("src").join(name);
+        io.delete_file(&path)
+            .and_then(|()| io.writer(&path))
+            .and_then(|mut writer| writer.write(code.as_bytes()))
+            .expect("source file");
+    };
+    write("one.gleam", "pub fn one() { 1 }");
+    write("two.gleam", "import one\npub fn two() { one.one() }");
+    write("three.gleam", "pub fn three() { let x = 3 3 }");
+
+    let analysed = Arc::new(std::sync::Mutex::new(vec![]));
+    let config = PackageConfig {
+        name: "app".into(),
+        target: Target::JavaScript,
+        ..Default::default()
+    };
+    let options = Options {
+        mode: Mode::Dev,
+        target: None,
+        perform_codegen: false,
+        warn_dead_code: false,
+        warning_levels: WarningLevels::default(),
+    };
+    let telemetry = Box::new(Analysed(analysed.clone()));
+    let mut compiler = ProjectCompiler::new(config, options, vec![], telemetry, io.clone());
+    compiler.enable_incremental_compilation();
+    compiler.compile_dependencies().expect("dependencies");
+
+    // Each build is done the same way as by the language server, returning
+    // the modules analysed and the number of warnings
+    let mut build = || {
+        compiler.clear_changed_modules();
+        let checkpoint = compiler.checkpoint();
+        let result = compiler
+            .compile_local_packages()
+            .and_then(|()| compiler.compile_root_package());
+        compiler.restore(checkpoint);
+        let _ = result.expect("Should compile OK");
+        let modules = std::mem::take(&mut *analysed.lock().expect("Analysed lock"));
+        (
+            modules.into_iter().sorted().collect_vec(),
+            compiler.take_warnings().len(),
+        )
+    };
+
+    assert_eq!(
+        build(),
+        (vec!["one".into(), "three".into(), "two".into()], 1)
+    );
+    // The warnings of reused modules are emitted again
+    assert_eq!(build(), (vec![], 1));
+    // Modules importing a module whose interface has changed are analysed
+    write("one.gleam", "pub fn one() { 1.0 }");
+    assert_eq!(build(), (vec!["one".into(), "two".into()], 1));
+    // The interface changed by the previous build is not counted as changed
+    // again
+    write("one.gleam", "pub fn one() { 2.0 }");
+    assert_eq!(build(), (vec!["one".into()], 1));
+}
+
+// The modules kept to be reused by later builds were given ids after the
+// checkpoint, so restoring it does not give those ids out again
+#[test]
+fn restore_keeps_ids_when_compiling_incrementally() {
+    let io = crate::io::memory::InMemoryFileSystem::new();
+    io.writer(&paths::root().join("src/app.gleam"))
+        .and_then(|mut writer| writer.write(b"pub fn main() { 1 }"))
+        .expect("app.gleam");
+    let ids_used_by_build = |incremental| {
+        let config = PackageConfig {
+            name: "app".into(),
+            target: Target::JavaScript,
+            ..Default::default()
+        };
+        let options = Options {
+            mode: Mode::Dev,
+            target: None,
+            perform_codegen: false,
+            warn_dead_code: false,
+            warning_levels: WarningLevels::default(),
+        };
+        let mut compiler =
+            ProjectCompiler::new(config, options, vec![], Box::new(NullTelemetry), io.clone());
+        if incremental {
+            compiler.enable_incremental_compilation();
+        }
+        let before = compiler.ids().next();
+        let checkpoint = compiler.checkpoint();
+        let _ = compiler.compile_root_package().expect("Should compile OK");
+        compiler.restore(checkpoint);
+        compiler.ids().next() - before - 1
+    };
+    assert_eq!(ids_used_by_build(false), 0);
+    assert!(ids_used_by_build(true) > 0);
+}
@@ -3,14 +3,45 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::Warning;
+use crate::{build::Phase, Warning};
 
-pub trait Telemetry: Debug {
+/// Reports the progress of a build, such as the packages being downloaded
+/// and compiled and the warnings emitted. The command line prints these
+/// events, while editors and build servers can implement this to show
+/// progress bars or structured logs.
+///
+/// The methods with a default implementation report events in more detail,
+/// and are ignored unless implemented. The phases of a build may be run on
+/// several threads at once, so they may be reported from any of them.
+pub trait Telemetry: Debug + Send + Sync {
     fn waiting_for_build_directory_lock(&self);
     fn resolving_package_versions(&self);
     fn downloading_package(&self, name: &str);
     fn packages_downloaded(&self, start: Instant, count: usize);
     fn compiling_package(&self, name: &str);
     fn checking_package(&self, name: &str);
+    fn running_build_hook(&self, package: &str, hook: &str);
     fn warning(&self, warning: &Warning);
+
+    /// A package has been downloaded, being the `downloaded`th of the
+    /// `total` packages being downloaded.
+    fn package_downloaded(&self, _name: &str, _downloaded: usize, _total: usize) {}
+
+    /// A phase of the build of a package has started, for the module given
+    /// if the phase is run for each module.
+    fn phase_started(&self, _phase: Phase, _package: &str, _module: Option<&str>) {}
+
+    /// A phase of the build of a package has finished, successfully or not.
+    fn phase_finished(
+        &self,
+        _phase: Phase,
+        _package: &str,
+        _module: Option<&str>,
+        _duration: Duration,
+    ) {
+    }
+
+    /// A module of a package has been compiled, or reused from an earlier
+    /// build as it has not changed.
+    fn module_compiled(&self, _package: &str, _module: &str) {}
 }
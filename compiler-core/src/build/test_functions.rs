@@ -0,0 +1,155 @@
+//! Discovery of the test functions that `gleam test` runs itself when given
+//! `--filter` or `--parallel`, rather than running the `main` function of the
+//! test module.
+//!
+//! Test functions are found the same way as gleeunit finds them: they are the
+//! public functions of the test modules that take no arguments and have names
+//! ending in `_test`.
+
+use crate::{
+    ast::{Statement, TypedStatement},
+    build::{Module, Origin},
+};
+use itertools::Itertools;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestFunction {
+    pub module: String,
+    pub function: String,
+}
+
+impl TestFunction {
+    /// The name the test is reported and filtered by, such as
+    /// `my_app_test.add_test`.
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.module, self.function)
+    }
+}
+
+/// The test functions of the modules, sorted by module and then in the order
+/// they are defined. When there is a filter only the test functions whose
+/// name contains it are returned.
+pub fn test_functions(modules: &[Module], filter: Option<&str>) -> Vec<TestFunction> {
+    modules
+        .iter()
+        .filter(|module| module.origin == Origin::Test)
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .flat_map(|module| {
+            module.ast.statements.iter().filter_map(move |statement| {
+                Some(TestFunction {
+                    module: module.name.clone(),
+                    function: test_function_name(statement)?.to_string(),
+                })
+            })
+        })
+        .filter(|test| filter.map_or(true, |filter| test.name().contains(filter)))
+        .collect()
+}
+
+/// The name of the function defined by the statement of a test module, if it
+/// is a test function.
+pub fn test_function_name(statement: &TypedStatement) -> Option<&str> {
+    match statement {
+        Statement::Fn {
+            name,
+            public: true,
+            arguments,
+            ..
+        } if arguments.is_empty() && name.ends_with("_test") => Some(name),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+fn test_names(sources: &[(&str, Origin, &str)], filter: Option<&str>) -> Vec<String> {
+    use crate::{
+        parse::extra::ModuleExtra,
+        type_::{build_prelude, infer_module},
+        uid::UniqueIdGenerator,
+    };
+    let ids = UniqueIdGenerator::new();
+    let mut importable = im::HashMap::new();
+    let _ = importable.insert("gleam".to_string(), build_prelude(&ids));
+    let modules = sources
+        .iter()
+        .map(|(name, origin, src)| {
+            let arena = crate::ast::Arena::new();
+            let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+            ast.name = name.split('/').map(Into::into).collect();
+            let ast = infer_module(
+                crate::build::Target::Erlang,
+                &ids,
+                ast,
+                *origin,
+                "thepackage",
+                &importable,
+                &mut vec![],
+            )
+            .expect("should successfully infer");
+            Module {
+                name: name.to_string(),
+                code: src.to_string(),
+                input_path: format!("/{}.gleam", name).into(),
+                origin: *origin,
+                ast,
+                extra: ModuleExtra::new(),
+            }
+        })
+        .collect_vec();
+    test_functions(&modules, filter)
+        .iter()
+        .map(TestFunction::name)
+        .collect()
+}
+
+#[test]
+fn test_functions_of_test_modules() {
+    let sources = [
+        (
+            "app_test",
+            Origin::Test,
+            "pub fn b_test() { Nil }
+pub fn a_test() { Nil }
+fn private_test() { Nil }
+pub fn helper() { Nil }
+pub fn argument_test(x) { x }",
+        ),
+        ("app", Origin::Src, "pub fn main_test() { Nil }"),
+        (
+            "app/parser_test",
+            Origin::Test,
+            "pub fn parse_test() { Nil }",
+        ),
+    ];
+    assert_eq!(
+        test_names(&sources, None),
+        [
+            "app/parser_test.parse_test",
+            "app_test.b_test",
+            "app_test.a_test"
+        ]
+    );
+}
+
+#[test]
+fn test_functions_filter() {
+    let sources = [
+        (
+            "app_test",
+            Origin::Test,
+            "pub fn add_test() { Nil }
+pub fn subtract_test() { Nil }",
+        ),
+        (
+            "app/parser_test",
+            Origin::Test,
+            "pub fn parse_test() { Nil }",
+        ),
+    ];
+    assert_eq!(test_names(&sources, Some("add")), ["app_test.add_test"]);
+    assert_eq!(
+        test_names(&sources, Some("parser_test.")),
+        ["app/parser_test.parse_test"]
+    );
+    assert!(test_names(&sources, Some("nothing")).is_empty());
+}
@@ -0,0 +1,353 @@
+//! The wall time taken by each phase of a build, recorded when the user asks
+//! for it with `--timings`.
+//!
+//! Timings are disabled by default, in which case recording costs nothing
+//! more than running the timed work.
+//!
+//! The start and end of each piece of work timed is also reported to the
+//! telemetry of the build, if it is given one, so that editors can show the
+//! progress of the build.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+    sync::{Arc, Mutex},
+    thread::ThreadId,
+    time::{Duration, Instant},
+};
+
+use itertools::Itertools;
+
+use super::{Target, Telemetry};
+
+/// The number of slowest modules listed by the summary.
+const SLOWEST_MODULES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    LoadDependencies,
+    Parse,
+    Analyse,
+    Codegen(Target),
+    ErlangCompilation,
+    CPlusPlusFormatting,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::LoadDependencies => f.write_str("load dependencies"),
+            Phase::Parse => f.write_str("parse"),
+            Phase::Analyse => f.write_str("analyse"),
+            Phase::Codegen(target) => write!(f, "codegen ({})", target),
+            Phase::ErlangCompilation => f.write_str("erlang compilation"),
+            Phase::CPlusPlusFormatting => f.write_str("c++ formatting"),
+        }
+    }
+}
+
+/// A piece of work timed during the build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub phase: Phase,
+    pub package: String,
+    /// The module the work was for, if it was for a single module rather
+    /// than the whole package.
+    pub module: Option<String>,
+    /// When the work started, relative to the start of the recording.
+    pub start: Duration,
+    pub duration: Duration,
+    /// A small number identifying the thread the work was done on.
+    pub thread: usize,
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    spans: Vec<Span>,
+    threads: HashMap<ThreadId, usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    recording: Option<(Instant, Arc<Mutex<Recording>>)>,
+    /// Told when each piece of work starts and finishes.
+    telemetry: Option<Arc<dyn Telemetry>>,
+}
+
+impl Timings {
+    /// Timings that record the work timed with them.
+    pub fn new() -> Self {
+        Self {
+            recording: Some((Instant::now(), Arc::default())),
+            telemetry: None,
+        }
+    }
+
+    /// Timings that record nothing.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// These timings, also reporting the start and end of the timed work to
+    /// the telemetry.
+    pub fn with_telemetry(self, telemetry: Arc<dyn Telemetry>) -> Self {
+        Self {
+            telemetry: Some(telemetry),
+            ..self
+        }
+    }
+
+    /// Run the function, recording how long it took if timings are enabled.
+    pub fn time<T>(
+        &self,
+        phase: Phase,
+        package: &str,
+        module: Option<&str>,
+        f: impl FnOnce() -> T,
+    ) -> T {
+        if self.recording.is_none() && self.telemetry.is_none() {
+            return f();
+        }
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.phase_started(phase, package, module);
+        }
+        let start = Instant::now();
+        let result = f();
+        let duration = start.elapsed();
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.phase_finished(phase, package, module, duration);
+        }
+
+        let (origin, recording) = match &self.recording {
+            Some(recording) => recording,
+            None => return result,
+        };
+
+        let mut recording = recording.lock().expect("Timings lock");
+        let next_thread = recording.threads.len();
+        let thread = *recording
+            .threads
+            .entry(std::thread::current().id())
+            .or_insert(next_thread);
+        recording.spans.push(Span {
+            phase,
+            package: package.to_string(),
+            module: module.map(String::from),
+            start: start.duration_since(*origin),
+            duration,
+            thread,
+        });
+        result
+    }
+
+    /// The work recorded so far, in the order it finished.
+    pub fn spans(&self) -> Vec<Span> {
+        match &self.recording {
+            Some((_, recording)) => recording.lock().expect("Timings lock").spans.clone(),
+            None => vec![],
+        }
+    }
+
+    /// The total time of each phase, slowest first, followed by the modules
+    /// that took longest to work on.
+    pub fn summary(&self) -> String {
+        summary(&self.spans())
+    }
+
+    /// The recorded work in the Chrome trace event format, which can be
+    /// viewed with `chrome://tracing` or Perfetto.
+    pub fn chrome_trace(&self) -> String {
+        chrome_trace(&self.spans())
+    }
+}
+
+fn summary(spans: &[Span]) -> String {
+    let mut totals: HashMap<Phase, (Duration, usize)> = HashMap::new();
+    for span in spans {
+        let total = totals.entry(span.phase).or_default();
+        total.0 += span.duration;
+        total.1 += 1;
+    }
+
+    let mut summary = String::new();
+    let _ = writeln!(summary, "{:<24} {:>10} {:>8}", "Phase", "Time", "Count");
+    let totals = totals
+        .into_iter()
+        .sorted_by_key(|(phase, (duration, _))| (std::cmp::Reverse(*duration), phase.to_string()));
+    for (phase, (duration, count)) in totals {
+        let _ = writeln!(
+            summary,
+            "{:<24} {:>10} {:>8}",
+            phase.to_string(),
+            milliseconds(duration),
+            count
+        );
+    }
+
+    let slowest = spans
+        .iter()
+        .filter(|span| span.module.is_some())
+        .sorted_by(|a, b| b.duration.cmp(&a.duration))
+        .take(SLOWEST_MODULES)
+        .collect_vec();
+    if !slowest.is_empty() {
+        let _ = writeln!(summary, "\nSlowest modules");
+        for span in slowest {
+            let _ = writeln!(
+                summary,
+                "{:<24} {:>10} {}/{}",
+                span.phase.to_string(),
+                milliseconds(span.duration),
+                span.package,
+                span.module.as_deref().unwrap_or_default(),
+            );
+        }
+    }
+    summary
+}
+
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.)
+}
+
+fn chrome_trace(spans: &[Span]) -> String {
+    let events = spans
+        .iter()
+        .map(|span| {
+            let name = match &span.module {
+                Some(module) => format!("{}/{}", span.package, module),
+                None => span.package.clone(),
+            };
+            serde_json::json!({
+                "name": name,
+                "cat": span.phase.to_string(),
+                "ph": "X",
+                "ts": span.start.as_micros() as u64,
+                "dur": span.duration.as_micros() as u64,
+                "pid": 1,
+                "tid": span.thread,
+            })
+        })
+        .collect_vec();
+    let trace = serde_json::json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+    });
+    serde_json::to_string_pretty(&trace).expect("Chrome trace serialisation")
+}
+
+#[cfg(test)]
+fn span(phase: Phase, module: Option<&str>, start: u64, duration: u64) -> Span {
+    Span {
+        phase,
+        package: "app".to_string(),
+        module: module.map(String::from),
+        start: Duration::from_millis(start),
+        duration: Duration::from_millis(duration),
+        thread: 0,
+    }
+}
+
+#[test]
+fn timings_summary() {
+    let spans = vec![
+        span(Phase::Parse, Some("one"), 0, 2),
+        span(Phase::Parse, Some("two"), 0, 3),
+        span(Phase::Analyse, Some("one"), 3, 10),
+        span(Phase::Analyse, Some("two"), 13, 4),
+        span(Phase::Codegen(Target::Erlang), None, 17, 6),
+    ];
+    assert_eq!(
+        summary(&spans),
+        "Phase                          Time    Count
+analyse                     14.00ms        2
+codegen (erlang)             6.00ms        1
+parse                        5.00ms        2
+
+Slowest modules
+analyse                     10.00ms app/one
+analyse                      4.00ms app/two
+parse                        3.00ms app/two
+parse                        2.00ms app/one
+"
+    );
+}
+
+#[test]
+fn timings_chrome_trace() {
+    let spans = vec![span(Phase::Parse, Some("one"), 1, 2)];
+    let trace: serde_json::Value =
+        serde_json::from_str(&chrome_trace(&spans)).expect("Chrome trace JSON");
+    assert_eq!(
+        trace,
+        serde_json::json!({
+            "traceEvents": [{
+                "name": "app/one",
+                "cat": "parse",
+                "ph": "X",
+                "ts": 1000,
+                "dur": 2000,
+                "pid": 1,
+                "tid": 0,
+            }],
+            "displayTimeUnit": "ms",
+        })
+    );
+}
+
+#[test]
+fn disabled_timings_record_nothing() {
+    let timings = Timings::disabled();
+    assert_eq!(timings.time(Phase::Parse, "app", None, || 1), 1);
+    assert!(timings.spans().is_empty());
+
+    let timings = Timings::new();
+    assert_eq!(timings.time(Phase::Parse, "app", Some("one"), || 1), 1);
+    let spans = timings.spans();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(
+        spans.first().map(|span| span.module.as_deref()),
+        Some(Some("one"))
+    );
+}
+
+#[test]
+fn timings_report_to_telemetry() {
+    #[derive(Debug, Default)]
+    struct Phases(Mutex<Vec<String>>);
+
+    impl Telemetry for Phases {
+        fn waiting_for_build_directory_lock(&self) {}
+        fn resolving_package_versions(&self) {}
+        fn downloading_package(&self, _name: &str) {}
+        fn packages_downloaded(&self, _start: Instant, _count: usize) {}
+        fn compiling_package(&self, _name: &str) {}
+        fn checking_package(&self, _name: &str) {}
+        fn running_build_hook(&self, _package: &str, _hook: &str) {}
+        fn warning(&self, _warning: &crate::Warning) {}
+        fn phase_started(&self, phase: Phase, package: &str, module: Option<&str>) {
+            let mut phases = self.0.lock().expect("Phases lock");
+            phases.push(format!("started {} {} {:?}", phase, package, module));
+        }
+        fn phase_finished(&self, phase: Phase, package: &str, module: Option<&str>, _: Duration) {
+            let mut phases = self.0.lock().expect("Phases lock");
+            phases.push(format!("finished {} {} {:?}", phase, package, module));
+        }
+    }
+
+    let phases = Arc::new(Phases::default());
+    let timings = Timings::disabled().with_telemetry(phases.clone());
+    assert_eq!(timings.time(Phase::Analyse, "app", Some("one"), || 1), 1);
+    assert!(timings.spans().is_empty());
+    assert_eq!(
+        *phases.0.lock().expect("Phases lock"),
+        vec![
+            "started analyse app Some(\"one\")",
+            "finished analyse app Some(\"one\")",
+        ]
+    );
+}
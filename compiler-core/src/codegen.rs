@@ -1,14 +1,17 @@
 use crate::{
     build::Module,
     config::PackageConfig,
-    cplusplus, erlang,
+    cplusplus, elixir, erlang, go,
     io::{FileSystemWriter, Utf8Writer},
     javascript,
     line_numbers::LineNumbers,
-    Result,
+    llvm, Result,
 };
 use itertools::Itertools;
-use std::{fmt::Debug, path::Path};
+use std::{
+    fmt::Debug,
+    path::{Path, PathBuf},
+};
 
 /// A code generator that creates a .erl Erlang module and record header files
 /// for each Gleam module in the package.
@@ -16,13 +19,15 @@ use std::{fmt::Debug, path::Path};
 pub struct Erlang<'a> {
     build_directory: &'a Path,
     include_directory: &'a Path,
+    line_length: isize,
 }
 
 impl<'a> Erlang<'a> {
-    pub fn new(build_directory: &'a Path, include_directory: &'a Path) -> Self {
+    pub fn new(build_directory: &'a Path, include_directory: &'a Path, line_length: isize) -> Self {
         Self {
             build_directory,
             include_directory,
+            line_length,
         }
     }
 
@@ -31,43 +36,82 @@ impl<'a> Erlang<'a> {
         writer: Writer,
         modules: &[Module],
     ) -> Result<()> {
-        for module in modules {
+        let files = crate::parallel::map(modules, |module| {
             let erl_name = module.name.replace('/', "@");
-            self.erlang_module(&writer, module, &erl_name)?;
-            self.erlang_record_headers(&writer, module, &erl_name)?;
-        }
-        Ok(())
+            let mut files = vec![self.erlang_module(module, &erl_name)?];
+            files.extend(self.erlang_record_headers(module, &erl_name));
+            Ok(files)
+        });
+        write_generated_files(&writer, files)
     }
 
-    fn erlang_module<Writer: FileSystemWriter>(
-        &self,
-        writer: &Writer,
-        module: &Module,
-        erl_name: &str,
-    ) -> Result<()> {
+    fn erlang_module(&self, module: &Module, erl_name: &str) -> Result<GeneratedFile> {
         let name = format!("{}.erl", erl_name);
         let path = self.build_directory.join(&name);
-        let mut file = writer.writer(&path)?;
+        let mut text = String::new();
         let line_numbers = LineNumbers::new(&module.code);
-        let res = erlang::module(&module.ast, &line_numbers, &mut file);
+        erlang::module(&module.ast, &line_numbers, self.line_length, &mut text)?;
         tracing::debug!(name = ?name, "Generated Erlang module");
-        res
+        Ok((path, text))
+    }
+
+    fn erlang_record_headers(&self, module: &Module, erl_name: &str) -> Vec<GeneratedFile> {
+        erlang::records(&module.ast, self.line_length)
+            .into_iter()
+            .map(|(name, text)| {
+                let name = format!("{}_{}.hrl", erl_name, name);
+                tracing::debug!(name = ?name, "Generated Erlang header");
+                (self.include_directory.join(name), text)
+            })
+            .collect()
+    }
+}
+
+/// A code generator that creates a .ex Elixir module for each Gleam module in
+/// the package, along with the same record header files as for Erlang.
+#[derive(Debug)]
+pub struct Elixir<'a> {
+    build_directory: &'a Path,
+    include_directory: &'a Path,
+    line_length: isize,
+}
+
+impl<'a> Elixir<'a> {
+    pub fn new(build_directory: &'a Path, include_directory: &'a Path, line_length: isize) -> Self {
+        Self {
+            build_directory,
+            include_directory,
+            line_length,
+        }
     }
 
-    fn erlang_record_headers<Writer: FileSystemWriter>(
+    pub fn render<Writer: FileSystemWriter>(
         &self,
-        writer: &Writer,
-        module: &Module,
-        erl_name: &str,
+        writer: Writer,
+        modules: &[Module],
     ) -> Result<()> {
-        for (name, text) in erlang::records(&module.ast) {
-            let name = format!("{}_{}.hrl", erl_name, name);
-            tracing::debug!(name = ?name, "Generated Erlang header");
-            writer
-                .writer(&self.include_directory.join(name))?
-                .write(text.as_bytes())?;
-        }
-        Ok(())
+        let headers = Erlang::new(
+            self.build_directory,
+            self.include_directory,
+            self.line_length,
+        );
+        let files = crate::parallel::map(modules, |module| {
+            let erl_name = module.name.replace('/', "@");
+            let mut files = vec![self.elixir_module(module, &erl_name)?];
+            files.extend(headers.erlang_record_headers(module, &erl_name));
+            Ok(files)
+        });
+        write_generated_files(&writer, files)
+    }
+
+    fn elixir_module(&self, module: &Module, erl_name: &str) -> Result<GeneratedFile> {
+        let name = format!("{}.ex", erl_name);
+        let path = self.build_directory.join(&name);
+        let mut text = String::new();
+        let line_numbers = LineNumbers::new(&module.code);
+        elixir::module(&module.ast, &line_numbers, self.line_length, &mut text)?;
+        tracing::debug!(name = ?name, "Generated Elixir module");
+        Ok((path, text))
     }
 }
 
@@ -143,7 +187,9 @@ impl<'a> ErlangApp<'a> {
             version = config.version,
         );
 
-        writer.writer(&path)?.write(text.as_bytes())
+        let mut file = writer.writer(&path)?;
+        file.write(text.as_bytes())?;
+        file.close()
     }
 }
 
@@ -157,131 +203,285 @@ pub enum TypeScriptDeclarations {
 pub struct JavaScript<'a> {
     output_directory: &'a Path,
     typescript: TypeScriptDeclarations,
+    line_length: isize,
 }
 
 impl<'a> JavaScript<'a> {
-    pub fn new(output_directory: &'a Path, typescript: TypeScriptDeclarations) -> Self {
+    pub fn new(
+        output_directory: &'a Path,
+        typescript: TypeScriptDeclarations,
+        line_length: isize,
+    ) -> Self {
         Self {
             output_directory,
             typescript,
+            line_length,
         }
     }
 
     pub fn render(&self, writer: &impl FileSystemWriter, modules: &[Module]) -> Result<()> {
-        for module in modules {
+        let files = crate::parallel::map(modules, |module| {
             let js_name = module.name.clone();
+            let mut files = vec![];
             if self.typescript == TypeScriptDeclarations::Emit {
-                self.ts_declaration(writer, module, &js_name)?;
+                files.push(self.ts_declaration(module, &js_name)?);
             }
-            self.js_module(writer, module, &js_name)?
-        }
+            files.push(self.js_module(module, &js_name)?);
+            Ok(files)
+        });
+        write_generated_files(writer, files)?;
         self.write_prelude(writer)?;
         Ok(())
     }
 
     fn write_prelude(&self, writer: &impl FileSystemWriter) -> Result<()> {
-        writer
-            .writer(&self.output_directory.join("gleam.mjs"))?
-            .str_write(javascript::PRELUDE)?;
+        write_file(
+            writer,
+            &self.output_directory.join("gleam.mjs"),
+            javascript::PRELUDE,
+        )?;
         tracing::debug!("Generated JS prelude");
         if self.typescript == TypeScriptDeclarations::Emit {
-            writer
-                .writer(&self.output_directory.join("gleam.d.ts"))?
-                .str_write(javascript::PRELUDE_TS_DEF)?;
+            write_file(
+                writer,
+                &self.output_directory.join("gleam.d.ts"),
+                javascript::PRELUDE_TS_DEF,
+            )?;
             tracing::debug!("Generated TS prelude");
         }
         Ok(())
     }
 
-    fn ts_declaration(
-        &self,
-        writer: &impl FileSystemWriter,
-        module: &Module,
-        js_name: &str,
-    ) -> Result<()> {
+    fn ts_declaration(&self, module: &Module, js_name: &str) -> Result<GeneratedFile> {
         let name = format!("{}.d.ts", js_name);
         let path = self.output_directory.join(&name);
-        let mut file = writer.writer(&path)?;
-        let res =
-            javascript::ts_declaration(&module.ast, &module.input_path, &module.code, &mut file);
+        let mut text = String::new();
+        javascript::ts_declaration(
+            &module.ast,
+            &module.input_path,
+            &module.code,
+            self.line_length,
+            &mut text,
+        )?;
         tracing::debug!(name = ?js_name, "Generated TS declaration");
-        res
+        Ok((path, text))
     }
 
-    fn js_module(
-        &self,
-        writer: &impl FileSystemWriter,
-        module: &Module,
-        js_name: &str,
-    ) -> Result<()> {
+    fn js_module(&self, module: &Module, js_name: &str) -> Result<GeneratedFile> {
         let name = format!("{}.mjs", js_name);
         let path = self.output_directory.join(&name);
-        let mut file = writer.writer(&path)?;
+        let mut text = String::new();
         let line_numbers = LineNumbers::new(&module.code);
-        let res = javascript::module(
+        javascript::module(
             &module.ast,
             &line_numbers,
             &module.input_path,
             &module.code,
-            &mut file,
-        );
+            self.line_length,
+            &mut text,
+        )?;
         tracing::debug!(name = ?js_name, "Generated js module");
-        res
+        Ok((path, text))
     }
 }
 
 #[derive(Debug)]
 pub struct CPlusPlus<'a> {
     output_directory: &'a Path,
+    line_length: isize,
 }
 
 impl<'a> CPlusPlus<'a> {
-    pub fn new(output_directory: &'a Path) -> Self {
-        Self { output_directory }
+    pub fn new(output_directory: &'a Path, line_length: isize) -> Self {
+        Self {
+            output_directory,
+            line_length,
+        }
     }
 
     pub fn render(&self, writer: &impl FileSystemWriter, modules: &[Module]) -> Result<()> {
-        for module in modules {
+        let files = crate::parallel::map(modules, |module| {
             let mod_name = module.name.clone();
-            self.module_header(writer, module, &mod_name)?;
-            self.module_impl(writer, module, &mod_name)?;
-        }
+            Ok(vec![
+                self.module_header(module, &mod_name)?,
+                self.module_impl(module, &mod_name)?,
+            ])
+        });
+        write_generated_files(writer, files)?;
         self.write_prelude(writer)?;
         Ok(())
     }
 
-    fn module_header(
-        &self,
-        writer: &impl FileSystemWriter,
-        module: &Module,
-        mod_name: &String,
-    ) -> Result<()> {
+    fn module_header(&self, module: &Module, mod_name: &String) -> Result<GeneratedFile> {
         let name = format!("{}.h", mod_name);
         let path = self.output_directory.join(&name);
         let line_numbers = LineNumbers::new(&module.code);
-        let mut file = writer.writer(&path)?;
-        cplusplus::module_header(&module.ast, &line_numbers, &path, &module.code, &mut file)
+        let mut text = String::new();
+        cplusplus::module_header(
+            &module.ast,
+            &line_numbers,
+            &path,
+            &module.code,
+            self.line_length,
+            &mut text,
+        )?;
+        Ok((path, text))
     }
 
-    fn module_impl(
-        &self,
-        writer: &impl FileSystemWriter,
-        module: &Module,
-        mod_name: &String,
-    ) -> Result<()> {
+    fn module_impl(&self, module: &Module, mod_name: &String) -> Result<GeneratedFile> {
         let name = format!("{}.cc", mod_name);
         let path = self.output_directory.join(&name);
         let line_numbers = LineNumbers::new(&module.code);
-        let mut file = writer.writer(&path)?;
-        cplusplus::module_impl(&module.ast, &line_numbers, &path, &module.code, &mut file)
+        let mut text = String::new();
+        cplusplus::module_impl(
+            &module.ast,
+            &line_numbers,
+            &path,
+            &module.code,
+            self.line_length,
+            &mut text,
+        )?;
+        Ok((path, text))
     }
 
     fn write_prelude(&self, writer: &impl FileSystemWriter) -> Result<()> {
-        writer
-            .writer(&self.output_directory.join("gleam.h"))?
-            .str_write(cplusplus::PRELUDE_HEADER)?;
-        writer
-            .writer(&self.output_directory.join("gleam.cc"))?
-            .str_write(cplusplus::PRELUDE_IMPL)
+        let directory = self.output_directory;
+        write_file(
+            writer,
+            &directory.join("gleam.h"),
+            cplusplus::PRELUDE_HEADER,
+        )?;
+        write_file(writer, &directory.join("gleam.cc"), cplusplus::PRELUDE_IMPL)?;
+        // clang-format wraps lines at the same width the code was generated at
+        let clang_format = format!(
+            "{}ColumnLimit: {}\n",
+            cplusplus::CLANG_FORMAT_CONFIG,
+            self.line_length
+        );
+        write_file(writer, &directory.join(".clang-format"), &clang_format)
+    }
+}
+
+/// A code generator that creates a Go package for each Gleam module. The
+/// packages of every Gleam package being built share a single Go module,
+/// rooted at the output directory.
+#[derive(Debug)]
+pub struct Go<'a> {
+    output_directory: &'a Path,
+    line_length: isize,
+}
+
+impl<'a> Go<'a> {
+    pub fn new(output_directory: &'a Path, line_length: isize) -> Self {
+        Self {
+            output_directory,
+            line_length,
+        }
+    }
+
+    pub fn render(&self, writer: &impl FileSystemWriter, modules: &[Module]) -> Result<()> {
+        let files = crate::parallel::map(modules, |module| Ok(vec![self.go_package(module)?]));
+        write_generated_files(writer, files)?;
+        self.write_prelude(writer)?;
+        Ok(())
+    }
+
+    fn go_package(&self, module: &Module) -> Result<GeneratedFile> {
+        let path = self.output_directory.join(go::module_path(&module.name));
+        let line_numbers = LineNumbers::new(&module.code);
+        let mut text = String::new();
+        go::module(
+            &module.ast,
+            &line_numbers,
+            &module.input_path,
+            &module.code,
+            self.line_length,
+            &mut text,
+        )?;
+        tracing::debug!(name = ?module.name, "Generated Go package");
+        Ok((path, text))
+    }
+
+    fn write_prelude(&self, writer: &impl FileSystemWriter) -> Result<()> {
+        let directory = self.output_directory;
+        write_file(writer, &directory.join("go.mod"), go::GO_MOD)?;
+        write_file(writer, &directory.join(go::PRELUDE_PATH), go::PRELUDE)?;
+        tracing::debug!("Generated Go prelude");
+        Ok(())
+    }
+}
+
+/// A code generator that creates an LLVM IR file for each Gleam module, which
+/// are linked together with the prelude written to the prelude directory.
+#[derive(Debug)]
+pub struct Llvm<'a> {
+    output_directory: &'a Path,
+    prelude_directory: &'a Path,
+    pointer_width: llvm::PointerWidth,
+}
+
+impl<'a> Llvm<'a> {
+    pub fn new(
+        output_directory: &'a Path,
+        prelude_directory: &'a Path,
+        pointer_width: llvm::PointerWidth,
+    ) -> Self {
+        Self {
+            output_directory,
+            prelude_directory,
+            pointer_width,
+        }
     }
+
+    pub fn render(&self, writer: &impl FileSystemWriter, modules: &[Module]) -> Result<()> {
+        let files = crate::parallel::map(modules, |module| Ok(vec![self.llvm_module(module)?]));
+        write_generated_files(writer, files)?;
+        self.write_prelude(writer)?;
+        Ok(())
+    }
+
+    fn llvm_module(&self, module: &Module) -> Result<GeneratedFile> {
+        let path = self.output_directory.join(llvm::module_path(&module.name));
+        let mut text = String::new();
+        llvm::module(
+            &module.ast,
+            self.pointer_width,
+            &module.input_path,
+            &module.code,
+            &mut text,
+        )?;
+        tracing::debug!(name = ?module.name, "Generated LLVM IR module");
+        Ok((path, text))
+    }
+
+    fn write_prelude(&self, writer: &impl FileSystemWriter) -> Result<()> {
+        let path = self.prelude_directory.join(llvm::PRELUDE_PATH);
+        write_file(writer, &path, &llvm::prelude(self.pointer_width))?;
+        tracing::debug!("Generated LLVM prelude");
+        Ok(())
+    }
+}
+
+/// The path and contents of a file generated for a module.
+type GeneratedFile = (PathBuf, String);
+
+/// Write the files generated for each module, stopping at the first module
+/// that could not be generated. Modules are generated in parallel but their
+/// files are written one at a time, in the order of the modules.
+fn write_generated_files(
+    writer: &impl FileSystemWriter,
+    modules: Vec<Result<Vec<GeneratedFile>>>,
+) -> Result<()> {
+    for files in modules {
+        for (path, text) in files? {
+            write_file(writer, &path, &text)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_file(writer: &impl FileSystemWriter, path: &Path, text: &str) -> Result<()> {
+    let mut file = writer.writer(path)?;
+    file.str_write(text)?;
+    file.close()
 }
@@ -0,0 +1,309 @@
+//! Compiling Gleam source code held in memory, for programs that embed the
+//! compiler, such as build tools of other languages and the playground.
+//!
+//! ```ignore
+//! let compiled = Compiler::new(config)
+//!     .add_source("app", "pub fn main() { 1 }")
+//!     .target(Target::JavaScript)
+//!     .compile()?;
+//! ```
+//!
+//! No files are read or written, and no programs are run: the Erlang code is
+//! not compiled to BEAM bytecode and the C++ code is not formatted.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    build::{
+        package_compiler::Source, Mode, Module, Origin, PackageCompiler, Target,
+        TargetCodegenConfiguration,
+    },
+    config::PackageConfig,
+    diagnostic::Diagnostic,
+    io::{memory::InMemoryFileSystem, FileSystemReader, OutputFile},
+    paths, type_,
+    uid::UniqueIdGenerator,
+    Result, Warning,
+};
+
+/// Compiles the modules of a package, and of the packages it depends on,
+/// from source code given to it rather than read from a project.
+#[derive(Debug)]
+pub struct Compiler {
+    config: PackageConfig,
+    sources: Vec<Source>,
+    dependencies: Vec<(PackageConfig, Vec<Source>)>,
+    targets: Vec<Target>,
+    perform_codegen: bool,
+}
+
+/// The result of compiling a package.
+#[derive(Debug)]
+pub struct Compiled {
+    /// The modules of the package, as type checked for the first target.
+    pub modules: Vec<Module>,
+    /// The warnings for the modules of the package, for any target.
+    pub warnings: Vec<Warning>,
+    /// The code generated for the modules of the package and its
+    /// dependencies for each target, with paths relative to the directory
+    /// the packages are built in, such as `my_app/my_app.mjs`.
+    pub code: HashMap<Target, Vec<OutputFile>>,
+}
+
+impl Compiler {
+    /// A compiler for the package described by the configuration, which
+    /// generates code for the target of the package unless others are given.
+    pub fn new(config: PackageConfig) -> Self {
+        Self {
+            config,
+            sources: vec![],
+            dependencies: vec![],
+            targets: vec![],
+            perform_codegen: true,
+        }
+    }
+
+    /// Adds a module of the package, such as `my_app/parser`.
+    pub fn add_source(mut self, module: &str, code: &str) -> Self {
+        self.sources.push(source(module, code));
+        self
+    }
+
+    /// Adds a module of a dependency of the package. Dependencies are
+    /// compiled in the order they are first added, so a dependency must be
+    /// added after those it imports.
+    pub fn add_dependency_source(mut self, package: &str, module: &str, code: &str) -> Self {
+        let source = source(module, code);
+        match self
+            .dependencies
+            .iter_mut()
+            .find(|(config, _)| config.name == package)
+        {
+            Some((_, sources)) => sources.push(source),
+            None => {
+                let config = PackageConfig {
+                    name: package.to_string(),
+                    ..Default::default()
+                };
+                self.dependencies.push((config, vec![source]));
+            }
+        }
+        self
+    }
+
+    /// Adds a target to generate code for.
+    pub fn target(mut self, target: Target) -> Self {
+        if !self.targets.contains(&target) {
+            self.targets.push(target);
+        }
+        self
+    }
+
+    /// Type checks the modules for each target without generating code, as
+    /// editors do to report errors and warnings.
+    pub fn check(mut self) -> Result<Compiled> {
+        self.perform_codegen = false;
+        self.compile()
+    }
+
+    /// Type checks the modules and generates code for each target. The first
+    /// error found in the package or its dependencies is returned.
+    pub fn compile(self) -> Result<Compiled> {
+        let targets = if self.targets.is_empty() {
+            vec![self.config.target]
+        } else {
+            self.targets.clone()
+        };
+
+        let mut compiled = Compiled {
+            modules: vec![],
+            warnings: vec![],
+            code: HashMap::new(),
+        };
+        for (index, target) in targets.into_iter().enumerate() {
+            let io = InMemoryFileSystem::new();
+            let ids = UniqueIdGenerator::new();
+            let mut importable_modules = im::HashMap::new();
+            let mut defined_modules = im::HashMap::new();
+            let mut warnings = vec![];
+
+            for (config, sources) in &self.dependencies {
+                let _ = compile_package(
+                    config,
+                    sources.clone(),
+                    target,
+                    self.perform_codegen,
+                    &io,
+                    ids.clone(),
+                    &mut vec![],
+                    &mut importable_modules,
+                    &mut defined_modules,
+                )?;
+            }
+            let modules = compile_package(
+                &self.config,
+                self.sources.clone(),
+                target,
+                self.perform_codegen,
+                &io,
+                ids,
+                &mut warnings,
+                &mut importable_modules,
+                &mut defined_modules,
+            )?;
+
+            if index == 0 {
+                compiled.modules = modules;
+            }
+            for warning in warnings {
+                if !compiled.warnings.contains(&warning) {
+                    compiled.warnings.push(warning);
+                }
+            }
+            if self.perform_codegen {
+                let lib = paths::build_packages(Mode::Dev, target);
+                let _ = compiled.code.insert(target, generated_code(&io, &lib)?);
+            }
+        }
+        Ok(compiled)
+    }
+}
+
+impl Compiled {
+    /// The warnings as diagnostics, to be shown the way the compiler shows
+    /// them.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.warnings.iter().map(Warning::to_diagnostic).collect()
+    }
+}
+
+fn source(module: &str, code: &str) -> Source {
+    Source {
+        path: PathBuf::from(format!("src/{}.gleam", module)),
+        name: module.to_string(),
+        code: code.to_string(),
+        origin: Origin::Src,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compile_package(
+    config: &PackageConfig,
+    sources: Vec<Source>,
+    target: Target,
+    perform_codegen: bool,
+    io: &InMemoryFileSystem,
+    ids: UniqueIdGenerator,
+    warnings: &mut Vec<Warning>,
+    importable_modules: &mut im::HashMap<String, type_::Module>,
+    defined_modules: &mut im::HashMap<String, PathBuf>,
+) -> Result<Vec<Module>> {
+    let codegen = match target {
+        Target::Erlang => TargetCodegenConfiguration::Erlang { app_file: None },
+        Target::JavaScript => TargetCodegenConfiguration::JavaScript {
+            emit_typescript_definitions: config.javascript.typescript_declarations,
+        },
+        Target::Native => TargetCodegenConfiguration::CPlusPlus,
+        Target::Go => TargetCodegenConfiguration::Go,
+        Target::Llvm => TargetCodegenConfiguration::Llvm,
+        Target::Wasm => TargetCodegenConfiguration::Wasm,
+    };
+    let root = PathBuf::from("/");
+    let out = paths::build_package(Mode::Dev, target, &config.name);
+    let lib = paths::build_packages(Mode::Dev, target);
+    let mut compiler =
+        PackageCompiler::new(config, &root, &out, &lib, &codegen, ids, io.clone(), None);
+    compiler.perform_codegen = perform_codegen;
+    compiler.write_metadata = false;
+    compiler.copy_native_files = false;
+    compiler.compile_beam_bytecode = false;
+    compiler.sources = sources;
+    compiler.compile(warnings, importable_modules, defined_modules)
+}
+
+// The files written to the build directories of the packages, sorted by path
+fn generated_code(io: &InMemoryFileSystem, out: &Path) -> Result<Vec<OutputFile>> {
+    let mut files = vec![];
+    for entry in io.read_dir(out)?.into_iter().flatten() {
+        let path = entry.pathbuf;
+        let text = io.read(&path)?;
+        let path = path.strip_prefix(out).unwrap_or(&path).to_path_buf();
+        files.push(OutputFile { path, text });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+#[test]
+fn compile_test() {
+    let compiled = Compiler::new(PackageConfig {
+        name: "app".into(),
+        ..Default::default()
+    })
+    .add_dependency_source("lib", "lib/maths", "pub fn double(x) { x * 2 }")
+    .add_source("app", "import lib/maths\npub fn main() { maths.double(2) }")
+    .add_source("app/unused", "pub fn wobble() { let x = 1 2 }")
+    .target(Target::Erlang)
+    .target(Target::JavaScript)
+    .compile()
+    .expect("compile");
+
+    assert_eq!(
+        compiled
+            .modules
+            .iter()
+            .map(|module| module.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["app", "app/unused"]
+    );
+    assert_eq!(compiled.warnings.len(), 1);
+    let paths = |target| {
+        compiled
+            .code
+            .get(&target)
+            .expect("target code")
+            .iter()
+            .map(|file| file.path.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        paths(Target::Erlang),
+        vec![
+            "app/_gleam_artefacts/app.erl",
+            "app/_gleam_artefacts/app@unused.erl",
+            "lib/_gleam_artefacts/lib@maths.erl",
+        ]
+    );
+    assert_eq!(
+        paths(Target::JavaScript),
+        vec![
+            "app/app/unused.mjs",
+            "app/app.mjs",
+            "app/gleam.mjs",
+            "lib/gleam.mjs",
+            "lib/lib/maths.mjs",
+        ]
+    );
+}
+
+#[test]
+fn check_test() {
+    let checked = Compiler::new(PackageConfig::default())
+        .add_source("app", "pub fn main() { let x = 1 2 }")
+        .check()
+        .expect("check");
+    assert_eq!(checked.modules.len(), 1);
+    assert_eq!(checked.warnings.len(), 1);
+    assert!(checked.code.is_empty());
+}
+
+#[test]
+fn compile_error_test() {
+    let result = Compiler::new(PackageConfig::default())
+        .add_source("app", "pub fn main() { 1 + \"1\" }")
+        .compile();
+    assert!(matches!(result, Err(crate::Error::Type { .. })));
+}
@@ -1,9 +1,13 @@
 use crate::error::{FileIoAction, FileKind};
+use crate::format;
 use crate::io::FileSystemReader;
+use crate::lint::LintConfig;
 use crate::manifest::Manifest;
+use crate::type_::WarningKind;
 use crate::{Error, Result};
 use hexpm::version::{Range, Version};
 use http::Uri;
+use itertools::Itertools;
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -21,7 +25,176 @@ fn erlang_target() -> Target {
     Target::Erlang
 }
 
-pub type Dependencies = HashMap<String, Range>;
+/// Git dependencies without a `ref` use the default branch of the repository.
+fn default_git_reference() -> String {
+    "HEAD".into()
+}
+
+fn default_line_length() -> usize {
+    crate::pretty::DEFAULT_LINE_LENGTH as usize
+}
+
+pub type Dependencies = HashMap<String, Requirement>;
+
+/// Where a dependency comes from, as given in the `dependencies` and
+/// `dev-dependencies` sections of gleam.toml.
+///
+/// ```toml
+/// [dependencies]
+/// gleam_stdlib = "~> 0.25"
+/// gleam_http = { version = "~> 3.1" }
+/// acme_auth = { version = "~> 1.0", registry = "acme" }
+/// my_lib = { path = "../my_lib" }
+/// gleam_json = { git = "https://github.com/gleam-lang/json.git", ref = "v0.5.0" }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Requirement {
+    /// A package published to Hex, with the versions that may be used. The
+    /// registry is one of those given in the `registries` section of
+    /// gleam.toml, if it is not the default registry of the package.
+    Hex {
+        version: Range,
+        registry: Option<String>,
+    },
+    /// A local package, which is compiled in place from its directory.
+    Path { path: PathBuf },
+    /// A package in a git repository, at a branch, tag, or commit.
+    Git { repo: String, reference: String },
+}
+
+impl Requirement {
+    pub fn hex(version: &str) -> Requirement {
+        Requirement::Hex {
+            version: Range::new(version.to_string()),
+            registry: None,
+        }
+    }
+
+    pub fn path(path: &str) -> Requirement {
+        Requirement::Path {
+            path: PathBuf::from(path),
+        }
+    }
+
+    pub fn git(repo: &str, reference: &str) -> Requirement {
+        Requirement::Git {
+            repo: repo.to_string(),
+            reference: reference.to_string(),
+        }
+    }
+
+    pub fn hex_version(&self) -> Option<&Range> {
+        match self {
+            Requirement::Hex { version, .. } => Some(version),
+            Requirement::Path { .. } | Requirement::Git { .. } => None,
+        }
+    }
+
+    /// The registry given for the package, if it is to be resolved from a
+    /// registry other than the default one.
+    pub fn registry(&self) -> Option<&str> {
+        match self {
+            Requirement::Hex { registry, .. } => registry.as_deref(),
+            Requirement::Path { .. } | Requirement::Git { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Requirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Requirement::Hex {
+                version,
+                registry: None,
+            } => write!(f, "{}", version),
+            Requirement::Hex {
+                version,
+                registry: Some(registry),
+            } => write!(
+                f,
+                "{{ version = \"{}\", registry = \"{}\" }}",
+                version, registry
+            ),
+            Requirement::Path { path } => write!(f, "{{ path = \"{}\" }}", path.display()),
+            Requirement::Git { repo, reference } => {
+                write!(f, "{{ git = \"{}\", ref = \"{}\" }}", repo, reference)
+            }
+        }
+    }
+}
+
+impl serde::Serialize for Requirement {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+        match self {
+            Requirement::Hex {
+                version,
+                registry: None,
+            } => version.serialize(serializer),
+            Requirement::Hex {
+                version,
+                registry: Some(registry),
+            } => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("version", version)?;
+                map.serialize_entry("registry", registry)?;
+                map.end()
+            }
+            Requirement::Path { path } => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("path", path)?;
+                map.end()
+            }
+            Requirement::Git { repo, reference } => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("git", repo)?;
+                map.serialize_entry("ref", reference)?;
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Requirement {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged, deny_unknown_fields)]
+        enum Toml {
+            Version(Range),
+            Hex {
+                version: Range,
+                #[serde(default)]
+                registry: Option<String>,
+            },
+            Path {
+                path: PathBuf,
+            },
+            Git {
+                git: String,
+                #[serde(rename = "ref", default = "default_git_reference")]
+                reference: String,
+            },
+        }
+        match Toml::deserialize(deserializer)? {
+            Toml::Version(version) => Ok(Requirement::Hex {
+                version,
+                registry: None,
+            }),
+            Toml::Hex { version, registry } => Ok(Requirement::Hex { version, registry }),
+            Toml::Path { path } => Ok(Requirement::Path { path }),
+            Toml::Git { git, reference } => Ok(Requirement::Git {
+                repo: git,
+                reference,
+            }),
+        }
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SpdxLicense {
@@ -40,11 +213,16 @@ impl<'de> Deserialize<'de> for SpdxLicense {
         D: serde::Deserializer<'de>,
     {
         let s: &str = serde::de::Deserialize::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl std::str::FromStr for SpdxLicense {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match spdx::license_id(s) {
-            None => Err(serde::de::Error::custom(format!(
-                "{} is not a valid SPDX License ID",
-                s
-            ))),
+            None => Err(format!("{} is not a valid SPDX License ID", s)),
             Some(_) => Ok(SpdxLicense {
                 licence: String::from(s),
             }),
@@ -82,8 +260,48 @@ pub struct PackageConfig {
     pub erlang: ErlangConfig,
     #[serde(default)]
     pub javascript: JavaScriptConfig,
+    #[serde(default)]
+    pub native: NativeConfig,
     #[serde(default = "erlang_target")]
     pub target: Target,
+    /// The targets that `gleam build` compiles the package for, all in one
+    /// build, when no target is given on the command line. When there are
+    /// none only the default target is built.
+    #[serde(default)]
+    pub targets: Vec<Target>,
+    #[serde(default, rename = "dependency-licences", alias = "dependency-licenses")]
+    pub dependency_licences: LicencePolicy,
+    #[serde(default)]
+    pub warnings: WarningLevels,
+    #[serde(default)]
+    pub lints: LintConfig,
+    /// The width in columns that `gleam format` and the code generators fit
+    /// code within.
+    #[serde(default = "default_line_length")]
+    pub line_length: usize,
+    /// Whether `gleam format` sorts and groups imports.
+    #[serde(default)]
+    pub sort_imports: bool,
+    #[serde(default)]
+    pub workspace: WorkspaceConfig,
+    /// The registry that the dependencies are resolved from, and that the
+    /// package is published to, when it is not hex.pm.
+    #[serde(default)]
+    pub registry: Option<String>,
+    #[serde(default)]
+    pub registries: HashMap<String, RegistryConfig>,
+    #[serde(default)]
+    pub build: BuildConfig,
+    #[serde(default)]
+    pub publish: PublishConfig,
+    /// The feature flags of the package, each with whether it is enabled
+    /// by default. They are queried in code with `@cfg(flag)`.
+    #[serde(default)]
+    pub features: HashMap<String, bool>,
+    /// The feature flags of the dependencies of the package that are set
+    /// when it is the root package of a build, by dependency name.
+    #[serde(default, rename = "dependency-features")]
+    pub dependency_features: HashMap<String, HashMap<String, bool>>,
 }
 
 impl PackageConfig {
@@ -94,6 +312,56 @@ impl PackageConfig {
         }
     }
 
+    /// How `gleam format` lays out the code of this package.
+    pub fn format_options(&self) -> format::Options {
+        format::Options {
+            line_length: self.line_length as isize,
+            sort_imports: self.sort_imports,
+            package: Some(self.name.clone()),
+            verify: false,
+        }
+    }
+
+    /// The values of the feature flags of the given package when it is
+    /// compiled as a dependency of this one: its defaults overridden by the
+    /// `dependency-features` of this package.
+    pub fn features_of(&self, package: &PackageConfig) -> Result<HashMap<String, bool>> {
+        let mut features = package.features.clone();
+        let overrides = self.dependency_features.get(&package.name);
+        for (flag, enabled) in overrides.into_iter().flatten() {
+            match features.get_mut(flag) {
+                Some(value) => *value = *enabled,
+                None => {
+                    return Err(Error::UnknownDependencyFeature {
+                        package: package.name.clone(),
+                        flag: flag.clone(),
+                    })
+                }
+            }
+        }
+        Ok(features)
+    }
+
+    /// The dependencies of the package that are resolved from Hex, with the
+    /// versions of them that may be used.
+    pub fn hex_dependencies_for(&self, mode: Mode) -> Result<HashMap<String, Range>> {
+        Ok(hex_versions(self.dependencies_for(mode)?))
+    }
+
+    /// The local packages this package depends upon, with their directories
+    /// relative to the directory of this package.
+    pub fn path_dependencies_for(&self, mode: Mode) -> Result<Vec<(String, PathBuf)>> {
+        Ok(self
+            .dependencies_for(mode)?
+            .into_iter()
+            .filter_map(|(name, requirement)| match requirement {
+                Requirement::Path { path } => Some((name, path)),
+                Requirement::Hex { .. } | Requirement::Git { .. } => None,
+            })
+            .sorted()
+            .collect())
+    }
+
     pub fn all_dependencies(&self) -> Result<Dependencies> {
         let mut deps =
             HashMap::with_capacity(self.dependencies.len() + self.dev_dependencies.len());
@@ -106,6 +374,61 @@ impl PackageConfig {
         Ok(deps)
     }
 
+    /// The dependencies of this package and of the given local packages, such
+    /// as the members of its workspace, which are resolved together so that
+    /// every package of the project uses the same versions. The local packages
+    /// themselves are compiled in place rather than resolved, so they are left
+    /// out.
+    pub fn with_local_dependencies(&self, members: &[PackageConfig]) -> Result<PackageConfig> {
+        let _ = self.all_dependencies()?;
+        let is_member = |name: &str| members.iter().any(|member| member.name == name);
+        let mut config = self.clone();
+        config.dependencies.retain(|name, _| !is_member(name));
+        config.dev_dependencies.retain(|name, _| !is_member(name));
+        for member in members {
+            let member_dependencies = member
+                .dependencies
+                .iter()
+                .map(|dependency| (dependency, false))
+                .chain(
+                    member
+                        .dev_dependencies
+                        .iter()
+                        .map(|dependency| (dependency, true)),
+                );
+            for ((name, requirement), dev) in member_dependencies {
+                if is_member(name) {
+                    continue;
+                }
+                let existing = config
+                    .dependencies
+                    .get(name)
+                    .or_else(|| config.dev_dependencies.get(name));
+                match existing {
+                    Some(existing) if existing != requirement => {
+                        return Err(Error::WorkspaceDependencyMismatch {
+                            package: name.clone(),
+                            requirements: vec![existing.to_string(), requirement.to_string()],
+                        });
+                    }
+                    // A package that is a dependency of one member and a dev
+                    // dependency of another is a dependency of the workspace.
+                    Some(_) if dev => (),
+                    _ => {
+                        let _ = config.dev_dependencies.remove(name);
+                        let dependencies = if dev {
+                            &mut config.dev_dependencies
+                        } else {
+                            &mut config.dependencies
+                        };
+                        let _ = dependencies.insert(name.clone(), requirement.clone());
+                    }
+                }
+            }
+        }
+        Ok(config)
+    }
+
     pub fn read<FS: FileSystemReader, P: AsRef<Path>>(
         path: P,
         fs: &FS,
@@ -140,6 +463,16 @@ impl PackageConfig {
     }
 }
 
+fn hex_versions(dependencies: Dependencies) -> HashMap<String, Range> {
+    dependencies
+        .into_iter()
+        .filter_map(|(name, requirement)| match requirement {
+            Requirement::Hex { version, .. } => Some((name, version)),
+            Requirement::Path { .. } | Requirement::Git { .. } => None,
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 struct StalePackageRemover<'a> {
     // These are the packages for which the requirement or their parents
@@ -150,7 +483,7 @@ struct StalePackageRemover<'a> {
 
 impl<'a> StalePackageRemover<'a> {
     pub fn fresh_and_locked(
-        requirements: &'a HashMap<String, Range>,
+        requirements: &'a Dependencies,
         manifest: &'a Manifest,
     ) -> HashMap<String, Version> {
         let locked = manifest
@@ -167,7 +500,7 @@ impl<'a> StalePackageRemover<'a> {
 
     fn run(
         &mut self,
-        requirements: &'a HashMap<String, Range>,
+        requirements: &'a Dependencies,
         manifest: &'a Manifest,
     ) -> HashMap<String, Version> {
         // Record all the requirements that have not changed
@@ -215,13 +548,13 @@ impl<'a> StalePackageRemover<'a> {
 fn locked_no_manifest() {
     let mut config = PackageConfig::default();
     config.dependencies = [
-        ("prod1".into(), Range::new("~> 1.0".into())),
-        ("prod2".into(), Range::new("~> 2.0".into())),
+        ("prod1".into(), Requirement::hex("~> 1.0")),
+        ("prod2".into(), Requirement::hex("~> 2.0")),
     ]
     .into();
     config.dev_dependencies = [
-        ("dev1".into(), Range::new("~> 1.0".into())),
-        ("dev2".into(), Range::new("~> 2.0".into())),
+        ("dev1".into(), Requirement::hex("~> 1.0")),
+        ("dev2".into(), Requirement::hex("~> 2.0")),
     ]
     .into();
     assert_eq!(config.locked(None).unwrap(), [].into());
@@ -231,13 +564,13 @@ fn locked_no_manifest() {
 fn locked_no_changes() {
     let mut config = PackageConfig::default();
     config.dependencies = [
-        ("prod1".into(), Range::new("~> 1.0".into())),
-        ("prod2".into(), Range::new("~> 2.0".into())),
+        ("prod1".into(), Requirement::hex("~> 1.0")),
+        ("prod2".into(), Requirement::hex("~> 2.0")),
     ]
     .into();
     config.dev_dependencies = [
-        ("dev1".into(), Range::new("~> 1.0".into())),
-        ("dev2".into(), Range::new("~> 2.0".into())),
+        ("dev1".into(), Requirement::hex("~> 1.0")),
+        ("dev2".into(), Requirement::hex("~> 2.0")),
     ]
     .into();
     let manifest = Manifest {
@@ -264,8 +597,8 @@ fn locked_no_changes() {
 #[test]
 fn locked_some_removed() {
     let mut config = PackageConfig::default();
-    config.dependencies = [("prod1".into(), Range::new("~> 1.0".into()))].into();
-    config.dev_dependencies = [("dev2".into(), Range::new("~> 2.0".into()))].into();
+    config.dependencies = [("prod1".into(), Requirement::hex("~> 1.0"))].into();
+    config.dev_dependencies = [("dev2".into(), Requirement::hex("~> 2.0"))].into();
     let manifest = Manifest {
         requirements: config.all_dependencies().unwrap(),
         packages: vec![
@@ -291,21 +624,21 @@ fn locked_some_removed() {
 fn locked_some_changed() {
     let mut config = PackageConfig::default();
     config.dependencies = [
-        ("prod1".into(), Range::new("~> 3.0".into())), // Does not match manifest
-        ("prod2".into(), Range::new("~> 2.0".into())),
+        ("prod1".into(), Requirement::hex("~> 3.0")), // Does not match manifest
+        ("prod2".into(), Requirement::hex("~> 2.0")),
     ]
     .into();
     config.dev_dependencies = [
-        ("dev1".into(), Range::new("~> 3.0".into())), // Does not match manifest
-        ("dev2".into(), Range::new("~> 2.0".into())),
+        ("dev1".into(), Requirement::hex("~> 3.0")), // Does not match manifest
+        ("dev2".into(), Requirement::hex("~> 2.0")),
     ]
     .into();
     let manifest = Manifest {
         requirements: [
-            ("prod1".into(), Range::new("~> 1.0".into())),
-            ("prod2".into(), Range::new("~> 2.0".into())),
-            ("dev1".into(), Range::new("~> 1.0".into())),
-            ("dev2".into(), Range::new("~> 2.0".into())),
+            ("prod1".into(), Requirement::hex("~> 1.0")),
+            ("prod2".into(), Requirement::hex("~> 2.0")),
+            ("dev1".into(), Requirement::hex("~> 1.0")),
+            ("dev2".into(), Requirement::hex("~> 2.0")),
         ]
         .into(),
         packages: vec![
@@ -331,15 +664,15 @@ fn locked_some_changed() {
 fn locked_nested_are_removed_too() {
     let mut config = PackageConfig::default();
     config.dependencies = [
-        ("1".into(), Range::new("~> 2.0".into())), // Does not match manifest
-        ("2".into(), Range::new("~> 1.0".into())),
+        ("1".into(), Requirement::hex("~> 2.0")), // Does not match manifest
+        ("2".into(), Requirement::hex("~> 1.0")),
     ]
     .into();
     config.dev_dependencies = [].into();
     let manifest = Manifest {
         requirements: [
-            ("1".into(), Range::new("~> 1.0".into())),
-            ("2".into(), Range::new("~> 1.0".into())),
+            ("1".into(), Requirement::hex("~> 1.0")),
+            ("2".into(), Requirement::hex("~> 1.0")),
         ]
         .into(),
         packages: vec![
@@ -377,6 +710,46 @@ fn locked_nested_are_removed_too() {
     );
 }
 
+#[test]
+fn workspace_dependencies_are_merged() {
+    let member = |name: &str, dependencies: &[(&str, &str)], dev: &[(&str, &str)]| {
+        let to_map = |dependencies: &[(&str, &str)]| {
+            dependencies
+                .iter()
+                .map(|(name, range)| (name.to_string(), Requirement::hex(range)))
+                .collect()
+        };
+        PackageConfig {
+            name: name.into(),
+            dependencies: to_map(dependencies),
+            dev_dependencies: to_map(dev),
+            ..Default::default()
+        }
+    };
+    let root = member("root", &[("core", "~> 1.0")], &[("gleeunit", "~> 0.6")]);
+    let core = member("core", &[("stdlib", "~> 0.25")], &[("gleeunit", "~> 0.6")]);
+    let web = member("web", &[("core", "~> 1.0"), ("gleeunit", "~> 0.6")], &[]);
+    let config = root.with_local_dependencies(&[core.clone(), web]).unwrap();
+    assert_eq!(
+        config.dependencies,
+        [
+            ("stdlib".into(), Requirement::hex("~> 0.25")),
+            ("gleeunit".into(), Requirement::hex("~> 0.6")),
+        ]
+        .into()
+    );
+    assert_eq!(config.dev_dependencies, [].into());
+
+    let other = member("other", &[("stdlib", "~> 0.26")], &[]);
+    assert_eq!(
+        root.with_local_dependencies(&[core, other]),
+        Err(Error::WorkspaceDependencyMismatch {
+            package: "stdlib".into(),
+            requirements: vec!["~> 0.25".into(), "~> 0.26".into()],
+        })
+    );
+}
+
 #[cfg(test)]
 fn manifest_package(
     name: &'static str,
@@ -393,6 +766,7 @@ fn manifest_package(
         requirements: requirements.iter().map(|e| (*e).to_string()).collect(),
         source: crate::manifest::ManifestPackageSource::Hex {
             outer_checksum: Base16Checksum(vec![]),
+            registry: None,
         },
     }
 }
@@ -412,11 +786,25 @@ impl Default for PackageConfig {
             dependencies: Default::default(),
             erlang: Default::default(),
             javascript: Default::default(),
+            native: Default::default(),
             repository: Default::default(),
             dev_dependencies: Default::default(),
             licences: Default::default(),
             links: Default::default(),
             target: Target::Erlang,
+            targets: Default::default(),
+            dependency_licences: Default::default(),
+            warnings: Default::default(),
+            lints: Default::default(),
+            line_length: default_line_length(),
+            sort_imports: false,
+            workspace: Default::default(),
+            registry: None,
+            registries: Default::default(),
+            build: Default::default(),
+            publish: Default::default(),
+            features: Default::default(),
+            dependency_features: Default::default(),
         }
     }
 }
@@ -427,12 +815,283 @@ pub struct ErlangConfig {
     pub application_start_module: Option<String>,
     #[serde(default)]
     pub extra_applications: Vec<String>,
+    /// The language the modules of the package are generated in.
+    #[serde(default)]
+    pub source: ErlangSource,
 }
 
+/// The language that code is generated in for the Erlang target. Both are
+/// compiled to BEAM bytecode, but teams using Mix may prefer to vendor and
+/// read generated Elixir rather than Erlang.
+///
+/// ```toml
+/// [erlang]
+/// source = "elixir"
+/// ```
 #[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ErlangSource {
+    /// `.erl` modules.
+    #[default]
+    Erlang,
+    /// `.ex` modules.
+    Elixir,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
 pub struct JavaScriptConfig {
     #[serde(default)]
     pub typescript_declarations: bool,
+    #[serde(default)]
+    pub bundle: Option<BundleConfig>,
+}
+
+/// The `[native]` section of gleam.toml, describing what the C++ code of the
+/// package needs from outside of Gleam. These are given to the linker and
+/// to the package managers of the native library of a project that depends
+/// upon the package.
+///
+/// ```toml
+/// [native]
+/// libraries = ["z"]
+/// conan = ["zlib/1.3.1"]
+/// vcpkg = ["zlib"]
+/// ```
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NativeConfig {
+    /// The system libraries the package links with, as given to `-l`.
+    #[serde(default)]
+    pub libraries: Vec<String>,
+    /// The Conan packages providing the libraries, such as `zlib/1.3.1`.
+    #[serde(default)]
+    pub conan: Vec<String>,
+    /// The vcpkg ports providing the libraries, such as `zlib`.
+    #[serde(default)]
+    pub vcpkg: Vec<String>,
+}
+
+/// The `[javascript.bundle]` section of gleam.toml, used to hand the compiled
+/// JavaScript to a bundler such as esbuild.
+///
+/// Each command is run with a shell and given the path of an entry module
+/// that runs the project's `main` function. The `GLEAM_ENTRY_MODULE` and
+/// `GLEAM_OUTPUT_DIRECTORY` environment variables hold the entry module and
+/// the directory the JavaScript packages were compiled into.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct BundleConfig {
+    /// Run by `gleam build --bundle` once the project has been compiled.
+    pub command: String,
+    /// Run by `gleam run --bundle` once the project has been bundled, to serve
+    /// the bundled output.
+    #[serde(default)]
+    pub serve: Option<String>,
+}
+
+/// The `[workspace]` section of gleam.toml, which lists the directories of
+/// local packages that are built, tested, and formatted along with this one.
+///
+/// ```toml
+/// [workspace]
+/// members = ["packages/core", "packages/web"]
+/// ```
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct WorkspaceConfig {
+    #[serde(default)]
+    pub members: Vec<PathBuf>,
+}
+
+/// The `[build]` section of gleam.toml.
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct BuildConfig {
+    #[serde(default)]
+    pub hooks: HashMap<String, BuildHook>,
+}
+
+impl BuildConfig {
+    /// The build hooks, in the order they are run: by name.
+    pub fn hooks(&self) -> Vec<(&String, &BuildHook)> {
+        self.hooks.iter().sorted_by(|a, b| a.0.cmp(b.0)).collect()
+    }
+
+    /// The directories that the build hooks write Gleam code to, which are
+    /// compiled along with the `src` directory.
+    pub fn source_directories(&self) -> Vec<&PathBuf> {
+        self.hooks()
+            .into_iter()
+            .filter_map(|(_, hook)| hook.output.as_ref())
+            .collect()
+    }
+}
+
+/// A shell command that is run in the directory of the package before it is
+/// built, such as one that generates Gleam code from protobuf or SQL files.
+/// The Gleam modules in its `output` directory are compiled as if they were
+/// in the `src` directory.
+///
+/// ```toml
+/// [build.hooks.protobuf]
+/// command = "protoc --gleam_out=gen/proto proto/*.proto"
+/// output = "gen/proto"
+/// ```
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BuildHook {
+    pub command: String,
+    #[serde(default)]
+    pub output: Option<PathBuf>,
+}
+
+/// The `[publish]` section of gleam.toml, which selects the files that
+/// `gleam publish` adds to the package besides its source code, README, and
+/// licences. The globs are written as in a `.gitignore` file.
+///
+/// ```toml
+/// [publish]
+/// include = ["priv/**", "c_src/*.c"]
+/// exclude = ["src/**/internal_notes.md"]
+/// ```
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PublishConfig {
+    /// Files to publish in addition to the usual ones.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Files not to publish, even if they would be otherwise. gleam.toml is
+    /// always published.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// A Hex compatible package registry other than hex.pm, given in the
+/// `registries` section of gleam.toml. The API key for the registry is read
+/// from the environment variable named by `key-env`, or is printed by the
+/// shell command `key-command`. Packages are verified with the public key of
+/// hex.pm if no `public-key` is given, as is right for a mirror of hex.pm.
+///
+/// ```toml
+/// [registries.acme]
+/// api = "https://hex.acme.com/api/"
+/// repository = "https://repo.hex.acme.com/"
+/// public-key = "-----BEGIN PUBLIC KEY-----..."
+/// key-env = "ACME_HEX_KEY"
+/// ```
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RegistryConfig {
+    pub api: String,
+    pub repository: String,
+    #[serde(default)]
+    pub public_key: Option<String>,
+    #[serde(default)]
+    pub key_env: Option<String>,
+    #[serde(default)]
+    pub key_command: Option<String>,
+}
+
+/// The `[dependency-licences]` section of gleam.toml. When licences are
+/// listed in `allow` every dependency package must be available under one of
+/// them, or the dependencies cannot be downloaded.
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct LicencePolicy {
+    #[serde(default)]
+    pub allow: Vec<SpdxLicense>,
+}
+
+/// The `[warnings]` section of gleam.toml, which sets how each kind of
+/// warning is treated when building the package.
+///
+/// ```toml
+/// [warnings]
+/// unused_imported_module = "deny"
+/// todo = "allow"
+/// ```
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+#[serde(try_from = "HashMap<String, WarningLevel>")]
+pub struct WarningLevels {
+    /// The level of warnings that do not have a level of their own.
+    pub default: WarningLevel,
+    pub levels: HashMap<WarningKind, WarningLevel>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningLevel {
+    /// The warning is not shown.
+    Allow,
+    /// The warning is shown.
+    #[default]
+    Warn,
+    /// The warning is shown and the build fails.
+    Deny,
+}
+
+impl WarningLevels {
+    /// The level of warnings of the given kind. Warnings without a kind, such
+    /// as those for dead code, always have the default level.
+    pub fn level(&self, kind: Option<WarningKind>) -> WarningLevel {
+        kind.and_then(|kind| self.levels.get(&kind))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// These levels with those set by `overrides` taking precedence, as is
+    /// done for the levels given to the command line. If `overrides` denies
+    /// warnings by default then every warning that would be shown is denied.
+    pub fn overridden_by(&self, overrides: &WarningLevels) -> WarningLevels {
+        let mut levels = self.levels.clone();
+        levels.extend(overrides.levels.iter().map(|(kind, level)| (*kind, *level)));
+        let mut default = self.default;
+        if overrides.default == WarningLevel::Deny {
+            default = WarningLevel::Deny;
+            for level in levels.values_mut() {
+                if *level == WarningLevel::Warn {
+                    *level = WarningLevel::Deny;
+                }
+            }
+        }
+        WarningLevels { default, levels }
+    }
+}
+
+impl TryFrom<HashMap<String, WarningLevel>> for WarningLevels {
+    type Error = String;
+
+    fn try_from(levels: HashMap<String, WarningLevel>) -> Result<Self, Self::Error> {
+        let levels = levels
+            .into_iter()
+            .map(|(name, level)| Ok((name.parse()?, level)))
+            .collect::<Result<_, String>>()?;
+        Ok(WarningLevels {
+            default: WarningLevel::Warn,
+            levels,
+        })
+    }
+}
+
+impl LicencePolicy {
+    pub fn is_enforced(&self) -> bool {
+        !self.allow.is_empty()
+    }
+
+    /// Whether a package with the given licences may be depended upon. Each
+    /// licence may be an SPDX expression such as `MIT OR Apache-2.0`, and a
+    /// package with several licences is allowed if any one of them is.
+    pub fn allows(&self, licences: &[String]) -> bool {
+        licences.iter().any(|licence| {
+            spdx::Expression::parse_mode(licence, spdx::ParseMode::LAX)
+                .map(|expression| {
+                    expression.evaluate(|requirement| match &requirement.license {
+                        spdx::LicenseItem::Spdx { id, .. } => self
+                            .allow
+                            .iter()
+                            .any(|allowed| allowed.licence.eq_ignore_ascii_case(id.name)),
+                        spdx::LicenseItem::Other { .. } => false,
+                    })
+                })
+                .unwrap_or(false)
+        })
+    }
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -473,6 +1132,86 @@ impl Default for Repository {
 pub struct Docs {
     #[serde(default)]
     pub pages: Vec<DocsPage>,
+    /// Where the documentation is hosted when not on HexDocs. The latest
+    /// version is expected at this URL and each version in a directory of it
+    /// named after the version, as HexDocs does.
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// The earlier versions of the documentation hosted at the base URL, which
+    /// the pages link to along with the current version.
+    #[serde(default)]
+    pub versions: Vec<Version>,
+    #[serde(default)]
+    pub theme: DocsTheme,
+}
+
+/// The branding of the generated documentation. Colours are CSS colours and
+/// files are relative to the root of the package.
+#[derive(Deserialize, Default, Debug, PartialEq, Eq, Clone)]
+pub struct DocsTheme {
+    #[serde(default, deserialize_with = "css_colour")]
+    pub accent_color: Option<String>,
+    #[serde(default, deserialize_with = "css_colour")]
+    pub link_color: Option<String>,
+    #[serde(default, deserialize_with = "css_colour")]
+    pub dark_accent_color: Option<String>,
+    #[serde(default, deserialize_with = "css_colour")]
+    pub dark_link_color: Option<String>,
+    #[serde(default)]
+    pub logo: Option<PathBuf>,
+    #[serde(default)]
+    pub favicon: Option<PathBuf>,
+    #[serde(default)]
+    pub stylesheet: Option<PathBuf>,
+    /// The theme shown to readers who have not chosen one, rather than the
+    /// one their system prefers.
+    #[serde(default)]
+    pub default: Option<DocsColourScheme>,
+}
+
+impl DocsTheme {
+    /// The files of the package the theme includes in the documentation.
+    pub fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.logo
+            .iter()
+            .chain(self.favicon.iter())
+            .chain(self.stylesheet.iter())
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DocsColourScheme {
+    Light,
+    Dark,
+}
+
+impl DocsColourScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+}
+
+// Colours are written into the stylesheet of the documentation so they are
+// limited to the characters of hex, named and functional notation colours.
+fn css_colour<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error as _;
+    let colour = String::deserialize(deserializer)?;
+    let valid = !colour.trim().is_empty()
+        && colour
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "#(),.% /-".contains(c));
+    if valid {
+        Ok(Some(colour))
+    } else {
+        Err(D::Error::custom(format!("invalid CSS colour `{}`", colour)))
+    }
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -559,3 +1298,465 @@ name = "1"
         "Package names may only container lowercase letters, numbers, and underscores for key `name` at line 1 column 1"
     )
 }
+
+#[test]
+fn javascript_bundle_config() {
+    let input = r#"
+name = "app"
+
+[javascript.bundle]
+command = "esbuild --bundle --outdir=dist"
+serve = "esbuild --servedir=dist"
+"#;
+    assert_eq!(
+        toml::from_str::<PackageConfig>(input).map(|config| config.javascript.bundle),
+        Ok(Some(BundleConfig {
+            command: "esbuild --bundle --outdir=dist".into(),
+            serve: Some("esbuild --servedir=dist".into()),
+        }))
+    );
+}
+
+#[test]
+fn dependency_licences_config() {
+    let input = r#"
+name = "app"
+licences = ["Apache-2.0"]
+
+[dependency-licences]
+allow = ["MIT", "Apache-2.0"]
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    assert_eq!(
+        config.dependency_licences.allow,
+        vec![
+            SpdxLicense {
+                licence: "MIT".into()
+            },
+            SpdxLicense {
+                licence: "Apache-2.0".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn licence_policy_allows() {
+    let policy = LicencePolicy {
+        allow: vec![SpdxLicense {
+            licence: "MIT".into(),
+        }],
+    };
+    assert!(policy.allows(&["MIT".into()]));
+    assert!(policy.allows(&["GPL-3.0-only".into(), "MIT".into()]));
+    assert!(policy.allows(&["Apache-2.0 OR MIT".into()]));
+    assert!(!policy.allows(&["Apache-2.0 AND MIT".into()]));
+    assert!(!policy.allows(&["Apache-2.0".into()]));
+    assert!(!policy.allows(&[]));
+}
+
+#[test]
+fn warnings_config() {
+    let input = r#"
+name = "app"
+
+[warnings]
+unused_imported_module = "deny"
+todo = "allow"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    let warnings = config.warnings;
+    assert_eq!(
+        warnings.level(Some(WarningKind::UnusedImportedModule)),
+        WarningLevel::Deny
+    );
+    assert_eq!(warnings.level(Some(WarningKind::Todo)), WarningLevel::Allow);
+    assert_eq!(
+        warnings.level(Some(WarningKind::UnusedVariable)),
+        WarningLevel::Warn
+    );
+    assert_eq!(warnings.level(None), WarningLevel::Warn);
+}
+
+#[test]
+fn warnings_config_unknown_kind() {
+    let input = r#"
+name = "app"
+
+[warnings]
+unused_everything = "deny"
+"#;
+    let error = toml::from_str::<PackageConfig>(input).expect_err("unknown kind");
+    assert!(error
+        .to_string()
+        .contains("unknown kind of warning `unused_everything`"));
+}
+
+#[test]
+fn warning_levels_overridden() {
+    let config = WarningLevels {
+        default: WarningLevel::Warn,
+        levels: [
+            (WarningKind::Todo, WarningLevel::Allow),
+            (WarningKind::UnusedVariable, WarningLevel::Warn),
+            (WarningKind::UnusedLiteral, WarningLevel::Deny),
+        ]
+        .into(),
+    };
+
+    let overrides = WarningLevels {
+        default: WarningLevel::Warn,
+        levels: [(WarningKind::UnusedLiteral, WarningLevel::Allow)].into(),
+    };
+    let levels = config.overridden_by(&overrides);
+    assert_eq!(
+        levels.level(Some(WarningKind::UnusedLiteral)),
+        WarningLevel::Allow
+    );
+    assert_eq!(
+        levels.level(Some(WarningKind::UnusedVariable)),
+        WarningLevel::Warn
+    );
+
+    // Denying every warning still leaves allowed warnings hidden
+    let overrides = WarningLevels {
+        default: WarningLevel::Deny,
+        levels: HashMap::new(),
+    };
+    let levels = config.overridden_by(&overrides);
+    assert_eq!(levels.level(Some(WarningKind::Todo)), WarningLevel::Allow);
+    assert_eq!(
+        levels.level(Some(WarningKind::UnusedVariable)),
+        WarningLevel::Deny
+    );
+    assert_eq!(levels.level(None), WarningLevel::Deny);
+}
+
+#[test]
+fn lints_config() {
+    use crate::lint::{BoolCase, DeepNesting, LongFunction};
+    let input = r#"
+name = "app"
+
+[lints.deep_nesting]
+max_depth = 3
+
+[lints.long_function]
+
+[lints.bool_case]
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    assert_eq!(
+        config.lints,
+        LintConfig {
+            deep_nesting: Some(DeepNesting { max_depth: 3 }),
+            long_function: Some(LongFunction { max_lines: 50 }),
+            nested_calls: None,
+            bool_case: Some(BoolCase {}),
+        }
+    );
+}
+
+#[test]
+fn lints_config_unknown_rule() {
+    let input = r#"
+name = "app"
+
+[lints.short_function]
+"#;
+    let error = toml::from_str::<PackageConfig>(input).expect_err("unknown rule");
+    assert!(error.to_string().contains("unknown field `short_function`"));
+}
+
+#[test]
+fn line_length_config() {
+    let config = toml::from_str::<PackageConfig>("name = \"app\"").expect("config");
+    assert_eq!(config.line_length, 80);
+    let config =
+        toml::from_str::<PackageConfig>("name = \"app\"\nline_length = 100").expect("config");
+    assert_eq!(config.line_length, 100);
+}
+
+#[test]
+fn features_config() {
+    let input = r#"
+name = "app"
+
+[features]
+json = true
+xml = false
+
+[dependency-features.lib]
+colour = true
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    assert_eq!(
+        config.features,
+        [("json".into(), true), ("xml".into(), false)].into()
+    );
+
+    let lib = toml::from_str::<PackageConfig>(
+        "name = \"lib\"\n[features]\ncolour = false\nunicode = true",
+    )
+    .expect("config");
+    assert_eq!(
+        config.features_of(&lib),
+        Ok([("colour".into(), true), ("unicode".into(), true)].into())
+    );
+
+    let lib = toml::from_str::<PackageConfig>("name = \"lib\"").expect("config");
+    assert_eq!(
+        config.features_of(&lib),
+        Err(Error::UnknownDependencyFeature {
+            package: "lib".into(),
+            flag: "colour".into(),
+        })
+    );
+}
+
+#[test]
+fn native_config() {
+    let input = r#"
+name = "app"
+
+[native]
+libraries = ["z"]
+conan = ["zlib/1.3.1"]
+vcpkg = ["zlib"]
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    assert_eq!(
+        config.native,
+        NativeConfig {
+            libraries: vec!["z".into()],
+            conan: vec!["zlib/1.3.1".into()],
+            vcpkg: vec!["zlib".into()],
+        }
+    );
+
+    let error = toml::from_str::<PackageConfig>("name = \"app\"\n[native]\nlinks = []")
+        .expect_err("unknown field");
+    assert!(error.to_string().contains("unknown field `links`"));
+}
+
+#[test]
+fn erlang_source() {
+    let config = toml::from_str::<PackageConfig>("name = \"app\"").expect("config");
+    assert_eq!(config.erlang.source, ErlangSource::Erlang);
+
+    let input = r#"
+name = "app"
+
+[erlang]
+source = "elixir"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    assert_eq!(config.erlang.source, ErlangSource::Elixir);
+}
+
+#[test]
+fn format_options() {
+    let config =
+        toml::from_str::<PackageConfig>("name = \"app\"\nsort_imports = true").expect("config");
+    assert_eq!(
+        config.format_options(),
+        format::Options {
+            line_length: 80,
+            sort_imports: true,
+            package: Some("app".into()),
+            verify: false,
+        }
+    );
+}
+
+#[test]
+fn publish_files() {
+    let input = r#"
+name = "app"
+
+[publish]
+include = ["assets/**"]
+exclude = ["src/scratch.gleam"]
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    assert_eq!(
+        config.publish,
+        PublishConfig {
+            include: vec!["assets/**".into()],
+            exclude: vec!["src/scratch.gleam".into()],
+        }
+    );
+}
+
+#[test]
+fn build_hooks() {
+    let input = r#"
+name = "app"
+
+[build.hooks.sql]
+command = "squirrel generate"
+output = "gen/sql"
+
+[build.hooks.assets]
+command = "npm run build"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    assert_eq!(
+        config.build.hooks(),
+        vec![
+            (
+                &"assets".to_string(),
+                &BuildHook {
+                    command: "npm run build".into(),
+                    output: None,
+                }
+            ),
+            (
+                &"sql".to_string(),
+                &BuildHook {
+                    command: "squirrel generate".into(),
+                    output: Some("gen/sql".into()),
+                }
+            ),
+        ]
+    );
+    assert_eq!(
+        config.build.source_directories(),
+        vec![&PathBuf::from("gen/sql")]
+    );
+}
+
+#[test]
+fn registries() {
+    let input = r#"
+name = "app"
+registry = "acme"
+
+[dependencies]
+gleam_stdlib = "~> 0.25"
+acme_auth = { version = "~> 1.0", registry = "internal" }
+
+[registries.acme]
+api = "https://hex.acme.com/api/"
+repository = "https://repo.hex.acme.com/"
+key-env = "ACME_HEX_KEY"
+
+[registries.internal]
+api = "https://hex.internal.acme.com/api/"
+repository = "https://repo.hex.internal.acme.com/"
+public-key = "-----BEGIN PUBLIC KEY-----"
+key-command = "acme-auth hex-key"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    assert_eq!(config.registry, Some("acme".into()));
+    assert_eq!(
+        config.dependencies.get("acme_auth"),
+        Some(&Requirement::Hex {
+            version: Range::new("~> 1.0".into()),
+            registry: Some("internal".into()),
+        })
+    );
+    assert_eq!(
+        config.registries,
+        [
+            (
+                "acme".into(),
+                RegistryConfig {
+                    api: "https://hex.acme.com/api/".into(),
+                    repository: "https://repo.hex.acme.com/".into(),
+                    public_key: None,
+                    key_env: Some("ACME_HEX_KEY".into()),
+                    key_command: None,
+                }
+            ),
+            (
+                "internal".into(),
+                RegistryConfig {
+                    api: "https://hex.internal.acme.com/api/".into(),
+                    repository: "https://repo.hex.internal.acme.com/".into(),
+                    public_key: Some("-----BEGIN PUBLIC KEY-----".into()),
+                    key_env: None,
+                    key_command: Some("acme-auth hex-key".into()),
+                }
+            ),
+        ]
+        .into()
+    );
+}
+
+#[test]
+fn dependency_requirements() {
+    let input = r#"
+name = "app"
+
+[dependencies]
+gleam_stdlib = "~> 0.25"
+gleam_http = { version = "~> 3.1" }
+my_lib = { path = "../my_lib" }
+gleam_json = { git = "https://github.com/gleam-lang/json.git", ref = "v0.5.0" }
+gleam_otp = { git = "https://github.com/gleam-lang/otp.git" }
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    assert_eq!(
+        config.dependencies,
+        [
+            ("gleam_stdlib".into(), Requirement::hex("~> 0.25")),
+            ("gleam_http".into(), Requirement::hex("~> 3.1")),
+            ("my_lib".into(), Requirement::path("../my_lib")),
+            (
+                "gleam_json".into(),
+                Requirement::git("https://github.com/gleam-lang/json.git", "v0.5.0")
+            ),
+            (
+                "gleam_otp".into(),
+                Requirement::git("https://github.com/gleam-lang/otp.git", "HEAD")
+            ),
+        ]
+        .into()
+    );
+    assert_eq!(
+        config.path_dependencies_for(Mode::Prod),
+        Ok(vec![("my_lib".into(), PathBuf::from("../my_lib"))])
+    );
+    assert_eq!(
+        config.hex_dependencies_for(Mode::Prod),
+        Ok([
+            ("gleam_stdlib".into(), Range::new("~> 0.25".into())),
+            ("gleam_http".into(), Range::new("~> 3.1".into())),
+        ]
+        .into())
+    );
+}
+
+#[test]
+fn docs_theme_config() {
+    let input = r##"
+name = "app"
+
+[documentation.theme]
+accent_color = "#4c8bf5"
+dark_link_color = "rgb(120, 170, 255)"
+logo = "docs/logo.svg"
+default = "dark"
+"##;
+    let config = toml::from_str::<PackageConfig>(input).expect("config");
+    assert_eq!(
+        config.documentation.theme,
+        DocsTheme {
+            accent_color: Some("#4c8bf5".into()),
+            dark_link_color: Some("rgb(120, 170, 255)".into()),
+            logo: Some("docs/logo.svg".into()),
+            default: Some(DocsColourScheme::Dark),
+            ..Default::default()
+        }
+    );
+
+    let input = r#"
+name = "app"
+
+[documentation.theme]
+accent_color = "red; } body { display: none"
+"#;
+    let error = toml::from_str::<PackageConfig>(input).expect_err("invalid colour");
+    assert!(error.to_string().contains("invalid CSS colour"));
+}
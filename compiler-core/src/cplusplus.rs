@@ -12,6 +12,7 @@ use std::path::Path;
 mod declaration;
 pub(crate) mod error;
 mod expression;
+mod extern_c;
 mod keywords;
 mod meta;
 mod record;
@@ -19,18 +20,22 @@ mod symbolizer;
 #[cfg(test)]
 mod tests;
 
+pub use extern_c::ExternC;
+
 pub type Output<'a> = Result<Document<'a>, error::Error>;
 
 const INDENT: isize = 2;
 
 pub const PRELUDE_HEADER: &str = include_str!("../templates/gleam.h");
 pub const PRELUDE_IMPL: &str = include_str!("../templates/gleam.cc");
+pub const CLANG_FORMAT_CONFIG: &str = include_str!("../templates/clang-format");
 
 pub fn module_header(
     module: &TypedModule,
     _line_numbers: &LineNumbers,
     path: &Path,
     src: &str,
+    line_length: isize,
     writer: &mut impl Utf8Writer,
 ) -> Result<(), Error> {
     // TODO: private stuff needs to be grouped into namespaces.
@@ -82,17 +87,9 @@ pub fn module_header(
 
     let mut document = Document::Vec(statements);
     document = meta::wrap_with_namespace_scope(document, module);
-    document = docvec!("#include <gleam.h>", line()).append(document);
-    for import_name in collect_imports(module) {
-        let import = if import_name.starts_with('<') && import_name.ends_with('>') {
-            Document::String(import_name)
-        } else {
-            docvec!("\"", Document::String(import_name), "\"")
-        };
-        document = docvec!("#include ", import, line())
-    }
+    document = include_directives(collect_imports(module)).append(document);
     document = meta::wrap_with_include_guards(document, module);
-    document.pretty_print(80, writer)?;
+    document.pretty_print(line_length, writer)?;
     Ok(())
 }
 
@@ -101,13 +98,14 @@ pub fn module_impl(
     _line_numbers: &LineNumbers,
     path: &Path,
     src: &str,
+    line_length: isize,
     writer: &mut impl Utf8Writer,
 ) -> Result<(), Error> {
     let mut document = nil();
     let declarations: Vec<Document<'_>> = module
         .statements
         .iter()
-        .map(implementation)
+        .map(|statement| implementation(&module.name, statement))
         .filter_map_ok(std::convert::identity)
         .try_collect()
         .map_err(|err| Error::CPlusPlus {
@@ -127,10 +125,29 @@ pub fn module_impl(
     })?;
     document = meta::wrap_with_namespace_scope(document, module);
     document = docvec!("#include \"", header_name, ".h\"", line()).append(document);
-    document.pretty_print(80, writer)?;
+    document.pretty_print(line_length, writer)?;
     Ok(())
 }
 
+/// Renders the `#include` directives for a header, system headers first and
+/// then project headers, each group sorted so the output is stable.
+fn include_directives<'a>(imports: Vec<String>) -> Document<'a> {
+    let (system, project): (Vec<_>, Vec<_>) = std::iter::once("<gleam.h>".to_string())
+        .chain(imports)
+        .sorted()
+        .dedup()
+        .partition(|name| name.starts_with('<') && name.ends_with('>'));
+    let system = system.into_iter().map(Document::String);
+    let project = project
+        .into_iter()
+        .map(|name| docvec!("\"", Document::String(name), "\""));
+    concat(
+        system
+            .chain(project)
+            .map(|include| docvec!("#include ", include, line())),
+    )
+}
+
 fn collect_imports(module: &TypedModule) -> Vec<String> {
     module
         .statements
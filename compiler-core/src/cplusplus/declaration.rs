@@ -4,10 +4,11 @@ use crate::cplusplus::expression::*;
 use crate::cplusplus::INDENT;
 use crate::docvec;
 use crate::ir::IntermediateRepresentationConverter;
+use crate::name::Name;
 use crate::pretty::*;
-use crate::type_::{Type, TypeVar};
+use crate::type_::{Type, TypeVar, TypeVarCell};
 use itertools::Itertools;
-use std::{cell::RefCell, ops::Deref, sync::Arc};
+use std::{ops::Deref, sync::Arc};
 
 use super::record::StructGenerator;
 
@@ -47,7 +48,10 @@ impl<'a> Declaration<'a> {
     }
 }
 
-pub(crate) fn implementation(statement: &TypedStatement) -> Result<Option<Document<'_>>, Error> {
+pub(crate) fn implementation<'a>(
+    module: &'a [Name],
+    statement: &'a TypedStatement,
+) -> Result<Option<Document<'a>>, Error> {
     Ok(match statement {
         TypedStatement::Fn {
             name,
@@ -56,8 +60,9 @@ pub(crate) fn implementation(statement: &TypedStatement) -> Result<Option<Docume
             body,
             ..
         } => {
-            let mut ir_generator = IntermediateRepresentationConverter::new_for_function(arguments);
-            let ir = ir_generator.ast_to_ir(body);
+            let mut ir_generator =
+                IntermediateRepresentationConverter::new_for_function(module, arguments);
+            let ir = ir_generator.ast_to_ir(body)?;
 
             let mut generator = NativeIrCodeGenerator::new();
             let doc = generator.ir_to_doc(ir)?;
@@ -67,7 +72,7 @@ pub(crate) fn implementation(statement: &TypedStatement) -> Result<Option<Docume
                 " {",
                 line().append(doc).nest(INDENT).group(),
                 line(),
-                "};"
+                "}"
             ])
         }
         TypedStatement::TypeAlias { .. } => None,
@@ -228,7 +233,7 @@ pub(crate) fn function_type<'a>(result: Arc<Type>, args: Vec<Arc<Type>>) -> Docu
 pub(crate) fn to_symbol<'a, 'b>(
     name: &'a str,
     public: bool,
-    module: &'a Vec<String>,
+    module: &'a [Name],
     args: &'a [Arc<Type>],
 ) -> Document<'b> {
     let mut doc = if module.is_empty() {
@@ -269,7 +274,7 @@ pub(crate) fn transform_type<'a, 'b>(type_: &'a Type) -> Document<'b> {
                     .map(|a| match a.as_ref() {
                         Type::Var { .. } => a.clone(),
                         _ => Arc::new(Type::Var {
-                            type_: Arc::new(RefCell::new(TypeVar::Link { type_: a.clone() })),
+                            type_: Arc::new(TypeVarCell::new(TypeVar::Link { type_: a.clone() })),
                         }),
                     })
                     .collect();
@@ -4,3 +4,13 @@ pub enum Error {
     Unimplemented { message: String },
     InternalError { message: String },
 }
+
+impl From<crate::ir::Error> for Error {
+    fn from(error: crate::ir::Error) -> Self {
+        match error {
+            crate::ir::Error::Unsupported { feature, .. } => Error::Unimplemented {
+                message: format!("{feature} are not yet supported"),
+            },
+        }
+    }
+}
@@ -44,7 +44,10 @@ impl<'module> NativeIrCodeGenerator {
             }
             ir::Statement::Assignment { var, expr, typ } => {
                 docvec![
-                    self.typ_to_symbol(typ)?,
+                    match typ {
+                        Some(typ) => self.typ_to_symbol(typ)?,
+                        None => "auto".to_doc(),
+                    },
                     " ",
                     self.ir_identifier_to_doc(var)?,
                     " = ",
@@ -97,8 +100,10 @@ impl<'module> NativeIrCodeGenerator {
             ir::Literal::Bool { value } => if value { "true" } else { "false" }.to_doc(),
             // TODO: Can we tell the compiler that the str's lifetime here is not tied to `literal`
             // but to `'module`?
-            ir::Literal::Int { value } => Document::String(value.to_owned()),
-            ir::Literal::Float { value } => Document::String(value.to_owned()),
+            ir::Literal::Int { value } => Document::String(int(&value)),
+            ir::Literal::Float { value } => {
+                Document::String(crate::float::canonical_literal(value))
+            }
             ir::Literal::String { value } => {
                 Document::String(value).surround("gleam::MakeString(u8\"", "\")")
             }
@@ -116,6 +121,28 @@ impl<'module> NativeIrCodeGenerator {
                 );
                 docvec![self.ir_expr_to_doc(*callee)?, "(", formatted_args, ")",]
             }
+            ir::Call::Builtin { function, args } => {
+                let name = match function {
+                    ir::BuiltinFn::Echo => "gleam::Echo",
+                    ir::BuiltinFn::Panic => "gleam::Crash",
+                    ir::BuiltinFn::Rescue { .. } => "gleam::Rescue",
+                    ir::BuiltinFn::BitStringSize { .. } => "gleam::BitStringSize",
+                    ir::BuiltinFn::BitStringInt { .. } => "gleam::BitStringInt",
+                    ir::BuiltinFn::BitStringFloat { .. } => "gleam::BitStringFloat",
+                    ir::BuiltinFn::BitStringSlice { .. } => "gleam::BitStringSlice",
+                    function => {
+                        return Err(Error::Unimplemented {
+                            message: format!("The {function:?} builtin is not yet supported"),
+                        })
+                    }
+                };
+                let formatted_args = comma_seperate(
+                    args.into_iter()
+                        .map(|e| self.ir_expr_to_doc(e))
+                        .try_collect()?,
+                );
+                docvec![name, "(", formatted_args.nest(INDENT).group(), ")"]
+            }
         })
     }
 
@@ -124,13 +151,24 @@ impl<'module> NativeIrCodeGenerator {
         accessor: ir::Accessor<'module>,
     ) -> Result<Document<'module>, Error> {
         Ok(match accessor {
-            ir::Accessor::Custom { label, reciever } => {
-                docvec![self.ir_expr_to_doc(*reciever)?, "->", label.to_doc()]
-            }
-            ir::Accessor::TupleIndex { index, tuple } => self.ir_expr_to_doc(*tuple)?.surround(
-                docvec!["gleam::Get<", Document::String(format!("{}", index)), ">("],
-                ")",
-            ),
+            // Fields shared by every constructor are members of the base struct of
+            // the type, whatever their position in each constructor
+            ir::Accessor::Custom {
+                label,
+                index,
+                reciever,
+                ..
+            } => docvec![
+                self.ir_expr_to_doc(*reciever)?,
+                "->",
+                match label {
+                    Some(label) => label.to_doc(),
+                    None => Document::String(format!("_${index}")),
+                }
+            ],
+            ir::Accessor::TupleIndex { index, tuple } => self
+                .ir_expr_to_doc(*tuple)?
+                .surround(docvec!["gleam::Get<", index.to_doc(), ">("], ")"),
             ir::Accessor::LocalVariable { name, .. } => self.ir_identifier_to_doc(name)?,
             ir::Accessor::ModuleVariable {
                 public,
@@ -139,6 +177,7 @@ impl<'module> NativeIrCodeGenerator {
                 name,
                 typ,
             } => self.module_symbol(name, public, &module[..], module_alias, &typ)?,
+            ir::Accessor::External { name, .. } => name.to_doc(),
         })
     }
 
@@ -209,7 +248,7 @@ impl<'module> NativeIrCodeGenerator {
                         .surround("{", "}"),
                         tail.map(|e| {
                             let t = self.ir_expr_to_doc(*e)?;
-                            Ok(break_(",", ", ").append(t))
+                            Ok::<_, Error>(break_(",", ", ").append(t))
                         })
                         .unwrap_or_else(|| Ok(nil()))?,
                     ]
@@ -253,6 +292,21 @@ impl<'module> NativeIrCodeGenerator {
                     ">()",
                 ]
             }
+            ir::TypeConstruction::BitString { segments, .. } => {
+                let mut doc = "gleam::BitStringBuilder()".to_doc();
+                for segment in segments {
+                    let value = self.ir_expr_to_doc(segment.value)?;
+                    doc = doc.append(match segment.kind {
+                        ir::BitStringSegmentKind::Int { size } => {
+                            docvec![".Int(", value, ", ", self.ir_expr_to_doc(*size)?, ")"]
+                        }
+                        ir::BitStringSegmentKind::Float => docvec![".Float(", value, ")"],
+                        ir::BitStringSegmentKind::Utf8 => docvec![".Utf8(", value, ")"],
+                        ir::BitStringSegmentKind::BitString => docvec![".Bits(", value, ")"],
+                    });
+                }
+                doc.append(".Build()")
+            }
             ir::TypeConstruction::Function { typ, args, body } => {
                 let (_, result_type) = typ.fn_types().ok_or(Error::InternalError {
                     message: format!("Unexpected type for function: {:?}", typ),
@@ -292,7 +346,9 @@ impl<'module> NativeIrCodeGenerator {
             expr,
             ir::Expression::Literal(_)
                 | ir::Expression::Accessor(
-                    ir::Accessor::LocalVariable { .. } | ir::Accessor::ModuleVariable { .. }
+                    ir::Accessor::LocalVariable { .. }
+                        | ir::Accessor::ModuleVariable { .. }
+                        | ir::Accessor::External { .. }
                 )
         );
         if !needs_wrap {
@@ -354,3 +410,21 @@ fn generate_bin_op(op: ast::BinOp) -> Result<&'static str, Error> {
         ast::BinOp::Or => "||",
     })
 }
+
+// C++ has no `0o` prefix, writes octal with a leading zero instead, and does
+// not allow underscores between digits.
+fn int(value: &str) -> String {
+    let literal = crate::int::Literal::new(value);
+    let prefix = match literal.radix {
+        16 => "0x",
+        8 => "0",
+        2 => "0b",
+        _ => "",
+    };
+    format!(
+        "{}{}{}",
+        literal.sign(),
+        prefix,
+        literal.significant_digits()
+    )
+}
@@ -0,0 +1,278 @@
+//! The `extern "C"` surface of a package, through which C programs, and C++
+//! programs that do not use the prelude, call the public functions of its
+//! modules.
+//!
+//! Each function is named after its module, so `parse` in `my_package/util`
+//! is `my_package_util_parse`. Only functions whose arguments and results
+//! have a C representation are included:
+//!
+//! | Gleam    | C                                        |
+//! |----------|------------------------------------------|
+//! | `Int`    | `int64_t`                                |
+//! | `Float`  | `double`                                 |
+//! | `Bool`   | `bool`                                   |
+//! | `String` | `const char *` argument, `char *` result |
+//! | `Nil`    | `void` result                            |
+//!
+//! Returned strings are allocated with `malloc` and are owned by the caller.
+
+use std::fmt::Write;
+
+use heck::{ToShoutySnakeCase, ToSnakeCase};
+use itertools::Itertools;
+
+use super::keywords::to_identifier;
+use crate::{
+    ast::{TypedModule, TypedStatement},
+    name::Name,
+    type_,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Int,
+    Float,
+    Bool,
+    String,
+}
+
+impl Type {
+    fn from_type(type_: &type_::Type) -> Option<Self> {
+        if type_.is_int() {
+            Some(Self::Int)
+        } else if type_.is_float() {
+            Some(Self::Float)
+        } else if type_.is_bool() {
+            Some(Self::Bool)
+        } else if type_.is_string() {
+            Some(Self::String)
+        } else {
+            None
+        }
+    }
+
+    fn parameter(self) -> &'static str {
+        match self {
+            Self::String => "const char*",
+            _ => self.result(),
+        }
+    }
+
+    fn result(self) -> &'static str {
+        match self {
+            Self::Int => "int64_t",
+            Self::Float => "double",
+            Self::Bool => "bool",
+            Self::String => "char*",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Function {
+    /// The C name of the function, such as `my_package_util_parse`.
+    symbol: String,
+    /// The C++ function it calls, such as `::my_package::util::parse`.
+    target: String,
+    parameters: Vec<(String, Type)>,
+    /// The result of the function, or nothing if it returns `Nil`.
+    result: Option<Type>,
+}
+
+impl Function {
+    fn signature(&self) -> String {
+        let result = self.result.map_or("void", Type::result);
+        let parameters = if self.parameters.is_empty() {
+            "void".into()
+        } else {
+            self.parameters
+                .iter()
+                .map(|(name, type_)| format!("{} {name}", type_.parameter()))
+                .join(", ")
+        };
+        format!("{result} {}({parameters})", self.symbol)
+    }
+}
+
+/// The `extern "C"` functions of the modules of a package.
+#[derive(Debug)]
+pub struct ExternC {
+    package: String,
+    modules: Vec<String>,
+    functions: Vec<Function>,
+}
+
+impl ExternC {
+    pub fn new(package: &str, modules: &[&TypedModule]) -> Self {
+        let mut functions: Vec<Function> = vec![];
+        let mut names = vec![];
+        for module in modules.iter().sorted_by_key(|module| &module.name) {
+            let mut exported = false;
+            for function in module
+                .statements
+                .iter()
+                .filter_map(|statement| function(&module.name, statement))
+            {
+                // Functions of different modules can be given the same name,
+                // such as `a_b` in `a` and `b` in `a/b`, of which only the
+                // first is included.
+                if functions.iter().all(|f| f.symbol != function.symbol) {
+                    functions.push(function);
+                    exported = true;
+                }
+            }
+            if exported {
+                names.push(module.name.join("/"));
+            }
+        }
+        Self {
+            package: package.into(),
+            modules: names,
+            functions,
+        }
+    }
+
+    /// The name of the C header, which unlike the names of the headers of
+    /// modules does not clash with that of the package's top level module.
+    pub fn header_name(&self) -> String {
+        format!("{}_c.h", self.package)
+    }
+
+    /// The name of the C++ source file implementing the functions.
+    pub fn implementation_name(&self) -> String {
+        format!("{}_c.cc", self.package)
+    }
+
+    pub fn header(&self) -> String {
+        let guard = format!("{}_C_H_", self.package.to_shouty_snake_case());
+        let mut header = format!(
+            "// The C interface of the {package} package.
+//
+// Strings returned by these functions are allocated with malloc, and are to
+// be freed by the caller.
+
+#ifndef {guard}
+#define {guard}
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern \"C\" {{
+#endif
+",
+            package = self.package
+        );
+        for function in &self.functions {
+            let _ = write!(header, "\n{};\n", function.signature());
+        }
+        let _ = write!(
+            header,
+            "
+#ifdef __cplusplus
+}} // extern \"C\"
+#endif
+
+#endif // {guard}
+"
+        );
+        header
+    }
+
+    pub fn implementation(&self) -> String {
+        let mut implementation = format!("#include \"{}\"\n", self.header_name());
+        let returns_strings = self
+            .functions
+            .iter()
+            .any(|function| function.result == Some(Type::String));
+        if returns_strings {
+            implementation.push_str("\n#include <cstdlib>\n#include <cstring>\n");
+        }
+        if !self.modules.is_empty() {
+            implementation.push('\n');
+        }
+        for module in &self.modules {
+            let _ = writeln!(implementation, "#include \"{module}.h\"");
+        }
+        if returns_strings {
+            implementation.push_str(
+                "
+namespace {
+
+char* ToCString(const gleam::String& value) {
+  char* result = static_cast<char*>(std::malloc(value->size() + 1));
+  std::memcpy(result, value->c_str(), value->size() + 1);
+  return result;
+}
+
+} // namespace
+",
+            );
+        }
+        implementation.push_str("\nextern \"C\" {\n");
+        for function in &self.functions {
+            let arguments = function
+                .parameters
+                .iter()
+                .map(|(name, type_)| match type_ {
+                    Type::String => format!("gleam::MakeRef<std::string>({name})"),
+                    _ => name.clone(),
+                })
+                .join(", ");
+            let call = format!("{}({arguments})", function.target);
+            let body = match function.result {
+                None => format!("{call};"),
+                Some(Type::String) => format!("return ToCString({call});"),
+                Some(_) => format!("return {call};"),
+            };
+            let _ = write!(
+                implementation,
+                "\n{} {{\n  {body}\n}}\n",
+                function.signature()
+            );
+        }
+        implementation.push_str("\n} // extern \"C\"\n");
+        implementation
+    }
+}
+
+/// The `extern "C"` function for a statement, if it is a public function
+/// whose arguments and result have a C representation.
+fn function(module: &[Name], statement: &TypedStatement) -> Option<Function> {
+    let TypedStatement::Fn {
+        name,
+        arguments,
+        return_type,
+        public: true,
+        ..
+    } = statement
+    else {
+        return None;
+    };
+    let result = if return_type.is_nil() {
+        None
+    } else {
+        Some(Type::from_type(return_type)?)
+    };
+    let parameters = arguments
+        .iter()
+        .enumerate()
+        .map(|(index, argument)| {
+            let name = match argument.names.get_variable_name() {
+                Some(name) => to_identifier(name.to_string()),
+                None => format!("arg{index}"),
+            };
+            Some((name, Type::from_type(&argument.type_)?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let namespace = module
+        .iter()
+        .map(|part| format!("::{}", to_identifier(part.to_snake_case())))
+        .join("");
+    Some(Function {
+        symbol: format!("{}_{name}", module.join("_")),
+        target: format!("{namespace}::{name}"),
+        parameters,
+        result,
+    })
+}
@@ -6,7 +6,7 @@ use heck::{ToShoutySnakeCase, ToSnakeCase};
 
 pub fn wrap_with_namespace_scope<'a>(doc: Document<'a>, module: &'a TypedModule) -> Document<'a> {
     let mut head = line();
-    let mut tail = line();
+    let mut tail = nil();
     for module_name_part in &module.name {
         let doc_part = Document::String(to_identifier(module_name_part.to_snake_case()));
         head = head
@@ -14,11 +14,10 @@ pub fn wrap_with_namespace_scope<'a>(doc: Document<'a>, module: &'a TypedModule)
             .append(doc_part.clone())
             .append(" {")
             .append(line());
-        tail = tail
-            .append("} // namespace ")
-            .append(doc_part)
-            .append(line());
+        // Namespaces are closed innermost first.
+        tail = docvec!("} // namespace ", doc_part, line()).append(tail);
     }
+    tail = line().append(tail);
     doc.surround(head.append(line()), line().append(tail))
 }
 
@@ -1,5 +1,5 @@
-use crate::ast::RecordConstructor;
 use crate::ast::RecordConstructorArg;
+use crate::ast::{Name, RecordConstructor};
 use crate::cplusplus::error::Error;
 use crate::cplusplus::symbolizer::Symbolizer;
 use crate::cplusplus::INDENT;
@@ -10,8 +10,8 @@ use itertools::Itertools;
 use std::sync::Arc;
 
 struct SharedFields {
-    names: im::HashSet<String>,
-    arguments: Vec<(String, RecordConstructorArg<Arc<Type>>)>,
+    names: im::HashSet<Name>,
+    arguments: Vec<(Name, RecordConstructorArg<Arc<Type>>)>,
 }
 
 enum StructType<'a> {
@@ -58,7 +58,7 @@ impl StructGenerator {
                 "struct ",
                 Document::String(name.to_owned()),
                 "$",
-                Document::String(constructor.name.to_owned()),
+                Document::String(constructor.name.to_owned().to_string()),
                 ";"
             ]);
         }
@@ -108,12 +108,11 @@ impl StructGenerator {
         typed_parameters: &'a [Arc<Type>],
     ) -> Result<Document<'b>, Error> {
         let mut constructor_args: Vec<Document<'b>> = vec![];
-        let mut super_constructor_args: Vec<Document<'b>> = vec![];
         let mut member_initializers: Vec<Document<'b>> = vec![];
         let mut members: Vec<Document<'b>> = vec![];
         for (i, field) in fields.iter().enumerate() {
             let name = Document::String(match &field.label {
-                Some(label) => label.clone(),
+                Some(label) => label.to_string(),
                 None => format!("_${}", i),
             });
             let typ = self.symbolizer.type_to_symbol(&field.type_)?;
@@ -123,16 +122,24 @@ impl StructGenerator {
                 .as_ref()
                 .map(|l| supertype.is_shared_field(l))
                 .unwrap_or_default();
-            if is_shared_member {
-                super_constructor_args.push(name);
-            } else {
+            if !is_shared_member {
                 members.push(docvec![typ.clone(), " ", name.clone(), ";"]);
                 member_initializers.push(docvec![name.clone(), "(", name.clone(), ")"]);
             }
         }
         let mut struct_name = Document::String(name.to_owned());
         let mut super_declaration = nil();
-        if let StructType::Variant { supertype_name, .. } = supertype {
+        if let StructType::Variant {
+            supertype_name,
+            shared,
+        } = supertype
+        {
+            // The supertype takes the shared fields in the order they are in
+            // the first variant, which may differ from this variant's order.
+            let super_constructor_args = shared
+                .arguments
+                .iter()
+                .map(|(name, _)| Document::String(name.to_string()));
             let super_name_doc = Document::String((*supertype_name).to_owned());
             let super_type_args = self.symbolizer.app_symbol_args(typed_parameters)?;
             let super_initializer = docvec![
@@ -187,14 +194,15 @@ impl StructGenerator {
         let (first, rest) = constructors.split_first().ok_or(Error::InternalError {
             message: format!("No variants for {}", name),
         })?;
-        let shared_arguments: Vec<(String, RecordConstructorArg<Arc<Type>>)> = first
+        let shared_arguments: Vec<(Name, RecordConstructorArg<Arc<Type>>)> = first
             .arguments
             .iter()
-            .enumerate()
-            .filter_map(|(idx, arg)| {
-                let is_common_arg = rest.iter().all(|other| match other.arguments.get(idx) {
-                    None => false,
-                    Some(other_arg) => arg.label == other_arg.label && arg.type_ == other_arg.type_,
+            .filter_map(|arg| {
+                // Shared fields may be at a different position in each variant
+                let is_common_arg = rest.iter().all(|other| {
+                    other.arguments.iter().any(|other_arg| {
+                        arg.label == other_arg.label && arg.type_ == other_arg.type_
+                    })
                 });
                 match &arg.label {
                     Some(name) if is_common_arg => Some((name.clone(), arg.clone())),
@@ -202,7 +210,7 @@ impl StructGenerator {
                 }
             })
             .collect();
-        let shared_argument_names: im::HashSet<String> = shared_arguments
+        let shared_argument_names: im::HashSet<Name> = shared_arguments
             .iter()
             .map(|(name, _)| name.clone())
             .collect();
@@ -1,4 +1,6 @@
+mod bit_strings;
 mod expression;
+mod extern_c;
 mod functions;
 mod list;
 mod patterns;
@@ -7,38 +9,72 @@ mod records;
 #[macro_export]
 macro_rules! assert_cpp {
     ($src:expr $(,)?) => {{
-        use crate::{
-            build::Origin,
-            cplusplus::{module_header, module_impl},
-            line_numbers::LineNumbers,
-            type_::{build_prelude, infer_module},
-            uid::UniqueIdGenerator,
-        };
-        use std::path::Path;
-        let (mut ast, _) = crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my".to_string(), "module".to_string()];
-        let mut modules = im::HashMap::new();
-        let ids = UniqueIdGenerator::new();
-        // DUPE: preludeinsertion
-        // TODO: Currently we do this here and also in the tests. It would be better
-        // to have one place where we create all this required state for use in each
-        // place.
-        let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
-        let ast = infer_module(
-            crate::build::Target::Native,
-            &ids,
-            ast,
-            Origin::Src,
-            "my_package",
-            &modules,
-            &mut vec![],
-        )
-        .expect("should successfully infer");
-        let mut output = String::new();
-        let line_numbers = LineNumbers::new($src);
-        module_header(&ast, &line_numbers, Path::new("test.h"), $src, &mut output).unwrap();
-        output.push_str("\n---\n");
-        module_impl(&ast, &line_numbers, Path::new("test.cc"), $src, &mut output).unwrap();
+        let output = $crate::cplusplus::tests::compile($src).expect("should compile to C++");
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
     }};
 }
+
+#[macro_export]
+macro_rules! assert_cpp_unimplemented {
+    ($src:expr, $message:expr $(,)?) => {{
+        match $crate::cplusplus::tests::compile($src) {
+            Err($crate::Error::CPlusPlus {
+                error: $crate::cplusplus::error::Error::Unimplemented { message },
+                ..
+            }) => assert_eq!(message, $message),
+            other => panic!("expected an unimplemented feature error, got {other:?}"),
+        }
+    }};
+}
+
+pub fn compile(src: &str) -> Result<String, crate::Error> {
+    use crate::{
+        build::{Origin, Target},
+        line_numbers::LineNumbers,
+        pretty::DEFAULT_LINE_LENGTH,
+        type_::{build_prelude, infer_module},
+        uid::UniqueIdGenerator,
+    };
+    use std::path::Path;
+
+    let arena = crate::ast::Arena::new();
+    let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+    ast.name = vec!["my".into(), "module".into()];
+    let mut modules = im::HashMap::new();
+    let ids = UniqueIdGenerator::new();
+    // DUPE: preludeinsertion
+    // TODO: Currently we do this here and also in the tests. It would be better
+    // to have one place where we create all this required state for use in each
+    // place.
+    let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+    let ast = infer_module(
+        Target::Native,
+        &ids,
+        ast,
+        Origin::Src,
+        "my_package",
+        &modules,
+        &mut vec![],
+    )
+    .expect("should successfully infer");
+    let mut output = String::new();
+    let line_numbers = LineNumbers::new(src);
+    super::module_header(
+        &ast,
+        &line_numbers,
+        Path::new("test.h"),
+        src,
+        DEFAULT_LINE_LENGTH,
+        &mut output,
+    )?;
+    output.push_str("\n---\n");
+    super::module_impl(
+        &ast,
+        &line_numbers,
+        Path::new("test.cc"),
+        src,
+        DEFAULT_LINE_LENGTH,
+        &mut output,
+    )?;
+    Ok(output)
+}
@@ -0,0 +1,89 @@
+use crate::{assert_cpp, assert_cpp_unimplemented};
+
+#[test]
+fn segments() {
+    assert_cpp!(
+        r#"
+pub fn go(x: Int, y: Float, z: String, rest: BitString) {
+  <<x, 2:int, y:float, z:utf8, rest:bit_string>>
+}
+"#
+    );
+}
+
+#[test]
+fn variable_sized_segment() {
+    assert_cpp!(
+        r#"
+pub fn go(payload: Int, len: Int) {
+  <<payload:size(len)>>
+}
+"#
+    );
+}
+
+#[test]
+fn unsupported_segment_option() {
+    assert_cpp_unimplemented!(
+        r#"
+pub fn go(x: String) {
+  <<x:utf16>>
+}
+"#,
+        "This bit string segment option are not yet supported"
+    );
+}
+
+#[test]
+fn match_segments() {
+    assert_cpp!(
+        r#"
+pub fn go(x: BitString) {
+  case x {
+    <<1, a, b:size(16), c:float>> -> a + b
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn match_variable_sized_segment() {
+    assert_cpp!(
+        r#"
+pub fn go(x: BitString) {
+  case x {
+    <<len, payload:binary-size(len)>> -> payload
+    _ -> x
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn match_sized_by_argument() {
+    assert_cpp!(
+        r#"
+pub fn go(x: BitString, len: Int) {
+  case x {
+    <<payload:size(len), _:binary>> -> payload
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn match_rest() {
+    assert_cpp!(
+        r#"
+pub fn go(x: BitString) {
+  let <<_, rest:binary>> = x
+  rest
+}
+"#
+    );
+}
@@ -86,3 +86,23 @@ pub fn birthday(person: Person) -> Person {
     "#
     )
 }
+
+#[test]
+fn int_literals() {
+    assert_cpp!(
+        r#"
+pub fn ints() -> Int {
+  1_000_000 + 007 + 0x1F + 0o755 + 0b1010_1010 - 12
+}"#
+    );
+}
+
+#[test]
+fn echo() {
+    assert_cpp!(
+        r#"
+pub fn main(x: Int) {
+  echo x + 1
+}"#,
+    );
+}
@@ -0,0 +1,149 @@
+use crate::{
+    build::{Origin, Target},
+    cplusplus::ExternC,
+    type_::{build_prelude, infer_module},
+    uid::UniqueIdGenerator,
+};
+
+macro_rules! assert_extern_c {
+    ($src:expr $(,)?) => {{
+        let output = extern_c(&[("my/module", $src)]);
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    }};
+}
+
+/// Type checks the modules in order, each of which can import those before
+/// it, and renders the header and implementation of their C interface.
+fn extern_c(sources: &[(&str, &str)]) -> String {
+    let ids = UniqueIdGenerator::new();
+    let mut modules = im::HashMap::new();
+    let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+    let mut typed = vec![];
+    for (name, src) in sources {
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+        ast.name = name.split('/').map(Into::into).collect();
+        let module = infer_module(
+            Target::Native,
+            &ids,
+            ast,
+            Origin::Src,
+            "my_package",
+            &modules,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let _ = modules.insert(name.to_string(), module.type_info.clone());
+        typed.push(module);
+    }
+    let extern_c = ExternC::new("my_package", &typed.iter().collect::<Vec<_>>());
+    format!("{}\n---\n{}", extern_c.header(), extern_c.implementation())
+}
+
+#[test]
+fn scalars() {
+    assert_extern_c!(
+        r#"
+pub fn add(x: Int, y: Int) -> Int {
+  x + y
+}
+
+pub fn scale(value: Float, by factor: Float) -> Float {
+  value *. factor
+}
+
+pub fn negate(value: Bool) -> Bool {
+  !value
+}
+
+pub fn answer() -> Int {
+  42
+}
+"#
+    );
+}
+
+#[test]
+fn strings_and_nil() {
+    assert_extern_c!(
+        r#"
+pub fn greet(name: String) -> String {
+  "Hello, " <> name
+}
+
+pub fn log(message: String) -> Nil {
+  Nil
+}
+"#
+    );
+}
+
+#[test]
+fn unsupported_functions_are_not_included() {
+    assert_extern_c!(
+        r#"
+pub type Box {
+  Box(Int)
+}
+
+pub fn unbox(box: Box) -> Int {
+  todo
+}
+
+pub fn identity(value: a) -> a {
+  value
+}
+
+pub fn numbers() -> List(Int) {
+  [1, 2]
+}
+
+fn private(value: Int) -> Int {
+  value
+}
+
+pub fn supported(value: Int) -> Int {
+  private(value)
+}
+"#
+    );
+}
+
+#[test]
+fn keywords_are_escaped() {
+    assert_extern_c!(
+        r#"
+pub fn convert(int: Int, _: Float) -> Int {
+  int
+}
+"#
+    );
+}
+
+#[test]
+fn functions_of_several_modules() {
+    insta::assert_snapshot!(extern_c(&[
+        (
+            "my_package/util",
+            r#"
+pub fn double(x: Int) -> Int {
+  x * 2
+}
+"#,
+        ),
+        (
+            "my_package",
+            r#"
+import my_package/util
+
+pub fn quadruple(x: Int) -> Int {
+  util.double(util.double(x))
+}
+
+pub fn util_double(x: Int) -> Int {
+  x
+}
+"#,
+        ),
+    ]));
+}
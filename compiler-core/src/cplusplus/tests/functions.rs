@@ -1,4 +1,4 @@
-use crate::assert_cpp;
+use crate::{assert_cpp, assert_cpp_unimplemented};
 
 #[test]
 fn exported_functions() {
@@ -44,6 +44,31 @@ fn do_stuff(x, y) {
     )
 }
 
+#[test]
+fn operator_sections() {
+    assert_cpp!(
+        r#"
+fn go() {
+  _ * 2 + _
+}
+    "#
+    );
+}
+
+#[test]
+fn capture_with_multiple_holes() {
+    assert_cpp!(
+        r#"
+fn add(x, y, z) {
+  x + y + z
+}
+fn go() {
+  add(_, 1, _)
+}
+    "#
+    )
+}
+
 #[test]
 fn multiple_module_functions() {
     assert_cpp!(
@@ -85,3 +110,63 @@ fn add(a, b) {
         "#
     )
 }
+
+#[test]
+fn external_function_includes() {
+    assert_cpp!(
+        r#"
+external fn sqrt(Float) -> Float = "<cmath>" "std::sqrt"
+external fn log(String) -> Nil = "logger.h" "log"
+external fn abs(Float) -> Float = "<cmath>" "std::abs"
+        "#
+    )
+}
+
+#[test]
+fn external_function_call() {
+    assert_cpp!(
+        r#"
+external fn sqrt(Float) -> Float = "<cmath>" "std::sqrt"
+
+pub fn main() {
+  sqrt(4.0)
+}
+        "#
+    )
+}
+
+#[test]
+fn rescue() {
+    assert_cpp!(
+        r#"
+pub fn main() -> Result(Int, CrashReason) {
+  rescue(fn() { 1 })
+}
+"#
+    );
+}
+
+#[test]
+fn qualified_rescue() {
+    assert_cpp!(
+        r#"
+import gleam
+
+pub fn main() -> Result(Int, CrashReason) {
+  gleam.rescue(fn() { todo })
+}
+"#
+    );
+}
+
+#[test]
+fn rescue_without_calling_it_is_unimplemented() {
+    assert_cpp_unimplemented!(
+        r#"
+pub fn main() {
+  rescue
+}
+"#,
+        "References to `rescue` that are not calls are not yet supported"
+    );
+}
@@ -12,3 +12,27 @@ pub fn negate(b: Bool) -> Bool {
 }"#
     );
 }
+
+#[test]
+fn assert_pattern() {
+    assert_cpp!(
+        r#"
+pub fn zero(x: Int) -> Int {
+  assert 0 = x
+  x
+}
+"#
+    );
+}
+
+#[test]
+fn assert_pattern_with_message() {
+    assert_cpp!(
+        r#"
+pub fn zero(x: Int) -> Int {
+  assert 0 = x as "x should always be zero"
+  x
+}
+"#
+    );
+}
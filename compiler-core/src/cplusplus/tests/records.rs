@@ -62,6 +62,38 @@ pub fn make_box(v: inner) {
     );
 }
 
+#[test]
+fn shared_fields_in_different_positions() {
+    assert_cpp!(
+        r#"
+pub type Animal {
+  Cat(likes_milk: Bool, name: String, age: Int)
+  Dog(age: Int, name: String)
+}
+
+pub fn name(animal: Animal) {
+  animal.name
+}
+"#,
+    );
+}
+
+#[test]
+fn shared_field_at_a_different_index_in_each_constructor() {
+    assert_cpp!(
+        r#"
+pub type Animal {
+  Cat(likes_milk: Bool, name: String, age: Int)
+  Dog(age: Int, name: String)
+}
+
+pub fn age(animal: Animal) {
+  animal.age
+}
+"#,
+    );
+}
+
 #[test]
 fn generic_multiple_variant() {
     assert_cpp!(
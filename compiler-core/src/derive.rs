@@ -0,0 +1,1002 @@
+//! Generating functions for custom types, as requested with the `@derive`
+//! attribute.
+//!
+//! ```gleam
+//! @derive(predicates)
+//! pub type Shape {
+//!   Square(size: Int)
+//!   Circle(radius: Int)
+//! }
+//! ```
+//!
+//! Each name given to the attribute is that of a generator, which writes the
+//! definitions of new functions for the type. These are added to the module
+//! after it is parsed, so they are type checked and compiled the same as the
+//! functions written by hand. The generators built into the compiler are:
+//!
+//! - `predicates`, a function for each constructor that returns whether a
+//!   value was made with it, such as `is_circle(shape: Shape) -> Bool`.
+//! - `setters`, a function for each labelled field of a type with a single
+//!   constructor that returns a copy of a value with the field changed, such
+//!   as `with_size(shape: Shape, size: Int) -> Shape`.
+//! - `json`, functions that convert a value to JSON and decode it again, such
+//!   as `shape_to_json(value: Shape) -> json.Json` and
+//!   `shape_decoder(value: dynamic.Dynamic) -> Result(Shape, List(dynamic.DecodeError))`.
+//!   The package must depend on `gleam_json` and `gleam_stdlib`.
+
+use std::{fmt::Debug, sync::Arc};
+
+use heck::ToSnakeCase;
+
+use crate::{
+    ast::{
+        Arena, ArenaBox, Arg, ArgNames, CallArg, Clause, Deprecation, Name, Pattern,
+        RecordConstructor, RecordUpdateSpread, SrcSpan, Statement, SuppressionScope, TargetGroup,
+        TypeAst, UntypedExpr, UntypedModule, UntypedRecordUpdateArg, UntypedStatement,
+        WarningSuppression,
+    },
+    type_::WarningKind,
+};
+
+/// The definition of a custom type that functions are derived for.
+#[derive(Debug, Clone, Copy)]
+pub struct CustomType<'a> {
+    /// The location of the head of the definition, which the definitions of
+    /// the generated functions are given.
+    pub location: SrcSpan,
+    pub name: &'a str,
+    pub parameters: &'a [Name],
+    pub public: bool,
+    pub opaque: bool,
+    pub constructors: &'a [RecordConstructor<()>],
+}
+
+impl CustomType<'_> {
+    /// The type, as written in annotations.
+    pub fn annotation(&self) -> TypeAst {
+        TypeAst::Constructor {
+            location: self.location,
+            module: None,
+            name: self.name.into(),
+            arguments: self
+                .parameters
+                .iter()
+                .map(|name| TypeAst::Var {
+                    location: self.location,
+                    name: name.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Writes the definitions of functions for custom types.
+pub trait Generator: Debug + Send + Sync {
+    /// The name used for the generator in the `@derive` attribute.
+    fn name(&self) -> &'static str;
+
+    /// The functions for the type, or the reason they cannot be generated
+    /// for it. Their expressions are allocated in the arena of the module.
+    fn generate<'a>(
+        &self,
+        type_: CustomType<'_>,
+        arena: &'a Arena,
+    ) -> Result<Vec<UntypedStatement<'a>>, String>;
+}
+
+/// The generators that can be named in `@derive` attributes.
+#[derive(Debug, Clone)]
+pub struct Generators {
+    generators: Vec<Arc<dyn Generator>>,
+}
+
+impl Default for Generators {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl Generators {
+    /// The generators built into the compiler.
+    pub fn builtin() -> Self {
+        Self {
+            generators: vec![Arc::new(Predicates), Arc::new(Setters), Arc::new(Json)],
+        }
+    }
+
+    /// Adds a generator, replacing any with the same name.
+    pub fn register(&mut self, generator: Arc<dyn Generator>) {
+        self.generators
+            .retain(|existing| existing.name() != generator.name());
+        self.generators.push(generator);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Generator> {
+        self.generators
+            .iter()
+            .find(|generator| generator.name() == name)
+            .map(|generator| generator.as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.generators
+            .iter()
+            .map(|generator| generator.name())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// The location of the generator's name in the `@derive` attribute.
+    pub location: SrcSpan,
+    pub generator: String,
+    pub problem: Problem,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    UnknownGenerator { known: Vec<&'static str> },
+    Unsupported { reason: String },
+}
+
+/// Adds the functions derived for the custom types of the module to the
+/// module, each alongside its type.
+pub fn expand<'a>(
+    module: &mut UntypedModule<'a>,
+    arena: &'a Arena,
+    generators: &Generators,
+) -> Result<(), Error> {
+    // Generators may import modules, which are only added when the module
+    // does not import them already.
+    let mut imported: Vec<(Vec<Name>, Option<Name>)> = module
+        .statements
+        .iter()
+        .flat_map(|group| match group {
+            TargetGroup::Any(statements) | TargetGroup::Only(_, statements) => statements,
+        })
+        .filter_map(|statement| match statement {
+            Statement::Import {
+                module, as_name, ..
+            } => Some((module.clone(), as_name.clone())),
+            _ => None,
+        })
+        .collect();
+    for group in &mut module.statements {
+        let statements = match group {
+            TargetGroup::Any(statements) | TargetGroup::Only(_, statements) => statements,
+        };
+        let mut derived = vec![];
+        for statement in statements.iter() {
+            let (type_, derives) = match statement {
+                Statement::CustomType {
+                    location,
+                    name,
+                    parameters,
+                    public,
+                    opaque,
+                    constructors,
+                    derives,
+                    ..
+                } if !derives.is_empty() => (
+                    CustomType {
+                        location: *location,
+                        name,
+                        parameters,
+                        public: *public,
+                        opaque: *opaque,
+                        constructors,
+                    },
+                    derives,
+                ),
+                _ => continue,
+            };
+            for derive in derives {
+                let error = |problem| Error {
+                    location: derive.location,
+                    generator: derive.name.to_string(),
+                    problem,
+                };
+                let generator = generators.get(&derive.name).ok_or_else(|| {
+                    error(Problem::UnknownGenerator {
+                        known: generators.names(),
+                    })
+                })?;
+                let functions = generator
+                    .generate(type_, arena)
+                    .map_err(|reason| error(Problem::Unsupported { reason }))?;
+                for function in functions {
+                    if let Statement::Import {
+                        module, as_name, ..
+                    } = &function
+                    {
+                        let import = (module.clone(), as_name.clone());
+                        if imported.contains(&import) {
+                            continue;
+                        }
+                        imported.push(import);
+                    }
+                    derived.push(function);
+                }
+            }
+            // The functions of private types need not all be used
+            if !type_.public {
+                module.warning_suppressions.push(WarningSuppression {
+                    location: type_.location,
+                    kinds: vec![WarningKind::UnusedPrivateFunction],
+                    scope: SuppressionScope::Definition(type_.location),
+                });
+            }
+        }
+        statements.extend(derived);
+    }
+    Ok(())
+}
+
+/// Generates `is_<constructor>` functions.
+#[derive(Debug, Clone, Copy)]
+struct Predicates;
+
+impl Generator for Predicates {
+    fn name(&self) -> &'static str {
+        "predicates"
+    }
+
+    fn generate<'a>(
+        &self,
+        type_: CustomType<'_>,
+        _arena: &'a Arena,
+    ) -> Result<Vec<UntypedStatement<'a>>, String> {
+        let location = type_.location;
+        let variable = |name: &str| UntypedExpr::Var {
+            location,
+            name: name.into(),
+        };
+        let functions = type_
+            .constructors
+            .iter()
+            .map(|constructor| {
+                let matches = Pattern::Constructor {
+                    location,
+                    name: constructor.name.clone(),
+                    arguments: vec![],
+                    module: None,
+                    constructor: (),
+                    with_spread: !constructor.arguments.is_empty(),
+                    type_: (),
+                };
+                let mut clauses = vec![clause(location, matches, variable("True"))];
+                if type_.constructors.len() > 1 {
+                    let others = Pattern::Discard {
+                        name: "_".into(),
+                        location,
+                    };
+                    clauses.push(clause(location, others, variable("False")));
+                }
+                function(
+                    type_,
+                    format!("is_{}", constructor.name.to_snake_case()),
+                    format!(
+                        "Returns whether the `{}` value was made with `{}`.",
+                        type_.name, constructor.name
+                    ),
+                    vec![argument(location, "value", type_.annotation())],
+                    bool_annotation(location),
+                    UntypedExpr::Case {
+                        location,
+                        subjects: vec![variable("value")],
+                        clauses,
+                    },
+                )
+            })
+            .collect();
+        Ok(functions)
+    }
+}
+
+/// Generates `with_<field>` functions.
+#[derive(Debug, Clone, Copy)]
+struct Setters;
+
+impl Generator for Setters {
+    fn name(&self) -> &'static str {
+        "setters"
+    }
+
+    fn generate<'a>(
+        &self,
+        type_: CustomType<'_>,
+        arena: &'a Arena,
+    ) -> Result<Vec<UntypedStatement<'a>>, String> {
+        let constructor = match type_.constructors {
+            [constructor] => constructor,
+            _ => return Err("Setters can only be derived for types with one constructor.".into()),
+        };
+        let location = type_.location;
+        let variable = |name: &str| UntypedExpr::Var {
+            location,
+            name: name.into(),
+        };
+        let functions: Vec<_> = constructor
+            .arguments
+            .iter()
+            .filter_map(|field| Some((field.label.as_ref()?, field)))
+            .map(|(label, field)| {
+                function(
+                    type_,
+                    format!("with_{}", label),
+                    format!(
+                        "Returns a copy of the `{}` value with its `{}` field changed.",
+                        type_.name, label
+                    ),
+                    vec![
+                        argument(location, "record", type_.annotation()),
+                        argument(location, "value", field.ast.clone()),
+                    ],
+                    type_.annotation(),
+                    UntypedExpr::RecordUpdate {
+                        location,
+                        constructor: ArenaBox::new_in(variable(&constructor.name), arena),
+                        spread: RecordUpdateSpread {
+                            base: ArenaBox::new_in(variable("record"), arena),
+                            location,
+                        },
+                        arguments: vec![UntypedRecordUpdateArg {
+                            label: label.clone(),
+                            path: vec![],
+                            location,
+                            value: variable("value"),
+                        }],
+                    },
+                )
+            })
+            .collect();
+        if functions.is_empty() {
+            return Err("Setters can only be derived for types with labelled fields.".into());
+        }
+        Ok(functions)
+    }
+}
+
+/// Generates `<type>_to_json` and `<type>_decoder` functions, which convert
+/// values to JSON with the `gleam/json` module of the `gleam_json` package and
+/// back with the `gleam/dynamic` module of `gleam_stdlib`.
+///
+/// A type with one constructor is encoded as an object of its fields. A type
+/// with several constructors is encoded as an object of the fields of the
+/// constructor used, along with its name in a `type` field.
+#[derive(Debug, Clone, Copy)]
+struct Json;
+
+/// The largest number of fields `gleam/dynamic` has a `decode` function for.
+const MAX_DECODED_FIELDS: usize = 9;
+
+impl Generator for Json {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn generate<'a>(
+        &self,
+        type_: CustomType<'_>,
+        arena: &'a Arena,
+    ) -> Result<Vec<UntypedStatement<'a>>, String> {
+        if !type_.parameters.is_empty() {
+            return Err("JSON functions cannot be derived for types with parameters.".into());
+        }
+        let location = type_.location;
+        let ast = Ast { location, arena };
+        let snake_name = type_.name.to_snake_case();
+        let tagged = type_.constructors.len() > 1;
+
+        let mut encode_clauses = Vec::with_capacity(type_.constructors.len());
+        let mut decode_clauses = Vec::with_capacity(type_.constructors.len() + 2);
+        for constructor in type_.constructors {
+            let mut labels = Vec::with_capacity(constructor.arguments.len());
+            let mut fields = vec![];
+            let mut decoders = vec![];
+            if tagged {
+                fields.push(ast.tuple(vec![
+                    ast.string("type"),
+                    ast.call(
+                        ast.access("json", "string"),
+                        vec![ast.string(&constructor.name)],
+                    ),
+                ]));
+            }
+            for field in &constructor.arguments {
+                let label = field.label.as_ref().ok_or_else(|| {
+                    "JSON functions can only be derived for types with labelled fields.".to_string()
+                })?;
+                let variable = format!("{}_", label);
+                fields.push(ast.tuple(vec![
+                    ast.string(label),
+                    ast.call(encoder(ast, &field.ast)?, vec![ast.var(&variable)]),
+                ]));
+                decoders.push(ast.call(
+                    ast.access("dynamic", "field"),
+                    vec![ast.string(label), decoder(ast, &field.ast)?],
+                ));
+                labels.push((label, variable));
+            }
+            if decoders.len() > MAX_DECODED_FIELDS {
+                return Err(format!(
+                    "JSON functions can only be derived for constructors with at most {} fields.",
+                    MAX_DECODED_FIELDS
+                ));
+            }
+
+            let pattern = Pattern::Constructor {
+                location,
+                name: constructor.name.clone(),
+                arguments: labels
+                    .iter()
+                    .map(|(label, variable)| {
+                        let variable = Pattern::Var {
+                            location,
+                            name: variable.as_str().into(),
+                        };
+                        ast.argument(Some((*label).clone()), variable)
+                    })
+                    .collect(),
+                module: None,
+                constructor: (),
+                with_spread: false,
+                type_: (),
+            };
+            let object = ast.call(ast.access("json", "object"), vec![ast.list(fields)]);
+            encode_clauses.push(clause(location, pattern, object));
+
+            let decoded = if decoders.is_empty() {
+                ast.call(ast.var("Ok"), vec![ast.var(&constructor.name)])
+            } else {
+                let decode = format!("decode{}", decoders.len());
+                let mut arguments = vec![ast.var(&constructor.name)];
+                arguments.extend(decoders);
+                let decoder = ast.call(ast.access("dynamic", &decode), arguments);
+                ast.call(decoder, vec![ast.var("value")])
+            };
+            if tagged {
+                let tag = ast.result_pattern(
+                    "Ok",
+                    Pattern::String {
+                        location,
+                        value: constructor.name.to_string(),
+                    },
+                );
+                decode_clauses.push(clause(location, tag, decoded));
+            } else {
+                let anything = Pattern::Discard {
+                    name: "_".into(),
+                    location,
+                };
+                decode_clauses.push(clause(location, anything, decoded));
+            }
+        }
+
+        // Values of tagged types are decoded by first decoding the name of
+        // their constructor
+        let decode_body = if tagged {
+            let unknown = ast.result_pattern(
+                "Ok",
+                Pattern::Var {
+                    location,
+                    name: "other".into(),
+                },
+            );
+            let error = ast.labelled_call(
+                ast.access("dynamic", "DecodeError"),
+                vec![
+                    ("expected", ast.string(type_.name)),
+                    ("found", ast.var("other")),
+                    ("path", ast.list(vec![ast.string("type")])),
+                ],
+            );
+            let unknown_error = ast.call(ast.var("Error"), vec![ast.list(vec![error])]);
+            decode_clauses.push(clause(location, unknown, unknown_error));
+            let failed = ast.result_pattern(
+                "Error",
+                Pattern::Var {
+                    location,
+                    name: "errors".into(),
+                },
+            );
+            let failed_error = ast.call(ast.var("Error"), vec![ast.var("errors")]);
+            decode_clauses.push(clause(location, failed, failed_error));
+            let tag_decoder = ast.call(
+                ast.access("dynamic", "field"),
+                vec![ast.string("type"), ast.access("dynamic", "string")],
+            );
+            UntypedExpr::Case {
+                location,
+                subjects: vec![ast.call(tag_decoder, vec![ast.var("value")])],
+                clauses: decode_clauses,
+            }
+        } else {
+            UntypedExpr::Case {
+                location,
+                subjects: vec![ast.var("value")],
+                clauses: decode_clauses,
+            }
+        };
+
+        let to_json = function(
+            type_,
+            format!("{}_to_json", snake_name),
+            format!("Converts a `{}` value to JSON.", type_.name),
+            vec![argument(location, "value", type_.annotation())],
+            ast.type_(Some("json"), "Json", vec![]),
+            UntypedExpr::Case {
+                location,
+                subjects: vec![ast.var("value")],
+                clauses: encode_clauses,
+            },
+        );
+        let decode_error = ast.type_(Some("dynamic"), "DecodeError", vec![]);
+        let decoder = function(
+            type_,
+            format!("{}_decoder", snake_name),
+            format!(
+                "Decodes a `{}` value from JSON, as converted by `{}_to_json`.",
+                type_.name, snake_name
+            ),
+            vec![argument(
+                location,
+                "value",
+                ast.type_(Some("dynamic"), "Dynamic", vec![]),
+            )],
+            ast.type_(
+                None,
+                "Result",
+                vec![
+                    type_.annotation(),
+                    ast.type_(None, "List", vec![decode_error]),
+                ],
+            ),
+            decode_body,
+        );
+        Ok(vec![
+            import(location, "gleam/json"),
+            import(location, "gleam/dynamic"),
+            to_json,
+            decoder,
+        ])
+    }
+}
+
+/// A function that converts a value of the type to JSON.
+fn encoder<'a>(ast: Ast<'a>, type_: &TypeAst) -> Result<UntypedExpr<'a>, String> {
+    let (module, name, arguments) = json_type(type_)?;
+    let argument = |name: &str| {
+        let argument = arguments.first().ok_or_else(|| unsupported(type_))?;
+        let element = encoder(ast, argument)?;
+        let body = ast.call(ast.access("json", name), vec![ast.var("element"), element]);
+        Ok(UntypedExpr::Fn {
+            location: ast.location,
+            is_capture: false,
+            arguments: vec![Arg {
+                names: ArgNames::Named {
+                    name: "element".into(),
+                },
+                location: ast.location,
+                annotation: None,
+                type_: (),
+                doc: None,
+            }],
+            body: ArenaBox::new_in(body, ast.arena),
+            return_annotation: None,
+        })
+    };
+    match (module, name.as_str(), arguments.len()) {
+        (None, "Int", 0) => Ok(ast.access("json", "int")),
+        (None, "Float", 0) => Ok(ast.access("json", "float")),
+        (None, "String", 0) => Ok(ast.access("json", "string")),
+        (None, "Bool", 0) => Ok(ast.access("json", "bool")),
+        (None, "List", 1) => argument("array"),
+        (_, "Option", 1) => argument("nullable"),
+        (module, _, 0) => {
+            let function = format!("{}_to_json", name.to_snake_case());
+            Ok(match module {
+                Some(module) => ast.access(module, &function),
+                None => ast.var(&function),
+            })
+        }
+        _ => Err(unsupported(type_)),
+    }
+}
+
+/// A function that decodes a value of the type.
+fn decoder<'a>(ast: Ast<'a>, type_: &TypeAst) -> Result<UntypedExpr<'a>, String> {
+    let (module, name, arguments) = json_type(type_)?;
+    let argument = |name: &str| {
+        let argument = arguments.first().ok_or_else(|| unsupported(type_))?;
+        let element = decoder(ast, argument)?;
+        Ok(ast.call(ast.access("dynamic", name), vec![element]))
+    };
+    match (module, name.as_str(), arguments.len()) {
+        (None, "Int", 0) => Ok(ast.access("dynamic", "int")),
+        (None, "Float", 0) => Ok(ast.access("dynamic", "float")),
+        (None, "String", 0) => Ok(ast.access("dynamic", "string")),
+        (None, "Bool", 0) => Ok(ast.access("dynamic", "bool")),
+        (None, "List", 1) => argument("list"),
+        (_, "Option", 1) => argument("optional"),
+        (module, _, 0) => {
+            let function = format!("{}_decoder", name.to_snake_case());
+            Ok(match module {
+                Some(module) => ast.access(module, &function),
+                None => ast.var(&function),
+            })
+        }
+        _ => Err(unsupported(type_)),
+    }
+}
+
+/// The module, name and arguments of a type that can be converted to JSON.
+fn json_type(type_: &TypeAst) -> Result<(Option<&str>, &Name, &[TypeAst]), String> {
+    match type_ {
+        TypeAst::Constructor {
+            module,
+            name,
+            arguments,
+            ..
+        } => Ok((module.as_deref(), name, arguments)),
+        TypeAst::Fn { .. } | TypeAst::Var { .. } | TypeAst::Tuple { .. } | TypeAst::Hole { .. } => {
+            Err(unsupported(type_))
+        }
+    }
+}
+
+fn unsupported(type_: &TypeAst) -> String {
+    let kind = match type_ {
+        TypeAst::Fn { .. } => "functions",
+        TypeAst::Tuple { .. } => "tuples",
+        TypeAst::Var { .. } | TypeAst::Hole { .. } => "type variables",
+        TypeAst::Constructor { .. } => "types with parameters other than `List` and `Option`",
+    };
+    format!(
+        "JSON functions cannot be derived for fields that are {}.",
+        kind
+    )
+}
+
+/// Builds expressions and annotations all at the same location.
+#[derive(Debug, Clone, Copy)]
+struct Ast<'a> {
+    location: SrcSpan,
+    arena: &'a Arena,
+}
+
+impl<'a> Ast<'a> {
+    fn var(self, name: &str) -> UntypedExpr<'a> {
+        UntypedExpr::Var {
+            location: self.location,
+            name: name.into(),
+        }
+    }
+
+    /// A value defined in an imported module, such as `json.int`.
+    fn access(self, module: &str, name: &str) -> UntypedExpr<'a> {
+        UntypedExpr::FieldAccess {
+            location: self.location,
+            label: name.into(),
+            container: ArenaBox::new_in(self.var(module), self.arena),
+        }
+    }
+
+    fn string(self, value: &str) -> UntypedExpr<'a> {
+        UntypedExpr::String {
+            location: self.location,
+            value: value.into(),
+        }
+    }
+
+    fn list(self, elements: Vec<UntypedExpr<'a>>) -> UntypedExpr<'a> {
+        UntypedExpr::List {
+            location: self.location,
+            elements,
+            tail: None,
+        }
+    }
+
+    fn tuple(self, elems: Vec<UntypedExpr<'a>>) -> UntypedExpr<'a> {
+        UntypedExpr::Tuple {
+            location: self.location,
+            elems,
+        }
+    }
+
+    fn call(self, fun: UntypedExpr<'a>, arguments: Vec<UntypedExpr<'a>>) -> UntypedExpr<'a> {
+        UntypedExpr::Call {
+            location: self.location,
+            fun: ArenaBox::new_in(fun, self.arena),
+            arguments: arguments
+                .into_iter()
+                .map(|value| self.argument(None, value))
+                .collect(),
+        }
+    }
+
+    fn labelled_call(
+        self,
+        fun: UntypedExpr<'a>,
+        arguments: Vec<(&str, UntypedExpr<'a>)>,
+    ) -> UntypedExpr<'a> {
+        UntypedExpr::Call {
+            location: self.location,
+            fun: ArenaBox::new_in(fun, self.arena),
+            arguments: arguments
+                .into_iter()
+                .map(|(label, value)| self.argument(Some(label.into()), value))
+                .collect(),
+        }
+    }
+
+    fn argument<A>(self, label: Option<Name>, value: A) -> CallArg<A> {
+        CallArg {
+            label,
+            location: self.location,
+            value,
+            implicit: false,
+        }
+    }
+
+    /// A pattern matching `Ok` or `Error` with the given pattern inside.
+    fn result_pattern(self, name: &str, inner: Pattern<(), ()>) -> Pattern<(), ()> {
+        Pattern::Constructor {
+            location: self.location,
+            name: name.into(),
+            arguments: vec![self.argument(None, inner)],
+            module: None,
+            constructor: (),
+            with_spread: false,
+            type_: (),
+        }
+    }
+
+    fn type_(self, module: Option<&str>, name: &str, arguments: Vec<TypeAst>) -> TypeAst {
+        TypeAst::Constructor {
+            location: self.location,
+            module: module.map(Name::from),
+            name: name.into(),
+            arguments,
+        }
+    }
+}
+
+/// An import of a module used by generated functions. Modules imported by
+/// the module already are not imported again.
+fn import<'a>(location: SrcSpan, module: &str) -> UntypedStatement<'a> {
+    Statement::Import {
+        location,
+        module: module.split('/').map(Name::from).collect(),
+        as_name: None,
+        unqualified: vec![],
+        package: (),
+    }
+}
+
+fn function<'a>(
+    type_: CustomType<'_>,
+    name: String,
+    doc: String,
+    arguments: Vec<Arg<()>>,
+    return_annotation: TypeAst,
+    body: UntypedExpr<'a>,
+) -> UntypedStatement<'a> {
+    Statement::Fn {
+        location: type_.location,
+        end_position: type_.location.end,
+        name: name.into(),
+        arguments,
+        body,
+        public: type_.public,
+        return_annotation: Some(return_annotation),
+        return_type: (),
+        doc: Some(doc),
+        deprecation: Deprecation::NotDeprecated,
+        externals: vec![],
+    }
+}
+
+fn argument(location: SrcSpan, name: &str, annotation: TypeAst) -> Arg<()> {
+    Arg {
+        names: ArgNames::Named { name: name.into() },
+        location,
+        annotation: Some(annotation),
+        type_: (),
+        doc: None,
+    }
+}
+
+fn clause(
+    location: SrcSpan,
+    pattern: Pattern<(), ()>,
+    then: UntypedExpr<'_>,
+) -> Clause<UntypedExpr<'_>, (), (), ()> {
+    Clause {
+        location,
+        pattern: vec![pattern],
+        alternative_patterns: vec![],
+        guard: None,
+        then,
+    }
+}
+
+fn bool_annotation(location: SrcSpan) -> TypeAst {
+    TypeAst::Constructor {
+        location,
+        module: None,
+        name: "Bool".into(),
+        arguments: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::PackageConfig, Compiler};
+
+    fn check(src: &str) -> crate::Result<crate::Compiled> {
+        Compiler::new(PackageConfig::default())
+            .add_source("app", src)
+            .check()
+    }
+
+    #[test]
+    fn derive_predicates_and_setters() {
+        let checked = check(
+            "
+@derive(predicates, setters)
+type Cat(a) {
+  Cat(name: String, toy: a)
+}
+
+@derive(predicates)
+pub type Shape {
+  Square(size: Int)
+  Dot
+}
+
+pub fn main() {
+  let cat = Cat(\"Nubi\", 1) |> with_name(\"Biffy\") |> with_toy(2)
+  #(is_cat(cat), is_square(Dot), is_dot(Dot), cat.name)
+}
+",
+        )
+        .expect("check");
+        // The unused functions of the private type are not warned about
+        assert_eq!(checked.warnings, vec![]);
+        let module = &checked.modules.first().expect("module").ast.type_info;
+        // Functions are public when their type is
+        let public = |name: &str| module.values.contains_key(name);
+        assert!(public("is_square"));
+        assert!(public("is_dot"));
+        assert!(!public("with_name"));
+        assert!(!public("is_cat"));
+    }
+
+    #[test]
+    fn unknown_generator() {
+        let error = check("@derive(yaml)\npub type A { A }").expect_err("check");
+        match error {
+            crate::Error::Derive { error, .. } => {
+                assert_eq!(error.generator, "yaml");
+                assert_eq!(error.location, SrcSpan { start: 8, end: 12 });
+                assert_eq!(
+                    error.problem,
+                    Problem::UnknownGenerator {
+                        known: vec!["predicates", "setters", "json"]
+                    }
+                );
+            }
+            error => panic!("unexpected error {error:?}"),
+        }
+    }
+
+    #[test]
+    fn unsupported_type() {
+        let error = check("@derive(setters)\npub type A { A B }").expect_err("check");
+        assert!(matches!(
+            error,
+            crate::Error::Derive {
+                error: Error {
+                    problem: Problem::Unsupported { .. },
+                    ..
+                },
+                ..
+            }
+        ));
+    }
+
+    const JSON: &str = r#"
+import gleam/option.{Option}
+pub external type Json
+pub external fn object(List(#(String, Json))) -> Json = "j" "object"
+pub external fn string(String) -> Json = "j" "string"
+pub external fn int(Int) -> Json = "j" "int"
+pub external fn float(Float) -> Json = "j" "float"
+pub external fn bool(Bool) -> Json = "j" "bool"
+pub external fn array(List(a), fn(a) -> Json) -> Json = "j" "array"
+pub external fn nullable(Option(a), fn(a) -> Json) -> Json = "j" "nullable"
+"#;
+
+    const DYNAMIC: &str = r#"
+import gleam/option.{Option}
+pub external type Dynamic
+pub type DecodeError {
+  DecodeError(expected: String, found: String, path: List(String))
+}
+pub type Decoder(t) = fn(Dynamic) -> Result(t, List(DecodeError))
+pub external fn string(Dynamic) -> Result(String, List(DecodeError)) = "d" "string"
+pub external fn int(Dynamic) -> Result(Int, List(DecodeError)) = "d" "int"
+pub external fn float(Dynamic) -> Result(Float, List(DecodeError)) = "d" "float"
+pub external fn bool(Dynamic) -> Result(Bool, List(DecodeError)) = "d" "bool"
+pub external fn list(Decoder(t)) -> Decoder(List(t)) = "d" "list"
+pub external fn optional(Decoder(t)) -> Decoder(Option(t)) = "d" "optional"
+pub external fn field(String, Decoder(t)) -> Decoder(t) = "d" "field"
+pub external fn decode1(fn(a) -> t, Decoder(a)) -> Decoder(t) = "d" "decode1"
+pub external fn decode2(fn(a, b) -> t, Decoder(a), Decoder(b)) -> Decoder(t) = "d" "decode2"
+"#;
+
+    fn check_json(src: &str) -> crate::Result<crate::Compiled> {
+        Compiler::new(PackageConfig::default())
+            .add_dependency_source(
+                "gleam_stdlib",
+                "gleam/option",
+                "pub type Option(a) { Some(a) None }",
+            )
+            .add_dependency_source("gleam_stdlib", "gleam/dynamic", DYNAMIC)
+            .add_dependency_source("gleam_json", "gleam/json", JSON)
+            .add_source("app", src)
+            .check()
+    }
+
+    #[test]
+    fn derive_json() {
+        let checked = check_json(
+            "
+import gleam/option.{Option}
+import gleam/json
+
+@derive(json)
+pub type Shape {
+  Square(size: Int)
+  Dot
+}
+
+@derive(json)
+pub type Drawing {
+  Drawing(title: Option(String), shapes: List(List(Shape)))
+}
+",
+        )
+        .expect("check");
+        assert_eq!(checked.warnings, vec![]);
+        let module = &checked.modules.first().expect("module").ast.type_info;
+        let type_of = |name: &str| {
+            let value = module.values.get(name).expect("value");
+            crate::type_::pretty::Printer::new().pretty_print(&value.type_, 0)
+        };
+        assert_eq!(type_of("shape_to_json"), "fn(Shape) -> Json");
+        assert_eq!(
+            type_of("drawing_decoder"),
+            "fn(Dynamic) -> Result(Drawing, List(DecodeError))"
+        );
+    }
+
+    #[test]
+    fn derive_json_for_unsupported_field() {
+        let error =
+            check_json("@derive(json)\npub type A { A(pair: #(Int, Int)) }").expect_err("check");
+        assert!(matches!(
+            error,
+            crate::Error::Derive {
+                error: Error {
+                    problem: Problem::Unsupported { .. },
+                    ..
+                },
+                ..
+            }
+        ));
+    }
+}
@@ -1,15 +1,32 @@
+pub mod sarif;
+
 use std::path::PathBuf;
 
 pub use codespan_reporting::diagnostic::{LabelStyle, Severity};
 use codespan_reporting::{diagnostic::Label as CodespanLabel, files::SimpleFile};
+use serde_json::{json, Value};
 use termcolor::Buffer;
 
-use crate::ast::SrcSpan;
+use crate::{ast::SrcSpan, fix::Fix, line_numbers::LineNumbers};
+
+/// How the diagnostics of a build are printed.
+#[derive(Debug, strum::EnumString, strum::EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum MessageFormat {
+    /// Rendered with the source code they refer to, for people to read.
+    Human,
+    /// One JSON object per line of standard output, for other programs.
+    Json,
+    /// A SARIF log printed to standard output once the build has finished,
+    /// for static analysis dashboards.
+    Sarif,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Level {
     Error,
     Warning,
+    Info,
 }
 
 #[derive(Debug, Clone)]
@@ -50,7 +67,7 @@ impl Diagnostic {
         };
 
         if !self.text.is_empty() {
-            writeln!(buffer, "{}", self.text).expect("write text");
+            self.write_text(buffer);
         }
 
         if let Some(hint) = &self.hint {
@@ -58,6 +75,84 @@ impl Diagnostic {
         }
     }
 
+    // A line of the text can be followed by a line of `^` marking the parts of
+    // it to highlight, such as where two types differ. When writing in colour
+    // the marked parts are coloured instead of being underlined.
+    fn write_text(&self, buffer: &mut Buffer) {
+        use std::io::Write;
+        use termcolor::WriteColor;
+        if !buffer.supports_color() || !self.text.lines().any(is_highlight_marker) {
+            writeln!(buffer, "{}", self.text).expect("write text");
+            return;
+        }
+
+        let mut lines = self.text.lines().peekable();
+        while let Some(line) = lines.next() {
+            match lines.next_if(|next| is_highlight_marker(next)) {
+                Some(marker) => write_highlighted(buffer, line, marker),
+                None => writeln!(buffer, "{}", line).expect("write text line"),
+            }
+        }
+        if self.text.ends_with('\n') {
+            writeln!(buffer).expect("write text end");
+        }
+    }
+
+    /// The diagnostic as a JSON object, with the kind of problem it reports
+    /// and the fixes that resolve it. Each span and edit has its byte offsets
+    /// and its 1-indexed line and column numbers.
+    pub fn to_json(&self, code: &str, fixes: &[Fix]) -> Value {
+        let severity = match self.level {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Info => "info",
+        };
+        let (path, spans, fixes) = match &self.location {
+            Some(location) => {
+                let lines = LineNumbers::new(&location.src);
+                let spans = location
+                    .labels()
+                    .enumerate()
+                    .map(|(i, label)| {
+                        let (start, end) = json_span(&lines, label.span);
+                        json!({
+                            "start": start,
+                            "end": end,
+                            "label": label.text,
+                            "primary": i == 0,
+                        })
+                    })
+                    .collect();
+                let fixes = fixes
+                    .iter()
+                    .map(|fix| {
+                        let edits = fix
+                            .edits
+                            .iter()
+                            .map(|edit| {
+                                let (start, end) = json_span(&lines, edit.location);
+                                json!({ "start": start, "end": end, "new_text": edit.new_text })
+                            })
+                            .collect::<Vec<_>>();
+                        json!({ "title": fix.title, "edits": edits })
+                    })
+                    .collect();
+                (Some(location.path.to_string_lossy()), spans, fixes)
+            }
+            None => (None, vec![], vec![]),
+        };
+        json!({
+            "severity": severity,
+            "code": code,
+            "title": self.title,
+            "text": self.text,
+            "hint": self.hint,
+            "path": path,
+            "spans": spans,
+            "fixes": fixes,
+        })
+    }
+
     fn write_span(&self, location: &Location, buffer: &mut Buffer) {
         let file = SimpleFile::new(location.path.to_string_lossy().to_string(), &location.src);
         let labels = location
@@ -78,6 +173,7 @@ impl Diagnostic {
         let severity = match self.level {
             Level::Error => Severity::Error,
             Level::Warning => Severity::Warning,
+            Level::Info => Severity::Note,
         };
 
         let diagnostic = codespan_reporting::diagnostic::Diagnostic::new(severity)
@@ -94,6 +190,7 @@ impl Diagnostic {
         let (kind, colour) = match self.level {
             Level::Error => ("error", Color::Red),
             Level::Warning => ("warning", Color::Yellow),
+            Level::Info => ("info", Color::Blue),
         };
         buffer
             .set_color(ColorSpec::new().set_bold(true).set_fg(Some(colour)))
@@ -108,3 +205,92 @@ impl Diagnostic {
             .expect("write_title_reset");
     }
 }
+
+fn json_span(lines: &LineNumbers, span: SrcSpan) -> (Value, Value) {
+    let position = |byte: u32| {
+        let position = lines.line_and_column_number(byte);
+        json!({ "byte": byte, "line": position.line, "column": position.column })
+    };
+    (position(span.start), position(span.end))
+}
+
+fn is_highlight_marker(line: &str) -> bool {
+    line.contains('^') && line.chars().all(|c| c == '^' || c == ' ')
+}
+
+fn write_highlighted(buffer: &mut Buffer, line: &str, marker: &str) {
+    use std::io::Write;
+    use termcolor::{Color, ColorSpec, WriteColor};
+    let marker: Vec<char> = marker.chars().collect();
+    let mut highlighting = false;
+    for (i, c) in line.chars().enumerate() {
+        let highlight = marker.get(i) == Some(&'^');
+        if highlight != highlighting {
+            let mut spec = ColorSpec::new();
+            if highlight {
+                let _ = spec.set_bold(true).set_fg(Some(Color::Red));
+            }
+            buffer.set_color(&spec).expect("write_highlighted_color");
+            highlighting = highlight;
+        }
+        write!(buffer, "{}", c).expect("write_highlighted_char");
+    }
+    if highlighting {
+        buffer
+            .set_color(&ColorSpec::new())
+            .expect("write_highlighted_reset");
+    }
+    writeln!(buffer).expect("write_highlighted_end");
+}
+
+#[test]
+fn diagnostic_to_json() {
+    use crate::language_server::TextEdit;
+    let diagnostic = Diagnostic {
+        title: "Unused imported module".into(),
+        text: "".into(),
+        level: Level::Warning,
+        hint: Some("You can safely remove it.".into()),
+        location: Some(Location {
+            src: "import one\nimport two\n".into(),
+            path: PathBuf::from("src/app.gleam"),
+            label: Label {
+                text: Some("This imported module is never used.".into()),
+                span: SrcSpan::new(18, 21),
+            },
+            extra_labels: vec![],
+        }),
+    };
+    let fix = Fix {
+        title: "Remove unused import of `two`".into(),
+        edits: vec![TextEdit {
+            location: SrcSpan::new(11, 22),
+            new_text: "".into(),
+        }],
+    };
+    assert_eq!(
+        diagnostic.to_json("unused_imported_module", &[fix]),
+        json!({
+            "severity": "warning",
+            "code": "unused_imported_module",
+            "title": "Unused imported module",
+            "text": "",
+            "hint": "You can safely remove it.",
+            "path": "src/app.gleam",
+            "spans": [{
+                "start": { "byte": 18, "line": 2, "column": 8 },
+                "end": { "byte": 21, "line": 2, "column": 11 },
+                "label": "This imported module is never used.",
+                "primary": true,
+            }],
+            "fixes": [{
+                "title": "Remove unused import of `two`",
+                "edits": [{
+                    "start": { "byte": 11, "line": 2, "column": 1 },
+                    "end": { "byte": 22, "line": 3, "column": 1 },
+                    "new_text": "",
+                }],
+            }],
+        })
+    );
+}
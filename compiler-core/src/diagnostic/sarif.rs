@@ -0,0 +1,195 @@
+//! Diagnostics in the SARIF 2.1.0 format, which static analysis dashboards
+//! such as GitHub code scanning read.
+//!
+//! <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html>
+
+use itertools::Itertools;
+use serde_json::{json, Value};
+
+use super::{Diagnostic, Level, Location};
+use crate::{ast::SrcSpan, fix::Fix, line_numbers::LineNumbers};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A diagnostic with the kind of problem it reports, such as
+/// `unused_variable`, and the fixes that resolve it.
+#[derive(Debug, Clone)]
+pub struct CodedDiagnostic {
+    pub code: String,
+    pub diagnostic: Diagnostic,
+    pub fixes: Vec<Fix>,
+}
+
+/// A SARIF log with a single run of the compiler, reporting the diagnostics.
+/// Each kind of problem is a rule of the run.
+pub fn log(diagnostics: &[CodedDiagnostic], compiler_version: &str) -> Value {
+    let rules = diagnostics
+        .iter()
+        .unique_by(|diagnostic| &diagnostic.code)
+        .sorted_by(|a, b| a.code.cmp(&b.code))
+        .map(|diagnostic| {
+            json!({
+                "id": diagnostic.code,
+                "shortDescription": { "text": diagnostic.diagnostic.title },
+            })
+        })
+        .collect_vec();
+    let results = diagnostics.iter().map(result).collect_vec();
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "gleam",
+                    "version": compiler_version,
+                    "informationUri": "https://gleam.run",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn result(coded: &CodedDiagnostic) -> Value {
+    let diagnostic = &coded.diagnostic;
+    let level = match diagnostic.level {
+        Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Info => "note",
+    };
+    let mut message = diagnostic.title.clone();
+    for text in std::iter::once(&diagnostic.text).chain(&diagnostic.hint) {
+        if !text.is_empty() {
+            message.push_str("\n\n");
+            message.push_str(text);
+        }
+    }
+
+    let (locations, fixes) = match &diagnostic.location {
+        Some(location) => {
+            let lines = LineNumbers::new(&location.src);
+            let uri = uri(location);
+            let locations = location
+                .labels()
+                .map(|label| {
+                    let mut location = json!({
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" },
+                            "region": region(&lines, label.span),
+                        },
+                    });
+                    if let (Some(text), Some(object)) = (&label.text, location.as_object_mut()) {
+                        let _ = object.insert("message".into(), json!({ "text": text }));
+                    }
+                    location
+                })
+                .collect_vec();
+            let fixes = coded
+                .fixes
+                .iter()
+                .map(|fix| {
+                    let replacements = fix
+                        .edits
+                        .iter()
+                        .map(|edit| {
+                            json!({
+                                "deletedRegion": region(&lines, edit.location),
+                                "insertedContent": { "text": edit.new_text },
+                            })
+                        })
+                        .collect_vec();
+                    json!({
+                        "description": { "text": fix.title },
+                        "artifactChanges": [{
+                            "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" },
+                            "replacements": replacements,
+                        }],
+                    })
+                })
+                .collect_vec();
+            (locations, fixes)
+        }
+        None => (vec![], vec![]),
+    };
+
+    json!({
+        "ruleId": coded.code,
+        "level": level,
+        "message": { "text": message },
+        "locations": locations,
+        "fixes": fixes,
+    })
+}
+
+// Paths are relative to the root of the project, which the log gives as
+// `%SRCROOT%`, and always use forward slashes
+fn uri(location: &Location) -> String {
+    let path = location.path.strip_prefix(".").unwrap_or(&location.path);
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .join("/")
+}
+
+fn region(lines: &LineNumbers, span: SrcSpan) -> Value {
+    let start = lines.line_and_column_number(span.start);
+    let end = lines.line_and_column_number(span.end);
+    json!({
+        "startLine": start.line,
+        "startColumn": start.column,
+        "endLine": end.line,
+        "endColumn": end.column,
+    })
+}
+
+#[test]
+fn sarif_log() {
+    use super::Label;
+    use std::path::PathBuf;
+    let diagnostic = CodedDiagnostic {
+        code: "unused_variable".into(),
+        diagnostic: Diagnostic {
+            title: "Unused variable".into(),
+            text: "".into(),
+            level: Level::Warning,
+            hint: Some("You can ignore it with an underscore: `_x`.".into()),
+            location: Some(Location {
+                src: "pub fn main() {\n  let x = 1\n  Nil\n}\n".into(),
+                path: PathBuf::from("./src/app.gleam"),
+                label: Label {
+                    text: Some("This variable is never used.".into()),
+                    span: SrcSpan::new(22, 23),
+                },
+                extra_labels: vec![],
+            }),
+        },
+        fixes: vec![],
+    };
+    let log = log(&[diagnostic.clone(), diagnostic], "0.25.1");
+    assert_eq!(
+        log.pointer("/runs/0/tool/driver/rules"),
+        Some(&json!([{
+            "id": "unused_variable",
+            "shortDescription": { "text": "Unused variable" },
+        }]))
+    );
+    assert_eq!(
+        log.pointer("/runs/0/results/0"),
+        Some(&json!({
+            "ruleId": "unused_variable",
+            "level": "warning",
+            "message": {
+                "text": "Unused variable\n\nYou can ignore it with an underscore: `_x`.",
+            },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": "src/app.gleam", "uriBaseId": "%SRCROOT%" },
+                    "region": { "startLine": 2, "startColumn": 7, "endLine": 2, "endColumn": 8 },
+                },
+                "message": { "text": "This variable is never used." },
+            }],
+            "fixes": [],
+        }))
+    );
+}
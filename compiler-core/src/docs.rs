@@ -1,35 +1,317 @@
+mod highlight;
 mod source_links;
-
-use std::{path::PathBuf, time::SystemTime};
+mod targets;
+mod theme;
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
 
 use crate::{
-    ast::{Statement, TypedStatement},
-    build::Module,
+    ast::{ArgNames, Deprecation, Statement, TypedStatement},
+    build::{Module, Target},
     config::{DocsPage, PackageConfig},
-    docs::source_links::SourceLinker,
+    docs::{
+        source_links::SourceLinker,
+        targets::{limited_targets, target_name},
+        theme::Branding,
+    },
     format,
-    io::OutputFile,
-    pretty,
+    io::{FileSystemReader, OutputFile},
+    paths, pretty,
+    type_::{self, pretty::Printer},
 };
 use askama::Template;
 use itertools::Itertools;
+use pulldown_cmark::{CowStr, Event, Tag};
 use serde::Serialize;
 use serde_json::to_string as serde_to_string;
 
 const MAX_COLUMNS: isize = 65;
 const VERSION: &str = env!("CARGO_PKG_VERSION");
-
+/// Incremented when the JSON documentation changes in a way that programs
+/// reading it would need to handle.
+const JSON_FORMAT_VERSION: u32 = 1;
+
+/// Renders the documentation of a package. The named types referred to in
+/// signatures link to their documentation, both for the types of the package
+/// and for those of the dependency modules given the URL of the hosted
+/// documentation of their package. Given the URL the latest version of the
+/// documentation is hosted at, each page names its counterpart there as its
+/// canonical URL.
 pub fn generate_html(
+    io: &dyn FileSystemReader,
     config: &PackageConfig,
     analysed: &[Module],
     docs_pages: &[DocsPage],
+    dependency_docs: &HashMap<String, String>,
+    canonical_url: Option<&str>,
 ) -> Vec<OutputFile> {
-    let modules = analysed.iter().filter(|module| !module.is_test());
-    let rendering_timestamp = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .expect("get current timestamp")
-        .as_secs()
+    let branding = Branding::new(&config.documentation.theme, io);
+    let base_url = config.documentation.base_url.as_deref();
+    let base_url = base_url.map(|url| url.trim_end_matches('/'));
+    let (mut files, search_data) = render_pages(
+        io,
+        config,
+        analysed,
+        docs_pages,
+        dependency_docs,
+        &branding,
+        Rendering::Site {
+            base_url,
+            canonical_url,
+        },
+    );
+
+    // Render static assets
+
+    for (path, text) in STYLESHEETS.into_iter().chain(SCRIPTS) {
+        files.push(OutputFile {
+            path: PathBuf::from(path),
+            text: text.to_string(),
+        });
+    }
+
+    if let Some(stylesheet) = &branding.stylesheet {
+        files.push(OutputFile {
+            path: PathBuf::from("css/theme.css"),
+            text: stylesheet.clone(),
+        });
+    }
+
+    if let Some(base_url) = base_url {
+        files.push(OutputFile {
+            path: PathBuf::from("docs_config.js"),
+            text: versions_script(config, base_url),
+        });
+    }
+
+    files.push(OutputFile {
+        path: PathBuf::from("search-data.js"),
+        text: search_data,
+    });
+
+    files
+}
+
+/// Renders the documentation of a package as a single HTML file that can be
+/// read offline, such as when archived by CI. Its stylesheets, scripts and
+/// search index are written into it, and the web font is left out, so that
+/// it makes no requests. The other pages are written into it too and shown
+/// by `offline.js` when the fragment of the URL names them, as in
+/// `#/gleam/list.html#map`.
+pub fn generate_offline(
+    io: &dyn FileSystemReader,
+    config: &PackageConfig,
+    analysed: &[Module],
+    docs_pages: &[DocsPage],
+    dependency_docs: &HashMap<String, String>,
+) -> OutputFile {
+    let branding = Branding::new(&config.documentation.theme, io);
+    let (pages, search_data) = render_pages(
+        io,
+        config,
+        analysed,
+        docs_pages,
+        dependency_docs,
+        &branding,
+        Rendering::SingleFile,
+    );
+
+    // The first page, usually the README, is the one shown when the file is
+    // opened, with the others written into it as data
+    let path = |page: &OutputFile| {
+        let path = page.path.to_string_lossy();
+        path.strip_prefix('/').unwrap_or(&path).to_string()
+    };
+    let first_page = pages.first().map(path).unwrap_or_default();
+    let pages: serde_json::Map<String, serde_json::Value> = pages
+        .iter()
+        .map(|page| (path(page), page.text.clone().into()))
+        .collect();
+
+    let mut styles = String::new();
+    for (path, text) in STYLESHEETS {
+        let text = text.strip_prefix(FONT_IMPORT).unwrap_or(text);
+        match path.strip_prefix("css/atom-one-") {
+            Some(theme) => {
+                let theme = theme.trim_end_matches(".min.css");
+                let media = if theme == "light" { "all" } else { "not all" };
+                styles.push_str(&format!(
+                    "<style data-syntax-theme=\"{}\" media=\"{}\">\n{}</style>\n",
+                    theme, media, text
+                ));
+            }
+            None => styles.push_str(&format!("<style>\n{}</style>\n", text)),
+        }
+    }
+    if let Some(stylesheet) = &branding.stylesheet {
+        styles.push_str(&format!("<style>\n{}</style>\n", stylesheet));
+    }
+
+    let mut scripts = String::new();
+    for (_, text) in SCRIPTS {
+        scripts.push_str(&format!("<script>\n{}</script>\n", text));
+    }
+    scripts.push_str(&format!(
+        "<script>\n{}</script>\n",
+        script_data(search_data)
+    ));
+    scripts.push_str(&format!(
+        "<script>\nconst gleamPages = {};\nconst gleamFirstPage = {};\n</script>\n",
+        script_data(serde_to_string(&pages).expect("offline pages serialization")),
+        serde_to_string(&first_page).expect("offline page path serialization")
+    ));
+    scripts.push_str(&format!(
+        "<script>\n{}</script>\n",
+        std::include_str!("../templates/docs-js/offline.js")
+    ));
+
+    let mut text = pages
+        .get(&first_page)
+        .and_then(|page| page.as_str())
+        .unwrap_or_default()
         .to_string();
+    if let Some(index) = text.find("</head>") {
+        text.insert_str(index, &styles);
+    }
+    if let Some(index) = text.rfind("</body>") {
+        text.insert_str(index, &scripts);
+    }
+
+    OutputFile {
+        path: PathBuf::from(format!("{}.html", config.name)),
+        text,
+    }
+}
+
+/// How the pages of the documentation refer to each other and to their
+/// assets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rendering<'a> {
+    /// Files that link to each other and to the assets beside them, hosted
+    /// at the base URL if it is configured.
+    Site {
+        base_url: Option<&'a str>,
+        canonical_url: Option<&'a str>,
+    },
+    /// Pages written into a single file, linking to each other by the
+    /// fragment of its URL.
+    SingleFile,
+}
+
+/// The stylesheets the pages of the documentation load, by their path.
+const STYLESHEETS: [(&str, &str); 3] = [
+    (
+        "css/atom-one-light.min.css",
+        std::include_str!("../templates/docs-css/atom-one-light.min.css"),
+    ),
+    (
+        "css/atom-one-dark.min.css",
+        std::include_str!("../templates/docs-css/atom-one-dark.min.css"),
+    ),
+    (
+        "css/index.css",
+        std::include_str!("../templates/docs-css/index.css"),
+    ),
+];
+
+/// The scripts the pages of the documentation load, by their path, in the
+/// order they are loaded in.
+const SCRIPTS: [(&str, &str); 7] = [
+    (
+        "js/highlight.min.js",
+        std::include_str!("../templates/docs-js/highlight.min.js"),
+    ),
+    (
+        "js/highlightjs-gleam.js",
+        std::include_str!("../templates/docs-js/highlightjs-gleam.js"),
+    ),
+    (
+        "js/highlightjs-erlang.min.js",
+        std::include_str!("../templates/docs-js/highlightjs-erlang.min.js"),
+    ),
+    (
+        "js/highlightjs-elixir.min.js",
+        std::include_str!("../templates/docs-js/highlightjs-elixir.min.js"),
+    ),
+    (
+        "js/highlightjs-javascript.min.js",
+        std::include_str!("../templates/docs-js/highlightjs-javascript.min.js"),
+    ),
+    (
+        "js/lunr.min.js",
+        std::include_str!("../templates/docs-js/lunr.min.js"),
+    ),
+    (
+        "js/index.js",
+        std::include_str!("../templates/docs-js/index.js"),
+    ),
+];
+
+/// The import of the web font the stylesheet starts with, which is left out
+/// of documentation read offline.
+const FONT_IMPORT: &str = "@import url(\"https://fonts.googleapis.com/css2?family=Karla:wght@400;700&family=Ubuntu+Mono&display=swap\");\n";
+
+// JSON written into a script element, with no `<` that could be read as the
+// end of the element
+fn script_data(json: String) -> String {
+    json.replace('<', "\\u003c")
+}
+
+#[test]
+fn script_data_test() {
+    assert_eq!(
+        script_data(r#"{"index.html":"<script></script><!-- -->"}"#.into()),
+        r#"{"index.html":"\u003cscript>\u003c/script>\u003c!-- -->"}"#
+    );
+}
+
+/// The pages of the documentation, and the script that loads its search
+/// index.
+fn render_pages(
+    io: &dyn FileSystemReader,
+    config: &PackageConfig,
+    analysed: &[Module],
+    docs_pages: &[DocsPage],
+    dependency_docs: &HashMap<String, String>,
+    branding: &Branding,
+    rendering: Rendering<'_>,
+) -> (Vec<OutputFile>, String) {
+    let modules = analysed.iter().filter(|module| !module.is_test());
+    let targets = limited_targets(modules.clone());
+    let (base_url, canonical_url) = match rendering {
+        Rendering::Site {
+            base_url,
+            canonical_url,
+        } => (base_url, canonical_url),
+        Rendering::SingleFile => (None, None),
+    };
+    let single_file = rendering == Rendering::SingleFile;
+    let unnest = |path: &str| {
+        if single_file {
+            "#".to_string()
+        } else {
+            page_unnest(path)
+        }
+    };
+    let canonical_url = canonical_url.map(|url| url.trim_end_matches('/'));
+    let canonical = |path: &str| {
+        canonical_url.map(|url| format!("{}/{}", url, path.strip_prefix('/').unwrap_or(path)))
+    };
+    // HexDocs provides the list of versions of the documentation, shared by
+    // each of them, unless it is hosted elsewhere
+    let docs_config = |unnest: &str| match base_url {
+        Some(url) => format!("{}/docs_config.js", url),
+        None => format!("{}/docs_config.js", unnest),
+    };
+    let assets_version = assets_version(config, modules.clone(), branding);
+    let package_modules: HashSet<&str> = modules.clone().map(|m| m.name.as_str()).collect();
+
+    let rendered_paths = rendered_paths(docs_pages, modules.clone());
 
     // Define user-supplied (or README) pages
     let pages: Vec<_> = docs_pages
@@ -55,6 +337,7 @@ pub fn generate_html(
     let mut files = vec![];
 
     let mut search_indexes = vec![];
+    let mut search_items = vec![];
 
     let modules_links: Vec<_> = modules
         .clone()
@@ -70,8 +353,15 @@ pub fn generate_html(
 
     // Generate user-supplied (or README) pages
     for page in docs_pages {
-        let content = std::fs::read_to_string(&page.source).unwrap_or_default();
-        let unnest = page_unnest(&page.path);
+        let content = io.read(&page.source).unwrap_or_default();
+        let unnest = unnest(&page.path);
+        let page_links = PageLinks {
+            unnest: &unnest,
+            source: &page.source,
+            rendered_paths: &rendered_paths,
+            package_modules: &package_modules,
+            dependency_docs,
+        };
 
         let temp = PageTemplate {
             gleam_version: VERSION,
@@ -81,8 +371,12 @@ pub fn generate_html(
             project_name: &config.name,
             page_title: &config.name,
             project_version: &config.version.to_string(),
-            content: render_markdown(&content),
-            rendering_timestamp: &rendering_timestamp,
+            content: render_markdown(&content, &page_links),
+            assets_version: &assets_version,
+            branding,
+            canonical: canonical(&page.path),
+            docs_config: docs_config(&unnest),
+            single_file,
             unnest: &unnest,
         };
 
@@ -102,36 +396,25 @@ pub fn generate_html(
     // Generate module documentation pages
     for module in modules {
         let name = module.name.clone();
-        let unnest = page_unnest(&module.name);
-
-        // Read module src & create line number lookup structure
-        let source_links = SourceLinker::new(config, module);
+        let unnest = unnest(&module.name);
+        let module_source = paths::src().join(format!("{}.gleam", module.name));
+        let page_links = PageLinks {
+            unnest: &unnest,
+            source: &module_source,
+            rendered_paths: &rendered_paths,
+            package_modules: &package_modules,
+            dependency_docs,
+        };
 
         let page_title = format!("{} - {}", name, config.name);
 
-        let functions: Vec<Function<'_>> = module
-            .ast
-            .statements
-            .iter()
-            .flat_map(|statement| function(&source_links, statement))
-            .sorted()
-            .collect();
-
-        let types: Vec<Type<'_>> = module
-            .ast
-            .statements
-            .iter()
-            .flat_map(|statement| type_(&source_links, statement))
-            .sorted()
-            .collect();
-
-        let constants: Vec<Constant<'_>> = module
-            .ast
-            .statements
-            .iter()
-            .flat_map(|statement| constant(&source_links, statement))
-            .sorted()
-            .collect();
+        let ModuleDocumentation {
+            documentation,
+            functions,
+            types,
+            constants,
+            ..
+        } = module_documentation(config, module, &page_links, &targets);
 
         types.iter().for_each(|typ| {
             let constructors = typ
@@ -178,13 +461,20 @@ pub fn generate_html(
             })
         });
         functions.iter().for_each(|function| {
+            let arguments = function
+                .arguments
+                .iter()
+                .map(|argument| format!("{}\n{}", argument.name, argument.doc))
+                .join("\n");
+
             search_indexes.push(SearchIndex {
                 doc: module.name.to_string(),
                 title: function.name.to_string(),
                 content: format!(
-                    "{}\n{}\n{}",
+                    "{}\n{}\n{}\n{}",
                     function.signature,
                     function.text_documentation,
+                    arguments,
                     import_synonyms(&module.name, function.name)
                 ),
                 url: format!("{}.html#{}", module.name, function.name),
@@ -196,13 +486,21 @@ pub fn generate_html(
             content: module.ast.documentation.iter().join("\n"),
             url: format!("{}.html", module.name),
         });
+        search_items.extend(
+            module
+                .ast
+                .statements
+                .iter()
+                .filter_map(|statement| search_item(&module.name, statement)),
+        );
 
+        let docs_config = docs_config(&unnest);
         let template = ModuleTemplate {
             gleam_version: VERSION,
             unnest,
             links: &links,
             pages: &pages,
-            documentation: render_markdown(&module.ast.documentation.iter().join("\n")),
+            documentation,
             modules: &modules_links,
             project_name: &config.name,
             page_title: &page_title,
@@ -211,7 +509,11 @@ pub fn generate_html(
             functions,
             types,
             constants,
-            rendering_timestamp: &rendering_timestamp,
+            assets_version: &assets_version,
+            branding,
+            canonical: canonical(&format!("{}.html", module.name)),
+            docs_config,
+            single_file,
         };
 
         files.push(OutputFile {
@@ -222,68 +524,299 @@ pub fn generate_html(
         });
     }
 
-    // Render static assets
-
-    files.push(OutputFile {
-        path: PathBuf::from("css/atom-one-light.min.css"),
-        text: std::include_str!("../templates/docs-css/atom-one-light.min.css").to_string(),
-    });
+    let search_data = format!(
+        "window.Gleam.initSearch({}, {});",
+        serde_to_string(&escape_html_contents(search_indexes)).expect("search index serialization"),
+        serde_to_string(&search_items).expect("search items serialization")
+    );
 
-    files.push(OutputFile {
-        path: PathBuf::from("css/atom-one-dark.min.css"),
-        text: std::include_str!("../templates/docs-css/atom-one-dark.min.css").to_string(),
-    });
+    (files, search_data)
+}
 
-    files.push(OutputFile {
-        path: PathBuf::from("css/index.css"),
-        text: std::include_str!("../templates/docs-css/index.css").to_string(),
-    });
+/// Renders the documentation of a package as a single JSON document for other
+/// programs to read, such as alternative frontends and search services. It
+/// holds the same modules, definitions and rendered documentation as the
+/// HTML, with links relative to the page each would be rendered to.
+pub fn generate_json(
+    io: &dyn FileSystemReader,
+    config: &PackageConfig,
+    analysed: &[Module],
+    docs_pages: &[DocsPage],
+    dependency_docs: &HashMap<String, String>,
+) -> OutputFile {
+    let modules = analysed.iter().filter(|module| !module.is_test());
+    let targets = limited_targets(modules.clone());
+    let package_modules: HashSet<&str> = modules.clone().map(|m| m.name.as_str()).collect();
+    let rendered_paths = rendered_paths(docs_pages, modules.clone());
 
-    files.push(OutputFile {
-        path: PathBuf::from("js/highlight.min.js"),
-        text: std::include_str!("../templates/docs-js/highlight.min.js").to_string(),
-    });
+    let pages = docs_pages
+        .iter()
+        .map(|page| {
+            let unnest = page_unnest(&page.path);
+            let page_links = PageLinks {
+                unnest: &unnest,
+                source: &page.source,
+                rendered_paths: &rendered_paths,
+                package_modules: &package_modules,
+                dependency_docs,
+            };
+            let content = io.read(&page.source).unwrap_or_default();
+            PageDocumentation {
+                title: &page.title,
+                path: &page.path,
+                content: render_markdown(&content, &page_links),
+            }
+        })
+        .collect();
 
-    files.push(OutputFile {
-        path: PathBuf::from("js/highlightjs-gleam.js"),
-        text: std::include_str!("../templates/docs-js/highlightjs-gleam.js").to_string(),
-    });
+    let modules = modules
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .map(|module| {
+            let unnest = page_unnest(&module.name);
+            let module_source = paths::src().join(format!("{}.gleam", module.name));
+            let page_links = PageLinks {
+                unnest: &unnest,
+                source: &module_source,
+                rendered_paths: &rendered_paths,
+                package_modules: &package_modules,
+                dependency_docs,
+            };
+            module_documentation(config, module, &page_links, &targets)
+        })
+        .collect();
 
-    files.push(OutputFile {
-        path: PathBuf::from("js/highlightjs-erlang.min.js"),
-        text: std::include_str!("../templates/docs-js/highlightjs-erlang.min.js").to_string(),
-    });
+    let package = PackageDocumentation {
+        format_version: JSON_FORMAT_VERSION,
+        gleam_version: VERSION,
+        name: &config.name,
+        version: config.version.to_string(),
+        pages,
+        modules,
+    };
+    OutputFile {
+        path: PathBuf::from(format!("{}.json", config.name)),
+        text: serde_json::to_string_pretty(&package).expect("Documentation JSON serialization"),
+    }
+}
 
-    files.push(OutputFile {
-        path: PathBuf::from("js/highlightjs-elixir.min.js"),
-        text: std::include_str!("../templates/docs-js/highlightjs-elixir.min.js").to_string(),
-    });
+#[test]
+fn generate_json_test() {
+    use crate::{build::Origin, io::FileSystemWriter, uid::UniqueIdGenerator};
+
+    let ids = UniqueIdGenerator::new();
+    let mut importable = im::HashMap::new();
+    let _ = importable.insert("gleam".to_string(), type_::build_prelude(&ids));
+    let module = |name: &str, origin, code: &str| {
+        let arena = crate::ast::Arena::new();
+        let (mut ast, extra) = crate::parse::parse_module(code, &arena).expect("syntax error");
+        ast.name = name.split('/').map(Into::into).collect();
+        let ast = type_::infer_module(
+            Target::Erlang,
+            &ids,
+            ast,
+            origin,
+            "app",
+            &importable,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let mut module = Module {
+            name: name.into(),
+            code: code.into(),
+            input_path: PathBuf::from(format!("src/{}.gleam", name)),
+            origin,
+            ast,
+            extra,
+        };
+        module.attach_doc_and_module_comments();
+        module
+    };
+    let modules = [
+        module(
+            "wibble/wobble",
+            Origin::Src,
+            "//// Read the [guide](../../docs/guide.md).
 
-    files.push(OutputFile {
-        path: PathBuf::from("js/highlightjs-javascript.min.js"),
-        text: std::include_str!("../templates/docs-js/highlightjs-javascript.min.js").to_string(),
-    });
+/// Wobble an int.
+pub fn wobble(x: Int) -> Int { x }
 
-    files.push(OutputFile {
-        path: PathBuf::from("js/lunr.min.js"),
-        text: std::include_str!("../templates/docs-js/lunr.min.js").to_string(),
-    });
+fn hidden() { 1 }
 
-    files.push(OutputFile {
-        path: PathBuf::from("js/index.js"),
-        text: std::include_str!("../templates/docs-js/index.js").to_string(),
-    });
+pub type Wibble { Wibble }
 
-    files.push(OutputFile {
-        path: PathBuf::from("search-data.js"),
-        text: format!(
-            "window.Gleam.initSearch({});",
-            serde_to_string(&escape_html_contents(search_indexes))
-                .expect("search index serialization")
+pub const answer = 42
+",
         ),
-    });
+        module("wibble_test", Origin::Test, "pub fn wibble_test() { 1 }"),
+    ];
+    let io = crate::io::memory::InMemoryFileSystem::new();
+    io.writer(Path::new("docs/guide.md"))
+        .and_then(|mut writer| writer.write(b"See [wobble](../src/wibble/wobble.gleam)."))
+        .expect("guide.md");
+    let pages = [DocsPage {
+        title: "Guide".into(),
+        path: "guide.html".into(),
+        source: PathBuf::from("docs/guide.md"),
+    }];
+    let config = PackageConfig {
+        name: "app".into(),
+        version: hexpm::version::Version::new(1, 2, 0),
+        ..Default::default()
+    };
+
+    let output = generate_json(&io, &config, &modules, &pages, &HashMap::new());
+    assert_eq!(output.path, PathBuf::from("app.json"));
+    let json: serde_json::Value = serde_json::from_str(&output.text).expect("JSON");
+    let at = |pointer: &str| json.pointer(pointer).cloned().unwrap_or_default();
+    let len = |pointer: &str| at(pointer).as_array().map(Vec::len);
+    assert_eq!(at("/format_version"), JSON_FORMAT_VERSION);
+    assert_eq!(at("/name"), "app");
+    assert_eq!(at("/version"), "1.2.0");
+
+    // Links are relative to the page each is rendered to
+    assert_eq!(at("/pages/0/title"), "Guide");
+    assert_eq!(at("/pages/0/path"), "guide.html");
+    assert_eq!(
+        at("/pages/0/content"),
+        "<p>See <a href=\"./wibble/wobble.html\">wobble</a>.</p>\n"
+    );
+    assert_eq!(
+        at("/modules/0/documentation"),
+        "<p>Read the <a href=\"../guide.html\">guide</a>.</p>\n"
+    );
+
+    // Test modules and private definitions are left out
+    assert_eq!(len("/modules"), Some(1));
+    assert_eq!(at("/modules/0/name"), "wibble/wobble");
+    assert_eq!(len("/modules/0/functions"), Some(1));
+    assert_eq!(at("/modules/0/functions/0/name"), "wobble");
+    assert_eq!(
+        at("/modules/0/functions/0/signature"),
+        "pub fn wobble(x: Int) -> Int"
+    );
+    assert_eq!(
+        at("/modules/0/functions/0/documentation"),
+        "<p>Wobble an int.</p>\n"
+    );
+    assert_eq!(at("/modules/0/types/0/name"), "Wibble");
+    assert_eq!(at("/modules/0/types/0/constructors/0/definition"), "Wibble");
+    assert_eq!(at("/modules/0/constants/0/name"), "answer");
+    assert_eq!(
+        at("/modules/0/constants/0/definition"),
+        "pub const answer: Int = 42"
+    );
+}
 
-    files
+// Relative links between pages and modules are resolved to where their
+// source files are rendered
+fn rendered_paths<'a>(
+    docs_pages: &[DocsPage],
+    modules: impl Iterator<Item = &'a Module>,
+) -> HashMap<PathBuf, String> {
+    docs_pages
+        .iter()
+        .map(|page| {
+            let path = page.path.strip_prefix('/').unwrap_or(&page.path);
+            (normalise(&page.source), path.to_string())
+        })
+        .chain(modules.map(|module| {
+            let source = paths::src().join(format!("{}.gleam", module.name));
+            (normalise(&source), format!("{}.html", module.name))
+        }))
+        .collect()
+}
+
+fn module_documentation<'a>(
+    config: &PackageConfig,
+    module: &'a Module,
+    page_links: &PageLinks<'_>,
+    targets: &HashMap<(String, String), Vec<Target>>,
+) -> ModuleDocumentation<'a> {
+    // Read module src & create line number lookup structure
+    let source_links = SourceLinker::new(config, module);
+    let statements = &module.ast.statements;
+    ModuleDocumentation {
+        name: &module.name,
+        documentation: render_markdown(&module.ast.documentation.iter().join("\n"), page_links),
+        functions: statements
+            .iter()
+            .flat_map(|statement| function(&source_links, page_links, statement))
+            .map(|mut function| {
+                let key = (module.name.clone(), function.name.to_string());
+                function.targets = targets
+                    .get(&key)
+                    .map(|targets| targets.iter().map(|target| target_name(*target)).collect())
+                    .unwrap_or_default();
+                function
+            })
+            .sorted()
+            .collect(),
+        types: statements
+            .iter()
+            .flat_map(|statement| type_(&source_links, page_links, statement))
+            .sorted()
+            .collect(),
+        constants: statements
+            .iter()
+            .flat_map(|statement| constant(&source_links, page_links, statement))
+            .sorted()
+            .collect(),
+    }
+}
+
+/// The script listing the versions of the documentation hosted at the base
+/// URL, newest first, that the version selector of each page is made from.
+/// It is the same script HexDocs provides for the documentation it hosts.
+fn versions_script(config: &PackageConfig, base_url: &str) -> String {
+    let versions: Vec<_> = std::iter::once(&config.version)
+        .chain(config.documentation.versions.iter())
+        .sorted_by(|a, b| b.cmp(a))
+        .dedup()
+        .map(|version| VersionNode {
+            version: format!("v{}", version),
+            url: format!("{}/{}/", base_url, version),
+        })
+        .collect();
+    format!(
+        "var versionNodes = {};\n",
+        serde_to_string(&versions).expect("Version list serialization")
+    )
+}
+
+#[test]
+fn versions_script_test() {
+    let mut config = PackageConfig::default();
+    config.version = hexpm::version::Version::new(1, 1, 0);
+    config.documentation.versions = vec![
+        hexpm::version::Version::new(1, 0, 0),
+        hexpm::version::Version::new(1, 1, 0),
+    ];
+    assert_eq!(
+        versions_script(&config, "https://docs.example.com/app"),
+        "var versionNodes = [\
+{\"version\":\"v1.1.0\",\"url\":\"https://docs.example.com/app/1.1.0/\"},\
+{\"version\":\"v1.0.0\",\"url\":\"https://docs.example.com/app/1.0.0/\"}];\n"
+    );
+}
+
+/// Browsers cache the scripts of the documentation until this changes. It is
+/// derived from the source code rather than the time of rendering so that the
+/// documentation is the same each time it is built.
+fn assets_version<'a>(
+    config: &PackageConfig,
+    modules: impl Iterator<Item = &'a Module>,
+    branding: &Branding,
+) -> String {
+    let mut hasher = DefaultHasher::new();
+    branding.stylesheet.hash(&mut hasher);
+    VERSION.hash(&mut hasher);
+    config.name.hash(&mut hasher);
+    config.version.to_string().hash(&mut hasher);
+    for module in modules {
+        module.name.hash(&mut hasher);
+        module.code.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
 }
 
 fn page_unnest(path: &str) -> String {
@@ -339,8 +872,116 @@ fn import_synonyms(parent: &str, child: &str) -> String {
     format!("Synonyms:\n{}.{}\n{} {}", parent, child, parent, child)
 }
 
+/// A public definition of a module as it is found by its name in the search.
+fn search_item(module: &str, statement: &TypedStatement) -> Option<SearchItem> {
+    let mut formatter = format::Formatter::new();
+    // Signatures are written on one line
+    let (name, kind, signature, doc) = match statement {
+        Statement::Fn {
+            public: true,
+            name,
+            arguments,
+            return_type,
+            doc,
+            ..
+        } => {
+            let signature =
+                formatter.docs_fn_signature(false, name, arguments, return_type.clone());
+            (
+                name,
+                SearchItemKind::Function,
+                signature.to_pretty_string(isize::MAX),
+                doc,
+            )
+        }
+        Statement::ExternalFn {
+            public: true,
+            name,
+            arguments,
+            return_,
+            doc,
+            ..
+        } => {
+            let signature = formatter.external_fn_signature(false, name, arguments, return_);
+            (
+                name,
+                SearchItemKind::Function,
+                signature.to_pretty_string(isize::MAX),
+                doc,
+            )
+        }
+        Statement::CustomType {
+            public: true,
+            name,
+            parameters,
+            doc,
+            ..
+        }
+        | Statement::ExternalType {
+            public: true,
+            name,
+            arguments: parameters,
+            doc,
+            ..
+        }
+        | Statement::TypeAlias {
+            public: true,
+            alias: name,
+            parameters,
+            doc,
+            ..
+        } => {
+            let signature = if parameters.is_empty() {
+                format!("type {}", name)
+            } else {
+                format!("type {}({})", name, parameters.join(", "))
+            };
+            (name, SearchItemKind::Type, signature, doc)
+        }
+        Statement::ModuleConstant {
+            public: true,
+            name,
+            value,
+            doc,
+            ..
+        } => {
+            let type_ = Printer::new().pretty_print(&value.type_(), 0);
+            let signature = format!("const {}: {}", name, type_);
+            (name, SearchItemKind::Constant, signature, doc)
+        }
+        _ => return None,
+    };
+    Some(SearchItem(
+        name.to_string(),
+        kind,
+        module.to_string(),
+        signature,
+        first_line(doc),
+    ))
+}
+
+fn first_line(doc: &Option<String>) -> String {
+    doc.as_deref()
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[test]
+fn first_line_test() {
+    assert_eq!(first_line(&None), "");
+    assert_eq!(
+        first_line(&Some("\n Decodes a list.\n\nMore.".into())),
+        "Decodes a list."
+    );
+}
+
 fn function<'a>(
     source_links: &SourceLinker,
+    links: &PageLinks<'_>,
     statement: &'a TypedStatement,
 ) -> Option<Function<'a>> {
     let mut formatter = format::Formatter::new();
@@ -351,15 +992,32 @@ fn function<'a>(
             name,
             doc,
             return_: retrn,
+            return_type,
             arguments: args,
             location,
+            deprecation,
             ..
         } => Some(Function {
             name,
-            documentation: markdown_documentation(doc),
+            signature_html: links.html(
+                &print(formatter.external_fn_signature(true, name, args, retrn)),
+                args.iter().map(|arg| &arg.type_).chain([return_type]),
+            ),
+            documentation: markdown_documentation(doc, links),
             text_documentation: text_documentation(doc),
+            arguments: args
+                .iter()
+                .filter_map(|arg| arg.label.as_ref().map(|label| (arg, label)))
+                .map(|(argument, label)| DocumentedArgument {
+                    name: label.to_string(),
+                    doc: markdown_documentation(&argument.doc, links),
+                })
+                .filter(|arg| !arg.doc.is_empty())
+                .collect(),
             signature: print(formatter.external_fn_signature(true, name, args, retrn)),
             source_url: source_links.url(location),
+            deprecation_message: deprecation_message(deprecation),
+            targets: vec![],
         }),
 
         Statement::Fn {
@@ -369,19 +1027,45 @@ fn function<'a>(
             arguments: args,
             return_type: ret,
             location,
+            deprecation,
             ..
         } => Some(Function {
             name,
-            documentation: markdown_documentation(doc),
+            signature_html: links.html(
+                &print(formatter.docs_fn_signature(true, name, args, ret.clone())),
+                args.iter().map(|arg| &arg.type_).chain([ret]),
+            ),
+            documentation: markdown_documentation(doc, links),
             text_documentation: text_documentation(doc),
+            arguments: args
+                .iter()
+                .map(|argument| {
+                    let name = match &argument.names {
+                        ArgNames::LabelledDiscard { label, .. }
+                        | ArgNames::NamedLabelled { label, .. } => label,
+                        ArgNames::Discard { name } | ArgNames::Named { name } => name,
+                    };
+                    DocumentedArgument {
+                        name: name.to_string(),
+                        doc: markdown_documentation(&argument.doc, links),
+                    }
+                })
+                .filter(|arg| !arg.doc.is_empty())
+                .collect(),
             signature: print(formatter.docs_fn_signature(true, name, args, ret.clone())),
             source_url: source_links.url(location),
+            deprecation_message: deprecation_message(deprecation),
+            targets: vec![],
         }),
 
         _ => None,
     }
 }
 
+fn deprecation_message(deprecation: &Deprecation) -> String {
+    deprecation.message().unwrap_or_default().to_string()
+}
+
 fn text_documentation(doc: &Option<String>) -> String {
     let raw_text = doc
         .as_ref()
@@ -392,18 +1076,34 @@ fn text_documentation(doc: &Option<String>) -> String {
     raw_text.replace("```gleam", "").replace("```", "")
 }
 
-fn markdown_documentation(doc: &Option<String>) -> String {
-    doc.as_deref().map(render_markdown).unwrap_or_default()
+fn markdown_documentation(doc: &Option<String>, links: &PageLinks<'_>) -> String {
+    doc.as_deref()
+        .map(|doc| render_markdown(doc, links))
+        .unwrap_or_default()
 }
 
-fn render_markdown(text: &str) -> String {
+fn render_markdown(text: &str, links: &PageLinks<'_>) -> String {
     let mut s = String::with_capacity(text.len() * 3 / 2);
-    let p = pulldown_cmark::Parser::new_ext(text, pulldown_cmark::Options::all());
-    pulldown_cmark::html::push_html(&mut s, p);
+    let p = pulldown_cmark::Parser::new_ext(text, pulldown_cmark::Options::all()).map(|event| {
+        match event {
+            Event::Start(Tag::Link(kind, destination, title)) => {
+                let destination = links
+                    .resolve(&destination)
+                    .map_or(destination, CowStr::from);
+                Event::Start(Tag::Link(kind, destination, title))
+            }
+            event => event,
+        }
+    });
+    pulldown_cmark::html::push_html(&mut s, highlight::highlight_code_blocks(p));
     s
 }
 
-fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedStatement) -> Option<Type<'a>> {
+fn type_<'a>(
+    source_links: &SourceLinker,
+    links: &PageLinks<'_>,
+    statement: &'a TypedStatement,
+) -> Option<Type<'a>> {
     let mut formatter = format::Formatter::new();
 
     match statement {
@@ -413,14 +1113,17 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedStatement) -> Opti
             doc,
             arguments: args,
             location,
+            deprecation,
             ..
         } => Some(Type {
             name,
+            definition_html: links.html(&print(formatter.external_type(true, name, args)), []),
             definition: print(formatter.external_type(true, name, args)),
-            documentation: markdown_documentation(doc),
+            documentation: markdown_documentation(doc, links),
             text_documentation: text_documentation(doc),
             constructors: vec![],
             source_url: source_links.url(location),
+            deprecation_message: deprecation_message(deprecation),
         }),
 
         Statement::CustomType {
@@ -431,34 +1134,47 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedStatement) -> Opti
             doc,
             constructors: cs,
             location,
+            deprecation,
             ..
         } => Some(Type {
             name,
+            definition_html: links.html(
+                &print(formatter.custom_type(true, false, name, parameters, cs, location)),
+                cs.iter()
+                    .flat_map(|constructor| &constructor.arguments)
+                    .map(|argument| &argument.type_),
+            ),
             // TODO: Don't use the same printer for docs as for the formatter.
             // We are not interested in showing the exact implementation in the
             // documentation and we could add things like colours, etc.
             definition: print(formatter.custom_type(true, false, name, parameters, cs, location)),
-            documentation: markdown_documentation(doc),
+            documentation: markdown_documentation(doc, links),
             text_documentation: text_documentation(doc),
             constructors: cs
                 .iter()
                 .map(|constructor| TypeConstructor {
+                    definition_html: links.html(
+                        &print(formatter.record_constructor(constructor)),
+                        constructor.arguments.iter().map(|argument| &argument.type_),
+                    ),
                     definition: print(formatter.record_constructor(constructor)),
-                    documentation: markdown_documentation(&constructor.documentation),
+                    documentation: markdown_documentation(&constructor.documentation, links),
                     text_documentation: text_documentation(&constructor.documentation),
                     arguments: constructor
                         .arguments
                         .iter()
                         .filter_map(|arg| arg.label.as_ref().map(|label| (arg, label)))
-                        .map(|(argument, label)| TypeConstructorArg {
+                        .map(|(argument, label)| DocumentedArgument {
                             name: label.trim_end().to_string(),
-                            doc: markdown_documentation(&argument.doc),
+                            doc: markdown_documentation(&argument.doc, links),
                         })
                         .filter(|arg| !arg.doc.is_empty())
                         .collect(),
+                    deprecation_message: deprecation_message(&constructor.deprecation),
                 })
                 .collect(),
             source_url: source_links.url(location),
+            deprecation_message: deprecation_message(deprecation),
         }),
 
         Statement::CustomType {
@@ -468,31 +1184,42 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedStatement) -> Opti
             parameters,
             doc,
             location,
+            deprecation,
             ..
         } => Some(Type {
             name,
+            definition_html: links.html(
+                &print(formatter.docs_opaque_custom_type(true, name, parameters, location)),
+                [],
+            ),
             definition: print(formatter.docs_opaque_custom_type(true, name, parameters, location)),
-            documentation: markdown_documentation(doc),
+            documentation: markdown_documentation(doc, links),
             text_documentation: text_documentation(doc),
             constructors: vec![],
             source_url: source_links.url(location),
+            deprecation_message: deprecation_message(deprecation),
         }),
 
         Statement::TypeAlias {
             public: true,
             alias: name,
             type_ast: typ,
+            type_,
             doc,
             parameters: args,
             location,
+            deprecation,
             ..
         } => Some(Type {
             name,
+            definition_html: links
+                .html(&print(formatter.type_alias(true, name, args, typ)), [type_]),
             definition: print(formatter.type_alias(true, name, args, typ)),
-            documentation: markdown_documentation(doc),
+            documentation: markdown_documentation(doc, links),
             text_documentation: text_documentation(doc),
             constructors: vec![],
             source_url: source_links.url(location),
+            deprecation_message: deprecation_message(deprecation),
         }),
 
         _ => None,
@@ -501,6 +1228,7 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedStatement) -> Opti
 
 fn constant<'a>(
     source_links: &SourceLinker,
+    links: &PageLinks<'_>,
     statement: &'a TypedStatement,
 ) -> Option<Constant<'a>> {
     let mut formatter = format::Formatter::new();
@@ -511,19 +1239,167 @@ fn constant<'a>(
             name,
             value,
             location,
+            deprecation,
             ..
         } => Some(Constant {
             name,
+            definition_html: links.html(
+                &print(formatter.docs_const_expr(true, name, value)),
+                [&value.type_()],
+            ),
             definition: print(formatter.docs_const_expr(true, name, value)),
-            documentation: markdown_documentation(doc),
+            documentation: markdown_documentation(doc, links),
             text_documentation: text_documentation(doc),
             source_url: source_links.url(location),
+            deprecation_message: deprecation_message(deprecation),
         }),
 
         _ => None,
     }
 }
 
+/// Where the documentation and the named types referred to in the
+/// definitions rendered on a page link to.
+struct PageLinks<'a> {
+    /// The path from the page to the root of the documentation.
+    unnest: &'a str,
+    /// The file the documentation of the page is written in, which relative
+    /// links are relative to.
+    source: &'a Path,
+    /// Where each markdown page and module is rendered, by its source file.
+    rendered_paths: &'a HashMap<PathBuf, String>,
+    package_modules: &'a HashSet<&'a str>,
+    /// The URLs of the documentation of the packages of dependency modules.
+    dependency_docs: &'a HashMap<String, String>,
+}
+
+impl PageLinks<'_> {
+    /// A definition as highlighted HTML, with each of the named types among
+    /// the types it refers to linked to its documentation.
+    fn html<'t>(
+        &self,
+        definition: &str,
+        types: impl IntoIterator<Item = &'t Arc<type_::Type>>,
+    ) -> String {
+        let mut named = vec![];
+        for type_ in types {
+            named_types(type_, &mut named);
+        }
+        highlight::highlight_definition(definition, |qualifier, name| {
+            let candidates = named.iter().filter(|(_, named)| named == name);
+            // A type may be qualified with its full path or the alias of its
+            // module, which is not known here
+            let qualified = candidates.clone().find(|(module, _)| {
+                qualifier.map_or(false, |qualifier| {
+                    module == qualifier || module.rsplit('/').next() == Some(qualifier)
+                })
+            });
+            let (module, _) = match qualified {
+                Some(qualified) => qualified,
+                None => {
+                    let first = candidates.clone().next()?;
+                    if !candidates.clone().all(|(other, _)| *other == first.0) {
+                        return None;
+                    }
+                    first
+                }
+            };
+            self.url(module, name)
+        })
+    }
+
+    /// The rendered page a relative link to the source file of a page or a
+    /// module is to, such as `../docs/guide.md#usage`. Other links are left
+    /// as they are.
+    fn resolve(&self, destination: &str) -> Option<String> {
+        if destination.contains(':') || destination.starts_with(['#', '/']) {
+            return None;
+        }
+        let (path, fragment) = match destination.split_once('#') {
+            Some((path, fragment)) => (path, format!("#{}", fragment)),
+            None => (destination, String::new()),
+        };
+        let directory = self.source.parent().unwrap_or_else(|| Path::new(""));
+        let rendered = self.rendered_paths.get(&normalise(&directory.join(path)))?;
+        Some(format!("{}/{}{}", self.unnest, rendered, fragment))
+    }
+
+    fn url(&self, module: &str, name: &str) -> Option<String> {
+        if self.package_modules.contains(module) {
+            return Some(format!("{}/{}.html#{}", self.unnest, module, name));
+        }
+        let docs = self.dependency_docs.get(module)?;
+        Some(format!("{}/{}.html#{}", docs, module, name))
+    }
+}
+
+// The path without `.` components, and with each `..` component removing the
+// one before it where there is one
+fn normalise(path: &Path) -> PathBuf {
+    let mut normalised = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir
+                if matches!(normalised.components().last(), Some(Component::Normal(_))) =>
+            {
+                let _ = normalised.pop();
+            }
+            component => normalised.push(component),
+        }
+    }
+    normalised
+}
+
+#[test]
+fn normalise_test() {
+    assert_eq!(
+        normalise(Path::new("./README.md")),
+        PathBuf::from("README.md")
+    );
+    assert_eq!(
+        normalise(Path::new("src/wibble/../../docs/./guide.md")),
+        PathBuf::from("docs/guide.md")
+    );
+    assert_eq!(
+        normalise(Path::new("../guide.md")),
+        PathBuf::from("../guide.md")
+    );
+}
+
+// The module and name of each named type a type refers to, other than those
+// of the prelude
+fn named_types(type_: &type_::Type, named: &mut Vec<(String, String)>) {
+    match type_ {
+        type_::Type::App {
+            module, name, args, ..
+        } => {
+            if !module.is_empty() {
+                named.push((module.join("/"), name.to_string()));
+            }
+            for arg in args {
+                named_types(arg, named);
+            }
+        }
+        type_::Type::Fn { args, retrn } => {
+            for arg in args {
+                named_types(arg, named);
+            }
+            named_types(retrn, named);
+        }
+        type_::Type::Tuple { elems } => {
+            for elem in elems {
+                named_types(elem, named);
+            }
+        }
+        type_::Type::Var { type_ } => {
+            if let type_::TypeVar::Link { type_ } = &*type_.borrow() {
+                named_types(type_, named);
+            }
+        }
+    }
+}
+
 fn print(doc: pretty::Document<'_>) -> String {
     doc.to_pretty_string(MAX_COLUMNS)
 }
@@ -534,46 +1410,84 @@ struct Link {
     path: String,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Serialize)]
 struct Function<'a> {
     name: &'a str,
     signature: String,
+    signature_html: String,
     documentation: String,
     text_documentation: String,
+    arguments: Vec<DocumentedArgument>,
     source_url: String,
+    deprecation_message: String,
+    /// The targets the function can be used on, if not all of them.
+    targets: Vec<&'static str>,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Serialize)]
 struct TypeConstructor {
     definition: String,
+    definition_html: String,
     documentation: String,
     text_documentation: String,
-    arguments: Vec<TypeConstructorArg>,
+    arguments: Vec<DocumentedArgument>,
+    deprecation_message: String,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
-struct TypeConstructorArg {
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Serialize)]
+struct DocumentedArgument {
     name: String,
     doc: String,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Serialize)]
 struct Type<'a> {
     name: &'a str,
     definition: String,
+    definition_html: String,
     documentation: String,
     constructors: Vec<TypeConstructor>,
     text_documentation: String,
     source_url: String,
+    deprecation_message: String,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Serialize)]
 struct Constant<'a> {
     name: &'a str,
     definition: String,
+    definition_html: String,
     documentation: String,
     text_documentation: String,
     source_url: String,
+    deprecation_message: String,
+}
+
+/// The documentation of a package written as JSON.
+#[derive(Serialize)]
+struct PackageDocumentation<'a> {
+    format_version: u32,
+    gleam_version: &'a str,
+    name: &'a str,
+    version: String,
+    pages: Vec<PageDocumentation<'a>>,
+    modules: Vec<ModuleDocumentation<'a>>,
+}
+
+#[derive(Serialize)]
+struct PageDocumentation<'a> {
+    title: &'a str,
+    path: &'a str,
+    content: String,
+}
+
+#[derive(Serialize)]
+struct ModuleDocumentation<'a> {
+    name: &'a str,
+    documentation: String,
+    functions: Vec<Function<'a>>,
+    types: Vec<Type<'a>>,
+    constants: Vec<Constant<'a>>,
 }
 
 #[derive(Template)]
@@ -588,7 +1502,11 @@ struct PageTemplate<'a> {
     links: &'a [Link],
     modules: &'a [Link],
     content: String,
-    rendering_timestamp: &'a str,
+    assets_version: &'a str,
+    branding: &'a Branding,
+    canonical: Option<String>,
+    docs_config: String,
+    single_file: bool,
 }
 
 #[derive(Template)]
@@ -607,7 +1525,17 @@ struct ModuleTemplate<'a> {
     types: Vec<Type<'a>>,
     constants: Vec<Constant<'a>>,
     documentation: String,
-    rendering_timestamp: &'a str,
+    assets_version: &'a str,
+    branding: &'a Branding,
+    canonical: Option<String>,
+    docs_config: String,
+    single_file: bool,
+}
+
+#[derive(Serialize, PartialEq, Eq, Debug)]
+struct VersionNode {
+    version: String,
+    url: String,
 }
 
 #[derive(Serialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -617,3 +1545,17 @@ struct SearchIndex {
     content: String,
     url: String,
 }
+
+/// The definitions found by name in the search are written as arrays of
+/// their name, kind, module, signature, and the first line of their
+/// documentation, keeping the index small.
+#[derive(Serialize, PartialEq, Eq, Debug)]
+struct SearchItem(String, SearchItemKind, String, String, String);
+
+#[derive(Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum SearchItemKind {
+    Function,
+    Type,
+    Constant,
+}
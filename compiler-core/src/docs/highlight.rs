@@ -0,0 +1,195 @@
+use pulldown_cmark::{CodeBlockKind, Event, Tag};
+
+use super::escape_html_content;
+use crate::parse::{lexer::make_tokenizer, token::Token};
+
+/// Renders the Gleam code blocks of markdown as highlighted HTML, using the
+/// class names of highlight.js so that its themes style them. A code block
+/// is Gleam if it declares Gleam as its language or declares none. Blocks of
+/// other languages are left to be highlighted by highlight.js in the browser.
+pub fn highlight_code_blocks<'a>(
+    events: impl Iterator<Item = Event<'a>>,
+) -> impl Iterator<Item = Event<'a>> {
+    let mut gleam_code: Option<String> = None;
+    events.filter_map(move |event| {
+        if let Some(code) = &mut gleam_code {
+            return match event {
+                Event::Text(text) => {
+                    code.push_str(&text);
+                    None
+                }
+                Event::End(Tag::CodeBlock(_)) => {
+                    let html = highlight_gleam(&gleam_code.take().unwrap_or_default());
+                    Some(Event::Html(html.into()))
+                }
+                event => Some(event),
+            };
+        }
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) if is_gleam(&kind) => {
+                gleam_code = Some(String::new());
+                None
+            }
+            event => Some(event),
+        }
+    })
+}
+
+fn is_gleam(kind: &CodeBlockKind<'_>) -> bool {
+    match kind {
+        CodeBlockKind::Indented => true,
+        CodeBlockKind::Fenced(info) => {
+            let language = info.split([' ', ',']).next().unwrap_or_default();
+            language.is_empty() || language == "gleam"
+        }
+    }
+}
+
+/// A block of Gleam code as HTML. Code that cannot be lexed is left for
+/// highlight.js to highlight.
+fn highlight_gleam(code: &str) -> String {
+    let mut html = String::from("<pre><code class=\"nohighlight hljs\">");
+    match highlighted_tokens(code, |_, _| None) {
+        Some(highlighted) => html.push_str(&highlighted),
+        None => {
+            html = String::from("<pre><code class=\"language-gleam\">");
+            html.push_str(&escape_html_content(code.to_string()));
+        }
+    }
+    html.push_str("</code></pre>\n");
+    html
+}
+
+/// A definition written as Gleam code as highlighted HTML, with each type
+/// name it refers to linked to the URL given for it. The URL is given the
+/// name and the module it is qualified with, if any.
+pub fn highlight_definition(
+    code: &str,
+    link: impl Fn(Option<&str>, &str) -> Option<String>,
+) -> String {
+    highlighted_tokens(code, link).unwrap_or_else(|| escape_html_content(code.to_string()))
+}
+
+fn highlighted_tokens(
+    code: &str,
+    link: impl Fn(Option<&str>, &str) -> Option<String>,
+) -> Option<String> {
+    let mut html = String::new();
+    let mut written = 0;
+    let push = |html: &mut String, start: usize, end: usize, class: Option<&str>| {
+        let text = escape_html_content(code.get(start..end).unwrap_or_default().to_string());
+        match class {
+            Some(class) => {
+                html.push_str(&format!("<span class=\"hljs-{}\">{}</span>", class, text))
+            }
+            None => html.push_str(&text),
+        }
+    };
+
+    let mut previous = None;
+    for token in make_tokenizer(code) {
+        let (start, token, end) = token.ok()?;
+        let (start, end) = (start as usize, end as usize);
+        let (start, class) = match &token {
+            // The location of a comment starts after its slashes
+            Token::CommentNormal => (start - 2, "comment"),
+            Token::CommentDoc => (start - 3, "comment"),
+            Token::CommentModule => (start - 4, "comment"),
+            Token::EmptyLine | Token::EndOfFile => continue,
+            Token::String { .. } => (start, "string"),
+            Token::Int { .. } | Token::Float { .. } => (start, "number"),
+            Token::UpName { .. } => (start, "title"),
+            Token::DiscardName { .. } => (start, "comment"),
+            Token::Name { .. } if previous == Some(Token::Fn) => (start, "title"),
+            Token::Name { .. } => (start, "variable"),
+            Token::As
+            | Token::Assert
+            | Token::Case
+            | Token::Const
+            | Token::Echo
+            | Token::External
+            | Token::Fn
+            | Token::If
+            | Token::Import
+            | Token::Let
+            | Token::Opaque
+            | Token::Pub
+            | Token::Todo
+            | Token::Try
+            | Token::Type
+            | Token::Use => (start, "keyword"),
+            Token::LeftParen
+            | Token::RightParen
+            | Token::LeftSquare
+            | Token::RightSquare
+            | Token::LeftBrace
+            | Token::RightBrace
+            | Token::Colon
+            | Token::Comma
+            | Token::Hash => {
+                previous = Some(token);
+                continue;
+            }
+            _ => (start, "operator"),
+        };
+        push(&mut html, written, start, None);
+        let url = match &token {
+            Token::UpName { name } => link(qualifier(code, start), name),
+            _ => None,
+        };
+        match url {
+            Some(url) => {
+                html.push_str(&format!("<a href=\"{}\">", escape_html_content(url)));
+                push(&mut html, start, end, Some(class));
+                html.push_str("</a>");
+            }
+            None => push(&mut html, start, end, Some(class)),
+        }
+        written = end;
+        previous = Some(token);
+    }
+    push(&mut html, written, code.len(), None);
+    Some(html)
+}
+
+// The module a name starting at the byte index is qualified with, such as
+// `gleam/option` in `gleam/option.Option`
+fn qualifier(code: &str, start: usize) -> Option<&str> {
+    let before = code.get(..start)?.strip_suffix('.')?;
+    let qualifier_start = before
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '/'))
+        .map_or(0, |index| index + 1);
+    before
+        .get(qualifier_start..)
+        .filter(|module| !module.is_empty())
+}
+
+#[test]
+fn highlight_gleam_test() {
+    assert_eq!(
+        highlight_gleam("pub fn two() { 1 + 1 } // <2>\n"),
+        "<pre><code class=\"nohighlight hljs\">\
+<span class=\"hljs-keyword\">pub</span> <span class=\"hljs-keyword\">fn</span> \
+<span class=\"hljs-title\">two</span>() { <span class=\"hljs-number\">1</span> \
+<span class=\"hljs-operator\">+</span> <span class=\"hljs-number\">1</span> } \
+<span class=\"hljs-comment\">// &lt;2&gt;</span>\n</code></pre>\n"
+    );
+}
+
+#[test]
+fn highlight_definition_test() {
+    let link = |module: Option<&str>, name: &str| match (module, name) {
+        (None, "Order") => Some("order.html#Order".to_string()),
+        (Some("gleam/option"), "Option") => Some("option.html#Option".to_string()),
+        _ => None,
+    };
+    assert_eq!(
+        highlight_definition("fn(Order, gleam/option.Option(Int))", link),
+        "<span class=\"hljs-keyword\">fn</span>(\
+<a href=\"order.html#Order\"><span class=\"hljs-title\">Order</span></a>, \
+<span class=\"hljs-variable\">gleam</span><span class=\"hljs-operator\">/</span>\
+<span class=\"hljs-variable\">option</span><span class=\"hljs-operator\">.</span>\
+<a href=\"option.html#Option\"><span class=\"hljs-title\">Option</span></a>(\
+<span class=\"hljs-title\">Int</span>))"
+    );
+}
@@ -0,0 +1,181 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        SrcSpan, Statement, TypedExpr,
+    },
+    build::{Module, Target},
+    type_::{ModuleValueConstructor, ValueConstructorVariant},
+};
+
+/// A function of the package, by its module and name.
+type Key = (String, String);
+
+/// The targets each function of the package can be used on, for those that
+/// cannot be used on all of them. A function is limited to a target when it
+/// is defined in an `if` block for that target, when it is an external
+/// function whose module is only found on the target, or when it calls a
+/// function of the package that is limited. The functions of dependencies are
+/// taken to be available on every target.
+pub fn limited_targets<'a>(modules: impl Iterator<Item = &'a Module>) -> HashMap<Key, Vec<Target>> {
+    let mut targets: HashMap<Key, HashSet<Target>> = HashMap::new();
+    let mut calls: HashMap<Key, HashSet<Key>> = HashMap::new();
+    // External functions are referred to by the function they call
+    let mut externals: HashMap<Key, Key> = HashMap::new();
+    for module in modules {
+        for statement in &module.ast.statements {
+            let (name, location, mut own) = match statement {
+                Statement::Fn { name, location, .. } => (name, location, all_targets()),
+                Statement::ExternalFn {
+                    name,
+                    location,
+                    module: external,
+                    fun,
+                    ..
+                } => {
+                    let _ = externals.insert(
+                        (external.clone(), fun.clone()),
+                        (module.name.clone(), name.to_string()),
+                    );
+                    (name, location, HashSet::from([external_target(external)]))
+                }
+                _ => continue,
+            };
+            if let Some(target) = target_group(module, *location) {
+                own.retain(|candidate| *candidate == target);
+            }
+            let key = (module.name.clone(), name.to_string());
+            let mut called = Calls::default();
+            called.visit_typed_statement(statement);
+            let _ = targets.insert(key.clone(), own);
+            let _ = calls.insert(key, called.functions);
+        }
+    }
+
+    // A function is limited to the targets of each function it calls, which
+    // are narrowed until none change
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (key, called) in &calls {
+            let narrowed: HashSet<Target> = called
+                .iter()
+                .map(|callee| externals.get(callee).unwrap_or(callee))
+                .filter_map(|callee| targets.get(callee))
+                .fold(
+                    targets.get(key).cloned().unwrap_or_default(),
+                    |own, callee| own.intersection(callee).copied().collect(),
+                );
+            if targets.get(key) != Some(&narrowed) {
+                let _ = targets.insert(key.clone(), narrowed);
+                changed = true;
+            }
+        }
+    }
+
+    targets
+        .into_iter()
+        .filter(|(_, available)| available.len() < Target::ALL.len())
+        .map(|(key, available)| {
+            let available = Target::ALL
+                .into_iter()
+                .filter(|target| available.contains(target))
+                .collect();
+            (key, available)
+        })
+        .collect()
+}
+
+/// The name of the target shown in the documentation.
+pub fn target_name(target: Target) -> &'static str {
+    match target {
+        Target::Erlang => "Erlang",
+        Target::JavaScript => "JavaScript",
+        Target::Native => "C++",
+        Target::Go => "Go",
+        Target::Llvm => "LLVM",
+        Target::Wasm => "WebAssembly",
+    }
+}
+
+fn all_targets() -> HashSet<Target> {
+    Target::ALL.into_iter().collect()
+}
+
+// The target of the `if` block the definition at the location is in, if any
+fn target_group(module: &Module, location: SrcSpan) -> Option<Target> {
+    let group = module
+        .extra
+        .target_groups
+        .iter()
+        .find(|group| group.start <= location.start && location.end <= group.end)?;
+    let code = module.code.get(group.start as usize..group.end as usize)?;
+    let target = code.strip_prefix("if")?.split_whitespace().next()?;
+    Target::from_str(target).ok()
+}
+
+/// The target whose code an external function refers to: C++ headers, such
+/// as `<cmath>` or `"vector.h"`, JavaScript modules and globals, or else an
+/// Erlang module.
+fn external_target(module: &str) -> Target {
+    let is_header = (module.starts_with('<') && module.ends_with('>'))
+        || [".h", ".hh", ".hpp"]
+            .iter()
+            .any(|extension| module.ends_with(extension));
+    let is_javascript = module.is_empty()
+        || module.starts_with("./")
+        || module.starts_with("../")
+        || [".js", ".mjs", ".cjs", ".ts"]
+            .iter()
+            .any(|extension| module.ends_with(extension));
+    if is_header {
+        Target::Native
+    } else if is_javascript {
+        Target::JavaScript
+    } else {
+        Target::Erlang
+    }
+}
+
+/// The module functions referred to by a definition.
+#[derive(Debug, Default)]
+struct Calls {
+    functions: HashSet<Key>,
+}
+
+impl<'ast> Visit<'ast> for Calls {
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        match expr {
+            TypedExpr::Var { constructor, .. } => {
+                if let ValueConstructorVariant::ModuleFn { module, name, .. } = &constructor.variant
+                {
+                    let _ = self.functions.insert((module.join("/"), name.to_string()));
+                }
+            }
+            TypedExpr::ModuleSelect {
+                label,
+                module_name,
+                constructor: ModuleValueConstructor::Fn { .. },
+                ..
+            } => {
+                let _ = self
+                    .functions
+                    .insert((module_name.to_string(), label.to_string()));
+            }
+            _ => visit::visit_typed_expr(self, expr),
+        }
+    }
+}
+
+#[test]
+fn external_target_test() {
+    assert_eq!(external_target("gleam_stdlib"), Target::Erlang);
+    assert_eq!(external_target("./ffi.mjs"), Target::JavaScript);
+    assert_eq!(external_target(""), Target::JavaScript);
+    assert_eq!(external_target("<cmath>"), Target::Native);
+    assert_eq!(external_target("vector.hpp"), Target::Native);
+}
@@ -0,0 +1,123 @@
+use std::{io::Read, path::Path};
+
+use crate::{config::DocsTheme, io::FileSystemReader};
+
+#[cfg(test)]
+use crate::io::memory::InMemoryFileSystem;
+
+/// The colour browsers show around the light theme when no accent colour is
+/// configured, the same as the accent of the default stylesheet.
+const THEME_COLOR: &str = "#ffaff3";
+
+/// The branding of the pages of the documentation, from the theme configured
+/// for the package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branding {
+    pub theme_color: String,
+    /// The logo and favicon as data URIs, so that they are published along
+    /// with the pages that show them.
+    pub logo: Option<String>,
+    pub favicon: Option<String>,
+    /// The stylesheet of the theme, loaded after that of the documentation,
+    /// if the theme changes any styles.
+    pub stylesheet: Option<String>,
+    /// The theme shown to readers who have not chosen one, or an empty
+    /// string to show the one their system prefers.
+    pub default_theme: &'static str,
+}
+
+impl Branding {
+    pub fn new(theme: &DocsTheme, io: &dyn FileSystemReader) -> Self {
+        Self {
+            theme_color: theme
+                .accent_color
+                .clone()
+                .unwrap_or_else(|| THEME_COLOR.to_string()),
+            logo: theme.logo.as_deref().and_then(|path| data_uri(path, io)),
+            favicon: theme.favicon.as_deref().and_then(|path| data_uri(path, io)),
+            stylesheet: stylesheet(theme, io),
+            default_theme: theme.default.map_or("", |default| default.as_str()),
+        }
+    }
+}
+
+// The colours of the theme override the variables of the default stylesheet,
+// followed by the stylesheet of the package, if any.
+fn stylesheet(theme: &DocsTheme, io: &dyn FileSystemReader) -> Option<String> {
+    let mut css = String::new();
+    let variables =
+        |css: &mut String, selector: &str, accent: &Option<String>, link: &Option<String>| {
+            if accent.is_none() && link.is_none() {
+                return;
+            }
+            css.push_str(selector);
+            css.push_str(" {\n");
+            if let Some(accent) = accent {
+                css.push_str(&format!("  --pink: {};\n", accent));
+            }
+            if let Some(link) = link {
+                css.push_str(&format!("  --hot-pink: {};\n  --links: {};\n", link, link));
+            }
+            css.push_str("}\n\n");
+        };
+    variables(&mut css, ":root", &theme.accent_color, &theme.link_color);
+    variables(
+        &mut css,
+        "body.theme-dark",
+        &theme.dark_accent_color,
+        &theme.dark_link_color,
+    );
+    if let Some(path) = &theme.stylesheet {
+        css.push_str(&io.read(path).unwrap_or_default());
+    }
+    (!css.is_empty()).then(|| css)
+}
+
+fn data_uri(path: &Path, io: &dyn FileSystemReader) -> Option<String> {
+    let mut bytes = vec![];
+    let _ = io.reader(path).ok()?.read_to_end(&mut bytes).ok()?;
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let media_type = match extension
+        .map(|extension| extension.to_lowercase())
+        .as_deref()
+    {
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        _ => "application/octet-stream",
+    };
+    Some(format!(
+        "data:{};base64,{}",
+        media_type,
+        base64::encode(bytes)
+    ))
+}
+
+#[test]
+fn stylesheet_test() {
+    let io = InMemoryFileSystem::new();
+    assert_eq!(stylesheet(&DocsTheme::default(), &io), None);
+    let theme = DocsTheme {
+        accent_color: Some("#4c8bf5".into()),
+        dark_link_color: Some("skyblue".into()),
+        ..Default::default()
+    };
+    assert_eq!(
+        stylesheet(&theme, &io).as_deref(),
+        Some(
+            ":root {
+  --pink: #4c8bf5;
+}
+
+body.theme-dark {
+  --hot-pink: skyblue;
+  --links: skyblue;
+}
+
+"
+        )
+    );
+}
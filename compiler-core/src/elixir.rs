@@ -0,0 +1,2319 @@
+//! Elixir code generation for the Erlang target, used in place of Erlang
+//! when a package sets `source = "elixir"` in the `erlang` section of its
+//! gleam.toml.
+//!
+//! Values are represented in the same way as they are in the generated
+//! Erlang, and each module is named with the same atom, so the two can be
+//! used interchangeably.
+
+mod pattern;
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    ast::*,
+    docvec,
+    erlang::{collect_type_var_usages, map_record_labels, type_variable_name},
+    io::Utf8Writer,
+    line_numbers::LineNumbers,
+    pretty::*,
+    type_::{
+        guard::GuardFunctions, ModuleValueConstructor, PatternConstructor, Type, TypeVar,
+        ValueConstructor, ValueConstructorVariant, VariantField,
+    },
+    Result,
+};
+use heck::ToSnakeCase;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use pattern::pattern;
+use regex::Regex;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Deref,
+    rc::Rc,
+    str::FromStr,
+    sync::Arc,
+};
+
+const INDENT: isize = 2;
+
+lazy_static! {
+    static ref ATOM: Regex = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_@]*$").expect("atom regex");
+    static ref IDENTIFIER: Regex =
+        Regex::new(r"^[a-z_][a-zA-Z0-9_]*[?!]?$").expect("identifier regex");
+}
+
+#[derive(Debug, Clone)]
+struct Env<'a> {
+    module: &'a [Name],
+    function: &'a str,
+    line_numbers: &'a LineNumbers,
+    current_scope_vars: im::HashMap<String, usize>,
+    function_scope_vars: im::HashMap<String, usize>,
+    /// The checks for the range patterns of the clause being generated, as
+    /// ranges cannot be matched by Elixir patterns and must be in the guard.
+    int_range_checks: Vec<Document<'a>>,
+    /// Functions of the module that are called in clause guards. Elixir does
+    /// not permit calling them there so their bodies are inlined instead.
+    guard_functions: Rc<GuardFunctions<'a>>,
+    /// The `Kernel` functions that are not imported because the module
+    /// defines functions of the same name and arity. Generated code that
+    /// uses them calls them through `Kernel`.
+    excluded_kernel_functions: Rc<BTreeSet<(&'a str, usize)>>,
+    /// Whether the variables of the pattern being generated are bound with
+    /// underscored names, as only the shape of the value is being checked.
+    unused_pattern_variables: bool,
+}
+
+impl<'env> Env<'env> {
+    pub fn new(
+        module: &'env [Name],
+        line_numbers: &'env LineNumbers,
+        guard_functions: Rc<GuardFunctions<'env>>,
+        excluded_kernel_functions: Rc<BTreeSet<(&'env str, usize)>>,
+    ) -> Self {
+        Self {
+            current_scope_vars: im::HashMap::new(),
+            function_scope_vars: im::HashMap::new(),
+            int_range_checks: vec![],
+            unused_pattern_variables: false,
+            excluded_kernel_functions,
+            guard_functions,
+            line_numbers,
+            function: "",
+            module,
+        }
+    }
+
+    /// A new environment for generating the given function of the module.
+    pub fn for_function(&self, function: &'env str) -> Self {
+        let mut env = Self::new(
+            self.module,
+            self.line_numbers,
+            self.guard_functions.clone(),
+            self.excluded_kernel_functions.clone(),
+        );
+        env.function = function;
+        env
+    }
+
+    pub fn local_var_name<'a>(&mut self, name: &str) -> Document<'a> {
+        match self.current_scope_vars.get(name) {
+            None => {
+                let _ = self.current_scope_vars.insert(name.to_string(), 0);
+                let _ = self.function_scope_vars.insert(name.to_string(), 0);
+                Document::String(variable_name(name))
+            }
+            Some(0) => Document::String(variable_name(name)),
+            Some(n) => Document::String(format!("{}__{}", variable_name(name), n)),
+        }
+    }
+
+    pub fn next_local_var_name<'a>(&mut self, name: &str) -> Document<'a> {
+        let next = self.function_scope_vars.get(name).map_or(0, |i| i + 1);
+        let _ = self.function_scope_vars.insert(name.to_string(), next);
+        let _ = self.current_scope_vars.insert(name.to_string(), next);
+        self.local_var_name(name)
+    }
+
+    /// The name to call a `Kernel` function by, which is qualified when the
+    /// module defines a function that would clash with it.
+    pub fn kernel_function<'a>(&self, name: &'static str, arity: usize) -> Document<'a> {
+        if self.excluded_kernel_functions.contains(&(name, arity)) {
+            docvec!["Kernel.", name]
+        } else {
+            name.to_doc()
+        }
+    }
+
+    /// `and` or `or`, written as an operator unless the module defines a
+    /// function of the same name.
+    pub fn boolean_operator<'a>(
+        &self,
+        operator: &'static str,
+        left: Document<'a>,
+        right: Document<'a>,
+    ) -> Document<'a> {
+        if self.excluded_kernel_functions.contains(&(operator, 2)) {
+            docvec!["Kernel.", operator, wrap_args([left, right])]
+        } else {
+            docvec![left, " ", operator, " ", right]
+        }
+    }
+}
+
+pub fn module<'a>(
+    module: &'a TypedModule,
+    line_numbers: &'a LineNumbers,
+    line_length: isize,
+    writer: &mut impl Utf8Writer,
+) -> Result<()> {
+    module_document(module, line_numbers).pretty_print(line_length, writer)
+}
+
+fn module_document<'a>(module: &'a TypedModule, line_numbers: &'a LineNumbers) -> Document<'a> {
+    let excluded_kernel_functions = excluded_kernel_functions(&module.statements);
+    let mut definitions = vec![];
+
+    if let Some(doc) = doc_attribute("moduledoc", &module.documentation.join("\n")) {
+        definitions.push(doc);
+    }
+
+    if !excluded_kernel_functions.is_empty() {
+        let functions = excluded_kernel_functions
+            .iter()
+            .map(|(name, arity)| docvec![*name, ": ", *arity]);
+        definitions.push(docvec![
+            "import Kernel, except: ",
+            list(join(functions, break_(",", ", ")), None)
+        ]);
+    }
+
+    definitions.extend(
+        module
+            .statements
+            .iter()
+            .filter_map(|statement| type_definition(statement, &module.name)),
+    );
+
+    let guard_functions = Rc::new(GuardFunctions::new(&module.name, &module.statements));
+    let env = Env::new(
+        &module.name,
+        line_numbers,
+        guard_functions,
+        Rc::new(excluded_kernel_functions),
+    );
+    definitions.extend(
+        module
+            .statements
+            .iter()
+            .filter_map(|statement| self::statement(statement, &env)),
+    );
+
+    let body = if definitions.is_empty() {
+        nil()
+    } else {
+        docvec![line(), join(definitions, lines(2))].nest(INDENT)
+    };
+    docvec![
+        "defmodule ",
+        module_reference(&module.name.join("@")),
+        " do",
+        body,
+        line(),
+        "end",
+        line()
+    ]
+}
+
+/// The functions of the module that have the same name and arity as a
+/// function or macro of `Kernel`, which is otherwise imported.
+fn excluded_kernel_functions(statements: &[TypedStatement]) -> BTreeSet<(&str, usize)> {
+    statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Fn {
+                name, arguments, ..
+            } => Some((name.as_str(), arguments.len())),
+            Statement::ExternalFn {
+                public: true,
+                name,
+                arguments,
+                ..
+            } => Some((name.as_str(), arguments.len())),
+            _ => None,
+        })
+        .filter(|(name, arity)| is_kernel_function(name, *arity))
+        .collect()
+}
+
+fn type_definition<'a>(statement: &'a TypedStatement, module: &'a [Name]) -> Option<Document<'a>> {
+    match statement {
+        Statement::ExternalType {
+            name,
+            arguments: args,
+            public,
+            doc,
+            ..
+        } => {
+            let args = args
+                .iter()
+                .map(|a| Document::String(variable_name(a)))
+                .collect_vec();
+            // phantom variant
+            let phantom = if args.is_empty() {
+                nil()
+            } else {
+                docvec![
+                    " | ",
+                    tuple(std::iter::once(atom("gleam_phantom")).chain(args.clone()))
+                ]
+            };
+            Some(docvec![
+                typedoc(*public, doc),
+                "@type ",
+                Document::String(elixir_safe_type_name(name.to_snake_case())),
+                "(",
+                join(args, ", ".to_doc()),
+                ") :: any()",
+                phantom
+            ])
+        }
+
+        Statement::CustomType {
+            name,
+            constructors,
+            typed_parameters,
+            opaque,
+            erlang_map,
+            public,
+            doc,
+            ..
+        } => {
+            // Type variables that are not used by any constructor are used by
+            // a phantom value, so that Elixir does not warn they are unused.
+            let type_var_usages = collect_type_var_usages(BTreeMap::new(), typed_parameters);
+            let mut constructor_var_usages = BTreeMap::new();
+            for c in constructors {
+                constructor_var_usages = collect_type_var_usages(
+                    constructor_var_usages,
+                    c.arguments.iter().map(|a| &a.type_),
+                );
+            }
+            let type_printer = TypePrinter::new(module);
+            let phantom_vars = type_var_usages
+                .keys()
+                .filter(|&id| !constructor_var_usages.contains_key(id))
+                .map(|&id| type_var(id))
+                .collect_vec();
+            let phantom_vars_constructor = if phantom_vars.is_empty() {
+                None
+            } else {
+                Some(tuple(
+                    std::iter::once(atom("gleam_phantom")).chain(phantom_vars),
+                ))
+            };
+            let constructors = join(
+                constructors
+                    .iter()
+                    .map(|c| {
+                        let name = atom(&c.name.to_snake_case());
+                        if c.arguments.is_empty() {
+                            name
+                        } else if *erlang_map {
+                            let labels = c.arguments.iter().filter_map(|a| a.label.as_deref());
+                            let args = c.arguments.iter().map(|a| type_printer.print(&a.type_));
+                            map(labels, args)
+                        } else {
+                            let args = c.arguments.iter().map(|a| type_printer.print(&a.type_));
+                            tuple(std::iter::once(name).chain(args))
+                        }
+                    })
+                    .chain(phantom_vars_constructor),
+                break_(" |", " | "),
+            )
+            .nest(INDENT)
+            .group();
+            let params = join(
+                typed_parameters.iter().map(|a| type_printer.print(a)),
+                ", ".to_doc(),
+            );
+            Some(docvec![
+                typedoc(*public, doc),
+                if *opaque { "@opaque " } else { "@type " },
+                Document::String(elixir_safe_type_name(name.to_snake_case())),
+                "(",
+                params,
+                ") :: ",
+                constructors
+            ])
+        }
+
+        Statement::Fn { .. }
+        | Statement::Import { .. }
+        | Statement::TypeAlias { .. }
+        | Statement::ExternalFn { .. }
+        | Statement::ModuleConstant { .. } => None,
+    }
+}
+
+fn typedoc<'a>(public: bool, doc: &Option<String>) -> Document<'a> {
+    match doc
+        .as_deref()
+        .filter(|_| public)
+        .and_then(|doc| doc_attribute("typedoc", doc))
+    {
+        Some(doc) => docvec![doc, line()],
+        None => nil(),
+    }
+}
+
+fn statement<'a>(statement: &'a TypedStatement, env: &Env<'a>) -> Option<Document<'a>> {
+    match statement {
+        Statement::TypeAlias { .. }
+        | Statement::CustomType { .. }
+        | Statement::Import { .. }
+        | Statement::ExternalType { .. }
+        | Statement::ModuleConstant { .. }
+        | Statement::ExternalFn { public: false, .. } => None,
+
+        Statement::Fn {
+            arguments: args,
+            name,
+            body,
+            return_type,
+            public,
+            doc,
+            deprecation,
+            ..
+        } => {
+            let mut env = env.for_function(name);
+            let head = function_head(
+                name,
+                *public,
+                doc,
+                deprecation,
+                args.iter().map(|a| &a.type_),
+                return_type,
+                env.module,
+            );
+            let args = fun_args(args, &mut env);
+            let body = expr(body, &mut env);
+            Some(def(head, name, *public, args, body))
+        }
+
+        Statement::ExternalFn {
+            fun,
+            module,
+            arguments: args,
+            name,
+            return_type,
+            doc,
+            deprecation,
+            ..
+        } => {
+            let head = function_head(
+                name,
+                true,
+                doc,
+                deprecation,
+                args.iter().map(|a| &a.type_),
+                return_type,
+                env.module,
+            );
+            let names = (0..args.len())
+                .map(|i| Document::String(format!("field_{i}")))
+                .collect_vec();
+            let body = remote_call(module, fun, names.clone());
+            Some(def(head, name, true, wrap_args(names), body))
+        }
+    }
+}
+
+fn def<'a>(
+    head: Document<'a>,
+    name: &'a str,
+    public: bool,
+    args: Document<'a>,
+    body: Document<'a>,
+) -> Document<'a> {
+    docvec![
+        head,
+        if public { "def " } else { "defp " },
+        definition_name(name),
+        args,
+        " do",
+        docvec![line(), body].nest(INDENT),
+        line(),
+        "end"
+    ]
+}
+
+/// The attributes of a function: its documentation, deprecation and type
+/// specification. Elixir discards the documentation of private functions so
+/// it is only given for public ones.
+fn function_head<'a, 'b>(
+    name: &'a str,
+    public: bool,
+    doc: &Option<String>,
+    deprecation: &'a Deprecation,
+    args: impl IntoIterator<Item = &'b Arc<Type>> + Clone,
+    return_type: &'b Arc<Type>,
+    module: &'a [Name],
+) -> Document<'a> {
+    let mut head = vec![];
+    if public {
+        if let Some(doc) = doc.as_deref().and_then(|doc| doc_attribute("doc", doc)) {
+            head.push(doc);
+        }
+        if let Deprecation::Deprecated { message } = deprecation {
+            head.push(docvec!["@deprecated ", string(message)]);
+        }
+    }
+    let var_usages = collect_type_var_usages(
+        BTreeMap::new(),
+        std::iter::once(return_type).chain(args.clone()),
+    );
+    let type_printer = TypePrinter::new(module).with_var_usages(&var_usages);
+    let args = args.into_iter().map(|a| type_printer.print(a));
+    head.push(
+        docvec![
+            "@spec ",
+            definition_name(name),
+            wrap_args(args),
+            " :: ",
+            type_printer.print(return_type)
+        ]
+        .group(),
+    );
+    join(head, line()).append(line())
+}
+
+/// A heredoc module attribute holding documentation, or nothing if the
+/// documentation is empty.
+fn doc_attribute<'a>(attribute: &'static str, doc: &str) -> Option<Document<'a>> {
+    let doc_lines = doc
+        .lines()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+        .collect_vec();
+    let first = doc_lines.iter().position(|line| !line.is_empty())?;
+    let last = doc_lines.iter().rposition(|line| !line.is_empty())?;
+    let mut document = docvec!["@", attribute, " \"\"\""];
+    let mut breaks = 1;
+    for line in doc_lines.iter().skip(first).take(last + 1 - first) {
+        if line.is_empty() {
+            breaks += 1;
+        } else {
+            let line = line
+                .replace('\\', "\\\\")
+                .replace("#{", "\\#{")
+                .replace("\"\"\"", "\\\"\"\"");
+            document = document
+                .append(lines(breaks))
+                .append(Document::String(line));
+            breaks = 1;
+        }
+    }
+    Some(document.append(line()).append("\"\"\""))
+}
+
+fn fun_args<'a>(args: &'a [TypedArg], env: &mut Env<'a>) -> Document<'a> {
+    wrap_args(fn_args(args, env))
+}
+
+fn fn_args<'a>(args: &'a [TypedArg], env: &mut Env<'a>) -> Vec<Document<'a>> {
+    args.iter()
+        .map(|a| match &a.names {
+            ArgNames::Discard { .. } | ArgNames::LabelledDiscard { .. } => "_".to_doc(),
+            ArgNames::Named { name } | ArgNames::NamedLabelled { name, .. } => {
+                env.next_local_var_name(name)
+            }
+        })
+        .collect()
+}
+
+fn wrap_args<'a, I>(args: I) -> Document<'a>
+where
+    I: IntoIterator<Item = Document<'a>>,
+{
+    break_("", "")
+        .append(join(args, break_(",", ", ")))
+        .nest(INDENT)
+        .append(break_("", ""))
+        .surround("(", ")")
+        .group()
+}
+
+/// The name a function of the module is defined with. Those that are Elixir
+/// keywords or special forms must be unquoted.
+fn definition_name(name: &str) -> Document<'_> {
+    if is_elixir_reserved_word(name) {
+        docvec!["unquote(", atom(name), ")"]
+    } else {
+        name.to_doc()
+    }
+}
+
+/// The name of a function after the `.` of a remote call.
+fn remote_function_name(name: &str) -> Document<'_> {
+    if IDENTIFIER.is_match(name) {
+        name.to_doc()
+    } else {
+        Document::String(format!("\"{}\"", escape_string(name)))
+    }
+}
+
+/// An Erlang module such as `gleam@list`, or an Elixir module given in an
+/// external function as `Elixir.String`.
+fn module_reference(name: &str) -> Document<'static> {
+    match name.strip_prefix("Elixir.") {
+        Some(alias) => Document::String(alias.to_string()),
+        None => atom(name),
+    }
+}
+
+fn remote_call<'a>(module: &'a str, name: &'a str, args: Vec<Document<'a>>) -> Document<'a> {
+    docvec![
+        module_reference(module),
+        ".",
+        remote_function_name(name),
+        wrap_args(args)
+    ]
+}
+
+fn atom(value: &str) -> Document<'static> {
+    match value {
+        "true" | "false" | "nil" => Document::String(value.to_string()),
+        _ if ATOM.is_match(value) => Document::String(format!(":{}", value)),
+        _ => Document::String(format!(":\"{}\"", escape_string(value))),
+    }
+}
+
+/// Escapes text so that it can be written in an Elixir string.
+fn escape_string(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace("#{", "\\#{")
+}
+
+/// A Gleam string literal, which is written with the same escape sequences
+/// as Elixir other than for interpolation.
+fn string(value: &str) -> Document<'_> {
+    Document::String(format!("\"{}\"", value.replace("#{", "\\#{")))
+}
+
+fn tuple<'a>(elems: impl IntoIterator<Item = Document<'a>>) -> Document<'a> {
+    join(elems, break_(",", ", "))
+        .nest(INDENT)
+        .surround("{", "}")
+        .group()
+}
+
+/// An Elixir map with atom keys, as used for the fields of a map record.
+fn map<'a, 'b>(
+    labels: impl IntoIterator<Item = &'b str>,
+    values: impl IntoIterator<Item = Document<'a>>,
+) -> Document<'a> {
+    let fields = labels
+        .into_iter()
+        .zip(values)
+        .map(|(label, value)| docvec![key(label), " ", value]);
+    join(fields, break_(",", ", "))
+        .nest(INDENT)
+        .surround("%{", "}")
+        .group()
+}
+
+/// An atom key of a map or keyword list, such as `name:`.
+fn key(label: &str) -> Document<'static> {
+    if ATOM.is_match(label) {
+        Document::String(format!("{}:", label))
+    } else {
+        Document::String(format!("\"{}\":", escape_string(label)))
+    }
+}
+
+fn string_concatenate<'a>(
+    left: &'a TypedExpr,
+    right: &'a TypedExpr,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let left = string_concatenate_argument(left, env);
+    let right = string_concatenate_argument(right, env);
+    docvec![left, " <>", break_("", " "), right]
+}
+
+fn string_concatenate_argument<'a>(value: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
+    match value {
+        TypedExpr::BinOp {
+            name: BinOp::Concatenate,
+            ..
+        } => expr(value, env),
+        TypedExpr::BinOp { .. } => expr(value, env).surround("(", ")"),
+        _ => maybe_block_expr(value, env),
+    }
+}
+
+fn bit_string<'a>(elems: impl IntoIterator<Item = Document<'a>>) -> Document<'a> {
+    join(elems, break_(",", ", "))
+        .nest(INDENT)
+        .surround("<<", ">>")
+        .group()
+}
+
+fn const_segment<'a>(
+    value: &'a TypedConstant,
+    options: &'a [BitStringSegmentOption<TypedConstant>],
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let document = match value {
+        // As normal
+        Constant::Int { .. }
+        | Constant::Float { .. }
+        | Constant::String { .. }
+        | Constant::BitString { .. } => const_inline(value, env),
+
+        // Wrap anything else in parentheses
+        value => const_inline(value, env).surround("(", ")"),
+    };
+
+    let size = |value: &'a TypedConstant, env: &mut Env<'a>| const_inline(value, env);
+
+    bit_string_segment(document, options, size, true, env)
+}
+
+fn expr_segment<'a>(
+    value: &'a TypedExpr,
+    options: &'a [BitStringSegmentOption<TypedExpr>],
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let document = match value {
+        // As normal
+        TypedExpr::Int { .. }
+        | TypedExpr::Float { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::Var { .. }
+        | TypedExpr::BitString { .. } => expr(value, env),
+
+        // Wrap anything else in parentheses
+        value => expr(value, env).surround("(", ")"),
+    };
+
+    let size = |expression: &'a TypedExpr, env: &mut Env<'a>| match expression {
+        TypedExpr::Int { value, .. } => {
+            let v = value.replace('_', "");
+            let v = u64::from_str(&v).unwrap_or(0);
+            v.to_doc()
+        }
+
+        // The value of size must be a non-negative integer, we use max here
+        // to ensure it is at least 0
+        _ => docvec![
+            env.kernel_function("max", 2),
+            wrap_args([expr(expression, env), "0".to_doc()])
+        ],
+    };
+
+    let value_is_a_string_literal = matches!(value, TypedExpr::String { .. });
+    bit_string_segment(document, options, size, value_is_a_string_literal, env)
+}
+
+fn bit_string_segment<'a, Value: 'a, SizeToDoc>(
+    document: Document<'a>,
+    options: &'a [BitStringSegmentOption<Value>],
+    mut size_to_doc: SizeToDoc,
+    value_is_a_string_literal: bool,
+    env: &mut Env<'a>,
+) -> Document<'a>
+where
+    SizeToDoc: FnMut(&'a Value, &mut Env<'a>) -> Document<'a>,
+{
+    let mut size = None;
+    let mut unit = None;
+    let mut modifiers = Vec::new();
+
+    // Only valid codepoint integers can be used as values for utf segments.
+    // We want to support <<string_var:utf8>> for all string variables, so
+    // the binary type is used for these segments instead.
+    let override_type = if !value_is_a_string_literal {
+        Some("binary")
+    } else {
+        None
+    };
+
+    for option in options {
+        use BitStringSegmentOption as Opt;
+        let modifier = match option {
+            Opt::Utf8 { .. } => override_type.unwrap_or("utf8"),
+            Opt::Utf16 { .. } => override_type.unwrap_or("utf16"),
+            Opt::Utf32 { .. } => override_type.unwrap_or("utf32"),
+            Opt::Int { .. } => "integer",
+            Opt::Float { .. } => "float",
+            Opt::Binary { .. } => "binary",
+            Opt::BitString { .. } => "bitstring",
+            Opt::Utf8Codepoint { .. } => "utf8",
+            Opt::Utf16Codepoint { .. } => "utf16",
+            Opt::Utf32Codepoint { .. } => "utf32",
+            Opt::Signed { .. } => "signed",
+            Opt::Unsigned { .. } => "unsigned",
+            Opt::Big { .. } => "big",
+            Opt::Little { .. } => "little",
+            Opt::Native { .. } => "native",
+            Opt::Size { value, .. } => {
+                size = Some(docvec!["size(", size_to_doc(value, env), ")"]);
+                continue;
+            }
+            Opt::Unit { value, .. } => {
+                unit = Some(docvec!["unit(", *value, ")"]);
+                continue;
+            }
+        };
+        modifiers.push(modifier.to_doc());
+    }
+
+    modifiers.extend(size);
+    modifiers.extend(unit);
+    if modifiers.is_empty() {
+        document
+    } else {
+        docvec![document, "::", join(modifiers, "-".to_doc())]
+    }
+}
+
+fn seq<'a>(expressions: &'a [TypedExpr], env: &mut Env<'a>) -> Document<'a> {
+    let documents = expressions
+        .iter()
+        .map(|expression| expr(expression, env).group())
+        .collect_vec();
+    join(documents, line()).force_break()
+}
+
+fn bin_op<'a>(
+    name: &'a BinOp,
+    left: &'a TypedExpr,
+    right: &'a TypedExpr,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let div_zero = match name {
+        BinOp::DivInt | BinOp::RemainderInt => Some("0"),
+        BinOp::DivFloat => Some("0.0"),
+        _ => None,
+    };
+    let op = match name {
+        BinOp::And => "and",
+        BinOp::Or => "or",
+        BinOp::LtInt | BinOp::LtFloat => "<",
+        BinOp::LtEqInt | BinOp::LtEqFloat => "<=",
+        BinOp::Eq => "===",
+        BinOp::NotEq => "!==",
+        BinOp::GtInt | BinOp::GtFloat => ">",
+        BinOp::GtEqInt | BinOp::GtEqFloat => ">=",
+        BinOp::AddInt | BinOp::AddFloat => "+",
+        BinOp::SubInt | BinOp::SubFloat => "-",
+        BinOp::MultInt | BinOp::MultFloat => "*",
+        BinOp::DivInt => "div",
+        BinOp::DivFloat => "/",
+        BinOp::RemainderInt => "rem",
+        BinOp::Concatenate => return string_concatenate(left, right, env),
+    };
+
+    let left_expr = match left {
+        TypedExpr::BinOp { .. } => expr(left, env).surround("(", ")"),
+        _ => maybe_block_expr(left, env),
+    };
+
+    let right_expr = match right {
+        TypedExpr::BinOp { .. } => expr(right, env).surround("(", ")"),
+        _ if div_zero.is_some() => subject(right, env),
+        _ => maybe_block_expr(right, env),
+    };
+
+    let operation = |left: Document<'a>, right: Document<'a>, env: &Env<'a>| match name {
+        BinOp::DivInt | BinOp::RemainderInt => {
+            docvec![env.kernel_function(op, 2), wrap_args([left, right])]
+        }
+        BinOp::And | BinOp::Or => env.boolean_operator(op, left, right),
+        _ => docvec![left, " ", op, break_("", " "), right],
+    };
+
+    match div_zero {
+        Some(_) if right.non_zero_compile_time_number() => operation(left_expr, right_expr, env),
+        None => operation(left_expr, right_expr, env),
+
+        Some(zero) => {
+            let denominator = "_denominator";
+            let name = env.next_local_var_name(denominator);
+            let division = operation(left_expr, env.local_var_name(denominator), env);
+            docvec![
+                "case ",
+                right_expr,
+                " do",
+                docvec![line(), zero, " -> ", zero, line(), name, " -> ", division].nest(INDENT),
+                line(),
+                "end"
+            ]
+        }
+    }
+}
+
+fn try_<'a>(
+    value: &'a TypedExpr,
+    pat: &'a TypedPattern,
+    then: &'a TypedExpr,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let value = subject(value, env);
+    let error = env.next_local_var_name(TRY_VARIABLE);
+    docvec![
+        "case ",
+        value,
+        " do",
+        docvec![
+            line(),
+            "{:error, ",
+            error.clone(),
+            "} -> {:error, ",
+            error,
+            "}",
+            line(),
+            "{:ok, ",
+            pattern(pat, env),
+            "} ->",
+            docvec![line(), expr(then, env)].nest(INDENT)
+        ]
+        .nest(INDENT),
+        line(),
+        "end"
+    ]
+    .group()
+}
+
+// The value is matched against the pattern in a case expression that crashes
+// when it does not match, and the variables of the pattern are bound by
+// matching the result of the case expression.
+fn assert<'a>(
+    value: &'a TypedExpr,
+    pat: &'a TypedPattern,
+    message: Option<&'a str>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let body = subject(value, env);
+    let binding = pattern(pat, env);
+    let scope_vars = env.current_scope_vars.clone();
+    let check = pattern::unused_variables_pattern(pat, env);
+    let value = env.next_local_var_name(ASSERT_VARIABLE);
+    let error = elixir_error(
+        "assert",
+        message.unwrap_or("Assertion pattern match failed"),
+        pat.location(),
+        vec![("value", value.clone())],
+        env,
+    );
+    env.current_scope_vars = scope_vars;
+    docvec![
+        binding,
+        " = case ",
+        body,
+        " do",
+        docvec![
+            line(),
+            check,
+            " = ",
+            value.clone(),
+            " -> ",
+            value.clone(),
+            line(),
+            value,
+            " ->",
+            docvec![line(), error].nest(INDENT)
+        ]
+        .nest(INDENT),
+        line(),
+        "end"
+    ]
+}
+
+fn let_<'a>(value: &'a TypedExpr, pat: &'a TypedPattern, env: &mut Env<'a>) -> Document<'a> {
+    let body = maybe_block_expr(value, env).group();
+    pattern(pat, env).append(" = ").append(body)
+}
+
+fn float<'a>(value: &str) -> Document<'a> {
+    Document::String(crate::float::canonical_literal(value))
+}
+
+// Elixir has the same syntax as Gleam for integers.
+fn int<'a>(value: &str) -> Document<'a> {
+    Document::String(value.to_string())
+}
+
+fn expr_list<'a>(
+    elements: &'a [TypedExpr],
+    tail: &'a Option<Box<TypedExpr>>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let elements = join(
+        elements
+            .iter()
+            .map(|e| maybe_block_expr(e, env))
+            .collect_vec(),
+        break_(",", ", "),
+    );
+    list(elements, tail.as_ref().map(|e| maybe_block_expr(e, env)))
+}
+
+fn list<'a>(elems: Document<'a>, tail: Option<Document<'a>>) -> Document<'a> {
+    let elems = if let Some(final_tail) = tail {
+        elems.append(break_(" |", " | ")).append(final_tail)
+    } else {
+        elems
+    };
+
+    elems.nest(INDENT).surround("[", "]").group()
+}
+
+fn var<'a>(name: &'a str, constructor: &'a ValueConstructor, env: &mut Env<'a>) -> Document<'a> {
+    match &constructor.variant {
+        ValueConstructorVariant::Record {
+            erlang_map: true,
+            field_map: Some(field_map),
+            arity,
+            ..
+        } => {
+            let args = incrementing_args(*arity as usize);
+            constructor_fun(args.clone(), map(map_record_labels(field_map), args))
+        }
+
+        ValueConstructorVariant::Record {
+            name: record_name, ..
+        } => match constructor.type_.deref() {
+            Type::Fn { args, .. } => {
+                let args = incrementing_args(args.len());
+                let tag = atom(&record_name.to_snake_case());
+                constructor_fun(args.clone(), tuple(std::iter::once(tag).chain(args)))
+            }
+            _ => atom(&record_name.to_snake_case()),
+        },
+
+        ValueConstructorVariant::LocalVariable { .. } => env.local_var_name(name),
+
+        ValueConstructorVariant::ModuleConstant { literal, .. } => const_inline(literal, env),
+
+        variant if variant.is_rescue() => rescue_fun(env),
+
+        ValueConstructorVariant::ModuleFn {
+            arity, ref module, ..
+        } if module == env.module => {
+            if is_elixir_reserved_word(name) {
+                docvec!["&__MODULE__.", name, "/", *arity]
+            } else {
+                docvec!["&", name, "/", *arity]
+            }
+        }
+
+        ValueConstructorVariant::ModuleFn {
+            arity,
+            module,
+            name,
+            ..
+        } => docvec![
+            "&",
+            module_reference(&module.join("@")),
+            ".",
+            remote_function_name(name),
+            "/",
+            *arity
+        ],
+    }
+}
+
+fn const_inline<'a>(literal: &'a TypedConstant, env: &mut Env<'a>) -> Document<'a> {
+    match literal {
+        Constant::Int { value, .. } => int(value),
+        Constant::Float { value, .. } => float(value),
+        Constant::String { value, .. } => string(value),
+        Constant::Tuple { elements, .. } => {
+            tuple(elements.iter().map(|e| const_inline(e, env)).collect_vec())
+        }
+
+        Constant::List { elements, .. } => {
+            let elements = elements.iter().map(|e| const_inline(e, env)).collect_vec();
+            list(join(elements, break_(",", ", ")), None)
+        }
+
+        Constant::BitString { segments, .. } => bit_string(
+            segments
+                .iter()
+                .map(|s| const_segment(&s.value, &s.options, env))
+                .collect_vec(),
+        ),
+
+        Constant::Record { tag, args, .. } => {
+            if args.is_empty() {
+                atom(&tag.to_snake_case())
+            } else {
+                let args = args
+                    .iter()
+                    .map(|a| const_inline(&a.value, env))
+                    .collect_vec();
+                tuple(std::iter::once(atom(&tag.to_snake_case())).chain(args))
+            }
+        }
+
+        Constant::Var {
+            name, constructor, ..
+        } => var(
+            name,
+            constructor
+                .as_ref()
+                .expect("This is guaranteed to hold a value."),
+            env,
+        ),
+
+        Constant::BinOp { .. } => {
+            unreachable!("Constant binary operators are evaluated during type checking")
+        }
+    }
+}
+
+fn clause<'a>(clause: &'a TypedClause, env: &mut Env<'a>) -> Document<'a> {
+    let Clause {
+        guard,
+        pattern: pat,
+        alternative_patterns,
+        then,
+        ..
+    } = clause;
+
+    // Each alternative pattern is a clause of its own with the same body.
+    // The body is generated only once, so the variables of each pattern
+    // must have the same names.
+    let mut then_doc = None;
+    let initial_vars = env.function_scope_vars.clone();
+    let mut end_vars = im::HashMap::new();
+
+    let docs = std::iter::once(pat)
+        .chain(alternative_patterns)
+        .map(|patterns| {
+            env.function_scope_vars = initial_vars.clone();
+
+            let patterns_doc = if patterns.len() == 1 {
+                let p = patterns.get(0).expect("Single pattern clause printing");
+                pattern(p, env)
+            } else {
+                tuple(patterns.iter().map(|p| pattern(p, env)).collect_vec())
+            };
+
+            let range_checks = std::mem::take(&mut env.int_range_checks);
+            let guard = guard.as_ref().map(|guard| clause_guard(guard, env));
+            let guard = range_checks
+                .into_iter()
+                .chain(guard)
+                .reduce(|left, right| env.boolean_operator("and", left, right));
+            if then_doc.is_none() {
+                then_doc = Some(expr(then, env));
+                end_vars = env.function_scope_vars.clone();
+            }
+
+            docvec![
+                patterns_doc,
+                guard.map(|guard| docvec![" when ", guard]),
+                " ->",
+                docvec![line(), then_doc.clone()].nest(INDENT).group()
+            ]
+        })
+        .collect_vec();
+
+    env.function_scope_vars = end_vars;
+    join(docs, lines(2))
+}
+
+fn bare_clause_guard<'a>(guard: &'a TypedClauseGuard, env: &mut Env<'a>) -> Document<'a> {
+    let operator = match guard {
+        ClauseGuard::Or { .. } => "or",
+        ClauseGuard::And { .. } => "and",
+        ClauseGuard::Equals { .. } => "===",
+        ClauseGuard::NotEquals { .. } => "!==",
+        ClauseGuard::GtInt { .. } | ClauseGuard::GtFloat { .. } => ">",
+        ClauseGuard::GtEqInt { .. } | ClauseGuard::GtEqFloat { .. } => ">=",
+        ClauseGuard::LtInt { .. } | ClauseGuard::LtFloat { .. } => "<",
+        ClauseGuard::LtEqInt { .. } | ClauseGuard::LtEqFloat { .. } => "<=",
+
+        // Only local variables are supported and the typer ensures that all
+        // ClauseGuard::Vars are local variables
+        ClauseGuard::Var { name, .. } => return env.local_var_name(name),
+
+        ClauseGuard::TupleIndex { tuple, index, .. } => {
+            let tuple = bare_clause_guard(tuple, env);
+            return docvec![
+                env.kernel_function("elem", 2),
+                wrap_args([tuple, index.to_doc()])
+            ];
+        }
+
+        ClauseGuard::Call { name, args, .. } => {
+            let args = args.iter().map(|arg| clause_guard(arg, env)).collect();
+            return guard_function_inline(name, args, env);
+        }
+
+        ClauseGuard::Constant(constant) => return const_inline(constant, env),
+    };
+
+    match guard {
+        ClauseGuard::Or { left, right, .. }
+        | ClauseGuard::And { left, right, .. }
+        | ClauseGuard::Equals { left, right, .. }
+        | ClauseGuard::NotEquals { left, right, .. }
+        | ClauseGuard::GtInt { left, right, .. }
+        | ClauseGuard::GtEqInt { left, right, .. }
+        | ClauseGuard::LtInt { left, right, .. }
+        | ClauseGuard::LtEqInt { left, right, .. }
+        | ClauseGuard::GtFloat { left, right, .. }
+        | ClauseGuard::GtEqFloat { left, right, .. }
+        | ClauseGuard::LtFloat { left, right, .. }
+        | ClauseGuard::LtEqFloat { left, right, .. } => {
+            let left = clause_guard(left, env);
+            let right = clause_guard(right, env);
+            match operator {
+                "and" | "or" => env.boolean_operator(operator, left, right),
+                _ => docvec![left, " ", operator, " ", right],
+            }
+        }
+
+        ClauseGuard::Var { .. }
+        | ClauseGuard::TupleIndex { .. }
+        | ClauseGuard::Call { .. }
+        | ClauseGuard::Constant(_) => unreachable!("Returned above"),
+    }
+}
+
+// Functions cannot be called in Elixir guards, so the body of the function is
+// used instead, with the arguments in place of its parameters. The type
+// checker ensures only functions with a body that can be inlined are called.
+fn guard_function_inline<'a>(
+    name: &str,
+    args: Vec<Document<'a>>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let function = env
+        .guard_functions
+        .get(name)
+        .expect("Elixir printing: Guard called a function that cannot be inlined");
+    let bindings = function
+        .arguments
+        .iter()
+        .zip(args)
+        .filter_map(|(parameter, arg)| Some((parameter.get_variable_name()?, arg)))
+        .collect();
+    guard_expr_inline(function.body, &bindings, env)
+}
+
+fn guard_expr_inline<'a>(
+    expression: &'a TypedExpr,
+    bindings: &HashMap<&str, Document<'a>>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    match expression {
+        TypedExpr::Int { value, .. } => int(value),
+        TypedExpr::Float { value, .. } => float(value),
+        TypedExpr::String { value, .. } => string(value),
+        TypedExpr::Cfg { enabled, .. } => atom(&enabled.to_string()),
+
+        TypedExpr::Var { name, .. } => bindings
+            .get(name.as_str())
+            .cloned()
+            .expect("Elixir printing: Inlined guard function variable was not an argument"),
+
+        TypedExpr::Sequence { expressions, .. } => match expressions.as_slice() {
+            [expression] => guard_expr_inline(expression, bindings, env),
+            _ => panic!("Elixir printing: Inlined guard function had multiple expressions"),
+        },
+
+        TypedExpr::BinOp {
+            name, left, right, ..
+        } => {
+            let op = match name {
+                BinOp::And => "and",
+                BinOp::Or => "or",
+                BinOp::Eq => "===",
+                BinOp::NotEq => "!==",
+                BinOp::LtInt | BinOp::LtFloat => "<",
+                BinOp::LtEqInt | BinOp::LtEqFloat => "<=",
+                BinOp::GtInt | BinOp::GtFloat => ">",
+                BinOp::GtEqInt | BinOp::GtEqFloat => ">=",
+                BinOp::AddInt | BinOp::AddFloat => "+",
+                BinOp::SubInt | BinOp::SubFloat => "-",
+                BinOp::MultInt | BinOp::MultFloat => "*",
+                BinOp::DivInt | BinOp::DivFloat | BinOp::RemainderInt | BinOp::Concatenate => {
+                    panic!("Elixir printing: Inlined guard function used {:?}", name)
+                }
+            };
+            let left = guard_expr_inline(left, bindings, env);
+            let right = guard_expr_inline(right, bindings, env);
+            match name {
+                BinOp::And | BinOp::Or => env.boolean_operator(op, left, right),
+                _ => docvec!["(", left, " ", op, " ", right, ")"],
+            }
+        }
+
+        TypedExpr::Negate { value, .. } => {
+            let value = guard_expr_inline(value, bindings, env);
+            docvec![env.kernel_function("not", 1), wrap_args([value])]
+        }
+
+        TypedExpr::TupleIndex { tuple, index, .. } => {
+            let tuple = guard_expr_inline(tuple, bindings, env);
+            docvec![
+                env.kernel_function("elem", 2),
+                wrap_args([tuple, index.to_doc()])
+            ]
+        }
+
+        TypedExpr::RecordAccess {
+            record,
+            label,
+            erlang_map: true,
+            ..
+        } => {
+            let record = guard_expr_inline(record, bindings, env);
+            docvec![":erlang.map_get", wrap_args([atom(label), record])]
+        }
+
+        TypedExpr::RecordAccess { record, index, .. } => {
+            let record = guard_expr_inline(record, bindings, env);
+            docvec![
+                env.kernel_function("elem", 2),
+                wrap_args([record, (index + 1).to_doc()])
+            ]
+        }
+
+        TypedExpr::Call { fun, args, .. } => match fun.as_ref() {
+            TypedExpr::Var {
+                constructor:
+                    ValueConstructor {
+                        variant: ValueConstructorVariant::ModuleFn { name, .. },
+                        ..
+                    },
+                ..
+            } => {
+                let args = args
+                    .iter()
+                    .map(|arg| guard_expr_inline(&arg.value, bindings, env))
+                    .collect();
+                guard_function_inline(name, args, env)
+            }
+            _ => panic!("Elixir printing: Inlined guard function called a non module function"),
+        },
+
+        _ => panic!("Elixir printing: Expression cannot be inlined into a guard"),
+    }
+}
+
+fn clause_guard<'a>(guard: &'a TypedClauseGuard, env: &mut Env<'a>) -> Document<'a> {
+    match guard {
+        // Binary ops are wrapped in parens
+        ClauseGuard::Or { .. }
+        | ClauseGuard::And { .. }
+        | ClauseGuard::Equals { .. }
+        | ClauseGuard::NotEquals { .. }
+        | ClauseGuard::GtInt { .. }
+        | ClauseGuard::GtEqInt { .. }
+        | ClauseGuard::LtInt { .. }
+        | ClauseGuard::LtEqInt { .. }
+        | ClauseGuard::GtFloat { .. }
+        | ClauseGuard::GtEqFloat { .. }
+        | ClauseGuard::LtFloat { .. }
+        | ClauseGuard::LtEqFloat { .. } => bare_clause_guard(guard, env).surround("(", ")"),
+
+        // Values are not wrapped, and inlined calls wrap any operators
+        ClauseGuard::Constant(_)
+        | ClauseGuard::Var { .. }
+        | ClauseGuard::TupleIndex { .. }
+        | ClauseGuard::Call { .. } => bare_clause_guard(guard, env),
+    }
+}
+
+fn clauses<'a>(cs: &'a [TypedClause], env: &mut Env<'a>) -> Document<'a> {
+    let docs = cs
+        .iter()
+        .map(|c| {
+            let vars = env.current_scope_vars.clone();
+            let doc = clause(c, env);
+            env.current_scope_vars = vars; // Reset the known variables now the clauses' scope has ended
+            doc
+        })
+        .collect_vec();
+    join(docs, lines(2))
+}
+
+fn case<'a>(subjects: &'a [TypedExpr], cs: &'a [TypedClause], env: &mut Env<'a>) -> Document<'a> {
+    // The branches for the disabled value of a feature flag are left out
+    if let Some(clause) = cfg_case_clause(subjects, cs) {
+        return maybe_block_expr(&clause.then, env);
+    }
+    let subjects_doc = if subjects.len() == 1 {
+        let subject = subjects
+            .get(0)
+            .expect("Elixir case printing of single subject");
+        self::subject(subject, env).group()
+    } else {
+        tuple(
+            subjects
+                .iter()
+                .map(|e| maybe_block_expr(e, env))
+                .collect_vec(),
+        )
+    };
+    docvec![
+        "case ",
+        subjects_doc,
+        " do",
+        docvec![line(), clauses(cs, env)].nest(INDENT),
+        line(),
+        "end"
+    ]
+    .group()
+}
+
+fn call<'a>(fun: &'a TypedExpr, args: &'a [CallArg<TypedExpr>], env: &mut Env<'a>) -> Document<'a> {
+    let args = args
+        .iter()
+        .map(|arg| maybe_block_expr(&arg.value, env))
+        .collect();
+    docs_args_call(fun, args, env)
+}
+
+fn module_fn_with_args<'a>(
+    module: &'a [Name],
+    name: &'a str,
+    args: Vec<Document<'a>>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    if module != env.module {
+        docvec![
+            module_reference(&module.join("@")),
+            ".",
+            remote_function_name(name),
+            wrap_args(args)
+        ]
+    } else if is_elixir_reserved_word(name) {
+        docvec!["__MODULE__.", name, wrap_args(args)]
+    } else {
+        docvec![name, wrap_args(args)]
+    }
+}
+
+fn docs_args_call<'a>(
+    fun: &'a TypedExpr,
+    args: Vec<Document<'a>>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    match fun {
+        TypedExpr::ModuleSelect {
+            constructor:
+                ModuleValueConstructor::Record {
+                    erlang_map: true,
+                    field_map: Some(field_map),
+                    ..
+                },
+            ..
+        }
+        | TypedExpr::Var {
+            constructor:
+                ValueConstructor {
+                    variant:
+                        ValueConstructorVariant::Record {
+                            erlang_map: true,
+                            field_map: Some(field_map),
+                            ..
+                        },
+                    ..
+                },
+            ..
+        } => map(map_record_labels(field_map), args),
+
+        TypedExpr::ModuleSelect {
+            constructor: ModuleValueConstructor::Record { name, .. },
+            ..
+        }
+        | TypedExpr::Var {
+            constructor:
+                ValueConstructor {
+                    variant: ValueConstructorVariant::Record { name, .. },
+                    ..
+                },
+            ..
+        } => tuple(std::iter::once(atom(&name.to_snake_case())).chain(args)),
+
+        TypedExpr::Var { constructor, .. } if constructor.variant.is_rescue() => {
+            let fun = args
+                .into_iter()
+                .next()
+                .expect("rescue is called with a function");
+            rescue(fun, env)
+        }
+
+        TypedExpr::Var {
+            constructor:
+                ValueConstructor {
+                    variant: ValueConstructorVariant::ModuleFn { module, name, .. },
+                    ..
+                },
+            ..
+        } => module_fn_with_args(module, name, args, env),
+
+        // Match against a Constant::Var that contains a function.
+        // We want this to be emitted like a normal function call, not a function variable
+        // substitution.
+        TypedExpr::Var {
+            constructor:
+                ValueConstructor {
+                    variant:
+                        ValueConstructorVariant::ModuleConstant {
+                            literal:
+                                Constant::Var {
+                                    constructor: Some(ref constructor),
+                                    ..
+                                },
+                            ..
+                        },
+                    ..
+                },
+            ..
+        } if constructor.variant.is_module_fn() => {
+            if let ValueConstructorVariant::ModuleFn { module, name, .. } = &constructor.variant {
+                module_fn_with_args(module, name, args, env)
+            } else {
+                unreachable!("The above clause guard ensures that this is a module fn")
+            }
+        }
+
+        TypedExpr::ModuleSelect { constructor, .. } if constructor.is_rescue() => {
+            let fun = args
+                .into_iter()
+                .next()
+                .expect("rescue is called with a function");
+            rescue(fun, env)
+        }
+
+        // The module and name of the constructor are used rather than those
+        // of the Gleam code, so that external functions are called directly.
+        TypedExpr::ModuleSelect {
+            constructor: ModuleValueConstructor::Fn { module, name, .. },
+            ..
+        } => docvec![
+            module_reference(&module.join("@")),
+            ".",
+            remote_function_name(name),
+            wrap_args(args)
+        ],
+
+        TypedExpr::Fn {
+            is_capture: true,
+            body,
+            ..
+        } if matches!(body.as_ref(), TypedExpr::Call { .. }) => {
+            if let TypedExpr::Call {
+                fun,
+                args: inner_args,
+                ..
+            } = body.as_ref()
+            {
+                let mut args = args.into_iter();
+                let mut merged_args = Vec::with_capacity(inner_args.len());
+                for arg in inner_args {
+                    match &arg.value {
+                        TypedExpr::Var { name, .. } if is_capture_variable(name) => merged_args
+                            .push(
+                                args.next()
+                                    .expect("Elixir printing: Capture hole without an argument"),
+                            ),
+                        e => merged_args.push(expr(e, env)),
+                    }
+                }
+                docs_args_call(fun, merged_args, env)
+            } else {
+                unreachable!("The above clause guard ensures that this is a call")
+            }
+        }
+
+        TypedExpr::Var {
+            constructor:
+                ValueConstructor {
+                    variant: ValueConstructorVariant::LocalVariable { .. },
+                    ..
+                },
+            ..
+        } => docvec![expr(fun, env), ".", wrap_args(args)],
+
+        other => docvec![
+            maybe_block_expr(other, env).surround("(", ")"),
+            ".",
+            wrap_args(args)
+        ],
+    }
+}
+
+fn record_update<'a>(
+    spread: &'a TypedExpr,
+    args: &'a [TypedRecordUpdateArg],
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let expr_doc = maybe_block_expr(spread, env);
+
+    args.iter().fold(expr_doc, |tuple_doc, arg| {
+        // Increment the index by 1, because the first element
+        // is the name of the record
+        let index_doc = (arg.index + 1).to_doc();
+        let value_doc = maybe_block_expr(&arg.value, env);
+        docvec![
+            env.kernel_function("put_elem", 3),
+            wrap_args([tuple_doc, index_doc, value_doc])
+        ]
+    })
+}
+
+fn map_record_update<'a>(
+    spread: &'a TypedExpr,
+    args: &'a [TypedRecordUpdateArg],
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let map_doc = maybe_block_expr(spread, env);
+    let fields = args
+        .iter()
+        .map(|arg| docvec![key(&arg.label), " ", maybe_block_expr(&arg.value, env)])
+        .collect_vec();
+    docvec![
+        "%{",
+        map_doc,
+        " |",
+        docvec![break_("", " "), join(fields, break_(",", ", "))].nest(INDENT),
+        "}"
+    ]
+    .group()
+}
+
+fn constructor_fun<'a>(args: Vec<Document<'a>>, value: Document<'a>) -> Document<'a> {
+    docvec!["fn ", join(args, ", ".to_doc()), " -> ", value, " end"]
+}
+
+fn incrementing_args<'a>(arity: usize) -> Vec<Document<'a>> {
+    (0..arity)
+        .map(|i| Document::String(format!("field_{i}")))
+        .collect()
+}
+
+/// Wrap a document in parentheses, as a block of expressions.
+///
+fn block(document: Document<'_>) -> Document<'_> {
+    docvec!["(", line().append(document).nest(INDENT), line(), ")"].force_break()
+}
+
+/// Same as expr, expect it wraps seq, let, etc in parentheses
+///
+fn maybe_block_expr<'a>(expression: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
+    if needs_wrapping_in_block(expression) {
+        block(expr(expression, env))
+    } else {
+        expr(expression, env)
+    }
+}
+
+/// The subject of a `case` or similar expression. A `do` block is given to
+/// the outermost call without parentheses, so expressions that could end in
+/// one of their own are wrapped in parentheses.
+///
+fn subject<'a>(expression: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
+    match expression {
+        TypedExpr::Int { .. }
+        | TypedExpr::Float { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::Var { .. }
+        | TypedExpr::Fn { .. }
+        | TypedExpr::List { .. }
+        | TypedExpr::Tuple { .. }
+        | TypedExpr::BitString { .. }
+        | TypedExpr::TupleIndex { .. }
+        | TypedExpr::ModuleSelect { .. }
+        | TypedExpr::Pipeline { .. }
+        | TypedExpr::Sequence { .. }
+        | TypedExpr::Assignment { .. } => maybe_block_expr(expression, env),
+
+        TypedExpr::RecordAccess { variants, .. } if variants.is_empty() => {
+            maybe_block_expr(expression, env)
+        }
+
+        TypedExpr::Call { fun, .. } if !is_rescue(fun) => maybe_block_expr(expression, env),
+
+        _ => expr(expression, env).surround("(", ")"),
+    }
+}
+
+fn is_rescue(fun: &TypedExpr) -> bool {
+    match fun {
+        TypedExpr::Var { constructor, .. } => constructor.variant.is_rescue(),
+        TypedExpr::ModuleSelect { constructor, .. } => constructor.is_rescue(),
+        _ => false,
+    }
+}
+
+fn needs_wrapping_in_block(expression: &TypedExpr) -> bool {
+    matches!(
+        expression,
+        TypedExpr::Pipeline { .. } | TypedExpr::Sequence { .. } | TypedExpr::Assignment { .. }
+    )
+}
+
+fn todo<'a>(message: &'a Option<String>, location: SrcSpan, env: &mut Env<'a>) -> Document<'a> {
+    let message = message
+        .as_deref()
+        .unwrap_or("This has not yet been implemented");
+    elixir_error("todo", message, location, vec![], env)
+}
+
+// Gleam crashes raise the same map as the generated Erlang does, so that
+// they can be handled in the same way whatever language they come from.
+fn elixir_error<'a>(
+    name: &'a str,
+    message: &'a str,
+    location: SrcSpan,
+    fields: Vec<(&'a str, Document<'a>)>,
+    env: &Env<'a>,
+) -> Document<'a> {
+    let fields = [("gleam_error", atom(name)), ("message", string(message))]
+        .into_iter()
+        .chain(fields)
+        .chain([
+            (
+                "module",
+                Document::String(format!("\"{}\"", env.module.join("/"))),
+            ),
+            ("function", string(env.function)),
+            (
+                "line",
+                env.line_numbers.line_number(location.start).to_doc(),
+            ),
+        ])
+        .map(|(key, value)| docvec![key, ": ", value]);
+    let error = docvec![
+        "%{",
+        docvec![break_("", ""), join(fields, break_(",", ", "))].nest(INDENT),
+        break_(",", ""),
+        "}"
+    ]
+    .group();
+    docvec![":erlang.error(", error, ")"]
+}
+
+fn expr<'a>(expression: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
+    match expression {
+        TypedExpr::Todo {
+            label, location, ..
+        } => todo(label, *location, env),
+
+        TypedExpr::Int { value, .. } => int(value),
+        TypedExpr::Float { value, .. } => float(value),
+        TypedExpr::String { value, .. } => string(value),
+        TypedExpr::Pipeline { expressions, .. } | TypedExpr::Sequence { expressions, .. } => {
+            seq(expressions, env)
+        }
+
+        TypedExpr::TupleIndex { tuple, index, .. } => tuple_index(tuple, *index, env),
+
+        TypedExpr::Var {
+            name, constructor, ..
+        } => var(name, constructor, env),
+
+        TypedExpr::Fn { args, body, .. } => fun(args, body, env),
+
+        TypedExpr::Negate { value, .. } => negate(value, env),
+
+        TypedExpr::Echo { value, location } => echo(value, *location, env),
+
+        TypedExpr::Cfg { enabled, .. } => atom(&enabled.to_string()),
+
+        TypedExpr::List { elements, tail, .. } => expr_list(elements, tail, env),
+
+        TypedExpr::Call { fun, args, .. } => call(fun, args, env),
+
+        TypedExpr::ModuleSelect {
+            constructor: ModuleValueConstructor::Record { name, arity: 0, .. },
+            ..
+        } => atom(&name.to_snake_case()),
+
+        TypedExpr::ModuleSelect {
+            constructor: ModuleValueConstructor::Constant { literal, .. },
+            ..
+        } => const_inline(literal, env),
+
+        TypedExpr::ModuleSelect {
+            constructor:
+                ModuleValueConstructor::Record {
+                    erlang_map: true,
+                    field_map: Some(field_map),
+                    arity,
+                    ..
+                },
+            ..
+        } => {
+            let args = incrementing_args(*arity as usize);
+            constructor_fun(args.clone(), map(map_record_labels(field_map), args))
+        }
+
+        TypedExpr::ModuleSelect {
+            constructor: ModuleValueConstructor::Record { name, arity, .. },
+            ..
+        } => {
+            let args = incrementing_args(*arity as usize);
+            let tag = atom(&name.to_snake_case());
+            constructor_fun(args.clone(), tuple(std::iter::once(tag).chain(args)))
+        }
+
+        TypedExpr::ModuleSelect { constructor, .. } if constructor.is_rescue() => rescue_fun(env),
+
+        TypedExpr::ModuleSelect {
+            typ,
+            label,
+            module_name,
+            constructor: ModuleValueConstructor::Fn { .. },
+            ..
+        } => module_select_fn(typ.clone(), module_name, label),
+
+        TypedExpr::RecordAccess {
+            record,
+            label,
+            erlang_map: true,
+            ..
+        } => match record.as_ref() {
+            TypedExpr::Var { .. } if IDENTIFIER.is_match(label) => {
+                docvec![expr(record, env), ".", label]
+            }
+            _ => docvec![
+                "Map.fetch!",
+                wrap_args([maybe_block_expr(record, env), atom(label)])
+            ],
+        },
+
+        TypedExpr::RecordAccess {
+            record, variants, ..
+        } if !variants.is_empty() => variant_field(record, variants, env),
+
+        TypedExpr::RecordAccess { record, index, .. } => tuple_index(record, index + 1, env),
+
+        TypedExpr::RecordUpdate {
+            spread,
+            args,
+            erlang_map: true,
+            ..
+        } => map_record_update(spread, args, env),
+
+        TypedExpr::RecordUpdate { spread, args, .. } => record_update(spread, args, env),
+
+        TypedExpr::Try {
+            value,
+            pattern,
+            then,
+            ..
+        } => try_(value, pattern, then, env),
+
+        TypedExpr::Assignment {
+            value,
+            pattern,
+            kind: AssignmentKind::Assert,
+            message,
+            ..
+        } => assert(value, pattern, message.as_deref(), env),
+
+        TypedExpr::Assignment {
+            value,
+            pattern,
+            kind: AssignmentKind::Let,
+            ..
+        } => let_(value, pattern, env),
+
+        TypedExpr::Case {
+            subjects, clauses, ..
+        } => case(subjects, clauses, env),
+
+        TypedExpr::BinOp {
+            name, left, right, ..
+        } => bin_op(name, left, right, env),
+
+        TypedExpr::Tuple { elems, .. } => {
+            tuple(elems.iter().map(|e| maybe_block_expr(e, env)).collect_vec())
+        }
+
+        TypedExpr::BitString { segments, .. } => bit_string(
+            segments
+                .iter()
+                .map(|s| expr_segment(&s.value, &s.options, env))
+                .collect_vec(),
+        ),
+    }
+}
+
+fn negate<'a>(value: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
+    let value = match value {
+        TypedExpr::BinOp { .. } => expr(value, env).surround("(", ")"),
+        _ => maybe_block_expr(value, env),
+    };
+    if env.excluded_kernel_functions.contains(&("not", 1)) {
+        docvec!["Kernel.not", wrap_args([value])]
+    } else {
+        docvec!["not ", value]
+    }
+}
+
+// The value is printed to standard error as Gleam code by the entrypoint
+// module, along with where the `echo` is and the type of the value, and
+// then returned.
+fn echo<'a>(value: &'a TypedExpr, location: SrcSpan, env: &mut Env<'a>) -> Document<'a> {
+    let value_doc = subject(value, env);
+    let var = env.next_local_var_name(ECHO_VARIABLE);
+    let typ = crate::type_::pretty::Printer::new().pretty_print(&value.type_(), 0);
+    let arguments = [
+        Document::String(format!("\"{}\"", env.module.join("/"))),
+        env.line_numbers.line_number(location.start).to_doc(),
+        docvec![":\"gleam@@main\".inspect(", var.clone(), ")"],
+        Document::String(format!("\"{}\"", escape_string(&typ))),
+    ];
+    let print = docvec![
+        ":io.format",
+        wrap_args([
+            atom("standard_error"),
+            string("~ts:~b~n~ts : ~ts~n"),
+            list(join(arguments, break_(",", ", ")), None),
+        ])
+    ];
+    docvec![
+        "case ",
+        value_doc,
+        " do",
+        docvec![
+            line(),
+            var.clone(),
+            " ->",
+            docvec![line(), print, line(), var].nest(INDENT)
+        ]
+        .nest(INDENT),
+        line(),
+        "end"
+    ]
+}
+
+// The function is called and any crash is returned as an error, holding the
+// message of a Gleam crash or the printed reason of any other exception.
+fn rescue<'a>(fun: Document<'a>, env: &mut Env<'a>) -> Document<'a> {
+    let message = env.next_local_var_name(RESCUE_VARIABLE);
+    let reason = env.next_local_var_name(RESCUE_VARIABLE);
+    let crash = |message: Document<'a>| docvec!["{:error, {:crash_reason, ", message, "}}"];
+    let printed_reason = docvec![
+        ":unicode.characters_to_binary(:io_lib.format(",
+        string("~tp"),
+        ", [",
+        reason.clone(),
+        "]))"
+    ];
+    docvec![
+        "try do",
+        docvec![line(), "{:ok, (", fun, ").()}"].nest(INDENT),
+        line(),
+        "catch",
+        docvec![
+            line(),
+            "_, %{gleam_error: _, message: ",
+            message.clone(),
+            "} ->",
+            docvec![line(), crash(message)].nest(INDENT),
+            lines(2),
+            "_, ",
+            reason,
+            " ->",
+            docvec![line(), crash(printed_reason)].nest(INDENT),
+        ]
+        .nest(INDENT),
+        line(),
+        "end"
+    ]
+}
+
+// `rescue` used as a value rather than called directly.
+fn rescue_fun<'a>(env: &mut Env<'a>) -> Document<'a> {
+    let fun = env.next_local_var_name(RESCUE_VARIABLE);
+    docvec![
+        "fn ",
+        fun.clone(),
+        " ->",
+        docvec![line(), rescue(fun, env)].nest(INDENT),
+        line(),
+        "end"
+    ]
+}
+
+fn tuple_index<'a>(tuple: &'a TypedExpr, index: u64, env: &mut Env<'a>) -> Document<'a> {
+    let tuple_doc = maybe_block_expr(tuple, env);
+    docvec![
+        env.kernel_function("elem", 2),
+        wrap_args([tuple_doc, index.to_doc()])
+    ]
+}
+
+// A field shared by all the constructors of a type but at different positions
+// is accessed by matching on each constructor.
+fn variant_field<'a>(
+    record: &'a TypedExpr,
+    variants: &'a [VariantField],
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let record = subject(record, env);
+    let var = env.next_local_var_name(FIELD_VARIABLE);
+    let clauses = variants.iter().map(|variant| {
+        let fields = (0..variant.arity).map(|i| {
+            if u64::from(i) == variant.index {
+                var.clone()
+            } else {
+                "_".to_doc()
+            }
+        });
+        let tag = atom(&variant.constructor.to_snake_case());
+        docvec![
+            tuple(std::iter::once(tag).chain(fields)),
+            " -> ",
+            var.clone()
+        ]
+    });
+    docvec![
+        "case ",
+        record,
+        " do",
+        docvec![line(), join(clauses, line())].nest(INDENT),
+        line(),
+        "end"
+    ]
+    .group()
+}
+
+fn module_select_fn<'a>(typ: Arc<Type>, module_name: &'a str, label: &'a str) -> Document<'a> {
+    let module = module_reference(&module_name.replace('/', "@"));
+    match crate::type_::collapse_links(typ).as_ref() {
+        Type::Fn { args, .. } => {
+            docvec![
+                "&",
+                module,
+                ".",
+                remote_function_name(label),
+                "/",
+                args.len()
+            ]
+        }
+
+        _ => docvec![module, ".", remote_function_name(label), "()"],
+    }
+}
+
+fn fun<'a>(args: &'a [TypedArg], body: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
+    let current_scope_vars = env.current_scope_vars.clone();
+    let args = fn_args(args, env);
+    let args = if args.is_empty() {
+        nil()
+    } else {
+        docvec![" ", join(args, ", ".to_doc())]
+    };
+    let doc = docvec![
+        "fn",
+        args,
+        " ->",
+        break_("", " ").append(expr(body, env)).nest(INDENT),
+        break_("", " "),
+        "end"
+    ]
+    .group();
+    env.current_scope_vars = current_scope_vars;
+    doc
+}
+
+/// The name of a variable, which is escaped when it is an Elixir keyword or
+/// could clash with the variables the compiler introduces, whose names begin
+/// with an underscore in Gleam and with `gleam_` in Elixir. Variables that
+/// are bound more than once in a function are suffixed with a number, which
+/// is separated by a double underscore.
+fn variable_name(name: &str) -> String {
+    match name.strip_prefix('_') {
+        Some(generated) => format!("gleam_{generated}"),
+        None if is_elixir_reserved_word(name)
+            || name.starts_with("gleam_")
+            || name.ends_with('_')
+            || name.contains("__") =>
+        {
+            format!("{name}_")
+        }
+        None => name.to_string(),
+    }
+}
+
+fn type_var(id: u64) -> Document<'static> {
+    Document::String(type_variable_name(id).to_lowercase())
+}
+
+/// Elixir keywords, and the special forms and definition macros that would
+/// be called instead of a local function of the same name.
+pub fn is_elixir_reserved_word(name: &str) -> bool {
+    matches!(
+        name,
+        "do" | "end"
+            | "fn"
+            | "when"
+            | "and"
+            | "or"
+            | "not"
+            | "in"
+            | "true"
+            | "false"
+            | "nil"
+            | "catch"
+            | "rescue"
+            | "after"
+            | "else"
+            | "case"
+            | "cond"
+            | "for"
+            | "receive"
+            | "try"
+            | "with"
+            | "quote"
+            | "unquote"
+            | "unquote_splicing"
+            | "import"
+            | "alias"
+            | "require"
+            | "super"
+            | "def"
+            | "defp"
+            | "defmodule"
+            | "defmacro"
+            | "defmacrop"
+    )
+}
+
+/// The functions and macros that `Kernel` imports into every module, other
+/// than the definition macros, operators and sigils.
+fn is_kernel_function(name: &str, arity: usize) -> bool {
+    matches!(
+        (name, arity),
+        ("abs", 1)
+            | ("and", 2)
+            | ("apply", 2 | 3)
+            | ("binary_part", 3)
+            | ("binary_slice", 2 | 3)
+            | ("binding", 0 | 1)
+            | ("bit_size", 1)
+            | ("byte_size", 1)
+            | ("ceil", 1)
+            | ("dbg", 0..=2)
+            | ("destructure", 2)
+            | ("div", 2)
+            | ("elem", 2)
+            | ("exit", 1)
+            | ("floor", 1)
+            | ("get_and_update_in", 2 | 3)
+            | ("get_in", 1 | 2)
+            | ("hd", 1)
+            | ("if", 2)
+            | ("in", 2)
+            | ("inspect", 1 | 2)
+            | ("is_atom", 1)
+            | ("is_binary", 1)
+            | ("is_bitstring", 1)
+            | ("is_boolean", 1)
+            | ("is_exception", 1 | 2)
+            | ("is_float", 1)
+            | ("is_function", 1 | 2)
+            | ("is_integer", 1)
+            | ("is_list", 1)
+            | ("is_map", 1)
+            | ("is_map_key", 2)
+            | ("is_nil", 1)
+            | ("is_non_struct_map", 1)
+            | ("is_number", 1)
+            | ("is_pid", 1)
+            | ("is_port", 1)
+            | ("is_reference", 1)
+            | ("is_struct", 1 | 2)
+            | ("is_tuple", 1)
+            | ("length", 1)
+            | ("make_ref", 0)
+            | ("map_size", 1)
+            | ("max", 2)
+            | ("min", 2)
+            | ("node", 0 | 1)
+            | ("not", 1)
+            | ("or", 2)
+            | ("pop_in", 1 | 2)
+            | ("put_elem", 3)
+            | ("put_in", 2 | 3)
+            | ("raise", 1 | 2)
+            | ("rem", 2)
+            | ("reraise", 2 | 3)
+            | ("round", 1)
+            | ("self", 0)
+            | ("send", 2)
+            | ("spawn", 1 | 3)
+            | ("spawn_link", 1 | 3)
+            | ("spawn_monitor", 1 | 3)
+            | ("struct", 1 | 2)
+            | ("tap", 2)
+            | ("then", 2)
+            | ("throw", 1)
+            | ("tl", 1)
+            | ("to_charlist", 1)
+            | ("to_string", 1)
+            | ("trunc", 1)
+            | ("tuple_size", 1)
+            | ("unless", 2)
+            | ("update_in", 2 | 3)
+            | ("use", 1 | 2)
+    )
+}
+
+fn elixir_safe_type_name(mut name: String) -> String {
+    if is_elixir_reserved_word(&name)
+        || matches!(
+            name.as_str(),
+            "any"
+                | "arity"
+                | "as_boolean"
+                | "atom"
+                | "binary"
+                | "bitstring"
+                | "boolean"
+                | "byte"
+                | "char"
+                | "charlist"
+                | "float"
+                | "fun"
+                | "function"
+                | "identifier"
+                | "integer"
+                | "iodata"
+                | "iolist"
+                | "keyword"
+                | "list"
+                | "map"
+                | "maybe_improper_list"
+                | "mfa"
+                | "module"
+                | "neg_integer"
+                | "no_return"
+                | "node"
+                | "non_neg_integer"
+                | "none"
+                | "nonempty_binary"
+                | "nonempty_bitstring"
+                | "nonempty_charlist"
+                | "nonempty_improper_list"
+                | "nonempty_list"
+                | "nonempty_maybe_improper_list"
+                | "number"
+                | "pid"
+                | "port"
+                | "pos_integer"
+                | "reference"
+                | "string"
+                | "nonempty_string"
+                | "struct"
+                | "term"
+                | "timeout"
+                | "tuple"
+        )
+    {
+        name.push('_');
+    }
+    name
+}
+
+#[derive(Debug)]
+struct TypePrinter<'a> {
+    current_module: &'a [Name],
+    var_usages: Option<&'a BTreeMap<u64, u64>>,
+}
+
+impl<'a> TypePrinter<'a> {
+    fn new(current_module: &'a [Name]) -> Self {
+        Self {
+            current_module,
+            var_usages: None,
+        }
+    }
+
+    pub fn with_var_usages(mut self, var_usages: &'a BTreeMap<u64, u64>) -> Self {
+        self.var_usages = Some(var_usages);
+        self
+    }
+
+    pub fn print(&self, type_: &Type) -> Document<'static> {
+        match type_ {
+            Type::Var { type_: typ } => self.print_var(&typ.borrow()),
+
+            Type::App {
+                name, module, args, ..
+            } if module.is_empty() => self.print_prelude_type(name, args),
+
+            Type::App {
+                name, module, args, ..
+            } => self.print_type_app(module, name, args),
+
+            Type::Fn { args, retrn } => self.print_fn(args, retrn),
+
+            Type::Tuple { elems } => tuple(elems.iter().map(|e| self.print(e)).collect_vec()),
+        }
+    }
+
+    fn print_var(&self, type_: &TypeVar) -> Document<'static> {
+        match type_ {
+            TypeVar::Generic { id, .. } | TypeVar::Unbound { id, .. } => match &self.var_usages {
+                Some(usages) => match usages.get(id) {
+                    Some(&0) => nil(),
+                    Some(&1) => "any()".to_doc(),
+                    _ => type_var(*id),
+                },
+                None => type_var(*id),
+            },
+            TypeVar::Link { type_: typ } => self.print(typ),
+        }
+    }
+
+    fn print_prelude_type(&self, name: &str, args: &[Arc<Type>]) -> Document<'static> {
+        match name {
+            "Nil" => "nil".to_doc(),
+            "Int" | "UtfCodepoint" => "integer()".to_doc(),
+            "String" => "binary()".to_doc(),
+            "Bool" => "boolean()".to_doc(),
+            "Float" => "float()".to_doc(),
+            "BitString" => "bitstring()".to_doc(),
+            "CrashReason" => tuple([atom("crash_reason"), "binary()".to_doc()]),
+            "List" => {
+                let arg0 = self.print(args.get(0).expect("print_prelude_type list"));
+                docvec!["list(", arg0, ")"]
+            }
+            "Result" => {
+                let arg_ok = self.print(args.get(0).expect("print_prelude_type result ok"));
+                let arg_err = self.print(args.get(1).expect("print_prelude_type result err"));
+                let ok = tuple([atom("ok"), arg_ok]);
+                let error = tuple([atom("error"), arg_err]);
+                docvec![ok, break_(" |", " | "), error].nest(INDENT).group()
+            }
+            // Getting here should mean we either forgot a built-in type or
+            // there is a compiler error
+            name => panic!("{} is not a built-in type.", name),
+        }
+    }
+
+    fn print_type_app(&self, module: &[Name], name: &str, args: &[Arc<Type>]) -> Document<'static> {
+        let args = join(
+            args.iter().map(|a| self.print(a)).collect_vec(),
+            ", ".to_doc(),
+        );
+        let name = Document::String(elixir_safe_type_name(name.to_snake_case()));
+        if self.current_module == module {
+            docvec![name, "(", args, ")"]
+        } else {
+            let module = module.iter().map(|m| m.to_snake_case()).join("@");
+            docvec![atom(&module), ".", name, "(", args, ")"]
+        }
+    }
+
+    fn print_fn(&self, args: &[Arc<Type>], retrn: &Type) -> Document<'static> {
+        let args = join(
+            args.iter().map(|a| self.print(a)).collect_vec(),
+            ", ".to_doc(),
+        );
+        let retrn = self.print(retrn);
+        if args.is_empty() {
+            docvec!["(-> ", retrn, ")"]
+        } else {
+            docvec!["(", args, " -> ", retrn, ")"]
+        }
+    }
+}
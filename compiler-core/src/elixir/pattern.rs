@@ -0,0 +1,166 @@
+use super::*;
+
+pub(super) fn pattern<'a>(p: &'a TypedPattern, env: &mut Env<'a>) -> Document<'a> {
+    let mut vars = vec![];
+    to_doc(p, &mut vars, env)
+}
+
+/// A pattern that only checks the shape of a value, binding its variables
+/// with underscored names so that Elixir does not warn they are unused.
+pub(super) fn unused_variables_pattern<'a>(p: &'a TypedPattern, env: &mut Env<'a>) -> Document<'a> {
+    env.unused_pattern_variables = true;
+    let doc = pattern(p, env);
+    env.unused_pattern_variables = false;
+    doc
+}
+
+fn bind<'a>(name: &'a str, vars: &mut Vec<&'a str>, env: &mut Env<'a>) -> Document<'a> {
+    vars.push(name);
+    let var = env.next_local_var_name(name);
+    if env.unused_pattern_variables {
+        docvec!["_", var]
+    } else {
+        var
+    }
+}
+
+fn to_doc<'a>(p: &'a TypedPattern, vars: &mut Vec<&'a str>, env: &mut Env<'a>) -> Document<'a> {
+    match p {
+        Pattern::Assign {
+            name, pattern: p, ..
+        } => {
+            let pattern = to_doc(p, vars, env);
+            pattern.append(" = ").append(bind(name, vars, env))
+        }
+
+        Pattern::List { elements, tail, .. } => pattern_list(elements, tail.as_deref(), vars, env),
+
+        Pattern::Discard { .. } => "_".to_doc(),
+
+        Pattern::VarUsage { name, .. } => {
+            let var = env.local_var_name(name);
+            if env.unused_pattern_variables && vars.contains(&name.as_str()) {
+                docvec!["_", var]
+            } else {
+                var
+            }
+        }
+
+        Pattern::Var { name, .. } => bind(name, vars, env),
+
+        Pattern::Int { value, .. } => int(value),
+
+        Pattern::IntRange { start, end, .. } => {
+            let var = env.next_local_var_name(RANGE_VARIABLE);
+            let check = env.boolean_operator(
+                "and",
+                docvec![var.clone(), " >= ", int(start)],
+                docvec![var.clone(), " <= ", int(end)],
+            );
+            env.int_range_checks.push(check);
+            var
+        }
+
+        Pattern::Float { value, .. } => float(value),
+
+        Pattern::String { value, .. } => string(value),
+
+        Pattern::Constructor {
+            arguments: args,
+            constructor:
+                PatternConstructor::Record {
+                    erlang_map: true,
+                    field_map: Some(field_map),
+                    ..
+                },
+            ..
+        } => {
+            let values = args
+                .iter()
+                .map(|p| to_doc(&p.value, vars, env))
+                .collect_vec();
+            map(map_record_labels(field_map), values)
+        }
+
+        Pattern::Constructor {
+            arguments: args,
+            constructor: PatternConstructor::Record { name, .. },
+            ..
+        } => tag_tuple_pattern(name, args, vars, env),
+
+        Pattern::Tuple { elems, .. } => tuple(elems.iter().map(|p| to_doc(p, vars, env))),
+
+        Pattern::BitString { segments, .. } => bit_string(
+            segments
+                .iter()
+                .map(|s| pattern_segment(&s.value, &s.options, vars, env)),
+        ),
+
+        Pattern::Concatenate {
+            left_side_string: left,
+            right_side_assignment: right,
+            ..
+        } => {
+            let right = match right {
+                AssignName::Variable(right) => bind(right, vars, env),
+                AssignName::Discard(_) => "_".to_doc(),
+            };
+            docvec![string(left), " <> ", right]
+        }
+    }
+}
+
+fn tag_tuple_pattern<'a>(
+    name: &'a str,
+    args: &'a [CallArg<TypedPattern>],
+    vars: &mut Vec<&'a str>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    if args.is_empty() {
+        atom(&name.to_snake_case())
+    } else {
+        tuple(
+            [atom(&name.to_snake_case())]
+                .into_iter()
+                .chain(args.iter().map(|p| to_doc(&p.value, vars, env))),
+        )
+    }
+}
+
+fn pattern_segment<'a>(
+    value: &'a TypedPattern,
+    options: &'a [BitStringSegmentOption<TypedPattern>],
+    vars: &mut Vec<&'a str>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let document = match value {
+        // As normal
+        Pattern::String { .. }
+        | Pattern::Discard { .. }
+        | Pattern::Var { .. }
+        | Pattern::Int { .. }
+        | Pattern::IntRange { .. }
+        | Pattern::Float { .. } => to_doc(value, vars, env),
+
+        // No other pattern variants are allowed in pattern bit string segments
+        _ => panic!("Pattern segment match not recognised"),
+    };
+
+    let size = |value: &'a TypedPattern, env: &mut Env<'a>| to_doc(value, vars, env);
+
+    bit_string_segment(document, options, size, true, env)
+}
+
+fn pattern_list<'a>(
+    elements: &'a [TypedPattern],
+    tail: Option<&'a TypedPattern>,
+    vars: &mut Vec<&'a str>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let elements = concat(Itertools::intersperse(
+        elements.iter().map(|e| to_doc(e, vars, env)),
+        break_(",", ", "),
+    ));
+    let tail = tail.map(|tail| to_doc(tail, vars, env));
+    list(elements, tail)
+}
@@ -0,0 +1,347 @@
+#[macro_export]
+macro_rules! assert_ex {
+    (($dep_package:expr, $dep_name:expr, $dep_src:expr), $src:expr $(,)?) => {{
+        use $crate::{elixir::module, line_numbers::LineNumbers, uid::UniqueIdGenerator};
+        let mut modules = im::HashMap::new();
+        let ids = UniqueIdGenerator::new();
+        let _ = modules.insert("gleam".to_string(), $crate::type_::build_prelude(&ids));
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($dep_src, &arena).expect("dep syntax error");
+        ast.name = $dep_name.iter().map(|name| name.as_str().into()).collect();
+        let dep = $crate::type_::infer_module(
+            $crate::build::Target::Erlang,
+            &ids,
+            ast,
+            $crate::build::Origin::Src,
+            $dep_package,
+            &modules,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let _ = modules.insert($dep_name.join("/"), dep.type_info);
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my".into(), "mod".into()];
+        let ast = $crate::type_::infer_module(
+            $crate::build::Target::Erlang,
+            &ids,
+            ast,
+            $crate::build::Origin::Src,
+            "thepackage",
+            &modules,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let mut output = String::new();
+        let line_numbers = LineNumbers::new($src);
+        module(
+            &ast,
+            &line_numbers,
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    }};
+
+    ($src:expr $(,)?) => {{
+        use $crate::{
+            build::Origin,
+            elixir::module,
+            line_numbers::LineNumbers,
+            type_::{build_prelude, infer_module},
+            uid::UniqueIdGenerator,
+        };
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, extra) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["the_app".into()];
+        let mut modules = im::HashMap::new();
+        let ids = UniqueIdGenerator::new();
+        let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+        let ast = infer_module(
+            $crate::build::Target::Erlang,
+            &ids,
+            ast,
+            Origin::Src,
+            "thepackage",
+            &modules,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        // Documentation is attached as it is when compiling a package
+        let mut compiled = $crate::build::Module {
+            name: "the_app".into(),
+            code: $src.into(),
+            input_path: "src/the_app.gleam".into(),
+            origin: Origin::Src,
+            ast,
+            extra,
+        };
+        compiled.attach_doc_and_module_comments();
+        let mut output = String::new();
+        let line_numbers = LineNumbers::new($src);
+        module(
+            &compiled.ast,
+            &line_numbers,
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    }};
+}
+
+#[test]
+fn functions() {
+    assert_ex!(
+        r#"
+pub fn add(x: Int, y: Int) -> Int {
+  x + y
+}
+
+fn double(x) {
+  let x = x * 2
+  let x = x + 0
+  x
+}
+
+pub fn main() {
+  let f = fn(a) { double(a) }
+  let g = add(1, _)
+  #(f(1), g(2), "Hello, #{name}" <> "!", [1, 2, ..[3]], 1.5 /. 2.0)
+}
+"#
+    );
+}
+
+#[test]
+fn division() {
+    assert_ex!(
+        r#"
+pub fn main(x, y) {
+  #(x / y, x % y, x / 2)
+}
+
+pub fn float(x, y) {
+  #(x /. y, x /. 0.5)
+}
+"#
+    );
+}
+
+#[test]
+fn case() {
+    assert_ex!(
+        r#"
+pub fn main(x, y) {
+  case x, y {
+    0, _ -> "zero"
+    1, [] | 2, [] -> "small"
+    n, [first, ..] if n > first && first != 3 -> "bigger"
+    _, _ -> "other"
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn records() {
+    assert_ex!(
+        r#"
+pub type Pet {
+  Cat(name: String, lives: Int)
+  Dog(name: String)
+  Fish
+}
+
+pub type Box {
+  Box(value: Int, label: String)
+}
+
+pub fn update(box: Box) {
+  Box(..box, value: box.value + 1)
+}
+
+pub fn main(pet: Pet) {
+  let tom = Cat("Tom", lives: 9)
+  let make = Dog
+  case pet {
+    Cat(name, lives) -> #(lives, make(name))
+    Dog(name) -> #(0, make(name))
+    Fish -> #(0, Fish)
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn erlang_map_records() {
+    assert_ex!(
+        r#"
+@erlang_map
+pub type Cat {
+  Cat(name: String, cuteness: Int)
+}
+
+pub fn main(cat: Cat) {
+  let tom = Cat(cuteness: 10, name: "Tom")
+  let tom = Cat(..tom, name: cat.name)
+  case cat {
+    Cat(cuteness: 10, ..) -> tom.cuteness
+    Cat(cuteness: c, ..) -> c
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn bit_strings() {
+    assert_ex!(
+        r#"
+pub fn main(x: Int, s: String) {
+  let <<a:8, rest:binary>> = <<x:size(x), s:utf8, "hi":utf8, 1.0:float>>
+  #(a, rest)
+}
+"#
+    );
+}
+
+#[test]
+fn assert() {
+    assert_ex!(
+        r#"
+pub fn main(x) {
+  assert [first, ..rest] = x
+  #(first, rest)
+}
+"#
+    );
+}
+
+#[test]
+fn try_() {
+    assert_ex!(
+        r#"
+pub fn main(x) {
+  try y = x
+  Ok(y + 1)
+}
+"#
+    );
+}
+
+#[test]
+fn reserved_words() {
+    assert_ex!(
+        r#"
+pub fn receive(do, end) {
+  #(do, end)
+}
+
+pub fn max(a, b) {
+  receive(a, b)
+}
+
+pub fn main(x, y, z: #(Bool, Int)) {
+  let f = receive
+  #(f(x, y), max(x, y), z.0 && y, z.1 / x)
+}
+"#
+    );
+}
+
+#[test]
+fn documentation() {
+    assert_ex!(
+        r#"//// A module for #{testing}.
+////
+//// With two paragraphs.
+
+/// A type.
+pub type Thing(a) {
+  Thing
+}
+
+/// A function.
+///
+///     let x = "\""
+@deprecated("Use something else")
+pub fn main() -> Thing(a) {
+  Thing
+}
+
+/// A private function.
+fn private() {
+  Nil
+}
+"#
+    );
+}
+
+#[test]
+fn external_functions() {
+    assert_ex!(
+        r#"
+pub external type Atom
+
+pub external fn upcase(String) -> String = "Elixir.String" "upcase"
+
+pub external fn to_atom(String) -> Atom = "erlang" "binary_to_atom"
+
+external fn go(x: Int) -> Int = "m" "f"
+
+pub fn main() {
+  #(upcase("a"), to_atom("b"), go(1), go)
+}
+"#
+    );
+}
+
+#[test]
+fn other_module() {
+    assert_ex!(
+        (
+            "other_package",
+            vec!["other".to_string()],
+            r#"
+pub type Box(a) {
+  Box(a)
+}
+
+pub const answer = 42
+
+pub fn unbox(box: Box(a)) -> a {
+  let Box(a) = box
+  a
+}
+"#
+        ),
+        r#"
+import other
+
+pub fn main(box: other.Box(Int)) -> Int {
+  let f = other.unbox
+  other.unbox(box) + f(box) + other.answer
+}
+"#
+    );
+}
+
+#[test]
+fn crashes() {
+    assert_ex!(
+        r#"
+pub fn main(f) {
+  let x = echo rescue(f)
+  let g = rescue
+  case x {
+    Ok(_) -> g(f)
+    Error(_) -> todo("not yet")
+  }
+}
+"#
+    );
+}
@@ -13,8 +13,8 @@ use crate::{
     line_numbers::LineNumbers,
     pretty::*,
     type_::{
-        ModuleValueConstructor, PatternConstructor, Type, TypeVar, ValueConstructor,
-        ValueConstructorVariant,
+        guard::GuardFunctions, FieldMap, ModuleValueConstructor, PatternConstructor, Type, TypeVar,
+        ValueConstructor, ValueConstructorVariant, VariantField,
     },
     Result,
 };
@@ -22,16 +22,22 @@ use heck::ToSnakeCase;
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use pattern::pattern;
-use std::{char, collections::HashMap, ops::Deref, str::FromStr, sync::Arc};
+use std::{
+    char,
+    collections::{BTreeMap, HashMap},
+    ops::Deref,
+    rc::Rc,
+    str::FromStr,
+    sync::Arc,
+};
 
 const INDENT: isize = 4;
-const MAX_COLUMNS: isize = 80;
 
 fn module_name_to_erlang(module: &str) -> Document<'_> {
     Document::String(module.replace('/', "@"))
 }
 
-fn module_name_join(module: &[String]) -> Document<'_> {
+fn module_name_join(module: &[Name]) -> Document<'_> {
     let mut name = String::new();
     for (i, segment) in module.iter().enumerate() {
         if i != 0 {
@@ -44,23 +50,32 @@ fn module_name_join(module: &[String]) -> Document<'_> {
 
 #[derive(Debug, Clone)]
 struct Env<'a> {
-    module: &'a [String],
+    module: &'a [Name],
     function: &'a str,
     line_numbers: &'a LineNumbers,
     current_scope_vars: im::HashMap<String, usize>,
     erl_function_scope_vars: im::HashMap<String, usize>,
+    /// The checks for the range patterns of the clause being generated, as
+    /// ranges cannot be matched by Erlang patterns and must be in the guard.
+    int_range_checks: Vec<Document<'a>>,
+    /// Functions of the module that are called in clause guards. Erlang does
+    /// not permit calling them there so their bodies are inlined instead.
+    guard_functions: Rc<GuardFunctions<'a>>,
 }
 
 impl<'env> Env<'env> {
     pub fn new(
-        module: &'env [String],
+        module: &'env [Name],
         function: &'env str,
         line_numbers: &'env LineNumbers,
+        guard_functions: Rc<GuardFunctions<'env>>,
     ) -> Self {
         let vars: im::HashMap<_, _> = std::iter::once(("_".to_string(), 0)).collect();
         Self {
             current_scope_vars: vars.clone(),
             erl_function_scope_vars: vars,
+            int_range_checks: vec![],
+            guard_functions,
             line_numbers,
             function,
             module,
@@ -92,13 +107,14 @@ impl<'env> Env<'env> {
     }
 }
 
-pub fn records(module: &TypedModule) -> Vec<(&str, String)> {
+pub fn records(module: &TypedModule, line_length: isize) -> Vec<(&str, String)> {
     module
         .statements
         .iter()
         .filter_map(|s| match s {
             Statement::CustomType {
                 public: true,
+                erlang_map: false,
                 constructors,
                 ..
             } => Some(constructors),
@@ -124,11 +140,11 @@ pub fn records(module: &TypedModule) -> Vec<(&str, String)> {
                 .collect::<Option<Vec<_>>>()
                 .map(|fields| (constructor.name.as_str(), fields))
         })
-        .map(|(name, fields)| (name, record_definition(name, &fields)))
+        .map(|(name, fields)| (name, record_definition(name, &fields, line_length)))
         .collect()
 }
 
-pub fn record_definition(name: &str, fields: &[(&str, Arc<Type>)]) -> String {
+pub fn record_definition(name: &str, fields: &[(&str, Arc<Type>)], line_length: isize) -> String {
     let name = &name.to_snake_case();
     let type_printer = TypePrinter::new(&[]).var_as_any();
     let fields = fields.iter().map(move |(name, type_)| {
@@ -148,15 +164,16 @@ pub fn record_definition(name: &str, fields: &[(&str, Arc<Type>)]) -> String {
         "}).",
         line()
     )
-    .to_pretty_string(MAX_COLUMNS)
+    .to_pretty_string(line_length)
 }
 
 pub fn module<'a>(
     module: &'a TypedModule,
     line_numbers: &'a LineNumbers,
+    line_length: isize,
     writer: &mut impl Utf8Writer,
 ) -> Result<()> {
-    module_document(module, line_numbers)?.pretty_print(MAX_COLUMNS, writer)
+    module_document(module, line_numbers)?.pretty_print(line_length, writer)
 }
 
 fn module_document<'a>(
@@ -227,11 +244,17 @@ fn module_document<'a>(
         concat(Itertools::intersperse(type_defs.into_iter(), lines(2))).append(lines(2))
     };
 
+    let guard_functions = Rc::new(GuardFunctions::new(&module.name, &module.statements));
     let statements = concat(Itertools::intersperse(
-        module
-            .statements
-            .iter()
-            .flat_map(|s| statement(&module.name, s, &module.name, line_numbers)),
+        module.statements.iter().flat_map(|s| {
+            statement(
+                &module.name,
+                s,
+                &module.name,
+                line_numbers,
+                &guard_functions,
+            )
+        }),
         lines(2),
     ));
 
@@ -249,7 +272,7 @@ fn register_imports(
     exports: &mut Vec<Document<'_>>,
     type_exports: &mut Vec<Document<'_>>,
     type_defs: &mut Vec<Document<'_>>,
-    module_name: &[String],
+    module_name: &[Name],
 ) {
     match s {
         Statement::Fn {
@@ -309,13 +332,14 @@ fn register_imports(
             constructors,
             typed_parameters,
             opaque,
+            erlang_map,
             ..
         } => {
             // Erlang doesn't allow phantom type variables in type definitions but gleam does
             // so we check the type declaratinon against its constroctors and generate a phantom
             // value that uses the unused type variables.
-            let type_var_usages = collect_type_var_usages(HashMap::new(), typed_parameters);
-            let mut constructor_var_usages = HashMap::new();
+            let type_var_usages = collect_type_var_usages(BTreeMap::new(), typed_parameters);
+            let mut constructor_var_usages = BTreeMap::new();
             for c in constructors {
                 constructor_var_usages = collect_type_var_usages(
                     constructor_var_usages,
@@ -326,7 +350,7 @@ fn register_imports(
                 .keys()
                 .filter(|&id| !constructor_var_usages.contains_key(id))
                 .map(|&id| Type::Var {
-                    type_: Arc::new(std::cell::RefCell::new(TypeVar::Generic { id })),
+                    type_: Arc::new(crate::type_::TypeVarCell::new(TypeVar::Generic { id })),
                 })
                 .collect();
             let phantom_vars_constructor = if !phantom_vars.is_empty() {
@@ -352,6 +376,11 @@ fn register_imports(
                         let name = atom(c.name.to_snake_case());
                         if c.arguments.is_empty() {
                             name
+                        } else if *erlang_map {
+                            let type_printer = TypePrinter::new(module_name);
+                            let labels = c.arguments.iter().filter_map(|a| a.label.as_deref());
+                            let args = c.arguments.iter().map(|a| type_printer.print(&a.type_));
+                            map_record(labels, args, " := ")
                         } else {
                             let type_printer = TypePrinter::new(module_name);
                             let args = c.arguments.iter().map(|a| type_printer.print(&a.type_));
@@ -388,10 +417,11 @@ fn register_imports(
 }
 
 fn statement<'a>(
-    current_module: &'a [String],
+    current_module: &'a [Name],
     statement: &'a TypedStatement,
-    module: &'a [String],
+    module: &'a [Name],
     line_numbers: &'a LineNumbers,
+    guard_functions: &Rc<GuardFunctions<'a>>,
 ) -> Vec<Document<'a>> {
     match statement {
         Statement::TypeAlias { .. }
@@ -407,7 +437,15 @@ fn statement<'a>(
             body,
             return_type,
             ..
-        } => vec![mod_fun(name, args, body, module, return_type, line_numbers)],
+        } => vec![mod_fun(
+            name,
+            args,
+            body,
+            module,
+            return_type,
+            line_numbers,
+            guard_functions.clone(),
+        )],
 
         Statement::ExternalFn {
             fun,
@@ -431,13 +469,14 @@ fn mod_fun<'a>(
     name: &'a str,
     args: &'a [TypedArg],
     body: &'a TypedExpr,
-    module: &'a [String],
+    module: &'a [Name],
     return_type: &'a Arc<Type>,
     line_numbers: &'a LineNumbers,
+    guard_functions: Rc<GuardFunctions<'a>>,
 ) -> Document<'a> {
-    let mut env = Env::new(module, name, line_numbers);
+    let mut env = Env::new(module, name, line_numbers, guard_functions);
     let var_usages = collect_type_var_usages(
-        HashMap::new(),
+        BTreeMap::new(),
         std::iter::once(return_type).chain(args.iter().map(|a| &a.type_)),
     );
     let type_printer = TypePrinter::new(module).with_var_usages(&var_usages);
@@ -603,7 +642,7 @@ fn const_segment<'a>(
         ),
     };
 
-    let unit = |value: &'a u8| Some(Document::String(format!("unit:{}", value)));
+    let unit = |value: &'a u8| Some(docvec!["unit:", *value]);
 
     bit_string_segment(document, options, size, unit, true, env)
 }
@@ -636,7 +675,7 @@ fn expr_segment<'a>(
         TypedExpr::Int { value, .. } => {
             let v = value.replace('_', "");
             let v = u64::from_str(&v).unwrap_or(0);
-            Some(Document::String(format!(":{}", v)))
+            Some(docvec![":", v])
         }
 
         _ => {
@@ -652,7 +691,7 @@ fn expr_segment<'a>(
         }
     };
 
-    let unit = |value: &'a u8| Some(Document::String(format!("unit:{}", value)));
+    let unit = |value: &'a u8| Some(docvec!["unit:", *value]);
 
     bit_string_segment(
         document,
@@ -856,7 +895,12 @@ fn try_<'a>(
         .group()
 }
 
-fn assert<'a>(value: &'a TypedExpr, pat: &'a TypedPattern, env: &mut Env<'a>) -> Document<'a> {
+fn assert<'a>(
+    value: &'a TypedExpr,
+    pat: &'a TypedPattern,
+    message: Option<&'a str>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
     let mut vars: Vec<&str> = vec![];
     let body = maybe_block_expr(value, env);
     let pattern1 = pattern::to_doc(pat, &mut vars, env);
@@ -873,7 +917,7 @@ fn assert<'a>(value: &'a TypedExpr, pat: &'a TypedPattern, env: &mut Env<'a>) ->
             line(),
             erlang_error(
                 "assert",
-                "Assertion pattern match failed",
+                message.unwrap_or("Assertion pattern match failed"),
                 pat.location(),
                 vec![("value", env.local_var_name(ASSERT_VARIABLE))],
                 env,
@@ -899,11 +943,7 @@ fn let_<'a>(value: &'a TypedExpr, pat: &'a TypedPattern, env: &mut Env<'a>) -> D
 }
 
 fn float<'a>(value: &str) -> Document<'a> {
-    let mut value = value.replace('_', "");
-    if value.ends_with('.') {
-        value.push('0')
-    }
-    Document::String(value)
+    Document::String(crate::float::canonical_literal(value))
 }
 
 fn expr_list<'a>(
@@ -930,6 +970,13 @@ fn list<'a>(elems: Document<'a>, tail: Option<Document<'a>>) -> Document<'a> {
 
 fn var<'a>(name: &'a str, constructor: &'a ValueConstructor, env: &mut Env<'a>) -> Document<'a> {
     match &constructor.variant {
+        ValueConstructorVariant::Record {
+            erlang_map: true,
+            field_map: Some(field_map),
+            arity,
+            ..
+        } => map_record_constructor_fun(field_map, *arity as usize),
+
         ValueConstructorVariant::Record {
             name: record_name, ..
         } => match constructor.type_.deref() {
@@ -951,6 +998,8 @@ fn var<'a>(name: &'a str, constructor: &'a ValueConstructor, env: &mut Env<'a>)
 
         ValueConstructorVariant::ModuleConstant { literal, .. } => const_inline(literal, env),
 
+        variant if variant.is_rescue() => rescue_fun(env),
+
         ValueConstructorVariant::ModuleFn {
             arity, ref module, ..
         } if module == env.module => "fun "
@@ -1024,6 +1073,10 @@ fn const_inline<'a>(literal: &'a TypedConstant, env: &mut Env<'a>) -> Document<'
                 .expect("This is guaranteed to hold a value."),
             env,
         ),
+
+        Constant::BinOp { .. } => {
+            unreachable!("Constant binary operators are evaluated during type checking")
+        }
     }
 }
 
@@ -1057,7 +1110,18 @@ fn clause<'a>(clause: &'a TypedClause, env: &mut Env<'a>) -> Document<'a> {
                     tuple(patterns.iter().map(|p| pattern(p, env)))
                 };
 
-                let guard = optional_clause_guard(guard.as_ref(), env);
+                let range_checks = std::mem::take(&mut env.int_range_checks);
+                let guard = if range_checks.is_empty() {
+                    optional_clause_guard(guard.as_ref(), env)
+                } else {
+                    let checks = join(range_checks, " andalso ".to_doc());
+                    match guard {
+                        Some(guard) => {
+                            docvec![" when ", checks, " andalso ", clause_guard(guard, env)]
+                        }
+                        None => docvec![" when ", checks],
+                    }
+                };
                 if then_doc.is_none() {
                     then_doc = Some(expr(then, env));
                     end_erlang_vars = env.erl_function_scope_vars.clone();
@@ -1142,10 +1206,133 @@ fn bare_clause_guard<'a>(guard: &'a TypedClauseGuard, env: &mut Env<'a>) -> Docu
 
         ClauseGuard::TupleIndex { tuple, index, .. } => tuple_index_inline(tuple, *index, env),
 
+        ClauseGuard::Call { name, args, .. } => {
+            let args = args.iter().map(|arg| clause_guard(arg, env)).collect();
+            guard_function_inline(name, args, env)
+        }
+
         ClauseGuard::Constant(constant) => const_inline(constant, env),
     }
 }
 
+// Functions cannot be called in Erlang guards, so the body of the function is
+// used instead, with the arguments in place of its parameters. The type
+// checker ensures only functions with a body that can be inlined are called.
+fn guard_function_inline<'a>(
+    name: &str,
+    args: Vec<Document<'a>>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let function = env
+        .guard_functions
+        .get(name)
+        .expect("Erl printing: Guard called a function that cannot be inlined");
+    let bindings = function
+        .arguments
+        .iter()
+        .zip(args)
+        .filter_map(|(parameter, arg)| Some((parameter.get_variable_name()?, arg)))
+        .collect();
+    guard_expr_inline(function.body, &bindings, env)
+}
+
+fn guard_expr_inline<'a>(
+    expression: &'a TypedExpr,
+    bindings: &HashMap<&str, Document<'a>>,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    match expression {
+        TypedExpr::Int { value, .. } => int(value),
+        TypedExpr::Float { value, .. } => float(value),
+        TypedExpr::String { value, .. } => string(value),
+        TypedExpr::Cfg { enabled, .. } => atom(enabled.to_string()),
+
+        TypedExpr::Var { name, .. } => bindings
+            .get(name.as_str())
+            .cloned()
+            .expect("Erl printing: Inlined guard function variable was not an argument"),
+
+        TypedExpr::Sequence { expressions, .. } => match expressions.as_slice() {
+            [expression] => guard_expr_inline(expression, bindings, env),
+            _ => panic!("Erl printing: Inlined guard function had multiple expressions"),
+        },
+
+        TypedExpr::BinOp {
+            name, left, right, ..
+        } => {
+            let op = match name {
+                BinOp::And => "andalso",
+                BinOp::Or => "orelse",
+                BinOp::Eq => "=:=",
+                BinOp::NotEq => "=/=",
+                BinOp::LtInt | BinOp::LtFloat => "<",
+                BinOp::LtEqInt | BinOp::LtEqFloat => "=<",
+                BinOp::GtInt | BinOp::GtFloat => ">",
+                BinOp::GtEqInt | BinOp::GtEqFloat => ">=",
+                BinOp::AddInt | BinOp::AddFloat => "+",
+                BinOp::SubInt | BinOp::SubFloat => "-",
+                BinOp::MultInt | BinOp::MultFloat => "*",
+                BinOp::DivInt | BinOp::DivFloat | BinOp::RemainderInt | BinOp::Concatenate => {
+                    panic!("Erl printing: Inlined guard function used {:?}", name)
+                }
+            };
+            let left = guard_expr_inline(left, bindings, env);
+            let right = guard_expr_inline(right, bindings, env);
+            docvec!["(", left, " ", op, " ", right, ")"]
+        }
+
+        TypedExpr::Negate { value, .. } => {
+            docvec!["not ", guard_expr_inline(value, bindings, env)]
+        }
+
+        TypedExpr::TupleIndex { tuple, index, .. } => {
+            let tuple = guard_expr_inline(tuple, bindings, env);
+            "erlang:element"
+                .to_doc()
+                .append(wrap_args([(index + 1).to_doc(), tuple]))
+        }
+
+        TypedExpr::RecordAccess {
+            record,
+            label,
+            erlang_map: true,
+            ..
+        } => {
+            let record = guard_expr_inline(record, bindings, env);
+            "erlang:map_get"
+                .to_doc()
+                .append(wrap_args([atom(label.to_string()), record]))
+        }
+
+        TypedExpr::RecordAccess { record, index, .. } => {
+            let record = guard_expr_inline(record, bindings, env);
+            "erlang:element"
+                .to_doc()
+                .append(wrap_args([(index + 2).to_doc(), record]))
+        }
+
+        TypedExpr::Call { fun, args, .. } => match fun.as_ref() {
+            TypedExpr::Var {
+                constructor:
+                    ValueConstructor {
+                        variant: ValueConstructorVariant::ModuleFn { name, .. },
+                        ..
+                    },
+                ..
+            } => {
+                let args = args
+                    .iter()
+                    .map(|arg| guard_expr_inline(&arg.value, bindings, env))
+                    .collect();
+                guard_function_inline(name, args, env)
+            }
+            _ => panic!("Erl printing: Inlined guard function called a non module function"),
+        },
+
+        _ => panic!("Erl printing: Expression cannot be inlined into a guard"),
+    }
+}
+
 fn tuple_index_inline<'a>(
     tuple: &'a TypedClauseGuard,
     index: u64,
@@ -1177,10 +1364,11 @@ fn clause_guard<'a>(guard: &'a TypedClauseGuard, env: &mut Env<'a>) -> Document<
             .append(bare_clause_guard(guard, env))
             .append(")"),
 
-        // Values are not wrapped
-        ClauseGuard::Constant(_) | ClauseGuard::Var { .. } | ClauseGuard::TupleIndex { .. } => {
-            bare_clause_guard(guard, env)
-        }
+        // Values are not wrapped, and inlined calls wrap any operators
+        ClauseGuard::Constant(_)
+        | ClauseGuard::Var { .. }
+        | ClauseGuard::TupleIndex { .. }
+        | ClauseGuard::Call { .. } => bare_clause_guard(guard, env),
     }
 }
 
@@ -1197,6 +1385,10 @@ fn clauses<'a>(cs: &'a [TypedClause], env: &mut Env<'a>) -> Document<'a> {
 }
 
 fn case<'a>(subjects: &'a [TypedExpr], cs: &'a [TypedClause], env: &mut Env<'a>) -> Document<'a> {
+    // The branches for the disabled value of a feature flag are left out
+    if let Some(clause) = cfg_case_clause(subjects, cs) {
+        return maybe_block_expr(&clause.then, env);
+    }
     let subjects_doc = if subjects.len() == 1 {
         let subject = subjects
             .get(0)
@@ -1226,7 +1418,7 @@ fn call<'a>(fun: &'a TypedExpr, args: &'a [CallArg<TypedExpr>], env: &mut Env<'a
 }
 
 fn module_fn_with_args<'a>(
-    module: &'a [String],
+    module: &'a [Name],
     name: &'a str,
     args: Vec<Document<'a>>,
     env: &mut Env<'a>,
@@ -1244,10 +1436,33 @@ fn module_fn_with_args<'a>(
 
 fn docs_args_call<'a>(
     fun: &'a TypedExpr,
-    mut args: Vec<Document<'a>>,
+    args: Vec<Document<'a>>,
     env: &mut Env<'a>,
 ) -> Document<'a> {
     match fun {
+        TypedExpr::ModuleSelect {
+            constructor:
+                ModuleValueConstructor::Record {
+                    erlang_map: true,
+                    field_map: Some(field_map),
+                    ..
+                },
+            ..
+        }
+        | TypedExpr::Var {
+            constructor:
+                ValueConstructor {
+                    variant:
+                        ValueConstructorVariant::Record {
+                            erlang_map: true,
+                            field_map: Some(field_map),
+                            ..
+                        },
+                    ..
+                },
+            ..
+        } => map_record(map_record_labels(field_map), args, " => "),
+
         TypedExpr::ModuleSelect {
             constructor: ModuleValueConstructor::Record { name, .. },
             ..
@@ -1261,6 +1476,14 @@ fn docs_args_call<'a>(
             ..
         } => tuple(std::iter::once(atom(name.to_snake_case())).chain(args)),
 
+        TypedExpr::Var { constructor, .. } if constructor.variant.is_rescue() => {
+            let fun = args
+                .into_iter()
+                .next()
+                .expect("rescue is called with a function");
+            rescue(fun, env)
+        }
+
         TypedExpr::Var {
             constructor:
                 ValueConstructor {
@@ -1296,6 +1519,14 @@ fn docs_args_call<'a>(
             }
         }
 
+        TypedExpr::ModuleSelect { constructor, .. } if constructor.is_rescue() => {
+            let fun = args
+                .into_iter()
+                .next()
+                .expect("rescue is called with a function");
+            rescue(fun, env)
+        }
+
         TypedExpr::ModuleSelect {
             constructor: ModuleValueConstructor::Fn { module, name, .. },
             ..
@@ -1319,25 +1550,28 @@ fn docs_args_call<'a>(
             is_capture: true,
             body,
             ..
-        } => {
+        } if matches!(body.as_ref(), TypedExpr::Call { .. }) => {
             if let TypedExpr::Call {
                 fun,
                 args: inner_args,
                 ..
             } = body.as_ref()
             {
+                let mut args = args.into_iter();
                 let mut merged_args = Vec::with_capacity(inner_args.len());
                 for arg in inner_args {
                     match &arg.value {
-                        TypedExpr::Var { name, .. } if name == CAPTURE_VARIABLE => {
-                            merged_args.push(args.swap_remove(0))
-                        }
+                        TypedExpr::Var { name, .. } if is_capture_variable(name) => merged_args
+                            .push(
+                                args.next()
+                                    .expect("Erl printing: Capture hole without an argument"),
+                            ),
                         e => merged_args.push(expr(e, env)),
                     }
                 }
                 docs_args_call(fun, merged_args, env)
             } else {
-                panic!("Erl printing: Capture was not a call")
+                unreachable!("The above clause guard ensures that this is a call")
             }
         }
 
@@ -1381,6 +1615,66 @@ fn record_update<'a>(
     })
 }
 
+fn map_record_update<'a>(
+    spread: &'a TypedExpr,
+    args: &'a [TypedRecordUpdateArg],
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    // Only variables and blocks can be updated without being wrapped in
+    // parentheses, as the map update syntax binds tighter than a call.
+    let map_doc = match spread {
+        TypedExpr::Var { .. } => expr(spread, env),
+        _ => maybe_block_expr(spread, env).surround("(", ")"),
+    };
+    let labels = args.iter().map(|arg| arg.label.as_str());
+    let values = args
+        .iter()
+        .map(|arg| maybe_block_expr(&arg.value, env))
+        .collect_vec();
+    map_doc.append(map_record(labels, values, " := "))
+}
+
+/// The labels of a map record's fields, in the order in which the record
+/// constructor takes them as arguments.
+///
+pub(crate) fn map_record_labels(field_map: &FieldMap) -> Vec<&str> {
+    field_map
+        .fields
+        .iter()
+        .sorted_by_key(|(_, index)| **index)
+        .map(|(label, _)| label.as_str())
+        .collect()
+}
+
+/// Renders the fields of a map record as an Erlang map, using `separator`
+/// to pick between construction (` => `) and matching or updating (` := `).
+///
+fn map_record<'a, 'b>(
+    labels: impl IntoIterator<Item = &'b str>,
+    values: impl IntoIterator<Item = Document<'a>>,
+    separator: &'static str,
+) -> Document<'a> {
+    let fields = labels
+        .into_iter()
+        .zip(values)
+        .map(|(label, value)| docvec![atom(label.to_string()), separator, value]);
+    concat(Itertools::intersperse(fields, break_(",", ", ")))
+        .nest(INDENT)
+        .surround("#{", "}")
+        .group()
+}
+
+fn map_record_constructor_fun(field_map: &FieldMap, arity: usize) -> Document<'_> {
+    let chars = incrementing_args_list(arity);
+    let values = (0..arity).map(|i| Document::String(format!("Field@{i}")));
+    "fun("
+        .to_doc()
+        .append(Document::String(chars))
+        .append(") -> ")
+        .append(map_record(map_record_labels(field_map), values, " => "))
+        .append(" end")
+}
+
 /// Wrap a document in begin end
 ///
 fn begin_end(document: Document<'_>) -> Document<'_> {
@@ -1477,6 +1771,10 @@ fn expr<'a>(expression: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
 
         TypedExpr::Negate { value, .. } => negate(value, env),
 
+        TypedExpr::Echo { value, location } => echo(value, *location, env),
+
+        TypedExpr::Cfg { enabled, .. } => atom(enabled.to_string()),
+
         TypedExpr::List { elements, tail, .. } => expr_list(elements, tail, env),
 
         TypedExpr::Call { fun, args, .. } => call(fun, args, env),
@@ -1491,6 +1789,17 @@ fn expr<'a>(expression: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
             ..
         } => const_inline(literal, env),
 
+        TypedExpr::ModuleSelect {
+            constructor:
+                ModuleValueConstructor::Record {
+                    erlang_map: true,
+                    field_map: Some(field_map),
+                    arity,
+                    ..
+                },
+            ..
+        } => map_record_constructor_fun(field_map, *arity as usize),
+
         TypedExpr::ModuleSelect {
             constructor: ModuleValueConstructor::Record { name, arity, .. },
             ..
@@ -1506,6 +1815,8 @@ fn expr<'a>(expression: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
                 .append("} end")
         }
 
+        TypedExpr::ModuleSelect { constructor, .. } if constructor.is_rescue() => rescue_fun(env),
+
         TypedExpr::ModuleSelect {
             typ,
             label,
@@ -1514,8 +1825,29 @@ fn expr<'a>(expression: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
             ..
         } => module_select_fn(typ.clone(), module_name, label),
 
+        TypedExpr::RecordAccess {
+            record,
+            label,
+            erlang_map: true,
+            ..
+        } => "maps:get".to_doc().append(wrap_args([
+            atom(label.to_string()),
+            maybe_block_expr(record, env),
+        ])),
+
+        TypedExpr::RecordAccess {
+            record, variants, ..
+        } if !variants.is_empty() => variant_field(record, variants, env),
+
         TypedExpr::RecordAccess { record, index, .. } => tuple_index(record, index + 1, env),
 
+        TypedExpr::RecordUpdate {
+            spread,
+            args,
+            erlang_map: true,
+            ..
+        } => map_record_update(spread, args, env),
+
         TypedExpr::RecordUpdate { spread, args, .. } => record_update(spread, args, env),
 
         TypedExpr::Try {
@@ -1529,8 +1861,9 @@ fn expr<'a>(expression: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
             value,
             pattern,
             kind: AssignmentKind::Assert,
+            message,
             ..
-        } => assert(value, pattern, env),
+        } => assert(value, pattern, message.as_deref(), env),
 
         TypedExpr::Assignment {
             value,
@@ -1561,6 +1894,85 @@ fn negate<'a>(value: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
     docvec!["not ", maybe_block_expr(value, env)]
 }
 
+// The value is printed to standard error along with where the `echo` is and
+// the type of the value, and then returned. It is printed as Gleam code by
+// the entrypoint module, the same as on the other targets.
+fn echo<'a>(value: &'a TypedExpr, location: SrcSpan, env: &mut Env<'a>) -> Document<'a> {
+    let value_doc = maybe_block_expr(value, env);
+    let var = env.next_local_var_name(ECHO_VARIABLE);
+    let typ = crate::type_::pretty::Printer::new().pretty_print(&value.type_(), 0);
+    let arguments = [
+        Document::String(env.module.join("/")).surround("<<\"", "\"/utf8>>"),
+        env.line_numbers.line_number(location.start).to_doc(),
+        docvec!["'gleam@@main':inspect(", var.clone(), ")"],
+        Document::String(typ).surround("<<\"", "\"/utf8>>"),
+    ];
+    let print = docvec![
+        "io:format",
+        wrap_args([
+            "standard_error".to_doc(),
+            string("~ts:~b~n~ts : ~ts~n"),
+            list(join(arguments, break_(",", ", ")), None),
+        ])
+    ];
+    docvec![
+        "case ",
+        value_doc,
+        " of",
+        docvec![
+            line(),
+            var.clone(),
+            " ->",
+            docvec![line(), print, ",", line(), var].nest(INDENT)
+        ]
+        .nest(INDENT),
+        line(),
+        "end"
+    ]
+}
+
+// The function is called and any crash is returned as an error, holding the
+// message of a Gleam crash or the printed reason of any other exception.
+fn rescue<'a>(fun: Document<'a>, env: &mut Env<'a>) -> Document<'a> {
+    let message = env.next_local_var_name(RESCUE_VARIABLE);
+    let reason = env.next_local_var_name(RESCUE_VARIABLE);
+    let crash = |message: Document<'a>| docvec!["{error, {crash_reason, ", message, "}}"];
+    let printed_reason = docvec![
+        "unicode:characters_to_binary(io_lib:format(",
+        string("~tp"),
+        ", [",
+        reason.clone(),
+        "]))"
+    ];
+    docvec![
+        "try {ok, (",
+        fun,
+        ")()} catch",
+        docvec![
+            line(),
+            "_:#{gleam_error := _, message := ",
+            message.clone(),
+            "} ->",
+            docvec![line(), crash(message)].nest(INDENT),
+            ";",
+            line(),
+            "_:",
+            reason,
+            " ->",
+            docvec![line(), crash(printed_reason)].nest(INDENT),
+        ]
+        .nest(INDENT),
+        line(),
+        "end"
+    ]
+}
+
+// `rescue` used as a value rather than called directly.
+fn rescue_fun<'a>(env: &mut Env<'a>) -> Document<'a> {
+    let fun = env.next_local_var_name(RESCUE_VARIABLE);
+    docvec!["fun(", fun.clone(), ") -> ", rescue(fun, env), " end"]
+}
+
 fn tuple_index<'a>(tuple: &'a TypedExpr, index: u64, env: &mut Env<'a>) -> Document<'a> {
     let index_doc = Document::String(format!("{}", (index + 1)));
     let tuple_doc = maybe_block_expr(tuple, env);
@@ -1569,6 +1981,41 @@ fn tuple_index<'a>(tuple: &'a TypedExpr, index: u64, env: &mut Env<'a>) -> Docum
         .append(wrap_args([index_doc, tuple_doc]))
 }
 
+// A field shared by all the constructors of a type but at different positions
+// is accessed by matching on each constructor.
+fn variant_field<'a>(
+    record: &'a TypedExpr,
+    variants: &'a [VariantField],
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    let record = maybe_block_expr(record, env);
+    let var = env.next_local_var_name(FIELD_VARIABLE);
+    let clauses = variants.iter().map(|variant| {
+        let fields = (0..variant.arity).map(|i| {
+            if u64::from(i) == variant.index {
+                var.clone()
+            } else {
+                "_".to_doc()
+            }
+        });
+        let tag = atom(variant.constructor.to_snake_case());
+        docvec![
+            tuple(std::iter::once(tag).chain(fields)),
+            " -> ",
+            var.clone()
+        ]
+    });
+    docvec![
+        "case ",
+        record,
+        " of",
+        docvec![line(), join(clauses, ";".to_doc().append(line()))].nest(INDENT),
+        line(),
+        "end"
+    ]
+    .group()
+}
+
 fn module_select_fn<'a>(typ: Arc<Type>, module_name: &'a str, label: &'a str) -> Document<'a> {
     match crate::type_::collapse_links(typ).as_ref() {
         crate::type_::Type::Fn { args, .. } => "fun "
@@ -1605,7 +2052,7 @@ fn incrementing_args_list(arity: usize) -> String {
 }
 
 fn external_fun<'a>(
-    current_module: &'a [String],
+    current_module: &'a [Name],
     name: &'a str,
     module: &'a str,
     fun: &'a str,
@@ -1614,7 +2061,7 @@ fn external_fun<'a>(
 ) -> Document<'a> {
     let chars: String = incrementing_args_list(args.len());
     let var_usages = collect_type_var_usages(
-        HashMap::new(),
+        BTreeMap::new(),
         [return_type]
             .into_iter()
             .chain(args.iter().map(|a| &a.type_)),
@@ -1644,14 +2091,18 @@ fn variable_name(name: &str) -> String {
     first_uppercased.chain(chars).collect()
 }
 
+fn id_to_type_var(id: u64) -> Document<'static> {
+    Document::String(type_variable_name(id))
+}
+
 // When rendering a type variable to an erlang type spec we need all type variables with the
 // same id to end up with the same name in the generated erlang.
 // This function converts a usize into base 26 A-Z for this purpose.
-fn id_to_type_var(id: u64) -> Document<'static> {
+pub(crate) fn type_variable_name(id: u64) -> String {
     if id < 26 {
         let mut name = "".to_string();
         name.push(std::char::from_u32((id % 26 + 65) as u32).expect("id_to_type_var 0"));
-        return Document::String(name);
+        return name;
     }
     let mut name = vec![];
     let mut last_char = id;
@@ -1661,7 +2112,7 @@ fn id_to_type_var(id: u64) -> Document<'static> {
     }
     name.push(std::char::from_u32((last_char % 26 + 64) as u32).expect("id_to_type_var 2"));
     name.reverse();
-    Document::String(name.into_iter().collect())
+    name.into_iter().collect()
 }
 
 pub fn is_erlang_reserved_word(name: &str) -> bool {
@@ -1778,17 +2229,17 @@ pub fn is_erlang_standard_library_module(name: &str) -> bool {
 //     fn(a) -> String       // `a` is `any()`
 //     fn() -> Result(a, b)  // `a` and `b` are `any()`
 //     fn(a) -> a            // `a` is a type var
-fn collect_type_var_usages<'a>(
-    mut ids: HashMap<u64, u64>,
+pub(crate) fn collect_type_var_usages<'a>(
+    mut ids: BTreeMap<u64, u64>,
     types: impl IntoIterator<Item = &'a Arc<Type>>,
-) -> HashMap<u64, u64> {
+) -> BTreeMap<u64, u64> {
     for typ in types {
         type_var_ids(typ, &mut ids);
     }
     ids
 }
 
-fn type_var_ids(type_: &Type, ids: &mut HashMap<u64, u64>) {
+fn type_var_ids(type_: &Type, ids: &mut BTreeMap<u64, u64>) {
     match type_ {
         Type::Var { type_: typ } => match typ.borrow().deref() {
             TypeVar::Generic { id, .. } | TypeVar::Unbound { id, .. } => {
@@ -1867,12 +2318,12 @@ fn erl_safe_type_name(mut name: String) -> String {
 #[derive(Debug)]
 struct TypePrinter<'a> {
     var_as_any: bool,
-    current_module: &'a [String],
-    var_usages: Option<&'a HashMap<u64, u64>>,
+    current_module: &'a [Name],
+    var_usages: Option<&'a BTreeMap<u64, u64>>,
 }
 
 impl<'a> TypePrinter<'a> {
-    fn new(current_module: &'a [String]) -> Self {
+    fn new(current_module: &'a [Name]) -> Self {
         Self {
             current_module,
             var_usages: None,
@@ -1880,7 +2331,7 @@ impl<'a> TypePrinter<'a> {
         }
     }
 
-    pub fn with_var_usages(mut self, var_usages: &'a HashMap<u64, u64>) -> Self {
+    pub fn with_var_usages(mut self, var_usages: &'a BTreeMap<u64, u64>) -> Self {
         self.var_usages = Some(var_usages);
         self
     }
@@ -1928,6 +2379,7 @@ impl<'a> TypePrinter<'a> {
             "Bool" => "boolean()".to_doc(),
             "Float" => "float()".to_doc(),
             "BitString" => "bitstring()".to_doc(),
+            "CrashReason" => tuple(["crash_reason".to_doc(), "binary()".to_doc()]),
             "List" => {
                 let arg0 = self.print(args.get(0).expect("print_prelude_type list"));
                 "list(".to_doc().append(arg0).append(")")
@@ -1945,12 +2397,7 @@ impl<'a> TypePrinter<'a> {
         }
     }
 
-    fn print_type_app(
-        &self,
-        module: &[String],
-        name: &str,
-        args: &[Arc<Type>],
-    ) -> Document<'static> {
+    fn print_type_app(&self, module: &[Name], name: &str, args: &[Arc<Type>]) -> Document<'static> {
         let args = concat(Itertools::intersperse(
             args.iter().map(|a| self.print(a)),
             ", ".to_doc(),
@@ -1963,7 +2410,7 @@ impl<'a> TypePrinter<'a> {
         }
     }
 
-    fn print_module_name(&self, module: &[String]) -> Document<'static> {
+    fn print_module_name(&self, module: &[Name]) -> Document<'static> {
         concat(Itertools::intersperse(
             module.iter().map(|m| Document::String(m.to_snake_case())),
             "@".to_doc(),
@@ -33,10 +33,41 @@ pub(super) fn to_doc<'a>(
 
         Pattern::Int { value, .. } => int(value),
 
+        Pattern::IntRange { start, end, .. } => {
+            let var = env.next_local_var_name(RANGE_VARIABLE);
+            env.int_range_checks.push(docvec![
+                var.clone(),
+                " >= ",
+                int(start),
+                " andalso ",
+                var.clone(),
+                " =< ",
+                int(end)
+            ]);
+            var
+        }
+
         Pattern::Float { value, .. } => float(value),
 
         Pattern::String { value, .. } => string(value),
 
+        Pattern::Constructor {
+            arguments: args,
+            constructor:
+                PatternConstructor::Record {
+                    erlang_map: true,
+                    field_map: Some(field_map),
+                    ..
+                },
+            ..
+        } => {
+            let values = args
+                .iter()
+                .map(|p| to_doc(&p.value, vars, env))
+                .collect_vec();
+            map_record(map_record_labels(field_map), values, " := ")
+        }
+
         Pattern::Constructor {
             arguments: args,
             constructor: PatternConstructor::Record { name, .. },
@@ -96,6 +127,7 @@ fn pattern_segment<'a>(
         Pattern::Discard { .. }
         | Pattern::Var { .. }
         | Pattern::Int { .. }
+        | Pattern::IntRange { .. }
         | Pattern::Float { .. } => to_doc(value, vars, env),
 
         // No other pattern variants are allowed in pattern bit string segments
@@ -106,7 +138,7 @@ fn pattern_segment<'a>(
         Some(":".to_doc().append(to_doc(value, vars, env)))
     };
 
-    let unit = |value: &'a u8| Some(Document::String(format!("unit:{}", value)));
+    let unit = |value: &'a u8| Some(docvec!["unit:", *value]);
 
     bit_string_segment(document, options, size, unit, true, env)
 }
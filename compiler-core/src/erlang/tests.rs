@@ -1,12 +1,15 @@
 mod assert;
 mod bit_strings;
 mod case;
+mod echo;
 mod external_fn;
+mod features;
 mod guards;
 mod numbers;
 mod patterns;
 mod pipes;
 mod records;
+mod rescue;
 mod reserved;
 mod statement_if;
 mod strings;
@@ -17,6 +20,47 @@ mod variables;
 
 #[macro_export]
 macro_rules! assert_erl {
+    (features: $features:expr, $src:expr $(,)?) => {{
+        use $crate::{
+            build::Origin,
+            erlang::module,
+            line_numbers::LineNumbers,
+            type_::{build_prelude, infer_module_with_features},
+            uid::UniqueIdGenerator,
+        };
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["the_app".into()];
+        let mut modules = im::HashMap::new();
+        let ids = UniqueIdGenerator::new();
+        let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+        let features = $features
+            .iter()
+            .map(|(flag, enabled): &(&str, bool)| (flag.to_string(), *enabled))
+            .collect();
+        let ast = infer_module_with_features(
+            $crate::build::Target::Erlang,
+            &ids,
+            ast,
+            Origin::Src,
+            "thepackage",
+            &modules,
+            features,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let mut output = String::new();
+        let line_numbers = LineNumbers::new($src);
+        module(
+            &ast,
+            &line_numbers,
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    }};
+
     (($dep_package:expr, $dep_name:expr, $dep_src:expr), $src:expr $(,)?) => {{
         use $crate::{erlang::module, line_numbers::LineNumbers, uid::UniqueIdGenerator};
         let mut modules = im::HashMap::new();
@@ -26,8 +70,9 @@ macro_rules! assert_erl {
         // to have one place where we create all this required state for use in each
         // place.
         let _ = modules.insert("gleam".to_string(), $crate::type_::build_prelude(&ids));
-        let (mut ast, _) = $crate::parse::parse_module($dep_src).expect("dep syntax error");
-        ast.name = $dep_name;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($dep_src, &arena).expect("dep syntax error");
+        ast.name = $dep_name.iter().map(|name| name.as_str().into()).collect();
         let dep = $crate::type_::infer_module(
             $crate::build::Target::JavaScript,
             &ids,
@@ -39,8 +84,9 @@ macro_rules! assert_erl {
         )
         .expect("should successfully infer");
         let _ = modules.insert($dep_name.join("/"), dep.type_info);
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my".to_string(), "mod".to_string()];
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my".into(), "mod".into()];
         let ast = $crate::type_::infer_module(
             $crate::build::Target::Erlang,
             &ids,
@@ -53,7 +99,13 @@ macro_rules! assert_erl {
         .expect("should successfully infer");
         let mut output = String::new();
         let line_numbers = LineNumbers::new($src);
-        module(&ast, &line_numbers, &mut output).unwrap();
+        module(
+            &ast,
+            &line_numbers,
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
     }};
 
@@ -65,8 +117,9 @@ macro_rules! assert_erl {
             type_::{build_prelude, infer_module},
             uid::UniqueIdGenerator,
         };
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["the_app".to_string()];
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["the_app".into()];
         let mut modules = im::HashMap::new();
         let ids = UniqueIdGenerator::new();
         // DUPE: preludeinsertion
@@ -86,7 +139,13 @@ macro_rules! assert_erl {
         .expect("should successfully infer");
         let mut output = String::new();
         let line_numbers = LineNumbers::new($src);
-        module(&ast, &line_numbers, &mut output).unwrap();
+        module(
+            &ast,
+            &line_numbers,
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
     }};
 }
@@ -161,6 +220,29 @@ fn integration_test1_5() {
     );
 }
 
+#[test]
+fn capture_with_multiple_holes() {
+    assert_erl!(
+        r#"fn add(x, y, z) { x + y + z }
+                    pub fn go() { add(_, 1, _) }"#
+    );
+
+    assert_erl!(
+        r#"fn add(x, y, z) { x + y + z }
+                    pub fn go() { add(_, 1, _)(2, 3) }"#
+    );
+}
+
+#[test]
+fn operator_sections() {
+    assert_erl!(
+        r#"pub fn go() {
+  let f = _ * 2 + _
+  #(f, { _ - 1 }(2))
+}"#
+    );
+}
+
 #[test]
 fn integration_test1_6() {
     assert_erl!(
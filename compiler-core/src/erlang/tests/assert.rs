@@ -45,4 +45,14 @@ fn variable_rewrites() {
     );
 }
 
+#[test]
+fn message() {
+    assert_erl!(
+        r#"pub fn go(x) {
+  assert Ok(y) = x as "x should always be ok"
+  y
+}"#
+    );
+}
+
 // TODO: patterns that are just vars don't render a case expression
@@ -17,3 +17,33 @@ pub fn myfun(mt) {
 "
     )
 }
+
+#[test]
+fn int_ranges() {
+    assert_erl!(
+        r#"
+pub fn classify(x, y) {
+  case x, y {
+    0..9 as digit, _ -> digit
+    -16..-1, 0..1 | 100..200, _ -> 1
+    10..99, _ if y > 2 || y < 0 -> 2
+    _, _ -> 3
+  }
+}
+"#
+    )
+}
+
+#[test]
+fn int_range_in_bit_string() {
+    assert_erl!(
+        r#"
+pub fn is_digit(x) {
+  case x {
+    <<48..57, _:bit_string>> -> True
+    _ -> False
+  }
+}
+"#
+    )
+}
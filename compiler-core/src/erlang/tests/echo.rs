@@ -0,0 +1,27 @@
+use crate::assert_erl;
+
+#[test]
+fn plain() {
+    assert_erl!(
+        r#"
+pub fn main() {
+  echo 1
+}
+"#
+    );
+}
+
+#[test]
+fn with_block() {
+    assert_erl!(
+        r#"
+pub fn main(x) {
+  let y = echo {
+    let z = x + 1
+    z * 2
+  }
+  echo [y, y]
+}
+"#
+    );
+}
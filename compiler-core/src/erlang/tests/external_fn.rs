@@ -76,3 +76,33 @@ pub fn main() {
 "
     );
 }
+
+#[test]
+fn external_attribute_for_target() {
+    assert_erl!(
+        r#"@external(erlang, "lists", "reverse")
+pub fn reverse(list: List(a)) -> List(a) {
+  list
+}
+
+pub fn main() {
+  reverse([1, 2])
+}
+"#
+    );
+}
+
+#[test]
+fn external_attribute_for_other_target_uses_body() {
+    assert_erl!(
+        r#"@external(javascript, "./ffi.mjs", "reverse")
+pub fn reverse(list: List(a)) -> List(a) {
+  list
+}
+
+pub fn main() {
+  reverse([1, 2])
+}
+"#
+    );
+}
@@ -0,0 +1,64 @@
+use crate::assert_erl;
+
+#[test]
+fn flag_value() {
+    assert_erl!(
+        features: [("json", true), ("xml", false)],
+        r#"
+pub fn main() {
+  #(@cfg(json), @cfg(xml))
+}
+"#
+    );
+}
+
+#[test]
+fn enabled_branch() {
+    assert_erl!(
+        features: [("json", true)],
+        r#"
+pub fn encode(x) {
+  case @cfg(json) {
+    True -> {
+      let y = x + 1
+      y * 2
+    }
+    False -> x
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn disabled_branch() {
+    assert_erl!(
+        features: [("json", false)],
+        r#"
+pub fn encode(x) {
+  let y = case @cfg(json) {
+    True -> x + 1
+    _ -> x
+  }
+  y
+}
+"#
+    );
+}
+
+// A clause with a guard cannot be chosen at compile time so the whole case
+// expression is kept.
+#[test]
+fn guarded_branch() {
+    assert_erl!(
+        features: [("json", true)],
+        r#"
+pub fn encode(x) {
+  case @cfg(json) {
+    True if x -> 1
+    _ -> 2
+  }
+}
+"#
+    );
+}
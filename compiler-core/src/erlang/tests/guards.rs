@@ -437,3 +437,29 @@ pub fn main(arg) {
 "#
     );
 }
+
+#[test]
+fn function_call() {
+    assert_erl!(
+        r#"
+pub type Box {
+  Box(size: Int)
+}
+
+fn is_small(box: Box) -> Bool {
+  box.size < limit(box.size)
+}
+
+fn limit(size: Int) -> Int {
+  size * 2 - 10
+}
+
+pub fn main(box) {
+  case box {
+    _ if is_small(box) -> 1
+    _ -> 0
+  }
+}
+"#
+    );
+}
@@ -32,3 +32,18 @@ pub fn main() {
 "#
     );
 }
+
+#[test]
+fn constant_arithmetic() {
+    assert_erl!(
+        r#"
+const seconds = 60 * 60
+const hours = { seconds + 1 } / 3600
+const zero = 1 / 0
+const half = 1.0 /. 2.0
+pub fn main() {
+  #(seconds, hours, zero, half)
+}
+"#
+    );
+}
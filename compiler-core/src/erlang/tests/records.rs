@@ -1,5 +1,6 @@
 use crate::assert_erl;
 use crate::erlang::*;
+use crate::pretty::DEFAULT_LINE_LENGTH;
 use crate::type_;
 
 #[test]
@@ -9,7 +10,8 @@ fn basic() {
         &[
             ("name", type_::tuple(vec![])),
             ("is_cute", type_::tuple(vec![]))
-        ]
+        ],
+        DEFAULT_LINE_LENGTH
     ));
 }
 
@@ -22,7 +24,8 @@ fn reserve_words() {
             ("receive", type_::int()),
             ("catch", type_::tuple(vec![])),
             ("unreserved", type_::tuple(vec![]))
-        ]
+        ],
+        DEFAULT_LINE_LENGTH
     ));
 }
 
@@ -35,14 +38,15 @@ fn type_vars() {
             ("name", type_::generic_var(1)),
             ("is_cute", type_::unbound_var(1)),
             ("linked", type_::link(type_::int()))
-        ]
+        ],
+        DEFAULT_LINE_LENGTH
     ));
 }
 
 #[test]
 fn module_types() {
     // Types are printed with module qualifiers
-    let module_name = vec!["name".to_string()];
+    let module_name = vec!["name".into()];
     insta::assert_snapshot!(record_definition(
         "PetCat",
         &[(
@@ -50,10 +54,11 @@ fn module_types() {
             Arc::new(type_::Type::App {
                 public: true,
                 module: module_name,
-                name: "my_type".to_string(),
+                name: "my_type".into(),
                 args: vec![]
             })
-        )]
+        )],
+        DEFAULT_LINE_LENGTH
     ));
 }
 
@@ -77,7 +82,8 @@ fn long_definition_formatting() {
                     type_::list(type_::tuple(vec![type_::nil(), type_::nil(), type_::nil()])),
                 ]))
             ),
-        ]
+        ],
+        DEFAULT_LINE_LENGTH
     ));
 }
 
@@ -150,6 +156,22 @@ pub fn get_age(person: Person) { person.age }"
     );
 }
 
+#[test]
+fn record_accessor_multiple_variants_different_positions() {
+    // We can access fields on custom types with multiple variants
+    // when the field is at a different position in each variant
+    assert_erl!(
+        "
+pub type Shape {
+    Circle(radius: Float, name: String)
+    Square(name: String, side: Float)
+    Point(name: String)
+}
+pub fn get_name(shape: Shape) { shape.name }
+pub fn get_names(a: Shape, b: Shape) { #(a.name, b.name) }"
+    );
+}
+
 #[test]
 fn record_spread() {
     // Test binding to a record field with the spread operator
@@ -298,6 +320,24 @@ fn main() {
     );
 }
 
+#[test]
+fn nested_record_updates() {
+    assert_erl!(
+        r#"
+pub type Car { Car(make: String, model: String, driver: Person) }
+pub type Person { Person(name: String, age: Int) }
+
+pub fn birthday(car: Car) {
+    Car(..car, driver.age: car.driver.age + 1, model: "Model 771")
+}
+
+pub fn new_driver(car: fn() -> Car) {
+    Car(..car(), driver.name: "Jane Doe")
+}
+"#
+    );
+}
+
 #[test]
 fn record_constants() {
     assert_erl!(
@@ -340,3 +380,75 @@ pub fn main() {
 }"
     );
 }
+
+#[test]
+fn erlang_map_record_construction() {
+    assert_erl!(
+        "@erlang_map
+pub type Cat {
+  Cat(name: String, cuteness: Int)
+}
+
+pub fn main() {
+  let tom = Cat(cuteness: 10, name: \"Tom\")
+  let make = Cat
+  make(\"Nubi\", 11)
+}"
+    );
+}
+
+#[test]
+fn erlang_map_record_access_and_update() {
+    assert_erl!(
+        "@erlang_map
+pub type Cat {
+  Cat(name: String, cuteness: Int)
+}
+
+pub fn rename(cat: Cat, name: String) {
+  Cat(..cat, name: name)
+}
+
+pub fn cuteness(cat: Cat) {
+  cat.cuteness
+}"
+    );
+}
+
+#[test]
+fn erlang_map_record_pattern() {
+    assert_erl!(
+        "@erlang_map
+pub type Cat {
+  Cat(name: String, cuteness: Int)
+}
+
+pub fn name(cat: Cat) {
+  case cat {
+    Cat(cuteness: 10, ..) -> \"cutest\"
+    Cat(name: name, ..) -> name
+  }
+}"
+    );
+}
+
+#[test]
+fn erlang_map_record_from_other_module() {
+    assert_erl!(
+        (
+            "other_package",
+            vec!["other".to_string()],
+            "@erlang_map
+pub type Cat {
+  Cat(name: String, cuteness: Int)
+}"
+        ),
+        "import other
+
+pub fn main() {
+  let make = other.Cat
+  let tom = other.Cat(name: \"Tom\", cuteness: 10)
+  make(tom.name, tom.cuteness)
+}"
+    );
+}
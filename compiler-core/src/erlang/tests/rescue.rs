@@ -0,0 +1,41 @@
+use crate::assert_erl;
+
+#[test]
+fn rescue_call() {
+    assert_erl!(
+        r#"
+pub fn main() {
+  rescue(fn() { todo })
+}
+"#
+    );
+}
+
+#[test]
+fn rescue_value() {
+    assert_erl!(
+        r#"
+import gleam
+
+pub fn main() {
+  let f = rescue
+  let g = gleam.rescue
+  #(f, g(fn() { 1 }))
+}
+"#
+    );
+}
+
+#[test]
+fn crash_reason() {
+    assert_erl!(
+        r#"
+pub fn main(f) {
+  case rescue(f) {
+    Ok(x) -> x
+    Error(CrashReason(message)) -> message
+  }
+}
+"#
+    );
+}
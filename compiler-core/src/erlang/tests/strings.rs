@@ -138,3 +138,17 @@ pub fn main() {
 "#,
     );
 }
+
+#[test]
+fn concat_in_constant() {
+    assert_erl!(
+        r#"
+const name = "Joe"
+const greeting = "Hello, " <> name <> "!\n"
+
+pub fn go() {
+  greeting
+}
+"#,
+    );
+}
@@ -1,14 +1,20 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 use crate::ast::SrcSpan;
+use crate::build::Target;
 use crate::cplusplus;
-use crate::diagnostic::{Diagnostic, Label, Location};
-use crate::type_::FieldAccessUsage;
-use crate::{ast::BinOp, parse::error::ParseErrorType, type_::Type};
+use crate::diagnostic::{sarif::CodedDiagnostic, Diagnostic, Label, Location};
+use crate::line_numbers::LineNumbers;
+use crate::type_::{FieldAccessUsage, TraceStep, TraceStepKind};
+use crate::{
+    ast::{BinOp, PIPE_VARIABLE},
+    parse::error::{ParseError, ParseErrorType},
+    type_::Type,
+};
 use crate::{
     bit_string,
     diagnostic::Level,
-    javascript,
-    type_::{pretty::Printer, UnifyErrorSituation},
+    go, javascript, llvm,
+    type_::{diff::TypeDiff, pretty::Printer, UnifyErrorSituation},
 };
 use hexpm::version::pubgrub_report::{DefaultStringReporter, Reporter};
 use hexpm::version::ResolutionError;
@@ -18,6 +24,7 @@ use std::fmt::Debug;
 use std::path::{Path, PathBuf};
 use termcolor::Buffer;
 use thiserror::Error;
+use vec1::Vec1;
 
 pub type Src = String;
 pub type Name = String;
@@ -30,13 +37,14 @@ macro_rules! wrap_format {
     }
 }
 
-#[derive(Debug, PartialEq, Error)]
+#[derive(Debug, PartialEq, Error, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum Error {
     #[error("failed to parse Gleam source code")]
     Parse {
         path: PathBuf,
         src: Src,
-        error: crate::parse::error::ParseError,
+        errors: Vec1<ParseError>,
     },
 
     #[error("type checking failed")]
@@ -76,7 +84,14 @@ pub enum Error {
     },
 
     #[error("cyclical module imports")]
-    ImportCycle { modules: Vec<String> },
+    ImportCycle {
+        /// The imports that form the cycle, each importing the module of the
+        /// next and the last importing the module of the first.
+        imports: Vec<CycleImport>,
+        /// The index of the import that is suggested to be removed to break
+        /// the cycle, if one is more likely to be the problem than the others.
+        suggested: Option<usize>,
+    },
 
     #[error("cyclical package dependencies")]
     PackageCycle { packages: Vec<String> },
@@ -101,6 +116,14 @@ pub enum Error {
     #[error("source code incorrectly formatted")]
     Format { problem_files: Vec<Unformatted> },
 
+    #[error("formatter produced incorrect code")]
+    FormatVerification {
+        path: PathBuf,
+        src: String,
+        location: SrcSpan,
+        problem: crate::format::VerificationProblem,
+    },
+
     #[error("Hex error: {0}")]
     Hex(String),
 
@@ -146,6 +169,11 @@ pub enum Error {
     #[error("metadata decoding failed")]
     MetadataDecodeError { error: Option<String> },
 
+    #[error("metadata was written in an incompatible format")]
+    IncompatibleMetadata {
+        version: Option<crate::metadata::FormatVersion>,
+    },
+
     #[error("warnings are not permitted")]
     ForbiddenWarnings { count: usize },
 
@@ -156,6 +184,20 @@ pub enum Error {
         error: crate::javascript::Error,
     },
 
+    #[error("go codegen failed")]
+    Go {
+        path: PathBuf,
+        src: Src,
+        error: go::Error,
+    },
+
+    #[error("llvm codegen failed")]
+    Llvm {
+        path: PathBuf,
+        src: Src,
+        error: llvm::Error,
+    },
+
     #[error("c++ codegen failed")]
     CPlusPlus {
         path: PathBuf,
@@ -179,6 +221,51 @@ pub enum Error {
     #[error("The package {0} is listed in dependencies and dev-dependencies")]
     DuplicateDependency(String),
 
+    #[error("The package at {path:?} is {found} rather than {expected}")]
+    WrongDependencyProvided {
+        path: PathBuf,
+        expected: String,
+        found: String,
+    },
+
+    #[error("The package {package} is not a Hex dependency and cannot be published")]
+    PublishNonHexDependency { package: String },
+
+    #[error("The build hook {hook} of {package} failed")]
+    BuildHook {
+        package: String,
+        hook: String,
+        status: i32,
+    },
+
+    #[error("No test functions match the filter {filter}")]
+    NoMatchingTests { filter: String },
+
+    #[error("The packages {packages:?} are not dependencies of the project")]
+    UnknownDependencies { packages: Vec<String> },
+
+    #[error("The registry {registry} is not in the registries of gleam.toml")]
+    UnknownRegistry { registry: String },
+
+    #[error("The registry {registry} could not be used")]
+    InvalidRegistry { registry: String, error: String },
+
+    #[error("The git dependency {package} could not be fetched")]
+    GitDependency {
+        package: String,
+        repo: String,
+        error: String,
+    },
+
+    #[error("The workspace packages require different versions of {package}")]
+    WorkspaceDependencyMismatch {
+        package: String,
+        requirements: Vec<String>,
+    },
+
+    #[error("The package {package} has no feature flag {flag}")]
+    UnknownDependencyFeature { package: String, flag: String },
+
     #[error("The package was missing required fields for publishing")]
     MissingHexPublishFields {
         description_missing: bool,
@@ -191,8 +278,90 @@ pub enum Error {
         build_tools: Vec<String>,
     },
 
+    #[error("The dependency {package} could not be built with {program}")]
+    DependencyBuildFailed {
+        package: String,
+        program: String,
+        output: String,
+        log: PathBuf,
+    },
+
     #[error("Native compilation is not yet supported")]
     CPlusPlusCompilationNotImplemented,
+
+    #[error("The JavaScript bundle `{key}` command has not been configured")]
+    JavaScriptBundleNotConfigured { key: String },
+
+    #[error("Bundling is not supported for the {target} target")]
+    BundleUnsupportedTarget { target: Target },
+
+    #[error("gleam {command} is not supported for the Go target")]
+    GoCommandUnsupported { command: String },
+
+    #[error("gleam {command} is not supported for the LLVM target")]
+    LlvmCommandUnsupported { command: String },
+
+    #[error("gleam {command} is not supported for the WebAssembly target")]
+    WasmCommandUnsupported { command: String },
+
+    #[error("The package {package} is not on Hex")]
+    UnknownHexPackage { package: String },
+
+    #[error("{user} is not an owner of the package {package}")]
+    NotHexPackageOwner { package: String, user: String },
+
+    #[error("There is no stored Hex API key named {name}")]
+    UnknownHexKey { name: String },
+
+    #[error("Dependencies cannot be resolved offline")]
+    OfflineResolution,
+
+    #[error("gleam {command} cannot be run offline")]
+    OfflineUnsupported { command: String },
+
+    #[error("The package {package} is not vendored")]
+    OfflinePackageNotVendored { package: String, version: String },
+
+    #[error("The publish glob {glob} is not valid")]
+    InvalidPublishGlob { glob: String, error: String },
+
+    #[error("Only one target can be built with {flag}")]
+    MultipleTargetsUnsupported { flag: String },
+
+    #[error("Dependency packages have licences that are not allowed")]
+    DisallowedDependencyLicences {
+        packages: Vec<crate::licences::PackageLicences>,
+    },
+
+    #[error("The main function of {module} cannot be run")]
+    InvalidMainFunction {
+        module: String,
+        problem: MainFunctionProblem,
+        /// The file and source of the module, and the location of the
+        /// definition named `main`, if there is one.
+        definition: Option<(PathBuf, Src, SrcSpan)>,
+    },
+
+    #[error("{name} cannot be renamed to {new_name}")]
+    CannotRename {
+        name: String,
+        new_name: String,
+        problem: RenameProblem,
+    },
+
+    #[error("Functions could not be derived for a type in {path}")]
+    Derive {
+        path: PathBuf,
+        src: Src,
+        error: crate::derive::Error,
+    },
+
+    #[error("The version {current} is not enough of a bump from {previous}")]
+    InsufficientVersionBump {
+        previous: String,
+        current: String,
+        required: crate::interface::diff::Bump,
+    },
 }
 
 impl Error {
@@ -272,6 +441,48 @@ impl From<capnp::NotInSchema> for Error {
     }
 }
 
+/// An import statement that is part of an import cycle.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CycleImport {
+    /// The module containing the import statement.
+    pub module: String,
+    pub imported: String,
+    pub path: PathBuf,
+    pub src: Src,
+    pub location: SrcSpan,
+}
+
+/// Why a module's `main` function cannot be used as the entrypoint of a
+/// program.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MainFunctionProblem {
+    ModuleNotFound,
+    NotFound,
+    NotAFunction,
+    Private,
+    HasArguments { arity: usize },
+    ReturnsFunction { return_type: String },
+}
+
+/// Why a symbol cannot be renamed by the language server.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RenameProblem {
+    NothingToRename,
+    /// The new name is not valid for the symbol, which must start with an
+    /// uppercase letter if it is a type or record constructor.
+    InvalidName {
+        uppercase: bool,
+    },
+    DefinedInDependency {
+        module: String,
+    },
+    /// The new name is already used where the symbol is in scope.
+    Conflict,
+    /// The project has been edited since it last compiled, so where the
+    /// symbol is used is not known.
+    NotCompiled,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum InvalidProjectNameReason {
     Format,
@@ -305,7 +516,7 @@ pub enum FileIoAction {
     Read,
     Parse,
     Delete,
-    // Rename,
+    Rename,
     Create,
     WriteTo,
     Canonicalise,
@@ -322,7 +533,7 @@ impl FileIoAction {
             FileIoAction::Read => "read",
             FileIoAction::Parse => "parse",
             FileIoAction::Delete => "delete",
-            // FileIoAction::Rename => "rename",
+            FileIoAction::Rename => "rename",
             FileIoAction::Create => "create",
             FileIoAction::WriteTo => "write to",
             FileIoAction::FindParent => "find the parent of",
@@ -348,16 +559,33 @@ impl FileKind {
 }
 
 fn did_you_mean(name: &str, options: &[String]) -> Option<String> {
-    // Find best match
-    options
-        .iter()
-        .filter(|&option| option != crate::ast::CAPTURE_VARIABLE)
-        .sorted()
-        .min_by_key(|option| strsim::levenshtein(option, name))
-        .map(|option| format!("Did you mean `{}`?", option))
+    crate::type_::closest_name(name, options).map(|option| format!("Did you mean `{}`?", option))
 }
 
 impl Error {
+    /// The name most similar to the unknown name this error is for, if any.
+    /// This is the name suggested in the error message, exposed for tooling
+    /// such as the language server.
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            Error::Type { error, .. } => error.suggestion(),
+            Error::UnknownImport {
+                import, modules, ..
+            } => crate::type_::closest_name(import, modules),
+            _ => None,
+        }
+    }
+
+    /// The parts of the expected and found types that differ, if this is a
+    /// type mismatch between large types. This is shown in the error message
+    /// and exposed for tooling such as the language server.
+    pub fn type_diff(&self) -> Option<TypeDiff> {
+        match self {
+            Error::Type { error, .. } => error.type_diff(),
+            _ => None,
+        }
+    }
+
     pub fn pretty_string(&self) -> String {
         let mut nocolor = Buffer::no_color();
         self.pretty(&mut nocolor);
@@ -365,7 +593,64 @@ impl Error {
     }
 
     pub fn pretty(&self, buffer: &mut Buffer) {
-        self.to_diagnostic().write(buffer)
+        use std::io::Write;
+        for (i, diagnostic) in self.to_diagnostics().into_iter().enumerate() {
+            if i > 0 {
+                buffer.write_all(b"\n").expect("error pretty buffer write");
+            }
+            diagnostic.write(buffer);
+        }
+    }
+
+    /// A short name for the kind of problem, such as `unknown_variable`,
+    /// used by tools that read the errors as JSON.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Type { error, .. } => error.into(),
+            _ => self.into(),
+        }
+    }
+
+    /// Each problem this error reports as a JSON object.
+    pub fn to_json(&self) -> Vec<serde_json::Value> {
+        self.to_coded_diagnostics()
+            .iter()
+            .map(|coded| coded.diagnostic.to_json(&coded.code, &coded.fixes))
+            .collect()
+    }
+
+    /// Each problem this error reports with its code, as reported in JSON
+    /// and SARIF output.
+    pub fn to_coded_diagnostics(&self) -> Vec<CodedDiagnostic> {
+        let coded = |code: &str, diagnostic| CodedDiagnostic {
+            code: code.into(),
+            diagnostic,
+            fixes: vec![],
+        };
+        match self {
+            Error::Parse { path, src, errors } => errors
+                .iter()
+                .map(|error| {
+                    coded(
+                        (&error.error).into(),
+                        parse_error_diagnostic(path, src, error),
+                    )
+                })
+                .collect(),
+            _ => vec![coded(self.code(), self.to_diagnostic())],
+        }
+    }
+
+    /// All the problems this error reports, which is more than one for a
+    /// module with several syntax errors.
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            Error::Parse { path, src, errors } => errors
+                .iter()
+                .map(|error| parse_error_diagnostic(path, src, error))
+                .collect(),
+            _ => vec![self.to_diagnostic()],
+        }
     }
 
     pub fn to_diagnostic(&self) -> Diagnostic {
@@ -389,6 +674,29 @@ of the Gleam dependency modules."
                 }
             }
 
+            Error::IncompatibleMetadata { version } => {
+                let found = match version {
+                    Some(version) => format!(
+                        "version {}.{} of the metadata format",
+                        version.major, version.minor
+                    ),
+                    None => "a format from before metadata was versioned".into(),
+                };
+                let text = format!(
+                    "The metadata for one of the Gleam dependency modules was written in
+{}, which this compiler cannot read. This compiler reads version {}.x.",
+                    found,
+                    crate::metadata::FORMAT_MAJOR_VERSION
+                );
+                Diagnostic {
+                    title: "Incompatible module metadata".into(),
+                    text,
+                    level: Level::Error,
+                    location: None,
+                    hint: Some("Run `gleam clean` to rebuild the project.".into()),
+                }
+            }
+
             Error::InvalidProjectName { name, reason } => {
                 let text = wrap_format!(
                     "We were not able to create your project as `{}` {}
@@ -982,6 +1290,64 @@ Names in a Gleam module must be unique so one will need to be renamed.",
                     }
                 }
 
+                TypeError::ExternalMissingAnnotation { location } => {
+                    let text = "A function with an external implementation must have type annotations
+for all of its arguments and its return value, so that it has the same
+signature on every target."
+                        .into();
+                    Diagnostic {
+                        title: "Missing type annotation".into(),
+                        text,
+                        hint: None,
+                        level: Level::Error,
+                        location: Some(Location {
+                            label: Label {
+                                text: Some("This needs a type annotation".into()),
+                                span: *location,
+                            },
+                            path: path.clone(),
+                            src: src.into(),
+                            extra_labels: vec![],
+                        }),
+                    }
+                }
+
+                TypeError::EmptyIntRange { location } => Diagnostic {
+                    title: "Empty range".into(),
+                    text: "The start of this range is greater than its end so it can never match.
+Ranges include both their start and their end, such as `1..3` matching
+1, 2, and 3."
+                        .into(),
+                    hint: None,
+                    level: Level::Error,
+                    location: Some(Location {
+                        label: Label {
+                            text: Some("This matches no values".into()),
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.into(),
+                        extra_labels: vec![],
+                    }),
+                },
+
+                TypeError::IntRangeOutsideCase { location } => Diagnostic {
+                    title: "Range pattern outside of case".into(),
+                    text: "Range patterns can only be used in the clauses of a case expression."
+                        .into(),
+                    hint: None,
+                    level: Level::Error,
+                    location: Some(Location {
+                        label: Label {
+                            text: Some("This is not allowed here".into()),
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.into(),
+                        extra_labels: vec![],
+                    }),
+                },
+
                 TypeError::DuplicateArgument { location, label } => {
                     let text = format!(
                         "The labelled argument `{}` has already been supplied.",
@@ -1004,12 +1370,27 @@ Names in a Gleam module must be unique so one will need to be renamed.",
                     }
                 }
 
-                TypeError::RecursiveType { location } => {
+                TypeError::RecursiveType {
+                    location, trace, ..
+                } => {
                     let text = "I don't know how to work out what type this value has. It seems
 to be defined in terms of itself.
 
 Hint: Add some type annotations and try again."
                         .into();
+                    let label_text = if trace.is_empty() {
+                        None
+                    } else {
+                        Some("so its type would have to contain itself here".into())
+                    };
+                    let extra_labels = trace
+                        .iter()
+                        .enumerate()
+                        .map(|(i, step)| Label {
+                            text: Some(trace_step_text(step, i == 0)),
+                            span: step.location,
+                        })
+                        .collect();
                     Diagnostic {
                         title: "Recursive type".into(),
                         text,
@@ -1017,12 +1398,12 @@ Hint: Add some type annotations and try again."
                         level: Level::Error,
                         location: Some(Location {
                             label: Label {
-                                text: None,
+                                text: label_text,
                                 span: *location,
                             },
                             path: path.clone(),
                             src: src.into(),
-                            extra_labels: vec![],
+                            extra_labels,
                         }),
                     }
                 }
@@ -1224,10 +1605,23 @@ But function expects:
                         } else {
                             "".into()
                         };
-                    text.push_str("Expected type:\n\n");
-                    text.push_str(&printer.pretty_print(expected, 4));
-                    text.push_str("\n\nFound type:\n\n");
-                    text.push_str(&printer.pretty_print(given, 4));
+                    match TypeDiff::new(&mut printer, expected, given) {
+                        Some(diff) => {
+                            text.push_str(
+                                "Parts of the types that are the same are shown as `_`.\n\n",
+                            );
+                            text.push_str("Expected type:\n\n");
+                            text.push_str(&diff.underlined_expected(4));
+                            text.push_str("\n\nFound type:\n\n");
+                            text.push_str(&diff.underlined_given(4));
+                        }
+                        None => {
+                            text.push_str("Expected type:\n\n");
+                            text.push_str(&printer.pretty_print(expected, 4));
+                            text.push_str("\n\nFound type:\n\n");
+                            text.push_str(&printer.pretty_print(given, 4));
+                        }
+                    }
                     Diagnostic {
                         title: "Type mismatch".into(),
                         text,
@@ -1318,6 +1712,24 @@ number of arguments."
                     }
                 }
 
+                TypeError::FloatOutOfRange { location } => Diagnostic {
+                    title: "Float out of range".into(),
+                    text: "This float is too large to be represented. Floats are 64-bit,
+so their magnitude must be at most 1.7976931348623157e308."
+                        .into(),
+                    hint: None,
+                    level: Level::Error,
+                    location: Some(Location {
+                        label: Label {
+                            text: None,
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.into(),
+                        extra_labels: vec![],
+                    }),
+                },
+
                 TypeError::UnnecessarySpreadOperator { location, arity } => {
                     let text = wrap_format!(
                         "This record has {arity} fields and you have already \
@@ -1340,6 +1752,27 @@ assigned variables to all of them."
                     }
                 }
 
+                TypeError::ConstantOutOfRange { location } => {
+                    let text = "The value of this constant is too large to be represented.
+Integer constants must fit in 128 bits and float constants must be finite."
+                        .into();
+                    Diagnostic {
+                        title: "Constant out of range".into(),
+                        text,
+                        hint: None,
+                        level: Level::Error,
+                        location: Some(Location {
+                            label: Label {
+                                text: None,
+                                span: *location,
+                            },
+                            path: path.clone(),
+                            src: src.into(),
+                            extra_labels: vec![],
+                        }),
+                    }
+                }
+
                 TypeError::UpdateMultiConstructorType { location } => {
                     let text = "This type has multiple constructors so it cannot be safely updated.
 If this value was one of the other variants then the update would be
@@ -1609,6 +2042,68 @@ Each clause must have a pattern for every subject value.",
                     }
                 }
 
+                TypeError::UnsafeClauseGuardCall { location, name } => {
+                    let text = wrap_format!(
+                        "Only functions defined in this module can be called in guards, and \
+their body must be a single expression using only arguments, literals, \
+operators other than division and `<>`, field access, and calls to other such \
+functions. The function `{}` is not one of these.",
+                        name
+                    );
+                    Diagnostic {
+                        title: "Invalid guard function call".into(),
+                        text,
+                        hint: None,
+                        level: Level::Error,
+                        location: Some(Location {
+                            label: Label {
+                                text: Some("Cannot be called in a guard".into()),
+                                span: *location,
+                            },
+                            path: path.clone(),
+                            src: src.into(),
+                            extra_labels: vec![],
+                        }),
+                    }
+                }
+
+                TypeError::UnknownFeatureFlag {
+                    location,
+                    name,
+                    flags,
+                } => {
+                    let text = if flags.is_empty() {
+                        wrap_format!(
+                            "The feature flag `{}` is not declared in the `features` of \
+gleam.toml, and this package has no feature flags.",
+                            name
+                        )
+                    } else {
+                        let flags = flags.iter().sorted().join(", ");
+                        wrap_format!(
+                            "The feature flag `{}` is not declared in the `features` of \
+gleam.toml. The feature flags of this package are: {}",
+                            name,
+                            flags
+                        )
+                    };
+                    Diagnostic {
+                        title: "Unknown feature flag".into(),
+                        text,
+                        hint: None,
+                        level: Level::Error,
+                        location: Some(Location {
+                            label: Label {
+                                text: Some("Not declared in gleam.toml".into()),
+                                span: *location,
+                            },
+                            path: path.clone(),
+                            src: src.into(),
+                            extra_labels: vec![],
+                        }),
+                    }
+                }
+
                 TypeError::ExtraVarInAlternativePattern { location, name } => {
                     let text = wrap_format!(
 "All alternative patterns must define the same variables as the initial pattern. \
@@ -1909,15 +2404,18 @@ function and try again."
                     }),
                 },
 
-                TypeError::UnexpectedTypeHole { location } => Diagnostic {
-                    title: "Unexpected type hole".into(),
-                    text: "We need to know the exact type here so type holes cannot be used."
-                        .into(),
-                    hint: None,
+                TypeError::InvalidErlangMapType { location } => Diagnostic {
+                    title: "Invalid Erlang map type".into(),
+                    text: wrap(
+                        "Only a type with a single constructor where every field \
+has a label can be represented as an Erlang map, as the labels are used as the \
+keys of the map.",
+                    ),
+                    hint: Some("Remove the `@erlang_map` attribute or label all the fields.".into()),
                     level: Level::Error,
                     location: Some(Location {
                         label: Label {
-                            text: Some("I need to know what this is".into()),
+                            text: Some("This type cannot be represented as a map".into()),
                             span: *location,
                         },
                         path: path.clone(),
@@ -1926,19 +2424,55 @@ function and try again."
                     }),
                 },
 
-                TypeError::ReservedModuleName { name } => {
-                    let text = format!(
-                        "The module name `{}` is reserved.
-Try a different name for this module.",
-                        name
-                    );
-                    Diagnostic {
-                        title: "Reserved module name".into(),
-                        text,
-                        hint: None,
-                        location: None,
-                        level: Level::Error,
-                    }
+                TypeError::ErlangMapRecordInConstant { location } => Diagnostic {
+                    title: "Erlang map record in constant".into(),
+                    text: wrap(
+                        "Records of types with the `@erlang_map` attribute cannot \
+be used in module constants.",
+                    ),
+                    hint: Some("Use a function that returns the record instead.".into()),
+                    level: Level::Error,
+                    location: Some(Location {
+                        label: Label {
+                            text: Some("This record is represented as a map".into()),
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.into(),
+                        extra_labels: vec![],
+                    }),
+                },
+
+                TypeError::UnexpectedTypeHole { location } => Diagnostic {
+                    title: "Unexpected type hole".into(),
+                    text: "We need to know the exact type here so type holes cannot be used."
+                        .into(),
+                    hint: None,
+                    level: Level::Error,
+                    location: Some(Location {
+                        label: Label {
+                            text: Some("I need to know what this is".into()),
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.into(),
+                        extra_labels: vec![],
+                    }),
+                },
+
+                TypeError::ReservedModuleName { name } => {
+                    let text = format!(
+                        "The module name `{}` is reserved.
+Try a different name for this module.",
+                        name
+                    );
+                    Diagnostic {
+                        title: "Reserved module name".into(),
+                        text,
+                        hint: None,
+                        location: None,
+                        level: Level::Error,
+                    }
                 }
 
                 TypeError::KeywordInModuleName { name, keyword } => {
@@ -1960,6 +2494,7 @@ Try a different name for this module.",
                 TypeError::NotExhaustivePatternMatch {
                     location,
                     unmatched,
+                    ..
                 } => {
                     let text = format!(
                         "This case expression does not match all possibilities.
@@ -1989,51 +2524,68 @@ These values are not matched:
                 }
             },
 
-            Error::Parse { path, src, error } => {
-                let (label, extra) = error.details();
-                let text = extra.join("\n");
-
-                let adjusted_location = if error.error == ParseErrorType::UnexpectedEof {
-                    SrcSpan {
-                        start: (src.len() - 1) as u32,
-                        end: (src.len() - 1) as u32,
-                    }
-                } else {
-                    error.location
-                };
-
-                Diagnostic {
-                    title: "Syntax error".into(),
-                    text,
-                    hint: None,
-                    level: Level::Error,
-                    location: Some(Location {
-                        label: Label {
-                            text: Some(label.to_string()),
-                            span: adjusted_location,
-                        },
-                        path: path.clone(),
-                        src: src.into(),
-                        extra_labels: vec![],
-                    }),
+            Error::Parse { path, src, errors } => parse_error_diagnostic(path, src, errors.first()),
+
+            Error::ImportCycle { imports, suggested } => {
+                let mut text = "The imports of these modules form a cycle:\n\n    ".to_string();
+                for import in imports {
+                    text.push_str(&import.module);
+                    text.push_str(" -> ");
+                }
+                if let Some(first) = imports.first() {
+                    text.push_str(&first.module);
+                }
+                text.push_str("\n\n");
+                for import in imports {
+                    let position = LineNumbers::new(&import.src)
+                        .line_and_column_number(import.location.start);
+                    text.push_str(&format!(
+                        "    {}:{}:{}: import {}\n",
+                        import.path.display(),
+                        position.line,
+                        position.column,
+                        import.imported
+                    ));
                 }
-            }
-
-            Error::ImportCycle { modules } => {
-                let mut text = "The import statements for these modules form a cycle:
-"
-                .into();
-                import_cycle(&mut text, modules);
                 text.push_str(
-                    "Gleam doesn't support dependency cycles like these, please break the
+                    "
+Gleam doesn't support dependency cycles like these, please break the
 cycle to continue.",
                 );
+                let (hint, label, import) = match suggested.and_then(|i| imports.get(i)) {
+                    Some(import) => (
+                        wrap(&format!(
+                            "`{}` is imported by the most other modules in the package, \
+so it is likely the lowest level module of the cycle. Consider removing its import of \
+`{}`, for example by moving the code it uses into `{}` or into a new module.",
+                            import.module, import.imported, import.module
+                        )),
+                        "Consider removing this import",
+                        Some(import),
+                    ),
+                    None => (
+                        wrap(
+                            "Consider moving the code these modules share into a new \
+module that they can each import.",
+                        ),
+                        "This import is part of the cycle",
+                        imports.first(),
+                    ),
+                };
                 Diagnostic {
                     title: "Import cycle".into(),
                     text,
-                    hint: None,
+                    hint: Some(hint),
                     level: Level::Error,
-                    location: None,
+                    location: import.map(|import| Location {
+                        label: Label {
+                            text: Some(label.into()),
+                            span: import.location,
+                        },
+                        path: import.path.clone(),
+                        src: import.src.clone(),
+                        extra_labels: vec![],
+                    }),
                 }
             }
 
@@ -2125,12 +2677,59 @@ cycle to continue.",
                 }
             }
 
+            Error::FormatVerification {
+                path,
+                src,
+                location,
+                problem,
+            } => {
+                let problem = match problem {
+                    crate::format::VerificationProblem::Unparsable => {
+                        "produced code that could not be parsed"
+                    }
+                    crate::format::VerificationProblem::ChangedMeaning => {
+                        "changed the meaning of the code"
+                    }
+                    crate::format::VerificationProblem::ChangedComments => {
+                        "lost or added comments"
+                    }
+                    crate::format::VerificationProblem::NotIdempotent => {
+                        "produced code that changes when formatted again"
+                    }
+                };
+                let snippet = src
+                    .get(location.start as usize..location.end as usize)
+                    .unwrap_or_default();
+                let text = wrap_format!(
+                    "The formatter {} so the file has been left unchanged. \
+This is a bug in the Gleam compiler, please report it along with the code \
+below at https://github.com/gleam-lang/gleam/issues",
+                    problem
+                );
+                Diagnostic {
+                    title: "Internal Error Formatter".into(),
+                    text: format!("{}\n\n{}", text, snippet.trim_end()),
+                    hint: None,
+                    level: Level::Error,
+                    location: Some(Location {
+                        label: Label {
+                            text: Some("Formatting this changes it incorrectly".into()),
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.clone(),
+                        extra_labels: vec![],
+                    }),
+                }
+            }
+
             Error::ForbiddenWarnings { count } => {
                 let word_warning = match count {
                     1 => "warning",
                     _ => "warnings",
                 };
-                let text = "Your project was compiled with the `--warnings-as-errors` flag.
+                let text = "Warnings are not permitted by the `--warnings-as-errors` or
+`--deny` flags, or by the `[warnings]` section of gleam.toml.
 Fix the warnings and try again."
                     .into();
                 Diagnostic {
@@ -2160,6 +2759,42 @@ Fix the warnings and try again."
                 },
             },
 
+            Error::Go { src, path, error } => match error {
+                go::Error::Unsupported { feature, location } => Diagnostic {
+                    title: "Unsupported feature for compilation target".into(),
+                    text: format!("{feature} is not supported for Go compilation"),
+                    hint: None,
+                    level: Level::Error,
+                    location: Some(Location {
+                        label: Label {
+                            text: None,
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.into(),
+                        extra_labels: vec![],
+                    }),
+                },
+            },
+
+            Error::Llvm { src, path, error } => match error {
+                llvm::Error::Unsupported { feature, location } => Diagnostic {
+                    title: "Unsupported feature for compilation target".into(),
+                    text: format!("{feature} is not supported for LLVM compilation"),
+                    hint: None,
+                    level: Level::Error,
+                    location: Some(Location {
+                        label: Label {
+                            text: None,
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.into(),
+                        extra_labels: vec![],
+                    }),
+                },
+            },
+
             Error::DownloadPackageError {
                 package_name,
                 package_version,
@@ -2248,6 +2883,193 @@ dev-dependencies sections of the gleam.toml file.",
                 }
             }
 
+            Error::WrongDependencyProvided {
+                path,
+                expected,
+                found,
+            } => {
+                let text = format!(
+                    "The package {} was expected to be found at {}, but the
+gleam.toml file there is for the package {}.",
+                    expected,
+                    path.to_string_lossy(),
+                    found
+                );
+                Diagnostic {
+                    title: "Wrong dependency provided".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
+            Error::PublishNonHexDependency { package } => {
+                let text = format!(
+                    "The package {} is a dependency of this package with a path or
+git repository rather than a version. Packages published to Hex can only
+depend on other packages published to Hex.",
+                    package
+                );
+                Diagnostic {
+                    title: "Non-Hex dependency cannot be published".into(),
+                    text,
+                    hint: Some(format!(
+                        "Publish {} to Hex and depend upon it by version instead.",
+                        package
+                    )),
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
+            Error::BuildHook {
+                package,
+                hook,
+                status,
+            } => {
+                let text = format!(
+                    "The command of the build hook {} of the package {} exited with
+status {}, so the package was not built.",
+                    hook, package, status
+                );
+                Diagnostic {
+                    title: "Build hook failed".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
+            Error::NoMatchingTests { filter } => Diagnostic {
+                title: "No tests found".into(),
+                text: format!("No test functions match the filter `{}`.", filter),
+                hint: Some(
+                    "Test functions are the public functions of the test modules that
+take no arguments and have names ending in `_test`. The filter is
+matched against their module and name, such as `my_app_test.add_test`."
+                        .into(),
+                ),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::UnknownDependencies { packages } => {
+                let text = format!(
+                    "These packages are not dependencies of this project:
+
+    {}",
+                    packages.join("\n    ")
+                );
+                Diagnostic {
+                    title: "Unknown dependencies".into(),
+                    text,
+                    hint: Some("Run `gleam deps list` to see the dependencies.".into()),
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
+            Error::UnknownRegistry { registry } => {
+                let text = format!(
+                    "The registry {} is used by this project but it is not one of the
+registries given in the `registries` section of gleam.toml.",
+                    registry
+                );
+                Diagnostic {
+                    title: "Unknown registry".into(),
+                    text,
+                    hint: Some(format!(
+                        "Add the URLs of the registry to gleam.toml:
+
+    [registries.{}]
+    api = \"https://...\"
+    repository = \"https://...\"",
+                        registry
+                    )),
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
+            Error::InvalidRegistry { registry, error } => {
+                let text = format!(
+                    "The registry {} given in gleam.toml could not be used.
+
+{}",
+                    registry, error
+                );
+                Diagnostic {
+                    title: "Invalid registry".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
+            Error::GitDependency {
+                package,
+                repo,
+                error,
+            } => {
+                let text = format!(
+                    "The package {} could not be fetched from the git repository
+{}
+
+{}",
+                    package, repo, error
+                );
+                Diagnostic {
+                    title: "Failed to fetch git dependency".into(),
+                    text,
+                    hint: Some("Check the `git` and `ref` of the dependency in gleam.toml.".into()),
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
+            Error::WorkspaceDependencyMismatch {
+                package,
+                requirements,
+            } => {
+                let text = format!(
+                    "The packages of this workspace require different versions of the
+package {}:
+
+    {}
+
+The dependencies of every package of a workspace are resolved together, so
+they must all require the same versions.",
+                    package,
+                    requirements.join("\n    ")
+                );
+                Diagnostic {
+                    title: "Workspace dependency mismatch".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
+            Error::UnknownDependencyFeature { package, flag } => {
+                let text = format!(
+                    "The `dependency-features` of gleam.toml set the feature flag `{}`
+of the package {}, but that package does not declare it in the `features`
+of its gleam.toml.",
+                    flag, package
+                );
+                Diagnostic {
+                    title: "Unknown feature flag".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
             Error::MissingHexPublishFields {
                 description_missing,
                 licence_missing,
@@ -2300,6 +3122,295 @@ issue in our tracker: https://github.com/gleam-lang/gleam/issues",
                     level: Level::Error,
                 }
             }
+            Error::DependencyBuildFailed {
+                package,
+                program,
+                output,
+                log,
+            } => {
+                let text = format!(
+                    "The {package} package could not be built with {program}. \
+This was its output:
+
+{}",
+                    output.trim_end()
+                );
+                Diagnostic {
+                    title: "Failed to build dependency".into(),
+                    text,
+                    hint: Some(format!(
+                        "This output has also been written to {}.",
+                        log.to_string_lossy()
+                    )),
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+            Error::JavaScriptBundleNotConfigured { key } => Diagnostic {
+                title: "Bundle command not configured".into(),
+                text: format!(
+                    "No `{key}` command has been set in the `[javascript.bundle]`
+section of gleam.toml so the project cannot be bundled."
+                ),
+                hint: Some(format!(
+                    "Add the command to gleam.toml, for example:
+
+    [javascript.bundle]
+    {key} = \"esbuild --bundle --outdir=dist\""
+                )),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::BundleUnsupportedTarget { target } => Diagnostic {
+                title: "Bundling not supported".into(),
+                text: format!(
+                    "Bundling is only supported when compiling to JavaScript, but the
+target is {target}."
+                ),
+                hint: Some("Run the command again with `--target javascript`.".into()),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::GoCommandUnsupported { command } => Diagnostic {
+                title: "Unsupported command for the Go target".into(),
+                text: format!(
+                    "The Go target can only be used to build and run projects, so
+`gleam {command}` cannot be used with it."
+                ),
+                hint: Some("Run the command again with another target.".into()),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::LlvmCommandUnsupported { command } => Diagnostic {
+                title: "Unsupported command for the LLVM target".into(),
+                text: format!(
+                    "The LLVM target can only be used to build and run projects, so
+`gleam {command}` cannot be used with it."
+                ),
+                hint: Some("Run the command again with another target.".into()),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::WasmCommandUnsupported { command } => Diagnostic {
+                title: "Unsupported command for the WebAssembly target".into(),
+                text: format!(
+                    "The WebAssembly target can only be used to build projects and
+export them as components, so `gleam {command}` cannot be used with it."
+                ),
+                hint: Some(
+                    "Run `gleam export wasm-component` and run the component with a
+WebAssembly runtime such as Wasmtime."
+                        .into(),
+                ),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::UnknownHexPackage { package } => Diagnostic {
+                title: "Unknown package".into(),
+                text: format!("The package `{package}` could not be found on Hex."),
+                hint: None,
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::NotHexPackageOwner { package, user } => Diagnostic {
+                title: "Not a package owner".into(),
+                text: format!(
+                    "The Hex user `{user}` is not an owner of the package `{package}`,
+so its releases cannot be retired or unretired."
+                ),
+                hint: Some("An owner of the package can add you as an owner.".into()),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::UnknownHexKey { name } => Diagnostic {
+                title: "Unknown Hex API key".into(),
+                text: format!("There is no stored Hex API key named `{name}`."),
+                hint: Some("Run `gleam hex keys` to see the stored keys.".into()),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::OfflineResolution => Diagnostic {
+                title: "Dependencies cannot be resolved offline".into(),
+                text: "The manifest.toml file is missing or out of date with gleam.toml,
+and the versions of the dependencies cannot be resolved without network
+access."
+                    .into(),
+                hint: Some("Run the command without `--offline` to update manifest.toml.".into()),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::OfflineUnsupported { command } => Diagnostic {
+                title: "Network access required".into(),
+                text: format!(
+                    "The `gleam {}` command looks up packages on Hex, which cannot be
+done without network access.",
+                    command
+                ),
+                hint: Some("Run the command without `--offline`.".into()),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::OfflinePackageNotVendored { package, version } => Diagnostic {
+                title: "Package not vendored".into(),
+                text: format!(
+                    "The package `{package}` at {version} is not in the vendor directory
+and cannot be downloaded without network access."
+                ),
+                hint: Some("Run `gleam deps vendor` to vendor the dependencies.".into()),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::InvalidPublishGlob { glob, error } => Diagnostic {
+                title: "Invalid publish glob".into(),
+                text: format!(
+                    "The glob `{glob}` in the `[publish]` section of gleam.toml is not
+valid: {error}"
+                ),
+                hint: None,
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::MultipleTargetsUnsupported { flag } => Diagnostic {
+                title: "Multiple targets not supported".into(),
+                text: format!(
+                    "The project can only be built for one target at a time with
+`{flag}`, but more than one target was to be built."
+                ),
+                hint: Some("Run the command again with the target to build, such as
+`--target erlang`."
+                    .into()),
+                location: None,
+                level: Level::Error,
+            },
+
+            Error::DisallowedDependencyLicences { packages } => {
+                let packages = packages
+                    .iter()
+                    .map(|package| {
+                        let licences = if package.licences.is_empty() {
+                            "unknown licence".to_string()
+                        } else {
+                            package.licences.join(", ")
+                        };
+                        format!("  - {} {} ({})", package.name, package.version, licences)
+                    })
+                    .join("\n");
+                Diagnostic {
+                    title: "Licence not allowed".into(),
+                    text: format!(
+                        "These dependency packages do not have an allowed licence:
+
+{packages}"
+                    ),
+                    hint: Some(
+                        "Add the licence to the allowed licences, or remove the packages \
+that depend on these ones."
+                            .into(),
+                    ),
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
+            Error::InvalidMainFunction {
+                module,
+                problem,
+                definition,
+            } => {
+                let (title, label, text, hint) = match problem {
+                    MainFunctionProblem::ModuleNotFound => (
+                        "Module not found",
+                        None,
+                        format!("The module `{module}` could not be found, so it cannot be run."),
+                        format!(
+                            "Add a `{module}` module with a public `main` function that takes
+no arguments:
+
+    pub fn main() {{
+      todo
+    }}"
+                        ),
+                    ),
+                    MainFunctionProblem::NotFound => (
+                        "Main function not found",
+                        None,
+                        format!(
+                            "The module `{module}` does not have a `main` function, so it
+cannot be run."
+                        ),
+                        "Add a public `main` function that takes no arguments:
+
+    pub fn main() {
+      todo
+    }"
+                        .into(),
+                    ),
+                    MainFunctionProblem::NotAFunction => (
+                        "Main is not a function",
+                        Some("This is not a function"),
+                        format!("`main` must be a function for `{module}` to be run."),
+                        "Replace the constant with a public function that takes no arguments."
+                            .into(),
+                    ),
+                    MainFunctionProblem::Private => (
+                        "Private main function",
+                        Some("This function is private"),
+                        format!("The `main` function must be public for `{module}` to be run."),
+                        "Add `pub` before `fn main` to make it public.".into(),
+                    ),
+                    MainFunctionProblem::HasArguments { arity } => (
+                        "Main function takes arguments",
+                        Some("This function takes arguments"),
+                        format!(
+                            "The `main` function of `{module}` takes {arity} {}, but
+it is called with none when the program is run.",
+                            if *arity == 1 { "argument" } else { "arguments" }
+                        ),
+                        "Remove the arguments from `main`.".into(),
+                    ),
+                    MainFunctionProblem::ReturnsFunction { return_type } => (
+                        "Main function returns a function",
+                        Some("This function returns a function"),
+                        format!(
+                            "The `main` function of `{module}` returns a value of type
+
+    {return_type}
+
+but this function is never called when the program is run."
+                        ),
+                        "Call the function within `main` instead of returning it.".into(),
+                    ),
+                };
+                let location = definition.as_ref().map(|(path, src, span)| Location {
+                    label: Label {
+                        text: label.map(String::from),
+                        span: *span,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                });
+                Diagnostic {
+                    title: title.into(),
+                    text,
+                    hint: Some(hint),
+                    location,
+                    level: Level::Error,
+                }
+            }
+
             Error::CPlusPlusCompilationNotImplemented  => {
                 Diagnostic {
                     title: "Native compilation is not yet supported".into(),
@@ -2346,7 +3457,148 @@ issue in our tracker: https://github.com/gleam-lang/gleam/issues",
                     },
                 }
             }
+
+            Error::CannotRename {
+                name,
+                new_name,
+                problem,
+            } => {
+                let (text, hint) = match problem {
+                    RenameProblem::NothingToRename => (
+                        "There is no function, constant, type, record constructor, label or
+variable at the cursor to be renamed."
+                            .into(),
+                        None,
+                    ),
+                    RenameProblem::InvalidName { uppercase: true } => (
+                        format!("`{new_name}` is not a valid name for `{name}`."),
+                        Some(
+                            "Type and record constructor names start with an uppercase letter
+and contain only letters and numbers, such as `LinkedList`."
+                                .into(),
+                        ),
+                    ),
+                    RenameProblem::InvalidName { uppercase: false } => (
+                        format!("`{new_name}` is not a valid name for `{name}`."),
+                        Some(
+                            "Names start with a lowercase letter and contain only lowercase
+letters, numbers and underscores, such as `linked_list`. Keywords cannot be used."
+                                .into(),
+                        ),
+                    ),
+                    RenameProblem::DefinedInDependency { module } => (
+                        format!(
+                            "`{name}` is defined in the module `{module}` of a dependency
+package, so it cannot be renamed."
+                        ),
+                        None,
+                    ),
+                    RenameProblem::NotCompiled => (
+                        "The project has changes that do not compile, so what is at the cursor
+and where it is used is not known."
+                            .into(),
+                        Some("Fix the errors in the project and then rename again.".into()),
+                    ),
+                    RenameProblem::Conflict => (
+                        format!(
+                            "`{name}` cannot be renamed to `{new_name}` as that name is
+already used where `{name}` is."
+                        ),
+                        None,
+                    ),
+                };
+                Diagnostic {
+                    title: "Cannot rename".into(),
+                    text,
+                    hint,
+                    location: None,
+                    level: Level::Error,
+                }
+            }
+
+            Error::Derive { path, src, error } => {
+                let (label, text, hint) = match &error.problem {
+                    crate::derive::Problem::UnknownGenerator { known } => (
+                        "Unknown generator",
+                        format!("There is no generator named `{}`.", error.generator),
+                        Some(format!(
+                            "The generators that can be derived are {}.",
+                            known.iter().map(|name| format!("`{name}`")).join(", ")
+                        )),
+                    ),
+                    crate::derive::Problem::Unsupported { reason } => (
+                        "Cannot be derived",
+                        format!(
+                            "The `{}` functions cannot be derived for this type.\n{}",
+                            error.generator, reason
+                        ),
+                        None,
+                    ),
+                };
+                Diagnostic {
+                    title: "Cannot derive functions".into(),
+                    text,
+                    hint,
+                    level: Level::Error,
+                    location: Some(Location {
+                        label: Label {
+                            text: Some(label.into()),
+                            span: error.location,
+                        },
+                        path: path.clone(),
+                        src: src.into(),
+                        extra_labels: vec![],
+                    }),
+                }
+            }
+
+            Error::InsufficientVersionBump {
+                previous,
+                current,
+                required,
+            } => Diagnostic {
+                title: "Insufficient version bump".into(),
+                text: format!(
+                    "The public API of the package has changes that require a {required}
+version bump from {previous}, but the version in gleam.toml is {current}."
+                ),
+                hint: Some(
+                    "Increase the version in gleam.toml, or undo the changes listed above.".into(),
+                ),
+                location: None,
+                level: Level::Error,
+            },
+        }
+    }
+}
+
+fn parse_error_diagnostic(path: &Path, src: &str, error: &ParseError) -> Diagnostic {
+    let (label, extra) = error.details();
+    let text = extra.join("\n");
+
+    let adjusted_location = if error.error == ParseErrorType::UnexpectedEof {
+        SrcSpan {
+            start: (src.len() - 1) as u32,
+            end: (src.len() - 1) as u32,
         }
+    } else {
+        error.location
+    };
+
+    Diagnostic {
+        title: "Syntax error".into(),
+        text,
+        hint: None,
+        level: Level::Error,
+        location: Some(Location {
+            label: Label {
+                text: Some(label.to_string()),
+                span: adjusted_location,
+            },
+            path: path.to_path_buf(),
+            src: src.into(),
+            extra_labels: vec![],
+        }),
     }
 }
 
@@ -2392,6 +3644,20 @@ fn import_cycle(buffer: &mut String, modules: &[String]) {
     buffer.push_str("    └─────┘\n");
 }
 
+fn trace_step_text(step: &TraceStep, first: bool) -> String {
+    let text = match (step.name.as_str(), step.kind) {
+        (PIPE_VARIABLE, TraceStepKind::Defined) => "it is piped into a function here".into(),
+        (PIPE_VARIABLE, TraceStepKind::Used) => "the piped value is used here".into(),
+        (name, TraceStepKind::Defined) => format!("`{name}` is defined here"),
+        (name, TraceStepKind::Used) => format!("`{name}` is used here"),
+    };
+    if first {
+        format!("because {text}")
+    } else {
+        format!("then {text}")
+    }
+}
+
 fn hint_alternative_operator(op: &BinOp, given: &Type) -> Option<String> {
     match op {
         BinOp::AddInt if given.is_float() => Some(hint_numeric_message("+.", "Float")),
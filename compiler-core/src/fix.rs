@@ -0,0 +1,595 @@
+//! Mechanical rewrites of source code, as applied by `gleam fix`.
+//!
+//! Each fix is a set of edits to the source of a module. They are found from
+//! the warnings emitted when type checking the module, such as unused imports
+//! and uses of deprecated values, and from syntax that is no longer
+//! recommended. Once applied only the definitions that were changed are
+//! formatted, so the rest of the module is left exactly as it was.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    ast::{
+        Arena, Pattern, SrcSpan, Statement, TargetGroup, UntypedExpr, UntypedModule,
+        UntypedStatement, Use,
+    },
+    build::{Origin, Target},
+    config::PackageConfig,
+    format,
+    language_server::TextEdit,
+    name::Name,
+    parse::{extra::ModuleExtra, lexer::make_tokenizer, token::Token},
+    type_::{self, Warning},
+    uid::UniqueIdGenerator,
+    Error, Result,
+};
+use itertools::Itertools;
+use std::{collections::HashSet, path::Path};
+use vec1::Vec1;
+
+/// A change that fixes a single problem with a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub title: String,
+    pub edits: Vec<TextEdit>,
+}
+
+/// The fixes that can be applied to a module, given the warnings emitted
+/// when it was type checked and the modules it could import.
+pub fn fixes(
+    src: &str,
+    path: &Path,
+    warnings: &[Warning],
+    importable_modules: &im::HashMap<String, type_::Module>,
+) -> Result<Vec<Fix>> {
+    let arena = Arena::new();
+    let (module, extra) = parse(src, path, &arena)?;
+    let imports = imports(&module);
+    let can_import_result = importable_modules.contains_key("gleam/result");
+    let tries = try_to_use(src, &module, &imports, can_import_result);
+    // `gleam/result` may only be unused because it is used by `try`
+    let keep_result = !tries.is_empty();
+    let mut fixes = unused_imports(src, &extra, &imports, warnings, keep_result);
+    fixes.extend(
+        warnings
+            .iter()
+            .filter_map(|warning| deprecated_rename(src, &imports, warning)),
+    );
+    fixes.extend(tries);
+    Ok(fixes)
+}
+
+/// The fixes for a single warning of a module. Unlike `fixes` this leaves out
+/// the rewrites of syntax that is no longer recommended, which are not
+/// reported by any warning.
+pub fn warning_fixes(src: &str, path: &Path, warning: &Warning) -> Result<Vec<Fix>> {
+    let arena = Arena::new();
+    let (module, extra) = parse(src, path, &arena)?;
+    let imports = imports(&module);
+    let keep_result = !try_to_use(src, &module, &imports, false).is_empty();
+    let warnings = std::slice::from_ref(warning);
+    let mut fixes = unused_imports(src, &extra, &imports, warnings, keep_result);
+    fixes.extend(deprecated_rename(src, &imports, warning));
+    Ok(fixes)
+}
+
+/// Apply fixes to the source of a module, formatting each definition that
+/// was changed. A fix that would change the same code as an earlier one is
+/// skipped.
+pub fn apply(src: &str, path: &Path, fixes: &[Fix], options: &format::Options) -> Result<String> {
+    let mut accepted: Vec<&TextEdit> = vec![];
+    for fix in fixes {
+        let overlaps = fix.edits.iter().any(|edit| {
+            accepted
+                .iter()
+                .any(|other| overlap(edit.location, other.location))
+        });
+        if !overlaps {
+            accepted.extend(&fix.edits);
+        }
+    }
+    accepted.sort_by_key(|edit| (edit.location.start, edit.location.end));
+
+    // Apply the edits, keeping track of where the new text of each ends up
+    let mut fixed = String::with_capacity(src.len());
+    let mut changed = vec![];
+    let mut position = 0;
+    for edit in accepted {
+        fixed.push_str(text(src, SrcSpan::new(position, edit.location.start)));
+        let start = fixed.len() as u32;
+        fixed.push_str(&edit.new_text);
+        changed.push(SrcSpan::new(start, fixed.len() as u32));
+        position = edit.location.end;
+    }
+    fixed.push_str(text(src, SrcSpan::new(position, src.len() as u32)));
+
+    // Format from the end of the module backwards so that the locations of
+    // the earlier changes are still correct
+    for location in changed.into_iter().rev() {
+        if let Some(edit) = format::pretty_range(&fixed, path, location, options)? {
+            fixed.replace_range(
+                edit.location.start as usize..edit.location.end as usize,
+                &edit.new_text,
+            );
+        }
+    }
+    Ok(fixed)
+}
+
+/// Whether the fixed source of a module of the package still type checks
+/// against the modules it can import, so that fixes which would stop it
+/// compiling are not written.
+pub fn type_checks(
+    src: &str,
+    name: &str,
+    origin: Origin,
+    target: Target,
+    config: &PackageConfig,
+    ids: &UniqueIdGenerator,
+    importable_modules: &im::HashMap<String, type_::Module>,
+) -> bool {
+    let arena = Arena::new();
+    let mut module = match crate::parse::parse_module(src, &arena) {
+        Ok((module, _)) => module,
+        Err(_) => return false,
+    };
+    module.name = name.split('/').map(Name::from).collect();
+    type_::infer_module_with_features(
+        target,
+        ids,
+        module,
+        origin,
+        &config.name,
+        importable_modules,
+        config.features.clone(),
+        &mut vec![],
+    )
+    .is_ok()
+}
+
+fn parse<'a>(src: &str, path: &Path, arena: &'a Arena) -> Result<(UntypedModule<'a>, ModuleExtra)> {
+    crate::parse::parse_module(src, arena).map_err(|error| Error::Parse {
+        path: path.to_path_buf(),
+        src: src.to_string(),
+        errors: Vec1::new(error),
+    })
+}
+
+fn overlap(a: SrcSpan, b: SrcSpan) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+fn text(src: &str, location: SrcSpan) -> &str {
+    src.get(location.start as usize..location.end as usize)
+        .unwrap_or_default()
+}
+
+// The import statements of the module, in any target group
+fn imports<'a, 'b>(module: &'a UntypedModule<'b>) -> Vec<&'a UntypedStatement<'b>> {
+    module
+        .statements
+        .iter()
+        .flat_map(TargetGroup::statements_ref)
+        .filter(|statement| matches!(statement, Statement::Import { .. }))
+        .collect()
+}
+
+// Unused imports are removed. When only some of the unqualified values of an
+// import are unused those values are removed from it.
+fn unused_imports(
+    src: &str,
+    extra: &ModuleExtra,
+    imports: &[&UntypedStatement<'_>],
+    warnings: &[Warning],
+    keep_result: bool,
+) -> Vec<Fix> {
+    let mut unused_modules = vec![];
+    let mut unused_values = vec![];
+    for warning in warnings {
+        match warning {
+            Warning::UnusedImportedModule { location, .. } => {
+                unused_modules.push(*location);
+            }
+            Warning::UnusedImportedValue { location, .. }
+            | Warning::UnusedType {
+                location,
+                imported: true,
+                ..
+            }
+            | Warning::UnusedConstructor {
+                location,
+                imported: true,
+                ..
+            } => {
+                unused_values.push(*location);
+            }
+            _ => (),
+        }
+    }
+
+    let mut fixes = vec![];
+    for import in imports {
+        let (location, module, unqualified) = match import {
+            Statement::Import {
+                location,
+                module,
+                unqualified,
+                ..
+            } => (*location, module.join("/"), unqualified),
+            _ => continue,
+        };
+        if keep_result && module == "gleam/result" {
+            continue;
+        }
+
+        if unused_modules.contains(&location) {
+            let edit = remove_definition(src, extra, location);
+            fixes.push(Fix {
+                title: format!("Remove unused import of `{}`", module),
+                edits: edit.into_iter().collect(),
+            });
+            continue;
+        }
+
+        let (unused, used): (Vec<_>, Vec<_>) = unqualified
+            .iter()
+            .partition(|value| unused_values.contains(&value.location));
+        if unused.is_empty() {
+            continue;
+        }
+        let import_src = text(src, location);
+        let (open, close) = match (import_src.find(".{"), import_src.rfind('}')) {
+            (Some(open), Some(close)) => (open as u32, close as u32 + 1),
+            _ => continue,
+        };
+        let new_text = if used.is_empty() {
+            String::new()
+        } else {
+            let values = used
+                .iter()
+                .map(|value| text(src, value.location))
+                .join(", ");
+            format!(".{{{}}}", values)
+        };
+        let names = unused.iter().map(|value| value.name.as_str()).join("`, `");
+        fixes.push(Fix {
+            title: format!("Remove unused `{}` from import of `{}`", names, module),
+            edits: vec![TextEdit {
+                location: SrcSpan::new(location.start + open, location.start + close),
+                new_text,
+            }],
+        });
+    }
+    fixes
+}
+
+// An edit removing the whole of the definition at the location along with
+// the rest of its line.
+fn remove_definition(src: &str, extra: &ModuleExtra, location: SrcSpan) -> Option<TextEdit> {
+    let definition = extra
+        .definitions
+        .iter()
+        .find(|definition| definition.contains(location.start))?;
+    let rest = text(src, SrcSpan::new(definition.end, src.len() as u32));
+    let at_start = text(src, SrcSpan::new(0, definition.start))
+        .trim()
+        .is_empty();
+    // At the start of the module the blank lines after the definition go too
+    let removed = if at_start {
+        rest.len() - rest.trim_start().len()
+    } else {
+        rest.find('\n').map(|index| index + 1).unwrap_or(rest.len())
+    };
+    Some(TextEdit {
+        location: SrcSpan::new(definition.start, definition.end + removed as u32),
+        new_text: String::new(),
+    })
+}
+
+// A deprecated value or type whose deprecation message is of the form
+// "Use `new_name` instead" is renamed to the new name.
+fn deprecated_rename(
+    src: &str,
+    imports: &[&UntypedStatement<'_>],
+    warning: &Warning,
+) -> Option<Fix> {
+    let (location, message) = match warning {
+        Warning::DeprecatedItem {
+            location, message, ..
+        } => (*location, message),
+        _ => return None,
+    };
+    let replacement = message
+        .trim()
+        .strip_prefix("Use ")?
+        .strip_suffix(" instead")?
+        .trim_matches('`');
+    if !is_name(replacement) {
+        return None;
+    }
+
+    // The name is the last part of a reference such as `list.at`, before
+    // any arguments such as those of a type `Box(a)`
+    let reference = text(src, location);
+    let reference = reference.split('(').next().unwrap_or_default().trim_end();
+    let start = reference.rfind('.').map(|index| index + 1).unwrap_or(0);
+    let name = reference.get(start..).unwrap_or_default();
+    if !is_name(name) {
+        return None;
+    }
+
+    // An unqualified import would need to be changed along with the name,
+    // so names that were imported unqualified are left alone
+    let qualified = start > 0;
+    if !qualified && is_imported_unqualified(imports, name) {
+        return None;
+    }
+
+    let start = location.start + start as u32;
+    Some(Fix {
+        title: format!("Replace deprecated `{}` with `{}`", name, replacement),
+        edits: vec![TextEdit {
+            location: SrcSpan::new(start, start + name.len() as u32),
+            new_text: replacement.to_string(),
+        }],
+    })
+}
+
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_imported_unqualified(imports: &[&UntypedStatement<'_>], name: &str) -> bool {
+    imports.iter().any(|import| match import {
+        Statement::Import { unqualified, .. } => unqualified
+            .iter()
+            .any(|value| value.variable_name() == name),
+        _ => false,
+    })
+}
+
+// `try value = result` becomes `use value <- result.then(result)`. A `try`
+// with a pattern other than a variable or an annotation cannot be written
+// with `use` and is left alone, as is every `try` when `result.then` cannot
+// be referred to.
+fn try_to_use(
+    src: &str,
+    module: &UntypedModule<'_>,
+    imports: &[&UntypedStatement<'_>],
+    can_import_result: bool,
+) -> Vec<Fix> {
+    let mut expressions = vec![];
+    for statement in module
+        .statements
+        .iter()
+        .flat_map(TargetGroup::statements_ref)
+    {
+        if let Statement::Fn { body, .. } = statement {
+            collect_tries(body, &mut expressions);
+        }
+    }
+
+    if expressions.is_empty() {
+        return vec![];
+    }
+    let (then, import) = match result_then(src, imports, can_import_result) {
+        Some(then) => then,
+        None => return vec![],
+    };
+    let mut fixes = vec![];
+    for expression in expressions {
+        let (location, value, name) = match expression {
+            UntypedExpr::Try {
+                location,
+                value,
+                pattern: Pattern::Var { name, .. } | Pattern::Discard { name, .. },
+                annotation: None,
+                ..
+            } => (location, value, name),
+            _ => continue,
+        };
+        let value_start = value.start_byte_index();
+        let value_end = value.location().end;
+        let mut edits: Vec<_> = import.clone().into_iter().collect();
+        edits.push(TextEdit {
+            location: SrcSpan::new(location.start, value_start),
+            new_text: format!("use {} <- {}(", name, then),
+        });
+        edits.push(TextEdit {
+            location: SrcSpan::new(value_end, value_end),
+            new_text: ")".into(),
+        });
+        fixes.push(Fix {
+            title: format!("Replace `try {}` with `use`", name),
+            edits,
+        });
+    }
+    fixes
+}
+
+// The name to call `result.then` with, along with an edit to import
+// `gleam/result` if it is not yet imported. An existing import is used
+// unless its name for the module is shadowed, and otherwise the module is
+// imported with a name that is not taken, if it can be imported.
+fn result_then(
+    src: &str,
+    imports: &[&UntypedStatement<'_>],
+    can_import_result: bool,
+) -> Option<(String, Option<TextEdit>)> {
+    let taken = taken_names(src, imports);
+    let import = imports.iter().find_map(|import| match import {
+        Statement::Import {
+            module,
+            as_name,
+            unqualified,
+            ..
+        } if module == &["gleam", "result"] => Some((as_name, unqualified)),
+        _ => None,
+    });
+    if let Some((as_name, unqualified)) = import {
+        if let Some(value) = unqualified.iter().find(|value| value.name == "then") {
+            return Some((value.variable_name().to_string(), None));
+        }
+        let qualifier = as_name.as_deref().unwrap_or("result");
+        return (!taken.contains(qualifier)).then(|| (format!("{}.then", qualifier), None));
+    }
+    if !can_import_result {
+        return None;
+    }
+
+    let qualifier = ["result".to_string(), "gleam_result".to_string()]
+        .into_iter()
+        .chain((2..).map(|number| format!("gleam_result_{}", number)))
+        .find(|name| !taken.contains(name))?;
+    let new_text = if qualifier == "result" {
+        "import gleam/result\n".to_string()
+    } else {
+        format!("import gleam/result as {}\n", qualifier)
+    };
+    let import = TextEdit {
+        location: SrcSpan::new(0, 0),
+        new_text,
+    };
+    Some((format!("{}.then", qualifier), Some(import)))
+}
+
+// The names that would shadow a module of the same name: the names given to
+// the modules and values imported other than from `gleam/result`, and every
+// name outside of imports that is not followed or preceded by a `.`. Labels
+// are included along with variables and functions, so this can take a name
+// to be used when it is not.
+fn taken_names(src: &str, imports: &[&UntypedStatement<'_>]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut import_locations = vec![];
+    for import in imports {
+        if let Statement::Import {
+            location,
+            module,
+            as_name,
+            unqualified,
+            ..
+        } = import
+        {
+            import_locations.push(*location);
+            if module == &["gleam", "result"] {
+                continue;
+            }
+            let qualifier = as_name
+                .as_deref()
+                .or(module.last().map(|name| name.as_str()));
+            names.extend(qualifier.map(str::to_string));
+            names.extend(
+                unqualified
+                    .iter()
+                    .map(|value| value.variable_name().to_string()),
+            );
+        }
+    }
+
+    let tokens: Vec<_> = make_tokenizer(src).filter_map(|token| token.ok()).collect();
+    for (index, (start, token, _)) in tokens.iter().enumerate() {
+        let name = match token {
+            Token::Name { name } => name,
+            _ => continue,
+        };
+        let in_import = import_locations
+            .iter()
+            .any(|location| location.start <= *start && *start < location.end);
+        let is_dot = |index: Option<usize>| {
+            matches!(
+                index.and_then(|index| tokens.get(index)),
+                Some((_, Token::Dot, _))
+            )
+        };
+        if !in_import && !is_dot(index.checked_sub(1)) && !is_dot(Some(index + 1)) {
+            let _ = names.insert(name.clone());
+        }
+    }
+    names
+}
+
+// Every `try` expression within an expression
+fn collect_tries<'a, 'b>(expression: &'a UntypedExpr<'b>, tries: &mut Vec<&'a UntypedExpr<'b>>) {
+    match expression {
+        UntypedExpr::Int { .. }
+        | UntypedExpr::Float { .. }
+        | UntypedExpr::String { .. }
+        | UntypedExpr::Var { .. }
+        | UntypedExpr::Todo { .. }
+        | UntypedExpr::Cfg { .. } => (),
+
+        UntypedExpr::Try { value, then, .. } => {
+            tries.push(expression);
+            collect_tries(value, tries);
+            collect_tries(then, tries);
+        }
+
+        UntypedExpr::Sequence { expressions, .. } => {
+            expressions
+                .iter()
+                .for_each(|expression| collect_tries(expression, tries));
+        }
+        UntypedExpr::PipeLine { expressions } => {
+            expressions
+                .iter()
+                .for_each(|expression| collect_tries(expression, tries));
+        }
+        UntypedExpr::Tuple { elems, .. } => {
+            elems
+                .iter()
+                .for_each(|expression| collect_tries(expression, tries));
+        }
+        UntypedExpr::List { elements, tail, .. } => {
+            elements
+                .iter()
+                .chain(tail.as_deref())
+                .for_each(|expression| collect_tries(expression, tries));
+        }
+        UntypedExpr::Call { fun, arguments, .. } => {
+            collect_tries(fun, tries);
+            arguments
+                .iter()
+                .for_each(|argument| collect_tries(&argument.value, tries));
+        }
+        UntypedExpr::BinOp { left, right, .. } => {
+            collect_tries(left, tries);
+            collect_tries(right, tries);
+        }
+        UntypedExpr::Case {
+            subjects, clauses, ..
+        } => {
+            subjects
+                .iter()
+                .chain(clauses.iter().map(|clause| &clause.then))
+                .for_each(|expression| collect_tries(expression, tries));
+        }
+        UntypedExpr::BitString { segments, .. } => {
+            segments
+                .iter()
+                .for_each(|segment| collect_tries(&segment.value, tries));
+        }
+        UntypedExpr::RecordUpdate {
+            constructor,
+            spread,
+            arguments,
+            ..
+        } => {
+            collect_tries(constructor, tries);
+            collect_tries(&spread.base, tries);
+            arguments
+                .iter()
+                .for_each(|argument| collect_tries(&argument.value, tries));
+        }
+        UntypedExpr::Use(Use { call, .. }) => collect_tries(call, tries),
+        UntypedExpr::Fn { body, .. } => collect_tries(body, tries),
+        UntypedExpr::Assignment { value, .. } => collect_tries(value, tries),
+        UntypedExpr::FieldAccess { container, .. } => collect_tries(container, tries),
+        UntypedExpr::TupleIndex { tuple, .. } => collect_tries(tuple, tries),
+        UntypedExpr::Negate { value, .. } | UntypedExpr::Echo { value, .. } => {
+            collect_tries(value, tries)
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use super::*;
+use crate::{
+    build::{Origin, Target},
+    type_::{build_prelude, infer_module},
+    uid::UniqueIdGenerator,
+};
+use pretty_assertions::assert_eq;
+
+// Type check the modules and then apply the fixes for the last one
+fn fix(dependencies: &[(&str, &str)], src: &str) -> String {
+    let ids = UniqueIdGenerator::new();
+    let mut modules = im::HashMap::new();
+    let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+    for (name, dependency_src) in dependencies {
+        let arena = Arena::new();
+        let (mut ast, _) =
+            crate::parse::parse_module(dependency_src, &arena).expect("syntax error");
+        ast.name = name.split('/').map(Into::into).collect();
+        let module = infer_module(
+            Target::Erlang,
+            &ids,
+            ast,
+            Origin::Src,
+            "thepackage",
+            &modules,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let _ = modules.insert(name.to_string(), module.type_info);
+    }
+
+    let arena = Arena::new();
+    let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+    ast.name = vec!["my_module".into()];
+    let mut warnings = vec![];
+    let _ = infer_module(
+        Target::Erlang,
+        &ids,
+        ast,
+        Origin::Src,
+        "thepackage",
+        &modules,
+        &mut warnings,
+    )
+    .expect("should successfully infer");
+
+    let path = Path::new("my_module.gleam");
+    let fixes = fixes(src, path, &warnings, &modules).expect("fixes");
+    apply(src, path, &fixes, &format::Options::default()).expect("apply")
+}
+
+const WIBBLE: (&str, &str) = (
+    "wibble",
+    r#"pub type Wibble { Wibble }
+pub fn one() { 1 }
+pub fn two() { 2 }
+@deprecated("Use `new` instead")
+pub fn old() { 1 }
+pub fn new() { 1 }
+"#,
+);
+
+const RESULT: (&str, &str) = (
+    "gleam/result",
+    "pub fn then(result, fun) { case result { Ok(x) -> fun(x) Error(e) -> Error(e) } }",
+);
+
+#[test]
+fn remove_unused_import() {
+    assert_eq!(
+        fix(
+            &[WIBBLE, RESULT],
+            r#"import gleam/result
+import wibble
+
+pub fn main( ) {   result.then(Ok(1), Ok)   }
+"#
+        ),
+        r#"import gleam/result
+
+pub fn main( ) {   result.then(Ok(1), Ok)   }
+"#
+    );
+}
+
+#[test]
+fn remove_unused_import_at_start_of_module() {
+    assert_eq!(
+        fix(
+            &[WIBBLE],
+            r#"import wibble
+
+pub fn main() {
+  1
+}
+"#
+        ),
+        r#"pub fn main() {
+  1
+}
+"#
+    );
+}
+
+#[test]
+fn remove_unused_unqualified_imports() {
+    assert_eq!(
+        fix(
+            &[WIBBLE],
+            r#"import wibble.{one,   two, Wibble}
+
+pub fn main() {
+  one()
+}
+"#
+        ),
+        r#"import wibble.{one}
+
+pub fn main() {
+  one()
+}
+"#
+    );
+}
+
+#[test]
+fn rename_deprecated_value() {
+    assert_eq!(
+        fix(
+            &[WIBBLE],
+            r#"import wibble
+
+pub fn main( ) { wibble.old( ) }
+
+pub fn other( ) { wibble.one( ) }
+"#
+        ),
+        r#"import wibble
+
+pub fn main() {
+  wibble.new()
+}
+
+pub fn other( ) { wibble.one( ) }
+"#
+    );
+}
+
+#[test]
+fn deprecated_value_without_replacement_is_left_alone() {
+    let src = r#"@deprecated("Do not use")
+fn old() { 1 }
+
+pub fn main( ) { old( ) }
+"#;
+    assert_eq!(fix(&[], src), src);
+}
+
+#[test]
+fn try_to_use() {
+    assert_eq!(
+        fix(
+            &[RESULT],
+            r#"import gleam/result
+
+pub fn main( ) {
+  try x = Ok(1)
+  try _ = Ok(2)
+  Ok(x)
+}
+"#
+        ),
+        r#"import gleam/result
+
+pub fn main() {
+  use x <- result.then(Ok(1))
+  use _ <- result.then(Ok(2))
+  Ok(x)
+}
+"#
+    );
+}
+
+#[test]
+fn try_to_use_imports_result() {
+    assert_eq!(
+        fix(
+            &[RESULT],
+            r#"pub fn main() {
+  try x = Ok(1)
+  Ok(x)
+}
+"#
+        ),
+        r#"import gleam/result
+pub fn main() {
+  use x <- result.then(Ok(1))
+  Ok(x)
+}
+"#
+    );
+}
+
+#[test]
+fn try_with_pattern_is_left_alone() {
+    let src = r#"pub fn main( ) {
+  try #(x, y) = Ok(#(1, 2))
+  Ok(x + y)
+}
+"#;
+    assert_eq!(fix(&[], src), src);
+}
+
+#[test]
+fn try_is_left_alone_without_result_module() {
+    let src = r#"pub fn main() {
+  try x = Ok(1)
+  Ok(x)
+}
+"#;
+    assert_eq!(fix(&[], src), src);
+}
+
+#[test]
+fn try_to_use_imports_result_with_unshadowed_name() {
+    assert_eq!(
+        fix(
+            &[RESULT],
+            r#"pub fn main() {
+  let result = Ok(1)
+  try x = result
+  Ok(x)
+}
+"#
+        ),
+        r#"import gleam/result as gleam_result
+pub fn main() {
+  let result = Ok(1)
+  use x <- gleam_result.then(result)
+  Ok(x)
+}
+"#
+    );
+}
+
+#[test]
+fn try_to_use_with_aliased_import() {
+    assert_eq!(
+        fix(
+            &[RESULT],
+            r#"import gleam/result as res
+
+pub fn main() {
+  let result = Ok(1)
+  try x = res.then(result, Ok)
+  Ok(x)
+}
+"#
+        ),
+        r#"import gleam/result as res
+
+pub fn main() {
+  let result = Ok(1)
+  use x <- res.then(res.then(result, Ok))
+  Ok(x)
+}
+"#
+    );
+}
+
+#[test]
+fn try_is_left_alone_when_imported_result_is_shadowed() {
+    let src = r#"import gleam/result
+
+pub fn main() {
+  try x = result.then(Ok(1), Ok)
+  Ok(x)
+}
+
+fn other(result) {
+  result
+}
+"#;
+    assert_eq!(fix(&[RESULT], src), src);
+}
+
+#[test]
+fn fixed_module_type_checks() {
+    let ids = UniqueIdGenerator::new();
+    let mut modules = im::HashMap::new();
+    let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+    let config = PackageConfig::default();
+    let check = |src: &str| {
+        type_checks(
+            src,
+            "my_module",
+            Origin::Src,
+            Target::Erlang,
+            &config,
+            &ids,
+            &modules,
+        )
+    };
+    assert!(check("pub fn main() { Ok(1) }"));
+    assert!(!check(
+        "import gleam/result pub fn main() { use x <- result.then(Ok(1)) Ok(x) }"
+    ));
+}
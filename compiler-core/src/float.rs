@@ -0,0 +1,171 @@
+//! The canonical textual representation of Gleam floats.
+//!
+//! Every target prints floats the same way: the shortest decimal digits that
+//! round-trip to the same 64-bit float, always with a fractional part, using
+//! scientific notation for numbers with a decimal exponent below -6 or above
+//! 20 (the same thresholds used by JavaScript's `Number.prototype.toString`).
+//!
+//! ```text
+//! 1.0       0.1       -0.0       100000000000000000000.0
+//! 1.0e21    1.5e-7    1.7976931348623157e308
+//! ```
+//!
+//! The same rules are implemented by the Erlang runtime (`float_to_string` and
+//! `parse_float` in `gleam@@main`), the JavaScript prelude (`floatToString`
+//! and `parseFloat`) and the native runtime (`gleam::FloatToString` and
+//! `gleam::ParseFloat`), and all of them are checked against the cases in
+//! `test/float_conformance/cases.txt`.
+
+/// Print a float in the canonical representation.
+///
+pub fn to_string(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".into();
+    }
+    if value.is_infinite() {
+        return if value < 0.0 { "-Infinity" } else { "Infinity" }.into();
+    }
+
+    // Rust's exponent formatting gives the shortest round-trip digits, e.g.
+    // `1.5e-7` or `-1e21`.
+    let scientific = format!("{:e}", value);
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("float scientific formatting has an exponent");
+    let exponent: i32 = exponent.parse().expect("float exponent is an integer");
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    format!("{}{}", sign, from_digits(&digits, exponent))
+}
+
+/// Render significant `digits` (with no leading or trailing zeros, besides a
+/// lone zero) whose first digit is multiplied by `10^exponent`.
+fn from_digits(digits: &str, exponent: i32) -> String {
+    if (-6..21).contains(&exponent) {
+        if exponent < 0 {
+            let zeros = "0".repeat((-exponent - 1) as usize);
+            format!("0.{}{}", zeros, digits)
+        } else {
+            let integer_length = exponent as usize + 1;
+            if digits.len() > integer_length {
+                let (integer, fraction) = digits.split_at(integer_length);
+                format!("{}.{}", integer, fraction)
+            } else {
+                let zeros = "0".repeat(integer_length - digits.len());
+                format!("{}{}.0", digits, zeros)
+            }
+        }
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let rest = if rest.is_empty() { "0" } else { rest };
+        format!("{}.{}e{}", first, rest, exponent)
+    }
+}
+
+/// Parse a float written in the canonical syntax: an optional minus sign,
+/// digits, a decimal point, digits, and an optional exponent. The result is
+/// the nearest 64-bit float to the written decimal.
+///
+pub fn parse(string: &str) -> Option<f64> {
+    let unsigned = string.strip_prefix('-').unwrap_or(string);
+    let (decimal, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((decimal, exponent)) => (decimal, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let (integer, fraction) = decimal.split_once('.')?;
+    let all_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let valid_exponent = match exponent {
+        Some(exponent) => all_digits(exponent.strip_prefix(['-', '+']).unwrap_or(exponent)),
+        None => true,
+    };
+    if !(all_digits(integer) && all_digits(fraction) && valid_exponent) {
+        return None;
+    }
+    string.parse().ok().filter(|value: &f64| value.is_finite())
+}
+
+/// The value of a float literal from Gleam source code, which may contain
+/// underscores or end with a decimal point. Literals too large to be
+/// represented as a 64-bit float have no value.
+///
+pub fn literal_value(literal: &str) -> Option<f64> {
+    literal
+        .replace('_', "")
+        .parse()
+        .ok()
+        .filter(|value: &f64| value.is_finite())
+}
+
+/// The canonical form of a float literal from Gleam source code. Literals too
+/// large to be represented are rejected during analysis, so are never given.
+///
+pub fn canonical_literal(literal: &str) -> String {
+    to_string(literal_value(literal).expect("float literal in range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CASES: &str = include_str!("../../test/float_conformance/cases.txt");
+
+    fn cases() -> impl Iterator<Item = (&'static str, &'static str)> {
+        CASES
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                line.split_once(char::is_whitespace)
+                    .map(|(input, expected)| (input, expected.trim()))
+                    .expect("float conformance case has an input and an expected output")
+            })
+    }
+
+    #[test]
+    fn conformance_to_string() {
+        for (input, expected) in cases() {
+            let value: f64 = input.parse().expect("float conformance input");
+            assert_eq!(to_string(value), expected, "printing {}", input);
+        }
+    }
+
+    #[test]
+    fn conformance_parse_round_trips() {
+        for (input, expected) in cases() {
+            let value: f64 = input.parse().expect("float conformance input");
+            assert_eq!(
+                parse(expected).map(f64::to_bits),
+                Some(value.to_bits()),
+                "parsing {}",
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn parse_rejects_non_canonical_syntax() {
+        for input in [
+            "", "1", "1.", ".5", "1.5e", "1_000.0", "Infinity", "NaN", "0x1.0", "1.0e400",
+        ] {
+            assert_eq!(parse(input), None, "parsing {:?}", input);
+        }
+    }
+
+    #[test]
+    fn canonical_literals() {
+        assert_eq!(canonical_literal("1."), "1.0");
+        assert_eq!(canonical_literal("1_000.50"), "1000.5");
+        assert_eq!(canonical_literal("-0.0"), "-0.0");
+        assert_eq!(canonical_literal("1000000000000000000000.0"), "1.0e21");
+    }
+
+    #[test]
+    fn literal_values() {
+        assert_eq!(literal_value("1_000.5"), Some(1000.5));
+        assert_eq!(literal_value("1.7976931348623157e308"), Some(f64::MAX));
+        assert_eq!(literal_value(&format!("{}.", "9".repeat(309))), None);
+        assert_eq!(literal_value(&format!("-1{}.0", "_000".repeat(103))), None);
+    }
+}
@@ -5,23 +5,69 @@ use crate::{
     ast::{Use, *},
     docvec,
     io::Utf8Writer,
-    parse::extra::Comment,
+    language_server::TextEdit,
+    parse::{extra::Comment, token::Token},
     pretty::*,
     type_::{self, Type},
     Error, Result,
 };
 use itertools::Itertools;
+use lazy_static::lazy_static;
 use std::{path::Path, sync::Arc};
 use vec1::Vec1;
 
 const INDENT: isize = 2;
 
-pub fn pretty(writer: &mut impl Utf8Writer, src: &str, path: &Path) -> Result<()> {
-    let (module, extra) = crate::parse::parse_module(src).map_err(|error| Error::Parse {
-        path: path.to_path_buf(),
-        src: src.to_string(),
-        error,
-    })?;
+/// How `gleam format` lays out code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The width in columns that code is fit within.
+    pub line_length: isize,
+    /// Sort imports by module name and group them into the standard library,
+    /// dependencies, and the modules of the package being formatted, in that
+    /// order.
+    pub sort_imports: bool,
+    /// The name of the package being formatted, used to tell its own modules
+    /// apart from those of dependencies when sorting imports.
+    pub package: Option<String>,
+    /// Check that the formatted code means the same as the original and is
+    /// not changed by formatting it again, returning an error rather than
+    /// mangled code if either is not the case.
+    pub verify: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            line_length: DEFAULT_LINE_LENGTH,
+            sort_imports: false,
+            package: None,
+            verify: false,
+        }
+    }
+}
+
+pub fn pretty(
+    writer: &mut impl Utf8Writer,
+    src: &str,
+    path: &Path,
+    options: &Options,
+) -> Result<()> {
+    let formatted = format_module(src, path, options)?;
+    if options.verify {
+        verify(src, &formatted, path, options)?;
+    }
+    writer.str_write(&formatted)
+}
+
+fn format_module(src: &str, path: &Path, options: &Options) -> Result<String> {
+    let arena = Arena::new();
+    let (module, extra) =
+        crate::parse::parse_module(src, &arena).map_err(|error| Error::Parse {
+            path: path.to_path_buf(),
+            src: src.to_string(),
+            errors: Vec1::new(error),
+        })?;
     let intermediate = Intermediate {
         comments: extra
             .comments
@@ -34,16 +80,417 @@ pub fn pretty(writer: &mut impl Utf8Writer, src: &str, path: &Path) -> Result<()
             .map(|span| Comment::from((span, src)))
             .collect(),
         empty_lines: &extra.empty_lines,
+        definitions: &extra.definitions,
         module_comments: extra
             .module_comments
             .iter()
             .map(|span| Comment::from((span, src)))
             .collect(),
+        warning_suppressions: &module.warning_suppressions,
     };
 
-    Formatter::with_comments(&intermediate)
+    let mut formatted = String::new();
+    Formatter::with_comments(&intermediate, options)
         .module(&module)
-        .pretty_print(80, writer)
+        .pretty_print(options.line_length, &mut formatted)?;
+    Ok(formatted)
+}
+
+/// A way in which the formatter has mangled code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationProblem {
+    /// The formatted code could not be parsed.
+    Unparsable,
+    /// The formatted code parses to a different module.
+    ChangedMeaning,
+    /// Comments were dropped or added.
+    ChangedComments,
+    /// Formatting the formatted code again changes it.
+    NotIdempotent,
+}
+
+/// Check that formatted code has the same syntax tree and comments as the
+/// source it was formatted from, ignoring locations, and that formatting it
+/// again leaves it unchanged.
+///
+/// The error points at the first top level definition that shows the problem
+/// when formatted on its own, or the whole module if none does, so the
+/// smallest snippet can be reported.
+pub fn verify(src: &str, formatted: &str, path: &Path, options: &Options) -> Result<()> {
+    let problem = match verification_problem(src, formatted, options) {
+        Some(problem) => problem,
+        None => return Ok(()),
+    };
+
+    let arena = Arena::new();
+    let definitions = match crate::parse::parse_module(src, &arena) {
+        Ok((_, extra)) => extra
+            .definitions
+            .iter()
+            .filter(|location| {
+                !extra
+                    .target_groups
+                    .iter()
+                    .any(|group| group.contains(location.start))
+            })
+            .chain(&extra.target_groups)
+            .copied()
+            .sorted_by_key(|location| location.start)
+            .collect_vec(),
+        Err(_) => vec![],
+    };
+    let location = definitions
+        .into_iter()
+        .find(|location| {
+            let snippet = src
+                .get(location.start as usize..location.end as usize)
+                .unwrap_or_default();
+            match format_module(snippet, path, options) {
+                Ok(formatted) => verification_problem(snippet, &formatted, options).is_some(),
+                Err(_) => false,
+            }
+        })
+        .unwrap_or_else(|| SrcSpan::new(0, src.len() as u32));
+
+    Err(Error::FormatVerification {
+        path: path.to_path_buf(),
+        src: src.to_string(),
+        location,
+        problem,
+    })
+}
+
+fn verification_problem(
+    src: &str,
+    formatted: &str,
+    options: &Options,
+) -> Option<VerificationProblem> {
+    let arena = Arena::new();
+    let (module, extra) = crate::parse::parse_module(src, &arena).ok()?;
+    let (formatted_module, formatted_extra) = match crate::parse::parse_module(formatted, &arena) {
+        Ok(parsed) => parsed,
+        Err(_) => return Some(VerificationProblem::Unparsable),
+    };
+
+    if normalise(module) != normalise(formatted_module) {
+        return Some(VerificationProblem::ChangedMeaning);
+    }
+
+    let comments = |extra: &crate::parse::extra::ModuleExtra, src| {
+        extra
+            .module_comments
+            .iter()
+            .chain(&extra.doc_comments)
+            .chain(&extra.comments)
+            .map(|span| Comment::from((span, src)).content.trim_end().to_string())
+            .sorted()
+            .collect_vec()
+    };
+    if comments(&extra, src) != comments(&formatted_extra, formatted) {
+        return Some(VerificationProblem::ChangedComments);
+    }
+
+    match format_module(formatted, Path::new(""), options) {
+        Ok(again) if again == formatted => None,
+        _ => Some(VerificationProblem::NotIdempotent),
+    }
+}
+
+// The debug representation of a module with the positions in the source
+// removed, so modules that differ only in layout compare equal. The changes
+// the formatter makes on purpose are made here too: ints are made canonical,
+// floats get a trailing zero, imports come first in their group and may be
+// sorted, and captures with the hole first are removed from pipelines.
+fn normalise(mut module: UntypedModule<'_>) -> String {
+    use regex::Regex;
+    lazy_static! {
+        static ref LOCATION: Regex =
+            Regex::new(r"SrcSpan \{ start: \d+, end: \d+ \}|end_position: \d+")
+                .expect("location regex");
+        static ref FLOAT: Regex =
+            Regex::new(r#"value: "(-?[0-9][0-9_]*)\.""#).expect("float regex");
+        static ref INT: Regex =
+            Regex::new(r#"Int \{ location: _, value: "([^"]*)" \}"#).expect("int regex");
+    }
+    for group in &mut module.statements {
+        let statements = match group {
+            TargetGroup::Any(statements) | TargetGroup::Only(_, statements) => statements,
+        };
+        for statement in statements.iter_mut() {
+            match statement {
+                Statement::Import { unqualified, .. } => {
+                    unqualified.sort_by(|a, b| (&a.name, &a.as_name).cmp(&(&b.name, &b.as_name)));
+                    unqualified.dedup_by(|a, b| a.name == b.name && a.as_name == b.as_name);
+                }
+                Statement::Fn { body, .. } => normalise_expr(body),
+                _ => (),
+            }
+        }
+        statements.sort_by_cached_key(|statement| match statement {
+            Statement::Import { module, .. } => (false, module.join("/")),
+            _ => (true, String::new()),
+        });
+    }
+    let debug = format!("{:?}", module);
+    let debug = LOCATION.replace_all(&debug, "_");
+    let debug = INT.replace_all(&debug, |captures: &regex::Captures<'_>| {
+        let value = captures
+            .get(1)
+            .map(|value| value.as_str())
+            .unwrap_or_default();
+        format!(
+            r#"Int {{ location: _, value: "{}" }}"#,
+            crate::int::canonical_literal(value)
+        )
+    });
+    FLOAT.replace_all(&debug, r#"value: "$1.0""#).into_owned()
+}
+
+fn normalise_expr(expr: &mut UntypedExpr<'_>) {
+    match expr {
+        UntypedExpr::Int { .. }
+        | UntypedExpr::Float { .. }
+        | UntypedExpr::String { .. }
+        | UntypedExpr::Var { .. }
+        | UntypedExpr::Todo { .. }
+        | UntypedExpr::Cfg { .. } => (),
+
+        UntypedExpr::Sequence {
+            expressions: elements,
+            ..
+        }
+        | UntypedExpr::Tuple {
+            elems: elements, ..
+        } => {
+            elements.iter_mut().for_each(normalise_expr);
+        }
+
+        UntypedExpr::List { elements, tail, .. } => {
+            elements.iter_mut().for_each(normalise_expr);
+            if let Some(tail) = tail {
+                normalise_expr(tail);
+            }
+        }
+
+        UntypedExpr::Call { fun, arguments, .. } => {
+            normalise_expr(fun);
+            for argument in arguments {
+                normalise_expr(&mut argument.value);
+            }
+        }
+
+        UntypedExpr::BinOp { left, right, .. } => {
+            normalise_expr(left);
+            normalise_expr(right);
+        }
+
+        UntypedExpr::PipeLine { expressions } => {
+            for expr in expressions.iter_mut().skip(1) {
+                if let UntypedExpr::Fn {
+                    is_capture: true,
+                    arguments: holes,
+                    body,
+                    ..
+                } = expr
+                {
+                    if holes.len() != 1 {
+                        continue;
+                    }
+                    if let UntypedExpr::Call { fun, arguments, .. } = body.as_mut() {
+                        match arguments.first() {
+                            Some(CallArg {
+                                value: UntypedExpr::Var { name, .. },
+                                ..
+                            }) if name == CAPTURE_VARIABLE => {
+                                let _ = arguments.remove(0);
+                                // The expression is moved out of the capture,
+                                // leaving an empty tuple in its place
+                                let empty = UntypedExpr::Tuple {
+                                    location: SrcSpan::default(),
+                                    elems: vec![],
+                                };
+                                *expr = if arguments.is_empty() {
+                                    std::mem::replace(fun.as_mut(), empty)
+                                } else {
+                                    std::mem::replace(body.as_mut(), empty)
+                                };
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+            }
+            expressions.iter_mut().for_each(normalise_expr);
+        }
+
+        UntypedExpr::Fn { body: value, .. }
+        | UntypedExpr::Assignment { value, .. }
+        | UntypedExpr::FieldAccess {
+            container: value, ..
+        }
+        | UntypedExpr::TupleIndex { tuple: value, .. }
+        | UntypedExpr::Negate { value, .. }
+        | UntypedExpr::Echo { value, .. }
+        | UntypedExpr::Use(Use { call: value, .. }) => normalise_expr(value),
+
+        UntypedExpr::Try { value, then, .. } => {
+            normalise_expr(value);
+            normalise_expr(then);
+        }
+
+        UntypedExpr::Case {
+            subjects, clauses, ..
+        } => {
+            subjects.iter_mut().for_each(normalise_expr);
+            for clause in clauses {
+                normalise_expr(&mut clause.then);
+            }
+        }
+
+        UntypedExpr::BitString { segments, .. } => {
+            for segment in segments {
+                normalise_expr(&mut segment.value);
+            }
+        }
+
+        UntypedExpr::RecordUpdate {
+            constructor,
+            spread,
+            arguments,
+            ..
+        } => {
+            normalise_expr(constructor);
+            normalise_expr(&mut spread.base);
+            for argument in arguments {
+                normalise_expr(&mut argument.value);
+            }
+        }
+    }
+}
+
+/// Format only the definitions of a module that overlap the given range,
+/// leaving the rest of the source exactly as it is. Definitions inside an
+/// `if` block are formatted along with the whole block.
+///
+/// Returns the edit that replaces the smallest run of definitions enclosing
+/// the range with their formatted code, or `None` if the range does not
+/// touch any definition.
+pub fn pretty_range(
+    src: &str,
+    path: &Path,
+    range: SrcSpan,
+    options: &Options,
+) -> Result<Option<TextEdit>> {
+    let arena = Arena::new();
+    let (_, extra) = crate::parse::parse_module(src, &arena).map_err(|error| Error::Parse {
+        path: path.to_path_buf(),
+        src: src.to_string(),
+        errors: Vec1::new(error),
+    })?;
+
+    let in_group = |location: &SrcSpan| {
+        extra
+            .target_groups
+            .iter()
+            .any(|group| group.contains(location.start))
+    };
+    let overlaps = |location: &&SrcSpan| {
+        (location.start < range.end && range.start < location.end) || location.contains(range.start)
+    };
+    let selected = extra
+        .definitions
+        .iter()
+        .filter(|location| !in_group(location))
+        .chain(&extra.target_groups)
+        .filter(overlaps)
+        .collect_vec();
+    let location = match (
+        selected.iter().map(|location| location.start).min(),
+        selected.iter().map(|location| location.end).max(),
+    ) {
+        (Some(start), Some(end)) => SrcSpan { start, end },
+        _ => return Ok(None),
+    };
+
+    let selected_src = src
+        .get(location.start as usize..location.end as usize)
+        .unwrap_or_default();
+    let mut new_text = String::new();
+    pretty(&mut new_text, selected_src, path, options)?;
+    let new_text = new_text.trim_end_matches('\n').to_string();
+    Ok(Some(TextEdit { location, new_text }))
+}
+
+/// The edits to make to a module as it is being written, after the character
+/// was typed ending at the byte offset.
+///
+/// After a `}` the definitions it is within are formatted as with
+/// `pretty_range`, if the module can be parsed. Otherwise, and after a
+/// newline, the line typed on is indented to the depth of the brackets it is
+/// within.
+pub fn on_type(
+    src: &str,
+    path: &Path,
+    position: u32,
+    typed: char,
+    options: &Options,
+) -> Vec<TextEdit> {
+    if typed == '}' {
+        let brace = position.saturating_sub(1);
+        let range = SrcSpan {
+            start: brace,
+            end: brace,
+        };
+        if let Ok(edit) = pretty_range(src, path, range, options) {
+            return edit
+                .into_iter()
+                .filter(|edit| {
+                    let location = edit.location.start as usize..edit.location.end as usize;
+                    src.get(location) != Some(edit.new_text.as_str())
+                })
+                .collect();
+        }
+    }
+    if typed == '}' || typed == '\n' {
+        reindent(src, position).into_iter().collect()
+    } else {
+        vec![]
+    }
+}
+
+/// The edit that indents the line containing the byte offset to the depth of
+/// the brackets left open before it, or `None` if it already is or the line
+/// is within a string.
+fn reindent(src: &str, position: u32) -> Option<TextEdit> {
+    let before = src.get(..position as usize)?;
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+
+    let mut depth: isize = 0;
+    for token in crate::parse::lexer::make_tokenizer(before.get(..line_start)?) {
+        match token.ok()?.1 {
+            Token::LeftParen | Token::LeftSquare | Token::LeftBrace => depth += 1,
+            Token::RightParen | Token::RightSquare | Token::RightBrace => depth -= 1,
+            _ => (),
+        }
+    }
+
+    let line = src.get(line_start..)?;
+    let rest = line.trim_start_matches(|c| c == ' ' || c == '\t');
+    if rest.starts_with(|c| matches!(c, ')' | ']' | '}')) {
+        depth -= 1;
+    }
+    let indent = " ".repeat((depth.max(0) * INDENT) as usize);
+    let whitespace = line.len() - rest.len();
+    if line.get(..whitespace)? == indent {
+        return None;
+    }
+    Some(TextEdit {
+        location: SrcSpan {
+            start: line_start as u32,
+            end: (line_start + whitespace) as u32,
+        },
+        new_text: indent,
+    })
 }
 
 struct Intermediate<'a> {
@@ -51,6 +498,15 @@ struct Intermediate<'a> {
     doc_comments: Vec<Comment<'a>>,
     module_comments: Vec<Comment<'a>>,
     empty_lines: &'a [u32],
+    definitions: &'a [SrcSpan],
+    warning_suppressions: &'a [WarningSuppression],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ImportGroup {
+    Std,
+    Dependency,
+    Package,
 }
 
 /// Hayleigh's bane
@@ -60,6 +516,10 @@ pub struct Formatter<'a> {
     doc_comments: &'a [Comment<'a>],
     module_comments: &'a [Comment<'a>],
     empty_lines: &'a [u32],
+    definitions: &'a [SrcSpan],
+    warning_suppressions: &'a [WarningSuppression],
+    sort_imports: bool,
+    package: Option<&'a str>,
 }
 
 impl<'comments> Formatter<'comments> {
@@ -67,12 +527,19 @@ impl<'comments> Formatter<'comments> {
         Default::default()
     }
 
-    fn with_comments(extra: &'comments Intermediate<'comments>) -> Self {
+    fn with_comments(
+        extra: &'comments Intermediate<'comments>,
+        options: &'comments Options,
+    ) -> Self {
         Self {
             comments: &extra.comments,
             doc_comments: &extra.doc_comments,
             module_comments: &extra.module_comments,
             empty_lines: extra.empty_lines,
+            definitions: extra.definitions,
+            warning_suppressions: extra.warning_suppressions,
+            sort_imports: options.sort_imports,
+            package: options.package.as_deref(),
         }
     }
 
@@ -86,6 +553,25 @@ impl<'comments> Formatter<'comments> {
         popped
     }
 
+    // Pop the comment that follows code ending at a byte-index on the same
+    // line, as long as it comes before the limit. It is printed at the end of
+    // the line of the node it follows rather than moved before the next one.
+    fn trailing_comment<'a>(&mut self, end: u32, limit: u32) -> Document<'a> {
+        let comment = match self.comments.first() {
+            Some(comment) => comment,
+            None => return nil(),
+        };
+        match comment.trailing_line {
+            Some(line) if line <= end && end <= comment.start && comment.start < limit => (),
+            _ => return nil(),
+        }
+        self.comments = self.comments.get(1..).unwrap_or_default();
+        let comment = " //"
+            .to_doc()
+            .append(Document::String(comment.content.to_string()));
+        Document::LineSuffix(Box::new(comment))
+    }
+
     // Pop doc comments that occur before a byte-index in the source, consuming
     // and dropping any empty lines contained within.
     fn pop_doc_comments(&mut self, limit: u32) -> impl Iterator<Item = Option<&'comments str>> {
@@ -114,32 +600,74 @@ impl<'comments> Formatter<'comments> {
         end != 0
     }
 
-    fn target_group<'a>(&mut self, target_group: &'a TargetGroup) -> Document<'a> {
+    // The end of the last token of a definition, which may be after the end
+    // of its location
+    fn statement_end(&self, statement: &UntypedStatement<'_>) -> u32 {
+        let location = statement.location();
+        self.definitions
+            .iter()
+            .find(|definition| definition.contains(location.start))
+            .map(|definition| definition.end)
+            .unwrap_or(location.end)
+    }
+
+    // The group an import of the given module is sorted into
+    fn import_group(&self, module: &[Name]) -> ImportGroup {
+        match module.first().map(Name::as_str) {
+            Some("gleam") => ImportGroup::Std,
+            Some(name) if Some(name) == self.package => ImportGroup::Package,
+            _ => ImportGroup::Dependency,
+        }
+    }
+
+    fn target_group<'a>(&mut self, target_group: &'a TargetGroup<'_>) -> Document<'a> {
         let mut has_imports = false;
         let mut has_declarations = false;
         let mut imports = Vec::new();
         let mut declarations = Vec::with_capacity(target_group.len());
 
-        for statement in target_group.statements_ref() {
+        let statements = target_group.statements_ref();
+        for (i, statement) in statements.iter().enumerate() {
             let start = statement.location().start;
+            let next = statements
+                .get(i + 1)
+                .map(|next| next.location().start)
+                .unwrap_or(u32::MAX);
             match statement {
-                Statement::Import { .. } => {
+                Statement::Import {
+                    module, location, ..
+                } => {
                     has_imports = true;
                     let comments = self.pop_comments(start);
-                    let statement = self.statement(statement);
-                    imports.push(commented(statement, comments))
+                    let statement = self
+                        .statement(statement)
+                        .append(self.trailing_comment(location.end, next));
+                    imports.push((module, commented(statement, comments)))
                 }
 
                 _other => {
                     has_declarations = true;
                     let comments = self.pop_comments(start);
-                    let declaration = self.documented_statement(statement);
+                    let declaration = self
+                        .documented_statement(statement)
+                        .append(self.trailing_comment(self.statement_end(statement), next));
                     declarations.push(commented(declaration, comments))
                 }
             }
         }
 
-        let imports = join(imports.into_iter(), line());
+        let imports = if self.sort_imports {
+            imports.sort_by_cached_key(|(module, _)| (self.import_group(module), module.join("/")));
+            let groups = imports
+                .into_iter()
+                .group_by(|(module, _)| self.import_group(module))
+                .into_iter()
+                .map(|(_, group)| join(group.map(|(_, import)| import), line()))
+                .collect_vec();
+            join(groups, lines(2))
+        } else {
+            join(imports.into_iter().map(|(_, import)| import), line())
+        };
         let declarations = join(declarations.into_iter(), lines(2));
 
         let sep = if has_imports && has_declarations {
@@ -161,7 +689,7 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
-    fn module<'a>(&mut self, module: &'a UntypedModule) -> Document<'a> {
+    fn module<'a>(&mut self, module: &'a UntypedModule<'_>) -> Document<'a> {
         let groups = join(
             module.statements.iter().map(|t| self.target_group(t)),
             lines(2),
@@ -195,14 +723,72 @@ impl<'comments> Formatter<'comments> {
             nil()
         };
 
-        let non_empty = vec![module_comments, groups, doc_comments, comments]
-            .into_iter()
-            .filter(|doc| !doc.is_empty());
+        let module_allows =
+            self.warning_suppressions
+                .iter()
+                .filter_map(|suppression| match suppression.scope {
+                    SuppressionScope::Module => {
+                        Some(allow_attribute("@module_allow(", suppression))
+                    }
+                    SuppressionScope::Definition(_) => None,
+                });
+        let module_allows = join(module_allows, line());
+        let module_allows = if module_allows.is_empty() {
+            nil()
+        } else {
+            module_allows.append(line())
+        };
+
+        let non_empty = vec![
+            module_comments,
+            module_allows,
+            groups,
+            doc_comments,
+            comments,
+        ]
+        .into_iter()
+        .filter(|doc| !doc.is_empty());
 
         join(non_empty, line()).append(line())
     }
 
-    fn statement<'a>(&mut self, statement: &'a UntypedStatement) -> Document<'a> {
+    fn statement<'a>(&mut self, statement: &'a UntypedStatement<'_>) -> Document<'a> {
+        let deprecation = match statement {
+            Statement::Fn { deprecation, .. }
+            | Statement::TypeAlias { deprecation, .. }
+            | Statement::CustomType { deprecation, .. }
+            | Statement::ExternalFn { deprecation, .. }
+            | Statement::ExternalType { deprecation, .. }
+            | Statement::ModuleConstant { deprecation, .. } => self.deprecation(deprecation),
+            Statement::Import { .. } => nil(),
+        };
+        let start = statement.location().start;
+        let allow = self
+            .warning_suppressions
+            .iter()
+            .find(|suppression| match suppression.scope {
+                SuppressionScope::Module => false,
+                SuppressionScope::Definition(span) => span.contains(start),
+            });
+        let allow = match allow {
+            Some(suppression) => allow_attribute("@allow(", suppression).append(line()),
+            None => nil(),
+        };
+        deprecation.append(allow).append(self.definition(statement))
+    }
+
+    fn deprecation<'a>(&self, deprecation: &'a Deprecation) -> Document<'a> {
+        match deprecation {
+            Deprecation::NotDeprecated => nil(),
+            Deprecation::Deprecated { message } => "@deprecated("
+                .to_doc()
+                .append(self.string(message))
+                .append(")")
+                .append(line()),
+        }
+    }
+
+    fn definition<'a>(&mut self, statement: &'a UntypedStatement<'_>) -> Document<'a> {
         match statement {
             Statement::Fn {
                 name,
@@ -211,8 +797,30 @@ impl<'comments> Formatter<'comments> {
                 public,
                 return_annotation,
                 end_position,
+                externals,
                 ..
-            } => self.statement_fn(public, name, args, return_annotation, body, *end_position),
+            } => {
+                let attributes = externals.iter().map(|external| {
+                    docvec![
+                        "@external(",
+                        Document::String(external.target.to_string()),
+                        ", ",
+                        self.string(&external.module),
+                        ", ",
+                        self.string(&external.function),
+                        ")",
+                        line(),
+                    ]
+                });
+                concat(attributes.collect_vec()).append(self.statement_fn(
+                    public,
+                    name,
+                    args,
+                    return_annotation,
+                    body,
+                    *end_position,
+                ))
+            }
 
             Statement::TypeAlias {
                 alias,
@@ -229,8 +837,32 @@ impl<'comments> Formatter<'comments> {
                 constructors,
                 location,
                 opaque,
+                erlang_map,
+                derives,
                 ..
-            } => self.custom_type(*public, *opaque, name, parameters, constructors, location),
+            } => {
+                let mut attributes = if *erlang_map {
+                    "@erlang_map".to_doc().append(line())
+                } else {
+                    nil()
+                };
+                if !derives.is_empty() {
+                    let names = derives.iter().map(|derive| derive.name.as_str()).join(", ");
+                    attributes = attributes
+                        .append("@derive(")
+                        .append(Document::String(names))
+                        .append(")")
+                        .append(line());
+                }
+                attributes.append(self.custom_type(
+                    *public,
+                    *opaque,
+                    name,
+                    parameters,
+                    constructors,
+                    location,
+                ))
+            }
 
             Statement::ExternalFn {
                 public,
@@ -268,11 +900,15 @@ impl<'comments> Formatter<'comments> {
                 .append(if unqualified.is_empty() {
                     nil()
                 } else {
+                    let mut unqualified = unqualified
+                        .iter()
+                        .sorted_by(|a, b| a.name.cmp(&b.name))
+                        .collect_vec();
+                    if self.sort_imports {
+                        unqualified.dedup_by(|a, b| a.name == b.name && a.as_name == b.as_name);
+                    }
                     let unqualified = Itertools::intersperse(
-                        unqualified
-                            .iter()
-                            .sorted_by(|a, b| a.name.cmp(&b.name))
-                            .map(|e| e.to_doc()),
+                        unqualified.into_iter().map(|e| e.to_doc()),
                         flex_break(",", ", "),
                     );
                     let unqualified = break_("", "")
@@ -307,7 +943,9 @@ impl<'comments> Formatter<'comments> {
 
     fn const_expr<'a, A, B>(&mut self, value: &'a Constant<A, B>) -> Document<'a> {
         match value {
-            Constant::Int { value, .. } | Constant::Float { value, .. } => value.to_doc(),
+            Constant::Int { value, .. } => self.int(value),
+
+            Constant::Float { value, .. } => value.to_doc(),
 
             Constant::String { value, .. } => self.string(value),
 
@@ -378,6 +1016,19 @@ impl<'comments> Formatter<'comments> {
                 module: Some(module),
                 ..
             } => docvec![module, ".", name],
+
+            Constant::BinOp {
+                name, left, right, ..
+            } => {
+                let precedence = name.precedence();
+                let left_precedence = left.binop_precedence();
+                let right_precedence = right.binop_precedence();
+                let left = self.const_expr(left);
+                let right = self.const_expr(right);
+                self.operator_side(left, precedence, left_precedence)
+                    .append(name)
+                    .append(self.operator_side(right, precedence, right_precedence - 1))
+            }
         }
     }
 
@@ -398,7 +1049,7 @@ impl<'comments> Formatter<'comments> {
             .append(self.const_expr(value))
     }
 
-    fn documented_statement<'a>(&mut self, s: &'a UntypedStatement) -> Document<'a> {
+    fn documented_statement<'a>(&mut self, s: &'a UntypedStatement<'_>) -> Document<'a> {
         let comments = self.doc_comments(s.location().start);
         comments.append(self.statement(s).group()).group()
     }
@@ -421,7 +1072,7 @@ impl<'comments> Formatter<'comments> {
 
     fn type_ast_constructor<'a>(
         &mut self,
-        module: &'a Option<String>,
+        module: &'a Option<Name>,
         name: &'a str,
         args: &'a [TypeAst],
     ) -> Document<'a> {
@@ -474,7 +1125,7 @@ impl<'comments> Formatter<'comments> {
         &mut self,
         public: bool,
         name: &'a str,
-        args: &'a [String],
+        args: &'a [Name],
         typ: &'a TypeAst,
     ) -> Document<'a> {
         let head = pub_(public).append("type ").append(name);
@@ -496,7 +1147,8 @@ impl<'comments> Formatter<'comments> {
             Some(a) => arg.names.to_doc().append(": ").append(self.type_ast(a)),
         }
         .group();
-        commented(doc, comments)
+        let doc_comments = self.doc_comments(arg.location.start);
+        commented(doc_comments.append(doc).group(), comments)
     }
 
     fn statement_fn<'a>(
@@ -505,7 +1157,7 @@ impl<'comments> Formatter<'comments> {
         name: &'a str,
         args: &'a [UntypedArg],
         return_annotation: &'a Option<TypeAst>,
-        body: &'a UntypedExpr,
+        body: &'a UntypedExpr<'_>,
         end_location: u32,
     ) -> Document<'a> {
         // Fn name and args
@@ -522,7 +1174,9 @@ impl<'comments> Formatter<'comments> {
         .group();
 
         // Format body
-        let body = self.expr(body);
+        let body = self
+            .expr(body)
+            .append(self.trailing_comment(body.location().end, end_location));
 
         // Add any trailing comments
         let body = match printed_comments(self.pop_comments(end_location), false) {
@@ -557,7 +1211,7 @@ impl<'comments> Formatter<'comments> {
         &mut self,
         args: &'a [UntypedArg],
         return_annotation: Option<&'a TypeAst>,
-        body: &'a UntypedExpr,
+        body: &'a UntypedExpr<'_>,
     ) -> Document<'a> {
         let args = wrap_args(args.iter().map(|e| self.fn_arg(e))).group();
         let body = match body {
@@ -583,7 +1237,7 @@ impl<'comments> Formatter<'comments> {
             .group()
     }
 
-    fn sequence<'a>(&mut self, expressions: &'a [UntypedExpr]) -> Document<'a> {
+    fn sequence<'a>(&mut self, expressions: &'a [UntypedExpr<'_>]) -> Document<'a> {
         let count = expressions.len();
         let mut documents = Vec::with_capacity(count * 2);
         for (i, expression) in expressions.iter().enumerate() {
@@ -593,7 +1247,12 @@ impl<'comments> Formatter<'comments> {
             } else if i != 0 {
                 documents.push(lines(1));
             }
+            let next = expressions
+                .get(i + 1)
+                .map(UntypedExpr::start_byte_index)
+                .unwrap_or(u32::MAX);
             documents.push(self.expr(expression).group());
+            documents.push(self.trailing_comment(expression.location().end, next));
         }
         documents.to_doc().force_break()
     }
@@ -601,10 +1260,11 @@ impl<'comments> Formatter<'comments> {
     fn assignment<'a>(
         &mut self,
         pattern: &'a UntypedPattern,
-        value: &'a UntypedExpr,
-        then: Option<&'a UntypedExpr>,
+        value: &'a UntypedExpr<'_>,
+        then: Option<&'a UntypedExpr<'_>>,
         kind: Option<AssignmentKind>,
         annotation: &'a Option<TypeAst>,
+        message: Option<&'a String>,
     ) -> Document<'a> {
         let _ = self.pop_empty_lines(pattern.location().end);
 
@@ -627,7 +1287,8 @@ impl<'comments> Formatter<'comments> {
         }
         .append(pattern.append(annotation).group())
         .append(" =")
-        .append(self.assigned_value(value));
+        .append(self.assigned_value(value))
+        .append(message.map(|message| docvec![" as ", self.string(message)]));
 
         if let Some(then) = then {
             doc.append(if self.pop_empty_lines(then.start_byte_index()) {
@@ -641,7 +1302,7 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
-    fn expr<'a>(&mut self, expr: &'a UntypedExpr) -> Document<'a> {
+    fn expr<'a>(&mut self, expr: &'a UntypedExpr<'_>) -> Document<'a> {
         let comments = self.pop_comments(expr.start_byte_index());
 
         let document = match expr {
@@ -651,7 +1312,7 @@ impl<'comments> Formatter<'comments> {
 
             UntypedExpr::PipeLine { expressions, .. } => self.pipeline(expressions),
 
-            UntypedExpr::Int { value, .. } => value.to_doc(),
+            UntypedExpr::Int { value, .. } => self.int(value),
 
             UntypedExpr::Float { value, .. } => self.float(value),
 
@@ -659,7 +1320,7 @@ impl<'comments> Formatter<'comments> {
 
             UntypedExpr::Sequence { expressions, .. } => self.sequence(expressions),
 
-            UntypedExpr::Var { name, .. } if name == CAPTURE_VARIABLE => "_".to_doc(),
+            UntypedExpr::Var { name, .. } if is_capture_variable(name) => "_".to_doc(),
 
             UntypedExpr::Var { name, .. } => name.to_doc(),
 
@@ -667,6 +1328,10 @@ impl<'comments> Formatter<'comments> {
 
             UntypedExpr::Negate { value, .. } => self.negate(value),
 
+            UntypedExpr::Echo { value, .. } => self.echo(value),
+
+            UntypedExpr::Cfg { flag, .. } => docvec!["@cfg(", flag.to_doc(), ")"],
+
             UntypedExpr::Fn {
                 is_capture: true,
                 body,
@@ -680,13 +1345,17 @@ impl<'comments> Formatter<'comments> {
                 ..
             } => self.expr_fn(args, return_annotation.as_ref(), body),
 
-            UntypedExpr::List { elements, tail, .. } => self.list(elements, tail.as_deref()),
+            UntypedExpr::List {
+                elements,
+                tail,
+                location,
+            } => self.list(elements, tail.as_deref(), location.end),
 
             UntypedExpr::Call {
                 fun,
                 arguments: args,
-                ..
-            } => self.call(fun, args),
+                location,
+            } => self.call(fun, args, location.end),
 
             UntypedExpr::BinOp {
                 name, left, right, ..
@@ -697,8 +1366,16 @@ impl<'comments> Formatter<'comments> {
                 pattern,
                 annotation,
                 kind,
+                message,
                 ..
-            } => self.assignment(pattern, value, None, Some(*kind), annotation),
+            } => self.assignment(
+                pattern,
+                value,
+                None,
+                Some(*kind),
+                annotation,
+                message.as_ref(),
+            ),
 
             UntypedExpr::Use(use_) => self.use_(use_),
 
@@ -708,11 +1385,13 @@ impl<'comments> Formatter<'comments> {
                 annotation,
                 then,
                 ..
-            } => self.assignment(pattern, value, Some(then), None, annotation),
+            } => self.assignment(pattern, value, Some(then), None, annotation, None),
 
             UntypedExpr::Case {
-                subjects, clauses, ..
-            } => self.case(subjects, clauses),
+                subjects,
+                clauses,
+                location,
+            } => self.case(subjects, clauses, location.end),
 
             UntypedExpr::FieldAccess {
                 label, container, ..
@@ -724,10 +1403,10 @@ impl<'comments> Formatter<'comments> {
             .append(".")
             .append(label.as_str()),
 
-            UntypedExpr::Tuple { elems, .. } => "#"
-                .to_doc()
-                .append(wrap_args(elems.iter().map(|e| self.wrap_expr(e))))
-                .group(),
+            UntypedExpr::Tuple { elems, location } => {
+                let elems = self.elements(elems, location.end, |this, e| this.wrap_expr(e));
+                "#".to_doc().append(wrap_args(elems)).group()
+            }
 
             UntypedExpr::BitString { segments, .. } => bit_string(
                 segments
@@ -754,6 +1433,10 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
+    fn int<'a>(&self, value: &str) -> Document<'a> {
+        Document::String(crate::int::canonical_literal(value))
+    }
+
     fn float<'a>(&self, value: &'a String) -> Document<'a> {
         let doc = value.to_doc();
         if value.ends_with('.') {
@@ -768,7 +1451,7 @@ impl<'comments> Formatter<'comments> {
         &mut self,
         name: &'a str,
         args: &'a [CallArg<UntypedPattern>],
-        module: &'a Option<String>,
+        module: &'a Option<Name>,
         with_spread: bool,
     ) -> Document<'a> {
         fn is_breakable(expr: &UntypedPattern) -> bool {
@@ -809,7 +1492,12 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
-    fn call<'a>(&mut self, fun: &'a UntypedExpr, args: &'a [CallArg<UntypedExpr>]) -> Document<'a> {
+    fn call<'a>(
+        &mut self,
+        fun: &'a UntypedExpr<'_>,
+        args: &'a [CallArg<UntypedExpr<'_>>],
+        end: u32,
+    ) -> Document<'a> {
         match args {
             [arg] if is_breakable_expr(&arg.value) => self
                 .expr(fun)
@@ -818,17 +1506,40 @@ impl<'comments> Formatter<'comments> {
                 .append(")")
                 .group(),
 
-            _ => self
-                .expr(fun)
-                .append(wrap_args(args.iter().map(|a| self.call_arg(a))))
-                .group(),
+            _ => {
+                let fun = self.expr(fun);
+                let args = self.elements(args, end, |this, arg| this.call_arg(arg));
+                fun.append(wrap_args(args)).group()
+            }
+        }
+    }
+
+    // Format the elements of a list, tuple, or arguments, each followed by
+    // any comment on the same line. The end is that of the closing bracket.
+    fn elements<'a, T: HasLocation>(
+        &mut self,
+        elements: &'a [T],
+        end: u32,
+        mut to_doc: impl FnMut(&mut Self, &'a T) -> Document<'a>,
+    ) -> Vec<Document<'a>> {
+        let mut documents = Vec::with_capacity(elements.len());
+        for (i, element) in elements.iter().enumerate() {
+            let next = elements
+                .get(i + 1)
+                .map(|next| next.location().start)
+                .unwrap_or(end);
+            let document = to_doc(self, element);
+            let comment = self.trailing_comment(element.location().end, next);
+            documents.push(document.append(comment));
         }
+        documents
     }
 
     pub fn case<'a>(
         &mut self,
-        subjects: &'a [UntypedExpr],
-        clauses: &'a [UntypedClause],
+        subjects: &'a [UntypedExpr<'_>],
+        clauses: &'a [UntypedClause<'_>],
+        end: u32,
     ) -> Document<'a> {
         let subjects_doc = break_("case", "case ")
             .append(join(
@@ -840,12 +1551,16 @@ impl<'comments> Formatter<'comments> {
             .append("{")
             .group();
 
-        let clauses_doc = concat(
-            clauses
-                .iter()
-                .enumerate()
-                .map(|(i, c)| self.clause(c, i as u32)),
-        );
+        let mut clause_docs = Vec::with_capacity(clauses.len());
+        for (i, clause) in clauses.iter().enumerate() {
+            let next = clauses
+                .get(i + 1)
+                .map(|next| next.location.start)
+                .unwrap_or(end);
+            clause_docs.push(self.clause(clause, i as u32));
+            clause_docs.push(self.trailing_comment(clause.then.location().end, next));
+        }
+        let clauses_doc = concat(clause_docs);
 
         subjects_doc
             .append(line().append(clauses_doc).nest(INDENT))
@@ -856,9 +1571,9 @@ impl<'comments> Formatter<'comments> {
 
     pub fn record_update<'a>(
         &mut self,
-        constructor: &'a UntypedExpr,
-        spread: &'a RecordUpdateSpread,
-        args: &'a [UntypedRecordUpdateArg],
+        constructor: &'a UntypedExpr<'_>,
+        spread: &'a RecordUpdateSpread<'_>,
+        args: &'a [UntypedRecordUpdateArg<'_>],
     ) -> Document<'a> {
         use std::iter::once;
         let constructor_doc = self.expr(constructor);
@@ -871,8 +1586,8 @@ impl<'comments> Formatter<'comments> {
     pub fn bin_op<'a>(
         &mut self,
         name: &'a BinOp,
-        left: &'a UntypedExpr,
-        right: &'a UntypedExpr,
+        left: &'a UntypedExpr<'_>,
+        right: &'a UntypedExpr<'_>,
     ) -> Document<'a> {
         let precedence = name.precedence();
         let left_precedence = left.binop_precedence();
@@ -897,21 +1612,28 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
-    fn pipeline<'a>(&mut self, expressions: &'a Vec1<UntypedExpr>) -> Document<'a> {
+    fn pipeline<'a>(&mut self, expressions: &'a Vec1<UntypedExpr<'_>>) -> Document<'a> {
         let mut docs = Vec::with_capacity(expressions.len() * 3);
         let first = expressions.first();
         let first_precedence = first.binop_precedence();
+        let first_end = first.location().end;
         let first = self.wrap_expr(first);
         docs.push(self.operator_side(first, 5, first_precedence));
+        let mut previous_end = first_end;
 
         for expr in expressions.iter().skip(1) {
+            docs.push(self.trailing_comment(previous_end, expr.start_byte_index()));
+            previous_end = expr.location().end;
             let comments = self.pop_comments(expr.location().start);
             let doc = match expr {
                 UntypedExpr::Fn {
                     is_capture: true,
+                    arguments: holes,
                     body,
                     ..
-                } => self.pipe_capture_right_hand_side(body),
+                } if holes.len() == 1 && matches!(body.as_ref(), UntypedExpr::Call { .. }) => {
+                    self.pipe_capture_right_hand_side(body)
+                }
 
                 _ => self.wrap_expr(expr),
             };
@@ -923,7 +1645,7 @@ impl<'comments> Formatter<'comments> {
         docs.to_doc().force_break()
     }
 
-    fn pipe_capture_right_hand_side<'a>(&mut self, fun: &'a UntypedExpr) -> Document<'a> {
+    fn pipe_capture_right_hand_side<'a>(&mut self, fun: &'a UntypedExpr<'_>) -> Document<'a> {
         let (fun, args) = match fun {
             UntypedExpr::Call {
                 fun,
@@ -955,7 +1677,7 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
-    fn fn_capture<'a>(&mut self, call: &'a UntypedExpr) -> Document<'a> {
+    fn fn_capture<'a>(&mut self, call: &'a UntypedExpr<'_>) -> Document<'a> {
         match call {
             UntypedExpr::Call {
                 fun,
@@ -975,8 +1697,8 @@ impl<'comments> Formatter<'comments> {
                     .append(wrap_args(args.iter().map(|a| self.call_arg(a))).group()),
             },
 
-            // The body of a capture being not a fn shouldn't be possible...
-            _ => panic!("Function capture body found not to be a call in the formatter",),
+            // An operator section such as `_ + 1`
+            _ => self.expr(call),
         }
     }
 
@@ -1015,6 +1737,7 @@ impl<'comments> Formatter<'comments> {
                 .group()
         };
 
+        let doc = self.deprecation(&constructor.deprecation).append(doc);
         commented(doc_comments.append(doc).group(), comments)
     }
 
@@ -1023,7 +1746,7 @@ impl<'comments> Formatter<'comments> {
         public: bool,
         opaque: bool,
         name: &'a str,
-        args: &'a [String],
+        args: &'a [Name],
         constructors: &'a [RecordConstructor<A>],
         location: &'a SrcSpan,
     ) -> Document<'a> {
@@ -1057,7 +1780,7 @@ impl<'comments> Formatter<'comments> {
         &mut self,
         public: bool,
         name: &'a str,
-        args: &'a [String],
+        args: &'a [Name],
         location: &'a SrcSpan,
     ) -> Document<'a> {
         let _ = self.pop_empty_lines(location.start);
@@ -1106,14 +1829,15 @@ impl<'comments> Formatter<'comments> {
     fn external_fn_arg<'a, A>(&mut self, arg: &'a ExternalFnArg<A>) -> Document<'a> {
         let comments = self.pop_comments(arg.location.start);
         let doc = label(&arg.label).append(self.type_ast(&arg.annotation));
-        commented(doc.group(), comments)
+        let doc_comments = self.doc_comments(arg.location.start);
+        commented(doc_comments.append(doc.group()).group(), comments)
     }
 
     fn external_fn_args<'a, A>(&mut self, args: &'a [ExternalFnArg<A>]) -> Document<'a> {
         wrap_args(args.iter().map(|e| self.external_fn_arg(e)))
     }
 
-    fn wrap_expr<'a>(&mut self, expr: &'a UntypedExpr) -> Document<'a> {
+    fn wrap_expr<'a>(&mut self, expr: &'a UntypedExpr<'_>) -> Document<'a> {
         match expr {
             UntypedExpr::Use(_)
             | UntypedExpr::Sequence { .. }
@@ -1129,7 +1853,7 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
-    fn call_arg<'a>(&mut self, arg: &'a CallArg<UntypedExpr>) -> Document<'a> {
+    fn call_arg<'a>(&mut self, arg: &'a CallArg<UntypedExpr<'_>>) -> Document<'a> {
         match &arg.label {
             Some(s) => commented(
                 s.to_doc().append(": "),
@@ -1140,14 +1864,15 @@ impl<'comments> Formatter<'comments> {
         .append(self.wrap_expr(&arg.value))
     }
 
-    fn record_update_arg<'a>(&mut self, arg: &'a UntypedRecordUpdateArg) -> Document<'a> {
+    fn record_update_arg<'a>(&mut self, arg: &'a UntypedRecordUpdateArg<'_>) -> Document<'a> {
         arg.label
             .to_doc()
+            .append(concat(arg.path.iter().map(|label| docvec![".", label])))
             .append(": ")
             .append(self.wrap_expr(&arg.value))
     }
 
-    fn tuple_index<'a>(&mut self, tuple: &'a UntypedExpr, index: u64) -> Document<'a> {
+    fn tuple_index<'a>(&mut self, tuple: &'a UntypedExpr<'_>, index: u64) -> Document<'a> {
         match tuple {
             UntypedExpr::TupleIndex { .. } => self.expr(tuple).surround("{", "}"),
             _ => self.expr(tuple),
@@ -1156,7 +1881,7 @@ impl<'comments> Formatter<'comments> {
         .append(index)
     }
 
-    fn case_clause_value<'a>(&mut self, expr: &'a UntypedExpr) -> Document<'a> {
+    fn case_clause_value<'a>(&mut self, expr: &'a UntypedExpr<'_>) -> Document<'a> {
         match expr {
             UntypedExpr::Try { .. }
             | UntypedExpr::Sequence { .. }
@@ -1178,14 +1903,14 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
-    fn assigned_value<'a>(&mut self, expr: &'a UntypedExpr) -> Document<'a> {
+    fn assigned_value<'a>(&mut self, expr: &'a UntypedExpr<'_>) -> Document<'a> {
         match expr {
             UntypedExpr::Case { .. } => " ".to_doc().append(self.expr(expr)).group(),
             _ => self.case_clause_value(expr),
         }
     }
 
-    fn clause<'a>(&mut self, clause: &'a UntypedClause, index: u32) -> Document<'a> {
+    fn clause<'a>(&mut self, clause: &'a UntypedClause<'_>, index: u32) -> Document<'a> {
         let space_before = self.pop_empty_lines(clause.location.start);
         let after_position = clause.location.end;
         let clause_doc = join(
@@ -1217,7 +1942,7 @@ impl<'comments> Formatter<'comments> {
         &mut self,
         public: bool,
         name: &'a str,
-        args: &'a [String],
+        args: &'a [Name],
     ) -> Document<'a> {
         pub_(public)
             .append("external type ")
@@ -1231,16 +1956,24 @@ impl<'comments> Formatter<'comments> {
 
     fn list<'a>(
         &mut self,
-        elements: &'a [UntypedExpr],
-        tail: Option<&'a UntypedExpr>,
+        elements: &'a [UntypedExpr<'_>],
+        tail: Option<&'a UntypedExpr<'_>>,
+        end: u32,
     ) -> Document<'a> {
-        let comma: fn() -> Document<'a> =
-            if tail.is_none() && elements.iter().all(UntypedExpr::is_simple_constant) {
-                || flex_break(",", ", ")
-            } else {
-                || break_(",", ", ")
-            };
-        let elements_document = join(elements.iter().map(|e| self.wrap_expr(e)), comma());
+        let comments = self.comments.len();
+        let elements_end = tail.map(UntypedExpr::start_byte_index).unwrap_or(end);
+        let elements_docs = self.elements(elements, elements_end, |this, e| this.wrap_expr(e));
+        let has_comments = self.comments.len() != comments;
+        // Comments at the end of lines need each element on its own line
+        let comma: fn() -> Document<'a> = if tail.is_none()
+            && !has_comments
+            && elements.iter().all(UntypedExpr::is_simple_constant)
+        {
+            || flex_break(",", ", ")
+        } else {
+            || break_(",", ", ")
+        };
+        let elements_document = join(elements_docs, comma());
         let tail = tail.map(|e| self.expr(e));
         list(elements_document, elements.len(), tail)
     }
@@ -1248,7 +1981,9 @@ impl<'comments> Formatter<'comments> {
     fn pattern<'a>(&mut self, pattern: &'a UntypedPattern) -> Document<'a> {
         let comments = self.pop_comments(pattern.location().start);
         let doc = match pattern {
-            Pattern::Int { value, .. } => value.to_doc(),
+            Pattern::Int { value, .. } => self.int(value),
+
+            Pattern::IntRange { start, end, .. } => docvec![self.int(start), "..", self.int(end)],
 
             Pattern::Float { value, .. } => value.to_doc(),
 
@@ -1384,6 +2119,11 @@ impl<'comments> Formatter<'comments> {
                 self.clause_guard(tuple).append(".").append(*index).to_doc()
             }
 
+            ClauseGuard::Call { name, args, .. } => name
+                .to_doc()
+                .append(wrap_args(args.iter().map(|arg| self.clause_guard(arg))))
+                .group(),
+
             ClauseGuard::Constant(constant) => self.const_expr(constant),
         }
     }
@@ -1395,14 +2135,28 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
-    fn negate<'a>(&mut self, expr: &'a UntypedExpr) -> Document<'a> {
+    fn negate<'a>(&mut self, expr: &'a UntypedExpr<'_>) -> Document<'a> {
         match expr {
             UntypedExpr::BinOp { .. } => docvec!["!{ ", self.expr(expr), " }"],
             _ => docvec!["!", self.wrap_expr(expr)],
         }
     }
 
-    fn use_<'a>(&mut self, use_: &'a Use) -> Document<'a> {
+    fn echo<'a>(&mut self, expr: &'a UntypedExpr<'_>) -> Document<'a> {
+        match expr {
+            UntypedExpr::BinOp { .. } => docvec!["echo { ", self.expr(expr), " }"],
+            UntypedExpr::PipeLine { .. } => docvec![
+                "echo {",
+                line().append(self.expr(expr)).nest(INDENT),
+                line(),
+                "}"
+            ]
+            .force_break(),
+            _ => docvec!["echo ", self.wrap_expr(expr)],
+        }
+    }
+
+    fn use_<'a>(&mut self, use_: &'a Use<'_>) -> Document<'a> {
         let call = self.expr(&use_.call).nest(INDENT);
 
         if use_.assignments.is_empty() {
@@ -1450,7 +2204,7 @@ impl<'a> Documentable<'a> for &'a UnqualifiedImport {
     }
 }
 
-fn label(label: &Option<String>) -> Document<'_> {
+fn label(label: &Option<Name>) -> Document<'_> {
     match label {
         Some(s) => Document::Str(s).append(": "),
         None => nil(),
@@ -1680,11 +2434,9 @@ where
             ..
         } => to_doc(value),
 
-        BitStringSegmentOption::Unit { value, .. } => "unit"
-            .to_doc()
-            .append("(")
-            .append(Document::String(format!("{}", value)))
-            .append(")"),
+        BitStringSegmentOption::Unit { value, .. } => {
+            "unit".to_doc().append("(").append(*value).append(")")
+        }
     }
 }
 
@@ -1736,7 +2488,7 @@ pub fn comments_before<'a>(
     )
 }
 
-fn is_breakable_expr(expr: &UntypedExpr) -> bool {
+fn is_breakable_expr(expr: &UntypedExpr<'_>) -> bool {
     matches!(
         expr,
         UntypedExpr::Fn { .. }
@@ -1749,3 +2501,11 @@ fn is_breakable_expr(expr: &UntypedExpr) -> bool {
             | UntypedExpr::BitString { .. }
     )
 }
+
+fn allow_attribute<'a>(attribute: &'static str, suppression: &WarningSuppression) -> Document<'a> {
+    let kinds = suppression.kinds.iter().map(|kind| kind.name().to_doc());
+    attribute
+        .to_doc()
+        .append(join(kinds, ", ".to_doc()))
+        .append(")")
+}
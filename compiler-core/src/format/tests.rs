@@ -5,7 +5,13 @@ use pretty_assertions::assert_eq;
 macro_rules! assert_format {
     ($src:expr $(,)?) => {
         let mut writer = String::new();
-        pretty(&mut writer, $src, std::path::Path::new("<stdin>")).unwrap();
+        pretty(
+            &mut writer,
+            $src,
+            std::path::Path::new("<stdin>"),
+            &Options::default(),
+        )
+        .unwrap();
         assert_eq!($src, writer);
     };
 }
@@ -13,7 +19,13 @@ macro_rules! assert_format {
 macro_rules! assert_format_rewrite {
     ($src:expr, $output:expr  $(,)?) => {
         let mut writer = String::new();
-        pretty(&mut writer, $src, std::path::Path::new("<stdin>")).unwrap();
+        pretty(
+            &mut writer,
+            $src,
+            std::path::Path::new("<stdin>"),
+            &Options::default(),
+        )
+        .unwrap();
         assert_eq!(writer, $output);
     };
 }
@@ -1059,6 +1071,14 @@ fn expr_int() {
     assert_format!("fn a() {\n  0xCA_B0_05E\n}\n");
     assert_format!("fn a() {\n  0b10_10_0001\n}\n");
     assert_format!("fn a() {\n  0o12_34_567\n}\n");
+
+    assert_format_rewrite!("fn a() {\n  0xca_fe\n}\n", "fn a() {\n  0xCA_FE\n}\n");
+    assert_format_rewrite!("fn a() {\n  12_34_567\n}\n", "fn a() {\n  1_234_567\n}\n");
+    assert_format_rewrite!("fn a() {\n  007\n}\n", "fn a() {\n  7\n}\n");
+    assert_format_rewrite!(
+        "const a = 0xff\n\nfn b(x) {\n  case x {\n    1_0000 -> 1\n  }\n}\n",
+        "const a = 0xFF\n\nfn b(x) {\n  case x {\n    10_000 -> 1\n  }\n}\n"
+    );
 }
 
 #[test]
@@ -1934,6 +1954,15 @@ fn expr_case_clause_guards() {
 "#
     );
 
+    assert_format!(
+        r#"fn main() {
+  case 1 {
+    _ if is_small(x, y) -> Nil
+  }
+}
+"#
+    );
+
     assert_format!(
         r#"fn main() {
   case "x" {
@@ -2425,6 +2454,94 @@ fn trailing_comments() {
     );
 }
 
+#[test]
+fn end_of_line_comments() {
+    assert_format!(
+        "import gleam/io // for printing
+
+const x = 1 // the answer
+
+pub fn main() {
+  let x = 1 // one
+  foo(a, b) // call
+  x // last
+}
+"
+    );
+
+    assert_format!(
+        "pub fn main() {
+  1
+} // done
+"
+    );
+}
+
+#[test]
+fn end_of_line_comments_in_lists_and_calls() {
+    assert_format!(
+        "pub fn main() {
+  let x = [
+    1, // one
+    2, // two
+  ]
+  foo(
+    a, // first
+    b,
+  )
+  #(
+    1, // first
+    2,
+  )
+}
+"
+    );
+
+    assert_format_rewrite!(
+        "pub fn main() {
+  [1, // one
+   2]
+}
+",
+        "pub fn main() {
+  [
+    1, // one
+    2,
+  ]
+}
+"
+    );
+}
+
+#[test]
+fn end_of_line_comments_in_case_clauses() {
+    assert_format!(
+        r#"pub fn main() {
+  case x {
+    1 -> "one" // the first
+    // before two
+    2 -> "two"
+    _ -> "other" // fallback
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn end_of_line_comments_in_pipelines() {
+    assert_format!(
+        "pub fn main() {
+  x
+  |> add(1) // add one
+  // then double
+  |> double
+  |> inc // last
+}
+"
+    );
+}
+
 #[test]
 fn commented_fn_arguments() {
     assert_format!(
@@ -2561,6 +2678,54 @@ fn function_captures_test() {
         "pub fn main() {
   run(1, 2, _, 4, 5)(_)
 }
+"
+    );
+
+    assert_format!(
+        "pub fn main() {
+  run(_, 2, _, 4, _)
+}
+"
+    );
+
+    assert_format!(
+        "pub fn main() {
+  x
+  |> run(_, 2, _)
+}
+"
+    );
+}
+
+#[test]
+fn operator_sections() {
+    assert_format!(
+        "pub fn main() {
+  list.map(xs, _ + 1)
+}
+"
+    );
+
+    assert_format!(
+        "pub fn main() {
+  let f = _ * 2 + _
+  f
+}
+"
+    );
+
+    assert_format!(
+        "pub fn main() {
+  { _ + 1 } + 2
+}
+"
+    );
+
+    assert_format!(
+        "pub fn main() {
+  x
+  |> { _ <> \"!\" }
+}
 "
     );
 }
@@ -2846,6 +3011,16 @@ pub const float = 3.14
     );
 }
 
+#[test]
+fn nested_record_update() {
+    assert_format!(
+        r#"fn main() {
+  Config(..config, server.port: 8080, server.tls.enabled: True)
+}
+"#
+    );
+}
+
 #[test]
 fn record_update() {
     assert_format!(
@@ -3168,6 +3343,20 @@ const f_alias: fn(Int) -> Int = f
     );
 }
 
+#[test]
+fn binop_constant() {
+    assert_format!(
+        r#"const x = 1 + 2 * 3
+
+const y = { 1 + 2 } * 3
+
+const z = 1.5 *. { 2.0 -. 0.5 }
+
+const greeting = "Hello, " <> "Joe"
+"#
+    );
+}
+
 #[test]
 fn let_as_expression() {
     assert_format!(
@@ -3206,6 +3395,17 @@ fn assert_as_expression() {
     );
 }
 
+#[test]
+fn assert_with_message() {
+    assert_format!(
+        r#"pub fn main() {
+  assert Ok(x) = parse(input) as "the input was already validated"
+  x
+}
+"#
+    );
+}
+
 #[test]
 fn block_containing_try() {
     assert_format!(
@@ -3363,6 +3563,51 @@ fn negation_block() {
     );
 }
 
+#[test]
+fn int_range_patterns() {
+    assert_format!(
+        "pub fn main(x) {
+  case x {
+    0..9 -> 0
+    -10..-1 | 0x10..0xFF -> 1
+    1_000..9_999 as n -> n
+    _ -> 2
+  }
+}
+"
+    );
+}
+
+#[test]
+fn echo() {
+    assert_format!(
+        "pub fn main(x) {
+  echo x
+  echo { 1 + x }
+  echo {
+    x
+    |> inc
+  }
+  echo inc(x)
+}
+"
+    );
+}
+
+#[test]
+fn cfg() {
+    assert_format!(
+        "pub fn main(x) {
+  let verbose = @cfg(debug)
+  case @cfg(json) {
+    True -> x
+    False -> !@cfg(xml)
+  }
+}
+"
+    );
+}
+
 #[test]
 fn single_empty_line_between_comments() {
     // empty line isn't added if it's not already present
@@ -3706,6 +3951,34 @@ fn multiple_line_custom_type_constructor_field_doc_comments() {
     );
 }
 
+#[test]
+fn function_parameter_doc_comments() {
+    assert_format!(
+        r#"pub fn greet(
+  /// The person to greet.
+  name: String,
+  /// How many times,
+  /// at least one.
+  times times: Int,
+) -> String {
+  name
+}
+"#
+    );
+}
+
+#[test]
+fn external_function_parameter_doc_comments() {
+    assert_format!(
+        r#"pub external fn show(
+  /// The thing to show.
+  thing: anything,
+) -> String =
+  "utils" "inspect"
+"#
+    );
+}
+
 // https://github.com/gleam-lang/gleam/issues/1786
 #[test]
 fn multiple_line_documentation_comment_statement_grouping() {
@@ -3878,3 +4151,463 @@ fn not_add() {
 "#
     );
 }
+
+#[test]
+fn derive_attribute() {
+    assert_format!(
+        "/// A shape
+@derive(predicates, setters)
+pub type Shape {
+  Square(size: Int)
+}
+"
+    );
+
+    assert_format_rewrite!(
+        "@derive( predicates ) pub type Shape { Square }",
+        "@derive(predicates)
+pub type Shape {
+  Square
+}
+"
+    );
+}
+
+#[test]
+fn external_attribute() {
+    assert_format!(
+        r#"/// Reverse a list
+@external(erlang, "lists", "reverse")
+@external(javascript, "./ffi.mjs", "reverse")
+pub fn reverse(list: List(a)) -> List(a) {
+  do_reverse(list, [])
+}
+"#
+    );
+
+    assert_format_rewrite!(
+        r#"@external( erlang,"lists" , "reverse" ) pub fn reverse(list: List(a)) -> List(a) { list }"#,
+        r#"@external(erlang, "lists", "reverse")
+pub fn reverse(list: List(a)) -> List(a) {
+  list
+}
+"#
+    );
+}
+
+#[test]
+fn erlang_map_attribute() {
+    assert_format!(
+        "@erlang_map
+pub type Cat {
+  Cat(name: String, cuteness: Int)
+}
+"
+    );
+
+    assert_format!(
+        "/// A cat
+@erlang_map
+pub type Cat {
+  Cat(name: String, cuteness: Int)
+}
+"
+    );
+
+    assert_format_rewrite!(
+        "@erlang_map pub type Cat { Cat(name: String) }",
+        "@erlang_map
+pub type Cat {
+  Cat(name: String)
+}
+"
+    );
+}
+
+#[test]
+fn deprecated_attribute() {
+    assert_format!(
+        r#"/// Adds one
+@deprecated("Use int.add instead")
+pub fn add_one(x: Int) -> Int {
+  x + 1
+}
+
+@deprecated("Use 2 instead")
+pub const two = 2
+
+@deprecated("Use List instead")
+pub external type Queue(a)
+"#
+    );
+
+    assert_format!(
+        r#"@deprecated("Use Int instead")
+@erlang_map
+pub type Number {
+  Number(value: Int)
+}
+"#
+    );
+
+    assert_format!(
+        r#"pub type Animal {
+  Cat
+  /// A dog
+  @deprecated("Dogs are no longer supported")
+  Dog(name: String)
+}
+"#
+    );
+
+    assert_format_rewrite!(
+        r#"@deprecated("Use wobble instead") fn wibble() { 1 }"#,
+        r#"@deprecated("Use wobble instead")
+fn wibble() {
+  1
+}
+"#
+    );
+}
+
+#[test]
+fn allow_attributes() {
+    assert_format!(
+        r#"//// A module of generated code
+
+@module_allow(todo, unused_imported_module)
+
+@allow(unused_imported_value)
+import gleam/list.{map}
+
+/// Does nothing yet
+@deprecated("Use wobble instead")
+@allow(unused_variable)
+pub fn wibble(x: Int) -> Int {
+  let y = 1
+  x
+}
+
+@allow(unused_private_constant)
+const three = 3
+"#
+    );
+
+    assert_format_rewrite!(
+        "import gleam
+@module_allow(todo)",
+        "@module_allow(todo)
+
+import gleam
+"
+    );
+}
+
+#[test]
+fn configured_line_length() {
+    let src = r#"pub fn main() {
+  call_a_function(with_an_argument, and_another_one)
+}
+"#;
+    let mut writer = String::new();
+    let options = Options {
+        line_length: 40,
+        ..Default::default()
+    };
+    pretty(&mut writer, src, Path::new("<stdin>"), &options).expect("format");
+    assert_eq!(
+        writer,
+        r#"pub fn main() {
+  call_a_function(
+    with_an_argument,
+    and_another_one,
+  )
+}
+"#
+    );
+
+    let mut writer = String::new();
+    pretty(&mut writer, src, Path::new("<stdin>"), &Options::default()).expect("format");
+    assert_eq!(writer, src);
+}
+
+#[test]
+fn sort_imports() {
+    let src = r#"import app/router
+import gleam/list
+import lustre.{element, attribute, element}
+// The server
+import mist
+import app.{Config}
+import gleam/io.{println, debug}
+
+pub fn main() {
+  Nil
+}
+
+if erlang {
+  import gleam/erlang
+  import app/ffi
+}
+"#;
+    let mut writer = String::new();
+    let options = Options {
+        sort_imports: true,
+        package: Some("app".into()),
+        ..Default::default()
+    };
+    pretty(&mut writer, src, Path::new("<stdin>"), &options).expect("format");
+    assert_eq!(
+        writer,
+        r#"import gleam/io.{debug, println}
+import gleam/list
+
+import lustre.{attribute, element}
+// The server
+import mist
+
+import app.{Config}
+import app/router
+
+pub fn main() {
+  Nil
+}
+
+if erlang {
+  import gleam/erlang
+
+  import app/ffi
+}
+"#
+    );
+}
+
+#[test]
+fn imports_are_not_sorted_by_default() {
+    assert_format!(
+        r#"import mist
+import gleam/list
+import lustre.{element, element}
+"#
+    );
+}
+
+fn format_range(src: &str, start: u32, end: u32) -> String {
+    let range = SrcSpan { start, end };
+    let edit =
+        pretty_range(src, Path::new("<stdin>"), range, &Options::default()).expect("format range");
+    let mut output = src.to_string();
+    if let Some(edit) = edit {
+        output.replace_range(
+            edit.location.start as usize..edit.location.end as usize,
+            &edit.new_text,
+        );
+    }
+    output
+}
+
+#[test]
+fn format_range_only_changes_enclosing_definition() {
+    let src = r#"import  gleam/io
+
+pub fn one( ) { 1 }
+
+pub fn two( ) {   io.println( "two" )   }
+
+pub fn three( ) { 3 }
+"#;
+    let start = src.find("io.println").expect("find") as u32;
+    assert_eq!(
+        format_range(src, start, start + 3),
+        r#"import  gleam/io
+
+pub fn one( ) { 1 }
+
+pub fn two() {
+  io.println("two")
+}
+
+pub fn three( ) { 3 }
+"#
+    );
+}
+
+#[test]
+fn format_range_spanning_definitions() {
+    let src = r#"pub fn one( ) { 1 }
+
+// Two
+pub fn two( ) { 2 }
+
+pub fn three( ) { 3 }
+"#;
+    let start = src.find("one").expect("find") as u32;
+    let end = src.find("two").expect("find") as u32;
+    assert_eq!(
+        format_range(src, start, end),
+        r#"pub fn one() {
+  1
+}
+
+// Two
+pub fn two() {
+  2
+}
+
+pub fn three( ) { 3 }
+"#
+    );
+}
+
+#[test]
+fn format_range_inside_target_group() {
+    let src = r#"pub fn one( ) { 1 }
+
+if erlang {
+pub fn two( ) { 2 }
+  pub fn three( ) { 3 }
+}
+"#;
+    let start = src.find("three").expect("find") as u32;
+    assert_eq!(
+        format_range(src, start, start),
+        r#"pub fn one( ) { 1 }
+
+if erlang {
+  pub fn two() {
+    2
+  }
+
+  pub fn three() {
+    3
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn format_range_outside_definitions() {
+    let src = "pub fn one( ) { 1 }\n\n\n";
+    assert_eq!(format_range(src, 21, 22), src);
+}
+
+fn format_on_type(src: &str, typed: &str) -> String {
+    let position = (src.find(typed).expect("find typed") + typed.len()) as u32;
+    let typed = typed.chars().last().expect("typed character");
+    let mut output = src.to_string();
+    for edit in on_type(
+        src,
+        Path::new("<stdin>"),
+        position,
+        typed,
+        &Options::default(),
+    )
+    .into_iter()
+    .rev()
+    {
+        output.replace_range(
+            edit.location.start as usize..edit.location.end as usize,
+            &edit.new_text,
+        );
+    }
+    output
+}
+
+#[test]
+fn format_on_typing_closing_brace() {
+    let src =
+        "pub fn one( ) { 1 }\n\npub fn two( ) {\n  [1,2] |> list.map(_,  int.to_string)\n    }\n";
+    assert_eq!(
+        format_on_type(src, "\n    }"),
+        "pub fn one( ) { 1 }\n\npub fn two() {\n  [1, 2]\n  |> list.map(int.to_string)\n}\n"
+    );
+
+    // Code that cannot be parsed yet is only reindented
+    let src = "pub fn main() {\n  case x {\n    1 -> \n      }\n";
+    assert_eq!(
+        format_on_type(src, "\n      }"),
+        "pub fn main() {\n  case x {\n    1 -> \n  }\n"
+    );
+}
+
+#[test]
+fn format_on_typing_newline() {
+    let src = "pub fn main() {\n  let x = #(\n1)\n}\n";
+    assert_eq!(
+        format_on_type(src, "#(\n"),
+        "pub fn main() {\n  let x = #(\n    1)\n}\n"
+    );
+
+    let src = "pub fn main() {\n  1\n}\n";
+    assert_eq!(format_on_type(src, "1\n"), src);
+
+    // Lines within a string are left as they are
+    let src = "pub fn main() {\n  \"one\ntwo\"\n}\n";
+    assert_eq!(format_on_type(src, "one\n"), src);
+}
+
+fn verification_problem(src: &str, formatted: &str) -> Option<VerificationProblem> {
+    match verify(src, formatted, Path::new("<stdin>"), &Options::default()) {
+        Ok(()) => None,
+        Err(Error::FormatVerification { problem, .. }) => Some(problem),
+        Err(error) => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn verify_formatted_code() {
+    let src = r#"import b
+import a.{y, x, x}
+pub fn main( ) {
+  // Hello
+  [1.,
+  2.5] |> list.map(_, float.round)  |> wibble(_)
+}
+"#;
+    let options = Options {
+        sort_imports: true,
+        verify: true,
+        ..Default::default()
+    };
+    let mut formatted = String::new();
+    pretty(&mut formatted, src, Path::new("<stdin>"), &options).expect("format");
+    assert_eq!(verification_problem(src, &formatted), None);
+}
+
+#[test]
+fn verify_mangled_code() {
+    let src = "pub fn main() {\n  // Hello\n  1\n}\n";
+    assert_eq!(
+        verification_problem(src, "pub fn main() {\n  1 +\n}\n"),
+        Some(VerificationProblem::Unparsable)
+    );
+    assert_eq!(
+        verification_problem(src, "pub fn main() {\n  // Hello\n  2\n}\n"),
+        Some(VerificationProblem::ChangedMeaning)
+    );
+    assert_eq!(
+        verification_problem(src, "pub fn main() {\n  1\n}\n"),
+        Some(VerificationProblem::ChangedComments)
+    );
+    assert_eq!(
+        verification_problem(src, "pub fn main() { // Hello\n 1 }\n"),
+        Some(VerificationProblem::NotIdempotent)
+    );
+}
+
+#[test]
+fn verify_error_location() {
+    let src = "pub fn one() {\n  1\n}\n\npub fn two() {\n  2\n}\n";
+    let error = verify(src, "", Path::new("<stdin>"), &Options::default())
+        .expect_err("verification should fail");
+    assert_eq!(
+        error,
+        Error::FormatVerification {
+            path: std::path::PathBuf::from("<stdin>"),
+            src: src.to_string(),
+            location: SrcSpan::new(0, src.len() as u32),
+            problem: VerificationProblem::ChangedMeaning,
+        }
+    );
+}
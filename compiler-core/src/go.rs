@@ -0,0 +1,838 @@
+//! An experimental code generator that compiles Gleam to Go.
+//!
+//! The modules of a project are compiled into a single Go module named
+//! `gleam`, with each Gleam module becoming a Go package in the directory of
+//! the same path. `gleam/list` is written to `gleam/list/list.go` and
+//! imported as `gleam/gleam/list`, and the prelude is the `gleam/gleam`
+//! package. Native `.go` files are copied into the same Go module, so the
+//! external `@external(go, "gleam/ffi", "Add")` refers to a function of
+//! `src/ffi/ffi.go`.
+//!
+//! Custom types with a single constructor of the same name are Go structs.
+//! Other custom types are interfaces that are implemented by a struct for each
+//! of their constructors. Every struct has a `Get` method for each of its
+//! labelled fields so that fields can be accessed the same way on both.
+
+mod expression;
+mod keywords;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use itertools::Itertools;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        *,
+    },
+    build::Target,
+    docvec,
+    io::Utf8Writer,
+    line_numbers::LineNumbers,
+    pretty::*,
+    type_::{self, Type, TypeVar, ValueConstructor, ValueConstructorVariant},
+};
+
+pub const PRELUDE: &str = include_str!("../templates/gleam.go");
+
+/// The `go.mod` file of the Go module that the packages are compiled into.
+pub const GO_MOD: &str = "module gleam\n\ngo 1.21\n";
+
+/// The path of the prelude in the Go module.
+pub const PRELUDE_PATH: &str = "gleam/gleam.go";
+
+const PRELUDE_IMPORT_PATH: &str = "gleam/gleam";
+
+const INDENT: isize = 4;
+
+pub type Output<'a> = Result<Document<'a>, Error>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Unsupported { feature: String, location: SrcSpan },
+}
+
+fn unsupported<T>(feature: &str, location: SrcSpan) -> Result<T, Error> {
+    Err(Error::Unsupported {
+        feature: feature.into(),
+        location,
+    })
+}
+
+/// The path of the Go file of the module within the Go module.
+pub fn module_path(module: &str) -> PathBuf {
+    let name = module.rsplit('/').next().unwrap_or(module);
+    PathBuf::from(module).join(format!("{name}.go"))
+}
+
+/// A Go `main` package that runs the `main` function of the module.
+pub fn entrypoint(module: &str) -> String {
+    let alias = import_alias(module.split('/'));
+    format!(
+        "package main\n\nimport {alias} \"gleam/{module}\"\n\nfunc main() {{\n    {alias}.Main()\n}}\n"
+    )
+}
+
+pub fn module(
+    module: &TypedModule,
+    line_numbers: &LineNumbers,
+    path: &Path,
+    src: &str,
+    line_length: isize,
+    writer: &mut impl Utf8Writer,
+) -> Result<(), crate::Error> {
+    let document = Generator::new(line_numbers, module)
+        .compile()
+        .map_err(|error| crate::Error::Go {
+            path: path.to_path_buf(),
+            src: src.into(),
+            error,
+        })?;
+    document.pretty_print(line_length, writer)
+}
+
+fn import_alias<'a>(module: impl IntoIterator<Item = &'a str>) -> String {
+    keywords::escape(&module.into_iter().join("_"))
+}
+
+/// The Go packages imported by a module, by their import path.
+#[derive(Debug)]
+struct Imports {
+    aliases: BTreeMap<String, String>,
+    taken: HashSet<String>,
+}
+
+impl Imports {
+    fn new() -> Self {
+        Self {
+            aliases: BTreeMap::new(),
+            taken: HashSet::from(["gleam".into()]),
+        }
+    }
+
+    /// The name the package is imported as, which is the preferred name
+    /// unless that is already used by another package or by a local variable.
+    fn alias(&mut self, path: &str, preferred: String, locals: &HashSet<String>) -> String {
+        if path == PRELUDE_IMPORT_PATH {
+            let _ = self.aliases.insert(path.into(), "gleam".into());
+            return "gleam".into();
+        }
+        if let Some(alias) = self.aliases.get(path) {
+            return alias.clone();
+        }
+        let mut alias = preferred.clone();
+        let mut suffix = 1;
+        while self.taken.contains(&alias) || locals.contains(&alias) {
+            alias = format!("{preferred}_{suffix}");
+            suffix += 1;
+        }
+        let _ = self.taken.insert(alias.clone());
+        let _ = self.aliases.insert(path.into(), alias.clone());
+        alias
+    }
+
+    fn into_doc<'a>(self) -> Document<'a> {
+        if self.aliases.is_empty() {
+            return nil();
+        }
+        let imports = self.aliases.into_iter().map(|(path, alias)| {
+            docvec![
+                line(),
+                Document::String(alias),
+                " \"",
+                Document::String(path),
+                "\""
+            ]
+        });
+        docvec![
+            "import (",
+            concat(imports).nest(INDENT),
+            line(),
+            ")",
+            lines(2)
+        ]
+    }
+}
+
+/// The definitions of the local variables that are referenced, as Go does
+/// not allow a variable to be declared and not used.
+#[derive(Debug, Default)]
+struct References(HashSet<u32>);
+
+impl<'ast> Visit<'ast> for References {
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::Var {
+            constructor:
+                ValueConstructor {
+                    variant: ValueConstructorVariant::LocalVariable { location },
+                    ..
+                },
+            ..
+        } = expr
+        {
+            let _ = self.0.insert(location.start);
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Generator<'a> {
+    line_numbers: &'a LineNumbers,
+    module: &'a TypedModule,
+    imports: Imports,
+    /// The module and name of each value imported unqualified, by the name
+    /// it is imported as.
+    unqualified: HashMap<&'a str, (&'a [Name], &'a str)>,
+    /// The modules imported by the module, by the name they are referred to
+    /// with.
+    module_aliases: HashMap<&'a str, &'a [Name]>,
+    /// Whether each value defined in the module is public.
+    values: HashMap<&'a str, bool>,
+    /// The types of the functions defined in the module, used to find the
+    /// type arguments of each call.
+    functions: HashMap<&'a str, Arc<Type>>,
+    /// The names of the types defined in the module.
+    type_names: HashSet<&'a str>,
+    references: HashSet<u32>,
+    /// The Go names of the type variables of the current declaration, in the
+    /// order they are declared.
+    type_params: Vec<(u64, String)>,
+    /// The Go names of the variables in scope, by their Gleam names.
+    scope: im::HashMap<String, String>,
+    /// The Go names of the variables declared in the current function.
+    declared: HashSet<String>,
+    /// The Go names of the variables declared in any function of the module,
+    /// which the names the packages are imported as must not clash with.
+    locals: HashSet<String>,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(line_numbers: &'a LineNumbers, module: &'a TypedModule) -> Self {
+        let mut references = References::default();
+        references.visit_typed_module(module);
+        let mut generator = Self {
+            line_numbers,
+            module,
+            imports: Imports::new(),
+            unqualified: HashMap::new(),
+            module_aliases: HashMap::new(),
+            values: HashMap::new(),
+            functions: HashMap::new(),
+            type_names: HashSet::new(),
+            references: references.0,
+            type_params: vec![],
+            scope: im::HashMap::new(),
+            declared: HashSet::new(),
+            locals: HashSet::new(),
+        };
+        generator.register_definitions();
+        generator
+    }
+
+    fn register_definitions(&mut self) {
+        for statement in &self.module.statements {
+            match statement {
+                Statement::Import {
+                    module,
+                    as_name,
+                    unqualified,
+                    ..
+                } => {
+                    let alias = as_name
+                        .as_deref()
+                        .or_else(|| module.last().map(Name::as_str));
+                    if let Some(alias) = alias {
+                        let _ = self.module_aliases.insert(alias, module);
+                    }
+                    for import in unqualified {
+                        let _ = self
+                            .unqualified
+                            .insert(import.variable_name(), (module, &import.name));
+                    }
+                }
+                Statement::Fn {
+                    name,
+                    public,
+                    arguments,
+                    return_type,
+                    ..
+                } => {
+                    let _ = self.values.insert(name, *public);
+                    let arguments = arguments.iter().map(|arg| arg.type_.clone()).collect();
+                    let type_ = type_::fn_(arguments, return_type.clone());
+                    let _ = self.functions.insert(name, type_);
+                }
+                Statement::ExternalFn {
+                    name,
+                    public,
+                    arguments,
+                    return_type,
+                    ..
+                } => {
+                    let _ = self.values.insert(name, *public);
+                    let arguments = arguments.iter().map(|arg| arg.type_.clone()).collect();
+                    let type_ = type_::fn_(arguments, return_type.clone());
+                    let _ = self.functions.insert(name, type_);
+                }
+                Statement::ModuleConstant { name, public, .. } => {
+                    let _ = self.values.insert(name, *public);
+                }
+                Statement::CustomType {
+                    name, constructors, ..
+                } => {
+                    let _ = self.type_names.insert(name);
+                    for constructor in constructors {
+                        let _ = self.type_names.insert(&constructor.name);
+                    }
+                }
+                Statement::ExternalType { name, .. } => {
+                    let _ = self.type_names.insert(name);
+                }
+                Statement::TypeAlias { .. } => (),
+            }
+        }
+    }
+
+    pub fn compile(mut self) -> Output<'a> {
+        self.check_names()?;
+        let declarations: Vec<_> = self
+            .module
+            .statements
+            .iter()
+            .map(|statement| self.statement(statement))
+            .filter_map_ok(|declaration| declaration)
+            .try_collect()?;
+        let package = self
+            .module
+            .name
+            .last()
+            .map(|name| keywords::escape(name))
+            .unwrap_or_default();
+        let imports = std::mem::replace(&mut self.imports, Imports::new());
+        Ok(docvec![
+            "package ",
+            Document::String(package),
+            lines(2),
+            imports.into_doc(),
+            join(declarations, lines(2)),
+            line()
+        ])
+    }
+
+    /// Go has a single namespace for the types and values of a package, so
+    /// public values cannot share a name with a type or record constructor.
+    fn check_names(&self) -> Result<(), Error> {
+        for statement in &self.module.statements {
+            match statement {
+                Statement::Fn {
+                    name,
+                    public: true,
+                    location,
+                    ..
+                }
+                | Statement::ExternalFn {
+                    name,
+                    public: true,
+                    location,
+                    ..
+                }
+                | Statement::ModuleConstant {
+                    name,
+                    public: true,
+                    location,
+                    ..
+                } if self.type_names.contains(keywords::exported(name).as_str()) => {
+                    return unsupported(
+                        "A value with the same name as a type or record constructor",
+                        *location,
+                    );
+                }
+                Statement::CustomType {
+                    name, constructors, ..
+                } if constructors.len() > 1 => {
+                    if let Some(constructor) = constructors.iter().find(|c| &c.name == name) {
+                        return unsupported(
+                            "A record constructor with the same name as its type",
+                            constructor.location,
+                        );
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: &'a TypedStatement) -> Result<Option<Document<'a>>, Error> {
+        match statement {
+            Statement::Import { .. } | Statement::TypeAlias { .. } => Ok(None),
+
+            Statement::ExternalType {
+                name, arguments, ..
+            } => Ok(Some(external_type(name, arguments.len()))),
+
+            Statement::CustomType {
+                name,
+                typed_parameters,
+                constructors,
+                ..
+            } => Ok(Some(self.custom_type(name, typed_parameters, constructors))),
+
+            Statement::ModuleConstant {
+                name, value, type_, ..
+            } => {
+                self.type_params.clear();
+                let type_ = self.type_(type_);
+                let value = self.constant(value)?;
+                Ok(Some(docvec![
+                    "var ",
+                    Document::String(self.value_name(name)),
+                    " ",
+                    Document::String(type_),
+                    " = ",
+                    value
+                ]))
+            }
+
+            Statement::Fn {
+                name,
+                arguments,
+                body,
+                externals,
+                ..
+            } => {
+                let external = externals
+                    .iter()
+                    .find(|external| external.target == Target::Go)
+                    .map(|external| (external.module.as_str(), external.function.as_str()));
+                self.function(name, arguments, body, external).map(Some)
+            }
+
+            Statement::ExternalFn {
+                name, module, fun, ..
+            } => Ok(Some(self.external_function(name, module, fun))),
+        }
+    }
+
+    fn custom_type(
+        &mut self,
+        name: &'a str,
+        parameters: &[Arc<Type>],
+        constructors: &'a [RecordConstructor<Arc<Type>>],
+    ) -> Document<'a> {
+        self.type_params.clear();
+        let ids = parameters.iter().flat_map(|type_| type_variables(type_));
+        let (declared_params, params) = self.declare_type_params(ids);
+
+        // The labels of the fields that all the constructors have
+        let shared_labels = constructors
+            .first()
+            .map(|first| {
+                first
+                    .arguments
+                    .iter()
+                    .filter_map(|arg| Some((arg.label.as_deref()?, &arg.type_)))
+                    .filter(|(label, _)| {
+                        constructors.iter().all(|constructor| {
+                            constructor
+                                .arguments
+                                .iter()
+                                .any(|arg| arg.label.as_deref() == Some(*label))
+                        })
+                    })
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
+        let is_struct = matches!(constructors, [constructor] if constructor.name == name);
+        let mut documents = vec![];
+        if !is_struct {
+            let methods = std::iter::once(docvec![line(), "is", name, "()"]).chain(
+                shared_labels.iter().map(|(label, type_)| {
+                    docvec![
+                        line(),
+                        "Get",
+                        Document::String(keywords::exported(label)),
+                        "() ",
+                        Document::String(self.type_(type_))
+                    ]
+                }),
+            );
+            documents.push(docvec![
+                "type ",
+                name,
+                Document::String(declared_params.clone()),
+                " interface {",
+                concat(methods).nest(INDENT),
+                line(),
+                "}"
+            ]);
+        }
+
+        for constructor in constructors {
+            let fields = constructor
+                .arguments
+                .iter()
+                .enumerate()
+                .map(|(index, arg)| {
+                    docvec![
+                        line(),
+                        Document::String(field_name(arg.label.as_deref(), index)),
+                        " ",
+                        Document::String(self.type_(&arg.type_))
+                    ]
+                });
+            let fields = if constructor.arguments.is_empty() {
+                "struct{}".to_doc()
+            } else {
+                docvec!["struct {", concat(fields).nest(INDENT), line(), "}"]
+            };
+            documents.push(docvec![
+                "type ",
+                constructor.name.as_str(),
+                Document::String(declared_params.clone()),
+                " ",
+                fields
+            ]);
+
+            let receiver = format!("{}{}", constructor.name, params);
+            if !is_struct {
+                documents.push(docvec![
+                    "func (",
+                    Document::String(receiver.clone()),
+                    ") is",
+                    name,
+                    "() {}"
+                ]);
+            }
+            for (index, arg) in constructor.arguments.iter().enumerate() {
+                let Some(label) = &arg.label else { continue };
+                let field = field_name(Some(label), index);
+                documents.push(docvec![
+                    "func (r ",
+                    Document::String(receiver.clone()),
+                    ") Get",
+                    Document::String(field.clone()),
+                    "() ",
+                    Document::String(self.type_(&arg.type_)),
+                    " {",
+                    docvec![line(), "return r.", Document::String(field)].nest(INDENT),
+                    line(),
+                    "}"
+                ]);
+            }
+        }
+        join(documents, lines(2))
+    }
+
+    fn function(
+        &mut self,
+        name: &'a str,
+        arguments: &'a [TypedArg],
+        body: &'a TypedExpr,
+        external: Option<(&'a str, &'a str)>,
+    ) -> Output<'a> {
+        self.begin_function(name);
+        let (arg_types, return_type) = self.signature(name);
+        let names = arguments
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| match (external, arg.get_variable_name()) {
+                (Some(_), _) => format!("p{index}"),
+                (None, Some(name)) => self.declare(name),
+                (None, None) => "_".into(),
+            })
+            .collect_vec();
+        let body = match external {
+            Some((module, function)) => self.external_call(module, function, &names),
+            None => self.function_body(body)?,
+        };
+        Ok(self.function_declaration(name, names, arg_types, return_type, body))
+    }
+
+    fn external_function(&mut self, name: &'a str, module: &'a str, fun: &'a str) -> Document<'a> {
+        self.begin_function(name);
+        let (arg_types, return_type) = self.signature(name);
+        let names = (0..arg_types.len())
+            .map(|index| format!("p{index}"))
+            .collect_vec();
+        let body = self.external_call(module, fun, &names);
+        self.function_declaration(name, names, arg_types, return_type, body)
+    }
+
+    fn begin_function(&mut self, name: &str) {
+        self.type_params.clear();
+        self.scope = im::HashMap::new();
+        self.declared.clear();
+        if let Some(type_) = self.functions.get(name).cloned() {
+            let _ = self.declare_type_params(type_variables(&type_));
+        }
+    }
+
+    /// The Go types of the arguments and return value of the function of the
+    /// module.
+    fn signature(&mut self, name: &str) -> (Vec<String>, String) {
+        let type_ = self.functions.get(name).cloned();
+        match type_.as_deref().and_then(Type::fn_types) {
+            Some((args, retrn)) => (
+                args.iter().map(|arg| self.type_(arg)).collect(),
+                self.type_(&retrn),
+            ),
+            None => (vec![], "gleam.Nil".into()),
+        }
+    }
+
+    fn function_declaration(
+        &mut self,
+        name: &'a str,
+        names: Vec<String>,
+        arg_types: Vec<String>,
+        return_type: String,
+        body: Document<'a>,
+    ) -> Document<'a> {
+        let type_params = self
+            .type_params
+            .iter()
+            .map(|(_, param)| format!("{param} any"))
+            .join(", ");
+        let type_params = if type_params.is_empty() {
+            String::new()
+        } else {
+            format!("[{type_params}]")
+        };
+        let arguments = names
+            .into_iter()
+            .zip(arg_types)
+            .map(|(name, type_)| format!("{name} {type_}"))
+            .join(", ");
+        docvec![
+            "func ",
+            Document::String(self.value_name(name)),
+            Document::String(type_params),
+            "(",
+            Document::String(arguments),
+            ") ",
+            Document::String(return_type),
+            " {",
+            docvec![line(), body].nest(INDENT),
+            line(),
+            "}"
+        ]
+    }
+
+    /// Calls the Go function that implements an external function, giving it
+    /// the type parameters of the Gleam function in the order they appear.
+    fn external_call(&mut self, module: &str, function: &str, names: &[String]) -> Document<'a> {
+        let last = module.rsplit('/').next().unwrap_or(module);
+        let preferred = keywords::escape(&last.replace(|c: char| !c.is_alphanumeric(), "_"));
+        let alias = self.imports.alias(module, preferred, &self.locals);
+        let type_args = self.type_params.iter().map(|(_, param)| param).join(", ");
+        let type_args = if type_args.is_empty() {
+            String::new()
+        } else {
+            format!("[{type_args}]")
+        };
+        Document::String(format!(
+            "return {alias}.{function}{type_args}({})",
+            names.join(", ")
+        ))
+    }
+
+    /// Names the type variables with the given ids, returning the type
+    /// parameters of the declaration and the type arguments that refer to
+    /// them.
+    fn declare_type_params(&mut self, ids: impl IntoIterator<Item = u64>) -> (String, String) {
+        let mut letters = ('A'..='Z')
+            .map(String::from)
+            .chain((1..).map(|i| format!("T{i}")))
+            .filter(|name| !self.type_names.contains(name.as_str()));
+        let mut names = vec![];
+        for id in ids {
+            if self.type_params.iter().any(|(declared, _)| *declared == id) {
+                continue;
+            }
+            let name = letters.next().expect("type parameter names are infinite");
+            self.type_params.push((id, name.clone()));
+            names.push(name);
+        }
+        if names.is_empty() {
+            return (String::new(), String::new());
+        }
+        (
+            format!(
+                "[{}]",
+                names.iter().map(|name| format!("{name} any")).join(", ")
+            ),
+            format!("[{}]", names.join(", ")),
+        )
+    }
+
+    /// The Go name of a value defined in the module.
+    fn value_name(&self, name: &str) -> String {
+        if self.values.get(name).copied().unwrap_or(false) {
+            keywords::exported(name)
+        } else {
+            keywords::escape(name)
+        }
+    }
+
+    /// The name a Gleam module is imported as, registering the import.
+    fn module_qualifier(&mut self, module: &[Name]) -> Option<String> {
+        if module.is_empty() || module == ["gleam"] {
+            return Some(self.prelude());
+        }
+        if module == self.module.name.as_slice() {
+            return None;
+        }
+        let path = format!("gleam/{}", module.join("/"));
+        let preferred = import_alias(module.iter().map(Name::as_str));
+        Some(self.imports.alias(&path, preferred, &self.locals))
+    }
+
+    fn prelude(&mut self) -> String {
+        self.imports
+            .alias(PRELUDE_IMPORT_PATH, "gleam".into(), &self.locals)
+    }
+
+    /// Declares a local variable, returning its Go name, which is the Gleam
+    /// name unless that is already declared in the function or is reserved.
+    fn declare(&mut self, name: &str) -> String {
+        let go_name = self.fresh(name);
+        let _ = self.scope.insert(name.into(), go_name.clone());
+        go_name
+    }
+
+    /// A Go name for a variable that is not already declared in the function.
+    fn fresh(&mut self, name: &str) -> String {
+        let base = keywords::escape(name);
+        let mut go_name = base.clone();
+        let mut suffix = 1;
+        while self.declared.contains(&go_name) || self.imports.taken.contains(&go_name) {
+            go_name = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        let _ = self.declared.insert(go_name.clone());
+        let _ = self.locals.insert(go_name.clone());
+        go_name
+    }
+
+    /// The Go type of a Gleam type.
+    fn type_(&mut self, type_: &Type) -> String {
+        match type_ {
+            Type::App {
+                module, name, args, ..
+            } => {
+                let args = args.iter().map(|arg| self.type_(arg)).collect_vec();
+                if module.is_empty() {
+                    match name.as_str() {
+                        "Int" => return "int64".into(),
+                        "Float" => return "float64".into(),
+                        "String" => return "string".into(),
+                        "Bool" => return "bool".into(),
+                        "BitString" => return "[]byte".into(),
+                        "UtfCodepoint" => return "rune".into(),
+                        "List" => return format!("*{}.List{}", self.prelude(), type_args(&args)),
+                        _ => (),
+                    }
+                }
+                let qualifier = self.module_qualifier(module);
+                format!("{}{}", qualified(qualifier, name), type_args(&args))
+            }
+
+            Type::Fn { args, retrn } => {
+                let args = args.iter().map(|arg| self.type_(arg)).join(", ");
+                format!("func({args}) {}", self.type_(retrn))
+            }
+
+            Type::Var { type_ } => match &*type_.borrow() {
+                TypeVar::Link { type_ } => self.type_(type_),
+                TypeVar::Unbound { id } | TypeVar::Generic { id } => self
+                    .type_params
+                    .iter()
+                    .find(|(declared, _)| declared == id)
+                    .map(|(_, name)| name.clone())
+                    .unwrap_or_else(|| "any".into()),
+            },
+
+            Type::Tuple { elems } if elems.is_empty() => "struct{}".into(),
+
+            Type::Tuple { elems } => {
+                let fields = elems
+                    .iter()
+                    .enumerate()
+                    .map(|(index, elem)| format!("P{index} {}", self.type_(elem)))
+                    .join("; ");
+                format!("struct{{ {fields} }}")
+            }
+        }
+    }
+}
+
+fn external_type<'a>(name: &'a str, arity: usize) -> Document<'a> {
+    if arity == 0 {
+        return docvec!["type ", name, " = any"];
+    }
+    let params = (0..arity).map(|index| format!("T{index} any")).join(", ");
+    docvec![
+        "type ",
+        name,
+        "[",
+        Document::String(params),
+        "] interface{}"
+    ]
+}
+
+fn qualified(qualifier: Option<String>, name: &str) -> String {
+    match qualifier {
+        Some(qualifier) => format!("{qualifier}.{name}"),
+        None => name.into(),
+    }
+}
+
+fn type_args(args: &[String]) -> String {
+    if args.is_empty() {
+        String::new()
+    } else {
+        format!("[{}]", args.join(", "))
+    }
+}
+
+/// The name of the field of a record, which is its label if it has one.
+fn field_name(label: Option<&str>, index: usize) -> String {
+    match label {
+        Some(label) => keywords::exported(label),
+        None => format!("P{index}"),
+    }
+}
+
+/// The ids of the type variables of the type in the order they first appear.
+fn type_variables(type_: &Type) -> Vec<u64> {
+    fn collect(type_: &Type, ids: &mut Vec<u64>) {
+        match type_ {
+            Type::App { args, .. } => args.iter().for_each(|arg| collect(arg, ids)),
+            Type::Fn { args, retrn } => {
+                args.iter().for_each(|arg| collect(arg, ids));
+                collect(retrn, ids);
+            }
+            Type::Tuple { elems } => elems.iter().for_each(|elem| collect(elem, ids)),
+            Type::Var { type_ } => match &*type_.borrow() {
+                TypeVar::Link { type_ } => collect(type_, ids),
+                TypeVar::Unbound { id } | TypeVar::Generic { id } => {
+                    if !ids.contains(id) {
+                        ids.push(*id);
+                    }
+                }
+            },
+        }
+    }
+    let mut ids = vec![];
+    collect(type_, &mut ids);
+    ids
+}
@@ -0,0 +1,1323 @@
+use super::*;
+use crate::type_::{FieldMap, ModuleValueConstructor, PatternConstructor};
+
+/// The checks that a value must pass to match a pattern, and the variables
+/// the pattern assigns.
+#[derive(Debug, Default)]
+struct CompiledPattern<'a> {
+    checks: Vec<String>,
+    bindings: Vec<Binding<'a>>,
+}
+
+#[derive(Debug)]
+struct Binding<'a> {
+    name: &'a str,
+    location: SrcSpan,
+    value: String,
+}
+
+impl<'a> Generator<'a> {
+    /// The statements of the body of a function, ending with a `return`.
+    pub(super) fn function_body(&mut self, body: &'a TypedExpr) -> Output<'a> {
+        self.tail(body)
+    }
+
+    /// Statements that return the value of the expression.
+    fn tail(&mut self, expression: &'a TypedExpr) -> Output<'a> {
+        match expression {
+            TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                let Some((last, rest)) = expressions.split_last() else {
+                    return Ok(nil());
+                };
+                let mut statements = vec![];
+                for expression in rest {
+                    statements.push(self.expression_statement(expression)?);
+                }
+                statements.push(self.tail(last)?);
+                Ok(join(statements, line()))
+            }
+
+            TypedExpr::Assignment {
+                value,
+                pattern,
+                location,
+                message,
+                ..
+            } => self.assignment(value, pattern, *location, message.as_deref(), true),
+
+            TypedExpr::Case {
+                subjects, clauses, ..
+            } => self.case(subjects, clauses),
+
+            _ => Ok(docvec!["return ", self.expression(expression)?]),
+        }
+    }
+
+    /// A statement that evaluates the expression, discarding its value.
+    fn expression_statement(&mut self, expression: &'a TypedExpr) -> Output<'a> {
+        match expression {
+            TypedExpr::Assignment {
+                value,
+                pattern,
+                location,
+                message,
+                ..
+            } => self.assignment(value, pattern, *location, message.as_deref(), false),
+
+            TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                let scope = self.scope.clone();
+                let statements: Vec<_> = expressions
+                    .iter()
+                    .map(|expression| self.expression_statement(expression))
+                    .try_collect()?;
+                self.scope = scope;
+                Ok(docvec![
+                    "{",
+                    docvec![line(), join(statements, line())].nest(INDENT),
+                    line(),
+                    "}"
+                ])
+            }
+
+            TypedExpr::Call { fun, .. } if record_constructor(fun).is_none() => {
+                self.expression(expression)
+            }
+
+            TypedExpr::Todo { .. } | TypedExpr::Echo { .. } => self.expression(expression),
+
+            _ => Ok(docvec!["_ = ", self.expression(expression)?]),
+        }
+    }
+
+    fn expression(&mut self, expression: &'a TypedExpr) -> Output<'a> {
+        match expression {
+            TypedExpr::Int { value, .. } => Ok(Document::String(int(value))),
+            TypedExpr::Float { value, .. } => Ok(Document::String(value.clone())),
+            TypedExpr::String { value, .. } => Ok(Document::String(string(value))),
+
+            TypedExpr::Cfg { enabled, .. } => Ok(if *enabled { "true" } else { "false" }.to_doc()),
+
+            TypedExpr::Var {
+                name, constructor, ..
+            } => Ok(self.variable(name, constructor)),
+
+            TypedExpr::Fn {
+                typ, args, body, ..
+            } => self.closure(typ, args, body),
+
+            TypedExpr::List {
+                typ,
+                elements,
+                tail,
+                ..
+            } => self.list(typ, elements, tail.as_deref()),
+
+            TypedExpr::Call { fun, args, typ, .. } => self.call(fun, args, typ),
+
+            TypedExpr::BinOp {
+                name, left, right, ..
+            } => self.bin_op(*name, left, right),
+
+            TypedExpr::Sequence { .. }
+            | TypedExpr::Pipeline { .. }
+            | TypedExpr::Assignment { .. }
+            | TypedExpr::Case { .. } => self.immediately_invoked_function(expression),
+
+            TypedExpr::Try { location, .. } => unsupported("Try expressions", *location),
+
+            TypedExpr::BitString { location, .. } => unsupported("Bit strings", *location),
+
+            TypedExpr::RecordAccess { label, record, .. } => Ok(docvec![
+                self.expression(record)?,
+                ".Get",
+                Document::String(keywords::exported(label)),
+                "()"
+            ]),
+
+            TypedExpr::ModuleSelect {
+                typ,
+                label,
+                module_name,
+                constructor,
+                ..
+            } => Ok(self.module_select(typ, label, module_name, constructor)),
+
+            TypedExpr::Tuple { typ, elems, .. } => {
+                let type_ = self.type_(typ);
+                let elements: Vec<_> = elems
+                    .iter()
+                    .map(|elem| self.expression(elem))
+                    .try_collect()?;
+                Ok(docvec![
+                    Document::String(type_),
+                    "{",
+                    join(elements, ", ".to_doc()),
+                    "}"
+                ])
+            }
+
+            TypedExpr::TupleIndex { index, tuple, .. } => Ok(docvec![
+                self.expression(tuple)?,
+                Document::String(format!(".P{index}"))
+            ]),
+
+            TypedExpr::Todo {
+                label,
+                location,
+                typ,
+            } => {
+                let message = label
+                    .as_deref()
+                    .unwrap_or("This has not yet been implemented");
+                Ok(self.prelude_call(
+                    "Todo",
+                    typ,
+                    vec![
+                        Document::String(string(message)),
+                        Document::String(string(&self.module.name.join("/"))),
+                        self.line_numbers.line_number(location.start).to_doc(),
+                    ],
+                ))
+            }
+
+            TypedExpr::RecordUpdate {
+                typ, spread, args, ..
+            } => {
+                let mut arguments = vec![self.expression(spread)?];
+                for arg in args {
+                    arguments.push(Document::String(format!(
+                        "\"{}\"",
+                        keywords::exported(&arg.label)
+                    )));
+                    arguments.push(self.typed_value(&arg.value)?);
+                }
+                Ok(self.prelude_call("Update", typ, arguments))
+            }
+
+            TypedExpr::Negate { value, .. } => Ok(docvec!["!", self.operand(value)?]),
+
+            TypedExpr::Echo { value, location } => {
+                let typ = value.type_();
+                let printed = type_::pretty::Printer::new().pretty_print(&typ, 0);
+                let arguments = vec![
+                    self.expression(value)?,
+                    Document::String(string(&self.module.name.join("/"))),
+                    self.line_numbers.line_number(location.start).to_doc(),
+                    Document::String(string(&printed)),
+                ];
+                Ok(self.prelude_call("Echo", &typ, arguments))
+            }
+        }
+    }
+
+    /// A call to a generic function of the prelude, instantiated with the
+    /// given type.
+    fn prelude_call(
+        &mut self,
+        function: &str,
+        type_: &Type,
+        arguments: Vec<Document<'a>>,
+    ) -> Document<'a> {
+        let prelude = self.prelude();
+        let type_ = self.type_(type_);
+        docvec![
+            Document::String(format!("{prelude}.{function}[{type_}]")),
+            "(",
+            join(arguments, ", ".to_doc()),
+            ")"
+        ]
+    }
+
+    /// An expression that is a Go statement, in a function that is called
+    /// immediately so that it can be used as a value.
+    fn immediately_invoked_function(&mut self, expression: &'a TypedExpr) -> Output<'a> {
+        let type_ = self.type_(&expression.type_());
+        let scope = self.scope.clone();
+        let body = self.tail(expression)?;
+        self.scope = scope;
+        Ok(docvec![
+            Document::String(format!("func() {type_} {{")),
+            docvec![line(), body].nest(INDENT),
+            line(),
+            "}()"
+        ])
+    }
+
+    /// The operand of an operator, in parentheses if it is itself an
+    /// operator.
+    fn operand(&mut self, expression: &'a TypedExpr) -> Output<'a> {
+        let document = self.expression(expression)?;
+        Ok(match expression {
+            TypedExpr::BinOp { .. } => docvec!["(", document, ")"],
+            _ => document,
+        })
+    }
+
+    /// The value of an expression that is assigned to a variable. Integer
+    /// constants are converted to `int64` as they would otherwise be `int`.
+    fn typed_value(&mut self, expression: &'a TypedExpr) -> Output<'a> {
+        let document = self.expression(expression)?;
+        if is_untyped_constant(expression) && expression.type_().is_int() {
+            Ok(docvec!["int64(", document, ")"])
+        } else {
+            Ok(document)
+        }
+    }
+
+    fn bin_op(&mut self, name: BinOp, left: &'a TypedExpr, right: &'a TypedExpr) -> Output<'a> {
+        let operator = match name {
+            BinOp::And => "&&",
+            BinOp::Or => "||",
+            BinOp::LtInt | BinOp::LtFloat => "<",
+            BinOp::LtEqInt | BinOp::LtEqFloat => "<=",
+            BinOp::GtInt | BinOp::GtFloat => ">",
+            BinOp::GtEqInt | BinOp::GtEqFloat => ">=",
+            BinOp::AddInt | BinOp::AddFloat | BinOp::Concatenate => "+",
+            BinOp::SubInt | BinOp::SubFloat => "-",
+            BinOp::MultInt | BinOp::MultFloat => "*",
+
+            BinOp::Eq | BinOp::NotEq if !is_comparable(&left.type_()) => {
+                let prelude = self.prelude();
+                let equal = docvec![
+                    Document::String(format!("{prelude}.Equal(")),
+                    self.expression(left)?,
+                    ", ",
+                    self.expression(right)?,
+                    ")"
+                ];
+                return Ok(if name == BinOp::Eq {
+                    equal
+                } else {
+                    docvec!["!", equal]
+                });
+            }
+            BinOp::Eq => "==",
+            BinOp::NotEq => "!=",
+
+            BinOp::DivInt | BinOp::RemainderInt | BinOp::DivFloat => {
+                let function = match name {
+                    BinOp::DivInt => "DivideInt",
+                    BinOp::RemainderInt => "RemainderInt",
+                    _ => "DivideFloat",
+                };
+                let prelude = self.prelude();
+                return Ok(docvec![
+                    Document::String(format!("{prelude}.{function}(")),
+                    self.expression(left)?,
+                    ", ",
+                    self.expression(right)?,
+                    ")"
+                ]);
+            }
+        };
+        Ok(docvec![
+            self.operand(left)?,
+            " ",
+            operator,
+            " ",
+            self.operand(right)?
+        ])
+    }
+
+    fn variable(&mut self, name: &'a str, constructor: &'a ValueConstructor) -> Document<'a> {
+        match &constructor.variant {
+            ValueConstructorVariant::LocalVariable { .. } => Document::String(
+                self.scope
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| keywords::escape(name)),
+            ),
+
+            ValueConstructorVariant::Record {
+                name, field_map, ..
+            } => self.record_constructor(name, field_map.as_ref(), &constructor.type_),
+
+            variant if variant.is_rescue() => {
+                let type_args = self.instantiation(&constructor.type_);
+                Document::String(format!("{}.Rescue{type_args}", self.prelude()))
+            }
+
+            ValueConstructorVariant::ModuleFn { .. }
+            | ValueConstructorVariant::ModuleConstant { .. } => {
+                Document::String(self.module_value(name, &constructor.type_))
+            }
+        }
+    }
+
+    /// A value defined in this module or imported unqualified from another,
+    /// along with the type arguments it is instantiated with if it is a
+    /// generic function.
+    fn module_value(&mut self, name: &str, type_: &Type) -> String {
+        if let Some((module, original)) = self.unqualified.get(name).copied() {
+            let qualifier = self.module_qualifier(module);
+            let type_args = self.instantiation(type_);
+            return format!(
+                "{}{type_args}",
+                qualified(qualifier, &keywords::exported(original))
+            );
+        }
+        let type_args = match self.functions.get(name).cloned() {
+            Some(declared) => self.declared_instantiation(&declared, type_),
+            None => String::new(),
+        };
+        format!("{}{type_args}", self.value_name(name))
+    }
+
+    fn module_select(
+        &mut self,
+        type_: &'a Type,
+        label: &'a str,
+        module_name: &'a str,
+        constructor: &'a ModuleValueConstructor,
+    ) -> Document<'a> {
+        match constructor {
+            ModuleValueConstructor::Record {
+                name,
+                field_map,
+                type_,
+                ..
+            } => self.record_constructor(name, field_map.as_ref(), type_),
+
+            ModuleValueConstructor::Fn { .. } | ModuleValueConstructor::Constant { .. } => {
+                let module = module_name.split('/').map(Name::from).collect_vec();
+                let qualifier = self.module_qualifier(&module);
+                let type_args = self.instantiation(type_);
+                Document::String(format!(
+                    "{}{type_args}",
+                    qualified(qualifier, &keywords::exported(label))
+                ))
+            }
+        }
+    }
+
+    /// The type arguments of an imported generic function, given its type
+    /// where it is used. The type of an imported function is instantiated
+    /// with a new type variable for each of its type parameters, so those are
+    /// the type variables of the type in the order they first appear.
+    fn instantiation(&mut self, type_: &Type) -> String {
+        fn collect<'t>(type_: &'t Type, variables: &mut Vec<&'t Arc<type_::TypeVarCell>>) {
+            match type_ {
+                Type::App { args, .. } => args.iter().for_each(|arg| collect(arg, variables)),
+                Type::Fn { args, retrn } => {
+                    args.iter().for_each(|arg| collect(arg, variables));
+                    collect(retrn, variables);
+                }
+                Type::Tuple { elems } => elems.iter().for_each(|elem| collect(elem, variables)),
+                Type::Var { type_ } => {
+                    if !variables
+                        .iter()
+                        .any(|variable| Arc::ptr_eq(variable, type_))
+                    {
+                        variables.push(type_);
+                    }
+                }
+            }
+        }
+        let mut variables = vec![];
+        collect(type_, &mut variables);
+        let args = variables
+            .into_iter()
+            .map(|variable| {
+                self.type_(&Type::Var {
+                    type_: variable.clone(),
+                })
+            })
+            .collect_vec();
+        type_args(&args)
+    }
+
+    /// The type arguments of a generic function of this module, matching
+    /// its declared type with its type where it is used.
+    fn declared_instantiation(&mut self, declared: &Type, type_: &Type) -> String {
+        fn matching(declared: &Type, type_: &Type, found: &mut HashMap<u64, Type>) {
+            if let Type::Var { type_: variable } = declared {
+                match &*variable.borrow() {
+                    TypeVar::Link { type_: declared } => matching(declared, type_, found),
+                    TypeVar::Unbound { id } | TypeVar::Generic { id } => {
+                        let _ = found.entry(*id).or_insert_with(|| type_.clone());
+                    }
+                }
+                return;
+            }
+            if let Type::Var { type_: variable } = type_ {
+                if let TypeVar::Link { type_ } = &*variable.borrow() {
+                    matching(declared, type_, found);
+                }
+                return;
+            }
+            match (declared, type_) {
+                (Type::App { args: declared, .. }, Type::App { args, .. })
+                | (Type::Tuple { elems: declared }, Type::Tuple { elems: args }) => {
+                    for (declared, arg) in declared.iter().zip(args) {
+                        matching(declared, arg, found);
+                    }
+                }
+                (
+                    Type::Fn {
+                        args: declared,
+                        retrn: declared_return,
+                    },
+                    Type::Fn { args, retrn },
+                ) => {
+                    for (declared, arg) in declared.iter().zip(args) {
+                        matching(declared, arg, found);
+                    }
+                    matching(declared_return, retrn, found);
+                }
+                _ => (),
+            }
+        }
+        let mut found = HashMap::new();
+        matching(declared, type_, &mut found);
+        let args = type_variables(declared)
+            .into_iter()
+            .map(|id| match found.get(&id) {
+                Some(type_) => self.type_(type_),
+                None => "any".into(),
+            })
+            .collect_vec();
+        type_args(&args)
+    }
+
+    fn closure(
+        &mut self,
+        type_: &'a Type,
+        args: &'a [TypedArg],
+        body: &'a TypedExpr,
+    ) -> Output<'a> {
+        let return_type = match resolve(type_).fn_types() {
+            Some((_, retrn)) => self.type_(&retrn),
+            None => "any".into(),
+        };
+        let scope = self.scope.clone();
+        let arguments = args
+            .iter()
+            .map(|arg| {
+                let name = match arg.get_variable_name() {
+                    Some(name) => self.declare(name),
+                    None => "_".into(),
+                };
+                format!("{name} {}", self.type_(&arg.type_))
+            })
+            .join(", ");
+        let body = self.tail(body)?;
+        self.scope = scope;
+        Ok(docvec![
+            Document::String(format!("func({arguments}) {return_type} {{")),
+            docvec![line(), body].nest(INDENT),
+            line(),
+            "}"
+        ])
+    }
+
+    fn list(
+        &mut self,
+        type_: &'a Type,
+        elements: &'a [TypedExpr],
+        tail: Option<&'a TypedExpr>,
+    ) -> Output<'a> {
+        let element_type = self.element_type(type_);
+        let prelude = self.prelude();
+        let mut arguments = vec![];
+        let function = match tail {
+            Some(tail) => {
+                arguments.push(self.expression(tail)?);
+                "Prepend"
+            }
+            None => "ToList",
+        };
+        for element in elements {
+            arguments.push(self.expression(element)?);
+        }
+        Ok(docvec![
+            Document::String(format!("{prelude}.{function}[{element_type}](")),
+            join(arguments, ", ".to_doc()),
+            ")"
+        ])
+    }
+
+    /// The Go type of the elements of a list type.
+    fn element_type(&mut self, list: &Type) -> String {
+        match resolve(list).as_ref() {
+            Type::App { args, .. } => match args.as_slice() {
+                [element] => self.type_(element),
+                _ => "any".into(),
+            },
+            _ => "any".into(),
+        }
+    }
+
+    fn call(
+        &mut self,
+        fun: &'a TypedExpr,
+        args: &'a [CallArg<TypedExpr>],
+        type_: &'a Type,
+    ) -> Output<'a> {
+        let arguments: Vec<_> = args
+            .iter()
+            .map(|arg| self.expression(&arg.value))
+            .try_collect()?;
+        if let Some((name, field_map)) = record_constructor(fun) {
+            return Ok(self.record(name, field_map, type_, arguments));
+        }
+        Ok(docvec![
+            self.expression(fun)?,
+            "(",
+            join(arguments, ", ".to_doc()),
+            ")"
+        ])
+    }
+
+    /// A record constructor used as a value, which is a function unless the
+    /// record has no fields.
+    fn record_constructor(
+        &mut self,
+        name: &'a str,
+        field_map: Option<&'a FieldMap>,
+        type_: &Type,
+    ) -> Document<'a> {
+        let type_ = resolve(type_);
+        let Some((args, retrn)) = type_.fn_types() else {
+            return self.record(name, field_map, &type_, vec![]);
+        };
+        let names = (0..args.len())
+            .map(|index| format!("p{index}"))
+            .collect_vec();
+        let arguments = names
+            .iter()
+            .zip(&args)
+            .map(|(name, arg)| format!("{name} {}", self.type_(arg)))
+            .join(", ");
+        let return_type = self.type_(&retrn);
+        let fields = names.into_iter().map(Document::String).collect();
+        let record = self.record(name, field_map, &retrn, fields);
+        docvec![
+            Document::String(format!("func({arguments}) {return_type} {{")),
+            docvec![line(), "return ", record].nest(INDENT),
+            line(),
+            "}"
+        ]
+    }
+
+    /// A value of a record constructor, given the values of its fields.
+    fn record(
+        &mut self,
+        name: &'a str,
+        field_map: Option<&'a FieldMap>,
+        type_: &Type,
+        fields: Vec<Document<'a>>,
+    ) -> Document<'a> {
+        if type_.is_bool() {
+            return if name == "True" { "true" } else { "false" }.to_doc();
+        }
+        if type_.is_nil() {
+            return Document::String(format!("{}.Nil{{}}", self.prelude()));
+        }
+        let record_type = self.constructor_type(name, type_);
+        let fields = fields.into_iter().enumerate().map(|(index, value)| {
+            docvec![
+                Document::String(field_name(label(field_map, index), index)),
+                ": ",
+                value
+            ]
+        });
+        docvec![
+            Document::String(record_type),
+            "{",
+            join(fields, ", ".to_doc()),
+            "}"
+        ]
+    }
+
+    /// The Go type of the struct of a record constructor of the type, which
+    /// may be the type of the constructor function.
+    fn constructor_type(&mut self, name: &str, type_: &Type) -> String {
+        match resolve(type_).as_ref() {
+            Type::Fn { retrn, .. } => self.constructor_type(name, retrn),
+            Type::App { module, args, .. } => {
+                let qualifier = self.module_qualifier(module);
+                let args = args.iter().map(|arg| self.type_(arg)).collect_vec();
+                format!("{}{}", qualified(qualifier, name), type_args(&args))
+            }
+            _ => name.into(),
+        }
+    }
+
+    fn assignment(
+        &mut self,
+        value: &'a TypedExpr,
+        pattern: &'a TypedPattern,
+        location: SrcSpan,
+        message: Option<&'a str>,
+        tail: bool,
+    ) -> Output<'a> {
+        match pattern {
+            Pattern::Var {
+                name,
+                location: definition,
+                ..
+            } if !tail => {
+                let value = self.typed_value(value)?;
+                if self.references.contains(&definition.start) {
+                    let name = self.declare(name);
+                    Ok(docvec![Document::String(name), " := ", value])
+                } else {
+                    Ok(docvec!["_ = ", value])
+                }
+            }
+
+            Pattern::Var { .. } | Pattern::Discard { .. } if tail => {
+                Ok(docvec!["return ", self.expression(value)?])
+            }
+
+            _ => {
+                let mut statements = vec![];
+                let subject = match self.local_variable(value) {
+                    Some(name) => name,
+                    None => {
+                        let name = self.fresh("_subject");
+                        let value = self.typed_value(value)?;
+                        statements.push(docvec![Document::String(name.clone()), " := ", value]);
+                        name
+                    }
+                };
+                let mut compiled = CompiledPattern::default();
+                self.pattern(pattern, &subject, &mut compiled)?;
+                let used = self.used_names(&compiled, &HashSet::new());
+                let bindings = self.bindings(compiled.bindings, &used);
+
+                if !tail && compiled.checks.is_empty() && bindings.is_empty() {
+                    // The value is still evaluated for its side effects
+                    if let Some(declaration) = statements.pop() {
+                        let value = self.typed_value(value)?;
+                        let _ = declaration;
+                        statements.push(docvec!["_ = ", value]);
+                    }
+                    return Ok(join(statements, line()));
+                }
+
+                if !compiled.checks.is_empty() {
+                    let message = message.unwrap_or("Assignment pattern did not match");
+                    let line_number = self.line_numbers.line_number(location.start);
+                    let panic = format!(
+                        "panic({})",
+                        string(&format!(
+                            "{message} ({}:{line_number})",
+                            self.module.name.join("/")
+                        ))
+                    );
+                    statements.push(docvec![
+                        Document::String(format!("if !({}) {{", compiled.checks.join(" && "))),
+                        docvec![line(), Document::String(panic)].nest(INDENT),
+                        line(),
+                        "}"
+                    ]);
+                }
+                statements.extend(bindings);
+                if tail {
+                    statements.push(Document::String(format!("return {subject}")));
+                }
+                Ok(join(statements, line()))
+            }
+        }
+    }
+
+    /// The Go name of the local variable the expression is, if it is one.
+    fn local_variable(&self, expression: &TypedExpr) -> Option<String> {
+        match expression {
+            TypedExpr::Var {
+                name,
+                constructor:
+                    ValueConstructor {
+                        variant: ValueConstructorVariant::LocalVariable { .. },
+                        ..
+                    },
+                ..
+            } => self.scope.get(name.as_str()).cloned(),
+            _ => None,
+        }
+    }
+
+    /// The names of the variables assigned by the pattern that are used,
+    /// along with those used by the clause's guard.
+    fn used_names(
+        &self,
+        compiled: &CompiledPattern<'a>,
+        guard_names: &HashSet<&'a str>,
+    ) -> HashSet<&'a str> {
+        compiled
+            .bindings
+            .iter()
+            .filter(|binding| self.references.contains(&binding.location.start))
+            .map(|binding| binding.name)
+            .chain(guard_names.iter().copied())
+            .collect()
+    }
+
+    /// Declares the variables assigned by a pattern that are used.
+    fn bindings(
+        &mut self,
+        bindings: Vec<Binding<'a>>,
+        used: &HashSet<&'a str>,
+    ) -> Vec<Document<'a>> {
+        bindings
+            .into_iter()
+            .filter(|binding| used.contains(binding.name))
+            .map(|binding| {
+                let name = self.declare(binding.name);
+                Document::String(format!("{name} := {}", binding.value))
+            })
+            .collect()
+    }
+
+    /// A case expression, as `if` statements for each clause that return the
+    /// value of the clause. Clauses with alternative patterns have an `if`
+    /// statement for each pattern.
+    fn case(&mut self, subjects: &'a [TypedExpr], clauses: &'a [TypedClause]) -> Output<'a> {
+        let mut subject_names = vec![];
+        let mut subject_values = vec![];
+        for subject in subjects {
+            match self.local_variable(subject) {
+                Some(name) => {
+                    subject_names.push(name);
+                    subject_values.push(None);
+                }
+                None => {
+                    subject_names.push(self.fresh("_subject"));
+                    subject_values.push(Some(subject));
+                }
+            }
+        }
+
+        let mut subjects_used = vec![false; subjects.len()];
+        let mut branches = vec![];
+        let mut exhaustive = false;
+        'clauses: for clause in clauses {
+            let mut guard_names = HashSet::new();
+            if let Some(guard) = &clause.guard {
+                guard_variables(guard, &mut guard_names);
+            }
+            let mut first_compiled = vec![];
+            for pattern in &clause.pattern {
+                let mut compiled = CompiledPattern::default();
+                self.pattern(pattern, "", &mut compiled)?;
+                first_compiled.push(compiled);
+            }
+            let used = first_compiled
+                .iter()
+                .flat_map(|compiled| self.used_names(compiled, &guard_names))
+                .collect::<HashSet<_>>();
+
+            for patterns in std::iter::once(&clause.pattern).chain(&clause.alternative_patterns) {
+                let scope = self.scope.clone();
+                let mut checks = vec![];
+                let mut bindings = vec![];
+                for ((pattern, subject), subject_used) in
+                    patterns.iter().zip(&subject_names).zip(&mut subjects_used)
+                {
+                    let mut compiled = CompiledPattern::default();
+                    self.pattern(pattern, subject, &mut compiled)?;
+                    let declared = self.bindings(compiled.bindings, &used);
+                    *subject_used |= !compiled.checks.is_empty() || !declared.is_empty();
+                    checks.extend(compiled.checks);
+                    bindings.extend(declared);
+                }
+                let guard = match &clause.guard {
+                    Some(guard) => Some(self.guard(guard)?),
+                    None => None,
+                };
+                let body = self.tail(&clause.then)?;
+                self.scope = scope;
+
+                let body = match guard {
+                    Some(guard) => docvec![
+                        "if ",
+                        guard,
+                        " {",
+                        docvec![line(), body].nest(INDENT),
+                        line(),
+                        "}"
+                    ],
+                    None => body,
+                };
+                let statements = join(bindings.into_iter().chain([body]), line());
+                let opening = if checks.is_empty() {
+                    "{".to_string()
+                } else {
+                    format!("if {} {{", checks.join(" && "))
+                };
+                branches.push(docvec![
+                    Document::String(opening),
+                    docvec![line(), statements].nest(INDENT),
+                    line(),
+                    "}"
+                ]);
+                if checks.is_empty() && clause.guard.is_none() {
+                    exhaustive = true;
+                    break 'clauses;
+                }
+            }
+        }
+        if !exhaustive {
+            branches.push(r#"panic("No case clause matched")"#.to_doc());
+        }
+
+        let mut statements = vec![];
+        for ((name, value), used) in subject_names.iter().zip(subject_values).zip(subjects_used) {
+            let Some(value) = value else { continue };
+            let value = self.typed_value(value)?;
+            statements.push(if used {
+                docvec![Document::String(name.clone()), " := ", value]
+            } else {
+                docvec!["_ = ", value]
+            });
+        }
+        statements.extend(branches);
+        Ok(join(statements, line()))
+    }
+
+    fn pattern(
+        &mut self,
+        pattern: &'a TypedPattern,
+        subject: &str,
+        compiled: &mut CompiledPattern<'a>,
+    ) -> Result<(), Error> {
+        match pattern {
+            Pattern::Int { value, .. } => {
+                compiled.checks.push(format!("{subject} == {}", int(value)));
+            }
+
+            Pattern::Float { value, .. } => {
+                compiled.checks.push(format!("{subject} == {value}"));
+            }
+
+            Pattern::String { value, .. } => {
+                compiled
+                    .checks
+                    .push(format!("{subject} == {}", string(value)));
+            }
+
+            Pattern::IntRange { start, end, .. } => {
+                compiled.checks.push(format!(
+                    "{subject} >= {} && {subject} <= {}",
+                    int(start),
+                    int(end)
+                ));
+            }
+
+            Pattern::Var { name, location } => compiled.bindings.push(Binding {
+                name,
+                location: *location,
+                value: subject.into(),
+            }),
+
+            Pattern::Assign {
+                name,
+                location,
+                pattern,
+            } => {
+                compiled.bindings.push(Binding {
+                    name,
+                    location: *location,
+                    value: subject.into(),
+                });
+                self.pattern(pattern, subject, compiled)?;
+            }
+
+            Pattern::Discard { .. } => (),
+
+            Pattern::List { elements, tail, .. } => {
+                let mut list = subject.to_string();
+                for element in elements {
+                    compiled.checks.push(format!("{list} != nil"));
+                    self.pattern(element, &format!("{list}.Head"), compiled)?;
+                    list = format!("{list}.Tail");
+                }
+                match tail {
+                    Some(tail) => self.pattern(tail, &list, compiled)?,
+                    None => compiled.checks.push(format!("{list} == nil")),
+                }
+            }
+
+            Pattern::Tuple { elems, .. } => {
+                for (index, elem) in elems.iter().enumerate() {
+                    self.pattern(elem, &format!("{subject}.P{index}"), compiled)?;
+                }
+            }
+
+            Pattern::Constructor {
+                constructor:
+                    PatternConstructor::Record {
+                        name, field_map, ..
+                    },
+                arguments,
+                type_,
+                ..
+            } => {
+                if type_.is_bool() {
+                    compiled.checks.push(if name == "True" {
+                        subject.into()
+                    } else {
+                        format!("!{subject}")
+                    });
+                    return Ok(());
+                }
+                if type_.is_nil() {
+                    return Ok(());
+                }
+                let prelude = self.prelude();
+                let record_type = self.constructor_type(name, type_);
+                compiled
+                    .checks
+                    .push(format!("{prelude}.Is[{record_type}]({subject})"));
+                let record = format!("{prelude}.As[{record_type}]({subject})");
+                for (index, arg) in arguments.iter().enumerate() {
+                    let field = field_name(label(field_map.as_ref(), index), index);
+                    self.pattern(&arg.value, &format!("{record}.{field}"), compiled)?;
+                }
+            }
+
+            Pattern::Concatenate {
+                left_side_string,
+                right_side_assignment,
+                right_location,
+                ..
+            } => {
+                let prefix = string(left_side_string);
+                let prelude = self.prelude();
+                compiled
+                    .checks
+                    .push(format!("{prelude}.HasPrefix({subject}, {prefix})"));
+                if let AssignName::Variable(name) = right_side_assignment {
+                    compiled.bindings.push(Binding {
+                        name,
+                        location: *right_location,
+                        value: format!("{subject}[len({prefix}):]"),
+                    });
+                }
+            }
+
+            Pattern::BitString { location, .. } | Pattern::VarUsage { location, .. } => {
+                return unsupported("Bit string patterns", *location);
+            }
+        }
+        Ok(())
+    }
+
+    fn guard(&mut self, guard: &'a TypedClauseGuard) -> Output<'a> {
+        let (left, operator, right) =
+            match guard {
+                ClauseGuard::Equals { left, right, .. }
+                | ClauseGuard::NotEquals { left, right, .. }
+                    if !is_comparable(&left.type_()) =>
+                {
+                    let prelude = self.prelude();
+                    let equal = docvec![
+                        Document::String(format!("{prelude}.Equal(")),
+                        self.guard(left)?,
+                        ", ",
+                        self.guard(right)?,
+                        ")"
+                    ];
+                    return Ok(match guard {
+                        ClauseGuard::Equals { .. } => equal,
+                        _ => docvec!["!", equal],
+                    });
+                }
+                ClauseGuard::Equals { left, right, .. } => (left, "==", right),
+                ClauseGuard::NotEquals { left, right, .. } => (left, "!=", right),
+                ClauseGuard::GtInt { left, right, .. }
+                | ClauseGuard::GtFloat { left, right, .. } => (left, ">", right),
+                ClauseGuard::GtEqInt { left, right, .. }
+                | ClauseGuard::GtEqFloat { left, right, .. } => (left, ">=", right),
+                ClauseGuard::LtInt { left, right, .. }
+                | ClauseGuard::LtFloat { left, right, .. } => (left, "<", right),
+                ClauseGuard::LtEqInt { left, right, .. }
+                | ClauseGuard::LtEqFloat { left, right, .. } => (left, "<=", right),
+                ClauseGuard::Or { left, right, .. } => (left, "||", right),
+                ClauseGuard::And { left, right, .. } => (left, "&&", right),
+
+                ClauseGuard::Var { name, .. } => {
+                    return Ok(Document::String(
+                        self.scope
+                            .get(name.as_str())
+                            .cloned()
+                            .unwrap_or_else(|| keywords::escape(name)),
+                    ))
+                }
+
+                ClauseGuard::TupleIndex { index, tuple, .. } => {
+                    return Ok(docvec![
+                        self.guard(tuple)?,
+                        Document::String(format!(".P{index}"))
+                    ])
+                }
+
+                ClauseGuard::Call {
+                    name, args, type_, ..
+                } => {
+                    let instantiated =
+                        type_::fn_(args.iter().map(|arg| arg.type_()).collect(), type_.clone());
+                    let function = self.module_value(name, &instantiated);
+                    let arguments: Vec<_> = args.iter().map(|arg| self.guard(arg)).try_collect()?;
+                    return Ok(docvec![
+                        Document::String(function),
+                        "(",
+                        join(arguments, ", ".to_doc()),
+                        ")"
+                    ]);
+                }
+
+                ClauseGuard::Constant(constant) => return self.constant(constant),
+            };
+        let operand = |generator: &mut Self, guard: &'a TypedClauseGuard| -> Output<'a> {
+            let document = generator.guard(guard)?;
+            Ok(match guard {
+                ClauseGuard::Var { .. }
+                | ClauseGuard::TupleIndex { .. }
+                | ClauseGuard::Call { .. }
+                | ClauseGuard::Constant(_) => document,
+                _ => docvec!["(", document, ")"],
+            })
+        };
+        Ok(docvec![
+            operand(self, left)?,
+            " ",
+            operator,
+            " ",
+            operand(self, right)?
+        ])
+    }
+
+    pub(super) fn constant(&mut self, constant: &'a TypedConstant) -> Output<'a> {
+        match constant {
+            Constant::Int { value, .. } => Ok(Document::String(int(value))),
+            Constant::Float { value, .. } => Ok(Document::String(value.clone())),
+
+            Constant::String { value, .. } => Ok(Document::String(string(value))),
+
+            Constant::Tuple { elements, .. } => {
+                let type_ = self.type_(&constant.type_());
+                let elements: Vec<_> = elements
+                    .iter()
+                    .map(|element| self.constant(element))
+                    .try_collect()?;
+                Ok(docvec![
+                    Document::String(type_),
+                    "{",
+                    join(elements, ", ".to_doc()),
+                    "}"
+                ])
+            }
+
+            Constant::List { elements, typ, .. } => {
+                let element_type = self.element_type(typ);
+                let prelude = self.prelude();
+                let elements: Vec<_> = elements
+                    .iter()
+                    .map(|element| self.constant(element))
+                    .try_collect()?;
+                Ok(docvec![
+                    Document::String(format!("{prelude}.ToList[{element_type}](")),
+                    join(elements, ", ".to_doc()),
+                    ")"
+                ])
+            }
+
+            Constant::Record {
+                tag,
+                args,
+                typ,
+                field_map,
+                ..
+            } => {
+                if args.is_empty() {
+                    return Ok(self.record_constructor(tag, field_map.as_ref(), typ));
+                }
+                let fields: Vec<_> = args
+                    .iter()
+                    .map(|arg| self.constant(&arg.value))
+                    .try_collect()?;
+                Ok(self.record(tag, field_map.as_ref(), typ, fields))
+            }
+
+            Constant::Var {
+                name,
+                module,
+                constructor,
+                typ,
+                ..
+            } => {
+                if let Some(ValueConstructor {
+                    variant:
+                        ValueConstructorVariant::Record {
+                            name, field_map, ..
+                        },
+                    type_,
+                    ..
+                }) = constructor.as_deref()
+                {
+                    return Ok(self.record_constructor(name, field_map.as_ref(), type_));
+                }
+                match module
+                    .as_deref()
+                    .and_then(|alias| self.module_aliases.get(alias))
+                {
+                    Some(module) => {
+                        let qualifier = self.module_qualifier(module);
+                        let type_args = self.instantiation(typ);
+                        Ok(Document::String(format!(
+                            "{}{type_args}",
+                            qualified(qualifier, &keywords::exported(name))
+                        )))
+                    }
+                    None => Ok(Document::String(self.module_value(name, typ))),
+                }
+            }
+
+            Constant::BitString { location, .. } => unsupported("Bit strings", *location),
+
+            Constant::BinOp { .. } => {
+                unreachable!("Constant binary operators are evaluated during type checking")
+            }
+        }
+    }
+}
+
+/// The name and fields of the record constructor that the expression is, if
+/// it is one.
+fn record_constructor(expression: &TypedExpr) -> Option<(&str, Option<&FieldMap>)> {
+    match expression {
+        TypedExpr::Var {
+            constructor:
+                ValueConstructor {
+                    variant:
+                        ValueConstructorVariant::Record {
+                            name, field_map, ..
+                        },
+                    ..
+                },
+            ..
+        }
+        | TypedExpr::ModuleSelect {
+            constructor:
+                ModuleValueConstructor::Record {
+                    name, field_map, ..
+                },
+            ..
+        } => Some((name, field_map.as_ref())),
+        _ => None,
+    }
+}
+
+/// The label of the field of a record at the index, if it has one.
+fn label(field_map: Option<&FieldMap>, index: usize) -> Option<&str> {
+    field_map?
+        .fields
+        .iter()
+        .find(|(_, field)| **field as usize == index)
+        .map(|(label, _)| label.as_str())
+}
+
+/// Whether values of the type can be compared with Go's `==` operator.
+fn is_comparable(type_: &Type) -> bool {
+    type_.is_int() || type_.is_float() || type_.is_string() || type_.is_bool()
+}
+
+/// Whether the expression is an integer constant, which Go gives the type
+/// `int` when it is assigned to a variable.
+fn is_untyped_constant(expression: &TypedExpr) -> bool {
+    match expression {
+        TypedExpr::Int { .. } => true,
+        TypedExpr::BinOp {
+            name: BinOp::AddInt | BinOp::SubInt | BinOp::MultInt,
+            left,
+            right,
+            ..
+        } => is_untyped_constant(left) && is_untyped_constant(right),
+        _ => false,
+    }
+}
+
+fn guard_variables<'a>(guard: &'a TypedClauseGuard, names: &mut HashSet<&'a str>) {
+    match guard {
+        ClauseGuard::Equals { left, right, .. }
+        | ClauseGuard::NotEquals { left, right, .. }
+        | ClauseGuard::GtInt { left, right, .. }
+        | ClauseGuard::GtEqInt { left, right, .. }
+        | ClauseGuard::LtInt { left, right, .. }
+        | ClauseGuard::LtEqInt { left, right, .. }
+        | ClauseGuard::GtFloat { left, right, .. }
+        | ClauseGuard::GtEqFloat { left, right, .. }
+        | ClauseGuard::LtFloat { left, right, .. }
+        | ClauseGuard::LtEqFloat { left, right, .. }
+        | ClauseGuard::Or { left, right, .. }
+        | ClauseGuard::And { left, right, .. } => {
+            guard_variables(left, names);
+            guard_variables(right, names);
+        }
+        ClauseGuard::Var { name, .. } => {
+            let _ = names.insert(name);
+        }
+        ClauseGuard::TupleIndex { tuple, .. } => guard_variables(tuple, names),
+        ClauseGuard::Call { args, .. } => {
+            for arg in args {
+                guard_variables(arg, names);
+            }
+        }
+        ClauseGuard::Constant(_) => (),
+    }
+}
+
+/// The type that a type variable has been linked to.
+fn resolve(type_: &Type) -> Arc<Type> {
+    match type_ {
+        Type::Var { type_: variable } => match &*variable.borrow() {
+            TypeVar::Link { type_ } => resolve(type_),
+            _ => Arc::new(type_.clone()),
+        },
+        _ => Arc::new(type_.clone()),
+    }
+}
+
+/// A Go integer literal. Go reads decimal literals with a leading zero as
+/// octal, so those zeros are removed.
+fn int(value: &str) -> String {
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", value),
+    };
+    if digits.starts_with("0x") || digits.starts_with("0o") || digits.starts_with("0b") {
+        return value.into();
+    }
+    let trimmed = digits.trim_start_matches(['0', '_']);
+    if trimmed.is_empty() {
+        "0".into()
+    } else {
+        format!("{sign}{trimmed}")
+    }
+}
+
+/// A Go string literal of the contents of a Gleam string.
+fn string(value: &str) -> String {
+    let mut literal = String::from("\"");
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => match chars.next() {
+                Some('e') => literal.push_str("\\x1b"),
+                Some(escaped) => {
+                    literal.push('\\');
+                    literal.push(escaped);
+                }
+                None => literal.push_str("\\\\"),
+            },
+            '"' => literal.push_str("\\\""),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            char => literal.push(char),
+        }
+    }
+    literal.push('"');
+    literal
+}
@@ -0,0 +1,81 @@
+mod custom_types;
+mod functions;
+mod generics;
+mod patterns;
+
+#[macro_export]
+macro_rules! assert_go {
+    ($src:expr $(,)?) => {{
+        let output = $crate::go::tests::compile($src).expect("should compile to Go");
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    }};
+}
+
+#[macro_export]
+macro_rules! assert_go_unsupported {
+    ($src:expr, $feature:expr $(,)?) => {{
+        match $crate::go::tests::compile($src) {
+            Err($crate::Error::Go {
+                error: $crate::go::Error::Unsupported { feature, .. },
+                ..
+            }) => assert_eq!(feature, $feature),
+            other => panic!("expected an unsupported feature error, got {other:?}"),
+        }
+    }};
+}
+
+pub fn compile(src: &str) -> Result<String, crate::Error> {
+    use crate::{
+        build::{Origin, Target},
+        line_numbers::LineNumbers,
+        pretty::DEFAULT_LINE_LENGTH,
+        type_::{build_prelude, infer_module},
+        uid::UniqueIdGenerator,
+    };
+    use std::path::Path;
+
+    let arena = crate::ast::Arena::new();
+    let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+    ast.name = vec!["my".into(), "module".into()];
+    let mut modules = im::HashMap::new();
+    let ids = UniqueIdGenerator::new();
+    let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+    let ast = infer_module(
+        Target::Go,
+        &ids,
+        ast,
+        Origin::Src,
+        "my_package",
+        &modules,
+        &mut vec![],
+    )
+    .expect("should successfully infer");
+    let mut output = String::new();
+    let line_numbers = LineNumbers::new(src);
+    super::module(
+        &ast,
+        &line_numbers,
+        Path::new("src/my/module.gleam"),
+        src,
+        DEFAULT_LINE_LENGTH,
+        &mut output,
+    )?;
+    Ok(output)
+}
+
+#[test]
+fn module_path() {
+    assert_eq!(
+        super::module_path("gleam/list"),
+        std::path::PathBuf::from("gleam/list/list.go")
+    );
+    assert_eq!(
+        super::module_path("app"),
+        std::path::PathBuf::from("app/app.go")
+    );
+}
+
+#[test]
+fn entrypoint() {
+    insta::assert_snapshot!(super::entrypoint("my/app"));
+}
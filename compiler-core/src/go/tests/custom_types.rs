@@ -0,0 +1,133 @@
+use crate::{assert_go, assert_go_unsupported};
+
+#[test]
+fn single_constructor_record() {
+    assert_go!(
+        r#"
+pub type Point {
+  Point(x: Float, y: Float)
+}
+
+pub fn origin() -> Point {
+  Point(0.0, 0.0)
+}
+
+pub fn x(point: Point) -> Float {
+  point.x
+}
+"#
+    );
+}
+
+#[test]
+fn multiple_constructors() {
+    assert_go!(
+        r#"
+pub type Shape {
+  Circle(radius: Float)
+  Rectangle(width: Float, height: Float)
+  Dot
+}
+
+pub fn new_circle() -> Shape {
+  Circle(radius: 1.0)
+}
+
+pub fn new_dot() -> Shape {
+  Dot
+}
+"#
+    );
+}
+
+#[test]
+fn shared_labels() {
+    assert_go!(
+        r#"
+pub type Pet {
+  Cat(name: String, lives: Int)
+  Dog(name: String)
+}
+
+pub fn name(pet: Pet) -> String {
+  pet.name
+}
+"#
+    );
+}
+
+#[test]
+fn unlabelled_fields() {
+    assert_go!(
+        r#"
+pub type Pair {
+  Pair(Int, String)
+}
+
+pub fn make() -> Pair {
+  Pair(1, "one")
+}
+"#
+    );
+}
+
+#[test]
+fn record_update() {
+    assert_go!(
+        r#"
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn birthday(person: Person) -> Person {
+  Person(..person, age: 30)
+}
+"#
+    );
+}
+
+#[test]
+fn constructor_as_function() {
+    assert_go!(
+        r#"
+pub type Box {
+  Box(value: Int)
+}
+
+pub fn constructor() -> fn(Int) -> Box {
+  Box
+}
+
+pub fn ok() -> fn(Int) -> Result(Int, Nil) {
+  Ok
+}
+"#
+    );
+}
+
+#[test]
+fn external_types() {
+    assert_go!(
+        r#"
+pub external type Handle
+pub external type Channel(a)
+"#
+    );
+}
+
+#[test]
+fn values_named_like_constructors_are_unsupported() {
+    assert_go_unsupported!(
+        r#"
+pub type Shape {
+  Circle(radius: Float)
+  Square(side: Float)
+}
+
+pub fn circle() -> Shape {
+  Circle(1.0)
+}
+"#,
+        "A value with the same name as a type or record constructor"
+    );
+}
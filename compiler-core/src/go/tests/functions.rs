@@ -0,0 +1,183 @@
+use crate::{assert_go, assert_go_unsupported};
+
+#[test]
+fn exported_functions() {
+    assert_go!(
+        r#"
+pub fn add(x: Int, y: Int) -> Int {
+  x + y
+}
+"#
+    );
+}
+
+#[test]
+fn private_functions() {
+    assert_go!(
+        r#"
+fn add(x: Int, y: Int) -> Int {
+  x + y
+}
+"#
+    );
+}
+
+#[test]
+fn reserved_names() {
+    assert_go!(
+        r#"
+fn len(string: String, nil: String) -> String {
+  let int = string <> nil
+  int
+}
+"#
+    );
+}
+
+#[test]
+fn shadowed_variables() {
+    assert_go!(
+        r#"
+pub fn shadow(x: Int) -> Int {
+  let x = x + 1
+  let x = x * 2
+  x
+}
+"#
+    );
+}
+
+#[test]
+fn unused_variables() {
+    assert_go!(
+        r#"
+pub fn main() -> Int {
+  let x = 1
+  let _ = "two"
+  3
+}
+"#
+    );
+}
+
+#[test]
+fn anonymous_functions() {
+    assert_go!(
+        r#"
+pub fn compose(f: fn(Int) -> Float, g: fn(Float) -> String) -> fn(Int) -> String {
+  fn(x) { g(f(x)) }
+}
+"#
+    );
+}
+
+#[test]
+fn blocks_as_values() {
+    assert_go!(
+        r#"
+pub fn main() -> Int {
+  let x = {
+    let y = 1
+    y + 2
+  }
+  x * 3
+}
+"#
+    );
+}
+
+#[test]
+fn pipelines() {
+    assert_go!(
+        r#"
+fn double(x: Int) -> Int {
+  x * 2
+}
+
+pub fn main() -> Int {
+  1
+  |> double
+  |> double
+}
+"#
+    );
+}
+
+#[test]
+fn operators() {
+    assert_go!(
+        r#"
+pub fn main() {
+  #(1 / 2, 1 % 2, 1.0 /. 2.0, "a" <> "b", 1 + 2 * 3, !True, 1 == 2, [1] == [2])
+}
+"#
+    );
+}
+
+#[test]
+fn constants() {
+    assert_go!(
+        r#"
+pub const limit = 10
+pub const names = ["a", "b"]
+pub const pair = #(1, "one")
+const leading_zeros = 007
+
+pub fn main() {
+  #(limit, names, pair, leading_zeros)
+}
+"#
+    );
+}
+
+#[test]
+fn string_escapes() {
+    assert_go!(
+        r#"
+pub fn main() -> String {
+  "\e[1m\"bold\"\e[0m\n"
+}
+"#
+    );
+}
+
+#[test]
+fn todo_and_echo() {
+    assert_go!(
+        r#"
+pub fn main() -> Int {
+  echo 1
+  todo("later")
+}
+"#
+    );
+}
+
+#[test]
+fn external_functions() {
+    assert_go!(
+        r#"
+@external(go, "gleam/ffi", "Print")
+pub fn print(message: String) -> Nil {
+  Nil
+}
+
+pub fn main() -> Nil {
+  print("Hello")
+}
+"#
+    );
+}
+
+#[test]
+fn try_is_unsupported() {
+    assert_go_unsupported!(
+        r#"
+pub fn main(x: Result(Int, Nil)) -> Result(Int, Nil) {
+  try y = x
+  Ok(y)
+}
+"#,
+        "Try expressions"
+    );
+}
@@ -0,0 +1,97 @@
+use crate::assert_go;
+
+#[test]
+fn generic_functions() {
+    assert_go!(
+        r#"
+pub fn identity(x: a) -> a {
+  x
+}
+
+pub fn main() -> Int {
+  identity(1)
+}
+"#
+    );
+}
+
+#[test]
+fn generic_types() {
+    assert_go!(
+        r#"
+pub type Tree(a) {
+  Leaf
+  Node(left: Tree(a), value: a, right: Tree(a))
+}
+
+pub fn singleton(x: a) -> Tree(a) {
+  Node(Leaf, x, Leaf)
+}
+"#
+    );
+}
+
+#[test]
+fn generic_recursion() {
+    assert_go!(
+        r#"
+pub fn map(list: List(a), f: fn(a) -> b) -> List(b) {
+  case list {
+    [] -> []
+    [x, ..xs] -> [f(x), ..map(xs, f)]
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn type_parameter_names_avoid_types() {
+    assert_go!(
+        r#"
+pub type A {
+  A
+}
+
+pub fn pair(x: a, y: b) -> #(a, b, A) {
+  #(x, y, A)
+}
+"#
+    );
+}
+
+#[test]
+fn generic_externals() {
+    assert_go!(
+        r#"
+@external(go, "gleam/ffi", "First")
+pub fn first(list: List(a)) -> a {
+  todo
+}
+"#
+    );
+}
+
+#[test]
+fn process_externals() {
+    assert_go!(
+        r#"
+pub external type Pid
+
+@external(go, "gleam/gleam", "Spawn")
+pub fn spawn(f: fn(Pid) -> Nil) -> Pid {
+  todo
+}
+
+@external(go, "gleam/gleam", "Send")
+pub fn send(pid: Pid, message: a) -> Nil {
+  todo
+}
+
+@external(go, "gleam/gleam", "Receive")
+pub fn receive(pid: Pid, timeout: Int) -> Result(a, Nil) {
+  todo
+}
+"#
+    );
+}
@@ -0,0 +1,121 @@
+use crate::assert_go;
+
+#[test]
+fn literal_patterns() {
+    assert_go!(
+        r#"
+pub fn classify(n: Int) -> String {
+  case n {
+    0 -> "zero"
+    1 | 2 -> "small"
+    _ -> "other"
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn guards() {
+    assert_go!(
+        r#"
+pub fn sign(n: Int) -> Int {
+  case n {
+    _ if n > 0 -> 1
+    _ if n < 0 -> -1
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn list_patterns() {
+    assert_go!(
+        r#"
+pub fn sum(list: List(Int)) -> Int {
+  case list {
+    [] -> 0
+    [x] -> x
+    [x, ..xs] -> x + sum(xs)
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn tuple_patterns() {
+    assert_go!(
+        r#"
+pub fn both(a: Bool, b: Bool) -> String {
+  case a, b {
+    True, True -> "both"
+    True, False | False, True -> "one"
+    False, False -> "neither"
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn constructor_patterns() {
+    assert_go!(
+        r#"
+pub fn unwrap(result: Result(Int, String)) -> Int {
+  case result {
+    Ok(n) if n > 0 -> n
+    Ok(_) -> 0
+    Error(_) -> -1
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn string_prefix_patterns() {
+    assert_go!(
+        r#"
+pub fn greeting(s: String) -> String {
+  case s {
+    "Hello, " <> name -> name
+    _ -> "stranger"
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn assignment_patterns() {
+    assert_go!(
+        r#"
+pub fn first(pair: #(Int, String), list: List(Int)) -> Int {
+  let #(a, _) = pair
+  assert [b, ..] = list
+  a + b
+}
+"#
+    );
+}
+
+#[test]
+fn case_subject_expressions() {
+    assert_go!(
+        r#"
+fn number() -> Int {
+  1
+}
+
+pub fn main() -> Int {
+  case number() {
+    1 -> 2
+    n -> n
+  }
+}
+"#
+    );
+}
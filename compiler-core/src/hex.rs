@@ -2,14 +2,16 @@ use debug_ignore::DebugIgnore;
 use flate2::read::GzDecoder;
 use futures::future;
 use hexpm::version::{PackageVersions, Version};
-use std::path::Path;
+use itertools::Itertools;
+use std::{cell::Cell, collections::HashMap, path::Path};
 use tar::Archive;
+use tracing::Instrument;
 
 use crate::{
-    build::Mode,
-    config::PackageConfig,
+    build::{Mode, Telemetry},
+    config::{PackageConfig, RegistryConfig},
     io::{FileSystemIO, HttpClient, TarUnpacker},
-    manifest::{Manifest, ManifestPackage, ManifestPackageSource},
+    manifest::{ManifestPackage, ManifestPackageSource},
     paths, Error, Result,
 };
 
@@ -24,28 +26,172 @@ J1i2xWFndWa6nfFnRxZmCStCOZWYYPlaxr+FZceFbpMwzTNs4g3d4tLNUcbKAIH4
 -----END PUBLIC KEY-----
 ";
 
+/// The name of the public Hex registry, hex.pm.
+pub const HEXPM: &str = "hexpm";
+
+/// A Hex compatible registry that packages are resolved from, downloaded
+/// from, and published to.
+#[derive(Debug, Clone)]
+pub struct Registry {
+    pub name: String,
+    pub config: hexpm::Config,
+    pub public_key: Vec<u8>,
+    /// The key to authenticate with, if the registry requires one.
+    pub api_key: Option<String>,
+}
+
+impl Registry {
+    pub fn hexpm() -> Self {
+        Self {
+            name: HEXPM.into(),
+            config: hexpm::Config::new(),
+            public_key: HEXPM_PUBLIC_KEY.to_vec(),
+            api_key: None,
+        }
+    }
+
+    pub fn new(name: &str, config: &RegistryConfig, api_key: Option<String>) -> Result<Self> {
+        let uri = |url: &str| {
+            url.parse::<http::Uri>()
+                .map_err(|error| Error::InvalidRegistry {
+                    registry: name.into(),
+                    error: format!("The URL {} is not valid: {}", url, error),
+                })
+        };
+        let public_key = match &config.public_key {
+            Some(key) => key.as_bytes().to_vec(),
+            None => HEXPM_PUBLIC_KEY.to_vec(),
+        };
+        Ok(Self {
+            name: name.into(),
+            config: hexpm::Config {
+                api_base: uri(&config.api)?,
+                repository_base: uri(&config.repository)?,
+            },
+            public_key,
+            api_key,
+        })
+    }
+}
+
+/// The registries of a project, and which of them each of its Hex packages
+/// are resolved from.
+#[derive(Debug, Clone)]
+pub struct Registries {
+    registries: HashMap<String, Registry>,
+    default: String,
+    packages: HashMap<String, String>,
+}
+
+impl Registries {
+    /// Only hex.pm, for when there is no project.
+    pub fn hexpm() -> Self {
+        Self {
+            registries: [(HEXPM.into(), Registry::hexpm())].into(),
+            default: HEXPM.into(),
+            packages: HashMap::new(),
+        }
+    }
+
+    /// hex.pm and the given registries of the project. Returns an error if the
+    /// project uses a registry that is not given.
+    pub fn new(config: &PackageConfig, registries: Vec<Registry>) -> Result<Self> {
+        let mut registries: HashMap<_, _> = registries
+            .into_iter()
+            .map(|registry| (registry.name.clone(), registry))
+            .collect();
+        let _ = registries
+            .entry(HEXPM.into())
+            .or_insert_with(Registry::hexpm);
+        let known = |registry: &str| {
+            if registries.contains_key(registry) {
+                Ok(registry.to_string())
+            } else {
+                Err(Error::UnknownRegistry {
+                    registry: registry.into(),
+                })
+            }
+        };
+        let default = known(config.registry.as_deref().unwrap_or(HEXPM))?;
+        let packages = config
+            .all_dependencies()?
+            .into_iter()
+            .filter_map(|(name, requirement)| Some((name, requirement.registry()?.to_string())))
+            .map(|(name, registry)| Ok((name, known(&registry)?)))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            registries,
+            default,
+            packages,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Registry> {
+        self.registries
+            .get(name)
+            .ok_or_else(|| Error::UnknownRegistry {
+                registry: name.into(),
+            })
+    }
+
+    /// The registry that the project is published to.
+    pub fn default(&self) -> &Registry {
+        self.get(&self.default).expect("Default registry is known")
+    }
+
+    /// The registries that a package may be resolved from, in the order they
+    /// are to be tried. Packages that are not given a registry are looked for
+    /// in the default registry of the project, and then in hex.pm.
+    pub fn for_package(&self, package: &str) -> Vec<&Registry> {
+        let registries = match self.packages.get(package) {
+            Some(registry) => vec![registry.as_str()],
+            None => vec![self.default.as_str(), HEXPM],
+        };
+        registries
+            .into_iter()
+            .unique()
+            .filter_map(|registry| self.registries.get(registry))
+            .collect()
+    }
+}
+
 pub fn resolve_versions(
     package_fetcher: Box<dyn hexpm::version::PackageFetcher>,
     mode: Mode,
     config: &PackageConfig,
-    manifest: Option<&Manifest>,
+    locked: &HashMap<String, Version>,
 ) -> Result<PackageVersions> {
-    let specified_dependencies = config.dependencies_for(mode)?.into_iter();
-    let locked = config.locked(manifest)?;
+    let specified_dependencies = config.hex_dependencies_for(mode)?.into_iter();
     tracing::info!("resolving_versions");
     hexpm::version::resolve_versions(
         package_fetcher,
         config.name.clone(),
         specified_dependencies,
-        &locked,
+        locked,
     )
     .map_err(Error::dependency_resolution_failed)
 }
 
-fn key_name(hostname: &str) -> String {
+/// The name of the API key created for a single operation on this machine.
+pub fn key_name(hostname: &str) -> String {
     format!("gleam-{}", hostname)
 }
 
+/// The config of the API of an organization of the registry, with which the
+/// private packages of the organization are published and managed.
+pub fn organization_config(registry: &Registry, organization: &str) -> Result<hexpm::Config> {
+    let api_base = format!("{}repos/{}/", registry.config.api_base, organization)
+        .parse::<http::Uri>()
+        .map_err(|error| Error::InvalidRegistry {
+            registry: registry.name.clone(),
+            error: format!("The organization {} is not valid: {}", organization, error),
+        })?;
+    Ok(hexpm::Config {
+        api_base,
+        repository_base: registry.config.repository_base.clone(),
+    })
+}
+
 pub async fn publish_package<Http: HttpClient>(
     release_tarball: Vec<u8>,
     api_key: &str,
@@ -59,6 +205,16 @@ pub async fn publish_package<Http: HttpClient>(
     hexpm::publish_package_response(response).map_err(Error::hex)
 }
 
+#[test]
+fn organization_api() {
+    let config = organization_config(&Registry::hexpm(), "acme").expect("organization config");
+    assert_eq!(
+        config.api_base.to_string(),
+        "https://hex.pm/api/repos/acme/"
+    );
+    assert_eq!(config.repository_base.to_string(), "https://repo.hex.pm/");
+}
+
 #[derive(Debug, strum::EnumString, strum::EnumVariantNames, Clone, Copy, PartialEq, Eq)]
 #[strum(serialize_all = "lowercase")]
 pub enum RetirementReason {
@@ -116,27 +272,135 @@ pub async fn unretire_release<Http: HttpClient>(
     hexpm::unretire_release_response(response).map_err(Error::hex)
 }
 
+/// A GET request to the Hex API, which the hexpm crate does not provide for
+/// all endpoints.
+fn api_get_request(path: &str, api_key: &str, config: &hexpm::Config) -> http::Request<Vec<u8>> {
+    http::Request::get(format!("{}{}", config.api_base, path))
+        .header("authorization", api_key)
+        .header("accept", "application/json")
+        .header(
+            "user-agent",
+            concat!("gleam (", env!("CARGO_PKG_VERSION"), ")"),
+        )
+        .body(vec![])
+        .expect("api_get_request request")
+}
+
+fn api_get_response<T: serde::de::DeserializeOwned>(
+    response: http::Response<Vec<u8>>,
+) -> Result<T, hexpm::ApiError> {
+    let (parts, body) = response.into_parts();
+    match parts.status {
+        http::StatusCode::OK => Ok(serde_json::from_slice(&body)?),
+        http::StatusCode::NOT_FOUND => Err(hexpm::ApiError::NotFound),
+        http::StatusCode::TOO_MANY_REQUESTS => Err(hexpm::ApiError::RateLimited),
+        http::StatusCode::UNAUTHORIZED => Err(hexpm::ApiError::InvalidApiKey),
+        http::StatusCode::FORBIDDEN => Err(hexpm::ApiError::Forbidden),
+        status => Err(hexpm::ApiError::UnexpectedResponse(
+            status,
+            String::from_utf8_lossy(&body).to_string(),
+        )),
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HexUser {
+    username: String,
+}
+
+/// Check that the user the API key belongs to is an owner of the package, so
+/// that they are told they cannot manage its releases before trying to.
+pub async fn verify_package_owner<Http: HttpClient>(
+    package: &str,
+    api_key: &str,
+    config: &hexpm::Config,
+    http: &Http,
+) -> Result<()> {
+    tracing::info!(package = package, "verifying_package_owner");
+    let request = api_get_request("users/me", api_key, config);
+    let user: HexUser = api_get_response(http.send(request).await?).map_err(Error::hex)?;
+    let request = api_get_request(&format!("packages/{}/owners", package), api_key, config);
+    let owners: Vec<HexUser> = api_get_response(http.send(request).await?).map_err(|error| {
+        if error.is_not_found() {
+            Error::UnknownHexPackage {
+                package: package.to_string(),
+            }
+        } else {
+            Error::hex(error)
+        }
+    })?;
+    if owners.iter().any(|owner| owner.username == user.username) {
+        Ok(())
+    } else {
+        Err(Error::NotHexPackageOwner {
+            package: package.to_string(),
+            user: user.username,
+        })
+    }
+}
+
+#[cfg(test)]
+struct OwnersHttpClient;
+
+#[cfg(test)]
+#[async_trait::async_trait]
+impl HttpClient for OwnersHttpClient {
+    async fn send(&self, request: http::Request<Vec<u8>>) -> Result<http::Response<Vec<u8>>> {
+        let (status, body) = match request.uri().path() {
+            "/api/users/me" => (200, r#"{"username": "louis"}"#),
+            "/api/packages/mine/owners" => (200, r#"[{"username": "louis"}]"#),
+            "/api/packages/theirs/owners" => (200, r#"[{"username": "jose"}]"#),
+            _ => (404, ""),
+        };
+        Ok(http::Response::builder()
+            .status(status)
+            .body(body.as_bytes().to_vec())
+            .expect("response"))
+    }
+}
+
+#[test]
+fn verify_package_owners() {
+    let verify = |package| {
+        futures::executor::block_on(verify_package_owner(
+            package,
+            "key",
+            &hexpm::Config::new(),
+            &OwnersHttpClient,
+        ))
+    };
+    assert!(verify("mine").is_ok());
+    assert!(matches!(
+        verify("theirs"),
+        Err(Error::NotHexPackageOwner { user, .. }) if user == "louis"
+    ));
+    assert!(matches!(
+        verify("unknown"),
+        Err(Error::UnknownHexPackage { .. })
+    ));
+}
+
 pub async fn create_api_key<Http: HttpClient>(
-    hostname: &str,
+    key_name: &str,
     username: &str,
     password: &str,
     config: &hexpm::Config,
     http: &Http,
 ) -> Result<String> {
     tracing::info!("Creating API key with Hex");
-    let request = hexpm::create_api_key_request(username, password, &key_name(hostname), config);
+    let request = hexpm::create_api_key_request(username, password, key_name, config);
     let response = http.send(request).await?;
     hexpm::create_api_key_response(response).map_err(Error::hex)
 }
 
 pub async fn remove_api_key<Http: HttpClient>(
-    hostname: &str,
+    key_name: &str,
     config: &hexpm::Config,
     auth_key: &str,
     http: &Http,
 ) -> Result<()> {
     tracing::info!("Deleting API key from Hex");
-    let request = hexpm::remove_api_key_request(&key_name(hostname), auth_key, config);
+    let request = hexpm::remove_api_key_request(key_name, auth_key, config);
     let response = http.send(request).await?;
     hexpm::remove_api_key_response(response).map_err(Error::hex)
 }
@@ -146,7 +410,7 @@ pub struct Downloader {
     fs: DebugIgnore<Box<dyn FileSystemIO>>,
     http: DebugIgnore<Box<dyn HttpClient>>,
     untar: DebugIgnore<Box<dyn TarUnpacker>>,
-    hex_config: hexpm::Config,
+    registries: Registries,
 }
 
 impl Downloader {
@@ -154,12 +418,13 @@ impl Downloader {
         fs: Box<dyn FileSystemIO>,
         http: Box<dyn HttpClient>,
         untar: Box<dyn TarUnpacker>,
+        registries: Registries,
     ) -> Self {
         Self {
             fs: DebugIgnore(fs),
             http: DebugIgnore(http),
             untar: DebugIgnore(untar),
-            hex_config: hexpm::Config::new(),
+            registries,
         }
     }
 
@@ -167,8 +432,19 @@ impl Downloader {
         &self,
         package: &ManifestPackage,
     ) -> Result<bool, Error> {
-        let tarball_path =
-            paths::package_cache_tarball(&package.name, &package.version.to_string());
+        let (outer_checksum, registry) = match &package.source {
+            ManifestPackageSource::Hex {
+                outer_checksum,
+                registry,
+            } => (outer_checksum, registry.as_deref().unwrap_or(HEXPM)),
+            ManifestPackageSource::Git { .. } => return Ok(false),
+        };
+        let registry = self.registries.get(registry)?;
+        let tarball_path = paths::package_cache_tarball(
+            &registry.name,
+            &package.name,
+            &package.version.to_string(),
+        );
         if self.fs.is_file(&tarball_path) {
             tracing::info!(
                 package = package.name.as_str(),
@@ -186,13 +462,11 @@ impl Downloader {
         let request = hexpm::get_package_tarball_request(
             &package.name,
             &package.version.to_string(),
-            None,
-            &self.hex_config,
+            registry.api_key.as_deref(),
+            &registry.config,
         );
         let response = self.http.send(request).await?;
 
-        let ManifestPackageSource::Hex { outer_checksum } = &package.source;
-
         let tarball =
             hexpm::get_package_tarball_response(response, &outer_checksum.0).map_err(|error| {
                 Error::DownloadPackageError {
@@ -210,12 +484,22 @@ impl Downloader {
         &self,
         package: &ManifestPackage,
     ) -> Result<bool> {
+        let registry = match &package.source {
+            ManifestPackageSource::Hex { registry, .. } => registry.as_deref().unwrap_or(HEXPM),
+            // Git packages are exported from their repositories instead
+            ManifestPackageSource::Git { .. } => return Ok(false),
+        };
         let _ = self.ensure_package_downloaded(package).await?;
-        self.extract_package_from_cache(&package.name, &package.version)
+        self.extract_package_from_cache(registry, &package.name, &package.version)
     }
 
     // It would be really nice if this was async but the library is sync
-    pub fn extract_package_from_cache(&self, name: &str, version: &Version) -> Result<bool> {
+    pub fn extract_package_from_cache(
+        &self,
+        registry: &str,
+        name: &str,
+        version: &Version,
+    ) -> Result<bool> {
         let contents_path = Path::new("contents.tar.gz");
         let destination = paths::build_deps_package(name);
 
@@ -226,7 +510,7 @@ impl Downloader {
         }
 
         tracing::info!(package = name, "writing_package_to_target");
-        let tarball = paths::package_cache_tarball(name, &version.to_string());
+        let tarball = paths::package_cache_tarball(registry, name, &version.to_string());
         let reader = self.fs.reader(&tarball)?;
         let mut archive = Archive::new(reader);
 
@@ -258,14 +542,35 @@ impl Downloader {
         })
     }
 
+    /// Downloads the packages, reporting to the telemetry as each one is
+    /// downloaded.
     pub async fn download_hex_packages<'a, Packages: Iterator<Item = &'a ManifestPackage>>(
         &self,
         packages: Packages,
         project_name: &str,
+        telemetry: &dyn Telemetry,
     ) -> Result<()> {
-        let futures = packages
+        let packages: Vec<_> = packages
             .filter(|package| project_name != package.name)
-            .map(|package| self.ensure_package_in_build_directory(package));
+            .collect();
+        let total = packages.len();
+        let downloaded = &Cell::new(0);
+        let futures = packages.into_iter().map(|package| {
+            let span = tracing::info_span!(
+                "download_package",
+                package = package.name.as_str(),
+                version = %package.version
+            );
+            async move {
+                let result = self.ensure_package_in_build_directory(package).await;
+                if result.is_ok() {
+                    downloaded.set(downloaded.get() + 1);
+                    telemetry.package_downloaded(&package.name, downloaded.get(), total);
+                }
+                result
+            }
+            .instrument(span)
+        });
 
         // Run the futures to download the packages concurrently
         let results = future::join_all(futures).await;
@@ -296,7 +601,7 @@ pub async fn publish_documentation<Http: HttpClient>(
 pub async fn get_package_release<Http: HttpClient>(
     name: &str,
     version: &Version,
-    config: &hexpm::Config,
+    registry: &Registry,
     http: &Http,
 ) -> Result<hexpm::Release<hexpm::ReleaseMeta>> {
     let version = version.to_string();
@@ -305,7 +610,12 @@ pub async fn get_package_release<Http: HttpClient>(
         version = version.as_str(),
         "looking_up_package_release"
     );
-    let request = hexpm::get_package_release_request(name, &version, None, config);
+    let request = hexpm::get_package_release_request(
+        name,
+        &version,
+        registry.api_key.as_deref(),
+        &registry.config,
+    );
     let response = http.send(request).await?;
     hexpm::get_package_release_response(response).map_err(Error::hex)
 }
@@ -0,0 +1,155 @@
+//! The textual representation of Gleam int literals.
+//!
+//! Ints are written in decimal, or in hexadecimal, octal or binary with a
+//! `0x`, `0o` or `0b` prefix. Digits may be grouped with underscores.
+//!
+//! ```text
+//! 1_000_000    0x1F    0o755    0b1010_1010
+//! ```
+//!
+//! Each target writes these differently, so code generators take the literal
+//! apart with [`Literal::new`] and print it in their own syntax.
+
+use itertools::Itertools;
+
+/// An int literal as written in Gleam source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Literal<'a> {
+    pub negative: bool,
+    pub radix: u32,
+    /// The digits after the sign and prefix, underscores included.
+    pub digits: &'a str,
+}
+
+impl<'a> Literal<'a> {
+    pub fn new(value: &'a str) -> Self {
+        let (negative, unsigned) = match value.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, value),
+        };
+        let (radix, digits) = [(16, "0x"), (8, "0o"), (2, "0b")]
+            .into_iter()
+            .find_map(|(radix, prefix)| Some((radix, unsigned.strip_prefix(prefix)?)))
+            .unwrap_or((10, unsigned));
+        Self {
+            negative,
+            radix,
+            digits,
+        }
+    }
+
+    pub fn sign(&self) -> &'static str {
+        if self.negative {
+            "-"
+        } else {
+            ""
+        }
+    }
+
+    /// The Gleam prefix for the radix of the literal.
+    pub fn prefix(&self) -> &'static str {
+        match self.radix {
+            16 => "0x",
+            8 => "0o",
+            2 => "0b",
+            _ => "",
+        }
+    }
+
+    /// The digits without underscores or leading zeros, or `0` for zero.
+    pub fn significant_digits(&self) -> String {
+        let digits: String = self
+            .digits
+            .chars()
+            .filter(|c| *c != '_')
+            .skip_while(|c| *c == '0')
+            .collect();
+        if digits.is_empty() {
+            "0".into()
+        } else {
+            digits
+        }
+    }
+}
+
+/// The value of a literal, if it fits in a 64-bit int.
+///
+pub fn parse(value: &str) -> Option<i64> {
+    let literal = Literal::new(value);
+    let digits = format!("{}{}", literal.sign(), literal.significant_digits());
+    i64::from_str_radix(&digits, literal.radix).ok()
+}
+
+/// The literal as the formatter prints it. Decimal ints lose any leading
+/// zeros, and if they were written with underscores the digits are regrouped
+/// in threes. Hexadecimal digits are uppercase. Otherwise underscores are
+/// kept where they were written, as they often group the digits of bit
+/// flags.
+///
+pub fn canonical_literal(value: &str) -> String {
+    let literal = Literal::new(value);
+    let digits = match literal.radix {
+        10 if literal.digits.contains('_') => group_thousands(&literal.significant_digits()),
+        10 => literal.significant_digits(),
+        16 => literal.digits.to_ascii_uppercase(),
+        _ => literal.digits.to_string(),
+    };
+    format!("{}{}{}", literal.sign(), literal.prefix(), digits)
+}
+
+fn group_thousands(digits: &str) -> String {
+    let digits = digits.chars().collect_vec();
+    digits
+        .rchunks(3)
+        .rev()
+        .map(|group| group.iter().collect::<String>())
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literals() {
+        assert_eq!(
+            Literal::new("-1_000"),
+            Literal {
+                negative: true,
+                radix: 10,
+                digits: "1_000"
+            }
+        );
+        assert_eq!(Literal::new("0xFF").radix, 16);
+        assert_eq!(Literal::new("0o755").digits, "755");
+        assert_eq!(Literal::new("0b0000_1010").significant_digits(), "1010");
+        assert_eq!(Literal::new("0").significant_digits(), "0");
+        assert_eq!(Literal::new("00_0").significant_digits(), "0");
+    }
+
+    #[test]
+    fn parse_literals() {
+        assert_eq!(parse("1_000"), Some(1000));
+        assert_eq!(parse("-007"), Some(-7));
+        assert_eq!(parse("0x1F"), Some(31));
+        assert_eq!(parse("0o755"), Some(493));
+        assert_eq!(parse("0b1010"), Some(10));
+        assert_eq!(parse("-9_223_372_036_854_775_808"), Some(i64::MIN));
+        assert_eq!(parse("0xFFFF_FFFF_FFFF_FFFF"), None);
+    }
+
+    #[test]
+    fn canonical_literals() {
+        assert_eq!(canonical_literal("1"), "1");
+        assert_eq!(canonical_literal("0"), "0");
+        assert_eq!(canonical_literal("007"), "7");
+        assert_eq!(canonical_literal("-12928347925"), "-12928347925");
+        assert_eq!(canonical_literal("1_234_567"), "1_234_567");
+        assert_eq!(canonical_literal("12_34_567"), "1_234_567");
+        assert_eq!(canonical_literal("-1_0000"), "-10_000");
+        assert_eq!(canonical_literal("100_000"), "100_000");
+        assert_eq!(canonical_literal("0xca_fe"), "0xCA_FE");
+        assert_eq!(canonical_literal("0o0755"), "0o0755");
+        assert_eq!(canonical_literal("0b1010_1010"), "0b1010_1010");
+    }
+}
@@ -0,0 +1,473 @@
+//! A machine readable description of the public interface of a module, as
+//! exported by the `gleam export interfaces` command for use by tools that
+//! would otherwise have to parse Gleam source code themselves.
+//!
+//! Types are described structurally, with each type variable named after the
+//! type parameter it was declared as where there is one, and otherwise named
+//! `a`, `b`, `c`, etc in order of appearance within each definition.
+
+use crate::{
+    ast::{ArgNames, Deprecation, Name, Statement, TypedModule, TypedStatement},
+    type_::{pretty::Printer, Type, TypeVar},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub mod diff;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleInterface {
+    pub name: String,
+    pub documentation: Option<String>,
+    pub types: Vec<TypeDefinitionInterface>,
+    pub type_aliases: Vec<TypeAliasInterface>,
+    pub constants: Vec<ConstantInterface>,
+    pub functions: Vec<FunctionInterface>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeDefinitionInterface {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub documentation: Option<String>,
+    pub deprecation: Option<String>,
+    /// Opaque and external types have no public constructors.
+    pub opaque: bool,
+    pub constructors: Vec<ConstructorInterface>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructorInterface {
+    pub name: String,
+    pub documentation: Option<String>,
+    pub deprecation: Option<String>,
+    pub parameters: Vec<ParameterInterface>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeAliasInterface {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub documentation: Option<String>,
+    pub deprecation: Option<String>,
+    pub alias: TypeInterface,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstantInterface {
+    pub name: String,
+    pub documentation: Option<String>,
+    pub deprecation: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: TypeInterface,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionInterface {
+    pub name: String,
+    pub documentation: Option<String>,
+    pub deprecation: Option<String>,
+    pub parameters: Vec<ParameterInterface>,
+    #[serde(rename = "return")]
+    pub return_: TypeInterface,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterInterface {
+    pub label: Option<String>,
+    pub documentation: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: TypeInterface,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TypeInterface {
+    Named {
+        /// The module the type is defined in, `gleam` for the prelude.
+        module: String,
+        name: String,
+        parameters: Vec<TypeInterface>,
+    },
+    Fn {
+        parameters: Vec<TypeInterface>,
+        #[serde(rename = "return")]
+        return_: Box<TypeInterface>,
+    },
+    Tuple {
+        elements: Vec<TypeInterface>,
+    },
+    Variable {
+        name: String,
+    },
+}
+
+impl ModuleInterface {
+    /// The interface of a module. Doc comments are only included if they have
+    /// been attached to the module's definitions.
+    pub fn from_module(module: &TypedModule) -> Self {
+        let mut interface = Self {
+            name: module.name.join("/"),
+            documentation: documentation(&module.documentation.join("\n")),
+            types: vec![],
+            type_aliases: vec![],
+            constants: vec![],
+            functions: vec![],
+        };
+        for statement in &module.statements {
+            interface.add_statement(module, statement);
+        }
+        interface
+    }
+
+    fn add_statement(&mut self, module: &TypedModule, statement: &TypedStatement) {
+        match statement {
+            Statement::CustomType {
+                public: true,
+                name,
+                parameters,
+                typed_parameters,
+                constructors,
+                opaque,
+                doc,
+                deprecation,
+                ..
+            } => {
+                let mut types = Types::with_parameters(parameters, typed_parameters);
+                let constructors = if *opaque {
+                    vec![]
+                } else {
+                    constructors
+                        .iter()
+                        .map(|constructor| ConstructorInterface {
+                            name: constructor.name.to_string(),
+                            documentation: doc_option(&constructor.documentation),
+                            deprecation: deprecation_message(&constructor.deprecation),
+                            parameters: constructor
+                                .arguments
+                                .iter()
+                                .map(|argument| ParameterInterface {
+                                    label: argument.label.as_ref().map(Name::to_string),
+                                    documentation: doc_option(&argument.doc),
+                                    type_: types.interface(&argument.type_),
+                                })
+                                .collect(),
+                        })
+                        .collect()
+                };
+                self.types.push(TypeDefinitionInterface {
+                    name: name.to_string(),
+                    parameters: parameters.iter().map(Name::to_string).collect(),
+                    documentation: doc_option(doc),
+                    deprecation: deprecation_message(deprecation),
+                    opaque: *opaque,
+                    constructors,
+                });
+            }
+
+            Statement::ExternalType {
+                public: true,
+                name,
+                arguments,
+                doc,
+                deprecation,
+                ..
+            } => self.types.push(TypeDefinitionInterface {
+                name: name.to_string(),
+                parameters: arguments.iter().map(Name::to_string).collect(),
+                documentation: doc_option(doc),
+                deprecation: deprecation_message(deprecation),
+                opaque: true,
+                constructors: vec![],
+            }),
+
+            Statement::TypeAlias {
+                public: true,
+                alias,
+                parameters,
+                type_,
+                doc,
+                deprecation,
+                ..
+            } => {
+                let typed_parameters = module
+                    .type_info
+                    .types
+                    .get(alias)
+                    .map(|constructor| constructor.parameters.clone())
+                    .unwrap_or_default();
+                let mut types = Types::with_parameters(parameters, &typed_parameters);
+                self.type_aliases.push(TypeAliasInterface {
+                    name: alias.to_string(),
+                    parameters: parameters.iter().map(Name::to_string).collect(),
+                    documentation: doc_option(doc),
+                    deprecation: deprecation_message(deprecation),
+                    alias: types.interface(type_),
+                });
+            }
+
+            Statement::ModuleConstant {
+                public: true,
+                name,
+                type_,
+                doc,
+                deprecation,
+                ..
+            } => self.constants.push(ConstantInterface {
+                name: name.to_string(),
+                documentation: doc_option(doc),
+                deprecation: deprecation_message(deprecation),
+                type_: Types::default().interface(type_),
+            }),
+
+            Statement::Fn {
+                public: true,
+                name,
+                arguments,
+                return_type,
+                doc,
+                deprecation,
+                ..
+            } => {
+                let mut types = Types::default();
+                let parameters = arguments
+                    .iter()
+                    .map(|argument| ParameterInterface {
+                        label: match &argument.names {
+                            ArgNames::LabelledDiscard { label, .. }
+                            | ArgNames::NamedLabelled { label, .. } => Some(label.to_string()),
+                            ArgNames::Discard { .. } | ArgNames::Named { .. } => None,
+                        },
+                        documentation: doc_option(&argument.doc),
+                        type_: types.interface(&argument.type_),
+                    })
+                    .collect();
+                self.functions.push(FunctionInterface {
+                    name: name.to_string(),
+                    documentation: doc_option(doc),
+                    deprecation: deprecation_message(deprecation),
+                    parameters,
+                    return_: types.interface(return_type),
+                });
+            }
+
+            Statement::ExternalFn {
+                public: true,
+                name,
+                arguments,
+                return_type,
+                doc,
+                deprecation,
+                ..
+            } => {
+                let mut types = Types::default();
+                let parameters = arguments
+                    .iter()
+                    .map(|argument| ParameterInterface {
+                        label: argument.label.as_ref().map(Name::to_string),
+                        documentation: doc_option(&argument.doc),
+                        type_: types.interface(&argument.type_),
+                    })
+                    .collect();
+                self.functions.push(FunctionInterface {
+                    name: name.to_string(),
+                    documentation: doc_option(doc),
+                    deprecation: deprecation_message(deprecation),
+                    parameters,
+                    return_: types.interface(return_type),
+                });
+            }
+
+            _ => (),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("module interface to json")
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+// Converts types to their interface, naming type variables consistently
+// within a single definition.
+#[derive(Debug, Default)]
+struct Types {
+    printer: Printer,
+}
+
+impl Types {
+    fn with_parameters(names: &[Name], parameters: &[Arc<Type>]) -> Self {
+        let names = parameters
+            .iter()
+            .zip(names)
+            .filter_map(|(type_, name)| Some((type_variable_id(type_)?, name.to_string())))
+            .collect();
+        let mut printer = Printer::new();
+        printer.with_names(names);
+        Self { printer }
+    }
+
+    fn interface(&mut self, type_: &Type) -> TypeInterface {
+        match type_ {
+            Type::App {
+                module, name, args, ..
+            } => TypeInterface::Named {
+                module: if module.is_empty() {
+                    "gleam".into()
+                } else {
+                    module.join("/")
+                },
+                name: name.to_string(),
+                parameters: args.iter().map(|arg| self.interface(arg)).collect(),
+            },
+
+            Type::Fn { args, retrn } => TypeInterface::Fn {
+                parameters: args.iter().map(|arg| self.interface(arg)).collect(),
+                return_: Box::new(self.interface(retrn)),
+            },
+
+            Type::Tuple { elems } => TypeInterface::Tuple {
+                elements: elems.iter().map(|elem| self.interface(elem)).collect(),
+            },
+
+            Type::Var { type_ } => match &*type_.borrow() {
+                TypeVar::Link { type_ } => self.interface(type_),
+                TypeVar::Unbound { id, .. } | TypeVar::Generic { id } => TypeInterface::Variable {
+                    name: self.printer.generic_type_var(*id).to_pretty_string(80),
+                },
+            },
+        }
+    }
+}
+
+fn type_variable_id(type_: &Type) -> Option<u64> {
+    match type_ {
+        Type::Var { type_ } => match &*type_.borrow() {
+            TypeVar::Link { type_ } => type_variable_id(type_),
+            TypeVar::Unbound { id, .. } | TypeVar::Generic { id } => Some(*id),
+        },
+        Type::App { .. } | Type::Fn { .. } | Type::Tuple { .. } => None,
+    }
+}
+
+fn documentation(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+fn doc_option(doc: &Option<String>) -> Option<String> {
+    doc.as_deref().and_then(documentation)
+}
+
+fn deprecation_message(deprecation: &Deprecation) -> Option<String> {
+    deprecation.message().map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        build::{Module, Origin, Target},
+        type_::{build_prelude, infer_module},
+        uid::UniqueIdGenerator,
+    };
+    use std::path::PathBuf;
+
+    fn interface(src: &str) -> ModuleInterface {
+        let ids = UniqueIdGenerator::new();
+        let mut modules = im::HashMap::new();
+        let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+        let arena = crate::ast::Arena::new();
+        let (mut ast, extra) = crate::parse::parse_module(src, &arena).expect("syntax error");
+        ast.name = vec!["app".into(), "shapes".into()];
+        let ast = infer_module(
+            Target::Erlang,
+            &ids,
+            ast,
+            Origin::Src,
+            "thepackage",
+            &modules,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let mut module = Module {
+            name: "app/shapes".into(),
+            code: src.into(),
+            input_path: PathBuf::from("src/app/shapes.gleam"),
+            origin: Origin::Src,
+            ast,
+            extra,
+        };
+        module.attach_doc_and_module_comments();
+        ModuleInterface::from_module(&module.ast)
+    }
+
+    #[test]
+    fn module_interface() {
+        let src = r#"//// Shapes and their areas.
+
+/// A shape with straight sides.
+pub type Shape(unit) {
+  /// A square.
+  Square(
+    /// The length of each side.
+    side: Float,
+  )
+  Rectangle(width: Float, height: Float)
+  @deprecated("Use Rectangle")
+  Box(Float, Float)
+  Tagged(unit)
+}
+
+pub opaque type Id {
+  Id(Int)
+}
+
+pub external type Canvas
+
+type Private {
+  Private
+}
+
+pub type Pair(first, second) = #(first, second)
+
+/// The number of sides of a square.
+pub const square_sides = 4
+
+@deprecated("Use area")
+pub fn size(shape: Shape(a)) -> Float {
+  area(shape)
+}
+
+pub fn area(
+  /// The shape to measure.
+  of shape: Shape(a),
+) -> Float {
+  case shape {
+    Square(side) -> side *. side
+    Rectangle(width, height) -> width *. height
+    Box(width, height) -> width *. height
+    Tagged(_) -> 0.0
+  }
+}
+
+pub fn map(list: List(a), with fun: fn(a) -> b) -> List(b) {
+  todo
+}
+
+pub external fn draw(Canvas, on: Shape(unit)) -> Nil = "canvas" "draw"
+
+fn private() {
+  Private
+}
+"#;
+        insta::assert_snapshot!(interface(src).to_json());
+    }
+}
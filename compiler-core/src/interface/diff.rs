@@ -0,0 +1,759 @@
+//! Comparing the public interface of two versions of a package, as done by
+//! `gleam api diff`, to find the version bump its changes require under
+//! semantic versioning.
+//!
+//! Removing or changing the type of a definition is a major change, as is
+//! adding a constructor to a custom type, which makes the `case` expressions
+//! matching on its values inexhaustive. Adding a definition or deprecating
+//! one is a minor change, and changing documentation is a patch change.
+
+use std::{collections::HashMap, fmt};
+
+use hexpm::version::Version;
+use itertools::Itertools;
+
+use super::{
+    ConstantInterface, ConstructorInterface, FunctionInterface, ModuleInterface,
+    ParameterInterface, TypeAliasInterface, TypeDefinitionInterface, TypeInterface,
+};
+
+/// The part of a version incremented for a release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bump::Patch => f.write_str("patch"),
+            Bump::Minor => f.write_str("minor"),
+            Bump::Major => f.write_str("major"),
+        }
+    }
+}
+
+impl Bump {
+    /// The bump made from one version to the next, if the next is newer. As
+    /// is the convention for versions before 1.0.0, incrementing the minor
+    /// version of those is a major bump and incrementing the patch version a
+    /// minor one.
+    pub fn between(previous: &Version, next: &Version) -> Option<Self> {
+        if next <= previous {
+            return None;
+        }
+        let bump = if next.major != previous.major {
+            Bump::Major
+        } else if next.minor != previous.minor {
+            Bump::Minor
+        } else {
+            Bump::Patch
+        };
+        match (next.major, bump) {
+            (0, Bump::Minor) => Some(Bump::Major),
+            (0, Bump::Patch) => Some(Bump::Minor),
+            _ => Some(bump),
+        }
+    }
+}
+
+/// A change to the public interface of a package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The bump the change requires.
+    pub bump: Bump,
+    pub description: String,
+}
+
+/// The changes from the interface of the modules of one version of a package
+/// to those of the next, the major changes first.
+pub fn diff(previous: &[ModuleInterface], next: &[ModuleInterface]) -> Vec<Change> {
+    let mut changes = Changes::default();
+    changes.definitions(
+        "module",
+        "",
+        previous,
+        next,
+        |module| &module.name,
+        Changes::module,
+    );
+    changes
+        .changes
+        .into_iter()
+        .sorted_by(|a, b| b.bump.cmp(&a.bump))
+        .collect()
+}
+
+/// The bump required by the changes, if there are any.
+pub fn required_bump(changes: &[Change]) -> Option<Bump> {
+    changes.iter().map(|change| change.bump).max()
+}
+
+#[derive(Debug, Default)]
+struct Changes {
+    changes: Vec<Change>,
+}
+
+impl Changes {
+    fn push(&mut self, bump: Bump, description: String) {
+        self.changes.push(Change { bump, description });
+    }
+
+    // Compares the definitions of the same kind by name, with those removed
+    // being major changes and those added minor ones
+    fn definitions<T>(
+        &mut self,
+        kind: &str,
+        module: &str,
+        previous: &[T],
+        next: &[T],
+        name: impl Fn(&T) -> &String,
+        compare: impl Fn(&mut Self, &str, &T, &T),
+    ) {
+        let qualified = |definition: &T| {
+            if module.is_empty() {
+                format!("`{}`", name(definition))
+            } else {
+                format!("`{}.{}`", module, name(definition))
+            }
+        };
+        for definition in previous {
+            match next.iter().find(|other| name(other) == name(definition)) {
+                Some(other) => compare(self, &qualified(definition), definition, other),
+                None => self.push(
+                    Bump::Major,
+                    format!("Removed {} {}", kind, qualified(definition)),
+                ),
+            }
+        }
+        for definition in next {
+            if !previous.iter().any(|other| name(other) == name(definition)) {
+                self.push(
+                    Bump::Minor,
+                    format!("Added {} {}", kind, qualified(definition)),
+                );
+            }
+        }
+    }
+
+    fn module(&mut self, name: &str, previous: &ModuleInterface, next: &ModuleInterface) {
+        let module = &previous.name;
+        self.documentation("module", name, &previous.documentation, &next.documentation);
+        self.definitions(
+            "type",
+            module,
+            &previous.types,
+            &next.types,
+            |type_| &type_.name,
+            Changes::type_definition,
+        );
+        self.definitions(
+            "type alias",
+            module,
+            &previous.type_aliases,
+            &next.type_aliases,
+            |alias| &alias.name,
+            Changes::type_alias,
+        );
+        self.definitions(
+            "constant",
+            module,
+            &previous.constants,
+            &next.constants,
+            |constant| &constant.name,
+            Changes::constant,
+        );
+        self.definitions(
+            "function",
+            module,
+            &previous.functions,
+            &next.functions,
+            |function| &function.name,
+            Changes::function,
+        );
+    }
+
+    fn type_definition(
+        &mut self,
+        name: &str,
+        previous: &TypeDefinitionInterface,
+        next: &TypeDefinitionInterface,
+    ) {
+        self.annotations(
+            "type",
+            name,
+            (&previous.documentation, &previous.deprecation),
+            (&next.documentation, &next.deprecation),
+        );
+        if previous.parameters.len() != next.parameters.len() {
+            return self.push(
+                Bump::Major,
+                format!(
+                    "Changed the number of parameters of type {} from {} to {}",
+                    name,
+                    previous.parameters.len(),
+                    next.parameters.len()
+                ),
+            );
+        }
+        match (previous.opaque, next.opaque) {
+            (false, true) => {
+                return self.push(Bump::Major, format!("Made type {} opaque", name));
+            }
+            (true, false) => {
+                return self.push(
+                    Bump::Minor,
+                    format!("Made the constructors of type {} public", name),
+                );
+            }
+            (true, true) => return,
+            (false, false) => (),
+        }
+
+        let module = name
+            .trim_matches('`')
+            .rsplit_once('.')
+            .map_or("", |(module, _)| module);
+        for constructor in &previous.constructors {
+            let other = next
+                .constructors
+                .iter()
+                .find(|other| other.name == constructor.name);
+            let other = match other {
+                Some(other) => other,
+                None => {
+                    self.push(
+                        Bump::Major,
+                        format!(
+                            "Removed constructor `{}.{}` of type {}",
+                            module, constructor.name, name
+                        ),
+                    );
+                    continue;
+                }
+            };
+            let qualified = format!("`{}.{}`", module, constructor.name);
+            self.constructor(&qualified, previous, next, constructor, other);
+        }
+        for constructor in &next.constructors {
+            if !previous
+                .constructors
+                .iter()
+                .any(|other| other.name == constructor.name)
+            {
+                self.push(
+                    Bump::Major,
+                    format!(
+                        "Added constructor `{}.{}` to type {}, so `case` expressions \
+matching on its values are no longer exhaustive",
+                        module, constructor.name, name
+                    ),
+                );
+            }
+        }
+    }
+
+    fn constructor(
+        &mut self,
+        name: &str,
+        previous_type: &TypeDefinitionInterface,
+        next_type: &TypeDefinitionInterface,
+        previous: &ConstructorInterface,
+        next: &ConstructorInterface,
+    ) {
+        self.annotations(
+            "constructor",
+            name,
+            (&previous.documentation, &previous.deprecation),
+            (&next.documentation, &next.deprecation),
+        );
+        let mut renaming =
+            Renaming::with_parameters(&previous_type.parameters, &next_type.parameters);
+        if !renaming.same_parameters(&previous.parameters, &next.parameters) {
+            return self.push(
+                Bump::Major,
+                format!(
+                    "Changed the fields of constructor {} from `{}` to `{}`",
+                    name,
+                    constructor_string(previous),
+                    constructor_string(next)
+                ),
+            );
+        }
+        self.labels("constructor", name, &previous.parameters, &next.parameters);
+    }
+
+    fn type_alias(&mut self, name: &str, previous: &TypeAliasInterface, next: &TypeAliasInterface) {
+        self.annotations(
+            "type alias",
+            name,
+            (&previous.documentation, &previous.deprecation),
+            (&next.documentation, &next.deprecation),
+        );
+        let mut renaming = Renaming::with_parameters(&previous.parameters, &next.parameters);
+        if previous.parameters.len() != next.parameters.len()
+            || !renaming.same(&previous.alias, &next.alias)
+        {
+            self.push(
+                Bump::Major,
+                format!(
+                    "Changed type alias {} from `{}` to `{}`",
+                    name,
+                    type_string(&previous.alias),
+                    type_string(&next.alias)
+                ),
+            );
+        }
+    }
+
+    fn constant(&mut self, name: &str, previous: &ConstantInterface, next: &ConstantInterface) {
+        self.annotations(
+            "constant",
+            name,
+            (&previous.documentation, &previous.deprecation),
+            (&next.documentation, &next.deprecation),
+        );
+        if !Renaming::default().same(&previous.type_, &next.type_) {
+            self.push(
+                Bump::Major,
+                format!(
+                    "Changed the type of constant {} from `{}` to `{}`",
+                    name,
+                    type_string(&previous.type_),
+                    type_string(&next.type_)
+                ),
+            );
+        }
+    }
+
+    fn function(&mut self, name: &str, previous: &FunctionInterface, next: &FunctionInterface) {
+        self.annotations(
+            "function",
+            name,
+            (&previous.documentation, &previous.deprecation),
+            (&next.documentation, &next.deprecation),
+        );
+        let mut renaming = Renaming::default();
+        if !renaming.same_parameters(&previous.parameters, &next.parameters)
+            || !renaming.same(&previous.return_, &next.return_)
+        {
+            return self.push(
+                Bump::Major,
+                format!(
+                    "Changed the type of function {} from `{}` to `{}`",
+                    name,
+                    function_string(previous),
+                    function_string(next)
+                ),
+            );
+        }
+        self.labels("function", name, &previous.parameters, &next.parameters);
+    }
+
+    // Labels can be added to the parameters of a function or constructor
+    // without breaking the code that calls it, but not removed or changed
+    fn labels(
+        &mut self,
+        kind: &str,
+        name: &str,
+        previous: &[ParameterInterface],
+        next: &[ParameterInterface],
+    ) {
+        for (index, (previous, next)) in previous.iter().zip(next).enumerate() {
+            match (&previous.label, &next.label) {
+                (Some(label), Some(other)) if label == other => (),
+                (None, None) => (),
+                (None, Some(label)) => self.push(
+                    Bump::Minor,
+                    format!(
+                        "Added the label `{}` to parameter {} of {} {}",
+                        label,
+                        index + 1,
+                        kind,
+                        name
+                    ),
+                ),
+                (Some(label), _) => self.push(
+                    Bump::Major,
+                    format!(
+                        "Removed the label `{}` from parameter {} of {} {}",
+                        label,
+                        index + 1,
+                        kind,
+                        name
+                    ),
+                ),
+            }
+        }
+    }
+
+    fn annotations(
+        &mut self,
+        kind: &str,
+        name: &str,
+        (previous_documentation, previous_deprecation): (&Option<String>, &Option<String>),
+        (next_documentation, next_deprecation): (&Option<String>, &Option<String>),
+    ) {
+        self.documentation(kind, name, previous_documentation, next_documentation);
+        match (previous_deprecation, next_deprecation) {
+            (None, Some(_)) => self.push(Bump::Minor, format!("Deprecated {} {}", kind, name)),
+            (Some(_), None) => self.push(
+                Bump::Patch,
+                format!("Removed the deprecation of {} {}", kind, name),
+            ),
+            (Some(previous), Some(next)) if previous != next => self.push(
+                Bump::Patch,
+                format!("Changed the deprecation message of {} {}", kind, name),
+            ),
+            _ => (),
+        }
+    }
+
+    fn documentation(
+        &mut self,
+        kind: &str,
+        name: &str,
+        previous: &Option<String>,
+        next: &Option<String>,
+    ) {
+        if previous != next {
+            self.push(
+                Bump::Patch,
+                format!("Changed the documentation of {} {}", kind, name),
+            );
+        }
+    }
+}
+
+/// Compares types for equality up to the names of their type variables,
+/// which are renamed consistently within a definition.
+#[derive(Debug, Default)]
+struct Renaming<'a> {
+    previous: HashMap<&'a str, &'a str>,
+    next: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> Renaming<'a> {
+    fn with_parameters(previous: &'a [String], next: &'a [String]) -> Self {
+        let mut renaming = Self::default();
+        for (previous, next) in previous.iter().zip(next) {
+            let _ = renaming.variables(previous, next);
+        }
+        renaming
+    }
+
+    fn variables(&mut self, previous: &'a str, next: &'a str) -> bool {
+        let previous_name = *self.previous.entry(previous).or_insert(next);
+        let next_name = *self.next.entry(next).or_insert(previous);
+        previous_name == next && next_name == previous
+    }
+
+    fn same_parameters(
+        &mut self,
+        previous: &'a [ParameterInterface],
+        next: &'a [ParameterInterface],
+    ) -> bool {
+        previous.len() == next.len()
+            && previous
+                .iter()
+                .zip(next)
+                .all(|(previous, next)| self.same(&previous.type_, &next.type_))
+    }
+
+    fn same(&mut self, previous: &'a TypeInterface, next: &'a TypeInterface) -> bool {
+        match (previous, next) {
+            (
+                TypeInterface::Named {
+                    module,
+                    name,
+                    parameters,
+                },
+                TypeInterface::Named {
+                    module: next_module,
+                    name: next_name,
+                    parameters: next_parameters,
+                },
+            ) => {
+                module == next_module && name == next_name && self.all(parameters, next_parameters)
+            }
+            (
+                TypeInterface::Fn {
+                    parameters,
+                    return_,
+                },
+                TypeInterface::Fn {
+                    parameters: next_parameters,
+                    return_: next_return,
+                },
+            ) => self.all(parameters, next_parameters) && self.same(return_, next_return),
+            (
+                TypeInterface::Tuple { elements },
+                TypeInterface::Tuple {
+                    elements: next_elements,
+                },
+            ) => self.all(elements, next_elements),
+            (TypeInterface::Variable { name }, TypeInterface::Variable { name: next_name }) => {
+                self.variables(name, next_name)
+            }
+            _ => false,
+        }
+    }
+
+    fn all(&mut self, previous: &'a [TypeInterface], next: &'a [TypeInterface]) -> bool {
+        previous.len() == next.len()
+            && previous
+                .iter()
+                .zip(next)
+                .all(|(previous, next)| self.same(previous, next))
+    }
+}
+
+fn type_string(type_: &TypeInterface) -> String {
+    let list = |types: &[TypeInterface]| types.iter().map(type_string).join(", ");
+    match type_ {
+        TypeInterface::Named {
+            name, parameters, ..
+        } if parameters.is_empty() => name.clone(),
+        TypeInterface::Named {
+            name, parameters, ..
+        } => format!("{}({})", name, list(parameters)),
+        TypeInterface::Fn {
+            parameters,
+            return_,
+        } => format!("fn({}) -> {}", list(parameters), type_string(return_)),
+        TypeInterface::Tuple { elements } => format!("#({})", list(elements)),
+        TypeInterface::Variable { name } => name.clone(),
+    }
+}
+
+fn parameters_string(parameters: &[ParameterInterface]) -> String {
+    parameters
+        .iter()
+        .map(|parameter| match &parameter.label {
+            Some(label) => format!("{}: {}", label, type_string(&parameter.type_)),
+            None => type_string(&parameter.type_),
+        })
+        .join(", ")
+}
+
+fn function_string(function: &FunctionInterface) -> String {
+    format!(
+        "fn({}) -> {}",
+        parameters_string(&function.parameters),
+        type_string(&function.return_)
+    )
+}
+
+fn constructor_string(constructor: &ConstructorInterface) -> String {
+    if constructor.parameters.is_empty() {
+        constructor.name.clone()
+    } else {
+        format!(
+            "{}({})",
+            constructor.name,
+            parameters_string(&constructor.parameters)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str, parameters: Vec<TypeInterface>) -> TypeInterface {
+        TypeInterface::Named {
+            module: "gleam".into(),
+            name: name.into(),
+            parameters,
+        }
+    }
+
+    fn variable(name: &str) -> TypeInterface {
+        TypeInterface::Variable { name: name.into() }
+    }
+
+    fn parameter(label: Option<&str>, type_: TypeInterface) -> ParameterInterface {
+        ParameterInterface {
+            label: label.map(String::from),
+            documentation: None,
+            type_,
+        }
+    }
+
+    fn function(name: &str, parameters: Vec<ParameterInterface>) -> FunctionInterface {
+        FunctionInterface {
+            name: name.into(),
+            documentation: None,
+            deprecation: None,
+            parameters,
+            return_: named("Int", vec![]),
+        }
+    }
+
+    fn shape(variable_name: &str, constructors: &[&str]) -> TypeDefinitionInterface {
+        TypeDefinitionInterface {
+            name: "Shape".into(),
+            parameters: vec![variable_name.into()],
+            documentation: None,
+            deprecation: None,
+            opaque: false,
+            constructors: constructors
+                .iter()
+                .map(|name| ConstructorInterface {
+                    name: name.to_string(),
+                    documentation: None,
+                    deprecation: None,
+                    parameters: vec![parameter(None, variable(variable_name))],
+                })
+                .collect(),
+        }
+    }
+
+    fn module(
+        types: Vec<TypeDefinitionInterface>,
+        functions: Vec<FunctionInterface>,
+    ) -> ModuleInterface {
+        ModuleInterface {
+            name: "app/shapes".into(),
+            documentation: None,
+            types,
+            type_aliases: vec![],
+            constants: vec![],
+            functions,
+        }
+    }
+
+    fn descriptions(changes: &[Change]) -> Vec<(Bump, &str)> {
+        changes
+            .iter()
+            .map(|change| (change.bump, change.description.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn unchanged_interface() {
+        let interface = module(
+            vec![shape("a", &["Square"])],
+            vec![function("area", vec![parameter(None, variable("a"))])],
+        );
+        let renamed = module(
+            vec![shape("b", &["Square"])],
+            vec![function("area", vec![parameter(None, variable("b"))])],
+        );
+        assert_eq!(diff(&[interface], &[renamed]), vec![]);
+    }
+
+    #[test]
+    fn function_changes() {
+        let previous = module(
+            vec![],
+            vec![
+                function("area", vec![parameter(None, named("Int", vec![]))]),
+                function("size", vec![parameter(Some("of"), named("Int", vec![]))]),
+                function("scale", vec![parameter(None, named("Int", vec![]))]),
+                function("removed", vec![]),
+            ],
+        );
+        let next = module(
+            vec![],
+            vec![
+                function("area", vec![parameter(Some("of"), named("Int", vec![]))]),
+                function("size", vec![parameter(None, named("Int", vec![]))]),
+                function("scale", vec![parameter(None, named("Float", vec![]))]),
+                function("added", vec![]),
+            ],
+        );
+        let changes = diff(&[previous], &[next]);
+        assert_eq!(
+            descriptions(&changes),
+            vec![
+                (
+                    Bump::Major,
+                    "Removed the label `of` from parameter 1 of function `app/shapes.size`"
+                ),
+                (
+                    Bump::Major,
+                    "Changed the type of function `app/shapes.scale` from `fn(Int) -> Int` to \
+`fn(Float) -> Int`"
+                ),
+                (Bump::Major, "Removed function `app/shapes.removed`"),
+                (
+                    Bump::Minor,
+                    "Added the label `of` to parameter 1 of function `app/shapes.area`"
+                ),
+                (Bump::Minor, "Added function `app/shapes.added`"),
+            ]
+        );
+        assert_eq!(required_bump(&changes), Some(Bump::Major));
+    }
+
+    #[test]
+    fn type_changes() {
+        let mut deprecated = shape("a", &["Square", "Circle"]);
+        deprecated.deprecation = Some("Use Polygon".into());
+        let changes = diff(
+            &[module(vec![shape("a", &["Square"])], vec![])],
+            &[module(vec![deprecated], vec![])],
+        );
+        assert_eq!(
+            descriptions(&changes),
+            vec![
+                (
+                    Bump::Major,
+                    "Added constructor `app/shapes.Circle` to type `app/shapes.Shape`, so \
+`case` expressions matching on its values are no longer exhaustive"
+                ),
+                (Bump::Minor, "Deprecated type `app/shapes.Shape`"),
+            ]
+        );
+
+        let mut opaque = shape("a", &[]);
+        opaque.opaque = true;
+        let changes = diff(
+            &[module(vec![opaque], vec![])],
+            &[module(vec![shape("a", &["Square"])], vec![])],
+        );
+        assert_eq!(
+            descriptions(&changes),
+            vec![(
+                Bump::Minor,
+                "Made the constructors of type `app/shapes.Shape` public"
+            )]
+        );
+    }
+
+    #[test]
+    fn module_changes() {
+        let mut documented = module(vec![], vec![]);
+        documented.documentation = Some("Shapes.".into());
+        let mut other = module(vec![], vec![]);
+        other.name = "app/other".into();
+        let changes = diff(&[module(vec![], vec![]), other], &[documented]);
+        assert_eq!(
+            descriptions(&changes),
+            vec![
+                (Bump::Major, "Removed module `app/other`"),
+                (
+                    Bump::Patch,
+                    "Changed the documentation of module `app/shapes`"
+                ),
+            ]
+        );
+        assert_eq!(required_bump(&[]), None);
+    }
+
+    #[test]
+    fn bump_between() {
+        let version = |text: &str| Version::parse(text).expect("version");
+        let between = |previous, next| Bump::between(&version(previous), &version(next));
+        assert_eq!(between("1.2.3", "2.0.0"), Some(Bump::Major));
+        assert_eq!(between("1.2.3", "1.3.0"), Some(Bump::Minor));
+        assert_eq!(between("1.2.3", "1.2.4"), Some(Bump::Patch));
+        assert_eq!(between("0.2.3", "0.3.0"), Some(Bump::Major));
+        assert_eq!(between("0.2.3", "0.2.4"), Some(Bump::Minor));
+        assert_eq!(between("1.2.3", "1.2.3"), None);
+        assert_eq!(between("1.2.3", "1.0.0"), None);
+    }
+}
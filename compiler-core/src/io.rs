@@ -1,4 +1,5 @@
 pub mod memory;
+pub mod overlay;
 
 use crate::error::{Error, FileIoAction, FileKind, Result};
 use async_trait::async_trait;
@@ -139,6 +140,42 @@ pub trait CommandExecutor {
         cwd: Option<&Path>,
         stdio: Stdio,
     ) -> Result<i32, Error>;
+
+    /// Run a program without printing its output, returning its exit status
+    /// along with what it printed to stdout and then stderr.
+    fn exec_captured(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &[(&str, String)],
+        cwd: Option<&Path>,
+    ) -> Result<(i32, String), Error> {
+        self.exec(program, args, env, cwd, Stdio::Null)
+            .map(|status| (status, String::new()))
+    }
+
+    /// Run a program without printing its output, returning its exit status
+    /// along with the bytes it printed to stdout and to stderr.
+    fn exec_output(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &[(&str, String)],
+        cwd: Option<&Path>,
+    ) -> Result<CommandOutput, Error> {
+        self.exec(program, args, env, cwd, Stdio::Null)
+            .map(|status| CommandOutput {
+                status,
+                ..Default::default()
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub status: i32,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -168,6 +205,18 @@ pub trait FileSystemWriter {
     fn hardlink(&self, from: &Path, to: &Path) -> Result<(), Error>;
     fn symlink_dir(&self, from: &Path, to: &Path) -> Result<(), Error>;
     fn delete_file(&self, path: &Path) -> Result<(), Error>;
+
+    /// Writes the text to the file, replacing it if it exists.
+    fn write(&self, path: &Path, text: &str) -> Result<(), Error> {
+        self.write_bytes(path, text.as_bytes())
+    }
+
+    /// Writes the bytes to the file, replacing it if it exists.
+    fn write_bytes(&self, path: &Path, bytes: &[u8]) -> Result<(), Error> {
+        let mut writer = self.writer(path)?;
+        writer.write(bytes)?;
+        writer.close()
+    }
 }
 
 #[derive(Debug)]
@@ -236,10 +285,13 @@ impl WrappedWriter {
     }
 
     pub fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
-        self.inner
-            .write(bytes)
-            .map(|_| ())
-            .map_err(|e| self.convert_err(e))
+        self.inner.write_all(bytes).map_err(|e| self.convert_err(e))
+    }
+
+    /// Write any buffered output to the file. Buffered output is also written
+    /// when the writer is dropped, but then any error that occurs is lost.
+    pub fn close(mut self) -> Result<(), Error> {
+        self.inner.flush().map_err(|e| self.convert_err(e))
     }
 }
 
@@ -256,8 +308,7 @@ impl std::io::Write for WrappedWriter {
 impl std::fmt::Write for WrappedWriter {
     fn write_str(&mut self, s: &str) -> std::fmt::Result {
         self.inner
-            .write(s.as_bytes())
-            .map(|_| ())
+            .write_all(s.as_bytes())
             .map_err(|_| std::fmt::Error)
     }
 }
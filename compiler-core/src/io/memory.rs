@@ -23,10 +23,51 @@ impl InMemoryFileSystem {
     pub fn new() -> Self {
         Self::default()
     }
+
+    fn file(&self, path: &Path) -> Result<InMemoryFile, Error> {
+        (*self.files)
+            .borrow()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| Error::FileIo {
+                kind: FileKind::File,
+                action: FileIoAction::Open,
+                path: path.to_path_buf(),
+                err: None,
+            })
+    }
+
+    // The files within the directory, with their paths relative to it
+    fn files_within(&self, dir: &Path) -> Vec<(PathBuf, PathBuf)> {
+        (*self.files)
+            .borrow()
+            .keys()
+            .filter_map(|path| {
+                let relative = path.strip_prefix(dir).ok()?.to_path_buf();
+                Some((path.clone(), relative))
+            })
+            .collect()
+    }
 }
 
 impl FileSystemIO for InMemoryFileSystem {}
 
+// There are no programs to run where there is no file system
+impl CommandExecutor for InMemoryFileSystem {
+    fn exec(
+        &self,
+        program: &str,
+        _args: &[String],
+        _env: &[(&str, String)],
+        _cwd: Option<&Path>,
+        _stdio: Stdio,
+    ) -> Result<i32, Error> {
+        Err(Error::ShellProgramNotFound {
+            program: program.to_string(),
+        })
+    }
+}
+
 impl FileSystemWriter for InMemoryFileSystem {
     fn writer(&self, path: &Path) -> Result<WrappedWriter, Error> {
         let mut files = (*self.files).borrow_mut();
@@ -38,33 +79,54 @@ impl FileSystemWriter for InMemoryFileSystem {
     }
 
     fn delete(&self, path: &Path) -> Result<(), Error> {
+        // Deleting a directory deletes the files within it
         let mut files = (*self.files).borrow_mut();
-        let _ = files.remove(path);
+        files.retain(|file_path, _| !file_path.starts_with(path));
         Ok(())
     }
 
-    fn copy(&self, _from: &Path, _to: &Path) -> Result<(), Error> {
-        panic!("unimplemented") // TODO
+    fn copy(&self, from: &Path, to: &Path) -> Result<(), Error> {
+        let file = self.file(from)?;
+        let copy = InMemoryFile::default();
+        *copy.buffer.borrow_mut() = file.buffer.borrow().clone();
+        let _ = (*self.files).borrow_mut().insert(to.to_path_buf(), copy);
+        Ok(())
     }
 
-    fn copy_dir(&self, _: &Path, _: &Path) -> Result<(), Error> {
-        panic!("unimplemented") // TODO
+    fn copy_dir(&self, from: &Path, to: &Path) -> Result<(), Error> {
+        // As with the file system, the directory is copied into the
+        // destination rather than replacing it
+        let to = to.join(from.file_name().unwrap_or_default());
+        for (path, relative) in self.files_within(from) {
+            self.copy(&path, &to.join(relative))?;
+        }
+        Ok(())
     }
 
     fn mkdir(&self, _: &Path) -> Result<(), Error> {
-        panic!("unimplemented") // TODO
+        // Directories exist implicitly, as the parents of files
+        Ok(())
     }
 
-    fn hardlink(&self, _: &Path, _: &Path) -> Result<(), Error> {
-        panic!("unimplemented") // TODO
+    fn hardlink(&self, from: &Path, to: &Path) -> Result<(), Error> {
+        // The files share the same buffer, so writes to one are seen by both
+        let file = self.file(from)?;
+        let _ = (*self.files).borrow_mut().insert(to.to_path_buf(), file);
+        Ok(())
     }
 
-    fn symlink_dir(&self, _: &Path, _: &Path) -> Result<(), Error> {
-        panic!("unimplemented") // TODO
+    fn symlink_dir(&self, from: &Path, to: &Path) -> Result<(), Error> {
+        // Each file of the directory is linked, so files added to it later
+        // are not seen through the link
+        for (path, relative) in self.files_within(from) {
+            self.hardlink(&path, &to.join(relative))?;
+        }
+        Ok(())
     }
 
-    fn delete_file(&self, _: &Path) -> Result<(), Error> {
-        panic!("unimplemented") // TODO
+    fn delete_file(&self, path: &Path) -> Result<(), Error> {
+        let _ = (*self.files).borrow_mut().remove(path);
+        Ok(())
     }
 }
 
@@ -95,13 +157,7 @@ impl FileSystemReader for InMemoryFileSystem {
 
     fn read(&self, path: &Path) -> Result<String, Error> {
         let path = path.to_path_buf();
-        let files = (*self.files).borrow();
-        let file = files.get(&path).ok_or_else(|| Error::FileIo {
-            kind: FileKind::File,
-            action: FileIoAction::Open,
-            path: path.clone(),
-            err: None,
-        })?;
+        let file = self.file(&path)?;
         let bytes = file.buffer.borrow();
         let unicode = String::from_utf8(bytes.clone()).map_err(|err| Error::FileIo {
             kind: FileKind::File,
@@ -116,12 +172,16 @@ impl FileSystemReader for InMemoryFileSystem {
         (*self.files).borrow().contains_key(path)
     }
 
-    fn is_directory(&self, _path: &Path) -> bool {
-        unreachable!() // TODO
+    fn is_directory(&self, path: &Path) -> bool {
+        (*self.files)
+            .borrow()
+            .keys()
+            .any(|file_path| file_path != path && file_path.starts_with(path))
     }
 
-    fn reader(&self, _path: &Path) -> Result<WrappedReader, Error> {
-        unreachable!() // TODO
+    fn reader(&self, path: &Path) -> Result<WrappedReader, Error> {
+        let bytes = self.file(path)?.buffer.borrow().clone();
+        Ok(WrappedReader::new(path, Box::new(io::Cursor::new(bytes))))
     }
 
     fn read_dir(&self, path: &Path) -> Result<ReadDir> {
@@ -168,3 +228,33 @@ impl std::io::Write for InMemoryFile {
         reference.flush()
     }
 }
+
+#[test]
+fn directories_test() {
+    let io = InMemoryFileSystem::new();
+    io.writer(Path::new("/src/app.gleam"))
+        .and_then(|mut writer| writer.write(b"pub fn main() { 1 }"))
+        .expect("write");
+    assert!(io.is_directory(Path::new("/src")));
+    assert!(!io.is_directory(Path::new("/src/app.gleam")));
+
+    io.copy_dir(Path::new("/src"), Path::new("/build"))
+        .expect("copy_dir");
+    io.symlink_dir(Path::new("/src"), Path::new("/link"))
+        .expect("symlink_dir");
+    io.writer(Path::new("/src/app.gleam"))
+        .and_then(|mut writer| writer.write(b" "))
+        .expect("write");
+    assert_eq!(
+        io.read(Path::new("/build/src/app.gleam")),
+        Ok("pub fn main() { 1 }".into())
+    );
+    assert_eq!(
+        io.read(Path::new("/link/app.gleam")),
+        Ok("pub fn main() { 1 } ".into())
+    );
+
+    io.delete(Path::new("/src")).expect("delete");
+    assert!(!io.is_file(Path::new("/src/app.gleam")));
+    assert!(io.is_file(Path::new("/build/src/app.gleam")));
+}
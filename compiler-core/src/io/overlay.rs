@@ -0,0 +1,234 @@
+use super::*;
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    sync::{Arc, Mutex},
+};
+
+/// The content of files held in memory, by absolute path, such as the
+/// buffers of an editor that have not been saved. Clones share the same
+/// files, so that they can be updated while a compiler is reading them.
+#[derive(Debug, Clone, Default)]
+pub struct Overlay {
+    files: Arc<Mutex<HashMap<PathBuf, String>>>,
+}
+
+impl Overlay {
+    pub fn get(&self, path: &Path) -> Option<String> {
+        self.files.lock().expect("Overlay lock").get(path).cloned()
+    }
+
+    pub fn insert(&self, path: impl Into<PathBuf>, text: String) {
+        let _ = self
+            .files
+            .lock()
+            .expect("Overlay lock")
+            .insert(path.into(), text);
+    }
+
+    pub fn remove(&self, path: impl AsRef<Path>) {
+        let _ = self
+            .files
+            .lock()
+            .expect("Overlay lock")
+            .remove(path.as_ref());
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        self.files
+            .lock()
+            .expect("Overlay lock")
+            .keys()
+            .cloned()
+            .collect()
+    }
+}
+
+/// File system IO that reads the files of an overlay from memory, and every
+/// other file from the IO it wraps. Files are written to the wrapped IO.
+///
+/// The language server uses this so that a project is compiled with the
+/// changes the user has not yet saved.
+#[derive(Debug, Clone)]
+pub struct OverlayFileSystem<IO> {
+    io: IO,
+    overlay: Overlay,
+}
+
+impl<IO: FileSystemReader> OverlayFileSystem<IO> {
+    pub fn new(io: IO, overlay: Overlay) -> Self {
+        Self { io, overlay }
+    }
+
+    fn overlaid(&self, path: &Path) -> Option<String> {
+        if path.is_absolute() {
+            return self.overlay.get(path);
+        }
+        let path = self.io.current_dir().ok()?.join(path);
+        self.overlay.get(&path)
+    }
+
+    // The files of the overlay in the directory with the extension that the
+    // wrapped IO does not have
+    fn overlaid_files(&self, dir: &Path, extension: &str) -> Vec<PathBuf> {
+        let absolute = match self.io.current_dir() {
+            Ok(current) => current.join(dir),
+            Err(_) => dir.to_path_buf(),
+        };
+        self.overlay
+            .paths()
+            .into_iter()
+            .filter(|path| path.extension() == Some(OsStr::new(extension)))
+            .filter_map(|path| Some(dir.join(path.strip_prefix(&absolute).ok()?)))
+            .filter(|path| !self.io.is_file(path))
+            .collect()
+    }
+}
+
+impl<IO: FileSystemReader> FileSystemReader for OverlayFileSystem<IO> {
+    fn gleam_source_files(&self, dir: &Path) -> Box<dyn Iterator<Item = PathBuf>> {
+        let overlaid = self.overlaid_files(dir, "gleam");
+        Box::new(self.io.gleam_source_files(dir).chain(overlaid))
+    }
+
+    fn gleam_metadata_files(&self, dir: &Path) -> Box<dyn Iterator<Item = PathBuf>> {
+        self.io.gleam_metadata_files(dir)
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<ReadDir> {
+        self.io.read_dir(path)
+    }
+
+    fn read(&self, path: &Path) -> Result<String, Error> {
+        match self.overlaid(path) {
+            Some(text) => Ok(text),
+            None => self.io.read(path),
+        }
+    }
+
+    fn reader(&self, path: &Path) -> Result<WrappedReader, Error> {
+        match self.overlaid(path) {
+            Some(text) => Ok(WrappedReader::new(
+                path,
+                Box::new(io::Cursor::new(text.into_bytes())),
+            )),
+            None => self.io.reader(path),
+        }
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.overlaid(path).is_some() || self.io.is_file(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.io.is_directory(path)
+    }
+
+    fn current_dir(&self) -> Result<PathBuf, Error> {
+        self.io.current_dir()
+    }
+}
+
+impl<IO: FileSystemWriter> FileSystemWriter for OverlayFileSystem<IO> {
+    fn mkdir(&self, path: &Path) -> Result<(), Error> {
+        self.io.mkdir(path)
+    }
+
+    fn writer(&self, path: &Path) -> Result<WrappedWriter, Error> {
+        self.io.writer(path)
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), Error> {
+        self.io.delete(path)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> Result<(), Error> {
+        self.io.copy(from, to)
+    }
+
+    fn copy_dir(&self, from: &Path, to: &Path) -> Result<(), Error> {
+        self.io.copy_dir(from, to)
+    }
+
+    fn hardlink(&self, from: &Path, to: &Path) -> Result<(), Error> {
+        self.io.hardlink(from, to)
+    }
+
+    fn symlink_dir(&self, from: &Path, to: &Path) -> Result<(), Error> {
+        self.io.symlink_dir(from, to)
+    }
+
+    fn delete_file(&self, path: &Path) -> Result<(), Error> {
+        self.io.delete_file(path)
+    }
+}
+
+impl<IO: CommandExecutor> CommandExecutor for OverlayFileSystem<IO> {
+    fn exec(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &[(&str, String)],
+        cwd: Option<&Path>,
+        stdio: Stdio,
+    ) -> Result<i32, Error> {
+        self.io.exec(program, args, env, cwd, stdio)
+    }
+
+    fn exec_captured(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &[(&str, String)],
+        cwd: Option<&Path>,
+    ) -> Result<(i32, String), Error> {
+        self.io.exec_captured(program, args, env, cwd)
+    }
+
+    fn exec_output(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &[(&str, String)],
+        cwd: Option<&Path>,
+    ) -> Result<CommandOutput, Error> {
+        self.io.exec_output(program, args, env, cwd)
+    }
+}
+
+impl<IO: FileSystemIO> FileSystemIO for OverlayFileSystem<IO> {}
+
+#[test]
+fn overlay_test() {
+    let io = memory::InMemoryFileSystem::new();
+    io.writer(Path::new("/src/app.gleam"))
+        .and_then(|mut writer| writer.write(b"pub fn main() { 1 }"))
+        .expect("write");
+    let overlay = Overlay::default();
+    let overlaid = OverlayFileSystem::new(io, overlay.clone());
+
+    overlay.insert("/src/app.gleam", "pub fn main() { 2 }".into());
+    overlay.insert("/src/new.gleam", "pub fn new() { 3 }".into());
+    assert_eq!(
+        overlaid.read(Path::new("/src/app.gleam")),
+        Ok("pub fn main() { 2 }".into())
+    );
+    assert!(overlaid.is_file(Path::new("/src/new.gleam")));
+    let mut sources: Vec<_> = overlaid.gleam_source_files(Path::new("/src")).collect();
+    sources.sort();
+    assert_eq!(
+        sources,
+        vec![
+            PathBuf::from("/src/app.gleam"),
+            PathBuf::from("/src/new.gleam")
+        ]
+    );
+
+    overlay.remove("/src/app.gleam");
+    overlay.remove("/src/new.gleam");
+    assert_eq!(
+        overlaid.read(Path::new("/src/app.gleam")),
+        Ok("pub fn main() { 1 }".into())
+    );
+    assert!(!overlaid.is_file(Path::new("/src/new.gleam")));
+}
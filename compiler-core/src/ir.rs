@@ -1,15 +1,21 @@
 use itertools::Itertools;
 
-use crate::ast;
-use crate::type_::{ModuleValueConstructor, Type, ValueConstructor, ValueConstructorVariant};
+use crate::ast::{self, SrcSpan};
+use crate::name::Name;
+use crate::type_::{
+    FieldMap, ModuleValueConstructor, Type, ValueConstructor, ValueConstructorVariant, VariantField,
+};
 use crate::uid::UniqueIdGenerator;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::vec::Vec;
 
 /// # An intermediate representation (IR) of Gleam's AST for a "simple" procedural language.
 ///
-/// Right now this IR supports being emitted to either C++ or JavaScript with very little actual
-/// transformations or needing to be "lowered" to another IR.
+/// Right now this IR supports being emitted to either C++ or LLVM with very little actual
+/// transformations or needing to be "lowered" to another IR. Pattern matching is lowered here into
+/// a sequence of checks and bindings, so each backend shares the same decision making for `case`.
 ///
 /// This IR preserves the full Gleam type information for output languages that are also typed.
 /// There are operations in the IR for "casting", which maybe a noop on dynamic languages (such as
@@ -20,10 +26,12 @@ pub enum Statement<'a> {
     Return {
         expr: Expression<'a>,
     },
+    /// The type is `None` for variables bound by a pattern within a record, whose field types are
+    /// not known here, so targets that need a type should infer it.
     Assignment {
         var: Identifier<'a>,
         expr: Expression<'a>,
-        typ: Arc<Type>,
+        typ: Option<Arc<Type>>,
     },
     /// An expression with an unused result. This maybe a side-effect or just dead code.
     Expr {
@@ -60,7 +68,7 @@ pub enum Expression<'a> {
 #[derive(Debug, Clone)]
 pub enum Literal<'a> {
     Bool { value: bool },
-    Int { value: &'a str },
+    Int { value: Cow<'a, str> },
     Float { value: &'a str },
     String { value: String },
     Nil,
@@ -73,8 +81,12 @@ pub enum UnaryOp {
 
 #[derive(Debug, Clone)]
 pub enum Accessor<'a> {
+    /// A field of a record. The field is at `index` in the record's constructor, unless the record
+    /// is one of the constructors in `variants`, which have the field at a different position.
     Custom {
-        label: &'a str,
+        label: Option<&'a str>,
+        index: u64,
+        variants: &'a [VariantField],
         reciever: Box<Expression<'a>>,
     },
     TupleIndex {
@@ -87,6 +99,8 @@ pub enum Accessor<'a> {
     },
     ModuleVariable {
         public: bool,
+        /// The module defining the variable. This is empty for functions of the current module
+        /// called from a guard, which do not record their module.
         module: Vec<&'a str>,
         /// This is the imported name of the module, if not set, then the module is either defined
         /// within the current module OR is an unqualified import.
@@ -94,6 +108,9 @@ pub enum Accessor<'a> {
         name: &'a str,
         typ: Arc<Type>,
     },
+    /// A function implemented in the target language with an `external fn`, referred to by its
+    /// name there such as `std::sqrt`.
+    External { name: &'a str, typ: Arc<Type> },
 }
 
 #[derive(Debug, Clone)]
@@ -129,7 +146,11 @@ pub enum TypeConstruction<'a> {
         name: &'a str,
         typ: Arc<Type>,
     },
-    // BitString {},
+    /// A bit string made of each segment in order.
+    BitString {
+        location: SrcSpan,
+        segments: Vec<BitStringSegment<'a>>,
+    },
     /// λ
     Function {
         typ: Arc<Type>,
@@ -138,6 +159,24 @@ pub enum TypeConstruction<'a> {
     },
 }
 
+#[derive(Debug, Clone)]
+pub struct BitStringSegment<'a> {
+    pub value: Expression<'a>,
+    pub kind: BitStringSegmentKind<'a>,
+}
+
+#[derive(Debug, Clone)]
+pub enum BitStringSegmentKind<'a> {
+    /// An unsigned big endian int of the given number of bits.
+    Int { size: Box<Expression<'a>> },
+    /// A 64 bit big endian float.
+    Float,
+    /// The UTF-8 bytes of a string.
+    Utf8,
+    /// All the bits of another bit string.
+    BitString,
+}
+
 #[derive(Debug, Clone)]
 pub struct FunctionArg<'a> {
     pub name: Identifier<'a>,
@@ -169,7 +208,10 @@ pub enum Identifier<'a> {
 pub enum Call<'a> {
     /// A "builtin" function is a function that is provided by the gleam compiler. It is usually
     /// apart of the prelude, but can sometimes be provided by the target language itself.
-    // Builtin(BuiltinFn),
+    Builtin {
+        function: BuiltinFn,
+        args: Vec<Expression<'a>>,
+    },
     /// Invoking a Gleam defined function in this module or another.
     Fn {
         callee: Box<Expression<'a>>,
@@ -179,22 +221,72 @@ pub enum Call<'a> {
 
 /// A "builtin" function is a function that is provided by the gleam compiler. It is usually
 /// apart of the prelude, but can sometimes be provided by the target language itself.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum BuiltinFn {
-    ListAtLeastLength,
+    /// Whether the list argument is empty.
+    ListIsEmpty,
+    /// The first element of a non-empty list.
     ListHead,
+    /// The rest of a non-empty list.
     ListTail,
+    /// Whether the argument was built with the named constructor of its custom type.
+    IsRecord { name: Name },
+    /// Whether the first string argument starts with the second.
+    StringHasPrefix,
+    /// The first string argument without the second, which it is known to start with.
+    StringDropPrefix,
+    /// Crashes the program with the string argument as the message.
+    Panic,
+    /// Prints the first argument along with its type, the second argument, and returns it.
+    Echo,
+    /// Calls the function argument, returning `Ok` with its result, or `Error` with a
+    /// `CrashReason` if it crashed.
+    Rescue { location: SrcSpan },
+    /// The number of bits in the bit string argument.
+    ///
+    /// The bit string builtins are only used for patterns, and the location is of the pattern.
+    BitStringSize { location: SrcSpan },
+    /// An unsigned big endian int read from the first bit string argument, at the offset and of
+    /// the size in bits given by the second and third.
+    BitStringInt { location: SrcSpan },
+    /// A 64 bit big endian float read from the first bit string argument at the offset in bits
+    /// given by the second.
+    BitStringFloat { location: SrcSpan },
+    /// The bits of the first bit string argument at the offset and of the size in bits given by
+    /// the second and third.
+    BitStringSlice { location: SrcSpan },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A Gleam feature which cannot be converted into the IR yet.
+    Unsupported { feature: String, location: SrcSpan },
+}
+
+fn unsupported<T>(feature: &str, location: SrcSpan) -> Result<T, Error> {
+    Err(Error::Unsupported {
+        feature: feature.into(),
+        location,
+    })
+}
+
+/// The checks a value must pass to match a pattern and the variables the pattern binds.
+#[derive(Debug, Default)]
+struct CompiledPattern<'a> {
+    checks: Vec<Expression<'a>>,
+    bindings: Vec<Statement<'a>>,
 }
 
 #[derive(Debug)]
 pub struct IntermediateRepresentationConverter<'module> {
+    module: &'module [Name],
     internal_variable_id_generator: UniqueIdGenerator,
     discard_variable_id_generator: UniqueIdGenerator,
     current_scope_vars: im::HashMap<&'module str, u64>,
 }
 
 impl<'module> IntermediateRepresentationConverter<'module> {
-    pub fn new_for_function(args: &'module [ast::Arg<Arc<Type>>]) -> Self {
+    pub fn new_for_function(module: &'module [Name], args: &'module [ast::Arg<Arc<Type>>]) -> Self {
         let mut current_scope_vars = im::HashMap::new();
         for arg in args {
             if let Some(name) = arg.names.get_variable_name() {
@@ -202,6 +294,7 @@ impl<'module> IntermediateRepresentationConverter<'module> {
             }
         }
         IntermediateRepresentationConverter {
+            module,
             internal_variable_id_generator: UniqueIdGenerator::new(),
             discard_variable_id_generator: UniqueIdGenerator::new(),
             current_scope_vars,
@@ -209,7 +302,10 @@ impl<'module> IntermediateRepresentationConverter<'module> {
     }
     /// Converts a typed expression that represents the body of a function call in gleam to a
     /// procedural IR.
-    pub fn ast_to_ir(&mut self, expr: &'module ast::TypedExpr) -> Vec<Statement<'module>> {
+    pub fn ast_to_ir(
+        &mut self,
+        expr: &'module ast::TypedExpr,
+    ) -> Result<Vec<Statement<'module>>, Error> {
         match expr {
             ast::TypedExpr::Sequence { expressions, .. }
             | ast::TypedExpr::Pipeline { expressions, .. } => {
@@ -222,21 +318,21 @@ impl<'module> IntermediateRepresentationConverter<'module> {
     fn convert_top_level_exprs_to_ir(
         &mut self,
         exprs: &'module [ast::TypedExpr],
-    ) -> Vec<Statement<'module>> {
+    ) -> Result<Vec<Statement<'module>>, Error> {
         let last_index = exprs.len() - 1;
-        exprs
-            .iter()
-            .enumerate()
-            .flat_map(|(i, e)| self.convert_top_level_expr_to_ir(e, i == last_index))
-            .collect()
+        let mut statements = vec![];
+        for (i, e) in exprs.iter().enumerate() {
+            statements.extend(self.convert_top_level_expr_to_ir(e, i == last_index)?);
+        }
+        Ok(statements)
     }
 
     fn convert_top_level_expr_to_ir(
         &mut self,
         expr: &'module ast::TypedExpr,
         is_in_return_position: bool,
-    ) -> Vec<Statement<'module>> {
-        match expr {
+    ) -> Result<Vec<Statement<'module>>, Error> {
+        Ok(match expr {
             ast::TypedExpr::Assignment {
                 typ,
                 value,
@@ -246,8 +342,8 @@ impl<'module> IntermediateRepresentationConverter<'module> {
             } => {
                 let mut assignment = vec![Statement::Assignment {
                     var: self.allocate_named_id(name),
-                    expr: self.convert_expr_to_ir(value),
-                    typ: typ.to_owned(),
+                    expr: self.convert_expr_to_ir(value)?,
+                    typ: Some(typ.to_owned()),
                 }];
                 if is_in_return_position {
                     assignment.push(Statement::Return {
@@ -259,26 +355,79 @@ impl<'module> IntermediateRepresentationConverter<'module> {
                 }
                 assignment
             }
-            ast::TypedExpr::Assignment { .. } => todo!(),
-            ast::TypedExpr::Try { .. } => todo!(),
+            ast::TypedExpr::Assignment {
+                typ,
+                value,
+                pattern,
+                message,
+                ..
+            } => self.convert_assignment_to_ir(
+                typ,
+                value,
+                pattern,
+                message.as_deref(),
+                is_in_return_position,
+            )?,
+            ast::TypedExpr::Try { location, .. } => return unsupported("Try", *location),
             // TODO: When `try` is supported, this is no longer valid, but JS makes this assumption
             // so it's probably fine until https://github.com/gleam-lang/gleam/issues/1834 is
             // fixed. Once that is fixed we should just be able to delete this case so it's wrapped
             // in a block.
             ast::TypedExpr::Sequence { expressions, .. }
             | ast::TypedExpr::Pipeline { expressions, .. } => {
-                self.convert_top_level_exprs_to_ir(expressions)
+                self.convert_top_level_exprs_to_ir(expressions)?
             }
             ast::TypedExpr::Case {
                 subjects, clauses, ..
-            } if is_in_return_position => self.convert_case_to_ir(subjects, clauses),
+            } if is_in_return_position => self.convert_case_to_ir(subjects, clauses)?,
             _ if is_in_return_position => vec![Statement::Return {
-                expr: self.convert_expr_to_ir(expr),
+                expr: self.convert_expr_to_ir(expr)?,
             }],
             _ => vec![Statement::Expr {
-                expr: self.convert_expr_to_ir(expr),
+                expr: self.convert_expr_to_ir(expr)?,
             }],
+        })
+    }
+
+    // A `let` or `assert` with a pattern other than a variable. The value is stored in a variable
+    // and the program crashes with the message of the `assert`, if any, if it does not match the
+    // pattern.
+    fn convert_assignment_to_ir(
+        &mut self,
+        typ: &'module Arc<Type>,
+        value: &'module ast::TypedExpr,
+        pattern: &'module ast::TypedPattern,
+        message: Option<&str>,
+        is_in_return_position: bool,
+    ) -> Result<Vec<Statement<'module>>, Error> {
+        let subject = self.allocate_internal_id();
+        let mut statements = vec![Statement::Assignment {
+            var: subject.clone(),
+            expr: self.convert_expr_to_ir(value)?,
+            typ: Some(typ.to_owned()),
+        }];
+        let subject = Expression::Accessor(Accessor::LocalVariable {
+            name: subject,
+            typ: typ.to_owned(),
+        });
+        let mut compiled = CompiledPattern::default();
+        self.convert_pattern_to_ir(pattern, subject.clone(), Some(typ), &mut compiled)?;
+        if !compiled.checks.is_empty() {
+            statements.push(Statement::Conditional {
+                test: Expression::UnaryOp {
+                    op: UnaryOp::Negate,
+                    expr: Box::new(all(compiled.checks)),
+                },
+                body: vec![Statement::Expr {
+                    expr: panic(message.unwrap_or("Assertion pattern match failed")),
+                }],
+            });
+        }
+        statements.extend(compiled.bindings);
+        if is_in_return_position {
+            statements.push(Statement::Return { expr: subject });
         }
+        Ok(statements)
     }
 
     // The case expression must be in return position, otherwise coordinating passing back the
@@ -287,20 +436,19 @@ impl<'module> IntermediateRepresentationConverter<'module> {
         &mut self,
         subjects: &'module [ast::TypedExpr],
         clauses: &'module [ast::TypedClause],
-    ) -> Vec<Statement<'module>> {
-        let subject_variables: Vec<_> = (0..subjects.len())
-            .into_iter()
-            .map(|_| self.allocate_internal_id())
-            .collect();
-        let mut statements: Vec<_> = subjects
-            .iter()
-            .zip_eq(subject_variables.iter())
-            .map(|(e, var)| Statement::Assignment {
-                var: var.to_owned(),
-                expr: self.convert_expr_to_ir(e),
-                typ: e.type_().to_owned(),
-            })
-            .collect();
+    ) -> Result<Vec<Statement<'module>>, Error> {
+        let mut statements = vec![];
+        let mut subject_variables = vec![];
+        for subject in subjects {
+            let var = self.allocate_internal_id();
+            let typ = subject.type_();
+            statements.push(Statement::Assignment {
+                var: var.clone(),
+                expr: self.convert_expr_to_ir(subject)?,
+                typ: Some(typ.clone()),
+            });
+            subject_variables.push((var, typ));
+        }
         for ast::TypedClause {
             pattern,
             alternative_patterns,
@@ -314,7 +462,7 @@ impl<'module> IntermediateRepresentationConverter<'module> {
                 pattern,
                 guard,
                 then,
-            ));
+            )?);
             // Alternative patterns are just shorthands for writing the same guard/then multiple
             // times, so just expand the shorthand here.
             // TODO: Is it better to extract the then into a lambda so that there is less code for
@@ -325,27 +473,514 @@ impl<'module> IntermediateRepresentationConverter<'module> {
                     pattern,
                     guard,
                     then,
-                ));
+                )?);
             }
         }
-        return statements;
+        Ok(statements)
     }
 
     // multipattern is the pattern for each subject
     // guard is an extra if statement before executing then.
     fn convert_multipattern_to_ir(
         &mut self,
-        subject_variables: &[Identifier<'module>],
+        subject_variables: &[(Identifier<'module>, Arc<Type>)],
         multipattern: &'module ast::TypedMultiPattern,
         guard: &'module Option<ast::TypedClauseGuard>,
         then: &'module ast::TypedExpr,
-    ) -> Statement<'module> {
-        todo!()
+    ) -> Result<Statement<'module>, Error> {
+        self.with_new_scope(|conv| {
+            let mut compiled = CompiledPattern::default();
+            for (pattern, (var, typ)) in multipattern.iter().zip_eq(subject_variables) {
+                let subject = Expression::Accessor(Accessor::LocalVariable {
+                    name: var.clone(),
+                    typ: typ.clone(),
+                });
+                conv.convert_pattern_to_ir(pattern, subject, Some(typ), &mut compiled)?;
+            }
+            let mut body = compiled.bindings;
+            let then = conv.convert_top_level_expr_to_ir(then, true)?;
+            match guard {
+                Some(guard) => body.push(Statement::Conditional {
+                    test: conv.convert_guard_to_ir(guard)?,
+                    body: then,
+                }),
+                None => body.extend(then),
+            }
+            Ok(Statement::Conditional {
+                test: all(compiled.checks),
+                body,
+            })
+        })
     }
 
-    fn convert_expr_to_ir(&mut self, expr: &'module ast::TypedExpr) -> Expression<'module> {
-        match expr {
-            ast::TypedExpr::Int { value, .. } => Expression::Literal(Literal::Int { value }),
+    // Adds the checks that the subject matches the pattern to `compiled`, and the assignments of
+    // the variables the pattern binds.
+    fn convert_pattern_to_ir(
+        &mut self,
+        pattern: &'module ast::TypedPattern,
+        subject: Expression<'module>,
+        typ: Option<&Arc<Type>>,
+        compiled: &mut CompiledPattern<'module>,
+    ) -> Result<(), Error> {
+        let equals = |subject, literal| Expression::BinOp {
+            left: Box::new(subject),
+            op: ast::BinOp::Eq,
+            right: Box::new(Expression::Literal(literal)),
+        };
+        match pattern {
+            ast::Pattern::Int { value, .. } => compiled.checks.push(equals(
+                subject,
+                Literal::Int {
+                    value: Cow::Borrowed(value),
+                },
+            )),
+            ast::Pattern::Float { value, .. } => compiled
+                .checks
+                .push(equals(subject, Literal::Float { value })),
+            ast::Pattern::String { value, .. } => compiled.checks.push(equals(
+                subject,
+                Literal::String {
+                    value: value.replace('\n', r#"\n"#),
+                },
+            )),
+            ast::Pattern::IntRange { start, end, .. } => {
+                let bound = |op, value| Expression::BinOp {
+                    left: Box::new(subject.clone()),
+                    op,
+                    right: Box::new(Expression::Literal(Literal::Int {
+                        value: Cow::Borrowed(value),
+                    })),
+                };
+                compiled.checks.push(bound(ast::BinOp::GtEqInt, start));
+                compiled.checks.push(bound(ast::BinOp::LtEqInt, end));
+            }
+            ast::Pattern::Var { name, .. } => compiled.bindings.push(Statement::Assignment {
+                var: self.allocate_named_id(name),
+                expr: subject,
+                typ: typ.cloned(),
+            }),
+            ast::Pattern::Assign { name, pattern, .. } => {
+                compiled.bindings.push(Statement::Assignment {
+                    var: self.allocate_named_id(name),
+                    expr: subject.clone(),
+                    typ: typ.cloned(),
+                });
+                self.convert_pattern_to_ir(pattern, subject, typ, compiled)?;
+            }
+            ast::Pattern::Discard { .. } => (),
+            ast::Pattern::List { elements, tail, .. } => {
+                let mut list = subject;
+                for element in elements {
+                    compiled.checks.push(Expression::UnaryOp {
+                        op: UnaryOp::Negate,
+                        expr: Box::new(builtin(BuiltinFn::ListIsEmpty, vec![list.clone()])),
+                    });
+                    let head = builtin(BuiltinFn::ListHead, vec![list.clone()]);
+                    self.convert_pattern_to_ir(element, head, None, compiled)?;
+                    list = builtin(BuiltinFn::ListTail, vec![list]);
+                }
+                match tail {
+                    Some(tail) => self.convert_pattern_to_ir(tail, list, typ, compiled)?,
+                    None => compiled
+                        .checks
+                        .push(builtin(BuiltinFn::ListIsEmpty, vec![list])),
+                }
+            }
+            ast::Pattern::Tuple { elems, .. } => {
+                for (index, elem) in elems.iter().enumerate() {
+                    let element = Expression::Accessor(Accessor::TupleIndex {
+                        index: index as u64,
+                        tuple: Box::new(subject.clone()),
+                    });
+                    self.convert_pattern_to_ir(elem, element, None, compiled)?;
+                }
+            }
+            ast::Pattern::Constructor {
+                constructor:
+                    crate::type_::PatternConstructor::Record {
+                        name, field_map, ..
+                    },
+                arguments,
+                type_,
+                ..
+            } => {
+                if type_.is_bool() {
+                    compiled.checks.push(if name == "True" {
+                        subject
+                    } else {
+                        Expression::UnaryOp {
+                            op: UnaryOp::Negate,
+                            expr: Box::new(subject),
+                        }
+                    });
+                    return Ok(());
+                }
+                if type_.is_nil() {
+                    return Ok(());
+                }
+                compiled.checks.push(builtin(
+                    BuiltinFn::IsRecord { name: name.clone() },
+                    vec![subject.clone()],
+                ));
+                for (index, arg) in arguments.iter().enumerate() {
+                    let field = Expression::Accessor(Accessor::Custom {
+                        label: field_label(field_map.as_ref(), index),
+                        index: index as u64,
+                        variants: &[],
+                        reciever: Box::new(subject.clone()),
+                    });
+                    self.convert_pattern_to_ir(&arg.value, field, None, compiled)?;
+                }
+            }
+            ast::Pattern::Concatenate {
+                left_side_string,
+                right_side_assignment,
+                ..
+            } => {
+                let prefix = Expression::Literal(Literal::String {
+                    value: left_side_string.replace('\n', r#"\n"#),
+                });
+                compiled.checks.push(builtin(
+                    BuiltinFn::StringHasPrefix,
+                    vec![subject.clone(), prefix.clone()],
+                ));
+                if let ast::AssignName::Variable(name) = right_side_assignment {
+                    compiled.bindings.push(Statement::Assignment {
+                        var: self.allocate_named_id(name),
+                        expr: builtin(BuiltinFn::StringDropPrefix, vec![subject, prefix]),
+                        typ: typ.cloned(),
+                    });
+                }
+            }
+            ast::Pattern::BitString { location, segments } => {
+                self.convert_bit_string_pattern_to_ir(*location, segments, subject, compiled)?
+            }
+            // Variables are only used in patterns as the size of a bit string segment, which is
+            // handled with the rest of the bit string.
+            ast::Pattern::VarUsage { location, .. } => {
+                return unsupported("Bit string patterns", *location)
+            }
+        }
+        Ok(())
+    }
+
+    // Each segment is read at its offset in bits from the start of the subject. A read in the
+    // checks, such as of a segment matched against a literal or used as a size, comes after a
+    // check that the subject is long enough, and the reads for the bindings after the final check
+    // of the length.
+    fn convert_bit_string_pattern_to_ir(
+        &mut self,
+        location: SrcSpan,
+        segments: &'module [ast::TypedPatternBitStringSegment],
+        subject: Expression<'module>,
+        compiled: &mut CompiledPattern<'module>,
+    ) -> Result<(), Error> {
+        use ast::BitStringSegmentOption as Opt;
+
+        let subject_size = builtin(BuiltinFn::BitStringSize { location }, vec![subject.clone()]);
+        let at_least = |bits: &Bits<'module>| Expression::BinOp {
+            left: Box::new(subject_size.clone()),
+            op: ast::BinOp::GtEqInt,
+            right: Box::new(bits.to_expression()),
+        };
+        // The variables bound by earlier segments are not assigned until every check has passed,
+        // so a size referring to one reads the segment again instead.
+        let mut bound = HashMap::new();
+        let mut offset = Bits::default();
+        // Whether the subject has been checked to be at least `offset` bits long
+        let mut checked = true;
+        for segment in segments {
+            let (read, size) = match segment.options.as_slice() {
+                [] | [Opt::Int { .. }] => (BuiltinFn::BitStringInt { location }, Bits::constant(8)),
+                [Opt::Size { value: size, .. }] => (
+                    BuiltinFn::BitStringInt { location },
+                    self.bit_string_pattern_size(size, 1, &bound, segment.location)?,
+                ),
+                [Opt::Binary { .. }, Opt::Size { value: size, .. }]
+                | [Opt::Size { value: size, .. }, Opt::Binary { .. }] => (
+                    BuiltinFn::BitStringSlice { location },
+                    self.bit_string_pattern_size(size, 8, &bound, segment.location)?,
+                ),
+                [Opt::Float { .. }] => (BuiltinFn::BitStringFloat { location }, Bits::constant(64)),
+                // The rest of the subject, which must be whole bytes
+                [Opt::Binary { .. }] => {
+                    if !checked {
+                        compiled.checks.push(at_least(&offset));
+                    }
+                    let rest = Expression::BinOp {
+                        left: Box::new(subject_size.clone()),
+                        op: ast::BinOp::SubInt,
+                        right: Box::new(offset.to_expression()),
+                    };
+                    compiled.checks.push(Expression::BinOp {
+                        left: Box::new(Expression::BinOp {
+                            left: Box::new(rest.clone()),
+                            op: ast::BinOp::RemainderInt,
+                            right: Box::new(int_literal(8)),
+                        }),
+                        op: ast::BinOp::Eq,
+                        right: Box::new(int_literal(0)),
+                    });
+                    let value = builtin(
+                        BuiltinFn::BitStringSlice { location },
+                        vec![subject.clone(), offset.to_expression(), rest],
+                    );
+                    self.convert_bit_string_segment_pattern_to_ir(
+                        segment, value, &mut bound, compiled,
+                    )?;
+                    return Ok(());
+                }
+                _ => {
+                    return unsupported(
+                        "This bit string segment option in patterns",
+                        segment.location,
+                    )
+                }
+            };
+            if !size.dynamic.is_empty() {
+                if !checked {
+                    compiled.checks.push(at_least(&offset));
+                }
+                for size in &size.dynamic {
+                    compiled.checks.push(Expression::BinOp {
+                        left: Box::new(size.clone()),
+                        op: ast::BinOp::GtEqInt,
+                        right: Box::new(int_literal(0)),
+                    });
+                }
+            }
+            let end = offset.add(&size);
+            let mut args = vec![subject.clone(), offset.to_expression()];
+            if !matches!(read, BuiltinFn::BitStringFloat { .. }) {
+                args.push(size.to_expression());
+            }
+            let mut segment_compiled = CompiledPattern::default();
+            self.convert_bit_string_segment_pattern_to_ir(
+                segment,
+                builtin(read, args),
+                &mut bound,
+                &mut segment_compiled,
+            )?;
+            checked = !segment_compiled.checks.is_empty();
+            if checked {
+                compiled.checks.push(at_least(&end));
+            }
+            compiled.checks.extend(segment_compiled.checks);
+            compiled.bindings.extend(segment_compiled.bindings);
+            offset = end;
+        }
+        compiled.checks.push(Expression::BinOp {
+            left: Box::new(subject_size),
+            op: ast::BinOp::Eq,
+            right: Box::new(offset.to_expression()),
+        });
+        Ok(())
+    }
+
+    fn convert_bit_string_segment_pattern_to_ir(
+        &mut self,
+        segment: &'module ast::TypedPatternBitStringSegment,
+        value: Expression<'module>,
+        bound: &mut HashMap<&'module str, Expression<'module>>,
+        compiled: &mut CompiledPattern<'module>,
+    ) -> Result<(), Error> {
+        if let ast::Pattern::Var { name, .. } | ast::Pattern::Assign { name, .. } =
+            segment.value.as_ref()
+        {
+            let _ = bound.insert(name.as_str(), value.clone());
+        }
+        self.convert_pattern_to_ir(&segment.value, value, Some(&segment.type_), compiled)
+    }
+
+    /// The size in bits of a segment in a bit string pattern, where `unit` is the number of bits
+    /// in each unit of the size.
+    fn bit_string_pattern_size(
+        &mut self,
+        size: &'module ast::TypedPattern,
+        unit: u64,
+        bound: &HashMap<&'module str, Expression<'module>>,
+        location: SrcSpan,
+    ) -> Result<Bits<'module>, Error> {
+        let size = match size {
+            ast::Pattern::Int { value, .. } => {
+                let size = crate::int::parse(value)
+                    .and_then(|value| u64::try_from(value).ok())
+                    .expect("part of an Int node should always parse as integer");
+                return Ok(Bits::constant(size * unit));
+            }
+            ast::Pattern::VarUsage { name, type_, .. } => match bound.get(name.as_str()) {
+                Some(value) => value.clone(),
+                None => Expression::Accessor(Accessor::LocalVariable {
+                    name: self.lookup_named_id(name),
+                    typ: type_.clone(),
+                }),
+            },
+            _ => return unsupported("This bit string size option in patterns", location),
+        };
+        Ok(Bits::dynamic(if unit == 1 {
+            size
+        } else {
+            Expression::BinOp {
+                left: Box::new(size),
+                op: ast::BinOp::MultInt,
+                right: Box::new(int_literal(unit)),
+            }
+        }))
+    }
+
+    fn convert_guard_to_ir(
+        &mut self,
+        guard: &'module ast::TypedClauseGuard,
+    ) -> Result<Expression<'module>, Error> {
+        let (left, op, right) = match guard {
+            ast::ClauseGuard::Equals { left, right, .. } => (left, ast::BinOp::Eq, right),
+            ast::ClauseGuard::NotEquals { left, right, .. } => (left, ast::BinOp::NotEq, right),
+            ast::ClauseGuard::GtInt { left, right, .. } => (left, ast::BinOp::GtInt, right),
+            ast::ClauseGuard::GtEqInt { left, right, .. } => (left, ast::BinOp::GtEqInt, right),
+            ast::ClauseGuard::LtInt { left, right, .. } => (left, ast::BinOp::LtInt, right),
+            ast::ClauseGuard::LtEqInt { left, right, .. } => (left, ast::BinOp::LtEqInt, right),
+            ast::ClauseGuard::GtFloat { left, right, .. } => (left, ast::BinOp::GtFloat, right),
+            ast::ClauseGuard::GtEqFloat { left, right, .. } => (left, ast::BinOp::GtEqFloat, right),
+            ast::ClauseGuard::LtFloat { left, right, .. } => (left, ast::BinOp::LtFloat, right),
+            ast::ClauseGuard::LtEqFloat { left, right, .. } => (left, ast::BinOp::LtEqFloat, right),
+            ast::ClauseGuard::Or { left, right, .. } => (left, ast::BinOp::Or, right),
+            ast::ClauseGuard::And { left, right, .. } => (left, ast::BinOp::And, right),
+            ast::ClauseGuard::Var { name, type_, .. } => {
+                return Ok(Expression::Accessor(Accessor::LocalVariable {
+                    name: self.lookup_named_id(name),
+                    typ: type_.to_owned(),
+                }))
+            }
+            ast::ClauseGuard::TupleIndex { index, tuple, .. } => {
+                return Ok(Expression::Accessor(Accessor::TupleIndex {
+                    index: *index,
+                    tuple: Box::new(self.convert_guard_to_ir(tuple)?),
+                }))
+            }
+            ast::ClauseGuard::Call {
+                name, args, type_, ..
+            } => {
+                let typ = Arc::new(Type::Fn {
+                    args: args.iter().map(|arg| arg.type_()).collect(),
+                    retrn: type_.to_owned(),
+                });
+                let callee = Box::new(Expression::Accessor(Accessor::ModuleVariable {
+                    public: false,
+                    module: self.module.iter().map(|s| &s[..]).collect(),
+                    module_alias: None,
+                    name,
+                    typ,
+                }));
+                let args = args
+                    .iter()
+                    .map(|arg| self.convert_guard_to_ir(arg))
+                    .try_collect()?;
+                return Ok(Expression::Call(Call::Fn { callee, args }));
+            }
+            ast::ClauseGuard::Constant(constant) => return self.convert_constant_to_ir(constant),
+        };
+        Ok(Expression::BinOp {
+            left: Box::new(self.convert_guard_to_ir(left)?),
+            op,
+            right: Box::new(self.convert_guard_to_ir(right)?),
+        })
+    }
+
+    // Constants are inlined where they are used, so a module's constants need no definitions.
+    fn convert_constant_to_ir(
+        &mut self,
+        constant: &'module ast::TypedConstant,
+    ) -> Result<Expression<'module>, Error> {
+        Ok(match constant {
+            ast::Constant::Int { value, .. } => Expression::Literal(Literal::Int {
+                value: Cow::Borrowed(value),
+            }),
+            ast::Constant::Float { value, .. } => Expression::Literal(Literal::Float { value }),
+            ast::Constant::String { value, .. } => Expression::Literal(Literal::String {
+                value: value.replace('\n', r#"\n"#),
+            }),
+            ast::Constant::Tuple { elements, .. } => {
+                Expression::TypeConstruction(TypeConstruction::Tuple {
+                    typ: constant.type_(),
+                    elements: elements
+                        .iter()
+                        .map(|e| self.convert_constant_to_ir(e))
+                        .try_collect()?,
+                })
+            }
+            ast::Constant::List { elements, typ, .. } => {
+                Expression::TypeConstruction(TypeConstruction::List {
+                    typ: typ.to_owned(),
+                    elements: elements
+                        .iter()
+                        .map(|e| self.convert_constant_to_ir(e))
+                        .try_collect()?,
+                    tail: None,
+                })
+            }
+            ast::Constant::Record { tag, typ, .. } if typ.is_bool() => {
+                Expression::Literal(Literal::Bool {
+                    value: tag == "True",
+                })
+            }
+            ast::Constant::Record { typ, .. } if typ.is_nil() => Expression::Literal(Literal::Nil),
+            ast::Constant::Record {
+                module,
+                tag,
+                args,
+                typ,
+                ..
+            } => {
+                let (public, type_module) = match typ.as_ref() {
+                    Type::App { public, module, .. } => {
+                        (*public, module.iter().map(|s| &s[..]).collect())
+                    }
+                    _ => (true, vec![]),
+                };
+                if args.is_empty() {
+                    Expression::TypeConstruction(TypeConstruction::CustomSingleton {
+                        public,
+                        module: type_module,
+                        module_alias: module.as_deref(),
+                        name: tag,
+                        typ: typ.to_owned(),
+                    })
+                } else {
+                    Expression::TypeConstruction(TypeConstruction::Custom {
+                        public,
+                        module: type_module,
+                        module_alias: module.as_deref(),
+                        name: tag,
+                        typ: typ.to_owned(),
+                        args: args
+                            .iter()
+                            .map(|arg| self.convert_constant_to_ir(&arg.value))
+                            .try_collect()?,
+                    })
+                }
+            }
+            ast::Constant::Var {
+                name,
+                constructor: Some(constructor),
+                ..
+            } => self.convert_variable_to_ir(name, constructor)?,
+            ast::Constant::Var { location, .. } | ast::Constant::BinOp { location, .. } => {
+                return unsupported("Unresolved constants", *location)
+            }
+            ast::Constant::BitString { location, .. } => {
+                return unsupported("Bit strings", *location)
+            }
+        })
+    }
+
+    fn convert_expr_to_ir(
+        &mut self,
+        expr: &'module ast::TypedExpr,
+    ) -> Result<Expression<'module>, Error> {
+        Ok(match expr {
+            ast::TypedExpr::Int { value, .. } => Expression::Literal(Literal::Int {
+                value: Cow::Borrowed(value),
+            }),
             ast::TypedExpr::Float { value, .. } => Expression::Literal(Literal::Float { value }),
             ast::TypedExpr::String { value, .. } => Expression::Literal(Literal::String {
                 value: value.replace('\n', r#"\n"#),
@@ -353,9 +988,9 @@ impl<'module> IntermediateRepresentationConverter<'module> {
             ast::TypedExpr::BinOp {
                 name, left, right, ..
             } => Expression::BinOp {
-                left: Box::new(self.convert_expr_to_ir(left)),
+                left: Box::new(self.convert_expr_to_ir(left)?),
                 op: *name,
-                right: Box::new(self.convert_expr_to_ir(right)),
+                right: Box::new(self.convert_expr_to_ir(right)?),
             },
             ast::TypedExpr::List {
                 elements,
@@ -367,93 +1002,217 @@ impl<'module> IntermediateRepresentationConverter<'module> {
                 elements: elements
                     .iter()
                     .map(|e| self.convert_expr_to_ir(e))
-                    .collect(),
-                tail: tail.as_ref().map(|e| Box::new(self.convert_expr_to_ir(e))),
+                    .try_collect()?,
+                tail: match tail {
+                    Some(tail) => Some(Box::new(self.convert_expr_to_ir(tail)?)),
+                    None => None,
+                },
             }),
+            // `rescue` is lowered to a builtin when called, so cannot be used as a value
+            ast::TypedExpr::Var {
+                constructor,
+                location,
+                ..
+            } if constructor.variant.is_rescue() => {
+                return unsupported("References to `rescue` that are not calls", *location)
+            }
+            ast::TypedExpr::ModuleSelect {
+                constructor,
+                location,
+                ..
+            } if constructor.is_rescue() => {
+                return unsupported("References to `rescue` that are not calls", *location)
+            }
             ast::TypedExpr::Var {
                 name, constructor, ..
-            } => self.convert_variable_to_ir(name, constructor),
+            } => self.convert_variable_to_ir(name, constructor)?,
             ast::TypedExpr::Fn {
                 typ, args, body, ..
-            } => self.convert_fn_to_ir(typ, args, body),
-            ast::TypedExpr::Call { fun, args, .. } => self.convert_call_to_ir(fun, args),
+            } => self.convert_fn_to_ir(typ, args, body)?,
+            ast::TypedExpr::Call { fun, args, .. } => self.convert_call_to_ir(fun, args)?,
             ast::TypedExpr::Negate { value, .. } => Expression::UnaryOp {
                 op: UnaryOp::Negate,
-                expr: Box::new(self.convert_expr_to_ir(value)),
+                expr: Box::new(self.convert_expr_to_ir(value)?),
             },
-            ast::TypedExpr::RecordAccess { label, record, .. } => {
-                Expression::Accessor(Accessor::Custom {
-                    label,
-                    reciever: Box::new(self.convert_expr_to_ir(record)),
-                })
-            }
+            ast::TypedExpr::RecordAccess {
+                label,
+                index,
+                variants,
+                record,
+                ..
+            } => Expression::Accessor(Accessor::Custom {
+                label: Some(label),
+                index: *index,
+                variants,
+                reciever: Box::new(self.convert_expr_to_ir(record)?),
+            }),
+            ast::TypedExpr::ModuleSelect {
+                typ,
+                constructor: ModuleValueConstructor::Fn { module, name, .. },
+                ..
+            } if is_external_header(module) => Expression::Accessor(Accessor::External {
+                name,
+                typ: typ.to_owned(),
+            }),
             ast::TypedExpr::ModuleSelect {
                 module_alias,
                 typ,
-                module_name,
-                label,
-                constructor:
-                    ModuleValueConstructor::Fn { .. } | ModuleValueConstructor::Constant { .. },
+                constructor: ModuleValueConstructor::Fn { module, name, .. },
                 ..
             } => Expression::Accessor(Accessor::ModuleVariable {
                 public: true,
-                module: split_module_name(module_name),
+                module: module.iter().map(|s| &s[..]).collect(),
                 module_alias: Some(module_alias),
-                name: label,
+                name,
                 typ: typ.to_owned(),
             }),
-            // TODO: This case needs to handled via wrapping the constructor
-            // see convert_variable_to_ir
-            // TODO: Does this need to handle Singletons or does the above?
             ast::TypedExpr::ModuleSelect {
-                constructor: ModuleValueConstructor::Record { .. },
+                constructor: ModuleValueConstructor::Constant { literal, .. },
+                ..
+            } => self.convert_constant_to_ir(literal)?,
+            ast::TypedExpr::ModuleSelect {
+                module_alias,
+                module_name,
+                constructor:
+                    ModuleValueConstructor::Record {
+                        name, arity, type_, ..
+                    },
                 ..
-            } => todo!(),
+            } => self.convert_record_constructor_to_ir(
+                true,
+                module_name,
+                Some(module_alias),
+                name,
+                *arity,
+                type_,
+            ),
             ast::TypedExpr::Tuple { typ, elems, .. } => {
                 Expression::TypeConstruction(TypeConstruction::Tuple {
                     typ: typ.to_owned(),
-                    elements: elems.iter().map(|e| self.convert_expr_to_ir(e)).collect(),
+                    elements: elems
+                        .iter()
+                        .map(|e| self.convert_expr_to_ir(e))
+                        .try_collect()?,
                 })
             }
             ast::TypedExpr::TupleIndex { tuple, index, .. } => {
                 Expression::Accessor(Accessor::TupleIndex {
                     index: *index,
-                    tuple: Box::new(self.convert_expr_to_ir(tuple)),
+                    tuple: Box::new(self.convert_expr_to_ir(tuple)?),
                 })
             }
-            ast::TypedExpr::Todo { .. } => todo!(),
-            ast::TypedExpr::BitString { .. } => todo!(),
-            ast::TypedExpr::RecordUpdate { .. } => todo!(),
+            ast::TypedExpr::Todo { label, .. } => panic(&match label {
+                Some(label) => format!("This has not yet been implemented: {label}"),
+                None => "This has not yet been implemented".into(),
+            }),
+            ast::TypedExpr::Cfg { enabled, .. } => {
+                Expression::Literal(Literal::Bool { value: *enabled })
+            }
+            // There is no way to print any Gleam value in the prelude yet so
+            // only the type of the value is printed.
+            ast::TypedExpr::Echo { value, .. } => builtin(
+                BuiltinFn::Echo,
+                vec![
+                    self.convert_expr_to_ir(value)?,
+                    Expression::Literal(Literal::String {
+                        value: crate::type_::pretty::Printer::new().pretty_print(&value.type_(), 0),
+                    }),
+                ],
+            ),
+            ast::TypedExpr::BitString {
+                location, segments, ..
+            } => Expression::TypeConstruction(TypeConstruction::BitString {
+                location: *location,
+                segments: segments
+                    .iter()
+                    .map(|segment| self.convert_bit_string_segment_to_ir(segment))
+                    .try_collect()?,
+            }),
+            ast::TypedExpr::RecordUpdate { location, .. } => {
+                return unsupported("Record updates", *location)
+            }
             // The rest here are things that cannot be represented as expressions in our IR, so we
             // wrap them in blocks that are immediately invoked functions.
             ast::TypedExpr::Sequence { expressions, .. }
             | ast::TypedExpr::Pipeline { expressions, .. } => self
                 .wrap_in_block(expr.type_(), |conv| {
                     conv.convert_top_level_exprs_to_ir(expressions)
-                }),
+                })?,
             ast::TypedExpr::Assignment { .. }
             | ast::TypedExpr::Try { .. }
             | ast::TypedExpr::Case { .. } => {
-                self.wrap_in_block(expr.type_(), |conv| conv.ast_to_ir(expr))
+                self.wrap_in_block(expr.type_(), |conv| conv.ast_to_ir(expr))?
             }
-        }
+        })
+    }
+
+    fn convert_bit_string_segment_to_ir(
+        &mut self,
+        segment: &'module ast::TypedExprBitStringSegment,
+    ) -> Result<BitStringSegment<'module>, Error> {
+        use ast::BitStringSegmentOption as Opt;
+
+        let kind = match segment.options.as_slice() {
+            [] | [Opt::Int { .. }] => BitStringSegmentKind::Int {
+                size: Box::new(int_literal(8)),
+            },
+            [Opt::Size { value: size, .. }] => BitStringSegmentKind::Int {
+                size: Box::new(self.convert_expr_to_ir(size)?),
+            },
+            [Opt::Float { .. }] => BitStringSegmentKind::Float,
+            [Opt::Utf8 { .. }] => BitStringSegmentKind::Utf8,
+            [Opt::BitString { .. }] => BitStringSegmentKind::BitString,
+            _ => return unsupported("This bit string segment option", segment.location),
+        };
+        Ok(BitStringSegment {
+            value: self.convert_expr_to_ir(&segment.value)?,
+            kind,
+        })
     }
 
     fn convert_call_to_ir(
         &mut self,
         fun: &'module ast::TypedExpr,
         args: &'module [ast::CallArg<ast::TypedExpr>],
-    ) -> Expression<'module> {
+    ) -> Result<Expression<'module>, Error> {
         let args: Vec<_> = args
             .iter()
             .map(|arg| self.convert_expr_to_ir(&arg.value))
-            .collect();
+            .try_collect()?;
+        match fun {
+            ast::TypedExpr::Var {
+                constructor,
+                location,
+                ..
+            } if constructor.variant.is_rescue() => {
+                return Ok(builtin(
+                    BuiltinFn::Rescue {
+                        location: *location,
+                    },
+                    args,
+                ))
+            }
+            ast::TypedExpr::ModuleSelect {
+                constructor,
+                location,
+                ..
+            } if constructor.is_rescue() => {
+                return Ok(builtin(
+                    BuiltinFn::Rescue {
+                        location: *location,
+                    },
+                    args,
+                ))
+            }
+            _ => (),
+        }
         // Special case direct construction of records - otherwise these will all get wrapped into
         // an anonymous function and have extra indirection.
         if let ast::TypedExpr::Var {
             constructor:
                 ValueConstructor {
                     public,
+                    deprecation: _,
                     variant: ValueConstructorVariant::Record { module, name, .. },
                     type_,
                 },
@@ -463,14 +1222,14 @@ impl<'module> IntermediateRepresentationConverter<'module> {
             let (_, retrn) = type_
                 .fn_types()
                 .expect("Constructor variable to be a function");
-            return Expression::TypeConstruction(TypeConstruction::Custom {
+            return Ok(Expression::TypeConstruction(TypeConstruction::Custom {
                 public: *public,
                 module_alias: None,
                 module: split_module_name(module),
                 name,
                 typ: retrn,
                 args,
-            });
+            }));
         } else if let ast::TypedExpr::ModuleSelect {
             module_name,
             constructor: ModuleValueConstructor::Record { name, type_, .. },
@@ -480,17 +1239,17 @@ impl<'module> IntermediateRepresentationConverter<'module> {
             let (_, retrn) = type_
                 .fn_types()
                 .expect("Constructor variable to be a function");
-            return Expression::TypeConstruction(TypeConstruction::Custom {
+            return Ok(Expression::TypeConstruction(TypeConstruction::Custom {
                 public: true,
                 module_alias: Some(module_name),
                 module: split_module_name(module_name),
                 name,
                 typ: retrn,
                 args,
-            });
+            }));
         }
-        let callee = Box::new(self.convert_expr_to_ir(fun));
-        Expression::Call(Call::Fn { callee, args })
+        let callee = Box::new(self.convert_expr_to_ir(fun)?);
+        Ok(Expression::Call(Call::Fn { callee, args }))
     }
 
     fn convert_fn_to_ir(
@@ -498,22 +1257,23 @@ impl<'module> IntermediateRepresentationConverter<'module> {
         typ: &'module Arc<Type>,
         args: &'module [ast::Arg<Arc<Type>>],
         body: &'module ast::TypedExpr,
-    ) -> Expression<'module> {
+    ) -> Result<Expression<'module>, Error> {
         self.with_new_scope(|conv| {
-            Expression::TypeConstruction(TypeConstruction::Function {
+            let args = args
+                .iter()
+                .map(|arg| FunctionArg {
+                    name: match arg.get_variable_name() {
+                        Some(name) => conv.allocate_named_id(name),
+                        None => conv.allocate_discard_id(),
+                    },
+                    typ: arg.type_.to_owned(),
+                })
+                .collect();
+            Ok(Expression::TypeConstruction(TypeConstruction::Function {
                 typ: typ.to_owned(),
-                args: args
-                    .iter()
-                    .map(|arg| FunctionArg {
-                        name: match arg.get_variable_name() {
-                            Some(name) => conv.allocate_named_id(name),
-                            None => conv.allocate_discard_id(),
-                        },
-                        typ: arg.type_.to_owned(),
-                    })
-                    .collect(),
-                body: conv.ast_to_ir(body),
-            })
+                args,
+                body: conv.ast_to_ir(body)?,
+            }))
         })
     }
 
@@ -521,32 +1281,40 @@ impl<'module> IntermediateRepresentationConverter<'module> {
         &mut self,
         name: &'module str,
         constructor: &'module ValueConstructor,
-    ) -> Expression<'module> {
-        match constructor {
+    ) -> Result<Expression<'module>, Error> {
+        Ok(match constructor {
             ValueConstructor {
-                public,
-                variant: ValueConstructorVariant::ModuleFn { module, .. },
+                variant:
+                    ValueConstructorVariant::ModuleFn {
+                        module,
+                        name: external,
+                        ..
+                    },
                 type_,
-            } => Expression::Accessor(Accessor::ModuleVariable {
-                public: *public,
-                module_alias: None,
-                module: module.iter().map(|s| &s[..]).collect(),
-                name,
+                ..
+            } if is_external_header(module) => Expression::Accessor(Accessor::External {
+                name: external,
                 typ: type_.to_owned(),
             }),
             ValueConstructor {
                 public,
-                variant: ValueConstructorVariant::ModuleConstant { module, .. },
+                deprecation: _,
+                variant: ValueConstructorVariant::ModuleFn { module, name, .. },
                 type_,
             } => Expression::Accessor(Accessor::ModuleVariable {
                 public: *public,
                 module_alias: None,
-                module: split_module_name(module),
+                module: module.iter().map(|s| &s[..]).collect(),
                 name,
                 typ: type_.to_owned(),
             }),
+            ValueConstructor {
+                variant: ValueConstructorVariant::ModuleConstant { literal, .. },
+                ..
+            } => self.convert_constant_to_ir(literal)?,
             ValueConstructor {
                 public,
+                deprecation: _,
                 variant:
                     ValueConstructorVariant::Record {
                         name,
@@ -555,75 +1323,7 @@ impl<'module> IntermediateRepresentationConverter<'module> {
                         ..
                     },
                 type_,
-            } if *arity > 0 => {
-                // Constructors in Gleam are essentially just factory functions. Here we wrap them in
-                // functions. We special case direct calls to create custom types in the call operator.
-                // This is a fallback path for something like:
-                // ```gleam
-                // type Foo {
-                //   Foo(String)
-                // }
-                // fn bar(str: String) -> Foo {
-                //   let x = Foo;
-                //   x(str)
-                // }
-                // ```
-                let (args, retrn) = type_
-                    .fn_types()
-                    .expect("Constructor variable to be a function");
-                let args: Vec<_> = args
-                    .into_iter()
-                    .map(|typ| FunctionArg {
-                        name: self.allocate_internal_id(),
-                        typ,
-                    })
-                    .collect();
-                Expression::TypeConstruction(TypeConstruction::Function {
-                    typ: type_.to_owned(),
-                    args: args.clone(),
-                    body: vec![Statement::Return {
-                        expr: Expression::TypeConstruction(TypeConstruction::Custom {
-                            public: *public,
-                            module_alias: None,
-                            module: split_module_name(module),
-                            name,
-                            typ: retrn,
-                            args: args
-                                .into_iter()
-                                .map(|arg| {
-                                    Expression::Accessor(Accessor::LocalVariable {
-                                        name: arg.name,
-                                        typ: arg.typ,
-                                    })
-                                })
-                                .collect(),
-                        }),
-                    }],
-                })
-            }
-            ValueConstructor {
-                variant: ValueConstructorVariant::Record { name, .. },
-                type_,
-                ..
-            } if type_.is_bool() => Expression::Literal(Literal::Bool {
-                value: name == "True",
-            }),
-            ValueConstructor {
-                variant: ValueConstructorVariant::Record { .. },
-                type_,
-                ..
-            } if type_.is_nil() => Expression::Literal(Literal::Nil),
-            ValueConstructor {
-                public,
-                variant: ValueConstructorVariant::Record { module, name, .. },
-                type_,
-            } => Expression::TypeConstruction(TypeConstruction::CustomSingleton {
-                public: *public,
-                module: split_module_name(module),
-                module_alias: None,
-                name,
-                typ: type_.to_owned(),
-            }),
+            } => self.convert_record_constructor_to_ir(*public, module, None, name, *arity, type_),
             ValueConstructor {
                 variant: ValueConstructorVariant::LocalVariable { .. },
                 type_,
@@ -632,7 +1332,79 @@ impl<'module> IntermediateRepresentationConverter<'module> {
                 name: self.lookup_named_id(name),
                 typ: type_.to_owned(),
             }),
+        })
+    }
+
+    fn convert_record_constructor_to_ir(
+        &mut self,
+        public: bool,
+        module: &'module str,
+        module_alias: Option<&'module str>,
+        name: &'module str,
+        arity: u16,
+        type_: &'module Arc<Type>,
+    ) -> Expression<'module> {
+        if arity == 0 && type_.is_bool() {
+            return Expression::Literal(Literal::Bool {
+                value: name == "True",
+            });
+        }
+        if arity == 0 && type_.is_nil() {
+            return Expression::Literal(Literal::Nil);
+        }
+        if arity == 0 {
+            return Expression::TypeConstruction(TypeConstruction::CustomSingleton {
+                public,
+                module: split_module_name(module),
+                module_alias,
+                name,
+                typ: type_.to_owned(),
+            });
         }
+        // Constructors in Gleam are essentially just factory functions. Here we wrap them in
+        // functions. We special case direct calls to create custom types in the call operator.
+        // This is a fallback path for something like:
+        // ```gleam
+        // type Foo {
+        //   Foo(String)
+        // }
+        // fn bar(str: String) -> Foo {
+        //   let x = Foo;
+        //   x(str)
+        // }
+        // ```
+        let (args, retrn) = type_
+            .fn_types()
+            .expect("Constructor variable to be a function");
+        let args: Vec<_> = args
+            .into_iter()
+            .map(|typ| FunctionArg {
+                name: self.allocate_internal_id(),
+                typ,
+            })
+            .collect();
+        Expression::TypeConstruction(TypeConstruction::Function {
+            typ: type_.to_owned(),
+            args: args.clone(),
+            body: vec![Statement::Return {
+                expr: Expression::TypeConstruction(TypeConstruction::Custom {
+                    public,
+                    module_alias,
+                    module: split_module_name(module),
+                    name,
+                    typ: retrn,
+                    args: args
+                        .into_iter()
+                        .map(|arg| {
+                            Expression::Accessor(Accessor::LocalVariable {
+                                name: arg.name,
+                                typ: arg.typ,
+                            })
+                        })
+                        .collect(),
+                }),
+            }],
+        })
     }
 
     fn allocate_internal_id(&mut self) -> Identifier<'module> {
@@ -658,7 +1430,7 @@ impl<'module> IntermediateRepresentationConverter<'module> {
     }
     fn with_new_scope<Block, Output>(&mut self, block: Block) -> Output
     where
-        Block: Fn(&mut Self) -> Output,
+        Block: FnOnce(&mut Self) -> Output,
     {
         let parent_scope = self.current_scope_vars.clone();
         let child_scope = parent_scope.clone();
@@ -670,11 +1442,15 @@ impl<'module> IntermediateRepresentationConverter<'module> {
 
     /// Some expressions can only be converted into statements, so we need to wrap the statements
     /// within a function.
-    fn wrap_in_block<Block>(&mut self, typ: Arc<Type>, expr: Block) -> Expression<'module>
+    fn wrap_in_block<Block>(
+        &mut self,
+        typ: Arc<Type>,
+        expr: Block,
+    ) -> Result<Expression<'module>, Error>
     where
-        Block: Fn(&mut Self) -> Vec<Statement<'module>>,
+        Block: FnOnce(&mut Self) -> Result<Vec<Statement<'module>>, Error>,
     {
-        Expression::Call(Call::Fn {
+        Ok(Expression::Call(Call::Fn {
             args: vec![],
             callee: Box::new(Expression::TypeConstruction(TypeConstruction::Function {
                 typ: Arc::new(Type::Fn {
@@ -682,12 +1458,103 @@ impl<'module> IntermediateRepresentationConverter<'module> {
                     retrn: typ,
                 }),
                 args: vec![],
-                body: self.with_new_scope(expr),
+                body: self.with_new_scope(expr)?,
             })),
-        })
+        }))
+    }
+}
+
+/// An offset or size in bits within a bit string pattern, kept as a constant and the sizes only
+/// known at runtime, so that the constant sizes are added up here.
+#[derive(Debug, Clone, Default)]
+struct Bits<'a> {
+    constant: u64,
+    dynamic: Vec<Expression<'a>>,
+}
+
+impl<'a> Bits<'a> {
+    fn constant(constant: u64) -> Self {
+        Bits {
+            constant,
+            dynamic: vec![],
+        }
+    }
+
+    fn dynamic(size: Expression<'a>) -> Self {
+        Bits {
+            constant: 0,
+            dynamic: vec![size],
+        }
     }
+
+    fn add(&self, other: &Self) -> Self {
+        Bits {
+            constant: self.constant + other.constant,
+            dynamic: self.dynamic.iter().chain(&other.dynamic).cloned().collect(),
+        }
+    }
+
+    fn to_expression(&self) -> Expression<'a> {
+        let constant =
+            (self.constant != 0 || self.dynamic.is_empty()).then(|| int_literal(self.constant));
+        constant
+            .into_iter()
+            .chain(self.dynamic.iter().cloned())
+            .reduce(|left, right| Expression::BinOp {
+                left: Box::new(left),
+                op: ast::BinOp::AddInt,
+                right: Box::new(right),
+            })
+            .expect("there is always a constant or dynamic size")
+    }
+}
+
+fn int_literal<'a>(value: u64) -> Expression<'a> {
+    Expression::Literal(Literal::Int {
+        value: Cow::Owned(value.to_string()),
+    })
+}
+
+fn builtin(function: BuiltinFn, args: Vec<Expression<'_>>) -> Expression<'_> {
+    Expression::Call(Call::Builtin { function, args })
+}
+
+fn panic<'a>(message: &str) -> Expression<'a> {
+    builtin(
+        BuiltinFn::Panic,
+        vec![Expression::Literal(Literal::String {
+            value: message.into(),
+        })],
+    )
+}
+
+/// All of the checks joined with `&&`, or `True` when there are none.
+fn all(checks: Vec<Expression<'_>>) -> Expression<'_> {
+    checks
+        .into_iter()
+        .reduce(|left, right| Expression::BinOp {
+            left: Box::new(left),
+            op: ast::BinOp::And,
+            right: Box::new(right),
+        })
+        .unwrap_or(Expression::Literal(Literal::Bool { value: true }))
+}
+
+fn field_label(field_map: Option<&FieldMap>, index: usize) -> Option<&str> {
+    field_map?
+        .fields
+        .iter()
+        .find(|(_, position)| **position as usize == index)
+        .map(|(label, _)| label.as_str())
 }
 
+/// The segments of a module name, which are none for the prelude.
 fn split_module_name(module: &str) -> Vec<&str> {
-    module.split('/').collect()
+    module.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// Whether the module of a function is the header of an `external fn`, such
+/// as `"<cmath>"` or `"ffi.h"`, rather than a Gleam module.
+fn is_external_header(module: &[Name]) -> bool {
+    matches!(module, [header] if header.starts_with('<') || header.contains('.'))
 }
@@ -7,7 +7,14 @@ mod typescript;
 
 use std::path::Path;
 
-use crate::{ast::*, docvec, io::Utf8Writer, line_numbers::LineNumbers, pretty::*};
+use crate::{
+    ast::*,
+    docvec,
+    io::Utf8Writer,
+    line_numbers::LineNumbers,
+    pretty::*,
+    type_::{Type, TypeVar},
+};
 use itertools::Itertools;
 
 use self::import::{Imports, Member};
@@ -16,6 +23,8 @@ const INDENT: isize = 2;
 
 pub const PRELUDE: &str = include_str!("../templates/prelude.js");
 pub const PRELUDE_TS_DEF: &str = include_str!("../templates/prelude.d.ts");
+pub const TEST_RUNNER: &str = include_str!("../templates/gleam@@test.mjs");
+pub const REPL: &str = include_str!("../templates/gleam@@repl.mjs");
 
 pub type Output<'a> = Result<Document<'a>, Error>;
 
@@ -68,6 +77,14 @@ impl<'a> Generator<'a> {
             self.register_prelude_usage(&mut imports, "Error", None);
         };
 
+        if self.tracker.crash_reason_used {
+            self.register_prelude_usage(&mut imports, "CrashReason", None);
+        };
+
+        if self.tracker.rescue_used {
+            self.register_prelude_usage(&mut imports, "rescue", None);
+        };
+
         if self.tracker.list_used {
             self.register_prelude_usage(&mut imports, "toList", None);
         };
@@ -80,6 +97,10 @@ impl<'a> Generator<'a> {
             self.register_prelude_usage(&mut imports, "throwError", None);
         };
 
+        if self.tracker.echo_used {
+            self.register_prelude_usage(&mut imports, "echo", None);
+        };
+
         if self.tracker.int_remainder_used {
             self.register_prelude_usage(&mut imports, "remainderInt", None);
         };
@@ -136,7 +157,7 @@ impl<'a> Generator<'a> {
         name: &'static str,
         alias: Option<&'static str>,
     ) {
-        let path = self.import_path(&self.module.type_info.package, &["gleam".to_string()]);
+        let path = self.import_path(&self.module.type_info.package, &["gleam".into()]);
         let member = Member {
             name: name.to_doc(),
             alias: alias.map(|a| a.to_doc()),
@@ -187,19 +208,31 @@ impl<'a> Generator<'a> {
     fn custom_type_definition(
         &mut self,
         constructors: &'a [TypedRecordConstructor],
+        parameters: &[std::sync::Arc<Type>],
         public: bool,
         opaque: bool,
     ) -> Vec<Output<'a>> {
         self.tracker.custom_type_used = true;
+        let parameters: Vec<u64> = parameters
+            .iter()
+            .filter_map(|parameter| match parameter.as_ref() {
+                Type::Var { type_ } => match &*type_.borrow() {
+                    TypeVar::Generic { id } => Some(*id),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
         constructors
             .iter()
-            .map(|constructor| Ok(self.record_definition(constructor, public, opaque)))
+            .map(|constructor| Ok(self.record_definition(constructor, &parameters, public, opaque)))
             .collect()
     }
 
     fn record_definition(
         &self,
         constructor: &'a TypedRecordConstructor,
+        parameters: &[u64],
         public: bool,
         opaque: bool,
     ) -> Document<'a> {
@@ -207,7 +240,7 @@ impl<'a> Generator<'a> {
             arg.label
                 .as_ref()
                 .map(|s| maybe_escape_identifier_doc(s))
-                .unwrap_or_else(|| Document::String(format!("x{}", i)))
+                .unwrap_or_else(|| docvec!["x", i])
         }
 
         let head = if public && !opaque {
@@ -221,7 +254,7 @@ impl<'a> Generator<'a> {
             return head.append("}");
         };
 
-        let parameters = concat(Itertools::intersperse(
+        let arguments = concat(Itertools::intersperse(
             constructor.arguments.iter().enumerate().map(parameter),
             break_(",", ", "),
         ));
@@ -237,14 +270,30 @@ impl<'a> Generator<'a> {
             line(),
         ));
 
+        let floats = float_shapes(
+            constructor.arguments.iter().map(|argument| &argument.type_),
+            parameters,
+        )
+        .map(|shapes| {
+            docvec![
+                lines(2),
+                "static get __gleam_floats__() {",
+                docvec![line(), "return [", Document::String(shapes), "];"].nest(INDENT),
+                line(),
+                "}",
+            ]
+        })
+        .unwrap_or_else(nil);
+
         let class_body = docvec![
             line(),
             "constructor(",
-            parameters,
+            arguments,
             ") {",
             docvec![line(), "super();", line(), constructor_body].nest(INDENT),
             line(),
             "}",
+            floats,
         ]
         .nest(INDENT);
 
@@ -260,8 +309,9 @@ impl<'a> Generator<'a> {
                     public,
                     constructors,
                     opaque,
+                    typed_parameters,
                     ..
-                } => self.custom_type_definition(constructors, *public, *opaque),
+                } => self.custom_type_definition(constructors, typed_parameters, *public, *opaque),
 
                 Statement::Fn { .. }
                 | Statement::TypeAlias { .. }
@@ -308,7 +358,7 @@ impl<'a> Generator<'a> {
         imports
     }
 
-    fn import_path(&self, package: &'a str, module: &'a [String]) -> String {
+    fn import_path(&self, package: &'a str, module: &'a [Name]) -> String {
         let path = module.join("/");
 
         // TODO: strip shared prefixed between current module and imported
@@ -333,11 +383,11 @@ impl<'a> Generator<'a> {
         &mut self,
         imports: &mut Imports<'a>,
         package: &'a str,
-        module: &'a [String],
-        as_name: &'a Option<String>,
+        module: &'a [Name],
+        as_name: &'a Option<Name>,
         unqualified: &'a [UnqualifiedImport],
     ) {
-        let module_name = as_name.as_ref().unwrap_or_else(|| {
+        let module_name = as_name.as_deref().unwrap_or_else(|| {
             module
                 .last()
                 .expect("JavaScript generator could not identify imported module name.")
@@ -482,6 +532,52 @@ impl<'a> Generator<'a> {
     }
 }
 
+/// Where the floats are within a value of the type, so that `echo` can print
+/// them as floats, as JavaScript has one type for both floats and ints. This
+/// is `"f"` for a float, `{ e: [...] }` for the elements of a tuple, and
+/// `{ a: [...] }` for the arguments of a custom type, whose classes describe
+/// their fields with the index of a type parameter where the field has the
+/// type of the parameter. `None` if the value can contain no floats.
+pub(crate) fn float_shape(type_: &Type, parameters: &[u64]) -> Option<String> {
+    match type_ {
+        Type::App { .. } if type_.is_float() => Some("\"f\"".into()),
+        Type::App { args, .. } => {
+            float_shapes(args, parameters).map(|args| format!("{{ a: [{}] }}", args))
+        }
+        Type::Tuple { elems } => {
+            float_shapes(elems, parameters).map(|elems| format!("{{ e: [{}] }}", elems))
+        }
+        Type::Fn { .. } => None,
+        Type::Var { type_ } => match &*type_.borrow() {
+            TypeVar::Link { type_ } => float_shape(type_, parameters),
+            TypeVar::Generic { id } => parameters
+                .iter()
+                .position(|parameter| parameter == id)
+                .map(|index| index.to_string()),
+            TypeVar::Unbound { .. } => None,
+        },
+    }
+}
+
+fn float_shapes<'t>(
+    types: impl IntoIterator<Item = &'t std::sync::Arc<Type>>,
+    parameters: &[u64],
+) -> Option<String> {
+    let shapes: Vec<_> = types
+        .into_iter()
+        .map(|type_| float_shape(type_, parameters))
+        .collect();
+    if shapes.iter().all(Option::is_none) {
+        return None;
+    }
+    Some(
+        shapes
+            .into_iter()
+            .map(|shape| shape.unwrap_or_else(|| "undefined".into()))
+            .join(", "),
+    )
+}
+
 fn external_fn_args<T>(arguments: &[ExternalFnArg<T>]) -> Document<'_> {
     wrap_args(
         arguments
@@ -491,7 +587,7 @@ fn external_fn_args<T>(arguments: &[ExternalFnArg<T>]) -> Document<'_> {
                 label
                     .as_ref()
                     .map(|l| l.to_doc())
-                    .unwrap_or_else(|| Document::String(format!("arg{}", index)))
+                    .unwrap_or_else(|| docvec!["arg", index])
             }),
     )
 }
@@ -501,6 +597,7 @@ pub fn module(
     line_numbers: &LineNumbers,
     path: &Path,
     src: &str,
+    line_length: isize,
     writer: &mut impl Utf8Writer,
 ) -> Result<(), crate::Error> {
     Generator::new(line_numbers, module)
@@ -510,13 +607,14 @@ pub fn module(
             src: src.to_string(),
             error,
         })?
-        .pretty_print(80, writer)
+        .pretty_print(line_length, writer)
 }
 
 pub fn ts_declaration(
     module: &TypedModule,
     path: &Path,
     src: &str,
+    line_length: isize,
     writer: &mut impl Utf8Writer,
 ) -> Result<(), crate::Error> {
     typescript::TypeScriptGenerator::new(module)
@@ -526,7 +624,7 @@ pub fn ts_declaration(
             src: src.to_string(),
             error,
         })?
-        .pretty_print(80, writer)
+        .pretty_print(line_length, writer)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -535,13 +633,13 @@ pub enum Error {
 }
 
 fn fun_args(args: &'_ [TypedArg], tail_recursion_used: bool) -> Document<'_> {
-    let mut discards = 0;
+    let mut discards = 0_usize;
     wrap_args(args.iter().map(|a| match a.get_variable_name() {
         None => {
             let doc = if discards == 0 {
                 "_".to_doc()
             } else {
-                Document::String(format!("_{}", discards))
+                docvec!["_", discards]
             };
             discards += 1;
             doc
@@ -697,6 +795,9 @@ pub(crate) struct UsageTracker {
     pub error_used: bool,
     pub int_remainder_used: bool,
     pub throw_error_used: bool,
+    pub crash_reason_used: bool,
+    pub rescue_used: bool,
+    pub echo_used: bool,
     pub custom_type_used: bool,
     pub int_division_used: bool,
     pub float_division_used: bool,
@@ -10,7 +10,7 @@ use crate::{
 
 #[derive(Debug)]
 pub(crate) struct Generator<'module> {
-    module_name: &'module [String],
+    module_name: &'module [Name],
     line_numbers: &'module LineNumbers,
     function_name: Option<&'module str>,
     function_arguments: Vec<Option<&'module str>>,
@@ -29,7 +29,7 @@ pub(crate) struct Generator<'module> {
 impl<'module> Generator<'module> {
     #[allow(clippy::too_many_arguments)] // TODO: FIXME
     pub fn new(
-        module_name: &'module [String],
+        module_name: &'module [Name],
         line_numbers: &'module LineNumbers,
         function_name: &'module str,
         function_arguments: Vec<Option<&'module str>>,
@@ -138,7 +138,12 @@ impl<'module> Generator<'module> {
                 self.sequence(expressions)
             }
 
-            TypedExpr::Assignment { value, pattern, .. } => self.assignment(value, pattern),
+            TypedExpr::Assignment {
+                value,
+                pattern,
+                message,
+                ..
+            } => self.assignment(value, pattern, message.as_deref()),
 
             TypedExpr::Try {
                 value,
@@ -165,6 +170,10 @@ impl<'module> Generator<'module> {
             } => Ok(self.module_select(module_alias, label, constructor)),
 
             TypedExpr::Negate { value, .. } => self.negate(value),
+
+            TypedExpr::Echo { value, location } => self.echo(value, *location),
+
+            TypedExpr::Cfg { enabled, .. } => Ok(if *enabled { "true" } else { "false" }.to_doc()),
         }?;
         Ok(if expression.handles_own_return() {
             document
@@ -177,6 +186,19 @@ impl<'module> Generator<'module> {
         self.not_in_tail_position(|gen| Ok(docvec!("!", gen.wrap_expression(value)?)))
     }
 
+    fn echo<'a>(&mut self, value: &'a TypedExpr, location: SrcSpan) -> Output<'a> {
+        self.tracker.echo_used = true;
+        let module = Document::String(self.module_name.join("/")).surround('"', '"');
+        let line = self.line_numbers.line_number(location.start).to_doc();
+        let typ = crate::type_::pretty::Printer::new().pretty_print(&value.type_(), 0);
+        let typ = Document::String(typ).surround('"', '"');
+        let floats = float_shape(&value.type_(), &[]);
+        let value = self.not_in_tail_position(|gen| gen.wrap_expression(value))?;
+        let mut arguments = vec![value, module, line, typ];
+        arguments.extend(floats.map(Document::String));
+        Ok(docvec!["echo", wrap_args(arguments)])
+    }
+
     fn bit_string<'a>(&mut self, segments: &'a [TypedExprBitStringSegment]) -> Output<'a> {
         self.tracker.bit_string_literal_used = true;
 
@@ -331,6 +353,10 @@ impl<'module> Generator<'module> {
             ValueConstructorVariant::Record { arity, .. } => {
                 self.record_constructor(constructor.type_.clone(), None, name, *arity)
             }
+            variant if variant.is_rescue() => {
+                self.tracker.rescue_used = true;
+                "rescue".to_doc()
+            }
             ValueConstructorVariant::ModuleFn { .. }
             | ValueConstructorVariant::ModuleConstant { .. }
             | ValueConstructorVariant::LocalVariable { .. } => self.local_var(name),
@@ -351,6 +377,9 @@ impl<'module> Generator<'module> {
                 self.tracker.error_used = true;
             }
         }
+        if qualifier.is_none() && type_.is_crash_reason_constructor() {
+            self.tracker.crash_reason_used = true;
+        }
         if type_.is_bool() && name == "True" {
             "true".to_doc()
         } else if type_.is_bool() {
@@ -363,7 +392,7 @@ impl<'module> Generator<'module> {
                 None => docvec!["new ", name, "()"],
             }
         } else {
-            let vars = (0..arity).map(|i| Document::String(format!("var{}", i)));
+            let vars = (0..arity).map(|i| docvec!["var", i]);
             let body = docvec![
                 "return ",
                 construct_record(qualifier, name, vars.clone()),
@@ -463,6 +492,7 @@ impl<'module> Generator<'module> {
                     compiled,
                     subject,
                     pattern.location(),
+                    None,
                 )?);
                 docs.push(lines(2));
             }
@@ -474,7 +504,12 @@ impl<'module> Generator<'module> {
         Ok(docs.to_doc().force_break())
     }
 
-    fn assignment<'a>(&mut self, value: &'a TypedExpr, pattern: &'a TypedPattern) -> Output<'a> {
+    fn assignment<'a>(
+        &mut self,
+        value: &'a TypedExpr,
+        pattern: &'a TypedPattern,
+        message: Option<&'a str>,
+    ) -> Output<'a> {
         // If it is a simple assignment to a variable we can generate a normal
         // JS assignment
         if let TypedPattern::Var { name, .. } = pattern {
@@ -521,11 +556,17 @@ impl<'module> Generator<'module> {
         // use in patterns
         let doc = match subject_assignment {
             Some(name) => {
-                let compiled =
-                    self.pattern_into_assignment_doc(compiled, subject, pattern.location())?;
+                let compiled = self.pattern_into_assignment_doc(
+                    compiled,
+                    subject,
+                    pattern.location(),
+                    message,
+                )?;
                 docvec!("let ", name, " = ", value, ";", line(), compiled)
             }
-            None => self.pattern_into_assignment_doc(compiled, subject, pattern.location())?,
+            None => {
+                self.pattern_into_assignment_doc(compiled, subject, pattern.location(), message)?
+            }
         };
 
         Ok(doc.append(afterwards).force_break())
@@ -537,6 +578,21 @@ impl<'module> Generator<'module> {
         subject_values: &'a [TypedExpr],
         clauses: &'a [TypedClause],
     ) -> Output<'a> {
+        // The branches for the disabled value of a feature flag are left out,
+        // and the body of the clause that runs is rendered on its own as it
+        // is when a case has a single clause that always matches.
+        if let Some(clause) = cfg_case_clause(subject_values, clauses) {
+            let scope = self.current_scope_vars.clone();
+            let consequence = self.expression(&clause.then)?;
+            self.current_scope_vars = scope;
+            // A case expression is not followed by a semicolon in a sequence
+            return Ok(if self.tail_position || clause.then.ends_with_statement() {
+                consequence
+            } else {
+                docvec![consequence, ";"]
+            });
+        }
+
         let mut possibility_of_no_match = true;
 
         let (subjects, subject_assignments): (Vec<_>, Vec<_>) =
@@ -663,10 +719,15 @@ impl<'module> Generator<'module> {
         ))
     }
 
-    fn assignment_no_match<'a>(&mut self, location: SrcSpan, subject: Document<'a>) -> Output<'a> {
+    fn assignment_no_match<'a>(
+        &mut self,
+        location: SrcSpan,
+        subject: Document<'a>,
+        message: Option<&'a str>,
+    ) -> Output<'a> {
         Ok(self.throw_error(
             "assignment_no_match",
-            "Assignment pattern did not match",
+            message.unwrap_or("Assignment pattern did not match"),
             location,
             [("value", subject)],
         ))
@@ -720,6 +781,9 @@ impl<'module> Generator<'module> {
                         self.tracker.error_used = true;
                     }
                 }
+                if type_.is_crash_reason_constructor() {
+                    self.tracker.crash_reason_used = true;
+                }
                 Ok(self.wrap_return(construct_record(None, name, arguments)))
             }
 
@@ -730,7 +794,7 @@ impl<'module> Generator<'module> {
                 if self.function_name == Some(name)
                     && !self.in_iife
                     && self.tail_position
-                    && self.current_scope_vars.get(name) == Some(&0) =>
+                    && self.current_scope_vars.get(name.as_str()) == Some(&0) =>
             {
                 let mut docs = Vec::with_capacity(arguments.len() * 4);
                 // Record that tail recursion is happening so that we know to
@@ -840,7 +904,7 @@ impl<'module> Generator<'module> {
     fn tuple_index<'a>(&mut self, tuple: &'a TypedExpr, index: u64) -> Output<'a> {
         self.not_in_tail_position(|gen| {
             let tuple = gen.wrap_expression(tuple)?;
-            Ok(docvec![tuple, Document::String(format!("[{}]", index))])
+            Ok(docvec![tuple, docvec!["[", index, "]"]])
         })
     }
 
@@ -1018,16 +1082,22 @@ impl<'module> Generator<'module> {
         compiled_pattern: CompiledPattern<'a>,
         subject: Document<'a>,
         location: SrcSpan,
+        message: Option<&'a str>,
     ) -> Output<'a> {
         if compiled_pattern.checks.is_empty() {
             return Ok(Self::pattern_assignments_doc(compiled_pattern.assignments));
         }
         if compiled_pattern.assignments.is_empty() {
-            return self.pattern_checks_or_throw_doc(compiled_pattern.checks, subject, location);
+            return self.pattern_checks_or_throw_doc(
+                compiled_pattern.checks,
+                subject,
+                location,
+                message,
+            );
         }
 
         Ok(docvec![
-            self.pattern_checks_or_throw_doc(compiled_pattern.checks, subject, location)?,
+            self.pattern_checks_or_throw_doc(compiled_pattern.checks, subject, location, message)?,
             line(),
             Self::pattern_assignments_doc(compiled_pattern.assignments)
         ])
@@ -1038,6 +1108,7 @@ impl<'module> Generator<'module> {
         checks: Vec<pattern::Check<'a>>,
         subject: Document<'a>,
         location: SrcSpan,
+        message: Option<&'a str>,
     ) -> Output<'a> {
         let checks = self.pattern_checks_doc(checks, false);
         Ok(docvec![
@@ -1045,7 +1116,11 @@ impl<'module> Generator<'module> {
             docvec![break_("", ""), checks].nest(INDENT),
             break_("", ""),
             ") {",
-            docvec![line(), self.assignment_no_match(location, subject)?].nest(INDENT),
+            docvec![
+                line(),
+                self.assignment_no_match(location, subject, message)?
+            ]
+            .nest(INDENT),
             line(),
             "}",
         ]
@@ -1099,11 +1174,19 @@ impl<'module> Generator<'module> {
 }
 
 pub fn int(value: &str) -> Document<'_> {
-    value.to_doc()
+    // Decimals with leading zeros are octal or invalid in JavaScript
+    let literal = crate::int::Literal::new(value);
+    if literal.radix == 10 && literal.digits.len() > 1 && literal.digits.starts_with('0') {
+        let digits = literal.digits.trim_start_matches(['0', '_']);
+        let digits = if digits.is_empty() { "0" } else { digits };
+        Document::String(format!("{}{}", literal.sign(), digits))
+    } else {
+        value.to_doc()
+    }
 }
 
 pub fn float(value: &str) -> Document<'_> {
-    value.to_doc()
+    Document::String(crate::float::canonical_literal(value))
 }
 
 pub(crate) fn constant_expression<'a>(
@@ -1148,6 +1231,9 @@ pub(crate) fn constant_expression<'a>(
                     tracker.error_used = true;
                 }
             }
+            if typ.is_crash_reason() {
+                tracker.crash_reason_used = true;
+            }
             let field_values: Vec<_> = args
                 .iter()
                 .map(|arg| constant_expression(tracker, &arg.value))
@@ -1159,7 +1245,15 @@ pub(crate) fn constant_expression<'a>(
             feature: "Bit string syntax".to_string(),
             location: *location,
         }),
+        Constant::Var {
+            module: Some(module),
+            name,
+            ..
+        } => Ok(docvec!["$", module, ".", maybe_escape_identifier_doc(name)]),
         Constant::Var { name, .. } => Ok(name.to_doc()),
+        Constant::BinOp { .. } => {
+            unreachable!("Constant binary operators are evaluated during type checking")
+        }
     }
 }
 
@@ -1240,6 +1334,18 @@ fn construct_record<'a>(
 }
 
 impl TypedExpr {
+    fn ends_with_statement(&self) -> bool {
+        match self {
+            TypedExpr::Assignment { .. } | TypedExpr::Case { .. } => true,
+            TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                expressions
+                    .last()
+                    .map_or(false, TypedExpr::ends_with_statement)
+            }
+            _ => false,
+        }
+    }
+
     fn handles_own_return(&self) -> bool {
         matches!(
             self,
@@ -7,10 +7,11 @@ pub static ASSIGNMENT_VAR: &str = "$";
 enum Index<'a> {
     Int(usize),
     String(&'a str),
-    ByteAt(usize),
-    IntFromSlice(usize, usize),
-    FloatAt(usize),
-    SliceAfter(usize),
+    ByteAt(Document<'a>),
+    IntFromSlice(Document<'a>, Document<'a>),
+    BinaryFromSlice(Document<'a>, Document<'a>),
+    FloatAt(Document<'a>),
+    SliceAfter(Document<'a>),
     StringPrefixSlice(usize),
 }
 
@@ -28,15 +29,18 @@ pub(crate) struct Generator<'module_ctx, 'expression_gen, 'a> {
     assignments: Vec<Assignment<'a>>,
 }
 
-struct Offset {
+struct Offset<'a> {
     bytes: usize,
+    // Sizes only known at runtime, such as those given by a variable, in bytes
+    dynamic: Vec<Document<'a>>,
     open_ended: bool,
 }
 
-impl Offset {
+impl<'a> Offset<'a> {
     pub fn new() -> Self {
         Self {
             bytes: 0,
+            dynamic: vec![],
             open_ended: false,
         }
     }
@@ -45,9 +49,26 @@ impl Offset {
     pub fn increment(&mut self, step: usize) {
         self.bytes += step
     }
+    pub fn increment_dynamic(&mut self, step: Document<'a>) {
+        self.dynamic.push(step)
+    }
     pub fn set_open_ended(&mut self) {
         self.open_ended = true
     }
+    pub fn to_doc(&self) -> Document<'a> {
+        if self.dynamic.is_empty() {
+            return self.bytes.to_doc();
+        }
+        let constant = if self.bytes == 0 {
+            None
+        } else {
+            Some(self.bytes.to_doc())
+        };
+        join(
+            constant.into_iter().chain(self.dynamic.iter().cloned()),
+            " + ".to_doc(),
+        )
+    }
 }
 
 impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, 'a> {
@@ -82,19 +103,23 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
         self.path.push(Index::StringPrefixSlice(i));
     }
 
-    fn push_byte_at(&mut self, i: usize) {
+    fn push_byte_at(&mut self, i: Document<'a>) {
         self.path.push(Index::ByteAt(i));
     }
 
-    fn push_int_from_slice(&mut self, start: usize, end: usize) {
+    fn push_int_from_slice(&mut self, start: Document<'a>, end: Document<'a>) {
         self.path.push(Index::IntFromSlice(start, end));
     }
 
-    fn push_float_at(&mut self, i: usize) {
+    fn push_binary_from_slice(&mut self, start: Document<'a>, end: Document<'a>) {
+        self.path.push(Index::BinaryFromSlice(start, end));
+    }
+
+    fn push_float_at(&mut self, i: Document<'a>) {
         self.path.push(Index::FloatAt(i));
     }
 
-    fn push_rest_from(&mut self, i: usize) {
+    fn push_rest_from(&mut self, i: Document<'a>) {
         self.path.push(Index::SliceAfter(i));
     }
 
@@ -116,13 +141,18 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
 
     fn path_document(&self) -> Document<'a> {
         concat(self.path.iter().map(|segment| match segment {
-            Index::Int(i) => Document::String(format!("[{}]", i)),
+            Index::Int(i) => docvec!["[", i, "]"],
             // TODO: escape string if needed
             Index::String(s) => docvec!(".", s),
-            Index::ByteAt(i) => docvec!(".byteAt(", i, ")"),
-            Index::IntFromSlice(start, end) => docvec!(".intFromSlice(", start, ", ", end, ")"),
-            Index::FloatAt(i) => docvec!(".floatAt(", i, ")"),
-            Index::SliceAfter(i) => docvec!(".sliceAfter(", i, ")"),
+            Index::ByteAt(i) => docvec!(".byteAt(", i.clone(), ")"),
+            Index::IntFromSlice(start, end) => {
+                docvec!(".intFromSlice(", start.clone(), ", ", end.clone(), ")")
+            }
+            Index::BinaryFromSlice(start, end) => {
+                docvec!(".binaryFromSlice(", start.clone(), ", ", end.clone(), ")")
+            }
+            Index::FloatAt(i) => docvec!(".floatAt(", i.clone(), ")"),
+            Index::SliceAfter(i) => docvec!(".sliceAfter(", i.clone(), ")"),
             Index::StringPrefixSlice(i) => docvec!(".slice(", i, ")"),
         }))
     }
@@ -151,16 +181,22 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
     }
 
     fn push_guard_check(&mut self, guard: &'a TypedClauseGuard) -> Result<(), Error> {
-        let expression = self.guard(guard)?;
+        // The guard is joined to the pattern's checks with `&&`, which binds
+        // more tightly than `||`
+        let expression = match guard {
+            ClauseGuard::Or { .. } => self.wrapped_guard(guard)?,
+            _ => self.guard(guard)?,
+        };
         self.checks.push(Check::Guard { expression });
         Ok(())
     }
 
     fn wrapped_guard(&mut self, guard: &'a TypedClauseGuard) -> Result<Document<'a>, Error> {
         match guard {
-            ClauseGuard::Var { .. } | ClauseGuard::TupleIndex { .. } | ClauseGuard::Constant(_) => {
-                self.guard(guard)
-            }
+            ClauseGuard::Var { .. }
+            | ClauseGuard::TupleIndex { .. }
+            | ClauseGuard::Call { .. }
+            | ClauseGuard::Constant(_) => self.guard(guard),
             ClauseGuard::Equals { .. }
             | ClauseGuard::NotEquals { .. }
             | ClauseGuard::GtInt { .. }
@@ -250,6 +286,11 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
                 docvec!(self.guard(tuple)?, "[", index, "]")
             }
 
+            ClauseGuard::Call { name, args, .. } => {
+                let args: Vec<_> = args.iter().map(|arg| self.guard(arg)).try_collect()?;
+                docvec!(self.local_var(name), wrap_args(args))
+            }
+
             ClauseGuard::Constant(constant) => {
                 return expression::constant_expression(self.expression_generator.tracker, constant)
             }
@@ -281,6 +322,14 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
                 self.push_equality_check(subject.clone(), expression::int(value));
                 Ok(())
             }
+            Pattern::IntRange { start, end, .. } => {
+                self.push_int_range_check(
+                    subject.clone(),
+                    expression::int(start),
+                    expression::int(end),
+                );
+                Ok(())
+            }
             Pattern::Float { value, .. } => {
                 self.push_equality_check(subject.clone(), expression::float(value));
                 Ok(())
@@ -385,7 +434,12 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
                         self.push_result_check(subject.clone(), record_name == "Ok")
                     }
                     Some(m) => self.push_variant_check(subject.clone(), docvec!["$", m, ".", name]),
-                    None => self.push_variant_check(subject.clone(), name.to_doc()),
+                    None => {
+                        if type_.is_crash_reason_constructor() {
+                            self.expression_generator.tracker.crash_reason_used = true;
+                        }
+                        self.push_variant_check(subject.clone(), name.to_doc())
+                    }
                 }
 
                 for (index, arg) in arguments.iter().enumerate() {
@@ -416,35 +470,38 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
                 for segment in segments {
                     match segment.options.as_slice() {
                         [] | [Opt::Int { .. }] => {
-                            self.push_byte_at(offset.bytes);
+                            self.push_byte_at(offset.to_doc());
                             self.traverse_pattern(subject, &segment.value)?;
                             self.pop();
                             offset.increment(1);
                             Ok(())
                         }
 
-                        [Opt::Size { value: size, .. }] => match &**size {
-                            Pattern::Int { value, .. } => {
-                                let start = offset.bytes;
-                                let increment = value
-                                    .parse::<usize>()
-                                    .expect("part of an Int node should always parse as integer");
-                                offset.increment(increment / 8);
-                                let end = offset.bytes;
-
-                                self.push_int_from_slice(start, end);
-                                self.traverse_pattern(subject, &segment.value)?;
-                                self.pop();
-                                Ok(())
-                            }
-                            _ => Err(Error::Unsupported {
-                                feature: "This bit string size option in patterns".to_string(),
-                                location: segment.location,
-                            }),
-                        },
+                        [Opt::Size { value: size, .. }] => {
+                            let start = offset.to_doc();
+                            self.increment_by_size(&mut offset, size, 1, segment.location)?;
+                            let end = offset.to_doc();
+
+                            self.push_int_from_slice(start, end);
+                            self.traverse_pattern(subject, &segment.value)?;
+                            self.pop();
+                            Ok(())
+                        }
+
+                        [Opt::Binary { .. }, Opt::Size { value: size, .. }]
+                        | [Opt::Size { value: size, .. }, Opt::Binary { .. }] => {
+                            let start = offset.to_doc();
+                            self.increment_by_size(&mut offset, size, 8, segment.location)?;
+                            let end = offset.to_doc();
+
+                            self.push_binary_from_slice(start, end);
+                            self.traverse_pattern(subject, &segment.value)?;
+                            self.pop();
+                            Ok(())
+                        }
 
                         [Opt::Float { .. }] => {
-                            self.push_float_at(offset.bytes);
+                            self.push_float_at(offset.to_doc());
                             self.traverse_pattern(subject, &segment.value)?;
                             self.pop();
                             offset.increment(8);
@@ -452,7 +509,7 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
                         }
 
                         [Opt::Binary { .. }] => {
-                            self.push_rest_from(offset.bytes);
+                            self.push_rest_from(offset.to_doc());
                             self.traverse_pattern(subject, &segment.value)?;
                             self.pop();
                             offset.set_open_ended();
@@ -466,7 +523,11 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
                     }?;
                 }
 
-                self.push_bitstring_length_check(subject.clone(), offset.bytes, offset.open_ended);
+                self.push_bitstring_length_check(
+                    subject.clone(),
+                    offset.to_doc(),
+                    offset.open_ended,
+                );
                 Ok(())
             }
             Pattern::VarUsage { location, .. } => Err(Error::Unsupported {
@@ -476,6 +537,50 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
         }
     }
 
+    /// Moves the offset along by the size of a bit string segment, where
+    /// `unit` is the number of bits in each unit of the size, either 1 or 8.
+    fn increment_by_size(
+        &mut self,
+        offset: &mut Offset<'a>,
+        size: &'a TypedPattern,
+        unit: usize,
+        location: SrcSpan,
+    ) -> Result<(), Error> {
+        match size {
+            Pattern::Int { value, .. } => {
+                let size = crate::int::parse(value)
+                    .and_then(|value| usize::try_from(value).ok())
+                    .expect("part of an Int node should always parse as integer");
+                offset.increment(size * unit / 8);
+                Ok(())
+            }
+
+            // The size may be a variable bound earlier in the same pattern, in
+            // which case it has not been assigned yet when the checks run, so
+            // the value is read from the subject instead.
+            Pattern::VarUsage { name, .. } => {
+                let size = self
+                    .assignments
+                    .iter()
+                    .rev()
+                    .find(|assignment| assignment.name == name)
+                    .map(|assignment| docvec![assignment.subject.clone(), assignment.path.clone()])
+                    .unwrap_or_else(|| self.local_var(name));
+                if unit == 8 {
+                    offset.increment_dynamic(size);
+                } else {
+                    offset.increment_dynamic(docvec!["(", size, " / ", 8 / unit, ")"]);
+                }
+                Ok(())
+            }
+
+            _ => Err(Error::Unsupported {
+                feature: "This bit string size option in patterns".to_string(),
+                location,
+            }),
+        }
+    }
+
     fn push_assignment(&mut self, subject: Document<'a>, name: &'a str) {
         let var = self.next_local_var(name);
         let path = self.path_document();
@@ -511,6 +616,20 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
         })
     }
 
+    fn push_int_range_check(
+        &mut self,
+        subject: Document<'a>,
+        start: Document<'a>,
+        end: Document<'a>,
+    ) {
+        self.checks.push(Check::IntRange {
+            start,
+            end,
+            subject,
+            path: self.path_document(),
+        })
+    }
+
     fn push_variant_check(&mut self, subject: Document<'a>, kind: Document<'a>) {
         self.checks.push(Check::Variant {
             kind,
@@ -544,7 +663,7 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
     fn push_bitstring_length_check(
         &mut self,
         subject: Document<'a>,
-        expected_bytes: usize,
+        expected_bytes: Document<'a>,
         has_tail_spread: bool,
     ) {
         self.checks.push(Check::BitStringLength {
@@ -603,6 +722,12 @@ pub enum Check<'a> {
         path: Document<'a>,
         to: Document<'a>,
     },
+    IntRange {
+        subject: Document<'a>,
+        path: Document<'a>,
+        start: Document<'a>,
+        end: Document<'a>,
+    },
     ListLength {
         subject: Document<'a>,
         path: Document<'a>,
@@ -612,7 +737,7 @@ pub enum Check<'a> {
     BitStringLength {
         subject: Document<'a>,
         path: Document<'a>,
-        expected_bytes: usize,
+        expected_bytes: Document<'a>,
         has_tail_spread: bool,
     },
     StringPrefix {
@@ -682,6 +807,30 @@ impl<'a> Check<'a> {
                 docvec![subject, path, operator, to]
             }
 
+            Check::IntRange {
+                subject,
+                path,
+                start,
+                end,
+            } => {
+                let value = docvec![subject, path];
+                if match_desired {
+                    docvec![value.clone(), " >= ", start, " && ", value, " <= ", end]
+                } else {
+                    docvec![
+                        "(",
+                        value.clone(),
+                        " < ",
+                        start,
+                        " || ",
+                        value,
+                        " > ",
+                        end,
+                        ")"
+                    ]
+                }
+            }
+
             Check::ListLength {
                 subject,
                 path,
@@ -705,11 +854,11 @@ impl<'a> Check<'a> {
                 expected_bytes,
                 has_tail_spread,
             } => {
-                let length_check = Document::String(if has_tail_spread {
-                    format!(".length >= {}", expected_bytes)
+                let length_check = if has_tail_spread {
+                    docvec![".length >= ", expected_bytes]
                 } else {
-                    format!(".length == {}", expected_bytes)
-                });
+                    docvec![".length == ", expected_bytes]
+                };
                 if match_desired {
                     docvec![subject, path, length_check,]
                 } else {
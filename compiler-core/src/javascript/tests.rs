@@ -5,7 +5,9 @@ mod bools;
 mod case;
 mod case_clause_guards;
 mod custom_types;
+mod echo;
 mod externals;
+mod features;
 mod functions;
 mod generics;
 mod lists;
@@ -14,6 +16,7 @@ mod numbers;
 mod prelude;
 mod records;
 mod recursion;
+mod rescue;
 mod results;
 mod strings;
 mod todo;
@@ -26,6 +29,45 @@ pub static CURRENT_PACKAGE: &str = "thepackage";
 
 #[macro_export]
 macro_rules! assert_js {
+    (features: $features:expr, $src:expr $(,)?) => {{
+        use crate::{javascript::*, uid::UniqueIdGenerator};
+        use std::path::Path;
+        let mut modules = im::HashMap::new();
+        let ids = UniqueIdGenerator::new();
+        let _ = modules.insert("gleam".to_string(), crate::type_::build_prelude(&ids));
+
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my".into(), "mod".into()];
+        let features = $features
+            .iter()
+            .map(|(flag, enabled): &(&str, bool)| (flag.to_string(), *enabled))
+            .collect();
+        let ast = crate::type_::infer_module_with_features(
+            crate::build::Target::JavaScript,
+            &ids,
+            ast,
+            crate::build::Origin::Src,
+            "thepackage",
+            &modules,
+            features,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let mut output = String::new();
+        let line_numbers = LineNumbers::new($src);
+        module(
+            &ast,
+            &line_numbers,
+            Path::new(""),
+            "",
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    }};
+
     (($dep_package:expr, $dep_name:expr, $dep_src:expr), $src:expr $(,)?) => {{
         use std::path::Path;
         use $crate::{javascript::*, uid::UniqueIdGenerator};
@@ -36,8 +78,9 @@ macro_rules! assert_js {
         // to have one place where we create all this required state for use in each
         // place.
         let _ = modules.insert("gleam".to_string(), $crate::type_::build_prelude(&ids));
-        let (mut ast, _) = $crate::parse::parse_module($dep_src).expect("dep syntax error");
-        ast.name = $dep_name;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($dep_src, &arena).expect("dep syntax error");
+        ast.name = $dep_name.iter().map(|name| name.as_str().into()).collect();
         let dep = $crate::type_::infer_module(
             $crate::build::Target::JavaScript,
             &ids,
@@ -49,8 +92,9 @@ macro_rules! assert_js {
         )
         .expect("should successfully infer");
         let _ = modules.insert($dep_name.join("/"), dep.type_info);
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my".to_string(), "mod".to_string()];
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my".into(), "mod".into()];
         let ast = $crate::type_::infer_module(
             $crate::build::Target::JavaScript,
             &ids,
@@ -63,7 +107,15 @@ macro_rules! assert_js {
         .expect("should successfully infer");
         let mut output = String::new();
         let line_numbers = LineNumbers::new($src);
-        module(&ast, &line_numbers, Path::new(""), "", &mut output).unwrap();
+        module(
+            &ast,
+            &line_numbers,
+            Path::new(""),
+            "",
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
     }};
 
@@ -76,8 +128,9 @@ macro_rules! assert_js {
         // to have one place where we create all this required state for use in each
         // place.
         let _ = modules.insert("gleam".to_string(), $crate::type_::build_prelude(&ids));
-        let (mut ast, _) = $crate::parse::parse_module($dep_src).expect("dep syntax error");
-        ast.name = $dep_name;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($dep_src, &arena).expect("dep syntax error");
+        ast.name = $dep_name.iter().map(|name| name.as_str().into()).collect();
         let dep = crate::type_::infer_module(
             crate::build::Target::JavaScript,
             &ids,
@@ -89,7 +142,8 @@ macro_rules! assert_js {
         )
         .expect("should successfully infer");
         let _ = modules.insert($dep_name.join("/"), dep.type_info);
-        let (mut ast, _) = crate::parse::parse_module($src).expect("syntax error");
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module($src, &arena).expect("syntax error");
         ast.name = vec!["my".to_string(), "mod".to_string()];
         let ast = crate::type_::infer_module(
             crate::build::Target::JavaScript,
@@ -103,7 +157,15 @@ macro_rules! assert_js {
         .expect("should successfully infer");
         let mut output = String::new();
         let line_numbers = LineNumbers::new($src);
-        module(&ast, &line_numbers, Path::new(""), "", &mut output).unwrap();
+        module(
+            &ast,
+            &line_numbers,
+            Path::new(""),
+            "",
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
         assert_eq!(($src, output), ($src, $js.to_string()));
     }};
 
@@ -118,8 +180,9 @@ macro_rules! assert_js {
         // place.
         let _ = modules.insert("gleam".to_string(), crate::type_::build_prelude(&ids));
 
-        let (mut ast, _) = crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my".to_string(), "mod".to_string()];
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my".into(), "mod".into()];
         let ast = crate::type_::infer_module(
             crate::build::Target::JavaScript,
             &ids,
@@ -132,7 +195,15 @@ macro_rules! assert_js {
         .expect("should successfully infer");
         let mut output = String::new();
         let line_numbers = LineNumbers::new($src);
-        module(&ast, &line_numbers, Path::new(""), "", &mut output).unwrap();
+        module(
+            &ast,
+            &line_numbers,
+            Path::new(""),
+            "",
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
     }};
 
@@ -147,8 +218,9 @@ macro_rules! assert_js {
         // place.
         let _ = modules.insert("gleam".to_string(), crate::type_::build_prelude(&ids));
 
-        let (mut ast, _) = crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my".to_string(), "mod".to_string()];
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my".into(), "mod".into()];
         let ast = crate::type_::infer_module(
             crate::build::Target::JavaScript,
             &ids,
@@ -161,7 +233,15 @@ macro_rules! assert_js {
         .expect("should successfully infer");
         let mut output = String::new();
         let line_numbers = LineNumbers::new($src);
-        module(&ast, &line_numbers, Path::new(""), "", &mut output).unwrap();
+        module(
+            &ast,
+            &line_numbers,
+            Path::new(""),
+            "",
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
         assert_eq!(($src, output), ($src, $js.to_string()));
     }};
 }
@@ -178,8 +258,9 @@ macro_rules! assert_ts_def {
         // to have one place where we create all this required state for use in each
         // place.
         let _ = modules.insert("gleam".to_string(), $crate::type_::build_prelude(&ids));
-        let (mut ast, _) = $crate::parse::parse_module($dep_src).expect("dep syntax error");
-        ast.name = $dep_name;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($dep_src, &arena).expect("dep syntax error");
+        ast.name = $dep_name.iter().map(|name| name.as_str().into()).collect();
         let dep = $crate::type_::infer_module(
             $crate::build::Target::JavaScript,
             &ids,
@@ -191,8 +272,9 @@ macro_rules! assert_ts_def {
         )
         .expect("should successfully infer");
         let _ = modules.insert($dep_name.join("/"), dep.type_info);
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my".to_string(), "mod".to_string()];
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my".into(), "mod".into()];
         let ast = $crate::type_::infer_module(
             $crate::build::Target::JavaScript,
             &ids,
@@ -204,7 +286,14 @@ macro_rules! assert_ts_def {
         )
         .expect("should successfully infer");
         let mut output = String::new();
-        ts_declaration(&ast, Path::new(""), "", &mut output).unwrap();
+        ts_declaration(
+            &ast,
+            Path::new(""),
+            "",
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
     }};
 
@@ -217,8 +306,9 @@ macro_rules! assert_ts_def {
         // to have one place where we create all this required state for use in each
         // place.
         let _ = modules.insert("gleam".to_string(), $crate::type_::build_prelude(&ids));
-        let (mut ast, _) = $crate::parse::parse_module($dep_src).expect("dep syntax error");
-        ast.name = $dep_name;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($dep_src, &arena).expect("dep syntax error");
+        ast.name = $dep_name.iter().map(|name| name.as_str().into()).collect();
         let dep = crate::type_::infer_module(
             crate::build::Target::JavaScript,
             &ids,
@@ -230,7 +320,8 @@ macro_rules! assert_ts_def {
         )
         .expect("should successfully infer");
         let _ = modules.insert($dep_name.join("/"), dep.type_info);
-        let (mut ast, _) = crate::parse::parse_module($src).expect("syntax error");
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module($src, &arena).expect("syntax error");
         ast.name = vec!["my".to_string(), "mod".to_string()];
         let ast = crate::type_::infer_module(
             crate::build::Target::JavaScript,
@@ -243,7 +334,14 @@ macro_rules! assert_ts_def {
         )
         .expect("should successfully infer");
         let mut output = String::new();
-        ts_declaration(&ast, Path::new(""), "", &mut output).unwrap();
+        ts_declaration(
+            &ast,
+            Path::new(""),
+            "",
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
         assert_eq!(($src, output), ($src, $js.to_string()));
     }};
 
@@ -258,8 +356,9 @@ macro_rules! assert_ts_def {
         // place.
         let _ = modules.insert("gleam".to_string(), crate::type_::build_prelude(&ids));
 
-        let (mut ast, _) = crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my".to_string(), "mod".to_string()];
+        let arena = crate::ast::Arena::new();
+        let (mut ast, extra) = crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my".into(), "mod".into()];
         let ast = crate::type_::infer_module(
             crate::build::Target::JavaScript,
             &ids,
@@ -270,8 +369,24 @@ macro_rules! assert_ts_def {
             &mut vec![],
         )
         .expect("should successfully infer");
+        let mut module = crate::build::Module {
+            name: "my/mod".into(),
+            code: $src.into(),
+            input_path: "src/my/mod.gleam".into(),
+            origin: crate::build::Origin::Src,
+            ast,
+            extra,
+        };
+        module.attach_doc_and_module_comments();
         let mut output = String::new();
-        ts_declaration(&ast, Path::new(""), "", &mut output).unwrap();
+        ts_declaration(
+            &module.ast,
+            Path::new(""),
+            "",
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
     }};
 
@@ -286,7 +401,8 @@ macro_rules! assert_ts_def {
         // place.
         let _ = modules.insert("gleam".to_string(), crate::type_::build_prelude(&ids));
 
-        let (mut ast, _) = crate::parse::parse_module($src).expect("syntax error");
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module($src, &arena).expect("syntax error");
         ast.name = vec!["my".to_string(), "mod".to_string()];
         let ast = crate::type_::infer_module(
             crate::build::Target::JavaScript,
@@ -299,7 +415,14 @@ macro_rules! assert_ts_def {
         )
         .expect("should successfully infer");
         let mut output = String::new();
-        ts_declaration(&ast, Path::new(""), "", &mut output).unwrap();
+        ts_declaration(
+            &ast,
+            Path::new(""),
+            "",
+            $crate::pretty::DEFAULT_LINE_LENGTH,
+            &mut output,
+        )
+        .unwrap();
         assert_eq!(($src, output), ($src, $js.to_string()));
     }};
 }
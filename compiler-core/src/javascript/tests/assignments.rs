@@ -80,6 +80,11 @@ fn returning_literal_subject() {
     assert_js!(r#"fn go(x) { assert 1 = x + 1 }"#,);
 }
 
+#[test]
+fn assert_with_message() {
+    assert_js!(r#"fn go(x) { assert Ok(y) = x as "x should always be ok" y }"#,);
+}
+
 #[test]
 fn rebound_argument() {
     assert_js!(
@@ -209,6 +209,50 @@ fn go(x) {
     );
 }
 
+#[test]
+fn match_sized_with_radix_literals() {
+    assert_js!(
+        r#"
+fn go(x) {
+  let <<a:0x10, b:0b1000>> = x
+}
+"#,
+    );
+}
+
+#[test]
+fn match_variable_sized() {
+    assert_js!(
+        r#"
+fn go(x, size) {
+  let <<a:size(size), b:8>> = x
+}
+"#,
+    );
+}
+
+#[test]
+fn match_sized_by_earlier_segment() {
+    assert_js!(
+        r#"
+fn go(x) {
+  let <<length, payload:binary-size(length), rest:binary>> = x
+}
+"#,
+    );
+}
+
+#[test]
+fn match_sized_binary() {
+    assert_js!(
+        r#"
+fn go(x) {
+  let <<a:binary-size(2), b:size(16)>> = x
+}
+"#,
+    );
+}
+
 #[test]
 fn match_float() {
     assert_js!(
@@ -158,3 +158,33 @@ fn go(x, y) {
 "#,
     )
 }
+
+#[test]
+fn int_ranges() {
+    assert_js!(
+        r#"
+fn classify(x, y) {
+  case x, y {
+    0..9 as digit, _ -> digit
+    -16..-1, 0..1 | 100..200, _ -> 1
+    10..99, _ if y > 2 || y < 0 -> 2
+    _, _ -> 3
+  }
+}
+"#,
+    )
+}
+
+#[test]
+fn int_range_in_tuple() {
+    assert_js!(
+        r#"
+fn go(x) {
+  case x {
+    #(0..9, _) -> True
+    _ -> False
+  }
+}
+"#,
+    )
+}
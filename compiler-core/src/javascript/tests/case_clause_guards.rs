@@ -67,6 +67,19 @@ fn operator_wrapping_left() {
     );
 }
 
+#[test]
+fn or_after_pattern_check() {
+    assert_js!(
+        r#"pub fn main(x, y: Bool, z: Bool) {
+  case x {
+    1 if y || z -> 1
+    _ -> 0
+  }
+}
+"#,
+    );
+}
+
 #[test]
 fn eq_scalar() {
     assert_js!(
@@ -196,3 +209,20 @@ fn alternative_patterns_guard() {
 "#,
     );
 }
+
+#[test]
+fn function_call() {
+    assert_js!(
+        r#"fn is_small(x: Int) -> Bool {
+  x < 10
+}
+
+pub fn main(x) {
+  case x {
+    _ if is_small(x) -> 1
+    _ -> 0
+  }
+}
+"#,
+    );
+}
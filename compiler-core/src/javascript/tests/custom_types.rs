@@ -265,6 +265,8 @@ fn update(cat) {
   Cat(..new_cat(), name: "Molly")
   let box = Box(occupant: cat)
   Cat(..box.occupant, cuteness: box.occupant.cuteness + 1)
+  Box(..box, occupant.name: "Tom")
+  Box(..Box(cat), occupant.name: "Tom")
 }
 
 fn access(cat: Cat) {
@@ -669,3 +671,73 @@ pub type One { One }
 "#
     );
 }
+
+#[test]
+fn deprecated_declarations() {
+    assert_ts_def!(
+        r#"
+@deprecated("Use Int instead")
+pub type Number {
+  Number(Int)
+}
+
+pub type Animal {
+  Cat
+  @deprecated("Dogs are no longer supported")
+  Dog
+}
+
+@deprecated("Use two instead")
+pub const one = 1
+
+@deprecated("Use add instead")
+pub fn add_one(x: Int) -> Int { x + 1 }
+
+@deprecated("Use List instead")
+pub external type Queue(a)
+"#
+    );
+}
+
+#[test]
+fn documented_declarations() {
+    assert_ts_def!(
+        r#"
+/// A person.
+pub type Person {
+  /// A person with a name.
+  ///
+  /// They may have a nickname too.
+  Person(
+    /// What they are called.
+    name: String,
+    age: Int,
+  )
+}
+
+/// Greets someone.
+pub fn greet(
+  /// The person to greet.
+  person: Person,
+  /// How excited to be,
+  /// from 0 to 10.
+  excitement excitement: Int,
+  _times: Int,
+) -> String {
+  person.name
+}
+
+/// Shows anything.
+pub external fn show(
+  /// The thing to show.
+  thing: anything,
+) -> String = "utils" "inspect"
+
+/// Two.
+pub fn two() -> Int { 2 }
+
+/// The answer.
+pub const answer = 42
+"#
+    );
+}
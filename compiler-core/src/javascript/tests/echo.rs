@@ -0,0 +1,43 @@
+use crate::assert_js;
+
+#[test]
+fn plain() {
+    assert_js!(
+        r#"
+pub fn main() {
+  echo 1
+}
+"#
+    );
+}
+
+#[test]
+fn in_function_call() {
+    assert_js!(
+        r#"
+fn id(x) {
+  x
+}
+
+pub fn main(x) {
+  id(echo #(x, "a"))
+}
+"#
+    );
+}
+
+#[test]
+fn floats() {
+    assert_js!(
+        r#"
+pub type Box(a) {
+  Box(label: String, value: a)
+}
+
+pub fn main() {
+  echo 1500.0
+  echo #(1, [Box("one", 1.0)])
+}
+"#
+    );
+}
@@ -80,3 +80,41 @@ pub external fn new() -> Queue(a) = "queue" "new"
 fn external_fn_escaping() {
     assert_js!(r#"pub external fn then(a) -> b = "./ffi.js" "then""#,);
 }
+
+#[test]
+fn external_attribute_for_target() {
+    assert_js!(
+        r#"@external(erlang, "lists", "reverse")
+@external(javascript, "./ffi.mjs", "reverse")
+pub fn reverse(list: List(a)) -> List(a) {
+  list
+}
+
+pub fn main() {
+  reverse([1, 2])
+}
+"#
+    );
+}
+
+#[test]
+fn external_attribute_for_other_target_uses_body() {
+    assert_js!(
+        r#"@external(erlang, "lists", "reverse")
+pub fn reverse(list: List(a)) -> List(a) {
+  list
+}
+"#
+    );
+}
+
+#[test]
+fn external_attribute_typescript() {
+    assert_ts_def!(
+        r#"@external(javascript, "./ffi.mjs", "reverse")
+pub fn reverse(list: List(a)) -> List(a) {
+  list
+}
+"#
+    );
+}
@@ -0,0 +1,65 @@
+use crate::assert_js;
+
+#[test]
+fn flag_value() {
+    assert_js!(
+        features: [("json", true), ("xml", false)],
+        r#"
+pub fn main() {
+  #(@cfg(json), @cfg(xml))
+}
+"#
+    );
+}
+
+#[test]
+fn enabled_branch() {
+    assert_js!(
+        features: [("json", true)],
+        r#"
+pub fn encode(x) {
+  case @cfg(json) {
+    True -> {
+      let y = x + 1
+      y * 2
+    }
+    False -> x
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn disabled_branch() {
+    assert_js!(
+        features: [("json", false)],
+        r#"
+pub fn encode(x) {
+  let y = case @cfg(json) {
+    True -> x + 1
+    _ -> x
+  }
+  y
+}
+"#
+    );
+}
+
+#[test]
+fn branch_in_sequence() {
+    assert_js!(
+        features: [("debug", true)],
+        r#"
+fn log(x) { x }
+
+pub fn main(x) {
+  case @cfg(debug) {
+    True -> log(x)
+    False -> x
+  }
+  x
+}
+"#
+    );
+}
@@ -264,6 +264,28 @@ fn reserved_word_argument() {
     );
 }
 
+#[test]
+fn operator_sections() {
+    assert_js!(
+        r#"pub fn main() {
+  let f = _ * 2 + _
+  #(f, { _ - 1 }(2))
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_with_multiple_holes() {
+    assert_js!(
+        r#"fn add(x, y, z) { x + y + z }
+pub fn main() {
+  add(_, 1, _)
+}
+"#,
+    );
+}
+
 // https://github.com/gleam-lang/gleam/issues/1186
 #[test]
 fn multiple_discard() {
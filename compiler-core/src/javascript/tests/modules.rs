@@ -66,6 +66,39 @@ pub fn go() { rocket_ship.x }
     );
 }
 
+#[test]
+fn constant_referencing_other_module_constant() {
+    assert_js!(
+        (
+            CURRENT_PACKAGE,
+            vec!["rocket_ship".to_string()],
+            r#"const speed = 1
+pub const x = #(speed, [2.0], "three", True)"#
+        ),
+        r#"
+import rocket_ship.{x as y}
+pub const x = rocket_ship.x
+pub const z = y
+"#,
+    );
+}
+
+#[test]
+fn constant_referencing_other_module_record_constant() {
+    assert_js!(
+        (
+            CURRENT_PACKAGE,
+            vec!["rocket_ship".to_string()],
+            r#"pub type Ship { Ship(speed: Int) }
+pub const x = Ship(1)"#
+        ),
+        r#"
+import rocket_ship
+pub const x = rocket_ship.x
+"#,
+    );
+}
+
 #[test]
 fn alias_constant() {
     assert_js!(
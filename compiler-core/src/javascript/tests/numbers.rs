@@ -18,6 +18,19 @@ fn go() {
     );
 }
 
+#[test]
+fn int_literals_with_leading_zeros() {
+    assert_js!(
+        r#"
+fn go() {
+    007
+    -0_1
+    00
+}
+"#,
+    );
+}
+
 #[test]
 fn float_literals() {
     assert_js!(
@@ -155,3 +168,18 @@ fn go() {
 "#,
     );
 }
+
+#[test]
+fn constant_arithmetic() {
+    assert_js!(
+        r#"
+const seconds = 60 * 60
+const hours = { seconds + 1 } / 3600
+const zero = 1 / 0
+const half = 1.0 /. 2.0
+pub fn main() {
+  #(seconds, hours, zero, half)
+}
+"#
+    );
+}
@@ -54,6 +54,22 @@ pub fn get_age(person: Person) { person.age }"
     );
 }
 
+#[test]
+fn record_accessor_multiple_variants_different_positions() {
+    // We can access fields on custom types with multiple variants
+    // when the field is at a different position in each variant
+    assert_js!(
+        "
+pub type Shape {
+    Circle(radius: Float, name: String)
+    Square(name: String, side: Float)
+    Point(name: String)
+}
+pub fn get_name(shape: Shape) { shape.name }
+pub fn get_names(a: Shape, b: Shape) { #(a.name, b.name) }"
+    );
+}
+
 #[test]
 fn record_accessor_multiple_variants_parameterised_types() {
     // We can access fields on custom types with multiple variants
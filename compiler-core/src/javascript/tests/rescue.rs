@@ -0,0 +1,54 @@
+use crate::{assert_js, assert_ts_def};
+
+#[test]
+fn rescue_call() {
+    assert_js!(
+        r#"
+pub fn main() {
+  rescue(fn() { todo })
+}
+"#
+    );
+}
+
+#[test]
+fn qualified_rescue() {
+    assert_js!(
+        r#"
+import gleam
+
+pub fn main() {
+  gleam.rescue(fn() { 1 })
+}
+"#
+    );
+}
+
+#[test]
+fn crash_reason() {
+    assert_js!(
+        r#"
+pub fn main(f) {
+  case rescue(f) {
+    Ok(x) -> x
+    Error(CrashReason(message)) -> message
+  }
+}
+
+pub fn make() {
+  CrashReason("message")
+}
+"#
+    );
+}
+
+#[test]
+fn crash_reason_typescript() {
+    assert_ts_def!(
+        r#"
+pub fn main(f: fn() -> String) {
+  rescue(f)
+}
+"#
+    );
+}
@@ -90,3 +90,17 @@ pub fn go(x) {
 "#,
     );
 }
+
+#[test]
+fn concat_in_constant() {
+    assert_js!(
+        r#"
+const name = "Joe"
+const greeting = "Hello, " <> name <> "!\n"
+
+pub fn go() {
+  greeting
+}
+"#,
+    );
+}
@@ -11,14 +11,18 @@
 //! <https://www.typescriptlang.org/>
 //! <https://www.typescriptlang.org/docs/handbook/declaration-files/introduction.html>
 
-use std::{collections::HashMap, ops::Deref, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Deref,
+    sync::Arc,
+};
 
 use heck::ToUpperCamelCase;
 use itertools::Itertools;
 
 use crate::{
     ast::{
-        Statement, TypedArg, TypedConstant, TypedExternalFnArg, TypedModule,
+        Deprecation, Name, Statement, TypedArg, TypedConstant, TypedExternalFnArg, TypedModule,
         TypedRecordConstructor, TypedStatement,
     },
     docvec,
@@ -52,7 +56,7 @@ fn name_with_generics<'a>(
     name: Document<'a>,
     types: impl IntoIterator<Item = &'a Arc<Type>>,
 ) -> Document<'a> {
-    let generic_usages = collect_generic_usages(HashMap::new(), types);
+    let generic_usages = collect_generic_usages(BTreeMap::new(), types);
     let generic_names: Vec<Document<'_>> = generic_usages
         .iter()
         .map(|(id, _use_count)| id_to_type_var(*id))
@@ -78,16 +82,16 @@ fn name_with_generics<'a>(
 //     fn() -> Result(a, b)  // `a` and `b` are `any`
 //     fn(a) -> a            // `a` is a generic
 fn collect_generic_usages<'a>(
-    mut ids: HashMap<u64, u64>,
+    mut ids: BTreeMap<u64, u64>,
     types: impl IntoIterator<Item = &'a Arc<Type>>,
-) -> HashMap<u64, u64> {
+) -> BTreeMap<u64, u64> {
     for typ in types {
         generic_ids(typ, &mut ids);
     }
     ids
 }
 
-fn generic_ids(type_: &Type, ids: &mut HashMap<u64, u64>) {
+fn generic_ids(type_: &Type, ids: &mut BTreeMap<u64, u64>) {
     match type_ {
         Type::Var { type_: typ } => match typ.borrow().deref() {
             TypeVar::Unbound { id, .. } | TypeVar::Generic { id, .. } => {
@@ -144,6 +148,70 @@ where
         .group()
 }
 
+/// Adds a `@deprecated` tag to the doc comment of a declaration if the Gleam
+/// definition was deprecated, so that editors can flag any uses of it.
+///
+fn deprecated<'a>(deprecation: &Deprecation, declaration: Output<'a>) -> Output<'a> {
+    match deprecation {
+        Deprecation::NotDeprecated => declaration,
+        Deprecation::Deprecated { message } => {
+            let message = message.replace("*/", "*\\/");
+            let tag = Document::String(format!("/** @deprecated {} */", message));
+            Ok(docvec![tag, line(), declaration?])
+        }
+    }
+}
+
+/// A documentation comment with the documentation of a declaration and of any of its
+/// parameters that have doc comments, or nothing if none are documented.
+///
+fn tsdoc<'a>(doc: &Option<String>, parameters: Vec<(String, &Option<String>)>) -> Document<'a> {
+    fn doc_lines(doc: &str) -> impl Iterator<Item = String> + '_ {
+        doc.lines().map(|line| {
+            let line = line.strip_prefix(' ').unwrap_or(line).trim_end();
+            line.replace("*/", "*\\/")
+        })
+    }
+
+    let mut lines = doc
+        .as_deref()
+        .map(doc_lines)
+        .into_iter()
+        .flatten()
+        .collect_vec();
+    for (name, doc) in parameters {
+        let mut doc_lines = match doc {
+            Some(doc) => doc_lines(doc),
+            None => continue,
+        };
+        if matches!(lines.last(), Some(line) if !line.starts_with("@param")) {
+            lines.push(String::new());
+        }
+        let first = doc_lines.next().unwrap_or_default();
+        lines.push(format!("@param {} - {}", name, first));
+        lines.extend(doc_lines);
+    }
+
+    match lines.as_slice() {
+        [] => super::nil(),
+        [text] => docvec![Document::String(format!("/** {} */", text)), line()],
+        lines => docvec![
+            "/**",
+            concat(lines.iter().map(|text| {
+                let text = if text.is_empty() {
+                    " *".to_string()
+                } else {
+                    format!(" * {}", text)
+                };
+                docvec![line(), Document::String(text)]
+            })),
+            line(),
+            " */",
+            line(),
+        ],
+    }
+}
+
 /// Returns a name that can be used as a TypeScript type name. If there is a
 /// naming clash a '_' will be appended.
 ///
@@ -178,7 +246,7 @@ fn ts_safe_type_name(mut name: String) -> String {
 #[derive(Debug)]
 pub struct TypeScriptGenerator<'a> {
     module: &'a TypedModule,
-    aliased_module_names: HashMap<&'a [String], &'a str>,
+    aliased_module_names: HashMap<&'a [Name], &'a str>,
     tracker: UsageTracker,
 }
 
@@ -256,19 +324,14 @@ impl<'a> TypeScriptGenerator<'a> {
     /// "$" symbol to prevent any clashes with other Gleam names that may be
     /// used in this module.
     ///
-    fn register_import(
-        &mut self,
-        imports: &mut Imports<'a>,
-        package: &'a str,
-        module: &'a [String],
-    ) {
+    fn register_import(&mut self, imports: &mut Imports<'a>, package: &'a str, module: &'a [Name]) {
         let path = self.import_path(package, module);
         imports.register_module(path, [self.module_name(module)], []);
     }
 
     /// Calculates the path of where to import an external module from
     ///
-    fn import_path(&self, package: &'a str, module: &'a [String]) -> String {
+    fn import_path(&self, package: &'a str, module: &'a [Name]) -> String {
         let path = module.join("/");
 
         // TODO: strip shared prefixed between current module and imported
@@ -295,16 +358,18 @@ impl<'a> TypeScriptGenerator<'a> {
                 alias,
                 public,
                 type_,
+                deprecation,
                 ..
-            } if *public => vec![self.type_alias(alias, type_)],
+            } if *public => vec![deprecated(deprecation, self.type_alias(alias, type_))],
             Statement::TypeAlias { .. } => vec![],
 
             Statement::ExternalType {
                 public,
                 name,
                 arguments,
+                deprecation,
                 ..
-            } if *public => vec![self.external_type(name, arguments)],
+            } if *public => vec![deprecated(deprecation, self.external_type(name, arguments))],
             Statement::ExternalType { .. } => vec![],
 
             Statement::Import { .. } => vec![],
@@ -315,18 +380,28 @@ impl<'a> TypeScriptGenerator<'a> {
                 opaque,
                 name,
                 typed_parameters,
+                deprecation,
+                doc,
                 ..
-            } if *public => {
-                self.custom_type_definition(name, typed_parameters, constructors, *opaque)
-            }
+            } if *public => self.custom_type_definition(
+                name,
+                typed_parameters,
+                constructors,
+                *opaque,
+                deprecation,
+                doc,
+            ),
             Statement::CustomType { .. } => vec![],
 
             Statement::ModuleConstant {
                 public,
                 name,
                 value,
+                deprecation,
+                doc,
                 ..
-            } if *public => vec![self.module_constant(name, value)],
+            } if *public => vec![deprecated(deprecation, self.module_constant(name, value))
+                .map(|constant| docvec![tsdoc(doc, vec![]), constant])],
             Statement::ModuleConstant { .. } => vec![],
 
             Statement::Fn {
@@ -334,8 +409,27 @@ impl<'a> TypeScriptGenerator<'a> {
                 name,
                 public,
                 return_type,
+                deprecation,
+                doc,
                 ..
-            } if *public => vec![self.module_function(name, arguments, return_type)],
+            } if *public => {
+                let parameters = arguments
+                    .iter()
+                    .enumerate()
+                    .map(|(i, argument)| {
+                        let name = match argument.get_variable_name() {
+                            Some(name) => super::maybe_escape_identifier_string(name),
+                            None => format!("x{}", i),
+                        };
+                        (name, &argument.doc)
+                    })
+                    .collect();
+                vec![deprecated(
+                    deprecation,
+                    self.module_function(name, arguments, return_type),
+                )
+                .map(|function| docvec![tsdoc(doc, parameters), function])]
+            }
             Statement::Fn { .. } => vec![],
 
             Statement::ExternalFn {
@@ -343,13 +437,32 @@ impl<'a> TypeScriptGenerator<'a> {
                 name,
                 arguments,
                 return_type,
+                deprecation,
+                doc,
                 ..
-            } if *public => vec![self.external_function(name, arguments, return_type)],
+            } if *public => {
+                let parameters = arguments
+                    .iter()
+                    .enumerate()
+                    .map(|(i, argument)| {
+                        let name = match &argument.label {
+                            Some(label) => super::maybe_escape_identifier_string(label),
+                            None => format!("x{}", i),
+                        };
+                        (name, &argument.doc)
+                    })
+                    .collect();
+                vec![deprecated(
+                    deprecation,
+                    self.external_function(name, arguments, return_type),
+                )
+                .map(|function| docvec![tsdoc(doc, parameters), function])]
+            }
             Statement::ExternalFn { .. } => vec![],
         }
     }
 
-    fn external_type(&self, name: &str, args: &'a [String]) -> Output<'a> {
+    fn external_type(&self, name: &str, args: &'a [Name]) -> Output<'a> {
         let doc_name = Document::String(format!("{}$", ts_safe_type_name(name.to_string())));
         if args.is_empty() {
             Ok(docvec!["export type ", doc_name, " = any;"])
@@ -390,25 +503,40 @@ impl<'a> TypeScriptGenerator<'a> {
         typed_parameters: &'a [Arc<Type>],
         constructors: &'a [TypedRecordConstructor],
         opaque: bool,
+        deprecation: &'a Deprecation,
+        doc: &Option<String>,
     ) -> Vec<Output<'a>> {
         let mut definitions: Vec<Output<'_>> = constructors
             .iter()
-            .map(|constructor| Ok(self.record_definition(constructor, opaque)))
+            .map(|constructor| {
+                // Constructors of a deprecated type are deprecated too
+                let deprecation = if constructor.deprecation.is_deprecated() {
+                    &constructor.deprecation
+                } else {
+                    deprecation
+                };
+                deprecated(deprecation, Ok(self.record_definition(constructor, opaque)))
+                    .map(|class| docvec![tsdoc(&constructor.documentation, vec![]), class])
+            })
             .collect();
 
-        definitions.push(Ok(docvec![
-            "export type ",
-            name_with_generics(Document::String(format!("{}$", name)), typed_parameters),
-            " = ",
-            concat(Itertools::intersperse(
-                constructors.iter().map(|x| name_with_generics(
-                    super::maybe_escape_identifier_doc(&x.name),
-                    x.arguments.iter().map(|a| &a.type_)
+        definitions.push(deprecated(
+            deprecation,
+            Ok(docvec![
+                tsdoc(doc, vec![]),
+                "export type ",
+                name_with_generics(Document::String(format!("{}$", name)), typed_parameters),
+                " = ",
+                concat(Itertools::intersperse(
+                    constructors.iter().map(|x| name_with_generics(
+                        super::maybe_escape_identifier_doc(&x.name),
+                        x.arguments.iter().map(|a| &a.type_)
+                    )),
+                    break_("| ", " | "),
                 )),
-                break_("| ", " | "),
-            )),
-            ";",
-        ]));
+                ";",
+            ]),
+        ));
 
         definitions
     }
@@ -447,7 +575,7 @@ impl<'a> TypeScriptGenerator<'a> {
                     .label
                     .as_ref()
                     .map(|s| super::maybe_escape_identifier_doc(s))
-                    .unwrap_or_else(|| Document::String(format!("{}", i)));
+                    .unwrap_or_else(|| i.to_doc());
                 docvec![name, ": ", self.do_print_force_generic_param(&arg.type_)]
             })),
             ";",
@@ -460,8 +588,9 @@ impl<'a> TypeScriptGenerator<'a> {
                         .label
                         .as_ref()
                         .map(|s| super::maybe_escape_identifier_doc(s))
-                        .unwrap_or_else(|| Document::String(format!("x{}", i)));
+                        .unwrap_or_else(|| docvec!["x", i]);
                     docvec![
+                        tsdoc(&arg.doc, vec![]),
                         name,
                         ": ",
                         self.do_print_force_generic_param(&arg.type_),
@@ -493,7 +622,7 @@ impl<'a> TypeScriptGenerator<'a> {
         return_type: &'a Arc<Type>,
     ) -> Output<'a> {
         let generic_usages = collect_generic_usages(
-            HashMap::new(),
+            BTreeMap::new(),
             std::iter::once(return_type).chain(args.iter().map(|a| &a.type_)),
         );
         let generic_names: Vec<Document<'_>> = generic_usages
@@ -543,7 +672,7 @@ impl<'a> TypeScriptGenerator<'a> {
         return_type: &'a Arc<Type>,
     ) -> Output<'a> {
         let generic_usages = collect_generic_usages(
-            HashMap::new(),
+            BTreeMap::new(),
             std::iter::once(return_type).chain(args.iter().map(|a| &a.type_)),
         );
         let generic_names: Vec<Document<'_>> = generic_usages
@@ -594,7 +723,7 @@ impl<'a> TypeScriptGenerator<'a> {
     pub fn print_type_with_generic_usages(
         &mut self,
         type_: &Type,
-        generic_usages: &HashMap<u64, u64>,
+        generic_usages: &BTreeMap<u64, u64>,
     ) -> Document<'static> {
         self.do_print(type_, Some(generic_usages))
     }
@@ -602,7 +731,7 @@ impl<'a> TypeScriptGenerator<'a> {
     /// Get the locally used name for a module. Either the last segment, or the
     /// alias if one was given when imported.
     ///
-    fn module_name(&self, parts: &[String]) -> String {
+    fn module_name(&self, parts: &[Name]) -> String {
         // The prelude is always `_`
         if parts.is_empty() {
             return "_".into();
@@ -610,14 +739,14 @@ impl<'a> TypeScriptGenerator<'a> {
 
         match self.aliased_module_names.get(parts) {
             Some(name) => (*name).to_string(),
-            None => parts.last().expect("Non empty module path").clone(),
+            None => parts.last().expect("Non empty module path").to_string(),
         }
     }
 
     fn do_print(
         &mut self,
         type_: &Type,
-        generic_usages: Option<&HashMap<u64, u64>>,
+        generic_usages: Option<&BTreeMap<u64, u64>>,
     ) -> Document<'static> {
         match type_ {
             Type::Var { type_: typ } => self.print_var(&typ.borrow(), generic_usages, false),
@@ -657,7 +786,7 @@ impl<'a> TypeScriptGenerator<'a> {
     fn print_var(
         &mut self,
         type_: &TypeVar,
-        generic_usages: Option<&HashMap<u64, u64>>,
+        generic_usages: Option<&BTreeMap<u64, u64>>,
         force_generic_id: bool,
     ) -> Document<'static> {
         match type_ {
@@ -688,7 +817,7 @@ impl<'a> TypeScriptGenerator<'a> {
         &mut self,
         name: &str,
         args: &[Arc<Type>],
-        generic_usages: Option<&HashMap<u64, u64>>,
+        generic_usages: Option<&BTreeMap<u64, u64>>,
     ) -> Document<'static> {
         match name {
             "Nil" => "null".to_doc(),
@@ -703,6 +832,10 @@ impl<'a> TypeScriptGenerator<'a> {
                 self.tracker.prelude_used = true;
                 "_.BitString".to_doc()
             }
+            "CrashReason" => {
+                self.tracker.prelude_used = true;
+                "_.CrashReason".to_doc()
+            }
             "List" => {
                 self.tracker.prelude_used = true;
                 docvec![
@@ -730,8 +863,8 @@ impl<'a> TypeScriptGenerator<'a> {
         &mut self,
         name: &str,
         args: &[Arc<Type>],
-        module: &[String],
-        generic_usages: Option<&HashMap<u64, u64>>,
+        module: &[Name],
+        generic_usages: Option<&BTreeMap<u64, u64>>,
     ) -> Document<'static> {
         let name = format!("{}$", ts_safe_type_name(name.to_upper_camel_case()));
         let name = match module == self.module.name {
@@ -763,7 +896,7 @@ impl<'a> TypeScriptGenerator<'a> {
         &mut self,
         args: &[Arc<Type>],
         retrn: &Type,
-        generic_usages: Option<&HashMap<u64, u64>>,
+        generic_usages: Option<&BTreeMap<u64, u64>>,
     ) -> Document<'static> {
         docvec![
             wrap_args(args.iter().enumerate().map(|(idx, a)| docvec![
@@ -0,0 +1,40 @@
+//! Editor tooling that works on the typed AST of a module, for use by the
+//! language server. Results are given in terms of byte offsets into the
+//! source of the module so they can be converted to the positions of whatever
+//! protocol is used to talk to the editor.
+
+mod code_action;
+mod code_lens;
+mod completion;
+mod hover;
+mod inlay_hints;
+mod references;
+mod rename;
+mod semantic_tokens;
+mod signature_help;
+mod symbols;
+
+#[cfg(test)]
+mod tests;
+
+pub use code_action::{code_actions, error_actions, extract_actions, CodeAction, ErrorActions};
+pub use code_lens::{code_lenses, CodeLens, Runnable};
+pub use completion::{completions, Completion, CompletionKind};
+pub use hover::documentation;
+pub use inlay_hints::{inlay_hints, InlayHint, InlayHintKind};
+pub use references::{Reference, ReferenceIndex, ReferenceKind};
+pub use rename::rename;
+pub use semantic_tokens::{semantic_tokens, SemanticToken, SemanticTokenKind};
+pub use signature_help::{parameter_documentation, signature_help, SignatureHelp};
+pub use symbols::{
+    document_symbols, workspace_symbols, DocumentSymbol, SymbolKind, WorkspaceSymbol,
+};
+
+use crate::ast::SrcSpan;
+
+/// A change to the source code of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub location: SrcSpan,
+    pub new_text: String,
+}
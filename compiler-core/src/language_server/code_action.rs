@@ -0,0 +1,915 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use itertools::Itertools;
+
+use super::TextEdit;
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        Arena, AssignmentKind, Layer, Pattern, SrcSpan, Statement, TypedExpr, TypedModule,
+        TypedStatement, UnqualifiedImport, UntypedModule,
+    },
+    format,
+    name::Name,
+    parse::extra::ModuleExtra,
+    type_::{self, pretty::Printer, Type, TypeVar, ValueConstructor, ValueConstructorVariant},
+    Error,
+};
+
+/// A change to a module that the user can choose to apply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeAction {
+    pub title: String,
+    pub edits: Vec<TextEdit>,
+}
+
+/// The code actions that can be applied to the selected part of a module.
+///
+/// - An expression with a `Result` or `Option` type can be wrapped in a
+///   `case` expression with a `todo` clause for each constructor.
+/// - A `Result` assigned with `let` can instead be unwrapped with
+///   `use value <- result.then(...)`.
+///
+pub fn code_actions(module: &TypedModule, src: &str, selection: SrcSpan) -> Vec<CodeAction> {
+    let mut actions = vec![];
+    let expression = match find_wrappable_expression(module, selection) {
+        Some(expression) => expression,
+        None => return actions,
+    };
+    let wrapped = match wrapped_type(&expression.type_()) {
+        Some(wrapped) => wrapped,
+        None => return actions,
+    };
+    actions.push(wrap_in_case(module, src, expression, wrapped));
+    if let Some(action) = use_result_then(module, src, expression) {
+        actions.push(action);
+    }
+    actions
+}
+
+/// The refactorings that extract the innermost expression covering the
+/// selected part of a module, written the way the formatter would.
+///
+/// - The expression can be assigned to a new variable with `let` before the
+///   statement it is in, replacing it with the variable.
+/// - The expression can be lifted into a new private function after the one
+///   it is in, replacing it with a call. Each local variable it uses becomes
+///   a parameter, annotated with its type.
+///
+/// Neither is offered if the expression uses a variable that is defined
+/// after the start of the statement it is in, such as one bound by the
+/// pattern of a `case` clause, as it would not be in scope where the
+/// extracted code is written.
+///
+pub fn extract_actions(module: &TypedModule, src: &str, selection: SrcSpan) -> Vec<CodeAction> {
+    if selection.start == selection.end {
+        return vec![];
+    }
+    let (function, expression) = match find_extractable_expression(module, selection) {
+        Some(found) => found,
+        None => return vec![],
+    };
+    let (end_position, body) = match function {
+        Statement::Fn {
+            end_position, body, ..
+        } => (*end_position, body),
+        _ => return vec![],
+    };
+    let location = expression.location();
+    let mut enclosing = EnclosingStatement {
+        expression: location,
+        statement: body.location(),
+    };
+    enclosing.visit_typed_expr(body);
+    let statement = enclosing.statement;
+
+    let mut captured = CapturedVariables {
+        expression: location,
+        variables: vec![],
+    };
+    captured.visit_typed_expr(expression);
+    if captured
+        .variables
+        .iter()
+        .any(|(_, _, defined)| defined.start >= statement.start)
+    {
+        return vec![];
+    }
+
+    let mut names = VariableNames::default();
+    names.visit_typed_statement(function);
+    let mut actions = vec![];
+    if statement != location && !matches!(expression, TypedExpr::Var { .. }) {
+        actions.extend(extract_variable(src, expression, statement, &names.0));
+    }
+    actions.extend(extract_function(
+        module,
+        src,
+        expression,
+        end_position + 1,
+        &captured.variables,
+        &names.0,
+    ));
+    actions
+}
+
+// `let value = expression` is written before the statement the expression is
+// in, and the expression is replaced by `value`.
+fn extract_variable(
+    src: &str,
+    expression: &TypedExpr,
+    statement: SrcSpan,
+    used_names: &[&str],
+) -> Option<CodeAction> {
+    let location = expression.location();
+    let name = unused_name("value", used_names);
+    let indent = line_indentation(src, statement.start);
+    let assignment = formatted_statement(
+        &format!("let {} = {}", name, source_text(src, location)),
+        indent,
+    )?;
+    let edits = if statement.start == location.start {
+        vec![TextEdit {
+            location,
+            new_text: format!("{}\n{}{}", assignment, indent, name),
+        }]
+    } else {
+        vec![
+            insert(statement.start, format!("{}\n{}", assignment, indent)),
+            TextEdit {
+                location,
+                new_text: name,
+            },
+        ]
+    };
+    Some(CodeAction {
+        title: "Extract variable".into(),
+        edits,
+    })
+}
+
+// A private function returning the expression is written after the function
+// it is in, and the expression is replaced by a call to it with each of the
+// local variables it uses as arguments.
+fn extract_function(
+    module: &TypedModule,
+    src: &str,
+    expression: &TypedExpr,
+    function_end: u32,
+    captured: &[(&str, Arc<Type>, SrcSpan)],
+    used_names: &[&str],
+) -> Option<CodeAction> {
+    let mut module_names = module_value_names(module);
+    module_names.extend_from_slice(used_names);
+    let name = unused_name("function", &module_names);
+
+    // Type variables are named consistently across the parameters and the
+    // return type. A type that cannot be referred to from the module is left
+    // unannotated.
+    let mut printer = Printer::new();
+    let parameters = captured
+        .iter()
+        .map(
+            |(variable, type_, _)| match annotation(module, &mut printer, type_) {
+                Some(annotation) => format!("{}: {}", variable, annotation),
+                None => variable.to_string(),
+            },
+        )
+        .join(", ");
+    let return_annotation = annotation(module, &mut printer, &expression.type_())
+        .map(|annotation| format!(" -> {}", annotation))
+        .unwrap_or_default();
+    let definition = format!(
+        "fn {}({}){} {{\n{}\n}}\n",
+        name,
+        parameters,
+        return_annotation,
+        source_text(src, expression.location())
+    );
+    let mut formatted = String::new();
+    format::pretty(
+        &mut formatted,
+        &definition,
+        Path::new(""),
+        &format::Options::default(),
+    )
+    .ok()?;
+
+    let arguments = captured.iter().map(|(variable, ..)| variable).join(", ");
+    Some(CodeAction {
+        title: "Extract function".into(),
+        edits: vec![
+            TextEdit {
+                location: expression.location(),
+                new_text: format!("{}({})", name, arguments),
+            },
+            insert(function_end, format!("\n\n{}", formatted.trim_end())),
+        ],
+    })
+}
+
+// The innermost expression of a function that covers the whole selection,
+// along with the function.
+fn find_extractable_expression(
+    module: &TypedModule,
+    selection: SrcSpan,
+) -> Option<(&TypedStatement, &TypedExpr)> {
+    let predicate = |expression: &TypedExpr| {
+        !matches!(
+            expression,
+            TypedExpr::Sequence { .. } | TypedExpr::Assignment { .. }
+        ) && expression.location().end >= selection.end
+    };
+    module
+        .statements
+        .iter()
+        .find_map(|statement| match statement {
+            Statement::Fn { body, .. } => Some((
+                statement,
+                body.find_node_where(selection.start, &predicate)?,
+            )),
+            _ => None,
+        })
+}
+
+// The location of the innermost statement of a block that contains the
+// expression, or of the body of the function if it is not in a block.
+struct EnclosingStatement {
+    expression: SrcSpan,
+    statement: SrcSpan,
+}
+
+impl<'ast> Visit<'ast> for EnclosingStatement {
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::Sequence { expressions, .. } = expr {
+            let containing = expressions
+                .iter()
+                .map(TypedExpr::location)
+                .find(|location| {
+                    location.start <= self.expression.start && self.expression.end <= location.end
+                });
+            if let Some(location) = containing {
+                self.statement = location;
+            }
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+}
+
+// The local variables used by an expression that are defined outside of it,
+// in the order they are first used, with their types and where they are
+// defined.
+struct CapturedVariables<'ast> {
+    expression: SrcSpan,
+    variables: Vec<(&'ast str, Arc<Type>, SrcSpan)>,
+}
+
+impl<'ast> Visit<'ast> for CapturedVariables<'ast> {
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::Var {
+            name,
+            constructor:
+                ValueConstructor {
+                    variant: ValueConstructorVariant::LocalVariable { location },
+                    type_,
+                    ..
+                },
+            ..
+        } = expr
+        {
+            let is_captured = !self.expression.contains(location.start);
+            if is_captured && !self.variables.iter().any(|(used, ..)| used == name) {
+                self.variables.push((name, type_.clone(), *location));
+            }
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+}
+
+// The names of the values referred to in a function, which a new variable
+// would shadow.
+#[derive(Default)]
+struct VariableNames<'ast>(Vec<&'ast str>);
+
+impl<'ast> Visit<'ast> for VariableNames<'ast> {
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::Var { name, .. } = expr {
+            self.0.push(name);
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+}
+
+// The names of the values defined in or imported unqualified into a module.
+fn module_value_names(module: &TypedModule) -> Vec<&str> {
+    module
+        .statements
+        .iter()
+        .flat_map(|statement| match statement {
+            Statement::Fn { name, .. }
+            | Statement::ExternalFn { name, .. }
+            | Statement::ModuleConstant { name, .. } => vec![name.as_str()],
+            Statement::CustomType { constructors, .. } => constructors
+                .iter()
+                .map(|constructor| constructor.name.as_str())
+                .collect(),
+            Statement::Import { unqualified, .. } => unqualified
+                .iter()
+                .filter(|import| import.layer == Layer::Value)
+                .map(UnqualifiedImport::variable_name)
+                .collect(),
+            Statement::TypeAlias { .. } | Statement::ExternalType { .. } => vec![],
+        })
+        .collect()
+}
+
+// The name, or the name followed by the lowest number from 2 that makes it
+// one that is not used.
+fn unused_name(name: &str, used_names: &[&str]) -> String {
+    std::iter::once(name.to_string())
+        .chain((2..).map(|number| format!("{}_{}", name, number)))
+        .find(|candidate| !used_names.contains(&candidate.as_str()))
+        .unwrap_or_default()
+}
+
+// A type written as it would be in an annotation in the module, if each of
+// the named types it refers to can be referred to from the module.
+fn annotation(module: &TypedModule, printer: &mut Printer, type_: &Type) -> Option<String> {
+    let annotations = |types: &[Arc<Type>], printer: &mut Printer| -> Option<String> {
+        types
+            .iter()
+            .map(|type_| annotation(module, printer, type_))
+            .collect::<Option<Vec<_>>>()
+            .map(|annotations| annotations.join(", "))
+    };
+    match type_ {
+        Type::App {
+            module: defined_in,
+            name,
+            args,
+            ..
+        } => {
+            let name = if defined_in.is_empty() || defined_in == &module.name {
+                name.to_string()
+            } else {
+                imported_type_name(module, defined_in, name)?
+            };
+            if args.is_empty() {
+                Some(name)
+            } else {
+                Some(format!("{}({})", name, annotations(args, printer)?))
+            }
+        }
+        Type::Fn { args, retrn } => Some(format!(
+            "fn({}) -> {}",
+            annotations(args, printer)?,
+            annotation(module, printer, retrn)?
+        )),
+        Type::Tuple { elems } => Some(format!("#({})", annotations(elems, printer)?)),
+        Type::Var { type_: var } => match &*var.borrow() {
+            TypeVar::Link { type_ } => annotation(module, printer, type_),
+            TypeVar::Unbound { .. } | TypeVar::Generic { .. } => {
+                Some(printer.pretty_print(type_, 0))
+            }
+        },
+    }
+}
+
+// The name a type of an imported module is referred to by, unqualified if it
+// is imported unqualified.
+fn imported_type_name(module: &TypedModule, path: &[Name], name: &str) -> Option<String> {
+    module
+        .statements
+        .iter()
+        .find_map(|statement| match statement {
+            Statement::Import {
+                module,
+                as_name,
+                unqualified,
+                ..
+            } if module == path => {
+                let unqualified = unqualified
+                    .iter()
+                    .find(|import| import.layer == Layer::Type && import.name == name);
+                Some(match unqualified {
+                    Some(import) => import.variable_name().to_string(),
+                    None => {
+                        let module_name = path.last().map(Name::as_str).unwrap_or_default();
+                        format!("{}.{}", as_name.as_deref().unwrap_or(module_name), name)
+                    }
+                })
+            }
+            _ => None,
+        })
+}
+
+// Code written the way the formatter would as statements of a function body
+// indented by the given whitespace, without indenting its first line.
+fn formatted_statement(code: &str, indent: &str) -> Option<String> {
+    let mut formatted = String::new();
+    format::pretty(
+        &mut formatted,
+        &format!("fn extracted() {{\n{}\n}}\n", code),
+        Path::new(""),
+        &format::Options::default(),
+    )
+    .ok()?;
+    let body = formatted
+        .strip_prefix("fn extracted() {\n")?
+        .strip_suffix("\n}\n")?;
+    Some(
+        body.lines()
+            .map(|line| line.strip_prefix("  ").unwrap_or(line))
+            .enumerate()
+            .map(|(index, line)| match (index, line) {
+                (0, _) | (_, "") => line.to_string(),
+                _ => format!("{}{}", indent, line),
+            })
+            .join("\n"),
+    )
+}
+
+/// The code actions that fix an error of a module that failed to compile,
+/// offered where the error is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorActions {
+    pub path: PathBuf,
+    /// The source of the module the edits of the actions apply to.
+    pub src: String,
+    /// Where the error is.
+    pub location: SrcSpan,
+    pub actions: Vec<CodeAction>,
+}
+
+/// The code actions that fix an error of a module. The edits write the code
+/// the way the formatter would.
+///
+/// - A value or type that is not in scope can be imported unqualified from
+///   each of the modules that could be imported which publish one of that
+///   name, adding it to the unqualified imports of the module or importing
+///   the module.
+/// - Such a value or type can instead be qualified with the name of the
+///   module, importing the module if it is not yet imported.
+/// - A `case` expression that does not match every record constructor of
+///   its subject can be given a clause for each of those it does not match,
+///   with a `todo` body.
+///
+pub fn error_actions(
+    error: &Error,
+    importable_modules: &im::HashMap<String, type_::Module>,
+) -> Option<ErrorActions> {
+    let (path, src, error) = match error {
+        Error::Type { path, src, error } => (path, src, error),
+        _ => return None,
+    };
+    let (location, actions) = match error {
+        type_::Error::UnknownVariable { location, name, .. } => {
+            import_actions(src, *location, name, false, importable_modules)?
+        }
+        type_::Error::UnknownType { location, name, .. } => {
+            import_actions(src, *location, name, true, importable_modules)?
+        }
+        type_::Error::NotExhaustivePatternMatch {
+            location, patterns, ..
+        } => (
+            *location,
+            vec![add_missing_clauses(src, *location, patterns)?],
+        ),
+        _ => return None,
+    };
+    if actions.is_empty() {
+        return None;
+    }
+    Some(ErrorActions {
+        path: path.clone(),
+        src: src.clone(),
+        location,
+        actions,
+    })
+}
+
+fn import_actions(
+    src: &str,
+    location: SrcSpan,
+    name: &str,
+    is_type: bool,
+    importable_modules: &im::HashMap<String, type_::Module>,
+) -> Option<(SrcSpan, Vec<CodeAction>)> {
+    // The location of an unknown type includes its arguments
+    let location = SrcSpan {
+        start: location.start,
+        end: location.start + name.len() as u32,
+    };
+    if source_text(src, location) != name {
+        return None;
+    }
+    let arena = Arena::new();
+    let (module, extra) = crate::parse::parse_module(src, &arena).ok()?;
+    let imports = Imports::new(&module, &extra, src);
+
+    let actions = importable_modules
+        .iter()
+        .filter(|(_, candidate)| {
+            if is_type {
+                candidate
+                    .types
+                    .get(name)
+                    .map_or(false, |type_| type_.public)
+            } else {
+                candidate
+                    .values
+                    .get(name)
+                    .map_or(false, |value| value.public)
+            }
+        })
+        .map(|(module_name, _)| module_name)
+        .sorted()
+        .flat_map(|module_name| {
+            [
+                imports.import_unqualified(module_name, name),
+                Some(imports.qualify(module_name, name, location)),
+            ]
+        })
+        .flatten()
+        .collect_vec();
+    Some((location, actions))
+}
+
+// A clause with a `todo` body for each of the unmatched patterns, added after
+// the last clause of the `case` expression
+fn add_missing_clauses(src: &str, location: SrcSpan, patterns: &[String]) -> Option<CodeAction> {
+    let closing_brace = location.end.checked_sub(1)?;
+    if patterns.is_empty()
+        || !source_text(src, location).starts_with("case")
+        || !source_text(src, location).ends_with('}')
+    {
+        return None;
+    }
+    let indent = line_indentation(src, location.start);
+    let clauses: String = patterns
+        .iter()
+        .map(|pattern| format!("{}  {} -> todo\n", indent, pattern))
+        .collect();
+    let line_start = src
+        .get(..closing_brace as usize)
+        .and_then(|before| before.rfind('\n'))
+        .map_or(0, |newline| newline as u32 + 1);
+    let before_brace = source_text(
+        src,
+        SrcSpan {
+            start: line_start,
+            end: closing_brace,
+        },
+    );
+    let edit = if before_brace.trim().is_empty() {
+        insert(line_start, clauses)
+    } else {
+        let content_end = line_start + before_brace.trim_end().len() as u32;
+        TextEdit {
+            location: SrcSpan {
+                start: content_end,
+                end: closing_brace,
+            },
+            new_text: format!("\n{}{}", clauses, indent),
+        }
+    };
+    Some(CodeAction {
+        title: "Add missing patterns".into(),
+        edits: vec![edit],
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Wrapped {
+    Result,
+    Option,
+}
+
+fn wrapped_type(type_: &Type) -> Option<Wrapped> {
+    match type_ {
+        Type::App { module, name, .. } if module.is_empty() && name == "Result" => {
+            Some(Wrapped::Result)
+        }
+        Type::App { module, name, .. } if module == &["gleam", "option"] && name == "Option" => {
+            Some(Wrapped::Option)
+        }
+        Type::Var { type_ } => match &*type_.borrow() {
+            TypeVar::Link { type_ } => wrapped_type(type_),
+            TypeVar::Unbound { .. } | TypeVar::Generic { .. } => None,
+        },
+        Type::App { .. } | Type::Fn { .. } | Type::Tuple { .. } => None,
+    }
+}
+
+// The innermost expression with a `Result` or `Option` type that covers the
+// whole selection.
+fn find_wrappable_expression(module: &TypedModule, selection: SrcSpan) -> Option<&TypedExpr> {
+    let predicate = |expression: &TypedExpr| {
+        !matches!(
+            expression,
+            TypedExpr::Sequence { .. } | TypedExpr::Assignment { .. }
+        ) && expression.location().end >= selection.end
+            && wrapped_type(&expression.type_()).is_some()
+    };
+    function_bodies(module).find_map(|body| body.find_node_where(selection.start, &predicate))
+}
+
+fn function_bodies(module: &TypedModule) -> impl Iterator<Item = &TypedExpr> {
+    module
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Fn { body, .. } => Some(body),
+            _ => None,
+        })
+}
+
+fn wrap_in_case(
+    module: &TypedModule,
+    src: &str,
+    expression: &TypedExpr,
+    wrapped: Wrapped,
+) -> CodeAction {
+    let location = expression.location();
+    let indent = line_indentation(src, location.start);
+    let mut edits = vec![];
+    let (title, success, failure) = match wrapped {
+        Wrapped::Result => (
+            "Wrap in case over Result",
+            "Ok(value)".to_string(),
+            "Error(e)".to_string(),
+        ),
+        Wrapped::Option => {
+            let (some, import) = imported_name(module, &["gleam", "option"], "Some");
+            let (none, _) = imported_name(module, &["gleam", "option"], "None");
+            edits.extend(import);
+            ("Wrap in case over Option", format!("{}(value)", some), none)
+        }
+    };
+    let new_text = format!(
+        "case {expression} {{
+{indent}  {success} -> todo
+{indent}  {failure} -> todo
+{indent}}}",
+        expression = source_text(src, location),
+    );
+    edits.push(TextEdit { location, new_text });
+    CodeAction {
+        title: title.into(),
+        edits,
+    }
+}
+
+// `let value = result` becomes `use value <- result.then(result)`
+fn use_result_then(module: &TypedModule, src: &str, expression: &TypedExpr) -> Option<CodeAction> {
+    if wrapped_type(&expression.type_()) != Some(Wrapped::Result) {
+        return None;
+    }
+    let location = expression.location();
+    let is_assignment = |candidate: &TypedExpr| match candidate {
+        TypedExpr::Assignment {
+            value,
+            pattern: Pattern::Var { .. } | Pattern::Discard { .. },
+            kind: AssignmentKind::Let,
+            ..
+        } => value.location() == location,
+        _ => false,
+    };
+    let assignment = function_bodies(module)
+        .find_map(|body| body.find_node_where(location.start, &is_assignment))?;
+    let (assignment_location, name) = match assignment {
+        TypedExpr::Assignment {
+            location,
+            pattern: Pattern::Var { name, .. } | Pattern::Discard { name, .. },
+            ..
+        } => (*location, name),
+        _ => return None,
+    };
+    let (then, import) = imported_name(module, &["gleam", "result"], "then");
+    let new_text = format!("use {} <- {}({})", name, then, source_text(src, location));
+    let mut edits: Vec<_> = import.into_iter().collect();
+    edits.push(TextEdit {
+        location: SrcSpan {
+            start: assignment_location.start,
+            end: location.end,
+        },
+        new_text,
+    });
+    Some(CodeAction {
+        title: "Unwrap with use and result.then".into(),
+        edits,
+    })
+}
+
+// The name to refer to a public value of a module with from the current
+// module, along with an edit to import the module if it is not yet imported.
+fn imported_name(module: &TypedModule, path: &[&str], name: &str) -> (String, Option<TextEdit>) {
+    let import = module
+        .statements
+        .iter()
+        .find_map(|statement| match statement {
+            Statement::Import {
+                module,
+                as_name,
+                unqualified,
+                ..
+            } if module == path => Some((as_name, unqualified)),
+            _ => None,
+        });
+    let module_name = path.last().copied().unwrap_or_default();
+    match import {
+        Some((as_name, unqualified)) => {
+            let unqualified = unqualified.iter().find(|import| import.name == name);
+            let name = match unqualified {
+                Some(import) => import.variable_name().to_string(),
+                None => format!("{}.{}", as_name.as_deref().unwrap_or(module_name), name),
+            };
+            (name, None)
+        }
+        None => {
+            let import = TextEdit {
+                location: SrcSpan { start: 0, end: 0 },
+                new_text: format!("import {}\n", path.join("/")),
+            };
+            (format!("{}.{}", module_name, name), Some(import))
+        }
+    }
+}
+
+// The import statements of a module that failed to compile.
+struct Imports<'a> {
+    src: &'a str,
+    imports: Vec<(SrcSpan, String, Option<&'a str>, &'a [UnqualifiedImport])>,
+    module_comments_end: Option<u32>,
+}
+
+impl<'a> Imports<'a> {
+    fn new(module: &'a UntypedModule<'_>, extra: &ModuleExtra, src: &'a str) -> Self {
+        let imports = module
+            .statements
+            .iter()
+            .flat_map(|group| group.statements_ref())
+            .filter_map(|statement| match statement {
+                Statement::Import {
+                    location,
+                    module,
+                    as_name,
+                    unqualified,
+                    ..
+                } => Some((
+                    *location,
+                    module.join("/"),
+                    as_name.as_deref(),
+                    unqualified.as_slice(),
+                )),
+                _ => None,
+            })
+            .sorted_by_key(|(location, ..)| location.start)
+            .collect();
+        let module_comments_end = extra
+            .module_comments
+            .iter()
+            .map(|comment| comment.end)
+            .max();
+        Self {
+            src,
+            imports,
+            module_comments_end,
+        }
+    }
+
+    // `name` becomes an unqualified import of the module
+    fn import_unqualified(&self, module_name: &str, name: &str) -> Option<CodeAction> {
+        let edit = match self
+            .imports
+            .iter()
+            .find(|(_, module, ..)| module == module_name)
+        {
+            Some((location, _, _, unqualified)) => {
+                if unqualified.iter().any(|import| import.name == name) {
+                    return None;
+                }
+                // Unqualified imports are written in order of their names
+                match unqualified
+                    .iter()
+                    .find(|import| import.name.as_str() > name)
+                {
+                    Some(after) => insert(after.location.start, format!("{}, ", name)),
+                    None => match unqualified.last() {
+                        Some(last) => insert(last.location.end, format!(", {}", name)),
+                        None => {
+                            let path_end = location.start + module_name.len() as u32;
+                            let rest = self.src.get(path_end as usize..).unwrap_or_default();
+                            match rest.strip_prefix(".{") {
+                                Some(_) => insert(path_end + 2, name.to_string()),
+                                None => insert(path_end, format!(".{{{}}}", name)),
+                            }
+                        }
+                    },
+                }
+            }
+            None => self.new_import(&format!("{}.{{{}}}", module_name, name)),
+        };
+        Some(CodeAction {
+            title: format!("Import `{}` from `{}`", name, module_name),
+            edits: vec![edit],
+        })
+    }
+
+    // `name` becomes `module.name`
+    fn qualify(&self, module_name: &str, name: &str, location: SrcSpan) -> CodeAction {
+        let import = self
+            .imports
+            .iter()
+            .find(|(_, module, ..)| module == module_name);
+        let (qualifier, import) = match import {
+            Some((_, _, as_name, _)) => {
+                let qualifier = as_name.unwrap_or_else(|| last_segment(module_name));
+                (qualifier, None)
+            }
+            None => (
+                last_segment(module_name),
+                Some(self.new_import(module_name)),
+            ),
+        };
+        let qualified = format!("{}.{}", qualifier, name);
+        let mut edits: Vec<_> = import.into_iter().collect();
+        edits.push(TextEdit {
+            location,
+            new_text: qualified.clone(),
+        });
+        CodeAction {
+            title: format!("Qualify as `{}`", qualified),
+            edits,
+        }
+    }
+
+    // An edit adding an import statement. Imports written in order of their
+    // module names are kept in order, otherwise it is added after the last
+    // import, or after the module comments if there are no imports.
+    fn new_import(&self, import: &str) -> TextEdit {
+        let module_name = import.split(".{").next().unwrap_or(import);
+        let sorted = self
+            .imports
+            .iter()
+            .tuple_windows()
+            .all(|((_, a, ..), (_, b, ..))| a <= b);
+        let before = self
+            .imports
+            .iter()
+            .find(|(_, module, ..)| sorted && module.as_str() > module_name);
+        if let Some((location, ..)) = before {
+            let line_start = self
+                .src
+                .get(..location.start as usize)
+                .and_then(|before| before.rfind('\n'))
+                .map_or(0, |newline| newline as u32 + 1);
+            return insert(line_start, format!("import {}\n", import));
+        }
+        if let Some((location, ..)) = self.imports.last() {
+            return insert(location.end, format!("\nimport {}", import));
+        }
+        match self.module_comments_end {
+            Some(end) => {
+                let line_end = self
+                    .src
+                    .get(end as usize..)
+                    .and_then(|rest| rest.find('\n'))
+                    .map_or(self.src.len() as u32, |newline| end + newline as u32);
+                insert(line_end, format!("\n\nimport {}", import))
+            }
+            None => insert(0, format!("import {}\n\n", import)),
+        }
+    }
+}
+
+fn insert(at: u32, new_text: String) -> TextEdit {
+    TextEdit {
+        location: SrcSpan { start: at, end: at },
+        new_text,
+    }
+}
+
+fn last_segment(module_name: &str) -> &str {
+    module_name.rsplit('/').next().unwrap_or(module_name)
+}
+
+fn source_text(src: &str, location: SrcSpan) -> &str {
+    src.get(location.start as usize..location.end as usize)
+        .unwrap_or_default()
+}
+
+// The whitespace at the start of the line that the byte index is on
+fn line_indentation(src: &str, byte_index: u32) -> &str {
+    let line_start = src
+        .get(..byte_index as usize)
+        .and_then(|before| before.rfind('\n'))
+        .map(|newline| newline + 1)
+        .unwrap_or(0);
+    let line = src.get(line_start..).unwrap_or_default();
+    let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+    line.get(..indent).unwrap_or_default()
+}
@@ -0,0 +1,51 @@
+use crate::{
+    ast::{SrcSpan, Statement, TypedModule},
+    build::{test_function_name, Origin, TestFunction},
+};
+
+/// A command shown above a function that can be run from the editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeLens {
+    /// The definition of the function.
+    pub location: SrcSpan,
+    pub runnable: Runnable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Runnable {
+    /// A test function, run by `gleam test`.
+    Test(TestFunction),
+    /// The `main` function of the module of a package with the same name,
+    /// run by `gleam run`.
+    Main,
+}
+
+/// What can be run from a module of the package: each of the test functions
+/// of a test module, and the `main` function of the entry module.
+pub fn code_lenses(module: &TypedModule, origin: Origin, package: &str) -> Vec<CodeLens> {
+    let module_name = module.name.join("/");
+    let is_entry = origin == Origin::Src && module_name == package;
+    module
+        .statements
+        .iter()
+        .filter_map(|statement| {
+            let runnable = match statement {
+                Statement::Fn {
+                    name,
+                    public: true,
+                    arguments,
+                    ..
+                } if is_entry && name == "main" && arguments.is_empty() => Runnable::Main,
+                _ if origin == Origin::Test => Runnable::Test(TestFunction {
+                    module: module_name.clone(),
+                    function: test_function_name(statement)?.to_string(),
+                }),
+                _ => return None,
+            };
+            Some(CodeLens {
+                location: statement.location(),
+                runnable,
+            })
+        })
+        .collect()
+}
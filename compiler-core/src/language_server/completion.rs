@@ -0,0 +1,538 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        ArgNames, Name, Pattern, Statement, TypedArg, TypedExpr, TypedModule, TypedPattern,
+        UnqualifiedImport,
+    },
+    build::Origin,
+    type_::{self, collapse_links, pretty::Printer, Type, ValueConstructorVariant},
+};
+
+/// A suggestion for the code being written at the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    /// The type of the suggestion, if it has one.
+    pub detail: Option<String>,
+    /// The text to insert, if it is not the label.
+    pub insert_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompletionKind {
+    Module,
+    Function,
+    Constructor,
+    Constant,
+    Variable,
+    Field,
+    Label,
+    Type,
+}
+
+/// The completions for the cursor at a byte offset into the source of a
+/// module, most relevant first.
+///
+/// The source is what the user is editing, so it may not compile. What it
+/// refers to is found in the module as it was when it last compiled, and in
+/// the interfaces of the modules it could import.
+///
+/// - After `import` the names of the importable modules are suggested, and
+///   inside the braces of an import the members of the imported module.
+/// - After `module.` the public values and types of the imported module are
+///   suggested, and after `value.` the fields of the record.
+/// - Otherwise the names in scope are suggested: the unused labels of the
+///   call the cursor is in, the variables defined before the cursor with the
+///   closest first, the definitions of the module, the values imported
+///   unqualified, and the imported modules.
+///
+pub fn completions(
+    src: &str,
+    cursor: u32,
+    module: &TypedModule,
+    importable_modules: &im::HashMap<String, type_::Module>,
+) -> Vec<Completion> {
+    let before = src.get(..cursor as usize).unwrap_or(src);
+    let line = &before[before.rfind('\n').map(|i| i + 1).unwrap_or(0)..];
+    let context = Context {
+        module,
+        importable_modules,
+        cursor,
+    };
+
+    if let Some(import) = line.trim_start().strip_prefix("import ") {
+        return match import.split_once('{') {
+            Some((imported, _)) => context.module_members(imported.trim().trim_end_matches('.')),
+            None if !import.contains(' ') => context.importable_module_names(),
+            None => vec![],
+        };
+    }
+
+    let word = before.trim_end_matches(is_name_char);
+    if let Some(qualifier) = word.strip_suffix('.') {
+        let qualifier = &qualifier[qualifier.trim_end_matches(is_name_char).len()..];
+        return match context.imported_module(qualifier) {
+            Some(imported) => context.module_members(&imported),
+            None => context.record_fields(qualifier),
+        };
+    }
+
+    let mut completions = enclosing_call(word)
+        .map(|(function, arguments)| context.labels(function, arguments))
+        .unwrap_or_default();
+    completions.extend(context.variables());
+    completions.extend(context.module_definitions());
+    completions.extend(context.unqualified_imports());
+    completions.extend(context.imported_modules());
+    completions
+        .into_iter()
+        .unique_by(|completion| (completion.label.clone(), completion.kind))
+        .collect()
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The function called by the call the end of the source is within, and the
+/// arguments given to it so far.
+fn enclosing_call(src: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+    for (index, c) in src.char_indices().rev() {
+        match c {
+            ')' => depth += 1,
+            '(' if depth == 0 => {
+                let callee = src[..index].trim_end();
+                let start = callee
+                    .trim_end_matches(|c| is_name_char(c) || c == '.')
+                    .len();
+                return Some((&callee[start..], &src[index + 1..]));
+            }
+            '(' => depth -= 1,
+            '{' | '}' if depth == 0 => return None,
+            _ => (),
+        }
+    }
+    None
+}
+
+struct Context<'a> {
+    module: &'a TypedModule,
+    importable_modules: &'a im::HashMap<String, type_::Module>,
+    cursor: u32,
+}
+
+impl<'a> Context<'a> {
+    fn imports(
+        &self,
+    ) -> impl Iterator<Item = (&'a [Name], Option<&'a str>, &'a [UnqualifiedImport])> {
+        self.module
+            .statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Import {
+                    module,
+                    as_name,
+                    unqualified,
+                    ..
+                } => Some((
+                    module.as_slice(),
+                    as_name.as_deref(),
+                    unqualified.as_slice(),
+                )),
+                _ => None,
+            })
+    }
+
+    /// The name of the module imported with the given name.
+    fn imported_module(&self, name: &str) -> Option<String> {
+        self.imports()
+            .find(|(module, as_name, _)| {
+                as_name.or_else(|| module.last().map(Name::as_str)) == Some(name)
+            })
+            .map(|(module, _, _)| module.join("/"))
+    }
+
+    fn importable_module_names(&self) -> Vec<Completion> {
+        let name = self.module.name.join("/");
+        let is_test = self.module.type_info.origin == Origin::Test;
+        self.importable_modules
+            .iter()
+            .filter(|(module, interface)| {
+                **module != name && (is_test || interface.origin != Origin::Test)
+            })
+            .map(|(module, _)| module)
+            .sorted()
+            .map(|module| completion(module, CompletionKind::Module, None))
+            .collect()
+    }
+
+    fn module_members(&self, name: &str) -> Vec<Completion> {
+        let interface = match self.importable_modules.get(name) {
+            Some(interface) => interface,
+            None => return vec![],
+        };
+        let values = interface
+            .values
+            .iter()
+            .filter(|(_, value)| value.public)
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .map(|(name, value)| completion(name, value_kind(&value.variant), Some(&value.type_)));
+        let types = interface
+            .types
+            .iter()
+            .filter(|(_, type_)| type_.public)
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .map(|(name, _)| completion(name, CompletionKind::Type, None));
+        values.chain(types).collect()
+    }
+
+    /// The fields of the record held by the variable with the given name.
+    fn record_fields(&self, variable: &str) -> Vec<Completion> {
+        let type_ = self
+            .local_variables()
+            .into_iter()
+            .find(|local| local.name == variable)
+            .and_then(|local| local.type_)
+            .map(collapse_links);
+        let (module, name) = match type_.as_deref() {
+            Some(Type::App { module, name, .. }) => (module.join("/"), name),
+            _ => return vec![],
+        };
+        let interface = if module == self.module.name.join("/") {
+            &self.module.type_info
+        } else {
+            match self.importable_modules.get(&module) {
+                Some(interface) => interface,
+                None => return vec![],
+            }
+        };
+        interface
+            .accessors
+            .get(name)
+            .into_iter()
+            .flat_map(|accessors| accessors.accessors.values())
+            .sorted_by_key(|accessor| accessor.index)
+            .map(|accessor| {
+                completion(
+                    &accessor.label,
+                    CompletionKind::Field,
+                    Some(&accessor.type_),
+                )
+            })
+            .collect()
+    }
+
+    /// The labels of the function not yet given in the arguments of the call.
+    fn labels(&self, function: &str, arguments: &str) -> Vec<Completion> {
+        let labels = match function.rsplit_once('.') {
+            Some((module, name)) => self
+                .imported_module(module)
+                .and_then(|module| self.interface_labels(&module, name)),
+            None => self
+                .module_labels(function)
+                .or_else(|| self.unqualified_import_labels(function)),
+        };
+        labels
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(label, _)| !arguments.contains(&format!("{}:", label)))
+            .map(|(label, type_)| Completion {
+                insert_text: Some(format!("{}: ", label)),
+                label: format!("{}:", label),
+                ..completion("", CompletionKind::Label, type_.as_ref())
+            })
+            .collect()
+    }
+
+    fn interface_labels(&self, module: &str, name: &str) -> Option<Vec<(Name, Option<Arc<Type>>)>> {
+        let value = self.importable_modules.get(module)?.values.get(name)?;
+        let field_map = match &value.variant {
+            ValueConstructorVariant::ModuleFn { field_map, .. }
+            | ValueConstructorVariant::Record { field_map, .. } => field_map.as_ref()?,
+            ValueConstructorVariant::ModuleConstant { .. }
+            | ValueConstructorVariant::LocalVariable { .. } => return None,
+        };
+        let arguments = value
+            .type_
+            .fn_types()
+            .map(|(arguments, _)| arguments)
+            .unwrap_or_default();
+        Some(
+            field_map
+                .fields
+                .iter()
+                .sorted_by_key(|(_, index)| **index)
+                .map(|(label, index)| (label.clone(), arguments.get(*index as usize).cloned()))
+                .collect(),
+        )
+    }
+
+    fn module_labels(&self, name: &str) -> Option<Vec<(Name, Option<Arc<Type>>)>> {
+        self.module
+            .statements
+            .iter()
+            .find_map(|statement| match statement {
+                Statement::Fn {
+                    name: function,
+                    arguments,
+                    ..
+                } if function == name => Some(
+                    arguments
+                        .iter()
+                        .filter_map(|argument| match &argument.names {
+                            ArgNames::NamedLabelled { label, .. }
+                            | ArgNames::LabelledDiscard { label, .. } => {
+                                Some((label.clone(), Some(argument.type_.clone())))
+                            }
+                            ArgNames::Named { .. } | ArgNames::Discard { .. } => None,
+                        })
+                        .collect(),
+                ),
+                Statement::CustomType { constructors, .. } => constructors
+                    .iter()
+                    .find(|constructor| constructor.name == name)
+                    .map(|constructor| {
+                        constructor
+                            .arguments
+                            .iter()
+                            .filter_map(|argument| {
+                                let label = argument.label.clone()?;
+                                Some((label, Some(argument.type_.clone())))
+                            })
+                            .collect()
+                    }),
+                _ => None,
+            })
+    }
+
+    fn unqualified_import_labels(&self, name: &str) -> Option<Vec<(Name, Option<Arc<Type>>)>> {
+        self.imports().find_map(|(module, _, unqualified)| {
+            let import = unqualified
+                .iter()
+                .find(|import| import.variable_name() == name)?;
+            self.interface_labels(&module.join("/"), &import.name)
+        })
+    }
+
+    /// The variables of the function the cursor is in that are defined before
+    /// the cursor, the closest first.
+    fn local_variables(&self) -> Vec<LocalVariable<'a>> {
+        let body = self
+            .module
+            .statements
+            .iter()
+            .find(|statement| match statement {
+                Statement::Fn {
+                    location,
+                    end_position,
+                    ..
+                } => location.start <= self.cursor && self.cursor <= *end_position,
+                _ => false,
+            });
+        let mut collector = LocalVariables::default();
+        if let Some(statement) = body {
+            collector.visit_typed_statement(statement);
+        }
+        let mut variables: Vec<LocalVariable<'a>> = vec![];
+        for variable in collector
+            .variables
+            .into_iter()
+            .filter(|variable| variable.start < self.cursor)
+            .sorted_by_key(|variable| std::cmp::Reverse(variable.start))
+        {
+            match variables
+                .iter_mut()
+                .find(|known| known.name == variable.name)
+            {
+                // Only a usage of a pattern variable has its type
+                Some(known) if known.start == variable.start && known.type_.is_none() => {
+                    known.type_ = variable.type_;
+                }
+                Some(_) => (),
+                None => variables.push(variable),
+            }
+        }
+        variables
+    }
+
+    fn variables(&self) -> Vec<Completion> {
+        self.local_variables()
+            .into_iter()
+            .map(|variable| {
+                completion(
+                    variable.name,
+                    CompletionKind::Variable,
+                    variable.type_.as_ref(),
+                )
+            })
+            .collect()
+    }
+
+    fn module_definitions(&self) -> Vec<Completion> {
+        let mut completions = vec![];
+        for statement in &self.module.statements {
+            match statement {
+                Statement::Fn {
+                    name,
+                    arguments,
+                    return_type,
+                    ..
+                } => completions.push(completion(
+                    name,
+                    CompletionKind::Function,
+                    Some(&type_::fn_(
+                        arguments.iter().map(|a| a.type_.clone()).collect(),
+                        return_type.clone(),
+                    )),
+                )),
+                Statement::ExternalFn {
+                    name,
+                    arguments,
+                    return_type,
+                    ..
+                } => completions.push(completion(
+                    name,
+                    CompletionKind::Function,
+                    Some(&type_::fn_(
+                        arguments.iter().map(|a| a.type_.clone()).collect(),
+                        return_type.clone(),
+                    )),
+                )),
+                Statement::ModuleConstant { name, type_, .. } => {
+                    completions.push(completion(name, CompletionKind::Constant, Some(type_)))
+                }
+                Statement::CustomType { constructors, .. } => {
+                    for constructor in constructors {
+                        let type_ = self
+                            .module
+                            .type_info
+                            .values
+                            .get(&constructor.name)
+                            .map(|value| &value.type_);
+                        completions.push(completion(
+                            &constructor.name,
+                            CompletionKind::Constructor,
+                            type_,
+                        ));
+                    }
+                }
+                Statement::TypeAlias { .. }
+                | Statement::ExternalType { .. }
+                | Statement::Import { .. } => (),
+            }
+        }
+        completions.sort_by(|a, b| a.label.cmp(&b.label));
+        completions
+    }
+
+    fn unqualified_imports(&self) -> Vec<Completion> {
+        self.imports()
+            .flat_map(|(module, _, unqualified)| {
+                let interface = self.importable_modules.get(&module.join("/"));
+                unqualified.iter().filter_map(move |import| {
+                    let value = interface?.values.get(&import.name)?;
+                    let kind = value_kind(&value.variant);
+                    Some(completion(import.variable_name(), kind, Some(&value.type_)))
+                })
+            })
+            .sorted_by(|a, b| a.label.cmp(&b.label))
+            .collect()
+    }
+
+    fn imported_modules(&self) -> Vec<Completion> {
+        self.imports()
+            .filter_map(|(module, as_name, _)| as_name.or_else(|| Some(module.last()?.as_str())))
+            .sorted()
+            .map(|name| completion(name, CompletionKind::Module, None))
+            .collect()
+    }
+}
+
+fn value_kind(variant: &ValueConstructorVariant) -> CompletionKind {
+    match variant {
+        ValueConstructorVariant::Record { .. } => CompletionKind::Constructor,
+        ValueConstructorVariant::ModuleConstant { .. } => CompletionKind::Constant,
+        ValueConstructorVariant::ModuleFn { .. }
+        | ValueConstructorVariant::LocalVariable { .. } => CompletionKind::Function,
+    }
+}
+
+fn completion(label: &str, kind: CompletionKind, type_: Option<&Arc<Type>>) -> Completion {
+    Completion {
+        label: label.to_string(),
+        kind,
+        detail: type_.map(|type_| Printer::new().pretty_print(&collapse_links(type_.clone()), 0)),
+        insert_text: None,
+    }
+}
+
+#[derive(Debug)]
+struct LocalVariable<'a> {
+    name: &'a str,
+    /// Where the variable is defined.
+    start: u32,
+    type_: Option<Arc<Type>>,
+}
+
+/// The variables defined by the arguments and patterns of a function, and the
+/// types of those that are used.
+#[derive(Debug, Default)]
+struct LocalVariables<'a> {
+    variables: Vec<LocalVariable<'a>>,
+}
+
+impl<'a> Visit<'a> for LocalVariables<'a> {
+    fn visit_typed_arg(&mut self, arg: &'a TypedArg) {
+        if let Some(name) = arg.get_variable_name() {
+            self.variables.push(LocalVariable {
+                name,
+                start: arg.location.start,
+                type_: Some(arg.type_.clone()),
+            });
+        }
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'a TypedExpr) {
+        match expr {
+            TypedExpr::Assignment {
+                pattern: Pattern::Var { name, location },
+                value,
+                ..
+            } => self.variables.push(LocalVariable {
+                name,
+                start: location.start,
+                type_: Some(value.type_()),
+            }),
+            TypedExpr::Var {
+                name, constructor, ..
+            } => {
+                if let ValueConstructorVariant::LocalVariable { location } = constructor.variant {
+                    self.variables.push(LocalVariable {
+                        name,
+                        start: location.start,
+                        type_: Some(constructor.type_.clone()),
+                    });
+                }
+            }
+            _ => (),
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'a TypedPattern) {
+        if let Pattern::Var { name, location } = pattern {
+            self.variables.push(LocalVariable {
+                name,
+                start: location.start,
+                type_: None,
+            });
+        }
+        visit::visit_typed_pattern(self, pattern);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    ast::SrcSpan,
+    parse::extra::{Comment, ModuleExtra},
+};
+
+/// The documentation of the definition at the location in the source of a
+/// module: the doc comments written directly before it, or before the
+/// attributes of the definition, joined as markdown.
+///
+/// The source is used rather than the syntax tree so that the documentation
+/// of definitions in dependency packages, of which only the interfaces are
+/// compiled, can be found in the same way as for those of the project.
+///
+pub fn documentation(src: &str, extra: &ModuleExtra, location: SrcSpan) -> Option<String> {
+    let mut lines = vec![];
+    let mut next_start = location.start;
+    for span in extra
+        .doc_comments
+        .iter()
+        .rev()
+        .skip_while(|span| span.end > location.start)
+    {
+        let between = src.get(span.end as usize..next_start as usize)?;
+        if !only_attributes(between) {
+            break;
+        }
+        let comment = Comment::from((span, src)).content;
+        lines.push(comment.strip_prefix(' ').unwrap_or(comment));
+        next_start = span.start;
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    lines.reverse();
+    Some(lines.join("\n"))
+}
+
+// Whether the code between a doc comment and what follows it could be part of
+// the same definition, being only whitespace, the slashes of the following
+// doc comment, and attributes such as `@deprecated`.
+fn only_attributes(code: &str) -> bool {
+    code.lines()
+        .map(str::trim)
+        .all(|line| line.is_empty() || line.starts_with('@') || line.chars().all(|c| c == '/'))
+}
@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        AssignmentKind, CallArg, Pattern, SrcSpan, Statement, TypedExpr, TypedModule,
+        TypedStatement, PIPE_VARIABLE,
+    },
+    type_::{
+        self, pretty::Printer, FieldMap, ModuleValueConstructor, Type, ValueConstructorVariant,
+    },
+};
+
+/// Information shown within the code of a module that is not written in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlayHint {
+    /// The byte offset the hint is shown at.
+    pub position: u32,
+    pub label: String,
+    pub kind: InlayHintKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlayHintKind {
+    /// The inferred type of a `let` binding, shown after its name.
+    BindingType,
+    /// The inferred return type of a function, shown after its arguments.
+    ReturnType,
+    /// The label of an argument given without one, shown before it.
+    ArgumentLabel,
+}
+
+/// The hints for the part of a module within the range, in the order they
+/// are shown.
+///
+/// - The types of the variables of `let` and `try` bindings written without a
+///   type annotation.
+/// - The return types of the functions of the module written without a
+///   return annotation.
+/// - The labels of the arguments of a call that are given without one, unless
+///   the argument is a variable of the same name as the label.
+///
+pub fn inlay_hints(
+    module: &TypedModule,
+    src: &str,
+    importable_modules: &im::HashMap<String, type_::Module>,
+    range: SrcSpan,
+) -> Vec<InlayHint> {
+    let mut hints = Hints {
+        src,
+        importable_modules,
+        hints: vec![],
+    };
+    hints.visit_typed_module(module);
+    hints
+        .hints
+        .into_iter()
+        .filter(|hint| range.start <= hint.position && hint.position <= range.end)
+        .sorted_by_key(|hint| hint.position)
+        .collect()
+}
+
+struct Hints<'a> {
+    src: &'a str,
+    importable_modules: &'a im::HashMap<String, type_::Module>,
+    hints: Vec<InlayHint>,
+}
+
+impl Hints<'_> {
+    fn push(&mut self, position: u32, label: String, kind: InlayHintKind) {
+        self.hints.push(InlayHint {
+            position,
+            label,
+            kind,
+        });
+    }
+
+    fn binding(&mut self, pattern: &Pattern<type_::PatternConstructor, Arc<Type>>, type_: &Type) {
+        if let Pattern::Var { location, name } = pattern {
+            if name != PIPE_VARIABLE {
+                let label = format!(": {}", Printer::new().pretty_print(type_, 0));
+                self.push(location.end, label, InlayHintKind::BindingType);
+            }
+        }
+    }
+
+    // The labels of the arguments of the function called, by position
+    fn labels(&self, fun: &TypedExpr) -> Option<FieldMap> {
+        match fun {
+            TypedExpr::Var { constructor, .. } => match &constructor.variant {
+                ValueConstructorVariant::ModuleFn { field_map, .. }
+                | ValueConstructorVariant::Record { field_map, .. } => field_map.clone(),
+                ValueConstructorVariant::LocalVariable { .. }
+                | ValueConstructorVariant::ModuleConstant { .. } => None,
+            },
+            TypedExpr::ModuleSelect {
+                constructor: ModuleValueConstructor::Record { field_map, .. },
+                ..
+            } => field_map.clone(),
+            TypedExpr::ModuleSelect {
+                module_name, label, ..
+            } => {
+                let value = self
+                    .importable_modules
+                    .get(module_name.as_str())?
+                    .values
+                    .get(label)?;
+                match &value.variant {
+                    ValueConstructorVariant::ModuleFn { field_map, .. } => field_map.clone(),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn argument_labels(&mut self, fun: &TypedExpr, args: &[CallArg<TypedExpr>]) {
+        let field_map = match self.labels(fun) {
+            Some(field_map) => field_map,
+            None => return,
+        };
+        for (index, arg) in args.iter().enumerate() {
+            let label = field_map
+                .fields
+                .iter()
+                .find(|(_, position)| **position as usize == index)
+                .map(|(label, _)| label);
+            let label = match label {
+                Some(label) if arg.label.is_none() && !arg.implicit => label,
+                _ => continue,
+            };
+            let written = self
+                .src
+                .get(arg.location.start as usize..arg.location.end as usize)
+                .unwrap_or_default();
+            let piped = matches!(&arg.value, TypedExpr::Var { name, .. } if name == PIPE_VARIABLE);
+            if !piped && written != label {
+                let label = format!("{}:", label);
+                self.push(arg.location.start, label, InlayHintKind::ArgumentLabel);
+            }
+        }
+    }
+}
+
+impl<'a> Visit<'a> for Hints<'_> {
+    fn visit_typed_statement(&mut self, statement: &'a TypedStatement) {
+        if let Statement::Fn {
+            location,
+            return_annotation: None,
+            return_type,
+            ..
+        } = statement
+        {
+            let label = format!("-> {}", Printer::new().pretty_print(return_type, 0));
+            self.push(location.end, label, InlayHintKind::ReturnType);
+        }
+        visit::visit_typed_statement(self, statement);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'a TypedExpr) {
+        match expr {
+            TypedExpr::Assignment {
+                pattern,
+                value,
+                kind: AssignmentKind::Let,
+                annotation: None,
+                ..
+            } => self.binding(pattern, &value.type_()),
+
+            TypedExpr::Try {
+                pattern,
+                value,
+                annotation: None,
+                ..
+            } => {
+                // The variable is bound to the value of a successful result
+                let type_ = value.type_();
+                if let Type::App { args, .. } = &*type_::collapse_links(type_) {
+                    if let Some(ok) = args.first() {
+                        self.binding(pattern, ok);
+                    }
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => self.argument_labels(fun, args),
+
+            _ => (),
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+}
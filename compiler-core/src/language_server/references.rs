@@ -0,0 +1,838 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use itertools::Itertools;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        ArgNames, AssignName, ClauseGuard, Constant, Layer, Name, Pattern, SrcSpan, Statement,
+        TypeAst, TypedArg, TypedClause, TypedClauseGuard, TypedConstant, TypedExpr, TypedModule,
+        TypedPattern, TypedStatement,
+    },
+    type_::{collapse_links, ModuleValueConstructor, Type, ValueConstructorVariant},
+};
+
+/// Something with a name that can be referred to, identified by where it is
+/// defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Symbol {
+    /// A function, constant or record constructor.
+    Value {
+        module: String,
+        name: String,
+    },
+    Type {
+        module: String,
+        name: String,
+    },
+    /// The label of an argument of a function, or of a field of the record
+    /// constructors of a custom type.
+    Label {
+        module: String,
+        owner: String,
+        label: String,
+    },
+    /// A variable, by the location it is defined at.
+    Local {
+        module: String,
+        definition: SrcSpan,
+        name: String,
+    },
+}
+
+impl Symbol {
+    pub(super) fn name(&self) -> &str {
+        match self {
+            Symbol::Value { name, .. } | Symbol::Type { name, .. } | Symbol::Local { name, .. } => {
+                name
+            }
+            Symbol::Label { label, .. } => label,
+        }
+    }
+
+    pub(super) fn module(&self) -> &str {
+        match self {
+            Symbol::Value { module, .. }
+            | Symbol::Type { module, .. }
+            | Symbol::Label { module, .. }
+            | Symbol::Local { module, .. } => module,
+        }
+    }
+
+    pub(super) fn is_uppercase(&self) -> bool {
+        match self {
+            Symbol::Type { .. } => true,
+            Symbol::Value { name, .. } => is_uppercase(name),
+            Symbol::Label { .. } | Symbol::Local { .. } => false,
+        }
+    }
+
+    /// Whether the symbols share a namespace, so they cannot have the same
+    /// name where both are in scope.
+    pub(super) fn clashes_with(&self, other: &Symbol) -> bool {
+        match (self, other) {
+            (Symbol::Type { .. }, Symbol::Type { .. }) => true,
+            (Symbol::Value { .. } | Symbol::Local { .. }, Symbol::Value { .. })
+            | (Symbol::Value { .. } | Symbol::Local { .. }, Symbol::Local { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A place the name of a symbol is written.
+#[derive(Debug, Clone)]
+pub(super) struct Occurrence {
+    pub(super) symbol: Symbol,
+    pub(super) location: SrcSpan,
+    pub(super) kind: ReferenceKind,
+    /// The top level function the name is written in, if any.
+    pub(super) function: Option<String>,
+    /// Whether the name is written without the module or record it belongs
+    /// to, so it is in scope alongside the other names of the module.
+    pub(super) unqualified: bool,
+}
+
+/// A place a symbol is referred to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub module: String,
+    pub location: SrcSpan,
+    pub kind: ReferenceKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// The name of a definition of a module, or of a label in the definition
+    /// of a function or custom type.
+    Definition,
+    /// A variable bound by a pattern or an argument of a function.
+    Binding,
+    /// A use of the symbol, which reads its value.
+    Read,
+}
+
+/// Where the symbols of the modules of a project are written, built once the
+/// project has been analysed so the references to a symbol can be looked up.
+#[derive(Debug, Default)]
+pub struct ReferenceIndex {
+    /// The symbols written in each module, by module name.
+    modules: Vec<(String, Vec<Occurrence>)>,
+}
+
+impl ReferenceIndex {
+    /// The index of the given modules, which are the modules of the project
+    /// and their source code.
+    pub fn new(modules: &[(&TypedModule, &str)]) -> Self {
+        let modules = modules
+            .iter()
+            .map(|(ast, src)| (ast.name.join("/"), Occurrences::find(ast, src)))
+            .sorted_by(|a, b| a.0.cmp(&b.0))
+            .collect();
+        Self { modules }
+    }
+
+    /// Every place the symbol at the cursor, a byte offset into the source of
+    /// the named module, is referred to, including where it is defined.
+    ///
+    /// Functions, constants, types, record constructors, labels and variables
+    /// are referred to by name, unless they are imported under another name
+    /// in which case the uses of that name are not included.
+    ///
+    pub fn references(&self, module: &str, cursor: u32) -> Vec<Reference> {
+        let symbol = match self.symbol_at(module, cursor) {
+            Some(symbol) => symbol,
+            None => return vec![],
+        };
+        self.modules
+            .iter()
+            .flat_map(|(module, found)| {
+                found
+                    .iter()
+                    .filter(|found| found.symbol == *symbol)
+                    .sorted_by_key(|found| found.location.start)
+                    .dedup_by(|a, b| a.location == b.location)
+                    .map(|found| Reference {
+                        module: module.clone(),
+                        location: found.location,
+                        kind: found.kind,
+                    })
+            })
+            .collect()
+    }
+
+    pub(super) fn symbol_at(&self, module: &str, cursor: u32) -> Option<&Symbol> {
+        self.modules
+            .iter()
+            .filter(|(name, _)| name == module)
+            .flat_map(|(_, found)| found)
+            .find(|found| found.location.start <= cursor && cursor <= found.location.end)
+            .map(|found| &found.symbol)
+    }
+
+    /// The symbols written in each module, by module name.
+    pub(super) fn modules(&self) -> &[(String, Vec<Occurrence>)] {
+        &self.modules
+    }
+}
+
+fn is_uppercase(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+/// The symbols written in the source of a module.
+struct Occurrences<'a> {
+    module: String,
+    src: &'a str,
+    /// The imported modules, by the name they are referred to with.
+    modules: HashMap<&'a str, String>,
+    /// The values and types imported unqualified, by the name they are
+    /// referred to with.
+    values: HashMap<&'a str, (String, &'a str)>,
+    types: HashMap<&'a str, (String, &'a str)>,
+    /// The values and types defined in the module.
+    own_values: HashSet<&'a str>,
+    own_types: HashSet<&'a str>,
+    function: Option<&'a str>,
+    /// The variables defined by the first pattern of the clause being
+    /// visited, which its alternative patterns define again.
+    clause_variables: Option<HashMap<String, Symbol>>,
+    found: Vec<Occurrence>,
+}
+
+impl<'a> Occurrences<'a> {
+    fn find(module: &'a TypedModule, src: &'a str) -> Vec<Occurrence> {
+        let mut occurrences = Self {
+            module: module.name.join("/"),
+            src,
+            modules: HashMap::new(),
+            values: HashMap::new(),
+            types: HashMap::new(),
+            own_values: HashSet::new(),
+            own_types: HashSet::new(),
+            function: None,
+            clause_variables: None,
+            found: vec![],
+        };
+        for statement in &module.statements {
+            match statement {
+                Statement::Import {
+                    module,
+                    as_name,
+                    unqualified,
+                    ..
+                } => {
+                    let module_name = module.join("/");
+                    let alias = as_name
+                        .as_deref()
+                        .or_else(|| module.last().map(Name::as_str))
+                        .unwrap_or_default();
+                    let _ = occurrences.modules.insert(alias, module_name.clone());
+                    // Importing a name without `type` imports both the value
+                    // and the type of that name
+                    for import in unqualified {
+                        let imported = (module_name.clone(), import.name.as_str());
+                        if import.layer == Layer::Value {
+                            let _ = occurrences
+                                .values
+                                .insert(import.variable_name(), imported.clone());
+                        }
+                        if import.layer == Layer::Type || is_uppercase(&import.name) {
+                            let _ = occurrences.types.insert(import.variable_name(), imported);
+                        }
+                    }
+                }
+                Statement::Fn { name, .. }
+                | Statement::ExternalFn { name, .. }
+                | Statement::ModuleConstant { name, .. } => {
+                    let _ = occurrences.own_values.insert(name);
+                }
+                Statement::CustomType {
+                    name, constructors, ..
+                } => {
+                    let _ = occurrences.own_types.insert(name);
+                    for constructor in constructors {
+                        let _ = occurrences.own_values.insert(&constructor.name);
+                    }
+                }
+                Statement::TypeAlias { alias: name, .. } | Statement::ExternalType { name, .. } => {
+                    let _ = occurrences.own_types.insert(name);
+                }
+            }
+        }
+        occurrences.visit_typed_module(module);
+        occurrences.found
+    }
+
+    /// Record the symbol as written at the location, unless something else is
+    /// written there. This is the case for nodes added by the compiler, such
+    /// as the variables of a pipeline, and for symbols imported under another
+    /// name.
+    fn push(&mut self, symbol: Symbol, location: SrcSpan, kind: ReferenceKind, unqualified: bool) {
+        let written = self.src.get(location.start as usize..location.end as usize);
+        if written == Some(symbol.name()) {
+            self.found.push(Occurrence {
+                symbol,
+                location,
+                kind,
+                function: self.function.map(String::from),
+                unqualified,
+            });
+        }
+    }
+
+    fn push_at(&mut self, symbol: Option<Symbol>, start: u32, unqualified: bool) {
+        if let Some(symbol) = symbol {
+            let end = start + symbol.name().len() as u32;
+            self.push(
+                symbol,
+                SrcSpan { start, end },
+                ReferenceKind::Read,
+                unqualified,
+            );
+        }
+    }
+
+    /// Record the definition of the symbol where its name is first written
+    /// within a span of the source.
+    fn define(&mut self, symbol: Symbol, within: SrcSpan) {
+        if let Some(location) = self.find_name(within, symbol.name()) {
+            self.push(symbol, location, ReferenceKind::Definition, true);
+        }
+    }
+
+    fn find_name(&self, within: SrcSpan, name: &str) -> Option<SrcSpan> {
+        find_name(self.src, within, name)
+    }
+
+    /// The module value a name refers to when written unqualified.
+    fn value(&self, name: &str) -> Option<Symbol> {
+        if self.own_values.contains(name) {
+            return Some(Symbol::Value {
+                module: self.module.clone(),
+                name: name.to_string(),
+            });
+        }
+        let (module, name) = self.values.get(name)?;
+        Some(Symbol::Value {
+            module: module.clone(),
+            name: name.to_string(),
+        })
+    }
+
+    fn type_(&self, name: &str) -> Option<Symbol> {
+        if self.own_types.contains(name) {
+            return Some(Symbol::Type {
+                module: self.module.clone(),
+                name: name.to_string(),
+            });
+        }
+        let (module, name) = self.types.get(name)?;
+        Some(Symbol::Type {
+            module: module.clone(),
+            name: name.to_string(),
+        })
+    }
+
+    /// The module value a name refers to, either unqualified or qualified
+    /// with the name of an imported module.
+    fn qualified_value(&self, module: Option<&str>, name: &str) -> Option<Symbol> {
+        match module {
+            Some(alias) => Some(Symbol::Value {
+                module: self.modules.get(alias)?.clone(),
+                name: name.to_string(),
+            }),
+            None => self.value(name),
+        }
+    }
+
+    /// The variable of the current function a name refers to, which is the
+    /// last defined before where it is used.
+    fn local(&self, name: &str) -> Option<Symbol> {
+        self.found
+            .iter()
+            .rev()
+            .filter(|found| found.function.as_deref() == self.function)
+            .map(|found| &found.symbol)
+            .find(|symbol| matches!(symbol, Symbol::Local { name: n, .. } if n == name))
+            .cloned()
+    }
+
+    fn define_local(&mut self, name: &str, location: SrcSpan) {
+        let symbol = self
+            .clause_variables
+            .as_ref()
+            .and_then(|variables| variables.get(name).cloned())
+            .unwrap_or_else(|| Symbol::Local {
+                module: self.module.clone(),
+                definition: location,
+                name: name.to_string(),
+            });
+        self.push(symbol, location, ReferenceKind::Binding, true);
+    }
+
+    fn label(&mut self, owner: Option<(String, String)>, label: &str, start: u32) {
+        let symbol = owner.map(|(module, owner)| Symbol::Label {
+            module,
+            owner,
+            label: label.to_string(),
+        });
+        self.push_at(symbol, start, false);
+    }
+
+    /// The function that labelled arguments of a call are the labels of,
+    /// or the type for a record constructor.
+    fn call_owner(&self, fun: &TypedExpr) -> Option<(String, String)> {
+        match fun {
+            TypedExpr::Var {
+                constructor, name, ..
+            } => match constructor.variant {
+                ValueConstructorVariant::ModuleFn { .. } => match self.value(name)? {
+                    Symbol::Value { module, name } => Some((module, name)),
+                    _ => None,
+                },
+                ValueConstructorVariant::Record { .. } => record_owner(fun.type_()),
+                ValueConstructorVariant::LocalVariable { .. }
+                | ValueConstructorVariant::ModuleConstant { .. } => None,
+            },
+            TypedExpr::ModuleSelect {
+                module_name,
+                label,
+                constructor,
+                typ,
+                ..
+            } => match constructor {
+                ModuleValueConstructor::Fn { .. } => {
+                    Some((module_name.to_string(), label.to_string()))
+                }
+                ModuleValueConstructor::Record { .. } => record_owner(typ.clone()),
+                ModuleValueConstructor::Constant { .. } => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn type_ast(&mut self, ast: &TypeAst) {
+        match ast {
+            TypeAst::Constructor {
+                location,
+                module,
+                name,
+                arguments,
+            } => {
+                let (symbol, start) = match module {
+                    Some(alias) => {
+                        let symbol = self.modules.get(alias.as_str()).map(|module| Symbol::Type {
+                            module: module.clone(),
+                            name: name.to_string(),
+                        });
+                        (symbol, location.start + alias.len() as u32 + 1)
+                    }
+                    None => (self.type_(name), location.start),
+                };
+                self.push_at(symbol, start, module.is_none());
+                for argument in arguments {
+                    self.type_ast(argument);
+                }
+            }
+            TypeAst::Fn {
+                arguments, return_, ..
+            } => {
+                for argument in arguments {
+                    self.type_ast(argument);
+                }
+                self.type_ast(return_);
+            }
+            TypeAst::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.type_ast(elem);
+                }
+            }
+            TypeAst::Var { .. } | TypeAst::Hole { .. } => (),
+        }
+    }
+}
+
+/// Where a name is first written as a whole word within a span of the source,
+/// such as the name of a definition within its location.
+pub(super) fn find_name(src: &str, within: SrcSpan, name: &str) -> Option<SrcSpan> {
+    let src = src.get(within.start as usize..within.end as usize)?;
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    src.match_indices(name)
+        .find(|(index, _)| {
+            let before = src.get(..*index).and_then(|s| s.chars().last());
+            let after = src.get(index + name.len()..).and_then(|s| s.chars().next());
+            !before.map_or(false, is_name_char) && !after.map_or(false, is_name_char)
+        })
+        .map(|(index, _)| {
+            let start = within.start + index as u32;
+            SrcSpan {
+                start,
+                end: start + name.len() as u32,
+            }
+        })
+}
+
+/// The custom type of a record, or of the records a constructor creates, by
+/// its module and name.
+fn record_owner(type_: Arc<Type>) -> Option<(String, String)> {
+    match collapse_links(type_).as_ref() {
+        Type::App { module, name, .. } => Some((module.join("/"), name.to_string())),
+        Type::Fn { retrn, .. } => record_owner(retrn.clone()),
+        Type::Var { .. } | Type::Tuple { .. } => None,
+    }
+}
+
+impl<'a> Visit<'a> for Occurrences<'a> {
+    fn visit_typed_statement(&mut self, statement: &'a TypedStatement) {
+        let module = self.module.clone();
+        let value = |name: &str| Symbol::Value {
+            module: module.clone(),
+            name: name.to_string(),
+        };
+        let type_ = |name: &str| Symbol::Type {
+            module: module.clone(),
+            name: name.to_string(),
+        };
+        let label = |owner: &str, label: &str| Symbol::Label {
+            module: module.clone(),
+            owner: owner.to_string(),
+            label: label.to_string(),
+        };
+
+        match statement {
+            Statement::Fn {
+                location,
+                name,
+                arguments,
+                return_annotation,
+                ..
+            } => {
+                self.define(value(name), *location);
+                for argument in arguments {
+                    if let ArgNames::NamedLabelled { label: l, .. }
+                    | ArgNames::LabelledDiscard { label: l, .. } = &argument.names
+                    {
+                        self.define(label(name, l), argument.location);
+                    }
+                }
+                if let Some(annotation) = return_annotation {
+                    self.type_ast(annotation);
+                }
+                self.function = Some(name);
+            }
+
+            Statement::ExternalFn {
+                location,
+                name,
+                arguments,
+                return_,
+                ..
+            } => {
+                self.define(value(name), *location);
+                for argument in arguments {
+                    if let Some(l) = &argument.label {
+                        self.define(label(name, l), argument.location);
+                    }
+                    self.type_ast(&argument.annotation);
+                }
+                self.type_ast(return_);
+            }
+
+            Statement::ModuleConstant {
+                location,
+                name,
+                annotation,
+                ..
+            } => {
+                self.define(value(name), *location);
+                if let Some(annotation) = annotation {
+                    self.type_ast(annotation);
+                }
+            }
+
+            Statement::CustomType {
+                location,
+                name,
+                constructors,
+                ..
+            } => {
+                self.define(type_(name), *location);
+                for constructor in constructors {
+                    self.define(value(&constructor.name), constructor.location);
+                    for argument in &constructor.arguments {
+                        if let Some(l) = &argument.label {
+                            self.define(label(name, l), argument.location);
+                        }
+                        self.type_ast(&argument.ast);
+                    }
+                }
+            }
+
+            Statement::TypeAlias {
+                location,
+                alias,
+                type_ast,
+                ..
+            } => {
+                self.define(type_(alias), *location);
+                self.type_ast(type_ast);
+            }
+
+            Statement::ExternalType { location, name, .. } => {
+                self.define(type_(name), *location);
+            }
+
+            Statement::Import {
+                module,
+                unqualified,
+                ..
+            } => {
+                let module = module.join("/");
+                for import in unqualified {
+                    let name = import.name.clone();
+                    let type_ = Symbol::Type {
+                        module: module.clone(),
+                        name: name.to_string(),
+                    };
+                    if import.layer == Layer::Type {
+                        if let Some(location) = self.find_name(import.location, &name) {
+                            self.push(type_, location, ReferenceKind::Read, true);
+                        }
+                        continue;
+                    }
+                    if is_uppercase(&name) {
+                        self.push_at(Some(type_), import.location.start, true);
+                    }
+                    let value = Symbol::Value {
+                        module: module.clone(),
+                        name: name.to_string(),
+                    };
+                    self.push_at(Some(value), import.location.start, true);
+                }
+            }
+        }
+
+        visit::visit_typed_statement(self, statement);
+        self.function = None;
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'a TypedArg) {
+        // The variable is written after the label, if there is one
+        let start = match &arg.names {
+            ArgNames::NamedLabelled { label, .. } => {
+                self.find_name(arg.location, label).map(|label| label.end)
+            }
+            ArgNames::Named { .. } => Some(arg.location.start),
+            ArgNames::Discard { .. } | ArgNames::LabelledDiscard { .. } => None,
+        };
+        if let (Some(name), Some(start)) = (arg.names.get_variable_name(), start) {
+            let within = SrcSpan {
+                start,
+                end: arg.location.end,
+            };
+            if let Some(location) = self.find_name(within, name) {
+                let symbol = Symbol::Local {
+                    module: self.module.clone(),
+                    definition: arg.location,
+                    name: name.to_string(),
+                };
+                self.push(symbol, location, ReferenceKind::Binding, true);
+            }
+        }
+        if let Some(annotation) = &arg.annotation {
+            self.type_ast(annotation);
+        }
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'a TypedExpr) {
+        match expr {
+            TypedExpr::Var {
+                location,
+                constructor,
+                name,
+            } => {
+                let symbol = match &constructor.variant {
+                    ValueConstructorVariant::LocalVariable { location } => Some(Symbol::Local {
+                        module: self.module.clone(),
+                        definition: *location,
+                        name: name.to_string(),
+                    }),
+                    ValueConstructorVariant::ModuleConstant { .. }
+                    | ValueConstructorVariant::ModuleFn { .. }
+                    | ValueConstructorVariant::Record { .. } => self.value(name),
+                };
+                if let Some(symbol) = symbol {
+                    self.push(symbol, *location, ReferenceKind::Read, true);
+                }
+            }
+
+            TypedExpr::ModuleSelect {
+                location,
+                label,
+                module_name,
+                ..
+            } => {
+                let symbol = Symbol::Value {
+                    module: module_name.to_string(),
+                    name: label.to_string(),
+                };
+                let start = location.end.saturating_sub(label.len() as u32);
+                self.push_at(Some(symbol), start, false);
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                let owner = self.call_owner(fun);
+                for arg in args {
+                    if let Some(label) = &arg.label {
+                        self.label(owner.clone(), label, arg.location.start);
+                    }
+                }
+            }
+
+            TypedExpr::RecordAccess {
+                location,
+                label,
+                record,
+                ..
+            } => {
+                let start = location.end.saturating_sub(label.len() as u32);
+                self.label(record_owner(record.type_()), label, start);
+            }
+
+            TypedExpr::RecordUpdate { typ, args, .. } => {
+                let owner = record_owner(typ.clone());
+                for arg in args {
+                    self.label(owner.clone(), &arg.label, arg.location.start);
+                }
+            }
+
+            TypedExpr::Fn {
+                return_annotation: Some(annotation),
+                ..
+            }
+            | TypedExpr::Assignment {
+                annotation: Some(annotation),
+                ..
+            }
+            | TypedExpr::Try {
+                annotation: Some(annotation),
+                ..
+            } => self.type_ast(annotation),
+
+            _ => (),
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'a TypedClause) {
+        let first = self.found.len();
+        for pattern in &clause.pattern {
+            self.visit_typed_pattern(pattern);
+        }
+        let variables = self
+            .found
+            .iter()
+            .skip(first)
+            .filter(|found| matches!(found.symbol, Symbol::Local { .. }))
+            .map(|found| (found.symbol.name().to_string(), found.symbol.clone()))
+            .collect();
+        self.clause_variables = Some(variables);
+        for pattern in clause.alternative_patterns.iter().flatten() {
+            self.visit_typed_pattern(pattern);
+        }
+        self.clause_variables = None;
+        if let Some(guard) = &clause.guard {
+            self.visit_typed_clause_guard(guard);
+        }
+        self.visit_typed_expr(&clause.then);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'a TypedPattern) {
+        match pattern {
+            Pattern::Var { location, name } | Pattern::Assign { location, name, .. } => {
+                self.define_local(name, *location);
+            }
+
+            Pattern::Concatenate {
+                right_location,
+                right_side_assignment: AssignName::Variable(name),
+                ..
+            } => self.define_local(name, *right_location),
+
+            Pattern::VarUsage { location, name, .. } => {
+                let symbol = self.local(name);
+                self.push_at(symbol, location.start, true);
+            }
+
+            Pattern::Constructor {
+                location,
+                name,
+                arguments,
+                module,
+                type_,
+                ..
+            } => {
+                let symbol = self.qualified_value(module.as_deref(), name);
+                let start = location.start + module.as_ref().map_or(0, |m| m.len() as u32 + 1);
+                self.push_at(symbol, start, module.is_none());
+                let owner = record_owner(type_.clone());
+                for argument in arguments {
+                    if let Some(label) = &argument.label {
+                        self.label(owner.clone(), label, argument.location.start);
+                    }
+                }
+            }
+
+            _ => (),
+        }
+        visit::visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_clause_guard(&mut self, guard: &'a TypedClauseGuard) {
+        if let ClauseGuard::Var { location, name, .. } = guard {
+            let symbol = self.local(name);
+            self.push_at(symbol, location.start, true);
+        }
+        visit::visit_typed_clause_guard(self, guard);
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'a TypedConstant) {
+        match constant {
+            Constant::Record {
+                location,
+                module,
+                name,
+                args,
+                typ,
+                ..
+            } => {
+                let symbol = self.qualified_value(module.as_deref(), name);
+                let start = location.start + module.as_ref().map_or(0, |m| m.len() as u32 + 1);
+                self.push_at(symbol, start, module.is_none());
+                let owner = record_owner(typ.clone());
+                for arg in args {
+                    if let Some(label) = &arg.label {
+                        self.label(owner.clone(), label, arg.location.start);
+                    }
+                }
+            }
+
+            Constant::Var {
+                location,
+                module,
+                name,
+                ..
+            } => {
+                let symbol = self.qualified_value(module.as_deref(), name);
+                let start = location.start + module.as_ref().map_or(0, |m| m.len() as u32 + 1);
+                self.push_at(symbol, start, module.is_none());
+            }
+
+            _ => (),
+        }
+        visit::visit_typed_constant(self, constant);
+    }
+}
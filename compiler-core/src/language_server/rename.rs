@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use crate::{ast::SrcSpan, error::RenameProblem, parse::lexer::str_to_keyword, Error, Result};
+
+use super::{
+    references::{Occurrence, Symbol},
+    ReferenceIndex, TextEdit,
+};
+
+/// The edits to make to the modules of a project to rename the symbol at the
+/// cursor, a byte offset into the source of the named module. The edits are
+/// given with the name of the module they are for.
+///
+/// Functions, constants, types, record constructors, labels and variables
+/// can be renamed, along with everywhere they are referred to in the modules
+/// of the index. Symbols imported under another name keep that name where
+/// they are used.
+///
+pub fn rename(
+    index: &ReferenceIndex,
+    module: &str,
+    cursor: u32,
+    new_name: &str,
+) -> Result<Vec<(String, Vec<TextEdit>)>> {
+    let occurrences = index.modules();
+    let symbol = index
+        .symbol_at(module, cursor)
+        .ok_or_else(|| Error::CannotRename {
+            name: String::new(),
+            new_name: new_name.to_string(),
+            problem: RenameProblem::NothingToRename,
+        })?;
+    let error = |problem| Error::CannotRename {
+        name: symbol.name().to_string(),
+        new_name: new_name.to_string(),
+        problem,
+    };
+
+    if !occurrences.iter().any(|(name, _)| name == symbol.module()) {
+        return Err(error(RenameProblem::DefinedInDependency {
+            module: symbol.module().to_string(),
+        }));
+    }
+    if !is_valid_name(new_name, symbol.is_uppercase()) {
+        return Err(error(RenameProblem::InvalidName {
+            uppercase: symbol.is_uppercase(),
+        }));
+    }
+    if occurrences
+        .iter()
+        .any(|(_, found)| has_conflict(symbol, new_name, found))
+    {
+        return Err(error(RenameProblem::Conflict));
+    }
+
+    let edits = occurrences
+        .iter()
+        .filter_map(|(name, found)| {
+            let edits = found
+                .iter()
+                .filter(|found| found.symbol == *symbol)
+                .map(|found| found.location)
+                .sorted_by_key(|location| location.start)
+                .dedup()
+                .map(|location| {
+                    if is_shared(symbol, location, found) {
+                        // Import the renamed symbol alongside what is still
+                        // imported with the old name
+                        TextEdit {
+                            location: SrcSpan {
+                                start: location.start,
+                                end: location.start,
+                            },
+                            new_text: format!("{}, ", new_name),
+                        }
+                    } else {
+                        TextEdit {
+                            location,
+                            new_text: new_name.to_string(),
+                        }
+                    }
+                })
+                .collect_vec();
+            (!edits.is_empty()).then(|| (name.clone(), edits))
+        })
+        .collect();
+    Ok(edits)
+}
+
+fn is_valid_name(name: &str, uppercase: bool) -> bool {
+    let mut chars = name.chars();
+    let valid = match chars.next() {
+        Some(first) if uppercase => {
+            first.is_ascii_uppercase() && chars.all(|c| c.is_ascii_alphanumeric())
+        }
+        Some(first) => {
+            first.is_ascii_lowercase()
+                && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        }
+        None => false,
+    };
+    valid && str_to_keyword(name).is_none()
+}
+
+/// Whether the name of the symbol at the location is also the name of
+/// another symbol used elsewhere in the module, as is the case for a type and
+/// record constructor of the same name imported together.
+fn is_shared(symbol: &Symbol, location: SrcSpan, found: &[Occurrence]) -> bool {
+    found
+        .iter()
+        .filter(|other| other.location == location && other.symbol != *symbol)
+        .any(|other| {
+            found
+                .iter()
+                .any(|used| used.symbol == other.symbol && used.location != location)
+        })
+}
+
+/// Whether renaming the symbol would make it clash with another symbol
+/// written in a module.
+fn has_conflict(symbol: &Symbol, new_name: &str, found: &[Occurrence]) -> bool {
+    let mut others = found
+        .iter()
+        .filter(|other| other.symbol != *symbol && other.symbol.name() == new_name);
+    match symbol {
+        // A label clashes with the other labels of its function or type
+        Symbol::Label { module, owner, .. } => others.any(|other| {
+            matches!(&other.symbol, Symbol::Label { module: m, owner: o, .. } if m == module && o == owner)
+        }),
+
+        // A variable clashes with the names used in its function
+        Symbol::Local { .. } => {
+            let function = found
+                .iter()
+                .find(|found| found.symbol == *symbol)
+                .map(|found| &found.function);
+            others.any(|other| {
+                other.unqualified
+                    && other.symbol.clashes_with(symbol)
+                    && Some(&other.function) == function
+            })
+        }
+
+        // A definition of a module clashes with the names used in the modules
+        // it is used unqualified in. Variables only clash if they are in a
+        // function it is used in.
+        Symbol::Value { .. } | Symbol::Type { .. } => {
+            let functions: HashSet<_> = found
+                .iter()
+                .filter(|found| found.symbol == *symbol && found.unqualified)
+                .map(|found| &found.function)
+                .collect();
+            if functions.is_empty() {
+                return false;
+            }
+            others.any(|other| {
+                other.unqualified
+                    && other.symbol.clashes_with(symbol)
+                    && match other.symbol {
+                        Symbol::Local { .. } => functions.contains(&other.function),
+                        _ => true,
+                    }
+            })
+        }
+    }
+}
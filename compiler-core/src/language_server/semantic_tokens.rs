@@ -0,0 +1,463 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        ArgNames, AssignName, ClauseGuard, Constant, Layer, Pattern, SrcSpan, Statement, TypeAst,
+        TypedArg, TypedClauseGuard, TypedConstant, TypedExpr, TypedModule, TypedPattern,
+        TypedStatement,
+    },
+    type_::{self, ModuleValueConstructor, ValueConstructorVariant},
+};
+
+use super::references::find_name;
+
+/// A name written in a module, classified by what it refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub location: SrcSpan,
+    pub kind: SemanticTokenKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticTokenKind {
+    /// The name of an imported module, or the alias it is imported as.
+    Module,
+    Type,
+    /// A record constructor of a custom type.
+    Constructor,
+    Function,
+    Constant,
+    /// An argument of a function, where it is defined and used.
+    Parameter,
+    /// A variable bound by a pattern.
+    Variable,
+    /// The label of an argument or record field.
+    Label,
+}
+
+/// The names written in a module, in the order they are written, classified
+/// by what they refer to once the module has been analysed. The names the
+/// module imports unqualified are classified using the interfaces of the
+/// modules it imports.
+pub fn semantic_tokens(
+    module: &TypedModule,
+    src: &str,
+    importable_modules: &im::HashMap<String, type_::Module>,
+) -> Vec<SemanticToken> {
+    let mut tokens = Tokens {
+        src,
+        importable_modules,
+        parameters: HashSet::new(),
+        tokens: vec![],
+    };
+    tokens.visit_typed_module(module);
+    tokens
+        .tokens
+        .into_iter()
+        .sorted_by_key(|token| token.location.start)
+        .dedup_by(|a, b| a.location.start == b.location.start)
+        .collect()
+}
+
+struct Tokens<'a> {
+    src: &'a str,
+    importable_modules: &'a im::HashMap<String, type_::Module>,
+    /// Where the arguments of the functions seen so far are defined.
+    parameters: HashSet<u32>,
+    tokens: Vec<SemanticToken>,
+}
+
+impl Tokens<'_> {
+    /// Adds the name at the location, if it is written there.
+    fn push(&mut self, location: SrcSpan, name: &str, kind: SemanticTokenKind) {
+        let written = self.src.get(location.start as usize..location.end as usize);
+        if written == Some(name) {
+            self.tokens.push(SemanticToken { location, kind });
+        }
+    }
+
+    fn push_at(&mut self, start: u32, name: &str, kind: SemanticTokenKind) {
+        let location = SrcSpan {
+            start,
+            end: start + name.len() as u32,
+        };
+        self.push(location, name, kind);
+    }
+
+    /// Adds the name where it is first written within the location.
+    fn push_within(&mut self, within: SrcSpan, name: &str, kind: SemanticTokenKind) {
+        if let Some(location) = find_name(self.src, within, name) {
+            self.push(location, name, kind);
+        }
+    }
+
+    /// Adds a name that may be qualified with the name of a module, which is
+    /// written at the start of the location.
+    fn push_qualified(
+        &mut self,
+        location: SrcSpan,
+        module: Option<&str>,
+        name: &str,
+        kind: SemanticTokenKind,
+    ) {
+        let start = match module {
+            Some(module) => {
+                self.push_at(location.start, module, SemanticTokenKind::Module);
+                location.start + module.len() as u32 + 1
+            }
+            None => location.start,
+        };
+        self.push_at(start, name, kind);
+    }
+
+    fn variable(&self, definition: SrcSpan) -> SemanticTokenKind {
+        if self.parameters.contains(&definition.start) {
+            SemanticTokenKind::Parameter
+        } else {
+            SemanticTokenKind::Variable
+        }
+    }
+
+    fn type_ast(&mut self, ast: &TypeAst) {
+        match ast {
+            TypeAst::Constructor {
+                location,
+                module,
+                name,
+                arguments,
+            } => {
+                self.push_qualified(*location, module.as_deref(), name, SemanticTokenKind::Type);
+                for argument in arguments {
+                    self.type_ast(argument);
+                }
+            }
+            TypeAst::Fn {
+                arguments, return_, ..
+            } => {
+                for argument in arguments {
+                    self.type_ast(argument);
+                }
+                self.type_ast(return_);
+            }
+            TypeAst::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.type_ast(elem);
+                }
+            }
+            TypeAst::Var { .. } | TypeAst::Hole { .. } => (),
+        }
+    }
+
+    /// What a name imported unqualified from a module refers to.
+    fn imported(&self, module: &str, name: &str, layer: Layer) -> Option<SemanticTokenKind> {
+        let module = self.importable_modules.get(module)?;
+        if layer == Layer::Type {
+            return Some(SemanticTokenKind::Type);
+        }
+        match module.values.get(name).map(|value| &value.variant) {
+            Some(ValueConstructorVariant::Record { .. }) => Some(SemanticTokenKind::Constructor),
+            Some(ValueConstructorVariant::ModuleFn { .. }) => Some(SemanticTokenKind::Function),
+            Some(ValueConstructorVariant::ModuleConstant { .. }) => {
+                Some(SemanticTokenKind::Constant)
+            }
+            Some(ValueConstructorVariant::LocalVariable { .. }) | None => module
+                .types
+                .contains_key(name)
+                .then_some(SemanticTokenKind::Type),
+        }
+    }
+}
+
+fn value_kind(variant: &ValueConstructorVariant) -> Option<SemanticTokenKind> {
+    match variant {
+        ValueConstructorVariant::ModuleFn { .. } => Some(SemanticTokenKind::Function),
+        ValueConstructorVariant::ModuleConstant { .. } => Some(SemanticTokenKind::Constant),
+        ValueConstructorVariant::Record { .. } => Some(SemanticTokenKind::Constructor),
+        ValueConstructorVariant::LocalVariable { .. } => None,
+    }
+}
+
+impl<'a> Visit<'a> for Tokens<'_> {
+    fn visit_typed_statement(&mut self, statement: &'a TypedStatement) {
+        match statement {
+            Statement::Fn {
+                location,
+                name,
+                return_annotation,
+                ..
+            } => {
+                self.push_within(*location, name, SemanticTokenKind::Function);
+                if let Some(annotation) = return_annotation {
+                    self.type_ast(annotation);
+                }
+            }
+
+            Statement::ExternalFn {
+                location,
+                name,
+                arguments,
+                return_,
+                ..
+            } => {
+                self.push_within(*location, name, SemanticTokenKind::Function);
+                for argument in arguments {
+                    if let Some(label) = &argument.label {
+                        self.push_within(argument.location, label, SemanticTokenKind::Label);
+                    }
+                    self.type_ast(&argument.annotation);
+                }
+                self.type_ast(return_);
+            }
+
+            Statement::ModuleConstant {
+                location,
+                name,
+                annotation,
+                ..
+            } => {
+                self.push_within(*location, name, SemanticTokenKind::Constant);
+                if let Some(annotation) = annotation {
+                    self.type_ast(annotation);
+                }
+            }
+
+            Statement::CustomType {
+                location,
+                name,
+                constructors,
+                ..
+            } => {
+                self.push_within(*location, name, SemanticTokenKind::Type);
+                for constructor in constructors {
+                    let kind = SemanticTokenKind::Constructor;
+                    self.push_within(constructor.location, &constructor.name, kind);
+                    for argument in &constructor.arguments {
+                        if let Some(label) = &argument.label {
+                            self.push_within(argument.location, label, SemanticTokenKind::Label);
+                        }
+                        self.type_ast(&argument.ast);
+                    }
+                }
+            }
+
+            Statement::TypeAlias {
+                location,
+                alias,
+                type_ast,
+                ..
+            } => {
+                self.push_within(*location, alias, SemanticTokenKind::Type);
+                self.type_ast(type_ast);
+            }
+
+            Statement::ExternalType { location, name, .. } => {
+                self.push_within(*location, name, SemanticTokenKind::Type);
+            }
+
+            Statement::Import {
+                location,
+                module,
+                as_name,
+                unqualified,
+                ..
+            } => {
+                let path = module.join("/");
+                self.push_at(location.start, &path, SemanticTokenKind::Module);
+                if let Some(as_name) = as_name {
+                    let start = location.end.saturating_sub(as_name.len() as u32);
+                    self.push_at(start, as_name, SemanticTokenKind::Module);
+                }
+                for import in unqualified {
+                    let kind = match self.imported(&path, &import.name, import.layer) {
+                        Some(kind) => kind,
+                        None => continue,
+                    };
+                    self.push_within(import.location, &import.name, kind);
+                    if let Some(as_name) = &import.as_name {
+                        let start = import.location.end.saturating_sub(as_name.len() as u32);
+                        self.push_at(start, as_name, kind);
+                    }
+                }
+            }
+        }
+        visit::visit_typed_statement(self, statement);
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'a TypedArg) {
+        let _ = self.parameters.insert(arg.location.start);
+        match &arg.names {
+            ArgNames::NamedLabelled { label, name } => {
+                self.push_at(arg.location.start, label, SemanticTokenKind::Label);
+                let after_label = SrcSpan {
+                    start: arg.location.start + label.len() as u32,
+                    end: arg.location.end,
+                };
+                self.push_within(after_label, name, SemanticTokenKind::Parameter);
+            }
+            ArgNames::LabelledDiscard { label, .. } => {
+                self.push_at(arg.location.start, label, SemanticTokenKind::Label);
+            }
+            ArgNames::Named { name } => {
+                self.push_at(arg.location.start, name, SemanticTokenKind::Parameter);
+            }
+            ArgNames::Discard { .. } => (),
+        }
+        if let Some(annotation) = &arg.annotation {
+            self.type_ast(annotation);
+        }
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'a TypedExpr) {
+        match expr {
+            TypedExpr::Var {
+                location,
+                constructor,
+                name,
+            } => {
+                let kind = match &constructor.variant {
+                    ValueConstructorVariant::LocalVariable { location } => self.variable(*location),
+                    variant => value_kind(variant).unwrap_or(SemanticTokenKind::Variable),
+                };
+                self.push(*location, name, kind);
+            }
+
+            TypedExpr::ModuleSelect {
+                location,
+                label,
+                module_alias,
+                constructor,
+                ..
+            } => {
+                let kind = match constructor {
+                    ModuleValueConstructor::Record { .. } => SemanticTokenKind::Constructor,
+                    ModuleValueConstructor::Fn { .. } => SemanticTokenKind::Function,
+                    ModuleValueConstructor::Constant { .. } => SemanticTokenKind::Constant,
+                };
+                self.push_at(location.start, module_alias, SemanticTokenKind::Module);
+                let start = location.end.saturating_sub(label.len() as u32);
+                self.push_at(start, label, kind);
+            }
+
+            TypedExpr::Call { args, .. } => {
+                for arg in args {
+                    if let Some(label) = &arg.label {
+                        self.push_at(arg.location.start, label, SemanticTokenKind::Label);
+                    }
+                }
+            }
+
+            TypedExpr::RecordAccess {
+                location, label, ..
+            } => {
+                let start = location.end.saturating_sub(label.len() as u32);
+                self.push_at(start, label, SemanticTokenKind::Label);
+            }
+
+            TypedExpr::RecordUpdate { args, .. } => {
+                for arg in args {
+                    self.push_at(arg.location.start, &arg.label, SemanticTokenKind::Label);
+                }
+            }
+
+            TypedExpr::Fn {
+                return_annotation: Some(annotation),
+                ..
+            }
+            | TypedExpr::Assignment {
+                annotation: Some(annotation),
+                ..
+            }
+            | TypedExpr::Try {
+                annotation: Some(annotation),
+                ..
+            } => self.type_ast(annotation),
+
+            _ => (),
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'a TypedPattern) {
+        match pattern {
+            Pattern::Var { location, name } | Pattern::Assign { location, name, .. } => {
+                self.push(*location, name, SemanticTokenKind::Variable);
+            }
+
+            Pattern::VarUsage { location, name, .. } => {
+                self.push_at(location.start, name, SemanticTokenKind::Variable);
+            }
+
+            Pattern::Concatenate {
+                right_location,
+                right_side_assignment: AssignName::Variable(name),
+                ..
+            } => self.push(*right_location, name, SemanticTokenKind::Variable),
+
+            Pattern::Constructor {
+                location,
+                name,
+                arguments,
+                module,
+                ..
+            } => {
+                let kind = SemanticTokenKind::Constructor;
+                self.push_qualified(*location, module.as_deref(), name, kind);
+                for argument in arguments {
+                    if let Some(label) = &argument.label {
+                        self.push_at(argument.location.start, label, SemanticTokenKind::Label);
+                    }
+                }
+            }
+
+            _ => (),
+        }
+        visit::visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_clause_guard(&mut self, guard: &'a TypedClauseGuard) {
+        if let ClauseGuard::Var { location, name, .. } = guard {
+            self.push_at(location.start, name, SemanticTokenKind::Variable);
+        }
+        visit::visit_typed_clause_guard(self, guard);
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'a TypedConstant) {
+        match constant {
+            Constant::Record {
+                location,
+                module,
+                name,
+                args,
+                ..
+            } => {
+                let kind = SemanticTokenKind::Constructor;
+                self.push_qualified(*location, module.as_deref(), name, kind);
+                for arg in args {
+                    if let Some(label) = &arg.label {
+                        self.push_at(arg.location.start, label, SemanticTokenKind::Label);
+                    }
+                }
+            }
+
+            Constant::Var {
+                location,
+                module,
+                name,
+                constructor,
+                ..
+            } => {
+                let kind = constructor
+                    .as_ref()
+                    .and_then(|constructor| value_kind(&constructor.variant))
+                    .unwrap_or(SemanticTokenKind::Constant);
+                self.push_qualified(*location, module.as_deref(), name, kind);
+            }
+
+            _ => (),
+        }
+        visit::visit_typed_constant(self, constant);
+    }
+}
@@ -0,0 +1,395 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use crate::{
+    ast::{Arena, ArgNames, Layer, Name, SrcSpan, Statement, TypedModule},
+    parse::{lexer::make_tokenizer, token::Token},
+    type_::{self, pretty::Printer, FieldMap, Type, ValueConstructor, ValueConstructorVariant},
+};
+
+use super::documentation;
+
+/// The signature of the function being called where the cursor is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureHelp {
+    /// The name of the function followed by its parameters and return type,
+    /// such as `map(over: List(a), with: fn(a) -> b) -> List(b)`.
+    pub label: String,
+    /// Where each parameter is written in the label.
+    pub parameters: Vec<SrcSpan>,
+    /// The parameter the argument being written is given for, if any.
+    pub active_parameter: Option<usize>,
+    /// The module the function is defined in and where, to find its
+    /// documentation with.
+    pub module: String,
+    pub location: SrcSpan,
+}
+
+/// The signature of the function or record constructor called by the
+/// innermost call the cursor is within, a byte offset into the source of a
+/// module as it is being written.
+///
+/// The call is found in the source rather than the syntax tree so that help
+/// is given for code that cannot yet be compiled, with the name called
+/// resolved using the module as it was last compiled. The active parameter
+/// is the one named by the label of the argument being written, or else the
+/// first that has not been given by an argument before it.
+///
+pub fn signature_help(
+    module: &TypedModule,
+    src: &str,
+    cursor: u32,
+    importable_modules: &im::HashMap<String, type_::Module>,
+) -> Option<SignatureHelp> {
+    let tokens: Vec<Token> = make_tokenizer(src.get(..cursor as usize)?)
+        .map(|token| token.map(|(_, token, _)| token))
+        .try_collect()
+        .ok()?;
+
+    // The brackets left open before the cursor, innermost last
+    let mut open = vec![];
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::LeftParen | Token::LeftSquare | Token::LeftBrace => open.push(index),
+            Token::RightParen | Token::RightSquare | Token::RightBrace => {
+                let _ = open.pop();
+            }
+            _ => (),
+        }
+    }
+
+    let (paren, callee) = open.iter().rev().find_map(|paren| {
+        let before = tokens.get(..*paren)?;
+        Some((*paren, callee(before)?))
+    })?;
+    let signature = resolve(module, importable_modules, &callee)?;
+
+    // The labels of the arguments given so far, the last being the one the
+    // cursor is in
+    let mut arguments = vec![None];
+    let mut depth = 0;
+    let mut written = tokens.get(paren + 1..)?.iter().peekable();
+    let mut argument_start = true;
+    while let Some(token) = written.next() {
+        match token {
+            Token::LeftParen | Token::LeftSquare | Token::LeftBrace => depth += 1,
+            Token::RightParen | Token::RightSquare | Token::RightBrace => depth -= 1,
+            Token::Comma if depth == 0 => {
+                arguments.push(None);
+                argument_start = true;
+                continue;
+            }
+            Token::Name { name } if depth == 0 && argument_start => {
+                if written.peek() == Some(&&Token::Colon) {
+                    if let Some(label) = arguments.last_mut() {
+                        *label = Some(name.clone());
+                    }
+                }
+            }
+            _ => (),
+        }
+        argument_start = false;
+    }
+    if callee.piped {
+        arguments.insert(0, None);
+    }
+
+    let labels = signature
+        .field_map
+        .as_ref()
+        .map(|field_map| &field_map.fields);
+    let position_of = |label: &str| labels.and_then(|labels| labels.get(label).copied());
+    let (current, given) = arguments.split_last()?;
+    let active_parameter = match current {
+        Some(label) => position_of(label).map(|position| position as usize),
+        None => {
+            let unlabelled = given.iter().filter(|label| label.is_none()).count();
+            let labelled = given
+                .iter()
+                .flatten()
+                .filter_map(|label| position_of(label))
+                .collect_vec();
+            (unlabelled..signature.parameters.len())
+                .find(|position| !labelled.contains(&(*position as u32)))
+        }
+    };
+
+    let mut printer = Printer::new();
+    let mut label = format!("{}(", callee.name);
+    let mut parameters = vec![];
+    for (position, type_) in signature.parameters.iter().enumerate() {
+        if position > 0 {
+            label.push_str(", ");
+        }
+        let start = label.len() as u32;
+        let parameter_label = labels.and_then(|labels| {
+            labels
+                .iter()
+                .find(|(_, index)| **index as usize == position)
+                .map(|(label, _)| label)
+        });
+        if let Some(parameter_label) = parameter_label {
+            label.push_str(parameter_label);
+            label.push_str(": ");
+        }
+        label.push_str(&printer.pretty_print(type_, 0));
+        parameters.push(SrcSpan {
+            start,
+            end: label.len() as u32,
+        });
+    }
+    label.push_str(") -> ");
+    label.push_str(&printer.pretty_print(&signature.return_type, 0));
+
+    Some(SignatureHelp {
+        label,
+        parameters,
+        active_parameter,
+        module: signature.module,
+        location: signature.location,
+    })
+}
+
+/// The documentation of each parameter of the function or record constructor
+/// defined at the location in the source of a module, found in the same way
+/// as that of definitions.
+pub fn parameter_documentation(src: &str, location: SrcSpan) -> Vec<Option<String>> {
+    let arena = Arena::new();
+    let (parsed, extra) = match crate::parse::parse_module(src, &arena) {
+        Ok(parsed) => parsed,
+        Err(_) => return vec![],
+    };
+    let arguments = parsed
+        .statements
+        .iter()
+        .flat_map(|group| group.statements_ref())
+        .find_map(|statement| match statement {
+            Statement::Fn {
+                location: found,
+                arguments,
+                ..
+            } if found.start == location.start => {
+                Some(arguments.iter().map(|arg| arg.location).collect_vec())
+            }
+            Statement::ExternalFn {
+                location: found,
+                arguments,
+                ..
+            } if found.start == location.start => {
+                Some(arguments.iter().map(|arg| arg.location).collect_vec())
+            }
+            Statement::CustomType { constructors, .. } => constructors
+                .iter()
+                .find(|constructor| constructor.location.start == location.start)
+                .map(|constructor| {
+                    constructor
+                        .arguments
+                        .iter()
+                        .map(|arg| arg.location)
+                        .collect_vec()
+                }),
+            _ => None,
+        })
+        .unwrap_or_default();
+    arguments
+        .into_iter()
+        .map(|location| documentation(src, &extra, location))
+        .collect()
+}
+
+/// The name of a function called with the arguments that follow the tokens.
+struct Callee {
+    module_alias: Option<String>,
+    name: String,
+    /// Whether the first argument is given by a pipe.
+    piped: bool,
+}
+
+fn callee(before: &[Token]) -> Option<Callee> {
+    let (name, rest) = match before.split_last()? {
+        (Token::Name { name } | Token::UpName { name }, rest) => (name, rest),
+        _ => return None,
+    };
+    let (module_alias, rest) = match rest {
+        [rest @ .., Token::Name { name: alias }, Token::Dot] => (Some(alias.clone()), rest),
+        [.., Token::Dot] => return None,
+        _ => (None, rest),
+    };
+    // The name of a function being defined is not a call
+    if let Some(Token::Fn) = rest.last() {
+        return None;
+    }
+    Some(Callee {
+        module_alias,
+        name: name.clone(),
+        piped: rest.last() == Some(&Token::Pipe),
+    })
+}
+
+struct Signature {
+    parameters: Vec<Arc<Type>>,
+    return_type: Arc<Type>,
+    field_map: Option<FieldMap>,
+    module: String,
+    location: SrcSpan,
+}
+
+/// The signature of the function or record constructor a name refers to in
+/// the module, either defined in it or imported.
+fn resolve(
+    module: &TypedModule,
+    importable_modules: &im::HashMap<String, type_::Module>,
+    callee: &Callee,
+) -> Option<Signature> {
+    let imports = module
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Import {
+                module,
+                as_name,
+                unqualified,
+                ..
+            } => Some((module.join("/"), as_name, unqualified)),
+            _ => None,
+        });
+
+    if let Some(alias) = &callee.module_alias {
+        let (imported, _, _) = imports.clone().find(|(imported, as_name, _)| {
+            as_name.as_deref().or_else(|| imported.rsplit('/').next()) == Some(alias.as_str())
+        })?;
+        let value = importable_modules
+            .get(&imported)?
+            .values
+            .get(callee.name.as_str())?;
+        return imported_signature(value);
+    }
+
+    if let Some(signature) = defined_signature(module, &callee.name) {
+        return Some(signature);
+    }
+    imports.into_iter().find_map(|(imported, _, unqualified)| {
+        let import = unqualified
+            .iter()
+            .find(|import| import.layer == Layer::Value && import.variable_name() == callee.name)?;
+        let value = importable_modules
+            .get(&imported)?
+            .values
+            .get(&import.name)?;
+        imported_signature(value)
+    })
+}
+
+fn defined_signature(module: &TypedModule, name: &str) -> Option<Signature> {
+    let module_name = module.name.join("/");
+    module
+        .statements
+        .iter()
+        .find_map(|statement| match statement {
+            Statement::Fn {
+                location,
+                name: defined,
+                arguments,
+                return_type,
+                ..
+            } if defined == name => {
+                let labels = arguments.iter().map(|arg| match &arg.names {
+                    ArgNames::NamedLabelled { label, .. }
+                    | ArgNames::LabelledDiscard { label, .. } => Some(label),
+                    ArgNames::Named { .. } | ArgNames::Discard { .. } => None,
+                });
+                Some(Signature {
+                    parameters: arguments.iter().map(|arg| arg.type_.clone()).collect(),
+                    return_type: return_type.clone(),
+                    field_map: field_map(labels),
+                    module: module_name.clone(),
+                    location: *location,
+                })
+            }
+
+            Statement::ExternalFn {
+                location,
+                name: defined,
+                arguments,
+                return_type,
+                ..
+            } if defined == name => Some(Signature {
+                parameters: arguments.iter().map(|arg| arg.type_.clone()).collect(),
+                return_type: return_type.clone(),
+                field_map: field_map(arguments.iter().map(|arg| arg.label.as_ref())),
+                module: module_name.clone(),
+                location: *location,
+            }),
+
+            Statement::CustomType {
+                name: type_name,
+                public,
+                constructors,
+                typed_parameters,
+                ..
+            } => {
+                let constructor = constructors
+                    .iter()
+                    .find(|constructor| constructor.name == name)?;
+                let return_type = Arc::new(Type::App {
+                    public: *public,
+                    module: module.name.clone(),
+                    name: type_name.clone(),
+                    args: typed_parameters.clone(),
+                });
+                let arguments = &constructor.arguments;
+                Some(Signature {
+                    parameters: arguments.iter().map(|arg| arg.type_.clone()).collect(),
+                    return_type,
+                    field_map: field_map(arguments.iter().map(|arg| arg.label.as_ref())),
+                    module: module_name.clone(),
+                    location: constructor.location,
+                })
+            }
+
+            _ => None,
+        })
+}
+
+fn imported_signature(value: &ValueConstructor) -> Option<Signature> {
+    let (field_map, module, location) = match &value.variant {
+        ValueConstructorVariant::ModuleFn {
+            field_map,
+            module,
+            location,
+            ..
+        } => (field_map, module.join("/"), location),
+        ValueConstructorVariant::Record {
+            field_map,
+            module,
+            location,
+            ..
+        } => (field_map, module.to_string(), location),
+        ValueConstructorVariant::LocalVariable { .. }
+        | ValueConstructorVariant::ModuleConstant { .. } => return None,
+    };
+    match &*type_::collapse_links(value.type_.clone()) {
+        Type::Fn { args, retrn } => Some(Signature {
+            parameters: args.clone(),
+            return_type: retrn.clone(),
+            field_map: field_map.clone(),
+            module,
+            location: *location,
+        }),
+        _ => None,
+    }
+}
+
+fn field_map<'a>(labels: impl Iterator<Item = Option<&'a Name>>) -> Option<FieldMap> {
+    let labels = labels.collect_vec();
+    let fields = labels
+        .iter()
+        .enumerate()
+        .filter_map(|(position, label)| Some(((*label)?.clone(), position as u32)))
+        .collect::<std::collections::HashMap<_, _>>();
+    (!fields.is_empty()).then(|| FieldMap {
+        arity: labels.len() as u32,
+        fields,
+    })
+}
@@ -0,0 +1,267 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use crate::{
+    ast::{SrcSpan, Statement, TypedModule, TypedStatement},
+    type_::{self, pretty::Printer, Type},
+};
+
+use super::references::find_name;
+
+/// A definition in the outline of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The type of a function, constant, record constructor or field.
+    pub detail: Option<String>,
+    /// The whole of the definition.
+    pub location: SrcSpan,
+    /// Where the name of the definition is written.
+    pub name_location: SrcSpan,
+    /// The record constructors of a custom type, or the labelled fields of a
+    /// record constructor.
+    pub children: Vec<DocumentSymbol>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Constant,
+    Type,
+    Constructor,
+    Field,
+}
+
+/// A definition of a project found by searching for its name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub module: String,
+    /// Where the name of the definition is written.
+    pub location: SrcSpan,
+    /// The custom type of a record constructor.
+    pub container: Option<String>,
+}
+
+/// The outline of a module: its functions, constants and types, with the
+/// record constructors of each custom type, in the order they are written.
+pub fn document_symbols(module: &TypedModule, src: &str) -> Vec<DocumentSymbol> {
+    module
+        .statements
+        .iter()
+        .filter_map(|statement| statement_symbol(module, src, statement))
+        .sorted_by_key(|symbol| symbol.location.start)
+        .collect()
+}
+
+/// The definitions of the modules of a project with names that match the
+/// query, best match first.
+///
+/// A name matches if the characters of the query are written in it in the
+/// same order, ignoring case, so `mkusr` matches `make_user`. Names that start
+/// with the query are the best matches, followed by those where the
+/// characters of the query are closest together.
+///
+pub fn workspace_symbols(modules: &[(&TypedModule, &str)], query: &str) -> Vec<WorkspaceSymbol> {
+    let query = query.to_lowercase();
+    modules
+        .iter()
+        .flat_map(|(module, src)| {
+            let module_name = module.name.join("/");
+            document_symbols(module, src)
+                .into_iter()
+                .flat_map(move |symbol| {
+                    let constructors = symbol
+                        .children
+                        .iter()
+                        .map(|constructor| (constructor.clone(), Some(symbol.name.clone())))
+                        .collect_vec();
+                    std::iter::once((symbol, None)).chain(constructors)
+                })
+                .map(move |(symbol, container)| WorkspaceSymbol {
+                    name: symbol.name,
+                    kind: symbol.kind,
+                    module: module_name.clone(),
+                    location: symbol.name_location,
+                    container,
+                })
+        })
+        .filter_map(|symbol| Some((fuzzy_score(&symbol.name, &query)?, symbol)))
+        .sorted_by(|(a_score, a), (b_score, b)| {
+            a_score
+                .cmp(b_score)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.module.cmp(&b.module))
+        })
+        .map(|(_, symbol)| symbol)
+        .collect()
+}
+
+/// How well a name matches a lowercase query, lower being better: whether the
+/// name does not start with the query, then the number of characters between
+/// the first and last matched, then the length of the name.
+fn fuzzy_score(name: &str, query: &str) -> Option<(bool, usize, usize)> {
+    let lowercase = name.to_lowercase();
+    let mut query_chars = query.chars().peekable();
+    let mut first = None;
+    let mut last = 0;
+    for (index, c) in lowercase.chars().enumerate() {
+        if query_chars.peek() == Some(&c) {
+            let _ = query_chars.next();
+            first = first.or(Some(index));
+            last = index;
+        }
+    }
+    if query_chars.peek().is_some() {
+        return None;
+    }
+    let spread = match first {
+        Some(first) => last - first + 1 - query.chars().count(),
+        None => 0,
+    };
+    Some((!lowercase.starts_with(query), spread, name.len()))
+}
+
+fn statement_symbol(
+    module: &TypedModule,
+    src: &str,
+    statement: &TypedStatement,
+) -> Option<DocumentSymbol> {
+    let printer = |type_: &Type| Printer::new().pretty_print(type_, 0);
+    let symbol = |name: &str, kind, detail, location: SrcSpan| {
+        let name_location = find_name(src, location, name)?;
+        Some(DocumentSymbol {
+            name: name.to_string(),
+            kind,
+            detail,
+            location,
+            name_location,
+            children: vec![],
+        })
+    };
+
+    match statement {
+        Statement::Fn {
+            location,
+            end_position,
+            name,
+            arguments,
+            return_type,
+            ..
+        } => {
+            let type_ = type_::fn_(
+                arguments.iter().map(|arg| arg.type_.clone()).collect(),
+                return_type.clone(),
+            );
+            let location = SrcSpan {
+                start: location.start,
+                end: end_position + 1,
+            };
+            symbol(name, SymbolKind::Function, Some(printer(&type_)), location)
+        }
+
+        Statement::ExternalFn {
+            location,
+            name,
+            arguments,
+            return_type,
+            ..
+        } => {
+            let type_ = type_::fn_(
+                arguments.iter().map(|arg| arg.type_.clone()).collect(),
+                return_type.clone(),
+            );
+            symbol(name, SymbolKind::Function, Some(printer(&type_)), *location)
+        }
+
+        Statement::ModuleConstant {
+            location,
+            name,
+            type_,
+            ..
+        } => symbol(name, SymbolKind::Constant, Some(printer(type_)), *location),
+
+        Statement::TypeAlias {
+            location, alias, ..
+        } => symbol(alias, SymbolKind::Type, None, *location),
+
+        Statement::ExternalType { location, name, .. } => {
+            symbol(name, SymbolKind::Type, None, *location)
+        }
+
+        Statement::CustomType {
+            location,
+            name,
+            public,
+            constructors,
+            typed_parameters,
+            ..
+        } => {
+            let custom_type = Arc::new(Type::App {
+                public: *public,
+                module: module.name.clone(),
+                name: name.clone(),
+                args: typed_parameters.clone(),
+            });
+            let children = constructors
+                .iter()
+                .filter_map(|constructor| {
+                    let type_ = if constructor.arguments.is_empty() {
+                        custom_type.clone()
+                    } else {
+                        let arguments = constructor
+                            .arguments
+                            .iter()
+                            .map(|arg| arg.type_.clone())
+                            .collect();
+                        type_::fn_(arguments, custom_type.clone())
+                    };
+                    let fields = constructor
+                        .arguments
+                        .iter()
+                        .filter_map(|arg| {
+                            let label = arg.label.as_ref()?;
+                            let detail = Some(printer(&arg.type_));
+                            symbol(label, SymbolKind::Field, detail, arg.location)
+                        })
+                        .collect();
+                    let detail = Some(printer(&type_));
+                    let location = constructor.location;
+                    let constructor =
+                        symbol(&constructor.name, SymbolKind::Constructor, detail, location)?;
+                    Some(DocumentSymbol {
+                        children: fields,
+                        ..constructor
+                    })
+                })
+                .collect_vec();
+
+            // The definition ends with the brace after the last constructor
+            let constructors_end = children
+                .iter()
+                .map(|constructor| constructor.location.end)
+                .max()
+                .unwrap_or(location.end);
+            let end = src
+                .get(constructors_end as usize..)
+                .and_then(|rest| rest.find('}'))
+                .map_or(constructors_end, |brace| {
+                    constructors_end + brace as u32 + 1
+                });
+            let location = SrcSpan {
+                start: location.start,
+                end,
+            };
+            let custom_type = symbol(name, SymbolKind::Type, None, location)?;
+            Some(DocumentSymbol {
+                children,
+                ..custom_type
+            })
+        }
+
+        Statement::Import { .. } => None,
+    }
+}
@@ -0,0 +1,1530 @@
+use super::{
+    code_actions, code_lenses, completions, document_symbols, documentation, error_actions,
+    extract_actions, inlay_hints, parameter_documentation, rename, semantic_tokens, signature_help,
+    workspace_symbols, CodeAction, Completion, CompletionKind, ErrorActions, InlayHintKind,
+    ReferenceIndex, ReferenceKind, Runnable, SemanticTokenKind, SymbolKind, TextEdit,
+};
+use crate::{
+    ast::{SrcSpan, TypedModule},
+    build::{Origin, Target, TestFunction},
+    error::RenameProblem,
+    type_::{self, build_prelude, infer_module},
+    uid::UniqueIdGenerator,
+    Error,
+};
+
+const OPTION_MODULE: &str = "pub type Option(a) { Some(a) None }";
+
+const RESULT_MODULE: &str = "
+pub fn then(result: Result(a, e), next: fn(a) -> Result(b, e)) -> Result(b, e) {
+  case result {
+    Ok(value) -> next(value)
+    Error(error) -> Error(error)
+  }
+}";
+
+fn compile_module(src: &str) -> TypedModule {
+    compile_module_and_imports(src).0
+}
+
+// The module along with the interfaces of the modules it can import
+fn compile_module_and_imports(src: &str) -> (TypedModule, im::HashMap<String, type_::Module>) {
+    let ids = UniqueIdGenerator::new();
+    let mut modules = im::HashMap::new();
+    let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+    for (name, dep_src) in [
+        ("gleam/option", OPTION_MODULE),
+        ("gleam/result", RESULT_MODULE),
+    ] {
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module(dep_src, &arena).expect("syntax error");
+        ast.name = name.split('/').map(Into::into).collect();
+        let module = infer_module(
+            Target::Erlang,
+            &ids,
+            ast,
+            Origin::Src,
+            "thepackage",
+            &modules,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let _ = modules.insert(name.to_string(), module.type_info);
+    }
+    let arena = crate::ast::Arena::new();
+    let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+    ast.name = vec!["my_module".into()];
+    let module = infer_module(
+        Target::Erlang,
+        &ids,
+        ast,
+        Origin::Src,
+        "thepackage",
+        &modules,
+        &mut vec![],
+    )
+    .expect("should successfully infer");
+    (module, modules)
+}
+
+// The code actions for a selection of the last occurrence of `selected`
+fn actions_for(src: &str, selected: &str) -> Vec<CodeAction> {
+    let start = src.rfind(selected).expect("selection not in source") as u32;
+    let end = start + selected.len() as u32;
+    code_actions(&compile_module(src), src, SrcSpan { start, end })
+}
+
+// Apply the edits of the action with the given title, which must not overlap
+fn apply(src: &str, actions: &[CodeAction], title: &str) -> String {
+    let action = actions
+        .iter()
+        .find(|action| action.title == title)
+        .expect("no action with title");
+    let mut edits: Vec<&TextEdit> = action.edits.iter().collect();
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.location.start));
+    let mut src = src.to_string();
+    for edit in edits {
+        src.replace_range(
+            edit.location.start as usize..edit.location.end as usize,
+            &edit.new_text,
+        );
+    }
+    src
+}
+
+#[test]
+fn wrap_result_in_case() {
+    let src = "fn parse() -> Result(Int, Nil) { Ok(1) }
+
+pub fn main() {
+  parse()
+}
+";
+    let actions = actions_for(src, "parse()");
+    assert_eq!(
+        apply(src, &actions, "Wrap in case over Result"),
+        "fn parse() -> Result(Int, Nil) { Ok(1) }
+
+pub fn main() {
+  case parse() {
+    Ok(value) -> todo
+    Error(e) -> todo
+  }
+}
+"
+    );
+}
+
+#[test]
+fn wrap_result_with_cursor_inside_expression() {
+    let src = "fn parse(x) -> Result(Int, Nil) { Ok(x) }
+
+pub fn main() {
+  parse(1)
+}
+";
+    let actions = actions_for(src, "1)");
+    assert_eq!(
+        apply(src, &actions, "Wrap in case over Result"),
+        "fn parse(x) -> Result(Int, Nil) { Ok(x) }
+
+pub fn main() {
+  case parse(1) {
+    Ok(value) -> todo
+    Error(e) -> todo
+  }
+}
+"
+    );
+}
+
+#[test]
+fn wrap_option_in_case_uses_qualified_constructors() {
+    let src = "import gleam/option.{Option}
+
+fn find() -> Option(Int) { todo }
+
+pub fn main(x) {
+  let _ = find()
+  x
+}
+";
+    let actions = actions_for(src, "find()");
+    assert_eq!(actions.len(), 1);
+    assert_eq!(
+        apply(src, &actions, "Wrap in case over Option"),
+        "import gleam/option.{Option}
+
+fn find() -> Option(Int) { todo }
+
+pub fn main(x) {
+  let _ = case find() {
+    option.Some(value) -> todo
+    option.None -> todo
+  }
+  x
+}
+"
+    );
+}
+
+#[test]
+fn wrap_option_in_case_uses_unqualified_imports() {
+    let src = "import gleam/option.{Option, Some, None}
+
+fn find() -> Option(Int) { None }
+
+pub fn main() {
+  find()
+}
+";
+    let actions = actions_for(src, "find()");
+    assert_eq!(
+        apply(src, &actions, "Wrap in case over Option"),
+        "import gleam/option.{Option, Some, None}
+
+fn find() -> Option(Int) { None }
+
+pub fn main() {
+  case find() {
+    Some(value) -> todo
+    None -> todo
+  }
+}
+"
+    );
+}
+
+#[test]
+fn wrap_option_in_case_uses_module_alias() {
+    let src = "import gleam/option as opt
+
+fn find() -> opt.Option(Int) { opt.None }
+
+pub fn main() {
+  find()
+}
+";
+    let actions = actions_for(src, "find()");
+    assert_eq!(
+        apply(src, &actions, "Wrap in case over Option"),
+        "import gleam/option as opt
+
+fn find() -> opt.Option(Int) { opt.None }
+
+pub fn main() {
+  case find() {
+    opt.Some(value) -> todo
+    opt.None -> todo
+  }
+}
+"
+    );
+}
+
+#[test]
+fn unwrap_result_assignment_with_use() {
+    let src = "fn parse() -> Result(Int, Nil) { Ok(1) }
+
+pub fn main() {
+  let x = parse()
+  Ok(x)
+}
+";
+    let actions = actions_for(src, "parse()");
+    assert_eq!(actions.len(), 2);
+    assert_eq!(
+        apply(src, &actions, "Unwrap with use and result.then"),
+        "import gleam/result
+fn parse() -> Result(Int, Nil) { Ok(1) }
+
+pub fn main() {
+  use x <- result.then(parse())
+  Ok(x)
+}
+"
+    );
+}
+
+#[test]
+fn unwrap_result_assignment_with_imported_then() {
+    let src = "import gleam/result.{then}
+
+fn parse() -> Result(Int, Nil) { Ok(1) }
+
+pub fn main() {
+  let x = parse()
+  Ok(x)
+}
+";
+    let actions = actions_for(src, "parse()");
+    assert_eq!(
+        apply(src, &actions, "Unwrap with use and result.then"),
+        "import gleam/result.{then}
+
+fn parse() -> Result(Int, Nil) { Ok(1) }
+
+pub fn main() {
+  use x <- then(parse())
+  Ok(x)
+}
+"
+    );
+}
+
+#[test]
+fn no_actions_for_other_types() {
+    let src = "pub fn main() {
+  1 + 2
+}
+";
+    assert_eq!(actions_for(src, "1 + 2"), vec![]);
+}
+
+#[test]
+fn no_actions_outside_expressions() {
+    let src = "fn parse() -> Result(Int, Nil) { Ok(1) }
+";
+    assert_eq!(actions_for(src, "fn parse"), vec![]);
+}
+
+fn extractions_for(src: &str, selected: &str) -> Vec<CodeAction> {
+    let start = src.find(selected).expect("selection not in source") as u32;
+    let end = start + selected.len() as u32;
+    extract_actions(&compile_module(src), src, SrcSpan { start, end })
+}
+
+#[test]
+fn extract_variable_before_statement() {
+    let src = "pub fn main() {
+  let x = 1
+  let y = x * 2 + 3
+  y
+}
+";
+    let actions = extractions_for(src, "x * 2");
+    assert_eq!(
+        apply(src, &actions, "Extract variable"),
+        "pub fn main() {
+  let x = 1
+  let value = x * 2
+  let y = value + 3
+  y
+}
+"
+    );
+}
+
+#[test]
+fn extract_function_with_captured_variables() {
+    let src = "fn function() { 0 }
+
+pub fn main(xs: List(a)) {
+  let n = function()
+  #(xs, n, \"s\")
+}
+";
+    let actions = extractions_for(src, "#(xs, n, \"s\")");
+    assert_eq!(
+        actions
+            .iter()
+            .map(|action| action.title.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Extract function"]
+    );
+    assert_eq!(
+        apply(src, &actions, "Extract function"),
+        "fn function() { 0 }
+
+pub fn main(xs: List(a)) {
+  let n = function()
+  function_2(xs, n)
+}
+
+fn function_2(xs: List(a), n: Int) -> #(List(a), Int, String) {
+  #(xs, n, \"s\")
+}
+"
+    );
+}
+
+#[test]
+fn no_extraction_of_variables_bound_within_statement() {
+    let src = "pub fn main(x) {
+  case x {
+    y -> y + 1
+  }
+}
+";
+    assert_eq!(extractions_for(src, "y + 1"), vec![]);
+    assert_eq!(extractions_for(src, "main"), vec![]);
+}
+
+// The documentation of the definition at the first occurrence of `name`
+fn documentation_for(src: &str, name: &str) -> Option<String> {
+    let arena = crate::ast::Arena::new();
+    let (_, extra) = crate::parse::parse_module(src, &arena).expect("syntax error");
+    let start = src.find(name).expect("name not in source") as u32;
+    let location = SrcSpan {
+        start,
+        end: start + name.len() as u32,
+    };
+    documentation(src, &extra, location)
+}
+
+#[test]
+fn documentation_of_definitions() {
+    let src = "/// Not the docs of `one`.
+const zero = 0
+
+/// The number one.
+///
+/// It comes after `zero`.
+@deprecated(\"Use `two` instead\")
+pub fn one() { 1 }
+
+// Not a doc comment.
+fn two() { 2 }
+
+pub type Number {
+  /// Three, a number.
+  Three
+  Four
+}
+";
+    assert_eq!(
+        documentation_for(src, "pub fn one").as_deref(),
+        Some("The number one.\n\nIt comes after `zero`.")
+    );
+    assert_eq!(
+        documentation_for(src, "const zero").as_deref(),
+        Some("Not the docs of `one`.")
+    );
+    assert_eq!(documentation_for(src, "fn two"), None);
+    assert_eq!(
+        documentation_for(src, "Three\n").as_deref(),
+        Some("Three, a number.")
+    );
+    assert_eq!(documentation_for(src, "Four"), None);
+}
+
+// The completions for the cursor at the `|` of the edited source of the
+// module, which compiled as `src`
+fn completions_for(src: &str, edited: &str) -> Vec<Completion> {
+    let (module, modules) = compile_module_and_imports(src);
+    let cursor = edited.find('|').expect("no cursor in source") as u32;
+    completions(&edited.replace('|', ""), cursor, &module, &modules)
+}
+
+fn labels(completions: &[Completion]) -> Vec<&str> {
+    completions
+        .iter()
+        .map(|completion| completion.label.as_str())
+        .collect()
+}
+
+#[test]
+fn complete_importable_modules() {
+    let completions = completions_for("", "import gleam/|");
+    assert_eq!(
+        labels(&completions),
+        vec!["gleam", "gleam/option", "gleam/result"]
+    );
+}
+
+#[test]
+fn complete_unqualified_import() {
+    let completions = completions_for("", "import gleam/option.{|}");
+    assert_eq!(labels(&completions), vec!["None", "Some", "Option"]);
+}
+
+#[test]
+fn complete_module_members() {
+    let src = "import gleam/result
+pub fn main() { result.then }
+";
+    let completions = completions_for(src, "import gleam/result\npub fn main() { result.th| }\n");
+    assert_eq!(
+        completions,
+        vec![Completion {
+            label: "then".into(),
+            kind: CompletionKind::Function,
+            detail: Some("fn(Result(a, b), fn(a) -> Result(c, b)) -> Result(c, b)".into()),
+            insert_text: None,
+        }]
+    );
+}
+
+#[test]
+fn complete_record_fields() {
+    let src = "pub type Cat { Cat(name: String, age: Int) }
+pub fn main(cat: Cat) { cat.name }
+";
+    let edited = "pub type Cat { Cat(name: String, age: Int) }
+pub fn main(cat: Cat) { cat.| }
+";
+    let completions = completions_for(src, edited);
+    assert_eq!(labels(&completions), vec!["name", "age"]);
+    assert_eq!(
+        completions.get(1).and_then(|field| field.detail.as_deref()),
+        Some("Int")
+    );
+}
+
+#[test]
+fn complete_call_labels() {
+    let src = r#"fn greet(name n: String, greeting g: String) { g <> n }
+pub fn main() { greet(name: "Lucy", greeting: "Hi") }
+"#;
+    let edited = r#"fn greet(name n: String, greeting g: String) { g <> n }
+pub fn main() { greet(name: "Lucy", |) }
+"#;
+    let completions = completions_for(src, edited);
+    assert_eq!(
+        completions.first(),
+        Some(&Completion {
+            label: "greeting:".into(),
+            kind: CompletionKind::Label,
+            detail: Some("String".into()),
+            insert_text: Some("greeting: ".into()),
+        })
+    );
+    assert!(!labels(&completions).contains(&"name:"));
+}
+
+#[test]
+fn complete_names_in_scope_closest_first() {
+    let src = "import gleam/option.{Some}
+const limit = 10
+pub fn main(x) {
+  let y = x + 1
+  let z = y + limit
+  Some(z)
+}
+";
+    let edited = "import gleam/option.{Some}
+const limit = 10
+pub fn main(x) {
+  let y = x + 1
+  let z = y + limit
+  |
+}
+";
+    let completions = completions_for(src, edited);
+    assert_eq!(
+        labels(&completions),
+        vec!["z", "y", "x", "limit", "main", "Some", "option"]
+    );
+}
+
+// The modules of a project, compiled in order, along with their source and
+// the cursor `^` in the last module
+fn compile_project(modules: &[(&str, &str)]) -> (Vec<(TypedModule, String)>, u32) {
+    let ids = UniqueIdGenerator::new();
+    let mut importable = im::HashMap::new();
+    let _ = importable.insert("gleam".to_string(), build_prelude(&ids));
+    let arena = crate::ast::Arena::new();
+    let (mut ast, _) = crate::parse::parse_module(OPTION_MODULE, &arena).expect("syntax error");
+    ast.name = vec!["gleam".into(), "option".into()];
+    let option = infer_module(
+        Target::Erlang,
+        &ids,
+        ast,
+        Origin::Src,
+        "thepackage",
+        &importable,
+        &mut vec![],
+    )
+    .expect("should successfully infer");
+    let _ = importable.insert("gleam/option".to_string(), option.type_info);
+
+    let mut cursor = 0;
+    let mut compiled = vec![];
+    for (name, src) in modules {
+        let src = match src.find('^') {
+            Some(index) => {
+                cursor = index as u32;
+                src.replace('^', "")
+            }
+            None => src.to_string(),
+        };
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module(&src, &arena).expect("syntax error");
+        ast.name = name.split('/').map(Into::into).collect();
+        let module = infer_module(
+            Target::Erlang,
+            &ids,
+            ast,
+            Origin::Src,
+            "thepackage",
+            &importable,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let _ = importable.insert(name.to_string(), module.type_info.clone());
+        compiled.push((module, src));
+    }
+
+    (compiled, cursor)
+}
+
+// The source of the modules of a project after renaming what is at the
+// cursor `^` in the last module. Only the modules that change are returned.
+fn rename_in(modules: &[(&str, &str)], new_name: &str) -> Result<Vec<(String, String)>, Error> {
+    let (compiled, cursor) = compile_project(modules);
+    let project = compiled
+        .iter()
+        .map(|(module, src)| (module, src.as_str()))
+        .collect::<Vec<_>>();
+    let module = modules.last().map(|(name, _)| *name).unwrap_or_default();
+    let index = ReferenceIndex::new(&project);
+    let edits = rename(&index, module, cursor, new_name)?;
+    Ok(edits
+        .into_iter()
+        .map(|(name, edits)| {
+            let mut src = project
+                .iter()
+                .find(|(module, _)| module.name.join("/") == name)
+                .map(|(_, src)| src.to_string())
+                .expect("edited module");
+            for edit in edits.iter().rev() {
+                src.replace_range(
+                    edit.location.start as usize..edit.location.end as usize,
+                    &edit.new_text,
+                );
+            }
+            (name, src)
+        })
+        .collect())
+}
+
+fn rename_problem(result: Result<Vec<(String, String)>, Error>) -> Option<RenameProblem> {
+    match result {
+        Err(Error::CannotRename { problem, .. }) => Some(problem),
+        _ => None,
+    }
+}
+
+#[test]
+fn rename_function_across_modules() {
+    let wibble = "pub fn wobble(x) { x }
+pub fn twice(x) { wobble(wobble(x)) }
+";
+    let app = "import wibble.{wobble}
+import wibble.{wobble as other} as w
+pub fn main() {
+  let f = w.wobble
+  other(^wobble(wibble.wobble(1)))
+}
+";
+    assert_eq!(
+        rename_in(&[("wibble", wibble), ("app", app)], "wubble").expect("rename"),
+        vec![
+            (
+                "app".to_string(),
+                "import wibble.{wubble}
+import wibble.{wubble as other} as w
+pub fn main() {
+  let f = w.wubble
+  other(wubble(wibble.wubble(1)))
+}
+"
+                .to_string()
+            ),
+            (
+                "wibble".to_string(),
+                "pub fn wubble(x) { x }
+pub fn twice(x) { wubble(wubble(x)) }
+"
+                .to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn rename_local_variable() {
+    let app = "pub fn main(number, other) {
+  let x = number + 1
+  let y = case #(x, other) {
+    #(0, n) | #(n, 0) if n > 1 -> n
+    _ -> x
+  }
+  let x = fn(number) { number * 2 }
+  x(^number + y)
+}
+";
+    assert_eq!(
+        rename_in(&[("app", app)], "count").expect("rename"),
+        vec![(
+            "app".to_string(),
+            "pub fn main(count, other) {
+  let x = count + 1
+  let y = case #(x, other) {
+    #(0, n) | #(n, 0) if n > 1 -> n
+    _ -> x
+  }
+  let x = fn(number) { number * 2 }
+  x(count + y)
+}
+"
+            .to_string()
+        )]
+    );
+
+    let app = app.replace('^', "").replacen("#(0, n)", "#(0, ^n)", 1);
+    assert_eq!(
+        rename_in(&[("app", &app)], "m").expect("rename"),
+        vec![(
+            "app".to_string(),
+            app.replace('^', "").replace(
+                "#(0, n) | #(n, 0) if n > 1 -> n",
+                "#(0, m) | #(m, 0) if m > 1 -> m"
+            )
+        )]
+    );
+}
+
+#[test]
+fn rename_type_constructor_and_label() {
+    let pets = "pub type Cat {
+  Cat(name: String, lives: Int)
+}
+pub fn new(name name: String) -> Cat { Cat(name: name, lives: 9) }
+";
+    let app = "import pets.{Cat}
+pub fn main(cat: pets.Cat) -> Cat {
+  let Cat(name: n, ..) = cat
+  let named: Cat = Cat(..cat, ^name: n)
+  pets.new(name: named.name)
+}
+";
+    let modules = [("pets", pets), ("app", app)];
+    assert_eq!(
+        rename_in(&modules, "nickname").expect("rename"),
+        vec![
+            (
+                "app".to_string(),
+                "import pets.{Cat}
+pub fn main(cat: pets.Cat) -> Cat {
+  let Cat(nickname: n, ..) = cat
+  let named: Cat = Cat(..cat, nickname: n)
+  pets.new(name: named.nickname)
+}
+"
+                .to_string()
+            ),
+            (
+                "pets".to_string(),
+                pets.replace("Cat(name: String", "Cat(nickname: String")
+                    .replace("Cat(name: name", "Cat(nickname: name")
+            ),
+        ]
+    );
+
+    let app = app.replace('^', "").replacen("-> Cat", "-> ^Cat", 1);
+    let renamed = rename_in(&[("pets", pets), ("app", &app)], "Kitten").expect("rename");
+    assert_eq!(
+        renamed.first().map(|(_, src)| src.as_str()),
+        Some(
+            "import pets.{Kitten, Cat}
+pub fn main(cat: pets.Kitten) -> Kitten {
+  let Cat(name: n, ..) = cat
+  let named: Kitten = Cat(..cat, name: n)
+  pets.new(name: named.name)
+}
+"
+        )
+    );
+}
+
+#[test]
+fn rename_problems() {
+    let app = "import gleam/option.{Some}
+const limit = 10
+pub fn main(x: Int) {
+  let y = x + limit
+  Some(y)
+}
+";
+    let rename_at = |target: &str, new_name: &str| {
+        let src = app.replacen(target, &format!("^{target}"), 1);
+        rename_problem(rename_in(&[("app", &src)], new_name))
+    };
+    assert_eq!(rename_at("y =", "limit"), Some(RenameProblem::Conflict));
+    assert_eq!(rename_at("limit\n", "max"), None);
+    assert_eq!(rename_at("limit =", "main"), Some(RenameProblem::Conflict));
+    assert_eq!(
+        rename_at("limit =", "Limit"),
+        Some(RenameProblem::InvalidName { uppercase: false })
+    );
+    assert_eq!(
+        rename_at("limit =", "case"),
+        Some(RenameProblem::InvalidName { uppercase: false })
+    );
+    assert_eq!(
+        rename_at("Some(y)", "Just"),
+        Some(RenameProblem::DefinedInDependency {
+            module: "gleam/option".into()
+        })
+    );
+    assert_eq!(
+        rename_at("Int", "Number"),
+        Some(RenameProblem::NothingToRename)
+    );
+}
+
+// The references to what is at the cursor `^` in the last module of a
+// project, as the referring source code and the kind of reference
+fn references_in(modules: &[(&str, &str)]) -> Vec<(String, String, ReferenceKind)> {
+    let (compiled, cursor) = compile_project(modules);
+    let project = compiled
+        .iter()
+        .map(|(module, src)| (module, src.as_str()))
+        .collect::<Vec<_>>();
+    let module = modules.last().map(|(name, _)| *name).unwrap_or_default();
+    ReferenceIndex::new(&project)
+        .references(module, cursor)
+        .into_iter()
+        .map(|reference| {
+            let src = project
+                .iter()
+                .find(|(module, _)| module.name.join("/") == reference.module)
+                .map(|(_, src)| *src)
+                .unwrap_or_default();
+            let start = reference.location.start as usize;
+            let end = reference.location.end as usize;
+            let line_start = src
+                .get(..start)
+                .and_then(|s| s.rfind('\n'))
+                .map_or(0, |i| i + 1);
+            let line_end = src
+                .get(end..)
+                .and_then(|s| s.find('\n'))
+                .map_or(src.len(), |i| end + i);
+            let line = src.get(line_start..line_end).unwrap_or_default();
+            (reference.module, line.trim().to_string(), reference.kind)
+        })
+        .collect()
+}
+
+#[test]
+fn references_across_modules() {
+    let wibble = "pub fn wobble(x) { x }
+pub fn twice(x) { wobble(wobble(x)) }
+";
+    let app = "import wibble.{wobble}
+pub fn main() {
+  wibble.wobble(^wobble(1))
+}
+";
+    assert_eq!(
+        references_in(&[("wibble", wibble), ("app", app)]),
+        vec![
+            (
+                "app".to_string(),
+                "import wibble.{wobble}".to_string(),
+                ReferenceKind::Read
+            ),
+            (
+                "app".to_string(),
+                "wibble.wobble(wobble(1))".to_string(),
+                ReferenceKind::Read
+            ),
+            (
+                "app".to_string(),
+                "wibble.wobble(wobble(1))".to_string(),
+                ReferenceKind::Read
+            ),
+            (
+                "wibble".to_string(),
+                "pub fn wobble(x) { x }".to_string(),
+                ReferenceKind::Definition
+            ),
+            (
+                "wibble".to_string(),
+                "pub fn twice(x) { wobble(wobble(x)) }".to_string(),
+                ReferenceKind::Read
+            ),
+            (
+                "wibble".to_string(),
+                "pub fn twice(x) { wobble(wobble(x)) }".to_string(),
+                ReferenceKind::Read
+            ),
+        ]
+    );
+}
+
+#[test]
+fn references_distinguish_bindings_from_reads() {
+    let app = "pub fn main(x) {
+  let y = case x {
+    #(0, n) | #(n, 0) -> n
+    _ -> 0
+  }
+  ^y + y
+}
+";
+    let kinds = |src: &str| {
+        references_in(&[("app", src)])
+            .into_iter()
+            .map(|(_, _, kind)| kind)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        kinds(app),
+        vec![
+            ReferenceKind::Binding,
+            ReferenceKind::Read,
+            ReferenceKind::Read
+        ]
+    );
+    let app = app.replace('^', "").replacen("-> n", "-> ^n", 1);
+    assert_eq!(
+        kinds(&app),
+        vec![
+            ReferenceKind::Binding,
+            ReferenceKind::Binding,
+            ReferenceKind::Read
+        ]
+    );
+    assert_eq!(references_in(&[("app", "pub fn main() { ^1 }")]), vec![]);
+}
+
+#[test]
+fn document_symbols_outline() {
+    let src = "import gleam/option
+pub type Pet {
+  Cat(name: String, Int)
+  Fish
+}
+const limit = 10
+pub fn adopt(pet: Pet) -> Pet { pet }
+";
+    let module = compile_module(src);
+    let symbols = document_symbols(&module, src);
+    let outline = symbols
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.detail.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        outline,
+        vec![
+            ("Pet", SymbolKind::Type, None),
+            ("limit", SymbolKind::Constant, Some("Int")),
+            ("adopt", SymbolKind::Function, Some("fn(Pet) -> Pet")),
+        ]
+    );
+
+    let pet = symbols.first().expect("Pet");
+    let text = |location: SrcSpan| &src[location.start as usize..location.end as usize];
+    assert_eq!(
+        text(pet.location),
+        "pub type Pet {
+  Cat(name: String, Int)
+  Fish
+}"
+    );
+    assert_eq!(text(pet.name_location), "Pet");
+    let constructors = pet
+        .children
+        .iter()
+        .map(|constructor| {
+            let fields = constructor
+                .children
+                .iter()
+                .map(|field| (field.name.as_str(), field.kind))
+                .collect::<Vec<_>>();
+            (
+                constructor.name.as_str(),
+                constructor.detail.as_deref(),
+                fields,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        constructors,
+        vec![
+            (
+                "Cat",
+                Some("fn(String, Int) -> Pet"),
+                vec![("name", SymbolKind::Field)]
+            ),
+            ("Fish", Some("Pet"), vec![]),
+        ]
+    );
+    let adopt = symbols.last().expect("adopt");
+    assert_eq!(
+        text(adopt.location),
+        "pub fn adopt(pet: Pet) -> Pet { pet }"
+    );
+}
+
+#[test]
+fn workspace_symbols_fuzzy_search() {
+    let pets = "pub type Pet { Cat Dog }
+pub fn make_user() { Nil }
+pub fn user_pet() { Nil }
+";
+    let app = "pub fn main() { Nil }
+fn unrelated() { Nil }
+";
+    let (compiled, _) = compile_project(&[("pets", pets), ("app", app)]);
+    let project = compiled
+        .iter()
+        .map(|(module, src)| (module, src.as_str()))
+        .collect::<Vec<_>>();
+    let search = |query: &str| {
+        workspace_symbols(&project, query)
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.module, symbol.container))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        search("user"),
+        vec![
+            ("user_pet".to_string(), "pets".to_string(), None),
+            ("make_user".to_string(), "pets".to_string(), None),
+        ]
+    );
+    assert_eq!(
+        search("mkusr"),
+        vec![("make_user".to_string(), "pets".to_string(), None)]
+    );
+    assert_eq!(
+        search("do"),
+        vec![(
+            "Dog".to_string(),
+            "pets".to_string(),
+            Some("Pet".to_string())
+        )]
+    );
+    assert_eq!(search("").len(), 7);
+}
+
+// The code actions for the error of the module `app`, which fails to compile
+// alongside the modules of the project.
+fn error_actions_for(modules: &[(&str, &str)], src: &str) -> ErrorActions {
+    let ids = UniqueIdGenerator::new();
+    let (compiled, _) = compile_project(modules);
+    let mut importable: im::HashMap<_, _> = compiled
+        .into_iter()
+        .map(|(module, _)| (module.name.join("/"), module.type_info))
+        .collect();
+    let _ = importable.insert("gleam".to_string(), build_prelude(&ids));
+    let arena = crate::ast::Arena::new();
+    let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+    ast.name = vec!["app".into()];
+    let error = infer_module(
+        Target::Erlang,
+        &ids,
+        ast,
+        Origin::Src,
+        "thepackage",
+        &importable,
+        &mut vec![],
+    )
+    .expect_err("should fail to infer");
+    let error = Error::Type {
+        path: "src/app.gleam".into(),
+        src: src.to_string(),
+        error,
+    };
+    error_actions(&error, &importable).expect("no error actions")
+}
+
+const UTIL_MODULE: &str = "
+pub fn helper() { 1 }
+pub type Pet { Cat Dog }
+fn private() { 2 }
+";
+
+#[test]
+fn import_missing_value() {
+    let modules = [
+        ("lib/util", UTIL_MODULE),
+        ("other", "pub fn helper() { 3 }"),
+    ];
+    let src = "pub fn main() {
+  helper()
+}
+";
+    let fixes = error_actions_for(&modules, src);
+    assert_eq!(fixes.location, SrcSpan { start: 18, end: 24 });
+    let titles: Vec<_> = fixes.actions.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec![
+            "Import `helper` from `lib/util`",
+            "Qualify as `util.helper`",
+            "Import `helper` from `other`",
+            "Qualify as `other.helper`",
+        ]
+    );
+    assert_eq!(
+        apply(src, &fixes.actions, "Import `helper` from `lib/util`"),
+        "import lib/util.{helper}
+
+pub fn main() {
+  helper()
+}
+"
+    );
+    assert_eq!(
+        apply(src, &fixes.actions, "Qualify as `util.helper`"),
+        "import lib/util
+
+pub fn main() {
+  util.helper()
+}
+"
+    );
+}
+
+#[test]
+fn import_extends_existing_imports() {
+    let modules = [("lib/util", UTIL_MODULE)];
+    let src = "import lib/util.{Cat, Pet}
+
+pub fn main() {
+  helper()
+}
+";
+    let fixes = error_actions_for(&modules, src);
+    assert_eq!(
+        apply(src, &fixes.actions, "Import `helper` from `lib/util`"),
+        "import lib/util.{Cat, Pet, helper}
+
+pub fn main() {
+  helper()
+}
+"
+    );
+
+    let src = "import lib/util as u
+
+pub fn main() {
+  helper()
+}
+";
+    let fixes = error_actions_for(&modules, src);
+    assert_eq!(
+        apply(src, &fixes.actions, "Import `helper` from `lib/util`"),
+        "import lib/util.{helper} as u
+
+pub fn main() {
+  helper()
+}
+"
+    );
+    assert_eq!(
+        apply(src, &fixes.actions, "Qualify as `u.helper`"),
+        "import lib/util as u
+
+pub fn main() {
+  u.helper()
+}
+"
+    );
+}
+
+#[test]
+fn import_missing_type_in_order() {
+    let modules = [("lib/util", UTIL_MODULE), ("zoo", "pub fn zoo() { 1 }")];
+    let src = "//// Pets
+import gleam
+import zoo
+
+pub fn main() -> Pet(Int) {
+  todo
+}
+";
+    let fixes = error_actions_for(&modules, src);
+    let titles: Vec<_> = fixes.actions.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec!["Import `Pet` from `lib/util`", "Qualify as `util.Pet`"]
+    );
+    assert_eq!(
+        apply(src, &fixes.actions, "Qualify as `util.Pet`"),
+        "//// Pets
+import gleam
+import lib/util
+import zoo
+
+pub fn main() -> util.Pet(Int) {
+  todo
+}
+"
+    );
+
+    let src = "//// Pets
+
+pub fn main() {
+  private()
+}
+";
+    let start = src.find("private").expect("private not in source") as u32;
+    let error = Error::Type {
+        path: "src/app.gleam".into(),
+        src: src.to_string(),
+        error: type_::Error::UnknownVariable {
+            location: SrcSpan {
+                start,
+                end: start + 7,
+            },
+            name: "private".into(),
+            variables: vec![],
+        },
+    };
+    let (compiled, _) = compile_project(&modules);
+    let importable = compiled
+        .into_iter()
+        .map(|(module, _)| (module.name.join("/"), module.type_info))
+        .collect();
+    assert_eq!(error_actions(&error, &importable), None);
+
+    let src = "//// Pets
+
+pub fn main() {
+  Dog
+}
+";
+    let fixes = error_actions_for(&modules, src);
+    assert_eq!(
+        apply(src, &fixes.actions, "Import `Dog` from `lib/util`"),
+        "//// Pets
+
+import lib/util.{Dog}
+
+pub fn main() {
+  Dog
+}
+"
+    );
+}
+
+#[test]
+fn add_missing_case_clauses() {
+    let modules = [(
+        "lib/pets",
+        "pub type Pet { Cat(name: String, lives: Int) Dog(String, Int) Fish }",
+    )];
+    let src = "import lib/pets.{Pet, Cat}
+
+pub fn main(pet: Pet) {
+  let name = case pet {
+    Cat(name, ..) -> name
+  }
+  name
+}
+";
+    let fixes = error_actions_for(&modules, src);
+    assert_eq!(
+        apply(src, &fixes.actions, "Add missing patterns"),
+        "import lib/pets.{Pet, Cat}
+
+pub fn main(pet: Pet) {
+  let name = case pet {
+    Cat(name, ..) -> name
+    pets.Dog(value_1, value_2) -> todo
+    pets.Fish -> todo
+  }
+  name
+}
+"
+    );
+
+    let src = "type Shape { Circle(radius: Float) Square(side: Float) }
+
+pub fn area(shape) {
+  case shape { Circle(radius) -> radius }
+}
+";
+    let fixes = error_actions_for(&[], src);
+    assert_eq!(
+        apply(src, &fixes.actions, "Add missing patterns"),
+        "type Shape { Circle(radius: Float) Square(side: Float) }
+
+pub fn area(shape) {
+  case shape { Circle(radius) -> radius
+    Square(side) -> todo
+  }
+}
+"
+    );
+}
+
+#[test]
+fn inlay_hints_for_types_and_labels() {
+    let src = "import lib/util
+
+pub fn main() {
+  let name = \"Lucy\"
+  let greeting = util.greet(name, 3)
+  greeting
+}
+
+fn add(first a, second b) { a + b }
+
+pub fn sum() -> Int {
+  assert total: Int = add(1, second: 2)
+  total
+}
+";
+    let modules = [
+        (
+            "lib/util",
+            "pub fn greet(name name: String, times times: Int) -> String { name }",
+        ),
+        ("app", src),
+    ];
+    let (compiled, _) = compile_project(&modules);
+    let importable = compiled
+        .iter()
+        .map(|(module, _)| (module.name.join("/"), module.type_info.clone()))
+        .collect();
+    let (module, _) = compiled.last().expect("no app module");
+    let range = SrcSpan {
+        start: 0,
+        end: src.len() as u32,
+    };
+    let hints: Vec<_> = inlay_hints(module, src, &importable, range)
+        .into_iter()
+        .map(|hint| {
+            let before = src.get(..hint.position as usize).unwrap_or_default();
+            let line = before.lines().last().unwrap_or_default().to_string();
+            (line, hint.label, hint.kind)
+        })
+        .collect();
+    assert_eq!(
+        hints,
+        vec![
+            (
+                "pub fn main()".into(),
+                "-> String".into(),
+                InlayHintKind::ReturnType
+            ),
+            (
+                "  let name".into(),
+                ": String".into(),
+                InlayHintKind::BindingType
+            ),
+            (
+                "  let greeting".into(),
+                ": String".into(),
+                InlayHintKind::BindingType
+            ),
+            (
+                "  let greeting = util.greet(name, ".into(),
+                "times:".into(),
+                InlayHintKind::ArgumentLabel
+            ),
+            (
+                "fn add(first a, second b)".into(),
+                "-> Int".into(),
+                InlayHintKind::ReturnType
+            ),
+            (
+                "  assert total: Int = add(".into(),
+                "first:".into(),
+                InlayHintKind::ArgumentLabel
+            ),
+        ]
+    );
+
+    // Only the hints within the range are given
+    let end = src.find(" {").expect("no function body") as u32;
+    let range = SrcSpan { start: 0, end };
+    assert_eq!(inlay_hints(module, src, &importable, range).len(), 1);
+}
+
+#[test]
+fn semantic_tokens_classify_names() {
+    let src = "import lib/pets.{Cat, Pet, adopt} as p
+
+const default: Pet = Cat(name: \"Nubi\")
+
+pub fn main(pet: p.Pet) -> Pet {
+  let adopted = adopt(pet)
+  case adopted {
+    p.Dog(..) -> default
+    Cat(name: n) -> p.rename(adopted, to: n)
+  }
+}
+";
+    let modules = [
+        (
+            "lib/pets",
+            "pub type Pet { Cat(name: String) Dog(name: String) }
+pub fn adopt(pet: Pet) -> Pet { pet }
+pub fn rename(pet: Pet, to name: String) -> Pet { Cat(name) }",
+        ),
+        ("app", src),
+    ];
+    let (compiled, _) = compile_project(&modules);
+    let importable = compiled
+        .iter()
+        .map(|(module, _)| (module.name.join("/"), module.type_info.clone()))
+        .collect();
+    let (module, _) = compiled.last().expect("no app module");
+    let tokens: Vec<_> = semantic_tokens(module, src, &importable)
+        .into_iter()
+        .map(|token| {
+            let start = token.location.start as usize;
+            let end = token.location.end as usize;
+            (src.get(start..end).unwrap_or_default(), token.kind)
+        })
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            ("lib/pets", SemanticTokenKind::Module),
+            ("Cat", SemanticTokenKind::Constructor),
+            ("Pet", SemanticTokenKind::Type),
+            ("adopt", SemanticTokenKind::Function),
+            ("p", SemanticTokenKind::Module),
+            ("default", SemanticTokenKind::Constant),
+            ("Pet", SemanticTokenKind::Type),
+            ("Cat", SemanticTokenKind::Constructor),
+            ("name", SemanticTokenKind::Label),
+            ("main", SemanticTokenKind::Function),
+            ("pet", SemanticTokenKind::Parameter),
+            ("p", SemanticTokenKind::Module),
+            ("Pet", SemanticTokenKind::Type),
+            ("Pet", SemanticTokenKind::Type),
+            ("adopted", SemanticTokenKind::Variable),
+            ("adopt", SemanticTokenKind::Function),
+            ("pet", SemanticTokenKind::Parameter),
+            ("adopted", SemanticTokenKind::Variable),
+            ("p", SemanticTokenKind::Module),
+            ("Dog", SemanticTokenKind::Constructor),
+            ("default", SemanticTokenKind::Constant),
+            ("Cat", SemanticTokenKind::Constructor),
+            ("name", SemanticTokenKind::Label),
+            ("n", SemanticTokenKind::Variable),
+            ("p", SemanticTokenKind::Module),
+            ("rename", SemanticTokenKind::Function),
+            ("adopted", SemanticTokenKind::Variable),
+            ("to", SemanticTokenKind::Label),
+            ("n", SemanticTokenKind::Variable),
+        ]
+    );
+}
+
+#[test]
+fn signature_help_tracks_labelled_arguments() {
+    let util = "pub fn greet(
+  /// Who to greet.
+  name name: String,
+  times times: Int,
+) -> String { name }
+";
+    let src = "import lib/util
+
+pub fn main() {
+  util.greet(\"Lucy\", 1)
+}
+
+fn add(first a, second b) { a + b }
+";
+    let modules = [("lib/util", util), ("app", src)];
+    let (compiled, _) = compile_project(&modules);
+    let importable = compiled
+        .iter()
+        .map(|(module, _)| (module.name.join("/"), module.type_info.clone()))
+        .collect();
+    let (module, _) = compiled.last().expect("no app module");
+
+    // The help for the call being written at the end of the main function
+    let help = |call: &str| {
+        let edited = src.replace("  util.greet(\"Lucy\", 1)\n", &format!("  {}\n", call));
+        let cursor = edited.find(call).expect("find call") + call.len();
+        signature_help(module, &edited, cursor as u32, &importable)
+            .map(|help| (help.label, help.active_parameter))
+    };
+    let greet = "greet(name: String, times: Int) -> String".to_string();
+    assert_eq!(help("util.greet("), Some((greet.clone(), Some(0))));
+    assert_eq!(
+        help("util.greet(\"Lucy\", "),
+        Some((greet.clone(), Some(1)))
+    );
+    assert_eq!(
+        help("util.greet(times: 1, "),
+        Some((greet.clone(), Some(0)))
+    );
+    assert_eq!(help("util.greet(times: "), Some((greet.clone(), Some(1))));
+    assert_eq!(
+        help("\"Lucy\" |> util.greet("),
+        Some((greet.clone(), Some(1)))
+    );
+    assert_eq!(
+        help("util.greet([add(1, "),
+        Some(("add(first: Int, second: Int) -> Int".to_string(), Some(1)))
+    );
+    assert_eq!(help("util.greet(\"Lucy\", 1, "), Some((greet, None)));
+    assert_eq!(help("#("), None);
+
+    let found = signature_help(
+        module,
+        src,
+        src.find("1)").expect("find") as u32,
+        &importable,
+    )
+    .expect("signature help");
+    assert_eq!(found.module, "lib/util");
+    assert_eq!(
+        found
+            .parameters
+            .iter()
+            .map(|span| found.label.get(span.start as usize..span.end as usize))
+            .collect::<Vec<_>>(),
+        vec![Some("name: String"), Some("times: Int")]
+    );
+    assert_eq!(
+        parameter_documentation(util, found.location),
+        vec![Some("Who to greet.".to_string()), None]
+    );
+}
+
+#[test]
+fn code_lenses_for_tests_and_main() {
+    let src = "pub fn main() { Nil }
+
+pub fn add_test() { Nil }
+
+fn private_test() { Nil }
+
+pub fn argument_test(x) { x }
+";
+    let lenses = |name: &str, origin| {
+        let (compiled, _) = compile_project(&[(name, src)]);
+        let (module, _) = compiled.last().expect("no module");
+        code_lenses(module, origin, "app")
+            .into_iter()
+            .map(|lens| {
+                let line = src.get(lens.location.start as usize..).unwrap_or_default();
+                (
+                    line.lines().next().unwrap_or_default().to_string(),
+                    lens.runnable,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        lenses("app", Origin::Src),
+        vec![("pub fn main() { Nil }".to_string(), Runnable::Main)]
+    );
+    assert_eq!(lenses("app/other", Origin::Src), vec![]);
+    assert_eq!(
+        lenses("app_test", Origin::Test),
+        vec![(
+            "pub fn add_test() { Nil }".to_string(),
+            Runnable::Test(TestFunction {
+                module: "app_test".to_string(),
+                function: "add_test".to_string(),
+            })
+        )]
+    );
+}
@@ -54,28 +54,47 @@ pub mod ast;
 pub mod bit_string;
 pub mod build;
 pub mod codegen;
+pub mod compiler;
 pub mod config;
 pub mod cplusplus;
+pub mod derive;
 pub mod diagnostic;
 pub mod docs;
+pub mod elixir;
 pub mod erlang;
 pub mod error;
+pub mod fix;
+pub mod float;
 pub mod format;
+pub mod go;
 pub mod hex;
+pub mod int;
+pub mod interface;
 pub mod io;
 pub mod ir;
 pub mod javascript;
+pub mod language_server;
+pub mod licences;
 pub mod line_numbers;
+pub mod lint;
+pub mod llvm;
 pub mod manifest;
 pub mod metadata;
+pub mod name;
+pub mod parallel;
 pub mod parse;
 pub mod paths;
 pub mod pretty;
+pub mod repl;
+pub mod signatures;
+pub mod todos;
 pub mod type_;
 pub mod uid;
 pub mod version;
 pub mod warning;
+pub mod wasm;
 
+pub use compiler::{Compiled, Compiler};
 pub use error::{Error, Result};
 pub use warning::Warning;
 
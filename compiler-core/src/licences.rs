@@ -0,0 +1,523 @@
+//! The licences of the packages a project depends upon, as shown by the
+//! `gleam deps licenses` command and checked against the
+//! `[dependency-licences]` policy in gleam.toml.
+
+use crate::{
+    config::{LicencePolicy, PackageConfig},
+    version::COMPILER_VERSION,
+};
+use hexpm::version::Version;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+
+#[derive(Debug, strum::EnumString, strum::EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum LicenceFormat {
+    /// A table with the name, version, and licences of each package.
+    Text,
+    /// A JSON array with the name, version, and licences of each package.
+    Json,
+    /// A CSV file with the name, version, and licences of each package.
+    Csv,
+    /// An SPDX 2.3 JSON software bill of materials.
+    Spdx,
+    /// A `CycloneDX` 1.4 JSON software bill of materials.
+    CycloneDx,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageLicences {
+    pub name: String,
+    pub version: Version,
+    /// Empty if the licences of the package could not be found.
+    pub licences: Vec<String>,
+}
+
+impl PackageLicences {
+    /// The licences of the package as a single SPDX license expression, or
+    /// `NOASSERTION` if they are not known.
+    ///
+    /// Licences that are not valid SPDX identifiers or expressions are
+    /// replaced with the closest known identifier where there is one, and
+    /// with a `LicenseRef-` reference otherwise.
+    pub fn spdx_expression(&self) -> String {
+        let expressions: Vec<_> = self.licences.iter().map(|l| spdx_expression(l)).collect();
+        match expressions.as_slice() {
+            [] => "NOASSERTION".into(),
+            [expression] => expression.clone(),
+            _ => expressions
+                .iter()
+                .map(|expression| {
+                    if expression.contains(' ') {
+                        format!("({})", expression)
+                    } else {
+                        expression.clone()
+                    }
+                })
+                .join(" OR "),
+        }
+    }
+
+    /// The package URL of the package on Hex.
+    pub fn purl(&self) -> String {
+        format!("pkg:hex/{}@{}", self.name, self.version)
+    }
+}
+
+fn spdx_expression(licence: &str) -> String {
+    if spdx::Expression::parse(licence).is_ok() {
+        return licence.to_string();
+    }
+    if let Some((id, _)) = spdx::imprecise_license_id(licence) {
+        return id.name.to_string();
+    }
+    let reference: String = licence
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("LicenseRef-{}", reference)
+}
+
+/// Read the licences from the `metadata.config` file of a Hex package, which
+/// holds Erlang terms such as:
+///
+/// ```erlang
+/// {<<"licenses">>, [<<"Apache-2.0">>]}.
+/// ```
+pub fn from_hex_metadata(metadata: &str) -> Vec<String> {
+    lazy_static! {
+        static ref LICENCES: Regex =
+            Regex::new(r#"\{\s*<<"licenses"(?:/utf8)?>>\s*,\s*\[([^\]]*)\]\s*\}"#)
+                .expect("licences regex");
+        static ref BINARY: Regex =
+            Regex::new(r#"<<"((?:[^"\\]|\\.)*)"(?:/utf8)?>>"#).expect("binary regex");
+    }
+    let list = match LICENCES.captures(metadata) {
+        Some(captures) => captures.get(1).map(|m| m.as_str()).unwrap_or_default(),
+        None => return vec![],
+    };
+    BINARY
+        .captures_iter(list)
+        .filter_map(|captures| captures.get(1))
+        .map(|m| m.as_str().replace("\\\"", "\"").replace("\\\\", "\\"))
+        .collect()
+}
+
+/// Recognise the licence of a LICENSE file bundled with a package, for
+/// packages whose metadata does not list their licences. Only the most common
+/// licences are recognised.
+pub fn from_licence_file(text: &str) -> Option<String> {
+    let text = text.split_whitespace().join(" ").to_lowercase();
+    let has = |phrase: &str| text.contains(phrase);
+    let licence = if has("apache license") && has("version 2.0") {
+        "Apache-2.0"
+    } else if has("mozilla public license version 2.0") {
+        "MPL-2.0"
+    } else if has("gnu lesser general public license") && has("version 3") {
+        "LGPL-3.0-only"
+    } else if has("gnu general public license") && has("version 3") {
+        "GPL-3.0-only"
+    } else if has("gnu general public license") && has("version 2") {
+        "GPL-2.0-only"
+    } else if has("permission is hereby granted, free of charge") {
+        "MIT"
+    } else if has("permission to use, copy, modify, and/or distribute this software") {
+        "ISC"
+    } else if has("redistribution and use in source and binary forms") {
+        if has("neither the name") || has("names of its contributors") {
+            "BSD-3-Clause"
+        } else {
+            "BSD-2-Clause"
+        }
+    } else if has("this is free and unencumbered software released into the public domain") {
+        "Unlicense"
+    } else {
+        return None;
+    };
+    Some(licence.to_string())
+}
+
+/// The packages that are not allowed by the licence policy.
+pub fn policy_violations(
+    policy: &LicencePolicy,
+    packages: &[PackageLicences],
+) -> Vec<PackageLicences> {
+    if !policy.is_enforced() {
+        return vec![];
+    }
+    packages
+        .iter()
+        .filter(|package| !policy.allows(&package.licences))
+        .cloned()
+        .collect()
+}
+
+pub fn render(
+    format: LicenceFormat,
+    config: &PackageConfig,
+    packages: &[PackageLicences],
+    created: &str,
+) -> String {
+    match format {
+        LicenceFormat::Text => text(packages),
+        LicenceFormat::Json => json(packages),
+        LicenceFormat::Csv => csv(packages),
+        LicenceFormat::Spdx => spdx_document(config, packages, created),
+        LicenceFormat::CycloneDx => cyclonedx_document(config, packages, created),
+    }
+}
+
+fn licences_or_unknown(package: &PackageLicences) -> String {
+    if package.licences.is_empty() {
+        "unknown".to_string()
+    } else {
+        package.licences.join(", ")
+    }
+}
+
+fn text(packages: &[PackageLicences]) -> String {
+    let rows: Vec<_> = packages
+        .iter()
+        .map(|package| {
+            (
+                package.name.as_str(),
+                package.version.to_string(),
+                licences_or_unknown(package),
+            )
+        })
+        .collect();
+    let name_width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0).max(4);
+    let version_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0).max(7);
+    std::iter::once(("Name", "Version".to_string(), "Licences".to_string()))
+        .chain(rows)
+        .map(|(name, version, licences)| {
+            format!(
+                "{:name_width$}  {:version_width$}  {}\n",
+                name,
+                version,
+                licences,
+                name_width = name_width,
+                version_width = version_width,
+            )
+        })
+        .collect()
+}
+
+fn json(packages: &[PackageLicences]) -> String {
+    let packages: Vec<_> = packages
+        .iter()
+        .map(|package| {
+            json!({
+                "name": package.name,
+                "version": package.version.to_string(),
+                "licences": package.licences,
+                "spdx": package.spdx_expression(),
+            })
+        })
+        .collect();
+    format!("{:#}\n", json!(packages))
+}
+
+fn csv(packages: &[PackageLicences]) -> String {
+    let field = |value: &str| {
+        if value.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    };
+    std::iter::once("name,version,licences\n".to_string())
+        .chain(packages.iter().map(|package| {
+            format!(
+                "{},{},{}\n",
+                field(&package.name),
+                field(&package.version.to_string()),
+                field(&package.licences.join(", ")),
+            )
+        }))
+        .collect()
+}
+
+fn spdx_id(name: &str) -> String {
+    format!("SPDXRef-Package-{}", name.replace('_', "-"))
+}
+
+fn spdx_document(config: &PackageConfig, packages: &[PackageLicences], created: &str) -> String {
+    let root = json!({
+        "name": config.name,
+        "SPDXID": spdx_id(&config.name),
+        "versionInfo": config.version.to_string(),
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "licenseConcluded": "NOASSERTION",
+        "licenseDeclared": PackageLicences {
+            name: config.name.clone(),
+            version: config.version.clone(),
+            licences: config.licences.iter().map(|l| l.to_string()).collect(),
+        }
+        .spdx_expression(),
+    });
+    let dependencies = packages.iter().map(|package| {
+        json!({
+            "name": package.name,
+            "SPDXID": spdx_id(&package.name),
+            "versionInfo": package.version.to_string(),
+            "downloadLocation": format!(
+                "https://repo.hex.pm/tarballs/{}-{}.tar",
+                package.name, package.version
+            ),
+            "filesAnalyzed": false,
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": package.spdx_expression(),
+            "externalRefs": [{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": package.purl(),
+            }],
+        })
+    });
+    let describes = json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": spdx_id(&config.name),
+    });
+    let depends_on = packages.iter().map(|package| {
+        json!({
+            "spdxElementId": spdx_id(&config.name),
+            "relationshipType": "DEPENDS_ON",
+            "relatedSpdxElement": spdx_id(&package.name),
+        })
+    });
+    let document = json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": format!("{}-{}", config.name, config.version),
+        "documentNamespace": format!(
+            "https://spdx.org/spdxdocs/{}-{}-{}",
+            config.name, config.version, created
+        ),
+        "creationInfo": {
+            "created": created,
+            "creators": [format!("Tool: gleam-{}", COMPILER_VERSION)],
+        },
+        "packages": std::iter::once(root).chain(dependencies).collect::<Vec<_>>(),
+        "relationships": std::iter::once(describes).chain(depends_on).collect::<Vec<_>>(),
+    });
+    format!("{:#}\n", document)
+}
+
+fn cyclonedx_licences(licences: &[String]) -> serde_json::Value {
+    let licences: Vec<_> = licences
+        .iter()
+        .map(|licence| {
+            if spdx::license_id(licence).is_some() {
+                json!({ "license": { "id": licence } })
+            } else if spdx::Expression::parse(licence).is_ok() {
+                json!({ "expression": licence })
+            } else {
+                json!({ "license": { "name": licence } })
+            }
+        })
+        .collect();
+    json!(licences)
+}
+
+fn cyclonedx_document(
+    config: &PackageConfig,
+    packages: &[PackageLicences],
+    created: &str,
+) -> String {
+    let root_licences: Vec<_> = config.licences.iter().map(|l| l.to_string()).collect();
+    let components: Vec<_> = packages
+        .iter()
+        .map(|package| {
+            json!({
+                "type": "library",
+                "bom-ref": package.purl(),
+                "name": package.name,
+                "version": package.version.to_string(),
+                "purl": package.purl(),
+                "licenses": cyclonedx_licences(&package.licences),
+            })
+        })
+        .collect();
+    let document = json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.4",
+        "version": 1,
+        "metadata": {
+            "timestamp": created,
+            "tools": [{ "vendor": "Gleam", "name": "gleam", "version": COMPILER_VERSION }],
+            "component": {
+                "type": "application",
+                "bom-ref": config.name,
+                "name": config.name,
+                "version": config.version.to_string(),
+                "licenses": cyclonedx_licences(&root_licences),
+            },
+        },
+        "components": components,
+        "dependencies": [{
+            "ref": config.name,
+            "dependsOn": packages.iter().map(|package| package.purl()).collect::<Vec<_>>(),
+        }],
+    });
+    format!("{:#}\n", document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, licences: &[&str]) -> PackageLicences {
+        PackageLicences {
+            name: name.into(),
+            version: Version::new(1, 2, 3),
+            licences: licences.iter().map(|l| l.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn hex_metadata_licences() {
+        let metadata = r#"{<<"app">>, <<"thoas">>}.
+{<<"build_tools">>, [<<"rebar3">>]}.
+{<<"licenses">>, [<<"Apache-2.0">>, <<"MIT">>]}.
+{<<"name">>, <<"thoas">>}.
+"#;
+        assert_eq!(
+            from_hex_metadata(metadata),
+            vec!["Apache-2.0".to_string(), "MIT".to_string()]
+        );
+    }
+
+    #[test]
+    fn hex_metadata_utf8_licences() {
+        let metadata = r#"{<<"licenses">>,[<<"Apache 2.0"/utf8>>]}."#;
+        assert_eq!(from_hex_metadata(metadata), vec!["Apache 2.0".to_string()]);
+    }
+
+    #[test]
+    fn hex_metadata_without_licences() {
+        assert_eq!(
+            from_hex_metadata(r#"{<<"app">>, <<"x">>}."#),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn spdx_expressions() {
+        assert_eq!(package("a", &[]).spdx_expression(), "NOASSERTION");
+        assert_eq!(package("a", &["MIT"]).spdx_expression(), "MIT");
+        assert_eq!(
+            package("a", &["Apache 2.0"]).spdx_expression(),
+            "Apache-2.0"
+        );
+        assert_eq!(
+            package("a", &["MIT", "Apache-2.0 AND ISC"]).spdx_expression(),
+            "MIT OR (Apache-2.0 AND ISC)"
+        );
+        assert_eq!(
+            package("a", &["My Own Licence"]).spdx_expression(),
+            "LicenseRef-My-Own-Licence"
+        );
+    }
+
+    #[test]
+    fn text_format() {
+        let packages = vec![package("gleam_stdlib", &["Apache-2.0"]), package("x", &[])];
+        assert_eq!(
+            text(&packages),
+            "Name          Version  Licences
+gleam_stdlib  1.2.3    Apache-2.0
+x             1.2.3    unknown
+"
+        );
+    }
+
+    #[test]
+    fn json_format() {
+        let packages = vec![package("a", &["MIT", "Apache 2.0"])];
+        let output: serde_json::Value = serde_json::from_str(&json(&packages)).expect("json");
+        assert_eq!(
+            output,
+            json!([{
+                "name": "a",
+                "version": "1.2.3",
+                "licences": ["MIT", "Apache 2.0"],
+                "spdx": "MIT OR Apache-2.0",
+            }])
+        );
+    }
+
+    #[test]
+    fn csv_format() {
+        let packages = vec![
+            package("a", &["MIT", "Apache-2.0"]),
+            package("b", &["My \"Licence\""]),
+            package("c", &[]),
+        ];
+        assert_eq!(
+            csv(&packages),
+            "name,version,licences
+a,1.2.3,\"MIT, Apache-2.0\"
+b,1.2.3,\"My \"\"Licence\"\"\"
+c,1.2.3,
+"
+        );
+    }
+
+    #[test]
+    fn licence_files() {
+        let mit = "MIT License
+
+Copyright (c) 2022 Someone
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software";
+        assert_eq!(from_licence_file(mit), Some("MIT".into()));
+        let apache = "
+                                 Apache License
+                           Version 2.0, January 2004
+                        http://www.apache.org/licenses/";
+        assert_eq!(from_licence_file(apache), Some("Apache-2.0".into()));
+        let bsd = "Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the following conditions are met:
+3. Neither the name of the copyright holder nor the names of its contributors";
+        assert_eq!(from_licence_file(bsd), Some("BSD-3-Clause".into()));
+        assert_eq!(from_licence_file("All rights reserved."), None);
+    }
+
+    #[test]
+    fn violations() {
+        let config: PackageConfig = toml::from_str(
+            r#"
+name = "app"
+
+[dependency-licences]
+allow = ["Apache-2.0"]
+"#,
+        )
+        .expect("config");
+        let packages = vec![
+            package("a", &["Apache-2.0"]),
+            package("b", &["GPL-3.0-only"]),
+            package("c", &[]),
+        ];
+        assert_eq!(
+            policy_violations(&config.dependency_licences, &packages),
+            vec![package("b", &["GPL-3.0-only"]), package("c", &[])]
+        );
+    }
+
+    #[test]
+    fn no_violations_without_policy() {
+        let packages = vec![package("b", &["GPL-3.0-only"])];
+        assert_eq!(
+            policy_violations(&LicencePolicy::default(), &packages),
+            vec![]
+        );
+    }
+}
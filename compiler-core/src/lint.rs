@@ -0,0 +1,436 @@
+//! Optional checks of the style of the code in a package, run over the typed
+//! AST once a module has compiled successfully.
+//!
+//! Each rule is enabled by giving it a table in the `[lints]` section of
+//! gleam.toml, which may set the limits the rule uses:
+//!
+//! ```toml
+//! [lints.deep_nesting]
+//! max_depth = 3
+//!
+//! [lints.long_function]
+//! [lints.nested_calls]
+//! [lints.bool_case]
+//! ```
+//!
+//! Lints are reported as warnings, so they can be allowed or denied with the
+//! `[warnings]` section like any other warning without a kind.
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        CallArg, Pattern, SrcSpan, Statement, TypedClause, TypedExpr, TypedModule, TypedStatement,
+    },
+    language_server::{CodeAction, TextEdit},
+    line_numbers::LineNumbers,
+    type_::{ModuleValueConstructor, ValueConstructorVariant},
+};
+use serde::Deserialize;
+
+/// The `[lints]` section of gleam.toml. Rules without a table are disabled.
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    pub deep_nesting: Option<DeepNesting>,
+    pub long_function: Option<LongFunction>,
+    pub nested_calls: Option<NestedCalls>,
+    pub bool_case: Option<BoolCase>,
+}
+
+impl LintConfig {
+    pub fn is_enabled(&self) -> bool {
+        self != &Self::default()
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct DeepNesting {
+    /// The number of `case` expressions and anonymous functions that may be
+    /// nested within each other.
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct LongFunction {
+    /// The number of lines a function may span, including its head.
+    #[serde(default = "default_max_lines")]
+    pub max_lines: u32,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct NestedCalls {
+    /// The number of function calls that may be nested within the first
+    /// argument of each other before a pipeline is suggested.
+    #[serde(default = "default_max_nested_calls")]
+    pub max_depth: usize,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct BoolCase {}
+
+fn default_max_depth() -> usize {
+    4
+}
+
+fn default_max_lines() -> u32 {
+    50
+}
+
+fn default_max_nested_calls() -> usize {
+    2
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintRule {
+    DeepNesting,
+    LongFunction,
+    NestedCalls,
+    BoolCase,
+}
+
+impl LintRule {
+    /// The name of the rule's table in the `[lints]` section of gleam.toml.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DeepNesting => "deep_nesting",
+            Self::LongFunction => "long_function",
+            Self::NestedCalls => "nested_calls",
+            Self::BoolCase => "bool_case",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub rule: LintRule,
+    pub location: SrcSpan,
+    pub label: String,
+    pub hint: String,
+    /// Edits that resolve the lint, if it can be done mechanically.
+    pub fix: Option<CodeAction>,
+}
+
+/// The lints of the module for the enabled rules, in the order they appear
+/// in the source code.
+pub fn module_lints(module: &TypedModule, src: &str, config: &LintConfig) -> Vec<Lint> {
+    let mut linter = Linter {
+        src,
+        config,
+        line_numbers: LineNumbers::new(src),
+        depth: 0,
+        tail_cases: vec![],
+        lints: vec![],
+    };
+    linter.visit_typed_module(module);
+    let mut lints = linter.lints;
+    lints.sort_by_key(|lint| lint.location.start);
+    lints
+}
+
+struct Linter<'a> {
+    src: &'a str,
+    config: &'a LintConfig,
+    line_numbers: LineNumbers,
+    /// The number of `case` expressions and anonymous functions enclosing the
+    /// expression being visited.
+    depth: usize,
+    /// The start of each `case` expression that is the last expression of a
+    /// function, where it could be replaced by a `use` expression.
+    tail_cases: Vec<u32>,
+    lints: Vec<Lint>,
+}
+
+impl<'ast> Visit<'ast> for Linter<'_> {
+    fn visit_typed_statement(&mut self, statement: &'ast TypedStatement) {
+        if let Statement::Fn {
+            location,
+            end_position,
+            body,
+            ..
+        } = statement
+        {
+            self.check_function_length(*location, *end_position);
+            self.mark_tail_case(body);
+        }
+        visit::visit_typed_statement(self, statement);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        match expr {
+            TypedExpr::Case {
+                location,
+                subjects,
+                clauses,
+                ..
+            } => {
+                self.check_bool_case(*location, subjects, clauses);
+                self.nested(*location, "case", |linter| {
+                    visit::visit_typed_expr(linter, expr)
+                });
+            }
+
+            TypedExpr::Fn {
+                location,
+                is_capture: false,
+                body,
+                ..
+            } => {
+                self.mark_tail_case(body);
+                self.nested(*location, "fn", |linter| {
+                    visit::visit_typed_expr(linter, expr)
+                });
+            }
+
+            TypedExpr::Call { .. } => self.check_nested_calls(expr),
+
+            _ => visit::visit_typed_expr(self, expr),
+        }
+    }
+
+    fn visit_typed_call_arg(&mut self, arg: &'ast CallArg<TypedExpr>) {
+        // The callback of a `use` expression is not nested in the source code
+        match &arg.value {
+            TypedExpr::Fn { args, body, .. } if arg.implicit => {
+                for arg in args {
+                    self.visit_typed_arg(arg);
+                }
+                self.mark_tail_case(body);
+                self.visit_typed_expr(body);
+            }
+            _ => visit::visit_typed_call_arg(self, arg),
+        }
+    }
+}
+
+impl Linter<'_> {
+    fn text(&self, location: SrcSpan) -> &str {
+        self.src
+            .get(location.start as usize..location.end as usize)
+            .unwrap_or_default()
+    }
+
+    fn mark_tail_case(&mut self, body: &TypedExpr) {
+        let last = match body {
+            TypedExpr::Sequence { expressions, .. } => expressions.last(),
+            _ => Some(body),
+        };
+        if let Some(TypedExpr::Case { location, .. }) = last {
+            self.tail_cases.push(location.start);
+        }
+    }
+
+    fn check_function_length(&mut self, location: SrcSpan, end_position: u32) {
+        let max_lines = match self.config.long_function {
+            Some(rule) => rule.max_lines,
+            None => return,
+        };
+        let lines = self.line_numbers.line_number(end_position)
+            - self.line_numbers.line_number(location.start)
+            + 1;
+        if lines > max_lines {
+            self.lints.push(Lint {
+                rule: LintRule::LongFunction,
+                location,
+                label: format!("This function is {} lines long", lines),
+                hint: format!(
+                    "Functions longer than {} lines are hard to follow. Consider \
+splitting it into smaller functions.",
+                    max_lines
+                ),
+                fix: None,
+            });
+        }
+    }
+
+    // Visit the children of a `case` expression or anonymous function one level
+    // deeper, reporting the outermost expression that is nested too deeply.
+    fn nested(&mut self, location: SrcSpan, keyword: &str, visit: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        if let Some(rule) = self.config.deep_nesting {
+            if self.depth == rule.max_depth + 1 {
+                self.lints.push(Lint {
+                    rule: LintRule::DeepNesting,
+                    location: SrcSpan {
+                        start: location.start,
+                        end: location.start + keyword.len() as u32,
+                    },
+                    label: format!("This is nested {} levels deep", self.depth),
+                    hint: format!(
+                        "Code nested more than {} levels deep is hard to follow. \
+Consider moving some of it into a separate function, or returning early with `use`.",
+                        rule.max_depth
+                    ),
+                    fix: None,
+                });
+            }
+        }
+        visit(self);
+        self.depth -= 1;
+    }
+
+    fn check_bool_case(
+        &mut self,
+        location: SrcSpan,
+        subjects: &[TypedExpr],
+        clauses: &[TypedClause],
+    ) {
+        if self.config.bool_case.is_none() || !self.tail_cases.contains(&location.start) {
+            return;
+        }
+        let (subject, true_clause, false_clause) = match (subjects, clauses) {
+            ([subject], [first, second]) if subject.type_().is_bool() => {
+                match (bool_pattern(first), bool_pattern(second)) {
+                    (Some(true), Some(false)) => (subject, first, second),
+                    (Some(false), Some(true)) => (subject, second, first),
+                    _ => return,
+                }
+            }
+            _ => return,
+        };
+
+        // A guard only helps when one branch returns early and the other
+        // continues with more code, which can then be written unindented.
+        let is_block = |clause: &TypedClause| matches!(clause.then, TypedExpr::Sequence { .. });
+        let is_simple = |clause: &TypedClause| {
+            !matches!(
+                clause.then,
+                TypedExpr::Sequence { .. } | TypedExpr::Case { .. }
+            )
+        };
+        let (when, early) = if is_simple(true_clause) && is_block(false_clause) {
+            (self.text(subject.location()).to_string(), true_clause)
+        } else if is_simple(false_clause) && is_block(true_clause) {
+            let subject_text = self.text(subject.location());
+            let when = match subject {
+                TypedExpr::Var { .. } | TypedExpr::Call { .. } => format!("!{}", subject_text),
+                _ => format!("!{{ {} }}", subject_text),
+            };
+            (when, false_clause)
+        } else {
+            return;
+        };
+
+        self.lints.push(Lint {
+            rule: LintRule::BoolCase,
+            location: SrcSpan {
+                start: location.start,
+                end: location.start + 4,
+            },
+            label: "This case could be a `bool.guard`".into(),
+            hint: format!(
+                "Return early with `use <- bool.guard(when: {}, return: {})` from the \
+`gleam/bool` module and continue with the rest of the code unindented.",
+                when,
+                self.text(early.then.location())
+            ),
+            fix: None,
+        });
+    }
+
+    fn check_nested_calls(&mut self, call: &TypedExpr) {
+        let max_depth = match self.config.nested_calls {
+            Some(rule) => rule.max_depth,
+            None => return visit::visit_typed_expr(self, call),
+        };
+
+        // The calls that are each the first argument of the previous one,
+        // from the outermost inwards.
+        let mut chain = vec![];
+        let mut current = call;
+        while let Some((fun, args)) = piped_call(current) {
+            chain.push((fun, args));
+            match args.first() {
+                Some(first) => current = &first.value,
+                None => break,
+            }
+        }
+
+        let innermost = match chain.last().and_then(|(_, args)| args.first()) {
+            Some(first) if chain.len() > max_depth => first,
+            _ => return visit::visit_typed_expr(self, call),
+        };
+        let location = call.location();
+        let mut pipeline = self.text(innermost.location).to_string();
+        for (fun, args) in chain.iter().rev() {
+            pipeline.push_str(" |> ");
+            pipeline.push_str(self.text(fun.location()));
+            if args.len() > 1 {
+                let rest: Vec<_> = args
+                    .iter()
+                    .skip(1)
+                    .map(|arg| self.text(arg.location))
+                    .collect();
+                pipeline.push_str(&format!("({})", rest.join(", ")));
+            }
+        }
+        self.lints.push(Lint {
+            rule: LintRule::NestedCalls,
+            location,
+            label: format!("These {} calls are nested", chain.len()),
+            hint: format!("This can be written as a pipeline: `{}`", pipeline),
+            fix: Some(CodeAction {
+                title: "Rewrite as a pipeline".into(),
+                edits: vec![TextEdit {
+                    location,
+                    new_text: pipeline,
+                }],
+            }),
+        });
+
+        // Continue into the rest of the calls, without reporting the inner
+        // calls of the chain again.
+        for (fun, args) in &chain {
+            self.visit_typed_expr(fun);
+            for arg in args.iter().skip(1) {
+                self.visit_typed_call_arg(arg);
+            }
+        }
+        self.visit_typed_call_arg(innermost);
+    }
+}
+
+// The function and arguments of a call that could be written as a step of a
+// pipeline. Calls to record constructors are left alone as nesting them is
+// the usual way to build a value, and labelled arguments are skipped as they
+// may not be in the same order as in the source code.
+fn piped_call(expr: &TypedExpr) -> Option<(&TypedExpr, &[CallArg<TypedExpr>])> {
+    let (fun, args) = match expr {
+        TypedExpr::Call { fun, args, .. } => (fun.as_ref(), args),
+        _ => return None,
+    };
+    let is_record = match fun {
+        TypedExpr::Var { constructor, .. } => {
+            matches!(constructor.variant, ValueConstructorVariant::Record { .. })
+        }
+        TypedExpr::ModuleSelect { constructor, .. } => {
+            matches!(constructor, ModuleValueConstructor::Record { .. })
+        }
+        _ => false,
+    };
+    if is_record || args.iter().any(|arg| arg.label.is_some() || arg.implicit) {
+        return None;
+    }
+    Some((fun, args))
+}
+
+fn bool_pattern(clause: &TypedClause) -> Option<bool> {
+    if clause.guard.is_some() || !clause.alternative_patterns.is_empty() {
+        return None;
+    }
+    match clause.pattern.as_slice() {
+        [Pattern::Constructor { name, module, .. }] if module.is_none() => match name.as_str() {
+            "True" => Some(true),
+            "False" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests;
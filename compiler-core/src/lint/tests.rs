@@ -0,0 +1,211 @@
+use super::*;
+use crate::{
+    build::{Origin, Target},
+    type_::{build_prelude, infer_module},
+    uid::UniqueIdGenerator,
+};
+
+fn lints(config: LintConfig, src: &str) -> Vec<Lint> {
+    let ids = UniqueIdGenerator::new();
+    let mut modules = im::HashMap::new();
+    let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+    let arena = crate::ast::Arena::new();
+    let (ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+    let module = infer_module(
+        Target::Erlang,
+        &ids,
+        ast,
+        Origin::Src,
+        "thepackage",
+        &modules,
+        &mut vec![],
+    )
+    .expect("should successfully infer");
+    module_lints(&module, src, &config)
+}
+
+fn labels(lints: &[Lint]) -> Vec<&str> {
+    lints.iter().map(|lint| lint.label.as_str()).collect()
+}
+
+#[test]
+fn no_rules_enabled() {
+    let src = r#"
+fn id(x) { x }
+pub fn main() { id(id(id(id(1)))) }
+"#;
+    assert!(!LintConfig::default().is_enabled());
+    assert_eq!(lints(LintConfig::default(), src), vec![]);
+}
+
+#[test]
+fn deep_nesting() {
+    let config = LintConfig {
+        deep_nesting: Some(DeepNesting { max_depth: 2 }),
+        ..Default::default()
+    };
+    let src = r#"
+pub fn main(x) {
+  case x {
+    1 -> fn() {
+      case x {
+        2 -> case x { _ -> 3 }
+        _ -> 4
+      }
+    }
+    _ -> fn() { 5 }
+  }
+}
+"#;
+    let lints = lints(config, src);
+    assert_eq!(labels(&lints), vec!["This is nested 3 levels deep"]);
+    assert_eq!(
+        lints.first().map(|lint| lint.rule),
+        Some(LintRule::DeepNesting)
+    );
+}
+
+#[test]
+fn use_callbacks_are_not_nested() {
+    let config = LintConfig {
+        deep_nesting: Some(DeepNesting { max_depth: 1 }),
+        ..Default::default()
+    };
+    let src = r#"
+fn apply(f) { f(1) }
+pub fn main() {
+  use x <- apply
+  use y <- apply
+  case x { _ -> y }
+}
+"#;
+    assert_eq!(lints(config, src), vec![]);
+}
+
+#[test]
+fn long_function() {
+    let config = LintConfig {
+        long_function: Some(LongFunction { max_lines: 3 }),
+        ..Default::default()
+    };
+    let src = r#"
+pub fn short() {
+  1
+}
+
+pub fn long() {
+  1
+  2
+}
+"#;
+    let lints = lints(config, src);
+    assert_eq!(labels(&lints), vec!["This function is 4 lines long"]);
+    assert_eq!(
+        lints.first().map(|lint| lint.location),
+        Some(SrcSpan { start: 25, end: 38 })
+    );
+}
+
+#[test]
+fn nested_calls() {
+    let config = LintConfig {
+        nested_calls: Some(NestedCalls { max_depth: 2 }),
+        ..Default::default()
+    };
+    let src = r#"
+fn add(x, y) { x + y }
+fn id(x) { x }
+pub fn main() {
+  id(add(id(1), 2))
+  id(add(id(add(1, 2)), 3))
+}
+"#;
+    let lints = lints(config, src);
+    assert_eq!(
+        lints
+            .iter()
+            .map(|lint| lint.fix.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            Some(CodeAction {
+                title: "Rewrite as a pipeline".into(),
+                edits: vec![TextEdit {
+                    location: SrcSpan { start: 57, end: 74 },
+                    new_text: "1 |> id |> add(2) |> id".into(),
+                }],
+            }),
+            Some(CodeAction {
+                title: "Rewrite as a pipeline".into(),
+                edits: vec![TextEdit {
+                    location: SrcSpan {
+                        start: 77,
+                        end: 102
+                    },
+                    new_text: "1 |> add(2) |> id |> add(3) |> id".into(),
+                }],
+            }),
+        ]
+    );
+}
+
+#[test]
+fn nested_record_constructors_and_labels_are_not_linted() {
+    let config = LintConfig {
+        nested_calls: Some(NestedCalls { max_depth: 1 }),
+        ..Default::default()
+    };
+    let src = r#"
+fn sub(x x, y y) { x - y }
+pub fn main() {
+  let _ = Ok(Ok(Error(1)))
+  sub(y: sub(x: 1, y: 2), x: 3)
+}
+"#;
+    assert_eq!(lints(config, src), vec![]);
+}
+
+#[test]
+fn bool_case() {
+    let config = LintConfig {
+        bool_case: Some(BoolCase {}),
+        ..Default::default()
+    };
+    let src = r#"
+pub fn main(x) {
+  case x > 1 {
+    False -> 0
+    True -> {
+      let y = x * 2
+      y + 1
+    }
+  }
+}
+
+pub fn not_in_tail_position(x) {
+  let y = case x {
+    True -> 1
+    False -> {
+      let z = 2
+      z
+    }
+  }
+  y
+}
+
+pub fn both_simple(x) {
+  case x {
+    True -> 1
+    False -> 2
+  }
+}
+"#;
+    let lints = lints(config, src);
+    assert_eq!(labels(&lints), vec!["This case could be a `bool.guard`"]);
+    assert_eq!(
+        lints.first().map(|lint| lint.hint.as_str()),
+        Some(
+            "Return early with `use <- bool.guard(when: !{ x > 1 }, return: 0)` from the \
+`gleam/bool` module and continue with the rest of the code unindented."
+        )
+    );
+}
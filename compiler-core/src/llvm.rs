@@ -0,0 +1,474 @@
+//! An experimental code generator that compiles Gleam to textual LLVM IR,
+//! which is linked with the prelude and compiled to a native executable by
+//! `llc` and the system linker without needing a C++ toolchain.
+//!
+//! Functions are lowered through the [intermediate representation](crate::ir)
+//! shared with the C++ code generator, so `case` expressions are compiled to
+//! the same checks and bindings on both.
+//!
+//! Every Gleam value is an `i64`, either a 63-bit int shifted left by one bit
+//! with the lowest bit set or the address of an object allocated by the
+//! prelude. The layout of each kind of object is described in
+//! `templates/gleam.ll`. The module `gleam/list` is written to
+//! `gleam@list.ll` and its function `map` is the symbol `@"gleam/list.map"`.
+//!
+//! External functions are written in LLVM IR in `.ll` files in `src`, which
+//! are linked with the generated code, e.g. the external
+//! `@external(llvm, "ffi.ll", "add")` refers to the function `@add` defined
+//! in `src/ffi.ll`.
+
+mod exports;
+mod function;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+
+use crate::{
+    ast::{SrcSpan, Statement, TypedModule},
+    io::Utf8Writer,
+    ir::{self, IntermediateRepresentationConverter},
+};
+
+use function::FunctionWriter;
+
+pub use exports::component_exports;
+
+const PRELUDE: &str = include_str!("../templates/gleam.ll");
+
+/// The path of the prelude within the build directory.
+pub const PRELUDE_PATH: &str = "gleam.ll";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Unsupported { feature: String, location: SrcSpan },
+}
+
+impl From<ir::Error> for Error {
+    fn from(error: ir::Error) -> Self {
+        match error {
+            ir::Error::Unsupported { feature, location } => {
+                Error::Unsupported { feature, location }
+            }
+        }
+    }
+}
+
+/// The width of the addresses of the platform the code is compiled for. Gleam
+/// values are 64 bits wide on every platform, but constants holding addresses
+/// must write them as words of the platform's own width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerWidth {
+    Bits32,
+    Bits64,
+}
+
+impl PointerWidth {
+    fn size_type(self) -> &'static str {
+        match self {
+            PointerWidth::Bits32 => "i32",
+            PointerWidth::Bits64 => "i64",
+        }
+    }
+
+    /// The type of a word of a constant holding an address.
+    fn address_type(self) -> &'static str {
+        match self {
+            PointerWidth::Bits32 => "{ i32, i32 }",
+            PointerWidth::Bits64 => "i64",
+        }
+    }
+
+    /// A word of a constant holding the address of the typed pointer.
+    fn address(self, pointer: &str) -> String {
+        match self {
+            PointerWidth::Bits32 => {
+                format!("{{ i32, i32 }} {{ i32 ptrtoint ({pointer} to i32), i32 0 }}")
+            }
+            PointerWidth::Bits64 => format!("i64 ptrtoint ({pointer} to i64)"),
+        }
+    }
+}
+
+/// The prelude for the platform.
+pub fn prelude(pointer_width: PointerWidth) -> String {
+    PRELUDE.replace("SIZE_T", pointer_width.size_type())
+}
+
+/// The path of the LLVM IR file of the module.
+pub fn module_path(module: &str) -> PathBuf {
+    PathBuf::from(format!("{}.ll", module.replace('/', "@")))
+}
+
+/// A module defining the C `main` function, which calls the `main` function
+/// of the Gleam module.
+pub fn entrypoint(module: &str) -> String {
+    let main = function_symbol(module, "main");
+    format!(
+        "declare i64 {main}()
+
+define i32 @main() {{
+entry:
+  %0 = call i64 {main}()
+  ret i32 0
+}}
+"
+    )
+}
+
+pub fn module(
+    module: &TypedModule,
+    pointer_width: PointerWidth,
+    path: &Path,
+    src: &str,
+    writer: &mut impl Utf8Writer,
+) -> Result<(), crate::Error> {
+    let text = Generator::new(module.name.join("/"), pointer_width)
+        .compile(module)
+        .map_err(|error| crate::Error::Llvm {
+            path: path.to_path_buf(),
+            src: src.into(),
+            error,
+        })?;
+    writer.str_write(&text)
+}
+
+/// The symbol of a function defined in Gleam.
+fn function_symbol(module: &str, name: &str) -> String {
+    format!("@\"{module}.{name}\"")
+}
+
+/// A value of the Gleam type `fn(..) -> a` with the given number of
+/// arguments, called with the closure itself followed by the arguments.
+fn closure_function_type(arity: usize) -> String {
+    format!("i64 ({})*", vec!["i64"; arity + 1].join(", "))
+}
+
+/// The state shared by the functions of a module: the constants and
+/// declarations they need, and the functions that are generated for their
+/// anonymous functions.
+#[derive(Debug)]
+struct Generator {
+    pointer_width: PointerWidth,
+    name: String,
+    /// The globals defined by the module, by name.
+    globals: HashMap<String, String>,
+    declarations: BTreeSet<String>,
+    functions: Vec<String>,
+    /// Constants are numbered so that each has a unique name.
+    constants: usize,
+    lambdas: usize,
+}
+
+impl Generator {
+    fn new(name: String, pointer_width: PointerWidth) -> Self {
+        Self {
+            pointer_width,
+            name,
+            globals: HashMap::new(),
+            declarations: BTreeSet::new(),
+            functions: vec![],
+            constants: 0,
+            lambdas: 0,
+        }
+    }
+
+    fn compile(mut self, module: &TypedModule) -> Result<String, Error> {
+        for statement in &module.statements {
+            match statement {
+                Statement::Fn {
+                    name,
+                    arguments,
+                    body,
+                    public,
+                    ..
+                } => {
+                    let ir = IntermediateRepresentationConverter::new_for_function(
+                        &module.name,
+                        arguments,
+                    )
+                    .ast_to_ir(body)?;
+                    let parameters = arguments
+                        .iter()
+                        .map(|argument| {
+                            argument
+                                .names
+                                .get_variable_name()
+                                .map(|name| ir::Identifier::Named(name, 0))
+                        })
+                        .collect_vec();
+                    self.function(name, *public, parameters, ir)?;
+                }
+
+                Statement::ExternalFn {
+                    module, location, ..
+                } if !module.ends_with(".ll") => {
+                    return Err(Error::Unsupported {
+                        feature: "External functions not defined in a `.ll` file".into(),
+                        location: *location,
+                    })
+                }
+
+                Statement::ExternalFn { .. }
+                | Statement::TypeAlias { .. }
+                | Statement::CustomType { .. }
+                | Statement::ExternalType { .. }
+                | Statement::Import { .. }
+                | Statement::ModuleConstant { .. } => (),
+            }
+        }
+        Ok(self.finish())
+    }
+
+    /// The text of the module: its declarations, globals and functions.
+    fn finish(self) -> String {
+        let mut output = String::new();
+        for declaration in &self.declarations {
+            let _ = writeln!(output, "{declaration}");
+        }
+        for global in self.globals.values().sorted() {
+            let _ = writeln!(output, "{global}");
+        }
+        for function in &self.functions {
+            output.push('\n');
+            output.push_str(function);
+        }
+        output
+    }
+
+    /// A module function, along with a closure for when it is used as a
+    /// value.
+    fn function<'a>(
+        &mut self,
+        name: &str,
+        public: bool,
+        parameters: Vec<Option<ir::Identifier<'a>>>,
+        body: Vec<ir::Statement<'a>>,
+    ) -> Result<(), Error> {
+        let linkage = if public { "" } else { "internal " };
+        let arity = parameters.len();
+        let symbol = function_symbol(&self.name, name);
+        let mut writer = FunctionWriter::new(self);
+        let arguments = writer.parameters(parameters);
+        writer.statements(body)?;
+        let definition = writer.finish(&format!(
+            "define {linkage}i64 {symbol}({arguments})",
+            arguments = arguments.iter().map(|a| format!("i64 {a}")).join(", ")
+        ));
+        self.functions.push(definition);
+
+        let entry = function_symbol(&self.name, &format!("{name}$entry"));
+        let closure = function_symbol(&self.name, &format!("{name}$closure"));
+        self.functions
+            .push(closure_entry(linkage, &entry, &symbol, arity));
+        let pointer = format!("{} {entry}", closure_function_type(arity));
+        let _ = self.object(&closure, linkage, 4, &pointer);
+        Ok(())
+    }
+
+    /// Declares a function of another module or of the prelude.
+    fn declare(&mut self, declaration: String) {
+        let _ = self.declarations.insert(declaration);
+    }
+
+    /// The address of a closure calling a module function, which is defined
+    /// by this module or declared if it is defined by another.
+    fn function_closure(&mut self, module: &str, name: &str) -> String {
+        let closure = function_symbol(module, &format!("{name}$closure"));
+        let type_ = self.object_type();
+        if module != self.name {
+            self.declare(format!("{closure} = external constant {type_}"));
+        }
+        format!("ptrtoint ({type_}* {closure} to i64)")
+    }
+
+    /// Calls to an external function are declared with the number of
+    /// arguments they are given.
+    fn declare_function(&mut self, symbol: &str, arity: usize) {
+        self.declare(format!(
+            "declare i64 {symbol}({})",
+            vec!["i64"; arity].join(", ")
+        ));
+    }
+
+    /// The address of a closure calling an external function.
+    fn external_closure(&mut self, name: &str, arity: usize) -> String {
+        let symbol = format!("@\"{name}\"");
+        let entry = format!("@\"gleam.external.{name}$entry\"");
+        let closure = format!("@\"gleam.external.{name}$closure\"");
+        self.declare_function(&symbol, arity);
+        if !self.globals.contains_key(&closure) {
+            self.functions
+                .push(closure_entry("linkonce_odr ", &entry, &symbol, arity));
+        }
+        let pointer = format!("{} {entry}", closure_function_type(arity));
+        self.object(&closure, "linkonce_odr ", 4, &pointer)
+    }
+
+    /// The address of a string constant.
+    fn string(&mut self, bytes: &[u8]) -> String {
+        let global = self.constant("string");
+        let size = bytes.len();
+        let _ = self.globals.insert(
+            global.clone(),
+            format!(
+                "{global} = private unnamed_addr constant {{ i64, [{size} x i8] }} {{ i64 {header}, [{size} x i8] c\"{bytes}\" }}, align 8",
+                header = size << 8 | 2,
+                bytes = escape(bytes),
+            ),
+        );
+        format!("ptrtoint ({{ i64, [{size} x i8] }}* {global} to i64)")
+    }
+
+    /// The address of a float constant.
+    fn float(&mut self, value: f64) -> String {
+        let global = self.constant("float");
+        let _ = self.globals.insert(
+            global.clone(),
+            format!(
+                "{global} = private unnamed_addr constant {{ i64, double }} {{ i64 1, double 0x{:016X} }}, align 8",
+                value.to_bits()
+            ),
+        );
+        format!("ptrtoint ({{ i64, double }}* {global} to i64)")
+    }
+
+    fn constant(&mut self, kind: &str) -> String {
+        self.constants += 1;
+        format!("@\"{}.{kind}.{}\"", self.name, self.constants)
+    }
+
+    /// The tag of the records built with the named constructor, which is the
+    /// address of a string holding the name. Tags are defined by each module
+    /// using them and merged when the modules are linked.
+    fn tag(&mut self, name: &str) -> String {
+        format!("ptrtoint ({} to i64)", self.tag_pointer(name))
+    }
+
+    fn tag_pointer(&mut self, name: &str) -> String {
+        let global = format!("@\"gleam.tag.{name}\"");
+        let size = name.len();
+        let _ = self.globals.insert(
+            global.clone(),
+            format!(
+                "{global} = linkonce_odr constant {{ i64, [{size} x i8] }} {{ i64 {header}, [{size} x i8] c\"{bytes}\" }}, align 8",
+                header = size << 8 | 2,
+                bytes = escape(name.as_bytes()),
+            ),
+        );
+        format!("{{ i64, [{size} x i8] }}* {global}")
+    }
+
+    /// The address of the only record built by a constructor without fields.
+    fn singleton(&mut self, name: &str) -> String {
+        let tag = self.tag_pointer(name);
+        let global = format!("@\"gleam.record.{name}\"");
+        self.object(&global, "linkonce_odr ", 3, &tag)
+    }
+
+    fn empty_list(&mut self) -> String {
+        let tag = self.tag_pointer("List");
+        self.object("@\"gleam.list.empty\"", "linkonce_odr ", 3, &tag)
+    }
+
+    /// The type of a constant object of two words, the second of which holds
+    /// an address: a closure without captured values or a record without
+    /// fields.
+    fn object_type(&self) -> String {
+        format!("{{ i64, {} }}", self.pointer_width.address_type())
+    }
+
+    /// Defines a constant object of two words, returning its address.
+    fn object(&mut self, global: &str, linkage: &str, header: u64, pointer: &str) -> String {
+        let type_ = self.object_type();
+        let address = self.pointer_width.address(pointer);
+        let _ = self.globals.insert(
+            global.into(),
+            format!("{global} = {linkage}constant {type_} {{ i64 {header}, {address} }}, align 8"),
+        );
+        format!("ptrtoint ({type_}* {global} to i64)")
+    }
+
+    fn lambda_symbol(&mut self) -> String {
+        self.lambdas += 1;
+        function_symbol(&self.name, &format!("$lambda{}", self.lambdas))
+    }
+}
+
+/// The function of a closure for a module or external function, which calls
+/// the function without the closure.
+fn closure_entry(linkage: &str, entry: &str, function: &str, arity: usize) -> String {
+    let parameters = (0..arity).map(|i| format!("i64 %{i}")).join(", ");
+    let separator = if arity == 0 { "" } else { ", " };
+    format!(
+        "define {linkage}i64 {entry}(i64 %self{separator}{parameters}) {{
+entry:
+  %result = tail call i64 {function}({parameters})
+  ret i64 %result
+}}
+"
+    )
+}
+
+/// The bytes of a string as the contents of an LLVM `c"..."` literal.
+fn escape(bytes: &[u8]) -> String {
+    let mut escaped = String::new();
+    for byte in bytes {
+        match byte {
+            b'"' | b'\\' => {
+                let _ = write!(escaped, "\\{byte:02X}");
+            }
+            b' '..=b'~' => escaped.push(*byte as char),
+            _ => {
+                let _ = write!(escaped, "\\{byte:02X}");
+            }
+        }
+    }
+    escaped
+}
+
+/// The bytes of a Gleam string literal, with its escape sequences replaced.
+fn unescape(value: &str) -> Vec<u8> {
+    let mut string = String::new();
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => match chars.next() {
+                Some('n') => string.push('\n'),
+                Some('r') => string.push('\r'),
+                Some('t') => string.push('\t'),
+                Some('e') => string.push('\x1b'),
+                Some('f') => string.push('\x0c'),
+                Some(escaped) => string.push(escaped),
+                None => string.push('\\'),
+            },
+            char => string.push(char),
+        }
+    }
+    string.into_bytes()
+}
+
+/// The value of an int literal, shifted and tagged.
+fn int(value: &str) -> String {
+    let literal = crate::int::Literal::new(value);
+    let magnitude =
+        u128::from_str_radix(&literal.significant_digits(), literal.radix).unwrap_or_default();
+    let value = if literal.negative {
+        (magnitude as i128).wrapping_neg()
+    } else {
+        magnitude as i128
+    };
+    ((value as i64).wrapping_shl(1) | 1).to_string()
+}
+
+fn float(value: &str) -> f64 {
+    crate::float::canonical_literal(value)
+        .parse()
+        .unwrap_or(f64::INFINITY)
+}
@@ -0,0 +1,821 @@
+//! The functions exported by a WebAssembly component, which convert the
+//! values they take and return between the canonical ABI of the component
+//! model and the representation of Gleam values.
+//!
+//! Arguments are lifted from the flat values they are passed as, or loaded
+//! from memory when there are too many of them. A result of a single flat
+//! value is returned directly, and any other is stored in a static return
+//! area whose address is returned. Memory allocated for the results is never
+//! freed, like that of every other value.
+
+use itertools::Itertools;
+
+use crate::wasm::{Component, Function, Type, TypeKind};
+
+use super::{
+    function::{header, FunctionWriter},
+    function_symbol, Generator, PointerWidth,
+};
+
+/// The most flat values that can be passed as arguments, above which they are
+/// passed in memory.
+const MAX_FLAT_PARAMS: usize = 16;
+
+const RETURN_AREA: &str = "@\"gleam.wasm.return\"";
+
+/// The allocator used by the host to pass values in memory.
+const REALLOC: &str = "define i32 @cabi_realloc(i32 %old, i32 %old_size, i32 %align, i32 %new_size) \"wasm-export-name\"=\"cabi_realloc\" {
+entry:
+  %pointer = inttoptr i32 %old to i8*
+  %memory = call i8* @realloc(i8* %pointer, i32 %new_size)
+  %address = ptrtoint i8* %memory to i32
+  ret i32 %address
+}
+";
+
+/// A module exporting the functions of the interfaces of the component, to
+/// be linked with the package compiled for the `wasm` target.
+pub fn component_exports(component: &Component) -> String {
+    let mut generator = Generator::new("gleam/wasm".into(), PointerWidth::Bits32);
+    let return_area = component
+        .interfaces
+        .iter()
+        .flat_map(|interface| &interface.functions)
+        .filter_map(|function| function.result.as_ref())
+        .filter(|result| flat(result).len() > 1)
+        .map(|result| layout(result).size)
+        .max()
+        .unwrap_or(0);
+    if return_area > 0 {
+        let _ = generator.globals.insert(
+            RETURN_AREA.into(),
+            format!(
+                "{RETURN_AREA} = internal global [{return_area} x i8] zeroinitializer, align 8"
+            ),
+        );
+    }
+    let return_area = format!("ptrtoint ([{return_area} x i8]* {RETURN_AREA} to i32)");
+
+    for interface in &component.interfaces {
+        for function in &interface.functions {
+            let export_name = component.export_name(interface, function);
+            let definition = export(
+                &mut generator,
+                &export_name,
+                &interface.module,
+                function,
+                &return_area,
+            );
+            generator.functions.push(definition);
+        }
+    }
+    generator.declare("declare i8* @realloc(i8*, i32)".into());
+    generator.functions.push(REALLOC.into());
+    generator.finish()
+}
+
+fn export(
+    generator: &mut Generator,
+    export_name: &str,
+    module: &str,
+    function: &Function,
+    return_area: &str,
+) -> String {
+    let symbol = match &function.external {
+        Some(external) => format!("@\"{external}\""),
+        None => function_symbol(module, &function.name),
+    };
+    generator.declare_function(&symbol, function.parameters.len());
+
+    let types = function
+        .parameters
+        .iter()
+        .map(|(_, type_)| type_)
+        .collect_vec();
+    let flat_parameters = types.iter().flat_map(|type_| flat(type_)).collect_vec();
+    let mut adapter = Adapter {
+        writer: FunctionWriter::new(generator),
+    };
+    let (parameters, arguments) = if flat_parameters.len() > MAX_FLAT_PARAMS {
+        let mut offset = 0;
+        let arguments = types
+            .iter()
+            .map(|type_| adapter.load(type_, "%p0", next_offset(&mut offset, type_)))
+            .collect_vec();
+        (vec!["i32 %p0".to_string()], arguments)
+    } else {
+        let registers = (0..flat_parameters.len())
+            .map(|i| format!("%p{i}"))
+            .collect_vec();
+        let mut values = registers.clone().into_iter();
+        let arguments = types
+            .iter()
+            .map(|type_| adapter.lift(type_, &mut values))
+            .collect_vec();
+        let parameters = flat_parameters
+            .iter()
+            .zip(registers)
+            .map(|(type_, register)| format!("{type_} {register}"))
+            .collect();
+        (parameters, arguments)
+    };
+
+    let arguments = arguments
+        .iter()
+        .map(|argument| format!("i64 {argument}"))
+        .join(", ");
+    let result = adapter
+        .writer
+        .assign(format!("call i64 {symbol}({arguments})"));
+    let result_type = match &function.result {
+        None => {
+            adapter.writer.terminate("ret void".into());
+            "void"
+        }
+        Some(type_) => match flat(type_).as_slice() {
+            [single] => {
+                let value = adapter.lower(type_, &result);
+                adapter.writer.terminate(format!("ret {single} {value}"));
+                single
+            }
+            _ => {
+                adapter.store(type_, &result, return_area, 0);
+                adapter.writer.terminate(format!("ret i32 {return_area}"));
+                "i32"
+            }
+        },
+    };
+    adapter.writer.finish(&format!(
+        "define {result_type} @\"gleam.wasm.{module}.{name}\"({parameters}) \"wasm-export-name\"=\"{export_name}\"",
+        name = function.name,
+        parameters = parameters.join(", "),
+    ))
+}
+
+/// How the canonical ABI represents a type.
+enum Abi<'a> {
+    S64,
+    F64,
+    Bool,
+    String,
+    List(&'a Type),
+    /// A tuple, or a record built with the constructor.
+    Fields {
+        constructor: Option<&'a str>,
+        fields: Vec<&'a Type>,
+    },
+    /// A variant, enum or result, one of whose cases is held.
+    Cases(Vec<Case<'a>>),
+}
+
+/// A constructor of a variant, enum or result. Fields holding `Nil` are not
+/// part of the case's payload.
+struct Case<'a> {
+    constructor: &'a str,
+    fields: Vec<Option<&'a Type>>,
+}
+
+impl<'a> Case<'a> {
+    fn payload(&self) -> Vec<&'a Type> {
+        self.fields.iter().flatten().copied().collect()
+    }
+}
+
+fn abi(type_: &Type) -> Abi<'_> {
+    match type_ {
+        Type::S64 => Abi::S64,
+        Type::F64 => Abi::F64,
+        Type::Bool => Abi::Bool,
+        Type::String => Abi::String,
+        Type::List(element) => Abi::List(element),
+        Type::Tuple(elements) => Abi::Fields {
+            constructor: None,
+            fields: elements.iter().collect(),
+        },
+        Type::Result { ok, error } => Abi::Cases(vec![
+            Case {
+                constructor: "Ok",
+                fields: vec![ok.as_deref()],
+            },
+            Case {
+                constructor: "Error",
+                fields: vec![error.as_deref()],
+            },
+        ]),
+        Type::Named(definition) => match &definition.kind {
+            TypeKind::Enum(cases) => Abi::Cases(
+                cases
+                    .iter()
+                    .map(|constructor| Case {
+                        constructor,
+                        fields: vec![],
+                    })
+                    .collect(),
+            ),
+            TypeKind::Record {
+                constructor,
+                fields,
+            } => Abi::Fields {
+                constructor: Some(constructor),
+                fields: fields.iter().map(|(_, type_)| type_).collect(),
+            },
+            TypeKind::Variant(cases) => Abi::Cases(
+                cases
+                    .iter()
+                    .map(|case| Case {
+                        constructor: &case.constructor,
+                        fields: case.fields.iter().map(Some).collect(),
+                    })
+                    .collect(),
+            ),
+        },
+    }
+}
+
+/// The types of the flat values a value of the type is passed as.
+fn flat(type_: &Type) -> Vec<&'static str> {
+    match abi(type_) {
+        Abi::S64 => vec!["i64"],
+        Abi::F64 => vec!["double"],
+        Abi::Bool => vec!["i32"],
+        Abi::String | Abi::List(_) => vec!["i32", "i32"],
+        Abi::Fields { fields, .. } => fields.into_iter().flat_map(flat).collect(),
+        Abi::Cases(cases) => {
+            // The payloads of the cases share the values after the
+            // discriminant, which are wide enough for any of them.
+            let mut values = vec!["i32"];
+            for case in &cases {
+                for (i, type_) in case.payload().into_iter().flat_map(flat).enumerate() {
+                    match values.get_mut(i + 1) {
+                        Some(joined) if *joined != type_ => *joined = "i64",
+                        Some(_) => (),
+                        None => values.push(type_),
+                    }
+                }
+            }
+            values
+        }
+    }
+}
+
+/// The size and alignment of a value of a type held in memory.
+struct Layout {
+    size: u32,
+    align: u32,
+}
+
+fn layout(type_: &Type) -> Layout {
+    match abi(type_) {
+        Abi::S64 | Abi::F64 => Layout { size: 8, align: 8 },
+        Abi::Bool => Layout { size: 1, align: 1 },
+        Abi::String | Abi::List(_) => Layout { size: 8, align: 4 },
+        Abi::Fields { fields, .. } => fields_layout(&fields),
+        Abi::Cases(cases) => cases_layout(&cases).2,
+    }
+}
+
+/// Fields are held one after another, each aligned.
+fn fields_layout(fields: &[&Type]) -> Layout {
+    let mut size = 0;
+    let mut align = 1;
+    for field in fields {
+        let _ = next_offset(&mut size, field);
+        align = align.max(layout(field).align);
+    }
+    Layout {
+        size: align_to(size, align),
+        align,
+    }
+}
+
+/// The offset of the next field of a type, moving the offset past it.
+fn next_offset(offset: &mut u32, type_: &Type) -> u32 {
+    let layout = layout(type_);
+    let field = align_to(*offset, layout.align);
+    *offset = field + layout.size;
+    field
+}
+
+/// The type of the discriminant, the offset of the payload and the layout of
+/// a value of one of the cases.
+fn cases_layout(cases: &[Case<'_>]) -> (&'static str, u32, Layout) {
+    let (discriminant, discriminant_size) = match cases.len() {
+        0..=256 => ("i8", 1),
+        257..=65536 => ("i16", 2),
+        _ => ("i32", 4),
+    };
+    let payloads = cases
+        .iter()
+        .map(|case| fields_layout(&case.payload()))
+        .collect_vec();
+    let payload_align = payloads
+        .iter()
+        .map(|payload| payload.align)
+        .max()
+        .unwrap_or(1);
+    let payload_size = payloads
+        .iter()
+        .map(|payload| payload.size)
+        .max()
+        .unwrap_or(0);
+    let align = payload_align.max(discriminant_size);
+    let payload = align_to(discriminant_size, payload_align);
+    let layout = Layout {
+        size: align_to(payload + payload_size, align),
+        align,
+    };
+    (discriminant, payload, layout)
+}
+
+fn align_to(offset: u32, align: u32) -> u32 {
+    offset.div_ceil(align) * align
+}
+
+/// Writes the body of an exported function, converting values as it goes.
+struct Adapter<'generator> {
+    writer: FunctionWriter<'generator, 'static>,
+}
+
+impl Adapter<'_> {
+    /// A Gleam value from the flat values of a type, taken from the start of
+    /// the values.
+    fn lift(&mut self, type_: &Type, values: &mut impl Iterator<Item = String>) -> String {
+        let mut next = || values.next().unwrap_or_else(|| "undef".into());
+        match abi(type_) {
+            Abi::S64 => {
+                let value = next();
+                self.int(&value)
+            }
+            Abi::F64 => {
+                let value = next();
+                self.writer
+                    .runtime("gleam_float", "i64", &[("double", value)])
+            }
+            Abi::Bool => {
+                let value = next();
+                let condition = self.writer.assign(format!("icmp ne i32 {value}, 0"));
+                self.writer.boolean(&condition)
+            }
+            Abi::String => {
+                let (pointer, size) = (next(), next());
+                self.string(&pointer, &size)
+            }
+            Abi::List(element) => {
+                let (pointer, size) = (next(), next());
+                self.list(element, &pointer, &size)
+            }
+            Abi::Fields {
+                constructor,
+                fields,
+            } => {
+                let fields = fields
+                    .into_iter()
+                    .map(|field| self.lift(field, values))
+                    .collect();
+                self.record(constructor, fields)
+            }
+            Abi::Cases(cases) => {
+                let discriminant = next();
+                let joined = flat(type_)
+                    .into_iter()
+                    .skip(1)
+                    .map(|type_| (values.next().unwrap_or_else(|| "undef".into()), type_))
+                    .collect_vec();
+                self.cases(&cases, &discriminant, |adapter, case| {
+                    let wanted = case.payload().into_iter().flat_map(flat);
+                    let mut payload = joined
+                        .iter()
+                        .zip(wanted)
+                        .map(|((value, from), to)| adapter.convert(value, from, to))
+                        .collect_vec()
+                        .into_iter();
+                    case.fields
+                        .iter()
+                        .map(|field| match field {
+                            Some(field) => adapter.lift(field, &mut payload),
+                            None => adapter.writer.generator().singleton("Nil"),
+                        })
+                        .collect()
+                })
+            }
+        }
+    }
+
+    /// A Gleam value from a value of a type held in memory.
+    fn load(&mut self, type_: &Type, base: &str, offset: u32) -> String {
+        match abi(type_) {
+            Abi::S64 => {
+                let value = self.load_at("i64", base, offset);
+                self.int(&value)
+            }
+            Abi::F64 => {
+                let value = self.load_at("double", base, offset);
+                self.writer
+                    .runtime("gleam_float", "i64", &[("double", value)])
+            }
+            Abi::Bool => {
+                let value = self.load_at("i8", base, offset);
+                let condition = self.writer.assign(format!("icmp ne i8 {value}, 0"));
+                self.writer.boolean(&condition)
+            }
+            Abi::String => {
+                let pointer = self.load_at("i32", base, offset);
+                let size = self.load_at("i32", base, offset + 4);
+                self.string(&pointer, &size)
+            }
+            Abi::List(element) => {
+                let pointer = self.load_at("i32", base, offset);
+                let size = self.load_at("i32", base, offset + 4);
+                self.list(element, &pointer, &size)
+            }
+            Abi::Fields {
+                constructor,
+                fields,
+            } => {
+                let mut field_offset = offset;
+                let fields = fields
+                    .into_iter()
+                    .map(|field| {
+                        let offset = next_offset(&mut field_offset, field);
+                        self.load(field, base, offset)
+                    })
+                    .collect();
+                self.record(constructor, fields)
+            }
+            Abi::Cases(cases) => {
+                let (discriminant_type, payload, _) = cases_layout(&cases);
+                let discriminant = self.load_at(discriminant_type, base, offset);
+                let discriminant = self
+                    .writer
+                    .assign(format!("zext {discriminant_type} {discriminant} to i32"));
+                self.cases(&cases, &discriminant, |adapter, case| {
+                    let mut field_offset = offset + payload;
+                    case.fields
+                        .iter()
+                        .map(|field| match field {
+                            Some(field) => {
+                                let offset = next_offset(&mut field_offset, field);
+                                adapter.load(field, base, offset)
+                            }
+                            None => adapter.writer.generator().singleton("Nil"),
+                        })
+                        .collect()
+                })
+            }
+        }
+    }
+
+    /// Stores a Gleam value in memory as a value of a type.
+    fn store(&mut self, type_: &Type, value: &str, base: &str, offset: u32) {
+        match abi(type_) {
+            Abi::S64 => {
+                let int = self.writer.assign(format!("ashr i64 {value}, 1"));
+                self.store_at("i64", &int, base, offset);
+            }
+            Abi::F64 => {
+                let float = self.writer.unbox_float(value);
+                self.store_at("double", &float, base, offset);
+            }
+            Abi::Bool => {
+                let bool = self.bool(value, "i8");
+                self.store_at("i8", &bool, base, offset);
+            }
+            Abi::String => {
+                let bytes =
+                    self.writer
+                        .runtime("gleam_string_bytes", "i8*", &[("i64", value.into())]);
+                let pointer = self.writer.assign(format!("ptrtoint i8* {bytes} to i32"));
+                let size =
+                    self.writer
+                        .runtime("gleam_string_size", "i64", &[("i64", value.into())]);
+                let size = self.writer.assign(format!("trunc i64 {size} to i32"));
+                self.store_at("i32", &pointer, base, offset);
+                self.store_at("i32", &size, base, offset + 4);
+            }
+            Abi::List(element) => {
+                let (pointer, size) = self.lower_list(element, value);
+                self.store_at("i32", &pointer, base, offset);
+                self.store_at("i32", &size, base, offset + 4);
+            }
+            Abi::Fields { fields, .. } => {
+                let mut field_offset = offset;
+                for (index, field) in fields.into_iter().enumerate() {
+                    let offset = next_offset(&mut field_offset, field);
+                    let value = self.writer.load_word(value, &(index + 2).to_string());
+                    self.store(field, &value, base, offset);
+                }
+            }
+            Abi::Cases(cases) => {
+                let (discriminant_type, payload, _) = cases_layout(&cases);
+                let tag = self.writer.load_word(value, "1");
+                let done = self.writer.label("stored");
+                for (discriminant, case) in cases.iter().enumerate() {
+                    let case_tag = self.writer.generator().tag(case.constructor);
+                    let matches = self.writer.assign(format!("icmp eq i64 {tag}, {case_tag}"));
+                    let then = self.writer.label("case");
+                    let next = self.writer.label("next");
+                    self.writer
+                        .terminate(format!("br i1 {matches}, label %{then}, label %{next}"));
+                    self.writer.start(&then);
+                    self.store_at(discriminant_type, &discriminant.to_string(), base, offset);
+                    let mut field_offset = offset + payload;
+                    for (index, field) in case.fields.iter().enumerate() {
+                        if let Some(field) = field {
+                            let offset = next_offset(&mut field_offset, field);
+                            let value = self.writer.load_word(value, &(index + 2).to_string());
+                            self.store(field, &value, base, offset);
+                        }
+                    }
+                    self.writer.terminate(format!("br label %{done}"));
+                    self.writer.start(&next);
+                }
+                self.writer.terminate("unreachable".into());
+                self.writer.start(&done);
+            }
+        }
+    }
+
+    /// The only flat value of a Gleam value of a type that is passed as one.
+    fn lower(&mut self, type_: &Type, value: &str) -> String {
+        match abi(type_) {
+            Abi::S64 => self.writer.assign(format!("ashr i64 {value}, 1")),
+            Abi::F64 => self.writer.unbox_float(value),
+            Abi::Bool => self.bool(value, "i32"),
+            Abi::Fields { fields, .. } => match fields.as_slice() {
+                [field] => {
+                    let value = self.writer.load_word(value, "2");
+                    self.lower(field, &value)
+                }
+                _ => unreachable!("A record of one flat value has one field"),
+            },
+            Abi::Cases(cases) => {
+                // None of the cases have a payload, so only the discriminant
+                // is passed.
+                let tag = self.writer.load_word(value, "1");
+                let mut discriminant = "0".to_string();
+                for (index, case) in cases.iter().enumerate().skip(1) {
+                    let case_tag = self.writer.generator().tag(case.constructor);
+                    let matches = self.writer.assign(format!("icmp eq i64 {tag}, {case_tag}"));
+                    discriminant = self.writer.assign(format!(
+                        "select i1 {matches}, i32 {index}, i32 {discriminant}"
+                    ));
+                }
+                discriminant
+            }
+            Abi::String | Abi::List(_) => unreachable!("Strings and lists are two flat values"),
+        }
+    }
+
+    /// Builds the Gleam value of the case selected by the discriminant, with
+    /// the fields given for it.
+    fn cases(
+        &mut self,
+        cases: &[Case<'_>],
+        discriminant: &str,
+        mut fields: impl FnMut(&mut Self, &Case<'_>) -> Vec<String>,
+    ) -> String {
+        let labels = cases
+            .iter()
+            .map(|_| self.writer.label("case"))
+            .collect_vec();
+        let invalid = self.writer.label("invalid");
+        let done = self.writer.label("lifted");
+        let targets = labels
+            .iter()
+            .enumerate()
+            .map(|(index, label)| format!("i32 {index}, label %{label}"))
+            .join(" ");
+        self.writer.terminate(format!(
+            "switch i32 {discriminant}, label %{invalid} [ {targets} ]"
+        ));
+        let mut results = vec![];
+        for (case, label) in cases.iter().zip(labels) {
+            self.writer.start(&label);
+            let fields = fields(self, case);
+            let value = self.record(Some(case.constructor), fields);
+            results.push(format!("[ {value}, %{} ]", self.writer.block()));
+            self.writer.terminate(format!("br label %{done}"));
+        }
+        self.writer.start(&invalid);
+        self.writer.terminate("unreachable".into());
+        self.writer.start(&done);
+        self.writer
+            .assign(format!("phi i64 {}", results.join(", ")))
+    }
+
+    fn record(&mut self, constructor: Option<&str>, fields: Vec<String>) -> String {
+        let tag = match constructor {
+            Some(constructor) if fields.is_empty() => {
+                return self.writer.generator().singleton(constructor)
+            }
+            Some(constructor) => self.writer.generator().tag(constructor),
+            None => "0".into(),
+        };
+        let mut words = vec![header(3, fields.len()), tag];
+        words.extend(fields);
+        self.writer.allocate(words)
+    }
+
+    fn int(&mut self, value: &str) -> String {
+        let shifted = self.writer.assign(format!("shl i64 {value}, 1"));
+        self.writer.assign(format!("or i64 {shifted}, 1"))
+    }
+
+    /// Whether a Gleam `Bool` is `True`, as an integer of the type.
+    fn bool(&mut self, value: &str, type_: &str) -> String {
+        let true_ = self.writer.generator().singleton("True");
+        let condition = self.writer.assign(format!("icmp eq i64 {value}, {true_}"));
+        self.writer
+            .assign(format!("zext i1 {condition} to {type_}"))
+    }
+
+    /// A Gleam string holding a copy of the bytes.
+    fn string(&mut self, pointer: &str, size: &str) -> String {
+        let size = self.writer.assign(format!("zext i32 {size} to i64"));
+        let string = self
+            .writer
+            .runtime("gleam_string_alloc", "i64", &[("i64", size.clone())]);
+        let bytes = self
+            .writer
+            .runtime("gleam_string_bytes", "i8*", &[("i64", string.clone())]);
+        let source = self.writer.assign(format!("inttoptr i32 {pointer} to i8*"));
+        self.writer
+            .generator()
+            .declare("declare void @llvm.memcpy.p0i8.p0i8.i64(i8*, i8*, i64, i1)".into());
+        self.writer.emit(format!(
+            "call void @llvm.memcpy.p0i8.p0i8.i64(i8* {bytes}, i8* {source}, i64 {size}, i1 false)"
+        ));
+        string
+    }
+
+    /// A Gleam list of the elements held in memory, built from the last.
+    fn list(&mut self, element: &Type, pointer: &str, size: &str) -> String {
+        let element_size = layout(element).size;
+        let empty = self.writer.generator().empty_list();
+        let tag = self.writer.generator().tag("List");
+        let values = self.repeat(
+            vec![("i32", size.into()), ("i64", empty)],
+            |adapter, values| {
+                let [index, _] = values else { unreachable!() };
+                adapter.writer.assign(format!("icmp eq i32 {index}, 0"))
+            },
+            |adapter, values| {
+                let [index, list] = values else {
+                    unreachable!()
+                };
+                let index = adapter.writer.assign(format!("sub i32 {index}, 1"));
+                let offset = adapter
+                    .writer
+                    .assign(format!("mul i32 {index}, {element_size}"));
+                let address = adapter
+                    .writer
+                    .assign(format!("add i32 {pointer}, {offset}"));
+                let element = adapter.load(element, &address, 0);
+                let list = adapter
+                    .writer
+                    .allocate(vec![header(3, 2), tag, element, list.clone()]);
+                vec![index, list]
+            },
+        );
+        values.into_iter().nth(1).unwrap_or_default()
+    }
+
+    /// Copies the elements of a Gleam list to memory, returning its address
+    /// and the number of elements.
+    fn lower_list(&mut self, element: &Type, list: &str) -> (String, String) {
+        let element_size = layout(element).size;
+        let counted = self.repeat(
+            vec![("i32", "0".into()), ("i64", list.into())],
+            |adapter, values| {
+                let [_, list] = values else { unreachable!() };
+                let word = adapter.writer.load_word(list, "0");
+                adapter
+                    .writer
+                    .assign(format!("icmp eq i64 {word}, {}", header(3, 0)))
+            },
+            |adapter, values| {
+                let [count, list] = values else {
+                    unreachable!()
+                };
+                let count = adapter.writer.assign(format!("add i32 {count}, 1"));
+                let tail = adapter.writer.load_word(list, "3");
+                vec![count, tail]
+            },
+        );
+        let size = counted.into_iter().next().unwrap_or_default();
+
+        let bytes = self
+            .writer
+            .assign(format!("mul i32 {size}, {element_size}"));
+        let rounded = self.writer.assign(format!("add i32 {bytes}, 7"));
+        let words = self.writer.assign(format!("lshr i32 {rounded}, 3"));
+        let words = self.writer.assign(format!("zext i32 {words} to i64"));
+        let buffer = self.writer.runtime("gleam_alloc", "i64", &[("i64", words)]);
+        let buffer = self.writer.assign(format!("trunc i64 {buffer} to i32"));
+
+        let _ = self.repeat(
+            vec![("i32", "0".into()), ("i64", list.into())],
+            |adapter, values| {
+                let [index, _] = values else { unreachable!() };
+                adapter
+                    .writer
+                    .assign(format!("icmp eq i32 {index}, {size}"))
+            },
+            |adapter, values| {
+                let [index, list] = values else {
+                    unreachable!()
+                };
+                let value = adapter.writer.load_word(list, "2");
+                let offset = adapter
+                    .writer
+                    .assign(format!("mul i32 {index}, {element_size}"));
+                let address = adapter.writer.assign(format!("add i32 {buffer}, {offset}"));
+                adapter.store(element, &value, &address, 0);
+                let index = adapter.writer.assign(format!("add i32 {index}, 1"));
+                let tail = adapter.writer.load_word(list, "3");
+                vec![index, tail]
+            },
+        );
+        (buffer, size)
+    }
+
+    /// Writes a loop over values starting with the initial ones, which ends
+    /// when the condition holds for them and otherwise continues with the
+    /// values given by the body, returning the values it ends with.
+    fn repeat(
+        &mut self,
+        initial: Vec<(&str, String)>,
+        condition: impl FnOnce(&mut Self, &[String]) -> String,
+        body: impl FnOnce(&mut Self, &[String]) -> Vec<String>,
+    ) -> Vec<String> {
+        let entry = self.writer.block().to_string();
+        let header = self.writer.label("loop");
+        let iteration = self.writer.label("iteration");
+        let latch = self.writer.label("continue");
+        let exit = self.writer.label("exit");
+        let current = initial.iter().map(|_| self.writer.register()).collect_vec();
+        let next = initial.iter().map(|_| self.writer.register()).collect_vec();
+
+        self.writer.terminate(format!("br label %{header}"));
+        self.writer.start(&header);
+        for ((type_, value), (current, next)) in initial.iter().zip(current.iter().zip(&next)) {
+            self.writer.emit(format!(
+                "{current} = phi {type_} [ {value}, %{entry} ], [ {next}, %{latch} ]"
+            ));
+        }
+        let ended = condition(self, &current);
+        self.writer
+            .terminate(format!("br i1 {ended}, label %{exit}, label %{iteration}"));
+
+        // The values for the next iteration are passed through a block of
+        // their own, as the body may end in a block other than the one it
+        // starts in.
+        self.writer.start(&iteration);
+        let values = body(self, &current);
+        let end = self.writer.block().to_string();
+        self.writer.terminate(format!("br label %{latch}"));
+        self.writer.start(&latch);
+        for ((type_, _), (next, value)) in initial.iter().zip(next.iter().zip(values)) {
+            self.writer
+                .emit(format!("{next} = phi {type_} [ {value}, %{end} ]"));
+        }
+        self.writer.terminate(format!("br label %{header}"));
+        self.writer.start(&exit);
+        current
+    }
+
+    /// Converts a flat value shared by the payloads of several cases to the
+    /// type of a value of one of them.
+    fn convert(&mut self, value: &str, from: &str, to: &str) -> String {
+        match (from, to) {
+            ("i64", "i32") => self.writer.assign(format!("trunc i64 {value} to i32")),
+            ("i64", "double") => self.writer.assign(format!("bitcast i64 {value} to double")),
+            _ => value.into(),
+        }
+    }
+
+    fn address(&mut self, base: &str, offset: u32) -> String {
+        match offset {
+            0 => base.into(),
+            _ => self.writer.assign(format!("add i32 {base}, {offset}")),
+        }
+    }
+
+    fn load_at(&mut self, type_: &str, base: &str, offset: u32) -> String {
+        let address = self.address(base, offset);
+        let pointer = self
+            .writer
+            .assign(format!("inttoptr i32 {address} to {type_}*"));
+        self.writer
+            .assign(format!("load {type_}, {type_}* {pointer}"))
+    }
+
+    fn store_at(&mut self, type_: &str, value: &str, base: &str, offset: u32) {
+        let address = self.address(base, offset);
+        let pointer = self
+            .writer
+            .assign(format!("inttoptr i32 {address} to {type_}*"));
+        self.writer
+            .emit(format!("store {type_} {value}, {type_}* {pointer}"));
+    }
+}
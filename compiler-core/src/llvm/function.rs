@@ -0,0 +1,834 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+
+use crate::{
+    ast::BinOp,
+    ir::{
+        Accessor, BitStringSegmentKind, BuiltinFn, Call, Expression, FunctionArg, Identifier,
+        Literal, Statement, TypeConstruction, UnaryOp,
+    },
+    type_::collapse_links,
+};
+
+use super::{closure_function_type, float, function_symbol, int, unescape, Error, Generator};
+
+/// Writes the body of a function, one basic block after another. Each IR
+/// variable is an SSA register or a constant, as variables are never
+/// reassigned.
+#[derive(Debug)]
+pub(super) struct FunctionWriter<'generator, 'a> {
+    generator: &'generator mut Generator,
+    body: String,
+    registers: usize,
+    labels: usize,
+    /// The label of the block being written.
+    block: String,
+    /// Whether the block being written has ended with a branch or return, in
+    /// which case any further instructions start a new unreachable block.
+    terminated: bool,
+    variables: HashMap<Identifier<'a>, String>,
+    /// The blocks at which the immediately invoked functions being inlined
+    /// continue, from the innermost.
+    continuations: Vec<Continuation>,
+}
+
+/// Where a `return` from an inlined function goes: a block that takes the
+/// returned value from each of the blocks returning it.
+#[derive(Debug)]
+struct Continuation {
+    label: String,
+    results: Vec<(String, String)>,
+}
+
+impl<'generator, 'a> FunctionWriter<'generator, 'a> {
+    pub(super) fn new(generator: &'generator mut Generator) -> Self {
+        Self {
+            generator,
+            body: "entry:\n".into(),
+            registers: 0,
+            labels: 0,
+            block: "entry".into(),
+            terminated: false,
+            variables: HashMap::new(),
+            continuations: vec![],
+        }
+    }
+
+    /// The registers of the parameters, with the variables they are bound to.
+    pub(super) fn parameters(&mut self, parameters: Vec<Option<Identifier<'a>>>) -> Vec<String> {
+        parameters
+            .into_iter()
+            .enumerate()
+            .map(|(i, identifier)| {
+                let register = format!("%arg{i}");
+                if let Some(identifier) = identifier {
+                    let _ = self.variables.insert(identifier, register.clone());
+                }
+                register
+            })
+            .collect()
+    }
+
+    /// The definition of the function. A function that can reach its end
+    /// without returning has a `case` expression that matched nothing.
+    pub(super) fn finish(mut self, signature: &str) -> String {
+        if !self.terminated {
+            self.panic_unmatched();
+        }
+        format!("{signature} {{\n{}}}\n", self.body)
+    }
+
+    pub(super) fn generator(&mut self) -> &mut Generator {
+        self.generator
+    }
+
+    /// The label of the block being written.
+    pub(super) fn block(&self) -> &str {
+        &self.block
+    }
+
+    pub(super) fn statements(&mut self, statements: Vec<Statement<'a>>) -> Result<(), Error> {
+        for statement in statements {
+            self.statement(statement)?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: Statement<'a>) -> Result<(), Error> {
+        match statement {
+            Statement::Return { expr } => {
+                let value = self.value(expr)?;
+                self.return_(value);
+            }
+            Statement::Assignment { var, expr, .. } => {
+                let value = self.value(expr)?;
+                let _ = self.variables.insert(var, value);
+            }
+            Statement::Expr { expr } => {
+                let _ = self.value(expr)?;
+            }
+            Statement::Conditional { test, body } => {
+                let test = self.condition(test)?;
+                let then = self.label("then");
+                let next = self.label("next");
+                self.terminate(format!("br i1 {test}, label %{then}, label %{next}"));
+                self.start(&then);
+                self.statements(body)?;
+                if !self.terminated {
+                    self.terminate(format!("br label %{next}"));
+                }
+                self.start(&next);
+            }
+        }
+        Ok(())
+    }
+
+    fn return_(&mut self, value: String) {
+        match self.continuations.last_mut() {
+            Some(continuation) => {
+                continuation.results.push((value, self.block.clone()));
+                let label = continuation.label.clone();
+                self.terminate(format!("br label %{label}"));
+            }
+            None => self.terminate(format!("ret i64 {value}")),
+        }
+    }
+
+    pub(super) fn register(&mut self) -> String {
+        self.registers += 1;
+        format!("%r{}", self.registers)
+    }
+
+    pub(super) fn label(&mut self, name: &str) -> String {
+        self.labels += 1;
+        format!("{name}{}", self.labels)
+    }
+
+    pub(super) fn start(&mut self, label: &str) {
+        self.body.push_str(label);
+        self.body.push_str(":\n");
+        self.block = label.into();
+        self.terminated = false;
+    }
+
+    pub(super) fn emit(&mut self, instruction: String) {
+        if self.terminated {
+            let label = self.label("unreachable");
+            self.start(&label);
+        }
+        self.body.push_str("  ");
+        self.body.push_str(&instruction);
+        self.body.push('\n');
+    }
+
+    pub(super) fn terminate(&mut self, instruction: String) {
+        self.emit(instruction);
+        self.terminated = true;
+    }
+
+    /// Emits an instruction producing a value, returning its register.
+    pub(super) fn assign(&mut self, instruction: String) -> String {
+        let register = self.register();
+        self.emit(format!("{register} = {instruction}"));
+        register
+    }
+
+    /// Calls a function of the prelude.
+    pub(super) fn runtime(
+        &mut self,
+        name: &str,
+        result: &str,
+        arguments: &[(&str, String)],
+    ) -> String {
+        let types = arguments.iter().map(|(type_, _)| *type_).join(", ");
+        self.generator
+            .declare(format!("declare {result} @{name}({types})"));
+        let arguments = arguments
+            .iter()
+            .map(|(type_, value)| format!("{type_} {value}"))
+            .join(", ");
+        self.assign(format!("call {result} @{name}({arguments})"))
+    }
+
+    fn panic(&mut self, message: String) -> String {
+        self.generator
+            .declare("declare void @gleam_panic(i64) noreturn".into());
+        self.emit(format!("call void @gleam_panic(i64 {message})"));
+        self.terminate("unreachable".into());
+        "undef".into()
+    }
+
+    fn panic_unmatched(&mut self) {
+        let message = self.generator.string(b"No case clause matched");
+        let _ = self.panic(message);
+    }
+
+    /// The address of the word at the index of an object.
+    fn word(&mut self, object: &str, index: &str) -> String {
+        let words = self.assign(format!("inttoptr i64 {object} to i64*"));
+        self.assign(format!("getelementptr i64, i64* {words}, i64 {index}"))
+    }
+
+    pub(super) fn load_word(&mut self, object: &str, index: &str) -> String {
+        let word = self.word(object, index);
+        self.assign(format!("load i64, i64* {word}"))
+    }
+
+    /// Allocates an object holding the words.
+    pub(super) fn allocate(&mut self, words: Vec<String>) -> String {
+        let object = self.runtime("gleam_alloc", "i64", &[("i64", words.len().to_string())]);
+        for (index, value) in words.into_iter().enumerate() {
+            let word = self.word(&object, &index.to_string());
+            self.emit(format!("store i64 {value}, i64* {word}"));
+        }
+        object
+    }
+
+    pub(super) fn unbox_float(&mut self, float: &str) -> String {
+        let doubles = self.assign(format!("inttoptr i64 {float} to double*"));
+        let value = self.assign(format!("getelementptr double, double* {doubles}, i64 1"));
+        self.assign(format!("load double, double* {value}"))
+    }
+
+    pub(super) fn boolean(&mut self, condition: &str) -> String {
+        let true_ = self.generator.singleton("True");
+        let false_ = self.generator.singleton("False");
+        self.assign(format!("select i1 {condition}, i64 {true_}, i64 {false_}"))
+    }
+
+    fn values(&mut self, expressions: Vec<Expression<'a>>) -> Result<Vec<String>, Error> {
+        expressions
+            .into_iter()
+            .map(|expression| self.value(expression))
+            .collect()
+    }
+
+    fn value(&mut self, expression: Expression<'a>) -> Result<String, Error> {
+        Ok(match expression {
+            Expression::Literal(literal) => self.literal(literal),
+            Expression::Call(Call::Fn { callee, args }) => self.call(*callee, args)?,
+            Expression::Call(Call::Builtin { function, args }) => self.builtin(function, args)?,
+            Expression::Accessor(accessor) => self.accessor(accessor)?,
+            Expression::TypeConstruction(construction) => self.construction(construction)?,
+            Expression::BinOp { left, op, right } => self.binop(*left, op, *right)?,
+            Expression::UnaryOp {
+                op: UnaryOp::Negate,
+                expr,
+            } => {
+                let condition = self.condition(*expr)?;
+                let negated = self.assign(format!("xor i1 {condition}, true"));
+                self.boolean(&negated)
+            }
+        })
+    }
+
+    fn literal(&mut self, literal: Literal<'a>) -> String {
+        match literal {
+            Literal::Bool { value: true } => self.generator.singleton("True"),
+            Literal::Bool { value: false } => self.generator.singleton("False"),
+            Literal::Nil => self.generator.singleton("Nil"),
+            Literal::Int { value } => int(&value),
+            Literal::Float { value } => self.generator.float(float(value)),
+            Literal::String { value } => self.generator.string(&unescape(&value)),
+        }
+    }
+
+    /// The `i1` value of a Gleam `Bool` expression. Comparisons and checks
+    /// made by patterns are used directly rather than being made into a
+    /// `Bool` first.
+    fn condition(&mut self, expression: Expression<'a>) -> Result<String, Error> {
+        match expression {
+            Expression::Literal(Literal::Bool { value }) => Ok(value.to_string()),
+            Expression::UnaryOp {
+                op: UnaryOp::Negate,
+                expr,
+            } => {
+                let condition = self.condition(*expr)?;
+                Ok(self.assign(format!("xor i1 {condition}, true")))
+            }
+            Expression::BinOp { left, op, right } if is_comparison(op) => {
+                self.comparison(*left, op, *right)
+            }
+            Expression::Call(Call::Builtin { function, args }) if is_check(&function) => {
+                self.check(function, args)
+            }
+            expression => {
+                let value = self.value(expression)?;
+                let true_ = self.generator.singleton("True");
+                Ok(self.assign(format!("icmp eq i64 {value}, {true_}")))
+            }
+        }
+    }
+
+    fn comparison(
+        &mut self,
+        left: Expression<'a>,
+        op: BinOp,
+        right: Expression<'a>,
+    ) -> Result<String, Error> {
+        let (predicate, float) = match op {
+            BinOp::And | BinOp::Or => return self.short_circuit(left, op, right),
+            BinOp::Eq | BinOp::NotEq => return self.equality(left, op, right),
+            BinOp::LtInt => ("slt", false),
+            BinOp::LtEqInt => ("sle", false),
+            BinOp::GtInt => ("sgt", false),
+            BinOp::GtEqInt => ("sge", false),
+            BinOp::LtFloat => ("olt", true),
+            BinOp::LtEqFloat => ("ole", true),
+            BinOp::GtFloat => ("ogt", true),
+            BinOp::GtEqFloat => ("oge", true),
+            _ => unreachable!("{op:?} is not a comparison"),
+        };
+        let left = self.value(left)?;
+        let right = self.value(right)?;
+        if float {
+            let left = self.unbox_float(&left);
+            let right = self.unbox_float(&right);
+            Ok(self.assign(format!("fcmp {predicate} double {left}, {right}")))
+        } else {
+            // Shifting and tagging ints does not change their order.
+            Ok(self.assign(format!("icmp {predicate} i64 {left}, {right}")))
+        }
+    }
+
+    fn short_circuit(
+        &mut self,
+        left: Expression<'a>,
+        op: BinOp,
+        right: Expression<'a>,
+    ) -> Result<String, Error> {
+        let left = self.condition(left)?;
+        let from = self.block.clone();
+        let rest = self.label("rest");
+        let done = self.label("done");
+        let (branches, shortcut) = match op {
+            BinOp::And => (format!("label %{rest}, label %{done}"), "false"),
+            _ => (format!("label %{done}, label %{rest}"), "true"),
+        };
+        self.terminate(format!("br i1 {left}, {branches}"));
+        self.start(&rest);
+        let right = self.condition(right)?;
+        let right_block = self.block.clone();
+        self.terminate(format!("br label %{done}"));
+        self.start(&done);
+        Ok(self.assign(format!(
+            "phi i1 [ {shortcut}, %{from} ], [ {right}, %{right_block} ]"
+        )))
+    }
+
+    /// Values are compared structurally by the prelude, unless one of them is
+    /// an int or a constructor without fields which are equal only to
+    /// themselves.
+    fn equality(
+        &mut self,
+        left: Expression<'a>,
+        op: BinOp,
+        right: Expression<'a>,
+    ) -> Result<String, Error> {
+        let identity = is_immediate(&left) || is_immediate(&right);
+        let left = self.value(left)?;
+        let right = self.value(right)?;
+        let equal = if identity {
+            self.assign(format!("icmp eq i64 {left}, {right}"))
+        } else {
+            self.runtime("gleam_equal", "i1", &[("i64", left), ("i64", right)])
+        };
+        Ok(match op {
+            BinOp::NotEq => self.assign(format!("xor i1 {equal}, true")),
+            _ => equal,
+        })
+    }
+
+    fn check(&mut self, function: BuiltinFn, args: Vec<Expression<'a>>) -> Result<String, Error> {
+        let args = self.values(args)?;
+        Ok(match (function, args.as_slice()) {
+            (BuiltinFn::ListIsEmpty, [list]) => {
+                let empty = self.generator.empty_list();
+                self.assign(format!("icmp eq i64 {list}, {empty}"))
+            }
+            (BuiltinFn::IsRecord { name }, [record]) => {
+                let tag = self.load_word(record, "1");
+                let expected = self.generator.tag(&name);
+                self.assign(format!("icmp eq i64 {tag}, {expected}"))
+            }
+            (BuiltinFn::StringHasPrefix, [string, prefix]) => self.runtime(
+                "gleam_string_has_prefix",
+                "i1",
+                &[("i64", string.clone()), ("i64", prefix.clone())],
+            ),
+            (function, _) => unreachable!("{function:?} is not a check"),
+        })
+    }
+
+    fn builtin(&mut self, function: BuiltinFn, args: Vec<Expression<'a>>) -> Result<String, Error> {
+        if is_check(&function) {
+            let condition = self.check(function, args)?;
+            return Ok(self.boolean(&condition));
+        }
+        // The runtime has no way to catch a crash or to represent bit strings yet
+        let unsupported = match function {
+            BuiltinFn::Rescue { location } => Some(("Calls to `rescue`", location)),
+            BuiltinFn::BitStringSize { location }
+            | BuiltinFn::BitStringInt { location }
+            | BuiltinFn::BitStringFloat { location }
+            | BuiltinFn::BitStringSlice { location } => Some(("Bit string patterns", location)),
+            _ => None,
+        };
+        if let Some((feature, location)) = unsupported {
+            return Err(Error::Unsupported {
+                feature: feature.into(),
+                location,
+            });
+        }
+        let args = self.values(args)?;
+        Ok(match (function, args.as_slice()) {
+            (BuiltinFn::ListHead, [list]) => self.load_word(list, "2"),
+            (BuiltinFn::ListTail, [list]) => self.load_word(list, "3"),
+            (BuiltinFn::StringDropPrefix, [string, prefix]) => self.runtime(
+                "gleam_string_drop_prefix",
+                "i64",
+                &[("i64", string.clone()), ("i64", prefix.clone())],
+            ),
+            (BuiltinFn::Panic, [message]) => self.panic(message.clone()),
+            (BuiltinFn::Echo, [value, type_]) => self.runtime(
+                "gleam_echo",
+                "i64",
+                &[("i64", value.clone()), ("i64", type_.clone())],
+            ),
+            (function, args) => unreachable!("{function:?} given {} arguments", args.len()),
+        })
+    }
+
+    fn binop(
+        &mut self,
+        left: Expression<'a>,
+        op: BinOp,
+        right: Expression<'a>,
+    ) -> Result<String, Error> {
+        if is_comparison(op) {
+            let condition = self.comparison(left, op, right)?;
+            return Ok(self.boolean(&condition));
+        }
+        let left = self.value(left)?;
+        let right = self.value(right)?;
+        Ok(match op {
+            // Ints are `2n + 1`, so the tag bit is taken off after adding
+            // and put back after subtracting.
+            BinOp::AddInt => {
+                let sum = self.assign(format!("add i64 {left}, {right}"));
+                self.assign(format!("sub i64 {sum}, 1"))
+            }
+            BinOp::SubInt => {
+                let difference = self.assign(format!("sub i64 {left}, {right}"));
+                self.assign(format!("add i64 {difference}, 1"))
+            }
+            BinOp::MultInt => {
+                let left = self.assign(format!("ashr i64 {left}, 1"));
+                let right = self.assign(format!("sub i64 {right}, 1"));
+                let product = self.assign(format!("mul i64 {left}, {right}"));
+                self.assign(format!("or i64 {product}, 1"))
+            }
+            BinOp::DivInt => {
+                self.runtime("gleam_int_divide", "i64", &[("i64", left), ("i64", right)])
+            }
+            BinOp::RemainderInt => self.runtime(
+                "gleam_int_remainder",
+                "i64",
+                &[("i64", left), ("i64", right)],
+            ),
+            BinOp::AddFloat | BinOp::SubFloat | BinOp::MultFloat | BinOp::DivFloat => {
+                let left = self.unbox_float(&left);
+                let right = self.unbox_float(&right);
+                let result = match op {
+                    BinOp::AddFloat => self.assign(format!("fadd double {left}, {right}")),
+                    BinOp::SubFloat => self.assign(format!("fsub double {left}, {right}")),
+                    BinOp::MultFloat => self.assign(format!("fmul double {left}, {right}")),
+                    _ => self.runtime(
+                        "gleam_float_divide",
+                        "double",
+                        &[("double", left), ("double", right)],
+                    ),
+                };
+                self.runtime("gleam_float", "i64", &[("double", result)])
+            }
+            BinOp::Concatenate => self.runtime(
+                "gleam_string_concat",
+                "i64",
+                &[("i64", left), ("i64", right)],
+            ),
+            _ => unreachable!("{op:?} is a comparison"),
+        })
+    }
+
+    fn call(&mut self, callee: Expression<'a>, args: Vec<Expression<'a>>) -> Result<String, Error> {
+        let symbol = match callee {
+            Expression::Accessor(Accessor::ModuleVariable { module, name, .. }) => {
+                let module = module.join("/");
+                let symbol = function_symbol(&module, name);
+                if module != self.generator.name {
+                    self.generator.declare_function(&symbol, args.len());
+                }
+                symbol
+            }
+            Expression::Accessor(Accessor::External { name, .. }) => {
+                let symbol = format!("@\"{name}\"");
+                self.generator.declare_function(&symbol, args.len());
+                symbol
+            }
+            Expression::TypeConstruction(TypeConstruction::Function {
+                args: parameters,
+                body,
+                ..
+            }) if parameters.is_empty() && args.is_empty() => return self.inline(body),
+            callee => {
+                let closure = self.value(callee)?;
+                let args = self.values(args)?;
+                let pointer = self.load_word(&closure, "1");
+                let function = self.assign(format!(
+                    "inttoptr i64 {pointer} to {}",
+                    closure_function_type(args.len())
+                ));
+                let args = std::iter::once(closure)
+                    .chain(args)
+                    .map(|arg| format!("i64 {arg}"))
+                    .join(", ");
+                return Ok(self.assign(format!("tail call i64 {function}({args})")));
+            }
+        };
+        let args = self
+            .values(args)?
+            .into_iter()
+            .map(|arg| format!("i64 {arg}"))
+            .join(", ");
+        Ok(self.assign(format!("tail call i64 {symbol}({args})")))
+    }
+
+    /// The body of a function that is called as soon as it is made, which
+    /// the IR uses for expressions that need statements, is written in place.
+    fn inline(&mut self, body: Vec<Statement<'a>>) -> Result<String, Error> {
+        let label = self.label("continue");
+        self.continuations.push(Continuation {
+            label: label.clone(),
+            results: vec![],
+        });
+        self.statements(body)?;
+        if !self.terminated {
+            self.panic_unmatched();
+        }
+        let continuation = self
+            .continuations
+            .pop()
+            .expect("The continuation of the inlined function");
+        self.start(&label);
+        if continuation.results.is_empty() {
+            return Ok("undef".into());
+        }
+        let incoming = continuation
+            .results
+            .iter()
+            .map(|(value, block)| format!("[ {value}, %{block} ]"))
+            .join(", ");
+        Ok(self.assign(format!("phi i64 {incoming}")))
+    }
+
+    fn accessor(&mut self, accessor: Accessor<'a>) -> Result<String, Error> {
+        Ok(match accessor {
+            Accessor::Custom {
+                index,
+                variants,
+                reciever,
+                ..
+            } => {
+                let record = self.value(*reciever)?;
+                if variants.is_empty() {
+                    return Ok(self.load_word(&record, &(index + 2).to_string()));
+                }
+                // The field is at a different position in some of the
+                // constructors, which are told apart by their tag.
+                let tag = self.load_word(&record, "1");
+                let mut position = index.to_string();
+                for variant in variants {
+                    let expected = self.generator.tag(&variant.constructor);
+                    let is_variant = self.assign(format!("icmp eq i64 {tag}, {expected}"));
+                    position = self.assign(format!(
+                        "select i1 {is_variant}, i64 {}, i64 {position}",
+                        variant.index
+                    ));
+                }
+                let word = self.assign(format!("add i64 {position}, 2"));
+                self.load_word(&record, &word)
+            }
+            Accessor::TupleIndex { index, tuple } => {
+                let tuple = self.value(*tuple)?;
+                self.load_word(&tuple, &(index + 2).to_string())
+            }
+            Accessor::LocalVariable { name, .. } => self
+                .variables
+                .get(&name)
+                .cloned()
+                .expect("Variables to be assigned before they are used"),
+            Accessor::ModuleVariable { module, name, .. } => {
+                self.generator.function_closure(&module.join("/"), name)
+            }
+            Accessor::External { name, typ } => {
+                let arity = collapse_links(typ).fn_arity().unwrap_or_default();
+                self.generator.external_closure(name, arity)
+            }
+        })
+    }
+
+    fn construction(&mut self, construction: TypeConstruction<'a>) -> Result<String, Error> {
+        Ok(match construction {
+            TypeConstruction::Tuple { elements, .. } => {
+                let mut words = vec![header(3, elements.len()), "0".into()];
+                words.extend(self.values(elements)?);
+                self.allocate(words)
+            }
+            TypeConstruction::List { elements, tail, .. } => {
+                let elements = self.values(elements)?;
+                let mut list = match tail {
+                    Some(tail) => self.value(*tail)?,
+                    None => self.generator.empty_list(),
+                };
+                if elements.is_empty() {
+                    return Ok(list);
+                }
+                let tag = self.generator.tag("List");
+                for element in elements.into_iter().rev() {
+                    list = self.allocate(vec![header(3, 2), tag.clone(), element, list]);
+                }
+                list
+            }
+            TypeConstruction::BitString { location, .. } => {
+                return Err(Error::Unsupported {
+                    feature: "Bit strings".into(),
+                    location,
+                })
+            }
+            TypeConstruction::Custom { name, args, .. } => {
+                let tag = self.generator.tag(name);
+                let mut words = vec![header(3, args.len()), tag];
+                words.extend(self.values(args)?);
+                self.allocate(words)
+            }
+            TypeConstruction::CustomSingleton { name, .. } => self.generator.singleton(name),
+            TypeConstruction::Function { args, body, .. } => self.closure(args, body)?,
+        })
+    }
+
+    /// An anonymous function is compiled to a function taking the closure,
+    /// from which it loads the variables it captures.
+    fn closure(
+        &mut self,
+        args: Vec<FunctionArg<'a>>,
+        body: Vec<Statement<'a>>,
+    ) -> Result<String, Error> {
+        let captured = free_variables(&args, &body)
+            .into_iter()
+            .filter_map(|identifier| {
+                let value = self.variables.get(&identifier)?.clone();
+                Some((identifier, value))
+            })
+            .collect_vec();
+        let arity = args.len();
+        let symbol = self.generator.lambda_symbol();
+
+        let mut writer = FunctionWriter::new(self.generator);
+        let parameters = writer.parameters(args.into_iter().map(|arg| Some(arg.name)).collect());
+        for (index, (identifier, _)) in captured.iter().enumerate() {
+            let value = writer.load_word("%self", &(index + 2).to_string());
+            let _ = writer.variables.insert(identifier.clone(), value);
+        }
+        writer.statements(body)?;
+        let parameters = std::iter::once("%self".to_string())
+            .chain(parameters)
+            .map(|parameter| format!("i64 {parameter}"))
+            .join(", ");
+        let definition = writer.finish(&format!("define internal i64 {symbol}({parameters})"));
+        self.generator.functions.push(definition);
+
+        let mut words = vec![
+            header(4, captured.len()),
+            format!(
+                "ptrtoint ({} {symbol} to i64)",
+                closure_function_type(arity)
+            ),
+        ];
+        words.extend(captured.into_iter().map(|(_, value)| value));
+        Ok(self.allocate(words))
+    }
+}
+
+/// The first word of an object of the kind with the given size.
+pub(super) fn header(kind: usize, size: usize) -> String {
+    (size << 8 | kind).to_string()
+}
+
+fn is_comparison(op: BinOp) -> bool {
+    matches!(
+        op,
+        BinOp::And
+            | BinOp::Or
+            | BinOp::Eq
+            | BinOp::NotEq
+            | BinOp::LtInt
+            | BinOp::LtEqInt
+            | BinOp::GtInt
+            | BinOp::GtEqInt
+            | BinOp::LtFloat
+            | BinOp::LtEqFloat
+            | BinOp::GtFloat
+            | BinOp::GtEqFloat
+    )
+}
+
+fn is_check(function: &BuiltinFn) -> bool {
+    matches!(
+        function,
+        BuiltinFn::ListIsEmpty | BuiltinFn::IsRecord { .. } | BuiltinFn::StringHasPrefix
+    )
+}
+
+/// Whether the expression is a value that is only equal to itself.
+fn is_immediate(expression: &Expression<'_>) -> bool {
+    matches!(
+        expression,
+        Expression::Literal(Literal::Int { .. } | Literal::Bool { .. } | Literal::Nil)
+            | Expression::TypeConstruction(TypeConstruction::CustomSingleton { .. })
+    )
+}
+
+/// The variables used by an anonymous function that are not its own, in the
+/// order they are first used.
+fn free_variables<'a>(args: &[FunctionArg<'a>], body: &[Statement<'a>]) -> Vec<Identifier<'a>> {
+    let mut variables = Variables::default();
+    for arg in args {
+        let _ = variables.defined.insert(arg.name.clone());
+    }
+    variables.statements(body);
+    let Variables { used, defined } = variables;
+    used.into_iter()
+        .filter(|identifier| !defined.contains(identifier))
+        .unique()
+        .collect()
+}
+
+#[derive(Debug, Default)]
+struct Variables<'a> {
+    used: Vec<Identifier<'a>>,
+    defined: HashSet<Identifier<'a>>,
+}
+
+impl<'a> Variables<'a> {
+    fn statements(&mut self, statements: &[Statement<'a>]) {
+        for statement in statements {
+            match statement {
+                Statement::Return { expr } | Statement::Expr { expr } => self.expression(expr),
+                Statement::Assignment { var, expr, .. } => {
+                    let _ = self.defined.insert(var.clone());
+                    self.expression(expr);
+                }
+                Statement::Conditional { test, body } => {
+                    self.expression(test);
+                    self.statements(body);
+                }
+            }
+        }
+    }
+
+    fn expressions(&mut self, expressions: &[Expression<'a>]) {
+        for expression in expressions {
+            self.expression(expression);
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression<'a>) {
+        match expression {
+            Expression::Literal(_) => (),
+            Expression::Call(Call::Fn { callee, args }) => {
+                self.expression(callee);
+                self.expressions(args);
+            }
+            Expression::Call(Call::Builtin { args, .. }) => self.expressions(args),
+            Expression::Accessor(Accessor::LocalVariable { name, .. }) => {
+                self.used.push(name.clone())
+            }
+            Expression::Accessor(Accessor::Custom { reciever, .. }) => self.expression(reciever),
+            Expression::Accessor(Accessor::TupleIndex { tuple, .. }) => self.expression(tuple),
+            Expression::Accessor(Accessor::ModuleVariable { .. } | Accessor::External { .. }) => {}
+            Expression::TypeConstruction(TypeConstruction::Tuple { elements, .. }) => {
+                self.expressions(elements)
+            }
+            Expression::TypeConstruction(TypeConstruction::List { elements, tail, .. }) => {
+                self.expressions(elements);
+                if let Some(tail) = tail {
+                    self.expression(tail);
+                }
+            }
+            Expression::TypeConstruction(TypeConstruction::Custom { args, .. }) => {
+                self.expressions(args)
+            }
+            Expression::TypeConstruction(TypeConstruction::CustomSingleton { .. }) => (),
+            Expression::TypeConstruction(TypeConstruction::BitString { segments, .. }) => {
+                for segment in segments {
+                    self.expression(&segment.value);
+                    if let BitStringSegmentKind::Int { size } = &segment.kind {
+                        self.expression(size);
+                    }
+                }
+            }
+            Expression::TypeConstruction(TypeConstruction::Function { args, body, .. }) => {
+                for arg in args {
+                    let _ = self.defined.insert(arg.name.clone());
+                }
+                self.statements(body);
+            }
+            Expression::BinOp { left, right, .. } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            Expression::UnaryOp { expr, .. } => self.expression(expr),
+        }
+    }
+}
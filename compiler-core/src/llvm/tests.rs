@@ -0,0 +1,91 @@
+mod custom_types;
+mod functions;
+mod patterns;
+
+#[macro_export]
+macro_rules! assert_llvm {
+    ($src:expr $(,)?) => {{
+        let output = $crate::llvm::tests::compile($src).expect("should compile to LLVM IR");
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    }};
+}
+
+#[macro_export]
+macro_rules! assert_llvm_unsupported {
+    ($src:expr, $feature:expr $(,)?) => {{
+        match $crate::llvm::tests::compile($src) {
+            Err($crate::Error::Llvm {
+                error: $crate::llvm::Error::Unsupported { feature, .. },
+                ..
+            }) => assert_eq!(feature, $feature),
+            other => panic!("expected an unsupported feature error, got {other:?}"),
+        }
+    }};
+}
+
+pub fn compile(src: &str) -> Result<String, crate::Error> {
+    use crate::{
+        build::{Origin, Target},
+        type_::{build_prelude, infer_module},
+        uid::UniqueIdGenerator,
+    };
+    use std::path::Path;
+
+    let arena = crate::ast::Arena::new();
+    let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+    ast.name = vec!["my".into(), "module".into()];
+    let mut modules = im::HashMap::new();
+    let ids = UniqueIdGenerator::new();
+    let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+    let ast = infer_module(
+        Target::Llvm,
+        &ids,
+        ast,
+        Origin::Src,
+        "my_package",
+        &modules,
+        &mut vec![],
+    )
+    .expect("should successfully infer");
+    let mut output = String::new();
+    super::module(
+        &ast,
+        super::PointerWidth::Bits64,
+        Path::new("src/my/module.gleam"),
+        src,
+        &mut output,
+    )?;
+    Ok(output)
+}
+
+#[test]
+fn module_path() {
+    assert_eq!(
+        super::module_path("gleam/list"),
+        std::path::PathBuf::from("gleam@list.ll")
+    );
+    assert_eq!(
+        super::module_path("app"),
+        std::path::PathBuf::from("app.ll")
+    );
+}
+
+#[test]
+fn entrypoint() {
+    insta::assert_snapshot!(super::entrypoint("my/app"));
+}
+
+#[test]
+fn int_literals() {
+    assert_eq!(super::int("0"), "1");
+    assert_eq!(super::int("21"), "43");
+    assert_eq!(super::int("-1"), "-1");
+    assert_eq!(super::int("0xFF"), "511");
+    assert_eq!(super::int("1_000"), "2001");
+}
+
+#[test]
+fn string_escapes() {
+    assert_eq!(super::unescape(r#"a\n\"b\\"#), b"a\n\"b\\".to_vec());
+    assert_eq!(super::escape(b"a\n\"b\\"), r#"a\0A\22b\5C"#);
+}
@@ -0,0 +1,83 @@
+use crate::assert_llvm;
+
+#[test]
+fn records() {
+    assert_llvm!(
+        r#"
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main() -> Int {
+  let person = Person("Lucy", 10)
+  person.age
+}
+"#
+    );
+}
+
+#[test]
+fn constructors_without_fields() {
+    assert_llvm!(
+        r#"
+pub type Colour {
+  Red
+  Green
+}
+
+pub fn main() -> List(Colour) {
+  [Red, Green]
+}
+"#
+    );
+}
+
+#[test]
+fn constructor_as_function() {
+    assert_llvm!(
+        r#"
+pub type Box {
+  Box(Int)
+}
+
+pub fn main() -> fn(Int) -> Box {
+  Box
+}
+"#
+    );
+}
+
+#[test]
+fn shared_labels() {
+    assert_llvm!(
+        r#"
+pub type Shape {
+  Circle(name: String, radius: Float)
+  Square(size: Float, name: String)
+}
+
+pub fn name(shape: Shape) -> String {
+  shape.name
+}
+"#
+    );
+}
+
+#[test]
+fn multiple_constructors() {
+    assert_llvm!(
+        r#"
+pub type Shape {
+  Circle(radius: Float)
+  Rectangle(width: Float, height: Float)
+}
+
+pub fn area(shape: Shape) -> Float {
+  case shape {
+    Circle(r) -> 3.0 *. r *. r
+    Rectangle(width: w, height: h) -> w *. h
+  }
+}
+"#
+    );
+}
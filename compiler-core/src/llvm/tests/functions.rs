@@ -0,0 +1,247 @@
+use crate::{assert_llvm, assert_llvm_unsupported};
+
+#[test]
+fn exported_functions() {
+    assert_llvm!(
+        r#"
+pub fn add(x: Int, y: Int) -> Int {
+  x + y
+}
+"#
+    );
+}
+
+#[test]
+fn private_functions() {
+    assert_llvm!(
+        r#"
+fn add(x: Int, y: Int) -> Int {
+  x + y
+}
+"#
+    );
+}
+
+#[test]
+fn int_operators() {
+    assert_llvm!(
+        r#"
+pub fn calculate(a: Int, b: Int) -> Int {
+  a * b - a / b + a % b
+}
+"#
+    );
+}
+
+#[test]
+fn float_operators() {
+    assert_llvm!(
+        r#"
+pub fn calculate(a: Float, b: Float) -> Bool {
+  a *. b +. 1.5 <. a /. b
+}
+"#
+    );
+}
+
+#[test]
+fn boolean_operators() {
+    assert_llvm!(
+        r#"
+pub fn between(n: Int, low: Int, high: Int) -> Bool {
+  n >= low && n <= high || !True
+}
+"#
+    );
+}
+
+#[test]
+fn equality() {
+    assert_llvm!(
+        r#"
+pub fn same(a: String, b: String, n: Int) -> Bool {
+  a == b && n != 0
+}
+"#
+    );
+}
+
+#[test]
+fn strings() {
+    assert_llvm!(
+        r#"
+pub fn greet(name: String) -> String {
+  "Hello, \"" <> name <> "\"\n"
+}
+"#
+    );
+}
+
+#[test]
+fn lists_and_tuples() {
+    assert_llvm!(
+        r#"
+pub fn build(x: Int, xs: List(Int)) -> #(List(Int), Float) {
+  #([1, x, ..xs], 2.0)
+}
+"#
+    );
+}
+
+#[test]
+fn calls() {
+    assert_llvm!(
+        r#"
+fn double(x: Int) -> Int {
+  x * 2
+}
+
+pub fn main() -> Int {
+  double(double(1))
+}
+"#
+    );
+}
+
+#[test]
+fn functions_as_values() {
+    assert_llvm!(
+        r#"
+fn double(x: Int) -> Int {
+  x * 2
+}
+
+fn apply(f: fn(Int) -> Int, x: Int) -> Int {
+  f(x)
+}
+
+pub fn main() -> Int {
+  apply(double, 1)
+}
+"#
+    );
+}
+
+#[test]
+fn anonymous_functions() {
+    assert_llvm!(
+        r#"
+pub fn adder(x: Int) -> fn(Int) -> Int {
+  fn(y) { x + y }
+}
+"#
+    );
+}
+
+#[test]
+fn blocks_as_values() {
+    assert_llvm!(
+        r#"
+pub fn main() -> Int {
+  let x = {
+    let y = 1
+    y + 1
+  }
+  x
+}
+"#
+    );
+}
+
+#[test]
+fn external_functions() {
+    assert_llvm!(
+        r#"
+@external(llvm, "ffi.ll", "gleam_add")
+fn add(a: Int, b: Int) -> Int {
+  a + b
+}
+
+pub fn main() -> fn(Int, Int) -> Int {
+  let _ = add(1, 2)
+  add
+}
+"#
+    );
+}
+
+#[test]
+fn constants() {
+    assert_llvm!(
+        r#"
+const greeting = "Hello"
+const numbers = [1, 2]
+
+pub fn main() -> #(String, List(Int)) {
+  #(greeting, numbers)
+}
+"#
+    );
+}
+
+#[test]
+fn todo_and_echo() {
+    assert_llvm!(
+        r#"
+pub fn main() -> Int {
+  echo 1
+}
+
+pub fn later() -> Int {
+  todo("soon")
+}
+"#
+    );
+}
+
+#[test]
+fn other_externals_are_unsupported() {
+    assert_llvm_unsupported!(
+        r#"
+@external(llvm, "ffi", "add")
+fn add(a: Int, b: Int) -> Int {
+  a + b
+}
+"#,
+        "External functions not defined in a `.ll` file"
+    );
+}
+
+#[test]
+fn bit_strings_are_unsupported() {
+    assert_llvm_unsupported!(
+        r#"
+pub fn main() {
+  <<1, 2>>
+}
+"#,
+        "Bit strings"
+    );
+}
+
+#[test]
+fn bit_string_patterns_are_unsupported() {
+    assert_llvm_unsupported!(
+        r#"
+pub fn main(x) {
+  case x {
+    <<len, payload:binary-size(len)>> -> payload
+    _ -> x
+  }
+}
+"#,
+        "Bit string patterns"
+    );
+}
+
+#[test]
+fn rescue_is_unsupported() {
+    assert_llvm_unsupported!(
+        r#"
+pub fn main() {
+  rescue(fn() { 1 })
+}
+"#,
+        "Calls to `rescue`"
+    );
+}
@@ -0,0 +1,135 @@
+use crate::assert_llvm;
+
+#[test]
+fn literal_patterns() {
+    assert_llvm!(
+        r#"
+pub fn classify(n: Int) -> String {
+  case n {
+    0 -> "zero"
+    1 | 2 -> "small"
+    _ -> "other"
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn guards() {
+    assert_llvm!(
+        r#"
+pub fn sign(n: Int) -> Int {
+  case n {
+    _ if n > 0 -> 1
+    _ if n < 0 -> -1
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn list_patterns() {
+    assert_llvm!(
+        r#"
+pub fn sum(list: List(Int)) -> Int {
+  case list {
+    [] -> 0
+    [x] -> x
+    [x, ..xs] -> x + sum(xs)
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn tuple_patterns() {
+    assert_llvm!(
+        r#"
+pub fn both(a: Bool, b: Bool) -> String {
+  case a, b {
+    True, True -> "both"
+    True, False | False, True -> "one"
+    False, False -> "neither"
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn constructor_patterns() {
+    assert_llvm!(
+        r#"
+pub fn unwrap(result: Result(Int, String)) -> Int {
+  case result {
+    Ok(n) if n > 0 -> n
+    Ok(_) -> 0
+    Error(_) -> -1
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn string_prefix_patterns() {
+    assert_llvm!(
+        r#"
+pub fn greeting(s: String) -> String {
+  case s {
+    "Hello, " <> name -> name
+    _ -> "stranger"
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn assignment_patterns() {
+    assert_llvm!(
+        r#"
+pub fn first(pair: #(Int, String), list: List(Int)) -> Int {
+  let #(a, _) = pair
+  assert [b, ..] = list
+  a + b
+}
+"#
+    );
+}
+
+#[test]
+fn case_expressions_as_values() {
+    assert_llvm!(
+        r#"
+pub fn main(n: Int) -> Int {
+  let x = case n {
+    1 -> 2
+    n -> n
+  }
+  x + 1
+}
+"#
+    );
+}
+
+#[test]
+fn assert_pattern_with_message() {
+    assert_llvm!(
+        r#"
+pub type Shape {
+  Circle(radius: Int)
+  Square(side: Int)
+}
+
+pub fn radius(shape: Shape) -> Int {
+  assert Circle(radius) = shape as "shape should always be a circle"
+  radius
+}
+"#
+    );
+}
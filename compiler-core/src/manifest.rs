@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 
-use crate::Result;
-use hexpm::version::{Range, Version};
+use crate::{config::Requirement, Result};
+use hexpm::version::Version;
 use itertools::Itertools;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
 pub struct Manifest {
     #[serde(serialize_with = "ordered_map")]
-    pub requirements: HashMap<String, Range>,
+    pub requirements: HashMap<String, Requirement>,
     #[serde(serialize_with = "sorted_vec")]
     pub packages: Vec<ManifestPackage>,
 }
@@ -66,14 +66,25 @@ impl Manifest {
             }
 
             match source {
-                ManifestPackageSource::Hex { outer_checksum } => {
+                ManifestPackageSource::Hex {
+                    outer_checksum,
+                    registry,
+                } => {
                     write!(buffer, r#", source = "hex""#)?;
+                    if let Some(registry) = registry {
+                        write!(buffer, r#", registry = "{}""#, registry)?;
+                    }
                     write!(
                         buffer,
                         r#", outer_checksum = "{}""#,
                         outer_checksum.to_string()
                     )?;
                 }
+                ManifestPackageSource::Git { repo, commit } => {
+                    write!(buffer, r#", source = "git""#)?;
+                    write!(buffer, r#", repo = "{}""#, repo)?;
+                    write!(buffer, r#", commit = "{}""#, commit)?;
+                }
             };
 
             writeln!(buffer, " }},")?;
@@ -82,8 +93,14 @@ impl Manifest {
 
         // Requirements
         writeln!(buffer, "[requirements]")?;
-        for (name, range) in requirements.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
-            writeln!(buffer, "{} = \"{}\"", name, range)?;
+        for (name, requirement) in requirements.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+            match requirement {
+                Requirement::Hex {
+                    version,
+                    registry: None,
+                } => writeln!(buffer, "{} = \"{}\"", name, version)?,
+                _ => writeln!(buffer, "{} = {}", name, requirement)?,
+            }
         }
 
         Ok(())
@@ -95,13 +112,14 @@ fn manifest_toml_format() {
     let mut buffer = String::new();
     let mut manifest = Manifest {
         requirements: [
-            ("zzz".to_string(), Range::new("> 0.0.0".to_string())),
-            ("aaa".to_string(), Range::new("> 0.0.0".to_string())),
+            ("zzz".to_string(), Requirement::hex("> 0.0.0")),
+            ("aaa".to_string(), Requirement::hex("> 0.0.0")),
+            ("gleam_stdlib".to_string(), Requirement::hex("~> 0.17")),
+            ("gleeunit".to_string(), Requirement::hex("~> 0.1")),
             (
-                "gleam_stdlib".to_string(),
-                Range::new("~> 0.17".to_string()),
+                "gleam_git".to_string(),
+                Requirement::git("https://github.com/gleam-lang/git.git", "v1"),
             ),
-            ("gleeunit".to_string(), Range::new("~> 0.1".to_string())),
         ]
         .into(),
         packages: vec![
@@ -113,6 +131,7 @@ fn manifest_toml_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 22]),
+                    registry: None,
                 },
             },
             ManifestPackage {
@@ -123,6 +142,7 @@ fn manifest_toml_format() {
                 requirements: vec!["zzz".into(), "gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    registry: None,
                 },
             },
             ManifestPackage {
@@ -133,6 +153,7 @@ fn manifest_toml_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    registry: Some("acme".into()),
                 },
             },
             ManifestPackage {
@@ -143,6 +164,18 @@ fn manifest_toml_format() {
                 requirements: vec!["gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 46]),
+                    registry: None,
+                },
+            },
+            ManifestPackage {
+                name: "gleam_git".to_string(),
+                version: Version::new(1, 2, 0),
+                build_tools: ["gleam".into()].into(),
+                otp_app: None,
+                requirements: vec!["gleam_stdlib".into()],
+                source: ManifestPackageSource::Git {
+                    repo: "https://github.com/gleam-lang/git.git".into(),
+                    commit: "0123456789abcdef".into(),
                 },
             },
         ],
@@ -155,13 +188,15 @@ fn manifest_toml_format() {
 
 packages = [
   { name = "aaa", version = "0.4.0", build_tools = ["rebar3", "make"], requirements = ["zzz", "gleam_stdlib"], otp_app = "aaa_app", source = "hex", outer_checksum = "0316" },
+  { name = "gleam_git", version = "1.2.0", build_tools = ["gleam"], requirements = ["gleam_stdlib"], source = "git", repo = "https://github.com/gleam-lang/git.git", commit = "0123456789abcdef" },
   { name = "gleam_stdlib", version = "0.17.1", build_tools = ["gleam"], requirements = [], source = "hex", outer_checksum = "0116" },
   { name = "gleeunit", version = "0.4.0", build_tools = ["gleam"], requirements = ["gleam_stdlib"], source = "hex", outer_checksum = "032E" },
-  { name = "zzz", version = "0.4.0", build_tools = ["mix"], requirements = [], source = "hex", outer_checksum = "0316" },
+  { name = "zzz", version = "0.4.0", build_tools = ["mix"], requirements = [], source = "hex", registry = "acme", outer_checksum = "0316" },
 ]
 
 [requirements]
 aaa = "> 0.0.0"
+gleam_git = { git = "https://github.com/gleam-lang/git.git", ref = "v1" }
 gleam_stdlib = "~> 0.17"
 gleeunit = "~> 0.1"
 zzz = "> 0.0.0"
@@ -217,8 +252,17 @@ pub struct ManifestPackage {
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "source")]
 pub enum ManifestPackageSource {
+    /// A package downloaded from hex.pm, or from another registry given in
+    /// gleam.toml.
     #[serde(rename = "hex")]
-    Hex { outer_checksum: Base16Checksum },
+    Hex {
+        outer_checksum: Base16Checksum,
+        #[serde(default)]
+        registry: Option<String>,
+    },
+    /// A package fetched from a git repository, locked to a commit.
+    #[serde(rename = "git")]
+    Git { repo: String, commit: String },
 }
 
 fn ordered_map<S, K, V>(value: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
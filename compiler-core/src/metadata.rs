@@ -1,5 +1,16 @@
 //! Seriaisation and deserialisation of Gleam compiler metadata into binary files
 //! using the Cap'n Proto schema.
+//!
+//! A metadata file starts with a header: the `GLMD` magic bytes followed by
+//! the major and minor versions of the format, each a big endian `u16`. The
+//! Cap'n Proto message follows the header.
+//!
+//! The minor version is incremented when fields or union variants are added
+//! to the schema. A decoder reads files of any minor version of its major
+//! version, skipping the definitions that use variants it does not know of,
+//! so that precompiled dependencies survive compiler upgrades. Any other
+//! change to the schema increments the major version, and files of another
+//! major version are rejected as incompatible.
 
 mod module_decoder;
 mod module_encoder;
@@ -7,4 +18,66 @@ mod module_encoder;
 #[cfg(test)]
 mod tests;
 
+use std::io::Read;
+
+use crate::{Error, Result};
+
 pub use self::{module_decoder::ModuleDecoder, module_encoder::ModuleEncoder};
+
+/// The bytes each metadata file starts with.
+pub const MAGIC: &[u8; 4] = b"GLMD";
+
+/// The major version of the metadata format written by this compiler.
+pub const FORMAT_MAJOR_VERSION: u16 = 1;
+
+/// The minor version of the metadata format written by this compiler.
+pub const FORMAT_MINOR_VERSION: u16 = 0;
+
+/// The version of the format a metadata file was written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FormatVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl FormatVersion {
+    /// The version of the format written by this compiler.
+    pub const CURRENT: Self = Self {
+        major: FORMAT_MAJOR_VERSION,
+        minor: FORMAT_MINOR_VERSION,
+    };
+
+    /// Whether files of this version can be read by this compiler.
+    pub fn is_compatible(&self) -> bool {
+        self.major == FORMAT_MAJOR_VERSION
+    }
+
+    pub(crate) fn header(&self) -> [u8; 8] {
+        let [major_high, major_low] = self.major.to_be_bytes();
+        let [minor_high, minor_low] = self.minor.to_be_bytes();
+        let [m0, m1, m2, m3] = *MAGIC;
+        [m0, m1, m2, m3, major_high, major_low, minor_high, minor_low]
+    }
+}
+
+/// Reads the header of a metadata file, leaving the reader at the start of
+/// the Cap'n Proto message. Tools reading metadata can use this to check the
+/// version before decoding. Files without a header, such as those written
+/// by compilers from before the header was added, are incompatible.
+pub fn read_format_version(reader: &mut impl Read) -> Result<FormatVersion> {
+    let mut header = [0; 8];
+    reader
+        .read_exact(&mut header)
+        .map_err(|_| Error::IncompatibleMetadata { version: None })?;
+    match header {
+        [m0, m1, m2, m3, major_high, major_low, minor_high, minor_low]
+            if [m0, m1, m2, m3] == *MAGIC =>
+        {
+            Ok(FormatVersion {
+                major: u16::from_be_bytes([major_high, major_low]),
+                minor: u16::from_be_bytes([minor_high, minor_low]),
+            })
+        }
+        _ => Err(Error::IncompatibleMetadata { version: None }),
+    }
+}
@@ -4,17 +4,18 @@ use itertools::Itertools;
 
 use crate::{
     ast::{
-        BitStringSegment, BitStringSegmentOption, CallArg, Constant, SrcSpan, TypedConstant,
-        TypedConstantBitStringSegment, TypedConstantBitStringSegmentOption,
+        BitStringSegment, BitStringSegmentOption, CallArg, Constant, Deprecation, Name, SrcSpan,
+        TypedConstant, TypedConstantBitStringSegment, TypedConstantBitStringSegmentOption,
     },
     build::Origin,
+    metadata::{self, FormatVersion},
     schema_capnp::{self as schema, *},
     type_::{
         self, AccessorsMap, FieldMap, Module, RecordAccessor, Type, TypeConstructor,
         ValueConstructor, ValueConstructorVariant,
     },
     uid::UniqueIdGenerator,
-    Result,
+    Error, Result,
 };
 use std::{collections::HashMap, io::BufRead, sync::Arc};
 
@@ -36,8 +37,17 @@ macro_rules! read_hashmap {
         let mut map = HashMap::with_capacity(reader.len() as usize);
         for prop in reader.into_iter() {
             let name = prop.get_key()?;
-            let type_ = $self.$method(&prop.get_value()?)?;
-            let _ = map.insert(name.to_string(), type_);
+            match $self.$method(&prop.get_value()?) {
+                Ok(value) => {
+                    let _ = map.insert(name.into(), value);
+                }
+                // Definitions using features added in a newer minor version
+                // of the format are skipped
+                Err(error) if $self.skip_unknown => {
+                    tracing::debug!(name = %name, error = ?error, "skipping_unknown_metadata");
+                }
+                Err(error) => return Err(error),
+            }
         }
         map
     }};
@@ -47,6 +57,7 @@ macro_rules! read_hashmap {
 pub struct ModuleDecoder {
     ids: UniqueIdGenerator,
     type_var_id_map: HashMap<u64, u64>,
+    skip_unknown: bool,
 }
 
 impl ModuleDecoder {
@@ -54,10 +65,19 @@ impl ModuleDecoder {
         Self {
             ids,
             type_var_id_map: Default::default(),
+            skip_unknown: false,
         }
     }
 
-    pub fn read(&mut self, reader: impl BufRead) -> Result<Module> {
+    pub fn read(&mut self, mut reader: impl BufRead) -> Result<Module> {
+        let version = metadata::read_format_version(&mut reader)?;
+        if !version.is_compatible() {
+            return Err(Error::IncompatibleMetadata {
+                version: Some(version),
+            });
+        }
+        self.skip_unknown = version > FormatVersion::CURRENT;
+
         let message_reader =
             capnp::serialize_packed::read_message(reader, capnp::message::ReaderOptions::new())?;
         let reader = message_reader.get_root::<module::Reader<'_>>()?;
@@ -89,6 +109,7 @@ impl ModuleDecoder {
             module,
             parameters: read_vec!(reader.get_parameters()?, self, type_),
             typ: type_,
+            deprecation: deprecation(reader.get_deprecated()?),
         })
     }
 
@@ -109,7 +130,7 @@ impl ModuleDecoder {
         Ok(Arc::new(Type::App {
             public: true,
             module,
-            name,
+            name: name.into(),
             args,
         }))
     }
@@ -138,8 +159,8 @@ impl ModuleDecoder {
         Ok(type_::generic_var(id))
     }
 
-    fn constructors_list(&mut self, reader: &capnp::text_list::Reader<'_>) -> Result<Vec<String>> {
-        let vec = reader.iter().map_ok(String::from).try_collect()?;
+    fn constructors_list(&mut self, reader: &capnp::text_list::Reader<'_>) -> Result<Vec<Name>> {
+        let vec = reader.iter().map_ok(Name::from).try_collect()?;
         Ok(vec)
     }
 
@@ -151,6 +172,7 @@ impl ModuleDecoder {
         let variant = self.value_constructor_variant(&reader.get_variant()?)?;
         Ok(ValueConstructor {
             public: true,
+            deprecation: deprecation(reader.get_deprecated()?),
             type_,
             variant,
         })
@@ -219,7 +241,7 @@ impl ModuleDecoder {
             module: Default::default(),
             name: Default::default(),
             args,
-            tag,
+            tag: tag.into(),
             typ: type_,
             field_map: None,
         })
@@ -257,8 +279,8 @@ impl ModuleDecoder {
         let constructor = self.value_constructor(&reader.get_constructor()?)?;
         Ok(Constant::Var {
             location: Default::default(),
-            module: module.map(String::from),
-            name: String::from(name),
+            module: module.map(Name::from),
+            name: name.into(),
             constructor: Some(Box::from(constructor)),
             typ: type_,
         })
@@ -358,7 +380,7 @@ impl ModuleDecoder {
         Ok(ValueConstructorVariant::ModuleConstant {
             location: self.src_span(&reader.get_location()?)?,
             literal: self.constant(&reader.get_literal()?)?,
-            module: reader.get_module()?.to_string(),
+            module: reader.get_module()?.into(),
         })
     }
 
@@ -374,7 +396,7 @@ impl ModuleDecoder {
         reader: &value_constructor_variant::module_fn::Reader<'_>,
     ) -> Result<ValueConstructorVariant> {
         Ok(ValueConstructorVariant::ModuleFn {
-            name: reader.get_name()?.to_string(),
+            name: reader.get_name()?.into(),
             module: module_name(&reader.get_module()?)?,
             arity: reader.get_arity() as usize,
             field_map: self.field_map(&reader.get_field_map()?)?,
@@ -387,10 +409,11 @@ impl ModuleDecoder {
         reader: &value_constructor_variant::record::Reader<'_>,
     ) -> Result<ValueConstructorVariant> {
         Ok(ValueConstructorVariant::Record {
-            name: reader.get_name()?.to_string(),
-            module: reader.get_module()?.to_string(),
+            name: reader.get_name()?.into(),
+            module: reader.get_module()?.into(),
             arity: reader.get_arity(),
             constructors_count: reader.get_constructors_count(),
+            erlang_map: reader.get_erlang_map(),
             field_map: self.field_map(&reader.get_field_map()?)?,
             location: self.src_span(&reader.get_location()?)?,
         })
@@ -419,19 +442,30 @@ impl ModuleDecoder {
             public: true,
             type_: self.type_(&reader.get_type()?)?,
             accessors: read_hashmap!(&reader.get_accessors()?, self, record_accessor),
+            erlang_map: reader.get_erlang_map(),
         })
     }
 
     fn record_accessor(&mut self, reader: &record_accessor::Reader<'_>) -> Result<RecordAccessor> {
         Ok(RecordAccessor {
             index: reader.get_index() as u64,
-            label: reader.get_label()?.to_string(),
+            label: reader.get_label()?.into(),
             type_: self.type_(&reader.get_type()?)?,
         })
     }
 }
 
-fn module_name(module: &capnp::text_list::Reader<'_>) -> Result<Vec<String>> {
-    let name = module.iter().map_ok(String::from).try_collect()?;
+fn module_name(module: &capnp::text_list::Reader<'_>) -> Result<Vec<Name>> {
+    let name = module.iter().map_ok(Name::from).try_collect()?;
     Ok(name)
 }
+
+fn deprecation(message: &str) -> Deprecation {
+    if message.is_empty() {
+        Deprecation::NotDeprecated
+    } else {
+        Deprecation::Deprecated {
+            message: message.to_string(),
+        }
+    }
+}
@@ -1,15 +1,17 @@
 use crate::{
     ast::{
-        Constant, SrcSpan, TypedConstant, TypedConstantBitStringSegment,
+        Constant, Name, SrcSpan, TypedConstant, TypedConstantBitStringSegment,
         TypedConstantBitStringSegmentOption,
     },
     io::Writer,
+    metadata::FormatVersion,
     schema_capnp::{self as schema, *},
     type_::{
         self, AccessorsMap, FieldMap, RecordAccessor, Type, TypeConstructor, TypeVar,
         ValueConstructor, ValueConstructorVariant,
     },
 };
+use itertools::Itertools;
 use std::{collections::HashMap, ops::Deref, sync::Arc};
 
 #[derive(Debug)]
@@ -42,6 +44,7 @@ impl<'a> ModuleEncoder<'a> {
         module.set_package(&self.data.package);
         self.set_module_types_constructors(&mut module);
 
+        Writer::write(&mut writer, &FormatVersion::CURRENT.header())?;
         let result = capnp::serialize_packed::write_message(&mut writer, &message);
         result.map_err(|e| writer.convert_err(e))
     }
@@ -51,7 +54,13 @@ impl<'a> ModuleEncoder<'a> {
         let mut builder = module
             .reborrow()
             .init_accessors(self.data.accessors.len() as u32);
-        for (i, (key, map)) in self.data.accessors.iter().enumerate() {
+        for (i, (key, map)) in self
+            .data
+            .accessors
+            .iter()
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .enumerate()
+        {
             let mut property = builder.reborrow().get(i as u32);
             property.set_key(key);
             self.build_accessors_map(property.init_value(), map);
@@ -64,8 +73,14 @@ impl<'a> ModuleEncoder<'a> {
         accessors: &AccessorsMap,
     ) {
         self.build_type(builder.reborrow().init_type(), &accessors.type_);
+        builder.set_erlang_map(accessors.erlang_map);
         let mut builder = builder.init_accessors(accessors.accessors.len() as u32);
-        for (i, (name, accessor)) in accessors.accessors.iter().enumerate() {
+        for (i, (name, accessor)) in accessors
+            .accessors
+            .iter()
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .enumerate()
+        {
             let mut property = builder.reborrow().get(i as u32);
             property.set_key(name);
             self.build_record_accessor(property.init_value(), accessor)
@@ -89,7 +104,7 @@ impl<'a> ModuleEncoder<'a> {
         }
     }
 
-    fn build_module_name(&mut self, mut builder: capnp::text_list::Builder<'_>, module: &[String]) {
+    fn build_module_name(&mut self, mut builder: capnp::text_list::Builder<'_>, module: &[Name]) {
         for (i, s) in module.iter().enumerate() {
             builder.set(i as u32, s);
         }
@@ -98,7 +113,13 @@ impl<'a> ModuleEncoder<'a> {
     fn set_module_types(&mut self, module: &mut module::Builder<'_>) {
         tracing::trace!("Writing module metadata types");
         let mut types = module.reborrow().init_types(self.data.types.len() as u32);
-        for (i, (name, type_)) in self.data.types.iter().enumerate() {
+        for (i, (name, type_)) in self
+            .data
+            .types
+            .iter()
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .enumerate()
+        {
             let mut property = types.reborrow().get(i as u32);
             property.set_key(name);
             self.build_type_constructor(property.init_value(), type_)
@@ -110,7 +131,13 @@ impl<'a> ModuleEncoder<'a> {
         let mut types_constructors = module
             .reborrow()
             .init_types_constructors(self.data.types_constructors.len() as u32);
-        for (i, (name, constructors)) in self.data.types_constructors.iter().enumerate() {
+        for (i, (name, constructors)) in self
+            .data
+            .types_constructors
+            .iter()
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .enumerate()
+        {
             let mut property = types_constructors.reborrow().get(i as u32);
             property.set_key(name);
             self.build_types_constructors_mapping(
@@ -123,7 +150,13 @@ impl<'a> ModuleEncoder<'a> {
     fn set_module_values(&mut self, module: &mut module::Builder<'_>) {
         tracing::trace!("Writing module metadata values");
         let mut values = module.reborrow().init_values(self.data.values.len() as u32);
-        for (i, (name, value)) in self.data.values.iter().enumerate() {
+        for (i, (name, value)) in self
+            .data
+            .values
+            .iter()
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .enumerate()
+        {
             let mut property = values.reborrow().get(i as u32);
             property.set_key(name);
             self.build_value_constructor(property.init_value(), value)
@@ -144,15 +177,18 @@ impl<'a> ModuleEncoder<'a> {
             &constructor.parameters,
         );
         self.build_module_name(
-            builder.init_module(constructor.module.len() as u32),
+            builder
+                .reborrow()
+                .init_module(constructor.module.len() as u32),
             &constructor.module,
         );
+        builder.set_deprecated(constructor.deprecation.message().unwrap_or_default());
     }
 
     fn build_types_constructors_mapping(
         &mut self,
         mut builder: capnp::text_list::Builder<'_>,
-        constructors: &[String],
+        constructors: &[Name],
     ) {
         for (i, s) in constructors.iter().enumerate() {
             builder.set(i as u32, s);
@@ -165,7 +201,11 @@ impl<'a> ModuleEncoder<'a> {
         constructor: &ValueConstructor,
     ) {
         self.build_type(builder.reborrow().init_type(), &constructor.type_);
-        self.build_value_constructor_variant(builder.init_variant(), &constructor.variant);
+        self.build_value_constructor_variant(
+            builder.reborrow().init_variant(),
+            &constructor.variant,
+        );
+        builder.set_deprecated(constructor.deprecation.message().unwrap_or_default());
     }
 
     fn build_src_span(&mut self, mut builder: src_span::Builder<'_>, span: SrcSpan) {
@@ -201,12 +241,14 @@ impl<'a> ModuleEncoder<'a> {
                 location,
                 module,
                 constructors_count,
+                erlang_map,
             } => {
                 let mut builder = builder.init_record();
                 builder.set_name(name);
                 builder.set_module(module);
                 builder.set_arity(*arity);
                 builder.set_constructors_count(*constructors_count);
+                builder.set_erlang_map(*erlang_map);
                 self.build_optional_field_map(builder.reborrow().init_field_map(), field_map);
                 self.build_src_span(builder.init_location(), *location);
             }
@@ -247,7 +289,12 @@ impl<'a> ModuleEncoder<'a> {
     fn build_field_map(&mut self, mut builder: field_map::Builder<'_>, field_map: &FieldMap) {
         builder.set_arity(field_map.arity);
         let mut builder = builder.init_fields(field_map.fields.len() as u32);
-        for (i, (name, &position)) in field_map.fields.iter().enumerate() {
+        for (i, (name, &position)) in field_map
+            .fields
+            .iter()
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .enumerate()
+        {
             let mut field = builder.reborrow().get(i as u32);
             field.set_key(name);
             field.init_value().set_value(position);
@@ -313,6 +360,10 @@ impl<'a> ModuleEncoder<'a> {
                         .expect("This is guaranteed to hold a value."),
                 );
             }
+
+            Constant::BinOp { .. } => {
+                unreachable!("Constant binary operators are evaluated during type checking")
+            }
         }
     }
 
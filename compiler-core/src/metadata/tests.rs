@@ -4,8 +4,8 @@ use type_::{AccessorsMap, FieldMap, RecordAccessor};
 use super::*;
 use crate::{
     ast::{
-        BitStringSegment, BitStringSegmentOption, CallArg, Constant, SrcSpan, TypedConstant,
-        TypedConstantBitStringSegmentOption,
+        BitStringSegment, BitStringSegmentOption, CallArg, Constant, Deprecation, Name, SrcSpan,
+        TypedConstant, TypedConstantBitStringSegmentOption,
     },
     build::Origin,
     io::test::InMemoryFile,
@@ -30,13 +30,14 @@ fn constant_module(constant: TypedConstant) -> Module {
     Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string()],
+        name: vec!["a".into()],
         types: HashMap::new(),
         types_constructors: HashMap::new(),
         accessors: HashMap::new(),
         values: [(
-            "one".to_string(),
+            "one".into(),
             ValueConstructor {
+                deprecation: Deprecation::NotDeprecated,
                 public: true,
                 type_: type_::int(),
                 variant: ValueConstructorVariant::ModuleConstant {
@@ -70,7 +71,7 @@ fn empty_module() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["one".to_string(), "two".to_string()],
+        name: vec!["one".into(), "two".into()],
         types: HashMap::new(),
         types_constructors: HashMap::new(),
         values: HashMap::new(),
@@ -84,14 +85,15 @@ fn module_with_app_type() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string(), "b".to_string()],
+        name: vec!["a".into(), "b".into()],
         types: [(
-            "ListIntType".to_string(),
+            "ListIntType".into(),
             TypeConstructor {
+                deprecation: Deprecation::NotDeprecated,
                 typ: type_::list(type_::int()),
                 public: true,
                 origin: Default::default(),
-                module: vec!["the".to_string(), "module".to_string()],
+                module: vec!["the".into(), "module".into()],
                 parameters: vec![],
             },
         )]
@@ -103,19 +105,64 @@ fn module_with_app_type() {
     assert_eq!(roundtrip(&module), module);
 }
 
+#[test]
+fn deprecated_type_and_value() {
+    let module = Module {
+        package: "some_package".to_string(),
+        origin: Origin::Src,
+        name: vec!["a".into()],
+        types: [(
+            "Number".into(),
+            TypeConstructor {
+                deprecation: Deprecation::Deprecated {
+                    message: "Use Int instead".to_string(),
+                },
+                typ: type_::int(),
+                public: true,
+                origin: Default::default(),
+                module: vec!["a".into()],
+                parameters: vec![],
+            },
+        )]
+        .into(),
+        types_constructors: HashMap::new(),
+        accessors: HashMap::new(),
+        values: [(
+            "one".into(),
+            ValueConstructor {
+                deprecation: Deprecation::Deprecated {
+                    message: "Use two instead".to_string(),
+                },
+                public: true,
+                type_: type_::int(),
+                variant: ValueConstructorVariant::ModuleFn {
+                    name: "one".into(),
+                    field_map: None,
+                    module: vec!["a".into()],
+                    arity: 0,
+                    location: SrcSpan { start: 5, end: 10 },
+                },
+            },
+        )]
+        .into(),
+    };
+    assert_eq!(roundtrip(&module), module);
+}
+
 #[test]
 fn module_with_fn_type() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string(), "b".to_string()],
+        name: vec!["a".into(), "b".into()],
         types: [(
-            "FnType".to_string(),
+            "FnType".into(),
             TypeConstructor {
+                deprecation: Deprecation::NotDeprecated,
                 typ: type_::fn_(vec![type_::nil(), type_::float()], type_::int()),
                 public: true,
                 origin: Default::default(),
-                module: vec!["the".to_string(), "module".to_string()],
+                module: vec!["the".into(), "module".into()],
                 parameters: vec![],
             },
         )]
@@ -132,14 +179,15 @@ fn module_with_tuple_type() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string(), "b".to_string()],
+        name: vec!["a".into(), "b".into()],
         types: [(
-            "TupleType".to_string(),
+            "TupleType".into(),
             TypeConstructor {
+                deprecation: Deprecation::NotDeprecated,
                 typ: type_::tuple(vec![type_::nil(), type_::float(), type_::int()]),
                 public: true,
                 origin: Default::default(),
-                module: vec!["the".to_string(), "module".to_string()],
+                module: vec!["the".into(), "module".into()],
                 parameters: vec![],
             },
         )]
@@ -162,14 +210,15 @@ fn module_with_generic_type() {
         Module {
             package: "some_package".to_string(),
             origin: Origin::Src,
-            name: vec!["a".to_string(), "b".to_string()],
+            name: vec!["a".into(), "b".into()],
             types: [(
-                "TupleType".to_string(),
+                "TupleType".into(),
                 TypeConstructor {
+                    deprecation: Deprecation::NotDeprecated,
                     typ: type_::tuple(vec![t1.clone(), t1.clone(), t2.clone()]),
                     public: true,
                     origin: Default::default(),
-                    module: vec!["the".to_string(), "module".to_string()],
+                    module: vec!["the".into(), "module".into()],
                     parameters: vec![t1, t2],
                 },
             )]
@@ -192,14 +241,15 @@ fn module_with_type_links() {
         Module {
             package: "some_package".to_string(),
             origin: Origin::Src,
-            name: vec!["a".to_string()],
+            name: vec!["a".into()],
             types: [(
-                "SomeType".to_string(),
+                "SomeType".into(),
                 TypeConstructor {
+                    deprecation: Deprecation::NotDeprecated,
                     typ: type_,
                     public: true,
                     origin: Default::default(),
-                    module: vec!["a".to_string()],
+                    module: vec!["a".into()],
                     parameters: vec![],
                 },
             )]
@@ -218,9 +268,9 @@ fn module_type_to_constructors_mapping() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string()],
+        name: vec!["a".into()],
         types: HashMap::new(),
-        types_constructors: [("SomeType".to_string(), vec!["One".to_string()])].into(),
+        types_constructors: [("SomeType".into(), vec!["One".into()])].into(),
         accessors: HashMap::new(),
         values: HashMap::new(),
     };
@@ -233,19 +283,20 @@ fn module_fn_value() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string()],
+        name: vec!["a".into()],
         types: HashMap::new(),
         types_constructors: HashMap::new(),
         accessors: HashMap::new(),
         values: [(
-            "one".to_string(),
+            "one".into(),
             ValueConstructor {
+                deprecation: Deprecation::NotDeprecated,
                 public: true,
                 type_: type_::int(),
                 variant: ValueConstructorVariant::ModuleFn {
-                    name: "one".to_string(),
+                    name: "one".into(),
                     field_map: None,
-                    module: vec!["a".to_string()],
+                    module: vec!["a".into()],
                     arity: 5,
                     location: SrcSpan {
                         start: 535,
@@ -271,14 +322,15 @@ fn module_fn_value_regression() {
         types_constructors: HashMap::new(),
         accessors: HashMap::new(),
         values: [(
-            "one".to_string(),
+            "one".into(),
             ValueConstructor {
+                deprecation: Deprecation::NotDeprecated,
                 public: true,
                 type_: type_::int(),
                 variant: ValueConstructorVariant::ModuleFn {
-                    name: "one".to_string(),
+                    name: "one".into(),
                     field_map: None,
-                    module: vec!["a".to_string()],
+                    module: vec!["a".into()],
                     arity: 5,
                     location: SrcSpan {
                         start: 52,
@@ -298,22 +350,23 @@ fn module_fn_value_with_field_map() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string()],
+        name: vec!["a".into()],
         types: HashMap::new(),
         types_constructors: HashMap::new(),
         accessors: HashMap::new(),
         values: [(
-            "one".to_string(),
+            "one".into(),
             ValueConstructor {
+                deprecation: Deprecation::NotDeprecated,
                 public: true,
                 type_: type_::int(),
                 variant: ValueConstructorVariant::ModuleFn {
-                    name: "one".to_string(),
+                    name: "one".into(),
                     field_map: Some(FieldMap {
                         arity: 20,
-                        fields: [("ok".to_string(), 5), ("ko".to_string(), 7)].into(),
+                        fields: [("ok".into(), 5), ("ko".into(), 7)].into(),
                     }),
-                    module: vec!["a".to_string()],
+                    module: vec!["a".into()],
                     arity: 5,
                     location: SrcSpan { start: 2, end: 11 },
                 },
@@ -332,21 +385,23 @@ fn record_value() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string()],
+        name: vec!["a".into()],
         types: HashMap::new(),
         types_constructors: HashMap::new(),
         accessors: HashMap::new(),
         values: [(
-            "one".to_string(),
+            "one".into(),
             ValueConstructor {
+                deprecation: Deprecation::NotDeprecated,
                 public: true,
                 type_: type_::int(),
                 variant: ValueConstructorVariant::Record {
-                    name: "one".to_string(),
-                    module: "themodule".to_string(),
+                    name: "one".into(),
+                    module: "themodule".into(),
                     field_map: None,
                     arity: random.gen(),
                     constructors_count: random.gen(),
+                    erlang_map: false,
                     location: SrcSpan {
                         start: random.gen(),
                         end: random.gen(),
@@ -367,28 +422,26 @@ fn record_value_with_field_map() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string()],
+        name: vec!["a".into()],
         types: HashMap::new(),
         types_constructors: HashMap::new(),
         accessors: HashMap::new(),
         values: [(
-            "one".to_string(),
+            "one".into(),
             ValueConstructor {
+                deprecation: Deprecation::NotDeprecated,
                 public: true,
                 type_: type_::int(),
                 variant: ValueConstructorVariant::Record {
-                    module: "themodule".to_string(),
-                    name: "one".to_string(),
+                    module: "themodule".into(),
+                    name: "one".into(),
                     field_map: Some(FieldMap {
                         arity: random.gen(),
-                        fields: [
-                            ("ok".to_string(), random.gen()),
-                            ("ko".to_string(), random.gen()),
-                        ]
-                        .into(),
+                        fields: [("ok".into(), random.gen()), ("ko".into(), random.gen())].into(),
                     }),
                     arity: random.gen(),
                     constructors_count: random.gen(),
+                    erlang_map: true,
                     location: SrcSpan {
                         start: random.gen(),
                         end: random.gen(),
@@ -407,30 +460,31 @@ fn accessors() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string()],
+        name: vec!["a".into()],
         types: HashMap::new(),
         types_constructors: HashMap::new(),
         values: HashMap::new(),
         accessors: [
             (
-                "one".to_string(),
+                "one".into(),
                 AccessorsMap {
                     public: true,
+                    erlang_map: false,
                     type_: type_::int(),
                     accessors: [
                         (
-                            "a".to_string(),
+                            "a".into(),
                             RecordAccessor {
                                 index: 6,
-                                label: "siiixxx".to_string(),
+                                label: "siiixxx".into(),
                                 type_: type_::nil(),
                             },
                         ),
                         (
-                            "a".to_string(),
+                            "a".into(),
                             RecordAccessor {
                                 index: 5,
-                                label: "fiveee".to_string(),
+                                label: "fiveee".into(),
                                 type_: type_::float(),
                             },
                         ),
@@ -439,15 +493,16 @@ fn accessors() {
                 },
             ),
             (
-                "two".to_string(),
+                "two".into(),
                 AccessorsMap {
                     public: true,
+                    erlang_map: true,
                     type_: type_::int(),
                     accessors: [(
-                        "a".to_string(),
+                        "a".into(),
                         RecordAccessor {
                             index: 1,
-                            label: "ok".to_string(),
+                            label: "ok".into(),
                             type_: type_::float(),
                         },
                     )]
@@ -552,7 +607,7 @@ fn constant_record() {
     let module = constant_module(Constant::Record {
         location: Default::default(),
         module: None,
-        name: "".to_string(),
+        name: "".into(),
         args: vec![
             CallArg {
                 implicit: false,
@@ -573,7 +628,7 @@ fn constant_record() {
                 },
             },
         ],
-        tag: "thetag".to_string(),
+        tag: "thetag".into(),
         typ: type_::int(),
         field_map: None,
     });
@@ -591,9 +646,10 @@ fn constant_var() {
     let one = Constant::Var {
         location: Default::default(),
         module: None,
-        name: "one_original".to_string(),
+        name: "one_original".into(),
         typ: type_::int(),
         constructor: Some(Box::from(ValueConstructor {
+            deprecation: Deprecation::NotDeprecated,
             public: true,
             type_: type_::int(),
             variant: ValueConstructorVariant::ModuleConstant {
@@ -607,14 +663,15 @@ fn constant_var() {
     let module = Module {
         package: "some_package".to_string(),
         origin: Origin::Src,
-        name: vec!["a".to_string()],
+        name: vec!["a".into()],
         types: HashMap::new(),
         types_constructors: HashMap::new(),
         accessors: HashMap::new(),
         values: [
             (
-                "one".to_string(),
+                "one".into(),
                 ValueConstructor {
+                    deprecation: Deprecation::NotDeprecated,
                     public: true,
                     type_: type_::int(),
                     variant: ValueConstructorVariant::ModuleConstant {
@@ -625,8 +682,9 @@ fn constant_var() {
                 },
             ),
             (
-                "one_original".to_string(),
+                "one_original".into(),
                 ValueConstructor {
+                    deprecation: Deprecation::NotDeprecated,
                     public: true,
                     type_: type_::int(),
                     variant: ValueConstructorVariant::ModuleConstant {
@@ -798,3 +856,105 @@ fn constant_bit_string_native() {
     });
     assert_eq!(roundtrip(&module), module);
 }
+
+#[test]
+fn encoding_is_independent_of_insertion_order() {
+    let value = |name: &str| {
+        (
+            Name::from(name),
+            ValueConstructor {
+                deprecation: Deprecation::NotDeprecated,
+                public: true,
+                type_: type_::int(),
+                variant: ValueConstructorVariant::ModuleConstant {
+                    literal: Constant::Int {
+                        location: Default::default(),
+                        value: "1".to_string(),
+                    },
+                    location: SrcSpan::default(),
+                    module: "a".into(),
+                },
+            },
+        )
+    };
+    let names = (0..32).map(|i| format!("value_{}", i)).collect::<Vec<_>>();
+    let module = |names: Vec<&String>| Module {
+        package: "some_package".to_string(),
+        origin: Origin::Src,
+        name: vec!["a".into()],
+        types: HashMap::new(),
+        types_constructors: HashMap::new(),
+        accessors: HashMap::new(),
+        values: names.into_iter().map(|name| value(name)).collect(),
+    };
+    let encode = |module: &Module| {
+        let buffer = InMemoryFile::new();
+        ModuleEncoder::new(module)
+            .write(buffer.clone())
+            .expect("Encoding module");
+        buffer.into_contents().expect("Encoded module")
+    };
+    assert_eq!(
+        encode(&module(names.iter().collect())),
+        encode(&module(names.iter().rev().collect()))
+    );
+}
+
+fn encoded_module() -> (Module, Vec<u8>) {
+    let module = constant_module(Constant::Int {
+        location: Default::default(),
+        value: "100".to_string(),
+    });
+    let buffer = InMemoryFile::new();
+    ModuleEncoder::new(&module)
+        .write(buffer.clone())
+        .expect("Encoding module");
+    (module, buffer.into_contents().expect("Encoded module"))
+}
+
+fn decode(bytes: &[u8]) -> Result<Module> {
+    ModuleDecoder::new(UniqueIdGenerator::new()).read(BufReader::new(bytes))
+}
+
+#[test]
+fn metadata_starts_with_header() {
+    let (_, bytes) = encoded_module();
+    assert_eq!(bytes.get(..8), Some(b"GLMD\x00\x01\x00\x00".as_slice()));
+    assert_eq!(
+        read_format_version(&mut bytes.as_slice()),
+        Ok(FormatVersion::CURRENT)
+    );
+}
+
+#[test]
+fn metadata_without_header_is_incompatible() {
+    let (_, bytes) = encoded_module();
+    let unversioned = bytes.get(8..).expect("Message");
+    assert_eq!(
+        decode(unversioned),
+        Err(Error::IncompatibleMetadata { version: None })
+    );
+    assert_eq!(
+        decode(b""),
+        Err(Error::IncompatibleMetadata { version: None })
+    );
+}
+
+#[test]
+fn metadata_of_other_major_version_is_incompatible() {
+    let (_, mut bytes) = encoded_module();
+    let _ = bytes.splice(4..6, 2u16.to_be_bytes());
+    assert_eq!(
+        decode(&bytes),
+        Err(Error::IncompatibleMetadata {
+            version: Some(FormatVersion { major: 2, minor: 0 })
+        })
+    );
+}
+
+#[test]
+fn metadata_of_newer_minor_version_is_read() {
+    let (module, mut bytes) = encoded_module();
+    let _ = bytes.splice(6..8, (FORMAT_MINOR_VERSION + 1).to_be_bytes());
+    assert_eq!(decode(&bytes), Ok(module));
+}
@@ -0,0 +1,290 @@
+//! The strings used for the names of modules, types, values and labels.
+//!
+//! These are cloned and compared a great deal as modules are analysed and
+//! code is generated for them, so rather than a `String` they are held in a
+//! `Name`: each distinct name in use is stored once, and every `Name` with
+//! that text points to the same allocation. Cloning one only increments a
+//! reference count, and names with the same address are equal without
+//! comparing their text.
+//!
+//! The interner only holds weak references, so a name is freed once nothing
+//! refers to it, such as when the language server replaces the modules of an
+//! earlier build. It is split into shards by the hash of the text so that
+//! threads type checking modules in parallel rarely wait on each other.
+
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::{Arc, Mutex, Weak},
+};
+
+use lazy_static::lazy_static;
+
+const SHARDS: usize = 32;
+
+lazy_static! {
+    static ref INTERNER: [Mutex<Shard>; SHARDS] = Default::default();
+}
+
+/// The names of one shard of the interner, by the hash of their text.
+#[derive(Debug, Default)]
+struct Shard {
+    names: HashMap<u64, Vec<Weak<str>>>,
+    /// The number of entries, some of which may be for freed names.
+    entries: usize,
+    /// The number of entries once freed names were last removed.
+    live: usize,
+}
+
+impl Shard {
+    fn intern(&mut self, hash: u64, text: &str) -> Arc<str> {
+        let bucket = self.names.entry(hash).or_default();
+        let before = bucket.len();
+        bucket.retain(|name| name.strong_count() > 0);
+        self.entries -= before - bucket.len();
+        if let Some(interned) = bucket
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|interned| &**interned == text)
+        {
+            return interned;
+        }
+
+        let interned: Arc<str> = Arc::from(text);
+        bucket.push(Arc::downgrade(&interned));
+        self.entries += 1;
+
+        // The entries of freed names are removed once there are twice as many
+        // entries as there were live names, so that the shard does not grow
+        // without bound as names are freed
+        if self.entries > 2 * self.live.max(64) {
+            self.names.retain(|_, bucket| {
+                bucket.retain(|name| name.strong_count() > 0);
+                !bucket.is_empty()
+            });
+            self.entries = self.names.values().map(Vec::len).sum();
+            self.live = self.entries;
+        }
+        interned
+    }
+}
+
+/// An immutable, interned string that is cheap to clone and compare.
+#[derive(Clone)]
+pub struct Name(Arc<str>);
+
+impl Name {
+    pub fn new(text: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        let hash = hasher.finish();
+        let shard = INTERNER
+            .get(hash as usize % SHARDS)
+            .expect("Name interner shard");
+        Self(shard.lock().expect("Name interner lock").intern(hash, text))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Name {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl Deref for Name {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Name {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<std::path::Path> for Name {
+    fn as_ref(&self) -> &std::path::Path {
+        std::path::Path::new(self.as_str())
+    }
+}
+
+impl Borrow<str> for Name {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for Name {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<&String> for Name {
+    fn from(text: &String) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for Name {
+    fn from(text: String) -> Self {
+        Self::new(&text)
+    }
+}
+
+impl From<&Name> for Name {
+    fn from(name: &Name) -> Self {
+        name.clone()
+    }
+}
+
+impl From<Name> for String {
+    fn from(name: Name) -> Self {
+        name.as_str().to_string()
+    }
+}
+
+impl From<&Name> for String {
+    fn from(name: &Name) -> Self {
+        name.as_str().to_string()
+    }
+}
+
+// Names with the same text are almost always the same allocation, so their
+// addresses are compared before their text.
+impl PartialEq for Name {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Name {}
+
+impl PartialEq<str> for Name {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Name {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Name {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<Name> for str {
+    fn eq(&self, other: &Name) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<Name> for &str {
+    fn eq(&self, other: &Name) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<Name> for String {
+    fn eq(&self, other: &Name) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialOrd for Name {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Name {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+// Hashed as a `str` is so that maps keyed by names can be looked up by `&str`
+impl Hash for Name {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl serde::Serialize for Name {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Name {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Name::from)
+    }
+}
+
+#[test]
+fn name_test() {
+    let name = Name::from("wibble");
+    let other = Name::from("wib".to_string() + "ble");
+    assert!(Arc::ptr_eq(&name.0, &other.0));
+    assert_eq!(name, other);
+    assert_ne!(name, Name::from("wobble"));
+    assert_eq!(name, "wibble");
+    assert_eq!(name.as_str(), "wibble");
+
+    let mut names = HashMap::new();
+    let _ = names.insert(name.clone(), 1);
+    assert_eq!(names.get("wibble"), Some(&1));
+}
+
+#[test]
+fn names_are_freed() {
+    let text = "names_are_freed";
+    let count = || {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        let hash = hasher.finish();
+        INTERNER[hash as usize % SHARDS]
+            .lock()
+            .expect("Name interner lock")
+            .names
+            .get(&hash)
+            .map(|bucket| bucket.iter().filter(|name| name.strong_count() > 0).count())
+            .unwrap_or_default()
+    };
+    let name = Name::from(text);
+    assert_eq!(count(), 1);
+    drop(name);
+    assert_eq!(count(), 0);
+    // A name made after the others were freed is still equal to new ones
+    let name = Name::from(text);
+    assert_eq!(name, Name::from(text));
+}
@@ -0,0 +1,137 @@
+//! Running independent pieces of work on several threads at once.
+//!
+//! Results are always returned in the order of the work given, so that the
+//! output of the compiler does not depend on how many threads are available
+//! or on which thread finishes first.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+#[cfg(test)]
+use pretty_assertions::assert_eq;
+
+/// The number of threads to spread work over, one for each processor the
+/// compiler may run on.
+pub fn threads() -> usize {
+    std::thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Apply the function to each item across the available threads, returning
+/// the results in the order of the items.
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = threads().min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    // Each thread takes the next item that has not been taken yet, so a
+    // thread given a slow item does not hold up the others.
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        match items.get(index) {
+                            Some(item) => results.push((index, f(item))),
+                            None => return results,
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| match worker.join() {
+                Ok(results) => results,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Apply the function to each item across the available threads, along with
+/// the value at the same position in `with` that the function has to itself
+/// for as long as the values are borrowed, such as an arena to allocate the
+/// result in. The results are returned in the order of the items.
+pub fn map_with<'a, T, W, R, F>(items: &[T], with: &'a mut [W], f: F) -> Vec<R>
+where
+    T: Sync,
+    W: Send,
+    R: Send,
+    F: Fn(&T, &'a mut W) -> R + Sync,
+{
+    let threads = threads().min(items.len());
+    let work = items.iter().zip(with).enumerate();
+    if threads <= 1 {
+        return work.map(|(_, (item, with))| f(item, with)).collect();
+    }
+
+    // The values of `with` cannot be shared between threads, so each thread
+    // takes the next item along with its value from the one iterator.
+    let work = Mutex::new(work);
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let next = work.lock().expect("Parallel work lock").next();
+                        match next {
+                            Some((index, (item, with))) => results.push((index, f(item, with))),
+                            None => return results,
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| match worker.join() {
+                Ok(results) => results,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[test]
+fn map_keeps_order() {
+    let items: Vec<u64> = (0..1000).collect();
+    let results = map(&items, |item| item * 2);
+    assert_eq!(results, (0..1000).map(|item| item * 2).collect::<Vec<_>>());
+}
+
+#[test]
+fn map_nothing() {
+    let items: Vec<u64> = vec![];
+    let results = map(&items, |item| item * 2);
+    assert_eq!(results, Vec::<u64>::new());
+}
+
+#[test]
+fn map_with_keeps_order() {
+    let items: Vec<u64> = (0..1000).collect();
+    let mut with: Vec<u64> = (0..1000).map(|item| item * 3).collect();
+    let results = map_with(&items, &mut with, |item, with| {
+        *with += item;
+        *with
+    });
+    assert_eq!(results, (0..1000).map(|item| item * 4).collect::<Vec<_>>());
+}
@@ -52,44 +52,70 @@
 pub mod error;
 pub mod extra;
 pub mod lexer;
-mod token;
+pub mod syntax_tree;
+pub mod token;
 
 use crate::ast::{
-    Arg, ArgNames, AssignName, AssignmentKind, BinOp, BitStringSegment, BitStringSegmentOption,
-    CallArg, Clause, ClauseGuard, Constant, ExternalFnArg, HasLocation, Module, Pattern,
-    RecordConstructor, RecordConstructorArg, RecordUpdateSpread, SrcSpan, Statement, TargetGroup,
+    capture_variable, Arena, ArenaBox, Arg, ArgNames, AssignName, AssignmentKind, BinOp,
+    BitStringSegment, BitStringSegmentOption, CallArg, Clause, ClauseGuard, Constant, Deprecation,
+    Derive, External, ExternalFnArg, HasLocation, Module, Name, Pattern, RecordConstructor,
+    RecordConstructorArg, RecordUpdateSpread, SrcSpan, Statement, SuppressionScope, TargetGroup,
     TodoKind, TypeAst, UnqualifiedImport, UntypedArg, UntypedClause, UntypedClauseGuard,
     UntypedConstant, UntypedExpr, UntypedExternalFnArg, UntypedModule, UntypedPattern,
-    UntypedRecordUpdateArg, UntypedStatement, Use, CAPTURE_VARIABLE,
+    UntypedRecordUpdateArg, UntypedStatement, Use, WarningSuppression,
 };
 use crate::build::Target;
+use crate::line_numbers::LineNumbers;
 use crate::parse::extra::ModuleExtra;
-use error::{LexicalError, ParseError, ParseErrorType};
+use crate::type_::WarningKind;
+use error::{LexicalError, ParseError, ParseErrorType, SpreadContext};
 use lexer::{LexResult, Spanned};
 use std::cmp::Ordering;
 use std::str::FromStr;
 use token::Token;
-use vec1::vec1;
+use vec1::{vec1, Vec1};
 #[cfg(test)]
 mod tests;
 
 //
 // Public Interface
 //
-pub fn parse_module(src: &str) -> Result<(UntypedModule, ModuleExtra), ParseError> {
+pub fn parse_module<'a>(
+    src: &str,
+    arena: &'a Arena,
+) -> Result<(UntypedModule<'a>, ModuleExtra), ParseError> {
+    match parse_module_with_recovery(src, arena) {
+        (module, extra, None) => Ok((module, extra)),
+        (_, _, Some(errors)) => Err(errors.split_off_first().0),
+    }
+}
+
+/// Parse a module, recovering from syntax errors at the next statement or
+/// expression so that all the syntax errors of the module are found in one
+/// pass. The module holds whatever could be parsed, with the code that
+/// failed to parse left out.
+pub fn parse_module_with_recovery<'a>(
+    src: &str,
+    arena: &'a Arena,
+) -> (UntypedModule<'a>, ModuleExtra, Option<Vec1<ParseError>>) {
     let lex = lexer::make_tokenizer(src);
-    let mut parser = Parser::new(lex);
-    let module = parser.parse_module()?;
-    Ok((module, parser.extra))
+    let mut parser = Parser::new(lex, arena);
+    parser.line_numbers = Some(LineNumbers::new(src));
+    let module = parser.parse_module();
+    let errors = Vec1::try_from_vec(std::mem::take(&mut parser.errors)).ok();
+    (module, parser.extra, errors)
 }
 
 //
 // Test Interface
 //
 #[cfg(test)]
-pub fn parse_expression_sequence(src: &str) -> Result<UntypedExpr, ParseError> {
+pub fn parse_expression_sequence<'a>(
+    src: &str,
+    arena: &'a Arena,
+) -> Result<UntypedExpr<'a>, ParseError> {
     let lex = lexer::make_tokenizer(src);
-    let mut parser = Parser::new(lex);
+    let mut parser = Parser::new(lex, arena);
     let expr = parser.parse_expression_seq();
     let expr = parser.ensure_no_errors_or_remaining_input(expr)?;
     if let Some((e, _)) = expr {
@@ -103,39 +129,144 @@ pub fn parse_expression_sequence(src: &str) -> Result<UntypedExpr, ParseError> {
 // Parser
 //
 #[derive(Debug)]
-pub struct Parser<T: Iterator<Item = LexResult>> {
+pub struct Parser<'a, T: Iterator<Item = LexResult>> {
     tokens: T,
+    // The arena the expressions of the module are allocated in
+    arena: &'a Arena,
     lex_errors: Vec<LexicalError>,
     tok0: Option<Spanned>,
     tok1: Option<Spanned>,
+    // The end of the tok0 and tok1 tokens, kept even if they are taken
+    lookahead_ends: (u32, u32),
+    // The end of the last token consumed
+    previous_end: u32,
     extra: ModuleExtra,
+    warning_suppressions: Vec<WarningSuppression>,
+    // The syntax errors recovered from so far
+    errors: Vec<ParseError>,
+    // The lines of the source, used to find where to resume parsing after a
+    // syntax error. Errors are not recovered from without them.
+    line_numbers: Option<LineNumbers>,
+    // The start of each bracket token read and the nesting depth after it
+    brackets: Vec<(u32, u32)>,
 }
-impl<T> Parser<T>
+impl<'a, T> Parser<'a, T>
 where
     T: Iterator<Item = LexResult>,
 {
-    pub fn new(input: T) -> Self {
+    pub fn new(input: T, arena: &'a Arena) -> Self {
         let mut parser = Parser {
             tokens: input,
+            arena,
             lex_errors: vec![],
             tok0: None,
             tok1: None,
+            lookahead_ends: (0, 0),
+            previous_end: 0,
             extra: ModuleExtra::new(),
+            warning_suppressions: vec![],
+            errors: vec![],
+            line_numbers: None,
+            brackets: vec![],
         };
         let _ = parser.next_tok();
         let _ = parser.next_tok();
         parser
     }
 
-    fn parse_module(&mut self) -> Result<UntypedModule, ParseError> {
-        let statements = Parser::series_of(self, &Parser::parse_target_group, None);
-        let statements = self.ensure_no_errors_or_remaining_input(statements)?;
-        Ok(Module {
+    fn parse_module(&mut self) -> UntypedModule<'a> {
+        let mut statements = vec![];
+        loop {
+            let start = self.tok0.as_ref().map(|(start, _, _)| *start).unwrap_or(0);
+            let group = match self.parse_target_group() {
+                Ok(None) => self.ensure_no_errors_or_remaining_input(Ok(None)),
+                group => self.ensure_no_errors(group),
+            };
+            match group {
+                Ok(Some(group)) => statements.push(group),
+                Ok(None) => break,
+                Err(error) => {
+                    if let Err(error) = self.recover(error, start, is_statement_start) {
+                        self.errors.push(error);
+                        break;
+                    }
+                }
+            }
+        }
+        Module {
             name: vec![],
             documentation: vec![],
             type_info: (),
             statements,
-        })
+            warning_suppressions: std::mem::take(&mut self.warning_suppressions),
+        }
+    }
+
+    // Record a syntax error and skip ahead to where parsing can resume.
+    // That is the closing bracket of the enclosing block, or the first token
+    // after `start` that begins a line no more indented than `start` and
+    // satisfies `is_boundary`. Indentation is used rather than brackets
+    // alone so that a bracket left unclosed does not stop recovery.
+    // The error is returned instead if this parser does not recover from
+    // errors, or if the source could not be tokenised, as the tokens stop at
+    // a lexical error.
+    fn recover(
+        &mut self,
+        error: ParseError,
+        start: u32,
+        is_boundary: fn(&Token) -> bool,
+    ) -> Result<(), ParseError> {
+        if self.line_numbers.is_none() || !self.lex_errors.is_empty() {
+            return Err(error);
+        }
+        self.errors.push(error);
+        let depth = self.depth_at(start);
+        let column = self.column_at(start);
+        while let Some((token_start, token, _)) = &self.tok0 {
+            if *token_start > start {
+                let closing = is_closing_bracket(token);
+                if closing && self.depth_at(*token_start) <= depth {
+                    break;
+                }
+                if self.starts_line(*token_start) {
+                    let token_column = self.column_at(*token_start);
+                    if closing && token_column < column
+                        || is_boundary(token) && token_column <= column
+                    {
+                        break;
+                    }
+                }
+            }
+            let _ = self.next_tok();
+        }
+        Ok(())
+    }
+
+    fn column_at(&self, position: u32) -> u32 {
+        self.line_numbers
+            .as_ref()
+            .map(|lines| lines.line_and_column_number(position).column)
+            .unwrap_or(0)
+    }
+
+    // The number of brackets open before the given position
+    fn depth_at(&self, position: u32) -> u32 {
+        let index = self
+            .brackets
+            .partition_point(|(start, _)| *start < position);
+        index
+            .checked_sub(1)
+            .and_then(|index| self.brackets.get(index))
+            .map(|(_, depth)| *depth)
+            .unwrap_or(0)
+    }
+
+    // Whether the token starting at the given position is the first on its line
+    fn starts_line(&self, position: u32) -> bool {
+        match &self.line_numbers {
+            Some(lines) => lines.line_number(position) > lines.line_number(self.previous_end),
+            None => false,
+        }
     }
 
     // The way the parser is currenly implemented, it cannot exit immediately while advancing
@@ -184,14 +315,16 @@ where
         }
     }
 
-    fn parse_target_group(&mut self) -> Result<Option<TargetGroup>, ParseError> {
+    fn parse_target_group(&mut self) -> Result<Option<TargetGroup<'a>>, ParseError> {
         match &self.tok0 {
-            Some((_, Token::If, _)) => {
+            Some((start, Token::If, _)) => {
+                let start = *start;
                 let _ = self.next_tok();
                 let target = self.expect_target()?;
                 let _ = self.expect_one(&Token::LeftBrace)?;
                 let statements = self.expect_statements()?;
-                let (_, _) = self.expect_one(&Token::RightBrace)?;
+                let (_, end) = self.expect_one(&Token::RightBrace)?;
+                self.extra.target_groups.push(SrcSpan { start, end });
                 Ok(Some(TargetGroup::Only(target, statements)))
             }
             Some(_) => {
@@ -206,12 +339,187 @@ where
         }
     }
 
-    fn expect_statements(&mut self) -> Result<Vec<UntypedStatement>, ParseError> {
-        let statements = Parser::series_of(self, &Parser::parse_statement, None);
-        self.ensure_no_errors(statements)
+    fn expect_statements(&mut self) -> Result<Vec<UntypedStatement<'a>>, ParseError> {
+        let mut statements = vec![];
+        loop {
+            let start = self.tok0.as_ref().map(|(start, _, _)| *start).unwrap_or(0);
+            match self.parse_statement() {
+                Ok(Some(statement)) => {
+                    let end = self.previous_end;
+                    self.extra.definitions.push(SrcSpan { start, end });
+                    statements.push(statement);
+                }
+                Ok(None) => break,
+                Err(error) => self.recover(error, start, is_statement_start)?,
+            }
+        }
+        self.ensure_no_errors(Ok(statements))
+    }
+
+    fn parse_statement(&mut self) -> Result<Option<UntypedStatement<'a>>, ParseError> {
+        let mut attributes = self.parse_attributes()?;
+        let start = self.tok0.as_ref().map(|(start, _, _)| *start).unwrap_or(0);
+        let statement = self.parse_definition()?;
+        if let Some((location, kinds)) = attributes.module_allow.take() {
+            self.warning_suppressions.push(WarningSuppression {
+                location,
+                kinds,
+                scope: SuppressionScope::Module,
+            });
+        }
+        if let Some((location, kinds)) = attributes.allow.take() {
+            if statement.is_none() {
+                return parse_error(ParseErrorType::UnexpectedAttribute, location);
+            }
+            let end = self.previous_end;
+            self.warning_suppressions.push(WarningSuppression {
+                location,
+                kinds,
+                scope: SuppressionScope::Definition(SrcSpan { start, end }),
+            });
+        }
+        apply_attributes(statement, attributes)
+    }
+
+    // examples:
+    //   @erlang_map
+    //   @deprecated("Use wobble instead")
+    //   @allow(unused_variable, todo)
+    //   @module_allow(unused_imported_module)
+    //   @derive(predicates, setters)
+    //   @external(erlang, "lists", "reverse")
+    fn parse_attributes(&mut self) -> Result<Attributes, ParseError> {
+        let mut attributes = Attributes::default();
+        while let Some((start, _)) = self.maybe_one(&Token::At) {
+            // `external` is a keyword so it is not lexed as a name
+            let (_, name, end) = match self.maybe_one(&Token::External) {
+                Some((name_start, end)) => (name_start, "external".into(), end),
+                None => self.expect_name()?,
+            };
+            let location = SrcSpan { start, end };
+            let duplicate = match name.as_str() {
+                "erlang_map" => attributes.erlang_map.replace(location).is_some(),
+                "deprecated" => {
+                    let _ = self.expect_one(&Token::LeftParen)?;
+                    let (_, message, _) = self.expect_string()?;
+                    let _ = self.expect_one(&Token::RightParen)?;
+                    attributes.deprecated.replace((location, message)).is_some()
+                }
+                "allow" => {
+                    let kinds = self.expect_warning_kinds()?;
+                    attributes.allow.replace((location, kinds)).is_some()
+                }
+                "module_allow" => {
+                    let kinds = self.expect_warning_kinds()?;
+                    attributes.module_allow.replace((location, kinds)).is_some()
+                }
+                "derive" => {
+                    let derives = self.expect_derives()?;
+                    attributes.derive.replace((location, derives)).is_some()
+                }
+                "external" => {
+                    let external = self.expect_external(location)?;
+                    let target = external.target;
+                    let duplicate = attributes.externals.iter().any(|e| e.target == target);
+                    attributes.externals.push(external);
+                    duplicate
+                }
+                _ => return parse_error(ParseErrorType::UnknownAttribute, location),
+            };
+            if duplicate {
+                return parse_error(ParseErrorType::DuplicateAttribute, location);
+            }
+        }
+        Ok(attributes)
+    }
+
+    // examples:
+    //   (unused_variable)
+    //   (todo, unused_imported_value)
+    fn expect_warning_kinds(&mut self) -> Result<Vec<WarningKind>, ParseError> {
+        let _ = self.expect_one(&Token::LeftParen)?;
+        let kinds = Parser::series_of(self, &Parser::parse_warning_kind, Some(&Token::Comma))?;
+        if kinds.is_empty() {
+            return self.next_tok_unexpected(vec!["The name of a warning".into()]);
+        }
+        let _ = self.expect_one(&Token::RightParen)?;
+        Ok(kinds)
     }
 
-    fn parse_statement(&mut self) -> Result<Option<UntypedStatement>, ParseError> {
+    // examples:
+    //   (predicates)
+    //   (predicates, setters)
+    fn expect_derives(&mut self) -> Result<Vec<Derive>, ParseError> {
+        let _ = self.expect_one(&Token::LeftParen)?;
+        let derives = Parser::series_of(self, &Parser::parse_derive, Some(&Token::Comma))?;
+        if derives.is_empty() {
+            return self.next_tok_unexpected(vec!["The name of a generator".into()]);
+        }
+        let _ = self.expect_one(&Token::RightParen)?;
+        Ok(derives)
+    }
+
+    // examples:
+    //   (erlang, "lists", "reverse")
+    //   (javascript, "./ffi.mjs", "reverse")
+    fn expect_external(&mut self, location: SrcSpan) -> Result<External, ParseError> {
+        let _ = self.expect_one(&Token::LeftParen)?;
+        let target = self.expect_target()?;
+        let _ = self.expect_one(&Token::Comma)?;
+        let (_, module, _) = self.expect_string()?;
+        let _ = self.expect_one(&Token::Comma)?;
+        let (_, function, _) = self.expect_string()?;
+        let _ = self.expect_one(&Token::RightParen)?;
+        Ok(External {
+            location,
+            target,
+            module,
+            function,
+        })
+    }
+
+    fn parse_derive(&mut self) -> Result<Option<Derive>, ParseError> {
+        match self.tok0.take() {
+            Some((start, Token::Name { name }, end)) => {
+                let _ = self.next_tok();
+                Ok(Some(Derive {
+                    location: SrcSpan { start, end },
+                    name: name.into(),
+                }))
+            }
+            t0 => {
+                self.tok0 = t0;
+                Ok(None)
+            }
+        }
+    }
+
+    fn parse_warning_kind(&mut self) -> Result<Option<WarningKind>, ParseError> {
+        match self.tok0.take() {
+            Some((start, Token::Name { name }, end)) => {
+                let _ = self.next_tok();
+                match WarningKind::from_name(&name) {
+                    Some(kind) => Ok(Some(kind)),
+                    None => parse_error(ParseErrorType::UnknownWarning, SrcSpan { start, end }),
+                }
+            }
+            // `todo` and `echo` are keywords so they are not lexed as names
+            Some((_, Token::Todo, _)) => {
+                let _ = self.next_tok();
+                Ok(Some(WarningKind::Todo))
+            }
+            Some((_, Token::Echo, _)) => {
+                let _ = self.next_tok();
+                Ok(Some(WarningKind::Echo))
+            }
+            t0 => {
+                self.tok0 = t0;
+                Ok(None)
+            }
+        }
+    }
+
+    fn parse_definition(&mut self) -> Result<Option<UntypedStatement<'a>>, ParseError> {
         match (self.tok0.take(), self.tok1.as_ref()) {
             // Imports
             (Some((_, Token::Import, _)), _) => {
@@ -298,15 +606,27 @@ where
     //   unit op unit
     //   unit op unit pipe unit(call)
     //   unit op unit pipe unit(call) pipe unit(call)
-    fn parse_expression(&mut self) -> Result<Option<UntypedExpr>, ParseError> {
+    fn parse_expression(&mut self) -> Result<Option<UntypedExpr<'a>>, ParseError> {
         // uses the simple operator parser algorithm
         let mut opstack = vec![];
         let mut estack = vec![];
+        let mut holes = vec![];
         let mut last_op_start = 0;
         let mut last_op_end = 0;
+        let arena = self.arena;
         loop {
             if let Some(unit) = self.parse_expression_unit()? {
                 estack.push(unit)
+            } else if let Some(location) = self.maybe_operator_hole(!estack.is_empty()) {
+                let name = capture_variable(holes.len());
+                holes.push(Arg {
+                    location: SrcSpan { start: 0, end: 0 },
+                    annotation: None,
+                    names: ArgNames::Named { name: name.clone() },
+                    type_: (),
+                    doc: None,
+                });
+                estack.push(UntypedExpr::Var { location, name })
             } else if estack.is_empty() {
                 return Ok(None);
             } else {
@@ -329,7 +649,7 @@ where
                         Some(((op_s, t, op_e), p)),
                         &mut opstack,
                         &mut estack,
-                        &do_reduce_expression,
+                        &|op, estack: &mut Vec<_>| do_reduce_expression(op, estack, arena),
                     );
                 } else {
                     // Is not Op
@@ -341,12 +661,43 @@ where
             }
         }
 
-        Ok(handle_op(
+        let expression = handle_op(
             None,
             &mut opstack,
             &mut estack,
-            &do_reduce_expression,
-        ))
+            &|op, estack: &mut Vec<_>| do_reduce_expression(op, estack, arena),
+        );
+        if holes.is_empty() {
+            return Ok(expression);
+        }
+
+        // An anon function using the operator section syntax _ + 1, taking
+        // one argument for each hole in order
+        Ok(expression.map(|body| UntypedExpr::Fn {
+            location: body.location(),
+            is_capture: true,
+            arguments: holes,
+            body: ArenaBox::new_in(body, self.arena),
+            return_annotation: None,
+        }))
+    }
+
+    // A `_` used as an operand in place of an expression, making the operator
+    // expression it is in into an anonymous function. It is only an operand if
+    // it comes after an operator or is followed by one, otherwise it may be a
+    // capture hole in a call.
+    fn maybe_operator_hole(&mut self, after_operator: bool) -> Option<SrcSpan> {
+        let before_operator = matches!(&self.tok1, Some((_, t, _)) if precedence(t).is_some());
+        match self.tok0.take() {
+            Some((start, Token::DiscardName { .. }, end)) if after_operator || before_operator => {
+                let _ = self.next_tok();
+                Some(SrcSpan { start, end })
+            }
+            t0 => {
+                self.tok0 = t0;
+                None
+            }
+        }
     }
 
     // examples:
@@ -357,7 +708,7 @@ where
     //   unit().unit().unit()
     //   A(a.., label: tuple(1))
     //   { expression_sequence }
-    fn parse_expression_unit(&mut self) -> Result<Option<UntypedExpr>, ParseError> {
+    fn parse_expression_unit(&mut self) -> Result<Option<UntypedExpr<'a>>, ParseError> {
         let mut expr = match self.tok0.take() {
             Some((start, Token::String { value }, end)) => {
                 let _ = self.next_tok();
@@ -387,7 +738,7 @@ where
                 let _ = self.next_tok();
                 UntypedExpr::Var {
                     location: SrcSpan { start, end },
-                    name,
+                    name: name.into(),
                 }
             }
 
@@ -412,6 +763,9 @@ where
                 let _ = self.expect_one(&Token::LeftParen)?;
                 let elems =
                     Parser::series_of(self, &Parser::parse_expression, Some(&Token::Comma))?;
+                if let Some((dot_start, dot_end)) = self.maybe_one(&Token::DotDot) {
+                    return self.unsupported_spread(dot_start, dot_end, SpreadContext::Tuple);
+                }
                 let (_, end) = self.expect_one(&Token::RightParen)?;
                 UntypedExpr::Tuple {
                     location: SrcSpan { start, end },
@@ -426,7 +780,9 @@ where
                     Parser::series_of(self, &Parser::parse_expression, Some(&Token::Comma))?;
                 let mut tail = None;
                 if self.maybe_one(&Token::DotDot).is_some() {
-                    tail = self.parse_expression()?.map(Box::new);
+                    tail = self
+                        .parse_expression()?
+                        .map(|tail| ArenaBox::new_in(tail, self.arena));
                     let _ = self.maybe_one(&Token::Comma);
                 }
                 let (_, end) = self.expect_one(&Token::RightSquare)?;
@@ -479,7 +835,7 @@ where
                         location,
                         is_capture: false,
                         arguments: args,
-                        body: Box::new(body),
+                        body: ArenaBox::new_in(body, self.arena),
                         return_annotation,
                     },
 
@@ -547,6 +903,42 @@ where
                 return parse_error(ParseErrorType::ExprLparStart, SrcSpan { start, end: start });
             }
 
+            // Feature flags
+            Some((start, Token::At, _)) => {
+                let _ = self.next_tok();
+                let (_, name, end) = self.expect_name()?;
+                if name != "cfg" {
+                    return parse_error(ParseErrorType::UnknownAttribute, SrcSpan { start, end });
+                }
+                let _ = self.expect_one(&Token::LeftParen)?;
+                let (_, flag, _) = self.expect_name()?;
+                let (_, end) = self.expect_one(&Token::RightParen)?;
+                UntypedExpr::Cfg {
+                    location: SrcSpan { start, end },
+                    flag: flag.into(),
+                }
+            }
+
+            // Debug printing
+            Some((start, Token::Echo, _end)) => {
+                let _ = self.next_tok();
+                match self.parse_expression_unit()? {
+                    Some(value) => UntypedExpr::Echo {
+                        location: SrcSpan {
+                            start,
+                            end: value.location().end,
+                        },
+                        value: ArenaBox::new_in(value, self.arena),
+                    },
+                    None => {
+                        return parse_error(
+                            ParseErrorType::ExpectedExpr,
+                            SrcSpan { start, end: start },
+                        )
+                    }
+                }
+            }
+
             // Boolean negation
             Some((start, Token::Bang, _end)) => {
                 let _ = self.next_tok();
@@ -556,7 +948,7 @@ where
                             start,
                             end: value.location().end,
                         },
-                        value: Box::from(value),
+                        value: ArenaBox::new_in(value, self.arena),
                     },
                     None => {
                         return parse_error(
@@ -596,7 +988,7 @@ where
                             expr = UntypedExpr::TupleIndex {
                                 location: SrcSpan { start, end },
                                 index,
-                                tuple: Box::new(expr),
+                                tuple: ArenaBox::new_in(expr, self.arena),
                             }
                         } else {
                             return parse_error(
@@ -610,8 +1002,8 @@ where
                         let _ = self.next_tok();
                         expr = UntypedExpr::FieldAccess {
                             location: SrcSpan { start, end },
-                            label,
-                            container: Box::new(expr),
+                            label: label.into(),
+                            container: ArenaBox::new_in(expr, self.arena),
                         }
                     }
 
@@ -619,8 +1011,8 @@ where
                         let _ = self.next_tok();
                         expr = UntypedExpr::FieldAccess {
                             location: SrcSpan { start, end },
-                            label,
-                            container: Box::new(expr),
+                            label: label.into(),
+                            container: ArenaBox::new_in(expr, self.arena),
                         }
                     }
 
@@ -638,7 +1030,7 @@ where
                     let base = self.expect_expression()?;
                     let base_e = base.location().end;
                     let spread = RecordUpdateSpread {
-                        base: Box::new(base),
+                        base: ArenaBox::new_in(base, self.arena),
                         location: SrcSpan {
                             start: dot_s,
                             end: base_e,
@@ -656,7 +1048,7 @@ where
 
                     expr = UntypedExpr::RecordUpdate {
                         location: SrcSpan { start, end },
-                        constructor: Box::new(expr),
+                        constructor: ArenaBox::new_in(expr, self.arena),
                         spread,
                         arguments: args,
                     };
@@ -664,15 +1056,7 @@ where
                     // Call
                     let args = self.parse_fn_args()?;
                     let (_, end) = self.expect_one(&Token::RightParen)?;
-                    match make_call(expr, args, start, end) {
-                        Ok(e) => expr = e,
-                        Err(_) => {
-                            return parse_error(
-                                ParseErrorType::TooManyArgHoles,
-                                SrcSpan { start, end },
-                            )
-                        }
-                    }
+                    expr = make_call(expr, args, start, end, self.arena);
                 }
             } else {
                 // done
@@ -690,7 +1074,7 @@ where
     // use <- module.function(a, b)
     // use a, b, c <- function(a, b)
     // use a, b, c, <- function(a, b)
-    fn parse_use(&mut self, start: u32) -> Result<UntypedExpr, ParseError> {
+    fn parse_use(&mut self, start: u32) -> Result<UntypedExpr<'a>, ParseError> {
         let assignments = if let Some((_, Token::LArrow, _)) = self.tok0 {
             vec![]
         } else {
@@ -703,7 +1087,7 @@ where
         Ok(UntypedExpr::Use(Use {
             location: SrcSpan::new(start, call.location().end),
             assignments,
-            call: Box::new(call),
+            call: ArenaBox::new_in(call, self.arena),
         }))
     }
 
@@ -717,7 +1101,7 @@ where
         &mut self,
         start: u32,
         kind: AssignmentKind,
-    ) -> Result<UntypedExpr, ParseError> {
+    ) -> Result<UntypedExpr<'a>, ParseError> {
         let pattern = if let Some(p) = self.parse_pattern()? {
             p
         } else {
@@ -742,15 +1126,20 @@ where
                 end: eq_e,
             },
         })?;
+        let mut end = value.location().end;
+        let mut message = None;
+        if kind == AssignmentKind::Assert && self.maybe_one(&Token::As).is_some() {
+            let (_, m, e) = self.expect_string()?;
+            message = Some(m);
+            end = e;
+        }
         Ok(UntypedExpr::Assignment {
-            location: SrcSpan {
-                start,
-                end: value.location().end,
-            },
-            value: Box::new(value),
+            location: SrcSpan { start, end },
+            value: ArenaBox::new_in(value, self.arena),
             pattern,
             annotation,
             kind,
+            message,
         })
     }
 
@@ -763,7 +1152,7 @@ where
     //
     //   In order to parse an expr sequence, you must try to parse an expr, if it is a `try`
     //   you MUST parse another expr, if it is some other expr, you MAY parse another expr
-    fn parse_expression_seq(&mut self) -> Result<Option<(UntypedExpr, u32)>, ParseError> {
+    fn parse_expression_seq(&mut self) -> Result<Option<(UntypedExpr<'a>, u32)>, ParseError> {
         // assignment
         if let Some(start) = self.maybe_try_start() {
             let pattern = if let Some(p) = self.parse_pattern()? {
@@ -783,10 +1172,10 @@ where
                 (Some(value), Some((then, end))) => Ok(Some((
                     UntypedExpr::Try {
                         location: SrcSpan { start, end },
-                        value: Box::new(value),
+                        value: ArenaBox::new_in(value, self.arena),
                         pattern,
                         annotation,
-                        then: Box::new(then),
+                        then: ArenaBox::new_in(then, self.arena),
                     },
                     end,
                 ))),
@@ -806,7 +1195,7 @@ where
                     },
                 ),
             }
-        } else if let Some(expression) = self.parse_expression()? {
+        } else if let Some(expression) = self.parse_recoverable_expression()? {
             let mut expression = expression;
             while let Some((next, _)) = self.parse_expression_seq()? {
                 expression = expression.append_in_sequence(next);
@@ -818,6 +1207,18 @@ where
         }
     }
 
+    // An expression of a sequence, skipping any that fail to parse when
+    // recovering from errors
+    fn parse_recoverable_expression(&mut self) -> Result<Option<UntypedExpr<'a>>, ParseError> {
+        loop {
+            let start = self.tok0.as_ref().map(|(start, _, _)| *start).unwrap_or(0);
+            match self.parse_expression() {
+                Err(error) => self.recover(error, start, is_expression_start)?,
+                result => return result,
+            }
+        }
+    }
+
     // try
     fn maybe_try_start(&mut self) -> Option<u32> {
         match self.tok0 {
@@ -853,7 +1254,7 @@ where
                         }
                         _ => Pattern::Var {
                             location: SrcSpan { start, end },
-                            name,
+                            name: name.into(),
                         },
                     }
                 }
@@ -874,7 +1275,7 @@ where
 
                 Pattern::Discard {
                     location: SrcSpan { start, end },
-                    name,
+                    name: name.into(),
                 }
             }
 
@@ -910,9 +1311,28 @@ where
             }
             Some((start, Token::Int { value }, end)) => {
                 let _ = self.next_tok();
-                Pattern::Int {
-                    location: SrcSpan { start, end },
-                    value,
+                if self.maybe_one(&Token::DotDot).is_some() {
+                    match self.tok0.take() {
+                        Some((_, Token::Int { value: range_end }, end)) => {
+                            let _ = self.next_tok();
+                            Pattern::IntRange {
+                                location: SrcSpan { start, end },
+                                start: value,
+                                end: range_end,
+                            }
+                        }
+                        t0 => {
+                            self.tok0 = t0;
+                            return self.next_tok_unexpected(vec![
+                                "An integer for the end of the range".to_string(),
+                            ]);
+                        }
+                    }
+                } else {
+                    Pattern::Int {
+                        location: SrcSpan { start, end },
+                        value,
+                    }
                 }
             }
             Some((start, Token::Float { value }, end)) => {
@@ -984,7 +1404,7 @@ where
                             start: rsqb_e - 1,
                             end: rsqb_e,
                         },
-                        name: "_".to_string(),
+                        name: "_".into(),
                     }),
                     // No tail specified
                     None => None,
@@ -1008,7 +1428,7 @@ where
             let _ = self.next_tok();
             let (start, name, end) = self.expect_name()?;
             Ok(Some(Pattern::Assign {
-                name,
+                name: name.into(),
                 location: SrcSpan { start, end },
                 pattern: Box::new(pattern),
             }))
@@ -1021,7 +1441,7 @@ where
     //   pattern -> expr
     //   pattern, pattern if -> expr
     //   pattern, pattern | pattern, pattern if -> expr
-    fn parse_case_clause(&mut self) -> Result<Option<UntypedClause>, ParseError> {
+    fn parse_case_clause(&mut self) -> Result<Option<UntypedClause<'a>>, ParseError> {
         let patterns = self.parse_patterns()?;
         if let Some(lead) = &patterns.first() {
             let mut alternative_patterns = vec![];
@@ -1132,6 +1552,7 @@ where
     // a
     // 1
     // a.1
+    // is_small(a)
     // { a }
     // a || b
     // a < b || b < c
@@ -1139,7 +1560,20 @@ where
         match self.tok0.take() {
             Some((start, Token::Name { name }, end)) => {
                 let _ = self.next_tok();
-                if let Some((dot_s, _)) = self.maybe_one(&Token::Dot) {
+                if self.maybe_one(&Token::LeftParen).is_some() {
+                    let args = Parser::series_of(
+                        self,
+                        &|parser| parser.parse_case_clause_guard(true),
+                        Some(&Token::Comma),
+                    )?;
+                    let (_, end) = self.expect_one(&Token::RightParen)?;
+                    Ok(Some(ClauseGuard::Call {
+                        location: SrcSpan { start, end },
+                        type_: (),
+                        name: name.into(),
+                        args,
+                    }))
+                } else if let Some((dot_s, _)) = self.maybe_one(&Token::Dot) {
                     match self.next_tok() {
                         Some((_, Token::Int { value }, int_e)) => {
                             let v = value.replace('_', "");
@@ -1154,7 +1588,7 @@ where
                                     tuple: Box::new(ClauseGuard::Var {
                                         location: SrcSpan { start, end },
                                         type_: (),
-                                        name,
+                                        name: name.into(),
                                     }),
                                 }))
                             } else {
@@ -1174,7 +1608,7 @@ where
                     Ok(Some(ClauseGuard::Var {
                         location: SrcSpan { start, end },
                         type_: (),
-                        name,
+                        name: name.into(),
                     }))
                 }
             }
@@ -1211,8 +1645,8 @@ where
         Ok(Pattern::Constructor {
             location: SrcSpan { start, end },
             arguments: args,
-            module: module.map(|(_, n, _)| n),
-            name,
+            module: module.map(|(_, n, _)| n.into()),
+            name: name.into(),
             with_spread,
             constructor: (),
             type_: (),
@@ -1260,7 +1694,7 @@ where
                             start,
                             end: value.location().end,
                         },
-                        label: Some(name),
+                        label: Some(name.into()),
                         value,
                     }))
                 } else {
@@ -1293,13 +1727,21 @@ where
 
     // examples:
     //   a: expr
-    fn parse_record_update_arg(&mut self) -> Result<Option<UntypedRecordUpdateArg>, ParseError> {
+    fn parse_record_update_arg(
+        &mut self,
+    ) -> Result<Option<UntypedRecordUpdateArg<'a>>, ParseError> {
         if let Some((start, label, _)) = self.maybe_name() {
+            let mut path = vec![];
+            while self.maybe_one(&Token::Dot).is_some() {
+                let (_, label, _) = self.expect_name()?;
+                path.push(label.into());
+            }
             let _ = self.expect_one(&Token::Colon)?;
             let value = self.parse_expression()?;
             if let Some(value) = value {
                 Ok(Some(UntypedRecordUpdateArg {
-                    label,
+                    label: label.into(),
+                    path,
                     location: SrcSpan {
                         start,
                         end: value.location().end,
@@ -1328,7 +1770,7 @@ where
         start: u32,
         public: bool,
         is_anon: bool,
-    ) -> Result<Option<UntypedStatement>, ParseError> {
+    ) -> Result<Option<UntypedStatement<'a>>, ParseError> {
         let mut name = String::new();
         if !is_anon {
             let (_, n, _) = self.expect_name()?;
@@ -1365,11 +1807,13 @@ where
             location: SrcSpan { start, end },
             end_position: rbr_e - 1,
             public,
-            name,
+            name: name.into(),
             arguments: args,
             body,
             return_type: (),
             return_annotation,
+            deprecation: Deprecation::NotDeprecated,
+            externals: vec![],
         }))
     }
 
@@ -1382,7 +1826,7 @@ where
         &mut self,
         start: u32,
         public: bool,
-    ) -> Result<Option<UntypedStatement>, ParseError> {
+    ) -> Result<Option<UntypedStatement<'a>>, ParseError> {
         let (_, name, _) = self.expect_name()?;
         let _ = self.expect_one(&Token::LeftParen)?;
         let args = Parser::series_of(self, &Parser::parse_external_fn_param, Some(&Token::Comma))?;
@@ -1398,12 +1842,13 @@ where
                 doc: None,
                 location: SrcSpan { start, end },
                 public,
-                name,
+                name: name.into(),
                 arguments: args,
                 module,
                 fun,
                 return_: retrn,
                 return_type: (),
+                deprecation: Deprecation::NotDeprecated,
             }))
         } else {
             parse_error(
@@ -1430,7 +1875,7 @@ where
                 let _ = self.next_tok();
                 let _ = self.next_tok();
                 start = s;
-                label = Some(name);
+                label = Some(name.into());
                 end = e;
             }
 
@@ -1449,6 +1894,7 @@ where
                 label: None,
                 annotation,
                 type_: (),
+                doc: None,
             })),
 
             (Some(_), Some(annotation)) => {
@@ -1458,6 +1904,7 @@ where
                     label,
                     annotation,
                     type_: (),
+                    doc: None,
                 }))
             }
         }
@@ -1491,13 +1938,20 @@ where
 
                 let _ = self.next_tok();
                 let _ = self.next_tok();
-                (start, ArgNames::LabelledDiscard { name, label }, end)
+                (
+                    start,
+                    ArgNames::LabelledDiscard {
+                        name: name.into(),
+                        label: label.into(),
+                    },
+                    end,
+                )
             }
             // discard
             (Some((start, Token::DiscardName { name }, end)), t1) => {
                 self.tok1 = t1;
                 let _ = self.next_tok();
-                (start, ArgNames::Discard { name }, end)
+                (start, ArgNames::Discard { name: name.into() }, end)
             }
             // labeled name
             (
@@ -1516,13 +1970,20 @@ where
 
                 let _ = self.next_tok();
                 let _ = self.next_tok();
-                (start, ArgNames::NamedLabelled { name, label }, end)
+                (
+                    start,
+                    ArgNames::NamedLabelled {
+                        name: name.into(),
+                        label: label.into(),
+                    },
+                    end,
+                )
             }
             // name
             (Some((start, Token::Name { name }, end)), t1) => {
                 self.tok1 = t1;
                 let _ = self.next_tok();
-                (start, ArgNames::Named { name }, end)
+                (start, ArgNames::Named { name: name.into() }, end)
             }
             (t0, t1) => {
                 self.tok0 = t0;
@@ -1541,6 +2002,7 @@ where
             type_: (),
             names,
             annotation,
+            doc: None,
         }))
     }
 
@@ -1551,11 +2013,34 @@ where
     //   expr, expr
     //   a: _, expr
     //   a: expr, _, b: _
-    fn parse_fn_args(&mut self) -> Result<Vec<ParserArg>, ParseError> {
+    fn parse_fn_args(&mut self) -> Result<Vec<ParserArg<'a>>, ParseError> {
         let args = Parser::series_of(self, &Parser::parse_fn_arg, Some(&Token::Comma))?;
         Ok(args)
     }
 
+    // Targeted recovery for a `..` spread written somewhere that Gleam does
+    // not support one, such as a function call or a tuple. The spread value is
+    // consumed so that the error can point at the whole spread rather than
+    // reporting a generic unexpected token.
+    fn unsupported_spread<A>(
+        &mut self,
+        dot_start: u32,
+        dot_end: u32,
+        context: SpreadContext,
+    ) -> Result<A, ParseError> {
+        let end = match self.parse_expression()? {
+            Some(value) => value.location().end,
+            None => dot_end,
+        };
+        parse_error(
+            ParseErrorType::UnsupportedSpread { context },
+            SrcSpan {
+                start: dot_start,
+                end,
+            },
+        )
+    }
+
     // Parse a single function call arg
     //
     // examples:
@@ -1563,14 +2048,18 @@ where
     //   expr
     //   a: _
     //   a: expr
-    fn parse_fn_arg(&mut self) -> Result<Option<ParserArg>, ParseError> {
+    fn parse_fn_arg(&mut self) -> Result<Option<ParserArg<'a>>, ParseError> {
+        if let Some((dot_start, dot_end)) = self.maybe_one(&Token::DotDot) {
+            return self.unsupported_spread(dot_start, dot_end, SpreadContext::CallArguments);
+        }
+
         let mut start = 0;
         let label = match (self.tok0.take(), &self.tok1) {
             (Some((s, Token::Name { name }, _)), Some((_, Token::Colon, _))) => {
                 let _ = self.next_tok();
                 let _ = self.next_tok();
                 start = s;
-                Some(name)
+                Some(name.into())
             }
             (t0, _) => {
                 self.tok0 = t0;
@@ -1609,14 +2098,15 @@ where
         &mut self,
         start: u32,
         public: bool,
-    ) -> Result<Option<UntypedStatement>, ParseError> {
+    ) -> Result<Option<UntypedStatement<'a>>, ParseError> {
         let (_, name, args, end) = self.expect_type_name()?;
         Ok(Some(Statement::ExternalType {
             location: SrcSpan { start, end },
             public,
-            name,
+            name: name.into(),
             arguments: args,
             doc: None,
+            deprecation: Deprecation::NotDeprecated,
         }))
     }
 
@@ -1634,24 +2124,29 @@ where
         start: u32,
         public: bool,
         opaque: bool,
-    ) -> Result<Option<UntypedStatement>, ParseError> {
+    ) -> Result<Option<UntypedStatement<'a>>, ParseError> {
         let (_, name, parameters, end) = self.expect_type_name()?;
         if self.maybe_one(&Token::LeftBrace).is_some() {
             // Custom Type
             let constructors = Parser::series_of(
                 self,
                 &|p| {
+                    let attributes = p.parse_attributes()?;
                     if let Some((c_s, c_n, c_e)) = Parser::maybe_upname(p) {
                         let (args, args_e) = Parser::parse_type_constructor_args(p)?;
                         let end = args_e.max(c_e);
-                        Ok(Some(RecordConstructor {
+                        let constructor = RecordConstructor {
                             location: SrcSpan { start: c_s, end },
-                            name: c_n,
+                            name: c_n.into(),
                             arguments: args,
                             documentation: None,
-                        }))
-                    } else {
+                            deprecation: Deprecation::NotDeprecated,
+                        };
+                        apply_constructor_attributes(constructor, attributes).map(Some)
+                    } else if attributes.is_empty() {
                         Ok(None)
+                    } else {
+                        p.next_tok_unexpected(vec!["A type constructor".into()])
                     }
                 },
                 // No separator
@@ -1666,10 +2161,13 @@ where
                     location: SrcSpan { start, end },
                     public,
                     opaque,
-                    name,
+                    name: name.into(),
                     parameters,
                     constructors,
                     typed_parameters: vec![],
+                    erlang_map: false,
+                    derives: vec![],
+                    deprecation: Deprecation::NotDeprecated,
                 }))
             }
         } else if let Some((eq_s, eq_e)) = self.maybe_one(&Token::Equal) {
@@ -1684,10 +2182,11 @@ where
                             end: type_end,
                         },
                         public,
-                        alias: name,
+                        alias: name.into(),
                         parameters,
                         type_ast: t,
                         type_: (),
+                        deprecation: Deprecation::NotDeprecated,
                     }))
                 } else {
                     parse_error(
@@ -1710,13 +2209,13 @@ where
     // examples:
     //   A
     //   A(one, two)
-    fn expect_type_name(&mut self) -> Result<(u32, String, Vec<String>, u32), ParseError> {
+    fn expect_type_name(&mut self) -> Result<(u32, String, Vec<Name>, u32), ParseError> {
         let (start, upname, end) = self.expect_upname()?;
         if self.maybe_one(&Token::LeftParen).is_some() {
             let args =
                 Parser::series_of(self, &|p| Ok(Parser::maybe_name(p)), Some(&Token::Comma))?;
             let (_, par_e) = self.expect_one(&Token::RightParen)?;
-            let args2 = args.into_iter().map(|(_, a, _)| a).collect();
+            let args2 = args.into_iter().map(|(_, a, _)| a.into()).collect();
             Ok((start, upname, args2, par_e))
         } else {
             Ok((start, upname, vec![], end))
@@ -1739,7 +2238,7 @@ where
                         let _ = Parser::next_tok(p);
                         match Parser::parse_type(p, false)? {
                             Some(type_ast) => Ok(Some(RecordConstructorArg {
-                                label: Some(name),
+                                label: Some(name.into()),
                                 ast: type_ast,
                                 location: SrcSpan { start, end },
                                 type_: (),
@@ -1809,7 +2308,7 @@ where
                 let _ = self.next_tok();
                 Ok(Some(TypeAst::Hole {
                     location: SrcSpan { start, end },
-                    name,
+                    name: name.into(),
                 }))
             }
 
@@ -1868,13 +2367,19 @@ where
                 let _ = self.next_tok();
                 if self.maybe_one(&Token::Dot).is_some() {
                     let (_, upname, upname_e) = self.expect_upname()?;
-                    self.parse_type_name_finish(for_const, start, Some(mod_name), upname, upname_e)
+                    self.parse_type_name_finish(
+                        for_const,
+                        start,
+                        Some(mod_name.into()),
+                        upname,
+                        upname_e,
+                    )
                 } else if for_const {
                     parse_error(ParseErrorType::NotConstType, SrcSpan { start, end })
                 } else {
                     Ok(Some(TypeAst::Var {
                         location: SrcSpan { start, end },
-                        name: mod_name,
+                        name: mod_name.into(),
                     }))
                 }
             }
@@ -1891,7 +2396,7 @@ where
         &mut self,
         for_const: bool,
         start: u32,
-        module: Option<String>,
+        module: Option<Name>,
         name: String,
         end: u32,
     ) -> Result<Option<TypeAst>, ParseError> {
@@ -1901,14 +2406,14 @@ where
             Ok(Some(TypeAst::Constructor {
                 location: SrcSpan { start, end: par_e },
                 module,
-                name,
+                name: name.into(),
                 arguments: args,
             }))
         } else {
             Ok(Some(TypeAst::Constructor {
                 location: SrcSpan { start, end },
                 module,
-                name,
+                name: name.into(),
                 arguments: vec![],
             }))
         }
@@ -1933,7 +2438,7 @@ where
     //   import a/b
     //   import a/b.{c}
     //   import a/b.{c as d} as e
-    fn parse_import(&mut self) -> Result<Option<UntypedStatement>, ParseError> {
+    fn parse_import(&mut self) -> Result<Option<UntypedStatement<'a>>, ParseError> {
         let mut start = 0;
         let mut end;
         let mut module = vec![];
@@ -1943,7 +2448,7 @@ where
             if module.is_empty() {
                 start = s;
             }
-            module.push(name);
+            module.push(name.into());
             end = e;
 
             // Ueful error for : import a/.{b}
@@ -1976,7 +2481,7 @@ where
         let mut as_name = None;
         if self.maybe_one(&Token::As).is_some() {
             let (_, name, e) = self.expect_name()?;
-            as_name = Some(name);
+            as_name = Some(name.into());
             end = e;
         }
 
@@ -1999,14 +2504,14 @@ where
                     let _ = self.next_tok();
                     let location = SrcSpan { start, end };
                     let mut import = UnqualifiedImport {
-                        name,
+                        name: name.into(),
                         location,
                         as_name: None,
                         layer: Default::default(),
                     };
                     if self.maybe_one(&Token::As).is_some() {
                         let (_, as_name, _) = self.expect_name()?;
-                        import.as_name = Some(as_name);
+                        import.as_name = Some(as_name.into());
                     }
                     imports.push(import)
                 }
@@ -2014,14 +2519,14 @@ where
                     let _ = self.next_tok();
                     let location = SrcSpan { start, end };
                     let mut import = UnqualifiedImport {
-                        name,
+                        name: name.into(),
                         location,
                         as_name: None,
                         layer: Default::default(),
                     };
                     if self.maybe_one(&Token::As).is_some() {
                         let (_, as_name, _) = self.expect_upname()?;
-                        import.as_name = Some(as_name);
+                        import.as_name = Some(as_name.into());
                     }
                     imports.push(import)
                 }
@@ -2049,21 +2554,25 @@ where
     //   const a = 1
     //   const a:Int = 1
     //   pub const a:Int = 1
-    fn parse_module_const(&mut self, public: bool) -> Result<Option<UntypedStatement>, ParseError> {
+    fn parse_module_const(
+        &mut self,
+        public: bool,
+    ) -> Result<Option<UntypedStatement<'a>>, ParseError> {
         let (start, name, end) = self.expect_name()?;
 
         let annotation = self.parse_type_annotation(&Token::Colon, true)?;
 
         let (eq_s, eq_e) = self.expect_one(&Token::Equal)?;
-        if let Some(value) = self.parse_const_value()? {
+        if let Some(value) = self.parse_const_expression()? {
             Ok(Some(Statement::ModuleConstant {
                 doc: None,
                 location: SrcSpan { start, end },
                 public,
-                name,
+                name: name.into(),
                 annotation,
                 value: Box::new(value),
                 type_: (),
+                deprecation: Deprecation::NotDeprecated,
             }))
         } else {
             parse_error(
@@ -2076,6 +2585,80 @@ where
         }
     }
 
+    // examples:
+    //   1
+    //   1 + 2
+    //   "Hello, " <> name
+    //   { width + 1 } * 2
+    fn parse_const_expression(&mut self) -> Result<Option<UntypedConstant>, ParseError> {
+        // uses the simple operator parser algorithm, as `parse_expression` does
+        let mut opstack = vec![];
+        let mut estack = vec![];
+        let mut last_op_start = 0;
+        let mut last_op_end = 0;
+        loop {
+            if let Some(unit) = self.parse_const_expression_unit()? {
+                estack.push(unit)
+            } else if estack.is_empty() {
+                return Ok(None);
+            } else {
+                return parse_error(
+                    ParseErrorType::OpNakedRight,
+                    SrcSpan {
+                        start: last_op_start,
+                        end: last_op_end,
+                    },
+                );
+            }
+
+            match self.tok0.take() {
+                Some((op_s, t, op_e)) if const_binop(&t).is_some() => {
+                    let _ = self.next_tok();
+                    last_op_start = op_s;
+                    last_op_end = op_e;
+                    let p = precedence(&t).expect("constant operator precedence");
+                    let _ = handle_op(
+                        Some(((op_s, t, op_e), p)),
+                        &mut opstack,
+                        &mut estack,
+                        &do_reduce_constant,
+                    );
+                }
+                t0 => {
+                    self.tok0 = t0;
+                    break;
+                }
+            }
+        }
+
+        Ok(handle_op(
+            None,
+            &mut opstack,
+            &mut estack,
+            &do_reduce_constant,
+        ))
+    }
+
+    fn parse_const_expression_unit(&mut self) -> Result<Option<UntypedConstant>, ParseError> {
+        match self.tok0.take() {
+            // Grouped constant expression
+            Some((start, Token::LeftBrace, end)) => {
+                let _ = self.next_tok();
+                match self.parse_const_expression()? {
+                    Some(value) => {
+                        let _ = self.expect_one(&Token::RightBrace)?;
+                        Ok(Some(value))
+                    }
+                    None => parse_error(ParseErrorType::NotConstType, SrcSpan { start, end }),
+                }
+            }
+            t0 => {
+                self.tok0 = t0;
+                self.parse_const_value()
+            }
+        }
+    }
+
     // examples:
     //   1
     //   "hi"
@@ -2111,7 +2694,7 @@ where
                 let _ = self.next_tok();
                 let _ = self.expect_one(&Token::LeftParen)?;
                 let elements =
-                    Parser::series_of(self, &Parser::parse_const_value, Some(&Token::Comma))?;
+                    Parser::series_of(self, &Parser::parse_const_expression, Some(&Token::Comma))?;
                 let (_, end) = self.expect_one(&Token::RightParen)?;
                 Ok(Some(Constant::Tuple {
                     elements,
@@ -2122,7 +2705,7 @@ where
             Some((start, Token::LeftSquare, _)) => {
                 let _ = self.next_tok();
                 let elements =
-                    Parser::series_of(self, &Parser::parse_const_value, Some(&Token::Comma))?;
+                    Parser::series_of(self, &Parser::parse_const_expression, Some(&Token::Comma))?;
                 let (_, end) = self.expect_one(&Token::RightSquare)?;
                 Ok(Some(Constant::List {
                     elements,
@@ -2164,7 +2747,7 @@ where
                 match self.tok0.take() {
                     Some((_, Token::UpName { name: upname }, end)) => {
                         let _ = self.next_tok(); // upname
-                        self.parse_const_record_finish(start, Some(name), upname, end)
+                        self.parse_const_record_finish(start, Some(name.into()), upname, end)
                     }
                     Some((_, Token::Name { name: end_name }, end)) => {
                         let _ = self.next_tok(); // name
@@ -2179,8 +2762,8 @@ where
                             ),
                             _ => Ok(Some(Constant::Var {
                                 location: SrcSpan { start, end },
-                                module: Some(name),
-                                name: end_name,
+                                module: Some(name.into()),
+                                name: end_name.into(),
                                 constructor: None,
                                 typ: (),
                             })),
@@ -2204,7 +2787,7 @@ where
                 Ok(Some(Constant::Var {
                     location: SrcSpan { start, end },
                     module: None,
-                    name,
+                    name: name.into(),
                     constructor: None,
                     typ: (),
                 }))
@@ -2226,7 +2809,7 @@ where
     fn parse_const_record_finish(
         &mut self,
         start: u32,
-        module: Option<String>,
+        module: Option<Name>,
         name: String,
         end: u32,
     ) -> Result<Option<UntypedConstant>, ParseError> {
@@ -2237,7 +2820,7 @@ where
             Ok(Some(Constant::Record {
                 location: SrcSpan { start, end: par_e },
                 module,
-                name,
+                name: name.into(),
                 args,
                 tag: (),
                 typ: (),
@@ -2247,7 +2830,7 @@ where
             Ok(Some(Constant::Record {
                 location: SrcSpan { start, end },
                 module,
-                name,
+                name: name.into(),
                 args: vec![],
                 tag: (),
                 typ: (),
@@ -2275,7 +2858,7 @@ where
             }
         };
 
-        if let Some(value) = self.parse_const_value()? {
+        if let Some(value) = self.parse_const_expression()? {
             if let Some((start, label, _)) = name {
                 Ok(Some(CallArg {
                     implicit: false,
@@ -2284,7 +2867,7 @@ where
                         end: value.location().end,
                     },
                     value,
-                    label: Some(label),
+                    label: Some(label.into()),
                 }))
             } else {
                 Ok(Some(CallArg {
@@ -2429,7 +3012,7 @@ where
         match self.next_tok() {
             Some((start, Token::Name { name }, end)) => Ok(Pattern::VarUsage {
                 location: SrcSpan { start, end },
-                name,
+                name: name.into(),
                 type_: (),
             }),
             Some((start, Token::Int { value }, end)) => Ok(Pattern::Int {
@@ -2450,7 +3033,7 @@ where
         }
     }
 
-    fn expect_expression(&mut self) -> Result<UntypedExpr, ParseError> {
+    fn expect_expression(&mut self) -> Result<UntypedExpr<'a>, ParseError> {
         if let Some(e) = self.parse_expression()? {
             Ok(e)
         } else {
@@ -2474,7 +3057,7 @@ where
     fn expect_name(&mut self) -> Result<(u32, String, u32), ParseError> {
         let (start, token, end) = self.expect_assign_name()?;
         match token {
-            AssignName::Variable(name) => Ok((start, name, end)),
+            AssignName::Variable(name) => Ok((start, name.to_string(), end)),
             AssignName::Discard(_) => {
                 parse_error(ParseErrorType::IncorrectName, SrcSpan { start, end })
             }
@@ -2486,9 +3069,9 @@ where
         match t {
             Some((start, tok, end)) => {
                 if let Token::Name { name } = tok {
-                    Ok((start, AssignName::Variable(name), end))
+                    Ok((start, AssignName::Variable(name.into()), end))
                 } else if let Token::DiscardName { name, .. } = tok {
-                    Ok((start, AssignName::Discard(name), end))
+                    Ok((start, AssignName::Discard(name.into()), end))
                 } else if let Token::UpName { .. } = tok {
                     parse_error(ParseErrorType::IncorrectName, SrcSpan { start, end })
                 } else if is_reserved_word(tok) {
@@ -2676,6 +3259,7 @@ where
                 }
 
                 Some(Ok(tok)) => {
+                    self.track_bracket(&tok);
                     nxt = Some(tok);
                     break;
                 }
@@ -2685,10 +3269,72 @@ where
                 }
             }
         }
+        let (tok0_end, tok1_end) = self.lookahead_ends;
+        let nxt_end = nxt.as_ref().map(|(_, _, end)| *end).unwrap_or(tok1_end);
+        self.previous_end = tok0_end;
+        self.lookahead_ends = (tok1_end, nxt_end);
         self.tok0 = self.tok1.take();
         self.tok1 = nxt.take();
         t
     }
+
+    fn track_bracket(&mut self, (start, token, _): &Spanned) {
+        let depth = self.brackets.last().map(|(_, depth)| *depth).unwrap_or(0);
+        let depth = match token {
+            Token::LeftParen | Token::LeftSquare | Token::LeftBrace | Token::LtLt => depth + 1,
+            _ if is_closing_bracket(token) => depth.saturating_sub(1),
+            _ => return,
+        };
+        self.brackets.push((*start, depth));
+    }
+}
+
+fn is_closing_bracket(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::RightParen | Token::RightSquare | Token::RightBrace | Token::GtGt
+    )
+}
+
+// Tokens that parsing of a module can resume from after a syntax error
+fn is_statement_start(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Pub
+            | Token::Fn
+            | Token::Type
+            | Token::Const
+            | Token::Import
+            | Token::External
+            | Token::If
+            | Token::At
+    )
+}
+
+// Tokens that parsing of an expression sequence can resume from after a
+// syntax error
+fn is_expression_start(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Name { .. }
+            | Token::UpName { .. }
+            | Token::Int { .. }
+            | Token::Float { .. }
+            | Token::String { .. }
+            | Token::Let
+            | Token::Assert
+            | Token::Use
+            | Token::Try
+            | Token::Case
+            | Token::Fn
+            | Token::Todo
+            | Token::Echo
+            | Token::Hash
+            | Token::LeftSquare
+            | Token::LeftBrace
+            | Token::LtLt
+            | Token::Bang
+    )
 }
 
 fn concat_pattern_variable_left_hand_side_error<T>(start: u32, end: u32) -> Result<T, ParseError> {
@@ -2786,16 +3432,51 @@ fn tok_to_binop(t: &Token) -> Option<BinOp> {
     }
 }
 // Simple-Precedence-Parser, perform reduction for expression
-fn do_reduce_expression(op: Spanned, estack: &mut Vec<UntypedExpr>) {
+fn do_reduce_expression<'a>(op: Spanned, estack: &mut Vec<UntypedExpr<'a>>, arena: &'a Arena) {
     match (estack.pop(), estack.pop()) {
         (Some(er), Some(el)) => {
-            let new_e = expr_op_reduction(op, el, er);
+            let new_e = expr_op_reduction(op, el, er, arena);
             estack.push(new_e);
         }
         _ => panic!("Tried to reduce without 2 expressions"),
     }
 }
 
+// The operators that can be used in constant expressions
+fn const_binop(t: &Token) -> Option<BinOp> {
+    tok_to_binop(t).filter(|op| {
+        matches!(
+            op,
+            BinOp::AddInt
+                | BinOp::SubInt
+                | BinOp::MultInt
+                | BinOp::DivInt
+                | BinOp::RemainderInt
+                | BinOp::AddFloat
+                | BinOp::SubFloat
+                | BinOp::MultFloat
+                | BinOp::DivFloat
+                | BinOp::Concatenate
+        )
+    })
+}
+
+// Simple-Precedence-Parser, perform reduction for constant expression
+fn do_reduce_constant(op: Spanned, estack: &mut Vec<UntypedConstant>) {
+    match (estack.pop(), estack.pop(), const_binop(&op.1)) {
+        (Some(right), Some(left), Some(name)) => estack.push(Constant::BinOp {
+            location: SrcSpan {
+                start: left.location().start,
+                end: right.location().end,
+            },
+            name,
+            left: Box::new(left),
+            right: Box::new(right),
+        }),
+        _ => panic!("Tried to reduce without 2 constants"),
+    }
+}
+
 // Simple-Precedence-Parser, perform reduction for clause guard
 fn do_reduce_clause_guard(op: Spanned, estack: &mut Vec<UntypedClauseGuard>) {
     match (estack.pop(), estack.pop()) {
@@ -2807,7 +3488,12 @@ fn do_reduce_clause_guard(op: Spanned, estack: &mut Vec<UntypedClauseGuard>) {
     }
 }
 
-fn expr_op_reduction((_, token, _): Spanned, l: UntypedExpr, r: UntypedExpr) -> UntypedExpr {
+fn expr_op_reduction<'a>(
+    (_, token, _): Spanned,
+    l: UntypedExpr<'a>,
+    r: UntypedExpr<'a>,
+    arena: &'a Arena,
+) -> UntypedExpr<'a> {
     if token == Token::Pipe {
         let expressions = if let UntypedExpr::PipeLine { mut expressions } = l {
             expressions.push(r);
@@ -2823,8 +3509,8 @@ fn expr_op_reduction((_, token, _): Spanned, l: UntypedExpr, r: UntypedExpr) ->
                 end: r.location().end,
             },
             name: bin_op,
-            left: Box::new(l),
-            right: Box::new(r),
+            left: ArenaBox::new_in(l, arena),
+            right: ArenaBox::new_in(r, arena),
         }
     } else {
         panic!("Token could not be converted to binop.")
@@ -2931,7 +3617,7 @@ fn bit_string_pattern_int(value: String, start: u32, end: u32) -> UntypedPattern
     }
 }
 
-fn bit_string_expr_int(value: String, start: u32, end: u32) -> UntypedExpr {
+fn bit_string_expr_int<'a>(value: String, start: u32, end: u32) -> UntypedExpr<'a> {
     UntypedExpr::Int {
         location: SrcSpan { start, end },
         value,
@@ -2971,6 +3657,91 @@ fn str_to_bit_string_segment_option<A>(
     }
 }
 
+// Attributes written before a definition, with the location of each one
+// that was given.
+#[derive(Debug, Default)]
+struct Attributes {
+    erlang_map: Option<SrcSpan>,
+    deprecated: Option<(SrcSpan, String)>,
+    allow: Option<(SrcSpan, Vec<WarningKind>)>,
+    module_allow: Option<(SrcSpan, Vec<WarningKind>)>,
+    derive: Option<(SrcSpan, Vec<Derive>)>,
+    externals: Vec<External>,
+}
+
+impl Attributes {
+    fn is_empty(&self) -> bool {
+        self.erlang_map.is_none()
+            && self.deprecated.is_none()
+            && self.allow.is_none()
+            && self.module_allow.is_none()
+            && self.derive.is_none()
+            && self.externals.is_empty()
+    }
+}
+
+fn apply_attributes<'a>(
+    mut statement: Option<UntypedStatement<'a>>,
+    attributes: Attributes,
+) -> Result<Option<UntypedStatement<'a>>, ParseError> {
+    if let Some(location) = attributes.erlang_map {
+        match &mut statement {
+            Some(Statement::CustomType { erlang_map, .. }) => *erlang_map = true,
+            _ => return parse_error(ParseErrorType::UnexpectedAttribute, location),
+        }
+    }
+    if let Some((location, names)) = attributes.derive {
+        match &mut statement {
+            Some(Statement::CustomType { derives, .. }) => *derives = names,
+            _ => return parse_error(ParseErrorType::UnexpectedAttribute, location),
+        }
+    }
+    if let Some(external) = attributes.externals.first() {
+        match &mut statement {
+            Some(Statement::Fn { externals, .. }) => *externals = attributes.externals,
+            _ => return parse_error(ParseErrorType::UnexpectedAttribute, external.location),
+        }
+    }
+    if let Some((location, message)) = attributes.deprecated {
+        match &mut statement {
+            Some(
+                Statement::Fn { deprecation, .. }
+                | Statement::TypeAlias { deprecation, .. }
+                | Statement::CustomType { deprecation, .. }
+                | Statement::ExternalFn { deprecation, .. }
+                | Statement::ExternalType { deprecation, .. }
+                | Statement::ModuleConstant { deprecation, .. },
+            ) => *deprecation = Deprecation::Deprecated { message },
+            Some(Statement::Import { .. }) | None => {
+                return parse_error(ParseErrorType::UnexpectedAttribute, location)
+            }
+        }
+    }
+    Ok(statement)
+}
+
+fn apply_constructor_attributes(
+    mut constructor: RecordConstructor<()>,
+    attributes: Attributes,
+) -> Result<RecordConstructor<()>, ParseError> {
+    let unexpected = attributes
+        .erlang_map
+        .or(attributes.allow.map(|(location, _)| location))
+        .or(attributes.module_allow.map(|(location, _)| location))
+        .or(attributes.derive.map(|(location, _)| location))
+        .or(attributes
+            .externals
+            .first()
+            .map(|external| external.location));
+    if let Some(location) = unexpected {
+        return parse_error(ParseErrorType::UnexpectedAttribute, location);
+    }
+    if let Some((_, message)) = attributes.deprecated {
+        constructor.deprecation = Deprecation::Deprecated { message };
+    }
+    Ok(constructor)
+}
+
 //
 // Error Helpers
 //
@@ -2990,6 +3761,7 @@ fn is_reserved_word(tok: Token) -> bool {
             | Token::Assert
             | Token::Case
             | Token::Const
+            | Token::Echo
             | Token::External
             | Token::Fn
             | Token::If
@@ -3006,64 +3778,61 @@ fn is_reserved_word(tok: Token) -> bool {
 
 // Parsing a function call into the appropriate structure
 #[derive(Debug)]
-pub enum ParserArg {
-    Arg(Box<CallArg<UntypedExpr>>),
+pub enum ParserArg<'a> {
+    Arg(Box<CallArg<UntypedExpr<'a>>>),
     Hole {
         location: SrcSpan,
-        label: Option<String>,
+        label: Option<Name>,
     },
 }
 
-pub fn make_call(
-    fun: UntypedExpr,
-    args: Vec<ParserArg>,
+pub fn make_call<'a>(
+    fun: UntypedExpr<'a>,
+    args: Vec<ParserArg<'a>>,
     start: u32,
     end: u32,
-) -> Result<UntypedExpr, ParseError> {
-    let mut num_holes = 0;
+    arena: &'a Arena,
+) -> UntypedExpr<'a> {
+    let mut holes = vec![];
     let args = args
         .into_iter()
         .map(|a| match a {
             ParserArg::Arg(arg) => *arg,
             ParserArg::Hole { location, label } => {
-                num_holes += 1;
+                let name = capture_variable(holes.len());
+                holes.push(Arg {
+                    location: SrcSpan { start: 0, end: 0 },
+                    annotation: None,
+                    names: ArgNames::Named { name: name.clone() },
+                    type_: (),
+                    doc: None,
+                });
                 CallArg {
                     implicit: false,
                     label,
                     location,
-                    value: UntypedExpr::Var {
-                        location,
-                        name: CAPTURE_VARIABLE.to_string(),
-                    },
+                    value: UntypedExpr::Var { location, name },
                 }
             }
         })
         .collect();
     let call = UntypedExpr::Call {
         location: SrcSpan { start, end },
-        fun: Box::new(fun),
+        fun: ArenaBox::new_in(fun, arena),
         arguments: args,
     };
-    match num_holes {
+    if holes.is_empty() {
         // A normal call
-        0 => Ok(call),
-
-        // An anon function using the capture syntax run(_, 1, 2)
-        1 => Ok(UntypedExpr::Fn {
+        call
+    } else {
+        // An anon function using the capture syntax run(_, 1, _), taking one
+        // argument for each hole in order
+        UntypedExpr::Fn {
             location: call.location(),
             is_capture: true,
-            arguments: vec![Arg {
-                location: SrcSpan { start: 0, end: 0 },
-                annotation: None,
-                names: ArgNames::Named {
-                    name: CAPTURE_VARIABLE.to_string(),
-                },
-                type_: (),
-            }],
-            body: Box::new(call),
+            arguments: holes,
+            body: ArenaBox::new_in(call, arena),
             return_annotation: None,
-        }),
-
-        _ => parse_error(ParseErrorType::TooManyArgHoles, call.location()),
+        }
     }
 }
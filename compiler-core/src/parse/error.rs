@@ -1,5 +1,6 @@
 use crate::ast::SrcSpan;
 use crate::error::wrap;
+use crate::type_::WarningKind;
 use heck::{ToSnakeCase, ToUpperCamelCase};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -141,13 +142,6 @@ utf16_codepoint, utf32_codepoint, signed, unsigned, big, little, native, size, u
                 "This operator has no value on its right side.",
                 vec!["Hint: Remove it or put a value after it.".to_string()],
             ),
-            ParseErrorType::TooManyArgHoles => (
-                "There is more than 1 argument hole in this function call.",
-                vec![
-                    "Hint: Function calls can have at most one argument hole.".to_string(),
-                    "See: https://gleam.run/book/tour/functions".to_string(),
-                ],
-            ),
             ParseErrorType::UnexpectedEof => ("The module ended unexpectedly.", vec![]),
             ParseErrorType::ListSpreadWithoutElements => (
                 "This spread does nothing",
@@ -156,6 +150,64 @@ utf16_codepoint, utf32_codepoint, signed, unsigned, big, little, native, size, u
                     "See: https://gleam.run/book/tour/lists.html".to_string(),
                 ],
             ),
+            ParseErrorType::UnsupportedSpread {
+                context: SpreadContext::CallArguments,
+            } => (
+                "Lists cannot be spread into function calls.",
+                vec![
+                    wrap(
+                        "Hint: Gleam functions take a fixed number of arguments, so \
+each one must be given explicitly, as in `f(a, b)`. If you have a list, \
+pattern match on it first with `case` to get at its elements, or change \
+the function to take the list as a single argument.",
+                    ),
+                    "See: https://gleam.run/book/tour/functions".to_string(),
+                ],
+            ),
+            ParseErrorType::UnsupportedSpread {
+                context: SpreadContext::Tuple,
+            } => (
+                "Lists cannot be spread into tuples.",
+                vec![
+                    wrap(
+                        "Hint: Tuples have a fixed size, so each element must be \
+given explicitly, as in `#(a, b)`. If you need a collection of any size use \
+a list instead.",
+                    ),
+                    "See: https://gleam.run/book/tour/tuples".to_string(),
+                ],
+            ),
+            ParseErrorType::UnknownAttribute => (
+                "This attribute is not known.",
+                vec![
+                    "Hint: The supported attributes are `@erlang_map`, `@deprecated`, \
+`@allow`, `@module_allow`, and `@derive`."
+                        .to_string(),
+                ],
+            ),
+            ParseErrorType::DuplicateAttribute => (
+                "This attribute has already been given.",
+                vec!["Hint: Try removing it?".to_string()],
+            ),
+            ParseErrorType::UnexpectedAttribute => (
+                "This attribute cannot be used here.",
+                vec![
+                    "Hint: The `@erlang_map` attribute can only be used on custom types, \
+`@deprecated` on definitions and constructors, `@allow` on definitions, and \
+`@module_allow` at the top level of a module."
+                        .to_string(),
+                ],
+            ),
+            ParseErrorType::UnknownWarning => {
+                let names: Vec<_> = WarningKind::ALL.iter().map(|kind| kind.name()).collect();
+                (
+                    "This is not the name of a warning.",
+                    vec![wrap(&format!(
+                        "Hint: The warnings that can be allowed are: {}.",
+                        names.join(", ")
+                    ))],
+                )
+            }
             ParseErrorType::UnexpectedReservedWord => (
                 "This is a reserved word.",
                 vec![
@@ -209,7 +261,8 @@ utf16_codepoint, utf32_codepoint, signed, unsigned, big, little, native, size, u
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum ParseErrorType {
     ExpectedEqual,           // expect "="
     ExpectedExpr,            // after "->" in a case clause
@@ -239,12 +292,18 @@ pub enum ParseErrorType {
     NotConstType, // :fn(), name, _  are not valid const types
     OpNakedRight, // Operator with no value to the right
     OpaqueTypeAlias, // Type aliases cannot be opaque
-    TooManyArgHoles, // a function call can have at most 1 arg hole
     ListSpreadWithoutElements, // Pointless spread: `[..xs]`
     LowcaseBooleanPattern, // most likely user meant True or False in patterns
     UnexpectedLabel, // argument labels were provided, but are not supported in this context
     UnexpectedEof,
     UnexpectedReservedWord, // reserved word used when a name was expected
+    UnknownAttribute,       // an attribute other than those supported, such as `@wibble`
+    DuplicateAttribute,     // the same attribute given twice to one definition
+    UnexpectedAttribute,    // an attribute given to a definition it cannot be used with
+    UnknownWarning,         // a warning name in `@allow(...)` other than those that exist
+    UnsupportedSpread {
+        context: SpreadContext, // `f(..xs)` or `#(..xs)`, spreads only work in lists and records
+    },
     UnexpectedToken {
         expected: Vec<String>,
         hint: Option<String>,
@@ -255,6 +314,14 @@ pub enum ParseErrorType {
     ConcatPatternVariableLeftHandSide,
 }
 
+/// Places where a `..` spread might be written by mistake, used to explain
+/// what to do instead.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SpreadContext {
+    CallArguments,
+    Tuple,
+}
+
 impl LexicalError {
     pub fn to_parse_error_info(&self) -> (&'static str, Vec<String>) {
         match &self.error {
@@ -1,11 +1,16 @@
 use crate::ast::SrcSpan;
 
-#[derive(Debug, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct ModuleExtra {
     pub module_comments: Vec<SrcSpan>,
     pub doc_comments: Vec<SrcSpan>,
     pub comments: Vec<SrcSpan>,
     pub empty_lines: Vec<u32>,
+    /// The location of each definition, from the start of its attributes to
+    /// the end of its last token.
+    pub definitions: Vec<SrcSpan>,
+    /// The location of each `if target { ... }` block of definitions.
+    pub target_groups: Vec<SrcSpan>,
 }
 
 impl ModuleExtra {
@@ -18,18 +23,33 @@ impl ModuleExtra {
 pub struct Comment<'a> {
     pub start: u32,
     pub content: &'a str,
+    /// The start of the line the comment is on, if there is code before the
+    /// comment on that line.
+    pub trailing_line: Option<u32>,
 }
 
 impl<'a> From<(&SrcSpan, &'a str)> for Comment<'a> {
     fn from(src: (&SrcSpan, &'a str)) -> Comment<'a> {
         let start = src.0.start;
         let end = src.0.end as usize;
+        // The comment's span starts after its slashes
+        let before = src
+            .1
+            .get(..start as usize)
+            .unwrap_or_default()
+            .trim_end_matches('/');
+        let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+        let trailing_line = before
+            .get(line_start..)
+            .filter(|code| !code.trim().is_empty())
+            .map(|_| line_start as u32);
         Comment {
             start,
             content: src
                 .1
                 .get(start as usize..end)
                 .expect("From span to comment"),
+            trailing_line,
         }
     }
 }
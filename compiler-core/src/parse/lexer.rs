@@ -23,6 +23,7 @@ pub fn str_to_keyword(word: &str) -> Option<Token> {
         "assert" => Some(Token::Assert),
         "case" => Some(Token::Case),
         "const" => Some(Token::Const),
+        "echo" => Some(Token::Echo),
         "external" => Some(Token::External),
         "fn" => Some(Token::Fn),
         "if" => Some(Token::If),
@@ -419,6 +420,9 @@ where
             '#' => {
                 self.eat_single_char(Token::Hash);
             }
+            '@' => {
+                self.eat_single_char(Token::At);
+            }
             '\n' => {
                 let _ = self.next_char();
                 let tok_start = self.get_pos();
@@ -613,8 +617,8 @@ where
         // consume first run of digits
         value.push_str(&self.radix_run(10));
 
-        // If float:
-        if self.chr0 == Some('.') {
+        // If float, unless this is the start of a range such as `1..10`:
+        if self.chr0 == Some('.') && self.chr1 != Some('.') {
             value.push(self.next_char().expect("lex_normal_number float"));
             value.push_str(&self.radix_run(10));
             let end_pos = self.get_pos();
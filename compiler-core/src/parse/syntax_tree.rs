@@ -0,0 +1,316 @@
+//! A lossless concrete syntax tree of a module.
+//!
+//! The AST drops everything that does not affect the meaning of the code.
+//! The syntax tree instead keeps every byte of the source: each token along
+//! with the whitespace and comments between them, which are called trivia.
+//! Tokens are grouped into nodes for definitions, target groups, and
+//! bracketed code, so tools can find the exact text of any part of a module
+//! and edit it without disturbing the formatting of the rest.
+//!
+//! The tree is built from the source and the [`ModuleExtra`] the parser
+//! returns, so it is available for any module that has been parsed.
+
+#[cfg(test)]
+mod tests;
+
+use super::{extra::ModuleExtra, lexer, token::Token};
+use crate::{ast::SrcSpan, language_server::TextEdit};
+use std::iter::Peekable;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxTree {
+    src: String,
+    root: SyntaxNode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxNode {
+    pub kind: NodeKind,
+    pub location: SrcSpan,
+    pub children: Vec<SyntaxElement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Module,
+    /// An `if target { ... }` block of definitions.
+    TargetGroup,
+    /// A definition, including its attributes and documentation.
+    Definition,
+    /// Code between a pair of brackets, including the brackets.
+    Group,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxToken {
+    pub kind: SyntaxKind,
+    pub location: SrcSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxKind {
+    Token(Token),
+    Trivia(Trivia),
+    /// Source that could not be tokenised, kept so no text is lost.
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trivia {
+    Whitespace,
+    Comment,
+    DocComment,
+    ModuleComment,
+}
+
+impl SyntaxTree {
+    pub fn new(src: &str, extra: &ModuleExtra) -> Self {
+        let tokens = tokens(src);
+
+        let mut ranges = extra
+            .target_groups
+            .iter()
+            .map(|location| (NodeKind::TargetGroup, *location))
+            .chain(
+                extra
+                    .definitions
+                    .iter()
+                    .map(|location| (NodeKind::Definition, with_docs(&tokens, *location))),
+            )
+            .chain(
+                bracket_groups(&tokens)
+                    .into_iter()
+                    .map(|location| (NodeKind::Group, location)),
+            )
+            .collect::<Vec<_>>();
+        // Outer nodes come before the nodes they contain
+        ranges.sort_by_key(|(_, location)| (location.start, std::cmp::Reverse(location.end)));
+
+        let location = SrcSpan::new(0, src.len() as u32);
+        let children = build(
+            &mut tokens.into_iter().peekable(),
+            &mut ranges.into_iter().peekable(),
+            location.end,
+        );
+        Self {
+            src: src.to_string(),
+            root: SyntaxNode {
+                kind: NodeKind::Module,
+                location,
+                children,
+            },
+        }
+    }
+
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    pub fn src(&self) -> &str {
+        &self.src
+    }
+
+    /// The source code of a token or node.
+    pub fn text(&self, location: SrcSpan) -> &str {
+        self.src
+            .get(location.start as usize..location.end as usize)
+            .unwrap_or_default()
+    }
+
+    /// Every token of the module in order, including trivia.
+    pub fn tokens(&self) -> Vec<&SyntaxToken> {
+        let mut tokens = vec![];
+        self.root.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    /// The token, trivia included, that the given byte offset falls in.
+    pub fn token_at(&self, position: u32) -> Option<&SyntaxToken> {
+        self.tokens()
+            .into_iter()
+            .find(|token| token.location.start <= position && position < token.location.end)
+    }
+
+    /// The source code with the edits applied. The edits must not overlap.
+    /// Everything outside the edited locations is kept exactly as it was,
+    /// comments and whitespace included.
+    pub fn apply_edits(&self, edits: &[TextEdit]) -> String {
+        let mut edits = edits.iter().collect::<Vec<_>>();
+        edits.sort_by_key(|edit| edit.location.start);
+        let mut src = String::with_capacity(self.src.len());
+        let mut position = 0;
+        for edit in edits {
+            src.push_str(self.text(SrcSpan::new(position, edit.location.start)));
+            src.push_str(&edit.new_text);
+            position = edit.location.end;
+        }
+        src.push_str(self.text(SrcSpan::new(position, self.src.len() as u32)));
+        src
+    }
+}
+
+impl SyntaxNode {
+    /// The nodes directly inside this one.
+    pub fn nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// The trivia before the first token of this node that is not trivia,
+    /// such as the documentation of a definition.
+    pub fn leading_trivia(&self) -> Vec<&SyntaxToken> {
+        let mut tokens = vec![];
+        self.collect_tokens(&mut tokens);
+        tokens
+            .into_iter()
+            .take_while(|token| matches!(token.kind, SyntaxKind::Trivia(_)))
+            .collect()
+    }
+
+    fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a SyntaxToken>) {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.collect_tokens(tokens),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+}
+
+fn build(
+    tokens: &mut Peekable<impl Iterator<Item = SyntaxToken>>,
+    ranges: &mut Peekable<impl Iterator<Item = (NodeKind, SrcSpan)>>,
+    end: u32,
+) -> Vec<SyntaxElement> {
+    let mut children = vec![];
+    while let Some(token) = tokens.next_if(|token| token.location.start < end) {
+        match ranges.next_if(|(_, location)| location.start <= token.location.start) {
+            Some((kind, location)) => {
+                let mut inner = vec![SyntaxElement::Token(token)];
+                inner.extend(build(tokens, ranges, location.end));
+                children.push(SyntaxElement::Node(SyntaxNode {
+                    kind,
+                    location,
+                    children: inner,
+                }));
+            }
+            None => children.push(SyntaxElement::Token(token)),
+        }
+    }
+    children
+}
+
+// The tokens of the source along with the trivia between them, covering
+// every byte of the source.
+fn tokens(src: &str) -> Vec<SyntaxToken> {
+    let mut tokens = vec![];
+    let mut position = 0;
+    let mut push = |kind, location: SrcSpan, tokens: &mut Vec<SyntaxToken>| {
+        if position < location.start {
+            tokens.push(SyntaxToken {
+                kind: SyntaxKind::Trivia(Trivia::Whitespace),
+                location: SrcSpan::new(position, location.start),
+            });
+        }
+        tokens.push(SyntaxToken { kind, location });
+        position = location.end;
+    };
+    for result in lexer::make_tokenizer(src) {
+        let (start, token, end) = match result {
+            Ok(spanned) => spanned,
+            Err(_) => break,
+        };
+        // Comment tokens start after their slashes
+        let kind = match token {
+            Token::EmptyLine => continue,
+            Token::CommentNormal => (Trivia::Comment, 2),
+            Token::CommentDoc => (Trivia::DocComment, 3),
+            Token::CommentModule => (Trivia::ModuleComment, 4),
+            token => {
+                push(
+                    SyntaxKind::Token(token),
+                    SrcSpan::new(start, end),
+                    &mut tokens,
+                );
+                continue;
+            }
+        };
+        let (trivia, slashes) = kind;
+        let location = SrcSpan::new(start.saturating_sub(slashes), end);
+        push(SyntaxKind::Trivia(trivia), location, &mut tokens);
+    }
+    let end = src.len() as u32;
+    if position < end {
+        let rest = src.get(position as usize..).unwrap_or_default();
+        let kind = if rest.trim().is_empty() {
+            SyntaxKind::Trivia(Trivia::Whitespace)
+        } else {
+            SyntaxKind::Error
+        };
+        tokens.push(SyntaxToken {
+            kind,
+            location: SrcSpan::new(position, end),
+        });
+    }
+    tokens
+}
+
+// Extend the location of a definition back over the documentation comments
+// before it.
+fn with_docs(tokens: &[SyntaxToken], location: SrcSpan) -> SrcSpan {
+    let index = tokens.partition_point(|token| token.location.start < location.start);
+    let start = tokens
+        .iter()
+        .take(index)
+        .rev()
+        .take_while(|token| {
+            matches!(
+                token.kind,
+                SyntaxKind::Trivia(Trivia::DocComment | Trivia::Whitespace)
+            )
+        })
+        .filter(|token| token.kind == SyntaxKind::Trivia(Trivia::DocComment))
+        .last()
+        .map(|token| token.location.start)
+        .unwrap_or(location.start);
+    SrcSpan::new(start, location.end)
+}
+
+// The locations of each pair of matching brackets. Brackets without a match
+// are not grouped.
+fn bracket_groups(tokens: &[SyntaxToken]) -> Vec<SrcSpan> {
+    let mut groups = vec![];
+    let mut open: Vec<(Token, u32)> = vec![];
+    for token in tokens {
+        let kind = match &token.kind {
+            SyntaxKind::Token(kind) => kind,
+            _ => continue,
+        };
+        let closing = match kind {
+            Token::LeftParen | Token::LeftSquare | Token::LeftBrace | Token::LtLt => {
+                open.push((kind.clone(), token.location.start));
+                continue;
+            }
+            Token::RightParen => Token::LeftParen,
+            Token::RightSquare => Token::LeftSquare,
+            Token::RightBrace => Token::LeftBrace,
+            Token::GtGt => Token::LtLt,
+            _ => continue,
+        };
+        if let Some(index) = open.iter().rposition(|(opening, _)| *opening == closing) {
+            if let Some((_, start)) = open.get(index) {
+                groups.push(SrcSpan::new(*start, token.location.end));
+            }
+            open.truncate(index);
+        }
+    }
+    groups
+}
@@ -0,0 +1,193 @@
+use super::*;
+use crate::parse::parse_module;
+
+fn syntax_tree(src: &str) -> SyntaxTree {
+    let arena = crate::ast::Arena::new();
+    let (_, extra) = parse_module(src, &arena).expect("syntax error");
+    SyntaxTree::new(src, &extra)
+}
+
+fn assert_lossless(tree: &SyntaxTree) {
+    let mut position = 0;
+    for token in tree.tokens() {
+        assert_eq!(token.location.start, position);
+        position = token.location.end;
+    }
+    assert_eq!(position as usize, tree.src().len());
+}
+
+fn nodes(node: &SyntaxNode) -> Vec<(NodeKind, SrcSpan)> {
+    node.nodes()
+        .map(|node| (node.kind, node.location))
+        .collect()
+}
+
+#[test]
+fn tokens_cover_the_whole_source() {
+    let src = r#"//// A module comment
+
+import gleam/io  // trailing comment
+
+/// Say hello
+/// to everyone
+@deprecated("Use greet instead")
+pub fn main() {
+  // A comment
+  io.println(  "Hello, world!"  )
+  <<1, 2:size(8)>>
+}
+
+if erlang {
+  external fn halt(Int) -> Nil =
+    "erlang" "halt"
+}
+"#;
+    let tree = syntax_tree(src);
+    assert_lossless(&tree);
+    let texts = tree
+        .tokens()
+        .into_iter()
+        .filter(|token| !matches!(token.kind, SyntaxKind::Trivia(Trivia::Whitespace)))
+        .map(|token| tree.text(token.location))
+        .take(6)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        texts,
+        vec![
+            "//// A module comment",
+            "import",
+            "gleam",
+            "/",
+            "io",
+            "// trailing comment"
+        ]
+    );
+}
+
+#[test]
+fn crlf_line_endings_are_kept() {
+    let tree = syntax_tree("pub fn main() {\r\n  1\r\n}\r\n");
+    assert_lossless(&tree);
+}
+
+#[test]
+fn definitions_include_their_documentation() {
+    let src = r#"import gleam/io
+
+/// Say hello
+pub fn main() { io.println("Hello") }
+
+if erlang {
+  pub const x = 1
+}
+"#;
+    let tree = syntax_tree(src);
+    assert_eq!(
+        nodes(tree.root()),
+        vec![
+            (NodeKind::Definition, SrcSpan::new(0, 15)),
+            (NodeKind::Definition, SrcSpan::new(17, 68)),
+            (NodeKind::TargetGroup, SrcSpan::new(70, 101)),
+        ]
+    );
+    let main = tree.root().nodes().nth(1).expect("main");
+    assert_eq!(
+        main.leading_trivia()
+            .into_iter()
+            .map(|token| tree.text(token.location))
+            .collect::<Vec<_>>(),
+        vec!["/// Say hello", "\n"]
+    );
+    let group = tree.root().nodes().nth(2).expect("target group");
+    assert_eq!(
+        nodes(group),
+        vec![(NodeKind::Group, SrcSpan::new(80, 101)),]
+    );
+}
+
+#[test]
+fn brackets_are_grouped() {
+    let src = "pub fn main() { #(1, [2]) }";
+    let tree = syntax_tree(src);
+    let main = tree.root().nodes().next().expect("main");
+    let groups = nodes(main);
+    assert_eq!(
+        groups,
+        vec![
+            (NodeKind::Group, SrcSpan::new(11, 13)),
+            (NodeKind::Group, SrcSpan::new(14, 27)),
+        ]
+    );
+    let body = main.nodes().nth(1).expect("body");
+    let tuple = body.nodes().next().expect("tuple");
+    assert_eq!(tree.text(tuple.location), "(1, [2])");
+    assert_eq!(nodes(tuple), vec![(NodeKind::Group, SrcSpan::new(21, 24))]);
+}
+
+#[test]
+fn edits_keep_comments_and_whitespace() {
+    let src = r#"pub fn main() {
+  // Add them up
+  add(1,   2)  // the numbers
+}
+"#;
+    let tree = syntax_tree(src);
+    let name = tree
+        .tokens()
+        .into_iter()
+        .find(|token| tree.text(token.location) == "add")
+        .expect("add");
+    let edits = vec![TextEdit {
+        location: name.location,
+        new_text: "sum".into(),
+    }];
+    assert_eq!(
+        tree.apply_edits(&edits),
+        r#"pub fn main() {
+  // Add them up
+  sum(1,   2)  // the numbers
+}
+"#
+    );
+}
+
+#[test]
+fn token_at() {
+    let src = "pub fn main() { 1 }";
+    let tree = syntax_tree(src);
+    let token = tree.token_at(8).expect("token");
+    assert_eq!(
+        token.kind,
+        SyntaxKind::Token(Token::Name {
+            name: "main".into()
+        })
+    );
+    assert_eq!(tree.text(token.location), "main");
+    assert_eq!(tree.token_at(100), None);
+}
+
+#[test]
+fn source_that_does_not_tokenise_is_kept() {
+    let src = "pub fn main() { \"unterminated }\n";
+    let tree = SyntaxTree::new(src, &ModuleExtra::new());
+    assert_lossless(&tree);
+    assert_eq!(
+        tree.tokens().last().map(|token| &token.kind),
+        Some(&SyntaxKind::Error)
+    );
+}
+
+#[test]
+fn language_tests_are_lossless() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/language/src");
+    for entry in std::fs::read_dir(dir).expect("read dir") {
+        let path = entry.expect("dir entry").path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("gleam") {
+            continue;
+        }
+        let src = std::fs::read_to_string(&path).expect("read");
+        let arena = crate::ast::Arena::new();
+        let (_, extra) = parse_module(&src, &arena).expect("syntax error");
+        assert_lossless(&SyntaxTree::new(&src, &extra));
+    }
+}
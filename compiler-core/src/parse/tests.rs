@@ -1,20 +1,28 @@
-use crate::ast::SrcSpan;
-use crate::parse::error::{LexicalError, LexicalErrorType, ParseError, ParseErrorType};
+use crate::ast::{SrcSpan, SuppressionScope, WarningSuppression};
+use crate::parse::error::{
+    LexicalError, LexicalErrorType, ParseError, ParseErrorType, SpreadContext,
+};
+use crate::type_::WarningKind;
 use std::path::PathBuf;
+use vec1::Vec1;
 
 use pretty_assertions::assert_eq;
 
 macro_rules! assert_error {
     ($src:expr, $error:expr $(,)?) => {
-        let result = crate::parse::parse_expression_sequence($src).expect_err("should not parse");
+        let arena = crate::ast::Arena::new();
+        let result =
+            crate::parse::parse_expression_sequence($src, &arena).expect_err("should not parse");
         assert_eq!(($src, $error), ($src, result),);
     };
     ($src:expr) => {
-        let result = crate::parse::parse_expression_sequence($src).expect_err("should not parse");
+        let arena = crate::ast::Arena::new();
+        let result =
+            crate::parse::parse_expression_sequence($src, &arena).expect_err("should not parse");
         let error = crate::error::Error::Parse {
             src: $src.to_string(),
             path: PathBuf::from("/src/parse/error.gleam"),
-            error: result,
+            errors: Vec1::new(result),
         };
         let result = error.pretty_string();
         insta::assert_snapshot!(insta::internals::AutoName, result, $src);
@@ -361,3 +369,316 @@ fn assign_left_hand_side_of_concat_pattern() {
         "#
     );
 }
+
+#[test]
+fn spread_into_call() {
+    assert_error!(
+        "add(1, ..rest)",
+        ParseError {
+            error: ParseErrorType::UnsupportedSpread {
+                context: SpreadContext::CallArguments,
+            },
+            location: SrcSpan { start: 7, end: 13 },
+        }
+    );
+}
+
+#[test]
+fn spread_into_call_later_argument() {
+    assert_error!("list.fold(over: items, ..rest)");
+}
+
+#[test]
+fn spread_into_tuple() {
+    assert_error!("#(1, ..rest)");
+}
+
+#[test]
+fn record_update_is_not_a_spread() {
+    let arena = crate::ast::Arena::new();
+    assert!(crate::parse::parse_expression_sequence("Person(..person, age: 1)", &arena).is_ok());
+}
+
+#[test]
+fn unknown_attribute() {
+    let arena = crate::ast::Arena::new();
+    assert_eq!(
+        crate::parse::parse_module("@wibble\npub type Cat { Cat(name: String) }", &arena)
+            .expect_err("should not parse"),
+        ParseError {
+            error: ParseErrorType::UnknownAttribute,
+            location: SrcSpan { start: 0, end: 7 },
+        }
+    );
+}
+
+#[test]
+fn duplicate_attribute() {
+    let arena = crate::ast::Arena::new();
+    assert_eq!(
+        crate::parse::parse_module(
+            "@erlang_map\n@erlang_map\npub type Cat { Cat(name: String) }",
+            &arena
+        )
+        .expect_err("should not parse"),
+        ParseError {
+            error: ParseErrorType::DuplicateAttribute,
+            location: SrcSpan { start: 12, end: 23 },
+        }
+    );
+}
+
+#[test]
+fn attribute_on_function() {
+    let arena = crate::ast::Arena::new();
+    assert_eq!(
+        crate::parse::parse_module("@erlang_map\npub fn main() { Nil }", &arena)
+            .expect_err("should not parse"),
+        ParseError {
+            error: ParseErrorType::UnexpectedAttribute,
+            location: SrcSpan { start: 0, end: 11 },
+        }
+    );
+}
+
+#[test]
+fn deprecated_attribute_on_import() {
+    let arena = crate::ast::Arena::new();
+    assert_eq!(
+        crate::parse::parse_module("@deprecated(\"Do not use\")\nimport gleam/list", &arena)
+            .expect_err("should not parse"),
+        ParseError {
+            error: ParseErrorType::UnexpectedAttribute,
+            location: SrcSpan { start: 0, end: 11 },
+        }
+    );
+}
+
+#[test]
+fn attribute_on_constructor() {
+    let arena = crate::ast::Arena::new();
+    assert_eq!(
+        crate::parse::parse_module(
+            "pub type Cat {\n  @erlang_map\n  Cat(name: String)\n}",
+            &arena
+        )
+        .expect_err("should not parse"),
+        ParseError {
+            error: ParseErrorType::UnexpectedAttribute,
+            location: SrcSpan { start: 17, end: 28 },
+        }
+    );
+}
+
+#[test]
+fn duplicate_external_attribute_for_target() {
+    let arena = crate::ast::Arena::new();
+    assert_eq!(
+        crate::parse::parse_module(
+            "@external(erlang, \"a\", \"b\")\n@external(erlang, \"c\", \"d\")\npub fn main() { Nil }",
+            &arena,
+        )
+        .expect_err("should not parse"),
+        ParseError {
+            error: ParseErrorType::DuplicateAttribute,
+            location: SrcSpan { start: 28, end: 37 },
+        }
+    );
+}
+
+#[test]
+fn external_attribute_on_type() {
+    let arena = crate::ast::Arena::new();
+    assert_eq!(
+        crate::parse::parse_module(
+            "@external(erlang, \"a\", \"b\")\npub type Cat { Cat }",
+            &arena
+        )
+        .expect_err("should not parse"),
+        ParseError {
+            error: ParseErrorType::UnexpectedAttribute,
+            location: SrcSpan { start: 0, end: 9 },
+        }
+    );
+}
+
+#[test]
+fn deprecated_attribute_without_message() {
+    let arena = crate::ast::Arena::new();
+    assert!(crate::parse::parse_module("@deprecated\npub fn main() { Nil }", &arena).is_err());
+}
+
+#[test]
+fn allow_unknown_warning() {
+    let arena = crate::ast::Arena::new();
+    assert_eq!(
+        crate::parse::parse_module("@allow(unused_wibble)\npub fn main() { Nil }", &arena)
+            .expect_err("should not parse"),
+        ParseError {
+            error: ParseErrorType::UnknownWarning,
+            location: SrcSpan { start: 7, end: 20 },
+        }
+    );
+}
+
+#[test]
+fn allow_without_definition() {
+    let arena = crate::ast::Arena::new();
+    assert_eq!(
+        crate::parse::parse_module("pub fn main() { Nil }\n@allow(todo)", &arena)
+            .expect_err("should not parse"),
+        ParseError {
+            error: ParseErrorType::UnexpectedAttribute,
+            location: SrcSpan { start: 22, end: 28 },
+        }
+    );
+}
+
+#[test]
+fn allow_covers_whole_definition() {
+    let arena = crate::ast::Arena::new();
+    let (module, _) = crate::parse::parse_module(
+        "@module_allow(todo)\n@allow(unused_variable, todo)\npub fn main() { Nil }\nconst x = 1",
+        &arena,
+    )
+    .expect("should parse");
+    assert_eq!(
+        module.warning_suppressions,
+        vec![
+            WarningSuppression {
+                location: SrcSpan { start: 0, end: 13 },
+                kinds: vec![WarningKind::Todo],
+                scope: SuppressionScope::Module,
+            },
+            WarningSuppression {
+                location: SrcSpan { start: 20, end: 26 },
+                kinds: vec![WarningKind::UnusedVariable, WarningKind::Todo],
+                scope: SuppressionScope::Definition(SrcSpan { start: 50, end: 71 }),
+            },
+        ]
+    );
+}
+
+fn recovered_errors(src: &str) -> (Vec<String>, Vec<ParseError>) {
+    let arena = crate::ast::Arena::new();
+    let (module, _, errors) = crate::parse::parse_module_with_recovery(src, &arena);
+    let names = module
+        .into_iter_statements(crate::build::Target::Erlang)
+        .filter_map(|statement| match statement {
+            crate::ast::Statement::Fn { name, .. } => Some(name.to_string()),
+            _ => None,
+        })
+        .collect();
+    (names, errors.map(Vec1::into_vec).unwrap_or_default())
+}
+
+#[test]
+fn recover_from_errors_in_definitions() {
+    let src = r#"
+pub fn one() { 1 }
+
+pub fn two( { 2 }
+
+pub fn three() { 3 }
+
+pub type = Int
+
+pub fn four() { 4 }
+"#;
+    let (names, errors) = recovered_errors(src);
+    assert_eq!(names, vec!["one", "three", "four"]);
+    assert_eq!(
+        errors
+            .iter()
+            .map(|error| error.location)
+            .collect::<Vec<_>>(),
+        vec![
+            SrcSpan { start: 33, end: 34 },
+            SrcSpan { start: 71, end: 72 }
+        ]
+    );
+}
+
+#[test]
+fn recover_from_errors_in_expressions() {
+    let src = r#"
+pub fn main() {
+  let x = #(1 2)
+  let y = [1, 2
+  let z = #(x, y)
+  case z {
+    _ -> 1
+  }
+}
+
+pub fn other() { 2 }
+"#;
+    let (names, errors) = recovered_errors(src);
+    assert_eq!(names, vec!["main", "other"]);
+    assert_eq!(
+        errors
+            .iter()
+            .map(|error| error.location)
+            .collect::<Vec<_>>(),
+        vec![
+            SrcSpan { start: 31, end: 32 },
+            SrcSpan { start: 52, end: 55 }
+        ]
+    );
+}
+
+#[test]
+fn recover_from_error_inside_target_group() {
+    let src = r#"
+if erlang {
+  pub fn one() { 1 +  }
+  pub fn two( { 2 }
+}
+
+pub fn three() { 3 }
+"#;
+    let (names, errors) = recovered_errors(src);
+    assert_eq!(names, vec!["one", "three"]);
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn no_recovery_after_lex_error() {
+    let src = r#"
+pub fn one( { 1 }
+
+pub fn two() { "unterminated }
+"#;
+    let (_, errors) = recovered_errors(src);
+    assert_eq!(
+        errors.iter().map(|error| &error.error).collect::<Vec<_>>(),
+        vec![
+            &ParseErrorType::UnexpectedToken {
+                expected: vec!["\")\"".into()],
+                hint: None
+            },
+            &ParseErrorType::LexError {
+                error: LexicalError {
+                    error: LexicalErrorType::UnexpectedStringEnd,
+                    location: SrcSpan { start: 35, end: 35 },
+                }
+            },
+        ]
+    );
+}
+
+#[test]
+fn multiple_syntax_errors() {
+    let src = r#"pub fn one( { 1 }
+
+pub fn two() { 2 + }
+"#;
+    let arena = crate::ast::Arena::new();
+    let (_, _, errors) = crate::parse::parse_module_with_recovery(src, &arena);
+    let error = crate::error::Error::Parse {
+        src: src.to_string(),
+        path: PathBuf::from("/src/parse/error.gleam"),
+        errors: errors.expect("syntax errors"),
+    };
+    insta::assert_snapshot!(error.pretty_string());
+}
@@ -40,6 +40,7 @@ pub enum Token {
     Colon,
     Comma,
     Hash, // '#'
+    At,   // '@'
     Bang, // '!'
     Equal,
     EqualEqual, // '=='
@@ -65,6 +66,7 @@ pub enum Token {
     Assert,
     Case,
     Const,
+    Echo,
     External,
     Fn,
     If,
@@ -136,6 +138,7 @@ impl fmt::Display for Token {
             Token::Colon => ":",
             Token::Comma => ",",
             Token::Hash => "#",
+            Token::At => "@",
             Token::Bang => "!",
             Token::Equal => "=",
             Token::EqualEqual => "==",
@@ -159,6 +162,7 @@ impl fmt::Display for Token {
             Token::Assert => "assert",
             Token::Case => "case",
             Token::Const => "const",
+            Token::Echo => "echo",
             Token::External => "external",
             Token::Fn => "fn",
             Token::If => "if",
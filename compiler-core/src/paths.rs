@@ -31,8 +31,8 @@ pub fn test() -> PathBuf {
     PathBuf::from("test")
 }
 
-pub fn package_cache_tarball(package_name: &str, version: &str) -> PathBuf {
-    packages_cache().join(format!("{}-{}.tar", package_name, version))
+pub fn package_cache_tarball(registry: &str, package_name: &str, version: &str) -> PathBuf {
+    packages_cache(registry).join(format!("{}-{}.tar", package_name, version))
 }
 
 pub fn build_deps_package_src(package: &str) -> PathBuf {
@@ -55,27 +55,64 @@ pub fn packages_toml() -> PathBuf {
     packages().join("packages.toml")
 }
 
+/// The directory `gleam deps vendor` copies the sources of the dependency
+/// packages to, so that they can be used without network access.
+pub fn vendor() -> PathBuf {
+    PathBuf::from("vendor")
+}
+
+pub fn vendor_package(package: &str) -> PathBuf {
+    vendor().join(package)
+}
+
+pub fn vendor_packages_toml() -> PathBuf {
+    vendor().join("packages.toml")
+}
+
 pub fn build_deps_package(package: &str) -> PathBuf {
     packages().join(package)
 }
 
+/// The clone of the repository of a git dependency, from which the locked
+/// commit is exported into the packages directory.
+pub fn build_git_repository(package: &str) -> PathBuf {
+    build().join("git").join(package)
+}
+
 pub fn build_scripts() -> PathBuf {
     build().join("scripts")
 }
 
-fn packages_cache() -> PathBuf {
+fn packages_cache(registry: &str) -> PathBuf {
     default_gleam_cache()
         .join("hex")
-        .join("hexpm")
+        .join(registry)
         .join("packages")
 }
 
+pub fn default_gleam_config() -> PathBuf {
+    dirs_next::config_dir()
+        .expect("Failed to determine user config directory")
+        .join("gleam")
+}
+
+/// The API keys stored by `gleam hex authenticate`.
+pub fn hex_credentials() -> PathBuf {
+    default_gleam_config().join("hex_credentials.toml")
+}
+
 pub fn default_gleam_cache() -> PathBuf {
     dirs_next::cache_dir()
         .expect("Failed to determine user cache directory")
         .join("gleam")
 }
 
+/// The dependencies compiled by any project of the user, shared so that
+/// each version of a package is only compiled once.
+pub fn global_build_cache() -> PathBuf {
+    default_gleam_cache().join("build")
+}
+
 pub fn build_packages(mode: Mode, target: Target) -> PathBuf {
     build().join(mode.to_string()).join(target.to_string())
 }
@@ -107,6 +144,12 @@ pub fn build_package(mode: Mode, target: Target, package: &str) -> PathBuf {
     build_packages(mode, target).join(package)
 }
 
+/// The output of the build tool that last compiled a dependency which is not
+/// built by Gleam, such as rebar3 or mix.
+pub fn build_package_log(mode: Mode, target: Target, package: &str) -> PathBuf {
+    build_packages(mode, target).join(format!("{}.log", package))
+}
+
 /// A path to a special file that contains the version of gleam that last built
 /// the artifacts. If this file does not match the current version of gleam we
 /// will rebuild from scratch
@@ -124,14 +167,42 @@ pub fn erlang_shipment() -> PathBuf {
     build().join("erlang-shipment")
 }
 
+pub fn escript() -> PathBuf {
+    build().join("escript")
+}
+
+pub fn javascript_bundle() -> PathBuf {
+    build().join("javascript-bundle")
+}
+
+pub fn npm_package() -> PathBuf {
+    build().join("npm-package")
+}
+
+pub fn wasm_component() -> PathBuf {
+    build().join("wasm-component")
+}
+
+pub fn native_lib() -> PathBuf {
+    build().join("native-lib")
+}
+
+pub fn interfaces() -> PathBuf {
+    build().join("interfaces")
+}
+
 #[test]
 fn paths() {
     assert!(default_gleam_cache().ends_with("gleam"));
 
-    assert!(packages_cache().ends_with("hex/hexpm/packages"));
+    assert!(packages_cache("hexpm").ends_with("hex/hexpm/packages"));
 
-    assert!(package_cache_tarball("gleam_stdlib", "0.17.1")
+    assert!(package_cache_tarball("hexpm", "gleam_stdlib", "0.17.1")
         .ends_with("hex/hexpm/packages/gleam_stdlib-0.17.1.tar"));
 
-    assert!(package_cache_tarball("elli", "1.0.0").ends_with("hex/hexpm/packages/elli-1.0.0.tar"));
+    assert!(package_cache_tarball("hexpm", "elli", "1.0.0")
+        .ends_with("hex/hexpm/packages/elli-1.0.0.tar"));
+
+    assert!(package_cache_tarball("acme", "elli", "1.0.0")
+        .ends_with("hex/acme/packages/elli-1.0.0.tar"));
 }
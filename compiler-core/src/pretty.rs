@@ -16,9 +16,14 @@
 #[cfg(test)]
 mod tests;
 
+use std::borrow::Cow;
+
 use itertools::Itertools;
 
-use crate::{io::Utf8Writer, Result};
+use crate::{io::Utf8Writer, name::Name, Result};
+
+/// The width in columns that code is printed to when no other is configured.
+pub const DEFAULT_LINE_LENGTH: isize = 80;
 
 #[macro_export]
 macro_rules! docvec {
@@ -40,7 +45,7 @@ pub trait Documentable<'a> {
 
 impl<'a> Documentable<'a> for char {
     fn to_doc(self) -> Document<'a> {
-        Document::String(format!("{}", self))
+        Document::Char(self)
     }
 }
 
@@ -50,21 +55,36 @@ impl<'a> Documentable<'a> for &'a str {
     }
 }
 
+impl<'a> Documentable<'a> for Cow<'a, str> {
+    fn to_doc(self) -> Document<'a> {
+        match self {
+            Cow::Borrowed(s) => Document::Str(s),
+            Cow::Owned(s) => Document::String(s),
+        }
+    }
+}
+
+impl<'a> Documentable<'a> for &'a Name {
+    fn to_doc(self) -> Document<'a> {
+        Document::Str(self)
+    }
+}
+
 impl<'a> Documentable<'a> for isize {
     fn to_doc(self) -> Document<'a> {
-        Document::String(format!("{}", self))
+        Document::Int(self as i128)
     }
 }
 
 impl<'a> Documentable<'a> for i64 {
     fn to_doc(self) -> Document<'a> {
-        Document::String(format!("{}", self))
+        Document::Int(self as i128)
     }
 }
 
 impl<'a> Documentable<'a> for usize {
     fn to_doc(self) -> Document<'a> {
-        Document::String(format!("{}", self))
+        Document::Int(self as i128)
     }
 }
 
@@ -76,25 +96,25 @@ impl<'a> Documentable<'a> for f64 {
 
 impl<'a> Documentable<'a> for u64 {
     fn to_doc(self) -> Document<'a> {
-        Document::String(format!("{:?}", self))
+        Document::Int(self as i128)
     }
 }
 
 impl<'a> Documentable<'a> for u32 {
     fn to_doc(self) -> Document<'a> {
-        Document::String(format!("{}", self))
+        Document::Int(self as i128)
     }
 }
 
 impl<'a> Documentable<'a> for u16 {
     fn to_doc(self) -> Document<'a> {
-        Document::String(format!("{}", self))
+        Document::Int(self as i128)
     }
 }
 
 impl<'a> Documentable<'a> for u8 {
     fn to_doc(self) -> Document<'a> {
-        Document::String(format!("{}", self))
+        Document::Int(self as i128)
     }
 }
 
@@ -159,6 +179,20 @@ pub enum Document<'a> {
 
     /// A str to render
     Str(&'a str),
+
+    /// A name to render, shared with where it came from rather than copied
+    Name(Name),
+
+    /// An integer to render, formatted as it is written out
+    Int(i128),
+
+    /// A character to render
+    Char(char),
+
+    /// Rendered at the end of the current line, just before the next line
+    /// break, such as a comment following some code. A group containing a
+    /// line suffix is always broken.
+    LineSuffix(Box<Self>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -181,17 +215,28 @@ impl Mode {
     }
 }
 
-fn fits(
+/// A document waiting to be rendered, along with the indentation and mode it
+/// is to be rendered with.
+type Pending<'d, 'a> = (isize, Mode, &'d Document<'a>);
+
+/// Whether `first` followed by the documents of `rest` fit within the limit
+/// up to the next line break. `stack` is scratch space that is reused
+/// between calls rather than copying the remaining documents each time.
+fn fits<'d, 'a>(
     mut limit: isize,
     mut current_width: isize,
-    mut docs: im::Vector<(isize, Mode, &Document<'_>)>,
+    stack: &mut Vec<Pending<'d, 'a>>,
+    first: Option<Pending<'d, 'a>>,
+    mut rest: impl Iterator<Item = Pending<'d, 'a>>,
 ) -> bool {
+    stack.clear();
+    stack.extend(first);
     loop {
         if current_width > limit {
             return false;
         };
 
-        let (indent, mode, document) = match docs.pop_front() {
+        let (indent, mode, document) = match stack.pop().or_else(|| rest.next()) {
             Some(x) => x,
             None => return true,
         };
@@ -203,25 +248,34 @@ fn fits(
 
             Document::Line(_) => return true,
 
-            Document::Nest(i, doc) => docs.push_front((i + indent, mode, doc)),
+            Document::Nest(i, doc) => stack.push((i + indent, mode, doc)),
 
-            Document::Group(doc) if mode.is_forced() => docs.push_front((indent, mode, doc)),
+            Document::Group(doc) if mode.is_forced() => stack.push((indent, mode, doc)),
 
-            Document::Group(doc) => docs.push_front((indent, Mode::Unbroken, doc)),
+            Document::Group(doc) => stack.push((indent, Mode::Unbroken, doc)),
 
             Document::Str(s) => limit -= s.len() as isize,
+
+            // Nothing can follow a line suffix on the same line
+            Document::LineSuffix(_) => match mode {
+                Mode::Broken | Mode::ForcedBroken => (),
+                Mode::Unbroken => return false,
+            },
             Document::String(s) => limit -= s.len() as isize,
+            Document::Name(s) => limit -= s.len() as isize,
+            Document::Int(i) => limit -= format_int(*i, &mut [0; INT_BUFFER]).len() as isize,
+            Document::Char(c) => limit -= c.len_utf8() as isize,
 
             Document::Break { unbroken, .. } => match mode {
                 Mode::Broken | Mode::ForcedBroken => return true,
                 Mode::Unbroken => current_width += unbroken.len() as isize,
             },
 
-            Document::FlexBreak(doc) => docs.push_front((indent, mode, doc)),
+            Document::FlexBreak(doc) => stack.push((indent, mode, doc)),
 
             Document::Vec(vec) => {
                 for doc in vec.iter().rev() {
-                    docs.push_front((indent, mode, doc));
+                    stack.push((indent, mode, doc));
                 }
             }
         }
@@ -234,21 +288,20 @@ pub enum BreakKind {
     Strict,
 }
 
-fn format(
+/// Render the documents, the last of which is rendered first.
+fn format<'d, 'a>(
     writer: &mut impl Utf8Writer,
     limit: isize,
     mut width: isize,
-    mut docs: im::Vector<(isize, Mode, &Document<'_>)>,
+    mut docs: Vec<Pending<'d, 'a>>,
+    fits_stack: &mut Vec<Pending<'d, 'a>>,
 ) -> Result<()> {
-    while let Some((indent, mode, document)) = docs.pop_front() {
+    let mut suffixes = Vec::new();
+    while let Some((indent, mode, document)) = docs.pop() {
         match document {
             Document::Line(i) => {
-                for _ in 0..*i {
-                    writer.str_write("\n")?;
-                }
-                for _ in 0..indent {
-                    writer.str_write(" ")?;
-                }
+                write_line_suffixes(writer, limit, width, &mut suffixes, fits_stack)?;
+                write_line_break(writer, *i, indent)?;
                 width = indent;
             }
 
@@ -260,15 +313,19 @@ fn format(
             } => {
                 let unbroken_width = width + unbroken.len() as isize;
 
-                if fits(limit, unbroken_width, docs.clone()) {
+                if fits(
+                    limit,
+                    unbroken_width,
+                    fits_stack,
+                    None,
+                    docs.iter().rev().copied(),
+                ) {
                     writer.str_write(unbroken)?;
                     width = unbroken_width;
                 } else {
                     writer.str_write(broken)?;
-                    writer.str_write("\n")?;
-                    for _ in 0..indent {
-                        writer.str_write(" ")?;
-                    }
+                    write_line_suffixes(writer, limit, width, &mut suffixes, fits_stack)?;
+                    write_line_break(writer, 1, indent)?;
                     width = indent;
                 }
             }
@@ -287,10 +344,8 @@ fn format(
 
                     Mode::Broken | Mode::ForcedBroken => {
                         writer.str_write(broken)?;
-                        writer.str_write("\n")?;
-                        for _ in 0..indent {
-                            writer.str_write(" ")?;
-                        }
+                        write_line_suffixes(writer, limit, width, &mut suffixes, fits_stack)?;
+                        write_line_break(writer, 1, indent)?;
                         indent
                     }
                 };
@@ -306,30 +361,113 @@ fn format(
                 writer.str_write(s)?;
             }
 
+            Document::Name(s) => {
+                width += s.len() as isize;
+                writer.str_write(s)?;
+            }
+
+            Document::Int(i) => {
+                let mut buffer = [0; INT_BUFFER];
+                let s = format_int(*i, &mut buffer);
+                width += s.len() as isize;
+                writer.str_write(s)?;
+            }
+
+            Document::Char(c) => {
+                width += c.len_utf8() as isize;
+                writer.str_write(c.encode_utf8(&mut [0; 4]))?;
+            }
+
             Document::Vec(vec) => {
                 for doc in vec.iter().rev() {
-                    docs.push_front((indent, mode, doc));
+                    docs.push((indent, mode, doc));
                 }
             }
 
             Document::Nest(i, doc) => {
-                docs.push_front((indent + i, mode, doc));
+                docs.push((indent + i, mode, doc));
             }
 
             Document::Group(doc) | Document::FlexBreak(doc) => {
-                let group_docs = im::vector![(indent, Mode::Unbroken, doc.as_ref())];
-                if fits(limit, width, group_docs) {
-                    docs.push_front((indent, Mode::Unbroken, doc));
+                let group = (indent, Mode::Unbroken, doc.as_ref());
+                if fits(limit, width, fits_stack, Some(group), std::iter::empty()) {
+                    docs.push((indent, Mode::Unbroken, doc));
                 } else {
-                    docs.push_front((indent, Mode::Broken, doc));
+                    docs.push((indent, Mode::Broken, doc));
                 }
             }
 
             Document::ForceBroken(document) => {
-                docs.push_front((indent, Mode::ForcedBroken, document));
+                docs.push((indent, Mode::ForcedBroken, document));
+            }
+
+            Document::LineSuffix(document) => {
+                suffixes.push((indent, mode, document.as_ref()));
             }
         }
     }
+    write_line_suffixes(writer, limit, width, &mut suffixes, fits_stack)
+}
+
+fn write_line_suffixes<'d, 'a>(
+    writer: &mut impl Utf8Writer,
+    limit: isize,
+    width: isize,
+    suffixes: &mut Vec<Pending<'d, 'a>>,
+    fits_stack: &mut Vec<Pending<'d, 'a>>,
+) -> Result<()> {
+    if suffixes.is_empty() {
+        return Ok(());
+    }
+    let mut docs = std::mem::take(suffixes);
+    docs.reverse();
+    format(writer, limit, width, docs, fits_stack)
+}
+
+/// Enough bytes for any `i128`, including its sign.
+const INT_BUFFER: usize = 40;
+
+/// Write the decimal digits of an integer into the end of the buffer,
+/// returning them without allocating a string.
+fn format_int(value: i128, buffer: &mut [u8; INT_BUFFER]) -> &str {
+    let mut start = INT_BUFFER;
+    let mut rest = value.unsigned_abs();
+    for byte in buffer.iter_mut().rev() {
+        *byte = b'0' + (rest % 10) as u8;
+        rest /= 10;
+        start -= 1;
+        if rest == 0 {
+            break;
+        }
+    }
+    if value < 0 {
+        start -= 1;
+        if let Some(byte) = buffer.get_mut(start) {
+            *byte = b'-';
+        }
+    }
+    buffer
+        .get(start..)
+        .and_then(|digits| std::str::from_utf8(digits).ok())
+        .unwrap_or_default()
+}
+
+const NEWLINES: &str = "\n\n\n\n\n\n\n\n\n\n\n\n\n\n\n\n";
+const SPACES: &str = "                                                                ";
+
+// Output is streamed to the writer as it is rendered, so whitespace is
+// written in chunks of bounded size rather than one character at a time.
+fn write_line_break(writer: &mut impl Utf8Writer, lines: usize, indent: isize) -> Result<()> {
+    write_repeated(writer, NEWLINES, lines)?;
+    write_repeated(writer, SPACES, usize::try_from(indent).unwrap_or(0))
+}
+
+fn write_repeated(writer: &mut impl Utf8Writer, chunk: &str, mut count: usize) -> Result<()> {
+    while count > 0 {
+        let length = count.min(chunk.len());
+        writer.str_write(chunk.get(..length).unwrap_or_default())?;
+        count -= length;
+    }
     Ok(())
 }
 
@@ -396,8 +534,8 @@ impl<'a> Document<'a> {
     }
 
     pub fn pretty_print(&self, limit: isize, writer: &mut impl Utf8Writer) -> Result<()> {
-        let docs = im::vector![(0, Mode::Unbroken, self)];
-        format(writer, limit, 0, docs)?;
+        let docs = vec![(0, Mode::Unbroken, self)];
+        format(writer, limit, 0, docs, &mut Vec::new())?;
         Ok(())
     }
 
@@ -409,9 +547,11 @@ impl<'a> Document<'a> {
             Line(n) => *n == 0,
             String(s) => s.is_empty(),
             Str(s) => s.is_empty(),
+            Name(s) => s.is_empty(),
+            Int(_) | Char(_) => false,
             // assuming `broken` and `unbroken` are equivalent
             Break { broken, .. } => broken.is_empty(),
-            ForceBroken(d) | FlexBreak(d) | Nest(_, d) | Group(d) => d.is_empty(),
+            ForceBroken(d) | FlexBreak(d) | Nest(_, d) | Group(d) | LineSuffix(d) => d.is_empty(),
             Vec(docs) => docs.iter().all(|d| d.is_empty()),
         }
     }
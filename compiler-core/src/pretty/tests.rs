@@ -2,28 +2,39 @@ use super::Document::*;
 use super::Mode::*;
 use super::*;
 
-use im::vector;
+use crate::docvec;
 use pretty_assertions::assert_eq;
 
+/// Whether the documents fit, the first in the list being rendered first.
+fn fits(limit: isize, width: isize, docs: std::vec::Vec<Pending<'_, '_>>) -> bool {
+    super::fits(
+        limit,
+        width,
+        &mut std::vec::Vec::new(),
+        None,
+        docs.into_iter(),
+    )
+}
+
 #[test]
 fn fits_test() {
     // Negative limits never fit
-    assert!(!fits(-1, 0, vector![]));
+    assert!(!fits(-1, 0, vec![]));
 
     // If no more documents it always fits
-    assert!(fits(0, 0, vector![]));
+    assert!(fits(0, 0, vec![]));
 
     // ForceBreak never fits
     let doc = ForceBroken(Box::new(nil()));
-    assert!(!fits(100, 0, vector![(0, Unbroken, &doc)]));
+    assert!(!fits(100, 0, vec![(0, Unbroken, &doc)]));
     let doc = ForceBroken(Box::new(nil()));
-    assert!(!fits(100, 0, vector![(0, Broken, &doc)]));
+    assert!(!fits(100, 0, vec![(0, Broken, &doc)]));
 
     // Break in Broken fits always
     assert!(fits(
         1,
         0,
-        vector![(
+        vec![(
             0,
             Broken,
             &Break {
@@ -38,7 +49,7 @@ fn fits_test() {
     assert!(fits(
         3,
         0,
-        vector![(
+        vec![(
             0,
             Unbroken,
             &Break {
@@ -51,7 +62,7 @@ fn fits_test() {
     assert!(!fits(
         2,
         0,
-        vector![(
+        vec![(
             0,
             Unbroken,
             &Break {
@@ -63,42 +74,42 @@ fn fits_test() {
     ));
 
     // Line always fits
-    assert!(fits(0, 0, vector![(0, Broken, &Line(100))]));
-    assert!(fits(0, 0, vector![(0, Unbroken, &Line(100))]));
+    assert!(fits(0, 0, vec![(0, Broken, &Line(100))]));
+    assert!(fits(0, 0, vec![(0, Unbroken, &Line(100))]));
 
     // String fits if smaller than limit
     let doc = String("Hello".to_string());
-    assert!(fits(5, 0, vector![(0, Broken, &doc)]));
+    assert!(fits(5, 0, vec![(0, Broken, &doc)]));
     let doc = String("Hello".to_string());
-    assert!(fits(5, 0, vector![(0, Unbroken, &doc)]));
+    assert!(fits(5, 0, vec![(0, Unbroken, &doc)]));
     let doc = String("Hello".to_string());
-    assert!(!fits(4, 0, vector![(0, Broken, &doc)]));
+    assert!(!fits(4, 0, vec![(0, Broken, &doc)]));
     let doc = String("Hello".to_string());
-    assert!(!fits(4, 0, vector![(0, Unbroken, &doc)]));
+    assert!(!fits(4, 0, vec![(0, Unbroken, &doc)]));
 
     // Cons fits if combined smaller than limit
     let doc = String("1".to_string()).append(String("2".to_string()));
-    assert!(fits(2, 0, vector![(0, Broken, &doc)]));
+    assert!(fits(2, 0, vec![(0, Broken, &doc)]));
     let doc = String("1".to_string()).append(String("2".to_string()));
-    assert!(fits(2, 0, vector![(0, Unbroken, &doc,)]));
+    assert!(fits(2, 0, vec![(0, Unbroken, &doc,)]));
     let doc = String("1".to_string()).append(String("2".to_string()));
-    assert!(!fits(1, 0, vector![(0, Broken, &doc)]));
+    assert!(!fits(1, 0, vec![(0, Broken, &doc)]));
     let doc = String("1".to_string()).append(String("2".to_string()));
-    assert!(!fits(1, 0, vector![(0, Unbroken, &doc)]));
+    assert!(!fits(1, 0, vec![(0, Unbroken, &doc)]));
 
     // Nest fits if combined smaller than limit
     let doc = Nest(1, Box::new(String("12".to_string())));
-    assert!(fits(2, 0, vector![(0, Broken, &doc)]));
-    assert!(fits(2, 0, vector![(0, Unbroken, &doc)]));
-    assert!(!fits(1, 0, vector![(0, Broken, &doc)]));
-    assert!(!fits(1, 0, vector![(0, Unbroken, &doc)]));
+    assert!(fits(2, 0, vec![(0, Broken, &doc)]));
+    assert!(fits(2, 0, vec![(0, Unbroken, &doc)]));
+    assert!(!fits(1, 0, vec![(0, Broken, &doc)]));
+    assert!(!fits(1, 0, vec![(0, Unbroken, &doc)]));
 
     // Nest fits if combined smaller than limit
     let doc = Nest(0, Box::new(String("12".to_string())));
-    assert!(fits(2, 0, vector![(0, Broken, &doc)]));
-    assert!(fits(2, 0, vector![(0, Unbroken, &doc)]));
-    assert!(!fits(1, 0, vector![(0, Broken, &doc)]));
-    assert!(!fits(1, 0, vector![(0, Unbroken, &doc)]));
+    assert!(fits(2, 0, vec![(0, Broken, &doc)]));
+    assert!(fits(2, 0, vec![(0, Unbroken, &doc)]));
+    assert!(!fits(1, 0, vec![(0, Broken, &doc)]));
+    assert!(!fits(1, 0, vec![(0, Unbroken, &doc)]));
 }
 
 #[test]
@@ -202,3 +213,70 @@ fn empty_documents() {
     assert!(!"foo".to_doc().append("".to_doc()).is_empty());
     assert!(!"".to_doc().append("foo".to_doc()).is_empty());
 }
+
+#[test]
+fn deep_indentation_and_many_lines() {
+    let doc = "a".to_doc().append(lines(20).append("b").nest(70));
+    let expected = format!("a{}{}b", "\n".repeat(20), " ".repeat(70));
+    assert_eq!(expected, doc.to_pretty_string(80));
+
+    let doc = "a"
+        .to_doc()
+        .append(break_("", " ").append("b").nest(100))
+        .group();
+    let expected = format!("a\n{}b", " ".repeat(100));
+    assert_eq!(expected, doc.to_pretty_string(1));
+}
+
+#[test]
+fn line_suffix() {
+    let doc = "a"
+        .to_doc()
+        .append(LineSuffix(Box::new(" // x".to_doc())))
+        .append(",")
+        .append(line())
+        .append("b");
+    assert_eq!("a, // x\nb", doc.to_pretty_string(80));
+
+    // A line suffix forces the group it is in to break
+    let doc = "a"
+        .to_doc()
+        .append(LineSuffix(Box::new(" // x".to_doc())))
+        .append(break_(",", ", "))
+        .append("b")
+        .group();
+    assert_eq!("a, // x\nb", doc.to_pretty_string(80));
+
+    let doc = "a".to_doc().append(LineSuffix(Box::new(" // x".to_doc())));
+    assert_eq!("a // x", doc.to_pretty_string(80));
+}
+
+#[test]
+fn integers() {
+    assert_eq!("0", 0_usize.to_doc().to_pretty_string(80));
+    assert_eq!("-42", (-42_i64).to_doc().to_pretty_string(80));
+    assert_eq!(
+        "18446744073709551615",
+        u64::MAX.to_doc().to_pretty_string(80)
+    );
+    assert_eq!(
+        "-9223372036854775808",
+        i64::MIN.to_doc().to_pretty_string(80)
+    );
+    assert!(!0_u8.to_doc().is_empty());
+
+    // Integers count towards the width of a group
+    let doc = docvec![12345_u32, break_("", " "), "a"].group();
+    assert_eq!("12345 a", doc.clone().to_pretty_string(7));
+    assert_eq!("12345\na", doc.to_pretty_string(6));
+}
+
+#[test]
+fn chars_and_cows() {
+    let doc = docvec!['é', Cow::Borrowed("a"), Cow::<str>::Owned("b".into())];
+    assert_eq!("éab", doc.to_pretty_string(80));
+
+    let doc = docvec!['é', break_("", " "), "a"].group();
+    assert_eq!("é a", doc.clone().to_pretty_string(4));
+    assert_eq!("é\na", doc.to_pretty_string(3));
+}
@@ -0,0 +1,196 @@
+//! The compilation of the input of the `gleam shell` REPL.
+//!
+//! Each expression entered is wrapped in the `main` function of a module of
+//! its own, with the imports entered earlier in the session, and is type
+//! checked against the compiled modules of the project. The module is then
+//! compiled for the target and run by the runtime the REPL is attached to.
+
+use std::path::PathBuf;
+
+use crate::{
+    ast::{Arena, Name, Statement, TypedModule},
+    build::{Origin, Target},
+    error::Error,
+    line_numbers::LineNumbers,
+    type_::{self, pretty::Printer},
+    uid::UniqueIdGenerator,
+    Result,
+};
+
+/// The directory the modules of the inputs are written to, within the
+/// build directory of the root package.
+pub const MODULE_DIRECTORY: &str = "gleam_repl";
+
+#[derive(Debug, Default)]
+pub struct Session {
+    /// The imports entered so far, which are added to each later input.
+    imports: Vec<String>,
+    inputs: usize,
+}
+
+/// An expression that has been type checked, ready to be compiled and run.
+#[derive(Debug)]
+pub struct Evaluation {
+    /// The name of the module whose `main` function returns the value of the
+    /// expression, such as `gleam_repl/input_1`.
+    pub module: String,
+    pub src: String,
+    pub ast: TypedModule,
+    /// The type of the expression, as it is written in Gleam.
+    pub type_: String,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Type check an input of the REPL. An import is added to the session,
+    /// returning `None`, and an expression is returned ready to be run.
+    pub fn compile(
+        &mut self,
+        input: &str,
+        target: Target,
+        package: &str,
+        ids: &UniqueIdGenerator,
+        importable_modules: &im::HashMap<String, type_::Module>,
+    ) -> Result<Option<Evaluation>> {
+        let input = input.trim();
+        let is_import = input.starts_with("import ");
+        self.inputs += 1;
+        let module = format!("{}/input_{}", MODULE_DIRECTORY, self.inputs);
+        let mut src: String = self
+            .imports
+            .iter()
+            .map(|line| format!("{}\n", line))
+            .collect();
+        if is_import {
+            src.push_str(input);
+            src.push_str("\npub fn main() {\n  Nil\n}\n");
+        } else {
+            src.push_str(&format!("pub fn main() {{\n{}\n}}\n", input));
+        }
+
+        let path = PathBuf::from(format!("{}.gleam", module));
+        let arena = Arena::new();
+        let (mut ast, _) =
+            crate::parse::parse_module(&src, &arena).map_err(|error| Error::Parse {
+                path: path.clone(),
+                src: src.clone(),
+                errors: vec1::vec1![error],
+            })?;
+        ast.name = module.split('/').map(Name::from).collect();
+        let ast = type_::infer_module(
+            target,
+            ids,
+            ast,
+            Origin::Src,
+            package,
+            importable_modules,
+            &mut vec![],
+        )
+        .map_err(|error| Error::Type {
+            path,
+            src: src.clone(),
+            error,
+        })?;
+
+        if is_import {
+            self.imports.push(input.to_string());
+            return Ok(None);
+        }
+        let type_ = ast
+            .statements
+            .iter()
+            .find_map(|statement| match statement {
+                Statement::Fn {
+                    name, return_type, ..
+                } if name == "main" => Some(Printer::new().pretty_print(return_type, 0)),
+                _ => None,
+            })
+            .unwrap_or_default();
+        Ok(Some(Evaluation {
+            module,
+            src,
+            ast,
+            type_,
+        }))
+    }
+}
+
+impl Evaluation {
+    pub fn erlang(&self, line_length: isize) -> Result<String> {
+        let mut code = String::new();
+        let line_numbers = LineNumbers::new(&self.src);
+        crate::erlang::module(&self.ast, &line_numbers, line_length, &mut code)?;
+        Ok(code)
+    }
+
+    pub fn javascript(&self, line_length: isize) -> Result<String> {
+        let mut code = String::new();
+        let line_numbers = LineNumbers::new(&self.src);
+        let path = PathBuf::from(format!("{}.gleam", self.module));
+        crate::javascript::module(
+            &self.ast,
+            &line_numbers,
+            &path,
+            &self.src,
+            line_length,
+            &mut code,
+        )?;
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+fn compile_inputs(inputs: &[&str]) -> Result<Vec<Option<Evaluation>>> {
+    let ids = UniqueIdGenerator::new();
+    let mut importable = im::HashMap::new();
+    let _ = importable.insert("gleam".to_string(), type_::build_prelude(&ids));
+    let mut session = Session::new();
+    inputs
+        .iter()
+        .map(|input| session.compile(input, Target::Erlang, "app", &ids, &importable))
+        .collect()
+}
+
+#[test]
+fn repl_expression_type() {
+    let evaluations = compile_inputs(&["1 + 2", "#(1, \"two\")"]).expect("compile");
+    let types: Vec<_> = evaluations
+        .iter()
+        .map(|evaluation| {
+            evaluation
+                .as_ref()
+                .map(|evaluation| evaluation.type_.as_str())
+        })
+        .collect();
+    assert_eq!(types, [Some("Int"), Some("#(Int, String)")]);
+}
+
+#[test]
+fn repl_modules_are_numbered() {
+    let evaluations = compile_inputs(&["1", "2"]).expect("compile");
+    let modules: Vec<_> = evaluations
+        .iter()
+        .flatten()
+        .map(|evaluation| evaluation.module.as_str())
+        .collect();
+    assert_eq!(modules, ["gleam_repl/input_1", "gleam_repl/input_2"]);
+}
+
+#[test]
+fn repl_type_error() {
+    assert!(matches!(
+        compile_inputs(&["1 + \"two\""]),
+        Err(Error::Type { .. })
+    ));
+}
+
+#[test]
+fn repl_unknown_import() {
+    assert!(matches!(
+        compile_inputs(&["import nope"]),
+        Err(Error::Type { .. })
+    ));
+}
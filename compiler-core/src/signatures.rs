@@ -0,0 +1,146 @@
+//! The inferred signatures of the functions and constants defined in a
+//! module, as printed by the `gleam types` command.
+
+use crate::{
+    ast::{Statement, TypedModule},
+    format::Formatter,
+    pretty::{Document, Documentable},
+    type_::pretty::Printer,
+};
+
+/// One line for each function and constant defined by the module, in the
+/// order they are defined. Private definitions are only included if
+/// `include_private` is true.
+pub fn module_signatures(module: &TypedModule, include_private: bool) -> Vec<String> {
+    let mut formatter = Formatter::new();
+    module
+        .statements
+        .iter()
+        .filter_map(|statement| {
+            let document = match statement {
+                Statement::Fn {
+                    public,
+                    name,
+                    arguments,
+                    return_type,
+                    ..
+                } if *public || include_private => {
+                    formatter.docs_fn_signature(*public, name, arguments, return_type.clone())
+                }
+
+                Statement::ExternalFn {
+                    public,
+                    name,
+                    arguments,
+                    return_,
+                    ..
+                } if *public || include_private => {
+                    formatter.external_fn_signature(*public, name, arguments, return_)
+                }
+
+                Statement::ModuleConstant {
+                    public,
+                    name,
+                    type_,
+                    ..
+                } if *public || include_private => constant_signature(*public, name, type_),
+
+                _ => return None,
+            };
+            Some(print(document))
+        })
+        .collect()
+}
+
+fn constant_signature<'a>(public: bool, name: &'a str, type_: &crate::type_::Type) -> Document<'a> {
+    let public = if public { "pub " } else { "" };
+    public
+        .to_doc()
+        .append("const ")
+        .append(name)
+        .append(": ")
+        .append(Printer::new().print(type_))
+}
+
+// Signatures are printed on a single line so they can be compared line by
+// line, which is useful when checking for changes to the API of a package.
+fn print(document: Document<'_>) -> String {
+    document.to_pretty_string(isize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        build::{Origin, Target},
+        type_::{build_prelude, infer_module},
+        uid::UniqueIdGenerator,
+    };
+
+    fn signatures(src: &str, include_private: bool) -> Vec<String> {
+        let ids = UniqueIdGenerator::new();
+        let mut modules = im::HashMap::new();
+        let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+        let arena = crate::ast::Arena::new();
+        let (ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+        let module = infer_module(
+            Target::Erlang,
+            &ids,
+            ast,
+            Origin::Src,
+            "thepackage",
+            &modules,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        module_signatures(&module, include_private)
+    }
+
+    const SRC: &str = r#"
+pub const greeting = "Hello"
+
+const answer = 42
+
+pub fn main() {
+  greet(greeting)
+}
+
+fn greet(name) {
+  #(name, answer)
+}
+
+pub fn map(list: List(a), with fun: fn(a) -> b) -> List(b) {
+  todo
+}
+
+pub external fn print(String) -> Nil = "io" "format"
+"#;
+
+    #[test]
+    fn public_signatures() {
+        assert_eq!(
+            signatures(SRC, false),
+            vec![
+                "pub const greeting: String",
+                "pub fn main() -> #(String, Int)",
+                "pub fn map(list: List(a), with fun: fn(a) -> b) -> List(b)",
+                "pub external fn print(String) -> Nil",
+            ]
+        );
+    }
+
+    #[test]
+    fn private_signatures() {
+        assert_eq!(
+            signatures(SRC, true),
+            vec![
+                "pub const greeting: String",
+                "const answer: Int",
+                "pub fn main() -> #(String, Int)",
+                "fn greet(name: String) -> #(String, Int)",
+                "pub fn map(list: List(a), with fun: fn(a) -> b) -> List(b)",
+                "pub external fn print(String) -> Nil",
+            ]
+        );
+    }
+}
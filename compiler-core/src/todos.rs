@@ -0,0 +1,130 @@
+//! The `todo` expressions remaining in a module along with the types they
+//! must produce, as printed by the `gleam todos` command.
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        TypedExpr, TypedModule,
+    },
+    line_numbers::LineNumbers,
+    type_::pretty::Printer,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Todo {
+    pub line: u32,
+    pub column: u32,
+    pub label: Option<String>,
+    /// The type the checker inferred for the `todo`, which is the type of
+    /// the value the code that replaces it must produce.
+    pub type_: String,
+}
+
+/// The `todo` expressions of the module, in the order they appear in the
+/// source code.
+pub fn module_todos(module: &TypedModule, src: &str) -> Vec<Todo> {
+    let mut collector = TodoCollector {
+        line_numbers: LineNumbers::new(src),
+        todos: vec![],
+    };
+    collector.visit_typed_module(module);
+    let mut todos = collector.todos;
+    todos.sort_by_key(|todo| (todo.line, todo.column));
+    todos
+}
+
+struct TodoCollector {
+    line_numbers: LineNumbers,
+    todos: Vec<Todo>,
+}
+
+impl<'ast> Visit<'ast> for TodoCollector {
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::Todo {
+            location,
+            label,
+            typ,
+        } = expr
+        {
+            let position = self.line_numbers.line_and_column_number(location.start);
+            self.todos.push(Todo {
+                line: position.line,
+                column: position.column,
+                label: label.clone(),
+                type_: Printer::new().pretty_print(typ, 0),
+            });
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        build::{Origin, Target},
+        type_::{build_prelude, infer_module},
+        uid::UniqueIdGenerator,
+    };
+
+    fn todos(src: &str) -> Vec<Todo> {
+        let ids = UniqueIdGenerator::new();
+        let mut modules = im::HashMap::new();
+        let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+        let arena = crate::ast::Arena::new();
+        let (ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+        let module = infer_module(
+            Target::Erlang,
+            &ids,
+            ast,
+            Origin::Src,
+            "thepackage",
+            &modules,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        module_todos(&module, src)
+    }
+
+    #[test]
+    fn todos_with_their_types() {
+        let src = r#"
+pub fn parse(input: String) -> Result(Int, String) {
+  todo
+}
+
+pub fn main() {
+  let names = todo("fetch the names")
+  #(names, 1 + todo)
+}
+"#;
+        assert_eq!(
+            todos(src),
+            vec![
+                Todo {
+                    line: 3,
+                    column: 3,
+                    label: None,
+                    type_: "Result(Int, String)".into(),
+                },
+                Todo {
+                    line: 7,
+                    column: 15,
+                    label: Some("fetch the names".into()),
+                    type_: "a".into(),
+                },
+                Todo {
+                    line: 8,
+                    column: 16,
+                    label: None,
+                    type_: "Int".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn no_todos() {
+        assert_eq!(todos("pub fn main() { 1 }"), vec![]);
+    }
+}
@@ -1,7 +1,10 @@
+pub mod diff;
 mod environment;
 mod error;
 mod expression;
 mod fields;
+mod fingerprint;
+pub mod guard;
 mod hydrator;
 mod pattern;
 mod pipe;
@@ -11,7 +14,9 @@ pub mod pretty;
 mod tests;
 
 pub use environment::*;
-pub use error::{Error, UnifyErrorSituation, Warning};
+pub use error::{
+    closest_name, Error, TraceStep, TraceStepKind, UnifyErrorSituation, Warning, WarningKind,
+};
 pub(crate) use expression::ExprTyper;
 pub use fields::FieldMap;
 pub use prelude::*;
@@ -19,22 +24,23 @@ pub use prelude::*;
 use crate::{
     ast::{
         self, ArgNames, BitStringSegment, BitStringSegmentOption, CallArg, Constant,
-        DefinitionLocation, Layer, Pattern, RecordConstructor, RecordConstructorArg, SrcSpan,
-        Statement, TypeAst, TypedConstant, TypedExpr, TypedModule, TypedPattern,
+        DefinitionLocation, Deprecation, External, ExternalFnArg, Layer, Pattern,
+        RecordConstructor, RecordConstructorArg, SrcSpan, Statement, TypeAst, TypedArg,
+        TypedConstant, TypedExpr, TypedExternalFnArg, TypedModule, TypedPattern,
         TypedPatternBitStringSegment, TypedRecordUpdateArg, TypedStatement, UnqualifiedImport,
-        UntypedModule, UntypedMultiPattern, UntypedPattern, UntypedRecordUpdateArg,
+        UntypedArg, UntypedModule, UntypedMultiPattern, UntypedPattern, UntypedRecordUpdateArg,
         UntypedStatement,
     },
     bit_string,
     build::{Origin, Target},
+    name::Name,
     uid::UniqueIdGenerator,
 };
 
 use std::{
-    cell::RefCell,
     collections::{HashMap, HashSet},
     ops::Deref,
-    sync::Arc,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
 };
 
 use error::*;
@@ -57,8 +63,8 @@ pub enum Type {
     ///
     App {
         public: bool,
-        module: Vec<String>,
-        name: String,
+        module: Vec<Name>,
+        name: Name,
         args: Vec<Arc<Type>>,
     },
 
@@ -71,7 +77,7 @@ pub enum Type {
 
     /// A type variable. See the contained `TypeVar` enum for more information.
     ///
-    Var { type_: Arc<RefCell<TypeVar>> },
+    Var { type_: Arc<TypeVarCell> },
 
     /// A tuple is an ordered collection of 0 or more values, each of which
     /// can have a different type, so the `tuple` type is the sum of all the
@@ -92,11 +98,22 @@ impl Type {
         matches!(self, Self::App { name, module, .. } if "Result" == name && module.is_empty())
     }
 
+    pub fn is_crash_reason_constructor(&self) -> bool {
+        match self {
+            Type::Fn { retrn, .. } => retrn.is_crash_reason(),
+            _ => false,
+        }
+    }
+
+    pub fn is_crash_reason(&self) -> bool {
+        matches!(self, Self::App { name, module, .. } if "CrashReason" == name && module.is_empty())
+    }
+
     pub fn is_unbound(&self) -> bool {
         matches!(self, Self::Var { type_: typ } if typ.borrow().is_unbound())
     }
 
-    pub fn type_vars(&self) -> Vec<Arc<RefCell<TypeVar>>> {
+    pub fn type_vars(&self) -> Vec<Arc<TypeVarCell>> {
         match self {
             Self::App { args, .. } => args.iter().flat_map(|a| a.type_vars()).collect(),
             Self::Fn { args, retrn } => {
@@ -190,7 +207,7 @@ impl Type {
     pub fn get_app_args(
         &self,
         public: bool,
-        module: &[String],
+        module: &[Name],
         name: &str,
         arity: usize,
         environment: &mut Environment<'_>,
@@ -226,7 +243,7 @@ impl Type {
                 // to the desired type.
                 *typ.borrow_mut() = TypeVar::Link {
                     type_: Arc::new(Self::App {
-                        name: name.to_string(),
+                        name: name.into(),
                         module: module.to_owned(),
                         args: args.clone(),
                         public,
@@ -261,6 +278,28 @@ impl Type {
         }
     }
 
+    /// Whether the unbound type variable with the given id appears anywhere
+    /// within this type.
+    pub fn contains_type_var(&self, id: u64) -> bool {
+        match self {
+            Self::App { args, .. } => args.iter().any(|t| t.contains_type_var(id)),
+
+            Self::Tuple { elems, .. } => elems.iter().any(|t| t.contains_type_var(id)),
+
+            Self::Fn { retrn, args, .. } => {
+                retrn.contains_type_var(id) || args.iter().any(|t| t.contains_type_var(id))
+            }
+
+            Self::Var { type_: typ, .. } => match typ.borrow().deref() {
+                TypeVar::Unbound { id: var_id } => *var_id == id,
+
+                TypeVar::Generic { .. } => false,
+
+                TypeVar::Link { type_: typ, .. } => typ.contains_type_var(id),
+            },
+        }
+    }
+
     pub fn fn_arity(&self) -> Option<usize> {
         match self {
             Self::Fn { args, .. } => Some(args.len()),
@@ -282,17 +321,29 @@ pub fn collapse_links(t: Arc<Type>) -> Arc<Type> {
 pub struct AccessorsMap {
     pub public: bool,
     pub type_: Arc<Type>,
-    pub accessors: HashMap<String, RecordAccessor>,
+    pub accessors: HashMap<Name, RecordAccessor>,
+    /// Whether the record is represented as a map on the Erlang target.
+    pub erlang_map: bool,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct RecordAccessor {
     // TODO: smaller int. Doesn't need to be this big
     pub index: u64,
-    pub label: String,
+    pub label: Name,
     pub type_: Arc<Type>,
 }
 
+/// The position of a field in one of the constructors of a custom type, used
+/// when a field shared by all the constructors is not at the same position in
+/// each of them.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VariantField {
+    pub constructor: Name,
+    pub arity: u16,
+    pub index: u64,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ValueConstructorVariant {
     /// A locally defined variable or function parameter
@@ -301,27 +352,30 @@ pub enum ValueConstructorVariant {
     /// A module constant
     ModuleConstant {
         location: SrcSpan,
-        module: String,
-        literal: Constant<Arc<Type>, String>,
+        module: Name,
+        literal: Constant<Arc<Type>, Name>,
     },
 
     /// A function belonging to the module
     ModuleFn {
-        name: String,
+        name: Name,
         field_map: Option<FieldMap>,
-        module: Vec<String>,
+        module: Vec<Name>,
         arity: usize,
         location: SrcSpan,
     },
 
     /// A constructor for a custom type
     Record {
-        name: String,
+        name: Name,
         arity: u16,
         field_map: Option<FieldMap>,
         location: SrcSpan,
-        module: String,
+        module: Name,
         constructors_count: u16,
+        /// Whether the record is represented as a map on the Erlang target,
+        /// as requested with the `@erlang_map` attribute.
+        erlang_map: bool,
     },
 }
 
@@ -329,7 +383,7 @@ impl ValueConstructorVariant {
     fn to_module_value_constructor(
         &self,
         type_: Arc<Type>,
-        module_name: &[String],
+        module_name: &[Name],
         function_name: &str,
     ) -> ModuleValueConstructor {
         match self {
@@ -338,6 +392,7 @@ impl ValueConstructorVariant {
                 arity,
                 field_map,
                 location,
+                erlang_map,
                 ..
             } => ModuleValueConstructor::Record {
                 name: name.clone(),
@@ -345,6 +400,7 @@ impl ValueConstructorVariant {
                 arity: *arity,
                 type_,
                 location: *location,
+                erlang_map: *erlang_map,
             },
 
             // TODO: remove this clone with an rc clone
@@ -356,7 +412,7 @@ impl ValueConstructorVariant {
             },
 
             Self::LocalVariable { location, .. } => ModuleValueConstructor::Fn {
-                name: function_name.to_string(),
+                name: function_name.into(),
                 module: module_name.to_vec(),
                 location: *location,
             },
@@ -395,16 +451,24 @@ impl ValueConstructorVariant {
     pub fn is_module_fn(&self) -> bool {
         matches!(self, Self::ModuleFn { .. })
     }
+
+    /// Returns `true` if the variant is the prelude's `rescue` function,
+    /// which each target generates itself.
+    #[must_use]
+    pub fn is_rescue(&self) -> bool {
+        matches!(self, Self::ModuleFn { module, name, .. } if module == &["gleam"] && name == RESCUE)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ModuleValueConstructor {
     Record {
-        name: String,
+        name: Name,
         arity: u16,
         type_: Arc<Type>,
         field_map: Option<FieldMap>,
         location: SrcSpan,
+        erlang_map: bool,
     },
 
     Fn {
@@ -421,8 +485,8 @@ pub enum ModuleValueConstructor {
         ///     pub external fn wibble() -> Nil =
         ///       "other" "whoop"
         ///
-        module: Vec<String>,
-        name: String,
+        module: Vec<Name>,
+        name: Name,
     },
 
     Constant {
@@ -432,6 +496,13 @@ pub enum ModuleValueConstructor {
 }
 
 impl ModuleValueConstructor {
+    /// Returns `true` if this is the prelude's `rescue` function, which each
+    /// target generates itself.
+    #[must_use]
+    pub fn is_rescue(&self) -> bool {
+        matches!(self, Self::Fn { module, name, .. } if module == &["gleam"] && name == RESCUE)
+    }
+
     pub fn location(&self) -> SrcSpan {
         match self {
             ModuleValueConstructor::Fn { location, .. }
@@ -443,20 +514,21 @@ impl ModuleValueConstructor {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Module {
-    pub name: Vec<String>,
+    pub name: Vec<Name>,
     pub origin: Origin,
     pub package: String,
-    pub types: HashMap<String, TypeConstructor>,
-    pub types_constructors: HashMap<String, Vec<String>>,
-    pub values: HashMap<String, ValueConstructor>,
-    pub accessors: HashMap<String, AccessorsMap>,
+    pub types: HashMap<Name, TypeConstructor>,
+    pub types_constructors: HashMap<Name, Vec<Name>>,
+    pub values: HashMap<Name, ValueConstructor>,
+    pub accessors: HashMap<Name, AccessorsMap>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PatternConstructor {
     Record {
-        name: String,
+        name: Name,
         field_map: Option<FieldMap>,
+        erlang_map: bool,
     },
 }
 
@@ -487,6 +559,39 @@ pub enum TypeVar {
     Generic { id: u64 },
 }
 
+/// The cell a type variable is kept in, shared by every type that refers to
+/// the variable and updated as inference learns what type it stands for. It
+/// is a lock rather than a `RefCell` so that types can be shared between the
+/// threads that compile independent modules in parallel.
+///
+pub struct TypeVarCell(RwLock<TypeVar>);
+
+impl TypeVarCell {
+    pub fn new(type_: TypeVar) -> Self {
+        Self(RwLock::new(type_))
+    }
+
+    pub fn borrow(&self) -> RwLockReadGuard<'_, TypeVar> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn borrow_mut(&self) -> RwLockWriteGuard<'_, TypeVar> {
+        self.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl std::fmt::Debug for TypeVarCell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TypeVarCell").field(&*self.borrow()).finish()
+    }
+}
+
+impl PartialEq for TypeVarCell {
+    fn eq(&self, other: &Self) -> bool {
+        *self.borrow() == *other.borrow()
+    }
+}
+
 impl TypeVar {
     pub fn is_unbound(&self) -> bool {
         matches!(self, Self::Unbound { .. })
@@ -540,14 +645,16 @@ impl TypeVar {
 pub struct TypeConstructor {
     pub public: bool,
     pub origin: SrcSpan,
-    pub module: Vec<String>,
+    pub module: Vec<Name>,
     pub parameters: Vec<Arc<Type>>,
     pub typ: Arc<Type>,
+    pub deprecation: Deprecation,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ValueConstructor {
     pub public: bool,
+    pub deprecation: Deprecation,
     pub variant: ValueConstructorVariant,
     pub type_: Arc<Type>,
 }
@@ -565,12 +672,18 @@ impl ValueConstructor {
             | ValueConstructorVariant::ModuleConstant {
                 location, module, ..
             } => DefinitionLocation {
-                module: Some(module.as_str()),
+                module: Some(module.as_str().into()),
                 span: *location,
             },
 
-            ValueConstructorVariant::ModuleFn { location, .. }
-            | ValueConstructorVariant::LocalVariable { location } => DefinitionLocation {
+            ValueConstructorVariant::ModuleFn {
+                location, module, ..
+            } => DefinitionLocation {
+                module: Some(module.join("/").into()),
+                span: *location,
+            },
+
+            ValueConstructorVariant::LocalVariable { location } => DefinitionLocation {
                 module: None,
                 span: *location,
             },
@@ -581,7 +694,7 @@ impl ValueConstructor {
 #[derive(Debug, Clone, PartialEq)]
 pub struct TypeAliasConstructor {
     pub public: bool,
-    pub module: Vec<String>,
+    pub module: Vec<Name>,
     pub type_: Type,
     pub arity: usize,
 }
@@ -603,15 +716,43 @@ impl ValueConstructor {
 pub fn infer_module(
     target: Target,
     ids: &UniqueIdGenerator,
-    mut module: UntypedModule,
+    module: UntypedModule<'_>,
     origin: Origin,
     package: &str,
     modules: &im::HashMap<String, Module>,
     warnings: &mut Vec<Warning>,
+) -> Result<TypedModule, Error> {
+    infer_module_with_features(
+        target,
+        ids,
+        module,
+        origin,
+        package,
+        modules,
+        HashMap::new(),
+        warnings,
+    )
+}
+
+/// Infer the types of a module of a package with the given feature flags,
+/// which are queried in the module with `@cfg(flag)`.
+#[allow(clippy::too_many_arguments)]
+pub fn infer_module_with_features(
+    target: Target,
+    ids: &UniqueIdGenerator,
+    mut module: UntypedModule<'_>,
+    origin: Origin,
+    package: &str,
+    modules: &im::HashMap<String, Module>,
+    features: HashMap<String, bool>,
+    warnings: &mut Vec<Warning>,
 ) -> Result<TypedModule, Error> {
     let name = module.name.clone();
     let documentation = std::mem::take(&mut module.documentation);
-    let mut environment = Environment::new(ids.clone(), &name, modules, warnings);
+    let warning_suppressions = std::mem::take(&mut module.warning_suppressions);
+    let mut environment = Environment::new(ids.clone(), target, &name, modules, warnings);
+    environment.warning_suppressions = warning_suppressions.clone();
+    environment.features = features;
     validate_module_name(&name)?;
 
     let mut type_names = HashMap::with_capacity(module.statements.len());
@@ -660,6 +801,18 @@ pub fn infer_module(
         statements.push(statement);
     }
 
+    // Now that every function has been inferred check that the functions
+    // called in guards can be used there
+    let guard_functions = guard::GuardFunctions::new(&name, &statements);
+    for (function, location) in std::mem::take(&mut environment.guard_calls) {
+        if guard_functions.get(&function).is_none() {
+            return Err(Error::UnsafeClauseGuardCall {
+                location,
+                name: function.to_string(),
+            });
+        }
+    }
+
     // Generalise functions now that the entire module has been inferred
     let statements = statements
         .into_iter()
@@ -674,9 +827,10 @@ pub fn infer_module(
         .module_types
         .retain(|_, info| info.public && info.module == name);
     environment.module_values.retain(|_, info| info.public);
-    environment
-        .accessors
-        .retain(|_, accessors| accessors.public);
+    environment.accessors.retain(|_, accessors| {
+        accessors.public
+            && matches!(accessors.type_.as_ref(), Type::App { module, .. } if *module == name)
+    });
 
     // Ensure no exported values have private types in their type signature
     for value in environment.module_values.values() {
@@ -700,6 +854,7 @@ pub fn infer_module(
         documentation,
         name: name.clone(),
         statements,
+        warning_suppressions,
         type_info: Module {
             name,
             types,
@@ -712,7 +867,7 @@ pub fn infer_module(
     })
 }
 
-fn validate_module_name(name: &[String]) -> Result<(), Error> {
+fn validate_module_name(name: &[Name]) -> Result<(), Error> {
     if name == ["gleam"] {
         return Err(Error::ReservedModuleName {
             name: name.join("/"),
@@ -775,9 +930,9 @@ fn assert_unique_const_name<'a>(
 }
 
 fn register_values<'a>(
-    s: &'a UntypedStatement,
-    module_name: &[String],
-    hydrators: &mut HashMap<String, Hydrator>,
+    s: &'a UntypedStatement<'_>,
+    module_name: &[Name],
+    hydrators: &mut HashMap<Name, Hydrator>,
     names: &mut HashMap<&'a str, &'a SrcSpan>,
     environment: &mut Environment<'_>,
 ) -> Result<(), Error> {
@@ -788,10 +943,15 @@ fn register_values<'a>(
             location,
             return_annotation,
             public,
+            deprecation,
+            externals,
             ..
         } => {
             assert_unique_value_name(names, name, location)?;
-            let _ = environment.ungeneralised_functions.insert(name.to_string());
+            if !externals.is_empty() {
+                assert_fully_annotated(args, return_annotation, *location)?;
+            }
+            let _ = environment.ungeneralised_functions.insert(name.clone());
 
             // Create the field map so we can reorder labels for usage of this function
             let mut field_map = FieldMap::new(args.len() as u32);
@@ -799,7 +959,7 @@ fn register_values<'a>(
                 if let ArgNames::NamedLabelled { label, .. }
                 | ArgNames::LabelledDiscard { label, .. } = &arg.names
                 {
-                    field_map.insert(label.clone(), i as u32).map_err(|_| {
+                    field_map.insert(label.to_string(), i as u32).map_err(|_| {
                         Error::DuplicateField {
                             label: label.to_string(),
                             location: *location,
@@ -825,17 +985,19 @@ fn register_values<'a>(
             let _ = hydrators.insert(name.clone(), hydrator);
 
             // Insert the function into the environment
+            let (module, name_) = implementation(module_name, name, externals, environment.target);
             environment.insert_variable(
                 name.clone(),
                 ValueConstructorVariant::ModuleFn {
-                    name: name.clone(),
+                    name: name_,
                     field_map,
-                    module: module_name.to_vec(),
+                    module,
                     arity: args.len(),
                     location: *location,
                 },
                 typ,
                 *public,
+                deprecation.clone(),
             );
             if !public {
                 environment.init_usage(name.clone(), EntityKind::PrivateFunction, *location);
@@ -850,6 +1012,7 @@ fn register_values<'a>(
             return_: retrn,
             module,
             fun,
+            deprecation,
             ..
         } => {
             assert_unique_value_name(names, name, location)?;
@@ -864,7 +1027,7 @@ fn register_values<'a>(
                     let t = hydrator.type_from_ast(&arg.annotation, environment)?;
                     args_types.push(t);
                     if let Some(label) = &arg.label {
-                        field_map.insert(label.clone(), i as u32).map_err(|_| {
+                        field_map.insert(label.to_string(), i as u32).map_err(|_| {
                             Error::DuplicateField {
                                 label: label.to_string(),
                                 location: *location,
@@ -883,11 +1046,12 @@ fn register_values<'a>(
                 name,
                 ValueConstructor {
                     public: *public,
+                    deprecation: deprecation.clone(),
                     type_: typ.clone(),
                     variant: ValueConstructorVariant::ModuleFn {
-                        name: fun.clone(),
+                        name: fun.into(),
                         field_map: field_map.clone(),
-                        module: vec![module.clone()],
+                        module: vec![module.as_str().into()],
                         arity: args.len(),
                         location: *location,
                     },
@@ -898,14 +1062,15 @@ fn register_values<'a>(
             environment.insert_variable(
                 name.clone(),
                 ValueConstructorVariant::ModuleFn {
-                    name: fun.clone(),
-                    module: vec![module.clone()],
+                    name: fun.into(),
+                    module: vec![module.as_str().into()],
                     arity: args.len(),
                     field_map,
                     location: *location,
                 },
                 typ,
                 *public,
+                deprecation.clone(),
             );
             if !public {
                 environment.init_usage(name.clone(), EntityKind::PrivateFunction, *location);
@@ -918,8 +1083,14 @@ fn register_values<'a>(
             opaque,
             name,
             constructors,
+            erlang_map,
+            deprecation,
             ..
         } => {
+            if *erlang_map {
+                assert_valid_erlang_map_type(constructors, *location)?;
+            }
+
             let mut hydrator = hydrators
                 .remove(name)
                 .expect("Could not find hydrator for register_values custom type");
@@ -943,6 +1114,7 @@ fn register_values<'a>(
                     // TODO: improve the ownership here so that we can use the
                     // `return_type_constructor` below rather than looking it up twice.
                     type_: typ.clone(),
+                    erlang_map: *erlang_map,
                 };
                 environment.insert_accessors(name, map)
             }
@@ -959,7 +1131,7 @@ fn register_values<'a>(
                     let t = hydrator.type_from_ast(ast, environment)?;
                     args_types.push(t);
                     if let Some(label) = label {
-                        field_map.insert(label.clone(), i as u32).map_err(|_| {
+                        field_map.insert(label.to_string(), i as u32).map_err(|_| {
                             Error::DuplicateField {
                                 label: label.to_string(),
                                 location: *location,
@@ -979,7 +1151,15 @@ fn register_values<'a>(
                     arity: constructor.arguments.len() as u16,
                     field_map: field_map.clone(),
                     location: constructor.location,
-                    module: module_name.join("/"),
+                    module: module_name.join("/").into(),
+                    erlang_map: *erlang_map,
+                };
+                // Constructors of a deprecated type are deprecated too, unless
+                // they were given their own message.
+                let deprecation = if constructor.deprecation.is_deprecated() {
+                    constructor.deprecation.clone()
+                } else {
+                    deprecation.clone()
                 };
 
                 if !opaque {
@@ -987,6 +1167,7 @@ fn register_values<'a>(
                         &constructor.name,
                         ValueConstructor {
                             public: *public,
+                            deprecation: deprecation.clone(),
                             type_: typ.clone(),
                             variant: constructor_info.clone(),
                         },
@@ -1006,6 +1187,7 @@ fn register_values<'a>(
                     constructor_info,
                     typ,
                     *public,
+                    deprecation,
                 );
             }
         }
@@ -1020,9 +1202,62 @@ fn register_values<'a>(
     Ok(())
 }
 
+/// The module and name a function is called with on the given target, which
+/// is its external implementation when it has one for that target.
+fn implementation(
+    module_name: &[Name],
+    name: &Name,
+    externals: &[External],
+    target: Target,
+) -> (Vec<Name>, Name) {
+    match externals.iter().find(|external| external.target == target) {
+        Some(external) => (
+            vec![external.module.as_str().into()],
+            external.function.as_str().into(),
+        ),
+        None => (module_name.to_vec(), name.clone()),
+    }
+}
+
+/// Externally implemented functions must give their full type so that their
+/// implementation on each target has the same signature.
+fn assert_fully_annotated(
+    args: &[UntypedArg],
+    return_annotation: &Option<TypeAst>,
+    location: SrcSpan,
+) -> Result<(), Error> {
+    if let Some(arg) = args.iter().find(|arg| arg.annotation.is_none()) {
+        return Err(Error::ExternalMissingAnnotation {
+            location: arg.location,
+        });
+    }
+    match return_annotation {
+        Some(_) => Ok(()),
+        None => Err(Error::ExternalMissingAnnotation { location }),
+    }
+}
+
+fn external_fn_arg(arg: TypedArg) -> TypedExternalFnArg {
+    let label = match arg.names {
+        ArgNames::NamedLabelled { label, .. } | ArgNames::LabelledDiscard { label, .. } => {
+            Some(label)
+        }
+        ArgNames::Discard { .. } | ArgNames::Named { .. } => None,
+    };
+    ExternalFnArg {
+        location: arg.location,
+        label,
+        annotation: arg
+            .annotation
+            .expect("Annotation checked for external fn argument"),
+        type_: arg.type_,
+        doc: arg.doc,
+    }
+}
+
 fn generalise_statement(
     s: TypedStatement,
-    module_name: &[String],
+    module_name: &[Name],
     environment: &mut Environment<'_>,
 ) -> TypedStatement {
     match s {
@@ -1036,6 +1271,8 @@ fn generalise_statement(
             return_annotation,
             end_position: end_location,
             return_type,
+            deprecation,
+            externals,
         } => {
             // Lookup the inferred function information
             let function = environment
@@ -1052,31 +1289,53 @@ fn generalise_statement(
             };
 
             // Insert the function into the module's interface
+            let (module, name_) =
+                implementation(module_name, &name, &externals, environment.target);
             environment.insert_module_value(
                 &name,
                 ValueConstructor {
                     public,
+                    deprecation: deprecation.clone(),
                     type_: typ,
                     variant: ValueConstructorVariant::ModuleFn {
-                        name: name.clone(),
+                        name: name_,
                         field_map,
-                        module: module_name.to_vec(),
+                        module,
                         arity: args.len(),
                         location,
                     },
                 },
             );
 
-            Statement::Fn {
-                doc,
-                location,
-                name,
-                public,
-                arguments: args,
-                end_position: end_location,
-                return_annotation,
-                return_type,
-                body,
+            // A function implemented externally on this target is generated
+            // in the same way as an external function, and its body is only
+            // used on other targets.
+            match externals.iter().find(|e| e.target == environment.target) {
+                Some(external) => Statement::ExternalFn {
+                    location,
+                    public,
+                    arguments: args.into_iter().map(external_fn_arg).collect(),
+                    name,
+                    return_: return_annotation.expect("Annotation checked for external fn"),
+                    return_type,
+                    module: external.module.clone(),
+                    fun: external.function.clone(),
+                    doc,
+                    deprecation,
+                },
+                None => Statement::Fn {
+                    doc,
+                    deprecation,
+                    location,
+                    name,
+                    public,
+                    arguments: args,
+                    end_position: end_location,
+                    return_annotation,
+                    return_type,
+                    body,
+                    externals,
+                },
             }
         }
 
@@ -1090,9 +1349,9 @@ fn generalise_statement(
 }
 
 fn infer_statement(
-    s: UntypedStatement,
-    module_name: &[String],
-    hydrators: &mut HashMap<String, Hydrator>,
+    s: UntypedStatement<'_>,
+    module_name: &[Name],
+    hydrators: &mut HashMap<Name, Hydrator>,
     environment: &mut Environment<'_>,
 ) -> Result<TypedStatement, Error> {
     match s {
@@ -1105,6 +1364,8 @@ fn infer_statement(
             body,
             return_annotation,
             end_position: end_location,
+            deprecation,
+            externals,
             ..
         } => {
             let preregistered_fn = environment
@@ -1117,6 +1378,7 @@ fn infer_statement(
                 .expect("Preregistered type for fn was not a fn");
 
             // Infer the type using the preregistered args + return types as a starting point
+            environment.variable_trace.clear();
             let (typ, args, body, safe_to_generalise) =
                 environment.in_new_scope(|environment| {
                     let args = args
@@ -1130,6 +1392,7 @@ fn infer_statement(
                         .expect("Could not find hydrator for fn");
                     let (args, body) =
                         expr_typer.infer_fn_with_known_types(args, body, Some(return_type))?;
+                    expr_typer.report_type_holes();
                     let args_types = args.iter().map(|a| a.type_.clone()).collect();
                     let typ = fn_(args_types, body.type_());
                     let safe_to_generalise = !expr_typer.ungeneralised_function_used;
@@ -1143,17 +1406,20 @@ fn infer_statement(
             let typ = if safe_to_generalise {
                 let _ = environment.ungeneralised_functions.remove(&name);
                 let typ = generalise(typ);
+                let (module, name_) =
+                    implementation(module_name, &name, &externals, environment.target);
                 environment.insert_variable(
                     name.clone(),
                     ValueConstructorVariant::ModuleFn {
-                        name: name.clone(),
+                        name: name_,
                         field_map,
-                        module: module_name.to_vec(),
+                        module,
                         arity: args.len(),
                         location,
                     },
                     typ.clone(),
                     public,
+                    deprecation.clone(),
                 );
                 typ
             } else {
@@ -1172,6 +1438,8 @@ fn infer_statement(
                     .return_type()
                     .expect("Could not find return type for fn"),
                 body,
+                deprecation,
+                externals,
             })
         }
 
@@ -1184,6 +1452,7 @@ fn infer_statement(
             return_: retrn,
             module,
             fun,
+            deprecation,
             ..
         } => {
             let preregistered_fn = environment
@@ -1208,6 +1477,7 @@ fn infer_statement(
                 return_: retrn,
                 module,
                 fun,
+                deprecation,
             })
         }
 
@@ -1218,6 +1488,7 @@ fn infer_statement(
             alias,
             parameters: args,
             type_ast: resolved_type,
+            deprecation,
             ..
         } => {
             let typ = environment
@@ -1233,6 +1504,7 @@ fn infer_statement(
                 parameters: args,
                 type_ast: resolved_type,
                 type_: typ,
+                deprecation,
             })
         }
 
@@ -1244,6 +1516,9 @@ fn infer_statement(
             name,
             parameters,
             constructors,
+            erlang_map,
+            derives,
+            deprecation,
             ..
         } => {
             let constructors = constructors
@@ -1254,6 +1529,7 @@ fn infer_statement(
                          name,
                          arguments: args,
                          documentation,
+                         deprecation,
                      }| {
                         let preregistered_fn = environment
                             .get_variable(&name)
@@ -1294,6 +1570,7 @@ fn infer_statement(
                             name,
                             arguments: args,
                             documentation,
+                            deprecation,
                         }
                     },
                 )
@@ -1313,6 +1590,9 @@ fn infer_statement(
                 parameters,
                 constructors,
                 typed_parameters,
+                erlang_map,
+                derives,
+                deprecation,
             })
         }
 
@@ -1322,13 +1602,14 @@ fn infer_statement(
             public,
             name,
             arguments: args,
+            deprecation,
         } => {
             // Check contained types are valid
             let mut hydrator = Hydrator::new();
             for arg in &args {
                 let var = TypeAst::Var {
                     location,
-                    name: arg.to_string(),
+                    name: arg.into(),
                 };
                 let _ = hydrator.type_from_ast(&var, environment)?;
             }
@@ -1338,6 +1619,7 @@ fn infer_statement(
                 public,
                 name,
                 arguments: args,
+                deprecation,
             })
         }
 
@@ -1357,7 +1639,11 @@ fn infer_statement(
                     .ok_or_else(|| Error::UnknownModule {
                         location,
                         name,
-                        imported_modules: environment.imported_modules.keys().cloned().collect(),
+                        imported_modules: environment
+                            .imported_modules
+                            .keys()
+                            .map(Name::to_string)
+                            .collect(),
                     })?;
             // Record any imports that are types only as this information is
             // needed to prevent types being imported in generated JavaScript
@@ -1382,16 +1668,20 @@ fn infer_statement(
             annotation,
             public,
             value,
+            deprecation,
             ..
         } => {
-            let typed_expr = ExprTyper::new(environment).infer_const(&annotation, *value)?;
+            let mut expr_typer = ExprTyper::new(environment);
+            let typed_expr = expr_typer.infer_const(&annotation, *value)?;
+            expr_typer.report_type_holes();
             let type_ = typed_expr.type_();
             let variant = ValueConstructor {
                 public,
+                deprecation: deprecation.clone(),
                 variant: ValueConstructorVariant::ModuleConstant {
                     location,
                     literal: typed_expr.clone(),
-                    module: module_name.join("/"),
+                    module: module_name.join("/").into(),
                 },
                 type_: type_.clone(),
             };
@@ -1401,6 +1691,7 @@ fn infer_statement(
                 variant.variant.clone(),
                 type_.clone(),
                 public,
+                deprecation.clone(),
             );
             environment.insert_module_value(&name, variant);
 
@@ -1416,6 +1707,7 @@ fn infer_statement(
                 public,
                 value: Box::new(typed_expr),
                 type_,
+                deprecation,
             })
         }
     }
@@ -1516,7 +1808,7 @@ fn unify_unbound_type(typ: Arc<Type>, own_id: u64) -> Result<(), UnifyError> {
 
             TypeVar::Unbound { id } => {
                 if id == &own_id {
-                    return Err(UnifyError::RecursiveType);
+                    return Err(UnifyError::RecursiveType { id: own_id });
                 } else {
                     Some(TypeVar::Unbound { id: *id })
                 }
@@ -1632,7 +1924,7 @@ fn generalise(t: Arc<Type>) -> Arc<Type> {
 }
 
 fn make_type_vars(
-    args: &[String],
+    args: &[Name],
     location: &SrcSpan,
     hydrator: &mut Hydrator,
     environment: &mut Environment<'_>,
@@ -1640,17 +1932,34 @@ fn make_type_vars(
     args.iter()
         .map(|arg| TypeAst::Var {
             location: *location,
-            name: arg.to_string(),
+            name: arg.clone(),
         })
         .map(|ast| hydrator.type_from_ast(&ast, environment))
         .try_collect()
 }
 
+/// Records can only be represented as Erlang maps when there is a single
+/// constructor with fields that all have labels to use as the map keys.
+fn assert_valid_erlang_map_type<A>(
+    constructors: &[RecordConstructor<A>],
+    location: SrcSpan,
+) -> Result<(), Error> {
+    match constructors {
+        [constructor]
+            if !constructor.arguments.is_empty()
+                && constructor.arguments.iter().all(|arg| arg.label.is_some()) =>
+        {
+            Ok(())
+        }
+        _ => Err(Error::InvalidErlangMapType { location }),
+    }
+}
+
 fn custom_type_accessors<A>(
     constructors: &[RecordConstructor<A>],
     hydrator: &mut Hydrator,
     environment: &mut Environment<'_>,
-) -> Result<Option<HashMap<String, RecordAccessor>>, Error> {
+) -> Result<Option<HashMap<Name, RecordAccessor>>, Error> {
     let args = get_compatible_record_fields(constructors);
 
     let mut fields = HashMap::with_capacity(args.len());
@@ -1658,10 +1967,10 @@ fn custom_type_accessors<A>(
     for (index, label, ast) in args {
         let typ = hydrator.type_from_ast(ast, environment)?;
         let _ = fields.insert(
-            label.to_string(),
+            label.clone(),
             RecordAccessor {
                 index: index as u64,
-                label: label.to_string(),
+                label: label.clone(),
                 type_: typ,
             },
         );
@@ -1670,10 +1979,11 @@ fn custom_type_accessors<A>(
 }
 
 /// Returns the fields that have the same label and type across all variants of
-/// the given type.
+/// the given type, along with their position in the first variant. The field
+/// may be at a different position in the other variants.
 fn get_compatible_record_fields<A>(
     constructors: &[RecordConstructor<A>],
-) -> Vec<(usize, &str, &TypeAst)> {
+) -> Vec<(usize, &Name, &TypeAst)> {
     let mut compatible = vec![];
 
     let first = match constructors.get(0) {
@@ -1684,24 +1994,23 @@ fn get_compatible_record_fields<A>(
     'next_argument: for (index, first_argument) in first.arguments.iter().enumerate() {
         // Fields without labels do not have accessors
         let label = match first_argument.label.as_ref() {
-            Some(label) => label.as_str(),
+            Some(label) => label,
             None => continue 'next_argument,
         };
 
-        // Check each variant to see if they have an field in the same position
-        // with the same label and the same type
+        // Check each variant to see if they have a field with the same label
+        // and the same type
         for constructor in constructors.iter().skip(1) {
             // The field must exist in all variants
-            let argument = match constructor.arguments.get(index) {
+            let argument = match constructor
+                .arguments
+                .iter()
+                .find(|argument| argument.label == first_argument.label)
+            {
                 Some(argument) => argument,
                 None => continue 'next_argument,
             };
 
-            // The labels must be the same
-            if argument.label != first_argument.label {
-                continue 'next_argument;
-            }
-
             // The types must be the same
             if !argument.ast.is_logically_equal(&first_argument.ast) {
                 continue 'next_argument;
@@ -1719,9 +2028,9 @@ fn get_compatible_record_fields<A>(
 
 /// Iterate over a module, registering any new types created by the module into the typer
 pub fn register_types<'a>(
-    statement: &'a UntypedStatement,
-    module: &[String],
-    hydrators: &mut HashMap<String, Hydrator>,
+    statement: &'a UntypedStatement<'_>,
+    module: &[Name],
+    hydrators: &mut HashMap<Name, Hydrator>,
     names: &mut HashMap<&'a str, &'a SrcSpan>,
     environment: &mut Environment<'_>,
 ) -> Result<(), Error> {
@@ -1731,6 +2040,7 @@ pub fn register_types<'a>(
             public,
             arguments: args,
             location,
+            deprecation,
             ..
         } => {
             assert_unique_type_name(names, name, location)?;
@@ -1754,6 +2064,7 @@ pub fn register_types<'a>(
                     public: *public,
                     parameters,
                     typ,
+                    deprecation: deprecation.clone(),
                 },
             )?;
 
@@ -1769,6 +2080,7 @@ pub fn register_types<'a>(
             parameters,
             location,
             constructors,
+            deprecation,
             ..
         } => {
             assert_unique_type_name(names, name, location)?;
@@ -1782,7 +2094,7 @@ pub fn register_types<'a>(
                 name: name.clone(),
                 args: parameters.clone(),
             });
-            let _ = hydrators.insert(name.to_string(), hydrator);
+            let _ = hydrators.insert(name.clone(), hydrator);
 
             environment.insert_type_constructor(
                 name.clone(),
@@ -1792,6 +2104,7 @@ pub fn register_types<'a>(
                     public: *public,
                     parameters,
                     typ,
+                    deprecation: deprecation.clone(),
                 },
             )?;
 
@@ -1810,6 +2123,7 @@ pub fn register_types<'a>(
             parameters: args,
             alias: name,
             type_ast: resolved_type,
+            deprecation,
             ..
         } => {
             assert_unique_type_name(names, name, location)?;
@@ -1831,6 +2145,7 @@ pub fn register_types<'a>(
                     public: *public,
                     parameters,
                     typ,
+                    deprecation: deprecation.clone(),
                 },
             )?;
 
@@ -1850,7 +2165,7 @@ pub fn register_types<'a>(
 }
 
 pub fn register_import(
-    s: &UntypedStatement,
+    s: &UntypedStatement<'_>,
     environment: &mut Environment<'_>,
 ) -> Result<(), Error> {
     match s {
@@ -1870,15 +2185,18 @@ pub fn register_import(
                     .ok_or_else(|| Error::UnknownModule {
                         location: *location,
                         name,
-                        imported_modules: environment.imported_modules.keys().cloned().collect(),
+                        imported_modules: environment
+                            .imported_modules
+                            .keys()
+                            .map(Name::to_string)
+                            .collect(),
                     })?;
 
             // Determine local alias of imported module
             let module_name = as_name
-                .as_ref()
-                .or_else(|| module.last())
-                .expect("Typer could not identify module name.")
-                .clone();
+                .clone()
+                .or_else(|| module.last().map(Name::from))
+                .expect("Typer could not identify module name.");
 
             // Insert unqualified imports into scope
             for UnqualifiedImport {
@@ -1916,6 +2234,7 @@ pub fn register_import(
                         value.variant.clone(),
                         value.type_.clone(),
                         true,
+                        value.deprecation.clone(),
                     );
                     variant = Some(&value.variant);
                     value_imported = true;
@@ -1937,26 +2256,26 @@ pub fn register_import(
 
                 if value_imported && type_imported {
                     environment.init_usage(
-                        imported_name.to_string(),
+                        imported_name.clone(),
                         EntityKind::ImportedTypeAndConstructor,
                         *location,
                     );
                 } else if type_imported {
-                    let _ = environment.imported_types.insert(imported_name.to_string());
+                    let _ = environment.imported_types.insert(imported_name.clone());
                     environment.init_usage(
-                        imported_name.to_string(),
+                        imported_name.clone(),
                         EntityKind::ImportedType,
                         *location,
                     );
                 } else if value_imported {
                     match variant {
                         Some(&ValueConstructorVariant::Record { .. }) => environment.init_usage(
-                            imported_name.to_string(),
+                            imported_name.clone(),
                             EntityKind::ImportedConstructor,
                             *location,
                         ),
                         _ => environment.init_usage(
-                            imported_name.to_string(),
+                            imported_name.clone(),
                             EntityKind::ImportedValue,
                             *location,
                         ),
@@ -1965,7 +2284,7 @@ pub fn register_import(
                     // Error if no type or value was found with that name
                     return Err(Error::UnknownModuleField {
                         location: *location,
-                        name: name.clone(),
+                        name: name.to_string(),
                         module_name: module.clone(),
                         value_constructors: module_info
                             .values
@@ -1994,7 +2313,7 @@ pub fn register_import(
                 return Err(Error::DuplicateImport {
                     location: *location,
                     previous_location: *previous_location,
-                    name: module_name,
+                    name: module_name.to_string(),
                 });
             }
 
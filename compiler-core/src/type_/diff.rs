@@ -0,0 +1,289 @@
+//! A comparison of the expected and found types of a type mismatch that
+//! only shows the parts of the types that differ.
+//!
+//! Parts of the types that are the same are replaced with `_`, so a mismatch
+//! deep within a large type is reported like so:
+//!
+//! ```text
+//! fn(_, List(#(_, String))) -> _
+//! fn(_, List(#(_, Float))) -> _
+//! ```
+//!
+
+use super::{pretty::Printer, Type, TypeVar};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Types narrower than this are printed in full as there is not enough of
+/// them for the diff to be any easier to read.
+const MINIMUM_WIDTH: usize = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypeDiff {
+    /// The expected type, with the parts that are the same in both types
+    /// replaced by `_`.
+    pub expected: String,
+    /// The found type, with the parts that are the same in both types
+    /// replaced by `_`.
+    pub given: String,
+    /// The parts of the types that differ, in the order they appear.
+    pub differences: Vec<Difference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Difference {
+    pub expected: Part,
+    pub given: Part,
+}
+
+/// A sub-part of one of the types of a diff. The offsets are in characters
+/// into the diff's text for that type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Part {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl TypeDiff {
+    /// The diff between two types, if they are large enough for it to be
+    /// useful and share some structure. Type variables are named with the
+    /// given printer so the names are the same as elsewhere in the error.
+    pub fn new(printer: &mut Printer, expected: &Arc<Type>, given: &Arc<Type>) -> Option<Self> {
+        let width = print(printer, expected)
+            .chars()
+            .count()
+            .max(print(printer, given).chars().count());
+        if width < MINIMUM_WIDTH {
+            return None;
+        }
+
+        let mut diff = Self {
+            expected: String::new(),
+            given: String::new(),
+            differences: vec![],
+        };
+        diff.compare(printer, expected, given);
+
+        // If the types are different at the root there is nothing to elide
+        match diff.differences.first() {
+            Some(difference) if difference.expected.start == 0 => None,
+            _ => Some(diff),
+        }
+    }
+
+    fn compare(&mut self, printer: &mut Printer, expected: &Arc<Type>, given: &Arc<Type>) {
+        let expected_text = print(printer, expected);
+        let given_text = print(printer, given);
+        if expected_text == given_text {
+            self.push("_");
+            return;
+        }
+
+        match (unlink(expected).as_ref(), unlink(given).as_ref()) {
+            (
+                Type::App {
+                    module: expected_module,
+                    name: expected_name,
+                    args: expected_args,
+                    ..
+                },
+                Type::App {
+                    module: given_module,
+                    name: given_name,
+                    args: given_args,
+                    ..
+                },
+            ) if expected_module == given_module
+                && expected_name == given_name
+                && !expected_args.is_empty()
+                && expected_args.len() == given_args.len() =>
+            {
+                // The name is printed the same way for both, qualified if
+                // needed to tell it apart from another type
+                let name = expected_text
+                    .split('(')
+                    .next()
+                    .unwrap_or(expected_name)
+                    .to_string();
+                self.push(&name);
+                self.push("(");
+                self.compare_all(printer, expected_args, given_args);
+                self.push(")");
+            }
+
+            (
+                Type::Fn {
+                    args: expected_args,
+                    retrn: expected_return,
+                },
+                Type::Fn {
+                    args: given_args,
+                    retrn: given_return,
+                },
+            ) if expected_args.len() == given_args.len() => {
+                self.push("fn(");
+                self.compare_all(printer, expected_args, given_args);
+                self.push(") -> ");
+                self.compare(printer, expected_return, given_return);
+            }
+
+            (
+                Type::Tuple {
+                    elems: expected_elems,
+                },
+                Type::Tuple { elems: given_elems },
+            ) if expected_elems.len() == given_elems.len() => {
+                self.push("#(");
+                self.compare_all(printer, expected_elems, given_elems);
+                self.push(")");
+            }
+
+            _ => {
+                let expected = part(&self.expected, expected_text);
+                let given = part(&self.given, given_text);
+                self.expected.push_str(&expected.type_);
+                self.given.push_str(&given.type_);
+                self.differences.push(Difference { expected, given });
+            }
+        }
+    }
+
+    fn compare_all(&mut self, printer: &mut Printer, expected: &[Arc<Type>], given: &[Arc<Type>]) {
+        for (i, (expected, given)) in expected.iter().zip(given).enumerate() {
+            if i > 0 {
+                self.push(", ");
+            }
+            self.compare(printer, expected, given);
+        }
+    }
+
+    // Add text that is the same in both types
+    fn push(&mut self, text: &str) {
+        self.expected.push_str(text);
+        self.given.push_str(text);
+    }
+
+    /// The expected type, followed by a line marking the parts that differ
+    /// with `^`.
+    pub fn underlined_expected(&self, indent: usize) -> String {
+        let parts = self.differences.iter().map(|d| &d.expected);
+        underline(&self.expected, parts, indent)
+    }
+
+    /// The found type, followed by a line marking the parts that differ with
+    /// `^`.
+    pub fn underlined_given(&self, indent: usize) -> String {
+        let parts = self.differences.iter().map(|d| &d.given);
+        underline(&self.given, parts, indent)
+    }
+}
+
+fn print(printer: &mut Printer, type_: &Type) -> String {
+    printer.print(type_).to_pretty_string(isize::MAX)
+}
+
+fn unlink(type_: &Arc<Type>) -> Arc<Type> {
+    if let Type::Var { type_: var } = type_.as_ref() {
+        if let TypeVar::Link { type_ } = &*var.borrow() {
+            return unlink(type_);
+        }
+    }
+    type_.clone()
+}
+
+fn part(text_so_far: &str, type_: String) -> Part {
+    let start = text_so_far.chars().count();
+    let end = start + type_.chars().count();
+    Part { type_, start, end }
+}
+
+fn underline<'a>(text: &str, parts: impl Iterator<Item = &'a Part>, indent: usize) -> String {
+    let padding = " ".repeat(indent);
+    let mut marker = String::new();
+    for part in parts {
+        while marker.chars().count() < part.start {
+            marker.push(' ');
+        }
+        for _ in part.start..part.end {
+            marker.push('^');
+        }
+    }
+    format!("{padding}{text}\n{padding}{marker}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_::{float, fn_, int, list, nil, result, string, tuple};
+
+    fn diff(expected: Arc<Type>, given: Arc<Type>) -> Option<TypeDiff> {
+        TypeDiff::new(&mut Printer::new(), &expected, &given)
+    }
+
+    #[test]
+    fn nested_difference() {
+        let diff = diff(
+            fn_(
+                vec![int(), list(tuple(vec![int(), string()]))],
+                result(int(), nil()),
+            ),
+            fn_(
+                vec![int(), list(tuple(vec![int(), float()]))],
+                result(int(), nil()),
+            ),
+        )
+        .expect("diff");
+        assert_eq!(diff.expected, "fn(_, List(#(_, String))) -> _");
+        assert_eq!(diff.given, "fn(_, List(#(_, Float))) -> _");
+        assert_eq!(
+            diff.differences,
+            vec![Difference {
+                expected: Part {
+                    type_: "String".into(),
+                    start: 16,
+                    end: 22,
+                },
+                given: Part {
+                    type_: "Float".into(),
+                    start: 16,
+                    end: 21,
+                },
+            }]
+        );
+        assert_eq!(
+            diff.underlined_given(2),
+            "  fn(_, List(#(_, Float))) -> _\n                  ^^^^^"
+        );
+    }
+
+    #[test]
+    fn several_differences() {
+        let diff = diff(
+            tuple(vec![int(), string(), list(int()), float(), nil()]),
+            tuple(vec![float(), string(), list(float()), float(), nil()]),
+        )
+        .expect("diff");
+        assert_eq!(diff.expected, "#(Int, _, List(Int), _, _)");
+        assert_eq!(diff.given, "#(Float, _, List(Float), _, _)");
+        assert_eq!(
+            diff.underlined_expected(0),
+            "#(Int, _, List(Int), _, _)\n  ^^^          ^^^"
+        );
+    }
+
+    #[test]
+    fn different_arity_is_a_single_difference() {
+        let diff = diff(
+            fn_(vec![list(int())], result(string(), nil())),
+            fn_(vec![list(int()), int()], result(string(), nil())),
+        );
+        assert_eq!(diff, None);
+    }
+
+    #[test]
+    fn small_types_are_not_diffed() {
+        assert_eq!(diff(list(int()), list(float())), None);
+    }
+}
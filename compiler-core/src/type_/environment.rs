@@ -1,60 +1,84 @@
-use crate::{ast::PIPE_VARIABLE, uid::UniqueIdGenerator};
+use crate::{
+    ast::{WarningSuppression, PIPE_VARIABLE},
+    build::Target,
+    uid::UniqueIdGenerator,
+};
 
 use super::*;
 use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct Environment<'a> {
-    pub current_module: &'a [String],
+    pub current_module: &'a [Name],
     pub ids: UniqueIdGenerator,
     previous_id: u64,
     /// Names of types or values that have been imported an unqualified fashion
     /// from other modules. Used to prevent multiple imports using the same name.
-    pub unqualified_imported_names: HashMap<String, SrcSpan>,
+    pub unqualified_imported_names: HashMap<Name, SrcSpan>,
     pub importable_modules: &'a im::HashMap<String, Module>,
 
     /// Modules that have been imported by the current module, along with the
     /// location of the import statement where they were imported.
-    pub imported_modules: HashMap<String, (SrcSpan, &'a Module)>,
-    pub unused_modules: HashMap<String, SrcSpan>,
-    pub imported_types: HashSet<String>,
+    pub imported_modules: HashMap<Name, (SrcSpan, &'a Module)>,
+    pub unused_modules: HashMap<Name, SrcSpan>,
+    pub imported_types: HashSet<Name>,
 
     /// Values defined in the current function (or the prelude)
-    pub scope: im::HashMap<String, ValueConstructor>,
+    pub scope: im::HashMap<Name, ValueConstructor>,
 
     /// Types defined in the current module (or the prelude)
-    pub module_types: HashMap<String, TypeConstructor>,
+    pub module_types: HashMap<Name, TypeConstructor>,
 
     /// Mapping from types to constructor names in the current module (or the prelude)
-    pub module_types_constructors: HashMap<String, Vec<String>>,
+    pub module_types_constructors: HashMap<Name, Vec<Name>>,
 
     /// Values defined in the current module (or the prelude)
-    pub module_values: HashMap<String, ValueConstructor>,
+    pub module_values: HashMap<Name, ValueConstructor>,
 
     /// Accessors defined in the current module
-    pub accessors: HashMap<String, AccessorsMap>,
+    pub accessors: HashMap<Name, AccessorsMap>,
 
     /// Warnings
     pub warnings: &'a mut Vec<Warning>,
 
+    /// Warnings silenced with `@allow` and `@module_allow` attributes. Any
+    /// warning these suppress is discarded rather than added to `warnings`.
+    pub warning_suppressions: Vec<WarningSuppression>,
+
     /// Functions that have not yet been inferred then generalised.
     /// We use this to determine whether functions that call this one
     /// can safely be generalised.
-    pub ungeneralised_functions: HashSet<String>,
+    pub ungeneralised_functions: HashSet<Name>,
 
     /// entity_usages is a stack of scopes. When an entity is created it is
     /// added to the top scope. When an entity is used we crawl down the scope
     /// stack for an entity with that name and mark it as used.
     /// NOTE: The bool in the tuple here tracks if the entity has been used
-    pub entity_usages: Vec<HashMap<String, (EntityKind, SrcSpan, bool)>>,
+    pub entity_usages: Vec<HashMap<Name, (EntityKind, SrcSpan, bool)>>,
+
+    /// Where local variables have been defined and used in the function
+    /// currently being inferred, along with their types. Used to explain how
+    /// a recursive type came about.
+    pub variable_trace: Vec<(TraceStep, Arc<Type>)>,
+
+    /// The target the module is being compiled for, used to warn about
+    /// values the target cannot represent.
+    pub target: Target,
+
+    /// Functions called in case clause guards, which are checked to be safe
+    /// to call in a guard once every function in the module has been inferred.
+    pub guard_calls: Vec<(Name, SrcSpan)>,
+
+    /// The feature flags of the package, with whether each is enabled.
+    pub features: HashMap<String, bool>,
 }
 
 /// For Keeping track of entity usages and knowing which error to display.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EntityKind {
     PrivateConstant,
-    // String here is the type constructor's type name
-    PrivateTypeConstructor(String),
+    // The name here is the type constructor's type name
+    PrivateTypeConstructor(Name),
     PrivateFunction,
     ImportedConstructor,
     ImportedType,
@@ -62,12 +86,15 @@ pub enum EntityKind {
     ImportedValue,
     PrivateType,
     Variable,
+    // The name here is the argument's label
+    LabelledArgument(Name),
 }
 
 impl<'a> Environment<'a> {
     pub fn new(
         ids: UniqueIdGenerator,
-        current_module: &'a [String],
+        target: Target,
+        current_module: &'a [Name],
         importable_modules: &'a im::HashMap<String, Module>,
         warnings: &'a mut Vec<Warning>,
     ) -> Self {
@@ -90,14 +117,23 @@ impl<'a> Environment<'a> {
             imported_types: HashSet::new(),
             current_module,
             warnings,
+            warning_suppressions: vec![],
             entity_usages: vec![HashMap::new()],
+            variable_trace: vec![],
+            target,
+            guard_calls: vec![],
+            features: HashMap::new(),
         }
     }
 }
 
+/// The most definitions and uses of variables shown when explaining a
+/// recursive type.
+const MAX_TRACE_STEPS: usize = 5;
+
 #[derive(Debug)]
 pub struct ScopeResetData {
-    local_values: im::HashMap<String, ValueConstructor>,
+    local_values: im::HashMap<Name, ValueConstructor>,
 }
 
 impl<'a> Environment<'a> {
@@ -154,30 +190,82 @@ impl<'a> Environment<'a> {
 
     /// Insert a variable in the current scope.
     ///
-    pub fn insert_local_variable(&mut self, name: String, location: SrcSpan, typ: Arc<Type>) {
+    pub fn insert_local_variable(&mut self, name: Name, location: SrcSpan, typ: Arc<Type>) {
+        self.trace_variable(&name, location, TraceStepKind::Defined, typ.clone());
         let _ = self.scope.insert(
             name,
             ValueConstructor {
                 public: false,
+                deprecation: Deprecation::NotDeprecated,
                 variant: ValueConstructorVariant::LocalVariable { location },
                 type_: typ,
             },
         );
     }
 
+    /// Record the definition or use of a local variable so it can be shown
+    /// if the variable's type turns out to be recursive.
+    pub fn trace_variable(
+        &mut self,
+        name: &str,
+        location: SrcSpan,
+        kind: TraceStepKind,
+        typ: Arc<Type>,
+    ) {
+        let step = TraceStep {
+            location,
+            name: name.to_string(),
+            kind,
+        };
+        self.variable_trace.push((step, typ));
+    }
+
+    /// Add the definitions and uses of the variables involved to a recursive
+    /// type error, so the error can show how the type came to be defined in
+    /// terms of itself. Other errors are returned unchanged.
+    pub fn explain_recursive_type(&self, error: Error) -> Error {
+        match error {
+            Error::RecursiveType {
+                location,
+                id,
+                trace,
+            } if trace.is_empty() => {
+                let trace = self
+                    .variable_trace
+                    .iter()
+                    .filter(|(step, typ)| {
+                        step.location.start < location.start && typ.contains_type_var(id)
+                    })
+                    .map(|(step, _)| step.clone())
+                    .sorted_by_key(|step| step.location.start)
+                    .dedup_by(|a, b| a.location == b.location)
+                    .take(MAX_TRACE_STEPS)
+                    .collect();
+                Error::RecursiveType {
+                    location,
+                    id,
+                    trace,
+                }
+            }
+            _ => error,
+        }
+    }
+
     /// Insert a variable in the current scope.
     ///
     pub fn insert_variable(
         &mut self,
-        name: String,
+        name: Name,
         variant: ValueConstructorVariant,
         typ: Arc<Type>,
         public: bool,
+        deprecation: Deprecation,
     ) {
         let _ = self.scope.insert(
             name,
             ValueConstructor {
                 public,
+                deprecation,
                 variant,
                 type_: typ,
             },
@@ -188,7 +276,7 @@ impl<'a> Environment<'a> {
     /// Errors if the module already has a value with that name.
     ///
     pub fn insert_module_value(&mut self, name: &str, value: ValueConstructor) {
-        let _ = self.module_values.insert(name.to_string(), value);
+        let _ = self.module_values.insert(name.into(), value);
     }
 
     /// Lookup a variable in the current scope.
@@ -214,10 +302,10 @@ impl<'a> Environment<'a> {
     ///
     pub fn insert_type_constructor(
         &mut self,
-        type_name: String,
+        type_name: Name,
         info: TypeConstructor,
     ) -> Result<(), Error> {
-        let name = type_name.clone();
+        let name = type_name.to_string();
         let location = info.origin;
         match self.module_types.insert(type_name, info) {
             None => Ok(()),
@@ -232,7 +320,7 @@ impl<'a> Environment<'a> {
 
     /// Map a type to constructors in the current scope.
     ///
-    pub fn insert_type_to_constructors(&mut self, type_name: String, constructors: Vec<String>) {
+    pub fn insert_type_to_constructors(&mut self, type_name: Name, constructors: Vec<Name>) {
         let _ = self
             .module_types_constructors
             .insert(type_name, constructors);
@@ -242,7 +330,7 @@ impl<'a> Environment<'a> {
     ///
     pub fn get_type_constructor(
         &mut self,
-        module_alias: &Option<String>,
+        module_alias: &Option<Name>,
         name: &str,
     ) -> Result<&TypeConstructor, UnknownTypeConstructorError> {
         match module_alias {
@@ -284,7 +372,7 @@ impl<'a> Environment<'a> {
         &mut self,
         full_module_name: &Option<String>,
         name: &str,
-    ) -> Result<&Vec<String>, UnknownTypeConstructorError> {
+    ) -> Result<&Vec<Name>, UnknownTypeConstructorError> {
         match full_module_name {
             None => self.module_types_constructors.get(name).ok_or_else(|| {
                 UnknownTypeConstructorError::Type {
@@ -304,7 +392,7 @@ impl<'a> Environment<'a> {
                             .collect(),
                     }
                 })?;
-                let _ = self.unused_modules.remove(m);
+                let _ = self.unused_modules.remove(m.as_str());
                 module.types_constructors.get(name).ok_or_else(|| {
                     UnknownTypeConstructorError::ModuleType {
                         name: name.to_string(),
@@ -320,7 +408,7 @@ impl<'a> Environment<'a> {
     ///
     pub fn get_value_constructor(
         &mut self,
-        module: Option<&String>,
+        module: Option<&Name>,
         name: &str,
     ) -> Result<&ValueConstructor, UnknownValueConstructorError> {
         match module {
@@ -357,7 +445,7 @@ impl<'a> Environment<'a> {
     }
 
     pub fn insert_accessors(&mut self, type_name: &str, accessors: AccessorsMap) {
-        let _ = self.accessors.insert(type_name.to_string(), accessors);
+        let _ = self.accessors.insert(type_name.into(), accessors);
     }
 
     /// Instantiate converts generic variables into unbound ones.
@@ -429,14 +517,18 @@ impl<'a> Environment<'a> {
     }
 
     /// Inserts an entity at the current scope for usage tracking.
-    pub fn init_usage(&mut self, name: String, kind: EntityKind, location: SrcSpan) {
+    pub fn init_usage(&mut self, name: Name, kind: EntityKind, location: SrcSpan) {
         use EntityKind::*;
 
+        if let Variable | LabelledArgument(_) = kind {
+            self.warn_if_shadowing(&name, location);
+        }
+
         match self
             .entity_usages
             .last_mut()
             .expect("Attempted to access non-existant entity usages scope")
-            .insert(name.to_string(), (kind, location, false))
+            .insert(name.clone(), (kind, location, false))
         {
             // Private types can be shadowed by a constructor with the same name
             //
@@ -456,9 +548,91 @@ impl<'a> Environment<'a> {
         }
     }
 
+    /// Emit a warning if a variable hides a variable of the same name from an
+    /// enclosing scope that has not been used yet, as any later use of the
+    /// name in this scope was likely intended to refer to the outer variable.
+    /// Variables that have been used, such as the subject of a case
+    /// expression being rebound in its clauses, can be shadowed freely.
+    fn warn_if_shadowing(&mut self, name: &str, location: SrcSpan) {
+        let shadowed = self
+            .entity_usages
+            .iter()
+            .rev()
+            .skip(1)
+            .find_map(|scope| scope.get(name));
+        if let Some((EntityKind::Variable | EntityKind::LabelledArgument(_), shadowed, false)) =
+            shadowed
+        {
+            let warning = Warning::ShadowedVariable {
+                name: name.to_string(),
+                location,
+                shadowed: *shadowed,
+            };
+            self.warn(warning);
+        }
+    }
+
+    /// Emit a warning, unless it has been suppressed with an `@allow` or
+    /// `@module_allow` attribute.
+    pub fn warn(&mut self, warning: Warning) {
+        let suppressed = self
+            .warning_suppressions
+            .iter()
+            .any(|suppression| suppression.suppresses(&warning));
+        if !suppressed {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Whether the target being compiled for can represent the given Int
+    /// exactly.
+    pub fn int_in_range(&self, value: i128) -> bool {
+        match self.target.int_range() {
+            Some((min, max)) => (min..=max).contains(&value),
+            None => true,
+        }
+    }
+
+    /// Emit a warning if an Int known at compile time cannot be represented
+    /// on the target being compiled for. A value of `None` is too large to be
+    /// evaluated at all.
+    pub fn check_int_range(&mut self, value: Option<i128>, location: SrcSpan) {
+        let in_range = match value {
+            Some(value) => self.int_in_range(value),
+            None => self.target.int_range().is_none(),
+        };
+        if !in_range {
+            self.warn(Warning::IntOutOfRange {
+                location,
+                target: self.target,
+            });
+        }
+    }
+
+    /// Emit a warning if a value or type that has been used is deprecated.
+    /// Some annotations are checked more than once so a use is only reported
+    /// the first time.
+    pub fn warn_if_deprecated(
+        &mut self,
+        deprecation: &Deprecation,
+        location: SrcSpan,
+        layer: Layer,
+    ) {
+        if let Deprecation::Deprecated { message } = deprecation {
+            let warning = Warning::DeprecatedItem {
+                location,
+                message: message.clone(),
+                layer,
+            };
+            if !self.warnings.contains(&warning) {
+                self.warn(warning);
+            }
+        }
+    }
+
     /// Increments an entity's usage in the current or nearest enclosing scope
     pub fn increment_usage(&mut self, name: &str) {
-        let mut name = name.to_string();
+        let mut name = Name::from(name);
 
         while let Some((kind, _, used)) = self
             .entity_usages
@@ -487,45 +661,62 @@ impl<'a> Environment<'a> {
         self.handle_unused(unused);
 
         for (name, location) in self.unused_modules.clone().into_iter() {
-            self.warnings
-                .push(Warning::UnusedImportedModule { name, location });
+            self.warn(Warning::UnusedImportedModule {
+                name: name.to_string(),
+                location,
+            });
         }
     }
 
-    fn handle_unused(&mut self, unused: HashMap<String, (EntityKind, SrcSpan, bool)>) {
+    fn handle_unused(&mut self, unused: HashMap<Name, (EntityKind, SrcSpan, bool)>) {
         for (name, (kind, location, _)) in unused.into_iter().filter(|(_, (_, _, used))| !used) {
             let warning = match kind {
                 EntityKind::ImportedType | EntityKind::ImportedTypeAndConstructor => {
                     Warning::UnusedType {
-                        name,
+                        name: name.to_string(),
                         imported: true,
                         location,
                     }
                 }
                 EntityKind::ImportedConstructor => Warning::UnusedConstructor {
-                    name,
+                    name: name.to_string(),
                     imported: true,
                     location,
                 },
-                EntityKind::PrivateConstant => {
-                    Warning::UnusedPrivateModuleConstant { name, location }
-                }
+                EntityKind::PrivateConstant => Warning::UnusedPrivateModuleConstant {
+                    name: name.to_string(),
+                    location,
+                },
                 EntityKind::PrivateTypeConstructor(_) => Warning::UnusedConstructor {
-                    name,
+                    name: name.to_string(),
                     imported: false,
                     location,
                 },
-                EntityKind::PrivateFunction => Warning::UnusedPrivateFunction { name, location },
+                EntityKind::PrivateFunction => Warning::UnusedPrivateFunction {
+                    name: name.to_string(),
+                    location,
+                },
                 EntityKind::PrivateType => Warning::UnusedType {
-                    name,
+                    name: name.to_string(),
                     imported: false,
                     location,
                 },
-                EntityKind::ImportedValue => Warning::UnusedImportedValue { name, location },
-                EntityKind::Variable => Warning::UnusedVariable { name, location },
+                EntityKind::ImportedValue => Warning::UnusedImportedValue {
+                    name: name.to_string(),
+                    location,
+                },
+                EntityKind::Variable => Warning::UnusedVariable {
+                    name: name.to_string(),
+                    location,
+                },
+                EntityKind::LabelledArgument(label) => Warning::UnusedLabelledArgument {
+                    label: label.to_string(),
+                    name: name.to_string(),
+                    location,
+                },
             };
 
-            self.warnings.push(warning);
+            self.warn(warning);
         }
     }
 
@@ -537,6 +728,77 @@ impl<'a> Environment<'a> {
             .collect()
     }
 
+    /// A pattern that matches the record constructor of the given type, with
+    /// the name of a field's label or `value` for the variable of each field.
+    /// The constructor is qualified if it is not imported unqualified.
+    pub fn constructor_pattern(&self, type_: &Type, constructor: &str) -> String {
+        let module = match type_ {
+            Type::App { module, .. } => module.join("/"),
+            Type::Fn { .. } | Type::Var { .. } | Type::Tuple { .. } => String::new(),
+        };
+        let is_constructor = |value: &&ValueConstructor| match &value.variant {
+            ValueConstructorVariant::Record {
+                name, module: m, ..
+            } => name == constructor && (module.is_empty() || *m == module),
+            _ => false,
+        };
+        let unqualified = self.scope.get(constructor).filter(is_constructor);
+        let qualified = || {
+            self.importable_modules
+                .get(&module)
+                .and_then(|imported| imported.values.get(constructor))
+        };
+        let (arity, field_map) = match unqualified.or_else(qualified).map(|value| &value.variant) {
+            Some(ValueConstructorVariant::Record {
+                arity, field_map, ..
+            }) => (*arity as u32, field_map.as_ref()),
+            _ => (0, None),
+        };
+
+        let qualifier = match unqualified {
+            Some(_) => String::new(),
+            None => {
+                let alias = self
+                    .imported_modules
+                    .iter()
+                    .find(|(_, (_, imported))| imported.name.join("/") == module)
+                    .map(|(alias, _)| alias.as_str())
+                    .or_else(|| module.rsplit('/').next())
+                    .unwrap_or_default();
+                format!("{}.", alias)
+            }
+        };
+        if arity == 0 {
+            return format!("{}{}", qualifier, constructor);
+        }
+
+        let names = (0..arity)
+            .map(|index| {
+                field_map
+                    .and_then(|field_map| {
+                        field_map
+                            .fields
+                            .iter()
+                            .find(|(_, position)| **position == index)
+                    })
+                    .map_or("value", |(label, _)| label.as_str())
+            })
+            .collect_vec();
+        // Variables that would share a name are numbered
+        let variables = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                if names.iter().filter(|other| *other == name).count() > 1 {
+                    format!("{}_{}", name, index + 1)
+                } else {
+                    name.to_string()
+                }
+            })
+            .join(", ");
+        format!("{}{}({})", qualifier, constructor, variables)
+    }
+
     /// Checks that the given patterns are exhaustive for given type.
     /// Currently only performs exhaustiveness checking for custom types,
     /// only at the top level (without recursing into constructor arguments).
@@ -558,7 +820,7 @@ impl<'a> Environment<'a> {
                 };
 
                 if let Ok(constructors) = self.get_constructors_for_type(&m, type_name) {
-                    let mut unmatched_constructors: HashSet<String> =
+                    let mut unmatched_constructors: HashSet<Name> =
                         constructors.iter().cloned().collect();
 
                     for p in &patterns {
@@ -588,7 +850,11 @@ impl<'a> Environment<'a> {
                     }
 
                     if !unmatched_constructors.is_empty() {
-                        return Err(unmatched_constructors.into_iter().sorted().collect());
+                        return Err(unmatched_constructors
+                            .into_iter()
+                            .sorted()
+                            .map(String::from)
+                            .collect());
                     }
                 }
                 Ok(())
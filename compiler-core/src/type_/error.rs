@@ -1,5 +1,6 @@
 use crate::{
-    ast::{BinOp, SrcSpan, TodoKind},
+    ast::{BinOp, Layer, Name, SrcSpan, TodoKind, CAPTURE_VARIABLE},
+    build::Target,
     type_::Type,
 };
 
@@ -8,9 +9,10 @@ use std::{path::PathBuf, sync::Arc};
 #[cfg(test)]
 use pretty_assertions::assert_eq;
 
-use super::FieldAccessUsage;
+use super::{diff::TypeDiff, pretty::Printer, FieldAccessUsage};
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum Error {
     BitStringSegmentError {
         error: crate::bit_string::ErrorType,
@@ -43,21 +45,21 @@ pub enum Error {
     UnknownModuleType {
         location: SrcSpan,
         name: String,
-        module_name: Vec<String>,
+        module_name: Vec<Name>,
         type_constructors: Vec<String>,
     },
 
     UnknownModuleValue {
         location: SrcSpan,
         name: String,
-        module_name: Vec<String>,
+        module_name: Vec<Name>,
         value_constructors: Vec<String>,
     },
 
     UnknownModuleField {
         location: SrcSpan,
         name: String,
-        module_name: Vec<String>,
+        module_name: Vec<Name>,
         value_constructors: Vec<String>,
         type_constructors: Vec<String>,
     },
@@ -86,11 +88,21 @@ pub enum Error {
         location: SrcSpan,
     },
 
+    /// A float literal too large to be represented as a 64-bit float, such as
+    /// `1.0e400`.
+    FloatOutOfRange {
+        location: SrcSpan,
+    },
+
     UnnecessarySpreadOperator {
         location: SrcSpan,
         arity: usize,
     },
 
+    ConstantOutOfRange {
+        location: SrcSpan,
+    },
+
     IncorrectTypeArity {
         location: SrcSpan,
         name: String,
@@ -108,6 +120,11 @@ pub enum Error {
 
     RecursiveType {
         location: SrcSpan,
+        /// The type variable that would have to contain itself.
+        id: u64,
+        /// The definitions and uses of the variables with that type leading
+        /// up to the error.
+        trace: Vec<TraceStep>,
     },
 
     DuplicateName {
@@ -144,6 +161,23 @@ pub enum Error {
         label: String,
     },
 
+    /// A function with an `@external` attribute was not given a type
+    /// annotation for one of its arguments or its return value.
+    ExternalMissingAnnotation {
+        location: SrcSpan,
+    },
+
+    /// A range pattern such as `10..1` whose start is after its end.
+    EmptyIntRange {
+        location: SrcSpan,
+    },
+
+    /// Range patterns cannot be used in `let`, `assert` or `try` as they
+    /// compile to guards, which assignments do not have.
+    IntRangeOutsideCase {
+        location: SrcSpan,
+    },
+
     PrivateTypeLeak {
         location: SrcSpan,
         leaked: Type,
@@ -169,6 +203,17 @@ pub enum Error {
         name: String,
     },
 
+    UnsafeClauseGuardCall {
+        location: SrcSpan,
+        name: String,
+    },
+
+    UnknownFeatureFlag {
+        location: SrcSpan,
+        name: String,
+        flags: Vec<String>,
+    },
+
     ExtraVarInAlternativePattern {
         location: SrcSpan,
         name: String,
@@ -207,6 +252,14 @@ pub enum Error {
         location: SrcSpan,
     },
 
+    InvalidErlangMapType {
+        location: SrcSpan,
+    },
+
+    ErlangMapRecordInConstant {
+        location: SrcSpan,
+    },
+
     UnexpectedTypeHole {
         location: SrcSpan,
     },
@@ -223,6 +276,9 @@ pub enum Error {
     NotExhaustivePatternMatch {
         location: SrcSpan,
         unmatched: Vec<String>,
+        /// A pattern for each of the unmatched constructors, written as the
+        /// module refers to them and with a variable for each field.
+        patterns: Vec<String>,
     },
 }
 
@@ -238,10 +294,30 @@ pub enum Warning {
         location: SrcSpan,
     },
 
+    Echo {
+        location: SrcSpan,
+    },
+
+    InferredTypeHole {
+        location: SrcSpan,
+        typ: Arc<Type>,
+    },
+
     UnusedLiteral {
         location: SrcSpan,
     },
 
+    UnreachableCaseClause {
+        location: SrcSpan,
+        covered_by: SrcSpan,
+    },
+
+    DeprecatedItem {
+        location: SrcSpan,
+        message: String,
+        layer: Layer,
+    },
+
     NoFieldsRecordUpdate {
         location: SrcSpan,
     },
@@ -286,6 +362,162 @@ pub enum Warning {
         location: SrcSpan,
         name: String,
     },
+
+    UnusedLabelledArgument {
+        location: SrcSpan,
+        label: String,
+        name: String,
+    },
+
+    ShadowedVariable {
+        location: SrcSpan,
+        name: String,
+        shadowed: SrcSpan,
+    },
+
+    /// An integer literal, or an operation on integer literals, whose value
+    /// cannot be represented on the target being compiled for.
+    IntOutOfRange {
+        location: SrcSpan,
+        target: Target,
+    },
+}
+
+impl Warning {
+    pub fn kind(&self) -> WarningKind {
+        match self {
+            Warning::Todo { .. } => WarningKind::Todo,
+            Warning::ImplicitlyDiscardedResult { .. } => WarningKind::ImplicitlyDiscardedResult,
+            Warning::Echo { .. } => WarningKind::Echo,
+            Warning::InferredTypeHole { .. } => WarningKind::InferredTypeHole,
+            Warning::UnusedLiteral { .. } => WarningKind::UnusedLiteral,
+            Warning::UnreachableCaseClause { .. } => WarningKind::UnreachableCaseClause,
+            Warning::DeprecatedItem { .. } => WarningKind::Deprecated,
+            Warning::NoFieldsRecordUpdate { .. } => WarningKind::NoFieldsRecordUpdate,
+            Warning::AllFieldsRecordUpdate { .. } => WarningKind::AllFieldsRecordUpdate,
+            Warning::UnusedType { .. } => WarningKind::UnusedType,
+            Warning::UnusedConstructor { .. } => WarningKind::UnusedConstructor,
+            Warning::UnusedImportedValue { .. } => WarningKind::UnusedImportedValue,
+            Warning::UnusedImportedModule { .. } => WarningKind::UnusedImportedModule,
+            Warning::UnusedPrivateModuleConstant { .. } => WarningKind::UnusedPrivateConstant,
+            Warning::UnusedPrivateFunction { .. } => WarningKind::UnusedPrivateFunction,
+            Warning::UnusedVariable { .. } => WarningKind::UnusedVariable,
+            Warning::UnusedLabelledArgument { .. } => WarningKind::UnusedLabelledArgument,
+            Warning::ShadowedVariable { .. } => WarningKind::ShadowedVariable,
+            Warning::IntOutOfRange { .. } => WarningKind::IntOutOfRange,
+        }
+    }
+
+    pub fn location(&self) -> SrcSpan {
+        match self {
+            Warning::Todo { location, .. }
+            | Warning::ImplicitlyDiscardedResult { location }
+            | Warning::Echo { location }
+            | Warning::InferredTypeHole { location, .. }
+            | Warning::UnusedLiteral { location }
+            | Warning::UnreachableCaseClause { location, .. }
+            | Warning::DeprecatedItem { location, .. }
+            | Warning::NoFieldsRecordUpdate { location }
+            | Warning::AllFieldsRecordUpdate { location }
+            | Warning::UnusedType { location, .. }
+            | Warning::UnusedConstructor { location, .. }
+            | Warning::UnusedImportedValue { location, .. }
+            | Warning::UnusedImportedModule { location, .. }
+            | Warning::UnusedPrivateModuleConstant { location, .. }
+            | Warning::UnusedPrivateFunction { location, .. }
+            | Warning::UnusedVariable { location, .. }
+            | Warning::UnusedLabelledArgument { location, .. }
+            | Warning::ShadowedVariable { location, .. }
+            | Warning::IntOutOfRange { location, .. } => *location,
+        }
+    }
+}
+
+/// The kinds of warning that can be silenced with the `@allow` and
+/// `@module_allow` attributes, such as `@allow(unused_variable)`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    Todo,
+    ImplicitlyDiscardedResult,
+    InferredTypeHole,
+    UnusedLiteral,
+    UnreachableCaseClause,
+    Deprecated,
+    NoFieldsRecordUpdate,
+    AllFieldsRecordUpdate,
+    UnusedType,
+    UnusedConstructor,
+    UnusedImportedValue,
+    UnusedImportedModule,
+    UnusedPrivateConstant,
+    UnusedPrivateFunction,
+    UnusedVariable,
+    UnusedLabelledArgument,
+    ShadowedVariable,
+    IntOutOfRange,
+    Echo,
+}
+
+impl WarningKind {
+    pub const ALL: [WarningKind; 19] = [
+        WarningKind::Todo,
+        WarningKind::ImplicitlyDiscardedResult,
+        WarningKind::InferredTypeHole,
+        WarningKind::UnusedLiteral,
+        WarningKind::UnreachableCaseClause,
+        WarningKind::Deprecated,
+        WarningKind::NoFieldsRecordUpdate,
+        WarningKind::AllFieldsRecordUpdate,
+        WarningKind::UnusedType,
+        WarningKind::UnusedConstructor,
+        WarningKind::UnusedImportedValue,
+        WarningKind::UnusedImportedModule,
+        WarningKind::UnusedPrivateConstant,
+        WarningKind::UnusedPrivateFunction,
+        WarningKind::UnusedVariable,
+        WarningKind::UnusedLabelledArgument,
+        WarningKind::ShadowedVariable,
+        WarningKind::IntOutOfRange,
+        WarningKind::Echo,
+    ];
+
+    /// The name used for this kind of warning in attributes.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarningKind::Todo => "todo",
+            WarningKind::ImplicitlyDiscardedResult => "implicitly_discarded_result",
+            WarningKind::InferredTypeHole => "inferred_type_hole",
+            WarningKind::UnusedLiteral => "unused_literal",
+            WarningKind::UnreachableCaseClause => "unreachable_case_clause",
+            WarningKind::Deprecated => "deprecated",
+            WarningKind::NoFieldsRecordUpdate => "no_fields_record_update",
+            WarningKind::AllFieldsRecordUpdate => "all_fields_record_update",
+            WarningKind::UnusedType => "unused_type",
+            WarningKind::UnusedConstructor => "unused_constructor",
+            WarningKind::UnusedImportedValue => "unused_imported_value",
+            WarningKind::UnusedImportedModule => "unused_imported_module",
+            WarningKind::UnusedPrivateConstant => "unused_private_constant",
+            WarningKind::UnusedPrivateFunction => "unused_private_function",
+            WarningKind::UnusedVariable => "unused_variable",
+            WarningKind::UnusedLabelledArgument => "unused_labelled_argument",
+            WarningKind::ShadowedVariable => "shadowed_variable",
+            WarningKind::IntOutOfRange => "int_out_of_range",
+            WarningKind::Echo => "echo",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+impl std::str::FromStr for WarningKind {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::from_name(name).ok_or_else(|| format!("unknown kind of warning `{}`", name))
+    }
 }
 
 impl Error {
@@ -313,6 +545,109 @@ impl Error {
             _ => self,
         }
     }
+
+    /// The name most similar to the one that could not be found, if this is
+    /// an error for an unknown name and there are any names to pick from.
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            Error::UnknownVariable {
+                name,
+                variables: options,
+                ..
+            }
+            | Error::UnknownType {
+                name,
+                types: options,
+                ..
+            }
+            | Error::UnknownModule {
+                name,
+                imported_modules: options,
+                ..
+            }
+            | Error::UnknownModuleType {
+                name,
+                type_constructors: options,
+                ..
+            }
+            | Error::UnknownModuleValue {
+                name,
+                value_constructors: options,
+                ..
+            }
+            | Error::UnknownRecordField {
+                label: name,
+                fields: options,
+                ..
+            } => closest_name(name, options),
+
+            Error::UnknownModuleField {
+                name,
+                value_constructors,
+                type_constructors,
+                ..
+            } => closest_name(name, type_constructors.iter().chain(value_constructors)),
+
+            _ => None,
+        }
+    }
+
+    /// The parts of the expected and found types that differ, if this is a
+    /// type mismatch between large types that share some structure.
+    pub fn type_diff(&self) -> Option<TypeDiff> {
+        match self {
+            Error::CouldNotUnify {
+                expected,
+                given,
+                situation,
+                rigid_type_names,
+                ..
+            } if !matches!(
+                situation,
+                Some(UnifyErrorSituation::Operator(_) | UnifyErrorSituation::PipeTypeMismatch)
+            ) =>
+            {
+                let mut printer = Printer::new();
+                printer.with_names(rigid_type_names.clone());
+                TypeDiff::new(&mut printer, expected, given)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Finds the option closest to the given name by edit distance, preferring
+/// the alphabetically first of any that are equally close.
+pub fn closest_name<'a>(
+    name: &str,
+    options: impl IntoIterator<Item = &'a String>,
+) -> Option<&'a str> {
+    options
+        .into_iter()
+        .map(String::as_str)
+        .filter(|&option| option != CAPTURE_VARIABLE)
+        .map(|option| (strsim::levenshtein(option, name), option))
+        .min()
+        .map(|(_, option)| option)
+}
+
+#[test]
+fn closest_name_test() {
+    let options = vec!["length".to_string(), "map".to_string(), "max".to_string()];
+    assert_eq!(Some("length"), closest_name("lenght", &options));
+    assert_eq!(Some("map"), closest_name("mab", &options));
+    assert_eq!(None, closest_name("length", &[]));
+    assert_eq!(None, closest_name("x", &[CAPTURE_VARIABLE.to_string()]));
+}
+
+#[test]
+fn unknown_variable_suggestion_test() {
+    let error = Error::UnknownVariable {
+        location: SrcSpan { start: 0, end: 5 },
+        name: "lenth".into(),
+        variables: vec!["list".into(), "length".into()],
+    };
+    assert_eq!(Some("length"), error.suggestion());
 }
 
 impl Warning {
@@ -339,7 +674,7 @@ pub enum UnknownValueConstructorError {
 
     ModuleValue {
         name: String,
-        module_name: Vec<String>,
+        module_name: Vec<Name>,
         value_constructors: Vec<String>,
     },
 }
@@ -391,7 +726,7 @@ pub enum UnknownTypeConstructorError {
 
     ModuleType {
         name: String,
-        module_name: Vec<String>,
+        module_name: Vec<Name>,
         type_constructors: Vec<String>,
     },
 }
@@ -528,6 +863,21 @@ fn unify_enclosed_type_test() {
     );
 }
 
+/// A step in the inference of a recursive type, used to explain how the
+/// type came to be defined in terms of itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub location: SrcSpan,
+    pub name: String,
+    pub kind: TraceStepKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceStepKind {
+    Defined,
+    Used,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UnifyErrorSituation {
     /// Clauses in a case expression were found to return different types.
@@ -599,7 +949,9 @@ pub enum UnifyError {
         name: String,
     },
 
-    RecursiveType,
+    RecursiveType {
+        id: u64,
+    },
 }
 
 impl UnifyError {
@@ -660,7 +1012,11 @@ impl UnifyError {
 
             Self::DuplicateVarInPattern { name } => Error::DuplicateVarInPattern { location, name },
 
-            Self::RecursiveType => Error::RecursiveType { location },
+            Self::RecursiveType { id } => Error::RecursiveType {
+                location,
+                id,
+                trace: vec![],
+            },
         }
     }
 }
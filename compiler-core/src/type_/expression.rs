@@ -3,12 +3,12 @@ use vec1::Vec1;
 
 use super::{pipe::PipeTyper, *};
 use crate::ast::{
-    Arg, AssignName, AssignmentKind, BinOp, BitStringSegment, BitStringSegmentOption, CallArg,
-    Clause, ClauseGuard, Constant, HasLocation, RecordUpdateSpread, SrcSpan, TodoKind, TypeAst,
-    TypedArg, TypedClause, TypedClauseGuard, TypedConstant, TypedExpr, TypedMultiPattern,
-    UntypedArg, UntypedClause, UntypedClauseGuard, UntypedConstant,
+    ArenaBox, Arg, AssignName, AssignmentKind, BinOp, BitStringSegment, BitStringSegmentOption,
+    CallArg, Clause, ClauseGuard, Constant, HasLocation, RecordUpdateSpread, SrcSpan, TodoKind,
+    TypeAst, TypedArg, TypedClause, TypedClauseGuard, TypedConstant, TypedExpr, TypedMultiPattern,
+    TypedPattern, UntypedArg, UntypedClause, UntypedClauseGuard, UntypedConstant,
     UntypedConstantBitStringSegment, UntypedExpr, UntypedExprBitStringSegment, UntypedMultiPattern,
-    UntypedPattern, Use,
+    UntypedPattern, Use, RECORD_VARIABLE,
 };
 
 use im::hashmap;
@@ -51,6 +51,15 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         result
     }
 
+    /// Emits a warning stating the inferred type of each type hole used in
+    /// annotations so far.
+    pub fn report_type_holes(&mut self) {
+        for (location, typ) in self.hydrator.take_hole_types() {
+            self.environment
+                .warn(Warning::InferredTypeHole { location, typ });
+        }
+    }
+
     pub fn type_from_ast(&mut self, ast: &TypeAst) -> Result<Arc<Type>, Error> {
         self.hydrator.type_from_ast(ast, self.environment)
     }
@@ -66,7 +75,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     /// Crawl the AST, annotating each node with the inferred type or
     /// returning an error.
     ///
-    pub fn infer(&mut self, expr: UntypedExpr) -> Result<TypedExpr, Error> {
+    pub fn infer(&mut self, expr: UntypedExpr<'_>) -> Result<TypedExpr, Error> {
         match expr {
             UntypedExpr::Todo {
                 location,
@@ -75,7 +84,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 ..
             } => Ok(self.infer_todo(location, kind, label)),
 
-            UntypedExpr::Var { location, name, .. } => self.infer_var(name, location),
+            UntypedExpr::Var { location, name, .. } => self.infer_var(name.to_string(), location),
 
             UntypedExpr::Int {
                 location, value, ..
@@ -92,7 +101,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
             UntypedExpr::Float {
                 location, value, ..
-            } => Ok(self.infer_float(value, location)),
+            } => self.infer_float(value, location),
 
             UntypedExpr::String {
                 location, value, ..
@@ -107,7 +116,14 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 body,
                 return_annotation,
                 ..
-            } => self.infer_fn(args, &[], *body, is_capture, return_annotation, location),
+            } => self.infer_fn(
+                args,
+                &[],
+                ArenaBox::into_inner(body),
+                is_capture,
+                return_annotation,
+                location,
+            ),
 
             UntypedExpr::Assignment {
                 location,
@@ -115,8 +131,15 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 value,
                 kind,
                 annotation,
-                ..
-            } => self.infer_assignment(pattern, *value, kind, &annotation, location),
+                message,
+            } => self.infer_assignment(
+                pattern,
+                ArenaBox::into_inner(value),
+                kind,
+                &annotation,
+                message,
+                location,
+            ),
 
             UntypedExpr::Try {
                 location,
@@ -125,7 +148,13 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 then,
                 annotation,
                 ..
-            } => self.infer_try(pattern, *value, *then, &annotation, location),
+            } => self.infer_try(
+                pattern,
+                ArenaBox::into_inner(value),
+                ArenaBox::into_inner(then),
+                &annotation,
+                location,
+            ),
 
             UntypedExpr::Case {
                 location,
@@ -146,7 +175,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 fun,
                 arguments: args,
                 ..
-            } => self.infer_call(*fun, args, location),
+            } => self.infer_call(ArenaBox::into_inner(fun), args, location),
 
             UntypedExpr::BinOp {
                 location,
@@ -154,21 +183,31 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 left,
                 right,
                 ..
-            } => self.infer_binop(name, *left, *right, location),
+            } => self.infer_binop(
+                name,
+                ArenaBox::into_inner(left),
+                ArenaBox::into_inner(right),
+                location,
+            ),
 
             UntypedExpr::FieldAccess {
                 location,
                 label,
                 container,
                 ..
-            } => self.infer_field_access(*container, label, location, FieldAccessUsage::Other),
+            } => self.infer_field_access(
+                ArenaBox::into_inner(container),
+                label.to_string(),
+                location,
+                FieldAccessUsage::Other,
+            ),
 
             UntypedExpr::TupleIndex {
                 location,
                 index,
                 tuple,
                 ..
-            } => self.infer_tuple_index(*tuple, index, location),
+            } => self.infer_tuple_index(ArenaBox::into_inner(tuple), index, location),
 
             UntypedExpr::BitString { location, segments } => {
                 self.infer_bit_string(segments, location)
@@ -179,10 +218,16 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 constructor,
                 spread,
                 arguments: args,
-            } => self.infer_record_update(*constructor, spread, args, location),
+            } => {
+                self.infer_record_update(ArenaBox::into_inner(constructor), spread, args, location)
+            }
 
             UntypedExpr::Negate { location, value } => self.infer_negate(location, value),
 
+            UntypedExpr::Echo { location, value } => self.infer_echo(location, value),
+
+            UntypedExpr::Cfg { location, flag } => self.infer_cfg(location, flag),
+
             UntypedExpr::Use(use_) => {
                 let location = use_.location;
                 self.infer_use(use_, location, vec![])
@@ -190,7 +235,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         }
     }
 
-    fn infer_pipeline(&mut self, expressions: Vec1<UntypedExpr>) -> Result<TypedExpr, Error> {
+    fn infer_pipeline(&mut self, expressions: Vec1<UntypedExpr<'_>>) -> Result<TypedExpr, Error> {
         PipeTyper::infer(self, expressions)
     }
 
@@ -201,7 +246,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         label: Option<String>,
     ) -> TypedExpr {
         let typ = self.new_unbound_var();
-        self.environment.warnings.push(Warning::Todo {
+        self.environment.warn(Warning::Todo {
             kind,
             location,
             typ: typ.clone(),
@@ -214,6 +259,22 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         }
     }
 
+    fn infer_cfg(&mut self, location: SrcSpan, flag: Name) -> Result<TypedExpr, Error> {
+        match self.environment.features.get(flag.as_str()) {
+            Some(enabled) => Ok(TypedExpr::Cfg {
+                location,
+                typ: bool(),
+                flag,
+                enabled: *enabled,
+            }),
+            None => Err(Error::UnknownFeatureFlag {
+                location,
+                name: flag.to_string(),
+                flags: self.environment.features.keys().cloned().collect(),
+            }),
+        }
+    }
+
     fn infer_string(&mut self, value: String, location: SrcSpan) -> TypedExpr {
         TypedExpr::String {
             location,
@@ -223,6 +284,8 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     }
 
     fn infer_int(&mut self, value: String, location: SrcSpan) -> TypedExpr {
+        self.environment
+            .check_int_range(int_literal_value(&value), location);
         TypedExpr::Int {
             location,
             value,
@@ -230,12 +293,13 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         }
     }
 
-    fn infer_float(&mut self, value: String, location: SrcSpan) -> TypedExpr {
-        TypedExpr::Float {
+    fn infer_float(&mut self, value: String, location: SrcSpan) -> Result<TypedExpr, Error> {
+        check_float_literal(&value, location)?;
+        Ok(TypedExpr::Float {
             location,
             value,
             typ: float(),
-        }
+        })
     }
 
     /// Emit a warning if the given expressions should not be discarded.
@@ -243,30 +307,58 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     /// e.g. because it's of the `Result` type (errors should be handled)
     fn expression_discarded(&mut self, discarded: &TypedExpr) {
         if discarded.is_literal() {
-            self.environment.warnings.push(Warning::UnusedLiteral {
+            self.environment.warn(Warning::UnusedLiteral {
                 location: discarded.location(),
             });
         }
-        if discarded.type_().is_result() && !discarded.is_assignment() {
-            self.environment
-                .warnings
-                .push(Warning::ImplicitlyDiscardedResult {
-                    location: discarded.location(),
-                });
+        if collapse_links(discarded.type_()).is_result() && !discarded.is_assignment() {
+            self.result_discarded(discarded);
+        }
+    }
+
+    /// Warn that a Result is being thrown away, unless it has been passed to
+    /// one of the `unwrap` functions of `gleam/result`, which are used to
+    /// state that any error can be ignored.
+    fn result_discarded(&mut self, discarded: &TypedExpr) {
+        let last_call = match discarded {
+            TypedExpr::Pipeline { expressions, .. } => expressions.last(),
+            _ => Some(discarded),
+        };
+        let explicitly_ignored = match last_call {
+            Some(TypedExpr::Call { fun, .. }) => match fun.as_ref() {
+                TypedExpr::ModuleSelect {
+                    module_name, label, ..
+                } => module_name == "gleam/result" && label.starts_with("unwrap"),
+                TypedExpr::Var {
+                    constructor:
+                        ValueConstructor {
+                            variant: ValueConstructorVariant::ModuleFn { module, name, .. },
+                            ..
+                        },
+                    ..
+                } => module == &["gleam", "result"] && name.starts_with("unwrap"),
+                _ => false,
+            },
+            _ => false,
+        };
+        if !explicitly_ignored {
+            self.environment.warn(Warning::ImplicitlyDiscardedResult {
+                location: discarded.location(),
+            });
         }
     }
 
     fn infer_seq(
         &mut self,
         location: SrcSpan,
-        untyped: Vec<UntypedExpr>,
+        untyped: Vec<UntypedExpr<'_>>,
     ) -> Result<TypedExpr, Error> {
         let count = untyped.len();
         let untyped = untyped.into_iter();
         self.infer_iter_seq(location, count, untyped)
     }
 
-    fn infer_iter_seq<Exprs: Iterator<Item = UntypedExpr>>(
+    fn infer_iter_seq<'e, Exprs: Iterator<Item = UntypedExpr<'e>>>(
         &mut self,
         location: SrcSpan,
         count: usize,
@@ -303,13 +395,12 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         })
     }
 
-    fn infer_use(
+    fn infer_use<'e>(
         &mut self,
-        use_: Use,
+        use_: Use<'e>,
         sequence_location: SrcSpan,
-        mut following_expressions: Vec<UntypedExpr>,
+        mut following_expressions: Vec<UntypedExpr<'e>>,
     ) -> Result<TypedExpr, Error> {
-        let mut call = get_use_expression_call(*use_.call)?;
         let callback_arguments = use_assignments_to_function_arguments(use_.assignments);
 
         // TODO: Upgrade this to an error when we have partial type checking.
@@ -330,15 +421,24 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         let first = following_expressions
             .get(0)
             .expect("default todo set above");
+        let location = SrcSpan::new(first.location().start, sequence_location.end);
+        // The box in the arena of the module that held the use's call is
+        // reused for the body of the callback.
+        let mut body = use_.call;
+        let call = std::mem::replace(
+            body.as_mut(),
+            UntypedExpr::Sequence {
+                location: sequence_location,
+                expressions: following_expressions,
+            },
+        );
+        let mut call = get_use_expression_call(call)?;
         let callback = UntypedExpr::Fn {
             arguments: callback_arguments,
-            location: SrcSpan::new(first.location().start, sequence_location.end),
+            location,
             return_annotation: None,
             is_capture: false,
-            body: Box::new(UntypedExpr::Sequence {
-                location: sequence_location,
-                expressions: following_expressions,
-            }),
+            body,
         };
 
         // Add this new callback function to the arguments to function call
@@ -351,19 +451,15 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             implicit: true,
         });
 
-        self.infer(UntypedExpr::Call {
-            location: call.location,
-            fun: call.function,
-            arguments: call.arguments,
-        })
+        self.infer_call(call.function, call.arguments, call.location)
     }
 
     fn infer_negate(
         &mut self,
         location: SrcSpan,
-        value: Box<UntypedExpr>,
+        value: ArenaBox<'_, UntypedExpr<'_>>,
     ) -> Result<TypedExpr, Error> {
-        let value = self.infer(*value)?;
+        let value = self.infer(ArenaBox::into_inner(value))?;
 
         unify(bool(), value.type_()).map_err(|e| convert_unify_error(e, value.location()))?;
 
@@ -373,11 +469,26 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         })
     }
 
+    fn infer_echo(
+        &mut self,
+        location: SrcSpan,
+        value: ArenaBox<'_, UntypedExpr<'_>>,
+    ) -> Result<TypedExpr, Error> {
+        let value = self.infer(ArenaBox::into_inner(value))?;
+        // The warning is only shown for production builds, where any `echo`
+        // is most likely debugging code that was left in by mistake.
+        self.environment.warn(Warning::Echo { location });
+        Ok(TypedExpr::Echo {
+            location,
+            value: Box::new(value),
+        })
+    }
+
     fn infer_fn(
         &mut self,
         args: Vec<UntypedArg>,
         expected_args: &[Arc<Type>],
-        body: UntypedExpr,
+        body: UntypedExpr<'_>,
         is_capture: bool,
         return_annotation: Option<TypeAst>,
         location: SrcSpan,
@@ -404,6 +515,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             names,
             annotation,
             location,
+            doc,
             ..
         } = arg;
         let typ = annotation
@@ -425,13 +537,14 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             location,
             annotation,
             type_: typ,
+            doc,
         })
     }
 
     fn infer_call(
         &mut self,
-        fun: UntypedExpr,
-        args: Vec<CallArg<UntypedExpr>>,
+        fun: UntypedExpr<'_>,
+        args: Vec<CallArg<UntypedExpr<'_>>>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
         let (fun, args, typ) = self.do_infer_call(fun, args, location)?;
@@ -445,8 +558,8 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
     fn infer_list(
         &mut self,
-        elements: Vec<UntypedExpr>,
-        tail: Option<Box<UntypedExpr>>,
+        elements: Vec<UntypedExpr<'_>>,
+        tail: Option<ArenaBox<'_, UntypedExpr<'_>>>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
         let typ = self.new_unbound_var();
@@ -465,7 +578,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         let typ = list(typ);
         let tail = match tail {
             Some(tail) => {
-                let tail = self.infer(*tail)?;
+                let tail = self.infer(ArenaBox::into_inner(tail))?;
                 // Ensure the tail has the same type as the preceeding elements
                 unify(typ.clone(), tail.type_()).map_err(|e| convert_unify_error(e, location))?;
                 Some(Box::new(tail))
@@ -482,7 +595,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
     fn infer_tuple(
         &mut self,
-        elems: Vec<UntypedExpr>,
+        elems: Vec<UntypedExpr<'_>>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
         let elems: Vec<_> = elems.into_iter().map(|e| self.infer(e)).try_collect()?;
@@ -498,24 +611,27 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         Ok(TypedExpr::Var {
             constructor,
             location,
-            name,
+            name: name.into(),
         })
     }
 
     fn infer_field_access(
         &mut self,
-        container: UntypedExpr,
+        container: UntypedExpr<'_>,
         label: String,
         access_location: SrcSpan,
         usage: FieldAccessUsage,
     ) -> Result<TypedExpr, Error> {
         // Attempt to infer the container as a record access. If that fails, we may be shadowing the name
         // of an imported module, so attempt to infer the container as a module access.
-        // TODO: Remove this cloning
-        match self.infer_record_access(container.clone(), label.clone(), access_location, usage) {
+        let variable = match &container {
+            UntypedExpr::Var { name, location } => Some((name.clone(), *location)),
+            _ => None,
+        };
+        match self.infer_record_access(container, label.clone(), access_location, usage) {
             Ok(record_access) => Ok(record_access),
-            Err(err) => match container {
-                UntypedExpr::Var { name, location, .. } => {
+            Err(err) => match variable {
+                Some((name, location)) => {
                     let module_access =
                         self.infer_module_access(&name, label, &location, access_location);
 
@@ -528,14 +644,14 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                         module_access
                     }
                 }
-                _ => Err(err),
+                None => Err(err),
             },
         }
     }
 
     fn infer_tuple_index(
         &mut self,
-        tuple: UntypedExpr,
+        tuple: UntypedExpr<'_>,
         index: u64,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
@@ -574,7 +690,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
     fn infer_bit_string(
         &mut self,
-        segments: Vec<UntypedExprBitStringSegment>,
+        segments: Vec<UntypedExprBitStringSegment<'_>>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
         let segments = segments
@@ -652,8 +768,8 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     fn infer_binop(
         &mut self,
         name: BinOp,
-        left: UntypedExpr,
-        right: UntypedExpr,
+        left: UntypedExpr<'_>,
+        right: UntypedExpr<'_>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
         let (input_type, output_type) = match &name {
@@ -704,6 +820,16 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 .into_error(right.type_defining_location())
         })?;
 
+        // Arithmetic on literals is checked once the operands are known to
+        // be in range, as any that are not have been warned about already.
+        if let (Some(left), Some(right)) = (folded_int(&left), folded_int(&right)) {
+            if self.environment.int_in_range(left) && self.environment.int_in_range(right) {
+                if let Some(value) = evaluate_int_binop(name, left, right) {
+                    self.environment.check_int_range(Some(value), location);
+                }
+            }
+        }
+
         Ok(TypedExpr::BinOp {
             location,
             name,
@@ -716,9 +842,10 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     fn infer_assignment(
         &mut self,
         pattern: UntypedPattern,
-        value: UntypedExpr,
+        value: UntypedExpr<'_>,
         kind: AssignmentKind,
         annotation: &Option<TypeAst>,
+        message: Option<String>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
         let value = self.in_new_scope(|value_typer| value_typer.infer(value))?;
@@ -728,6 +855,18 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         let pattern = pattern::PatternTyper::new(self.environment, &self.hydrator)
             .unify(pattern, value_typ.clone())?;
 
+        // Assigning a Result to `_` throws it away just as much as not
+        // assigning it at all. A named discard such as `_ignored` shows that
+        // this is intended.
+        if let Pattern::Discard { name, .. } = &pattern {
+            if name == "_"
+                && kind == AssignmentKind::Let
+                && collapse_links(value_typ.clone()).is_result()
+            {
+                self.result_discarded(&value);
+            }
+        }
+
         // Check that any type annotation is accurate.
         if let Some(ann) = annotation {
             let ann_typ = self
@@ -741,13 +880,16 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         // at the top level of patterns.
         // Do not perform exhaustiveness checking if user explicitly used `assert`.
         if kind != AssignmentKind::Assert {
+            let value_typ = collapse_links(value_typ.clone());
             if let Err(unmatched) = self
                 .environment
-                .check_exhaustiveness(vec![pattern.clone()], collapse_links(value_typ.clone()))
+                .check_exhaustiveness(vec![pattern.clone()], value_typ.clone())
             {
+                let patterns = self.unmatched_patterns(&value_typ, &unmatched);
                 return Err(Error::NotExhaustivePatternMatch {
                     location,
                     unmatched,
+                    patterns,
                 });
             }
         }
@@ -758,14 +900,16 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             kind,
             pattern,
             value: Box::new(value),
+            annotation: annotation.clone(),
+            message,
         })
     }
 
     fn infer_try(
         &mut self,
         pattern: UntypedPattern,
-        value: UntypedExpr,
-        then: UntypedExpr,
+        value: UntypedExpr<'_>,
+        then: UntypedExpr<'_>,
         annotation: &Option<TypeAst>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
@@ -814,13 +958,14 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             pattern,
             value: Box::new(value),
             then: Box::new(then),
+            annotation: annotation.clone(),
         })
     }
 
     fn infer_case(
         &mut self,
-        subjects: Vec<UntypedExpr>,
-        clauses: Vec<UntypedClause>,
+        subjects: Vec<UntypedExpr<'_>>,
+        clauses: Vec<UntypedClause<'_>>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
         let subjects_count = subjects.len();
@@ -848,12 +993,19 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             typed_clauses.push(typed_clause);
         }
 
+        self.check_unreachable_clauses(&typed_clauses);
+
         if let Err(unmatched) =
             self.check_case_exhaustiveness(subjects_count, &subject_types, &typed_clauses)
         {
+            let patterns = match subject_types.first() {
+                Some(type_) => self.unmatched_patterns(&collapse_links(type_.clone()), &unmatched),
+                None => vec![],
+            };
             return Err(Error::NotExhaustivePatternMatch {
                 location,
                 unmatched,
+                patterns,
             });
         }
 
@@ -867,7 +1019,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
     fn infer_clause(
         &mut self,
-        clause: UntypedClause,
+        clause: UntypedClause<'_>,
         subjects: &[Arc<Type>],
     ) -> Result<TypedClause, Error> {
         let Clause {
@@ -952,7 +1104,10 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                     ValueConstructorVariant::LocalVariable { .. } => (),
                     ValueConstructorVariant::ModuleFn { .. }
                     | ValueConstructorVariant::Record { .. } => {
-                        return Err(Error::NonLocalClauseGuardVariable { location, name });
+                        return Err(Error::NonLocalClauseGuardVariable {
+                            location,
+                            name: name.to_string(),
+                        });
                     }
 
                     ValueConstructorVariant::ModuleConstant { literal, .. } => {
@@ -1003,6 +1158,54 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 }
             }
 
+            ClauseGuard::Call {
+                location,
+                name,
+                args,
+                ..
+            } => {
+                let constructor = self.infer_value_constructor(&None, &name, &location)?;
+
+                // Only functions of this module can be called, as their bodies
+                // are needed to check they are safe to call in a guard once
+                // the whole module has been inferred.
+                let name = match &constructor.variant {
+                    ValueConstructorVariant::ModuleFn { module, name, .. }
+                        if module == &self.environment.current_module =>
+                    {
+                        name.clone()
+                    }
+                    _ => {
+                        return Err(Error::UnsafeClauseGuardCall {
+                            location,
+                            name: name.to_string(),
+                        })
+                    }
+                };
+
+                let (args_types, type_) =
+                    match_fun_type(constructor.type_, args.len(), self.environment)
+                        .map_err(|e| convert_not_fun_error(e, location, location))?;
+                let args = args_types
+                    .into_iter()
+                    .zip(args)
+                    .map(|(typ, arg)| {
+                        let arg = self.infer_clause_guard(arg)?;
+                        unify(typ, arg.type_())
+                            .map_err(|e| convert_unify_error(e, arg.location()))?;
+                        Ok(arg)
+                    })
+                    .try_collect()?;
+
+                self.environment.guard_calls.push((name.clone(), location));
+                Ok(ClauseGuard::Call {
+                    location,
+                    type_,
+                    name,
+                    args,
+                })
+            }
+
             ClauseGuard::And {
                 location,
                 left,
@@ -1251,7 +1454,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             let constructor =
                 module
                     .values
-                    .get(&label)
+                    .get(label.as_str())
                     .ok_or_else(|| Error::UnknownModuleValue {
                         name: label.clone(),
                         location: SrcSpan {
@@ -1269,6 +1472,12 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             (module.name.clone(), constructor.clone())
         };
 
+        self.environment.warn_if_deprecated(
+            &constructor.deprecation,
+            select_location,
+            Layer::Value,
+        );
+
         let type_ = self.instantiate(constructor.type_, &mut hashmap![]);
 
         let constructor = match &constructor.variant {
@@ -1284,18 +1493,18 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         };
 
         Ok(TypedExpr::ModuleSelect {
-            label,
+            label: label.into(),
             typ: Arc::clone(&type_),
             location: select_location,
-            module_name: module_name.join("/"),
-            module_alias: module_alias.to_string(),
+            module_name: module_name.join("/").into(),
+            module_alias: module_alias.into(),
             constructor,
         })
     }
 
     fn infer_record_access(
         &mut self,
-        record: UntypedExpr,
+        record: UntypedExpr<'_>,
         label: String,
         location: SrcSpan,
         usage: FieldAccessUsage,
@@ -1332,12 +1541,20 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         };
 
         // Check to see if it's a Type that can have accessible fields
-        let accessors = match collapse_links(record.type_()).as_ref() {
+        let record_type = collapse_links(record.type_());
+        let accessors = match record_type.as_ref() {
             // A type in the current module which may have fields
             Type::App { module, name, .. } if module == self.environment.current_module => {
                 self.environment.accessors.get(name)
             }
 
+            // A type in the prelude which may have fields
+            Type::App { module, name, .. } if module.is_empty() => self
+                .environment
+                .importable_modules
+                .get("gleam")
+                .and_then(|module| module.accessors.get(name)),
+
             // A type in another module which may have fields
             Type::App { module, name, .. } => self
                 .environment
@@ -1356,7 +1573,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             type_: typ,
         } = accessors
             .accessors
-            .get(&label)
+            .get(label.as_str())
             .ok_or_else(|| {
                 unknown_field(accessors.accessors.keys().map(|t| t.to_string()).collect())
             })?
@@ -1366,29 +1583,83 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         // This ensure that the type parameters of the retrieved value have the correct
         // types for this instance of the record.
         let accessor_record_type = accessors.type_.clone();
+        let erlang_map = accessors.erlang_map;
         let mut type_vars = hashmap![];
         let accessor_record_type = self.instantiate(accessor_record_type, &mut type_vars);
         let typ = self.instantiate(typ, &mut type_vars);
         unify(accessor_record_type, record.type_())
             .map_err(|e| convert_unify_error(e, record.location()))?;
 
+        let variants = match record_type.as_ref() {
+            Type::App { module, name, .. } => self.variant_fields(module, name, &label, index),
+            _ => vec![],
+        };
+
         Ok(TypedExpr::RecordAccess {
             record,
             label,
             index,
+            variants,
             location,
             typ,
+            erlang_map,
         })
     }
 
+    // The position of a field in each constructor of a custom type, if it is
+    // not at the same position in all of them.
+    fn variant_fields(
+        &self,
+        module: &[Name],
+        type_name: &str,
+        label: &str,
+        index: u64,
+    ) -> Vec<VariantField> {
+        let (constructors, values) = if module == self.environment.current_module {
+            (
+                self.environment.module_types_constructors.get(type_name),
+                &self.environment.module_values,
+            )
+        } else {
+            match self.environment.importable_modules.get(&module.join("/")) {
+                Some(module) => (module.types_constructors.get(type_name), &module.values),
+                None => return vec![],
+            }
+        };
+
+        let fields: Vec<_> = constructors
+            .into_iter()
+            .flatten()
+            .filter_map(|name| match &values.get(name)?.variant {
+                ValueConstructorVariant::Record {
+                    arity,
+                    field_map: Some(field_map),
+                    ..
+                } => Some(VariantField {
+                    constructor: name.clone(),
+                    arity: *arity,
+                    index: u64::from(*field_map.fields.get(label)?),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        if fields.iter().all(|field| field.index == index) {
+            vec![]
+        } else {
+            fields
+        }
+    }
+
     fn infer_record_update(
         &mut self,
-        constructor: UntypedExpr,
-        spread: RecordUpdateSpread,
-        args: Vec<UntypedRecordUpdateArg>,
+        constructor: UntypedExpr<'_>,
+        spread: RecordUpdateSpread<'_>,
+        args: Vec<UntypedRecordUpdateArg<'_>>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
-        let (module, name) = match self.infer(constructor.clone())? {
+        let constructor_location = constructor.location();
+        let (module, name) = match self.infer(constructor)? {
             TypedExpr::ModuleSelect {
                 module_alias,
                 label,
@@ -1411,15 +1682,16 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             .clone();
 
         // It must be a record with a field map for us to be able to update it
-        let (field_map, constructors_count) = match &value_constructor.variant {
+        let (field_map, constructors_count, erlang_map) = match &value_constructor.variant {
             ValueConstructorVariant::Record {
                 field_map: Some(field_map),
                 constructors_count,
+                erlang_map,
                 ..
-            } => (field_map, *constructors_count),
+            } => (field_map, *constructors_count, *erlang_map),
             _ => {
                 return Err(Error::RecordUpdateInvalidConstructor {
-                    location: constructor.location(),
+                    location: constructor_location,
                 });
             }
         };
@@ -1429,7 +1701,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         // could be one of the other variants.
         if constructors_count != 1 {
             return Err(Error::UpdateMultiConstructorType {
-                location: constructor.location(),
+                location: constructor_location,
             });
         }
 
@@ -1438,79 +1710,207 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             Type::Fn { retrn, .. } => retrn,
             _ => {
                 return Err(Error::RecordUpdateInvalidConstructor {
-                    location: constructor.location(),
+                    location: constructor_location,
                 })
             }
         };
 
-        let spread = self.infer(*spread.base)?;
+        let spread = self.infer(ArenaBox::into_inner(spread.base))?;
         let return_type = self.instantiate(retrn.clone(), &mut hashmap![]);
 
         // Check that the spread variable unifies with the return type of the constructor
         unify(return_type, spread.type_())
             .map_err(|e| convert_unify_error(e, spread.location()))?;
 
-        let args: Vec<TypedRecordUpdateArg> = args
-            .iter()
-            .map(
-                |UntypedRecordUpdateArg {
-                     label,
-                     value,
-                     location,
-                 }| {
-                    let value = self.infer(value.clone())?;
-                    let spread_field = self.infer_known_record_access(
-                        spread.clone(),
-                        label.to_string(),
-                        *location,
-                        FieldAccessUsage::Other,
-                    )?;
-
-                    // Check that the update argument unifies with the corresponding
-                    // field in the record contained within the spread variable. We
-                    // need to check the spread, and not the constructor, in order
-                    // to handle polymorphic types.
-                    unify(spread_field.type_(), value.type_())
-                        .map_err(|e| convert_unify_error(e, value.location()))?;
-
-                    match field_map.fields.get(label) {
-                        None => panic!(
-                            "Failed to lookup record field after successfully inferring that field",
-                        ),
-                        Some(p) => Ok(TypedRecordUpdateArg {
-                            location: *location,
-                            label: label.to_string(),
-                            value,
-                            index: *p,
-                        }),
-                    }
+        // Nested updates read fields of the spread as well as updating it, so
+        // unless it is a variable it is assigned to one first to ensure it is
+        // only evaluated once.
+        let mut assignment = None;
+        let is_nested = args.iter().any(|arg| !arg.path.is_empty());
+        let spread = if is_nested && !matches!(spread, TypedExpr::Var { .. }) {
+            let spread_location = spread.location();
+            self.environment.insert_local_variable(
+                RECORD_VARIABLE.into(),
+                spread_location,
+                spread.type_(),
+            );
+            assignment = Some(TypedExpr::Assignment {
+                location: spread_location,
+                typ: spread.type_(),
+                kind: AssignmentKind::Let,
+                value: Box::new(spread),
+                pattern: Pattern::Var {
+                    location: spread_location,
+                    name: RECORD_VARIABLE.into(),
                 },
-            )
-            .try_collect()?;
+                annotation: None,
+                message: None,
+            });
+            self.infer(UntypedExpr::Var {
+                location: spread_location,
+                name: RECORD_VARIABLE.into(),
+            })?
+        } else {
+            spread
+        };
+
+        let args = self.infer_record_update_args(&spread, args)?;
 
         if args.is_empty() {
             self.environment
-                .warnings
-                .push(Warning::NoFieldsRecordUpdate { location });
+                .warn(Warning::NoFieldsRecordUpdate { location });
         }
 
         if args.len() == field_map.arity as usize {
             self.environment
-                .warnings
-                .push(Warning::AllFieldsRecordUpdate { location });
+                .warn(Warning::AllFieldsRecordUpdate { location });
         }
 
-        Ok(TypedExpr::RecordUpdate {
+        let update = TypedExpr::RecordUpdate {
             location,
             typ: spread.type_(),
             spread: Box::new(spread),
             args,
+            erlang_map,
+        };
+        Ok(match assignment {
+            Some(assignment) => TypedExpr::Sequence {
+                location,
+                expressions: vec![assignment, update],
+            },
+            None => update,
         })
     }
 
+    fn infer_record_update_args(
+        &mut self,
+        spread: &TypedExpr,
+        args: Vec<UntypedRecordUpdateArg<'_>>,
+    ) -> Result<Vec<TypedRecordUpdateArg>, Error> {
+        let mut typed_args = Vec::with_capacity(args.len());
+
+        // Updates of fields within nested records, grouped by the field of
+        // this record that holds them, in the order they were first given.
+        let mut nested: Vec<(Name, SrcSpan, Vec<UntypedRecordUpdateArg<'_>>)> = vec![];
+
+        for UntypedRecordUpdateArg {
+            label,
+            mut path,
+            location,
+            value,
+        } in args
+        {
+            if !path.is_empty() {
+                let inner = UntypedRecordUpdateArg {
+                    label: path.remove(0),
+                    path,
+                    location,
+                    value,
+                };
+                match nested
+                    .iter_mut()
+                    .find(|(nested_label, ..)| *nested_label == label)
+                {
+                    Some((_, nested_location, inner_args)) => {
+                        nested_location.end = location.end;
+                        inner_args.push(inner);
+                    }
+                    None => nested.push((label, location, vec![inner])),
+                }
+                continue;
+            }
+
+            let value = self.infer(value)?;
+            let spread_field = self.infer_known_record_access(
+                spread.clone(),
+                label.to_string(),
+                location,
+                FieldAccessUsage::Other,
+            )?;
+
+            // Check that the update argument unifies with the corresponding
+            // field in the record contained within the spread variable. We
+            // need to check the spread, and not the constructor, in order
+            // to handle polymorphic types.
+            unify(spread_field.type_(), value.type_())
+                .map_err(|e| convert_unify_error(e, value.location()))?;
+
+            typed_args.push(TypedRecordUpdateArg {
+                location,
+                label,
+                value,
+                index: record_access_index(&spread_field),
+            });
+        }
+
+        // `Config(..config, server.port: 8080)` is checked as though it were
+        // `Config(..config, server: Server(..config.server, port: 8080))`
+        for (label, location, inner_args) in nested {
+            let record = self.infer_known_record_access(
+                spread.clone(),
+                label.to_string(),
+                location,
+                FieldAccessUsage::Other,
+            )?;
+            let index = record_access_index(&record);
+            let erlang_map = self.nested_record_update_erlang_map(&record.type_(), location)?;
+            let inner_args = self.infer_record_update_args(&record, inner_args)?;
+            typed_args.push(TypedRecordUpdateArg {
+                location,
+                label,
+                index,
+                value: TypedExpr::RecordUpdate {
+                    location,
+                    typ: record.type_(),
+                    spread: Box::new(record),
+                    args: inner_args,
+                    erlang_map,
+                },
+            });
+        }
+
+        Ok(typed_args)
+    }
+
+    // Whether the record updated by a nested update is represented as a map on
+    // the Erlang target, if it can be updated at all.
+    fn nested_record_update_erlang_map(
+        &self,
+        typ: &Type,
+        location: SrcSpan,
+    ) -> Result<bool, Error> {
+        let (constructors, values) = match typ {
+            Type::App { module, name, .. } if module == &self.environment.current_module => (
+                self.environment.module_types_constructors.get(name),
+                &self.environment.module_values,
+            ),
+            Type::App { module, name, .. } => {
+                match self.environment.importable_modules.get(&module.join("/")) {
+                    Some(module) => (module.types_constructors.get(name), &module.values),
+                    None => return Err(Error::RecordUpdateInvalidConstructor { location }),
+                }
+            }
+            _ => return Err(Error::RecordUpdateInvalidConstructor { location }),
+        };
+
+        // As with any record update the record must be the only variant of
+        // its type to be safely updated.
+        match constructors.map(Vec::as_slice) {
+            Some([constructor]) => match values.get(constructor).map(|value| &value.variant) {
+                Some(ValueConstructorVariant::Record {
+                    field_map: Some(_),
+                    erlang_map,
+                    ..
+                }) => Ok(*erlang_map),
+                _ => Err(Error::RecordUpdateInvalidConstructor { location }),
+            },
+            _ => Err(Error::UpdateMultiConstructorType { location }),
+        }
+    }
+
     fn infer_value_constructor(
         &mut self,
-        module: &Option<String>,
+        module: &Option<Name>,
         name: &str,
         location: &SrcSpan,
     ) -> Result<ValueConstructor, Error> {
@@ -1542,6 +1942,15 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 // Register the value as seen for detection of unused values
                 self.environment.increment_usage(name);
 
+                if let ValueConstructorVariant::LocalVariable { .. } = &constructor.variant {
+                    self.environment.trace_variable(
+                        name,
+                        *location,
+                        TraceStepKind::Used,
+                        constructor.type_.clone(),
+                    );
+                }
+
                 constructor
             }
 
@@ -1567,7 +1976,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                     .cloned()
                     .ok_or_else(|| Error::UnknownModuleValue {
                         location: *location,
-                        module_name: vec![module_name.to_string()],
+                        module_name: vec![module_name.as_str().into()],
                         name: name.to_string(),
                         value_constructors: module.values.keys().map(|t| t.to_string()).collect(),
                     })?
@@ -1576,14 +1985,19 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
         let ValueConstructor {
             public,
+            deprecation,
             variant,
             type_: typ,
         } = constructor;
 
+        self.environment
+            .warn_if_deprecated(&deprecation, *location, Layer::Value);
+
         // Instantiate generic variables into unbound variables for this usage
         let typ = self.instantiate(typ, &mut hashmap![]);
         Ok(ValueConstructor {
             public,
+            deprecation,
             variant,
             type_: typ,
         })
@@ -1599,11 +2013,18 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         let inferred = match value {
             Constant::Int {
                 location, value, ..
-            } => Ok(Constant::Int { location, value }),
+            } => {
+                self.environment
+                    .check_int_range(int_literal_value(&value), location);
+                Ok(Constant::Int { location, value })
+            }
 
             Constant::Float {
                 location, value, ..
-            } => Ok(Constant::Float { location, value }),
+            } => {
+                check_float_literal(&value, location)?;
+                Ok(Constant::Float { location, value })
+            }
 
             Constant::String {
                 location, value, ..
@@ -1644,7 +2065,10 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
                     ValueConstructorVariant::ModuleFn { .. }
                     | ValueConstructorVariant::LocalVariable { .. } => {
-                        return Err(Error::NonLocalClauseGuardVariable { location, name })
+                        return Err(Error::NonLocalClauseGuardVariable {
+                            location,
+                            name: name.to_string(),
+                        })
                     }
 
                     // TODO: remove this clone. Could use an rc instead
@@ -1680,13 +2104,23 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 let constructor = self.infer_value_constructor(&module, &name, &location)?;
 
                 let (tag, field_map) = match &constructor.variant {
+                    // Constants are inlined where they are used, including in
+                    // other modules, which only know the positions of the
+                    // fields and not the labels needed to build a map.
+                    ValueConstructorVariant::Record {
+                        erlang_map: true, ..
+                    } => return Err(Error::ErlangMapRecordInConstant { location }),
+
                     ValueConstructorVariant::Record {
                         name, field_map, ..
                     } => (name.clone(), field_map.clone()),
 
                     ValueConstructorVariant::ModuleFn { .. }
                     | ValueConstructorVariant::LocalVariable { .. } => {
-                        return Err(Error::NonLocalClauseGuardVariable { location, name })
+                        return Err(Error::NonLocalClauseGuardVariable {
+                            location,
+                            name: name.to_string(),
+                        })
                     }
 
                     // TODO: remove this clone. Could be an rc instead
@@ -1717,12 +2151,13 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                             arity: args.len() as u16,
                             type_: Arc::clone(&typ),
                             location: constructor.variant.definition_location(),
+                            erlang_map: false,
                         };
 
                         TypedExpr::ModuleSelect {
                             label: name.clone(),
                             module_alias: module_alias.clone(),
-                            module_name,
+                            module_name: module_name.into(),
                             typ,
                             constructor: module_value_constructor,
                             location,
@@ -1799,6 +2234,23 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
                 // Infer the type of this constant
                 let constructor = self.infer_value_constructor(&module, &name, &location)?;
+
+                // Constants from other modules are replaced by their value
+                // where it can be written the same way in this module, so
+                // that targets emit a literal rather than a reference.
+                if let ValueConstructorVariant::ModuleConstant {
+                    literal,
+                    module: constant_module,
+                    ..
+                } = &constructor.variant
+                {
+                    if *constant_module != self.environment.current_module.join("/") {
+                        if let Some(value) = fold_constant(literal, location) {
+                            return Ok(value);
+                        }
+                    }
+                }
+
                 match constructor.variant {
                     ValueConstructorVariant::ModuleConstant { .. }
                     | ValueConstructorVariant::ModuleFn { .. } => Ok(Constant::Var {
@@ -1815,6 +2267,13 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                     _ => unreachable!(),
                 }
             }
+
+            Constant::BinOp {
+                location,
+                name,
+                left,
+                right,
+            } => self.infer_const_binop(name, *left, *right, location),
         }?;
 
         // Check type annotation is accurate.
@@ -1827,6 +2286,46 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         Ok(inferred)
     }
 
+    // Binary operators in constants are evaluated here so that every target
+    // is given the resulting literal.
+    fn infer_const_binop(
+        &mut self,
+        name: BinOp,
+        left: UntypedConstant,
+        right: UntypedConstant,
+        location: SrcSpan,
+    ) -> Result<TypedConstant, Error> {
+        let input_type = match name {
+            BinOp::Concatenate => string(),
+            BinOp::AddFloat | BinOp::SubFloat | BinOp::MultFloat | BinOp::DivFloat => float(),
+            _ => int(),
+        };
+
+        let left = self.infer_const(&None, left)?;
+        unify(input_type.clone(), left.type_())
+            .map_err(|e| e.operator_situation(name).into_error(left.location()))?;
+        let right = self.infer_const(&None, right)?;
+        unify(input_type, right.type_())
+            .map_err(|e| e.operator_situation(name).into_error(right.location()))?;
+
+        let value = evaluate_const_binop(name, &left, &right, location)
+            .ok_or(Error::ConstantOutOfRange { location })?;
+        if let (
+            Constant::Int { value: left, .. },
+            Constant::Int { value: right, .. },
+            Constant::Int { value: result, .. },
+        ) = (const_literal(&left), const_literal(&right), &value)
+        {
+            let in_range =
+                |value| int_literal_value(value).is_some_and(|v| self.environment.int_in_range(v));
+            if in_range(left) && in_range(right) {
+                self.environment
+                    .check_int_range(int_literal_value(result), location);
+            }
+        }
+        Ok(value)
+    }
+
     fn infer_const_tuple(
         &mut self,
         untyped_elements: Vec<UntypedConstant>,
@@ -1888,8 +2387,8 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
     pub fn do_infer_call(
         &mut self,
-        fun: UntypedExpr,
-        args: Vec<CallArg<UntypedExpr>>,
+        fun: UntypedExpr<'_>,
+        args: Vec<CallArg<UntypedExpr<'_>>>,
         location: SrcSpan,
     ) -> Result<(TypedExpr, Vec<TypedCallArg>, Arc<Type>), Error> {
         let fun = match fun {
@@ -1897,7 +2396,12 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 location,
                 label,
                 container,
-            } => self.infer_field_access(*container, label, location, FieldAccessUsage::MethodCall),
+            } => self.infer_field_access(
+                ArenaBox::into_inner(container),
+                label.to_string(),
+                location,
+                FieldAccessUsage::MethodCall,
+            ),
 
             fun => self.infer(fun),
         }?;
@@ -1909,7 +2413,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     pub fn do_infer_call_with_known_fun(
         &mut self,
         fun: TypedExpr,
-        mut args: Vec<CallArg<UntypedExpr>>,
+        mut args: Vec<CallArg<UntypedExpr<'_>>>,
         location: SrcSpan,
     ) -> Result<(TypedExpr, Vec<TypedCallArg>, Arc<Type>), Error> {
         // Check to see if the function accepts labelled arguments
@@ -1954,7 +2458,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
     fn infer_call_argument(
         &mut self,
-        value: UntypedExpr,
+        value: UntypedExpr<'_>,
         typ: Arc<Type>,
     ) -> Result<TypedExpr, Error> {
         let typ = collapse_links(typ);
@@ -1983,7 +2487,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             ) if expected_arguments.len() == arguments.len() => self.infer_fn(
                 arguments,
                 expected_arguments,
-                *body,
+                ArenaBox::into_inner(body),
                 false,
                 return_annotation,
                 location,
@@ -2001,7 +2505,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         &mut self,
         args: Vec<UntypedArg>,
         expected_args: &[Arc<Type>],
-        body: UntypedExpr,
+        body: UntypedExpr<'_>,
         return_annotation: &Option<TypeAst>,
     ) -> Result<(Vec<TypedArg>, TypedExpr), Error> {
         // Construct an initial type for each argument of the function- either an unbound
@@ -2023,21 +2527,29 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     pub fn infer_fn_with_known_types(
         &mut self,
         args: Vec<TypedArg>,
-        body: UntypedExpr,
+        body: UntypedExpr<'_>,
         return_type: Option<Arc<Type>>,
     ) -> Result<(Vec<TypedArg>, TypedExpr), Error> {
         let (body_rigid_names, body_infer) = self.in_new_scope(|body_typer| {
             for (arg, t) in args.iter().zip(args.iter().map(|arg| arg.type_.clone())) {
                 match &arg.names {
-                    ArgNames::Named { name } | ArgNames::NamedLabelled { name, .. } => {
-                        body_typer.environment.insert_local_variable(
-                            name.to_string(),
+                    ArgNames::Named { name } => {
+                        body_typer
+                            .environment
+                            .insert_local_variable(name.clone(), arg.location, t);
+                        body_typer.environment.init_usage(
+                            name.clone(),
+                            EntityKind::Variable,
                             arg.location,
-                            t,
                         );
+                    }
+                    ArgNames::NamedLabelled { name, label } => {
+                        body_typer
+                            .environment
+                            .insert_local_variable(name.clone(), arg.location, t);
                         body_typer.environment.init_usage(
-                            name.to_string(),
-                            EntityKind::Variable,
+                            name.clone(),
+                            EntityKind::LabelledArgument(label.clone()),
                             arg.location,
                         );
                     }
@@ -2048,13 +2560,20 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             (body_typer.hydrator.rigid_names(), body_typer.infer(body))
         });
 
-        let body = body_infer.map_err(|e| e.with_unify_error_rigid_names(&body_rigid_names))?;
+        let body = body_infer.map_err(|e| {
+            self.environment
+                .explain_recursive_type(e)
+                .with_unify_error_rigid_names(&body_rigid_names)
+        })?;
 
         // Check that any return type is accurate.
         if let Some(return_type) = return_type {
             unify(return_type, body.type_()).map_err(|e| {
-                e.return_annotation_mismatch()
-                    .into_error(body.type_defining_location())
+                let error = e
+                    .return_annotation_mismatch()
+                    .into_error(body.type_defining_location());
+                self.environment
+                    .explain_recursive_type(error)
                     .with_unify_error_rigid_names(&body_rigid_names)
             })?;
         }
@@ -2066,7 +2585,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         &mut self,
         subjects_count: usize,
         subjects: &[Arc<Type>],
-        typed_clauses: &[Clause<TypedExpr, PatternConstructor, Arc<Type>, String>],
+        typed_clauses: &[Clause<TypedExpr, PatternConstructor, Arc<Type>, Name>],
     ) -> Result<(), Vec<String>> {
         // Because exhaustiveness checking in presence of multiple subjects is similar
         // to full exhaustiveness checking of tuples or other nested record patterns,
@@ -2102,15 +2621,396 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         }
         self.environment.check_exhaustiveness(patterns, value_typ)
     }
+
+    fn unmatched_patterns(&self, type_: &Type, unmatched: &[String]) -> Vec<String> {
+        unmatched
+            .iter()
+            .map(|constructor| self.environment.constructor_pattern(type_, constructor))
+            .collect()
+    }
+
+    /// Warns about clauses that can never match because every one of their
+    /// patterns is already matched by an earlier clause without a guard.
+    ///
+    /// This only considers one earlier pattern at a time, so a clause that is
+    /// only covered by several earlier clauses together is not reported.
+    fn check_unreachable_clauses(&mut self, typed_clauses: &[TypedClause]) {
+        let mut earlier_patterns: Vec<&TypedMultiPattern> = vec![];
+        for clause in typed_clauses {
+            let patterns = std::iter::once(&clause.pattern).chain(&clause.alternative_patterns);
+            let covering = patterns
+                .clone()
+                .map(|pattern| {
+                    earlier_patterns
+                        .iter()
+                        .find(|earlier| multi_pattern_subsumes(earlier, pattern))
+                })
+                .collect::<Option<Vec<_>>>();
+
+            if let Some(earlier) = covering.as_ref().and_then(|covering| covering.first()) {
+                let start = clause.pattern.first().map(|p| p.location().start);
+                let end = patterns
+                    .clone()
+                    .last()
+                    .and_then(|pattern| pattern.last())
+                    .map(|p| p.location().end);
+                let covered_by = SrcSpan {
+                    start: earlier
+                        .first()
+                        .map(|p| p.location().start)
+                        .unwrap_or_default(),
+                    end: earlier.last().map(|p| p.location().end).unwrap_or_default(),
+                };
+                self.environment.warn(Warning::UnreachableCaseClause {
+                    location: SrcSpan {
+                        start: start.unwrap_or_default(),
+                        end: end.unwrap_or_default(),
+                    },
+                    covered_by,
+                });
+            }
+
+            if clause.guard.is_none() {
+                earlier_patterns.extend(patterns);
+            }
+        }
+    }
+}
+
+/// Whether every value matched by the `other` patterns is also matched by the
+/// `pattern` patterns, subject by subject.
+fn multi_pattern_subsumes(pattern: &TypedMultiPattern, other: &TypedMultiPattern) -> bool {
+    pattern.len() == other.len()
+        && pattern
+            .iter()
+            .zip(other)
+            .all(|(pattern, other)| pattern_subsumes(pattern, other))
+}
+
+/// Whether every value matched by `other` is also matched by `pattern`. This
+/// is conservative and returns `false` for patterns it cannot compare, such
+/// as bit strings.
+fn pattern_subsumes(pattern: &TypedPattern, other: &TypedPattern) -> bool {
+    match (pattern, other) {
+        (Pattern::Assign { pattern, .. }, other) => pattern_subsumes(pattern, other),
+        (pattern, Pattern::Assign { pattern: other, .. }) => pattern_subsumes(pattern, other),
+
+        (Pattern::Discard { .. } | Pattern::Var { .. }, _) => true,
+
+        (Pattern::Int { value, .. }, Pattern::Int { value: other, .. }) => {
+            match (int_literal_value(value), int_literal_value(other)) {
+                (Some(value), Some(other)) => value == other,
+                _ => value == other,
+            }
+        }
+
+        (
+            Pattern::IntRange { .. } | Pattern::Int { .. },
+            Pattern::IntRange { .. } | Pattern::Int { .. },
+        ) => match (int_pattern_bounds(pattern), int_pattern_bounds(other)) {
+            (Some((start, end)), Some((other_start, other_end))) => {
+                start <= other_start && other_end <= end
+            }
+            _ => false,
+        },
+
+        (Pattern::Float { value, .. }, Pattern::Float { value: other, .. }) => {
+            match (float_literal_value(value), float_literal_value(other)) {
+                (Some(value), Some(other)) => value == other,
+                _ => value == other,
+            }
+        }
+
+        (Pattern::String { value, .. }, Pattern::String { value: other, .. }) => value == other,
+
+        (
+            Pattern::Concatenate {
+                left_side_string: prefix,
+                ..
+            },
+            Pattern::String { value: other, .. }
+            | Pattern::Concatenate {
+                left_side_string: other,
+                ..
+            },
+        ) => other.starts_with(prefix.as_str()),
+
+        (Pattern::Tuple { elems, .. }, Pattern::Tuple { elems: other, .. }) => {
+            elems.len() == other.len()
+                && elems
+                    .iter()
+                    .zip(other)
+                    .all(|(pattern, other)| pattern_subsumes(pattern, other))
+        }
+
+        (
+            Pattern::Constructor {
+                constructor: PatternConstructor::Record { name, .. },
+                arguments,
+                ..
+            },
+            Pattern::Constructor {
+                constructor:
+                    PatternConstructor::Record {
+                        name: other_name, ..
+                    },
+                arguments: other_arguments,
+                ..
+            },
+        ) => {
+            name == other_name
+                && arguments.len() == other_arguments.len()
+                && arguments
+                    .iter()
+                    .zip(other_arguments)
+                    .all(|(pattern, other)| pattern_subsumes(&pattern.value, &other.value))
+        }
+
+        (
+            Pattern::List { elements, tail, .. },
+            Pattern::List {
+                elements: other_elements,
+                tail: other_tail,
+                ..
+            },
+        ) => {
+            let elements_subsume = elements.len() <= other_elements.len()
+                && elements
+                    .iter()
+                    .zip(other_elements)
+                    .all(|(pattern, other)| pattern_subsumes(pattern, other));
+            match tail {
+                // Without a tail the lists must be of exactly the same length
+                None => {
+                    elements_subsume
+                        && elements.len() == other_elements.len()
+                        && other_tail.is_none()
+                }
+                // A tail matches any remaining elements so long as it is a
+                // catch-all pattern
+                Some(tail) => elements_subsume && is_catch_all(tail),
+            }
+        }
+
+        _ => false,
+    }
+}
+
+fn record_access_index(access: &TypedExpr) -> u32 {
+    match access {
+        TypedExpr::RecordAccess { index, .. } => {
+            u32::try_from(*index).expect("Record field index should fit in a u32")
+        }
+        _ => panic!("Record update field was not inferred as a record access"),
+    }
+}
+
+fn is_catch_all(pattern: &TypedPattern) -> bool {
+    match pattern {
+        Pattern::Discard { .. } | Pattern::Var { .. } => true,
+        Pattern::Assign { pattern, .. } => is_catch_all(pattern),
+        _ => false,
+    }
+}
+
+/// The inclusive bounds of the values matched by an Int or range pattern.
+fn int_pattern_bounds(pattern: &TypedPattern) -> Option<(i128, i128)> {
+    match pattern {
+        Pattern::Int { value, .. } => int_literal_value(value).map(|value| (value, value)),
+        Pattern::IntRange { start, end, .. } => {
+            Some((int_literal_value(start)?, int_literal_value(end)?))
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn int_literal_value(value: &str) -> Option<i128> {
+    let value = value.replace('_', "");
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.as_str()),
+    };
+    let parsed = if let Some(digits) = value.strip_prefix("0x") {
+        i128::from_str_radix(digits, 16)
+    } else if let Some(digits) = value.strip_prefix("0o") {
+        i128::from_str_radix(digits, 8)
+    } else if let Some(digits) = value.strip_prefix("0b") {
+        i128::from_str_radix(digits, 2)
+    } else {
+        value.parse()
+    };
+    parsed
+        .ok()
+        .map(|value| if negative { -value } else { value })
+}
+
+pub(crate) fn check_float_literal(value: &str, location: SrcSpan) -> Result<(), Error> {
+    match crate::float::literal_value(value) {
+        Some(_) => Ok(()),
+        None => Err(Error::FloatOutOfRange { location }),
+    }
+}
+
+fn float_literal_value(value: &str) -> Option<f64> {
+    value.replace('_', "").parse().ok()
+}
+
+// The literal a constant refers to, following references to other constants.
+fn const_literal(constant: &TypedConstant) -> &TypedConstant {
+    match constant {
+        Constant::Var {
+            constructor: Some(constructor),
+            ..
+        } => match &constructor.variant {
+            ValueConstructorVariant::ModuleConstant { literal, .. } => const_literal(literal),
+            _ => constant,
+        },
+        _ => constant,
+    }
+}
+
+// A copy of a constant with all references to other constants replaced by
+// their values. Returns `None` if the value contains anything that would need
+// to be qualified with the module it was defined in, such as a custom type
+// record or a function.
+fn fold_constant(constant: &TypedConstant, location: SrcSpan) -> Option<TypedConstant> {
+    match const_literal(constant) {
+        Constant::Int { value, .. } => Some(Constant::Int {
+            location,
+            value: value.clone(),
+        }),
+        Constant::Float { value, .. } => Some(Constant::Float {
+            location,
+            value: value.clone(),
+        }),
+        Constant::String { value, .. } => Some(Constant::String {
+            location,
+            value: value.clone(),
+        }),
+        Constant::Tuple { elements, .. } => Some(Constant::Tuple {
+            location,
+            elements: elements
+                .iter()
+                .map(|element| fold_constant(element, location))
+                .collect::<Option<_>>()?,
+        }),
+        Constant::List { elements, typ, .. } => Some(Constant::List {
+            location,
+            elements: elements
+                .iter()
+                .map(|element| fold_constant(element, location))
+                .collect::<Option<_>>()?,
+            typ: typ.clone(),
+        }),
+        Constant::Record {
+            name,
+            args,
+            tag,
+            typ,
+            ..
+        } if args.is_empty() && (typ.is_bool() || typ.is_nil()) => Some(Constant::Record {
+            location,
+            module: None,
+            name: name.clone(),
+            args: vec![],
+            tag: tag.clone(),
+            typ: typ.clone(),
+            field_map: None,
+        }),
+        _ => None,
+    }
+}
+
+// Evaluates an operator applied to two constants that have already been type
+// checked. Division by zero gives zero, as it does at runtime. Returns `None`
+// if the result cannot be represented.
+fn evaluate_const_binop(
+    name: BinOp,
+    left: &TypedConstant,
+    right: &TypedConstant,
+    location: SrcSpan,
+) -> Option<TypedConstant> {
+    match (name, const_literal(left), const_literal(right)) {
+        (
+            BinOp::Concatenate,
+            Constant::String { value: left, .. },
+            Constant::String { value: right, .. },
+        ) => Some(Constant::String {
+            location,
+            value: format!("{left}{right}"),
+        }),
+
+        (
+            BinOp::AddFloat | BinOp::SubFloat | BinOp::MultFloat | BinOp::DivFloat,
+            Constant::Float { value: left, .. },
+            Constant::Float { value: right, .. },
+        ) => {
+            let left = float_literal_value(left)?;
+            let right = float_literal_value(right)?;
+            let value = match name {
+                BinOp::AddFloat => left + right,
+                BinOp::SubFloat => left - right,
+                BinOp::MultFloat => left * right,
+                _ if right == 0.0 => 0.0,
+                _ => left / right,
+            };
+            if !value.is_finite() {
+                return None;
+            }
+            Some(Constant::Float {
+                location,
+                value: crate::float::to_string(value),
+            })
+        }
+
+        (_, Constant::Int { value: left, .. }, Constant::Int { value: right, .. }) => {
+            let left = int_literal_value(left)?;
+            let right = int_literal_value(right)?;
+            let value = evaluate_int_binop(name, left, right)?;
+            Some(Constant::Int {
+                location,
+                value: value.to_string(),
+            })
+        }
+
+        _ => None,
+    }
+}
+
+// Evaluates an Int operator. Division by zero gives zero, as it does at
+// runtime. Returns `None` if the operator is not an Int operator or the result
+// cannot be represented.
+fn evaluate_int_binop(name: BinOp, left: i128, right: i128) -> Option<i128> {
+    match name {
+        BinOp::AddInt => left.checked_add(right),
+        BinOp::SubInt => left.checked_sub(right),
+        BinOp::MultInt => left.checked_mul(right),
+        BinOp::DivInt | BinOp::RemainderInt if right == 0 => Some(0),
+        BinOp::DivInt => left.checked_div(right),
+        BinOp::RemainderInt => left.checked_rem(right),
+        _ => None,
+    }
+}
+
+// The value of an Int expression made only of literals and arithmetic
+// operators, which is known at compile time.
+fn folded_int(expr: &TypedExpr) -> Option<i128> {
+    match expr {
+        TypedExpr::Int { value, .. } => int_literal_value(value),
+        TypedExpr::BinOp {
+            name, left, right, ..
+        } => evaluate_int_binop(*name, folded_int(left)?, folded_int(right)?),
+        _ => None,
+    }
 }
 
-struct UseCall {
+struct UseCall<'a> {
     location: SrcSpan,
-    function: Box<UntypedExpr>,
-    arguments: Vec<CallArg<UntypedExpr>>,
+    function: UntypedExpr<'a>,
+    arguments: Vec<CallArg<UntypedExpr<'a>>>,
 }
 
-fn get_use_expression_call(call: UntypedExpr) -> Result<UseCall, Error> {
+fn get_use_expression_call(call: UntypedExpr<'_>) -> Result<UseCall<'_>, Error> {
     // Ensure that the use's call is of the right structure. i.e. it is a
     // call to a function.
     match call {
@@ -2121,12 +3021,12 @@ fn get_use_expression_call(call: UntypedExpr) -> Result<UseCall, Error> {
         } => Ok(UseCall {
             location,
             arguments,
-            function,
+            function: ArenaBox::into_inner(function),
         }),
 
         other => Ok(UseCall {
             location: other.location(),
-            function: Box::new(other),
+            function: other,
             arguments: vec![],
         }),
     }
@@ -2140,6 +3040,7 @@ fn use_assignments_to_function_arguments(assignments: Vec<(AssignName, SrcSpan)>
             location,
             annotation: None,
             type_: (),
+            doc: None,
         })
         .collect()
 }
@@ -1,12 +1,12 @@
 use super::Error;
-use crate::ast::{CallArg, SrcSpan};
+use crate::ast::{CallArg, Name, SrcSpan};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FieldMap {
     pub arity: u32,
-    pub fields: HashMap<String, u32>,
+    pub fields: HashMap<Name, u32>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -21,7 +21,7 @@ impl FieldMap {
     }
 
     pub fn insert(&mut self, label: String, index: u32) -> Result<(), DuplicateField> {
-        match self.fields.insert(label, index) {
+        match self.fields.insert(label.into(), index) {
             Some(_) => Err(DuplicateField),
             None => Ok(()),
         }
@@ -90,7 +90,7 @@ impl FieldMap {
 
             let position = match self.fields.get(label) {
                 None => {
-                    unknown_labels.push((label.clone(), location));
+                    unknown_labels.push((label.to_string(), location));
                     i += 1;
                     continue;
                 }
@@ -121,7 +121,7 @@ impl FieldMap {
             Err(Error::UnknownLabels {
                 valid: self.fields.keys().map(|t| t.to_string()).collect(),
                 unknown: unknown_labels,
-                supplied: seen_labels.into_iter().collect(),
+                supplied: seen_labels.into_iter().map(String::from).collect(),
             })
         }
     }
@@ -134,6 +134,7 @@ impl FieldMap {
             .cloned()
             .filter(|f| !given.contains(f))
             .sorted()
+            .map(String::from)
             .collect()
     }
 }
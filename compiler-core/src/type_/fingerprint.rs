@@ -0,0 +1,320 @@
+//! A fingerprint of the interface of a module: the parts of it that the
+//! modules importing it depend upon.
+//!
+//! The modules that import a module only need to be analysed again when its
+//! interface changes, not when the body of one of its functions or one of its
+//! private definitions changes. Locations are left out of the interface and
+//! type variables are numbered in the order they appear, so compiling the same
+//! interface twice gives the same fingerprint.
+
+use super::{
+    AccessorsMap, FieldMap, Module, Type, TypeConstructor, TypeVar, ValueConstructor,
+    ValueConstructorVariant,
+};
+use crate::ast::{BitStringSegmentOption, Constant, Deprecation, TypedConstant};
+use itertools::Itertools;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+impl Module {
+    /// A fingerprint of the public types, values, and record accessors of the
+    /// module. Modules with the same fingerprint can be used interchangeably
+    /// by the modules that import them.
+    pub fn interface_fingerprint(&self) -> u64 {
+        let mut fingerprint = Fingerprint::default();
+        fingerprint.write(&self.name);
+
+        let types = self.types.iter().filter(|(_, type_)| type_.public);
+        for (name, type_) in types.sorted_by(|a, b| a.0.cmp(b.0)) {
+            fingerprint.start_item(name);
+            fingerprint.type_constructor(type_);
+            fingerprint.write(self.types_constructors.get(name));
+        }
+
+        let values = self.values.iter().filter(|(_, value)| value.public);
+        for (name, value) in values.sorted_by(|a, b| a.0.cmp(b.0)) {
+            fingerprint.start_item(name);
+            fingerprint.value(value);
+        }
+
+        let accessors = self
+            .accessors
+            .iter()
+            .filter(|(_, accessors)| accessors.public);
+        for (name, accessors) in accessors.sorted_by(|a, b| a.0.cmp(b.0)) {
+            fingerprint.start_item(name);
+            fingerprint.accessors(accessors);
+        }
+
+        fingerprint.hasher.finish()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Fingerprint {
+    hasher: DefaultHasher,
+    /// The number given to each type variable of the current item, in the
+    /// order they were first seen.
+    type_variables: HashMap<u64, usize>,
+}
+
+impl Fingerprint {
+    fn write(&mut self, value: impl Hash) {
+        value.hash(&mut self.hasher);
+    }
+
+    /// Type variables are not shared between the items of a module, so they
+    /// are numbered afresh for each one.
+    fn start_item(&mut self, name: &str) {
+        self.type_variables.clear();
+        self.write(name);
+    }
+
+    fn type_constructor(&mut self, constructor: &TypeConstructor) {
+        self.write(&constructor.module);
+        self.deprecation(&constructor.deprecation);
+        self.write(constructor.parameters.len());
+        for parameter in &constructor.parameters {
+            self.type_(parameter);
+        }
+        self.type_(&constructor.typ);
+    }
+
+    fn value(&mut self, value: &ValueConstructor) {
+        self.deprecation(&value.deprecation);
+        self.type_(&value.type_);
+        match &value.variant {
+            ValueConstructorVariant::LocalVariable { .. } => self.write("local variable"),
+
+            ValueConstructorVariant::ModuleConstant {
+                module, literal, ..
+            } => {
+                self.write("constant");
+                self.write(module);
+                // Constants are inlined where they are used, so their values
+                // are part of the interface.
+                self.constant(literal);
+            }
+
+            ValueConstructorVariant::ModuleFn {
+                name,
+                field_map,
+                module,
+                arity,
+                ..
+            } => {
+                self.write("function");
+                self.write(name);
+                self.write(module);
+                self.write(arity);
+                self.field_map(field_map.as_ref());
+            }
+
+            ValueConstructorVariant::Record {
+                name,
+                arity,
+                field_map,
+                module,
+                constructors_count,
+                erlang_map,
+                ..
+            } => {
+                self.write("record");
+                self.write(name);
+                self.write(module);
+                self.write(arity);
+                self.write(constructors_count);
+                self.write(erlang_map);
+                self.field_map(field_map.as_ref());
+            }
+        }
+    }
+
+    fn accessors(&mut self, accessors: &AccessorsMap) {
+        self.type_(&accessors.type_);
+        self.write(accessors.erlang_map);
+        let fields = accessors.accessors.iter().sorted_by(|a, b| a.0.cmp(b.0));
+        for (name, accessor) in fields {
+            self.write(name);
+            self.write(accessor.index);
+            self.write(&accessor.label);
+            self.type_(&accessor.type_);
+        }
+    }
+
+    fn field_map(&mut self, field_map: Option<&FieldMap>) {
+        let field_map = match field_map {
+            Some(field_map) => field_map,
+            None => return self.write("no fields"),
+        };
+        self.write(field_map.arity);
+        for field in field_map.fields.iter().sorted() {
+            self.write(field);
+        }
+    }
+
+    fn deprecation(&mut self, deprecation: &Deprecation) {
+        match deprecation {
+            Deprecation::NotDeprecated => self.write("not deprecated"),
+            Deprecation::Deprecated { message } => {
+                self.write("deprecated");
+                self.write(message);
+            }
+        }
+    }
+
+    fn constant(&mut self, constant: &TypedConstant) {
+        match constant {
+            Constant::Int { value, .. } => {
+                self.write("int");
+                self.write(value);
+            }
+
+            Constant::Float { value, .. } => {
+                self.write("float");
+                self.write(value);
+            }
+
+            Constant::String { value, .. } => {
+                self.write("string");
+                self.write(value);
+            }
+
+            Constant::Tuple { elements, .. } => {
+                self.write("tuple");
+                self.write(elements.len());
+                for element in elements {
+                    self.constant(element);
+                }
+            }
+
+            Constant::List { elements, typ, .. } => {
+                self.write("list");
+                self.type_(typ);
+                self.write(elements.len());
+                for element in elements {
+                    self.constant(element);
+                }
+            }
+
+            Constant::Record {
+                module,
+                name,
+                args,
+                tag,
+                typ,
+                field_map,
+                ..
+            } => {
+                self.write("record");
+                self.write(module);
+                self.write(name);
+                self.write(tag);
+                self.type_(typ);
+                self.field_map(field_map.as_ref());
+                self.write(args.len());
+                for arg in args {
+                    self.write(&arg.label);
+                    self.constant(&arg.value);
+                }
+            }
+
+            Constant::BitString { segments, .. } => {
+                self.write("bit string");
+                self.write(segments.len());
+                for segment in segments {
+                    self.constant(&segment.value);
+                    self.write(segment.options.len());
+                    for option in &segment.options {
+                        self.write(option.label());
+                        if let Some(value) = option.value() {
+                            self.constant(value);
+                        }
+                        if let BitStringSegmentOption::Unit { value, .. } = option {
+                            self.write(value);
+                        }
+                    }
+                }
+            }
+
+            Constant::Var {
+                module,
+                name,
+                constructor,
+                typ,
+                ..
+            } => {
+                self.write("variable");
+                self.write(module);
+                self.write(name);
+                self.type_(typ);
+                // The constructor may be a private constant, whose value is
+                // inlined in place of the variable.
+                if let Some(constructor) = constructor {
+                    self.value(constructor);
+                }
+            }
+
+            Constant::BinOp { .. } => {
+                unreachable!("Constant binary operators are evaluated during type checking")
+            }
+        }
+    }
+
+    fn type_(&mut self, type_: &Type) {
+        match type_ {
+            Type::App {
+                public,
+                module,
+                name,
+                args,
+            } => {
+                self.write("app");
+                self.write(public);
+                self.write(module);
+                self.write(name);
+                self.write(args.len());
+                for arg in args {
+                    self.type_(arg);
+                }
+            }
+
+            Type::Fn { args, retrn } => {
+                self.write("fn");
+                self.write(args.len());
+                for arg in args {
+                    self.type_(arg);
+                }
+                self.type_(retrn);
+            }
+
+            Type::Tuple { elems } => {
+                self.write("tuple");
+                self.write(elems.len());
+                for elem in elems {
+                    self.type_(elem);
+                }
+            }
+
+            Type::Var { type_ } => match &*type_.borrow() {
+                TypeVar::Link { type_ } => self.type_(type_),
+                TypeVar::Unbound { id } => {
+                    self.write("unbound");
+                    self.type_variable(*id);
+                }
+                TypeVar::Generic { id } => {
+                    self.write("generic");
+                    self.type_variable(*id);
+                }
+            },
+        }
+    }
+
+    fn type_variable(&mut self, id: u64) {
+        let next = self.type_variables.len();
+        let number = *self.type_variables.entry(id).or_insert(next);
+        self.write(number);
+    }
+}
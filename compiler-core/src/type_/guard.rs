@@ -0,0 +1,154 @@
+//! Functions that can be called in case clause guards.
+//!
+//! A function can be called in a guard if it is defined in the same module
+//! and its body is a single expression built only from operations that are
+//! valid in a guard, so that targets which cannot call functions in guards,
+//! such as Erlang, can inline the body in place of the call.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{ValueConstructor, ValueConstructorVariant};
+use crate::ast::{BinOp, Name, Statement, TypedArg, TypedExpr, TypedStatement};
+
+#[derive(Debug, Clone, Copy)]
+pub struct GuardFunction<'a> {
+    pub arguments: &'a [TypedArg],
+    pub body: &'a TypedExpr,
+}
+
+#[derive(Debug, Default)]
+pub struct GuardFunctions<'a> {
+    functions: HashMap<&'a str, GuardFunction<'a>>,
+}
+
+impl<'a> GuardFunctions<'a> {
+    pub fn new(
+        module: &'a [Name],
+        statements: impl IntoIterator<Item = &'a TypedStatement>,
+    ) -> Self {
+        let candidates: HashMap<_, _> = statements
+            .into_iter()
+            .filter_map(|statement| match statement {
+                Statement::Fn {
+                    name,
+                    arguments,
+                    body,
+                    ..
+                } => Some((name.as_str(), GuardFunction { arguments, body })),
+                _ => None,
+            })
+            .collect();
+
+        let mut checker = Checker {
+            module,
+            candidates: &candidates,
+            safe: HashMap::new(),
+            visiting: HashSet::new(),
+        };
+        let safe: HashSet<_> = candidates
+            .keys()
+            .filter(|name| checker.is_safe_function(name))
+            .copied()
+            .collect();
+
+        let functions = candidates
+            .into_iter()
+            .filter(|(name, _)| safe.contains(name))
+            .collect();
+        Self { functions }
+    }
+
+    pub fn get(&self, name: &str) -> Option<GuardFunction<'a>> {
+        self.functions.get(name).copied()
+    }
+}
+
+struct Checker<'a, 'b> {
+    module: &'a [Name],
+    candidates: &'b HashMap<&'a str, GuardFunction<'a>>,
+    safe: HashMap<&'a str, bool>,
+    // Functions currently being checked, used to reject recursion, which
+    // could not be inlined.
+    visiting: HashSet<&'a str>,
+}
+
+impl<'a, 'b> Checker<'a, 'b> {
+    fn is_safe_function(&mut self, name: &'a str) -> bool {
+        if let Some(safe) = self.safe.get(name) {
+            return *safe;
+        }
+        if !self.visiting.insert(name) {
+            return false;
+        }
+        let safe = match self.candidates.get(name) {
+            Some(function) => self.is_safe_expression(function.body),
+            None => false,
+        };
+        let _ = self.visiting.remove(name);
+        let _ = self.safe.insert(name, safe);
+        safe
+    }
+
+    fn is_safe_expression(&mut self, expression: &'a TypedExpr) -> bool {
+        match expression {
+            TypedExpr::Int { .. }
+            | TypedExpr::Float { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::Cfg { .. } => true,
+
+            // The body is a single expression so the only local variables
+            // are the function's arguments.
+            TypedExpr::Var { constructor, .. } => matches!(
+                constructor.variant,
+                ValueConstructorVariant::LocalVariable { .. }
+            ),
+
+            TypedExpr::Sequence { expressions, .. } => match expressions.as_slice() {
+                [expression] => self.is_safe_expression(expression),
+                _ => false,
+            },
+
+            TypedExpr::BinOp {
+                name, left, right, ..
+            } => {
+                is_safe_operator(*name)
+                    && self.is_safe_expression(left)
+                    && self.is_safe_expression(right)
+            }
+
+            TypedExpr::Negate { value, .. } => self.is_safe_expression(value),
+
+            TypedExpr::TupleIndex { tuple, .. } => self.is_safe_expression(tuple),
+
+            TypedExpr::RecordAccess {
+                record, variants, ..
+            } => variants.is_empty() && self.is_safe_expression(record),
+
+            TypedExpr::Call { fun, args, .. } => match fun.as_ref() {
+                TypedExpr::Var {
+                    constructor:
+                        ValueConstructor {
+                            variant: ValueConstructorVariant::ModuleFn { module, name, .. },
+                            ..
+                        },
+                    ..
+                } if module.as_slice() == self.module => {
+                    self.is_safe_function(name)
+                        && args.iter().all(|arg| self.is_safe_expression(&arg.value))
+                }
+                _ => false,
+            },
+
+            _ => false,
+        }
+    }
+}
+
+// Division is not safe as it needs a check for a zero divisor, and strings
+// cannot be concatenated in Erlang guards.
+fn is_safe_operator(operator: BinOp) -> bool {
+    !matches!(
+        operator,
+        BinOp::DivInt | BinOp::DivFloat | BinOp::RemainderInt | BinOp::Concatenate
+    )
+}
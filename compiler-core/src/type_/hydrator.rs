@@ -18,7 +18,7 @@ use im::hashmap;
 ///
 #[derive(Debug)]
 pub struct Hydrator {
-    created_type_variables: im::HashMap<String, Arc<Type>>,
+    created_type_variables: im::HashMap<Name, Arc<Type>>,
     /// A rigid type is a generic type that was specified as being generic in
     /// an annotation. As such it should never be instantiated into an unbound
     /// variable. This type_id => name map is used for reporting the original
@@ -26,11 +26,14 @@ pub struct Hydrator {
     rigid_type_names: im::HashMap<u64, String>,
     permit_new_type_variables: bool,
     permit_holes: bool,
+    /// The type variables created for each type hole, so that the type
+    /// inferred for them can be reported once inference is complete.
+    hole_types: Vec<(SrcSpan, Arc<Type>)>,
 }
 
 #[derive(Debug)]
 pub struct ScopeResetData {
-    created_type_variables: im::HashMap<String, Arc<Type>>,
+    created_type_variables: im::HashMap<Name, Arc<Type>>,
     rigid_type_names: im::HashMap<u64, String>,
 }
 
@@ -47,6 +50,7 @@ impl Hydrator {
             rigid_type_names: hashmap![],
             permit_new_type_variables: true,
             permit_holes: false,
+            hole_types: vec![],
         }
     }
 
@@ -72,6 +76,12 @@ impl Hydrator {
         self.permit_holes = flag
     }
 
+    /// Returns the type holes encountered so far along with their types,
+    /// leaving none recorded.
+    pub fn take_hole_types(&mut self) -> Vec<(SrcSpan, Arc<Type>)> {
+        std::mem::take(&mut self.hole_types)
+    }
+
     /// A rigid type is a generic type that was specified as being generic in
     /// an annotation. As such it should never be instantiated into an unbound
     /// variable.
@@ -119,11 +129,13 @@ impl Hydrator {
                 let TypeConstructor {
                     parameters,
                     typ: return_type,
+                    deprecation,
                     ..
                 } = environment
                     .get_type_constructor(module, name)
                     .map_err(|e| convert_get_type_constructor_error(e, location))?
                     .clone();
+                environment.warn_if_deprecated(&deprecation, *location, Layer::Type);
 
                 // Register the type constructor as being used if it is unqualifed.
                 // We do not track use of qualified type constructors as they may be
@@ -190,7 +202,7 @@ impl Hydrator {
                     let var = environment.new_generic_var();
                     let _ = self
                         .rigid_type_names
-                        .insert(environment.previous_uid(), name.clone());
+                        .insert(environment.previous_uid(), name.to_string());
                     let _ = self
                         .created_type_variables
                         .insert(name.clone(), var.clone());
@@ -208,7 +220,11 @@ impl Hydrator {
                 }),
             },
 
-            TypeAst::Hole { .. } if self.permit_holes => Ok(environment.new_unbound_var()),
+            TypeAst::Hole { location, .. } if self.permit_holes => {
+                let var = environment.new_unbound_var();
+                self.hole_types.push((*location, var.clone()));
+                Ok(var)
+            }
 
             TypeAst::Hole { location, .. } => Err(Error::UnexpectedTypeHole {
                 location: *location,
@@ -1,6 +1,7 @@
 use im::hashmap;
 use itertools::Itertools;
 
+use super::expression::{check_float_literal, int_literal_value};
 ///! Type inference and checking of patterns used in case expressions
 ///! and variables bindings.
 ///!
@@ -13,6 +14,9 @@ pub struct PatternTyper<'a, 'b> {
     hydrator: &'a Hydrator,
     mode: PatternMode,
     initial_pattern_vars: HashSet<String>,
+    /// Range patterns are compiled to guards, so they are only allowed in
+    /// case clauses, which are the only patterns typed as multi-patterns.
+    int_ranges_allowed: bool,
 }
 
 enum PatternMode {
@@ -27,6 +31,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
             hydrator,
             mode: PatternMode::Initial,
             initial_pattern_vars: HashSet::new(),
+            int_ranges_allowed: false,
         }
     }
 
@@ -40,7 +45,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
             PatternMode::Initial => {
                 // Register usage for the unused variable detection
                 self.environment
-                    .init_usage(name.to_string(), EntityKind::Variable, location);
+                    .init_usage(name.into(), EntityKind::Variable, location);
                 // Ensure there are no duplicate variable names in the pattern
                 if self.initial_pattern_vars.contains(name) {
                     return Err(UnifyError::DuplicateVarInPattern {
@@ -54,7 +59,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                 // And now insert the variable for use in the code that comes
                 // after the pattern.
                 self.environment
-                    .insert_local_variable(name.to_string(), location, typ);
+                    .insert_local_variable(name.into(), location, typ);
                 Ok(())
             }
 
@@ -122,6 +127,8 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
             });
         }
 
+        self.int_ranges_allowed = true;
+
         // Unify each pattern in the multi-pattern with the corresponding subject
         let mut typed_multi = Vec::with_capacity(multi_pattern.len());
         for (pattern, subject_type) in multi_pattern.into_iter().zip(subjects) {
@@ -281,11 +288,38 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
 
             Pattern::Int { location, value } => {
                 unify(type_, int()).map_err(|e| convert_unify_error(e, location))?;
+                self.environment
+                    .check_int_range(int_literal_value(&value), location);
                 Ok(Pattern::Int { location, value })
             }
 
+            Pattern::IntRange {
+                location,
+                start,
+                end,
+            } => {
+                if !self.int_ranges_allowed {
+                    return Err(Error::IntRangeOutsideCase { location });
+                }
+                unify(type_, int()).map_err(|e| convert_unify_error(e, location))?;
+                let (start_value, end_value) = (int_literal_value(&start), int_literal_value(&end));
+                self.environment.check_int_range(start_value, location);
+                self.environment.check_int_range(end_value, location);
+                if let (Some(start_value), Some(end_value)) = (start_value, end_value) {
+                    if start_value > end_value {
+                        return Err(Error::EmptyIntRange { location });
+                    }
+                }
+                Ok(Pattern::IntRange {
+                    location,
+                    start,
+                    end,
+                })
+            }
+
             Pattern::Float { location, value } => {
                 unify(type_, float()).map_err(|e| convert_unify_error(e, location))?;
+                check_float_literal(&value, location)?;
                 Ok(Pattern::Float { location, value })
             }
 
@@ -397,7 +431,10 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                 let cons = self
                     .environment
                     .get_value_constructor(module.as_ref(), &name)
-                    .map_err(|e| convert_get_value_constructor_error(e, location))?;
+                    .map_err(|e| convert_get_value_constructor_error(e, location))?
+                    .clone();
+                self.environment
+                    .warn_if_deprecated(&cons.deprecation, location, Layer::Value);
 
                 match cons.field_map() {
                     // The fun has a field map so labelled arguments may be present and need to be reordered.
@@ -435,7 +472,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                             while pattern_args.len() < field_map.arity as usize {
                                 let new_call_arg = CallArg {
                                     value: Pattern::Discard {
-                                        name: "_".to_string(),
+                                        name: "_".into(),
                                         location: spread_location,
                                     },
                                     location: spread_location,
@@ -456,12 +493,15 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
 
                 let constructor_typ = cons.type_.clone();
                 let constructor = match cons.variant {
-                    ValueConstructorVariant::Record { ref name, .. } => {
-                        PatternConstructor::Record {
-                            name: name.clone(),
-                            field_map: cons.field_map().cloned(),
-                        }
-                    }
+                    ValueConstructorVariant::Record {
+                        ref name,
+                        erlang_map,
+                        ..
+                    } => PatternConstructor::Record {
+                        name: name.clone(),
+                        field_map: cons.field_map().cloned(),
+                        erlang_map,
+                    },
                     ValueConstructorVariant::LocalVariable { .. }
                     | ValueConstructorVariant::ModuleConstant { .. }
                     | ValueConstructorVariant::ModuleFn { .. } => {
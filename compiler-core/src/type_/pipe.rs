@@ -1,5 +1,5 @@
 use super::*;
-use crate::ast::{AssignmentKind, UntypedExpr, PIPE_VARIABLE};
+use crate::ast::{ArenaBox, AssignmentKind, UntypedExpr, PIPE_VARIABLE};
 use vec1::Vec1;
 
 #[derive(Debug)]
@@ -15,7 +15,7 @@ pub(crate) struct PipeTyper<'a, 'b, 'c> {
 impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
     pub fn infer(
         expr_typer: &'a mut ExprTyper<'b, 'c>,
-        expressions: Vec1<UntypedExpr>,
+        expressions: Vec1<UntypedExpr<'_>>,
     ) -> Result<TypedExpr, Error> {
         let size = expressions.len();
         let end = &expressions[..]
@@ -43,9 +43,9 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
         typer.infer_expressions(expressions)
     }
 
-    fn infer_expressions(
+    fn infer_expressions<'e>(
         mut self,
-        expressions: impl IntoIterator<Item = UntypedExpr>,
+        expressions: impl IntoIterator<Item = UntypedExpr<'e>>,
     ) -> Result<TypedExpr, Error> {
         let result = self.infer_each_expression(expressions);
 
@@ -61,9 +61,9 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
         })
     }
 
-    fn infer_each_expression(
+    fn infer_each_expression<'e>(
         &mut self,
-        expressions: impl IntoIterator<Item = UntypedExpr>,
+        expressions: impl IntoIterator<Item = UntypedExpr<'e>>,
     ) -> Result<(), Error> {
         for (i, call) in expressions.into_iter().enumerate() {
             let call = match call {
@@ -74,7 +74,7 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
                     location,
                     ..
                 } => {
-                    let fun = self.expr_typer.infer(*fun)?;
+                    let fun = self.expr_typer.infer(ArenaBox::into_inner(fun))?;
                     match fun.type_().fn_arity() {
                         // Rewrite as right(left, ..args)
                         Some(arity) if arity == arguments.len() + 1 => {
@@ -113,7 +113,7 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
 
     /// Create a call argument that can be used to refer to the value on the
     /// left hand side of the pipe
-    fn untyped_left_hand_value_variable_call_argument(&self) -> CallArg<UntypedExpr> {
+    fn untyped_left_hand_value_variable_call_argument<'e>(&self) -> CallArg<UntypedExpr<'e>> {
         CallArg {
             label: None,
             location: self.argument_location,
@@ -129,9 +129,10 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
     fn typed_left_hand_value_variable(&self) -> TypedExpr {
         TypedExpr::Var {
             location: self.argument_location,
-            name: PIPE_VARIABLE.to_string(),
+            name: PIPE_VARIABLE.into(),
             constructor: ValueConstructor {
                 public: true,
+                deprecation: Deprecation::NotDeprecated,
                 type_: self.argument_type.clone(),
                 variant: ValueConstructorVariant::LocalVariable {
                     location: self.argument_location,
@@ -142,10 +143,10 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
 
     /// Create a variable that can be used to refer to the value on the left
     /// hand side of the pipe
-    fn untyped_left_hand_value_variable(&self) -> UntypedExpr {
+    fn untyped_left_hand_value_variable<'e>(&self) -> UntypedExpr<'e> {
         UntypedExpr::Var {
             location: self.argument_location,
-            name: PIPE_VARIABLE.to_string(),
+            name: PIPE_VARIABLE.into(),
         }
     }
 
@@ -160,7 +161,7 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
         let location = expression.location();
         // Insert the variable for use in type checking the rest of the pipeline
         self.expr_typer.environment.insert_local_variable(
-            PIPE_VARIABLE.to_string(),
+            PIPE_VARIABLE.into(),
             location,
             expression.type_(),
         );
@@ -172,8 +173,10 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
             value: Box::new(expression),
             pattern: Pattern::Var {
                 location,
-                name: PIPE_VARIABLE.to_string(),
+                name: PIPE_VARIABLE.into(),
             },
+            annotation: None,
+            message: None,
         };
         self.expressions.push(assignment);
     }
@@ -182,7 +185,7 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
     fn infer_apply_to_call_pipe(
         &mut self,
         function: TypedExpr,
-        args: Vec<CallArg<UntypedExpr>>,
+        args: Vec<CallArg<UntypedExpr<'_>>>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
         let (function, args, typ) = self
@@ -215,7 +218,7 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
     fn infer_insert_pipe(
         &mut self,
         function: TypedExpr,
-        mut arguments: Vec<CallArg<UntypedExpr>>,
+        mut arguments: Vec<CallArg<UntypedExpr<'_>>>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
         arguments.insert(0, self.untyped_left_hand_value_variable_call_argument());
@@ -236,7 +239,7 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
     }
 
     /// Attempt to infer a |> b as b(a)
-    fn infer_apply_pipe(&mut self, function: UntypedExpr) -> Result<TypedExpr, Error> {
+    fn infer_apply_pipe(&mut self, function: UntypedExpr<'_>) -> Result<TypedExpr, Error> {
         let function = Box::new(self.expr_typer.infer(function)?);
         let return_type = self.expr_typer.new_unbound_var();
         // Ensure that the function accepts one argument of the correct type
@@ -1,10 +1,18 @@
-use crate::{ast::SrcSpan, build::Origin, uid::UniqueIdGenerator};
-
-use super::{Module, Type, TypeConstructor, TypeVar, ValueConstructor, ValueConstructorVariant};
-use std::{cell::RefCell, collections::HashMap, sync::Arc};
+use crate::{
+    ast::{Deprecation, SrcSpan},
+    build::Origin,
+    uid::UniqueIdGenerator,
+};
+
+use super::{
+    AccessorsMap, FieldMap, Module, RecordAccessor, Type, TypeConstructor, TypeVar, TypeVarCell,
+    ValueConstructor, ValueConstructorVariant,
+};
+use std::{collections::HashMap, sync::Arc};
 
 const BIT_STRING: &str = "BitString";
 const BOOL: &str = "Bool";
+const CRASH_REASON: &str = "CrashReason";
 const FLOAT: &str = "Float";
 const INT: &str = "Int";
 const LIST: &str = "List";
@@ -13,10 +21,12 @@ const RESULT: &str = "Result";
 const STRING: &str = "String";
 const UTF_CODEPOINT: &str = "UtfCodepoint";
 
+pub const RESCUE: &str = "rescue";
+
 pub fn int() -> Arc<Type> {
     Arc::new(Type::App {
         public: true,
-        name: INT.to_string(),
+        name: INT.into(),
         module: vec![],
         args: vec![],
     })
@@ -26,7 +36,7 @@ pub fn float() -> Arc<Type> {
     Arc::new(Type::App {
         args: vec![],
         public: true,
-        name: FLOAT.to_string(),
+        name: FLOAT.into(),
         module: vec![],
     })
 }
@@ -35,7 +45,7 @@ pub fn bool() -> Arc<Type> {
     Arc::new(Type::App {
         args: vec![],
         public: true,
-        name: BOOL.to_string(),
+        name: BOOL.into(),
         module: vec![],
     })
 }
@@ -44,7 +54,7 @@ pub fn string() -> Arc<Type> {
     Arc::new(Type::App {
         args: vec![],
         public: true,
-        name: STRING.to_string(),
+        name: STRING.into(),
         module: vec![],
     })
 }
@@ -53,7 +63,7 @@ pub fn nil() -> Arc<Type> {
     Arc::new(Type::App {
         args: vec![],
         public: true,
-        name: NIL.to_string(),
+        name: NIL.into(),
         module: vec![],
     })
 }
@@ -61,7 +71,7 @@ pub fn nil() -> Arc<Type> {
 pub fn list(t: Arc<Type>) -> Arc<Type> {
     Arc::new(Type::App {
         public: true,
-        name: LIST.to_string(),
+        name: LIST.into(),
         module: vec![],
         args: vec![t],
     })
@@ -70,7 +80,7 @@ pub fn list(t: Arc<Type>) -> Arc<Type> {
 pub fn result(a: Arc<Type>, e: Arc<Type>) -> Arc<Type> {
     Arc::new(Type::App {
         public: true,
-        name: RESULT.to_string(),
+        name: RESULT.into(),
         module: vec![],
         args: vec![a, e],
     })
@@ -88,7 +98,16 @@ pub fn bit_string() -> Arc<Type> {
     Arc::new(Type::App {
         args: vec![],
         public: true,
-        name: BIT_STRING.to_string(),
+        name: BIT_STRING.into(),
+        module: vec![],
+    })
+}
+
+pub fn crash_reason() -> Arc<Type> {
+    Arc::new(Type::App {
+        args: vec![],
+        public: true,
+        name: CRASH_REASON.into(),
         module: vec![],
     })
 }
@@ -97,39 +116,40 @@ pub fn utf_codepoint() -> Arc<Type> {
     Arc::new(Type::App {
         args: vec![],
         public: true,
-        name: UTF_CODEPOINT.to_string(),
+        name: UTF_CODEPOINT.into(),
         module: vec![],
     })
 }
 
 pub fn generic_var(id: u64) -> Arc<Type> {
     Arc::new(Type::Var {
-        type_: Arc::new(RefCell::new(TypeVar::Generic { id })),
+        type_: Arc::new(TypeVarCell::new(TypeVar::Generic { id })),
     })
 }
 
 pub fn unbound_var(id: u64) -> Arc<Type> {
     Arc::new(Type::Var {
-        type_: Arc::new(RefCell::new(TypeVar::Unbound { id })),
+        type_: Arc::new(TypeVarCell::new(TypeVar::Unbound { id })),
     })
 }
 
 #[cfg(test)]
 pub fn link(type_: Arc<Type>) -> Arc<Type> {
     Arc::new(Type::Var {
-        type_: Arc::new(RefCell::new(TypeVar::Link { type_ })),
+        type_: Arc::new(TypeVarCell::new(TypeVar::Link { type_ })),
     })
 }
 
 pub fn build_prelude(ids: &UniqueIdGenerator) -> Module {
     let value = |variant, type_| ValueConstructor {
         public: true,
+        deprecation: Deprecation::NotDeprecated,
         variant,
         type_,
     };
 
     let mut prelude = Module {
-        name: vec!["gleam".to_string()],
+        name: vec!["gleam".into()],
         package: "".to_string(),
         origin: Origin::Src,
         types: HashMap::new(),
@@ -139,171 +159,182 @@ pub fn build_prelude(ids: &UniqueIdGenerator) -> Module {
     };
 
     let _ = prelude.types.insert(
-        INT.to_string(),
+        INT.into(),
         TypeConstructor {
             parameters: vec![],
             typ: int(),
             origin: Default::default(),
             module: vec![],
             public: true,
+            deprecation: Deprecation::NotDeprecated,
         },
     );
 
-    let _ = prelude.types_constructors.insert(
-        BOOL.to_string(),
-        vec!["True".to_string(), "False".to_string()],
-    );
+    let _ = prelude
+        .types_constructors
+        .insert(BOOL.into(), vec!["True".into(), "False".into()]);
 
     let _ = prelude.values.insert(
-        "True".to_string(),
+        "True".into(),
         value(
             ValueConstructorVariant::Record {
                 module: "".into(),
-                name: "True".to_string(),
+                name: "True".into(),
                 field_map: None,
                 arity: 0,
                 location: SrcSpan::default(),
                 constructors_count: 2,
+                erlang_map: false,
             },
             bool(),
         ),
     );
     let _ = prelude.values.insert(
-        "False".to_string(),
+        "False".into(),
         value(
             ValueConstructorVariant::Record {
                 module: "".into(),
-                name: "False".to_string(),
+                name: "False".into(),
                 field_map: None,
                 arity: 0,
                 location: SrcSpan::default(),
                 constructors_count: 2,
+                erlang_map: false,
             },
             bool(),
         ),
     );
     let _ = prelude.types.insert(
-        BOOL.to_string(),
+        BOOL.into(),
         TypeConstructor {
             origin: Default::default(),
             parameters: vec![],
             typ: bool(),
             module: vec![],
             public: true,
+            deprecation: Deprecation::NotDeprecated,
         },
     );
 
     let list_parameter = generic_var(ids.next());
     let _ = prelude.types.insert(
-        LIST.to_string(),
+        LIST.into(),
         TypeConstructor {
             origin: Default::default(),
             parameters: vec![list_parameter.clone()],
             typ: list(list_parameter),
             module: vec![],
             public: true,
+            deprecation: Deprecation::NotDeprecated,
         },
     );
 
     let _ = prelude.types.insert(
-        FLOAT.to_string(),
+        FLOAT.into(),
         TypeConstructor {
             origin: Default::default(),
             parameters: vec![],
             typ: float(),
             module: vec![],
             public: true,
+            deprecation: Deprecation::NotDeprecated,
         },
     );
 
     let _ = prelude.types.insert(
-        STRING.to_string(),
+        STRING.into(),
         TypeConstructor {
             origin: Default::default(),
             parameters: vec![],
             typ: string(),
             module: vec![],
             public: true,
+            deprecation: Deprecation::NotDeprecated,
         },
     );
 
     let result_value = generic_var(ids.next());
     let result_error = generic_var(ids.next());
     let _ = prelude.types.insert(
-        RESULT.to_string(),
+        RESULT.into(),
         TypeConstructor {
             origin: Default::default(),
             parameters: vec![result_value.clone(), result_error.clone()],
             typ: result(result_value, result_error),
             module: vec![],
             public: true,
+            deprecation: Deprecation::NotDeprecated,
         },
     );
 
-    let _ = prelude.types_constructors.insert(
-        RESULT.to_string(),
-        vec!["Ok".to_string(), "Error".to_string()],
-    );
+    let _ = prelude
+        .types_constructors
+        .insert(RESULT.into(), vec!["Ok".into(), "Error".into()]);
 
     let _ = prelude.values.insert(
-        NIL.to_string(),
+        NIL.into(),
         value(
             ValueConstructorVariant::Record {
                 module: "".into(),
-                name: NIL.to_string(),
+                name: NIL.into(),
                 arity: 0,
                 field_map: None,
                 location: SrcSpan::default(),
                 constructors_count: 1,
+                erlang_map: false,
             },
             nil(),
         ),
     );
     let _ = prelude.types.insert(
-        NIL.to_string(),
+        NIL.into(),
         TypeConstructor {
             origin: Default::default(),
             parameters: vec![],
             typ: nil(),
             module: vec![],
             public: true,
+            deprecation: Deprecation::NotDeprecated,
         },
     );
 
     let _ = prelude.types.insert(
-        "BitString".to_string(),
+        "BitString".into(),
         TypeConstructor {
             origin: Default::default(),
             parameters: vec![],
             typ: bit_string(),
             module: vec![],
             public: true,
+            deprecation: Deprecation::NotDeprecated,
         },
     );
 
     let _ = prelude.types.insert(
-        UTF_CODEPOINT.to_string(),
+        UTF_CODEPOINT.into(),
         TypeConstructor {
             origin: Default::default(),
             parameters: vec![],
             typ: utf_codepoint(),
             module: vec![],
             public: true,
+            deprecation: Deprecation::NotDeprecated,
         },
     );
 
     let ok = generic_var(ids.next());
     let error = generic_var(ids.next());
     let _ = prelude.values.insert(
-        "Ok".to_string(),
+        "Ok".into(),
         value(
             ValueConstructorVariant::Record {
                 module: "".into(),
-                name: "Ok".to_string(),
+                name: "Ok".into(),
                 field_map: None,
                 arity: 1,
                 location: SrcSpan::default(),
                 constructors_count: 2,
+                erlang_map: false,
             },
             fn_(vec![ok.clone()], result(ok, error)),
         ),
@@ -312,19 +343,93 @@ pub fn build_prelude(ids: &UniqueIdGenerator) -> Module {
     let ok = generic_var(ids.next());
     let error = generic_var(ids.next());
     let _ = prelude.values.insert(
-        "Error".to_string(),
+        "Error".into(),
         value(
             ValueConstructorVariant::Record {
                 module: "".into(),
-                name: "Error".to_string(),
+                name: "Error".into(),
                 field_map: None,
                 arity: 1,
                 location: SrcSpan::default(),
                 constructors_count: 2,
+                erlang_map: false,
             },
             fn_(vec![error.clone()], result(ok, error)),
         ),
     );
 
+    // The reason a function called with `rescue` crashed, holding the message
+    // of the panic or exception.
+    let _ = prelude.types.insert(
+        CRASH_REASON.into(),
+        TypeConstructor {
+            origin: Default::default(),
+            parameters: vec![],
+            typ: crash_reason(),
+            module: vec![],
+            public: true,
+            deprecation: Deprecation::NotDeprecated,
+        },
+    );
+    let _ = prelude
+        .types_constructors
+        .insert(CRASH_REASON.into(), vec![CRASH_REASON.into()]);
+    let mut crash_reason_fields = FieldMap::new(1);
+    crash_reason_fields
+        .insert("message".into(), 0)
+        .expect("CrashReason fields are unique");
+    let _ = prelude.values.insert(
+        CRASH_REASON.into(),
+        value(
+            ValueConstructorVariant::Record {
+                module: "".into(),
+                name: CRASH_REASON.into(),
+                field_map: Some(crash_reason_fields),
+                arity: 1,
+                location: SrcSpan::default(),
+                constructors_count: 1,
+                erlang_map: false,
+            },
+            fn_(vec![string()], crash_reason()),
+        ),
+    );
+    let _ = prelude.accessors.insert(
+        CRASH_REASON.into(),
+        AccessorsMap {
+            public: true,
+            type_: crash_reason(),
+            accessors: [(
+                "message".into(),
+                RecordAccessor {
+                    index: 0,
+                    label: "message".into(),
+                    type_: string(),
+                },
+            )]
+            .into(),
+            erlang_map: false,
+        },
+    );
+
+    // Calls a function, returning any crash as an error rather than letting
+    // it propagate. Each target generates this itself.
+    let rescued = generic_var(ids.next());
+    let _ = prelude.values.insert(
+        RESCUE.into(),
+        value(
+            ValueConstructorVariant::ModuleFn {
+                name: RESCUE.into(),
+                field_map: None,
+                module: vec!["gleam".into()],
+                arity: 1,
+                location: SrcSpan::default(),
+            },
+            fn_(
+                vec![fn_(vec![], rescued.clone())],
+                result(rescued, crash_reason()),
+            ),
+        ),
+    );
+
     prelude
 }
@@ -1,6 +1,7 @@
 use super::{Type, TypeVar};
 use crate::{
     docvec,
+    name::Name,
     pretty::{nil, *},
 };
 use itertools::Itertools;
@@ -8,8 +9,6 @@ use std::sync::Arc;
 
 #[cfg(test)]
 use super::*;
-#[cfg(test)]
-use std::cell::RefCell;
 
 #[cfg(test)]
 use pretty_assertions::assert_eq;
@@ -22,7 +21,7 @@ pub struct Printer {
     names: im::HashMap<u64, String>,
     uid: u64,
     // A mapping of printd type names to the module that they are defined in.
-    printed_types: im::HashMap<String, Vec<String>>,
+    printed_types: im::HashMap<Name, Vec<Name>>,
 }
 
 impl Printer {
@@ -60,7 +59,7 @@ impl Printer {
                     qualify_type_name(module, name)
                 } else {
                     let _ = self.printed_types.insert(name.clone(), module.clone());
-                    Document::String(name.clone())
+                    Document::Name(name.clone())
                 };
                 if args.is_empty() {
                     doc
@@ -88,7 +87,7 @@ impl Printer {
         }
     }
 
-    fn name_clashes_if_unqualified(&mut self, type_: &String, module: &[String]) -> bool {
+    fn name_clashes_if_unqualified(&mut self, type_: &str, module: &[Name]) -> bool {
         match self.printed_types.get(type_) {
             None => false,
             Some(previous_module) if module == previous_module => false,
@@ -106,14 +105,14 @@ impl Printer {
     pub fn generic_type_var<'a>(&mut self, id: u64) -> Document<'a> {
         match self.names.get(&id) {
             Some(n) => {
-                let typ_name = n.clone();
+                let typ_name = n.as_str().into();
                 let _ = self.printed_types.insert(typ_name, vec![]);
                 Document::String(n.clone())
             }
             None => {
                 let n = self.next_letter();
                 let _ = self.names.insert(id, n.clone());
-                let _ = self.printed_types.insert(n.clone(), vec![]);
+                let _ = self.printed_types.insert(n.as_str().into(), vec![]);
                 Document::String(n)
             }
         }
@@ -158,7 +157,7 @@ impl Printer {
     }
 }
 
-fn qualify_type_name(module: &[String], typ_name: &str) -> Document<'static> {
+fn qualify_type_name(module: &[Name], typ_name: &str) -> Document<'static> {
     if module.is_empty() {
         docvec!["gleam.", Document::String(typ_name.to_string())]
     } else {
@@ -260,8 +259,8 @@ fn pretty_print_test() {
 
     assert_string!(
         Type::App {
-            module: vec!["whatever".to_string()],
-            name: "Int".to_string(),
+            module: vec!["whatever".into()],
+            name: "Int".into(),
             public: true,
             args: vec![],
         },
@@ -270,18 +269,18 @@ fn pretty_print_test() {
     assert_string!(
         Type::App {
             module: vec![],
-            name: "Pair".to_string(),
+            name: "Pair".into(),
             public: true,
             args: vec![
                 Arc::new(Type::App {
-                    module: vec!["whatever".to_string()],
-                    name: "Int".to_string(),
+                    module: vec!["whatever".into()],
+                    name: "Int".into(),
                     public: true,
                     args: vec![],
                 }),
                 Arc::new(Type::App {
-                    module: vec!["whatever".to_string()],
-                    name: "Bool".to_string(),
+                    module: vec!["whatever".into()],
+                    name: "Bool".into(),
                     public: true,
                     args: vec![],
                 }),
@@ -294,21 +293,21 @@ fn pretty_print_test() {
             args: vec![
                 Arc::new(Type::App {
                     args: vec![],
-                    module: vec!["whatever".to_string()],
-                    name: "Int".to_string(),
+                    module: vec!["whatever".into()],
+                    name: "Int".into(),
                     public: true,
                 }),
                 Arc::new(Type::App {
                     args: vec![],
-                    module: vec!["whatever".to_string()],
-                    name: "Bool".to_string(),
+                    module: vec!["whatever".into()],
+                    name: "Bool".into(),
                     public: true,
                 }),
             ],
             retrn: Arc::new(Type::App {
                 args: vec![],
-                module: vec!["whatever".to_string()],
-                name: "Bool".to_string(),
+                module: vec!["whatever".into()],
+                name: "Bool".into(),
                 public: true,
             }),
         },
@@ -316,11 +315,11 @@ fn pretty_print_test() {
     );
     assert_string!(
         Type::Var {
-            type_: Arc::new(RefCell::new(TypeVar::Link {
+            type_: Arc::new(TypeVarCell::new(TypeVar::Link {
                 type_: Arc::new(Type::App {
                     args: vec![],
-                    module: vec!["whatever".to_string()],
-                    name: "Int".to_string(),
+                    module: vec!["whatever".into()],
+                    name: "Int".into(),
                     public: true,
                 }),
             })),
@@ -329,17 +328,17 @@ fn pretty_print_test() {
     );
     assert_string!(
         Type::Var {
-            type_: Arc::new(RefCell::new(TypeVar::Unbound { id: 2231 })),
+            type_: Arc::new(TypeVarCell::new(TypeVar::Unbound { id: 2231 })),
         },
         "a",
     );
     assert_string!(
         fn_(
             vec![Arc::new(Type::Var {
-                type_: Arc::new(RefCell::new(TypeVar::Unbound { id: 78 })),
+                type_: Arc::new(TypeVarCell::new(TypeVar::Unbound { id: 78 })),
             })],
             Arc::new(Type::Var {
-                type_: Arc::new(RefCell::new(TypeVar::Unbound { id: 2 })),
+                type_: Arc::new(TypeVarCell::new(TypeVar::Unbound { id: 2 })),
             }),
         ),
         "fn(a) -> b",
@@ -347,10 +346,10 @@ fn pretty_print_test() {
     assert_string!(
         fn_(
             vec![Arc::new(Type::Var {
-                type_: Arc::new(RefCell::new(TypeVar::Generic { id: 78 })),
+                type_: Arc::new(TypeVarCell::new(TypeVar::Generic { id: 78 })),
             })],
             Arc::new(Type::Var {
-                type_: Arc::new(RefCell::new(TypeVar::Generic { id: 2 })),
+                type_: Arc::new(TypeVarCell::new(TypeVar::Generic { id: 2 })),
             }),
         ),
         "fn(a) -> b",
@@ -14,7 +14,8 @@ use std::path::PathBuf;
 macro_rules! assert_infer {
     ($src:expr, $typ:expr $(,)?) => {
         let mut printer = $crate::type_::pretty::Printer::new();
-        let ast = $crate::parse::parse_expression_sequence($src).expect("syntax error");
+        let arena = $crate::ast::Arena::new();
+        let ast = $crate::parse::parse_expression_sequence($src, &arena).expect("syntax error");
 
         let mut modules = im::HashMap::new();
         let ids = $crate::uid::UniqueIdGenerator::new();
@@ -25,6 +26,7 @@ macro_rules! assert_infer {
         let _ = modules.insert("gleam".to_string(), $crate::type_::build_prelude(&ids));
         let result = $crate::type_::ExprTyper::new(&mut $crate::type_::Environment::new(
             ids,
+            $crate::build::Target::Erlang,
             &[],
             &modules,
             &mut vec![],
@@ -51,8 +53,9 @@ macro_rules! assert_infer_with_module {
         // place.
         let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
         // Repeatedly create importable modules for each one given
-        let (mut ast, _) = $crate::parse::parse_module($module_src).expect("syntax error");
-        ast.name = $name;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($module_src, &arena).expect("syntax error");
+        ast.name = $name.iter().map(|name| name.as_str().into()).collect();
         let module = infer_module(
             Target::Erlang,
             &ids,
@@ -65,8 +68,9 @@ macro_rules! assert_infer_with_module {
         .expect("should successfully infer");
         let _ = modules.insert($name.join("/"), module.type_info);
 
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my_module".to_string()];
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my_module".into()];
         let ast = infer_module(
             Target::Erlang,
             &ids,
@@ -81,7 +85,7 @@ macro_rules! assert_infer_with_module {
             .type_info
             .values
             .iter()
-            .map(|(k, v)| (k.clone(), printer.pretty_print(&v.type_, 0)))
+            .map(|(k, v)| (k.to_string(), printer.pretty_print(&v.type_, 0)))
             .sorted()
             .collect();
         let expected: Vec<_> = $module
@@ -98,7 +102,8 @@ macro_rules! assert_module_infer {
         use itertools::Itertools;
         use $crate::type_::{build_prelude, infer_module};
         use $crate::uid::UniqueIdGenerator;
-        let (ast, _) = $crate::parse::parse_module($src).expect("syntax error");
+        let arena = $crate::ast::Arena::new();
+        let (ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
         let ids = UniqueIdGenerator::new();
         let mut modules = im::HashMap::new();
         // DUPE: preludeinsertion
@@ -122,7 +127,7 @@ macro_rules! assert_module_infer {
             .iter()
             .map(|(k, v)| {
                 let mut printer = $crate::type_::pretty::Printer::new();
-                (k.clone(), printer.pretty_print(&v.type_, 0))
+                (k.to_string(), printer.pretty_print(&v.type_, 0))
             })
             .sorted()
             .collect();
@@ -137,7 +142,8 @@ macro_rules! assert_module_infer {
 #[macro_export]
 macro_rules! assert_module_error {
     ($src:expr, $error:expr $(,)?) => {
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
         ast.name = vec!["my_module".to_string()];
         let mut modules = im::HashMap::new();
         let ids = UniqueIdGenerator::new();
@@ -160,7 +166,8 @@ macro_rules! assert_module_error {
     };
 
     ($src:expr) => {
-        let (ast, _) = $crate::parse::parse_module($src).expect("syntax error");
+        let arena = $crate::ast::Arena::new();
+        let (ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
         let mut modules = im::HashMap::new();
         let ids = UniqueIdGenerator::new();
         // DUPE: preludeinsertion
@@ -191,13 +198,14 @@ macro_rules! assert_module_error {
 #[macro_export]
 macro_rules! assert_module_syntax_error {
     ($src:expr) => {
-        let error =
-            $crate::parse::parse_module($src).expect_err("should trigger an error when parsing");
+        let arena = $crate::ast::Arena::new();
+        let error = $crate::parse::parse_module($src, &arena)
+            .expect_err("should trigger an error when parsing");
 
         let error = $crate::error::Error::Parse {
             src: $src.to_string(),
             path: std::path::PathBuf::from("/src/one/two.gleam"),
-            error,
+            errors: vec1::Vec1::new(error),
         };
 
         let output = error.pretty_string();
@@ -208,7 +216,8 @@ macro_rules! assert_module_syntax_error {
 #[macro_export]
 macro_rules! assert_error {
     ($src:expr, $error:expr $(,)?) => {
-        let ast = $crate::parse::parse_expression_sequence($src).expect("syntax error");
+        let arena = $crate::ast::Arena::new();
+        let ast = $crate::parse::parse_expression_sequence($src, &arena).expect("syntax error");
         let ids = UniqueIdGenerator::new();
         let mut modules = im::HashMap::new();
         // DUPE: preludeinsertion
@@ -219,6 +228,7 @@ macro_rules! assert_error {
         println!("new assert_error test: {}", modules.len());
         let result = ExprTyper::new(&mut Environment::new(
             ids,
+            $crate::build::Target::Erlang,
             &["somemod".to_string()],
             &modules,
             &mut vec![],
@@ -230,7 +240,8 @@ macro_rules! assert_error {
 
     ($src:expr) => {
         use std::path::PathBuf;
-        let ast = $crate::parse::parse_expression_sequence($src).expect("syntax error");
+        let arena = $crate::ast::Arena::new();
+        let ast = $crate::parse::parse_expression_sequence($src, &arena).expect("syntax error");
         let ids = $crate::type_::UniqueIdGenerator::new();
         let mut modules = im::HashMap::new();
         // DUPE: preludeinsertion
@@ -241,7 +252,8 @@ macro_rules! assert_error {
         println!("new assert_error test: {}", modules.len());
         let error = $crate::type_::ExprTyper::new(&mut $crate::type_::Environment::new(
             ids,
-            &["somemod".to_string()],
+            $crate::build::Target::Erlang,
+            &["somemod".into()],
             &modules,
             &mut vec![],
         ))
@@ -269,8 +281,9 @@ macro_rules! assert_with_module_error {
         // place.
         let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
         // Repeatedly create importable modules for each one given
-        let (mut ast, _) = $crate::parse::parse_module($module_src).expect("syntax error");
-        ast.name = $name;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($module_src, &arena).expect("syntax error");
+        ast.name = $name.iter().map(|name| name.as_str().into()).collect();
         let module = infer_module(
             Target::Erlang,
             &ids,
@@ -283,8 +296,9 @@ macro_rules! assert_with_module_error {
         .expect("should successfully infer");
         let _ = modules.insert($name.join("/"), module.type_info);
 
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my_module".to_string()];
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my_module".into()];
         let error = infer_module(
             Target::Erlang,
             &ids,
@@ -314,8 +328,9 @@ macro_rules! assert_with_module_error {
         // place.
         let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
         // Repeatedly create importable modules for each one given
-        let (mut ast, _) = $crate::parse::parse_module($module_src).expect("syntax error");
-        ast.name = $name;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($module_src, &arena).expect("syntax error");
+        ast.name = $name.iter().map(|name| name.as_str().into()).collect();
         let module = infer_module(
             Target::Erlang,
             &ids,
@@ -328,8 +343,10 @@ macro_rules! assert_with_module_error {
         .expect("should successfully infer");
         let _ = modules.insert($name.join("/"), module.type_info);
 
-        let (mut ast2, _) = $crate::parse::parse_module($module_src2).expect("syntax error");
-        ast2.name = $name2;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast2, _) =
+            $crate::parse::parse_module($module_src2, &arena).expect("syntax error");
+        ast2.name = $name2.iter().map(|name| name.as_str().into()).collect();
         let module = infer_module(
             Target::Erlang,
             &ids,
@@ -342,8 +359,9 @@ macro_rules! assert_with_module_error {
         .expect("should successfully infer");
         let _ = modules.insert($name2.join("/"), module.type_info);
 
-        let (mut ast, _) = crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my_module".to_string()];
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my_module".into()];
         let error = infer_module(
             Target::Erlang,
             &ids,
@@ -367,8 +385,9 @@ macro_rules! assert_with_module_error {
 #[macro_export]
 macro_rules! assert_warning {
     ($src:expr) => {
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my_module".to_string()];
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my_module".into()];
         let mut warnings: Vec<$crate::type_::error::Warning> = vec![];
         let ids = $crate::uid::UniqueIdGenerator::new();
         let mut modules = im::HashMap::new();
@@ -400,8 +419,9 @@ macro_rules! assert_warning {
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
     };
     ($src:expr, $warning:expr $(,)?) => {
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my_module".to_string()];
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my_module".into()];
         let mut warnings = vec![];
         let ids = UniqueIdGenerator::new();
         let mut modules = im::HashMap::new();
@@ -435,8 +455,9 @@ macro_rules! assert_warning {
         let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
         // Repeatedly create importable modules for each one given
         $(
-        let (mut ast, _) = $crate::parse::parse_module($module_src).expect("syntax error");
-        ast.name = $name;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($module_src, &arena).expect("syntax error");
+        ast.name = $name.iter().map(|name| name.as_str().into()).collect();
         let module = infer_module(
             Target::Erlang,
             &ids,
@@ -450,8 +471,9 @@ macro_rules! assert_warning {
         let _ = modules.insert($name.join("/"), module.type_info);
         )*
 
-        let (mut ast, _) = crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my_module".to_string()];
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my_module".into()];
         let _ = infer_module(
             Target::Erlang,
             &ids,
@@ -468,11 +490,49 @@ macro_rules! assert_warning {
     };
 }
 
+#[macro_export]
+macro_rules! assert_target_warning {
+    ($target:expr, $src:expr $(,)?) => {
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my_module".into()];
+        let mut warnings: Vec<$crate::type_::error::Warning> = vec![];
+        let ids = $crate::uid::UniqueIdGenerator::new();
+        let mut modules = im::HashMap::new();
+        let _ = modules.insert("gleam".to_string(), $crate::type_::build_prelude(&ids));
+        let _ = $crate::type_::infer_module(
+            $target,
+            &ids,
+            ast,
+            $crate::build::Origin::Src,
+            "thepackage",
+            &modules,
+            &mut warnings,
+        )
+        .expect("should successfully infer");
+
+        let mut nocolor = termcolor::Buffer::no_color();
+        for w in warnings {
+            let warning = w.into_warning(
+                std::path::PathBuf::from("/src/warning/wrn.gleam"),
+                $src.to_string(),
+            );
+            warning.pretty(&mut nocolor)
+        }
+
+        let output =
+            String::from_utf8(nocolor.into_inner()).expect("Error printing produced invalid utf8");
+
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    };
+}
+
 #[macro_export]
 macro_rules! assert_no_warnings {
     ($src:expr $(,)?) => {
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my_module".to_string()];
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my_module".into()];
         let expected: Vec<Warning> = vec![];
         let mut warnings = vec![];
         let ids = UniqueIdGenerator::new();
@@ -507,8 +567,9 @@ macro_rules! assert_no_warnings {
         let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
         // Repeatedly create importable modules for each one given
         $(
-        let (mut ast, _) = $crate::parse::parse_module($module_src).expect("syntax error");
-        ast.name = $name;
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($module_src, &arena).expect("syntax error");
+        ast.name = $name.iter().map(|name| name.as_str().into()).collect();
         let module = infer_module(
             Target::Erlang,
             &ids,
@@ -522,8 +583,9 @@ macro_rules! assert_no_warnings {
         let _ = modules.insert($name.join("/"), module.type_info);
         )*
 
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = vec!["my_module".to_string()];
+        let arena = $crate::ast::Arena::new();
+        let (mut ast, _) = $crate::parse::parse_module($src, &arena).expect("syntax error");
+        ast.name = vec!["my_module".into()];
         let _ = infer_module(
             Target::Erlang,
             &ids,
@@ -546,15 +608,15 @@ fn field_map_reorder_test() {
         location: SrcSpan { start: 0, end: 0 },
     };
 
-    struct Case {
+    struct Case<'a> {
         arity: u32,
-        fields: HashMap<String, u32>,
-        args: Vec<CallArg<UntypedExpr>>,
+        fields: HashMap<Name, u32>,
+        args: Vec<CallArg<UntypedExpr<'a>>>,
         expected_result: Result<(), Error>,
-        expected_args: Vec<CallArg<UntypedExpr>>,
+        expected_args: Vec<CallArg<UntypedExpr<'a>>>,
     }
 
-    impl Case {
+    impl Case<'_> {
         fn test(self) {
             let mut args = self.args;
             let fm = FieldMap {
@@ -625,7 +687,7 @@ fn field_map_reorder_test() {
 
     Case {
         arity: 3,
-        fields: [("last".to_string(), 2)].into(),
+        fields: [("last".into(), 2)].into(),
         args: vec![
             CallArg {
                 implicit: false,
@@ -642,7 +704,7 @@ fn field_map_reorder_test() {
             CallArg {
                 implicit: false,
                 location: Default::default(),
-                label: Some("last".to_string()),
+                label: Some("last".into()),
                 value: int("3"),
             },
         ],
@@ -663,7 +725,7 @@ fn field_map_reorder_test() {
             CallArg {
                 implicit: false,
                 location: Default::default(),
-                label: Some("last".to_string()),
+                label: Some("last".into()),
                 value: int("3"),
             },
         ],
@@ -673,11 +735,12 @@ fn field_map_reorder_test() {
 
 #[test]
 fn infer_module_type_retention_test() {
-    let module: UntypedModule = crate::ast::Module {
+    let module: UntypedModule<'_> = ast::Module {
         documentation: vec![],
-        name: vec!["ok".to_string()],
+        name: vec!["ok".into()],
         statements: vec![],
         type_info: (),
+        warning_suppressions: vec![],
     };
     let ids = UniqueIdGenerator::new();
     let mut modules = im::HashMap::new();
@@ -702,17 +765,12 @@ fn infer_module_type_retention_test() {
         Module {
             origin: Origin::Src,
             package: "thepackage".to_string(),
-            name: vec!["ok".to_string()],
+            name: vec!["ok".into()],
             types: HashMap::new(), // Core type constructors like String and Int are not included
             types_constructors: HashMap::from([
-                (
-                    "Bool".to_string(),
-                    vec!["True".to_string(), "False".to_string()]
-                ),
-                (
-                    "Result".to_string(),
-                    vec!["Ok".to_string(), "Error".to_string()]
-                )
+                ("Bool".into(), vec!["True".into(), "False".into()]),
+                ("Result".into(), vec!["Ok".into(), "Error".into()]),
+                ("CrashReason".into(), vec!["CrashReason".into()])
             ]),
             values: HashMap::new(),
             accessors: HashMap::new(),
@@ -832,6 +890,27 @@ fn try_() {
     assert_infer!("let _x0 = 1 2", "Int");
 }
 
+#[test]
+fn function_captures() {
+    assert_infer!("fn(f) { f(_, 1) }", "fn(fn(a, Int) -> b) -> fn(a) -> b");
+    assert_infer!(
+        "fn(f) { f(_, 1, _) }",
+        "fn(fn(a, Int, b) -> c) -> fn(a, b) -> c"
+    );
+    assert_infer!(
+        "fn(f) { f(_, 1, _)(2.0, \"\") }",
+        "fn(fn(Float, Int, String) -> a) -> a"
+    );
+}
+
+#[test]
+fn operator_sections() {
+    assert_infer!("_ + 1", "fn(Int) -> Int");
+    assert_infer!("_ *. 2.0 <. _", "fn(Float, Float) -> Bool");
+    assert_infer!("_ <> \"!\"", "fn(String) -> String");
+    assert_infer!("{ _ - 1 }(2)", "Int");
+}
+
 #[test]
 fn lists() {
     assert_infer!("[]", "List(a)");
@@ -944,6 +1023,13 @@ fn multiple_subject_case() {
     assert_infer!("case 1, 2.0, 3 { a, b, c -> a + c }", "Int");
 }
 
+#[test]
+fn rescue() {
+    assert_infer!("rescue(fn() { 1 })", "Result(Int, CrashReason)");
+    assert_infer!("rescue", "fn(fn() -> a) -> Result(a, CrashReason)");
+    assert_infer!("CrashReason(\"oh no\").message", "String");
+}
+
 #[test]
 fn tuple_index() {
     assert_infer!("#(1, 2.0).0", "Int");
@@ -1574,6 +1660,29 @@ fn record_update_generic() {
     );
 }
 
+#[test]
+fn record_update_nested() {
+    assert_module_infer!(
+        "
+        pub type Server {
+            Server(host: String, port: Int)
+        }
+
+        pub type Config {
+            Config(name: String, server: Server)
+        }
+
+        pub fn move(config: Config, port: Int) {
+            Config(..config, server.port: port, server.host: \"localhost\")
+        }",
+        vec![
+            ("Config", "fn(String, Server) -> Config"),
+            ("Server", "fn(String, Int) -> Server"),
+            ("move", "fn(Config, Int) -> Config"),
+        ]
+    );
+}
+
 #[test]
 fn record_update_generic_unannotated() {
     // A record update with unannotated polymorphic types
@@ -1624,6 +1733,61 @@ fn module_constants() {
     );
 }
 
+#[test]
+fn module_constant_binops() {
+    assert_module_infer!(
+        r#"
+    pub const seconds = 60 * 60
+    pub const hours = seconds / 3600 + 1
+    pub const half = 1.0 /. 2.0
+    pub const name = "Joe"
+    pub const greeting = "Hello, " <> name"#,
+        vec![
+            ("greeting", "String"),
+            ("half", "Float"),
+            ("hours", "Int"),
+            ("name", "String"),
+            ("seconds", "Int"),
+        ],
+    );
+}
+
+#[test]
+fn accessor_multiple_variants_multiple_positions() {
+    // We can access fields on custom types with multiple variants where they
+    // are in different positions e.g. 2nd and 3rd
+    assert_module_infer!(
+        "pub type Person {
+            Teacher(name: String, title: String, age: Int)
+            Student(name: String, age: Int)
+        }
+        pub fn get_name(person: Person) { person.name }
+        pub fn get_age(person: Person) { person.age }",
+        vec![
+            ("Student", "fn(String, Int) -> Person"),
+            ("Teacher", "fn(String, String, Int) -> Person"),
+            ("get_age", "fn(Person) -> Int"),
+            ("get_name", "fn(Person) -> String"),
+        ],
+    );
+
+    // e.g. 1st and 3rd
+    assert_module_infer!(
+        "pub type Person {
+            Teacher(title: String, age: Int, name: String)
+            Student(name: String, age: Int)
+        }
+        pub fn get_name(person: Person) { person.name }
+        pub fn get_age(person: Person) { person.age }",
+        vec![
+            ("Student", "fn(String, Int) -> Person"),
+            ("Teacher", "fn(String, Int, String) -> Person"),
+            ("get_age", "fn(Person) -> Int"),
+            ("get_name", "fn(Person) -> String"),
+        ],
+    );
+}
+
 #[test]
 fn custom_type_module_constants() {
     assert_module_infer!(
@@ -1912,23 +2076,23 @@ fn permit_holes_in_fn_args_and_returns() {
 
 #[test]
 fn module_name_validation() {
-    assert!(validate_module_name(&["dream".to_string()]).is_ok());
+    assert!(validate_module_name(&["dream".into()]).is_ok());
 
-    assert!(validate_module_name(&["gleam".to_string()]).is_err());
+    assert!(validate_module_name(&["gleam".into()]).is_err());
 
-    assert!(validate_module_name(&["gleam".to_string(), "ok".to_string()]).is_ok());
+    assert!(validate_module_name(&["gleam".into(), "ok".into()]).is_ok());
 
-    assert!(validate_module_name(&["ok".to_string(), "gleam".to_string()]).is_ok());
+    assert!(validate_module_name(&["ok".into(), "gleam".into()]).is_ok());
 
-    assert!(validate_module_name(&["external".to_string()]).is_err());
+    assert!(validate_module_name(&["external".into()]).is_err());
 
-    assert!(validate_module_name(&["type".to_string()]).is_err());
+    assert!(validate_module_name(&["type".into()]).is_err());
 
-    assert!(validate_module_name(&["pub".to_string()]).is_err());
+    assert!(validate_module_name(&["pub".into()]).is_err());
 
-    assert!(validate_module_name(&["ok".to_string(), "external".to_string()]).is_err());
+    assert!(validate_module_name(&["ok".into(), "external".into()]).is_err());
 
-    assert!(validate_module_name(&["ok".to_string(), "type".to_string()]).is_err());
+    assert!(validate_module_name(&["ok".into(), "type".into()]).is_err());
 
-    assert!(validate_module_name(&["ok".to_string(), "pub".to_string()]).is_err());
+    assert!(validate_module_name(&["ok".into(), "pub".into()]).is_err());
 }
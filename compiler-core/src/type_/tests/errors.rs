@@ -685,31 +685,6 @@ pub fn get_y(shape: Shape) { shape.y }"
     );
 }
 
-#[test]
-fn accessor_multiple_variants_multiple_positions() {
-    // We cannot access fields on custom types with multiple variants where they are in different positions e.g. 2nd and 3rd
-    assert_module_error!(
-        "
-pub type Person {
-    Teacher(name: String, title: String, age: Int)
-    Student(name: String, age: Int)
-}
-pub fn get_name(person: Person) { person.name }
-pub fn get_age(person: Person) { person.age }"
-    );
-
-    // We cannot access fields on custom types with multiple variants where they are in different positions e.g. 1st and 3rd
-    assert_module_error!(
-        "
-pub type Person {
-    Teacher(title: String, age: Int, name: String)
-    Student(name: String, age: Int)
-}
-pub fn get_name(person: Person) { person.name }
-pub fn get_age(person: Person) { person.age }"
-    );
-}
-
 #[test]
 fn module_could_not_unify() {
     assert_module_error!("fn go() { 1 + 2.0 }");
@@ -975,6 +950,47 @@ fn x() {
     );
 }
 
+#[test]
+fn unknown_feature_flag() {
+    assert_module_error!(
+        r#"pub fn main() {
+  case @cfg(json) {
+    True -> 1
+    False -> 2
+  }
+}"#
+    );
+}
+
+#[test]
+fn guard_call_unsafe_function() {
+    assert_module_error!(
+        r#"fn is_small(x: Int) -> Bool { x / 2 < 10 }
+fn x() {
+  case 1 { x if is_small(x) -> 1 }
+}"#
+    );
+}
+
+#[test]
+fn guard_call_recursive_function() {
+    assert_module_error!(
+        r#"fn is_small(x: Int) -> Bool { is_small(x) }
+fn x() {
+  case 1 { x if is_small(x) -> 1 }
+}"#
+    );
+}
+
+#[test]
+fn guard_call_local_function() {
+    assert_module_error!(
+        r#"fn x(is_small: fn(Int) -> Bool) {
+  case 1 { x if is_small(x) -> 1 }
+}"#
+    );
+}
+
 #[test]
 fn subject_int_float_guard_tuple() {
     assert_module_error!(
@@ -1116,6 +1132,57 @@ fn const_annotation_wrong_4() {
     assert_module_error!("pub const pair: #(Int, Float) = #(4.1, 1)");
 }
 
+#[test]
+fn large_type_mismatch_shows_differences() {
+    assert_module_error!(
+        r#"
+fn apply(f: fn(Int, List(#(Int, String))) -> Result(Int, Nil)) {
+  f
+}
+
+fn go(x: Int, y: List(#(Int, Float))) -> Result(Int, Nil) {
+  Ok(x)
+}
+
+pub fn main() {
+  apply(go)
+}"#
+    );
+}
+
+#[test]
+fn large_type_mismatch_with_different_shapes() {
+    assert_module_error!(
+        r#"
+pub fn main() {
+  let x: #(List(Int), Result(String, Nil)) = #([1], Ok("one"), 1)
+  x
+}"#
+    );
+}
+
+#[test]
+fn recursive_type_shows_trace() {
+    assert_module_error!(
+        r#"
+pub fn main(f) {
+  let g = f
+  g(f)
+}"#
+    );
+}
+
+#[test]
+fn recursive_type_in_pipeline_shows_trace() {
+    assert_module_error!(
+        r#"
+pub fn main(x) {
+  x
+  |> x
+}"#
+    );
+}
+
 #[test]
 fn const_usage_wrong() {
     assert_module_error!(
@@ -1129,6 +1196,32 @@ fn const_heterogenus_list() {
     assert_module_error!("const pair = [1, 1.0]");
 }
 
+#[test]
+fn const_binop_wrong_type() {
+    assert_module_error!("const x = 1 + 1.0");
+}
+
+#[test]
+fn const_concatenate_wrong_type() {
+    assert_module_error!(
+        "const name = 1
+const greeting = \"Hello, \" <> name"
+    );
+}
+
+#[test]
+fn const_int_out_of_range() {
+    assert_module_error!("const x = 170141183460469231731687303715884105727 + 1");
+}
+
+#[test]
+fn const_float_out_of_range() {
+    assert_module_error!(
+        "const big = 10000000000000000000000000000000000000000.0
+const x = big *. big *. big *. big *. big *. big *. big *. big"
+    );
+}
+
 #[test]
 fn custom_type_module_constants() {
     assert_module_error!(
@@ -1213,6 +1306,17 @@ pub fn update_person() {
     );
 }
 
+#[test]
+fn field_not_in_every_variant() {
+    assert_module_error!(
+        "pub type Shape {
+  Circle(radius: Float, name: String)
+  Square(side: Float)
+}
+pub fn get_name(shape: Shape) { shape.name }"
+    );
+}
+
 #[test]
 fn unknown_field_update() {
     // An unknown field given to a record update
@@ -1227,6 +1331,39 @@ fn unknown_field_update() {
     );
 }
 
+#[test]
+fn nested_update_multi_constructor_type() {
+    assert_module_error!(
+        "
+pub type Shape {
+  Circle(radius: Int)
+  Square(radius: Int)
+}
+pub type Canvas {
+  Canvas(shape: Shape)
+}
+pub fn grow(canvas: Canvas) {
+  Canvas(..canvas, shape.radius: 2)
+}"
+    );
+}
+
+#[test]
+fn unknown_nested_field_update() {
+    assert_module_error!(
+        "
+pub type Server {
+  Server(port: Int)
+}
+pub type Config {
+  Config(server: Server)
+}
+pub fn move(config: Config) {
+  Config(..config, server.host: 5)
+}"
+    );
+}
+
 #[test]
 fn unknown_field_update2() {
     // An unknown field given to a record update
@@ -1817,3 +1954,126 @@ pub fn main(_x: two.Thing) {
 "
     );
 }
+
+#[test]
+fn erlang_map_type_with_several_constructors() {
+    assert_module_error!(
+        "
+@erlang_map
+pub type Pet {
+  Cat(name: String)
+  Dog(name: String)
+}
+"
+    );
+}
+
+#[test]
+fn erlang_map_type_with_unlabelled_field() {
+    assert_module_error!(
+        "
+@erlang_map
+pub type Cat {
+  Cat(name: String, Int)
+}
+"
+    );
+}
+
+#[test]
+fn erlang_map_record_in_constant() {
+    assert_module_error!(
+        "
+@erlang_map
+pub type Cat {
+  Cat(name: String)
+}
+
+pub const tom = Cat(name: \"Tom\")
+"
+    );
+}
+
+#[test]
+fn external_fn_missing_argument_annotation() {
+    assert_module_error!(
+        r#"
+@external(erlang, "lists", "reverse")
+pub fn reverse(list) -> List(a) {
+  list
+}
+"#
+    );
+}
+
+#[test]
+fn external_fn_missing_return_annotation() {
+    assert_module_error!(
+        r#"
+@external(javascript, "./ffi.mjs", "reverse")
+pub fn reverse(list: List(a)) {
+  list
+}
+"#
+    );
+}
+
+#[test]
+fn external_fn_fallback_must_match_signature() {
+    assert_module_error!(
+        r#"
+@external(erlang, "erlang", "abs")
+pub fn abs(x: Int) -> Int {
+  "oh no"
+}
+"#
+    );
+}
+
+#[test]
+fn empty_int_range() {
+    assert_module_error!(
+        "pub fn main(x) {
+  case x {
+    10..1 -> 0
+    _ -> 1
+  }
+}"
+    );
+}
+
+#[test]
+fn int_range_on_string() {
+    assert_module_error!(
+        r#"pub fn main() {
+  case "a" {
+    1..2 -> 0
+    _ -> 1
+  }
+}"#
+    );
+}
+
+#[test]
+fn int_range_in_let() {
+    assert_module_error!(
+        "pub fn main() {
+  let 1..2 = 1
+}"
+    );
+}
+
+#[test]
+fn float_literal_out_of_range() {
+    assert_error!(&format!("{}.0", "9".repeat(309)));
+}
+
+#[test]
+fn float_pattern_out_of_range() {
+    assert_error!(&format!("case 1.0 {{ {}.0 -> 1 _ -> 0 }}", "9".repeat(309)));
+}
+
+#[test]
+fn const_float_literal_out_of_range() {
+    assert_module_error!(&format!("const x = -1{}.0", "_000".repeat(103)));
+}
@@ -1,6 +1,6 @@
 use super::*;
-use crate::ast::TodoKind;
-use crate::{assert_no_warnings, assert_warning};
+use crate::ast::{Layer, SrcSpan, TodoKind};
+use crate::{assert_no_warnings, assert_target_warning, assert_warning};
 
 #[test]
 fn unknown_label() {
@@ -20,7 +20,7 @@ fn todo_warning_test() {
             kind: TodoKind::Keyword,
             location: SrcSpan { start: 17, end: 21 },
             typ: Arc::new(Type::Var {
-                type_: Arc::new(RefCell::new(TypeVar::Link { type_: int() })),
+                type_: Arc::new(TypeVarCell::new(TypeVar::Link { type_: int() })),
             }),
         },
     );
@@ -45,6 +45,20 @@ fn todo_with_known_type() {
     );
 }
 
+#[test]
+fn echo_binds_tighter_than_operators() {
+    assert_warning!("pub fn main() { echo 1 + 2 }");
+}
+
+#[test]
+fn echo_has_the_type_of_its_value() {
+    assert_warning!(
+        "pub fn main() -> List(Int) {
+  echo [1, 2]
+}"
+    );
+}
+
 #[test]
 fn empty_func_warning_test() {
     assert_warning!(
@@ -90,11 +104,71 @@ fn main() { foo(); 5 }",
 
 #[test]
 fn result_discard_warning_test2() {
-    // Explicitly discarded Results do not emit warnings
+    // Results assigned to a named discard do not emit warnings
+    assert_no_warnings!(
+        "
+pub fn foo() { Ok(5) }
+pub fn main() { let _ignored = foo(); 5 }",
+    );
+}
+
+#[test]
+fn result_assigned_to_discard() {
+    assert_warning!(
+        "
+pub fn foo() { Ok(5) }
+pub fn main() { let _ = foo(); 5 }"
+    );
+}
+
+#[test]
+fn result_discarded_in_pipeline() {
+    assert_warning!(
+        "
+fn wrap(x) { Ok(x) }
+pub fn main() { 1 |> wrap; 5 }"
+    );
+}
+
+#[test]
+fn allowed_discarded_result() {
     assert_no_warnings!(
         "
 pub fn foo() { Ok(5) }
-pub fn main() { let _ = foo(); 5 }",
+@allow(implicitly_discarded_result)
+pub fn main() { let _ = foo(); foo(); 5 }"
+    );
+}
+
+#[test]
+fn result_passed_to_result_unwrap_function() {
+    assert_no_warnings!(
+        (
+            vec!["gleam".to_string(), "result".to_string()],
+            "pub fn unwrap_error(result: Result(a, b), default: b) -> b {
+  case result { Ok(_) -> default Error(e) -> e }
+}"
+        ),
+        "
+import gleam/result
+fn check() { Error(Error(1)) }
+pub fn main() { check() |> result.unwrap_error(Ok(1)); let _ = result.unwrap_error(check(), Ok(1)); 5 }",
+    );
+}
+
+#[test]
+fn result_passed_to_unqualified_result_unwrap_function() {
+    assert_no_warnings!(
+        (
+            vec!["gleam".to_string(), "result".to_string()],
+            "pub fn unwrap_error(result: Result(a, b), default: b) -> b {
+  case result { Ok(_) -> default Error(e) -> e }
+}"
+        ),
+        "
+import gleam/result.{unwrap_error}
+fn check() { Error(Error(1)) }
+pub fn main() { unwrap_error(check(), Ok(1)); 5 }",
     );
 }
 
@@ -386,6 +460,63 @@ fn used_variable_shadowing_test() {
     assert_no_warnings!("pub fn a() { let b = 1 let b = b + 1 b }");
 }
 
+#[test]
+fn shadowed_variable_in_nested_scope() {
+    assert_warning!("pub fn a(b) { fn(b) { b } }");
+}
+
+#[test]
+fn shadowed_variable_in_case_clause() {
+    assert_warning!(
+        "pub fn a(x, y) {
+  case y {
+    Ok(x) -> x
+    Error(_) -> x
+  }
+}"
+    );
+}
+
+#[test]
+fn shadowing_used_variable_in_case_clause() {
+    assert_no_warnings!(
+        "pub fn a(x) {
+  case x {
+    Ok(x) -> x
+    Error(_) -> 0
+  }
+}"
+    );
+}
+
+#[test]
+fn shadowing_used_variable_in_fn() {
+    assert_no_warnings!("pub fn a(b) { let c = b fn(b) { b + c } }");
+}
+
+#[test]
+fn allowed_shadowed_variable() {
+    assert_no_warnings!(
+        "@allow(shadowed_variable)
+pub fn a(b) { b + { let b = 1 b } }"
+    );
+}
+
+#[test]
+fn unused_labelled_argument() {
+    assert_warning!("pub fn greet(name name: String, greeting greeting: String) { name }");
+}
+
+#[test]
+fn used_labelled_argument() {
+    assert_no_warnings!("pub fn a(with b) { b }");
+}
+
+#[test]
+fn discarded_labelled_argument() {
+    assert_no_warnings!("pub fn a(with _b) { 1 }");
+}
+
 #[test]
 fn unused_destructure() {
     // Destructure
@@ -539,3 +670,401 @@ pub const make_two = one.Two
 "
     );
 }
+
+#[test]
+fn type_hole_in_function_annotation() {
+    assert_warning!(
+        "pub fn add_one(x: _) -> Int {
+  x + 1
+}"
+    );
+}
+
+#[test]
+fn named_type_hole_in_let_annotation() {
+    assert_warning!(
+        "pub fn main() {
+  let x: List(_item) = [1.0, 2.0]
+  x
+}"
+    );
+}
+
+#[test]
+fn type_hole_in_constant_annotation() {
+    assert_warning!(r#"pub const greeting: _ = "Hello""#);
+}
+
+#[test]
+fn unreachable_clause_after_catch_all() {
+    assert_warning!(
+        "pub fn main(x) {
+  case x {
+    _ -> 0
+    1 -> 1
+  }
+}"
+    );
+}
+
+#[test]
+fn unreachable_duplicate_literal_clause() {
+    assert_warning!(
+        r#"pub fn main(x) {
+  case x {
+    "a" -> 0
+    "b" -> 1
+    "a" -> 2
+    _ -> 3
+  }
+}"#
+    );
+}
+
+#[test]
+fn unreachable_clause_with_multiple_subjects() {
+    assert_warning!(
+        "pub fn main(x, y) {
+  case x, y {
+    Ok(_), _ -> 0
+    Ok(1), 2 -> 1
+    _, _ -> 2
+  }
+}"
+    );
+}
+
+#[test]
+fn unreachable_alternative_patterns() {
+    assert_warning!(
+        "pub fn main(x) {
+  case x {
+    [] -> 0
+    [_, ..] -> 1
+    [1] | [1, 2] -> 2
+    _ -> 3
+  }
+}"
+    );
+}
+
+#[test]
+fn unreachable_clause_covered_by_range() {
+    assert_warning!(
+        "pub fn main(x) {
+  case x {
+    0..9 -> 0
+    10..99 -> 1
+    20..29 -> 2
+    _ -> 3
+  }
+}"
+    );
+}
+
+#[test]
+fn partly_overlapping_ranges_are_reachable() {
+    assert_no_warnings!(
+        "pub fn main(x) {
+  case x {
+    0..9 -> 0
+    5..15 -> 1
+    16 -> 2
+    _ -> 3
+  }
+}"
+    );
+}
+
+#[test]
+fn guarded_clause_does_not_make_later_clauses_unreachable() {
+    assert_no_warnings!(
+        "pub fn main(x) {
+  case x {
+    _ if x > 1 -> 0
+    1 -> 1
+    _ -> 2
+  }
+}"
+    );
+}
+
+#[test]
+fn partly_overlapping_clauses_are_reachable() {
+    assert_no_warnings!(
+        r#"pub fn main(x) {
+  case x {
+    #(1, _) -> 0
+    #(_, 2) -> 1
+    #(_, _) -> 2
+  }
+}
+
+pub fn prefix(x) {
+  case x {
+    "ab" <> _ -> 0
+    "a" <> _ -> 1
+    _ -> 2
+  }
+}"#
+    );
+}
+
+#[test]
+fn deprecated_function() {
+    assert_warning!(
+        r#"@deprecated("Use wobble instead")
+fn wibble() { 1 }
+
+pub fn main() {
+  wibble()
+}"#
+    );
+}
+
+#[test]
+fn deprecated_constant() {
+    assert_warning!(
+        r#"@deprecated("Use wobble instead")
+const wibble = 1
+
+pub fn main() {
+  wibble
+}"#
+    );
+}
+
+#[test]
+fn deprecated_type_in_annotation() {
+    assert_warning!(
+        r#"@deprecated("Use Int instead")
+pub type Number =
+  Int
+
+pub fn main(x: Number) {
+  x
+}"#
+    );
+}
+
+#[test]
+fn deprecated_constructor_in_pattern() {
+    assert_warning!(
+        r#"pub type Animal {
+  Cat
+  @deprecated("Dogs are no longer supported")
+  Dog
+}
+
+pub fn main(animal) {
+  case animal {
+    Dog -> 1
+    Cat -> 2
+  }
+}"#
+    );
+}
+
+#[test]
+fn constructor_of_deprecated_type() {
+    assert_warning!(
+        r#"@deprecated("Use Int instead")
+pub type Number {
+  Number(Int)
+}
+
+pub fn main() {
+  Number(1)
+}"#,
+        Warning::DeprecatedItem {
+            location: SrcSpan { start: 84, end: 90 },
+            message: "Use Int instead".into(),
+            layer: Layer::Value,
+        },
+    );
+}
+
+#[test]
+fn deprecated_imported_function() {
+    assert_warning!(
+        (
+            vec!["one".to_string()],
+            r#"@deprecated("Use three instead") pub fn two() { 2 }"#
+        ),
+        "import one
+pub fn main() { one.two() }",
+        Warning::DeprecatedItem {
+            location: SrcSpan { start: 27, end: 34 },
+            message: "Use three instead".into(),
+            layer: Layer::Value,
+        },
+    );
+}
+
+#[test]
+fn deprecated_unqualified_imported_function() {
+    assert_warning!(
+        (
+            vec!["one".to_string()],
+            r#"@deprecated("Use three instead") pub fn two() { 2 }"#
+        ),
+        "import one.{two}
+pub fn main() { two() }",
+        Warning::DeprecatedItem {
+            location: SrcSpan { start: 33, end: 36 },
+            message: "Use three instead".into(),
+            layer: Layer::Value,
+        },
+    );
+}
+
+#[test]
+fn deprecated_definitions_can_be_defined_without_warnings() {
+    assert_no_warnings!(
+        r#"@deprecated("Use wobble instead")
+pub fn wibble() { 1 }
+
+@deprecated("Use Int instead")
+pub type Number {
+  Number(Int)
+}"#
+    );
+}
+
+#[test]
+fn allowed_unused_variable() {
+    assert_no_warnings!(
+        "@allow(unused_variable)
+pub fn main() { let x = 1 2 }"
+    );
+}
+
+#[test]
+fn allowed_warnings_of_several_kinds() {
+    assert_no_warnings!(
+        "@allow(todo, unused_literal)
+pub fn main() { 1 todo }"
+    );
+}
+
+#[test]
+fn allowed_unused_private_function() {
+    assert_no_warnings!(
+        "@allow(unused_private_function)
+fn wibble() { 1 }"
+    );
+}
+
+#[test]
+fn allowed_unused_import() {
+    assert_no_warnings!(
+        "@allow(unused_imported_module)
+import gleam"
+    );
+}
+
+#[test]
+fn allow_only_applies_to_its_definition() {
+    assert_warning!(
+        "@allow(unused_variable)
+pub fn wibble() { let x = 1 2 }
+
+pub fn wobble() { let y = 1 2 }",
+        Warning::UnusedVariable {
+            name: "y".into(),
+            location: SrcSpan { start: 79, end: 80 },
+        },
+    );
+}
+
+#[test]
+fn allow_only_applies_to_its_kinds() {
+    assert_warning!(
+        "@allow(unused_literal)
+pub fn main() { let x = 1 2 }",
+        Warning::UnusedVariable {
+            name: "x".into(),
+            location: SrcSpan { start: 43, end: 44 },
+        },
+    );
+}
+
+#[test]
+fn module_allow() {
+    assert_no_warnings!(
+        "@module_allow(unused_variable)
+
+pub fn wibble() { let x = 1 2 }
+
+pub fn wobble() { let y = 1 2 }"
+    );
+}
+
+#[test]
+fn allowed_deprecated() {
+    assert_no_warnings!(
+        r#"@deprecated("Use wobble instead")
+pub fn wibble() { 1 }
+
+@allow(deprecated)
+pub fn main() { wibble() }"#
+    );
+}
+
+#[test]
+fn int_literal_out_of_range_native() {
+    assert_target_warning!(
+        Target::Native,
+        "pub fn main() { 9_223_372_036_854_775_808 }"
+    );
+}
+
+#[test]
+fn int_literal_in_range_native() {
+    assert_target_warning!(
+        Target::Native,
+        "pub fn main() { #(9_223_372_036_854_775_807, -9_223_372_036_854_775_808, 0x7FFFFFFFFFFFFFFF) }"
+    );
+}
+
+#[test]
+fn int_literal_out_of_range_javascript() {
+    assert_target_warning!(
+        Target::JavaScript,
+        "pub fn main() { 9_007_199_254_740_993 }"
+    );
+}
+
+#[test]
+fn int_arithmetic_out_of_range_native() {
+    // Only the operation that first goes out of range is reported
+    assert_target_warning!(
+        Target::Native,
+        "pub fn main() { 1 + 4_000_000_000 * 4_000_000_000 * 1_000 }"
+    );
+}
+
+#[test]
+fn int_constant_out_of_range_javascript() {
+    assert_target_warning!(
+        Target::JavaScript,
+        "pub const big = 1_000_000_000 * 1_000_000_000
+pub const huge = 99_999_999_999_999_999"
+    );
+}
+
+#[test]
+fn int_pattern_out_of_range_native() {
+    assert_target_warning!(
+        Target::Native,
+        "pub fn main(x) { case x { 18_446_744_073_709_551_615 -> 1 _ -> 0 } }"
+    );
+}
+
+#[test]
+fn large_ints_are_fine_on_erlang() {
+    assert_no_warnings!(
+        "pub const huge = 999_999_999_999_999_999_999_999 * 99_999_999_999
+pub fn main() { 999_999_999_999_999_999_999_999_999 * 99_999_999_999_999 }"
+    );
+}
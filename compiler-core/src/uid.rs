@@ -27,8 +27,25 @@ impl UniqueIdGenerator {
     pub fn next(&self) -> u64 {
         self.id.fetch_add(1, Ordering::Relaxed)
     }
+
+    /// Reserve a block of ids for each of a number of modules, returning a
+    /// generator for each block. Modules that are compiled in parallel are
+    /// each given a block, so that they get the same ids whichever order they
+    /// are compiled in. A block is large enough for any one module.
+    pub fn reserve_blocks(&self, count: usize) -> Vec<Self> {
+        let start = self
+            .id
+            .fetch_add(count as u64 * ID_BLOCK_SIZE, Ordering::Relaxed);
+        (0..count as u64)
+            .map(|block| Self {
+                id: Arc::new(AtomicU64::new(start + block * ID_BLOCK_SIZE)),
+            })
+            .collect()
+    }
 }
 
+const ID_BLOCK_SIZE: u64 = 1 << 24;
+
 #[test]
 fn id_geneation() {
     let ids = UniqueIdGenerator::new();
@@ -47,3 +64,18 @@ fn id_geneation() {
     assert_eq!(ids.next(), 6);
     assert_eq!(ids.next(), 7);
 }
+
+#[test]
+fn reserved_blocks() {
+    let ids = UniqueIdGenerator::new();
+    assert_eq!(ids.next(), 0);
+
+    let blocks = ids.reserve_blocks(2);
+    let ids_of = |block: usize| blocks.get(block).expect("block");
+    assert_eq!(ids_of(0).next(), 1);
+    assert_eq!(ids_of(0).next(), 2);
+    assert_eq!(ids_of(1).next(), 1 + ID_BLOCK_SIZE);
+
+    // The original continues after the reserved blocks
+    assert_eq!(ids.next(), 1 + 2 * ID_BLOCK_SIZE);
+}
@@ -1,7 +1,9 @@
 use crate::{
-    ast::TodoKind,
-    diagnostic::{self, Diagnostic, Location},
-    type_,
+    ast::{Layer, SrcSpan, TodoKind},
+    build::Target,
+    diagnostic::{self, sarif::CodedDiagnostic, Diagnostic, Location},
+    error::wrap,
+    lint, type_,
 };
 use std::io::Write;
 use std::path::PathBuf;
@@ -9,18 +11,161 @@ use termcolor::Buffer;
 
 pub type Src = String;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Warning {
     Type {
         path: PathBuf,
         src: Src,
         warning: crate::type_::Warning,
     },
+
+    DeadCode {
+        path: PathBuf,
+        src: Src,
+        location: SrcSpan,
+        kind: DeadCodeKind,
+        name: String,
+    },
+
+    Lint {
+        path: PathBuf,
+        src: Src,
+        lint: lint::Lint,
+    },
+}
+
+/// The kind of definition reported by dead code detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadCodeKind {
+    Function,
+    Constant,
+    Type,
+    Constructor,
+}
+
+impl DeadCodeKind {
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Function => "function",
+            Self::Constant => "constant",
+            Self::Type => "type",
+            Self::Constructor => "type constructor",
+        }
+    }
 }
 
 impl Warning {
+    /// The kind of the warning, used to decide how it is treated by the
+    /// `[warnings]` section of gleam.toml. Dead code warnings and lints have
+    /// no kind.
+    pub fn kind(&self) -> Option<type_::WarningKind> {
+        match self {
+            Self::Type { warning, .. } => Some(warning.kind()),
+            Self::DeadCode { .. } | Self::Lint { .. } => None,
+        }
+    }
+
+    /// A short name for the kind of problem, such as `unused_variable`, used
+    /// by tools that read the warnings as JSON.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Type { warning, .. } => warning.kind().name(),
+            Self::DeadCode { .. } => "dead_code",
+            Self::Lint { lint, .. } => lint.rule.name(),
+        }
+    }
+
+    /// The warning as a JSON object, with the fixes that resolve it.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.to_diagnostic().to_json(self.code(), &self.fixes())
+    }
+
+    /// The warning with its code and the fixes that resolve it, as reported
+    /// in SARIF logs.
+    pub fn to_coded_diagnostic(&self) -> CodedDiagnostic {
+        CodedDiagnostic {
+            code: self.code().into(),
+            diagnostic: self.to_diagnostic(),
+            fixes: self.fixes(),
+        }
+    }
+
+    /// The fixes that resolve the warning, if it can be done mechanically.
+    pub fn fixes(&self) -> Vec<crate::fix::Fix> {
+        match self {
+            Self::Type { path, src, warning } => {
+                crate::fix::warning_fixes(src, path, warning).unwrap_or_default()
+            }
+            Self::Lint { lint, .. } => lint
+                .fix
+                .iter()
+                .map(|action| crate::fix::Fix {
+                    title: action.title.clone(),
+                    edits: action.edits.clone(),
+                })
+                .collect(),
+            Self::DeadCode { .. } => vec![],
+        }
+    }
+
     pub fn to_diagnostic(&self) -> Diagnostic {
         match self {
+            Self::DeadCode {
+                path,
+                src,
+                location,
+                kind,
+                name,
+            } => {
+                let kind = kind.description();
+                Diagnostic {
+                    title: format!("Unreachable {}", kind),
+                    text: wrap(&format!(
+                        "The {} `{}` cannot be reached from any `main` function or test \
+in this package.",
+                        kind, name
+                    )),
+                    hint: Some("If it is not needed you can safely remove it.".into()),
+                    level: diagnostic::Level::Warning,
+                    location: Some(Location {
+                        path: path.to_path_buf(),
+                        src: src.to_string(),
+                        label: diagnostic::Label {
+                            text: Some(format!("This {} is never used", kind)),
+                            span: *location,
+                        },
+                        extra_labels: Vec::new(),
+                    }),
+                }
+            }
+
+            Self::Lint { path, src, lint } => {
+                let title = match lint.rule {
+                    lint::LintRule::DeepNesting => "Deeply nested code",
+                    lint::LintRule::LongFunction => "Long function",
+                    lint::LintRule::NestedCalls => "Nested function calls",
+                    lint::LintRule::BoolCase => "Case on a Bool",
+                };
+                Diagnostic {
+                    title: title.into(),
+                    text: format!(
+                        "This is reported by the `{}` lint enabled in gleam.toml.",
+                        lint.rule.name()
+                    ),
+                    hint: Some(lint.hint.clone()),
+                    level: diagnostic::Level::Warning,
+                    location: Some(Location {
+                        path: path.to_path_buf(),
+                        src: src.to_string(),
+                        label: diagnostic::Label {
+                            text: Some(lint.label.clone()),
+                            span: lint.location,
+                        },
+                        extra_labels: Vec::new(),
+                    }),
+                }
+            }
+
             Self::Type { path, warning, src } => match warning {
                 type_::Warning::Todo {
                     kind,
@@ -73,7 +218,11 @@ expression.",
                 type_::Warning::ImplicitlyDiscardedResult { location } => Diagnostic {
                     title: "Unused result value".into(),
                     text: "".into(),
-                    hint: Some("If you are sure you don't need it you can assign it to `_`".into()),
+                    hint: Some(
+                        "If you are sure you don't need it you can assign it to a \
+named discard such as `_ignored`"
+                            .into(),
+                    ),
                     level: diagnostic::Level::Warning,
                     location: Some(Location {
                         path: path.to_path_buf(),
@@ -86,6 +235,44 @@ expression.",
                     }),
                 },
 
+                type_::Warning::Echo { location } => Diagnostic {
+                    title: "Echo found".into(),
+                    text: "\
+This code prints a value each time it is run. `echo` is meant for
+debugging so it should be removed before the code is released."
+                        .into(),
+                    hint: None,
+                    level: diagnostic::Level::Warning,
+                    location: Some(Location {
+                        path: path.to_path_buf(),
+                        src: src.to_string(),
+                        label: diagnostic::Label {
+                            text: Some("This value is printed".into()),
+                            span: *location,
+                        },
+                        extra_labels: Vec::new(),
+                    }),
+                },
+
+                type_::Warning::InferredTypeHole { location, typ } => Diagnostic {
+                    title: "Type hole".into(),
+                    text: format!(
+                        "I inferred the type of this hole to be:\n\n{}\n",
+                        type_::pretty::Printer::new().pretty_print(typ, 4)
+                    ),
+                    hint: Some("Replace the hole with this type to remove this note.".into()),
+                    level: diagnostic::Level::Info,
+                    location: Some(Location {
+                        path: path.to_path_buf(),
+                        src: src.to_string(),
+                        label: diagnostic::Label {
+                            text: Some("This type is to be inferred".into()),
+                            span: *location,
+                        },
+                        extra_labels: Vec::new(),
+                    }),
+                },
+
                 type_::Warning::UnusedLiteral { location } => Diagnostic {
                     title: "Unused literal".into(),
                     text: "".into(),
@@ -102,6 +289,57 @@ expression.",
                     }),
                 },
 
+                type_::Warning::UnreachableCaseClause {
+                    location,
+                    covered_by,
+                } => Diagnostic {
+                    title: "Unreachable case clause".into(),
+                    text: wrap(
+                        "This clause cannot be reached as any value it matches \
+would already have been matched by an earlier clause.",
+                    ),
+                    hint: Some("You can safely remove it.".into()),
+                    level: diagnostic::Level::Warning,
+                    location: Some(Location {
+                        path: path.to_path_buf(),
+                        src: src.to_string(),
+                        label: diagnostic::Label {
+                            text: Some("This clause can never match".into()),
+                            span: *location,
+                        },
+                        extra_labels: vec![diagnostic::Label {
+                            text: Some("Because this pattern matches first".into()),
+                            span: *covered_by,
+                        }],
+                    }),
+                },
+
+                type_::Warning::DeprecatedItem {
+                    location,
+                    message,
+                    layer,
+                } => {
+                    let kind = match layer {
+                        Layer::Value => "value",
+                        Layer::Type => "type",
+                    };
+                    Diagnostic {
+                        title: format!("Deprecated {} used", kind),
+                        text: wrap(&format!("It was deprecated with this message: {}", message)),
+                        hint: None,
+                        level: diagnostic::Level::Warning,
+                        location: Some(Location {
+                            path: path.to_path_buf(),
+                            src: src.to_string(),
+                            label: diagnostic::Label {
+                                text: Some(format!("This {} has been deprecated", kind)),
+                                span: *location,
+                            },
+                            extra_labels: Vec::new(),
+                        }),
+                    }
+                }
+
                 type_::Warning::NoFieldsRecordUpdate { location } => Diagnostic {
                     title: "Fieldless record update".into(),
                     text: "".into(),
@@ -278,6 +516,104 @@ expression.",
                         extra_labels: Vec::new(),
                     }),
                 },
+
+                type_::Warning::UnusedLabelledArgument {
+                    location,
+                    label,
+                    name,
+                } => Diagnostic {
+                    title: "Unused labelled argument".into(),
+                    text: wrap(
+                        "The label of this argument is part of the function's \
+API, so callers may be passing a value that is silently ignored.",
+                    ),
+                    hint: Some(format!(
+                        "You can ignore it with an underscore: `{} _{}`.",
+                        label, name
+                    )),
+                    level: diagnostic::Level::Warning,
+                    location: Some(Location {
+                        src: src.to_string(),
+                        path: path.to_path_buf(),
+                        label: diagnostic::Label {
+                            text: Some("This argument is never used.".into()),
+                            span: *location,
+                        },
+                        extra_labels: Vec::new(),
+                    }),
+                },
+
+                type_::Warning::ShadowedVariable {
+                    location,
+                    name,
+                    shadowed,
+                } => Diagnostic {
+                    title: "Shadowed variable".into(),
+                    text: wrap(&format!(
+                        "This definition of `{name}` hides an earlier one that \
+has not been used yet, so any uses of `{name}` in this scope refer to the new \
+definition."
+                    )),
+                    hint: Some("You may want to give one of them a different name.".into()),
+                    level: diagnostic::Level::Warning,
+                    location: Some(Location {
+                        src: src.to_string(),
+                        path: path.to_path_buf(),
+                        label: diagnostic::Label {
+                            text: Some("This shadows an unused variable".into()),
+                            span: *location,
+                        },
+                        extra_labels: vec![diagnostic::Label {
+                            text: Some("The earlier definition".into()),
+                            span: *shadowed,
+                        }],
+                    }),
+                },
+
+                type_::Warning::IntOutOfRange { location, target } => {
+                    let (min, max) = target.int_range().unwrap_or_default();
+                    let (name, consequence) = match target {
+                        Target::JavaScript => (
+                            "JavaScript",
+                            "Ints are floating point numbers on this target, so this \
+value will lose precision.",
+                        ),
+                        Target::Go => (
+                            "Go",
+                            "Ints are 64 bits wide on this target, so this value will overflow.",
+                        ),
+                        Target::Llvm => (
+                            "LLVM",
+                            "Ints are 63 bits wide on this target, so this value will overflow.",
+                        ),
+                        Target::Wasm => (
+                            "WebAssembly",
+                            "Ints are 63 bits wide on this target, so this value will overflow.",
+                        ),
+                        Target::Erlang | Target::Native => (
+                            "native",
+                            "Ints are 64 bits wide on this target, so this value will overflow.",
+                        ),
+                    };
+                    Diagnostic {
+                        title: "Int out of range".into(),
+                        text: wrap(&format!(
+                            "The {name} target can only represent Ints from {min} to \
+{max} exactly. {consequence}"
+                        )),
+                        hint: None,
+                        level: diagnostic::Level::Warning,
+                        location: Some(Location {
+                            src: src.to_string(),
+                            path: path.to_path_buf(),
+                            label: diagnostic::Label {
+                                text: Some("This Int is out of range".into()),
+                                span: *location,
+                            },
+                            extra_labels: Vec::new(),
+                        }),
+                    }
+                }
             },
         }
     }
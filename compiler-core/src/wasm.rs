@@ -0,0 +1,438 @@
+//! WebAssembly components, which export an interface for each module of the
+//! package with public functions that can be called from other components.
+//!
+//! The package is compiled to LLVM IR for a platform with 32-bit addresses,
+//! and its public functions are described by WebAssembly Interface Type
+//! (WIT) definitions derived from their signatures. Each interface is
+//! implemented by functions that convert the values they take and return
+//! between the canonical ABI of the component model and the representation
+//! of Gleam values, which are generated by [`crate::llvm::component_exports`].
+//!
+//! | Gleam                     | WIT                                    |
+//! | ------------------------- | -------------------------------------- |
+//! | `Int`                     | `s64`                                  |
+//! | `Float`                   | `f64`                                  |
+//! | `Bool`                    | `bool`                                 |
+//! | `String`                  | `string`                               |
+//! | `List(a)`                 | `list<a>`                              |
+//! | `#(a, b)`                 | `tuple<a, b>`                          |
+//! | `Result(a, b)`            | `result<a, b>`, either may be `Nil`    |
+//! | constructors w/o fields   | `enum`                                 |
+//! | one labelled constructor  | `record`                               |
+//! | other custom types        | `variant`                              |
+//!
+//! Only custom types of the package that are public, not opaque and not
+//! generic can be described, and only functions taking and returning values
+//! that can be described are exported. `Nil` can only be returned, or held by
+//! a `Result`.
+
+mod wit;
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use heck::ToKebabCase;
+use itertools::Itertools;
+
+use crate::{
+    ast::{Statement, TypedModule, TypedRecordConstructor},
+    type_::{self, collapse_links},
+};
+
+/// The namespace of the WIT package of a component, which is named after the
+/// Gleam package.
+pub const NAMESPACE: &str = "gleam";
+
+/// The world of the component, which exports every interface. The module
+/// `gleam` is reserved, so no interface has the same name.
+pub const WORLD: &str = "gleam";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    S64,
+    F64,
+    Bool,
+    String,
+    List(Box<Type>),
+    Tuple(Vec<Type>),
+    /// A Gleam `Result`, either side of which has no value when it holds
+    /// `Nil`.
+    Result {
+        ok: Option<Box<Type>>,
+        error: Option<Box<Type>>,
+    },
+    Named(Arc<TypeDefinition>),
+}
+
+/// A custom type of the package.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TypeDefinition {
+    /// The module defining the type.
+    pub module: String,
+    pub name: String,
+    pub kind: TypeKind,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TypeKind {
+    /// A type whose constructors have no fields, by the name of each.
+    Enum(Vec<String>),
+    /// A type with a single constructor, every field of which is labelled.
+    Record {
+        constructor: String,
+        fields: Vec<(String, Type)>,
+    },
+    Variant(Vec<Case>),
+}
+
+/// A constructor of a variant, whose fields are passed as its payload.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Case {
+    pub constructor: String,
+    pub fields: Vec<Type>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Function {
+    pub name: String,
+    /// The name of the LLVM function implementing an external function.
+    pub external: Option<String>,
+    pub parameters: Vec<(String, Type)>,
+    /// The type of the value returned, which is `None` for `Nil`.
+    pub result: Option<Type>,
+}
+
+/// The types and functions exported for a module.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Interface {
+    pub module: String,
+    pub types: Vec<Arc<TypeDefinition>>,
+    pub functions: Vec<Function>,
+}
+
+impl Interface {
+    pub fn name(&self) -> String {
+        interface_name(&self.module)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Component {
+    pub package: String,
+    pub interfaces: Vec<Interface>,
+}
+
+impl Component {
+    /// The component exporting the public functions of the modules of a
+    /// package, which can be described in WIT.
+    pub fn new(package: &str, modules: &[&TypedModule]) -> Self {
+        let mut resolver = Resolver::new(modules);
+        let mut interfaces = modules
+            .iter()
+            .map(|module| Interface {
+                module: module.name.join("/"),
+                types: vec![],
+                functions: resolver.functions(module),
+            })
+            .collect_vec();
+
+        // The types of each interface are those used by the functions of any
+        // interface, in the order they are defined in their module.
+        for interface in interfaces.iter_mut() {
+            interface.types = resolver
+                .candidates
+                .iter()
+                .filter(|(key, _)| key.0 == interface.module)
+                .filter_map(|(key, _)| resolver.definitions.get(key).cloned().flatten())
+                .collect();
+        }
+        interfaces
+            .retain(|interface| !interface.types.is_empty() || !interface.functions.is_empty());
+
+        Self {
+            package: package.into(),
+            interfaces,
+        }
+    }
+
+    /// The name of the WIT package, such as `gleam:my-app`.
+    pub fn package_name(&self) -> String {
+        format!("{NAMESPACE}:{}", kebab(&self.package))
+    }
+
+    /// The name a function is exported with, such as
+    /// `gleam:my-app/my-app#greet`.
+    pub fn export_name(&self, interface: &Interface, function: &Function) -> String {
+        format!(
+            "{}/{}#{}",
+            self.package_name(),
+            interface.name(),
+            kebab(&function.name)
+        )
+    }
+
+    /// The WIT definition of the package.
+    pub fn wit(&self) -> String {
+        wit::package(self)
+    }
+}
+
+/// Describes the types of a package, remembering the custom types that have
+/// been described so that each is only described once.
+#[derive(Debug)]
+struct Resolver<'a> {
+    /// The custom types that could be described, by module and name, in the
+    /// order they are defined.
+    candidates: Vec<((String, String), &'a [TypedRecordConstructor])>,
+    definitions: HashMap<(String, String), Option<Arc<TypeDefinition>>>,
+    /// The custom types being described, which cannot refer to themselves.
+    describing: HashSet<(String, String)>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(modules: &[&'a TypedModule]) -> Self {
+        let mut candidates = vec![];
+        for module in modules {
+            for statement in &module.statements {
+                if let Statement::CustomType {
+                    name,
+                    parameters,
+                    public: true,
+                    opaque: false,
+                    constructors,
+                    ..
+                } = statement
+                {
+                    if parameters.is_empty() && !constructors.is_empty() {
+                        let key = (module.name.join("/"), name.to_string());
+                        candidates.push((key, constructors.as_slice()));
+                    }
+                }
+            }
+        }
+        Self {
+            candidates,
+            definitions: HashMap::new(),
+            describing: HashSet::new(),
+        }
+    }
+
+    /// The public functions of a module that can be exported. A function
+    /// with the same name as one of the module's types cannot be.
+    fn functions(&mut self, module: &TypedModule) -> Vec<Function> {
+        let name = module.name.join("/");
+        let types: HashSet<_> = self
+            .candidates
+            .iter()
+            .filter(|(key, _)| key.0 == name)
+            .map(|(key, _)| kebab(&key.1))
+            .collect();
+        module
+            .statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Fn {
+                    name,
+                    arguments,
+                    return_type,
+                    public: true,
+                    ..
+                } => {
+                    let parameters = arguments
+                        .iter()
+                        .map(|argument| (argument.names.get_variable_name(), &argument.type_));
+                    self.function(name, None, parameters, return_type)
+                }
+                Statement::ExternalFn {
+                    name,
+                    arguments,
+                    return_type,
+                    fun,
+                    public: true,
+                    ..
+                } => {
+                    let parameters = arguments
+                        .iter()
+                        .map(|argument| (argument.label.as_deref(), &argument.type_));
+                    self.function(name, Some(fun), parameters, return_type)
+                }
+                _ => None,
+            })
+            .filter(|function| !types.contains(&kebab(&function.name)))
+            .collect()
+    }
+
+    fn function<'b>(
+        &mut self,
+        name: &str,
+        external: Option<&str>,
+        parameters: impl Iterator<Item = (Option<&'b str>, &'b Arc<type_::Type>)>,
+        return_type: &Arc<type_::Type>,
+    ) -> Option<Function> {
+        let mut names = HashSet::new();
+        let parameters = parameters
+            .enumerate()
+            .map(|(i, (name, type_))| {
+                let name = match name.map(kebab) {
+                    Some(name) if !name.is_empty() && names.insert(name.clone()) => name,
+                    _ => format!("arg{i}"),
+                };
+                Some((name, self.type_(type_)?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let result = match collapse_links(return_type.clone()).is_nil() {
+            true => None,
+            false => Some(self.type_(return_type)?),
+        };
+        Some(Function {
+            name: name.into(),
+            external: external.map(String::from),
+            parameters,
+            result,
+        })
+    }
+
+    fn type_(&mut self, type_: &Arc<type_::Type>) -> Option<Type> {
+        let type_ = collapse_links(type_.clone());
+        if type_.is_int() {
+            return Some(Type::S64);
+        }
+        if type_.is_float() {
+            return Some(Type::F64);
+        }
+        if type_.is_bool() {
+            return Some(Type::Bool);
+        }
+        if type_.is_string() {
+            return Some(Type::String);
+        }
+        if let Some(element) = type_.list_element_type() {
+            return Some(Type::List(Box::new(self.type_(&element)?)));
+        }
+        match type_.as_ref() {
+            type_::Type::Tuple { elems } if !elems.is_empty() => elems
+                .iter()
+                .map(|element| self.type_(element))
+                .collect::<Option<_>>()
+                .map(Type::Tuple),
+            type_::Type::App { args, .. } if type_.is_result() => {
+                let [ok, error] = args.as_slice() else {
+                    return None;
+                };
+                // Either side can hold `Nil`, in which case it has no value.
+                let ok = match collapse_links(ok.clone()).is_nil() {
+                    true => None,
+                    false => Some(Box::new(self.type_(ok)?)),
+                };
+                let error = match collapse_links(error.clone()).is_nil() {
+                    true => None,
+                    false => Some(Box::new(self.type_(error)?)),
+                };
+                Some(Type::Result { ok, error })
+            }
+            type_::Type::App {
+                module, name, args, ..
+            } if !module.is_empty() && args.is_empty() => self
+                .definition((module.join("/"), name.to_string()))
+                .map(Type::Named),
+            _ => None,
+        }
+    }
+
+    fn definition(&mut self, key: (String, String)) -> Option<Arc<TypeDefinition>> {
+        if let Some(definition) = self.definitions.get(&key) {
+            return definition.clone();
+        }
+        let constructors = self
+            .candidates
+            .iter()
+            .find(|(candidate, _)| *candidate == key)
+            .map(|(_, constructors)| *constructors)?;
+        if !self.describing.insert(key.clone()) {
+            return None;
+        }
+        let kind = self.kind(constructors);
+        let _ = self.describing.remove(&key);
+
+        let definition = kind.map(|kind| {
+            Arc::new(TypeDefinition {
+                module: key.0.clone(),
+                name: key.1.clone(),
+                kind,
+            })
+        });
+        let _ = self.definitions.insert(key, definition.clone());
+        definition
+    }
+
+    fn kind(&mut self, constructors: &[TypedRecordConstructor]) -> Option<TypeKind> {
+        if constructors
+            .iter()
+            .all(|constructor| constructor.arguments.is_empty())
+        {
+            let cases = constructors
+                .iter()
+                .map(|constructor| constructor.name.to_string())
+                .collect();
+            return Some(TypeKind::Enum(cases));
+        }
+
+        if let [constructor] = constructors {
+            let labelled = constructor
+                .arguments
+                .iter()
+                .map(|argument| Some((argument.label.as_ref()?.to_string(), &argument.type_)))
+                .collect::<Option<Vec<_>>>();
+            if let Some(labelled) = labelled {
+                let fields = labelled
+                    .into_iter()
+                    .map(|(label, type_)| Some((label, self.type_(type_)?)))
+                    .collect::<Option<_>>()?;
+                return Some(TypeKind::Record {
+                    constructor: constructor.name.to_string(),
+                    fields,
+                });
+            }
+        }
+
+        constructors
+            .iter()
+            .map(|constructor| {
+                let fields = constructor
+                    .arguments
+                    .iter()
+                    .map(|argument| self.type_(&argument.type_))
+                    .collect::<Option<_>>()?;
+                Some(Case {
+                    constructor: constructor.name.to_string(),
+                    fields,
+                })
+            })
+            .collect::<Option<_>>()
+            .map(TypeKind::Variant)
+    }
+}
+
+/// The interface of a module, such as `my-app-user` for `my_app/user`.
+fn interface_name(module: &str) -> String {
+    kebab(&module.replace('/', "_"))
+}
+
+/// A Gleam name in kebab case, as WIT names are. Each word of a WIT name must
+/// start with a letter, so a word starting with a digit is joined to the one
+/// before it.
+pub fn kebab(name: &str) -> String {
+    let mut words: Vec<String> = vec![];
+    for word in name.to_kebab_case().split('-') {
+        match words.last_mut() {
+            Some(last) if word.starts_with(|c: char| c.is_ascii_digit()) => last.push_str(word),
+            _ => words.push(word.into()),
+        }
+    }
+    words.join("-")
+}
@@ -0,0 +1,314 @@
+use super::{kebab, Component};
+use crate::{
+    ast::TypedModule,
+    build::{Origin, Target},
+    type_::{build_prelude, infer_module},
+    uid::UniqueIdGenerator,
+};
+
+#[macro_export]
+macro_rules! assert_wit {
+    ($src:expr $(,)?) => {{
+        let component = $crate::wasm::tests::compile(&[("my/module", $src)]);
+        insta::assert_snapshot!(insta::internals::AutoName, component.wit(), $src);
+    }};
+}
+
+#[macro_export]
+macro_rules! assert_exports {
+    ($src:expr $(,)?) => {{
+        let component = $crate::wasm::tests::compile(&[("my/module", $src)]);
+        let output = $crate::llvm::component_exports(&component);
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    }};
+}
+
+/// Type checks the modules in order, each of which can import those before
+/// it, and describes the component exporting them.
+pub fn compile(sources: &[(&str, &str)]) -> Component {
+    let ids = UniqueIdGenerator::new();
+    let mut modules = im::HashMap::new();
+    let _ = modules.insert("gleam".to_string(), build_prelude(&ids));
+    let mut typed: Vec<TypedModule> = vec![];
+    for (name, src) in sources {
+        let arena = crate::ast::Arena::new();
+        let (mut ast, _) = crate::parse::parse_module(src, &arena).expect("syntax error");
+        ast.name = name.split('/').map(Into::into).collect();
+        let module = infer_module(
+            Target::Wasm,
+            &ids,
+            ast,
+            Origin::Src,
+            "my_package",
+            &modules,
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let _ = modules.insert(name.to_string(), module.type_info.clone());
+        typed.push(module);
+    }
+    Component::new("my_package", &typed.iter().collect::<Vec<_>>())
+}
+
+#[test]
+fn kebab_case() {
+    assert_eq!(kebab("add_one"), "add-one");
+    assert_eq!(kebab("HttpRequest"), "http-request");
+    assert_eq!(kebab("UUID"), "uuid");
+    assert_eq!(kebab("base_64"), "base64");
+    assert_eq!(kebab("Utf8Decoder"), "utf8-decoder");
+}
+
+#[test]
+fn scalars() {
+    assert_wit!(
+        r#"
+pub fn add(x: Int, y: Int) -> Int {
+  x + y
+}
+
+pub fn scale(value: Float, by factor: Float) -> Float {
+  value *. factor
+}
+
+pub fn negate(value: Bool) -> Bool {
+  !value
+}
+
+pub fn greet(name: String) -> String {
+  "Hello, " <> name
+}
+
+pub fn ignore(_value: Int) -> Nil {
+  Nil
+}
+"#
+    );
+}
+
+#[test]
+fn collections() {
+    assert_wit!(
+        r#"
+pub fn pairs(values: List(Int)) -> List(#(Int, String)) {
+  todo
+}
+
+pub fn parse(text: String) -> Result(Int, String) {
+  todo
+}
+
+pub fn check(value: Int) -> Result(Nil, Nil) {
+  todo
+}
+"#
+    );
+}
+
+#[test]
+fn custom_types() {
+    assert_wit!(
+        r#"
+pub type Colour {
+  Red
+  Green
+  Blue
+}
+
+pub type Person {
+  Person(name: String, age: Int, favourite: Colour)
+}
+
+pub type Shape {
+  Circle(Float)
+  Rectangle(width: Float, height: Float)
+  Empty
+}
+
+pub fn describe(person: Person, shape: Shape) -> String {
+  todo
+}
+
+pub fn colours() -> List(Colour) {
+  [Red, Green, Blue]
+}
+"#
+    );
+}
+
+#[test]
+fn unsupported_functions_are_not_exported() {
+    assert_wit!(
+        r#"
+pub opaque type Secret {
+  Secret(Int)
+}
+
+pub type Box(a) {
+  Box(a)
+}
+
+pub type Tree {
+  Leaf
+  Node(Tree, Tree)
+}
+
+pub fn reveal(secret: Secret) -> Int {
+  todo
+}
+
+pub fn unbox(box: Box(Int)) -> Int {
+  todo
+}
+
+pub fn identity(value: a) -> a {
+  value
+}
+
+pub fn apply(f: fn(Int) -> Int) -> Int {
+  f(1)
+}
+
+pub fn size(tree: Tree) -> Int {
+  todo
+}
+
+pub fn nothing(value: Nil) -> Int {
+  1
+}
+
+fn private(value: Int) -> Int {
+  value
+}
+
+pub fn supported(value: Int) -> Int {
+  private(value)
+}
+"#
+    );
+}
+
+#[test]
+fn keywords_are_escaped() {
+    assert_wit!(
+        r#"
+pub type Record {
+  Record(list: List(Int), string: String)
+}
+
+pub fn export(record: Record, list: Int) -> Int {
+  list
+}
+"#
+    );
+}
+
+#[test]
+fn types_of_other_modules_are_used() {
+    let component = compile(&[
+        (
+            "my/shapes",
+            r#"
+pub type Shape {
+  Circle(Float)
+  Square(Float)
+}
+"#,
+        ),
+        (
+            "my/app",
+            r#"
+import my/shapes.{Shape}
+
+pub fn area(shape: Shape) -> Float {
+  todo
+}
+"#,
+        ),
+    ]);
+    insta::assert_snapshot!(component.wit());
+}
+
+#[test]
+fn exported_scalars() {
+    assert_exports!(
+        r#"
+pub fn add(x: Int, y: Float, z: Bool) -> Bool {
+  todo
+}
+
+pub fn log(message: String) -> Nil {
+  Nil
+}
+"#
+    );
+}
+
+#[test]
+fn exported_lists_and_strings() {
+    assert_exports!(
+        r#"
+pub fn words(text: String) -> List(String) {
+  todo
+}
+"#
+    );
+}
+
+#[test]
+fn exported_custom_types() {
+    assert_exports!(
+        r#"
+pub type Colour {
+  Red
+  Green
+}
+
+pub type Shape {
+  Circle(Float)
+  Rectangle(Int, Colour)
+}
+
+pub fn paint(shape: Shape, colour: Colour) -> Result(Shape, Nil) {
+  todo
+}
+
+pub fn colour(shape: Shape) -> Colour {
+  todo
+}
+"#
+    );
+}
+
+#[test]
+fn exported_external_functions() {
+    assert_exports!(
+        r#"
+@external(wasm, "ffi.ll", "random")
+pub fn random() -> Float {
+  todo("in ffi.ll")
+}
+"#
+    );
+}
+
+#[test]
+fn arguments_passed_in_memory() {
+    assert_exports!(
+        r#"
+pub fn many(
+  a: String,
+  b: String,
+  c: String,
+  d: String,
+  e: String,
+  f: String,
+  g: String,
+  h: String,
+  i: String,
+) -> Int {
+  todo
+}
+"#
+    );
+}
@@ -0,0 +1,232 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use itertools::Itertools;
+
+use super::{interface_name, kebab, Component, Interface, Type, TypeDefinition, TypeKind, WORLD};
+
+/// WIT names that are keywords, which are escaped with a `%`.
+const KEYWORDS: &[&str] = &[
+    "as",
+    "async",
+    "bool",
+    "borrow",
+    "char",
+    "constructor",
+    "enum",
+    "export",
+    "f32",
+    "f64",
+    "flags",
+    "float32",
+    "float64",
+    "from",
+    "func",
+    "future",
+    "import",
+    "include",
+    "interface",
+    "list",
+    "option",
+    "own",
+    "package",
+    "record",
+    "resource",
+    "result",
+    "s16",
+    "s32",
+    "s64",
+    "s8",
+    "static",
+    "stream",
+    "string",
+    "tuple",
+    "type",
+    "u16",
+    "u32",
+    "u64",
+    "u8",
+    "use",
+    "variant",
+    "with",
+    "world",
+];
+
+pub(super) fn package(component: &Component) -> String {
+    let mut wit = format!("package {};\n", component.package_name());
+    for interface in &component.interfaces {
+        wit.push('\n');
+        wit.push_str(&self::interface(interface));
+    }
+    let _ = writeln!(wit, "\nworld {WORLD} {{");
+    for interface in &component.interfaces {
+        let _ = writeln!(wit, "  export {};", identifier(&interface.name()));
+    }
+    wit.push_str("}\n");
+    wit
+}
+
+fn interface(interface: &Interface) -> String {
+    let mut wit = format!("interface {} {{\n", identifier(&interface.name()));
+
+    // Types of other modules are used from their interfaces.
+    let mut uses: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut used = |type_: &Type| {
+        for definition in named_types(type_) {
+            if definition.module != interface.module {
+                let names = uses.entry(definition.module.clone()).or_default();
+                if !names.contains(&definition.name) {
+                    names.push(definition.name.clone());
+                }
+            }
+        }
+    };
+    for definition in &interface.types {
+        for type_ in field_types(definition) {
+            used(type_);
+        }
+    }
+    for function in &interface.functions {
+        for (_, type_) in &function.parameters {
+            used(type_);
+        }
+        if let Some(result) = &function.result {
+            used(result);
+        }
+    }
+    for (module, names) in &uses {
+        let _ = writeln!(
+            wit,
+            "  use {}.{{{}}};",
+            identifier(&interface_name(module)),
+            names.iter().map(|name| identifier(&kebab(name))).join(", ")
+        );
+    }
+
+    let mut blocks = interface
+        .types
+        .iter()
+        .map(|definition| type_definition(definition))
+        .collect_vec();
+    let functions = interface
+        .functions
+        .iter()
+        .map(|function| {
+            let parameters = function
+                .parameters
+                .iter()
+                .map(|(name, type_)| format!("{}: {}", identifier(name), self::type_(type_)))
+                .join(", ");
+            let result = match &function.result {
+                Some(result) => format!(" -> {}", type_(result)),
+                None => String::new(),
+            };
+            format!(
+                "  {}: func({parameters}){result};\n",
+                identifier(&kebab(&function.name))
+            )
+        })
+        .join("");
+    if !functions.is_empty() {
+        blocks.push(functions);
+    }
+    let blocks = blocks.join("\n");
+    if !uses.is_empty() && !blocks.is_empty() {
+        wit.push('\n');
+    }
+    wit.push_str(&blocks);
+    wit.push_str("}\n");
+    wit
+}
+
+fn type_definition(definition: &TypeDefinition) -> String {
+    let name = identifier(&kebab(&definition.name));
+    let (keyword, items) = match &definition.kind {
+        TypeKind::Enum(cases) => (
+            "enum",
+            cases
+                .iter()
+                .map(|case| identifier(&kebab(case)))
+                .collect_vec(),
+        ),
+        TypeKind::Record { fields, .. } => (
+            "record",
+            fields
+                .iter()
+                .map(|(label, type_)| {
+                    format!("{}: {}", identifier(&kebab(label)), self::type_(type_))
+                })
+                .collect(),
+        ),
+        TypeKind::Variant(cases) => (
+            "variant",
+            cases
+                .iter()
+                .map(|case| {
+                    let name = identifier(&kebab(&case.constructor));
+                    match case.fields.as_slice() {
+                        [] => name,
+                        [field] => format!("{name}({})", type_(field)),
+                        fields => format!("{name}(tuple<{}>)", fields.iter().map(type_).join(", ")),
+                    }
+                })
+                .collect(),
+        ),
+    };
+    let items = items
+        .into_iter()
+        .map(|item| format!("    {item},\n"))
+        .join("");
+    format!("  {keyword} {name} {{\n{items}  }}\n")
+}
+
+fn type_(type_: &Type) -> String {
+    match type_ {
+        Type::S64 => "s64".into(),
+        Type::F64 => "f64".into(),
+        Type::Bool => "bool".into(),
+        Type::String => "string".into(),
+        Type::List(element) => format!("list<{}>", self::type_(element)),
+        Type::Tuple(elements) => format!("tuple<{}>", elements.iter().map(self::type_).join(", ")),
+        Type::Result { ok, error } => match (ok, error) {
+            (None, None) => "result".into(),
+            (Some(ok), None) => format!("result<{}>", self::type_(ok)),
+            (None, Some(error)) => format!("result<_, {}>", self::type_(error)),
+            (Some(ok), Some(error)) => {
+                format!("result<{}, {}>", self::type_(ok), self::type_(error))
+            }
+        },
+        Type::Named(definition) => identifier(&kebab(&definition.name)),
+    }
+}
+
+/// The types of the fields of a custom type.
+fn field_types(definition: &TypeDefinition) -> Vec<&Type> {
+    match &definition.kind {
+        TypeKind::Enum(_) => vec![],
+        TypeKind::Record { fields, .. } => fields.iter().map(|(_, type_)| type_).collect(),
+        TypeKind::Variant(cases) => cases.iter().flat_map(|case| &case.fields).collect(),
+    }
+}
+
+/// The custom types named by a type, without those used by their fields.
+fn named_types(type_: &Type) -> Vec<&TypeDefinition> {
+    match type_ {
+        Type::S64 | Type::F64 | Type::Bool | Type::String => vec![],
+        Type::List(element) => named_types(element),
+        Type::Tuple(elements) => elements.iter().flat_map(named_types).collect(),
+        Type::Result { ok, error } => ok
+            .iter()
+            .chain(error)
+            .flat_map(|type_| named_types(type_))
+            .collect(),
+        Type::Named(definition) => vec![definition],
+    }
+}
+
+fn identifier(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("%{name}")
+    } else {
+        name.into()
+    }
+}
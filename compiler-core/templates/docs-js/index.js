@@ -32,9 +32,12 @@ window.Gleam = (function () {
 
   self.scrollToHash = function () {
     const locationHash = arguments[0] || window.location.hash;
-    const query = locationHash ? locationHash : "body";
-    const hashTop = document.querySelector(query).offsetTop;
-    window.scrollTo(0, hashTop - self.hashOffset);
+    const target = locationHash
+      ? document.getElementById(decodeURIComponent(locationHash.slice(1)))
+      : document.body;
+    if (target) {
+      window.scrollTo(0, target.offsetTop - self.hashOffset);
+    }
     return locationHash;
   };
 
@@ -168,10 +171,108 @@ window.Gleam = (function () {
     else el.addEventListener(type, handler);
   };
 
-  const searchLoaded = function (index, docs) {
+  // How well the query matches the text, with the query's characters in the
+  // same order but not necessarily next to each other. Characters that follow
+  // one another or that start a word score higher, and shorter texts score
+  // higher than longer ones. Returns null if the text does not match.
+  const fuzzyMatch = function (query, text) {
+    query = query.toLowerCase();
+    const lowerText = text.toLowerCase();
+    const isWordStart = (i) => i == 0 || "_./".includes(text[i - 1]);
+
+    // Each character after the first continues the previous match if it can,
+    // or else starts the next word it can while leaving the rest matchable
+    const matchFrom = function (start) {
+      const positions = [start];
+      let score = isWordStart(start) ? 3 : 1;
+      for (let i = 1; i < query.length; i++) {
+        const from = positions[i - 1] + 1;
+        const rest = query.substring(i + 1);
+        const candidates = [from];
+        for (let j = from; j < lowerText.length; j++) {
+          if (isWordStart(j)) {
+            candidates.push(j);
+          }
+        }
+        candidates.push(lowerText.indexOf(query[i], from));
+        const found = candidates.find(
+          (candidate) =>
+            candidate >= 0 &&
+            lowerText[candidate] == query[i] &&
+            isSubsequence(rest, candidate + 1)
+        );
+        if (found === undefined) {
+          return null;
+        }
+        score += found == from ? 5 : isWordStart(found) ? 3 : 1;
+        positions.push(found);
+      }
+      return { score: score, positions: positions };
+    };
+    const isSubsequence = function (rest, from) {
+      for (const char of rest) {
+        from = lowerText.indexOf(char, from);
+        if (from < 0) {
+          return false;
+        }
+        from++;
+      }
+      return true;
+    };
+
+    let best = null;
+    for (let start = 0; start < lowerText.length; start++) {
+      if (lowerText[start] != query[0]) {
+        continue;
+      }
+      const match = matchFrom(start);
+      if (match && (!best || match.score > best.score)) {
+        best = match;
+      }
+    }
+    if (!best) {
+      return null;
+    }
+    if (lowerText == query) {
+      best.score += 10;
+    }
+    best.score -= text.length / 100;
+    return best;
+  };
+
+  // The definitions whose names best match the query, best first. A query
+  // with a `.` or `/` is matched against the module and name of each.
+  const matchingDefinitions = function (definitions, query, limit) {
+    query = query.trim();
+    const qualified = /[./]/.test(query);
+    const matches = [];
+    for (const definition of definitions) {
+      const [name, kind, module, signature, summary] = definition;
+      const prefix = qualified ? `${module}.` : "";
+      const match = fuzzyMatch(query, prefix + name);
+      if (match) {
+        matches.push({
+          name: name,
+          kind: kind,
+          module: module,
+          signature: signature,
+          summary: summary,
+          score: match.score,
+          positions: match.positions
+            .filter((position) => position >= prefix.length)
+            .map((position) => position - prefix.length),
+        });
+      }
+    }
+    matches.sort((a, b) => b.score - a.score);
+    return matches.slice(0, limit);
+  };
+
+  const searchLoaded = function (index, docs, definitions) {
     const preview_words_after = 10;
     const preview_words_before = 5;
     const previews = 3;
+    const definition_results = 8;
 
     const searchInput = document.getElementById("search-input");
     const searchNavButton = document.getElementById("search-nav-button");
@@ -232,7 +333,11 @@ window.Gleam = (function () {
         }
       }
 
-      if (results.length == 0) {
+      const matches = /\s/.test(input.trim())
+        ? []
+        : matchingDefinitions(definitions, input, definition_results);
+
+      if (results.length == 0 && matches.length == 0) {
         const noResultsDiv = document.createElement("div");
         noResultsDiv.classList.add("search-no-result");
         noResultsDiv.innerText = "No results found";
@@ -242,9 +347,76 @@ window.Gleam = (function () {
         resultsList.classList.add("search-results-list");
         searchResults.appendChild(resultsList);
 
+        for (const match of matches) {
+          addDefinitionResult(resultsList, match);
+        }
         addResults(resultsList, results, 0, 10, 100, currentSearchIndex);
       }
 
+      function addDefinitionResult(resultsList, match) {
+        const resultsListItem = document.createElement("li");
+        resultsListItem.classList.add("search-results-list-item");
+        resultsList.appendChild(resultsListItem);
+        const resultLink = document.createElement("a");
+        resultLink.classList.add("search-result");
+        const url = `${match.module}.html#${match.name}`;
+        resultLink.setAttribute("href", `${window.unnest}/${url}`);
+        resultsListItem.appendChild(resultLink);
+        const resultTitle = document.createElement("div");
+        resultTitle.classList.add("search-result-title");
+        resultLink.appendChild(resultTitle);
+        const resultDoc = document.createElement("div");
+        resultDoc.classList.add(
+          "search-result-doc",
+          "search-result-doc-parent"
+        );
+        resultDoc.innerHTML =
+          '<svg viewBox="0 0 24 24" class="search-result-icon"><use xlink:href="#icon-svg-doc"></use></svg>';
+        resultTitle.appendChild(resultDoc);
+        const resultDocTitle = document.createElement("div");
+        resultDocTitle.classList.add("search-result-doc-title");
+        resultDocTitle.textContent = match.module;
+        resultDoc.appendChild(resultDocTitle);
+        const resultSection = document.createElement("div");
+        resultSection.classList.add("search-result-section");
+        let index = 0;
+        for (const position of match.positions) {
+          resultSection.appendChild(
+            document.createTextNode(match.name.substring(index, position))
+          );
+          const highlight = document.createElement("span");
+          highlight.classList.add("search-result-highlight");
+          highlight.textContent = match.name[position];
+          resultSection.appendChild(highlight);
+          index = position + 1;
+        }
+        resultSection.appendChild(
+          document.createTextNode(match.name.substring(index))
+        );
+        resultTitle.appendChild(resultSection);
+        const resultKind = document.createElement("span");
+        resultKind.classList.add("search-result-kind");
+        resultKind.textContent = match.kind;
+        resultTitle.appendChild(resultKind);
+        const resultPreviews = document.createElement("div");
+        resultPreviews.classList.add("search-result-previews");
+        resultLink.appendChild(resultPreviews);
+        const resultSignature = document.createElement("code");
+        resultSignature.classList.add("search-result-signature");
+        resultSignature.textContent = match.signature;
+        resultPreviews.appendChild(resultSignature);
+        if (match.summary) {
+          const resultPreview = document.createElement("div");
+          resultPreview.classList.add("search-result-preview");
+          resultPreview.textContent = match.summary;
+          resultPreviews.appendChild(resultPreview);
+        }
+        const resultRelUrl = document.createElement("span");
+        resultRelUrl.classList.add("search-result-rel-url");
+        resultRelUrl.innerText = url;
+        resultTitle.appendChild(resultRelUrl);
+      }
+
       function addResults(
         resultsList,
         results,
@@ -538,7 +710,7 @@ window.Gleam = (function () {
     });
   };
 
-  self.initSearch = function initSeach(docs) {
+  self.initSearch = function initSeach(docs, definitions) {
     // enable support for hyphenated search words
     lunr.tokenizer.separator = /[\s/]+/;
 
@@ -559,7 +731,7 @@ window.Gleam = (function () {
       }
     });
 
-    searchLoaded(index, docs);
+    searchLoaded(index, docs, definitions || []);
   };
 
   const init = function () {
@@ -0,0 +1,65 @@
+"use strict";
+
+/* Documentation read offline is a single file, with each of its pages
+ * written into `gleamPages` by path. The page shown is the one named by the
+ * fragment of the URL, such as `#/gleam/list.html#map`, and the rest of the
+ * fragment is the definition on the page to scroll to. */
+void function () {
+  const parser = new DOMParser();
+  let current = gleamFirstPage;
+
+  const highlight = function () {
+    document.querySelectorAll("main.content pre code").forEach((elem) => {
+      if (elem.className === "") {
+        elem.classList.add("gleam");
+      }
+      if (!elem.classList.contains("hljs")) {
+        hljs.highlightElement(elem);
+      }
+    });
+  };
+
+  const show = function (path) {
+    const page = parser.parseFromString(gleamPages[path], "text/html");
+    document.title = page.title;
+
+    /* The sidebar button and its listeners are kept */
+    const sidebar = document.querySelector("nav.sidebar");
+    const toggle = sidebar.querySelector(".sidebar-toggle");
+    const items = Array.from(page.querySelector("nav.sidebar").children)
+      .filter((elem) => !elem.classList.contains("sidebar-toggle"));
+    sidebar.replaceChildren(toggle, ...items);
+
+    document.querySelector("main.content")
+      .replaceChildren(...page.querySelector("main.content").childNodes);
+    current = path;
+    highlight();
+  };
+
+  const route = function () {
+    const hash = window.location.hash;
+    if (!hash.startsWith("#/")) {
+      /* A link to a definition of the page shown, which index.js scrolls
+       * to. The page is kept in the URL so that it is shown on reload. */
+      if (hash) {
+        history.replaceState(null, "", `#/${current}${hash}`);
+      }
+      return;
+    }
+
+    const fragment = hash.slice(2);
+    const index = fragment.indexOf("#");
+    const path = (-1 === index ? fragment : fragment.slice(0, index)) || "index.html";
+    const id = -1 === index ? "" : fragment.slice(index + 1);
+    if (path !== current && gleamPages.hasOwnProperty(path)) {
+      show(path);
+      Gleam.toggleSidebar(false);
+    }
+    const target = id ? document.getElementById(decodeURIComponent(id)) : null;
+    window.scrollTo(0, target ? target.offsetTop - Gleam.hashOffset : 0);
+  };
+
+  highlight();
+  window.addEventListener("hashchange", route);
+  route();
+}();
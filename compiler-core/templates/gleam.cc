@@ -1,7 +1,174 @@
 #include "gleam.h"
 
+#include <charconv>
+#include <cmath>
+#include <cstdlib>
+#include <cstring>
+#include <exception>
+
 namespace gleam {
 
-String MakeString(char* str) { return MakeRef<std::string>(str); }
+namespace {
+
+// Reports a panic that was not rescued and exits, rather than aborting with
+// the default message for an uncaught exception.
+[[noreturn]] void Terminate() {
+  try {
+    if (auto exception = std::current_exception()) {
+      std::rethrow_exception(exception);
+    }
+  } catch (const Panic& panic) {
+    std::cerr << "panic: " << *panic.message() << std::endl;
+    std::_Exit(1);
+  } catch (...) {
+  }
+  std::abort();
+}
+
+[[maybe_unused]] const std::terminate_handler default_terminate =
+    std::set_terminate(Terminate);
+
+}  // namespace
+
+String MakeString(const char* str) { return MakeRef<std::string>(str); }
+
+Crashed Crash(const String& message) { throw Panic(message); }
+
+BitStringBuilder& BitStringBuilder::Int(int64_t value, int64_t size) {
+  if (size < 0) Crash(MakeString("Bit string segment size is negative"));
+  for (int64_t i = size - 1; i >= 0; --i) {
+    Push(i < 64 ? (value >> i) & 1 : value < 0);
+  }
+  return *this;
+}
+
+BitStringBuilder& BitStringBuilder::Float(double value) {
+  uint64_t bits;
+  std::memcpy(&bits, &value, sizeof(bits));
+  for (int i = 63; i >= 0; --i) Push((bits >> i) & 1);
+  return *this;
+}
+
+BitStringBuilder& BitStringBuilder::Utf8(const String& value) {
+  for (unsigned char byte : *value) {
+    for (int i = 7; i >= 0; --i) Push((byte >> i) & 1);
+  }
+  return *this;
+}
+
+BitStringBuilder& BitStringBuilder::Bits(const Ref<BitString>& value) {
+  for (int64_t i = 0; i < value->size(); ++i) Push(value->bit(i));
+  return *this;
+}
+
+Ref<BitString> BitStringBuilder::Build() {
+  return MakeRef<BitString>(bytes_, size_);
+}
+
+void BitStringBuilder::Push(bool bit) {
+  if (size_ % 8 == 0) bytes_.push_back(0);
+  if (bit) bytes_.back() |= 1 << (7 - size_ % 8);
+  ++size_;
+}
+
+int64_t BitStringSize(const Ref<BitString>& bits) { return bits->size(); }
+
+int64_t BitStringInt(const Ref<BitString>& bits, int64_t offset,
+                     int64_t size) {
+  uint64_t value = 0;
+  for (int64_t i = offset; i < offset + size; ++i) {
+    value = (value << 1) | bits->bit(i);
+  }
+  return static_cast<int64_t>(value);
+}
+
+double BitStringFloat(const Ref<BitString>& bits, int64_t offset) {
+  uint64_t value = 0;
+  for (int64_t i = offset; i < offset + 64; ++i) {
+    value = (value << 1) | bits->bit(i);
+  }
+  double result;
+  std::memcpy(&result, &value, sizeof(result));
+  return result;
+}
+
+Ref<BitString> BitStringSlice(const Ref<BitString>& bits, int64_t offset,
+                              int64_t size) {
+  BitStringBuilder builder;
+  for (int64_t i = offset; i < offset + size; ++i) {
+    builder.Int(bits->bit(i), 1);
+  }
+  return builder.Build();
+}
+
+String FloatToString(double value) {
+  if (std::isnan(value)) return MakeRef<std::string>("NaN");
+  if (std::isinf(value)) {
+    return MakeRef<std::string>(value < 0 ? "-Infinity" : "Infinity");
+  }
+
+  // Without a precision std::to_chars gives the shortest digits that
+  // round-trip, such as `1.5e-07` or `-1e+21`.
+  char buffer[32];
+  auto result = std::to_chars(buffer, buffer + sizeof(buffer), value,
+                              std::chars_format::scientific);
+  std::string scientific(buffer, result.ptr);
+  auto e = scientific.find('e');
+  int exponent = std::stoi(scientific.substr(e + 1));
+  std::string sign;
+  std::string digits;
+  for (char c : scientific.substr(0, e)) {
+    if (c == '-') {
+      sign = "-";
+    } else if (c != '.') {
+      digits.push_back(c);
+    }
+  }
+
+  std::string text;
+  if (exponent >= -6 && exponent < 21) {
+    if (exponent < 0) {
+      text = "0." + std::string(-exponent - 1, '0') + digits;
+    } else {
+      size_t integer_length = exponent + 1;
+      if (digits.size() > integer_length) {
+        text = digits.substr(0, integer_length) + "." +
+               digits.substr(integer_length);
+      } else {
+        text = digits + std::string(integer_length - digits.size(), '0') + ".0";
+      }
+    }
+  } else {
+    std::string rest = digits.size() > 1 ? digits.substr(1) : "0";
+    text = digits.substr(0, 1) + "." + rest + "e" + std::to_string(exponent);
+  }
+  return MakeRef<std::string>(sign + text);
+}
+
+std::optional<double> ParseFloat(const std::string& str) {
+  size_t i = 0;
+  auto digits = [&]() {
+    size_t start = i;
+    while (i < str.size() && str[i] >= '0' && str[i] <= '9') ++i;
+    return i > start;
+  };
+
+  if (i < str.size() && str[i] == '-') ++i;
+  if (!digits()) return std::nullopt;
+  if (i == str.size() || str[i] != '.') return std::nullopt;
+  ++i;
+  if (!digits()) return std::nullopt;
+  if (i < str.size() && (str[i] == 'e' || str[i] == 'E')) {
+    ++i;
+    if (i < str.size() && (str[i] == '-' || str[i] == '+')) ++i;
+    if (!digits()) return std::nullopt;
+  }
+  if (i != str.size()) return std::nullopt;
+
+  double value;
+  auto result = std::from_chars(str.data(), str.data() + str.size(), value);
+  if (result.ec != std::errc() || !std::isfinite(value)) return std::nullopt;
+  return value;
+}
 
 }  // namespace gleam
@@ -0,0 +1,205 @@
+// Package gleam is the runtime support for Go code generated by the Gleam
+// compiler. It defines the types of the Gleam prelude along with the helpers
+// that the generated code and externals make use of.
+package gleam
+
+import (
+	"fmt"
+	"os"
+	"reflect"
+	"strings"
+	"sync"
+	"time"
+)
+
+// Nil is the Gleam `Nil` type, which has a single value.
+type Nil = struct{}
+
+// List is a Gleam list, a singly linked list where the nil pointer is the
+// empty list.
+type List[A any] struct {
+	Head A
+	Tail *List[A]
+}
+
+// ToList builds a list of the given elements.
+func ToList[A any](elements ...A) *List[A] {
+	return Prepend(nil, elements...)
+}
+
+// Prepend builds a list of the given elements followed by those of tail.
+func Prepend[A any](tail *List[A], elements ...A) *List[A] {
+	list := tail
+	for i := len(elements) - 1; i >= 0; i-- {
+		list = &List[A]{Head: elements[i], Tail: list}
+	}
+	return list
+}
+
+// Result is the Gleam `Result` type, which is either an Ok or an Error.
+type Result[A, E any] interface {
+	isResult()
+}
+
+type Ok[A, E any] struct {
+	P0 A
+}
+
+type Error[A, E any] struct {
+	P0 E
+}
+
+func (Ok[A, E]) isResult()    {}
+func (Error[A, E]) isResult() {}
+
+// CrashReason describes a crash caught by `rescue`.
+type CrashReason struct {
+	Message string
+}
+
+func (r CrashReason) GetMessage() string {
+	return r.Message
+}
+
+// Rescue calls the function, returning any crash as an error rather than
+// letting it propagate.
+func Rescue[A any](f func() A) (result Result[A, CrashReason]) {
+	defer func() {
+		if reason := recover(); reason != nil {
+			result = Error[A, CrashReason]{P0: CrashReason{Message: fmt.Sprint(reason)}}
+		}
+	}()
+	return Ok[A, CrashReason]{P0: f()}
+}
+
+// Equal is Gleam's structural equality.
+func Equal(a, b any) bool {
+	return reflect.DeepEqual(a, b)
+}
+
+// Is reports whether the value is of the given record constructor.
+func Is[T any](value any) bool {
+	_, ok := value.(T)
+	return ok
+}
+
+// As returns the value as the given record constructor, which it must be.
+func As[T any](value any) T {
+	return value.(T)
+}
+
+// Update returns a copy of the record with the named fields replaced, each
+// name being followed by the new value of the field.
+func Update[T any](record T, fields ...any) T {
+	original := reflect.ValueOf(record)
+	updated := reflect.New(original.Type()).Elem()
+	updated.Set(original)
+	for i := 0; i+1 < len(fields); i += 2 {
+		field := updated.FieldByName(fields[i].(string))
+		value := reflect.ValueOf(fields[i+1])
+		if value.IsValid() {
+			field.Set(value)
+		} else {
+			field.Set(reflect.Zero(field.Type()))
+		}
+	}
+	return updated.Interface().(T)
+}
+
+// HasPrefix reports whether the string starts with the prefix.
+func HasPrefix(s, prefix string) bool {
+	return strings.HasPrefix(s, prefix)
+}
+
+// DivideInt divides the integers, returning zero when dividing by zero.
+func DivideInt(a, b int64) int64 {
+	if b == 0 {
+		return 0
+	}
+	return a / b
+}
+
+// RemainderInt returns the remainder of dividing the integers, or zero when
+// dividing by zero.
+func RemainderInt(a, b int64) int64 {
+	if b == 0 {
+		return 0
+	}
+	return a % b
+}
+
+// DivideFloat divides the floats, returning zero when dividing by zero.
+func DivideFloat(a, b float64) float64 {
+	if b == 0 {
+		return 0
+	}
+	return a / b
+}
+
+// Todo crashes the program as the code has not been written yet.
+func Todo[A any](message string, module string, line int64) A {
+	panic(fmt.Sprintf("todo: %s (%s:%d)", message, module, line))
+}
+
+// Echo prints the value along with where it was echoed and its type, then
+// returns it.
+func Echo[A any](value A, module string, line int64, typ string) A {
+	fmt.Fprintf(os.Stderr, "%s:%d\n%#v : %s\n", module, line, value, typ)
+	return value
+}
+
+// Processes are goroutines with a mailbox of the messages sent to them.
+type process struct {
+	lock     sync.Mutex
+	messages []any
+	signal   chan struct{}
+}
+
+// Spawn starts a process running the function, which is given the process's
+// own pid, and returns its pid.
+func Spawn(f func(any) Nil) any {
+	pid := &process{signal: make(chan struct{}, 1)}
+	go f(pid)
+	return pid
+}
+
+// Send sends the message to the mailbox of the process.
+func Send[A any](pid any, message A) Nil {
+	process := pid.(*process)
+	process.lock.Lock()
+	process.messages = append(process.messages, message)
+	process.lock.Unlock()
+	select {
+	case process.signal <- struct{}{}:
+	default:
+	}
+	return Nil{}
+}
+
+// Receive takes the oldest message from the mailbox of the process, waiting
+// up to the timeout in milliseconds for one to arrive.
+func Receive[A any](pid any, timeout int64) Result[A, Nil] {
+	process := pid.(*process)
+	deadline := time.After(time.Duration(timeout) * time.Millisecond)
+	for {
+		process.lock.Lock()
+		if len(process.messages) > 0 {
+			message := process.messages[0]
+			process.messages = process.messages[1:]
+			process.lock.Unlock()
+			return Ok[A, Nil]{P0: message.(A)}
+		}
+		process.lock.Unlock()
+		select {
+		case <-process.signal:
+		case <-deadline:
+			return Error[A, Nil]{P0: Nil{}}
+		}
+	}
+}
+
+// Sleep pauses the current process for the given number of milliseconds.
+func Sleep(milliseconds int64) Nil {
+	time.Sleep(time.Duration(milliseconds) * time.Millisecond)
+	return Nil{}
+}
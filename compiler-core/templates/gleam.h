@@ -3,10 +3,15 @@
 
 #include <stdint.h>
 
+#include <cstdlib>
 #include <functional>
 #include <initializer_list>
+#include <iostream>
 #include <memory>
+#include <optional>
+#include <stdexcept>
 #include <string>
+#include <vector>
 
 /// This namespace defines the prelude for Native Gleam.
 namespace gleam {
@@ -18,7 +23,52 @@ Ref<T> MakeRef(Args&&... args) {
   return std::make_shared<T>(args...);
 }
 using String = Ref<std::string>;
-String MakeString(char* str);
+String MakeString(const char* str);
+
+/// Prints the type of a value to standard error for an `echo` expression,
+/// returning the value unchanged.
+template <typename T>
+T Echo(T value, const String& type) {
+  std::cerr << "echo : " << *type << std::endl;
+  return value;
+}
+
+/// The exception thrown when the program crashes, such as with `panic` or a
+/// failed `assert`. A panic that is not rescued prints its message to standard
+/// error and exits the program.
+class Panic : public std::runtime_error {
+ public:
+  explicit Panic(const String& message)
+      : std::runtime_error(*message), message_(message) {}
+
+  const String& message() const { return message_; }
+
+ private:
+  String message_;
+};
+
+/// The result of `Crash`, which converts to any type so that a crash can be
+/// used wherever a value is expected. It is never created as `Crash` always
+/// throws.
+struct Crashed {
+  template <typename T>
+  [[noreturn]] operator T() const {
+    std::abort();
+  }
+};
+
+/// Crashes the program by throwing a `Panic` with the message.
+[[noreturn]] Crashed Crash(const String& message);
+
+/// Prints a float using the shortest digits that round-trip, in the same
+/// format as the Erlang and JavaScript targets, such as `1.0`, `0.1` and
+/// `1.0e21`.
+String FloatToString(double value);
+
+/// Parses a float written as an optional minus sign, digits, a decimal point,
+/// digits and an optional exponent, giving the nearest double to the written
+/// decimal, or nothing if the string is not a float.
+std::optional<double> ParseFloat(const std::string& str);
 
 /// A base type for all anonymous or referenced functions declared in Native
 /// Gleam
@@ -48,6 +98,115 @@ Function<Ref<ConstructorType>, Args...> WrappedConstructor() {
   };
 }
 
+template <typename T, typename E>
+struct Result {
+  virtual ~Result() = default;
+};
+
+template <typename T, typename E>
+struct Result$Ok : public Result<T, E> {
+  explicit Result$Ok(T _$0) : Result<T, E>(), _$0(_$0) {}
+
+  T _$0;
+};
+
+template <typename T, typename E>
+struct Result$Error : public Result<T, E> {
+  explicit Result$Error(E _$0) : Result<T, E>(), _$0(_$0) {}
+
+  E _$0;
+};
+
+struct CrashReason {
+  explicit CrashReason(String message) : message(message) {}
+  virtual ~CrashReason() = default;
+
+  String message;
+};
+
+struct CrashReason$CrashReason : public CrashReason {
+  explicit CrashReason$CrashReason(String message) : CrashReason(message) {}
+};
+
+/// Calls a function for `rescue`, returning `Ok` with its result, or `Error`
+/// with the reason if it panicked.
+template <typename F>
+auto Rescue(F function)
+    -> Ref<Result<decltype(function()), Ref<CrashReason>>> {
+  using T = decltype(function());
+  try {
+    return MakeRef<Result$Ok<T, Ref<CrashReason>>>(function());
+  } catch (const Panic& panic) {
+    return MakeRef<Result$Error<T, Ref<CrashReason>>>(
+        MakeRef<CrashReason$CrashReason>(panic.message()));
+  }
+}
+
+/// A sequence of bits, stored most significant bit first in bytes, with any
+/// bits of the last byte past the end unset.
+class BitString {
+ public:
+  BitString(std::vector<uint8_t> bytes, int64_t size)
+      : bytes_(std::move(bytes)), size_(size) {}
+
+  /// The number of bits.
+  int64_t size() const { return size_; }
+
+  const std::vector<uint8_t>& bytes() const { return bytes_; }
+
+  /// The bit at the index, counting from the start.
+  bool bit(int64_t index) const {
+    return (bytes_[index / 8] >> (7 - index % 8)) & 1;
+  }
+
+ private:
+  std::vector<uint8_t> bytes_;
+  int64_t size_;
+};
+
+/// Builds the bit string of a bit string expression, one segment at a time.
+class BitStringBuilder {
+ public:
+  /// Appends the lowest `size` bits of an int, most significant first, with
+  /// the bits past the 64 of the int repeating its sign.
+  BitStringBuilder& Int(int64_t value, int64_t size);
+
+  /// Appends a 64 bit big endian float.
+  BitStringBuilder& Float(double value);
+
+  /// Appends the UTF-8 bytes of a string.
+  BitStringBuilder& Utf8(const String& value);
+
+  /// Appends all the bits of another bit string.
+  BitStringBuilder& Bits(const Ref<BitString>& value);
+
+  Ref<BitString> Build();
+
+ private:
+  void Push(bool bit);
+
+  std::vector<uint8_t> bytes_;
+  int64_t size_ = 0;
+};
+
+/// The number of bits in a bit string.
+int64_t BitStringSize(const Ref<BitString>& bits);
+
+/// Reads an unsigned big endian int of `size` bits starting at the bit
+/// `offset`, keeping the lowest 64 bits if it is larger. The bits must be
+/// within the bit string.
+int64_t BitStringInt(const Ref<BitString>& bits, int64_t offset,
+                     int64_t size);
+
+/// Reads a 64 bit big endian float starting at the bit `offset`. The bits must
+/// be within the bit string.
+double BitStringFloat(const Ref<BitString>& bits, int64_t offset);
+
+/// The `size` bits starting at the bit `offset`. The bits must be within the
+/// bit string.
+Ref<BitString> BitStringSlice(const Ref<BitString>& bits, int64_t offset,
+                              int64_t size);
+
 template <typename T>
 class List {
  public:
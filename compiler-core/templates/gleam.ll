@@ -0,0 +1,440 @@
+; The Gleam prelude for the LLVM target.
+;
+; Every Gleam value is an i64. Ints are shifted left by one bit with the
+; lowest bit set. Every other value is the address of an 8-byte aligned object
+; whose first word holds its kind in the lowest byte and its size above that:
+;
+;   1 Float    the double follows the header
+;   2 String   the size is the number of UTF-8 bytes, which follow the header
+;   3 Record   the size is the number of fields, which follow a tag word. The
+;              tag of a custom type constructor is the address of a String
+;              holding its name, tuples have a tag of 0, and lists are made
+;              of records tagged with @"gleam.tag.List"
+;   4 Closure  the size is the number of captured values, which follow the
+;              address of the function. The function is called with the
+;              closure followed by the arguments
+;
+; Memory is allocated with malloc and never freed.
+;
+; SIZE_T is replaced with the integer type as wide as a pointer when the
+; prelude is written, as the prelude is shared by 32 and 64-bit platforms.
+; Constants holding addresses are generated with the rest of the code, as
+; they are written differently on each.
+
+declare i8* @malloc(SIZE_T)
+declare void @llvm.memcpy.p0i8.p0i8.i64(i8*, i8*, i64, i1)
+declare i32 @memcmp(i8*, i8*, SIZE_T)
+declare i8* @strpbrk(i8*, i8*)
+declare double @strtod(i8*, i8**)
+declare i32 @snprintf(i8*, SIZE_T, i8*, ...)
+declare SIZE_T @write(i32, i8*, SIZE_T)
+declare void @exit(i32) noreturn
+
+@"gleam.tag.List" = linkonce_odr constant { i64, [4 x i8] } { i64 1026, [4 x i8] c"List" }, align 8
+
+@panic = private constant [7 x i8] c"panic: "
+@newline = private constant [1 x i8] c"\0A"
+@quote = private constant [1 x i8] c"\22"
+@separator = private constant [2 x i8] c", "
+@open_tuple = private constant [2 x i8] c"#("
+@open_list = private constant [1 x i8] c"["
+@close_list = private constant [1 x i8] c"]"
+@open_record = private constant [1 x i8] c"("
+@close_record = private constant [1 x i8] c")"
+@function = private constant [4 x i8] c"//fn"
+@of_type = private constant [3 x i8] c" : "
+@point_zero = private constant [2 x i8] c".0"
+@int_format = private constant [5 x i8] c"%lld\00"
+@float_format = private constant [5 x i8] c"%.*g\00"
+@float_characters = private constant [5 x i8] c".eni\00"
+
+; A size as the size_t of the platform.
+define SIZE_T @gleam_size(i64 %size) {
+entry:
+  %pointer = inttoptr i64 %size to i8*
+  %size_t = ptrtoint i8* %pointer to SIZE_T
+  ret SIZE_T %size_t
+}
+
+define i64 @gleam_alloc(i64 %words) {
+entry:
+  %bytes = shl i64 %words, 3
+  %size = call SIZE_T @gleam_size(i64 %bytes)
+  %memory = call i8* @malloc(SIZE_T %size)
+  %address = ptrtoint i8* %memory to i64
+  ret i64 %address
+}
+
+define i64 @gleam_float(double %value) {
+entry:
+  %float = call i64 @gleam_alloc(i64 2)
+  %header = inttoptr i64 %float to i64*
+  store i64 1, i64* %header
+  %words = inttoptr i64 %float to double*
+  %field = getelementptr double, double* %words, i64 1
+  store double %value, double* %field
+  ret i64 %float
+}
+
+; Division by zero gives zero.
+define double @gleam_float_divide(double %left, double %right) {
+entry:
+  %zero = fcmp oeq double %right, 0.0
+  %quotient = fdiv double %left, %right
+  %result = select i1 %zero, double 0.0, double %quotient
+  ret double %result
+}
+
+define i64 @gleam_int_divide(i64 %left, i64 %right) {
+entry:
+  %zero = icmp eq i64 %right, 1
+  br i1 %zero, label %by_zero, label %divide
+by_zero:
+  ret i64 1
+divide:
+  %x = ashr i64 %left, 1
+  %y = ashr i64 %right, 1
+  %quotient = sdiv i64 %x, %y
+  %shifted = shl i64 %quotient, 1
+  %result = or i64 %shifted, 1
+  ret i64 %result
+}
+
+define i64 @gleam_int_remainder(i64 %left, i64 %right) {
+entry:
+  %zero = icmp eq i64 %right, 1
+  br i1 %zero, label %by_zero, label %divide
+by_zero:
+  ret i64 1
+divide:
+  %x = ashr i64 %left, 1
+  %y = ashr i64 %right, 1
+  %remainder = srem i64 %x, %y
+  %shifted = shl i64 %remainder, 1
+  %result = or i64 %shifted, 1
+  ret i64 %result
+}
+
+define i64 @gleam_string_size(i64 %string) {
+entry:
+  %header = inttoptr i64 %string to i64*
+  %word = load i64, i64* %header
+  %size = lshr i64 %word, 8
+  ret i64 %size
+}
+
+define i8* @gleam_string_bytes(i64 %string) {
+entry:
+  %address = add i64 %string, 8
+  %bytes = inttoptr i64 %address to i8*
+  ret i8* %bytes
+}
+
+define i64 @gleam_string_alloc(i64 %size) {
+entry:
+  %rounded = add i64 %size, 7
+  %data = lshr i64 %rounded, 3
+  %words = add i64 %data, 1
+  %string = call i64 @gleam_alloc(i64 %words)
+  %shifted = shl i64 %size, 8
+  %header = or i64 %shifted, 2
+  %pointer = inttoptr i64 %string to i64*
+  store i64 %header, i64* %pointer
+  ret i64 %string
+}
+
+define i64 @gleam_string_concat(i64 %left, i64 %right) {
+entry:
+  %left_size = call i64 @gleam_string_size(i64 %left)
+  %right_size = call i64 @gleam_string_size(i64 %right)
+  %size = add i64 %left_size, %right_size
+  %string = call i64 @gleam_string_alloc(i64 %size)
+  %bytes = call i8* @gleam_string_bytes(i64 %string)
+  %left_bytes = call i8* @gleam_string_bytes(i64 %left)
+  %right_bytes = call i8* @gleam_string_bytes(i64 %right)
+  call void @llvm.memcpy.p0i8.p0i8.i64(i8* %bytes, i8* %left_bytes, i64 %left_size, i1 0)
+  %rest = getelementptr i8, i8* %bytes, i64 %left_size
+  call void @llvm.memcpy.p0i8.p0i8.i64(i8* %rest, i8* %right_bytes, i64 %right_size, i1 0)
+  ret i64 %string
+}
+
+define i1 @gleam_string_has_prefix(i64 %string, i64 %prefix) {
+entry:
+  %size = call i64 @gleam_string_size(i64 %string)
+  %prefix_size = call i64 @gleam_string_size(i64 %prefix)
+  %long_enough = icmp uge i64 %size, %prefix_size
+  br i1 %long_enough, label %compare, label %no
+compare:
+  %bytes = call i8* @gleam_string_bytes(i64 %string)
+  %prefix_bytes = call i8* @gleam_string_bytes(i64 %prefix)
+  %compared = call SIZE_T @gleam_size(i64 %prefix_size)
+  %difference = call i32 @memcmp(i8* %bytes, i8* %prefix_bytes, SIZE_T %compared)
+  %same = icmp eq i32 %difference, 0
+  ret i1 %same
+no:
+  ret i1 0
+}
+
+; The string without the prefix, which it is known to start with.
+define i64 @gleam_string_drop_prefix(i64 %string, i64 %prefix) {
+entry:
+  %size = call i64 @gleam_string_size(i64 %string)
+  %prefix_size = call i64 @gleam_string_size(i64 %prefix)
+  %rest_size = sub i64 %size, %prefix_size
+  %rest = call i64 @gleam_string_alloc(i64 %rest_size)
+  %rest_bytes = call i8* @gleam_string_bytes(i64 %rest)
+  %bytes = call i8* @gleam_string_bytes(i64 %string)
+  %suffix = getelementptr i8, i8* %bytes, i64 %prefix_size
+  call void @llvm.memcpy.p0i8.p0i8.i64(i8* %rest_bytes, i8* %suffix, i64 %rest_size, i1 0)
+  ret i64 %rest
+}
+
+define i64 @gleam_field(i64 %record, i64 %index) {
+entry:
+  %words = inttoptr i64 %record to i64*
+  %word = add i64 %index, 2
+  %field = getelementptr i64, i64* %words, i64 %word
+  %value = load i64, i64* %field
+  ret i64 %value
+}
+
+; Structural equality of two values of the same type.
+define i1 @gleam_equal(i64 %left, i64 %right) {
+entry:
+  %identical = icmp eq i64 %left, %right
+  br i1 %identical, label %yes, label %check_ints
+check_ints:
+  %either = or i64 %left, %right
+  %int_bit = and i64 %either, 1
+  %is_int = icmp ne i64 %int_bit, 0
+  br i1 %is_int, label %no, label %check_headers
+check_headers:
+  %left_words = inttoptr i64 %left to i64*
+  %right_words = inttoptr i64 %right to i64*
+  %left_header = load i64, i64* %left_words
+  %right_header = load i64, i64* %right_words
+  %same_header = icmp eq i64 %left_header, %right_header
+  br i1 %same_header, label %check_kind, label %no
+check_kind:
+  %kind = and i64 %left_header, 255
+  switch i64 %kind, label %no [
+    i64 1, label %float
+    i64 2, label %string
+    i64 3, label %record
+  ]
+float:
+  %left_doubles = inttoptr i64 %left to double*
+  %right_doubles = inttoptr i64 %right to double*
+  %left_float = getelementptr double, double* %left_doubles, i64 1
+  %right_float = getelementptr double, double* %right_doubles, i64 1
+  %left_value = load double, double* %left_float
+  %right_value = load double, double* %right_float
+  %same_float = fcmp oeq double %left_value, %right_value
+  ret i1 %same_float
+string:
+  %size = lshr i64 %left_header, 8
+  %left_bytes = call i8* @gleam_string_bytes(i64 %left)
+  %right_bytes = call i8* @gleam_string_bytes(i64 %right)
+  %compared = call SIZE_T @gleam_size(i64 %size)
+  %difference = call i32 @memcmp(i8* %left_bytes, i8* %right_bytes, SIZE_T %compared)
+  %same_string = icmp eq i32 %difference, 0
+  ret i1 %same_string
+record:
+  %left_tag_word = getelementptr i64, i64* %left_words, i64 1
+  %right_tag_word = getelementptr i64, i64* %right_words, i64 1
+  %left_tag = load i64, i64* %left_tag_word
+  %right_tag = load i64, i64* %right_tag_word
+  %same_tag = icmp eq i64 %left_tag, %right_tag
+  %count = lshr i64 %left_header, 8
+  br i1 %same_tag, label %fields, label %no
+fields:
+  %index = phi i64 [ 0, %record ], [ %next, %field ]
+  %done = icmp eq i64 %index, %count
+  br i1 %done, label %yes, label %field
+field:
+  %left_field = call i64 @gleam_field(i64 %left, i64 %index)
+  %right_field = call i64 @gleam_field(i64 %right, i64 %index)
+  %same_field = call i1 @gleam_equal(i64 %left_field, i64 %right_field)
+  %next = add i64 %index, 1
+  br i1 %same_field, label %fields, label %no
+yes:
+  ret i1 1
+no:
+  ret i1 0
+}
+
+define void @gleam_write(i8* %bytes, i64 %size) {
+entry:
+  %written = call SIZE_T @gleam_size(i64 %size)
+  %0 = call SIZE_T @write(i32 2, i8* %bytes, SIZE_T %written)
+  ret void
+}
+
+define void @gleam_write_string(i64 %string) {
+entry:
+  %size = call i64 @gleam_string_size(i64 %string)
+  %bytes = call i8* @gleam_string_bytes(i64 %string)
+  call void @gleam_write(i8* %bytes, i64 %size)
+  ret void
+}
+
+define void @gleam_panic(i64 %message) noreturn {
+entry:
+  call void @gleam_write(i8* getelementptr ([7 x i8], [7 x i8]* @panic, i64 0, i64 0), i64 7)
+  call void @gleam_write_string(i64 %message)
+  call void @gleam_write(i8* getelementptr ([1 x i8], [1 x i8]* @newline, i64 0, i64 0), i64 1)
+  call void @exit(i32 1)
+  unreachable
+}
+
+; Floats are printed with the fewest digits that give the same float when
+; parsed, with a `.0` added to whole numbers.
+define void @gleam_print_float(double %value) {
+entry:
+  %buffer = alloca [32 x i8]
+  %bytes = getelementptr [32 x i8], [32 x i8]* %buffer, i64 0, i64 0
+  br label %digits
+digits:
+  %precision = phi i32 [ 1, %entry ], [ %more, %digits ]
+  %size = call i32 (i8*, SIZE_T, i8*, ...) @snprintf(i8* %bytes, SIZE_T 32, i8* getelementptr ([5 x i8], [5 x i8]* @float_format, i64 0, i64 0), i32 %precision, double %value)
+  %parsed = call double @strtod(i8* %bytes, i8** null)
+  %exact = fcmp oeq double %parsed, %value
+  %enough = icmp sge i32 %precision, 17
+  %stop = or i1 %exact, %enough
+  %more = add i32 %precision, 1
+  br i1 %stop, label %print, label %digits
+print:
+  %length = sext i32 %size to i64
+  call void @gleam_write(i8* %bytes, i64 %length)
+  %marker = call i8* @strpbrk(i8* %bytes, i8* getelementptr ([5 x i8], [5 x i8]* @float_characters, i64 0, i64 0))
+  %whole = icmp eq i8* %marker, null
+  br i1 %whole, label %point_zero, label %done
+point_zero:
+  call void @gleam_write(i8* getelementptr ([2 x i8], [2 x i8]* @point_zero, i64 0, i64 0), i64 2)
+  br label %done
+done:
+  ret void
+}
+
+; Prints the fields of a record from the given index, separated by commas.
+define void @gleam_print_fields(i64 %record, i64 %from) {
+entry:
+  %words = inttoptr i64 %record to i64*
+  %header = load i64, i64* %words
+  %count = lshr i64 %header, 8
+  br label %fields
+fields:
+  %index = phi i64 [ %from, %entry ], [ %next, %value ]
+  %done = icmp eq i64 %index, %count
+  br i1 %done, label %exit, label %field
+field:
+  %first = icmp eq i64 %index, %from
+  br i1 %first, label %value, label %separator
+separator:
+  call void @gleam_write(i8* getelementptr ([2 x i8], [2 x i8]* @separator, i64 0, i64 0), i64 2)
+  br label %value
+value:
+  %element = call i64 @gleam_field(i64 %record, i64 %index)
+  call void @gleam_print(i64 %element)
+  %next = add i64 %index, 1
+  br label %fields
+exit:
+  ret void
+}
+
+define void @gleam_print(i64 %value) {
+entry:
+  %int_bit = and i64 %value, 1
+  %is_int = icmp ne i64 %int_bit, 0
+  br i1 %is_int, label %int, label %object
+int:
+  %buffer = alloca [32 x i8]
+  %bytes = getelementptr [32 x i8], [32 x i8]* %buffer, i64 0, i64 0
+  %number = ashr i64 %value, 1
+  %size = call i32 (i8*, SIZE_T, i8*, ...) @snprintf(i8* %bytes, SIZE_T 32, i8* getelementptr ([5 x i8], [5 x i8]* @int_format, i64 0, i64 0), i64 %number)
+  %length = sext i32 %size to i64
+  call void @gleam_write(i8* %bytes, i64 %length)
+  ret void
+object:
+  %words = inttoptr i64 %value to i64*
+  %header = load i64, i64* %words
+  %kind = and i64 %header, 255
+  switch i64 %kind, label %function [
+    i64 1, label %float
+    i64 2, label %string
+    i64 3, label %record
+  ]
+float:
+  %doubles = inttoptr i64 %value to double*
+  %float_word = getelementptr double, double* %doubles, i64 1
+  %float_value = load double, double* %float_word
+  call void @gleam_print_float(double %float_value)
+  ret void
+string:
+  call void @gleam_write(i8* getelementptr ([1 x i8], [1 x i8]* @quote, i64 0, i64 0), i64 1)
+  call void @gleam_write_string(i64 %value)
+  call void @gleam_write(i8* getelementptr ([1 x i8], [1 x i8]* @quote, i64 0, i64 0), i64 1)
+  ret void
+function:
+  call void @gleam_write(i8* getelementptr ([4 x i8], [4 x i8]* @function, i64 0, i64 0), i64 4)
+  ret void
+record:
+  %tag_word = getelementptr i64, i64* %words, i64 1
+  %tag = load i64, i64* %tag_word
+  %count = lshr i64 %header, 8
+  %is_tuple = icmp eq i64 %tag, 0
+  br i1 %is_tuple, label %tuple, label %check_list
+tuple:
+  call void @gleam_write(i8* getelementptr ([2 x i8], [2 x i8]* @open_tuple, i64 0, i64 0), i64 2)
+  call void @gleam_print_fields(i64 %value, i64 0)
+  call void @gleam_write(i8* getelementptr ([1 x i8], [1 x i8]* @close_record, i64 0, i64 0), i64 1)
+  ret void
+check_list:
+  %is_list = icmp eq i64 %tag, ptrtoint ({ i64, [4 x i8] }* @"gleam.tag.List" to i64)
+  br i1 %is_list, label %list, label %custom
+list:
+  call void @gleam_write(i8* getelementptr ([1 x i8], [1 x i8]* @open_list, i64 0, i64 0), i64 1)
+  br label %elements
+elements:
+  %cell = phi i64 [ %value, %list ], [ %tail, %element ]
+  %first = phi i1 [ 1, %list ], [ 0, %element ]
+  %cell_words = inttoptr i64 %cell to i64*
+  %cell_header = load i64, i64* %cell_words
+  %empty = icmp eq i64 %cell_header, 3
+  br i1 %empty, label %end_list, label %separate
+separate:
+  br i1 %first, label %element, label %separator
+separator:
+  call void @gleam_write(i8* getelementptr ([2 x i8], [2 x i8]* @separator, i64 0, i64 0), i64 2)
+  br label %element
+element:
+  %head = call i64 @gleam_field(i64 %cell, i64 0)
+  call void @gleam_print(i64 %head)
+  %tail = call i64 @gleam_field(i64 %cell, i64 1)
+  br label %elements
+end_list:
+  call void @gleam_write(i8* getelementptr ([1 x i8], [1 x i8]* @close_list, i64 0, i64 0), i64 1)
+  ret void
+custom:
+  call void @gleam_write_string(i64 %tag)
+  %has_fields = icmp ne i64 %count, 0
+  br i1 %has_fields, label %fields, label %done
+fields:
+  call void @gleam_write(i8* getelementptr ([1 x i8], [1 x i8]* @open_record, i64 0, i64 0), i64 1)
+  call void @gleam_print_fields(i64 %value, i64 0)
+  call void @gleam_write(i8* getelementptr ([1 x i8], [1 x i8]* @close_record, i64 0, i64 0), i64 1)
+  br label %done
+done:
+  ret void
+}
+
+; Prints the value and its type to standard error for an `echo` expression,
+; returning the value unchanged.
+define i64 @gleam_echo(i64 %value, i64 %type) {
+entry:
+  call void @gleam_print(i64 %value)
+  call void @gleam_write(i8* getelementptr ([3 x i8], [3 x i8]* @of_type, i64 0, i64 0), i64 3)
+  call void @gleam_write_string(i64 %type)
+  call void @gleam_write(i8* getelementptr ([1 x i8], [1 x i8]* @newline, i64 0, i64 0), i64 1)
+  ret i64 %value
+}
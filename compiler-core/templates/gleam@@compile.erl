@@ -63,7 +63,7 @@ start_compiler_workers(Out) ->
     NumSchedulers.
 
 worker_loop(Parent, Out) ->
-    Options = [report_errors, report_warnings, debug_info, {outdir, Out}],
+    Options = [report_errors, report_warnings, debug_info, deterministic, {outdir, Out}],
     erlang:send(Parent, {work_please, self()}),
     receive
         {module, Module} ->
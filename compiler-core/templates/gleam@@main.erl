@@ -1,6 +1,6 @@
 -module(gleam@@main).
 
--export([run/1]).
+-export([run/1, run_tests/2, repl/0, main/1, inspect/1, float_to_string/1, parse_float/1]).
 
 run(Module) ->
     io:setopts(standard_io, [binary, {encoding, utf8}]),
@@ -15,6 +15,231 @@ run(Module) ->
             erlang:halt(127, [{flush, true}])
     end.
 
+%% The entry point of the escript made by `gleam export escript`, which runs
+%% the main function of the module named after the application. Programs
+%% read their arguments with `init:get_plain_arguments/0` as usual, though
+%% for an escript these begin with the path of the script.
+main(_Arguments) ->
+    run('{{ application }}').
+
+%% Run the test functions, at most Parallel of them at once, printing the
+%% outcome and duration of each as they finish.
+run_tests(Tests, Parallel) ->
+    io:setopts(standard_io, [binary, {encoding, utf8}]),
+    io:setopts(standard_error, [{encoding, utf8}]),
+    {ok, _} = application:ensure_all_started('{{ application }}'),
+    Failures = run_tests(Tests, Parallel, #{}, 0),
+    io:format("~n~B tests, ~B failures~n", [length(Tests), Failures]),
+    case Failures of
+        0 -> erlang:halt(0);
+        _ -> erlang:halt(1, [{flush, true}])
+    end.
+
+%% Each test is run in a monitored process that exits with the outcome, so a
+%% test that is killed by a linked process still finishes as a failure, and
+%% one that has not finished within the timeout is killed and fails.
+-define(TEST_TIMEOUT, 60000).
+
+run_tests([], _Free, Running, Failures) when map_size(Running) =:= 0 ->
+    Failures;
+run_tests([Test | Tests], Free, Running, Failures) when Free > 0 ->
+    {Pid, Ref} = spawn_monitor(fun() -> exit({test_finished, run_test(Test)}) end),
+    Timer = erlang:send_after(?TEST_TIMEOUT, self(), {test_timeout, Ref}),
+    Start = erlang:monotonic_time(microsecond),
+    Running1 = Running#{Ref => {Pid, Test, Start, Timer}},
+    run_tests(Tests, Free - 1, Running1, Failures);
+run_tests(Tests, Free, Running, Failures) ->
+    receive
+        {'DOWN', Ref, process, _, Reason} when is_map_key(Ref, Running) ->
+            {Entry, Running1} = maps:take(Ref, Running),
+            {_, {_, _, Name}, Start, Timer} = Entry,
+            _ = erlang:cancel_timer(Timer),
+            Result =
+                case Reason of
+                    {test_finished, Finished} -> Finished;
+                    _ -> {Name, {error, exit, Reason, []}, elapsed(Start)}
+                end,
+            Failed = report_test(Result),
+            run_tests(Tests, Free + 1, Running1, Failures + Failed);
+        {test_timeout, Ref} when is_map_key(Ref, Running) ->
+            {Entry, Running1} = maps:take(Ref, Running),
+            {Pid, {_, _, Name}, Start, _} = Entry,
+            erlang:demonitor(Ref, [flush]),
+            exit(Pid, kill),
+            Failed = report_test({Name, timeout, elapsed(Start)}),
+            run_tests(Tests, Free + 1, Running1, Failures + Failed)
+    end.
+
+run_test({Module, Function, Name}) ->
+    Start = erlang:monotonic_time(microsecond),
+    Outcome =
+        try
+            Module:Function(),
+            ok
+        catch
+            Class:Reason:StackTrace -> {error, Class, Reason, StackTrace}
+        end,
+    {Name, Outcome, elapsed(Start)}.
+
+elapsed(Start) ->
+    erlang:monotonic_time(microsecond) - Start.
+
+report_test({Name, ok, Duration}) ->
+    io:format("  ok      ~ts (~ts)~n", [Name, format_duration(Duration)]),
+    0;
+report_test({Name, timeout, Duration}) ->
+    io:format("  FAILED  ~ts (~ts)~n", [Name, format_duration(Duration)]),
+    io:format("timed out after ~Bs~n~n", [?TEST_TIMEOUT div 1000]),
+    1;
+report_test({Name, {error, Class, Reason, StackTrace}, Duration}) ->
+    io:format("  FAILED  ~ts (~ts)~n", [Name, format_duration(Duration)]),
+    print_error(Class, Reason, StackTrace),
+    io:format("~n"),
+    1.
+
+format_duration(Microseconds) ->
+    io_lib:format("~.2fms", [Microseconds / 1000]).
+
+%% Evaluate the expressions entered into `gleam shell`. The path of the
+%% Erlang module generated for each expression is read from a line of stdin,
+%% and the module is compiled and loaded, then its main function is called.
+%% Once it has been evaluated a line is printed with the value of the
+%% expression, or to say that it failed.
+repl() ->
+    io:setopts(standard_io, [binary, {encoding, utf8}]),
+    io:setopts(standard_error, [{encoding, utf8}]),
+    {ok, _} = application:ensure_all_started('{{ application }}'),
+    repl_loop().
+
+repl_loop() ->
+    case io:get_line(<<>>) of
+        eof -> erlang:halt(0);
+        {error, _} -> erlang:halt(0);
+        Line ->
+            evaluate(unicode:characters_to_list(string:trim(Line))),
+            repl_loop()
+    end.
+
+evaluate(Path) ->
+    try
+        {ok, Module, Binary} = compile:file(Path, [binary, report_errors]),
+        {module, Module} = code:load_binary(Module, Path, Binary),
+        Value = Module:main(),
+        io:format("gleam@@repl:value:~ts~n", [inspect(Value)])
+    catch
+        Class:Reason:StackTrace ->
+            print_error(Class, Reason, StackTrace),
+            io:format("~ngleam@@repl:error~n")
+    end.
+
+%% Print a value the way it would be written in Gleam, as `echo` does.
+inspect(true) -> <<"True">>;
+inspect(false) -> <<"False">>;
+inspect(nil) -> <<"Nil">>;
+inspect(Atom) when is_atom(Atom) -> constructor_name(Atom);
+inspect(Int) when is_integer(Int) -> integer_to_binary(Int);
+inspect(Float) when is_float(Float) -> float_to_string(Float);
+inspect(Binary) when is_binary(Binary) ->
+    case unicode:characters_to_binary(Binary) of
+        Binary -> [$", escape(Binary), $"];
+        _ -> inspect_bit_string(Binary)
+    end;
+inspect(Bits) when is_bitstring(Bits) -> inspect_bit_string(Bits);
+inspect(List) when is_list(List) -> ["[", inspect_elements(List), "]"];
+inspect(Tuple) when is_tuple(Tuple), is_atom(element(1, Tuple)) ->
+    [Tag | Fields] = tuple_to_list(Tuple),
+    [constructor_name(Tag), "(", inspect_elements(Fields), ")"];
+inspect(Tuple) when is_tuple(Tuple) ->
+    ["#(", inspect_elements(tuple_to_list(Tuple)), ")"];
+inspect(Fun) when is_function(Fun) ->
+    {arity, Arity} = erlang:fun_info(Fun, arity),
+    Arguments = lists:join(", ", [[$a + I] || I <- lists:seq(0, Arity - 1)]),
+    ["//fn(", Arguments, ") { ... }"];
+inspect(Other) ->
+    io_lib:format("//erl(~p)", [Other]).
+
+%% Print a float the same way as every other target: the shortest digits that
+%% round-trip, always with a fractional part, using scientific notation for
+%% decimal exponents below -6 or above 20. `io_lib_format:fwrite_g/1` picks
+%% the shortest digits but has its own rules for where to put the point, so
+%% its output is only used for the digits and their exponent.
+float_to_string(Float) ->
+    {Sign, Shortest} =
+        case io_lib_format:fwrite_g(Float) of
+            [$- | Unsigned] -> {"-", Unsigned};
+            Unsigned -> {"", Unsigned}
+        end,
+    {Decimal, Exponent} =
+        case string:split(Shortest, "e") of
+            [Mantissa, Power] -> {Mantissa, list_to_integer(Power)};
+            [Mantissa] -> {Mantissa, 0}
+        end,
+    {Integer, Fraction} =
+        case string:split(Decimal, ".") of
+            [Whole, Part] -> {Whole, Part};
+            [Whole] -> {Whole, ""}
+        end,
+    {Zeros, Significant} =
+        lists:splitwith(fun(C) -> C =:= $0 end, Integer ++ Fraction),
+    Digits = string:trim(Significant, trailing, "0"),
+    Text =
+        case Digits of
+            [] -> "0.0";
+            _ -> canonical_float(Digits, length(Integer) + Exponent - length(Zeros) - 1)
+        end,
+    unicode:characters_to_binary([Sign, Text]).
+
+%% Render significant digits whose first digit is multiplied by
+%% `10^Exponent`.
+canonical_float([First | Rest], Exponent) when Exponent < -6; Exponent > 20 ->
+    Fraction =
+        case Rest of
+            [] -> "0";
+            _ -> Rest
+        end,
+    [First, $., Fraction, $e, integer_to_list(Exponent)];
+canonical_float(Digits, Exponent) when Exponent < 0 ->
+    ["0.", lists:duplicate(-Exponent - 1, $0), Digits];
+canonical_float(Digits, Exponent) when length(Digits) > Exponent + 1 ->
+    {Integer, Fraction} = lists:split(Exponent + 1, Digits),
+    [Integer, $., Fraction];
+canonical_float(Digits, Exponent) ->
+    [Digits, lists:duplicate(Exponent + 1 - length(Digits), $0), ".0"].
+
+%% Parse a float written in the syntax every target prints: an optional minus
+%% sign, digits, a decimal point, digits, and an optional exponent.
+parse_float(Binary) ->
+    Pattern = "^-?[0-9]+\\.[0-9]+([eE][-+]?[0-9]+)?$",
+    case re:run(Binary, Pattern, [dollar_endonly, {capture, none}]) of
+        match ->
+            try
+                {ok, binary_to_float(Binary)}
+            catch
+                error:badarg -> {error, nil}
+            end;
+        nomatch ->
+            {error, nil}
+    end.
+
+inspect_elements(Elements) ->
+    lists:join(", ", lists:map(fun inspect/1, Elements)).
+
+inspect_bit_string(Bits) ->
+    ["<<", lists:join(", ", [integer_to_binary(B) || <<B>> <= Bits]), ">>"].
+
+constructor_name(Atom) ->
+    Parts = string:split(atom_to_binary(Atom, utf8), "_", all),
+    [string:titlecase(Part) || Part <- Parts].
+
+escape(String) ->
+    lists:foldl(
+        fun({Pattern, Replacement}, Acc) ->
+            string:replace(Acc, Pattern, Replacement, all)
+        end,
+        String,
+        [{"\\", "\\\\"}, {"\"", "\\\""}, {"\n", "\\n"}, {"\r", "\\r"}, {"\t", "\\t"}]
+    ).
+
 print_error(Class, Reason, StackTrace) -> 
     E = erl_error:format_exception(
         1, Class, Reason, StackTrace, fun stack_filter/3, 
@@ -0,0 +1,21 @@
+// Evaluates the expressions entered into `gleam shell`. The path of the
+// module generated for each expression is read from a line of stdin, relative
+// to this file, and its main function is called. Once it has been evaluated a
+// line is printed with the value of the expression, or to say that it failed.
+import { createInterface } from "node:readline";
+import { inspect } from "./gleam.mjs";
+
+export async function run() {
+  let lines = createInterface({ input: process.stdin, terminal: false });
+  for await (let path of lines) {
+    try {
+      let module = await import(path.trim());
+      let value = module.main();
+      console.log(`gleam@@repl:value:${inspect(value)}`);
+    } catch (error) {
+      console.log(error);
+      console.log("\ngleam@@repl:error");
+    }
+  }
+  process.exit(0);
+}
@@ -0,0 +1,100 @@
+import { Worker, isMainThread, parentPort } from "node:worker_threads";
+import { inspect } from "node:util";
+
+// Runs the test functions given by `gleam test --filter` or `--parallel`,
+// with at most `parallel` of them running at once, printing the outcome and
+// duration of each as they finish.
+//
+// Each test is a list of its name, the path of its module relative to this
+// file, and the name of its function.
+//
+// When more than one test is to run at once each is run in a worker thread,
+// so that tests that do not wait on anything also run in parallel.
+export async function run(tests, parallel) {
+  let failures = 0;
+  let record = (result) => {
+    report(result);
+    if (result.error !== undefined) failures++;
+  };
+
+  if (parallel <= 1) {
+    for (let test of tests) record(await runTest(test));
+  } else {
+    let queue = tests.map((test) => ({ test }));
+    let threads = [];
+    for (let i = 0; i < Math.min(parallel, tests.length); i++) {
+      threads.push(runThread(queue, record));
+    }
+    await Promise.all(threads);
+  }
+
+  console.log(`\n${tests.length} tests, ${failures} failures`);
+  process.exit(failures === 0 ? 0 : 1);
+}
+
+// A worker thread runs the tests it is sent one at a time, taking the next
+// from the queue when one finishes. A test that stops the thread fails, and
+// the remaining tests are run by a new thread.
+function runThread(queue, record) {
+  return new Promise((resolve) => {
+    let thread = new Worker(new URL(import.meta.url), {
+      argv: process.argv.slice(2),
+    });
+    let current;
+    let next = () => {
+      current = queue.shift();
+      if (current === undefined) {
+        thread.removeAllListeners();
+        thread.terminate().then(() => resolve());
+      } else {
+        current.start = performance.now();
+        thread.postMessage(current.test);
+      }
+    };
+    let stopped = (error) => {
+      thread.removeAllListeners();
+      if (current !== undefined) {
+        let [name] = current.test;
+        record({ name, duration: since(current.start), error });
+      }
+      runThread(queue, record).then(resolve);
+    };
+    thread.on("message", (result) => {
+      record(result);
+      next();
+    });
+    thread.on("error", (error) => stopped(inspect(error)));
+    thread.on("exit", (code) => stopped(`The test exited with code ${code}`));
+    next();
+  });
+}
+
+async function runTest([name, path, fn]) {
+  let start = performance.now();
+  try {
+    let module = await import(path);
+    await module[fn]();
+    return { name, duration: since(start) };
+  } catch (error) {
+    return { name, duration: since(start), error: inspect(error) };
+  }
+}
+
+if (!isMainThread) {
+  parentPort.on("message", async (test) => {
+    parentPort.postMessage(await runTest(test));
+  });
+}
+
+function since(start) {
+  return performance.now() - start;
+}
+
+function report({ name, duration, error }) {
+  let outcome = error === undefined ? "ok     " : "FAILED ";
+  console.log(`  ${outcome} ${name} (${duration.toFixed(2)}ms)`);
+  if (error !== undefined) {
+    console.log(error);
+    console.log();
+  }
+}
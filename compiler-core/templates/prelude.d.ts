@@ -32,6 +32,7 @@ export interface BitString {
   byteAt(index: number): number;
   floatAt(index: number): number;
   intFromSlice(start: number, end: number): number;
+  binaryFromSlice(start: number, end: number): BitString;
   sliceAfter(index: number): BitString;
 }
 
@@ -68,6 +69,13 @@ export interface ErrorStatic extends ResultStatic {
   new <T, E>(value: E): Result<T, E>;
 }
 
+export class CrashReason extends CustomType {
+  message: string;
+  constructor(message: string);
+}
+
+export function rescue<T>(f: () => T): Result<T, CrashReason>;
+
 export function inspect(value: any): string;
 
 export function isEqual(a: any, b: any): boolean;
@@ -77,3 +85,7 @@ export function remainderInt(a: number, b: number): number;
 export function divideInt(a: number, b: number): number;
 
 export function divideFloat(a: number, b: number): number;
+
+export function floatToString(float: number): string;
+
+export function parseFloat(string: string): Result<number, undefined>;
@@ -1,8 +1,10 @@
 export class CustomType {
-  inspect() {
-    let field = (label) => {
-      let value = inspect(this[label]);
-      return isNaN(parseInt(label)) ? `${label}: ${value}` : value;
+  inspect(floats, labels = true) {
+    let shapes = this.constructor.__gleam_floats__ || [];
+    let field = (label, index) => {
+      let shape = substituteFloats(shapes[index], floats?.a);
+      let value = inspect(this[label], shape, labels);
+      return labels && isNaN(parseInt(label)) ? `${label}: ${value}` : value;
     };
     let props = Object.keys(this).map(field).join(", ");
     return props ? `${this.constructor.name}(${props})` : this.constructor.name;
@@ -31,8 +33,9 @@ export class List {
     return new ListIterator(this);
   }
 
-  inspect() {
-    return `[${this.toArray().map(inspect).join(", ")}]`;
+  inspect(floats, labels) {
+    let element = (value) => inspect(value, floats?.a?.[0], labels);
+    return `[${this.toArray().map(element).join(", ")}]`;
   }
 
   toArray() {
@@ -143,6 +146,10 @@ export class BitString {
     return byteArrayToInt(this.buffer.slice(start, end));
   }
 
+  binaryFromSlice(start, end) {
+    return new BitString(this.buffer.slice(start, end));
+  }
+
   sliceAfter(index) {
     return new BitString(this.buffer.slice(index));
   }
@@ -232,6 +239,10 @@ export class Result extends CustomType {
 }
 
 export class Ok extends Result {
+  static get __gleam_floats__() {
+    return [0];
+  }
+
   constructor(value) {
     super();
     this[0] = value;
@@ -247,6 +258,10 @@ export class Ok extends Result {
 }
 
 export class Error extends Result {
+  static get __gleam_floats__() {
+    return [1];
+  }
+
   constructor(detail) {
     super();
     this[0] = detail;
@@ -261,15 +276,40 @@ export class Error extends Result {
   }
 }
 
-export function inspect(v) {
+export class CrashReason extends CustomType {
+  constructor(message) {
+    super();
+    this.message = message;
+  }
+}
+
+export function rescue(f) {
+  try {
+    return new Ok(f());
+  } catch (error) {
+    let message =
+      error instanceof globalThis.Error ? error.message : inspect(error);
+    return new Error(new CrashReason(message));
+  }
+}
+
+// Print a value the way it would be written in Gleam. The floats describe
+// where the floats are within the value, as generated by the compiler from
+// its type, as they cannot be told apart from ints. Labels are left out of
+// records when printing for `echo`, to match the Erlang target.
+export function inspect(v, floats, labels = true) {
   let t = typeof v;
   if (v === true) return "True";
   if (v === false) return "False";
   if (v === null) return "//js(null)";
   if (v === undefined) return "Nil";
   if (t === "string") return JSON.stringify(v);
+  if (t === "number" && floats === "f") return floatToString(v);
   if (t === "bigint" || t === "number") return v.toString();
-  if (Array.isArray(v)) return `#(${v.map(inspect).join(", ")})`;
+  if (Array.isArray(v)) {
+    let element = (value, index) => inspect(value, floats?.e?.[index], labels);
+    return `#(${v.map(element).join(", ")})`;
+  }
   if (v instanceof Set) return `//js(Set(${[...v].map(inspect).join(", ")}))`;
   if (v instanceof RegExp) return `//js(${v})`;
   if (v instanceof Date) return `//js(Date("${v.toISOString()}"))`;
@@ -280,12 +320,21 @@ export function inspect(v) {
     return `//fn(${args.join(", ")}) { ... }`;
   }
   try {
-    return v.inspect();
+    return v.inspect(floats, labels);
   } catch (_) {
     return inspectObject(v);
   }
 }
 
+// The floats of a field whose type refers to the parameters of its custom
+// type, given the floats of the arguments of the type.
+function substituteFloats(shape, args) {
+  if (typeof shape === "number") return args?.[shape];
+  if (shape?.a) return { a: shape.a.map((s) => substituteFloats(s, args)) };
+  if (shape?.e) return { e: shape.e.map((s) => substituteFloats(s, args)) };
+  return shape;
+}
+
 function inspectObject(v) {
   let [keys, get] = getters(v);
   let name = Object.getPrototypeOf(v)?.constructor?.name || "Object";
@@ -406,6 +455,26 @@ export function divideFloat(a, b) {
   }
 }
 
+// Floats are printed with the shortest digits that round-trip, matching the
+// Erlang and native targets. `Number.prototype.toString` already picks these
+// digits and switches to scientific notation at the same exponents, so only
+// the formatting of whole numbers and exponents needs adjusting.
+export function floatToString(float) {
+  if (Object.is(float, -0)) return "-0.0";
+  let string = float.toString().replace("e+", "e");
+  if (!Number.isFinite(float) || string.includes(".")) return string;
+  let exponent = string.indexOf("e");
+  return exponent === -1
+    ? string + ".0"
+    : string.slice(0, exponent) + ".0" + string.slice(exponent);
+}
+
+export function parseFloat(string) {
+  let valid = /^-?\d+\.\d+([eE][-+]?\d+)?$/.test(string);
+  let float = valid ? Number(string) : NaN;
+  return Number.isFinite(float) ? new Ok(float) : new Error(undefined);
+}
+
 export function throwError(variant, module, line, fn, message, extra) {
   let error = new globalThis.Error(message);
   error.gleam_error = variant;
@@ -415,3 +484,9 @@ export function throwError(variant, module, line, fn, message, extra) {
   for (let k in extra) error[k] = extra[k];
   throw error;
 }
+
+export function echo(value, module, line, type, floats) {
+  let printed = inspect(value, floats, false);
+  globalThis.console.error(`${module}:${line}\n${printed} : ${type}`);
+  return value;
+}
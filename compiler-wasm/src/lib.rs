@@ -1,146 +1,158 @@
-use std::{collections::HashMap, ffi::OsStr, path::Path};
+//! The compiler built as WebAssembly, so that the playground and tutorials
+//! can compile, check and format Gleam code in the browser.
+//!
+//! The modules to compile are written to a store held by the WebAssembly
+//! instance, so that a page can update the module being edited without
+//! sending every module with each compilation.
 
-use gleam_core::{
-    build::{Mode, Options, Package, ProjectCompiler, Target},
-    config::PackageConfig,
-    io::{FileSystemReader, FileSystemWriter},
-    manifest::{Base16Checksum, ManifestPackage, ManifestPackageSource},
-    paths, Error,
-};
+use std::{cell::RefCell, collections::BTreeMap, path::Path, str::FromStr};
 
-use hexpm::version::Version;
-use serde::{Deserialize, Serialize};
-
-mod wasm_filesystem;
-use wasm_filesystem::WasmFileSystem;
-
-mod log_telemetry;
-use log_telemetry::LogTelemetry;
+use gleam_core::{build::Target, config::PackageConfig, format, Compiler, Error, Warning};
+use serde::Serialize;
+use termcolor::Buffer;
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
-const PROJECT_NAME: &str = "gleam-wasm";
+const PROJECT_NAME: &str = "gleam_wasm";
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CompileOptions {
-    target: Target,
-    source_files: HashMap<String, String>,
-    dependencies: Vec<String>,
-    mode: Mode,
+thread_local! {
+    static MODULES: RefCell<ModuleStore> = RefCell::new(ModuleStore::default());
+}
+
+/// The modules written by the page, by package and name.
+#[derive(Debug, Default)]
+struct ModuleStore {
+    modules: BTreeMap<String, String>,
+    /// The modules of each dependency, which are compiled in the order they
+    /// were first written.
+    dependencies: Vec<(String, BTreeMap<String, String>)>,
 }
 
-impl Default for CompileOptions {
-    fn default() -> Self {
-        CompileOptions {
-            target: Target::JavaScript,
-            mode: Mode::Dev,
-            source_files: HashMap::default(),
-            dependencies: Vec::default(),
+impl ModuleStore {
+    fn compiler(&self) -> Compiler {
+        let config = PackageConfig {
+            name: PROJECT_NAME.to_string(),
+            ..Default::default()
+        };
+        let mut compiler = Compiler::new(config);
+        for (package, modules) in &self.dependencies {
+            for (module, code) in modules {
+                compiler = compiler.add_dependency_source(package, module, code);
+            }
+        }
+        for (module, code) in &self.modules {
+            compiler = compiler.add_source(module, code);
         }
+        compiler
     }
 }
 
-/// Compile a set of `source_files` into a different set of source files for the
-/// `target` language.
-pub fn compile_(options: CompileOptions) -> Result<HashMap<String, String>, String> {
-    let mut wfs = WasmFileSystem::new();
-
-    for (path, source) in options.source_files.iter() {
-        write_source_file(source, path, &mut wfs);
-    }
+/// The code generated for the modules, with paths relative to the directory
+/// the packages are built in, such as `gleam_wasm/main.mjs`.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompileOutput {
+    files: BTreeMap<String, String>,
+    warnings: Vec<String>,
+}
 
-    let _package =
-        compile_project(&mut wfs, options.target, &options).map_err(|e| e.pretty_string())?;
+/// Writes a module of the package to the store, replacing any module of the
+/// same name.
+pub fn write_module(module: &str, code: &str) {
+    MODULES.with(|store| {
+        let _ = store
+            .borrow_mut()
+            .modules
+            .insert(module.to_string(), code.to_string());
+    })
+}
 
-    Ok(gather_compiled_files(&wfs, options.target).unwrap())
+/// Writes a module of a dependency to the store, such as a module of the
+/// standard library, replacing any module of the same name.
+pub fn write_dependency_module(package: &str, module: &str, code: &str) {
+    MODULES.with(|store| {
+        let mut store = store.borrow_mut();
+        let index = match store
+            .dependencies
+            .iter()
+            .position(|(name, _)| name == package)
+        {
+            Some(index) => index,
+            None => {
+                store
+                    .dependencies
+                    .push((package.to_string(), BTreeMap::new()));
+                store.dependencies.len() - 1
+            }
+        };
+        if let Some((_, modules)) = store.dependencies.get_mut(index) {
+            let _ = modules.insert(module.to_string(), code.to_string());
+        }
+    })
 }
 
-fn write_source_file<P: AsRef<Path>>(source: &str, path: P, wfs: &mut WasmFileSystem) {
-    wfs.writer(path.as_ref())
-        .expect("should always succeed with the virtual file system")
-        .write(source.as_bytes())
-        .expect("should always succeed with the virtual file system");
+/// Removes a module of the package from the store.
+pub fn delete_module(module: &str) {
+    MODULES.with(|store| {
+        let _ = store.borrow_mut().modules.remove(module);
+    })
 }
 
-fn manifest_from_name(name: &str) -> ManifestPackage {
-    ManifestPackage {
-        name: name.to_string(),
-        version: Version {
-            major: 0,
-            minor: 0,
-            patch: 0,
-            pre: vec![],
-            build: None,
-        },
-        build_tools: vec!["gleam".to_string()],
-        otp_app: None,
-        requirements: vec![],
-        source: ManifestPackageSource::Hex {
-            outer_checksum: Base16Checksum(vec![]),
-        },
-    }
+/// Removes every module from the store, including those of dependencies.
+pub fn reset() {
+    MODULES.with(|store| *store.borrow_mut() = ModuleStore::default())
 }
 
-fn compile_project(
-    wfs: &mut WasmFileSystem,
-    target: Target,
-    compile_options: &CompileOptions,
-) -> Result<Package, Error> {
-    let packages: Vec<ManifestPackage> = compile_options
-        .dependencies
-        .iter()
-        .map(|s| manifest_from_name(s.as_str()))
+/// Compiles the modules in the store for the target, returning the generated
+/// code and the warnings, or the error.
+pub fn compile_package(target: &str) -> Result<CompileOutput, String> {
+    let target = Target::from_str(target).map_err(|_| format!("Unknown target `{}`", target))?;
+    let compiled = MODULES
+        .with(|store| store.borrow().compiler().target(target).compile())
+        .map_err(|error| error.pretty_string())?;
+    let files = compiled
+        .code
+        .get(&target)
+        .into_iter()
+        .flatten()
+        .map(|file| {
+            let path = file.path.to_string_lossy().replace('\\', "/");
+            (path, file.text.clone())
+        })
         .collect();
-
-    let options = Options {
-        mode: Mode::Dev,
-        target: Some(target),
-        perform_codegen: true,
-    };
-
-    let mut pcompiler = ProjectCompiler::new(
-        PackageConfig {
-            target,
-            name: PROJECT_NAME.to_string(),
-            ..Default::default()
-        },
-        options,
-        packages,
-        Box::new(LogTelemetry),
-        wfs.clone(),
-    );
-
-    pcompiler.compile()
+    Ok(CompileOutput {
+        files,
+        warnings: compiled.warnings.iter().map(warning_string).collect(),
+    })
 }
 
-fn gather_compiled_files(
-    wfs: &WasmFileSystem,
-    target: Target,
-) -> Result<HashMap<String, String>, ()> {
-    let mut files: HashMap<String, String> = HashMap::new();
-
-    let extension_to_search_for = match target {
-        Target::Erlang => OsStr::new("erl"),
-        Target::JavaScript => OsStr::new("mjs"),
-        Target::Native => todo!("Implement me"),
-    };
-
-    wfs.read_dir(&paths::build())
-        .expect("expect the build directory to exist")
-        .into_iter()
-        .filter_map(|result| result.ok())
-        .filter(|dir_entry| dir_entry.as_path().extension() == Some(extension_to_search_for))
-        .for_each(|dir_entry| {
-            let path = dir_entry.as_path();
-            let contents: String = wfs.read(path).expect("iterated dir entries should exist");
-            let path = path.to_str().unwrap().replace('\\', "/");
+/// Type checks the modules in the store, returning the warnings, or the
+/// error.
+pub fn type_check() -> Result<Vec<String>, String> {
+    let checked = MODULES
+        .with(|store| store.borrow().compiler().check())
+        .map_err(|error| error.pretty_string())?;
+    Ok(checked.warnings.iter().map(warning_string).collect())
+}
 
-            files.insert(path, contents);
-        });
+/// Formats the code of a module, returning the error if it cannot be parsed.
+pub fn format_module(code: &str) -> Result<String, String> {
+    let mut formatted = String::new();
+    format::pretty(
+        &mut formatted,
+        code,
+        Path::new("src/main.gleam"),
+        &format::Options::default(),
+    )
+    .map_err(|error: Error| error.pretty_string())?;
+    Ok(formatted)
+}
 
-    Ok(files)
+fn warning_string(warning: &Warning) -> String {
+    let mut buffer = Buffer::no_color();
+    warning.pretty(&mut buffer);
+    String::from_utf8(buffer.into_inner()).expect("Warning printing produced invalid utf8")
 }
 
 /// Should be called once to setup any state that persists across compilation
@@ -156,117 +168,106 @@ pub fn init(debug: bool) {
 }
 
 #[cfg(target_arch = "wasm32")]
-#[wasm_bindgen]
-pub fn compile(options: JsValue) -> JsValue {
-    match serde_wasm_bindgen::from_value(options) {
-        Ok(compile_options) => {
-            let result = compile_(compile_options);
-            serde_wasm_bindgen::to_value(&result)
-        }
-        Err(error) => serde_wasm_bindgen::to_value::<Result<HashMap<String, String>, String>>(
-            &Err(format!("Invalid options passed to `compile`: `{}`", error)),
-        ),
-    }
-    .expect("should never fail")
+#[wasm_bindgen(js_name = writeModule)]
+pub fn write_module_js(module: &str, code: &str) {
+    write_module(module, code)
 }
 
 #[cfg(target_arch = "wasm32")]
-#[cfg(test)]
-mod test {
-    use super::*;
-    use wasm_bindgen_test::wasm_bindgen_test;
+#[wasm_bindgen(js_name = writeDependencyModule)]
+pub fn write_dependency_module_js(package: &str, module: &str, code: &str) {
+    write_dependency_module(package, module, code)
+}
 
-    fn source(source: &str) -> HashMap<String, String> {
-        let mut source_files = HashMap::new();
-        source_files.insert("./src/main.gleam".to_string(), source.to_string());
-        source_files
-    }
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = deleteModule)]
+pub fn delete_module_js(module: &str) {
+    delete_module(module)
+}
 
-    fn compile_wrapper(options: CompileOptions) -> Result<HashMap<String, String>, String> {
-        init(false);
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = reset)]
+pub fn reset_js() {
+    reset()
+}
 
-        let result = compile(serde_wasm_bindgen::to_value(&options).unwrap());
-        serde_wasm_bindgen::from_value(result).unwrap()
-    }
+/// Returns `{Ok: {files, warnings}}` or `{Err: error}`.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = compilePackage)]
+pub fn compile_package_js(target: &str) -> JsValue {
+    serde_wasm_bindgen::to_value(&compile_package(target)).expect("should never fail")
+}
 
-    #[wasm_bindgen_test]
-    fn import_library_compile_javascript_test_wasm() {
-        let mut source_files = source(
-            r#"
-            import some_library
+/// Returns `{Ok: warnings}` or `{Err: error}`.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = typeCheck)]
+pub fn type_check_js() -> JsValue {
+    serde_wasm_bindgen::to_value(&type_check()).expect("should never fail")
+}
 
-            pub fn main() {
-                some_library.function("Hello, world!")
-            }
-            "#,
-        );
+/// Returns `{Ok: code}` or `{Err: error}`.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = formatModule)]
+pub fn format_module_js(code: &str) -> JsValue {
+    serde_wasm_bindgen::to_value(&format_module(code)).expect("should never fail")
+}
 
-        source_files.insert(
-            "build/packages/some_library/src/some_library.gleam".to_string(),
-            r#"
-            pub fn function(string: String) -> Nil {
-                Nil
-            }
-        "#
-            .to_string(),
-        );
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        source_files.insert(
-            "build/packages/some_library/gleam.toml".to_string(),
-            "name = \"some_library\"".to_string(),
+    #[test]
+    fn compile_package_test() {
+        reset();
+        write_dependency_module(
+            "some_library",
+            "some_library",
+            "pub fn function(string: String) -> Nil { Nil }",
         );
+        write_module(
+            "main",
+            r#"import some_library
 
-        let result = compile_wrapper(CompileOptions {
-            source_files,
-            dependencies: vec![String::from("some_library")],
-            ..Default::default()
-        })
-        .unwrap();
+pub fn main() {
+  some_library.function("Hello, world!")
+}
+"#,
+        );
 
+        let output = compile_package("javascript").expect("compile");
         assert_eq!(
-            result.get("build/dev/javascript/gleam-wasm/main.mjs"),
+            output.files.get("gleam_wasm/main.mjs"),
             Some(&String::from("import * as $some_library from \"../some_library/some_library.mjs\";\n\nexport function main() {\n  return $some_library.function$(\"Hello, world!\");\n}\n"))
         );
-    }
+        assert!(output.files.contains_key("some_library/some_library.mjs"));
+        assert_eq!(output.warnings, Vec::<String>::new());
 
-    #[wasm_bindgen_test]
-    fn import_library_compile_erlang_test_wasm() {
-        let mut source_files = source(
-            r#"
-            import some_library
-
-            pub fn main() {
-                some_library.function("Hello, world!")
-            }
-            "#,
+        let output = compile_package("erlang").expect("compile");
+        assert_eq!(
+            output.files.get("gleam_wasm/_gleam_artefacts/main.erl"),
+            Some(&String::from("-module(main).\n-compile(no_auto_import).\n\n-export([main/0]).\n\n-spec main() -> nil.\nmain() ->\n    some_library:function(<<\"Hello, world!\"/utf8>>).\n"))
         );
+    }
 
-        source_files.insert(
-            "build/packages/some_library/src/some_library.gleam".to_string(),
-            r#"
-            pub fn function(string: String) -> Nil {
-                Nil
-            }
-        "#
-            .to_string(),
-        );
+    #[test]
+    fn type_check_test() {
+        reset();
+        write_module("main", "pub fn main() { todo }");
+        assert_eq!(type_check().map(|warnings| warnings.len()), Ok(1));
 
-        source_files.insert(
-            "build/packages/some_library/gleam.toml".to_string(),
-            "name = \"some_library\"".to_string(),
-        );
+        write_module("main", "pub fn main() { 1 + 1.0 }");
+        assert!(type_check().is_err());
 
-        let result = compile_wrapper(CompileOptions {
-            source_files,
-            target: Target::Erlang,
-            dependencies: vec![String::from("some_library")],
-            ..Default::default()
-        })
-        .unwrap();
+        delete_module("main");
+        assert_eq!(type_check(), Ok(vec![]));
+    }
 
+    #[test]
+    fn format_module_test() {
         assert_eq!(
-            result.get("build/dev/erlang/gleam-wasm/_gleam_artefacts/main.erl"),
-            Some(&String::from("-module(main).\n-compile(no_auto_import).\n\n-export([main/0]).\n\n-spec main() -> nil.\nmain() ->\n    some_library:function(<<\"Hello, world!\"/utf8>>).\n"))
+            format_module("pub fn main() {   1 }"),
+            Ok("pub fn main() {\n  1\n}\n".to_string())
         );
+        assert!(format_module("pub fn main(").is_err());
     }
 }
@@ -0,0 +1,44 @@
+; ModuleID = 'probe1.103258e1af9ac305-cgu.0'
+source_filename = "probe1.103258e1af9ac305-cgu.0"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+; core::f64::<impl f64>::to_int_unchecked
+; Function Attrs: inlinehint nonlazybind uwtable
+define i32 @"_ZN4core3f6421_$LT$impl$u20$f64$GT$16to_int_unchecked17hd34a1e1cb4353ceeE"(double %self) unnamed_addr #0 {
+start:
+; call <f64 as core::convert::num::FloatToInt<i32>>::to_int_unchecked
+  %_0 = call i32 @"_ZN65_$LT$f64$u20$as$u20$core..convert..num..FloatToInt$LT$i32$GT$$GT$16to_int_unchecked17h45974b3b976e12c4E"(double %self) #2
+  ret i32 %_0
+}
+
+; <f64 as core::convert::num::FloatToInt<i32>>::to_int_unchecked
+; Function Attrs: inlinehint nonlazybind uwtable
+define internal i32 @"_ZN65_$LT$f64$u20$as$u20$core..convert..num..FloatToInt$LT$i32$GT$$GT$16to_int_unchecked17h45974b3b976e12c4E"(double %self) unnamed_addr #0 {
+start:
+  %0 = alloca [4 x i8], align 4
+  %1 = fptosi double %self to i32
+  store i32 %1, ptr %0, align 4
+  %_0 = load i32, ptr %0, align 4
+  ret i32 %_0
+}
+
+; probe1::probe
+; Function Attrs: nonlazybind uwtable
+define void @_ZN6probe15probe17ha84fcc02d0d1c740E() unnamed_addr #1 {
+start:
+; call core::f64::<impl f64>::to_int_unchecked
+  %_1 = call i32 @"_ZN4core3f6421_$LT$impl$u20$f64$GT$16to_int_unchecked17hd34a1e1cb4353ceeE"(double 1.000000e+00) #2
+  ret void
+}
+
+attributes #0 = { inlinehint nonlazybind uwtable "probe-stack"="inline-asm" "target-cpu"="x86-64" }
+attributes #1 = { nonlazybind uwtable "probe-stack"="inline-asm" "target-cpu"="x86-64" }
+attributes #2 = { inlinehint }
+
+!llvm.module.flags = !{!0, !1}
+!llvm.ident = !{!2}
+
+!0 = !{i32 8, !"PIC Level", i32 2}
+!1 = !{i32 2, !"RtLibUseGOT", i32 1}
+!2 = !{!"rustc version 1.95.0 (59807616e 2026-04-14)"}
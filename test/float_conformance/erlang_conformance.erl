@@ -0,0 +1,57 @@
+%% Checks the Erlang runtime's float printing and parsing against the shared
+%% conformance cases.
+
+-module(erlang_conformance).
+
+-export([main/0]).
+
+main() ->
+    {ok, Text} = file:read_file("cases.txt"),
+    Cases = [
+        string:lexemes(Line, " \t")
+     || Line <- string:split(Text, "\n", all),
+        Line =/= <<>>,
+        binary:first(Line) =/= $#
+    ],
+    Results =
+        lists:flatmap(fun check_case/1, Cases) ++
+            lists:map(fun check_invalid/1, invalid()),
+    Failures = length([failure || false <- Results]),
+    io:format("~B passes~n~B failures~n", [length(Results) - Failures, Failures]),
+    erlang:halt(min(Failures, 1)).
+
+check_case([Input, Expected]) ->
+    Float = to_float(Input),
+    Printed = 'gleam@@main':float_to_string(Float),
+    PrintedOk = Printed =:= Expected,
+    PrintedOk orelse
+        io:format(standard_error, "float_to_string(~s) = ~s, expected ~s~n", [
+            Input, Printed, Expected
+        ]),
+    ParsedOk =
+        case 'gleam@@main':parse_float(Expected) of
+            {ok, Parsed} -> <<Parsed/float>> =:= <<Float/float>>;
+            {error, nil} -> false
+        end,
+    ParsedOk orelse io:format(standard_error, "parse_float(~s) != ~s~n", [Expected, Input]),
+    [PrintedOk, ParsedOk].
+
+check_invalid(Input) ->
+    Failed = 'gleam@@main':parse_float(Input) =:= {error, nil},
+    Failed orelse io:format(standard_error, "parse_float(~s) should fail~n", [Input]),
+    Failed.
+
+invalid() ->
+    [<<"1">>, <<"1.">>, <<".5">>, <<"1.5e">>, <<"1.0e400">>, <<"Infinity">>].
+
+%% Some inputs, such as `1e20`, have no fractional part, which Erlang's float
+%% syntax requires.
+to_float(Input) ->
+    case binary:match(Input, <<".">>) of
+        nomatch ->
+            [Mantissa | Exponent] = binary:split(Input, <<"e">>),
+            Power = [[$e, E] || E <- Exponent],
+            binary_to_float(iolist_to_binary([Mantissa, ".0", Power]));
+        _ ->
+            binary_to_float(Input)
+    end.
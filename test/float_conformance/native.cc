@@ -0,0 +1,56 @@
+// Checks the native runtime's float printing and parsing against the shared
+// conformance cases.
+
+#include <cmath>
+#include <cstdlib>
+#include <cstring>
+#include <fstream>
+#include <iostream>
+#include <sstream>
+
+#include "gleam.h"
+
+int main() {
+  std::ifstream cases("cases.txt");
+  std::string line;
+  int failures = 0;
+  int passes = 0;
+
+  while (std::getline(cases, line)) {
+    if (line.empty() || line[0] == '#') continue;
+    std::istringstream fields(line);
+    std::string input;
+    std::string expected;
+    fields >> input >> expected;
+    double value = std::strtod(input.c_str(), nullptr);
+
+    std::string printed = *gleam::FloatToString(value);
+    if (printed == expected) {
+      ++passes;
+    } else {
+      ++failures;
+      std::cerr << "FloatToString(" << input << ") = " << printed
+                << ", expected " << expected << "\n";
+    }
+
+    auto parsed = gleam::ParseFloat(expected);
+    if (parsed && std::memcmp(&*parsed, &value, sizeof(double)) == 0) {
+      ++passes;
+    } else {
+      ++failures;
+      std::cerr << "ParseFloat(" << expected << ") != " << input << "\n";
+    }
+  }
+
+  for (auto invalid : {"1", "1.", ".5", "1.5e", "1.0e400", "Infinity"}) {
+    if (gleam::ParseFloat(invalid)) {
+      ++failures;
+      std::cerr << "ParseFloat(" << invalid << ") should fail\n";
+    } else {
+      ++passes;
+    }
+  }
+
+  std::cout << passes << " passes\n" << failures << " failures\n";
+  return failures == 0 ? EXIT_SUCCESS : EXIT_FAILURE;
+}
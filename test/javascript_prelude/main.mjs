@@ -10,12 +10,15 @@ import {
   codepointBits,
   divideFloat,
   divideInt,
+  floatToString,
   inspect,
   isEqual,
+  parseFloat,
   stringBits,
   toBitString,
   toList,
 } from "./prelude.mjs";
+import { readFileSync } from "fs";
 
 let failures = 0;
 let passes = 0;
@@ -411,6 +414,45 @@ assertEqual(inspect([]), "#()");
 assertEqual(inspect([1, 2, 3]), "#(1, 2, 3)");
 assertEqual(inspect([new Ok(1), new Ok(2)]), "#(Ok(1), Ok(2))");
 
+// Floats are printed as floats when the compiler says where they are
+
+assertEqual(inspect(1500, "f"), "1500.0");
+assertEqual(inspect(1.5e21, "f"), "1.5e21");
+assertEqual(inspect([1, 2], { e: [undefined, "f"] }), "#(1, 2.0)");
+assertEqual(inspect(new Ok(1), { a: ["f", undefined] }), "Ok(1.0)");
+assertEqual(inspect(new Error(1), { a: [undefined, "f"] }), "Error(1.0)");
+assertEqual(
+  inspect(List.fromArray([1, 2]), { a: ["f"] }),
+  "[1.0, 2.0]"
+);
+assertEqual(
+  inspect(new Ok(List.fromArray([[1, 2]])), {
+    a: [{ a: [{ e: ["f", undefined] }] }, undefined],
+  }),
+  "Ok([#(1.0, 2)])"
+);
+
+class BoxImpl extends CustomType {
+  static get __gleam_floats__() {
+    return [undefined, { a: [0] }];
+  }
+
+  constructor(count, values) {
+    super();
+    this.count = count;
+    this.values = values;
+  }
+}
+
+assertEqual(
+  inspect(new BoxImpl(1, List.fromArray([2])), { a: ["f"] }),
+  "BoxImpl(count: 1, values: [2.0])"
+);
+assertEqual(
+  inspect(new BoxImpl(1, List.fromArray([2])), { a: ["f"] }, false),
+  "BoxImpl(1, [2.0])"
+);
+
 assertEqual(inspect(List.fromArray([])), "[]");
 assertEqual(inspect(List.fromArray([1, 2, 3])), "[1, 2, 3]");
 assertEqual(inspect(List.fromArray([new Ok(1), new Ok(2)])), "[Ok(1), Ok(2)]");
@@ -603,6 +645,30 @@ assertEqual(
   new ExampleRecordImpl(6, 5, 4)
 );
 
+// Float printing and parsing conformance
+
+let floatCases = readFileSync("../float_conformance/cases.txt", "utf8")
+  .split("\n")
+  .filter((line) => line !== "" && !line.startsWith("#"))
+  .map((line) => line.split(/\s+/));
+
+for (let [input, expected] of floatCases) {
+  let float = Number(input);
+  assertEqual(floatToString(float), expected);
+  let parsed = parseFloat(expected);
+  if (parsed.isOk() && Object.is(parsed[0], float)) {
+    pass();
+  } else {
+    fail(`\n\tparseFloat(${JSON.stringify(expected)}) != ${input}`);
+  }
+}
+
+assertEqual(parseFloat("1"), new Error(undefined));
+assertEqual(parseFloat("1."), new Error(undefined));
+assertEqual(parseFloat(".5"), new Error(undefined));
+assertEqual(parseFloat("1.0e400"), new Error(undefined));
+assertEqual(parseFloat("Infinity"), new Error(undefined));
+
 //
 // Summary
 //
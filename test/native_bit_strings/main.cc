@@ -0,0 +1,76 @@
+// Checks that bit strings built by the native runtime are read back by its
+// bit string patterns, including segments sized at runtime.
+
+#include "bit_strings.h"
+#include "gleam.h"
+
+namespace {
+
+using gleam::Ref;
+using Bits = Ref<gleam::BitString>;
+
+int failures = 0;
+
+void Check(bool passed, const char* name) {
+  if (!passed) {
+    std::cerr << "failed: " << name << std::endl;
+    ++failures;
+  }
+}
+
+Bits Bytes(std::vector<uint8_t> bytes) {
+  auto size = static_cast<int64_t>(bytes.size()) * 8;
+  return gleam::MakeRef<gleam::BitString>(std::move(bytes), size);
+}
+
+bool Equal(const Bits& left, const Bits& right) {
+  return left->size() == right->size() && left->bytes() == right->bytes();
+}
+
+}  // namespace
+
+int main() {
+  Check(Equal(::bit_strings::sized(0x1234, 16), Bytes({0x12, 0x34})),
+        "sized int");
+  Check(Equal(::bit_strings::sized(-1, 72),
+              Bytes(std::vector<uint8_t>(9, 0xff))),
+        "sized int wider than 64 bits");
+  auto twelve_bits = ::bit_strings::sized(0xabc, 12);
+  Check(twelve_bits->size() == 12 && twelve_bits->bytes()[1] == 0xc0,
+        "sized int of a part of a byte");
+
+  auto built = ::bit_strings::segments(7, 1.5, gleam::MakeString("hi"),
+                                       Bytes({0xfe}));
+  Check(Equal(built, Bytes({7, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0, 'h', 'i', 0xfe})),
+        "segments");
+
+  auto message = ::bit_strings::length_prefixed(Bytes({1, 2, 3}), 3);
+  Check(Equal(::bit_strings::payload(message), Bytes({1, 2, 3})),
+        "binary sized by an earlier segment");
+  Check(Equal(::bit_strings::payload(Bytes({4, 1, 2, 3})), Bytes({})),
+        "binary longer than the bit string");
+
+  Check(::bit_strings::header(Bytes({1, 0x52, 9})) == 502, "header of kind 1");
+  Check(::bit_strings::header(Bytes({2, 0x01, 0x02})) == 258,
+        "header of kind 2");
+  Check(::bit_strings::header(Bytes({2, 0x01})) == -1, "header too short");
+  Check(::bit_strings::header(Bytes({1, 0x52, 1})) == 502,
+        "header with any rest");
+
+  Check(::bit_strings::int_of_size(Bytes({0x12, 0x34}), 8) == 0x12,
+        "int sized by an argument");
+  Check(::bit_strings::int_of_size(Bytes({0x12, 0x34}), 24) == -1,
+        "int sized past the end");
+  Check(::bit_strings::int_of_size(Bytes({0x12, 0x34}), -8) == -1,
+        "negative size");
+  Check(::bit_strings::int_of_size(Bytes({0x12, 0x34}), 4) == -1,
+        "rest that is not whole bytes");
+
+  auto float_then_byte =
+      gleam::BitStringBuilder().Float(-2.25).Int(5, 8).Build();
+  Check(::bit_strings::leading_float(float_then_byte) == -2.25, "float");
+  Check(Equal(::bit_strings::after_float(float_then_byte), Bytes({5})),
+        "rest after a float");
+
+  return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,55 @@
+// Checks that the native runtime's `rescue` catches crashes, and that a crash
+// which is not rescued exits the program.
+
+#include <cstring>
+
+#include "gleam.h"
+#include "rescue.h"
+
+namespace {
+
+using Reason = gleam::Ref<gleam::CrashReason>;
+
+template <typename T>
+bool IsOk(const gleam::Ref<gleam::Result<T, Reason>>& result, const T& value) {
+  auto ok = std::dynamic_pointer_cast<gleam::Result$Ok<T, Reason>>(result);
+  return ok != nullptr && ok->_$0 == value;
+}
+
+template <typename T>
+bool IsError(const gleam::Ref<gleam::Result<T, Reason>>& result,
+             const std::string& message) {
+  auto error = std::dynamic_pointer_cast<gleam::Result$Error<T, Reason>>(result);
+  return error != nullptr && *error->_$0->message == message;
+}
+
+int failures = 0;
+
+void Check(bool passed, const char* name) {
+  if (!passed) {
+    std::cerr << "failed: " << name << std::endl;
+    ++failures;
+  }
+}
+
+}  // namespace
+
+int main(int argc, char** argv) {
+  if (argc > 1 && std::strcmp(argv[1], "unrescued") == 0) {
+    return static_cast<int>(::rescue::unrescued());
+  }
+
+  Check(IsOk<int64_t>(::rescue::returns(), 1), "returns");
+  Check(IsError<int64_t>(::rescue::crashes(),
+                         "This has not yet been implemented: boom"),
+        "crashes");
+  auto nested = std::dynamic_pointer_cast<
+      gleam::Result$Ok<gleam::Ref<gleam::Result<int64_t, Reason>>, Reason>>(
+      ::rescue::nested());
+  Check(nested != nullptr &&
+            IsError<int64_t>(nested->_$0,
+                             "This has not yet been implemented: boom"),
+        "nested");
+
+  return failures == 0 ? 0 : 1;
+}